@@ -43,6 +43,10 @@ pub struct TransferArgs {
     /// The input tx id and vout seperated with `:` symbol. For example `dcdd...eda45:0`
     #[clap(long, num_args = 1..)]
     pub outpoint: Vec<OutPoint>,
+
+    /// Prove the amounts of all the outputs with one aggregated range proof.
+    #[clap(long)]
+    pub aggregate: bool,
 }
 
 pub async fn run(
@@ -54,6 +58,7 @@ pub async fn run(
         chroma,
         recipient,
         outpoint,
+        aggregate,
     }: TransferArgs,
     mut context: Context,
 ) -> eyre::Result<()> {
@@ -108,6 +113,7 @@ pub async fn run(
     }
 
     builder.set_fee_rate_strategy(config.fee_rate_strategy);
+    builder.set_aggregate_bulletproofs(aggregate);
 
     let tx = builder.finish(&blockchain).await?;
 
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use bitcoin::OutPoint;
+use clap::Args;
+use color_eyre::eyre::{self, Context as EyreContext, OptionExt};
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Output to export the bundle of, the tx id and vout separated with `:` symbol.
+    #[clap(long)]
+    pub outpoint: OutPoint,
+
+    /// Path to the file to save the bundle to, printed if not specified.
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+}
+
+pub async fn run(
+    ExportArgs { outpoint, output }: ExportArgs,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    let yuv_client = ctx.yuv_client()?;
+
+    let bundle = yuv_client
+        .export_proof_bundle(outpoint.txid, outpoint.vout)
+        .await?
+        .ok_or_eyre("Output is not attached by YUV node or has no tokens")?;
+
+    let Some(output) = output else {
+        println!("{}", bundle);
+        return Ok(());
+    };
+
+    std::fs::write(&output, bundle)
+        .wrap_err_with(|| format!("Failed to write the bundle to {}", output.display()))?;
+
+    println!("Bundle of {} is saved to {}", outpoint, output.display());
+
+    Ok(())
+}
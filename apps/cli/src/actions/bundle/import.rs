@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::eyre::{self, bail, Context as EyreContext, OptionExt};
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+use yuv_tx_check::verify_yuv_transaction_chain;
+use yuv_types::bundle::ProofBundle;
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// Path to the file with the hex encoded proof bundle.
+    pub bundle: PathBuf,
+
+    /// Provide the transactions of the bundle to the node, parents first.
+    #[clap(long)]
+    pub provide: bool,
+}
+
+pub async fn run(ImportArgs { bundle, provide }: ImportArgs, mut ctx: Context) -> eyre::Result<()> {
+    let bundle_hex = std::fs::read_to_string(&bundle)
+        .wrap_err_with(|| format!("Failed to read the bundle from {}", bundle.display()))?;
+    let bundle = ProofBundle::from_hex(bundle_hex.trim())?;
+
+    if bundle.target_tx().is_none() {
+        bail!(
+            "The bundle doesn't end with the transaction of {}",
+            bundle.outpoint
+        );
+    }
+
+    // The chain check doesn't need the node, so the bundle is verified before anything is
+    // provided to it.
+    let state = verify_yuv_transaction_chain(&bundle.txs)?;
+    let proof = state
+        .get(&bundle.outpoint)
+        .ok_or_eyre("The output of the bundle is spent by its transactions or has no tokens")?;
+
+    if provide {
+        let yuv_client = ctx.yuv_client()?;
+
+        for tx in &bundle.txs {
+            yuv_client.provide_yuv_proof(tx.clone()).await?;
+        }
+    }
+
+    if proof.is_empty_pixelproof() {
+        println!(
+            "Bundle of {} is valid: the output has no tokens",
+            bundle.outpoint
+        );
+    } else {
+        let pixel = proof.pixel();
+        println!(
+            "Bundle of {} is valid: the output has {} tokens of {}",
+            bundle.outpoint,
+            pixel.luma.amount,
+            pixel.chroma.to_address(ctx.config()?.network())
+        );
+    }
+
+    Ok(())
+}
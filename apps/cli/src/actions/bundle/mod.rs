@@ -0,0 +1,23 @@
+use clap::Subcommand;
+use color_eyre::eyre;
+
+use crate::context::Context;
+
+mod export;
+mod import;
+
+#[derive(Subcommand, Debug)]
+pub enum BundleCommands {
+    /// Export the proof bundle of the output from the node: the transactions needed to validate
+    /// the output without the node.
+    Export(export::ExportArgs),
+    /// Verify the proof bundle offline, and optionally provide its transactions to the node.
+    Import(import::ImportArgs),
+}
+
+pub async fn run(cmd: BundleCommands, context: Context) -> eyre::Result<()> {
+    match cmd {
+        BundleCommands::Export(args) => export::run(args, context).await,
+        BundleCommands::Import(args) => import::run(args, context).await,
+    }
+}
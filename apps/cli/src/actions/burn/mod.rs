@@ -1,11 +1,10 @@
-use crate::context::Context;
+use crate::{actions::dry_run::print_preview, context::Context};
 use bdk::blockchain::Blockchain;
 use clap::Args;
 use color_eyre::eyre::{self, Ok};
 use yuv_pixels::Chroma;
 use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
-
-const DEFAULT_SATOSHIS: u64 = 1000;
+use yuv_types::dust::DEFAULT_PIXEL_SATOSHIS;
 
 #[derive(Args, Debug)]
 pub struct BurnArgs {
@@ -14,7 +13,7 @@ pub struct BurnArgs {
     pub amount: u128,
 
     /// Satoshis to spend.
-    #[clap(long, short, default_value_t = DEFAULT_SATOSHIS)]
+    #[clap(long, short, default_value_t = DEFAULT_PIXEL_SATOSHIS)]
     pub satoshis: u64,
 
     /// Type of the token, public key of the issuer.
@@ -30,6 +29,10 @@ pub struct BurnArgs {
     /// It's worth noting that change from regular satoshis will be tweaked.
     #[clap(long)]
     pub drain_tweaked_satoshis: bool,
+
+    /// Build the transaction and show its size, fee, outputs and proofs without sending it.
+    #[clap(long)]
+    pub dry_run: bool,
 }
 
 pub async fn run(
@@ -39,6 +42,7 @@ pub async fn run(
         chroma,
         do_not_provide_proofs,
         drain_tweaked_satoshis,
+        dry_run,
     }: BurnArgs,
     mut ctx: Context,
 ) -> eyre::Result<()> {
@@ -58,6 +62,18 @@ pub async fn run(
         builder.finish(&blockchain).await?
     };
 
+    if dry_run {
+        let change_keys = [wallet.public_key().inner, wallet.change_key()];
+
+        return print_preview(
+            blockchain.as_ref(),
+            cfg.network(),
+            &tx.bitcoin_tx,
+            Some(&tx.tx_type),
+            &change_keys,
+        );
+    }
+
     if do_not_provide_proofs {
         blockchain.broadcast(&tx.bitcoin_tx)?;
     } else {
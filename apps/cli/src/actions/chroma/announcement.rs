@@ -1,8 +1,10 @@
 use crate::{actions::announcement_args::broadcast_announcement, context::Context};
 
+use bitcoin::PublicKey;
 use clap::Args;
 use color_eyre::eyre::{self};
 use yuv_pixels::Chroma;
+use yuv_types::announcements::{ChromaAnnouncement, ChromaIssuers};
 use yuv_types::Announcement;
 
 /// Arguments to make a chroma announcement. See [`yuv_types::announcements::ChromaAnnouncement`].
@@ -26,6 +28,13 @@ pub struct ChromaAnnnouncementArgs {
     /// Indicates whether the token can be frozen by the issuer.
     #[clap(long, default_value_t = true)]
     pub is_freezable: bool,
+    /// Public keys of the issuers that own the chroma after the announcement.
+    #[clap(long = "issuer", requires = "threshold")]
+    pub issuers: Vec<PublicKey>,
+    /// The number of the issuers' signatures required for the issuance, freeze and ownership
+    /// transfer announcements.
+    #[clap(long, requires = "issuers")]
+    pub threshold: Option<u8>,
 }
 
 pub async fn run(args: ChromaAnnnouncementArgs, mut context: Context) -> eyre::Result<()> {
@@ -34,7 +43,7 @@ pub async fn run(args: ChromaAnnnouncementArgs, mut context: Context) -> eyre::R
         .chroma
         .unwrap_or_else(|| Chroma::from(wallet.public_key()));
 
-    let announcement = Announcement::chroma_announcement(
+    let mut announcement = ChromaAnnouncement::new(
         chroma,
         args.name,
         args.symbol,
@@ -43,5 +52,10 @@ pub async fn run(args: ChromaAnnnouncementArgs, mut context: Context) -> eyre::R
         args.is_freezable,
     )?;
 
-    broadcast_announcement(announcement, context).await
+    if let Some(threshold) = args.threshold {
+        let pubkeys = args.issuers.iter().map(|pubkey| pubkey.inner).collect();
+        announcement = announcement.with_issuers(ChromaIssuers::new(threshold, pubkeys)?);
+    }
+
+    broadcast_announcement(Announcement::Chroma(announcement), context).await
 }
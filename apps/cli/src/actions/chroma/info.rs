@@ -39,9 +39,23 @@ pub async fn run(args: InfoArgs, mut context: Context) -> eyre::Result<()> {
         };
         println!("Max supply: {}", max_supply);
         println!("Is freezable: {}", announcement.is_freezable);
+
+        if let Some(issuers) = announcement.issuers {
+            println!(
+                "Issuers ({}-of-{}):",
+                issuers.threshold,
+                issuers.pubkeys.len()
+            );
+            for pubkey in issuers.pubkeys {
+                println!("  {}", pubkey);
+            }
+        }
     };
 
     println!("Total supply: {}", chroma_info.total_supply);
+    if let Some(remaining_supply) = chroma_info.remaining_supply {
+        println!("Remaining supply: {}", remaining_supply);
+    }
 
     let network = config.network();
     let address = if let Some(owner_script) = chroma_info.owner {
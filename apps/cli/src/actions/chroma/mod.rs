@@ -5,6 +5,7 @@ use color_eyre::eyre;
 mod announcement;
 mod info;
 mod transfer_ownership;
+mod update;
 
 #[derive(Subcommand, Debug)]
 pub enum ChromaCommands {
@@ -14,6 +15,8 @@ pub enum ChromaCommands {
     Info(info::InfoArgs),
     /// Transfer ownership of the chroma to another address.
     TransferOwnership(transfer_ownership::TransferOwnershipArgs),
+    /// Update the name and the symbol of the announced Chroma.
+    Update(update::UpdateArgs),
 }
 
 pub async fn run(cmd: ChromaCommands, context: Context) -> eyre::Result<()> {
@@ -21,5 +24,6 @@ pub async fn run(cmd: ChromaCommands, context: Context) -> eyre::Result<()> {
         ChromaCommands::Announcement(args) => announcement::run(args, context).await,
         ChromaCommands::Info(args) => info::run(args, context).await,
         ChromaCommands::TransferOwnership(args) => transfer_ownership::run(args, context).await,
+        ChromaCommands::Update(args) => update::run(args, context).await,
     }
 }
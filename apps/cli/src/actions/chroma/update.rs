@@ -0,0 +1,31 @@
+use crate::{actions::announcement_args::broadcast_announcement, context::Context};
+
+use clap::Args;
+use color_eyre::eyre::{self};
+use yuv_pixels::Chroma;
+use yuv_types::Announcement;
+
+/// Arguments to make an update chroma announcement. See [`yuv_types::announcements::UpdateChromaAnnouncement`].
+#[derive(Clone, Args, Debug)]
+pub struct UpdateArgs {
+    /// The [`Chroma`] to update.
+    #[clap(long, short, value_parser = Chroma::from_address)]
+    pub chroma: Option<Chroma>,
+    /// The new name of the token.
+    #[clap(long, short)]
+    pub name: String,
+    /// The new symbol of the token.
+    #[clap(long)]
+    pub symbol: String,
+}
+
+pub async fn run(args: UpdateArgs, mut context: Context) -> eyre::Result<()> {
+    let wallet = context.wallet().await?;
+    let chroma = args
+        .chroma
+        .unwrap_or_else(|| Chroma::from(wallet.public_key()));
+
+    let announcement = Announcement::update_chroma_announcement(chroma, args.name, args.symbol)?;
+
+    broadcast_announcement(announcement, context).await
+}
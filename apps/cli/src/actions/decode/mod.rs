@@ -1,13 +1,19 @@
 use std::process::exit;
 
+use bitcoin::{consensus, hashes::hex::FromHex, Script, ScriptBuf, Transaction};
 use clap::{ArgGroup, Args};
-use yuv_types::{YuvTransaction, YuvTxType};
+use serde::Serialize;
+use serde_json::Value;
+use yuv_types::{
+    announcements::{announcement_from_script, Announcement},
+    YuvTransaction, YuvTxType,
+};
 
 #[derive(Args, Debug, Clone)]
 #[clap(group(
     ArgGroup::new("decode")
         .required(true)
-        .args(&["tx", "proofs"])
+        .args(&["tx", "proofs", "bitcoin_tx", "script"])
         .multiple(false),
 ))]
 pub struct DecodeArgs {
@@ -18,10 +24,41 @@ pub struct DecodeArgs {
 
     #[clap(long, group = "decode")]
     pub proofs: bool,
+
+    /// Decode the announcements from the `OP_RETURN` outputs of the raw Bitcoin transaction.
+    #[clap(long, group = "decode")]
+    pub bitcoin_tx: bool,
+
+    /// Decode the announcement from the `OP_RETURN` script.
+    #[clap(long, group = "decode")]
+    pub script: bool,
+
+    /// Print the decoded announcements as JSON.
+    #[clap(long)]
+    pub json: bool,
+}
+
+/// Announcement decoded from the `OP_RETURN` script, or the error why it's invalid.
+#[derive(Serialize)]
+struct DecodedAnnouncement {
+    /// Index of the transaction's output with the script.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    announcement: Option<Announcement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
 pub(crate) async fn run(
-    DecodeArgs { hex, tx, proofs }: DecodeArgs,
+    DecodeArgs {
+        hex,
+        tx,
+        proofs,
+        bitcoin_tx,
+        script,
+        json,
+    }: DecodeArgs,
 ) -> Result<(), color_eyre::Report> {
     if tx {
         let Ok(yuv_tx) = YuvTransaction::from_hex(hex) else {
@@ -41,5 +78,106 @@ pub(crate) async fn run(
         return Ok(());
     }
 
+    if bitcoin_tx {
+        let bitcoin_tx = Vec::<u8>::from_hex(&hex)
+            .ok()
+            .and_then(|bytes| consensus::deserialize::<Transaction>(&bytes).ok());
+        let Some(bitcoin_tx) = bitcoin_tx else {
+            eprintln!("The hex value could not be parsed as a Bitcoin transaction");
+            exit(1);
+        };
+
+        // Only the `OP_RETURN` outputs can contain the announcements.
+        let decoded = bitcoin_tx
+            .output
+            .iter()
+            .enumerate()
+            .filter(|(_, output)| output.script_pubkey.is_op_return())
+            .map(|(index, output)| decode_script(&output.script_pubkey, Some(index)))
+            .collect::<Vec<_>>();
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&decoded)?);
+        } else if decoded.is_empty() {
+            println!("The transaction has no OP_RETURN outputs");
+        } else {
+            for announcement in &decoded {
+                print_announcement(announcement)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    if script {
+        let Ok(script) = ScriptBuf::from_hex(&hex) else {
+            eprintln!("The hex value could not be parsed as a Bitcoin script");
+            exit(1);
+        };
+
+        let decoded = decode_script(&script, None);
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&decoded)?);
+        } else {
+            print_announcement(&decoded)?;
+        }
+
+        return Ok(());
+    }
+
+    Ok(())
+}
+
+fn decode_script(script: &Script, output: Option<usize>) -> DecodedAnnouncement {
+    match announcement_from_script(script) {
+        Ok(announcement) => DecodedAnnouncement {
+            output,
+            announcement: Some(announcement),
+            error: None,
+        },
+        Err(err) => DecodedAnnouncement {
+            output,
+            announcement: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+/// Prints the announcement's kind and fields line by line.
+fn print_announcement(decoded: &DecodedAnnouncement) -> Result<(), color_eyre::Report> {
+    if let Some(output) = decoded.output {
+        print!("Output {output}: ");
+    }
+
+    if let Some(err) = &decoded.error {
+        println!("Invalid announcement: {err}");
+        return Ok(());
+    }
+
+    let Some(announcement) = &decoded.announcement else {
+        return Ok(());
+    };
+
+    // The announcement is serialized as `{ "<Kind>": { <fields> } }`.
+    let Value::Object(kinds) = serde_json::to_value(announcement)? else {
+        return Ok(());
+    };
+
+    for (kind, fields) in kinds {
+        println!("{kind} announcement");
+
+        let Value::Object(fields) = fields else {
+            continue;
+        };
+
+        for (name, value) in fields {
+            match value {
+                Value::String(value) => println!("  {name}: {value}"),
+                value => println!("  {name}: {value}"),
+            }
+        }
+    }
+
     Ok(())
 }
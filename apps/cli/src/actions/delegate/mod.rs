@@ -0,0 +1,51 @@
+use bitcoin::PublicKey;
+use clap::Args;
+
+use color_eyre::eyre::{self, bail};
+use yuv_pixels::Chroma;
+use yuv_types::{announcements::DelegateCapabilities, Announcement};
+
+use crate::{actions::announcement_args::broadcast_announcement, context::Context};
+
+#[derive(Args, Clone, Debug)]
+pub struct DelegateArgs {
+    /// The [`Chroma`] whose capabilities are delegated
+    #[clap(long, short, value_parser = Chroma::from_address)]
+    pub chroma: Option<Chroma>,
+    /// Public key the capabilities are granted to
+    pub pubkey: PublicKey,
+    /// Grant the capability to freeze the outputs and the public keys
+    #[clap(long)]
+    pub freeze: bool,
+    /// Grant the capability to unfreeze the public keys
+    #[clap(long)]
+    pub unfreeze: bool,
+    /// Revoke all the capabilities previously granted to the public key
+    #[clap(long, conflicts_with_all = ["freeze", "unfreeze"])]
+    pub revoke: bool,
+}
+
+pub async fn run(args: DelegateArgs, mut context: Context) -> eyre::Result<()> {
+    let mut capabilities = DelegateCapabilities::NONE;
+    if args.freeze {
+        capabilities = capabilities | DelegateCapabilities::FREEZE;
+    }
+    if args.unfreeze {
+        capabilities = capabilities | DelegateCapabilities::UNFREEZE;
+    }
+
+    if capabilities.is_empty() && !args.revoke {
+        bail!("Specify the capabilities to grant, or --revoke to revoke them");
+    }
+
+    let wallet = context.wallet().await?;
+    let chroma = args
+        .chroma
+        .unwrap_or_else(|| Chroma::from(wallet.public_key()));
+
+    broadcast_announcement(
+        Announcement::delegate_announcement(chroma, args.pubkey.inner, capabilities),
+        context,
+    )
+    .await
+}
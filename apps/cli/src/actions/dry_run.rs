@@ -0,0 +1,90 @@
+use bdk::blockchain::GetTx;
+use bitcoin::{secp256k1, Network, Transaction};
+use color_eyre::eyre::{self, eyre};
+use yuv_pixels::PixelProof;
+use yuv_types::YuvTxType;
+
+/// Print the size, the fee and the outputs of the transaction built with `--dry-run`, and its
+/// proofs if it's a YUV transaction.
+///
+/// The outputs with the proofs for one of the `change_keys` are marked as change.
+pub(crate) fn print_preview(
+    blockchain: &impl GetTx,
+    network: Network,
+    tx: &Transaction,
+    tx_type: Option<&YuvTxType>,
+    change_keys: &[secp256k1::PublicKey],
+) -> eyre::Result<()> {
+    let fee = calculate_fee(blockchain, tx)?;
+    let vsize = tx.vsize();
+
+    println!("tx id: {}", tx.txid());
+    println!("vsize: {} vbytes", vsize);
+    println!(
+        "fee: {} sats ({:.2} sat/vB)",
+        fee,
+        fee as f64 / vsize as f64
+    );
+
+    let output_proofs = tx_type.and_then(YuvTxType::output_proofs);
+
+    println!("outputs:");
+    for (vout, output) in tx.output.iter().enumerate() {
+        let proof = output_proofs.and_then(|proofs| proofs.get(&(vout as u32)));
+
+        let mut line = format!("  {}: {} sats", vout, output.value);
+        if let Some(proof) = proof.filter(|proof| !proof.is_empty_pixelproof()) {
+            let pixel = proof.pixel();
+            line += &format!(
+                ", {} of {}",
+                pixel.luma.amount,
+                pixel.chroma.to_address(network)
+            );
+        }
+        if output.script_pubkey.is_op_return() {
+            line += ", announcement";
+        }
+        if proof.is_some_and(|proof| is_change(proof, change_keys)) {
+            line += " (change)";
+        }
+
+        println!("{}", line);
+    }
+
+    if let Some(tx_type) = tx_type {
+        println!("proofs: {}", serde_json::to_string_pretty(tx_type)?);
+    }
+
+    println!("The transaction is neither broadcasted nor provided to the node");
+
+    Ok(())
+}
+
+fn is_change(proof: &PixelProof, change_keys: &[secp256k1::PublicKey]) -> bool {
+    match proof {
+        PixelProof::Sig(sig) => change_keys.contains(&sig.inner_key),
+        PixelProof::EmptyPixel(empty) => change_keys.contains(&empty.inner_key),
+        _ => false,
+    }
+}
+
+/// Calculate the fee of the transaction from the values of the outputs it spends.
+pub(crate) fn calculate_fee(blockchain: &impl GetTx, tx: &Transaction) -> eyre::Result<u64> {
+    let mut inputs_sum = 0;
+    for input in &tx.input {
+        let outpoint = input.previous_output;
+        let prev_tx = blockchain
+            .get_tx(&outpoint.txid)?
+            .ok_or_else(|| eyre!("transaction {} is not found", outpoint.txid))?;
+        let prev_output = prev_tx
+            .output
+            .get(outpoint.vout as usize)
+            .ok_or_else(|| eyre!("output {} is not found", outpoint))?;
+
+        inputs_sum += prev_output.value;
+    }
+
+    let outputs_sum = tx.output.iter().map(|output| output.value).sum::<u64>();
+
+    Ok(inputs_sum.saturating_sub(outputs_sum))
+}
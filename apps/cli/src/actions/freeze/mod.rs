@@ -3,7 +3,7 @@ use clap::Args;
 
 use color_eyre::eyre;
 use yuv_pixels::Chroma;
-use yuv_types::Announcement;
+use yuv_types::{announcements::FreezeAnnouncement, Announcement};
 
 use crate::{actions::announcement_args::broadcast_announcement, context::Context};
 
@@ -16,6 +16,9 @@ pub struct FreezeArgs {
     pub txid: Txid,
     /// Output index
     pub vout: u32,
+    /// Height of the block starting from which the output is unfrozen
+    #[clap(long)]
+    pub expiry_height: Option<u32>,
 }
 
 pub async fn run(args: FreezeArgs, mut context: Context) -> eyre::Result<()> {
@@ -24,9 +27,10 @@ pub async fn run(args: FreezeArgs, mut context: Context) -> eyre::Result<()> {
         .chroma
         .unwrap_or_else(|| Chroma::from(wallet.public_key()));
 
-    broadcast_announcement(
-        Announcement::freeze_announcement(chroma, OutPoint::new(args.txid, args.vout)),
-        context,
-    )
-    .await
+    let mut announcement = FreezeAnnouncement::new(chroma, OutPoint::new(args.txid, args.vout));
+    if let Some(expiry_height) = args.expiry_height {
+        announcement = announcement.with_expiry_height(expiry_height);
+    }
+
+    broadcast_announcement(Announcement::Freeze(announcement), context).await
 }
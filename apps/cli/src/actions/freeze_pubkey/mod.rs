@@ -0,0 +1,33 @@
+use bitcoin::PublicKey;
+use clap::Args;
+
+use color_eyre::eyre;
+use yuv_pixels::Chroma;
+use yuv_types::Announcement;
+
+use crate::{actions::announcement_args::broadcast_announcement, context::Context};
+
+#[derive(Args, Clone, Debug)]
+pub struct FreezePubkeyArgs {
+    /// The [`Chroma`] to freeze
+    #[clap(long, short, value_parser = Chroma::from_address)]
+    pub chroma: Option<Chroma>,
+    /// Public key whose outputs of the chroma are frozen
+    pub pubkey: PublicKey,
+    /// Unfreeze the previously frozen public key
+    #[clap(long)]
+    pub unfreeze: bool,
+}
+
+pub async fn run(args: FreezePubkeyArgs, mut context: Context) -> eyre::Result<()> {
+    let wallet = context.wallet().await?;
+    let chroma = args
+        .chroma
+        .unwrap_or_else(|| Chroma::from(wallet.public_key()));
+
+    broadcast_announcement(
+        Announcement::freeze_pubkey_announcement(chroma, args.pubkey.inner, args.unfreeze),
+        context,
+    )
+    .await
+}
@@ -3,6 +3,7 @@ use bitcoin::{Address, Network, PublicKey};
 use clap::Args;
 use color_eyre::eyre;
 use yuv_pixels::{Pixel, PixelKey};
+use yuv_types::network::parse_bitcoin_network;
 
 #[derive(Args, Debug)]
 /// Generate P2WPKH YUV address from public key, amount and chroma.
@@ -17,7 +18,7 @@ pub struct GenerateAddressArgs {
     #[clap(long)]
     pub chroma: PublicKey,
     /// Network to use.
-    #[clap(long, short, default_value = "regtest")]
+    #[clap(long, short, default_value = "regtest", value_parser = parse_bitcoin_network)]
     pub network: Network,
 }
 
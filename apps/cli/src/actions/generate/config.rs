@@ -8,6 +8,7 @@ use ydk::{
     bitcoin_provider::{BitcoinProviderConfig, BitcoinRpcConfig},
     types::FeeRateStrategy,
 };
+use yuv_types::network::parse_bitcoin_network;
 
 use crate::{
     config::{Config, YuvNodeConfig},
@@ -24,7 +25,7 @@ pub struct GenerateConfigArgs {
     storage: PathBuf,
 
     /// Network to generate the config for
-    #[clap(long, short, default_value = "regtest")]
+    #[clap(long, short, default_value = "regtest", value_parser = parse_bitcoin_network)]
     network: Network,
 }
 
@@ -51,6 +52,9 @@ pub(crate) fn run(args: GenerateConfigArgs, context: Context) -> eyre::Result<()
         },
         fee_rate_strategy: DEFAULT_FEERATE_STRATEGY,
         storage: args.storage,
+        mnemonic: None,
+        accounts: Vec::new(),
+        keystore: None,
     };
 
     config.save_to_file(args.output)?;
@@ -1,12 +1,12 @@
-use bdk::miniscript::ToPublicKey;
 use bitcoin::{
     address::{Payload, WitnessProgram, WitnessVersion},
-    secp256k1::rand::thread_rng,
+    secp256k1::{rand::thread_rng, All, Secp256k1},
     Address, Network, PrivateKey,
 };
 use clap::Args;
 use color_eyre::eyre;
 use yuv_pixels::ToEvenPublicKey;
+use yuv_types::network::parse_bitcoin_network;
 
 use crate::context::Context;
 
@@ -14,7 +14,7 @@ use crate::context::Context;
 /// Generate P2WPKH YUV address from public key, amount and chroma.
 pub struct GenerateKeypairArgs {
     /// Network to use.
-    #[clap(long, short, default_value = "regtest")]
+    #[clap(long, short, default_value = "regtest", value_parser = parse_bitcoin_network)]
     pub network: Network,
 }
 
@@ -25,10 +25,26 @@ pub fn run(
 ) -> eyre::Result<()> {
     let secp_ctx = context.secp_ctx();
 
-    let (seckey, pubkey) = secp_ctx.generate_keypair(&mut thread_rng());
+    let (seckey, _pubkey) = secp_ctx.generate_keypair(&mut thread_rng());
 
     let privkey = PrivateKey::new(seckey, network);
-    let even_pubkey = privkey.public_key(secp_ctx).even_public_key(secp_ctx);
+    let (p2tr, p2wpkh) = keypair_addresses(&privkey, secp_ctx)?;
+
+    println!("Private key: {}", privkey);
+    println!("P2TR address: {}", p2tr);
+    println!("P2WPKH address: {}", p2wpkh);
+
+    Ok(())
+}
+
+/// Returns the P2TR and P2WPKH addresses of the key in its network.
+fn keypair_addresses(
+    privkey: &PrivateKey,
+    secp_ctx: &Secp256k1<All>,
+) -> eyre::Result<(Address, Address)> {
+    let network = privkey.network;
+    let pubkey = privkey.public_key(secp_ctx);
+    let even_pubkey = pubkey.even_public_key(secp_ctx);
 
     let (xonly, _parity) = even_pubkey.x_only_public_key();
 
@@ -42,11 +58,34 @@ pub fn run(
         ),
     );
 
-    println!("Private key: {}", privkey);
-    println!("P2TR address: {}", p2tr);
+    let p2wpkh = Address::p2wpkh(&pubkey, network)?;
+
+    Ok((p2tr, p2wpkh))
+}
 
-    let address = Address::p2wpkh(&pubkey.to_public_key(), network)?;
-    println!("P2WPKH address: {}", address);
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::SecretKey;
+    use yuv_pixels::Chroma;
 
-    Ok(())
+    use super::*;
+
+    #[test]
+    fn test_testnet4_addresses() {
+        let secp_ctx = Secp256k1::new();
+        let network = parse_bitcoin_network("testnet4").unwrap();
+        let privkey = PrivateKey::new(SecretKey::from_slice(&[1; 32]).unwrap(), network);
+
+        let (p2tr, p2wpkh) = keypair_addresses(&privkey, &secp_ctx).unwrap();
+
+        assert!(p2tr.to_string().starts_with("tb1p"));
+        assert!(p2wpkh.to_string().starts_with("tb1q"));
+
+        // The P2TR address is the one of the chroma issued with the key.
+        let xonly = privkey.public_key(&secp_ctx).inner.x_only_public_key().0;
+        assert_eq!(
+            Chroma::from_address(&p2tr.to_string()).unwrap(),
+            Chroma::from(xonly)
+        );
+    }
 }
@@ -0,0 +1,45 @@
+use bdk::blockchain::Blockchain;
+use clap::Args;
+use color_eyre::eyre::{self, OptionExt};
+use yuv_dev_kit::txbuilder::IssuancePsbt;
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct FinalizeArgs {
+    /// The issuance PSBTs signed by the co-signers in base64.
+    #[clap(long = "psbt", num_args = 1.., required = true)]
+    pub psbts: Vec<IssuancePsbt>,
+
+    /// Provide proof of the transaction to YUV node or not.
+    #[clap(long)]
+    pub do_not_provide_proofs: bool,
+}
+
+pub async fn run(
+    FinalizeArgs {
+        psbts,
+        do_not_provide_proofs,
+    }: FinalizeArgs,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    let mut psbts = psbts.into_iter();
+    let mut issuance = psbts.next().ok_or_eyre("No PSBTs to finalize")?;
+    for signed in psbts {
+        issuance.merge_signatures(signed)?;
+    }
+
+    let tx = issuance.finalize_from_psbt()?;
+
+    if do_not_provide_proofs {
+        ctx.blockchain()?.broadcast(&tx.bitcoin_tx)?;
+    } else {
+        ctx.yuv_client()?.send_yuv_tx(tx.hex(), None).await?;
+    }
+
+    println!("tx id: {}", tx.bitcoin_tx.txid());
+    println!("tx hex: {}", tx.hex());
+
+    Ok(())
+}
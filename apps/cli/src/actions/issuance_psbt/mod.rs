@@ -0,0 +1,22 @@
+use clap::Subcommand;
+use color_eyre::eyre;
+
+use crate::context::Context;
+
+mod finalize;
+mod sign;
+
+#[derive(Subcommand, Debug)]
+pub enum IssuancePsbtCommands {
+    /// Sign the owner multisig inputs of the issuance PSBT with the key from the config.
+    Sign(sign::SignArgs),
+    /// Merge the signatures of the co-signers, and send the issuance.
+    Finalize(finalize::FinalizeArgs),
+}
+
+pub async fn run(cmd: IssuancePsbtCommands, context: Context) -> eyre::Result<()> {
+    match cmd {
+        IssuancePsbtCommands::Sign(args) => sign::run(args, context),
+        IssuancePsbtCommands::Finalize(args) => finalize::run(args, context).await,
+    }
+}
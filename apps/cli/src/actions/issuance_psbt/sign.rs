@@ -0,0 +1,22 @@
+use clap::Args;
+use color_eyre::eyre;
+use yuv_dev_kit::txbuilder::IssuancePsbt;
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct SignArgs {
+    /// The issuance PSBT in base64.
+    #[clap(long)]
+    pub psbt: IssuancePsbt,
+}
+
+pub fn run(SignArgs { mut psbt }: SignArgs, mut ctx: Context) -> eyre::Result<()> {
+    let private_key = ctx.config()?.private_key;
+
+    psbt.sign_owner_inputs(&private_key, ctx.secp_ctx())?;
+
+    println!("{}", psbt);
+
+    Ok(())
+}
@@ -1,12 +1,15 @@
 use bdk::blockchain::Blockchain;
+use bitcoin::{OutPoint, PublicKey};
 use clap::Args;
 use color_eyre::eyre::{self, bail};
 use yuv_pixels::Chroma;
 use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+use yuv_types::dust::DEFAULT_PIXEL_SATOSHIS;
 
-use crate::{actions::transfer::process_satoshis, context::Context};
-
-pub const DEFAULT_SATOSHIS: u64 = 1000;
+use crate::{
+    actions::{dry_run::print_preview, transfer::process_satoshis},
+    context::Context,
+};
 
 #[derive(Args, Debug)]
 pub struct IssueArgs {
@@ -20,7 +23,7 @@ pub struct IssueArgs {
     ///
     /// Default is 10,000 satoshis, if only one amount is provided it will be
     /// used for all recipients.
-    #[clap(long, short, num_args = 1.., default_values_t = vec![DEFAULT_SATOSHIS])]
+    #[clap(long, short, num_args = 1.., default_values_t = vec![DEFAULT_PIXEL_SATOSHIS])]
     pub satoshis: Vec<u64>,
     /// YUV token amount
     #[clap(long = "amount", num_args = 1..)]
@@ -36,6 +39,26 @@ pub struct IssueArgs {
     /// It's worth noting that change from regular satoshis will be tweaked.
     #[clap(long)]
     pub drain_tweaked_satoshis: bool,
+    /// Output of the chroma's owner to spend, if the ownership is transferred to the P2WSH
+    /// `sortedmulti` of the issuers. Such issuance is co-signed, so `--psbt` is required.
+    #[clap(
+        long,
+        requires_all = ["owner_participants", "owner_required_signatures", "psbt"]
+    )]
+    pub owner_outpoint: Option<OutPoint>,
+    /// Public keys of the participants of the owner multisig.
+    #[clap(long = "owner-participant", num_args = 1..)]
+    pub owner_participants: Vec<PublicKey>,
+    /// Number of the signatures required by the owner multisig.
+    #[clap(long)]
+    pub owner_required_signatures: Option<u8>,
+    /// Print the PSBT to be co-signed by the participants of the owner multisig with
+    /// `issuance-psbt sign`, instead of sending the issuance.
+    #[clap(long, requires = "owner_outpoint")]
+    pub psbt: bool,
+    /// Build the transaction and show its size, fee, outputs and proofs without sending it.
+    #[clap(long, conflicts_with = "psbt")]
+    pub dry_run: bool,
 }
 
 pub async fn run(
@@ -46,6 +69,11 @@ pub async fn run(
         do_not_provide_proofs,
         drain_tweaked_satoshis,
         chroma,
+        owner_outpoint,
+        owner_participants,
+        owner_required_signatures,
+        psbt,
+        dry_run,
     }: IssueArgs,
     mut ctx: Context,
 ) -> eyre::Result<()> {
@@ -70,9 +98,38 @@ pub async fn run(
             .set_fee_rate_strategy(cfg.fee_rate_strategy)
             .set_drain_tweaked_satoshis(drain_tweaked_satoshis);
 
+        if let (Some(outpoint), Some(required_signatures)) =
+            (owner_outpoint, owner_required_signatures)
+        {
+            builder.add_owner_multisig_input(
+                outpoint,
+                owner_participants.iter().map(|key| key.inner).collect(),
+                required_signatures,
+            );
+        }
+
+        if psbt {
+            let issuance_psbt = builder.finish_cosigning_psbt(&blockchain).await?;
+            println!("{}", issuance_psbt);
+
+            return Ok(());
+        }
+
         builder.finish(&blockchain).await?
     };
 
+    if dry_run {
+        let change_keys = [wallet.public_key().inner, wallet.change_key()];
+
+        return print_preview(
+            blockchain.as_ref(),
+            cfg.network(),
+            &tx.bitcoin_tx,
+            Some(&tx.tx_type),
+            &change_keys,
+        );
+    }
+
     if do_not_provide_proofs {
         blockchain.broadcast(&tx.bitcoin_tx)?;
     } else {
@@ -10,9 +10,11 @@ use get::GetArgs;
 use tracing_log::AsTrace;
 
 use self::{
-    convert::ConvertCommands, freeze::FreezeArgs, generate::GenerateCommands, issue::IssueArgs,
-    provide::ProvideArgs, transfer::TransferArgs, utxos::UtxosArgs, validate::ValidateArgs,
-    wallet::WalletCommands,
+    bundle::BundleCommands, convert::ConvertCommands, delegate::DelegateArgs, freeze::FreezeArgs,
+    freeze_pubkey::FreezePubkeyArgs, generate::GenerateCommands,
+    issuance_psbt::IssuancePsbtCommands, issue::IssueArgs, provide::ProvideArgs,
+    reserves::ReservesCommands, swap::SwapCommands, sweep::SweepArgs, transfer::TransferArgs,
+    utxos::UtxosArgs, validate::ValidateArgs, wallet::WalletCommands, watch::WatchArgs,
 };
 use crate::context::Context;
 
@@ -20,24 +22,32 @@ mod announcement_args;
 mod balances;
 #[cfg(feature = "bulletproof")]
 mod bulletproof;
+mod bundle;
 mod burn;
 mod chroma;
 mod convert;
 mod decode;
+mod delegate;
+mod dry_run;
 mod freeze;
+mod freeze_pubkey;
 mod generate;
 mod get;
+mod issuance_psbt;
 mod issue;
 mod p2tr;
 mod p2wpkh;
 mod proof;
 mod provide;
+mod reserves;
 mod rpc_args;
+mod swap;
 mod sweep;
 mod transfer;
 mod utxos;
 mod validate;
 mod wallet;
+mod watch;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -50,6 +60,10 @@ pub struct Cli {
 
     #[clap(short, long, default_value = "config.toml")]
     pub config: PathBuf,
+
+    /// Account derived from the mnemonic of the wallet to use, see `wallet init`.
+    #[clap(long, global = true)]
+    pub account: Option<u32>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -68,18 +82,34 @@ pub enum Commands {
     /// Issue new tokens.
     Issue(IssueArgs),
 
+    /// Co-sign the issuance of the chroma owned by the multisig.
+    #[command(subcommand)]
+    IssuancePsbt(IssuancePsbtCommands),
+
     /// Transfer tokens
     Transfer(TransferArgs),
 
     /// Burn tokens
     Burn(BurnArgs),
 
+    /// Atomically swap tokens of different chromas with another party
+    #[command(subcommand)]
+    Swap(SwapCommands),
+
+    /// Prove the ownership of the tokens without moving them, or verify the proof
+    #[command(subcommand)]
+    Reserves(ReservesCommands),
+
+    /// Export the proof bundle of the output, or verify and import the received one
+    #[command(subcommand)]
+    Bundle(BundleCommands),
+
     /// Decode raw YUV transaction
     Decode(DecodeArgs),
 
     /// Sweep tweaked Bitcoin UTXOs created with the YUV protocol.
     /// Outputs will be sweeped to a p2wpkh address.
-    Sweep,
+    Sweep(SweepArgs),
 
     /// Validate pixel proof of provided transaction.
     Validate(ValidateArgs),
@@ -87,6 +117,12 @@ pub enum Commands {
     /// Send freeze transaction
     Freeze(FreezeArgs),
 
+    /// Send freeze transaction for all the outputs of the public key
+    FreezePubkey(FreezePubkeyArgs),
+
+    /// Send delegation of the freeze and unfreeze capabilities of the chroma to the public key
+    Delegate(DelegateArgs),
+
     /// Provide proof to node
     Provide(ProvideArgs),
 
@@ -100,6 +136,10 @@ pub enum Commands {
     #[command(subcommand)]
     Wallet(WalletCommands),
 
+    /// Print the transactions, freezes and announcements attached by the node that affect the
+    /// chroma or the wallet's key, as they come.
+    Watch(WatchArgs),
+
     /// Get the p2wpkh address of the current user.
     P2WPKH,
 
@@ -123,7 +163,7 @@ impl Cli {
             .with_max_level(self.verbosity.log_level_filter().as_trace())
             .init();
 
-        let context = Context::new(self.config);
+        let context = Context::new(self.config, self.account);
         execute_command(self.command, context).await
     }
 }
@@ -133,10 +173,16 @@ async fn execute_command(command: Commands, context: Context) -> eyre::Result<()
     match command {
         Cmd::Generate(cmd) => generate::run(cmd, context),
         Cmd::Issue(args) => issue::run(args, context).await,
+        Cmd::IssuancePsbt(cmd) => issuance_psbt::run(cmd, context).await,
         Cmd::Transfer(args) => transfer::run(args, context).await,
         Cmd::Burn(args) => burn::run(args, context).await,
+        Cmd::Swap(cmd) => swap::run(cmd, context).await,
+        Cmd::Reserves(cmd) => reserves::run(cmd, context).await,
+        Cmd::Bundle(cmd) => bundle::run(cmd, context).await,
         Cmd::Validate(args) => validate::run(args, context).await,
         Cmd::Freeze(args) => freeze::run(args, context).await,
+        Cmd::FreezePubkey(args) => freeze_pubkey::run(args, context).await,
+        Cmd::Delegate(args) => delegate::run(args, context).await,
         Cmd::Provide(args) => provide::run(args, context).await,
         Cmd::Get(args) => get::run(args, context).await,
         Cmd::Balances => balances::run(context).await,
@@ -147,9 +193,10 @@ async fn execute_command(command: Commands, context: Context) -> eyre::Result<()
         Cmd::Convert(args) => convert::run(args),
         Cmd::P2WPKH => p2wpkh::run(context),
         Cmd::P2TR => p2tr::run(context),
-        Cmd::Sweep => sweep::run(context).await,
+        Cmd::Sweep(args) => sweep::run(args, context).await,
         Cmd::Chroma(cmd) => chroma::run(cmd, context).await,
         Cmd::Decode(args) => decode::run(args).await,
+        Cmd::Watch(args) => watch::run(args, context).await,
     }
 }
 
@@ -0,0 +1,23 @@
+use clap::Subcommand;
+use color_eyre::eyre;
+
+use crate::context::Context;
+
+mod prove;
+mod verify;
+
+#[derive(Subcommand, Debug)]
+pub enum ReservesCommands {
+    /// Sign the challenge with the keys of the outputs with the chroma, to prove the ownership
+    /// of the tokens without moving them.
+    Prove(prove::ProveArgs),
+    /// Verify the proof of reserves, and check with the nodes that its outputs are unspent.
+    Verify(verify::VerifyArgs),
+}
+
+pub async fn run(cmd: ReservesCommands, context: Context) -> eyre::Result<()> {
+    match cmd {
+        ReservesCommands::Prove(args) => prove::run(args, context).await,
+        ReservesCommands::Verify(args) => verify::run(args, context).await,
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use bitcoin::OutPoint;
+use clap::Args;
+use color_eyre::eyre::{self, Context as EyreContext};
+use yuv_pixels::Chroma;
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct ProveArgs {
+    /// Type of the token to prove the reserves of, public key of the issuer.
+    #[clap(long, value_parser = Chroma::from_address)]
+    pub chroma: Chroma,
+
+    /// Message chosen by the verifier, e.g. the name of the auditor and the date.
+    #[clap(long)]
+    pub challenge: String,
+
+    /// Outputs to attest, the tx id and vout separated with `:` symbol. All the outputs with the
+    /// chroma if not specified.
+    #[clap(long, num_args = 1..)]
+    pub outpoint: Vec<OutPoint>,
+
+    /// Path to the file to save the proof to, printed if not specified.
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+}
+
+pub async fn run(
+    ProveArgs {
+        chroma,
+        challenge,
+        outpoint,
+        output,
+    }: ProveArgs,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    let wallet = ctx.wallet().await?;
+
+    let outpoints = (!outpoint.is_empty()).then_some(outpoint.as_slice());
+    let proof = wallet.prove_reserves(chroma, challenge, outpoints)?;
+    let proof_json = serde_json::to_string_pretty(&proof)?;
+
+    let Some(output) = output else {
+        println!("{}", proof_json);
+        return Ok(());
+    };
+
+    std::fs::write(&output, proof_json)
+        .wrap_err_with(|| format!("Failed to write the proof to {}", output.display()))?;
+
+    println!(
+        "Proof of {} outputs is saved to {}",
+        proof.outputs.len(),
+        output.display()
+    );
+
+    Ok(())
+}
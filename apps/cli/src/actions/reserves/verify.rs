@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::eyre::{self, bail, ensure, Context as EyreContext};
+use ydk::bitcoin_provider::{BitcoinProvider, TxOutputStatus};
+use yuv_pixels::{verify_proof_of_reserves, PixelProof, ProofOfReserves};
+use yuv_rpc_api::transactions::{YuvTransactionStatus, YuvTransactionsRpcClient};
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Path to the file with the proof of reserves.
+    pub proof: PathBuf,
+
+    /// Expected challenge of the proof.
+    #[clap(long)]
+    pub challenge: Option<String>,
+}
+
+pub async fn run(
+    VerifyArgs { proof, challenge }: VerifyArgs,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    let proof_json = std::fs::read_to_string(&proof)
+        .wrap_err_with(|| format!("Failed to read the proof from {}", proof.display()))?;
+    let proof: ProofOfReserves =
+        serde_json::from_str(&proof_json).wrap_err("Failed to parse the proof of reserves")?;
+
+    if let Some(challenge) = challenge {
+        ensure!(
+            proof.challenge == challenge,
+            "The proof is made for another challenge: {}",
+            proof.challenge
+        );
+    }
+
+    let total = verify_proof_of_reserves(&proof)?;
+
+    let yuv_client = ctx.yuv_client()?;
+    let bitcoin_provider = ctx.bitcoin_provider()?;
+
+    // The signatures prove only the ownership of the keys, so the outputs are checked to be
+    // attached with the same pixels and keys, and not spent yet.
+    for output in &proof.outputs {
+        let outpoint = output.outpoint;
+
+        let yuv_tx = yuv_client.get_yuv_transaction(outpoint.txid).await?;
+        if yuv_tx.status != YuvTransactionStatus::Attached {
+            bail!("Transaction {} is not attached by YUV node", outpoint.txid);
+        }
+        let Some(attached_tx) = yuv_tx.data else {
+            bail!(
+                "Transaction {} is not present in the node's storage",
+                outpoint.txid
+            );
+        };
+
+        let attached_proof = attached_tx
+            .tx_type
+            .output_proofs()
+            .and_then(|proofs| proofs.get(&outpoint.vout));
+        let Some(PixelProof::Sig(attached_proof)) = attached_proof else {
+            bail!("Output {} is not a single signature one", outpoint);
+        };
+        ensure!(
+            attached_proof.pixel == output.pixel
+                && attached_proof.inner_key.x_only_public_key().0
+                    == output.inner_key.x_only_public_key().0,
+            "Output {} doesn't match the attached one",
+            outpoint
+        );
+
+        match bitcoin_provider.get_tx_out_status(outpoint)? {
+            TxOutputStatus::Unspent => {}
+            TxOutputStatus::Spent => bail!("Output {} is spent", outpoint),
+            TxOutputStatus::NotFound => bail!("Output {} is not found", outpoint),
+        }
+    }
+
+    println!(
+        "Proof of reserves is valid: {} outputs with {} tokens of {}",
+        proof.outputs.len(),
+        total,
+        proof.chroma.to_address(ctx.config()?.network())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use clap::Args;
+use color_eyre::eyre;
+use yuv_dev_kit::txbuilder::{SwapPsbt, SwapTransactionBuilder};
+
+use super::{build_half, check_received, ExpectArgs, GiveArgs};
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct AcceptArgs {
+    /// The offer of the counterparty in base64.
+    #[clap(long)]
+    pub offer: SwapPsbt,
+
+    #[clap(flatten)]
+    pub give: GiveArgs,
+
+    #[clap(flatten)]
+    pub expect: ExpectArgs,
+}
+
+pub async fn run(
+    AcceptArgs {
+        offer,
+        give,
+        expect,
+    }: AcceptArgs,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    let half = build_half(give, &mut ctx).await?;
+
+    let mut builder = SwapTransactionBuilder::new();
+    builder.add_half(offer).add_half(half);
+    let mut swap = builder.finish()?;
+
+    check_received(&swap, expect, &mut ctx).await?;
+
+    let wallet = ctx.wallet().await?;
+    wallet.sign_swap(&mut swap).await?;
+
+    println!("{}", swap);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use bdk::blockchain::Blockchain;
+use clap::Args;
+use color_eyre::eyre;
+use yuv_dev_kit::txbuilder::SwapPsbt;
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+
+use super::{check_received, ExpectArgs};
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct CompleteArgs {
+    /// The swap accepted by the counterparty in base64.
+    #[clap(long)]
+    pub swap: SwapPsbt,
+
+    #[clap(flatten)]
+    pub expect: ExpectArgs,
+
+    /// Provide proof of the transaction to YUV node or not.
+    #[clap(long)]
+    pub do_not_provide_proofs: bool,
+}
+
+pub async fn run(
+    CompleteArgs {
+        mut swap,
+        expect,
+        do_not_provide_proofs,
+    }: CompleteArgs,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    check_received(&swap, expect, &mut ctx).await?;
+
+    let wallet = ctx.wallet().await?;
+    wallet.sign_swap(&mut swap).await?;
+
+    let tx = swap.finalize()?;
+
+    if do_not_provide_proofs {
+        ctx.blockchain()?.broadcast(&tx.bitcoin_tx)?;
+    } else {
+        ctx.yuv_client()?.send_yuv_tx(tx.hex(), None).await?;
+    }
+
+    println!("tx id: {}", tx.bitcoin_tx.txid());
+
+    Ok(())
+}
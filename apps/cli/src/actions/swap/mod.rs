@@ -0,0 +1,106 @@
+use clap::{Args, Subcommand};
+use color_eyre::eyre::{self, ensure};
+use yuv_dev_kit::txbuilder::SwapPsbt;
+use yuv_pixels::Chroma;
+use yuv_types::dust::DEFAULT_PIXEL_SATOSHIS;
+
+use crate::context::Context;
+
+mod accept;
+mod complete;
+mod offer;
+
+#[derive(Subcommand, Debug)]
+pub enum SwapCommands {
+    /// Create the offer: the unsigned half of the swap with the tokens to give.
+    Offer(offer::OfferArgs),
+    /// Accept the offer: add the half with the tokens to give, and sign own inputs.
+    Accept(accept::AcceptArgs),
+    /// Complete the accepted swap: sign own inputs and broadcast the transaction.
+    Complete(complete::CompleteArgs),
+}
+
+pub async fn run(cmd: SwapCommands, context: Context) -> eyre::Result<()> {
+    match cmd {
+        SwapCommands::Offer(args) => offer::run(args, context).await,
+        SwapCommands::Accept(args) => accept::run(args, context).await,
+        SwapCommands::Complete(args) => complete::run(args, context).await,
+    }
+}
+
+/// Tokens the party gives to the counterparty in the swap.
+#[derive(Args, Debug)]
+pub struct GiveArgs {
+    /// Type of the token to give, public key of the issuer.
+    #[clap(long, value_parser = Chroma::from_address)]
+    pub chroma: Chroma,
+
+    /// Amount of the tokens to give.
+    #[clap(long)]
+    pub amount: u128,
+
+    /// The public key of the counterparty.
+    #[clap(long, value_parser = Chroma::from_address)]
+    pub recipient: Chroma,
+
+    /// Satoshis to put in the output with the tokens.
+    #[clap(long, default_value_t = DEFAULT_PIXEL_SATOSHIS)]
+    pub satoshis: u64,
+}
+
+/// Tokens the party expects to receive from the counterparty in the swap.
+#[derive(Args, Debug)]
+pub struct ExpectArgs {
+    /// Type of the token to receive, public key of the issuer.
+    #[clap(long, value_parser = Chroma::from_address)]
+    pub expect_chroma: Chroma,
+
+    /// Minimal amount of the tokens to receive.
+    #[clap(long)]
+    pub expect_amount: u128,
+}
+
+/// Build the unsigned half of the swap with the tokens the wallet gives.
+async fn build_half(
+    GiveArgs {
+        chroma,
+        amount,
+        recipient,
+        satoshis,
+    }: GiveArgs,
+    ctx: &mut Context,
+) -> eyre::Result<SwapPsbt> {
+    let wallet = ctx.wallet().await?;
+    let blockchain = ctx.blockchain()?;
+    let cfg = ctx.config()?;
+
+    let mut builder = wallet.build_transfer()?;
+    builder
+        .add_recipient(chroma, &recipient.public_key().inner, amount, satoshis)
+        .set_fee_rate_strategy(cfg.fee_rate_strategy);
+
+    builder.finish_swap_half(&blockchain).await
+}
+
+/// Check that the swap transfers the expected tokens to the wallet.
+async fn check_received(
+    swap: &SwapPsbt,
+    ExpectArgs {
+        expect_chroma,
+        expect_amount,
+    }: ExpectArgs,
+    ctx: &mut Context,
+) -> eyre::Result<()> {
+    let wallet = ctx.wallet().await?;
+
+    let received = swap.received_amount(expect_chroma, &wallet.public_key().inner)?;
+    ensure!(
+        received >= expect_amount,
+        "Swap transfers {} of {} to the wallet, expected at least {}",
+        received,
+        expect_chroma,
+        expect_amount
+    );
+
+    Ok(())
+}
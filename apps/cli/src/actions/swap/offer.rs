@@ -0,0 +1,19 @@
+use clap::Args;
+use color_eyre::eyre;
+
+use super::{build_half, GiveArgs};
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct OfferArgs {
+    #[clap(flatten)]
+    pub give: GiveArgs,
+}
+
+pub async fn run(OfferArgs { give }: OfferArgs, mut ctx: Context) -> eyre::Result<()> {
+    let offer = build_half(give, &mut ctx).await?;
+
+    println!("{}", offer);
+
+    Ok(())
+}
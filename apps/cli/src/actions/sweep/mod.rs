@@ -1,9 +1,17 @@
 use bdk::blockchain::Blockchain;
+use clap::Args;
 use color_eyre::eyre;
 
-use crate::context::Context;
+use crate::{actions::dry_run::print_preview, context::Context};
 
-pub async fn run(mut ctx: Context) -> eyre::Result<()> {
+#[derive(Args, Debug)]
+pub struct SweepArgs {
+    /// Build the transaction and show its size, fee and outputs without sending it.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+pub async fn run(SweepArgs { dry_run }: SweepArgs, mut ctx: Context) -> eyre::Result<()> {
     let wallet = ctx.wallet().await?;
     let blockchain = ctx.blockchain()?;
     let cfg = ctx.config()?;
@@ -21,6 +29,10 @@ pub async fn run(mut ctx: Context) -> eyre::Result<()> {
         return Ok(());
     };
 
+    if dry_run {
+        return print_preview(blockchain.as_ref(), cfg.network(), &tx, None, &[]);
+    }
+
     blockchain.broadcast(&tx)?;
 
     println!("tx id: {}", tx.txid());
@@ -0,0 +1,195 @@
+use std::{collections::HashSet, path::Path};
+
+use bdk::blockchain::Blockchain;
+use color_eyre::eyre::{self, bail, eyre, Context as _};
+use serde::Deserialize;
+use yuv_pixels::Chroma;
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+use yuv_types::dust::DEFAULT_PIXEL_SATOSHIS;
+
+use crate::{actions::dry_run::calculate_fee, context::Context};
+
+/// Row of the payouts file.
+#[derive(Deserialize, Debug)]
+struct PayoutRecord {
+    recipient: String,
+    chroma: String,
+    amount: u128,
+    satoshis: Option<u64>,
+}
+
+#[derive(Debug)]
+struct Payout {
+    recipient: Chroma,
+    chroma: Chroma,
+    amount: u128,
+    satoshis: u64,
+}
+
+pub(crate) struct BatchTransferArgs<'a> {
+    pub path: &'a Path,
+    pub max_outputs: usize,
+    pub broadcast: bool,
+    pub do_not_provide_proofs: bool,
+    pub drain_tweaked_satoshis: bool,
+}
+
+/// Build the transfers to the recipients from the CSV file, print the fees and broadcast them
+/// if asked to.
+pub(crate) async fn run(
+    BatchTransferArgs {
+        path,
+        max_outputs,
+        broadcast,
+        do_not_provide_proofs,
+        drain_tweaked_satoshis,
+    }: BatchTransferArgs<'_>,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    let payouts = read_payouts(path)?;
+    let batches = split_into_batches(payouts, max_outputs)?;
+
+    let wallet = ctx.wallet().await?;
+    let blockchain = ctx.blockchain()?;
+    let cfg = ctx.config()?;
+
+    // The transactions are built before any of them is broadcasted, so the outpoints spent by
+    // the previous ones are excluded from the coin selection of the next ones.
+    let mut spent = HashSet::new();
+    let mut txs = Vec::with_capacity(batches.len());
+
+    for batch in &batches {
+        let mut builder = wallet.build_transfer()?;
+
+        for payout in batch {
+            builder.add_recipient(
+                payout.chroma,
+                &payout.recipient.public_key().inner,
+                payout.amount,
+                payout.satoshis,
+            );
+        }
+
+        for outpoint in &spent {
+            builder.add_unspendable(*outpoint);
+        }
+
+        builder
+            .set_fee_rate_strategy(cfg.fee_rate_strategy)
+            .set_drain_tweaked_satoshis(drain_tweaked_satoshis);
+
+        let tx = builder.finish(&blockchain).await?;
+
+        spent.extend(
+            tx.bitcoin_tx
+                .input
+                .iter()
+                .map(|input| input.previous_output),
+        );
+        txs.push(tx);
+    }
+
+    let mut total_fee = 0;
+    for (index, (tx, batch)) in txs.iter().zip(&batches).enumerate() {
+        let fee = calculate_fee(blockchain.as_ref(), &tx.bitcoin_tx)?;
+        total_fee += fee;
+
+        println!(
+            "tx {}: {} recipients, {} outputs, {} vbytes, fee: {} sats",
+            index + 1,
+            batch.len(),
+            tx.bitcoin_tx.output.len(),
+            tx.bitcoin_tx.vsize(),
+            fee,
+        );
+    }
+    println!("total fee: {} sats", total_fee);
+
+    if !broadcast {
+        println!("The transactions are not broadcasted, use --broadcast to send them");
+        return Ok(());
+    }
+
+    for tx in &txs {
+        if do_not_provide_proofs {
+            blockchain.broadcast(&tx.bitcoin_tx)?;
+        } else {
+            let client = ctx.yuv_client()?;
+
+            client.send_yuv_tx(tx.hex(), None).await?;
+        }
+
+        println!("tx id: {}", tx.bitcoin_tx.txid());
+    }
+
+    Ok(())
+}
+
+/// Read the payouts from the CSV file with the `recipient,chroma,amount,satoshis` header. The
+/// `satoshis` column can be omitted or left empty to use the default.
+fn read_payouts(path: &Path) -> eyre::Result<Vec<Payout>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_path(path)
+        .wrap_err_with(|| format!("failed to open {}", path.display()))?;
+
+    let mut payouts = Vec::new();
+    for (index, record) in reader.deserialize::<PayoutRecord>().enumerate() {
+        // The header is the first line.
+        let line = index + 2;
+        let record = record.wrap_err_with(|| format!("invalid payout on line {}", line))?;
+
+        let recipient = Chroma::from_address(&record.recipient)
+            .map_err(|err| eyre!("invalid recipient on line {}: {}", line, err))?;
+        let chroma = Chroma::from_address(&record.chroma)
+            .map_err(|err| eyre!("invalid chroma on line {}: {}", line, err))?;
+
+        payouts.push(Payout {
+            recipient,
+            chroma,
+            amount: record.amount,
+            satoshis: record.satoshis.unwrap_or(DEFAULT_PIXEL_SATOSHIS),
+        });
+    }
+
+    if payouts.is_empty() {
+        bail!("{} has no payouts", path.display());
+    }
+
+    Ok(payouts)
+}
+
+/// Split the payouts into the batches that fit into the transactions with at most `max_outputs`
+/// outputs. Each transaction has the YUV change output for every chroma it transfers and the
+/// Bitcoin change output, so the payouts are grouped by chroma to need fewer of them.
+fn split_into_batches(
+    mut payouts: Vec<Payout>,
+    max_outputs: usize,
+) -> eyre::Result<Vec<Vec<Payout>>> {
+    // One recipient, its chroma's change and the Bitcoin change.
+    if max_outputs < 3 {
+        bail!("The transaction must have at least 3 outputs to transfer the tokens");
+    }
+
+    payouts.sort_by_key(|payout| payout.chroma);
+
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut chromas = HashSet::new();
+
+    for payout in payouts {
+        let change_outputs = chromas.len() + usize::from(!chromas.contains(&payout.chroma)) + 1;
+
+        if batch.len() + 1 + change_outputs > max_outputs {
+            batches.push(std::mem::take(&mut batch));
+            chromas.clear();
+        }
+
+        chromas.insert(payout.chroma);
+        batch.push(payout);
+    }
+    batches.push(batch);
+
+    Ok(batches)
+}
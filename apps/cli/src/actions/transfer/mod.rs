@@ -1,11 +1,17 @@
-use crate::{check_equal_lengths, context::Context};
+use std::path::PathBuf;
+
+use crate::{actions::dry_run::print_preview, check_equal_lengths, context::Context};
+use batch::BatchTransferArgs;
 use bdk::blockchain::Blockchain;
 use clap::Args;
 use color_eyre::eyre::{self, Ok};
 use yuv_pixels::Chroma;
 use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+use yuv_types::dust::DEFAULT_PIXEL_SATOSHIS;
+
+mod batch;
 
-const DEFAULT_SATOSHIS: u64 = 1000;
+const DEFAULT_MAX_OUTPUTS: usize = 100;
 
 #[derive(Args, Debug)]
 pub struct TransferArgs {
@@ -15,7 +21,7 @@ pub struct TransferArgs {
 
     /// Satoshis to spend. Specify it either once to override the default,
     /// or per chroma to use a different number of satoshis in each output.
-    #[clap(long, short, num_args = 1.., default_values_t = vec![DEFAULT_SATOSHIS])]
+    #[clap(long, short, num_args = 1.., default_values_t = vec![DEFAULT_PIXEL_SATOSHIS])]
     pub satoshis: Vec<u64>,
 
     /// Type of the token, public key of the issuer.
@@ -35,6 +41,23 @@ pub struct TransferArgs {
     /// It's worth noting that change from regular satoshis will be tweaked.
     #[clap(long)]
     pub drain_tweaked_satoshis: bool,
+
+    /// CSV file with the `recipient,chroma,amount,satoshis` rows to transfer the tokens to
+    /// many recipients at once. The `satoshis` column is optional.
+    #[clap(long, conflicts_with_all = ["amount", "chroma", "recipient", "satoshis"])]
+    pub from_file: Option<PathBuf>,
+
+    /// Max number of outputs in each transaction built from the file, including the change.
+    #[clap(long, requires = "from_file", default_value_t = DEFAULT_MAX_OUTPUTS)]
+    pub max_outputs: usize,
+
+    /// Broadcast the transactions built from the file. Without it only the fees are shown.
+    #[clap(long, requires = "from_file")]
+    pub broadcast: bool,
+
+    /// Build the transaction and show its size, fee, outputs and proofs without sending it.
+    #[clap(long, conflicts_with = "from_file")]
+    pub dry_run: bool,
 }
 
 // TODO: refactor this, please...
@@ -46,9 +69,25 @@ pub async fn run(
         recipient,
         do_not_provide_proofs,
         drain_tweaked_satoshis,
+        from_file,
+        max_outputs,
+        broadcast,
+        dry_run,
     }: TransferArgs,
     mut ctx: Context,
 ) -> eyre::Result<()> {
+    if let Some(path) = from_file {
+        let args = BatchTransferArgs {
+            path: &path,
+            max_outputs,
+            broadcast,
+            do_not_provide_proofs,
+            drain_tweaked_satoshis,
+        };
+
+        return batch::run(args, ctx).await;
+    }
+
     check_equal_lengths!(amount, chroma, recipient);
 
     let wallet = ctx.wallet().await?;
@@ -75,6 +114,18 @@ pub async fn run(
         builder.finish(&blockchain).await?
     };
 
+    if dry_run {
+        let change_keys = [wallet.public_key().inner, wallet.change_key()];
+
+        return print_preview(
+            blockchain.as_ref(),
+            cfg.network(),
+            &tx.bitcoin_tx,
+            Some(&tx.tx_type),
+            &change_keys,
+        );
+    }
+
     if do_not_provide_proofs {
         blockchain.broadcast(&tx.bitcoin_tx)?;
     } else {
@@ -0,0 +1,41 @@
+use clap::Args;
+use color_eyre::eyre::{self, Context as EyreContext};
+use yuv_pixels::Chroma;
+
+use crate::{
+    config::{derive_account_key, AccountConfig, Config},
+    context::Context,
+};
+
+#[derive(Args, Debug)]
+pub struct AddAccountArgs {
+    /// Human-readable name of the account.
+    #[clap(long)]
+    pub name: Option<String>,
+}
+
+/// Add the next account derived from the mnemonic to the config, and print its index and
+/// address.
+pub fn run(AddAccountArgs { name }: AddAccountArgs, ctx: Context) -> eyre::Result<()> {
+    let config_path = ctx.config_path().clone();
+    let mut config = Config::from_path(config_path.clone()).wrap_err("Failed to load config")?;
+
+    let mnemonic = config.mnemonic(ctx.secp_ctx())?;
+
+    let index = config
+        .accounts
+        .iter()
+        .map(|account| account.index + 1)
+        .max()
+        .unwrap_or(0);
+
+    let private_key = derive_account_key(&mnemonic, config.network(), index, ctx.secp_ctx())?;
+    let address = Chroma::from(private_key.public_key(ctx.secp_ctx())).to_address(config.network());
+
+    config.accounts.push(AccountConfig { index, name });
+    config.save_to_file(config_path)?;
+
+    println!("Account {}: {}", index, address);
+
+    Ok(())
+}
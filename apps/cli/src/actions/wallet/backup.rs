@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::eyre::{self, Context as EyreContext};
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct BackupArgs {
+    /// Path to the backup file to create.
+    pub output: PathBuf,
+}
+
+pub async fn run(BackupArgs { output }: BackupArgs, mut ctx: Context) -> eyre::Result<()> {
+    let wallet = ctx.wallet().await?;
+
+    let backup = wallet.export_backup().await?;
+    std::fs::write(&output, backup)
+        .wrap_err_with(|| format!("Failed to write the backup to {}", output.display()))?;
+
+    println!("Wallet backup is saved to {}", output.display());
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::eyre::{self, bail, Context as EyreContext};
+
+use crate::{config::Config, context::Context};
+
+#[derive(Args, Debug)]
+pub struct EncryptArgs {
+    /// Path to the keystore file to create.
+    #[clap(long)]
+    pub keystore: PathBuf,
+}
+
+/// Move the `private_key` from the config to the keystore encrypted with the passphrase along
+/// with the mnemonic of the initialized wallet, and replace the key in the config with the path
+/// to the keystore.
+pub fn run(EncryptArgs { keystore }: EncryptArgs, ctx: Context) -> eyre::Result<()> {
+    let config_path = ctx.config_path().clone();
+    let mut config = Config::from_path(config_path.clone()).wrap_err("Failed to load config")?;
+
+    if config.keystore.is_some() {
+        bail!("The key of the wallet is already encrypted");
+    }
+    if keystore.exists() {
+        bail!("{} already exists", keystore.display());
+    }
+
+    let mnemonic = if config.accounts.is_empty() {
+        None
+    } else {
+        Some(config.mnemonic(ctx.secp_ctx())?)
+    };
+
+    let passphrase = yuv_keystore::new_passphrase()?;
+    let secrets = yuv_keystore::Secrets::new(config.private_key.inner).with_mnemonic(mnemonic);
+    yuv_keystore::save(&keystore, &secrets, &passphrase)?;
+
+    config.keystore = Some(keystore);
+    config.save_to_file(config_path)?;
+
+    println!("The key is encrypted, unlock the wallet with the passphrase to use it");
+
+    Ok(())
+}
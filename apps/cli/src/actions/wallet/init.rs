@@ -0,0 +1,62 @@
+use bdk::keys::bip39::Mnemonic;
+use bitcoin::secp256k1::rand::{thread_rng, RngCore};
+use clap::Args;
+use color_eyre::eyre::{self, bail, Context as EyreContext};
+
+use crate::{
+    config::{derive_account_key, AccountConfig, Config, MNEMONIC_ENV},
+    context::Context,
+};
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// BIP39 mnemonic to restore the wallet from. If not specified, the new one is generated.
+    #[clap(long)]
+    pub mnemonic: Option<String>,
+}
+
+/// Replace the `private_key` in the config with the key of the account `0` derived from the
+/// mnemonic. The mnemonic itself isn't stored in the config, it's taken from the keystore, the
+/// environment or the prompt to derive the other accounts.
+pub fn run(InitArgs { mnemonic }: InitArgs, ctx: Context) -> eyre::Result<()> {
+    let config_path = ctx.config_path().clone();
+    let mut config = Config::from_path(config_path.clone()).wrap_err("Failed to load config")?;
+
+    if !config.accounts.is_empty() {
+        bail!("The wallet is already initialized with the mnemonic");
+    }
+    if config.keystore.is_some() {
+        bail!("The key of the wallet is in the keystore, the mnemonic can't replace it");
+    }
+
+    let is_generated = mnemonic.is_none();
+    let mnemonic = match mnemonic {
+        Some(mnemonic) => Mnemonic::parse(mnemonic).wrap_err("Invalid mnemonic")?,
+        None => {
+            let mut entropy = [0u8; 32];
+            thread_rng().fill_bytes(&mut entropy);
+
+            Mnemonic::from_entropy(&entropy)?
+        }
+    };
+
+    config.private_key =
+        derive_account_key(&mnemonic.to_string(), config.network(), 0, ctx.secp_ctx())?;
+    config.accounts = vec![AccountConfig {
+        index: 0,
+        name: None,
+    }];
+
+    config.save_to_file(config_path)?;
+
+    if is_generated {
+        println!("Mnemonic: {}", mnemonic);
+        println!("Write it down, it's the only way to restore the wallet");
+    }
+    println!(
+        "Encrypt the mnemonic with `wallet encrypt`, or set {} to use the other accounts",
+        MNEMONIC_ENV
+    );
+
+    Ok(())
+}
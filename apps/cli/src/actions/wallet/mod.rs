@@ -3,8 +3,19 @@ use color_eyre::eyre;
 
 use crate::context::Context;
 
+use self::{
+    add_account::AddAccountArgs, backup::BackupArgs, encrypt::EncryptArgs, init::InitArgs,
+    restore::RestoreArgs,
+};
+
 pub mod abort;
+pub mod add_account;
+pub mod backup;
+pub mod encrypt;
+pub mod init;
+pub mod restore;
 pub mod sync;
+pub mod unlock;
 
 #[derive(Subcommand, Debug)]
 pub enum WalletCommands {
@@ -12,11 +23,29 @@ pub enum WalletCommands {
     AbortRescan,
     /// Syncs yuv and bitcoin wallets  
     Sync,
+    /// Exports the encrypted backup of the YUV state of the wallet
+    Backup(BackupArgs),
+    /// Restores the YUV state of the wallet from the backup
+    Restore(RestoreArgs),
+    /// Sets the BIP39 mnemonic the keys of the accounts are derived from
+    Init(InitArgs),
+    /// Adds the next account derived from the mnemonic, use it with `--account`
+    AddAccount(AddAccountArgs),
+    /// Moves the private key from the config to the keystore encrypted with the passphrase
+    Encrypt(EncryptArgs),
+    /// Checks the passphrase of the keystore and prints the address of the wallet
+    Unlock,
 }
 
 pub async fn run(cmd: WalletCommands, context: Context) -> eyre::Result<()> {
     match cmd {
         WalletCommands::AbortRescan => abort::run(context).await,
         WalletCommands::Sync => sync::run(context).await,
+        WalletCommands::Backup(args) => backup::run(args, context).await,
+        WalletCommands::Restore(args) => restore::run(args, context).await,
+        WalletCommands::Init(args) => init::run(args, context),
+        WalletCommands::AddAccount(args) => add_account::run(args, context),
+        WalletCommands::Encrypt(args) => encrypt::run(args, context),
+        WalletCommands::Unlock => unlock::run(context),
     }
 }
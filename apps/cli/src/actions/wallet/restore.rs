@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::eyre::{self, Context as EyreContext};
+
+use crate::context::Context;
+
+#[derive(Args, Debug)]
+pub struct RestoreArgs {
+    /// Path to the backup file made with the same private key.
+    pub input: PathBuf,
+}
+
+pub async fn run(RestoreArgs { input }: RestoreArgs, mut ctx: Context) -> eyre::Result<()> {
+    let data = std::fs::read(&input)
+        .wrap_err_with(|| format!("Failed to read the backup from {}", input.display()))?;
+
+    // Restore the state before the first sync, so the proofs are not synced from the node again.
+    let wallet = ctx.unsynced_wallet().await?;
+    let backup = wallet.restore_backup(&data).await?;
+
+    println!(
+        "Restored {} proofs and {} pixel descriptors",
+        backup.proofs.len(),
+        backup.pixel_descriptors.len()
+    );
+
+    Ok(())
+}
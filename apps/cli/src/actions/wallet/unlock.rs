@@ -0,0 +1,25 @@
+use color_eyre::eyre::{self, OptionExt};
+use yuv_pixels::Chroma;
+
+use crate::context::Context;
+
+/// Check the passphrase of the keystore, and print the address of the unlocked key.
+pub fn run(mut ctx: Context) -> eyre::Result<()> {
+    let config = ctx.config()?;
+
+    let keystore = config
+        .keystore
+        .as_ref()
+        .ok_or_eyre("The key is not encrypted, encrypt it with `wallet encrypt`")?;
+
+    let address =
+        Chroma::from(config.private_key.public_key(ctx.secp_ctx())).to_address(config.network());
+
+    println!("Keystore {} is unlocked: {}", keystore.display(), address);
+    println!(
+        "Set {} to unlock it without the prompt",
+        yuv_keystore::PASSPHRASE_ENV
+    );
+
+    Ok(())
+}
@@ -0,0 +1,267 @@
+use std::time::Duration;
+
+use bitcoin::{secp256k1::XOnlyPublicKey, Network, Txid};
+use clap::Args;
+use color_eyre::eyre::{self, Context as _};
+use jsonrpsee::http_client::HttpClient;
+use serde::Serialize;
+use yuv_pixels::{Chroma, PixelProof};
+use yuv_rpc_api::transactions::{YuvTransactionResponse, YuvTransactionsRpcClient};
+use yuv_types::{announcements::Announcement, ProofMap, YuvTxType};
+
+use crate::context::Context;
+
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// Chroma of the token to watch, in addition to the wallet's key.
+    #[clap(long, value_parser = Chroma::from_address)]
+    pub chroma: Option<Chroma>,
+
+    /// Interval between the polls of the node, in seconds.
+    #[clap(long, default_value_t = DEFAULT_INTERVAL_SECS)]
+    pub interval: u64,
+
+    /// Print each event as a JSON object on a separate line.
+    #[clap(long)]
+    pub json: bool,
+}
+
+/// Event printed with `--json`.
+#[derive(Serialize)]
+struct WatchEvent<'a> {
+    event: &'static str,
+    txid: Txid,
+    tx_type: &'a YuvTxType,
+}
+
+/// Poll the node for the newly attached transactions, and print the ones that affect the chroma
+/// or the wallet's key, until the command is interrupted.
+pub async fn run(
+    WatchArgs {
+        chroma,
+        interval,
+        json,
+    }: WatchArgs,
+    mut ctx: Context,
+) -> eyre::Result<()> {
+    let config = ctx.config()?;
+    let client = ctx.yuv_client()?;
+    let network = config.network();
+
+    let (wallet_key, _parity) = config
+        .private_key
+        .public_key(ctx.secp_ctx())
+        .inner
+        .x_only_public_key();
+
+    let filter = EventFilter { chroma, wallet_key };
+    let interval = Duration::from_secs(interval);
+
+    // Only the transactions attached after the start are printed.
+    let mut page = last_page_number(&client).await?;
+    let mut seen = list_page(&client, page).await?.len();
+
+    loop {
+        // The node starts the next page only when the current one is full, so it's checked
+        // first to not miss the transactions added to the current page in between.
+        let is_page_full = !list_page(&client, page + 1).await?.is_empty();
+        let txs = list_page(&client, page).await?;
+
+        for tx in txs.iter().skip(seen) {
+            if filter.matches(&tx.tx_type) {
+                print_event(tx, network, &filter, json)?;
+            }
+        }
+
+        if is_page_full {
+            page += 1;
+            seen = 0;
+            continue;
+        }
+
+        seen = txs.len();
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn list_page(client: &HttpClient, page: u64) -> eyre::Result<Vec<YuvTransactionResponse>> {
+    client
+        .list_yuv_transactions(page)
+        .await
+        .wrap_err("Failed to fetch transactions from node")
+}
+
+/// Find the number of the last non-empty page, doubling the number until the empty page is
+/// found, and then narrowing it down.
+async fn last_page_number(client: &HttpClient) -> eyre::Result<u64> {
+    if list_page(client, 0).await?.is_empty() {
+        return Ok(0);
+    }
+
+    let mut non_empty = 0;
+    let mut empty = 1;
+    while !list_page(client, empty).await?.is_empty() {
+        non_empty = empty;
+        empty *= 2;
+    }
+
+    while empty - non_empty > 1 {
+        let middle = non_empty + (empty - non_empty) / 2;
+
+        if list_page(client, middle).await?.is_empty() {
+            empty = middle;
+        } else {
+            non_empty = middle;
+        }
+    }
+
+    Ok(non_empty)
+}
+
+struct EventFilter {
+    chroma: Option<Chroma>,
+    wallet_key: XOnlyPublicKey,
+}
+
+impl EventFilter {
+    fn matches(&self, tx_type: &YuvTxType) -> bool {
+        match tx_type {
+            YuvTxType::Issue {
+                output_proofs,
+                announcement,
+            } => {
+                self.is_watched_chroma(&announcement.chroma)
+                    || output_proofs
+                        .iter()
+                        .any(|proofs| self.matches_proofs(proofs))
+            }
+            YuvTxType::Transfer {
+                input_proofs,
+                output_proofs,
+            } => self.matches_proofs(input_proofs) || self.matches_proofs(output_proofs),
+            YuvTxType::Announcement(Announcement::FreezePubkey(announcement)) => {
+                self.is_watched_chroma(&announcement.chroma)
+                    || announcement.pubkey.x_only_public_key().0 == self.wallet_key
+            }
+            YuvTxType::Announcement(Announcement::Delegate(announcement)) => {
+                self.is_watched_chroma(&announcement.chroma)
+                    || announcement.delegate.x_only_public_key().0 == self.wallet_key
+            }
+            YuvTxType::Announcement(announcement) => self.is_watched_chroma(&announcement.chroma()),
+        }
+    }
+
+    fn matches_proofs(&self, proofs: &ProofMap) -> bool {
+        proofs
+            .values()
+            .any(|proof| self.matches_proof(proof).is_some())
+    }
+
+    /// Returns whether the proof belongs to the wallet's key, `None` if it doesn't affect the
+    /// watched chroma either.
+    fn matches_proof(&self, proof: &PixelProof) -> Option<bool> {
+        let is_wallet_key = match proof {
+            PixelProof::Sig(sig) => sig.inner_key.x_only_public_key().0 == self.wallet_key,
+            PixelProof::EmptyPixel(empty) => {
+                empty.inner_key.x_only_public_key().0 == self.wallet_key
+            }
+            _ => false,
+        };
+
+        let is_watched_chroma =
+            !proof.is_empty_pixelproof() && self.is_watched_chroma(&proof.pixel().chroma);
+
+        (is_wallet_key || is_watched_chroma).then_some(is_wallet_key)
+    }
+
+    fn is_watched_chroma(&self, chroma: &Chroma) -> bool {
+        self.chroma.as_ref() == Some(chroma)
+    }
+}
+
+fn print_event(
+    tx: &YuvTransactionResponse,
+    network: Network,
+    filter: &EventFilter,
+    json: bool,
+) -> eyre::Result<()> {
+    let txid = tx.bitcoin_tx.txid;
+    let event = match &tx.tx_type {
+        YuvTxType::Issue { .. } => "issue",
+        YuvTxType::Transfer { .. } => "transfer",
+        YuvTxType::Announcement(Announcement::Freeze(_) | Announcement::FreezePubkey(_)) => {
+            "freeze"
+        }
+        YuvTxType::Announcement(_) => "announcement",
+    };
+
+    if json {
+        let event = WatchEvent {
+            event,
+            txid,
+            tx_type: &tx.tx_type,
+        };
+        println!("{}", serde_json::to_string(&event)?);
+
+        return Ok(());
+    }
+
+    match &tx.tx_type {
+        YuvTxType::Announcement(Announcement::Freeze(freeze)) => println!(
+            "freeze {}: {} of {}",
+            txid,
+            freeze.outpoint,
+            freeze.chroma.to_address(network)
+        ),
+        YuvTxType::Announcement(Announcement::FreezePubkey(freeze)) => println!(
+            "freeze {}: key {} of {}",
+            txid,
+            freeze.pubkey,
+            freeze.chroma.to_address(network)
+        ),
+        YuvTxType::Announcement(announcement) => println!(
+            "announcement {}: {} of {}",
+            txid,
+            announcement_name(announcement),
+            announcement.chroma().to_address(network)
+        ),
+        tx_type => {
+            println!("{} {}", event, txid);
+
+            for (vout, proof) in tx_type.output_proofs().into_iter().flatten() {
+                let Some(is_wallet_key) = filter.matches_proof(proof) else {
+                    continue;
+                };
+                if proof.is_empty_pixelproof() {
+                    continue;
+                }
+
+                let pixel = proof.pixel();
+                println!(
+                    "  output {}: {} of {}{}",
+                    vout,
+                    pixel.luma.amount,
+                    pixel.chroma.to_address(network),
+                    if is_wallet_key { " (yours)" } else { "" }
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn announcement_name(announcement: &Announcement) -> &'static str {
+    match announcement {
+        Announcement::Chroma(_) => "chroma",
+        Announcement::Freeze(_) => "freeze",
+        Announcement::Issue(_) => "issue",
+        Announcement::TransferOwnership(_) => "transfer ownership",
+        Announcement::Burn(_) => "burn",
+        Announcement::UpdateChroma(_) => "update chroma",
+        Announcement::FreezePubkey(_) => "freeze pubkey",
+        Announcement::Delegate(_) => "delegate",
+    }
+}
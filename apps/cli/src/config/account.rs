@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use bdk::keys::bip39::Mnemonic;
+use bitcoin::{
+    bip32::{DerivationPath, ExtendedPrivKey},
+    secp256k1::{All, Secp256k1},
+    Network, PrivateKey,
+};
+use color_eyre::eyre::{self, Context as _};
+use serde::{Deserialize, Serialize};
+
+/// Environment variable with the BIP39 mnemonic of the wallet, used if it's not in the keystore.
+pub const MNEMONIC_ENV: &str = "YUV_MNEMONIC";
+
+/// Account derived from the mnemonic of the wallet.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct AccountConfig {
+    /// Index of the account in the derivation path.
+    pub index: u32,
+
+    /// Human-readable name of the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Derive the private key of the account from the BIP39 mnemonic by the BIP84 path
+/// `m/84'/<coin>'/<account>'/0/0`, where the coin is `0` for mainnet and `1` for the others.
+pub fn derive_account_key(
+    mnemonic: &str,
+    network: Network,
+    account: u32,
+    secp_ctx: &Secp256k1<All>,
+) -> eyre::Result<PrivateKey> {
+    let mnemonic = Mnemonic::parse(mnemonic).wrap_err("Invalid mnemonic")?;
+    let seed = mnemonic.to_seed("");
+
+    let master = ExtendedPrivKey::new_master(network, &seed)?;
+    let coin = if network == Network::Bitcoin { 0 } else { 1 };
+    let path = DerivationPath::from_str(&format!("m/84'/{coin}'/{account}'/0/0"))?;
+
+    Ok(master.derive_priv(secp_ctx, &path)?.to_priv())
+}
+
+/// Take the mnemonic of the wallet from [`MNEMONIC_ENV`], or ask it interactively if it's not set.
+pub fn read_mnemonic() -> eyre::Result<String> {
+    match std::env::var(MNEMONIC_ENV) {
+        Ok(mnemonic) => Ok(mnemonic),
+        Err(_) => Ok(rpassword::prompt_password("Mnemonic of the wallet: ")?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{Address, PublicKey};
+
+    use super::*;
+
+    /// Mnemonic of the BIP84 test vectors.
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+        abandon abandon abandon about";
+
+    #[test]
+    fn test_bip84_vector() {
+        let secp_ctx = Secp256k1::new();
+
+        let private_key = derive_account_key(MNEMONIC, Network::Bitcoin, 0, &secp_ctx).unwrap();
+        let public_key = private_key.public_key(&secp_ctx);
+
+        assert_eq!(
+            private_key.to_wif(),
+            "KyZpNDKnfs94vbrwhJneDi77V6jF64PWPF8x5cdJb8ifgg2DUc9d"
+        );
+        assert_eq!(
+            public_key,
+            PublicKey::from_str(
+                "0330d54fd0dd420a6e5f8d3624f5f3482cae350f79d5f0753bf5beef9c2d91af3c"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Address::p2wpkh(&public_key, Network::Bitcoin)
+                .unwrap()
+                .to_string(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+    }
+
+    #[test]
+    fn test_accounts_and_networks_have_own_keys() {
+        let secp_ctx = Secp256k1::new();
+        let derive = |network, account| {
+            derive_account_key(MNEMONIC, network, account, &secp_ctx)
+                .unwrap()
+                .inner
+        };
+
+        let mainnet = derive(Network::Bitcoin, 0);
+        let testnet = derive(Network::Testnet, 0);
+
+        assert_ne!(mainnet, derive(Network::Bitcoin, 1));
+        assert_ne!(mainnet, testnet);
+        // The coin is `1` for all the networks except mainnet.
+        assert_eq!(testnet, derive(Network::Regtest, 0));
+        assert!(derive_account_key("abandon", Network::Bitcoin, 0, &secp_ctx).is_err());
+    }
+}
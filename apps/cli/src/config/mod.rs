@@ -1,14 +1,20 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use bitcoin::{Network, PrivateKey};
-use color_eyre::eyre;
+use bitcoin::{
+    secp256k1::{All, Secp256k1},
+    Network, PrivateKey,
+};
+use color_eyre::eyre::{self, bail, Context as _};
 use serde::{Deserialize, Serialize};
 
+pub use account::{derive_account_key, read_mnemonic, AccountConfig, MNEMONIC_ENV};
 use ydk::bitcoin_provider::BitcoinProviderConfig;
 use ydk::types::FeeRateStrategy;
 use ydk::wallet::WalletConfig;
+use yuv_types::network::parse_bitcoin_network;
 pub use yuvnode::YuvNodeConfig;
 
+mod account;
 mod yuvnode;
 
 #[derive(Deserialize, Clone, Serialize)]
@@ -23,15 +29,59 @@ pub struct Config {
     pub fee_rate_strategy: FeeRateStrategy,
 
     pub storage: PathBuf,
+
+    /// BIP39 mnemonic the keys of the accounts are derived from, decrypted from the keystore. It's
+    /// never stored in the config, see [`Config::mnemonic`].
+    #[serde(skip)]
+    pub mnemonic: Option<String>,
+
+    /// Accounts derived from the mnemonic. If set, `private_key` is the key of the account `0`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<AccountConfig>,
+
+    /// Path to the keystore with the `private_key` and the `mnemonic` encrypted by
+    /// `wallet encrypt`. If set, neither of them is stored in the config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<PathBuf>,
 }
 
 impl Config {
     pub fn from_path(path: PathBuf) -> eyre::Result<Self> {
-        let config = config::Config::builder()
-            .add_source(config::File::from(path))
-            .build()?;
+        let mut builder = config::Config::builder().add_source(config::File::from(path));
+
+        let config = builder.build_cloned()?;
+        if config.get_string("mnemonic").is_ok() {
+            bail!(
+                "The mnemonic can't be stored in the config in plaintext, remove it and set {} \
+                or encrypt it with `wallet encrypt`",
+                MNEMONIC_ENV
+            );
+        }
+
+        let mut mnemonic = None;
+        if let Ok(keystore) = config.get_string("keystore") {
+            if config.get_string("private_key").is_ok() {
+                bail!(
+                    "The config has both the keystore and the plaintext private_key, remove the \
+                    latter"
+                );
+            }
+
+            let network = parse_bitcoin_network(&config.get_string("bitcoin_provider.network")?)?;
+            let secrets = yuv_keystore::unlock(Path::new(&keystore))
+                .wrap_err_with(|| format!("Failed to unlock {}", keystore))?;
 
-        Ok(config.try_deserialize()?)
+            builder = builder.set_override(
+                "private_key",
+                PrivateKey::new(secrets.secret_key, network).to_wif(),
+            )?;
+            mnemonic = secrets.mnemonic;
+        }
+
+        let mut config: Self = builder.build()?.try_deserialize()?;
+        config.mnemonic = mnemonic;
+
+        Ok(config)
     }
 
     pub fn network(&self) -> Network {
@@ -41,9 +91,56 @@ impl Config {
         }
     }
 
-    /// Serialize and save configuration to a file specified in `path`.
+    /// Returns the mnemonic of the wallet decrypted from the keystore, or taken from
+    /// [`MNEMONIC_ENV`] or the prompt. The latter ones must derive the `private_key`.
+    pub fn mnemonic(&self, secp_ctx: &Secp256k1<All>) -> eyre::Result<String> {
+        if let Some(mnemonic) = &self.mnemonic {
+            return Ok(mnemonic.clone());
+        }
+
+        if self.accounts.is_empty() {
+            bail!("The wallet has no mnemonic, initialize it with `wallet init`");
+        }
+
+        let mnemonic = read_mnemonic()?;
+        let private_key = derive_account_key(&mnemonic, self.network(), 0, secp_ctx)?;
+        if private_key.inner != self.private_key.inner {
+            bail!("The mnemonic doesn't match the key of the wallet");
+        }
+
+        Ok(mnemonic)
+    }
+
+    /// Returns the configuration of the account derived from the mnemonic, with its own key and
+    /// storage. The storage of the account `0` is the default one, as its key is `private_key`.
+    pub fn for_account(mut self, index: u32, secp_ctx: &Secp256k1<All>) -> eyre::Result<Self> {
+        if !self.accounts.iter().any(|account| account.index == index) {
+            bail!(
+                "Account {} is not found, add it with `wallet add-account`",
+                index
+            );
+        }
+
+        let mnemonic = self.mnemonic(secp_ctx)?;
+        self.private_key = derive_account_key(&mnemonic, self.network(), index, secp_ctx)?;
+        if index != 0 {
+            self.storage = self.storage.join(format!("account-{index}"));
+        }
+
+        Ok(self)
+    }
+
+    /// Serialize and save configuration to a file specified in `path`. The `private_key` is
+    /// omitted if it's stored in the keystore.
     pub fn save_to_file(&self, path: PathBuf) -> eyre::Result<()> {
-        let serialized = toml::to_string_pretty(&self)?;
+        let mut value = toml::Value::try_from(self)?;
+        if self.keystore.is_some() {
+            if let Some(table) = value.as_table_mut() {
+                table.remove("private_key");
+            }
+        }
+
+        let serialized = toml::to_string_pretty(&value)?;
 
         std::fs::write(path, serialized)?;
 
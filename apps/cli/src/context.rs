@@ -21,6 +21,9 @@ pub struct Context {
     /// Stored path to configuration file, ti lazy load it when needed.
     config_path: PathBuf,
 
+    /// Account derived from the mnemonic to use instead of the `private_key` from the config.
+    account: Option<u32>,
+
     /// Global secp256k1 context, used for signing and verifying signatures.
     secp_ctx: Secp256k1<All>,
 
@@ -38,11 +41,12 @@ pub struct Context {
 }
 
 impl Context {
-    pub fn new(config: PathBuf) -> Self {
+    pub fn new(config: PathBuf, account: Option<u32>) -> Self {
         let secp_ctx = Secp256k1::new();
 
         Self {
             config_path: config,
+            account,
             secp_ctx,
             config: None,
             yuv_client: None,
@@ -56,14 +60,22 @@ impl Context {
             return Ok(config.clone());
         }
 
-        let cfg: Config =
+        let mut cfg: Config =
             Config::from_path(self.config_path.clone()).wrap_err("Failed to load config")?;
 
+        if let Some(account) = self.account {
+            cfg = cfg.for_account(account, &self.secp_ctx)?;
+        }
+
         self.config = Some(cfg.clone());
 
         Ok(cfg)
     }
 
+    pub fn config_path(&self) -> &PathBuf {
+        &self.config_path
+    }
+
     pub fn secp_ctx(&self) -> &Secp256k1<All> {
         &self.secp_ctx
     }
@@ -137,9 +149,23 @@ impl Context {
             return Ok(wallet.clone());
         }
 
+        let wallet = self.unsynced_wallet().await?;
+
+        let pb = setup_progress_bar("Syncing yuv and bitcoin wallets...".into());
+        wallet.sync(SyncOptions::default()).await?;
+        pb.finish();
+
+        let wallet = Arc::new(wallet);
+        self.yuv_wallet = Some(wallet.clone());
+
+        Ok(wallet)
+    }
+
+    /// Returns the wallet without syncing it, e.g. to restore its state before the first sync.
+    pub async fn unsynced_wallet(&mut self) -> eyre::Result<StorageWallet> {
         let config = self.config()?;
 
-        let wallet = StorageWallet::from_storage_config(StorageWalletConfig {
+        StorageWallet::from_storage_config(StorageWalletConfig {
             inner: WalletConfig {
                 privkey: config.private_key,
                 bitcoin_provider: config.bitcoin_provider.clone(),
@@ -148,16 +174,7 @@ impl Context {
             },
             storage_path: config.storage.clone(),
         })
-        .await?;
-
-        let pb = setup_progress_bar("Syncing yuv and bitcoin wallets...".into());
-        wallet.sync(SyncOptions::default()).await?;
-        pb.finish();
-
-        let wallet = Arc::new(wallet);
-        self.yuv_wallet = Some(wallet.clone());
-
-        Ok(wallet)
+        .await
     }
 }
 
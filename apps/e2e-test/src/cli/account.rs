@@ -3,14 +3,9 @@ use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::sync::mpsc::UnboundedSender;
 
 use bdk::blockchain::{EsploraBlockchain, RpcBlockchain};
-use bitcoin::{
-    secp256k1::{
-        rand::{seq::IteratorRandom, thread_rng},
-        Secp256k1,
-    },
-    Address, PrivateKey,
-};
+use bitcoin::{secp256k1::Secp256k1, Address, PrivateKey};
 use jsonrpsee::http_client::HttpClient;
+use rand::{rngs::StdRng, seq::IteratorRandom};
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
@@ -22,7 +17,7 @@ use yuv_pixels::{Chroma, Pixel};
 use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
 use yuv_types::{YuvTransaction, YuvTxType};
 
-use super::e2e::NETWORK;
+use super::e2e::{SentTxs, NETWORK};
 
 /// Minimum transfer amount.
 const TRANSFER_LOWER_BOUND: u128 = 1000;
@@ -53,6 +48,8 @@ pub(crate) struct Account {
     rpc_blockchain: Option<RpcBlockchain>,
 
     wallet: MemoryWallet,
+    /// Picks the recipients and the pixels to send.
+    rng: StdRng,
 }
 
 impl Account {
@@ -62,6 +59,7 @@ impl Account {
         esplora: EsploraBlockchain,
         rpc_blockchain: Option<RpcBlockchain>,
         wallet: MemoryWallet,
+        rng: StdRng,
     ) -> Self {
         Self {
             private_key,
@@ -69,15 +67,17 @@ impl Account {
             esplora,
             rpc_blockchain,
             wallet,
+            rng,
         }
     }
 
     /// Start sending transactions.
     pub async fn run(
-        self,
+        mut self,
         recipients: Arc<[PrivateKey]>,
         tx_sender: UnboundedSender<YuvTransaction>,
         balance_sender: UnboundedSender<(PrivateKey, HashMap<Chroma, u128>)>,
+        sent_txs: SentTxs,
         cancellation_token: CancellationToken,
     ) -> eyre::Result<()> {
         info!("Started sending transactions");
@@ -115,6 +115,11 @@ impl Account {
                 let tx_type = tx_type(&tx.tx_type);
                 info!("{} tx sent | Txid: {}", tx_type, txid);
 
+                sent_txs
+                    .lock()
+                    .expect("Sent txs lock should not be poisoned")
+                    .push(tx.clone());
+
                 // Send the TX to the tx checker.
                 tx_sender.send(tx)?;
                 continue;
@@ -139,13 +144,13 @@ impl Account {
     /// If there are no balances, it builds an issuance TX with a random recipient.
     /// If the address has balances, a transfer transaction will be built.
     async fn build_transaction(
-        &self,
+        &mut self,
         recipients: &Arc<[PrivateKey]>,
     ) -> eyre::Result<YuvTransaction> {
         // Choose a random recipient.
         let recipient = recipients
             .iter()
-            .choose(&mut thread_rng())
+            .choose(&mut self.rng)
             .expect("Recipients should not be empty");
 
         let balances = self.wallet.balances().await?;
@@ -158,7 +163,7 @@ impl Account {
             let (chroma, luma) = balances
                 .yuv
                 .iter()
-                .choose(&mut thread_rng())
+                .choose(&mut self.rng)
                 .expect("At least one pixel should be present");
 
             self.transfer(recipient, Pixel::new(*luma, *chroma)).await
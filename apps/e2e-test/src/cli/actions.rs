@@ -5,9 +5,9 @@ use tracing_subscriber::layer::{Context, Filter, SubscriberExt};
 use tracing_subscriber::{fmt, Layer, Registry};
 
 use crate::cli::arguments;
-use crate::config::TestConfig;
+use crate::config::{ScenarioConfig, TestConfig};
 
-use super::e2e::E2e;
+use super::{e2e::E2e, simulation::Simulation};
 
 struct ErrorLogFilter;
 impl<S> Filter<S> for ErrorLogFilter {
@@ -18,26 +18,7 @@ impl<S> Filter<S> for ErrorLogFilter {
 
 pub async fn run(args: arguments::Run) -> eyre::Result<()> {
     let config = TestConfig::from_path(args.config)?;
-
-    let file_appender =
-        RollingFileAppender::new(Rotation::MINUTELY, "", &config.report.error_log_file);
-    let subscriber = Registry::default()
-        .with(
-            fmt::layer()
-                .compact()
-                .with_target(false)
-                .with_filter(LevelFilter::INFO),
-        )
-        .with(
-            fmt::layer()
-                .json()
-                .with_writer(file_appender)
-                .with_target(false)
-                .with_filter(ErrorLogFilter),
-        );
-
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set up tracing subscriber");
+    init_tracing(&config);
 
     let e2e = E2e::new(config.clone()).await?;
     e2e.run().await?;
@@ -67,3 +48,70 @@ pub async fn run(args: arguments::Run) -> eyre::Result<()> {
 
     Ok(())
 }
+
+/// Run the test with the scripted scenario instead of the fixed duration, and check the final
+/// state of the nodes.
+pub async fn simulate(args: arguments::Simulate) -> eyre::Result<()> {
+    let mut config = TestConfig::from_path(args.config)?;
+    let scenario = ScenarioConfig::from_path(args.scenario)?;
+    init_tracing(&config);
+
+    let seed = args.seed.unwrap_or(scenario.seed);
+    config.seed = Some(seed);
+
+    let e2e = E2e::new(config.clone()).await?;
+    e2e.run().await?;
+
+    let mut simulation = Simulation::new(config, seed);
+    let steps = tokio::select! {
+        result = simulation.run(&scenario.steps, &e2e) => Some(result),
+        _ = tokio::signal::ctrl_c() => None,
+    };
+
+    let Some(result) = steps else {
+        info!("Cancellation received");
+        e2e.shutdown().await;
+        return Ok(());
+    };
+    if let Err(err) = result {
+        e2e.shutdown().await;
+        return Err(err);
+    }
+
+    info!("Waiting {:?} for the nodes to settle", scenario.settle);
+    tokio::time::sleep(scenario.settle).await;
+
+    let sent_txs = e2e.sent_txs();
+    e2e.shutdown().await;
+
+    simulation.check(&scenario.assertions, &sent_txs).await?;
+
+    info!(
+        "The simulation with the seed {} has successfully ended",
+        seed
+    );
+
+    Ok(())
+}
+
+fn init_tracing(config: &TestConfig) {
+    let file_appender =
+        RollingFileAppender::new(Rotation::MINUTELY, "", &config.report.error_log_file);
+    let subscriber = Registry::default()
+        .with(
+            fmt::layer()
+                .compact()
+                .with_target(false)
+                .with_filter(LevelFilter::INFO),
+        )
+        .with(
+            fmt::layer()
+                .json()
+                .with_writer(file_appender)
+                .with_target(false)
+                .with_filter(ErrorLogFilter),
+        );
+
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("Failed to set up tracing subscriber");
+}
@@ -8,3 +8,16 @@ pub struct Run {
     #[clap(short, long, default_value = "config.toml")]
     pub config: PathBuf,
 }
+
+#[derive(Args, Debug, Clone)]
+pub struct Simulate {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Path to scenario file
+    #[clap(short, long, default_value = "scenario.toml")]
+    pub scenario: PathBuf,
+    /// Seed of the RNG, overrides the one of the scenario
+    #[clap(long)]
+    pub seed: Option<u64>,
+}
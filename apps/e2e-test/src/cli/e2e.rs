@@ -1,4 +1,8 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use bdk::blockchain::EsploraBlockchain;
 use eyre::OptionExt;
@@ -16,12 +20,10 @@ use crate::{
 };
 use bdk::blockchain::rpc::Auth;
 use bitcoin::{
-    secp256k1::{
-        rand::{seq::IteratorRandom, thread_rng},
-        All, Secp256k1,
-    },
+    secp256k1::{All, Secp256k1},
     Network, PrivateKey, PublicKey,
 };
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
 use tokio::sync::mpsc::unbounded_channel;
 use tracing::{info, span, Instrument, Level};
 use yuv_pixels::Chroma;
@@ -33,8 +35,14 @@ pub(crate) const NETWORK: Network = Network::Regtest;
 const STOP_GAP: usize = 100000;
 const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Transactions sent by the accounts, in the order they were sent.
+pub(crate) type SentTxs = Arc<Mutex<Vec<YuvTransaction>>>;
+
 pub(crate) struct E2e {
     config: TestConfig,
+    /// Seed of the RNG that generates the accounts and picks the nodes.
+    seed: u64,
+    sent_txs: SentTxs,
 
     task_tracker: TaskTracker,
     cancellation_token: CancellationToken,
@@ -42,13 +50,25 @@ pub(crate) struct E2e {
 
 impl E2e {
     pub async fn new(config: TestConfig) -> eyre::Result<Self> {
+        let seed = config.seed.unwrap_or_else(|| rand::thread_rng().gen());
+
         Ok(Self {
             config,
+            seed,
+            sent_txs: Default::default(),
             task_tracker: TaskTracker::new(),
             cancellation_token: CancellationToken::new(),
         })
     }
 
+    /// Transactions sent by the accounts so far.
+    pub fn sent_txs(&self) -> Vec<YuvTransaction> {
+        self.sent_txs
+            .lock()
+            .expect("Sent txs lock should not be poisoned")
+            .clone()
+    }
+
     /// Start the End-to-End test.
     pub async fn run(&self) -> eyre::Result<()> {
         info!(
@@ -61,8 +81,12 @@ impl E2e {
             info!("Test will end in {:?}", duration);
         };
 
+        // Log the seed, so the run can be repeated with the same accounts and nodes.
+        info!("RNG seed: {}", self.seed);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
         // Generate the accounts
-        let accounts = Self::generate_accounts(&self.config).await?;
+        let accounts = Self::generate_accounts(&self.config, &mut rng).await?;
 
         let mut recipients = Vec::new();
         for account in &accounts {
@@ -79,11 +103,12 @@ impl E2e {
         );
 
         // Pick the funder.
-        let funder = Self::generate_account(&self.config, &Secp256k1::new(), true).await?;
+        let funder =
+            Self::generate_account(&self.config, &Secp256k1::new(), true, &mut rng).await?;
         // Init the miner.
         let miner = Miner::new(
             funder.p2wpkh_address()?,
-            self.config.get_bitcoin_node("miner")?.1,
+            self.config.get_bitcoin_node("miner", &mut rng)?.1,
         );
 
         // Spawn the miner.
@@ -94,7 +119,7 @@ impl E2e {
                 .instrument(span!(Level::ERROR, "miner")),
         );
         // Init the faucet.
-        let faucet = Faucet::new(funder, self.config.get_bitcoin_node("faucet")?.1);
+        let faucet = Faucet::new(funder, self.config.get_bitcoin_node("faucet", &mut rng)?.1);
         let faucet_span = span!(Level::ERROR, "faucet");
 
         // Perform the initial funding.
@@ -125,8 +150,8 @@ impl E2e {
         // Initialize the tx checker.
         let tx_checker = TxChecker::new(
             self.config.clone(),
-            self.config.get_yuv_node()?.1,
-            self.config.get_bitcoin_node("tx-checker")?.1,
+            self.config.get_yuv_node(&mut rng)?.1,
+            self.config.get_bitcoin_node("tx-checker", &mut rng)?.1,
         );
 
         let cancellation_token = self.cancellation_token.clone();
@@ -154,10 +179,17 @@ impl E2e {
 
             let tx_sender = tx_sender.clone();
             let balance_sender = balance_sender.clone();
+            let sent_txs = Arc::clone(&self.sent_txs);
 
             self.task_tracker.spawn(
                 account
-                    .run(recipients, tx_sender, balance_sender, cancellation_token)
+                    .run(
+                        recipients,
+                        tx_sender,
+                        balance_sender,
+                        sent_txs,
+                        cancellation_token,
+                    )
                     .instrument(span),
             );
         }
@@ -182,7 +214,10 @@ impl E2e {
     }
 
     /// Generate a keypair with random YUV and Bitcoin nodes.
-    async fn generate_accounts(config: &TestConfig) -> eyre::Result<Vec<Account>> {
+    async fn generate_accounts(
+        config: &TestConfig,
+        rng: &mut StdRng,
+    ) -> eyre::Result<Vec<Account>> {
         let mut accounts = Vec::with_capacity(config.accounts.number as usize);
 
         // Specified amount of accounts should use a Bitcoin node. Others use Esplora.
@@ -198,7 +233,7 @@ impl E2e {
         for i in 0..config.accounts.number {
             let btc_node = i < threshold;
 
-            let account = Self::generate_account(config, &secp, btc_node).await?;
+            let account = Self::generate_account(config, &secp, btc_node, rng).await?;
 
             info!(
                 "Generated {} account (connected to {})",
@@ -216,25 +251,26 @@ impl E2e {
         config: &TestConfig,
         secp: &Secp256k1<All>,
         has_btc_node: bool,
+        rng: &mut StdRng,
     ) -> eyre::Result<Account> {
-        let (seckey, _pubkey) = secp.generate_keypair(&mut thread_rng());
+        let (seckey, _pubkey) = secp.generate_keypair(rng);
 
         let private_key = PrivateKey::new(seckey, NETWORK);
         let pubkey = private_key.public_key(secp);
 
         // Pick a random YUV node for the account.
-        let (yuv_url, yuv_client) = config.get_yuv_node()?;
+        let (yuv_url, yuv_client) = config.get_yuv_node(rng)?;
 
         // Pick a random Esplora URL for the account.
         let esplora_url = &config
             .nodes
             .esplora
             .iter()
-            .choose(&mut thread_rng())
+            .choose(rng)
             .expect("At least one Esplora URL should be specified");
 
         let btc_node = if has_btc_node {
-            Some(config.get_bitcoin_node(&pubkey.to_string())?)
+            Some(config.get_bitcoin_node(&pubkey.to_string(), rng)?)
         } else {
             None
         };
@@ -273,6 +309,7 @@ impl E2e {
             EsploraBlockchain::new(esplora_url, STOP_GAP),
             btc_node.map(|node| node.1),
             wallet,
+            StdRng::from_rng(rng)?,
         ))
     }
 
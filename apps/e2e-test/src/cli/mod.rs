@@ -4,6 +4,7 @@ mod arguments;
 mod e2e;
 mod faucet;
 mod miner;
+mod simulation;
 mod tx_checker;
 use clap::Parser;
 
@@ -12,12 +13,15 @@ use clap::Parser;
 pub enum Cli {
     /// Run the end-to-end test
     Run(arguments::Run),
+    /// Run the end-to-end test with the scripted scenario and check the final state of the nodes
+    Simulate(arguments::Simulate),
 }
 
 impl Cli {
     pub async fn exec(self) -> eyre::Result<()> {
         match self {
             Self::Run(args) => actions::run(args).await,
+            Self::Simulate(args) => actions::simulate(args).await,
         }
     }
 }
@@ -0,0 +1,349 @@
+use std::time::Duration;
+
+use bdk::{bitcoincore_rpc::RpcApi, blockchain::RpcBlockchain};
+use eyre::{bail, OptionExt, WrapErr};
+use jsonrpsee::http_client::HttpClientBuilder;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde_json::Value;
+use tokio::{process::Command, time::Instant};
+use tracing::{error, info, warn};
+use yuv_rpc_api::transactions::{YuvTransactionStatus, YuvTransactionsRpcClient};
+use yuv_types::YuvTransaction;
+
+use crate::config::{AssertionsConfig, Step, TestConfig};
+
+use super::{
+    e2e::E2e,
+    tx_checker::{check_pages_match, list_paged_txids, ADDRESS},
+};
+
+/// Name of the wallet used to control the Bitcoin nodes.
+const WALLET_NAME: &str = "simulation";
+/// How often the restarted Bitcoin node is polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Executes the steps of the scenario against the nodes of the running test, and checks their
+/// final state.
+pub(crate) struct Simulation {
+    config: TestConfig,
+    /// Picks the nodes and the order of the duplicate transactions.
+    rng: StdRng,
+}
+
+impl Simulation {
+    pub fn new(config: TestConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Execute the `steps` one by one.
+    pub async fn run(&mut self, steps: &[Step], e2e: &E2e) -> eyre::Result<()> {
+        for (i, step) in steps.iter().enumerate() {
+            info!("Step #{}: {:?}", i + 1, step);
+
+            self.execute(step, e2e)
+                .await
+                .wrap_err_with(|| format!("Step #{} failed", i + 1))?;
+        }
+
+        info!("All the steps are executed");
+
+        Ok(())
+    }
+
+    async fn execute(&mut self, step: &Step, e2e: &E2e) -> eyre::Result<()> {
+        match step {
+            Step::Wait { duration } => tokio::time::sleep(*duration).await,
+            Step::Reorg { depth, node } => self.reorg(*depth, *node)?,
+            Step::DisconnectPeers { duration, node } => {
+                self.disconnect_peers(*duration, *node).await?
+            }
+            Step::RestartBitcoind {
+                command,
+                node,
+                timeout,
+            } => self.restart_bitcoind(command, *node, *timeout).await?,
+            Step::InvFlood { txs, copies } => {
+                self.inv_flood(&e2e.sent_txs(), *txs, *copies).await?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the Bitcoin node by its index, or a random one.
+    fn bitcoin_node(&mut self, node: Option<usize>) -> eyre::Result<RpcBlockchain> {
+        let (_, blockchain) = match node {
+            Some(index) => self.config.get_bitcoin_node_at(index, WALLET_NAME)?,
+            None => self.config.get_bitcoin_node(WALLET_NAME, &mut self.rng)?,
+        };
+
+        Ok(blockchain)
+    }
+
+    /// Invalidate the last `depth` blocks of the node, and mine one block more on top of their
+    /// parent, so the new chain is also the longest one for the node's peers.
+    fn reorg(&mut self, depth: u64, node: Option<usize>) -> eyre::Result<()> {
+        eyre::ensure!(depth > 0, "Reorg depth should be positive");
+
+        let bitcoin = self.bitcoin_node(node)?;
+        let height = bitcoin.get_block_count()?;
+        eyre::ensure!(
+            height >= depth,
+            "Can't reorg {} blocks, the chain has only {}",
+            depth,
+            height
+        );
+
+        let fork_block = bitcoin.get_block_hash(height - depth + 1)?;
+        bitcoin.invalidate_block(&fork_block)?;
+        bitcoin.generate_to_address(depth + 1, &ADDRESS)?;
+
+        info!("Replaced {} block(s) starting from {}", depth, fork_block);
+
+        Ok(())
+    }
+
+    async fn disconnect_peers(
+        &mut self,
+        duration: Duration,
+        node: Option<usize>,
+    ) -> eyre::Result<()> {
+        let bitcoin = self.bitcoin_node(node)?;
+
+        bitcoin.call::<Value>("setnetworkactive", &[false.into()])?;
+        info!("Disconnected the Bitcoin node for {:?}", duration);
+
+        tokio::time::sleep(duration).await;
+
+        bitcoin.call::<Value>("setnetworkactive", &[true.into()])?;
+        info!("Reconnected the Bitcoin node");
+
+        Ok(())
+    }
+
+    /// Stop the node, start it with the `command`, and load back its wallets, as they aren't
+    /// loaded on the startup by default.
+    async fn restart_bitcoind(
+        &mut self,
+        command: &[String],
+        node: Option<usize>,
+        timeout: Duration,
+    ) -> eyre::Result<()> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_eyre("Restart command should not be empty")?;
+
+        let bitcoin = self.bitcoin_node(node)?;
+        bitcoin.stop()?;
+
+        if !wait_for(timeout, || bitcoin.get_block_count().is_err()).await {
+            bail!("Bitcoin node didn't stop in {:?}", timeout);
+        }
+        info!("Bitcoin node is stopped");
+
+        let status = Command::new(program).args(args).status().await?;
+        eyre::ensure!(status.success(), "Restart command failed: {}", status);
+
+        if !wait_for(timeout, || bitcoin.get_block_count().is_ok()).await {
+            bail!("Bitcoin node didn't start in {:?}", timeout);
+        }
+
+        let wallets = bitcoin.call::<Value>("listwalletdir", &[])?;
+        let names = wallets["wallets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|wallet| wallet["name"].as_str());
+
+        for name in names {
+            if let Err(err) = bitcoin.call::<Value>("loadwallet", &[name.into()]) {
+                warn!("Failed to load the wallet {}: {}", name, err);
+            }
+        }
+
+        info!("Bitcoin node is restarted");
+
+        Ok(())
+    }
+
+    /// Send `copies` of each of the last `txs` sent transactions to every YUV node, in random
+    /// order. The duplicates are expected to be rejected, so the errors are only counted.
+    async fn inv_flood(
+        &mut self,
+        sent_txs: &[YuvTransaction],
+        txs: usize,
+        copies: usize,
+    ) -> eyre::Result<()> {
+        let sent_txs = &sent_txs[sent_txs.len().saturating_sub(txs)..];
+
+        let clients = self
+            .config
+            .nodes
+            .yuv
+            .iter()
+            .map(|node| HttpClientBuilder::new().build(node))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut requests = Vec::new();
+        for tx in sent_txs {
+            let hex = tx.hex();
+            for client in &clients {
+                for _ in 0..copies {
+                    requests.push((client, hex.clone()));
+                }
+            }
+        }
+        requests.shuffle(&mut self.rng);
+
+        let results = futures::future::join_all(
+            requests
+                .into_iter()
+                .map(|(client, hex)| async move { client.send_yuv_tx(hex, None).await }),
+        )
+        .await;
+
+        let rejected = results.iter().filter(|result| result.is_err()).count();
+        info!(
+            "Sent {} duplicate(s) of {} tx(s), {} rejected",
+            results.len(),
+            sent_txs.len(),
+            rejected
+        );
+
+        Ok(())
+    }
+
+    /// Check the final state of the nodes. All the failed assertions are logged before
+    /// returning the error.
+    pub async fn check(
+        &mut self,
+        assertions: &AssertionsConfig,
+        sent_txs: &[YuvTransaction],
+    ) -> eyre::Result<()> {
+        let mut results = Vec::new();
+
+        if assertions.tips_match {
+            results.push(self.check_tips());
+        }
+        if assertions.pages_match {
+            results.push(check_pages_match(&self.config.nodes.yuv).await);
+        }
+        if let Some(min_attached) = assertions.min_attached {
+            results.push(self.check_min_attached(min_attached).await);
+        }
+        if assertions.no_pending {
+            results.push(self.check_no_pending(sent_txs).await);
+        }
+
+        let failed = results
+            .into_iter()
+            .filter_map(Result::err)
+            .inspect(|err| error!("Assertion failed: {:?}", err))
+            .count();
+
+        if failed > 0 {
+            bail!("{} assertion(s) failed", failed);
+        }
+
+        info!("All the assertions passed");
+
+        Ok(())
+    }
+
+    fn check_tips(&mut self) -> eyre::Result<()> {
+        let mut expected = None;
+
+        for index in 0..self.config.nodes.bitcoin.len() {
+            let tip = self.bitcoin_node(Some(index))?.get_best_block_hash()?;
+
+            let Some(expected_tip) = expected else {
+                expected = Some(tip);
+                continue;
+            };
+
+            if tip != expected_tip {
+                bail!(
+                    "Bitcoin node #{} has the tip {}, the node #0 has {}",
+                    index,
+                    tip,
+                    expected_tip
+                );
+            }
+        }
+
+        info!("Tips match");
+
+        Ok(())
+    }
+
+    async fn check_min_attached(&self, min_attached: usize) -> eyre::Result<()> {
+        for node in &self.config.nodes.yuv {
+            let client = HttpClientBuilder::new().build(node)?;
+            let attached = list_paged_txids(&client).await?.len();
+
+            if attached < min_attached {
+                bail!(
+                    "{} has {} attached txs, at least {} expected",
+                    node,
+                    attached,
+                    min_attached
+                );
+            }
+        }
+
+        info!("At least {} txs are attached on each node", min_attached);
+
+        Ok(())
+    }
+
+    async fn check_no_pending(&self, sent_txs: &[YuvTransaction]) -> eyre::Result<()> {
+        for node in &self.config.nodes.yuv {
+            let client = HttpClientBuilder::new().build(node)?;
+
+            let mut pending = Vec::new();
+            for tx in sent_txs {
+                let txid = tx.bitcoin_tx.txid();
+                let status = client.get_yuv_transaction(txid).await?.status;
+
+                if !matches!(
+                    status,
+                    YuvTransactionStatus::Attached | YuvTransactionStatus::None
+                ) {
+                    pending.push(txid);
+                }
+            }
+
+            if let Some(first) = pending.first() {
+                bail!(
+                    "{} of {} sent txs are pending on {}, e.g. {}",
+                    pending.len(),
+                    sent_txs.len(),
+                    node,
+                    first
+                );
+            }
+        }
+
+        info!("No sent txs are pending");
+
+        Ok(())
+    }
+}
+
+/// Poll the `condition` until it's true or the `timeout` is reached.
+async fn wait_for(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    false
+}
@@ -18,7 +18,7 @@ use bitcoin::{
 use chrono::{DateTime, Utc};
 use csv::Writer;
 use eyre::bail;
-use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use once_cell::sync::Lazy;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio_util::sync::CancellationToken;
@@ -30,7 +30,7 @@ use yuv_types::{ProofMap, YuvTransaction};
 use crate::{cli::e2e::NETWORK, config::TestConfig};
 
 /// A dummy address that is used to generate blocks.
-static ADDRESS: Lazy<Address<NetworkChecked>> = Lazy::new(|| {
+pub(crate) static ADDRESS: Lazy<Address<NetworkChecked>> = Lazy::new(|| {
     Address::from_str("bcrt1p7re7k8hwapgh4l9a2hx39u8t8ltnvn93tfcqnm02e2qzzjpnmqwq4rk0ya")
         .unwrap()
         .assume_checked()
@@ -141,6 +141,12 @@ impl TxChecker {
                         }
                     }
 
+                    // Check if pages match.
+                    if self.config.checker.check_pages_matching {
+                        info!("Checking pages matching");
+                        self.check_pages().await?;
+                    }
+
                     return Ok(());
                 }
             }
@@ -274,6 +280,12 @@ impl TxChecker {
         }
     }
 
+    /// `check_pages` checks if all the YUV nodes have the same transactions in the same order in
+    /// their pages.
+    async fn check_pages(&self) -> eyre::Result<()> {
+        check_pages_match(&self.config.nodes.yuv).await
+    }
+
     /// `check_balance` checks if the actual balances match the expected balances for a certain address.
     #[instrument(level = Level::INFO,
         name = "balance_checker",
@@ -325,3 +337,59 @@ impl TxChecker {
         }
     }
 }
+
+/// Get the ids of the attached transactions from all the pages of the node.
+pub(crate) async fn list_paged_txids(client: &HttpClient) -> eyre::Result<Vec<Txid>> {
+    let mut txids = Vec::new();
+
+    for page in 0.. {
+        let txs = client.list_yuv_transactions(page).await?;
+        if txs.is_empty() {
+            break;
+        }
+
+        txids.extend(
+            txs.into_iter()
+                .map(|tx| YuvTransaction::from(tx).bitcoin_tx.txid()),
+        );
+    }
+
+    Ok(txids)
+}
+
+/// Checks if all the `nodes` have the same transactions in the same order in their pages. The
+/// pages are compared as a whole list, as the page size may differ between the nodes.
+pub(crate) async fn check_pages_match(nodes: &[String]) -> eyre::Result<()> {
+    let mut expected: Option<(&String, Vec<Txid>)> = None;
+
+    for node in nodes {
+        let client = HttpClientBuilder::new().build(node)?;
+        let txids = list_paged_txids(&client).await?;
+
+        let Some((expected_node, expected_txids)) = &expected else {
+            expected = Some((node, txids));
+            continue;
+        };
+
+        if txids != *expected_txids {
+            let mismatch = txids
+                .iter()
+                .zip(expected_txids.iter())
+                .position(|(txid, expected_txid)| txid != expected_txid)
+                .unwrap_or(txids.len().min(expected_txids.len()));
+
+            bail!(
+                "Pages of {} and {} differ starting from the tx #{}: {} txs and {} txs in total",
+                node,
+                expected_node,
+                mismatch,
+                txids.len(),
+                expected_txids.len(),
+            );
+        }
+    }
+
+    info!("Pages match");
+
+    Ok(())
+}
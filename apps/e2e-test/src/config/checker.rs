@@ -4,4 +4,12 @@ use serde::Deserialize;
 pub struct CheckerConfig {
     pub threshold: u64,
     pub check_balances_matching: bool,
+    /// Compare the pages of the attached transactions of all the YUV nodes in the end of
+    /// the test. Enabled by default.
+    #[serde(default = "default_check_pages_matching")]
+    pub check_pages_matching: bool,
+}
+
+fn default_check_pages_matching() -> bool {
+    true
 }
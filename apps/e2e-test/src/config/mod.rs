@@ -13,11 +13,13 @@ mod accounts;
 mod checker;
 mod miner;
 mod report;
+mod scenario;
 use accounts::AccountsConfig;
 use checker::CheckerConfig;
 use miner::MinerConfig;
-use rand::{seq::IteratorRandom, thread_rng};
+use rand::{seq::IteratorRandom, Rng};
 use report::ReportConfig;
+pub(crate) use scenario::{AssertionsConfig, ScenarioConfig, Step};
 use serde::Deserialize;
 
 use config::Config;
@@ -25,6 +27,10 @@ use config::Config;
 #[derive(Deserialize, Debug, Clone)]
 pub(crate) struct TestConfig {
     pub duration: Option<Duration>,
+    /// Seed of the RNG that generates the accounts and picks the nodes. A random one is used if
+    /// not set, and is logged, so the run can be repeated.
+    #[serde(default)]
+    pub seed: Option<u64>,
     pub nodes: NodesConfig,
     pub accounts: AccountsConfig,
     pub checker: CheckerConfig,
@@ -42,12 +48,12 @@ impl TestConfig {
     }
 
     // Get a random YUV node.
-    pub fn get_yuv_node(&self) -> eyre::Result<(String, HttpClient)> {
+    pub fn get_yuv_node(&self, rng: &mut impl Rng) -> eyre::Result<(String, HttpClient)> {
         let node = self
             .nodes
             .yuv
             .iter()
-            .choose(&mut thread_rng())
+            .choose(rng)
             .ok_or_eyre("At least one YUV node should be present")?;
 
         Ok((node.to_string(), HttpClientBuilder::new().build(node)?))
@@ -57,36 +63,51 @@ impl TestConfig {
     pub fn get_bitcoin_node(
         &self,
         wallet_name: &str,
+        rng: &mut impl Rng,
     ) -> eyre::Result<(BitcoinNode, RpcBlockchain)> {
-        self.nodes
+        eyre::ensure!(
+            !self.nodes.bitcoin.is_empty(),
+            "At least one Bitcoin node should be present"
+        );
+        let index = rng.gen_range(0..self.nodes.bitcoin.len());
+
+        self.get_bitcoin_node_at(index, wallet_name)
+    }
+
+    // Get the Bitcoin node by its index in the config.
+    pub fn get_bitcoin_node_at(
+        &self,
+        index: usize,
+        wallet_name: &str,
+    ) -> eyre::Result<(BitcoinNode, RpcBlockchain)> {
+        let node = self
+            .nodes
             .bitcoin
-            .iter()
-            .choose(&mut thread_rng())
-            .map(|node| {
-                let auth = match &node.auth {
-                    Some(auth) => Auth::UserPass {
-                        username: auth.username.to_string(),
-                        password: auth.password.to_string(),
-                    },
-                    None => Auth::None,
-                };
+            .get(index)
+            .ok_or_eyre("Bitcoin node index is out of range")?;
+
+        let auth = match &node.auth {
+            Some(auth) => Auth::UserPass {
+                username: auth.username.to_string(),
+                password: auth.password.to_string(),
+            },
+            None => Auth::None,
+        };
 
-                let wallet_name = format!("test_wallet_{wallet_name}");
+        let wallet_name = format!("test_wallet_{wallet_name}");
 
-                Ok((
-                    node.clone(),
-                    RpcBlockchain::from_config(&RpcConfig {
-                        url: node.url.to_string(),
-                        auth,
-                        network: bitcoin::Network::Regtest,
-                        wallet_name,
-                        sync_params: Some(RpcSyncParams {
-                            start_time: 0,
-                            ..Default::default()
-                        }),
-                    })?,
-                ))
-            })
-            .expect("At least one Bitcoin node should be present")
+        Ok((
+            node.clone(),
+            RpcBlockchain::from_config(&RpcConfig {
+                url: node.url.to_string(),
+                auth,
+                network: bitcoin::Network::Regtest,
+                wallet_name,
+                sync_params: Some(RpcSyncParams {
+                    start_time: 0,
+                    ..Default::default()
+                }),
+            })?,
+        ))
     }
 }
@@ -0,0 +1,97 @@
+use std::{path::PathBuf, time::Duration};
+
+use config::Config;
+use serde::Deserialize;
+
+const DEFAULT_SETTLE_DURATION: Duration = Duration::from_secs(60);
+const DEFAULT_RESTART_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Scripted scenario of the simulation: the steps are executed one by one while the accounts
+/// are sending the transactions, and the assertions are checked in the end.
+#[derive(Deserialize, Clone, Debug)]
+pub(crate) struct ScenarioConfig {
+    /// Seed of the RNG that generates the accounts and picks the nodes, so the same scenario
+    /// can be replayed.
+    pub seed: u64,
+    /// How long to wait after the last step, so the nodes catch up before the assertions.
+    #[serde(default = "default_settle_duration")]
+    pub settle: Duration,
+    pub steps: Vec<Step>,
+    #[serde(default)]
+    pub assertions: AssertionsConfig,
+}
+
+impl ScenarioConfig {
+    pub fn from_path(path: PathBuf) -> eyre::Result<Self> {
+        let config = Config::builder()
+            .add_source(config::File::from(path))
+            .build()?;
+
+        Ok(config.try_deserialize()?)
+    }
+}
+
+fn default_settle_duration() -> Duration {
+    DEFAULT_SETTLE_DURATION
+}
+
+fn default_restart_timeout() -> Duration {
+    DEFAULT_RESTART_TIMEOUT
+}
+
+fn default_copies() -> usize {
+    1
+}
+
+/// Step of the scenario. The Bitcoin nodes are the indexes in the `nodes.bitcoin` list of the
+/// test config, a random one is picked if the index isn't set.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub(crate) enum Step {
+    /// Let the accounts send the transactions.
+    Wait { duration: Duration },
+    /// Replace the last `depth` blocks of the Bitcoin node with the longer chain.
+    Reorg {
+        depth: u64,
+        #[serde(default)]
+        node: Option<usize>,
+    },
+    /// Disconnect the Bitcoin node from its peers for the `duration`.
+    DisconnectPeers {
+        duration: Duration,
+        #[serde(default)]
+        node: Option<usize>,
+    },
+    /// Stop the Bitcoin node, and start it again with the `command`, e.g.
+    /// `["docker", "start", "bitcoind"]`.
+    RestartBitcoind {
+        command: Vec<String>,
+        #[serde(default)]
+        node: Option<usize>,
+        /// How long to wait for the node to start.
+        #[serde(default = "default_restart_timeout")]
+        timeout: Duration,
+    },
+    /// Send `copies` of each of the last `txs` transactions to every YUV node, so the nodes
+    /// receive the same transactions again and flood each other with the duplicate invs.
+    InvFlood {
+        txs: usize,
+        #[serde(default = "default_copies")]
+        copies: usize,
+    },
+}
+
+/// Checks of the nodes' state in the end of the simulation.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub(crate) struct AssertionsConfig {
+    /// All the YUV nodes have the same transactions in the same order in their pages.
+    pub pages_match: bool,
+    /// All the Bitcoin nodes have the same best block.
+    pub tips_match: bool,
+    /// Min number of the attached transactions on each of the YUV nodes.
+    pub min_attached: Option<usize>,
+    /// All the transactions sent by the accounts are attached or rejected, none of them is
+    /// stuck in the pending state.
+    pub no_pending: bool,
+}
@@ -0,0 +1,62 @@
+use config::Config;
+use serde::Deserialize;
+use yuv_types::network::Network;
+
+use std::path::PathBuf;
+
+mod storage;
+pub use storage::StorageConfig;
+
+mod bnode;
+pub use bnode::BitcoinConfig;
+
+mod logger;
+pub use logger::LoggerConfig;
+
+mod indexer;
+pub use indexer::IndexerConfig;
+
+mod upstream;
+pub use upstream::UpstreamConfig;
+
+mod sink;
+pub use sink::SinkConfig;
+
+#[derive(Deserialize)]
+pub struct IndexerAppConfig {
+    #[serde(default = "default_network")]
+    pub network: Network,
+
+    pub bnode: BitcoinConfig,
+    pub storage: StorageConfig,
+    pub sink: SinkConfig,
+
+    /// YUV node to fetch the transfers from. The transfers are not announced in the blocks, so
+    /// without the upstream node only the announcements and the checkpoint's transactions are
+    /// indexed.
+    #[serde(default)]
+    pub upstream: Option<UpstreamConfig>,
+
+    #[serde(default)]
+    pub shutdown_timeout: Option<u64>,
+
+    #[serde(default)]
+    pub logger: LoggerConfig,
+
+    #[serde(default)]
+    pub indexer: IndexerConfig,
+}
+
+fn default_network() -> Network {
+    Network::Bitcoin
+}
+
+impl IndexerAppConfig {
+    pub fn from_path(path: PathBuf) -> eyre::Result<Self> {
+        let config = Config::builder()
+            .add_source(config::File::from(path))
+            .build()?;
+
+        Ok(config.try_deserialize()?)
+    }
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Default interval between the requests of the new pages of the upstream node.
+pub const DEFAULT_UPSTREAM_POLLING_PERIOD: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize, Clone)]
+pub struct UpstreamConfig {
+    /// Url of the JSON RPC API of the YUV node to fetch the transactions from.
+    pub url: String,
+
+    /// Interval between the requests of the new pages.
+    #[serde(default = "default_polling_period")]
+    pub polling_period: Duration,
+}
+
+fn default_polling_period() -> Duration {
+    DEFAULT_UPSTREAM_POLLING_PERIOD
+}
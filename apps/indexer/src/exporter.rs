@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+use bitcoin::{secp256k1::XOnlyPublicKey, BlockHash, Txid};
+use bitcoin_client::{BitcoinRpcApi, BitcoinRpcClient};
+use event_bus::{typeid, EventBus};
+use eyre::{Context, Result};
+use tokio_util::sync::CancellationToken;
+use yuv_pixels::Chroma;
+use yuv_storage::{AddressIndexStorage, ChromaInfoStorage, LevelDB};
+use yuv_types::{
+    announcements::ChromaAnnouncement, network::Network, RpcSubscriptionMessage, YuvTransaction,
+};
+
+use crate::records::{
+    chroma_address, proof_owners, BalanceRecord, ChromaStatsRecord, Record, TransactionRecord,
+};
+use crate::sink::Sink;
+
+/// Exports the attached transactions along with the balances of their owners and the stats of
+/// their chromas to the [`Sink`].
+///
+/// Receives the same [`RpcSubscriptionMessage`]s the RPC subscriptions of the node are built
+/// from, so the records are written right after the controller updates the storage.
+pub struct Exporter {
+    event_bus: EventBus,
+    txs_storage: LevelDB,
+    state_storage: LevelDB,
+    btc_client: Arc<BitcoinRpcClient>,
+    sink: Box<dyn Sink>,
+    network: Network,
+}
+
+impl Exporter {
+    pub fn new(
+        full_event_bus: &EventBus,
+        txs_storage: LevelDB,
+        state_storage: LevelDB,
+        btc_client: Arc<BitcoinRpcClient>,
+        sink: Box<dyn Sink>,
+        network: Network,
+    ) -> Self {
+        let event_bus = full_event_bus
+            .extract(&typeid![], &typeid![RpcSubscriptionMessage])
+            .expect("event channels must be presented");
+
+        Self {
+            event_bus,
+            txs_storage,
+            state_storage,
+            btc_client,
+            sink,
+            network,
+        }
+    }
+
+    pub async fn run(mut self, cancellation: CancellationToken) {
+        let events = self.event_bus.subscribe::<RpcSubscriptionMessage>();
+
+        loop {
+            tokio::select! {
+                event_received = events.recv() => {
+                    let Ok(event) = event_received else {
+                        tracing::trace!("All incoming events senders are dropped");
+                        return;
+                    };
+
+                    if let Err(err) = self.handle_event(event).await {
+                        tracing::error!("Failed to export the records: {:?}", err);
+
+                        // The records of the already attached transactions can't be exported
+                        // again, so the indexer is stopped instead of skipping them.
+                        cancellation.cancel()
+                    }
+                }
+                _ = cancellation.cancelled() => {
+                    tracing::trace!("Cancellation received, stopping exporter");
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, event: RpcSubscriptionMessage) -> Result<()> {
+        match event {
+            RpcSubscriptionMessage::AttachedTxs(txs) => self
+                .export_txs(txs)
+                .await
+                .wrap_err("failed to export the attached transactions")?,
+            RpcSubscriptionMessage::ChromaAnnouncements(announcements) => self
+                .export_chroma_announcements(announcements)
+                .await
+                .wrap_err("failed to export the chroma announcements")?,
+            // Freezes don't change the balances, and the unconfirmed transfers are not attached.
+            // The exported records of the detached and the invalidated transactions are kept, as
+            // the sinks are append-only.
+            RpcSubscriptionMessage::Freezes(_)
+            | RpcSubscriptionMessage::UnconfirmedTransfers(_)
+            | RpcSubscriptionMessage::DetachedTxs(_)
+            | RpcSubscriptionMessage::InvalidatedTxs(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Exports the transactions, then the updated balances of the keys that own their inputs and
+    /// outputs, then the stats of the chromas of the transactions.
+    async fn export_txs(&mut self, txs: Vec<YuvTransaction>) -> Result<()> {
+        let mut records = Vec::with_capacity(txs.len());
+        let mut touched_chromas: BTreeMap<XOnlyPublicKey, BTreeSet<Chroma>> = BTreeMap::new();
+        let mut block_heights: HashMap<BlockHash, usize> = HashMap::new();
+
+        for yuv_tx in &txs {
+            let mut record = TransactionRecord::new(yuv_tx, self.network);
+
+            if let Some((block_hash, block_height)) =
+                self.find_block(&record.txid, &mut block_heights).await
+            {
+                record.block_hash = Some(block_hash);
+                record.block_height = Some(block_height);
+            }
+
+            let proofs = yuv_tx
+                .tx_type
+                .input_proofs()
+                .into_iter()
+                .chain(yuv_tx.tx_type.output_proofs())
+                .flat_map(|proofs| proofs.values());
+
+            for proof in proofs {
+                for owner in proof_owners(proof) {
+                    touched_chromas
+                        .entry(owner)
+                        .or_default()
+                        .insert(proof.pixel().chroma);
+                }
+            }
+
+            records.push(Record::Transaction(record));
+        }
+
+        for (owner, chromas) in &touched_chromas {
+            records.extend(self.balance_records(owner, chromas).await?);
+        }
+
+        let chromas = touched_chromas
+            .into_values()
+            .flatten()
+            .collect::<BTreeSet<_>>();
+        records.extend(self.chroma_stats_records(chromas).await?);
+
+        self.sink.write(records).await
+    }
+
+    async fn export_chroma_announcements(
+        &mut self,
+        announcements: Vec<ChromaAnnouncement>,
+    ) -> Result<()> {
+        let chromas = announcements
+            .iter()
+            .map(|announcement| announcement.chroma)
+            .collect::<BTreeSet<_>>();
+
+        let records = self.chroma_stats_records(chromas).await?;
+
+        self.sink.write(records).await
+    }
+
+    /// Returns the balances of the key in the chromas, including the zero ones, so the sink can
+    /// update the balances that were spent completely.
+    async fn balance_records(
+        &self,
+        owner: &XOnlyPublicKey,
+        chromas: &BTreeSet<Chroma>,
+    ) -> Result<Vec<Record>> {
+        let utxos = self.txs_storage.get_address_utxos(owner).await?;
+
+        let records = chromas
+            .iter()
+            .map(|chroma| {
+                let pixels = utxos.values().filter(|pixel| pixel.chroma == *chroma);
+
+                Record::Balance(BalanceRecord {
+                    owner: *owner,
+                    chroma: chroma_address(chroma, self.network),
+                    balance: pixels.clone().map(|pixel| pixel.luma.amount).sum(),
+                    utxos: pixels.count(),
+                })
+            })
+            .collect();
+
+        Ok(records)
+    }
+
+    async fn chroma_stats_records(&self, chromas: BTreeSet<Chroma>) -> Result<Vec<Record>> {
+        let mut records = Vec::with_capacity(chromas.len());
+
+        for chroma in chromas {
+            let Some(info) = self.state_storage.get_chroma_info(&chroma).await? else {
+                continue;
+            };
+
+            records.push(Record::ChromaStats(ChromaStatsRecord::new(
+                &chroma,
+                info,
+                self.network,
+            )));
+        }
+
+        Ok(records)
+    }
+
+    /// Returns the hash and the height of the block the transaction is mined in. Lookup requires
+    /// `txindex` to be enabled on the Bitcoin node, otherwise the block is left unknown.
+    async fn find_block(
+        &self,
+        txid: &Txid,
+        block_heights: &mut HashMap<BlockHash, usize>,
+    ) -> Option<(BlockHash, usize)> {
+        let tx_info = match self.btc_client.get_raw_transaction_info(txid, None).await {
+            Ok(tx_info) => tx_info,
+            Err(err) => {
+                tracing::debug!(txid = txid.to_string(), "Failed to find the block: {}", err);
+                return None;
+            }
+        };
+
+        let block_hash = tx_info.blockhash?;
+        if let Some(block_height) = block_heights.get(&block_hash) {
+            return Some((block_hash, *block_height));
+        }
+
+        let header = match self.btc_client.get_block_header_info(&block_hash).await {
+            Ok(header) => header,
+            Err(err) => {
+                tracing::debug!(%block_hash, "Failed to get the block header: {}", err);
+                return None;
+            }
+        };
+
+        block_heights.insert(block_hash, header.height);
+
+        Some((block_hash, header.height))
+    }
+}
@@ -0,0 +1,357 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bitcoin_client::BitcoinRpcClient;
+use event_bus::{BusEvent, Completion, EventBus};
+use eyre::{eyre, Context};
+use tokio::select;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{error, info};
+use yuv_controller::Controller;
+use yuv_indexers::{
+    import_checkpoint, AnnouncementsIndexer, BitcoinBlockIndexer, CheckpointParams,
+    ConfirmationIndexer, IndexingParams, RunParams,
+};
+use yuv_p2p::client::handle::OfflineHandle;
+use yuv_scheduler::{Job, Schedule, Scheduler};
+use yuv_storage::{FlushStrategy, LevelDB, LevelDbOptions};
+use yuv_tx_attach::GraphBuilder;
+use yuv_tx_check::TxChecker;
+use yuv_tx_confirm::TxConfirmator;
+use yuv_types::{
+    ControllerMessage, GraphBuilderMessage, IndexerMessage, RpcSubscriptionMessage,
+    TxCheckerMessage, TxConfirmMessage,
+};
+
+use crate::config::{IndexerAppConfig, StorageConfig};
+use crate::exporter::Exporter;
+use crate::sink;
+use crate::upstream::UpstreamSource;
+
+/// Default size of the channel for the event bus.
+const DEFAULT_CHANNEL_SIZE: usize = 1000;
+/// The limit of time to wait for the indexer to shutdown.
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+/// Interval of the graph builder's clean up of the transactions waiting for their parents.
+const GRAPH_BUILDER_CLEAN_UP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Indexer runs the same services that check and attach the transactions as the node, but
+/// without the P2P network and the RPC servers, and exports the attached transactions with the
+/// [`Exporter`].
+pub struct Indexer {
+    config: IndexerAppConfig,
+    event_bus: EventBus,
+    txs_storage: LevelDB,
+    state_storage: LevelDB,
+    btc_client: Arc<BitcoinRpcClient>,
+
+    cancelation: CancellationToken,
+    pub(crate) task_tracker: TaskTracker,
+}
+
+impl Indexer {
+    pub async fn new(config: IndexerAppConfig) -> eyre::Result<Self> {
+        let event_bus = Self::init_event_bus();
+        let (txs_storage, state_storage) = Self::init_storage(config.storage.clone())?;
+
+        let btc_client = Arc::new(
+            BitcoinRpcClient::new_with_proxy(
+                config.bnode.auth().clone(),
+                config.bnode.url.clone(),
+                config.bnode.timeout,
+                config.bnode.proxy.clone(),
+            )
+            .await?,
+        );
+
+        Ok(Self {
+            config,
+            event_bus,
+            txs_storage,
+            state_storage,
+            btc_client,
+            cancelation: CancellationToken::new(),
+            task_tracker: TaskTracker::new(),
+        })
+    }
+
+    /// Wait for the signal from any indexer's service about the cancellation.
+    pub async fn cancelled(&self) {
+        self.cancelation.cancelled().await
+    }
+
+    /// The exporter is started before the indexer, so the records of the transactions attached
+    /// during the initial indexing are not missed.
+    pub async fn run(&self) -> eyre::Result<()> {
+        self.spawn_exporter().await?;
+
+        self.spawn_graph_builder();
+        self.spawn_tx_checker();
+        self.spawn_tx_confirmator();
+        self.spawn_controller().await?;
+        self.spawn_indexer().await?;
+
+        self.spawn_upstream()?;
+        self.spawn_scheduler()?;
+
+        self.task_tracker.close();
+
+        Ok(())
+    }
+
+    async fn spawn_exporter(&self) -> eyre::Result<()> {
+        let sink = sink::from_config(&self.config.sink).await?;
+
+        let exporter = Exporter::new(
+            &self.event_bus,
+            self.txs_storage.clone(),
+            self.state_storage.clone(),
+            self.btc_client.clone(),
+            sink,
+            self.config.network,
+        );
+
+        self.task_tracker
+            .spawn(exporter.run(self.cancelation.clone()));
+
+        Ok(())
+    }
+
+    /// The controller has no P2P peers, and maintains the address and the history indexes the
+    /// balances are exported from.
+    async fn spawn_controller(&self) -> eyre::Result<()> {
+        let mut controller = Controller::new(
+            &self.event_bus,
+            self.txs_storage.clone(),
+            self.state_storage.clone(),
+            OfflineHandle,
+            self.config.storage.tx_per_page,
+        )
+        .set_address_index(true)
+        .set_history_index(true);
+
+        controller.recover_attached_txs().await?;
+        controller.build_address_index().await?;
+        controller.handle_mempool_txs().await?;
+
+        self.task_tracker
+            .spawn(controller.run(self.cancelation.clone()));
+
+        Ok(())
+    }
+
+    fn spawn_graph_builder(&self) {
+        let graph_builder = GraphBuilder::new(self.txs_storage.clone(), &self.event_bus);
+
+        self.task_tracker
+            .spawn(graph_builder.run(self.cancelation.clone()));
+    }
+
+    fn spawn_tx_checker(&self) {
+        let tx_checker = TxChecker::new(
+            self.event_bus.clone(),
+            self.txs_storage.clone(),
+            self.state_storage.clone(),
+            self.config.network,
+        );
+
+        self.task_tracker
+            .spawn(tx_checker.run(self.cancelation.clone()));
+    }
+
+    fn spawn_tx_confirmator(&self) {
+        let tx_confirmator = TxConfirmator::new(
+            &self.event_bus,
+            self.btc_client.clone(),
+            self.config.indexer.max_confirmation_time,
+            self.config.indexer.confirmations_number,
+        );
+
+        self.task_tracker
+            .spawn(tx_confirmator.run(self.cancelation.clone()));
+    }
+
+    fn spawn_upstream(&self) -> eyre::Result<()> {
+        let Some(upstream) = &self.config.upstream else {
+            info!("Upstream node is not set, only the announcements are indexed from the blocks");
+            return Ok(());
+        };
+
+        let source = UpstreamSource::new(&self.event_bus, upstream)?;
+
+        self.task_tracker
+            .spawn(source.run(self.cancelation.clone()));
+
+        Ok(())
+    }
+
+    fn spawn_scheduler(&self) -> eyre::Result<()> {
+        let mut scheduler = Scheduler::default();
+        scheduler
+            .add_job(
+                "graph_builder_clean_up",
+                Schedule::Every(GRAPH_BUILDER_CLEAN_UP_INTERVAL),
+                send_event_job(self.event_bus.clone(), GraphBuilderMessage::CleanUp),
+            )?
+            .add_job(
+                "tx_confirmator_clean_up",
+                Schedule::Every(self.config.indexer.clean_up_interval),
+                send_event_job(self.event_bus.clone(), TxConfirmMessage::CleanUp),
+            )?;
+
+        self.task_tracker
+            .spawn(scheduler.run(self.cancelation.clone()));
+
+        Ok(())
+    }
+
+    async fn spawn_indexer(&self) -> eyre::Result<()> {
+        let mut indexer = BitcoinBlockIndexer::new(
+            self.btc_client.clone(),
+            self.state_storage.clone(),
+            &self.event_bus,
+            self.config.network,
+        );
+
+        indexer.add_subindexer(AnnouncementsIndexer::new(
+            &self.event_bus,
+            self.config.network,
+        ));
+        indexer.add_subindexer(ConfirmationIndexer::new(&self.event_bus));
+
+        let params: IndexingParams = self.config.indexer.clone().into();
+        if let Some(checkpoint) = &params.checkpoint {
+            let checkpoint = CheckpointParams {
+                address_index: true,
+                history_index: true,
+                ..checkpoint.clone()
+            };
+            import_checkpoint(
+                &checkpoint,
+                self.btc_client.as_ref(),
+                &self.txs_storage,
+                &self.state_storage,
+                self.config.storage.tx_per_page,
+            )
+            .await
+            .wrap_err("failed to import the checkpoint")?;
+        }
+
+        let restart_interval = self.config.indexer.restart_interval;
+        let mut current_attempt = 1;
+        while let Err(err) = indexer
+            .init(
+                params.clone(),
+                self.config.indexer.blockloader.clone(),
+                self.btc_client.clone(),
+                self.config.indexer.confirmations_number as usize,
+                self.cancelation.clone(),
+            )
+            .await
+        {
+            if current_attempt >= self.config.indexer.max_restart_attempts {
+                return Err(err);
+            }
+
+            current_attempt += 1;
+            error!(
+                %err,
+                "Failed to init the indexer. Trying again in {} secs",
+                restart_interval.as_secs()
+            );
+            tokio::time::sleep(restart_interval).await;
+        }
+
+        self.task_tracker.spawn(indexer.run(
+            RunParams {
+                polling_period: self.config.indexer.polling_period,
+            },
+            self.cancelation.clone(),
+        ));
+
+        Ok(())
+    }
+
+    fn init_storage(config: StorageConfig) -> eyre::Result<(LevelDB, LevelDB)> {
+        if !config.path.exists() {
+            std::fs::create_dir_all(&config.path)
+                .wrap_err_with(|| format!("failed to create directory {:?}", config.path))?;
+        }
+
+        let opt = LevelDbOptions {
+            create_if_missing: config.create_if_missing,
+            path: config.path.join("transactions"),
+            flush_strategy: FlushStrategy::Ticker {
+                period: config.flush_period,
+            },
+        };
+        let txs_storage = LevelDB::from_opts(opt).wrap_err("failed to initialize storage")?;
+
+        let opt = LevelDbOptions {
+            path: config.path.join("state"),
+            create_if_missing: config.create_if_missing,
+            flush_strategy: FlushStrategy::Ticker {
+                period: config.flush_period,
+            },
+        };
+        let state_storage = LevelDB::from_opts(opt).wrap_err("failed to initialize storage")?;
+
+        Ok((txs_storage, state_storage))
+    }
+
+    fn init_event_bus() -> EventBus {
+        let mut event_bus = EventBus::default();
+        event_bus.register::<TxCheckerMessage>(Some(DEFAULT_CHANNEL_SIZE));
+        event_bus.register::<GraphBuilderMessage>(Some(DEFAULT_CHANNEL_SIZE));
+        event_bus.register::<ControllerMessage>(Some(DEFAULT_CHANNEL_SIZE));
+        event_bus.register::<TxConfirmMessage>(Some(DEFAULT_CHANNEL_SIZE));
+        event_bus.register::<IndexerMessage>(Some(DEFAULT_CHANNEL_SIZE));
+        event_bus.register::<RpcSubscriptionMessage>(Some(DEFAULT_CHANNEL_SIZE));
+
+        event_bus
+    }
+
+    pub async fn shutdown(&self) {
+        info!("Shutting down indexer, finishing exporting the records...");
+
+        self.cancelation.cancel();
+
+        let timeout = self
+            .config
+            .shutdown_timeout
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS);
+
+        select! {
+            _ = self.task_tracker.wait() => {},
+            _ = sleep(Duration::from_secs(timeout)) => {
+                info!("Shutdown timeout reached, exiting...");
+            },
+        }
+    }
+}
+
+/// Returns the scheduled job that sends the event to the service that does the actual work. The
+/// job waits for the service to complete the work, so the scheduler doesn't overlap the runs and
+/// reports their real duration and failures.
+fn send_event_job<E, F>(event_bus: EventBus, event: F) -> impl Job
+where
+    E: BusEvent + Clone + Sync + 'static,
+    F: Fn(Completion) -> E + Send + Sync + 'static,
+{
+    move || {
+        let event_bus = event_bus.clone();
+        let (completion, waiter) = Completion::new();
+        let event = event(completion);
+
+        async move {
+            event_bus
+                .try_send(event)
+                .await
+                .map_err(|err| eyre!("failed to send the event: {err}"))?;
+
+            waiter.wait().await.map_err(|err| eyre!(err))
+        }
+    }
+}
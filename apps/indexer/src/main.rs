@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Parser;
+use tokio::select;
+use tokio::signal::unix::{self, SignalKind};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+use crate::config::IndexerAppConfig;
+use crate::indexer::Indexer;
+
+mod config;
+mod exporter;
+mod indexer;
+mod records;
+mod sink;
+mod upstream;
+
+#[derive(Parser)]
+#[clap(author, version, about, long_about = None)]
+struct Cli {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    config: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let args = Cli::parse();
+    let config = IndexerAppConfig::from_path(args.config)?;
+
+    // Disable `hyper_util` logs emitting from the `jsonrpc` crate.
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::from_level(config.logger.level).into())
+        .from_env()?
+        .add_directive("hyper_util=info".parse()?)
+        .add_directive("hyper=info".parse()?);
+
+    tracing_subscriber::fmt().with_env_filter(env_filter).init();
+
+    let indexer = Arc::new(Indexer::new(config).await?);
+    let indexer_clone = indexer.clone();
+
+    tokio::spawn(async move {
+        if let Err(err) = indexer_clone.run().await {
+            tracing::error!("Indexer cancelled: {:?}", err);
+        }
+        indexer_clone.task_tracker.close();
+    });
+
+    let mut sigterm =
+        unix::signal(SignalKind::terminate()).expect("Failed to create SIGTERM signal handler");
+    let mut sigint =
+        unix::signal(SignalKind::interrupt()).expect("Failed to create SIGINT signal handler");
+
+    select! {
+        _ = indexer.cancelled() => {
+            tracing::info!("Indexer run failed");
+        }
+        _ = sigterm.recv() => {
+            tracing::info!("Received SIGTERM signal");
+        }
+        _ = sigint.recv() => {
+            tracing::info!("Received SIGINT signal");
+        }
+    }
+
+    indexer.shutdown().await;
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! Records the indexer exports to the [`Sink`](crate::sink::Sink).
+use bitcoin::{secp256k1::XOnlyPublicKey, BlockHash, OutPoint, Txid};
+use serde::Serialize;
+use yuv_pixels::{Chroma, PixelProof};
+use yuv_types::{announcements::ChromaInfo, network::Network, YuvTransaction, YuvTxType};
+
+/// Exported record of one of the kinds. Records of each kind are written to the separate file or
+/// table, so the kind is not serialized.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Record {
+    Transaction(TransactionRecord),
+    Balance(BalanceRecord),
+    ChromaStats(ChromaStatsRecord),
+}
+
+impl Record {
+    /// Name of the record's kind, which is the name of the JSONL file or the table.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Transaction(_) => "transactions",
+            Self::Balance(_) => "balances",
+            Self::ChromaStats(_) => "chroma_stats",
+        }
+    }
+}
+
+/// Attached transaction with the pixels of its inputs and outputs.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionRecord {
+    pub txid: Txid,
+    /// `issue`, `transfer` or `announcement`.
+    pub tx_type: &'static str,
+    /// Block the transaction is mined in, if it's found by the Bitcoin node.
+    pub block_hash: Option<BlockHash>,
+    pub block_height: Option<usize>,
+    pub inputs: Vec<PixelRecord>,
+    pub outputs: Vec<PixelRecord>,
+}
+
+/// Pixel of the transaction's input or output.
+#[derive(Debug, Clone, Serialize)]
+pub struct PixelRecord {
+    pub outpoint: OutPoint,
+    /// Address of the chroma.
+    pub chroma: String,
+    pub amount: u128,
+    /// Keys of the owners. Empty for the outputs with the Lightning, empty or bulletproof proofs.
+    pub owners: Vec<XOnlyPublicKey>,
+}
+
+/// Balance of the owner's key in the chroma after the transactions.
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceRecord {
+    pub owner: XOnlyPublicKey,
+    /// Address of the chroma.
+    pub chroma: String,
+    pub balance: u128,
+    /// Number of the unspent outputs of the chroma owned by the key.
+    pub utxos: usize,
+}
+
+/// State of the chroma after the transactions.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChromaStatsRecord {
+    /// Address of the chroma.
+    pub chroma: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimal: Option<u8>,
+    pub max_supply: Option<u128>,
+    pub total_supply: u128,
+    pub total_burned: u128,
+    pub announcement_version: u32,
+}
+
+impl TransactionRecord {
+    pub fn new(yuv_tx: &YuvTransaction, network: Network) -> Self {
+        let txid = yuv_tx.bitcoin_tx.txid();
+
+        let tx_type = match yuv_tx.tx_type {
+            YuvTxType::Issue { .. } => "issue",
+            YuvTxType::Transfer { .. } => "transfer",
+            YuvTxType::Announcement(_) => "announcement",
+        };
+
+        let inputs = yuv_tx
+            .tx_type
+            .input_proofs()
+            .into_iter()
+            .flatten()
+            .filter_map(|(index, proof)| {
+                let input = yuv_tx.bitcoin_tx.input.get(*index as usize)?;
+
+                Some(PixelRecord::new(input.previous_output, proof, network))
+            })
+            .collect();
+
+        let outputs = yuv_tx
+            .tx_type
+            .output_proofs()
+            .into_iter()
+            .flatten()
+            .map(|(vout, proof)| PixelRecord::new(OutPoint::new(txid, *vout), proof, network))
+            .collect();
+
+        Self {
+            txid,
+            tx_type,
+            block_hash: None,
+            block_height: None,
+            inputs,
+            outputs,
+        }
+    }
+}
+
+impl PixelRecord {
+    fn new(outpoint: OutPoint, proof: &PixelProof, network: Network) -> Self {
+        let pixel = proof.pixel();
+
+        Self {
+            outpoint,
+            chroma: chroma_address(&pixel.chroma, network),
+            amount: pixel.luma.amount,
+            owners: proof_owners(proof),
+        }
+    }
+}
+
+impl ChromaStatsRecord {
+    pub fn new(chroma: &Chroma, info: ChromaInfo, network: Network) -> Self {
+        let announcement = info.announcement.as_ref();
+
+        Self {
+            chroma: chroma_address(chroma, network),
+            name: announcement.map(|announcement| announcement.name.clone()),
+            symbol: announcement.map(|announcement| announcement.symbol.clone()),
+            decimal: announcement.map(|announcement| announcement.decimal),
+            max_supply: announcement.map(|announcement| announcement.max_supply),
+            total_supply: info.total_supply,
+            total_burned: info.total_burned,
+            announcement_version: info.announcement_version,
+        }
+    }
+}
+
+/// Returns the x-only keys of the output's owners, the same ones the address index of the
+/// controller is built by.
+pub fn proof_owners(proof: &PixelProof) -> Vec<XOnlyPublicKey> {
+    if proof.is_empty_pixelproof() {
+        return Vec::new();
+    }
+
+    let owners = match proof {
+        PixelProof::Sig(proof) => vec![proof.inner_key],
+        PixelProof::Multisig(proof) => proof.inner_keys.clone(),
+        PixelProof::Musig(proof) => proof.inner_keys.clone(),
+        PixelProof::Timelock(proof) => vec![proof.inner_key],
+        PixelProof::Htlc(proof) => vec![proof.data.recipient_pubkey, proof.data.sender_pubkey],
+        _ => return Vec::new(),
+    };
+
+    owners
+        .iter()
+        .map(|owner| owner.x_only_public_key().0)
+        .collect()
+}
+
+pub fn chroma_address(chroma: &Chroma, network: Network) -> String {
+    chroma.to_address(network.to_bitcoin_network()).to_string()
+}
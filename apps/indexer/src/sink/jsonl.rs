@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use eyre::Context;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use super::Sink;
+use crate::records::Record;
+
+/// Appends the records to the JSON Lines files in the directory: `transactions.jsonl`,
+/// `balances.jsonl` and `chroma_stats.jsonl`. The balances and the stats are appended on each
+/// change, so the last line of the key is the actual one.
+pub struct JsonlSink {
+    path: PathBuf,
+    files: HashMap<&'static str, File>,
+}
+
+impl JsonlSink {
+    pub fn new(path: PathBuf) -> eyre::Result<Self> {
+        if !path.exists() {
+            std::fs::create_dir_all(&path)
+                .wrap_err_with(|| format!("failed to create directory {:?}", path))?;
+        }
+
+        Ok(Self {
+            path,
+            files: HashMap::new(),
+        })
+    }
+
+    async fn file(&mut self, kind: &'static str) -> eyre::Result<&mut File> {
+        if !self.files.contains_key(kind) {
+            let path = self.path.join(format!("{kind}.jsonl"));
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .await
+                .wrap_err_with(|| format!("failed to open {:?}", path))?;
+
+            self.files.insert(kind, file);
+        }
+
+        Ok(self.files.get_mut(kind).expect("file is opened above"))
+    }
+}
+
+#[async_trait]
+impl Sink for JsonlSink {
+    async fn write(&mut self, records: Vec<Record>) -> eyre::Result<()> {
+        let mut lines: HashMap<&'static str, Vec<u8>> = HashMap::new();
+
+        for record in &records {
+            let buf = lines.entry(record.kind()).or_default();
+            serde_json::to_writer(&mut *buf, record)?;
+            buf.push(b'\n');
+        }
+
+        for (kind, buf) in lines {
+            let file = self.file(kind).await?;
+            file.write_all(&buf).await?;
+            file.flush().await?;
+        }
+
+        Ok(())
+    }
+}
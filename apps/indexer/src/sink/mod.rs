@@ -0,0 +1,39 @@
+//! Destinations of the records exported by the indexer.
+use async_trait::async_trait;
+
+use crate::config::SinkConfig;
+use crate::records::Record;
+
+mod jsonl;
+pub use jsonl::JsonlSink;
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresSink;
+
+/// Destination of the exported records.
+#[async_trait]
+pub trait Sink: Send + Sync {
+    /// Write the records of one batch of the attached transactions. The records are ordered:
+    /// the transactions go first, then the balances and the stats of the chromas after them.
+    async fn write(&mut self, records: Vec<Record>) -> eyre::Result<()>;
+}
+
+/// Create the sink from the config.
+pub async fn from_config(config: &SinkConfig) -> eyre::Result<Box<dyn Sink>> {
+    match config {
+        SinkConfig::Jsonl { path } => Ok(Box::new(JsonlSink::new(path.clone())?)),
+        #[cfg(feature = "postgres")]
+        SinkConfig::Postgres {
+            url,
+            max_connections,
+        } => Ok(Box::new(
+            PostgresSink::connect(url, *max_connections).await?,
+        )),
+        #[cfg(not(feature = "postgres"))]
+        SinkConfig::Postgres { .. } => eyre::bail!(
+            "The indexer is built without the `postgres` feature, the Postgres sink is unavailable"
+        ),
+    }
+}
@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use eyre::Context;
+use sqlx::{postgres::PgPoolOptions, PgPool, Postgres, Transaction};
+
+use super::Sink;
+use crate::records::{BalanceRecord, ChromaStatsRecord, Record, TransactionRecord};
+
+/// Tables of the records, created on the connection if missing. The amounts are stored as
+/// `NUMERIC`, as they don't fit into `BIGINT`.
+const SCHEMA: [&str; 3] = [
+    "CREATE TABLE IF NOT EXISTS transactions (
+        txid TEXT PRIMARY KEY,
+        tx_type TEXT NOT NULL,
+        block_hash TEXT,
+        block_height BIGINT,
+        inputs JSONB NOT NULL,
+        outputs JSONB NOT NULL
+    )",
+    "CREATE TABLE IF NOT EXISTS balances (
+        owner TEXT NOT NULL,
+        chroma TEXT NOT NULL,
+        balance NUMERIC(39, 0) NOT NULL,
+        utxos BIGINT NOT NULL,
+        PRIMARY KEY (owner, chroma)
+    )",
+    "CREATE TABLE IF NOT EXISTS chroma_stats (
+        chroma TEXT PRIMARY KEY,
+        name TEXT,
+        symbol TEXT,
+        decimals SMALLINT,
+        max_supply NUMERIC(39, 0),
+        total_supply NUMERIC(39, 0) NOT NULL,
+        total_burned NUMERIC(39, 0) NOT NULL,
+        announcement_version BIGINT NOT NULL
+    )",
+];
+
+/// Writes the records to the PostgreSQL database. The transactions are inserted, and the balances
+/// and the stats of the chromas are upserted, so the tables contain the actual ones. Each batch is
+/// written in a single database transaction.
+pub struct PostgresSink {
+    pool: PgPool,
+}
+
+impl PostgresSink {
+    pub async fn connect(url: &str, max_connections: u32) -> eyre::Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(url)
+            .await
+            .wrap_err("failed to connect to the database")?;
+
+        for statement in SCHEMA {
+            sqlx::query(statement)
+                .execute(&pool)
+                .await
+                .wrap_err("failed to create the tables")?;
+        }
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Sink for PostgresSink {
+    async fn write(&mut self, records: Vec<Record>) -> eyre::Result<()> {
+        let mut db_tx = self.pool.begin().await?;
+
+        for record in &records {
+            match record {
+                Record::Transaction(record) => insert_transaction(&mut db_tx, record).await?,
+                Record::Balance(record) => upsert_balance(&mut db_tx, record).await?,
+                Record::ChromaStats(record) => upsert_chroma_stats(&mut db_tx, record).await?,
+            }
+        }
+
+        db_tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+async fn insert_transaction(
+    db_tx: &mut Transaction<'_, Postgres>,
+    record: &TransactionRecord,
+) -> eyre::Result<()> {
+    sqlx::query(
+        "INSERT INTO transactions (txid, tx_type, block_hash, block_height, inputs, outputs)
+        VALUES ($1, $2, $3, $4, $5::jsonb, $6::jsonb)
+        ON CONFLICT (txid) DO UPDATE SET
+            block_hash = EXCLUDED.block_hash,
+            block_height = EXCLUDED.block_height",
+    )
+    .bind(record.txid.to_string())
+    .bind(record.tx_type)
+    .bind(record.block_hash.map(|hash| hash.to_string()))
+    .bind(record.block_height.map(|height| height as i64))
+    .bind(serde_json::to_string(&record.inputs)?)
+    .bind(serde_json::to_string(&record.outputs)?)
+    .execute(&mut **db_tx)
+    .await
+    .wrap_err("failed to insert the transaction")?;
+
+    Ok(())
+}
+
+async fn upsert_balance(
+    db_tx: &mut Transaction<'_, Postgres>,
+    record: &BalanceRecord,
+) -> eyre::Result<()> {
+    sqlx::query(
+        "INSERT INTO balances (owner, chroma, balance, utxos)
+        VALUES ($1, $2, $3::numeric, $4)
+        ON CONFLICT (owner, chroma) DO UPDATE SET
+            balance = EXCLUDED.balance,
+            utxos = EXCLUDED.utxos",
+    )
+    .bind(record.owner.to_string())
+    .bind(&record.chroma)
+    .bind(record.balance.to_string())
+    .bind(record.utxos as i64)
+    .execute(&mut **db_tx)
+    .await
+    .wrap_err("failed to upsert the balance")?;
+
+    Ok(())
+}
+
+async fn upsert_chroma_stats(
+    db_tx: &mut Transaction<'_, Postgres>,
+    record: &ChromaStatsRecord,
+) -> eyre::Result<()> {
+    sqlx::query(
+        "INSERT INTO chroma_stats (chroma, name, symbol, decimals, max_supply, total_supply,
+            total_burned, announcement_version)
+        VALUES ($1, $2, $3, $4, $5::numeric, $6::numeric, $7::numeric, $8)
+        ON CONFLICT (chroma) DO UPDATE SET
+            name = EXCLUDED.name,
+            symbol = EXCLUDED.symbol,
+            decimals = EXCLUDED.decimals,
+            max_supply = EXCLUDED.max_supply,
+            total_supply = EXCLUDED.total_supply,
+            total_burned = EXCLUDED.total_burned,
+            announcement_version = EXCLUDED.announcement_version",
+    )
+    .bind(&record.chroma)
+    .bind(&record.name)
+    .bind(&record.symbol)
+    .bind(record.decimal.map(i16::from))
+    .bind(record.max_supply.map(|max_supply| max_supply.to_string()))
+    .bind(record.total_supply.to_string())
+    .bind(record.total_burned.to_string())
+    .bind(record.announcement_version as i64)
+    .execute(&mut **db_tx)
+    .await
+    .wrap_err("failed to upsert the chroma stats")?;
+
+    Ok(())
+}
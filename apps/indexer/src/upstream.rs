@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use event_bus::{typeid, EventBus};
+use eyre::Context;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use tokio_util::sync::CancellationToken;
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+use yuv_types::{ControllerMessage, YuvTransaction};
+
+use crate::config::UpstreamConfig;
+
+/// Fetches the attached transactions from the pages of the upstream YUV node, and submits them to
+/// the controller the same way the transactions from the RPC are. The transactions are checked
+/// again by the indexer, so the upstream node doesn't have to be trusted.
+pub struct UpstreamSource {
+    event_bus: EventBus,
+    client: HttpClient,
+    polling_period: Duration,
+    /// Number of the page to request next. The last page is requested until it's full, as the
+    /// upstream node appends the new transactions to it.
+    page: u64,
+}
+
+impl UpstreamSource {
+    pub fn new(full_event_bus: &EventBus, config: &UpstreamConfig) -> eyre::Result<Self> {
+        let event_bus = full_event_bus
+            .extract(&typeid![ControllerMessage], &typeid![])
+            .expect("event channels must be presented");
+
+        let client = HttpClientBuilder::new()
+            .build(&config.url)
+            .wrap_err("failed to create the upstream node client")?;
+
+        Ok(Self {
+            event_bus,
+            client,
+            polling_period: config.polling_period,
+            page: 0,
+        })
+    }
+
+    pub async fn run(mut self, cancellation: CancellationToken) {
+        let mut timer = tokio::time::interval(self.polling_period);
+
+        loop {
+            tokio::select! {
+                _ = timer.tick() => {
+                    if let Err(err) = self.fetch_new_pages().await {
+                        tracing::warn!("Failed to fetch the upstream transactions: {:?}", err);
+                    }
+                }
+                _ = cancellation.cancelled() => {
+                    tracing::trace!("Cancellation received, stopping upstream source");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Requests the pages starting from the last one until the empty page, and submits their
+    /// transactions. The ones that are already attached or handled are skipped by the controller.
+    async fn fetch_new_pages(&mut self) -> eyre::Result<()> {
+        loop {
+            let txs: Vec<YuvTransaction> = self
+                .client
+                .list_yuv_transactions(self.page)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect();
+
+            if txs.is_empty() {
+                // Step back to the last non-empty page, as it can be filled up later.
+                self.page = self.page.saturating_sub(1);
+                return Ok(());
+            }
+
+            tracing::debug!(
+                page = self.page,
+                "Fetched {} transactions from the upstream node",
+                txs.len()
+            );
+
+            self.event_bus
+                .send(ControllerMessage::InitializeTxs(txs))
+                .await;
+
+            self.page += 1;
+        }
+    }
+}
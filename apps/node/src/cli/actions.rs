@@ -1,21 +1,32 @@
+use eyre::{bail, Context, OptionExt};
+use jsonrpsee::http_client::HttpClientBuilder;
 use std::sync::Arc;
 use tokio::select;
 use tokio::signal::unix;
 use tokio::signal::unix::SignalKind;
 
+#[cfg(feature = "otel")]
+use crate::cli::telemetry;
 use crate::{
-    cli::{arguments, node::Node},
-    config::NodeConfig,
+    cli::{arguments, node::Node, repair, replay, state},
+    config::{LogFormat, NodeConfig, StorageBackend},
+};
+use tracing::{
+    level_filters::LevelFilter,
+    span::{Attributes, Id, Record},
+    Event, Level, Subscriber,
 };
-use tracing::{level_filters::LevelFilter, Event, Level, Subscriber};
 use tracing_subscriber::{
     filter::Targets,
     fmt::format::{DefaultVisitor, Writer},
     layer::Layer,
     prelude::*,
+    registry::LookupSpan,
     util::SubscriberInitExt,
-    EnvFilter,
+    EnvFilter, Registry,
 };
+use yuv_indexers::Checkpoint;
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
 
 pub async fn run(args: arguments::Run) -> eyre::Result<()> {
     let config = NodeConfig::from_path(args.config)?;
@@ -30,7 +41,8 @@ pub async fn run(args: arguments::Run) -> eyre::Result<()> {
         .with_target("yuv_tx_attach", level_filter)
         .with_target("yuv_tx_check", level_filter)
         .with_target("yuv_p2p", level_filter)
-        .with_default(level_filter);
+        .with_default(level_filter)
+        .with_targets(config.logger.targets.clone());
 
     // Disable `hyper_util` logs emitting from the `jsonrpc` crate.
     let env_filter = EnvFilter::builder()
@@ -39,11 +51,26 @@ pub async fn run(args: arguments::Run) -> eyre::Result<()> {
         .add_directive("hyper_util=info".parse()?)
         .add_directive("hyper=info".parse()?);
 
+    let logger = match config.logger.format {
+        LogFormat::Text => YuvTracer.with_filter(filter.clone()).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_filter(filter.clone())
+            .boxed(),
+    };
+
     tracing_subscriber::registry()
-        .with(YuvTracer.with_filter(filter))
+        .with(telemetry_layer(&config, filter)?)
+        .with(logger)
         .with(env_filter)
         .try_init()?;
 
+    #[cfg(not(feature = "otel"))]
+    if config.telemetry.is_some() {
+        tracing::warn!("The node is built without the `otel` feature, the traces are not exported");
+    }
+
     // Start all main components, but do not start external services
     // like RPC, p2p until indexer will be initialized.
 
@@ -76,16 +103,288 @@ pub async fn run(args: arguments::Run) -> eyre::Result<()> {
 
     node.shutdown().await;
 
+    #[cfg(feature = "otel")]
+    telemetry::shutdown();
+
+    Ok(())
+}
+
+/// Returns the layer that exports the spans to the OTLP collector, `None` if the export isn't
+/// configured.
+#[cfg(feature = "otel")]
+fn telemetry_layer(
+    config: &NodeConfig,
+    filter: Targets,
+) -> eyre::Result<Option<Box<dyn Layer<Registry> + Send + Sync>>> {
+    let Some(telemetry) = &config.telemetry else {
+        return Ok(None);
+    };
+
+    let tracer = telemetry::init_tracer(telemetry)?;
+
+    Ok(Some(
+        tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(filter)
+            .boxed(),
+    ))
+}
+
+#[cfg(not(feature = "otel"))]
+fn telemetry_layer(
+    _config: &NodeConfig,
+    _filter: Targets,
+) -> eyre::Result<Option<Box<dyn Layer<Registry> + Send + Sync>>> {
+    Ok(None)
+}
+
+/// Rewrites the pages of the node's storage in the canonical order. The node must be stopped.
+pub async fn repair_pages(args: arguments::RepairPages) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config)?;
+
+    let (txs_number, pages_number) = repair::repair_pages(&config).await?;
+
+    println!("Pages are repaired: {txs_number} transactions in {pages_number} pages");
+
+    Ok(())
+}
+
+/// Exports the checkpoint of the node's storage signed with the given key. The node must be
+/// stopped.
+pub async fn export_checkpoint(args: arguments::ExportCheckpoint) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config)?;
+
+    let (txs_storage, state_storage) = Node::init_storage(config.storage).await?;
+
+    let checkpoint = Checkpoint::export(&txs_storage, &state_storage).await?;
+    let signed_checkpoint = checkpoint.sign(&args.private_key)?;
+    signed_checkpoint.write(&args.output)?;
+
+    println!(
+        "Checkpoint at block {} ({}) is exported: {} transactions, hash {}",
+        checkpoint.block_height,
+        checkpoint.block_hash,
+        checkpoint.txs.len(),
+        signed_checkpoint.hash(),
+    );
+
+    Ok(())
+}
+
+/// Exports the state of the node's storage for the bootstrap of the new nodes. The node must be
+/// stopped.
+pub async fn export_state(args: arguments::ExportState) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config)?;
+
+    let summary = state::export_state(&config, &args.file).await?;
+
+    println!(
+        "State at block {} ({}) is exported: {} transactions, hash {}",
+        summary.block_height, summary.block_hash, summary.txs_number, summary.txs_hash,
+    );
+
+    Ok(())
+}
+
+/// Verifies and imports the state exported by another node. The node must be stopped and its
+/// storage must be empty.
+pub async fn import_state(args: arguments::ImportState) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config)?;
+
+    let summary = state::import_state(&config, &args.file).await?;
+
+    println!(
+        "State at block {} ({}) is imported: {} transactions, hash {}",
+        summary.block_height, summary.block_hash, summary.txs_number, summary.txs_hash,
+    );
+
+    Ok(())
+}
+
+/// Creates the snapshot of the running node's storage through its RPC. The snapshot is created
+/// by the node, so the directory is on the node's host.
+pub async fn create_snapshot(args: arguments::SnapshotCreate) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config)?;
+
+    // The node's working directory may differ, so the relative paths are resolved here.
+    let dir = if args.dir.is_absolute() {
+        args.dir
+    } else {
+        std::env::current_dir()?.join(args.dir)
+    };
+    let Some(dir) = dir.to_str() else {
+        bail!("snapshot directory {dir:?} is not valid UTF-8");
+    };
+
+    let client = HttpClientBuilder::new()
+        .build(format!("http://{}", config.rpc.address))
+        .wrap_err("failed to create the node's RPC client")?;
+
+    let snapshot = client
+        .create_snapshot(dir.to_string())
+        .await
+        .wrap_err("failed to create the snapshot")?;
+
+    println!(
+        "Snapshot at block {} ({}) is created in {}",
+        snapshot.block_height, snapshot.block_hash, snapshot.dir,
+    );
+
+    Ok(())
+}
+
+/// Restores the snapshot to the node's storage. The node must be stopped and its storage must be
+/// empty. The restored storage is validated on the next start of the node.
+pub async fn restore_snapshot(args: arguments::SnapshotRestore) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config)?;
+
+    if config.storage.backend != StorageBackend::LevelDB {
+        bail!("snapshots are supported by the LevelDB storage only");
+    }
+
+    let metadata = yuv_storage::restore_snapshot(&args.dir, &config.storage.path)?;
+
+    println!(
+        "Snapshot at block {} ({}) is restored to {}",
+        metadata.block_height,
+        metadata.block_hash,
+        config.storage.path.display(),
+    );
+
+    Ok(())
+}
+
+/// Replays the blocks of the Bitcoin node through the pipeline with the temporary storage, and
+/// prints the steps and the differences with the node's state. The node must be stopped.
+pub async fn replay(args: arguments::Replay) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config)?;
+
+    // The services' logs are printed along with the steps, without the telemetry.
+    let filter = Targets::new()
+        .with_default(config.logger.level)
+        .with_targets(config.logger.targets.clone());
+    tracing_subscriber::registry()
+        .with(YuvTracer.with_filter(filter))
+        .try_init()?;
+
+    let summary = replay::replay(&config, args.from_height, args.to_height).await?;
+
+    println!(
+        "Replayed {} blocks in {} steps: {} transactions are attached",
+        summary.blocks_number, summary.steps_number, summary.attached_number,
+    );
+
+    if summary.differences.is_empty() {
+        println!("State matches the node's one");
+        return Ok(());
+    }
+
+    for difference in &summary.differences {
+        println!("{difference}");
+    }
+
+    bail!(
+        "{} differences with the node's state",
+        summary.differences.len()
+    )
+}
+
+/// Encrypts the P2P private key from the config to the keystore, and replaces the key in
+/// the config with the path to the keystore. The rest of the config is kept as is.
+pub fn encrypt_key(args: arguments::EncryptKey) -> eyre::Result<()> {
+    let config = NodeConfig::from_path(args.config.clone())?;
+
+    let Some(auth) = config.p2p.auth else {
+        bail!("p2p.auth.private_key is not set in the config");
+    };
+    if auth.keystore.is_some() {
+        bail!("p2p.auth.keystore is already set in the config");
+    }
+    let Some(private_key) = auth.private_key else {
+        bail!("p2p.auth.private_key is not set in the config");
+    };
+
+    if args.output.exists() {
+        bail!("{} already exists", args.output.display());
+    }
+
+    let mut document = std::fs::read_to_string(&args.config)?
+        .parse::<toml_edit::DocumentMut>()
+        .wrap_err("Only the TOML configs can be rewritten")?;
+    let auth = document
+        .get_mut("p2p")
+        .and_then(|p2p| p2p.get_mut("auth"))
+        .and_then(toml_edit::Item::as_table_like_mut)
+        .ok_or_eyre("The [p2p.auth] section is not found in the config")?;
+    auth.remove("private_key");
+    auth.insert(
+        "keystore",
+        toml_edit::value(args.output.display().to_string()),
+    );
+
+    let passphrase = yuv_keystore::new_passphrase()?;
+    yuv_keystore::save(
+        &args.output,
+        &yuv_keystore::Secrets::new(private_key),
+        &passphrase,
+    )?;
+    std::fs::write(&args.config, document.to_string()).wrap_err("Failed to rewrite the config")?;
+
+    println!(
+        "The key is encrypted to {}, and replaced with the keystore in {}",
+        args.output.display(),
+        args.config.display(),
+    );
+
     Ok(())
 }
 
 struct YuvTracer;
 
+/// Formatted fields of the span, e.g. `txid` of the handled transaction, that are printed with
+/// the span's events.
+struct SpanFields(String);
+
 impl<S> Layer<S> for YuvTracer
 where
-    S: Subscriber,
+    S: Subscriber + for<'a> LookupSpan<'a>,
 {
-    fn on_event(&self, event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+    fn on_new_span(
+        &self,
+        attrs: &Attributes<'_>,
+        id: &Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut fields = String::new();
+        attrs.record(&mut DefaultVisitor::new(Writer::new(&mut fields), true));
+
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(
+        &self,
+        id: &Id,
+        values: &Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut extensions = span.extensions_mut();
+        let Some(SpanFields(fields)) = extensions.get_mut::<SpanFields>() else {
+            return;
+        };
+
+        let is_empty = fields.is_empty();
+        values.record(&mut DefaultVisitor::new(Writer::new(fields), is_empty));
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: tracing_subscriber::layer::Context<'_, S>) {
         let target = match event.metadata().level() {
             &Level::INFO | &Level::WARN | &Level::ERROR => event
                 .metadata()
@@ -103,6 +402,18 @@ where
             target,
         );
 
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                let fields = extensions
+                    .get::<SpanFields>()
+                    .map(|SpanFields(fields)| fields.as_str())
+                    .unwrap_or_default();
+
+                print!("{}{{{}}}: ", span.name(), fields);
+            }
+        }
+
         let mut message = String::new();
 
         event.record(&mut DefaultVisitor::new(Writer::new(&mut message), true));
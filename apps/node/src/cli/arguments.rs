@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
-use clap::Args;
+use bitcoin::secp256k1::SecretKey;
+use clap::{Args, Subcommand};
 
 #[derive(Args, Debug, Clone)]
 pub struct Run {
@@ -8,3 +9,90 @@ pub struct Run {
     #[clap(short, long, default_value = "config.toml")]
     pub config: PathBuf,
 }
+
+#[derive(Args, Debug, Clone)]
+pub struct RepairPages {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportCheckpoint {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Path to the checkpoint file to create
+    #[clap(short, long)]
+    pub output: PathBuf,
+    /// Hex encoded private key to sign the checkpoint with
+    #[clap(long)]
+    pub private_key: SecretKey,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportState {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Path to the state file to create
+    pub file: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ImportState {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Path to the state file exported by another node
+    pub file: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct Replay {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Height of the first block to replay
+    #[clap(long)]
+    pub from_height: usize,
+    /// Height of the last block to replay
+    #[clap(long)]
+    pub to_height: usize,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct EncryptKey {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Path to the keystore file to create
+    #[clap(short, long)]
+    pub output: PathBuf,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Snapshot {
+    /// Create the snapshot of the running node's storage through its RPC
+    Create(SnapshotCreate),
+    /// Restore the snapshot to the empty storage of the stopped node
+    Restore(SnapshotRestore),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SnapshotCreate {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Directory of the snapshot to create on the node's host, must not exist
+    pub dir: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SnapshotRestore {
+    /// Path to config file
+    #[clap(short, long, default_value = "config.toml")]
+    pub config: PathBuf,
+    /// Directory of the snapshot created by `snapshot create`
+    pub dir: PathBuf,
+}
@@ -1,6 +1,12 @@
 mod actions;
 mod arguments;
 mod node;
+pub use node::Node;
+mod repair;
+mod replay;
+mod state;
+#[cfg(feature = "otel")]
+mod telemetry;
 use clap::Parser;
 
 #[derive(Parser)]
@@ -8,12 +14,46 @@ use clap::Parser;
 pub enum Cli {
     /// Run p2p node, see `node --help` for more information
     Run(arguments::Run),
+    /// Rewrite the pages of the stopped node in the canonical order, see
+    /// `node repair-pages --help` for more information
+    RepairPages(arguments::RepairPages),
+    /// Export the signed checkpoint of the stopped node's state for the fast sync, see
+    /// `node export-checkpoint --help` for more information
+    ExportCheckpoint(arguments::ExportCheckpoint),
+    /// Export the attached transactions, frozen outputs and chromas of the stopped node for the
+    /// bootstrap of the new nodes, see `node export-state --help` for more information
+    ExportState(arguments::ExportState),
+    /// Verify and import the state exported by another node to the empty storage, see
+    /// `node import-state --help` for more information
+    ImportState(arguments::ImportState),
+    /// Create or restore the snapshots of the node's storage, see `node snapshot --help` for
+    /// more information
+    #[clap(subcommand)]
+    Snapshot(arguments::Snapshot),
+    /// Replay the blocks with the temporary storage step by step and compare the result with the
+    /// stopped node's state, see `node replay --help` for more information
+    Replay(arguments::Replay),
+    /// Move the P2P private key from the config to the passphrase-encrypted keystore, see
+    /// `node encrypt-key --help` for more information
+    EncryptKey(arguments::EncryptKey),
 }
 
 impl Cli {
     pub async fn exec(self) -> eyre::Result<()> {
         match self {
             Self::Run(args) => actions::run(args).await,
+            Self::RepairPages(args) => actions::repair_pages(args).await,
+            Self::ExportCheckpoint(args) => actions::export_checkpoint(args).await,
+            Self::ExportState(args) => actions::export_state(args).await,
+            Self::ImportState(args) => actions::import_state(args).await,
+            Self::Snapshot(arguments::Snapshot::Create(args)) => {
+                actions::create_snapshot(args).await
+            }
+            Self::Snapshot(arguments::Snapshot::Restore(args)) => {
+                actions::restore_snapshot(args).await
+            }
+            Self::Replay(args) => actions::replay(args).await,
+            Self::EncryptKey(args) => actions::encrypt_key(args),
         }
     }
 }
@@ -1,33 +1,55 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::config::{NodeConfig, StorageConfig};
+use crate::config::{
+    BrokerConfig, NodeConfig, P2pTransport, StorageBackend, StorageConfig,
+    BITCOIN_HEALTH_CHECK_JOB, DEFAULT_BITCOIN_HEALTH_CHECK_INTERVAL,
+    DEFAULT_GRAPH_BUILDER_CLEAN_UP_INTERVAL, DEFAULT_REVALIDATION_INTERVAL,
+    DEFAULT_STORAGE_PRUNING_INTERVAL, GRAPH_BUILDER_CLEAN_UP_JOB, REVALIDATION_JOB,
+    STORAGE_PRUNING_JOB, TX_CONFIRMATOR_CLEAN_UP_JOB,
+};
 use bitcoin_client::BitcoinRpcClient;
-use event_bus::EventBus;
-use eyre::{Context, Ok};
+use event_bus::{BusEvent, ChannelPolicy, Completion, EventBus, OverflowPolicy, SpillToDisk};
+use eyre::{eyre, Context, Ok};
 use tokio::select;
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::TaskTracker;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use yuv_controller::Controller;
-use yuv_indexers::{AnnouncementsIndexer, BitcoinBlockIndexer, ConfirmationIndexer, RunParams};
+use yuv_event_sink::{EventSink, Publisher};
+use yuv_indexers::{
+    import_checkpoint, AnnouncementsIndexer, BitcoinBlockIndexer, CheckpointParams,
+    ConfirmationIndexer, IndexingParams, RunParams,
+};
 
 use yuv_p2p::{
     client::{Handle, P2PClient},
-    net::{ReactorTcp, Waker},
+    net::{NetReactor, NetWaker, ReactorQuic, ReactorTcp},
+};
+use yuv_rpc_server::{
+    subscriptions::{SubscriptionsController, DEFAULT_SUBSCRIPTION_CAPACITY},
+    ServerConfig,
+};
+use yuv_scheduler::{Job, JobStatuses, Scheduler};
+use yuv_storage::{
+    backfill_tx_dependents, check_pages, migrate_txs_encoding, validate_restored_snapshot,
+    AnyStorage, AttachStorage, FlushStrategy, KeyValueStorage, LevelDB, LevelDbOptions, Pruner,
+    Snapshotter, TransactionsStorage, TxsEncoding, STATE_STORAGE_DIR, TXS_STORAGE_DIR,
 };
-use yuv_rpc_server::ServerConfig;
-use yuv_storage::{FlushStrategy, LevelDB, LevelDbOptions};
 use yuv_tx_attach::GraphBuilder;
-use yuv_tx_check::TxChecker;
+use yuv_tx_check::{Revalidator, TxChecker};
 use yuv_tx_confirm::TxConfirmator;
 use yuv_types::{
-    ControllerMessage, GraphBuilderMessage, IndexerMessage, TxCheckerMessage, TxConfirmMessage,
+    ControllerMessage, GraphBuilderMessage, IndexerMessage, RevalidatorMessage,
+    RpcSubscriptionMessage, TxCheckerMessage, TxConfirmMessage,
 };
+use yuv_webhooks::WebhookDispatcher;
 
 /// Default size of the channel for the event bus.
 const DEFAULT_CHANNEL_SIZE: usize = 1000;
+/// Directory in the storage directory for the events that overflowed their channels.
+const SPILL_DIR: &str = "spill";
 /// The limit of time to wait for the node to shutdown.
 const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
 
@@ -35,8 +57,8 @@ const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
 pub struct Node {
     config: NodeConfig,
     event_bus: EventBus,
-    txs_storage: LevelDB,
-    state_storage: LevelDB,
+    txs_storage: AnyStorage,
+    state_storage: AnyStorage,
     btc_client: Arc<BitcoinRpcClient>,
 
     cancelation: CancellationToken,
@@ -45,17 +67,24 @@ pub struct Node {
 
 impl Node {
     pub async fn new(config: NodeConfig) -> eyre::Result<Self> {
-        let event_bus = Self::init_event_bus();
-        let (txs_storage, state_storage) = Self::init_storage(config.storage.clone())?;
-
-        let btc_client = Arc::new(
-            BitcoinRpcClient::new(
-                config.bnode.auth().clone(),
-                config.bnode.url.clone(),
-                config.bnode.timeout,
-            )
-            .await?,
-        );
+        let event_bus = Self::init_event_bus(&config)?;
+        let (txs_storage, state_storage) = Self::init_storage(config.storage.clone()).await?;
+
+        if config.storage.backend == StorageBackend::LevelDB {
+            if let Some(snapshot) = validate_restored_snapshot(&config.storage.path, &state_storage)
+                .await
+                .wrap_err("failed to validate the restored snapshot")?
+            {
+                info!(
+                    block_height = snapshot.block_height,
+                    "Storage is restored from the snapshot at block {}", snapshot.block_hash
+                );
+            }
+        }
+
+        Self::check_storage(&txs_storage).await?;
+
+        let btc_client = Arc::new(config.bnode.client().await?);
 
         Ok(Self {
             config,
@@ -79,23 +108,44 @@ impl Node {
         self.spawn_graph_builder();
         self.spawn_tx_checker()?;
         self.spawn_tx_confirmator();
+        self.spawn_revalidator();
         self.spawn_indexer().await?;
 
-        let p2p_handle = self.spawn_p2p()?;
-        self.spawn_controller(p2p_handle).await?;
+        match self.config.p2p.transport() {
+            P2pTransport::Tcp => {
+                let p2p_handle = self.spawn_p2p::<ReactorTcp>().await?;
+                self.spawn_controller(p2p_handle).await?;
+            }
+            P2pTransport::Quic => {
+                let p2p_handle = self.spawn_p2p::<ReactorQuic>().await?;
+                self.spawn_controller(p2p_handle).await?;
+            }
+        }
+
+        let job_statuses = self.spawn_scheduler()?;
 
-        self.spawn_rpc();
+        // The subscriptions are shared by the RPC servers and the services that forward the
+        // events, as each event is received from the event bus only once.
+        let subscriptions = SubscriptionsController::new(DEFAULT_SUBSCRIPTION_CAPACITY);
+        self.spawn_webhooks(&subscriptions)?;
+        self.spawn_event_sink(&subscriptions).await?;
+        self.spawn_rpc(job_statuses, subscriptions);
 
         self.task_tracker.close();
 
         Ok(())
     }
 
-    fn spawn_p2p(&self) -> eyre::Result<Handle<Waker>> {
-        let p2p_client_runner = P2PClient::<ReactorTcp>::new(
+    async fn spawn_p2p<R>(&self) -> eyre::Result<Handle<R::Waker>>
+    where
+        R: NetReactor + Send + 'static,
+    {
+        let p2p_client_runner = P2PClient::<R, AnyStorage>::new(
             self.config.p2p.to_client_config(self.config.network)?,
             &self.event_bus,
+            self.state_storage.clone(),
         )
+        .await
         .expect("P2P client must be successfully created");
 
         let handle = p2p_client_runner.handle();
@@ -106,7 +156,7 @@ impl Node {
         Ok(handle)
     }
 
-    async fn spawn_controller(&self, handle: Handle<Waker>) -> eyre::Result<()> {
+    async fn spawn_controller<W: NetWaker + 'static>(&self, handle: Handle<W>) -> eyre::Result<()> {
         let mut controller = Controller::new(
             &self.event_bus,
             self.txs_storage.clone(),
@@ -117,8 +167,14 @@ impl Node {
         .set_inv_sharing_interval(Duration::from_secs(
             self.config.controller.inv_sharing_interval,
         ))
-        .set_max_inv_size(self.config.controller.max_inv_size);
-
+        .set_max_inv_size(self.config.controller.max_inv_size)
+        .set_zero_conf_max_amount(self.config.controller.zero_conf_max_amount)
+        .set_address_index(self.config.controller.address_index)
+        .set_history_index(self.config.controller.history_index)
+        .set_pruning(self.config.storage.pruning.is_some());
+
+        controller.recover_attached_txs().await?;
+        controller.build_address_index().await?;
         controller.handle_mempool_txs().await?;
 
         self.task_tracker
@@ -128,7 +184,8 @@ impl Node {
     }
 
     fn spawn_graph_builder(&self) {
-        let graph_builder = GraphBuilder::new(self.txs_storage.clone(), &self.event_bus);
+        let graph_builder = GraphBuilder::new(self.txs_storage.clone(), &self.event_bus)
+            .with_limits((&self.config.graph_builder).into());
 
         self.task_tracker
             .spawn(graph_builder.run(self.cancelation.clone()));
@@ -139,7 +196,9 @@ impl Node {
             self.event_bus.clone(),
             self.txs_storage.clone(),
             self.state_storage.clone(),
-        );
+            self.config.network,
+        )
+        .with_dust_policy(self.config.dust.to_policy(self.config.network));
 
         self.task_tracker
             .spawn(tx_checker.run(self.cancelation.clone()));
@@ -152,33 +211,290 @@ impl Node {
             &self.event_bus,
             self.btc_client.clone(),
             self.config.indexer.max_confirmation_time,
-            self.config.indexer.clean_up_interval,
             self.config.indexer.confirmations_number,
-        );
+        )
+        .with_confirmation_policy(self.config.indexer.confirmation_policy())
+        .with_accept_unconfirmed(self.config.controller.zero_conf_max_amount.is_some());
 
         self.task_tracker
             .spawn(tx_confirmator.run(self.cancelation.clone()));
     }
 
-    fn spawn_rpc(&self) {
+    fn spawn_revalidator(&self) {
+        let revalidator = Revalidator::new(
+            &self.event_bus,
+            self.txs_storage.clone(),
+            self.state_storage.clone(),
+            self.btc_client.clone(),
+        );
+
+        self.task_tracker
+            .spawn(revalidator.run(self.cancelation.clone()));
+    }
+
+    fn spawn_scheduler(&self) -> eyre::Result<JobStatuses> {
+        let config = &self.config.scheduler;
+
+        let known_jobs = [
+            GRAPH_BUILDER_CLEAN_UP_JOB,
+            TX_CONFIRMATOR_CLEAN_UP_JOB,
+            STORAGE_PRUNING_JOB,
+            BITCOIN_HEALTH_CHECK_JOB,
+            REVALIDATION_JOB,
+        ];
+        for job in config.jobs.keys() {
+            if !known_jobs.contains(&job.as_str()) {
+                warn!("Unknown job {job} in the scheduler config is ignored");
+            }
+        }
+
+        let mut scheduler = Scheduler::default();
+        scheduler
+            .add_job(
+                GRAPH_BUILDER_CLEAN_UP_JOB,
+                config.schedule(
+                    GRAPH_BUILDER_CLEAN_UP_JOB,
+                    DEFAULT_GRAPH_BUILDER_CLEAN_UP_INTERVAL,
+                ),
+                send_event_job(self.event_bus.clone(), GraphBuilderMessage::CleanUp),
+            )?
+            .add_job(
+                TX_CONFIRMATOR_CLEAN_UP_JOB,
+                config.schedule(
+                    TX_CONFIRMATOR_CLEAN_UP_JOB,
+                    self.config.indexer.clean_up_interval,
+                ),
+                send_event_job(self.event_bus.clone(), TxConfirmMessage::CleanUp),
+            )?
+            .add_job(
+                REVALIDATION_JOB,
+                config.schedule(REVALIDATION_JOB, DEFAULT_REVALIDATION_INTERVAL),
+                send_event_job(self.event_bus.clone(), RevalidatorMessage::Run),
+            )?;
+
+        if let Some(pruning) = &self.config.storage.pruning {
+            let pruner = Pruner::new(
+                self.txs_storage.clone(),
+                self.state_storage.clone(),
+                pruning.retention_blocks,
+            );
+
+            scheduler.add_job(
+                STORAGE_PRUNING_JOB,
+                config.schedule(STORAGE_PRUNING_JOB, DEFAULT_STORAGE_PRUNING_INTERVAL),
+                pruning_job(pruner),
+            )?;
+        }
+
+        if !self.config.bnode.fallbacks.is_empty() {
+            scheduler.add_job(
+                BITCOIN_HEALTH_CHECK_JOB,
+                config.schedule(
+                    BITCOIN_HEALTH_CHECK_JOB,
+                    DEFAULT_BITCOIN_HEALTH_CHECK_INTERVAL,
+                ),
+                bitcoin_health_check_job(self.btc_client.clone()),
+            )?;
+        }
+
+        let job_statuses = scheduler.statuses();
+
+        self.task_tracker
+            .spawn(scheduler.run(self.cancelation.clone()));
+
+        Ok(job_statuses)
+    }
+
+    fn spawn_rpc(&self, job_statuses: JobStatuses, subscriptions: SubscriptionsController) {
         let address = self.config.rpc.address.to_string();
         let max_items_per_request = self.config.rpc.max_items_per_request;
         let max_request_size_kb = self.config.rpc.max_request_size_kb;
+        let max_response_size_kb = self.config.rpc.max_response_size_kb;
+        let max_batch_size = self.config.rpc.max_batch_size;
+        let max_bundle_parents = self.config.rpc.max_bundle_parents;
+        let max_bundle_size = self.config.rpc.max_bundle_size;
+
+        self.task_tracker.spawn(
+            subscriptions
+                .clone()
+                .run(self.event_bus.clone(), self.cancelation.clone()),
+        );
+
+        self.spawn_grpc(&subscriptions);
+        self.spawn_rest();
 
         self.task_tracker.spawn(yuv_rpc_server::run_server(
             ServerConfig {
                 address,
                 max_items_per_request,
                 max_request_size_kb,
+                max_response_size_kb,
+                max_batch_size,
+                max_bundle_parents,
+                max_bundle_size,
+                network: self.config.network,
+                address_index: self.config.controller.address_index,
+                history_index: self.config.controller.history_index,
+                auth: self.config.rpc.auth.as_ref().map(Into::into),
+                tls: self.config.rpc.tls.as_ref().map(Into::into),
+                cors_allowed_origins: self.config.rpc.cors_allowed_origins.clone(),
+                rate_limit: self.config.rpc.rate_limit.as_ref().map(Into::into),
+                request_timeout: self.config.rpc.request_timeout(),
+                max_connections: self.config.rpc.max_connections,
+                max_batch_requests: self.config.rpc.max_batch_requests,
             },
             self.txs_storage.clone(),
             self.state_storage.clone(),
             self.event_bus.clone(),
             self.btc_client.clone(),
+            job_statuses,
+            self.snapshotter(),
+            subscriptions,
             self.cancelation.clone(),
         ));
     }
 
+    /// Spawns the dispatcher of the webhooks, which receives the events from the subscriptions,
+    /// so it's spawned before they start not to miss the first events.
+    fn spawn_webhooks(&self, subscriptions: &SubscriptionsController) -> eyre::Result<()> {
+        let Some(config) = &self.config.webhooks else {
+            return Ok(());
+        };
+
+        if config.endpoints.is_empty() {
+            warn!("No webhook endpoints in the config, the webhooks are not started");
+            return Ok(());
+        }
+
+        let dispatcher = WebhookDispatcher::new(
+            config.endpoints.clone(),
+            self.txs_storage.clone(),
+            self.state_storage.clone(),
+        )
+        .with_max_attempts(config.max_attempts)
+        .with_max_queue_size(config.max_queue_size)
+        .with_timeout(Duration::from_secs(config.timeout_secs))?;
+
+        self.task_tracker
+            .spawn(dispatcher.run(subscriptions.events(), self.cancelation.clone()));
+
+        Ok(())
+    }
+
+    /// Spawns the forwarding of the events to the broker, which receives the events from the
+    /// subscriptions the same way as the webhooks.
+    async fn spawn_event_sink(&self, subscriptions: &SubscriptionsController) -> eyre::Result<()> {
+        let Some(config) = &self.config.event_sink else {
+            return Ok(());
+        };
+
+        if config.topics.is_empty() {
+            warn!("No topics in the event sink config, the events are not forwarded");
+            return Ok(());
+        }
+
+        let publisher = Self::init_publisher(&config.broker).await?;
+        let event_sink =
+            EventSink::new(publisher, config.topics.clone(), self.state_storage.clone())
+                .with_max_queue_size(config.max_queue_size);
+
+        self.task_tracker
+            .spawn(event_sink.run(subscriptions.events(), self.cancelation.clone()));
+
+        Ok(())
+    }
+
+    async fn init_publisher(config: &BrokerConfig) -> eyre::Result<Box<dyn Publisher>> {
+        match config {
+            #[cfg(feature = "kafka")]
+            BrokerConfig::Kafka {
+                brokers,
+                timeout_secs,
+            } => Ok(Box::new(yuv_event_sink::KafkaPublisher::new(
+                brokers,
+                Duration::from_secs(*timeout_secs),
+            )?)),
+            #[cfg(not(feature = "kafka"))]
+            BrokerConfig::Kafka { .. } => {
+                eyre::bail!("the node is built without the `kafka` feature")
+            }
+            #[cfg(feature = "nats")]
+            BrokerConfig::Nats { url } => {
+                Ok(Box::new(yuv_event_sink::NatsPublisher::connect(url).await?))
+            }
+            #[cfg(not(feature = "nats"))]
+            BrokerConfig::Nats { .. } => {
+                eyre::bail!("the node is built without the `nats` feature")
+            }
+        }
+    }
+
+    /// Returns the snapshotter of the storages, `None` if they aren't LevelDB.
+    fn snapshotter(&self) -> Option<Snapshotter> {
+        let txs_storage = self.txs_storage.as_leveldb()?;
+        let state_storage = self.state_storage.as_leveldb()?;
+
+        Some(Snapshotter::new(txs_storage.clone(), state_storage.clone()))
+    }
+
+    #[cfg(feature = "rest")]
+    fn spawn_rest(&self) {
+        let Some(address) = self.config.rpc.rest_address else {
+            return;
+        };
+
+        self.task_tracker
+            .spawn(yuv_rpc_server::rest::run_rest_server(
+                yuv_rpc_server::rest::RestConfig {
+                    address,
+                    max_items_per_request: self.config.rpc.max_items_per_request,
+                    max_response_size_kb: self.config.rpc.max_response_size_kb,
+                    network: self.config.network,
+                    address_index: self.config.controller.address_index,
+                },
+                self.txs_storage.clone(),
+                self.state_storage.clone(),
+                self.event_bus.clone(),
+                self.btc_client.clone(),
+                self.cancelation.clone(),
+            ));
+    }
+
+    #[cfg(not(feature = "rest"))]
+    fn spawn_rest(&self) {
+        if self.config.rpc.rest_address.is_some() {
+            warn!("The node is built without the `rest` feature, the REST gateway is not started");
+        }
+    }
+
+    #[cfg(feature = "grpc")]
+    fn spawn_grpc(&self, subscriptions: &SubscriptionsController) {
+        let Some(grpc_config) = &self.config.grpc else {
+            return;
+        };
+
+        self.task_tracker.spawn(yuv_grpc_server::run_server(
+            yuv_grpc_server::GrpcServerConfig {
+                address: grpc_config.address,
+                max_items_per_request: self.config.rpc.max_items_per_request,
+                network: self.config.network,
+            },
+            self.txs_storage.clone(),
+            self.state_storage.clone(),
+            self.event_bus.clone(),
+            self.btc_client.clone(),
+            subscriptions.clone(),
+            self.cancelation.clone(),
+        ));
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    fn spawn_grpc(&self, _subscriptions: &SubscriptionsController) {
+        if self.config.grpc.is_some() {
+            warn!("The node is built without the `grpc` feature, the gRPC server is not started");
+        }
+    }
+
     async fn spawn_indexer(&self) -> eyre::Result<()> {
         let mut indexer = BitcoinBlockIndexer::new(
             self.btc_client.clone(),
@@ -187,20 +503,41 @@ impl Node {
             self.config.network,
         );
 
-        indexer.add_subindexer(AnnouncementsIndexer::new(
-            &self.event_bus,
-            self.config.network,
-        ));
+        indexer.add_subindexer(
+            AnnouncementsIndexer::new(&self.event_bus, self.config.network)
+                .with_revalidation(&self.event_bus),
+        );
         indexer.add_subindexer(ConfirmationIndexer::new(&self.event_bus));
 
+        let params: IndexingParams = self.config.indexer.clone().into();
+        if let Some(checkpoint) = &params.checkpoint {
+            let checkpoint = CheckpointParams {
+                address_index: self.config.controller.address_index,
+                history_index: self.config.controller.history_index,
+                ..checkpoint.clone()
+            };
+            import_checkpoint(
+                &checkpoint,
+                self.btc_client.as_ref(),
+                &self.txs_storage,
+                &self.state_storage,
+                self.config.storage.tx_per_page,
+            )
+            .await
+            .wrap_err("failed to import the checkpoint")?;
+        }
+
         let restart_interval = self.config.indexer.restart_interval;
         let mut current_attempt = 1;
         while let Err(err) = indexer
             .init(
-                self.config.indexer.clone().into(),
+                params.clone(),
                 self.config.indexer.blockloader.clone(),
                 self.btc_client.clone(),
-                self.config.indexer.confirmations_number as usize,
+                self.config
+                    .indexer
+                    .confirmation_policy()
+                    .max_confirmations() as usize,
                 self.cancelation.clone(),
             )
             .await
@@ -228,7 +565,36 @@ impl Node {
         Ok(())
     }
 
-    fn init_storage(config: StorageConfig) -> eyre::Result<(LevelDB, LevelDB)> {
+    pub(crate) async fn init_storage(
+        config: StorageConfig,
+    ) -> eyre::Result<(AnyStorage, AnyStorage)> {
+        let (txs_storage, state_storage) = match config.backend {
+            StorageBackend::LevelDB => Self::init_leveldb_storage(config)?,
+            StorageBackend::Postgres => Self::init_postgres_storage(config).await?,
+        };
+
+        let migrated = migrate_txs_encoding(&txs_storage, &state_storage)
+            .await
+            .wrap_err("failed to convert the stored transactions")?;
+        if migrated > 0 {
+            info!(
+                encoding = ?txs_storage.txs_encoding(),
+                "Converted {} stored transactions",
+                migrated
+            );
+        }
+
+        let backfilled = backfill_tx_dependents(&txs_storage)
+            .await
+            .wrap_err("failed to backfill the dependents of the transactions")?;
+        if backfilled > 0 {
+            info!("Recorded dependents of {} attached transfers", backfilled);
+        }
+
+        Ok((txs_storage, state_storage))
+    }
+
+    fn init_leveldb_storage(config: StorageConfig) -> eyre::Result<(AnyStorage, AnyStorage)> {
         // Create directory if it does not exist
         if !config.path.exists() {
             std::fs::create_dir_all(&config.path)
@@ -238,16 +604,18 @@ impl Node {
         // Initialize storage for transactions
         let opt = LevelDbOptions {
             create_if_missing: config.create_if_missing,
-            path: config.path.join("transactions"),
+            path: config.path.join(TXS_STORAGE_DIR),
             flush_strategy: FlushStrategy::Ticker {
                 period: config.flush_period,
             },
         };
-        let txs_storage = LevelDB::from_opts(opt).wrap_err("failed to initialize storage")?;
+        let txs_storage = LevelDB::from_opts(opt)
+            .wrap_err("failed to initialize storage")?
+            .with_txs_encoding(TxsEncoding::from_proofs_dedup(config.proofs_dedup));
 
         // Initialize storage for states
         let opt = LevelDbOptions {
-            path: config.path.join("state"),
+            path: config.path.join(STATE_STORAGE_DIR),
             create_if_missing: config.create_if_missing,
             flush_strategy: FlushStrategy::Ticker {
                 period: config.flush_period,
@@ -255,18 +623,106 @@ impl Node {
         };
         let state_storage = LevelDB::from_opts(opt).wrap_err("failed to initialize storage")?;
 
-        Ok((txs_storage, state_storage))
+        Ok((txs_storage.into(), state_storage.into()))
     }
 
-    fn init_event_bus() -> EventBus {
+    /// Both storages are the tables in the same database, and share the pool of connections.
+    #[cfg(feature = "postgres")]
+    async fn init_postgres_storage(
+        config: StorageConfig,
+    ) -> eyre::Result<(AnyStorage, AnyStorage)> {
+        use yuv_storage::{Postgres, PostgresOptions};
+
+        let Some(postgres) = config.postgres else {
+            eyre::bail!("`storage.postgres` section is required for the postgres backend");
+        };
+
+        let txs_storage = Postgres::from_opts(PostgresOptions {
+            url: postgres.url,
+            max_connections: postgres.max_connections,
+            table: "transactions".to_string(),
+        })
+        .await
+        .wrap_err("failed to initialize storage")?
+        .with_txs_encoding(TxsEncoding::from_proofs_dedup(config.proofs_dedup));
+
+        let state_storage = txs_storage
+            .with_table("state")
+            .await
+            .wrap_err("failed to initialize storage")?;
+
+        Ok((txs_storage.into(), state_storage.into()))
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    async fn init_postgres_storage(
+        _config: StorageConfig,
+    ) -> eyre::Result<(AnyStorage, AnyStorage)> {
+        eyre::bail!("the node is built without the `postgres` feature")
+    }
+
+    /// Checks and repairs the pages if the node wasn't stopped gracefully last time, and clears
+    /// the mark of the graceful stop until the next one.
+    async fn check_storage(txs_storage: &AnyStorage) -> eyre::Result<()> {
+        if !txs_storage.is_clean_shutdown().await? {
+            info!("Node wasn't stopped gracefully, checking the pages...");
+
+            let check = check_pages(txs_storage)
+                .await
+                .wrap_err("failed to check the pages")?;
+
+            if check.is_repaired() {
+                warn!(
+                    missing_txs = check.missing_txs,
+                    duplicate_txs = check.duplicate_txs,
+                    uncounted_pages = check.uncounted_pages,
+                    "Pages are repaired"
+                );
+            }
+        }
+
+        txs_storage.delete_clean_shutdown().await?;
+
+        Ok(())
+    }
+
+    /// Marks the storage as stopped gracefully, so the pages aren't checked on the next start,
+    /// and flushes the storages.
+    async fn mark_clean_shutdown(&self) -> eyre::Result<()> {
+        self.txs_storage.put_clean_shutdown().await?;
+
+        KeyValueStorage::<(), ()>::flush(&self.txs_storage).await?;
+        KeyValueStorage::<(), ()>::flush(&self.state_storage).await?;
+
+        Ok(())
+    }
+
+    /// Registers the channels of the services. The slow TxChecker spills its messages to disk
+    /// instead of stalling the controller and the indexer behind it, and the subscriptions drop
+    /// the oldest notifications if the subscribers can't keep up.
+    fn init_event_bus(config: &NodeConfig) -> eyre::Result<EventBus> {
         let mut event_bus = EventBus::default();
-        event_bus.register::<TxCheckerMessage>(Some(DEFAULT_CHANNEL_SIZE));
+
+        let spill = SpillToDisk::new::<TxCheckerMessage>(config.storage.path.join(SPILL_DIR));
+        event_bus
+            .register_with_policy::<TxCheckerMessage>(
+                ChannelPolicy::bounded(DEFAULT_CHANNEL_SIZE)
+                    .with_overflow(OverflowPolicy::SpillToDisk(spill)),
+            )
+            .map_err(|err| eyre!("failed to register the tx checker channel: {err}"))?;
         event_bus.register::<GraphBuilderMessage>(Some(DEFAULT_CHANNEL_SIZE));
         event_bus.register::<ControllerMessage>(Some(DEFAULT_CHANNEL_SIZE));
         event_bus.register::<TxConfirmMessage>(Some(DEFAULT_CHANNEL_SIZE));
         event_bus.register::<IndexerMessage>(Some(DEFAULT_CHANNEL_SIZE));
-
+        event_bus.register::<RevalidatorMessage>(Some(DEFAULT_CHANNEL_SIZE));
         event_bus
+            .register_with_policy::<RpcSubscriptionMessage>(
+                ChannelPolicy::bounded(DEFAULT_CHANNEL_SIZE)
+                    .with_overflow(OverflowPolicy::DropOldest),
+            )
+            .map_err(|err| eyre!("failed to register the subscriptions channel: {err}"))?;
+
+        Ok(event_bus)
     }
 
     pub async fn shutdown(&self) {
@@ -281,7 +737,11 @@ impl Node {
 
         select! {
             // Wait until all tasks are finished
-            _ = self.task_tracker.wait() => {},
+            _ = self.task_tracker.wait() => {
+                if let Err(err) = self.mark_clean_shutdown().await {
+                    error!("Failed to mark the graceful shutdown: {err:?}");
+                }
+            },
             // Or wait for and exit by timeout
             _ = sleep(Duration::from_secs(timeout)) => {
                 info!("Shutdown timeout reached, exiting...");
@@ -289,3 +749,61 @@ impl Node {
         }
     }
 }
+
+/// Returns the scheduled job that sends the event to the service that does the actual work, so
+/// the service's state is never accessed concurrently. The
+/// job waits for the service to complete the work, so the scheduler doesn't overlap the runs and
+/// reports their real duration and failures.
+fn send_event_job<E, F>(event_bus: EventBus, event: F) -> impl Job
+where
+    E: BusEvent + Clone + Sync + 'static,
+    F: Fn(Completion) -> E + Send + Sync + 'static,
+{
+    move || {
+        let event_bus = event_bus.clone();
+        let (completion, waiter) = Completion::new();
+        let event = event(completion);
+
+        async move {
+            event_bus
+                .try_send(event)
+                .await
+                .map_err(|err| eyre!("failed to send the event: {err}"))?;
+
+            waiter.wait().await.map_err(|err| eyre!(err))
+        }
+    }
+}
+
+/// Returns the scheduled job that prunes the spent transactions and compacts the storage.
+fn pruning_job(pruner: Pruner<AnyStorage, AnyStorage>) -> impl Job {
+    move || {
+        let pruner = pruner.clone();
+
+        async move {
+            let pruned = pruner.prune().await?;
+            if pruned > 0 {
+                info!(pruned, "Pruned the spent transactions");
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Returns the scheduled job that checks the bitcoin nodes and switches the client to the
+/// healthy one.
+fn bitcoin_health_check_job(btc_client: Arc<BitcoinRpcClient>) -> impl Job {
+    move || {
+        let btc_client = btc_client.clone();
+
+        async move {
+            let heights = btc_client.check_endpoints().await;
+            if heights.iter().all(Option::is_none) {
+                return Err(eyre!("all the bitcoin nodes are unavailable"));
+            }
+
+            Ok(())
+        }
+    }
+}
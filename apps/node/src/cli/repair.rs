@@ -0,0 +1,129 @@
+//! Repair of the pages written before the canonical ordering of the attached transactions.
+//!
+//! Nodes used to write the transactions to the pages in the order they were attached in, so
+//! the same pages could differ between the nodes. The repair reads all the paged transactions,
+//! finds their positions in the chain and rewrites the pages in the
+//! [canonical order](yuv_controller::canonical_order).
+use std::collections::{HashMap, HashSet};
+
+use bitcoin::{BlockHash, Txid};
+use bitcoin_client::{BitcoinRpcApi, BitcoinRpcClient};
+use eyre::eyre;
+use yuv_controller::canonical_order;
+use yuv_storage::{
+    AttachStorage, BlockIndexerStorage, KeyValueStorage, PagesNumberStorage, PagesStorage,
+    TransactionsStorage, TxPosition,
+};
+
+use crate::{cli::node::Node, config::NodeConfig};
+
+/// Rewrites the pages in the canonical order. Returns the number of the transactions and pages.
+pub async fn repair_pages(config: &NodeConfig) -> eyre::Result<(usize, u64)> {
+    let (txs_storage, state_storage) = Node::init_storage(config.storage.clone()).await?;
+
+    let btc_client = config.bnode.client().await?;
+
+    let last_page_num = txs_storage.get_pages_number().await?.unwrap_or_default();
+
+    let mut txs = Vec::new();
+    let mut positions = HashMap::new();
+    for page_num in 0..=last_page_num {
+        let page = txs_storage
+            .get_page_by_num(page_num)
+            .await?
+            .unwrap_or_default();
+
+        for txid in page {
+            let yuv_tx = txs_storage
+                .get_yuv_tx(&txid)
+                .await?
+                .ok_or_else(|| eyre!("Paged tx {} is not present in the storage", txid))?;
+
+            positions.insert(txid, txs_storage.get_tx_position(&txid).await?);
+            txs.push(yuv_tx);
+        }
+    }
+
+    let mut missing = positions
+        .iter()
+        .filter_map(|(txid, position)| position.is_none().then_some(*txid))
+        .collect::<HashSet<_>>();
+    if !missing.is_empty() {
+        let last_block_hash = match state_storage.get_last_indexed_hash().await? {
+            Some(block_hash) => block_hash,
+            None => btc_client.get_best_block_hash().await?,
+        };
+
+        let found = find_tx_positions(
+            &btc_client,
+            last_block_hash,
+            config.indexer.starting_block,
+            &mut missing,
+        )
+        .await?;
+
+        for (txid, position) in found {
+            txs_storage.put_tx_position(&txid, position).await?;
+            positions.insert(txid, Some(position));
+        }
+    }
+
+    let txids = canonical_order(
+        txs.iter()
+            .map(|yuv_tx| (&yuv_tx.bitcoin_tx, positions[&yuv_tx.bitcoin_tx.txid()])),
+    );
+
+    let tx_per_page = config.storage.tx_per_page.max(1) as usize;
+    let mut pages_number = 0;
+    for (page_num, page) in txids.chunks(tx_per_page).enumerate() {
+        txs_storage.put_page(page_num as u64, page.to_vec()).await?;
+        pages_number += 1;
+    }
+
+    // The pages left after the removal of the rolled back transactions are not needed anymore.
+    for page_num in pages_number..=last_page_num {
+        txs_storage.delete_page(page_num).await?;
+    }
+
+    txs_storage
+        .put_pages_number(pages_number.saturating_sub(1))
+        .await?;
+    KeyValueStorage::<Vec<u8>, Vec<u8>>::flush(&txs_storage).await?;
+
+    Ok((txids.len(), pages_number))
+}
+
+/// Finds the positions of the `txids` in the chain by walking the blocks back from the
+/// `last_block_hash` to the `starting_block_hash`, or to the genesis block if it's not set. The
+/// blocks are read as a whole, so the Bitcoin node doesn't need the `txindex`. The found
+/// transactions are removed from the `txids`.
+async fn find_tx_positions(
+    btc_client: &BitcoinRpcClient,
+    last_block_hash: BlockHash,
+    starting_block_hash: Option<BlockHash>,
+    txids: &mut HashSet<Txid>,
+) -> eyre::Result<Vec<(Txid, TxPosition)>> {
+    let mut positions = Vec::new();
+    let mut block_hash = Some(last_block_hash);
+
+    while let Some(hash) = block_hash {
+        if txids.is_empty() {
+            break;
+        }
+
+        let block = btc_client.get_block_info(&hash).await?;
+        for (block_index, txid) in block.tx.iter().enumerate() {
+            if txids.remove(txid) {
+                positions.push((*txid, TxPosition::new(block.block_data.height, block_index)));
+            }
+        }
+
+        if Some(hash) == starting_block_hash {
+            break;
+        }
+
+        block_hash = block.block_data.previousblockhash;
+    }
+
+    Ok(positions)
+}
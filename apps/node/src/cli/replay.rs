@@ -0,0 +1,506 @@
+//! Deterministic replay of the indexer pipeline over the blocks of the Bitcoin node, for the
+//! debugging of the divergences in the checked and attached transactions between the nodes'
+//! versions.
+//!
+//! The replay indexes the blocks with the same subindexers and passes their events through the
+//! same services as the running node, but against the temporary in-memory storage, and handles
+//! the events one by one in the fixed order of the services instead of running the services
+//! concurrently, so every replay of the same blocks takes the same steps. Each step is printed
+//! along with the transactions of the handled event.
+//!
+//! Only the announcements are found in the blocks, so the issuances and the transfers the node
+//! has received from the RPC and the P2P network are taken from the node's storage, either
+//! attached or rejected, as soon as their block is replayed. The attached parents of the
+//! transfers mined before the replayed blocks are copied to the temporary storage as well.
+//!
+//! The rest of the state before the first replayed block, e.g. the owners of the chromas or the
+//! frozen outputs, isn't known to the replay, so the range should start before the announcements
+//! of the debugged chromas. The node's state also includes the blocks after the last replayed one,
+//! so the chromas can differ because of them.
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+
+use bitcoin::Txid;
+use bitcoin_client::{json::GetBlockTxResult, BitcoinRpcApi, BitcoinRpcClient};
+use event_bus::{BusEvent, EventBus, Receiver};
+use eyre::{bail, Context};
+use yuv_controller::Controller;
+use yuv_indexers::{related_chromas, AnnouncementsIndexer, ConfirmationIndexer, Subindexer};
+use yuv_p2p::client::handle::OfflineHandle;
+use yuv_pixels::Chroma;
+use yuv_storage::{
+    AnyStorage, BlockIndexerStorage, ChromaInfoStorage, InvalidTxsStorage, LevelDB,
+    TransactionsStorage,
+};
+use yuv_tx_attach::GraphBuilder;
+use yuv_tx_check::TxChecker;
+use yuv_tx_confirm::TxConfirmator;
+use yuv_types::{
+    ControllerMessage, GraphBuilderMessage, IndexerMessage, RpcSubscriptionMessage,
+    TxCheckerMessage, TxConfirmMessage, YuvTransaction, YuvTxType,
+};
+
+use crate::{cli::node::Node, config::NodeConfig};
+
+/// Difference between the states of the node and the replay.
+pub enum Difference {
+    /// Transaction is attached by the node, but not by the replay.
+    AttachedByNode(Txid),
+    /// Transaction is attached by the replay, but not by the node.
+    AttachedByReplay(Txid),
+    /// Transaction is rejected for different reasons, `None` if it isn't rejected.
+    Rejection {
+        txid: Txid,
+        node: Option<String>,
+        replay: Option<String>,
+    },
+    /// Chroma's info differs, `None` if the chroma is unknown.
+    Chroma {
+        chroma: Chroma,
+        node: Option<String>,
+        replay: Option<String>,
+    },
+}
+
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AttachedByNode(txid) => write!(f, "tx {txid} is attached by the node only"),
+            Self::AttachedByReplay(txid) => write!(f, "tx {txid} is attached by the replay only"),
+            Self::Rejection { txid, node, replay } => write!(
+                f,
+                "tx {txid} is rejected differently: node {node:?}, replay {replay:?}"
+            ),
+            Self::Chroma {
+                chroma,
+                node,
+                replay,
+            } => write!(
+                f,
+                "chroma {chroma} differs: node {node:?}, replay {replay:?}"
+            ),
+        }
+    }
+}
+
+/// Summary of the replay.
+pub struct ReplaySummary {
+    pub blocks_number: usize,
+    pub steps_number: usize,
+    pub attached_number: usize,
+    pub differences: Vec<Difference>,
+}
+
+/// Replays the blocks from `from_height` to `to_height` inclusive and compares the resulting
+/// state with the state of the stopped node.
+pub async fn replay(
+    config: &NodeConfig,
+    from_height: usize,
+    to_height: usize,
+) -> eyre::Result<ReplaySummary> {
+    if from_height > to_height {
+        bail!("--from-height {from_height} is above --to-height {to_height}");
+    }
+
+    let (node_txs_storage, node_state_storage) = Node::init_storage(config.storage.clone()).await?;
+    let btc_client = Arc::new(config.bnode.client().await?);
+
+    let mut pipeline = Pipeline::new(config, btc_client.clone())?;
+
+    // Transactions of the replayed blocks, the state of which is compared.
+    let mut replayed_txids = BTreeSet::new();
+
+    for height in from_height..=to_height {
+        let block_hash = btc_client
+            .get_block_hash(height as u64)
+            .await
+            .wrap_err_with(|| format!("failed to get the hash of the block {height}"))?;
+        let block = btc_client
+            .get_block_txs(&block_hash)
+            .await
+            .wrap_err_with(|| format!("failed to get the block {block_hash}"))?;
+
+        println!("Block {height} ({block_hash}): {} txs", block.tx.len());
+
+        let block_txids: Vec<Txid> = block
+            .tx
+            .iter()
+            .filter(|tx| !tx.is_coin_base())
+            .map(|tx| tx.txid())
+            .collect();
+        replayed_txids.extend(block_txids.iter().copied());
+
+        pipeline.index_block(&block).await?;
+
+        let node_txs =
+            node_txs_of_block(&node_txs_storage, &node_state_storage, &block_txids).await?;
+        for yuv_tx in &node_txs {
+            pipeline
+                .copy_parents(yuv_tx, &node_txs_storage, &replayed_txids)
+                .await?;
+        }
+        pipeline.initialize_txs(node_txs).await;
+
+        pipeline.handle_events().await?;
+    }
+
+    let mut differences = Vec::new();
+    let mut attached_number = 0;
+    let mut chromas = BTreeSet::new();
+
+    for txid in &replayed_txids {
+        let node_tx = node_txs_storage.get_yuv_tx(txid).await?;
+        let replay_tx = pipeline.txs_storage.get_yuv_tx(txid).await?;
+
+        match (&node_tx, &replay_tx) {
+            (Some(_), None) => differences.push(Difference::AttachedByNode(*txid)),
+            (None, Some(_)) => differences.push(Difference::AttachedByReplay(*txid)),
+            _ => {}
+        }
+
+        if replay_tx.is_some() {
+            attached_number += 1;
+        }
+
+        for yuv_tx in node_tx.iter().chain(&replay_tx) {
+            chromas.extend(related_chromas(yuv_tx));
+        }
+
+        let node_rejection = node_state_storage
+            .get_tx_rejection(*txid)
+            .await?
+            .map(|rejection| rejection.reason.to_string());
+        let replay_rejection = pipeline
+            .state_storage
+            .get_tx_rejection(*txid)
+            .await?
+            .map(|rejection| rejection.reason.to_string());
+        if node_rejection != replay_rejection {
+            differences.push(Difference::Rejection {
+                txid: *txid,
+                node: node_rejection,
+                replay: replay_rejection,
+            });
+        }
+    }
+
+    for chroma in chromas {
+        let node_info = node_state_storage.get_chroma_info(&chroma).await?;
+        let replay_info = pipeline.state_storage.get_chroma_info(&chroma).await?;
+
+        if node_info != replay_info {
+            differences.push(Difference::Chroma {
+                chroma,
+                node: node_info.map(|info| format!("{info:?}")),
+                replay: replay_info.map(|info| format!("{info:?}")),
+            });
+        }
+    }
+
+    Ok(ReplaySummary {
+        blocks_number: to_height - from_height + 1,
+        steps_number: pipeline.steps_number,
+        attached_number,
+        differences,
+    })
+}
+
+/// Issuances and transfers of the block the node has attached or rejected. The announcements
+/// are skipped, as the replay finds them in the block itself.
+async fn node_txs_of_block(
+    txs_storage: &AnyStorage,
+    state_storage: &AnyStorage,
+    txids: &[Txid],
+) -> eyre::Result<Vec<YuvTransaction>> {
+    let mut txs = Vec::new();
+
+    for txid in txids {
+        let yuv_tx = match txs_storage.get_yuv_tx(txid).await? {
+            Some(yuv_tx) => Some(yuv_tx),
+            None => state_storage.get_invalid_tx(*txid).await?,
+        };
+
+        let Some(yuv_tx) = yuv_tx else {
+            continue;
+        };
+
+        if !matches!(yuv_tx.tx_type, YuvTxType::Announcement(_)) {
+            txs.push(yuv_tx);
+        }
+    }
+
+    Ok(txs)
+}
+
+/// Services of the node with the temporary storage, whose events are handled one by one.
+struct Pipeline {
+    event_bus: EventBus,
+    txs_storage: LevelDB,
+    state_storage: LevelDB,
+    subindexers: Vec<Box<dyn Subindexer>>,
+    controller: Controller<LevelDB, LevelDB, OfflineHandle>,
+    tx_checker: TxChecker<LevelDB, LevelDB>,
+    tx_confirmator: TxConfirmator<BitcoinRpcClient>,
+    graph_builder: GraphBuilder<LevelDB>,
+    steps_number: usize,
+}
+
+impl Pipeline {
+    fn new(config: &NodeConfig, btc_client: Arc<BitcoinRpcClient>) -> eyre::Result<Self> {
+        let txs_storage =
+            LevelDB::in_memory().wrap_err("failed to create the temporary storage")?;
+        let state_storage =
+            LevelDB::in_memory().wrap_err("failed to create the temporary storage")?;
+
+        // The channels are unbounded, as the events are handled only after the sending service
+        // is done.
+        let mut event_bus = EventBus::default();
+        event_bus.register::<TxCheckerMessage>(None);
+        event_bus.register::<GraphBuilderMessage>(None);
+        event_bus.register::<ControllerMessage>(None);
+        event_bus.register::<TxConfirmMessage>(None);
+        event_bus.register::<IndexerMessage>(None);
+        event_bus.register::<RpcSubscriptionMessage>(None);
+
+        let subindexers: Vec<Box<dyn Subindexer>> = vec![
+            Box::new(AnnouncementsIndexer::new(&event_bus, config.network)),
+            Box::new(ConfirmationIndexer::new(&event_bus)),
+        ];
+
+        let controller = Controller::new(
+            &event_bus,
+            txs_storage.clone(),
+            state_storage.clone(),
+            OfflineHandle,
+            config.storage.tx_per_page,
+        )
+        .set_address_index(config.controller.address_index)
+        .set_history_index(config.controller.history_index);
+
+        let tx_checker = TxChecker::new(
+            event_bus.clone(),
+            txs_storage.clone(),
+            state_storage.clone(),
+            config.network,
+        )
+        .with_dust_policy(config.dust.to_policy(config.network));
+
+        let tx_confirmator = TxConfirmator::new(
+            &event_bus,
+            btc_client,
+            config.indexer.max_confirmation_time,
+            config.indexer.confirmations_number,
+        )
+        .with_confirmation_policy(config.indexer.confirmation_policy());
+
+        let graph_builder = GraphBuilder::new(txs_storage.clone(), &event_bus)
+            .with_limits((&config.graph_builder).into());
+
+        Ok(Self {
+            event_bus,
+            txs_storage,
+            state_storage,
+            subindexers,
+            controller,
+            tx_checker,
+            tx_confirmator,
+            graph_builder,
+            steps_number: 0,
+        })
+    }
+
+    /// Passes the block to the subindexers and marks it as the last indexed one, the same as the
+    /// node's indexer does.
+    async fn index_block(&mut self, block: &GetBlockTxResult) -> eyre::Result<()> {
+        for subindexer in self.subindexers.iter_mut() {
+            subindexer
+                .index(block)
+                .await
+                .wrap_err("failed to handle new block")?;
+        }
+
+        self.state_storage
+            .put_last_indexed_hash(block.block_data.hash)
+            .await?;
+        self.state_storage
+            .put_last_indexed_height(block.block_data.height)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Copies the parents of the transfer that are mined before the replayed blocks from the
+    /// node's storage, as they are never replayed.
+    async fn copy_parents(
+        &self,
+        yuv_tx: &YuvTransaction,
+        node_txs_storage: &AnyStorage,
+        replayed_txids: &BTreeSet<Txid>,
+    ) -> eyre::Result<()> {
+        let YuvTxType::Transfer { input_proofs, .. } = &yuv_tx.tx_type else {
+            return Ok(());
+        };
+
+        let parents: HashSet<Txid> = input_proofs
+            .keys()
+            .filter_map(|input| yuv_tx.bitcoin_tx.input.get(*input as usize))
+            .map(|input| input.previous_output.txid)
+            .collect();
+
+        for parent in parents {
+            if replayed_txids.contains(&parent)
+                || self.txs_storage.get_yuv_tx(&parent).await?.is_some()
+            {
+                continue;
+            }
+
+            if let Some(parent_tx) = node_txs_storage.get_yuv_tx(&parent).await? {
+                self.txs_storage.put_yuv_tx(parent_tx).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends the transactions to the controller, the same as the RPC does.
+    async fn initialize_txs(&self, txs: Vec<YuvTransaction>) {
+        if txs.is_empty() {
+            return;
+        }
+
+        self.event_bus
+            .send(ControllerMessage::InitializeTxs(txs))
+            .await;
+    }
+
+    /// Handles the events until there are none left. The events of the earlier services of the
+    /// pipeline are handled first, so the order of the steps doesn't depend on the timings.
+    async fn handle_events(&mut self) -> eyre::Result<()> {
+        let tx_confirm_events = self.event_bus.subscribe::<TxConfirmMessage>();
+        let tx_checker_events = self.event_bus.subscribe::<TxCheckerMessage>();
+        let controller_events = self.event_bus.subscribe::<ControllerMessage>();
+        let graph_builder_events = self.event_bus.subscribe::<GraphBuilderMessage>();
+        let indexer_events = self.event_bus.subscribe::<IndexerMessage>();
+        let subscription_events = self.event_bus.subscribe::<RpcSubscriptionMessage>();
+
+        // The failures of the services that stop the node stop the replay, the rest are only
+        // printed, the same as the node logs them.
+        loop {
+            if let Some(event) = next_event(&tx_confirm_events).await? {
+                self.step("TxConfirmator", describe_tx_confirm(&event));
+                self.tx_confirmator.handle_event(event).await?;
+            } else if let Some(event) = next_event(&tx_checker_events).await? {
+                self.step("TxChecker", describe_tx_checker(&event));
+                self.tx_checker.handle_event(event).await?;
+            } else if let Some(event) = next_event(&controller_events).await? {
+                self.step("Controller", describe_controller(&event));
+                if let Err(err) = self.controller.handle_event(event).await {
+                    println!("  failed: {err:#}");
+                }
+            } else if let Some(event) = next_event(&graph_builder_events).await? {
+                self.step("GraphBuilder", describe_graph_builder(&event));
+                if let Err(err) = self.graph_builder.handle_event(event).await {
+                    println!("  failed: {err:#}");
+                }
+            } else if let Some(event) = next_event(&indexer_events).await? {
+                // The replayed blocks are already in the best chain, so the reorgs are ignored.
+                self.step("Indexer", format!("{event:?}"));
+            } else if let Some(event) = next_event(&subscription_events).await? {
+                self.step("Subscriptions", describe_subscription(&event));
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn step(&mut self, service: &str, event: String) {
+        self.steps_number += 1;
+        println!("  [{}] {service} <- {event}", self.steps_number);
+    }
+}
+
+/// Returns the next event of the channel, `None` if it's empty.
+async fn next_event<E: BusEvent + Clone + 'static>(
+    events: &Receiver<E>,
+) -> eyre::Result<Option<E>> {
+    if events.is_empty() {
+        return Ok(None);
+    }
+
+    let event = events
+        .recv()
+        .await
+        .wrap_err("event channel is disconnected")?;
+
+    Ok(Some(event))
+}
+
+fn describe_tx_confirm(event: &TxConfirmMessage) -> String {
+    match event {
+        TxConfirmMessage::Txs(txs) => format!("Txs {:?}", yuv_txids(txs)),
+        TxConfirmMessage::Block(block) => format!("Block {}", block.block_data.height),
+        TxConfirmMessage::CleanUp(_) => "CleanUp".to_string(),
+    }
+}
+
+fn describe_tx_checker(event: &TxCheckerMessage) -> String {
+    match event {
+        TxCheckerMessage::FullCheck(txs) => {
+            let txids: Vec<Txid> = txs.iter().map(|(tx, _)| tx.bitcoin_tx.txid()).collect();
+            format!("FullCheck {txids:?}")
+        }
+        TxCheckerMessage::IsolatedCheck(txs) => format!("IsolatedCheck {:?}", yuv_txids(txs)),
+    }
+}
+
+fn describe_controller(event: &ControllerMessage) -> String {
+    match event {
+        ControllerMessage::InvalidTxs(txids) => format!("InvalidTxs {txids:?}"),
+        ControllerMessage::GetData { inv, .. } => format!("GetData {inv:?}"),
+        ControllerMessage::PartiallyCheckedTxs(txids) => format!("PartiallyCheckedTxs {txids:?}"),
+        ControllerMessage::FullyCheckedTxs(txs) => {
+            format!("FullyCheckedTxs {:?}", yuv_txids(txs))
+        }
+        ControllerMessage::MinedTxs { txs, block_height } => {
+            format!("MinedTxs at {block_height} {txs:?}")
+        }
+        ControllerMessage::ConfirmedTxs(txids) => format!("ConfirmedTxs {txids:?}"),
+        ControllerMessage::UnconfirmedTxs(txids) => format!("UnconfirmedTxs {txids:?}"),
+        ControllerMessage::ExpiredTxs(txids) => format!("ExpiredTxs {txids:?}"),
+        ControllerMessage::InitializeTxs(txs) => format!("InitializeTxs {:?}", yuv_txids(txs)),
+        ControllerMessage::Reorganization {
+            new_indexing_height,
+            ..
+        } => format!("Reorganization to {new_indexing_height}"),
+        ControllerMessage::AttachedTxs(txids) => format!("AttachedTxs {txids:?}"),
+        ControllerMessage::P2P(_) => "P2P".to_string(),
+    }
+}
+
+fn describe_graph_builder(event: &GraphBuilderMessage) -> String {
+    match event {
+        GraphBuilderMessage::CheckedTxs(txs) => format!("CheckedTxs {:?}", yuv_txids(txs)),
+        GraphBuilderMessage::CleanUp(_) => "CleanUp".to_string(),
+    }
+}
+
+fn describe_subscription(event: &RpcSubscriptionMessage) -> String {
+    match event {
+        RpcSubscriptionMessage::AttachedTxs(txs) => format!("AttachedTxs {:?}", yuv_txids(txs)),
+        RpcSubscriptionMessage::Freezes(freezes) => format!("Freezes {}", freezes.len()),
+        RpcSubscriptionMessage::ChromaAnnouncements(announcements) => {
+            format!("ChromaAnnouncements {}", announcements.len())
+        }
+        RpcSubscriptionMessage::UnconfirmedTransfers(txs) => {
+            format!("UnconfirmedTransfers {:?}", yuv_txids(txs))
+        }
+        RpcSubscriptionMessage::DetachedTxs(txs) => format!("DetachedTxs {:?}", yuv_txids(txs)),
+        RpcSubscriptionMessage::InvalidatedTxs(txs) => {
+            format!("InvalidatedTxs {:?}", yuv_txids(txs))
+        }
+    }
+}
+
+fn yuv_txids(txs: &[YuvTransaction]) -> Vec<Txid> {
+    txs.iter().map(|tx| tx.bitcoin_tx.txid()).collect()
+}
@@ -0,0 +1,490 @@
+//! Bulk export and import of the node's state for the bootstrap of the new nodes.
+//!
+//! The state file is a sequence of CBOR items: the [`StateHeader`], the [`StateRecord`]s with
+//! the attached transactions in the order of the pages, the frozen outputs and public keys, the
+//! delegates, the chromas' info, and the [`StateRecord::End`] trailer. The records are written
+//! and read one by one, so the whole state is never held in memory.
+//!
+//! Unlike the [checkpoints](yuv_indexers::Checkpoint), the state file isn't signed, so it can be
+//! provided by any peer, and the importing node verifies it instead: every transaction must pass
+//! the isolated checks and spend only the outputs of the transactions imported before it, the
+//! freezes and the chromas must be related to the imported transactions, the trailer must match
+//! the records, and the last block must be in the best chain of the Bitcoin node.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use bitcoin::consensus::{deserialize, serialize};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::{secp256k1::PublicKey, BlockHash, OutPoint, Txid};
+use bitcoin_client::BitcoinRpcApi;
+use eyre::{bail, eyre, Context};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use yuv_indexers::related_chromas;
+use yuv_pixels::{Chroma, Pixel};
+use yuv_storage::{
+    BlockIndexerStorage, ChromaInfoStorage, DelegateEntry, DelegatesStorage, FrozenPubkeysStorage,
+    FrozenTxsStorage, IsIndexedStorage, KeyValueStorage, PagesNumberStorage, PagesStorage,
+    PubkeyFreezeEntry, SupplyTreeStorage, TransactionsStorage, TxFreezeEntry,
+};
+use yuv_tx_check::check_transaction;
+use yuv_types::{
+    announcements::ChromaInfo,
+    supply::{supply_inputs, supply_outputs},
+    Announcement, YuvTransaction, YuvTxType,
+};
+
+use crate::{cli::node::Node, config::NodeConfig};
+
+/// Version of the state file format.
+const STATE_VERSION: u32 = 1;
+
+/// First item of the state file.
+#[derive(Serialize, Deserialize)]
+struct StateHeader {
+    version: u32,
+    /// Height of the last block included in the state.
+    block_height: usize,
+    /// Hash of the last block included in the state.
+    block_hash: BlockHash,
+}
+
+#[derive(Serialize, Deserialize)]
+enum StateRecord {
+    /// Consensus encoded attached transaction.
+    Tx(#[serde(with = "serde_bytes")] Vec<u8>),
+    /// Output frozen by one of the transactions.
+    Frozen {
+        outpoint: OutPoint,
+        entry: TxFreezeEntry,
+    },
+    /// Public key whose pixels of the chroma are frozen by one of the transactions.
+    FrozenPubkey {
+        chroma: Chroma,
+        pubkey: PublicKey,
+        entry: PubkeyFreezeEntry,
+    },
+    /// Public key the chroma's capabilities are delegated to by one of the transactions.
+    Delegate {
+        chroma: Chroma,
+        delegate: PublicKey,
+        entry: DelegateEntry,
+    },
+    /// State of the chroma one of the transactions is related to.
+    Chroma { chroma: Chroma, info: ChromaInfo },
+    /// Number of the transactions and the SHA256 hash of their encoded bytes.
+    End {
+        txs_number: usize,
+        txs_hash: sha256::Hash,
+    },
+}
+
+/// Summary of the exported or imported state.
+pub struct StateSummary {
+    pub block_height: usize,
+    pub block_hash: BlockHash,
+    pub txs_number: usize,
+    pub txs_hash: sha256::Hash,
+}
+
+/// Writes the state of the stopped node to the file.
+pub async fn export_state(config: &NodeConfig, path: &Path) -> eyre::Result<StateSummary> {
+    let (txs_storage, state_storage) = Node::init_storage(config.storage.clone()).await?;
+
+    let block_hash = state_storage
+        .get_last_indexed_hash()
+        .await?
+        .ok_or_else(|| eyre!("The node hasn't indexed any blocks yet"))?;
+    let block_height = state_storage
+        .get_last_indexed_height()
+        .await?
+        .ok_or_else(|| eyre!("The height of the last indexed block is missing"))?;
+
+    let file = File::create(path)
+        .wrap_err_with(|| format!("Failed to create the state file {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+
+    write_item(
+        &mut writer,
+        &StateHeader {
+            version: STATE_VERSION,
+            block_height,
+            block_hash,
+        },
+    )?;
+
+    let mut txs_hash = sha256::Hash::engine();
+    let mut txs_number = 0;
+    let mut seen_chromas = HashSet::new();
+    let mut chromas = Vec::new();
+    let mut seen_freezes = HashSet::new();
+    let mut freeze_outpoints = Vec::new();
+    let mut seen_pubkey_freezes = HashSet::new();
+    let mut frozen_pubkeys = Vec::new();
+    let mut seen_delegates = HashSet::new();
+    let mut delegates = Vec::new();
+
+    let last_page_num = txs_storage.get_pages_number().await?.unwrap_or_default();
+    for page_num in 0..=last_page_num {
+        let page = txs_storage
+            .get_page_by_num(page_num)
+            .await?
+            .unwrap_or_default();
+
+        for txid in page {
+            let yuv_tx = txs_storage
+                .get_yuv_tx(&txid)
+                .await?
+                .ok_or_else(|| eyre!("Paged tx {} is not present in the storage", txid))?;
+
+            for chroma in related_chromas(&yuv_tx) {
+                if seen_chromas.insert(chroma) {
+                    chromas.push(chroma);
+                }
+            }
+
+            if let YuvTxType::Announcement(Announcement::Freeze(freeze)) = &yuv_tx.tx_type {
+                let freeze_outpoint = freeze.freeze_outpoint();
+                if seen_freezes.insert(freeze_outpoint) {
+                    freeze_outpoints.push(freeze_outpoint);
+                }
+            }
+
+            if let YuvTxType::Announcement(Announcement::FreezePubkey(freeze)) = &yuv_tx.tx_type {
+                let frozen_pubkey = (freeze.chroma, freeze.pubkey);
+                if seen_pubkey_freezes.insert(frozen_pubkey) {
+                    frozen_pubkeys.push(frozen_pubkey);
+                }
+            }
+
+            if let YuvTxType::Announcement(Announcement::Delegate(delegate)) = &yuv_tx.tx_type {
+                let delegate = (delegate.chroma, delegate.delegate);
+                if seen_delegates.insert(delegate) {
+                    delegates.push(delegate);
+                }
+            }
+
+            let raw_tx = serialize(&yuv_tx);
+            txs_hash.input(&raw_tx);
+            txs_number += 1;
+
+            write_item(&mut writer, &StateRecord::Tx(raw_tx))?;
+        }
+    }
+
+    for outpoint in freeze_outpoints {
+        if let Some(entry) = state_storage.get_frozen_tx(&outpoint).await? {
+            write_item(&mut writer, &StateRecord::Frozen { outpoint, entry })?;
+        }
+    }
+
+    // Unfrozen keys are removed from the storage, so only the currently frozen ones are written.
+    for (chroma, pubkey) in frozen_pubkeys {
+        if let Some(entry) = state_storage.get_frozen_pubkey(&chroma, &pubkey).await? {
+            write_item(
+                &mut writer,
+                &StateRecord::FrozenPubkey {
+                    chroma,
+                    pubkey,
+                    entry,
+                },
+            )?;
+        }
+    }
+
+    // Revoked delegations are removed from the storage as well.
+    for (chroma, delegate) in delegates {
+        if let Some(entry) = state_storage.get_delegate(&chroma, &delegate).await? {
+            write_item(
+                &mut writer,
+                &StateRecord::Delegate {
+                    chroma,
+                    delegate,
+                    entry,
+                },
+            )?;
+        }
+    }
+
+    for chroma in chromas {
+        if let Some(info) = state_storage.get_chroma_info(&chroma).await? {
+            write_item(&mut writer, &StateRecord::Chroma { chroma, info })?;
+        }
+    }
+
+    let txs_hash = sha256::Hash::from_engine(txs_hash);
+    write_item(
+        &mut writer,
+        &StateRecord::End {
+            txs_number,
+            txs_hash,
+        },
+    )?;
+    writer.flush().wrap_err("Failed to write the state")?;
+
+    Ok(StateSummary {
+        block_height,
+        block_hash,
+        txs_number,
+        txs_hash,
+    })
+}
+
+/// Verifies the state from the file and writes it to the empty storage of the stopped node.
+///
+/// The records are written as they are verified, so the storage must be removed if the import
+/// fails. The storage is marked as indexed only after the whole state is verified.
+pub async fn import_state(config: &NodeConfig, path: &Path) -> eyre::Result<StateSummary> {
+    let (txs_storage, state_storage) = Node::init_storage(config.storage.clone()).await?;
+
+    if state_storage.get_last_indexed_hash().await?.is_some() {
+        bail!("The storage is already indexed, the state can be imported only to the empty one");
+    }
+
+    let file = File::open(path)
+        .wrap_err_with(|| format!("Failed to open the state file {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+
+    let header: StateHeader = read_item(&mut reader)?;
+    if header.version != STATE_VERSION {
+        bail!("Unsupported state version {}", header.version);
+    }
+
+    let btc_client = config.bnode.client().await?;
+
+    let best_chain_hash = btc_client
+        .get_block_hash(header.block_height as u64)
+        .await?;
+    if best_chain_hash != header.block_hash {
+        bail!(
+            "State block {} at height {} is not in the best chain",
+            header.block_hash,
+            header.block_height
+        );
+    }
+
+    let tx_per_page = config.storage.tx_per_page.max(1) as usize;
+    let mut page = Vec::with_capacity(tx_per_page);
+    let mut pages_number = 0;
+
+    let mut txids = HashSet::new();
+    let mut chromas = HashSet::new();
+    let mut unspent_outputs = HashMap::new();
+    let mut supply_utxos: HashMap<Chroma, BTreeMap<OutPoint, u128>> = HashMap::new();
+    let mut txs_hash = sha256::Hash::engine();
+
+    let (txs_number, expected_txs_hash) = loop {
+        match read_item(&mut reader)? {
+            StateRecord::Tx(raw_tx) => {
+                txs_hash.input(&raw_tx);
+
+                let yuv_tx: YuvTransaction =
+                    deserialize(&raw_tx).wrap_err("Invalid transaction in the state")?;
+                let txid = yuv_tx.bitcoin_tx.txid();
+
+                if !txids.insert(txid) {
+                    bail!("Transaction {} is duplicated", txid);
+                }
+
+                verify_tx(&yuv_tx, &txids, &mut unspent_outputs)
+                    .wrap_err_with(|| format!("Invalid transaction {}", txid))?;
+
+                chromas.extend(related_chromas(&yuv_tx));
+
+                // Parents go before their children, so the spent outputs are always added
+                // before they are removed.
+                for (outpoint, pixel) in supply_outputs(&yuv_tx) {
+                    supply_utxos
+                        .entry(pixel.chroma)
+                        .or_default()
+                        .insert(outpoint, pixel.luma.amount);
+                }
+                for (outpoint, pixel) in supply_inputs(&yuv_tx) {
+                    if let Some(utxos) = supply_utxos.get_mut(&pixel.chroma) {
+                        utxos.remove(&outpoint);
+                    }
+                }
+
+                txs_storage.put_yuv_tx(yuv_tx).await?;
+
+                page.push(txid);
+                if page.len() == tx_per_page {
+                    txs_storage
+                        .put_page(pages_number, std::mem::take(&mut page))
+                        .await?;
+                    pages_number += 1;
+                }
+            }
+            StateRecord::Frozen { outpoint, entry } => {
+                if !txids.contains(&entry.txid) {
+                    bail!(
+                        "Output {} is frozen by the unknown transaction {}",
+                        outpoint,
+                        entry.txid
+                    );
+                }
+
+                state_storage
+                    .put_frozen_tx(
+                        &outpoint,
+                        entry.txid,
+                        entry.chroma,
+                        entry.amount,
+                        entry.expiry_height,
+                    )
+                    .await?;
+            }
+            StateRecord::FrozenPubkey {
+                chroma,
+                pubkey,
+                entry,
+            } => {
+                if !txids.contains(&entry.txid) {
+                    bail!(
+                        "Public key {} is frozen by the unknown transaction {}",
+                        pubkey,
+                        entry.txid
+                    );
+                }
+
+                state_storage
+                    .put_frozen_pubkey(&chroma, &pubkey, entry.txid)
+                    .await?;
+            }
+            StateRecord::Delegate {
+                chroma,
+                delegate,
+                entry,
+            } => {
+                if !txids.contains(&entry.txid) {
+                    bail!(
+                        "Public key {} is delegated by the unknown transaction {}",
+                        delegate,
+                        entry.txid
+                    );
+                }
+
+                state_storage
+                    .put_delegate(&chroma, &delegate, entry.capabilities, entry.txid)
+                    .await?;
+            }
+            StateRecord::Chroma { chroma, info } => {
+                if !chromas.contains(&chroma) {
+                    bail!("Chroma {} is not related to any transaction", chroma);
+                }
+
+                state_storage
+                    .put_chroma_info(
+                        &chroma,
+                        info.announcement,
+                        info.total_supply,
+                        info.total_burned,
+                        info.announcement_version,
+                        info.owner,
+                    )
+                    .await?;
+            }
+            StateRecord::End {
+                txs_number,
+                txs_hash,
+            } => break (txs_number, txs_hash),
+        }
+    };
+
+    if txs_number != txids.len() {
+        bail!(
+            "State is truncated: expected {} transactions, got {}",
+            txs_number,
+            txids.len()
+        );
+    }
+
+    let txs_hash = sha256::Hash::from_engine(txs_hash);
+    if txs_hash != expected_txs_hash {
+        bail!(
+            "Transactions hash mismatch: expected {}, got {}",
+            expected_txs_hash,
+            txs_hash
+        );
+    }
+
+    if !page.is_empty() {
+        txs_storage.put_page(pages_number, page).await?;
+        pages_number += 1;
+    }
+    txs_storage
+        .put_pages_number(pages_number.saturating_sub(1))
+        .await?;
+
+    for (chroma, utxos) in supply_utxos {
+        txs_storage.put_supply_utxos(&chroma, utxos).await?;
+    }
+
+    state_storage
+        .put_last_indexed_hash(header.block_hash)
+        .await?;
+    state_storage
+        .put_last_indexed_height(header.block_height)
+        .await?;
+    state_storage.put_is_indexed().await?;
+
+    KeyValueStorage::<Vec<u8>, Vec<u8>>::flush(&txs_storage).await?;
+    KeyValueStorage::<Vec<u8>, Vec<u8>>::flush(&state_storage).await?;
+
+    Ok(StateSummary {
+        block_height: header.block_height,
+        block_hash: header.block_hash,
+        txs_number,
+        txs_hash,
+    })
+}
+
+/// Checks the transaction in isolation, and that its inputs spend the unspent outputs of the
+/// transactions imported before it with the same pixels.
+fn verify_tx(
+    yuv_tx: &YuvTransaction,
+    txids: &HashSet<Txid>,
+    unspent_outputs: &mut HashMap<OutPoint, Pixel>,
+) -> eyre::Result<()> {
+    check_transaction(yuv_tx)?;
+
+    let txid = yuv_tx.bitcoin_tx.txid();
+
+    if let Some(input_proofs) = yuv_tx.tx_type.input_proofs() {
+        for (index, proof) in input_proofs {
+            let input = yuv_tx
+                .bitcoin_tx
+                .input
+                .get(*index as usize)
+                .ok_or_else(|| eyre!("Input {} is missing", index))?;
+            let outpoint = input.previous_output;
+
+            if outpoint.txid == txid || !txids.contains(&outpoint.txid) {
+                bail!("Parent of the input {} is not imported before", outpoint);
+            }
+
+            match unspent_outputs.remove(&outpoint) {
+                Some(pixel) if pixel == proof.pixel() => {}
+                Some(_) => bail!("Pixel of the input {} doesn't match the output", outpoint),
+                None => bail!("Output {} is already spent", outpoint),
+            }
+        }
+    }
+
+    if let Some(output_proofs) = yuv_tx.tx_type.output_proofs() {
+        for (vout, proof) in output_proofs {
+            unspent_outputs.insert(OutPoint::new(txid, *vout), proof.pixel());
+        }
+    }
+
+    Ok(())
+}
+
+fn write_item<T: Serialize>(writer: &mut impl Write, item: &T) -> eyre::Result<()> {
+    ciborium::into_writer(item, writer).wrap_err("Failed to write the state")
+}
+
+fn read_item<T: DeserializeOwned>(reader: &mut impl Read) -> eyre::Result<T> {
+    ciborium::from_reader(reader).wrap_err("Invalid state file")
+}
@@ -0,0 +1,41 @@
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime::Tokio,
+    trace::{self, Sampler, Tracer},
+    Resource,
+};
+
+use crate::config::TelemetryConfig;
+
+/// Installs the tracer that exports the spans to the OTLP collector in batches, and the
+/// propagator of the W3C trace context, which continues the traces of the RPC requests.
+pub(crate) fn init_tracer(config: &TelemetryConfig) -> eyre::Result<Tracer> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let exporter = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(&config.endpoint);
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sampling_ratio)));
+
+    let resource = Resource::new([KeyValue::new("service.name", config.service_name.clone())]);
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(
+            trace::config()
+                .with_sampler(sampler)
+                .with_resource(resource),
+        )
+        .install_batch(Tokio)?;
+
+    Ok(tracer)
+}
+
+/// Exports the remaining spans.
+pub(crate) fn shutdown() {
+    global::shutdown_tracer_provider();
+}
@@ -1,4 +1,6 @@
-use bitcoin_client::BitcoinRpcAuth;
+use bitcoin_client::{
+    BitcoinRpcAuth, BitcoinRpcClient, CircuitBreakerConfig, RetryPolicy, DEFAULT_MAX_BLOCK_LAG,
+};
 use serde::Deserialize;
 use std::time::Duration;
 
@@ -12,6 +14,30 @@ pub struct BitcoinConfig {
     /// The timeout after which requests will abort if they aren't finished.
     #[serde(default)]
     pub timeout: Option<Duration>,
+    /// Proxy to send the requests through, e.g. `socks5h://127.0.0.1:9050` for Tor.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Retries of the requests that failed because the node is unavailable.
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    /// Circuit breaker that fails the requests immediately while the node is unavailable.
+    #[serde(default)]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Bitcoin nodes to fail over to when the primary one is unavailable or lagging.
+    #[serde(default)]
+    pub fallbacks: Vec<BitcoinEndpointConfig>,
+    /// Max number of blocks the bitcoin node can be behind the best one to be used.
+    #[serde(default = "default_max_block_lag")]
+    pub max_block_lag: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct BitcoinEndpointConfig {
+    /// Url to the bitcoin node
+    pub url: String,
+    /// Authentication for the bitcoin node
+    #[serde(default)]
+    pub auth: Option<BitcoinAuth>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -22,14 +48,56 @@ pub struct BitcoinAuth {
     pub password: String,
 }
 
+fn default_max_block_lag() -> u64 {
+    DEFAULT_MAX_BLOCK_LAG
+}
+
+fn rpc_auth(auth: Option<&BitcoinAuth>) -> BitcoinRpcAuth {
+    match auth {
+        Some(auth) => BitcoinRpcAuth::UserPass {
+            username: auth.username.clone(),
+            password: auth.password.clone(),
+        },
+        None => BitcoinRpcAuth::None,
+    }
+}
+
 impl BitcoinConfig {
     pub fn auth(&self) -> BitcoinRpcAuth {
-        match &self.auth {
-            Some(auth) => BitcoinRpcAuth::UserPass {
-                username: auth.username.clone(),
-                password: auth.password.clone(),
-            },
-            None => BitcoinRpcAuth::None,
+        rpc_auth(self.auth.as_ref())
+    }
+    /// Creates the client of the bitcoin node with the configured timeout, retries, circuit
+    /// breaker and fallbacks.
+    pub async fn client(&self) -> bitcoin_client::Result<BitcoinRpcClient> {
+        let mut client = BitcoinRpcClient::new_with_proxy(
+            self.auth(),
+            self.url.clone(),
+            self.timeout,
+            self.proxy.clone(),
+        )
+        .await?
+        .with_max_block_lag(self.max_block_lag);
+
+        for fallback in &self.fallbacks {
+            let fallback_client = BitcoinRpcClient::new_with_proxy(
+                rpc_auth(fallback.auth.as_ref()),
+                fallback.url.clone(),
+                self.timeout,
+                self.proxy.clone(),
+            )
+            .await?;
+
+            client = client.with_fallback(fallback_client);
+        }
+
+        if let Some(retry) = &self.retry {
+            client = client.with_retry_policy(retry.clone());
         }
+
+        if let Some(circuit_breaker) = &self.circuit_breaker {
+            client = client.with_circuit_breaker(circuit_breaker.clone());
+        }
+
+        Ok(client)
     }
 }
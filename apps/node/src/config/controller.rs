@@ -11,6 +11,18 @@ pub struct ControllerConfig {
     /// Interval between inventory sharing in seconds
     #[serde(default = "default_inv_sharing_interval")]
     pub inv_sharing_interval: u64,
+    /// Max total output amount of the transfers that are provisionally attached from the
+    /// Bitcoin mempool before they are mined. Zero-conf mode is disabled if not set.
+    #[serde(default)]
+    pub zero_conf_max_amount: Option<u128>,
+    /// Index the unspent outputs by the keys of their owners for `listutxosbypubkey`. Only the
+    /// transactions attached after the index is enabled are indexed.
+    #[serde(default)]
+    pub address_index: bool,
+    /// Record the transfer history of the owners for `getbalanceat` and `listtransfers`. Only
+    /// the transactions attached after the index is enabled are recorded.
+    #[serde(default)]
+    pub history_index: bool,
 }
 
 fn default_max_inv_size() -> usize {
@@ -26,6 +38,9 @@ impl Default for ControllerConfig {
         Self {
             max_inv_size: default_max_inv_size(),
             inv_sharing_interval: default_inv_sharing_interval(),
+            zero_conf_max_amount: None,
+            address_index: false,
+            history_index: false,
         }
     }
 }
@@ -0,0 +1,22 @@
+use serde::Deserialize;
+use yuv_types::{network::Network, DustPolicy};
+
+/// Minimal amount of satoshis of the pixel outputs. If unset, the one of the network's
+/// [`DustPolicy`] is used.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct DustConfig {
+    /// Pixel outputs with less satoshis are rejected before the confirmation.
+    pub min_satoshis: Option<u64>,
+}
+
+impl DustConfig {
+    pub fn to_policy(&self, network: Network) -> DustPolicy {
+        let mut policy = DustPolicy::for_network(network);
+        if let Some(min_satoshis) = self.min_satoshis {
+            policy.min_satoshis = min_satoshis;
+        }
+
+        policy
+    }
+}
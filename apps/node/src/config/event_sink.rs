@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use yuv_event_sink::{SinkEvent, DEFAULT_MAX_QUEUE_SIZE};
+
+#[derive(Deserialize)]
+pub struct EventSinkConfig {
+    /// Broker the events are published to.
+    pub broker: BrokerConfig,
+
+    /// Topics, or the subjects for NATS, of the forwarded events. The events without the topic
+    /// aren't forwarded.
+    #[serde(default)]
+    pub topics: BTreeMap<SinkEvent, String>,
+
+    /// Number of the unpublished events after which the oldest ones are dropped.
+    #[serde(default = "default_max_queue_size")]
+    pub max_queue_size: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BrokerConfig {
+    /// Available only if the node is built with the `kafka` feature.
+    #[cfg_attr(not(feature = "kafka"), allow(dead_code))]
+    Kafka {
+        /// Comma-separated list of the bootstrap servers, e.g. `127.0.0.1:9092`.
+        brokers: String,
+
+        /// Time to wait for the acknowledgement of the event, in seconds.
+        #[serde(default = "default_timeout_secs")]
+        timeout_secs: u64,
+    },
+    /// Available only if the node is built with the `nats` feature.
+    #[cfg_attr(not(feature = "nats"), allow(dead_code))]
+    Nats {
+        /// Url of the server, e.g. `nats://127.0.0.1:4222`.
+        url: String,
+    },
+}
+
+fn default_max_queue_size() -> usize {
+    DEFAULT_MAX_QUEUE_SIZE
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
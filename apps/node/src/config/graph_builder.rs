@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use yuv_tx_attach::GraphLimits;
+
+/// Limits of the transactions that are waiting for their parents to attach.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct GraphBuilderConfig {
+    /// Max number of the waiting transactions.
+    pub max_pending_txs: usize,
+    /// Max length of a chain of the waiting transactions that depend on each other.
+    pub max_depth: usize,
+    /// Max number of the waiting transactions that depend on the same parent.
+    pub max_fanout: usize,
+}
+
+impl Default for GraphBuilderConfig {
+    fn default() -> Self {
+        let limits = GraphLimits::default();
+
+        Self {
+            max_pending_txs: limits.max_pending_txs,
+            max_depth: limits.max_depth,
+            max_fanout: limits.max_fanout,
+        }
+    }
+}
+
+impl From<&GraphBuilderConfig> for GraphLimits {
+    fn from(config: &GraphBuilderConfig) -> Self {
+        Self {
+            max_pending_txs: config.max_pending_txs,
+            max_depth: config.max_depth,
+            max_fanout: config.max_fanout,
+        }
+    }
+}
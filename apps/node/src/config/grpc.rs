@@ -0,0 +1,9 @@
+use std::net::SocketAddr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct GrpcConfig {
+    /// Address to listen of incoming connections
+    pub address: SocketAddr,
+}
@@ -1,8 +1,10 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
-use bitcoin::BlockHash;
+use bitcoin::{hashes::sha256, secp256k1::PublicKey, BlockHash};
 use serde::Deserialize;
-use yuv_indexers::{BlockLoaderConfig, IndexingParams};
+use yuv_indexers::{BlockLoaderConfig, CheckpointParams, IndexingParams};
+use yuv_tx_confirm::{ConfirmationPolicy, ConfirmationTier};
 use yuv_types::DEFAULT_CONFIRMATIONS_NUMBER;
 
 pub const DEFAULT_POLLING_PERIOD: Duration = Duration::from_secs(5);
@@ -28,6 +30,8 @@ pub struct IndexerConfig {
     #[serde(default = "default_max_confirmation_time")]
     pub max_confirmation_time: Duration,
 
+    /// Interval of the confirmator's clean up. Used if the `tx_confirmator_clean_up` job's
+    /// schedule is not set in the scheduler config.
     #[serde(default = "default_clean_up_interval")]
     pub clean_up_interval: Duration,
 
@@ -40,8 +44,40 @@ pub struct IndexerConfig {
     #[serde(default = "default_max_restart_attempts")]
     pub max_restart_attempts: u32,
 
+    /// Number of confirmations required for the transactions above all the
+    /// `confirmation_tiers`.
     #[serde(default = "default_confirmations_number")]
     pub confirmations_number: u8,
+
+    /// Numbers of confirmations required for the transactions depending on their amount.
+    #[serde(default)]
+    pub confirmation_tiers: Vec<ConfirmationTierConfig>,
+
+    /// Checkpoint to import instead of indexing the history on the first start.
+    #[serde(default)]
+    pub checkpoint: Option<CheckpointConfig>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ConfirmationTierConfig {
+    /// Upper bound of the transaction's amount, exclusive.
+    pub below: u128,
+
+    /// Number of confirmations required for the transactions below the bound.
+    pub confirmations: u8,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct CheckpointConfig {
+    /// Path to the checkpoint file produced by `export-checkpoint`.
+    pub path: PathBuf,
+
+    /// Expected hash of the checkpoint.
+    #[serde(default)]
+    pub hash: Option<sha256::Hash>,
+
+    /// Public keys of the signers whose checkpoints are trusted.
+    pub trusted_signers: Vec<PublicKey>,
 }
 
 fn default_polling_period() -> Duration {
@@ -68,10 +104,37 @@ fn default_confirmations_number() -> u8 {
     DEFAULT_CONFIRMATIONS_NUMBER
 }
 
+impl IndexerConfig {
+    /// Returns the policy of the confirmations required for the transactions.
+    pub fn confirmation_policy(&self) -> ConfirmationPolicy {
+        let tiers = self
+            .confirmation_tiers
+            .iter()
+            .map(|tier| ConfirmationTier::new(tier.below, tier.confirmations))
+            .collect();
+
+        ConfirmationPolicy::fixed(self.confirmations_number).with_tiers(tiers)
+    }
+}
+
 impl From<IndexerConfig> for IndexingParams {
     fn from(value: IndexerConfig) -> Self {
         Self {
             starting_block_hash: value.starting_block,
+            checkpoint: value.checkpoint.map(Into::into),
+        }
+    }
+}
+
+impl From<CheckpointConfig> for CheckpointParams {
+    fn from(value: CheckpointConfig) -> Self {
+        Self {
+            path: value.path,
+            hash: value.hash,
+            trusted_signers: value.trusted_signers,
+            // Set from the config of the controller, which maintains the indexes.
+            address_index: false,
+            history_index: false,
         }
     }
 }
@@ -87,6 +150,8 @@ impl Default for IndexerConfig {
             max_restart_attempts: default_max_restart_attempts(),
             clean_up_interval: default_clean_up_interval(),
             confirmations_number: Default::default(),
+            confirmation_tiers: Vec::new(),
+            checkpoint: None,
         }
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use serde::Deserialize;
@@ -7,6 +8,26 @@ use tracing::metadata::Level;
 pub struct LoggerConfig {
     #[serde(default = "default_level", deserialize_with = "deserialize_level")]
     pub level: Level,
+
+    /// Format of the log lines.
+    #[serde(default)]
+    pub format: LogFormat,
+
+    /// Levels of the targets, e.g. `yuv_p2p = "DEBUG"`, that override the `level`. The target
+    /// matches the events of its submodules too.
+    #[serde(default, deserialize_with = "deserialize_targets")]
+    pub targets: HashMap<String, Level>,
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line with the fields of the event and its spans, e.g. `txid` and
+    /// `chroma` of the handled transaction.
+    Json,
 }
 
 fn deserialize_level<'de, D>(deserializer: D) -> Result<Level, D::Error>
@@ -18,6 +39,22 @@ where
     Level::from_str(&s).map_err(serde::de::Error::custom)
 }
 
+fn deserialize_targets<'de, D>(deserializer: D) -> Result<HashMap<String, Level>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let targets = HashMap::<String, String>::deserialize(deserializer)?;
+
+    targets
+        .into_iter()
+        .map(|(target, level)| {
+            let level = Level::from_str(&level).map_err(serde::de::Error::custom)?;
+
+            Ok((target, level))
+        })
+        .collect()
+}
+
 fn default_level() -> Level {
     Level::INFO
 }
@@ -26,6 +63,8 @@ impl Default for LoggerConfig {
     fn default() -> Self {
         Self {
             level: default_level(),
+            format: LogFormat::default(),
+            targets: HashMap::new(),
         }
     }
 }
@@ -1,23 +1,30 @@
 use config::Config;
+use eyre::bail;
 use serde::Deserialize;
-use yuv_types::network::Network;
+use yuv_types::network::{CustomNetwork, Network};
 
 use std::path::PathBuf;
 
 mod p2p;
-pub use p2p::P2pConfig;
+pub use p2p::{P2pConfig, P2pTransport};
 
 mod rpc;
 pub use rpc::RpcConfig;
 
+mod grpc;
+pub use grpc::GrpcConfig;
+
 mod storage;
-pub use storage::StorageConfig;
+pub use storage::{StorageBackend, StorageConfig};
 
 mod bnode;
 pub use bnode::BitcoinConfig;
 
 mod logger;
-pub use logger::LoggerConfig;
+pub use logger::{LogFormat, LoggerConfig};
+
+mod telemetry;
+pub use telemetry::TelemetryConfig;
 
 mod indexer;
 pub use indexer::IndexerConfig;
@@ -26,13 +33,41 @@ mod controller;
 
 pub use controller::ControllerConfig;
 
+mod graph_builder;
+pub use graph_builder::GraphBuilderConfig;
+
+mod dust;
+pub use dust::DustConfig;
+
+mod webhooks;
+pub use webhooks::WebhooksConfig;
+
+mod event_sink;
+pub use event_sink::{BrokerConfig, EventSinkConfig};
+
+mod scheduler;
+pub use scheduler::{
+    SchedulerConfig, BITCOIN_HEALTH_CHECK_JOB, DEFAULT_BITCOIN_HEALTH_CHECK_INTERVAL,
+    DEFAULT_GRAPH_BUILDER_CLEAN_UP_INTERVAL, DEFAULT_REVALIDATION_INTERVAL,
+    DEFAULT_STORAGE_PRUNING_INTERVAL, GRAPH_BUILDER_CLEAN_UP_JOB, REVALIDATION_JOB,
+    STORAGE_PRUNING_JOB, TX_CONFIRMATOR_CLEAN_UP_JOB,
+};
+
 #[derive(Deserialize)]
 pub struct NodeConfig {
     #[serde(default = "default_network")]
     pub network: Network,
 
+    /// Parameters of the `custom` network. If not set, the ones of regtest are used.
+    #[serde(default)]
+    pub custom_network: Option<CustomNetwork>,
+
     pub p2p: P2pConfig,
     pub rpc: RpcConfig,
+    /// Address of the gRPC server, which is served only if the node is built with the `grpc`
+    /// feature.
+    #[serde(default)]
+    pub grpc: Option<GrpcConfig>,
     pub bnode: BitcoinConfig,
     pub storage: StorageConfig,
 
@@ -42,11 +77,35 @@ pub struct NodeConfig {
     #[serde(default)]
     pub logger: LoggerConfig,
 
+    /// Export of the traces over OTLP, which is done only if the node is built with the `otel`
+    /// feature.
+    #[serde(default)]
+    pub telemetry: Option<TelemetryConfig>,
+
     #[serde(default)]
     pub indexer: IndexerConfig,
 
     #[serde(default)]
     pub controller: ControllerConfig,
+
+    #[serde(default)]
+    pub graph_builder: GraphBuilderConfig,
+
+    /// Minimal amount of satoshis of the pixel outputs, the network's one by default.
+    #[serde(default)]
+    pub dust: DustConfig,
+
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+
+    /// Webhooks notified about the attached, frozen and reorged transactions.
+    #[serde(default)]
+    pub webhooks: Option<WebhooksConfig>,
+
+    /// Forwarding of the events to Kafka or NATS, which is available only if the node is built
+    /// with the `kafka` or `nats` feature.
+    #[serde(default)]
+    pub event_sink: Option<EventSinkConfig>,
 }
 
 fn default_network() -> Network {
@@ -59,6 +118,16 @@ impl NodeConfig {
             .add_source(config::File::from(path))
             .build()?;
 
-        Ok(config.try_deserialize()?)
+        let mut config: Self = config.try_deserialize()?;
+
+        if let Some(params) = config.custom_network {
+            let Network::Custom(_) = config.network else {
+                bail!("Custom network parameters require the `custom` network");
+            };
+
+            config.network = Network::Custom(params);
+        }
+
+        Ok(config)
     }
 }
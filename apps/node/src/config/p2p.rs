@@ -1,7 +1,9 @@
-use eyre::{Context, OptionExt};
+use bitcoin::secp256k1::{PublicKey, SecretKey};
+use eyre::{bail, Context, OptionExt};
 use serde::{Deserialize, Serialize};
 use std::net::{SocketAddr, ToSocketAddrs};
-use yuv_p2p::client;
+use std::path::PathBuf;
+use yuv_p2p::{client, net::OnionAddr};
 use yuv_types::network::Network;
 
 /// Default number of peers connected to this node.
@@ -10,9 +12,22 @@ pub const DEFAULT_MAX_INBOUND_CONNECTIONS: usize = 125;
 /// Default number of peers this node is connected to.
 pub const DEFAULT_MAX_OUTBOUND_CONNECTIONS: usize = 8;
 
+/// Default number of peers below which this node reconnects more often.
+pub const DEFAULT_MIN_OUTBOUND_CONNECTIONS: usize = 2;
+
+/// Transport of the P2P connections, selected by the scheme of the listen address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum P2pTransport {
+    /// Addresses without the scheme or with `tcp://`.
+    Tcp,
+    /// Addresses with `quic://`, e.g. `quic://0.0.0.0:8002`.
+    Quic,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct P2pConfig {
-    /// Address to listen to incoming connections
+    /// Address to listen to incoming connections. The `quic://` scheme selects the QUIC
+    /// transport for all the connections.
     pub address: String,
     /// Maximum amount of inbound connections
     #[serde(default = "default_max_inbound_connections")]
@@ -20,9 +35,119 @@ pub struct P2pConfig {
     /// Maximum amount of outbound connections
     #[serde(default = "default_max_outbound_connections")]
     pub max_outbound_connections: usize,
-    /// List of nodes to connect to firstly.
+    /// Minimum amount of outbound connections, below which the node reconnects more often
+    #[serde(default = "default_min_outbound_connections")]
+    pub min_outbound_connections: usize,
+    /// List of nodes to connect to firstly. Onion addresses (`<host>.onion:<port>`) require
+    /// the proxy to be set. The scheme must match the one of the listen address.
     #[serde(default)]
     pub bootnodes: Vec<String>,
+    /// List of DNS seeds to discover peers with, e.g. `seed.example.com` or
+    /// `seed.example.com:8002`.
+    #[serde(default)]
+    pub dns_seeds: Vec<String>,
+    /// Address of the SOCKS5 proxy to establish the outbound connections through, e.g.
+    /// `127.0.0.1:9050` for Tor.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Authentication of the peers with their keys.
+    #[serde(default)]
+    pub auth: Option<P2pAuthConfig>,
+    /// Limits of the messages a peer can send.
+    #[serde(default)]
+    pub rate_limits: P2pRateLimitsConfig,
+    /// Reconcile the inventories with the supporting peers by exchanging the sketches, so only
+    /// the difference between the inventories is sent.
+    #[serde(default)]
+    pub reconciliation: bool,
+    /// Announce the mined transactions with the short ids to the supporting peers, so they
+    /// request only the transactions they don't have.
+    #[serde(default)]
+    pub compact_relay: bool,
+    /// Compress the large transaction messages sent to the supporting peers with zstd.
+    #[serde(default)]
+    pub compression: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct P2pAuthConfig {
+    /// Hex encoded private key to authenticate with and to sign the inventories.
+    #[serde(default)]
+    pub private_key: Option<SecretKey>,
+    /// Path to the keystore with the private key encrypted by `encrypt-key`, used if the
+    /// `private_key` isn't set. The passphrase is taken from `YUV_KEYSTORE_PASSPHRASE` or asked
+    /// on the start.
+    #[serde(default)]
+    pub keystore: Option<PathBuf>,
+    /// Public keys of the peers whose inventories and transactions are accepted, e.g. the
+    /// members of the federation. If empty, they are accepted from all the peers.
+    #[serde(default)]
+    pub trusted_peers: Vec<PublicKey>,
+}
+
+impl P2pAuthConfig {
+    fn to_client_config(&self) -> eyre::Result<client::AuthConfig> {
+        let secret_key = match (self.private_key, &self.keystore) {
+            (Some(_), Some(_)) => bail!(
+                "Both p2p.auth.private_key and p2p.auth.keystore are set, remove the plaintext \
+                private_key from the config"
+            ),
+            (Some(private_key), None) => Some(private_key),
+            (None, Some(keystore)) => Some(
+                yuv_keystore::unlock(keystore)
+                    .wrap_err_with(|| format!("Failed to unlock {}", keystore.display()))?
+                    .secret_key,
+            ),
+            (None, None) => None,
+        };
+
+        // Trusted peers sign their inventories only to the authenticated peers.
+        if !self.trusted_peers.is_empty() && secret_key.is_none() {
+            bail!("Trusted peers require the private key to be set");
+        }
+
+        Ok(client::AuthConfig {
+            secret_key,
+            trusted_peers: self.trusted_peers.iter().copied().collect(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct P2pRateLimitsConfig {
+    /// Max number of the announced inventory items per second.
+    pub inv_items_per_sec: u32,
+    /// Max number of the `getdata` messages per second.
+    pub getdata_per_sec: u32,
+    /// Max size of the received transactions in bytes per second.
+    pub tx_bytes_per_sec: u32,
+    /// Number of the messages dropped due to the limits after which the peer is banned.
+    pub ban_threshold: u32,
+}
+
+impl Default for P2pRateLimitsConfig {
+    fn default() -> Self {
+        let limits = client::RateLimits::default();
+
+        Self {
+            inv_items_per_sec: limits.inv_items_per_sec,
+            getdata_per_sec: limits.getdata_per_sec,
+            tx_bytes_per_sec: limits.tx_bytes_per_sec,
+            ban_threshold: limits.ban_threshold,
+        }
+    }
+}
+
+impl From<&P2pRateLimitsConfig> for client::RateLimits {
+    fn from(config: &P2pRateLimitsConfig) -> Self {
+        Self {
+            inv_items_per_sec: config.inv_items_per_sec,
+            getdata_per_sec: config.getdata_per_sec,
+            tx_bytes_per_sec: config.tx_bytes_per_sec,
+            ban_threshold: config.ban_threshold,
+        }
+    }
 }
 
 fn default_max_inbound_connections() -> usize {
@@ -33,11 +158,44 @@ fn default_max_outbound_connections() -> usize {
     DEFAULT_MAX_OUTBOUND_CONNECTIONS
 }
 
+fn default_min_outbound_connections() -> usize {
+    DEFAULT_MIN_OUTBOUND_CONNECTIONS
+}
+
 impl P2pConfig {
+    /// Transport of the connections, selected by the scheme of the listen address.
+    pub fn transport(&self) -> P2pTransport {
+        split_transport(&self.address).0
+    }
+
     pub fn to_client_config(&self, network: Network) -> eyre::Result<client::P2PConfig> {
-        let bootnodes: Vec<SocketAddr> = self
+        if self.min_outbound_connections > self.max_outbound_connections {
+            bail!("Minimum amount of outbound connections exceeds the maximum");
+        }
+
+        let (transport, address) = split_transport(&self.address);
+        if transport == P2pTransport::Quic && self.proxy.is_some() {
+            bail!("QUIC transport doesn't support the proxy");
+        }
+
+        let bootnodes = self
             .bootnodes
             .iter()
+            .map(|bootnode| {
+                let (bootnode_transport, bootnode) = split_transport(bootnode);
+                if bootnode_transport != transport {
+                    bail!("Bootnode {bootnode} uses a different transport than the listen address");
+                }
+
+                Ok(bootnode)
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let (onion_bootnodes, bootnodes): (Vec<_>, Vec<_>) =
+            bootnodes.into_iter().partition(|x| is_onion(x));
+
+        let bootnodes: Vec<SocketAddr> = bootnodes
+            .into_iter()
             .map(|x| {
                 x.to_socket_addrs()
                     .wrap_err("Failed to resolve bootnode address")
@@ -47,19 +205,68 @@ impl P2pConfig {
             .flatten()
             .collect();
 
-        let address = self
-            .address
+        let address = address
             .to_socket_addrs()
             .wrap_err("Failed to resolve address")?
             .next()
             .ok_or_eyre("No address found in listen address")?;
 
-        Ok(client::P2PConfig::new(
+        let onion_bootnodes = onion_bootnodes
+            .into_iter()
+            .map(|x| x.parse::<OnionAddr>())
+            .collect::<Result<Vec<_>, _>>()
+            .wrap_err("Failed to parse onion bootnode address")?;
+
+        let mut config = client::P2PConfig::new(
             network,
             address,
             bootnodes,
             self.max_inbound_connections,
             self.max_outbound_connections,
-        ))
+            self.dns_seeds.clone(),
+        )
+        .with_min_outbound_peers(self.min_outbound_connections)
+        .with_rate_limits((&self.rate_limits).into())
+        .with_reconciliation(self.reconciliation)
+        .with_compact_relay(self.compact_relay)
+        .with_compression(self.compression);
+
+        if let Some(auth) = &self.auth {
+            config = config.with_auth(auth.to_client_config()?);
+        }
+
+        let Some(proxy) = &self.proxy else {
+            if !onion_bootnodes.is_empty() {
+                bail!("Onion bootnodes require the proxy to be set");
+            }
+
+            return Ok(config);
+        };
+
+        let proxy = proxy
+            .to_socket_addrs()
+            .wrap_err("Failed to resolve proxy address")?
+            .next()
+            .ok_or_eyre("No address found in proxy address")?;
+
+        Ok(config.with_proxy(proxy, onion_bootnodes))
+    }
+}
+
+/// Split the transport scheme off the address. Addresses without the scheme use TCP.
+fn split_transport(addr: &str) -> (P2pTransport, &str) {
+    if let Some(addr) = addr.strip_prefix("quic://") {
+        return (P2pTransport::Quic, addr);
     }
+
+    (
+        P2pTransport::Tcp,
+        addr.strip_prefix("tcp://").unwrap_or(addr),
+    )
+}
+
+/// Check whether the address has the onion host, i.e. `<host>.onion:<port>`.
+fn is_onion(addr: &str) -> bool {
+    addr.rsplit_once(':')
+        .is_some_and(|(host, _)| host.ends_with(".onion"))
 }
@@ -1,6 +1,14 @@
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use yuv_rpc_server::{
+    auth::{AuthConfig, Credentials, WalletKey, DEFAULT_PROTECTED_METHODS},
+    limits::{Quota, RateLimitConfig},
+    TlsConfig,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct RpcConfig {
@@ -14,6 +22,201 @@ pub struct RpcConfig {
     /// Maximum request size in kilobytes
     #[serde(default = "default_max_request_size_kb")]
     pub max_request_size_kb: u32,
+
+    /// Maximum response size in kilobytes
+    #[serde(default = "default_max_response_size_kb")]
+    pub max_response_size_kb: u32,
+
+    /// Maximum number of transactions per batch request
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+
+    /// Maximum number of the parents sent along with the transaction to
+    /// `sendrawyuvtxwithparents`
+    #[serde(default = "default_max_bundle_parents")]
+    pub max_bundle_parents: usize,
+
+    /// Maximum size of the transaction with its parents in bytes
+    #[serde(default = "default_max_bundle_size")]
+    pub max_bundle_size: usize,
+
+    /// Address of the read-only REST gateway, which is served only if the node is built with
+    /// the `rest` feature
+    #[serde(default)]
+    pub rest_address: Option<SocketAddr>,
+
+    /// Authentication of the calls of the protected methods. If not set, all the methods are
+    /// public
+    #[serde(default)]
+    pub auth: Option<RpcAuthConfig>,
+
+    /// Certificate and key to serve the RPC over TLS, which is supported only if the node is
+    /// built with the `tls` feature
+    #[serde(default)]
+    pub tls: Option<RpcTlsConfig>,
+
+    /// Origins allowed to call the RPC from the browsers, e.g. `https://wallet.example.com`, or
+    /// `*` for any
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+
+    /// Limit of the requests per client
+    #[serde(default)]
+    pub rate_limit: Option<RpcRateLimitConfig>,
+
+    /// Maximum time of handling the request in seconds
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+
+    /// Maximum number of simultaneous connections
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+
+    /// Maximum number of calls in the batch request
+    #[serde(default)]
+    pub max_batch_requests: Option<u32>,
+}
+
+impl RpcConfig {
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout_secs.map(Duration::from_secs)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcAuthConfig {
+    /// Credentials the clients authenticate with
+    pub credentials: RpcCredentials,
+
+    /// Methods that require the credentials, `["*"]` for all of them. By default, the ones that
+    /// change the state of the node
+    #[serde(default = "default_protected_methods")]
+    pub protected_methods: Vec<String>,
+
+    /// Bearer tokens of the tenants' wallets, which grant access only to the wallet methods, e.g.
+    /// `providewalletyuvproofs`, made on behalf of the wallet with the key's label
+    #[serde(default)]
+    pub wallet_keys: Vec<RpcWalletKeyConfig>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcWalletKeyConfig {
+    /// Label of the wallet, unique per tenant
+    pub label: String,
+
+    /// Token in the `Authorization: Bearer <token>` header
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RpcCredentials {
+    /// HTTP Basic authentication
+    Basic { username: String, password: String },
+    /// Bearer token in the `Authorization` header
+    Bearer { token: String },
+}
+
+impl From<&RpcAuthConfig> for AuthConfig {
+    fn from(config: &RpcAuthConfig) -> Self {
+        let credentials = match &config.credentials {
+            RpcCredentials::Basic { username, password } => Credentials::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            },
+            RpcCredentials::Bearer { token } => Credentials::Bearer {
+                token: token.clone(),
+            },
+        };
+
+        let wallet_keys = config
+            .wallet_keys
+            .iter()
+            .map(|key| WalletKey {
+                label: key.label.clone(),
+                token: key.token.clone(),
+            })
+            .collect();
+
+        AuthConfig::new(credentials)
+            .with_protected_methods(config.protected_methods.clone())
+            .with_wallet_keys(wallet_keys)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcRateLimitConfig {
+    /// Maximum number of requests per second from a client. If not set, only the methods are
+    /// limited
+    #[serde(default)]
+    pub requests_per_sec: Option<f64>,
+
+    /// Maximum number of requests a client can send at once, after being idle
+    #[serde(default = "default_rate_limit_burst")]
+    pub burst: u32,
+
+    /// Quotas of the calls of the methods per client, e.g. for `providelistyuvproofs`
+    #[serde(default)]
+    pub methods: HashMap<String, RpcQuotaConfig>,
+
+    /// Whether the clients are told apart by the `X-Forwarded-For` header, set it only if the
+    /// node is reachable through the reverse proxy only
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcQuotaConfig {
+    /// Maximum number of calls per second from a client, can be fractional
+    pub requests_per_sec: f64,
+
+    /// Maximum number of calls a client can make at once, after being idle
+    #[serde(default = "default_rate_limit_burst")]
+    pub burst: u32,
+}
+
+impl From<&RpcQuotaConfig> for Quota {
+    fn from(config: &RpcQuotaConfig) -> Self {
+        Self {
+            requests_per_sec: config.requests_per_sec,
+            burst: config.burst,
+        }
+    }
+}
+
+impl From<&RpcRateLimitConfig> for RateLimitConfig {
+    fn from(config: &RpcRateLimitConfig) -> Self {
+        Self {
+            requests: config.requests_per_sec.map(|requests_per_sec| Quota {
+                requests_per_sec,
+                burst: config.burst,
+            }),
+            methods: config
+                .methods
+                .iter()
+                .map(|(method, quota)| (method.clone(), quota.into()))
+                .collect(),
+            trust_forwarded_for: config.trust_forwarded_for,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcTlsConfig {
+    /// Path to the PEM encoded certificate chain
+    pub cert_path: PathBuf,
+
+    /// Path to the PEM encoded private key
+    pub key_path: PathBuf,
+}
+
+impl From<&RpcTlsConfig> for TlsConfig {
+    fn from(config: &RpcTlsConfig) -> Self {
+        Self {
+            cert_path: config.cert_path.clone(),
+            key_path: config.key_path.clone(),
+        }
+    }
 }
 
 fn default_max_items_per_request() -> usize {
@@ -23,3 +226,34 @@ fn default_max_items_per_request() -> usize {
 fn default_max_request_size_kb() -> u32 {
     20480
 }
+
+fn default_max_response_size_kb() -> u32 {
+    10240
+}
+
+fn default_max_connections() -> u32 {
+    100
+}
+
+fn default_rate_limit_burst() -> u32 {
+    20
+}
+
+fn default_protected_methods() -> Vec<String> {
+    DEFAULT_PROTECTED_METHODS
+        .iter()
+        .map(|method| method.to_string())
+        .collect()
+}
+
+fn default_max_batch_size() -> usize {
+    yuv_rpc_server::transactions::DEFAULT_MAX_BATCH_SIZE
+}
+
+fn default_max_bundle_parents() -> usize {
+    yuv_rpc_server::transactions::DEFAULT_MAX_BUNDLE_PARENTS
+}
+
+fn default_max_bundle_size() -> usize {
+    yuv_rpc_server::transactions::DEFAULT_MAX_BUNDLE_SIZE
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+use yuv_scheduler::Schedule;
+
+/// Name of the job that cleans up the outdated transactions waiting for their parents to attach.
+pub const GRAPH_BUILDER_CLEAN_UP_JOB: &str = "graph_builder_clean_up";
+/// Name of the job that cleans up the transactions waiting for confirmation for too long.
+pub const TX_CONFIRMATOR_CLEAN_UP_JOB: &str = "tx_confirmator_clean_up";
+/// Name of the job that prunes the spent transactions, if the pruning is enabled.
+pub const STORAGE_PRUNING_JOB: &str = "storage_pruning";
+/// Name of the job that checks the bitcoin nodes, if the fallback nodes are configured.
+pub const BITCOIN_HEALTH_CHECK_JOB: &str = "bitcoin_health_check";
+/// Name of the job that re-checks the issuances after the owners of their chromas have changed.
+pub const REVALIDATION_JOB: &str = "revalidation";
+
+/// Default interval of the graph builder's clean up.
+pub const DEFAULT_GRAPH_BUILDER_CLEAN_UP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Default interval of the pruning of the spent transactions.
+pub const DEFAULT_STORAGE_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Default interval of the health check of the bitcoin nodes.
+pub const DEFAULT_BITCOIN_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default interval of the re-validation of the issuances.
+pub const DEFAULT_REVALIDATION_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default, Deserialize)]
+pub struct SchedulerConfig {
+    /// Schedules of the maintenance jobs by their names. Jobs that are not listed run with
+    /// their default schedules.
+    #[serde(default)]
+    pub jobs: HashMap<String, Schedule>,
+}
+
+impl SchedulerConfig {
+    /// Returns the configured schedule of the job, or runs it with the default interval.
+    pub fn schedule(&self, job: &str, default_interval: Duration) -> Schedule {
+        self.jobs
+            .get(job)
+            .cloned()
+            .unwrap_or(Schedule::Every(default_interval))
+    }
+}
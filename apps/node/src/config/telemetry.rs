@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct TelemetryConfig {
+    /// Address of the OTLP gRPC collector, e.g. Jaeger or Tempo.
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+
+    /// Ratio of the traces to export, from `0.0` to `1.0`. The traces continued from the RPC
+    /// requests follow the sampling decision of the caller.
+    #[serde(default = "default_sampling_ratio")]
+    pub sampling_ratio: f64,
+
+    /// Name of the node in the exported traces.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+fn default_endpoint() -> String {
+    "http://127.0.0.1:4317".to_string()
+}
+
+fn default_sampling_ratio() -> f64 {
+    1.0
+}
+
+fn default_service_name() -> String {
+    "yuvd".to_string()
+}
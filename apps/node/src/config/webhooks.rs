@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use yuv_webhooks::{Webhook, DEFAULT_MAX_ATTEMPTS, DEFAULT_MAX_QUEUE_SIZE, DEFAULT_TIMEOUT};
+
+#[derive(Deserialize)]
+pub struct WebhooksConfig {
+    /// Webhooks the notifications are posted to.
+    #[serde(default)]
+    pub endpoints: Vec<Webhook>,
+
+    /// Number of the attempts to deliver the notification after which it's dropped.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Number of the queued notifications after which the oldest ones are dropped.
+    #[serde(default = "default_max_queue_size")]
+    pub max_queue_size: usize,
+
+    /// Time to wait for the response of the webhook, in seconds.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    DEFAULT_MAX_ATTEMPTS
+}
+
+fn default_max_queue_size() -> usize {
+    DEFAULT_MAX_QUEUE_SIZE
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT.as_secs()
+}
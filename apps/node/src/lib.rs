@@ -0,0 +1,9 @@
+//! The YUV node.
+//!
+//! The binary runs the node from the command line, and the library allows to run it in-process,
+//! e.g. in the integration tests, see the `yuv-test-harness` crate.
+mod cli;
+pub mod config;
+
+pub use cli::{run, Node};
+pub use config::NodeConfig;
@@ -1,9 +1,6 @@
 use eyre::Result;
 
-mod cli;
-pub(crate) mod config;
-
 #[tokio::main]
 async fn main() -> Result<()> {
-    cli::run().await
+    yuvd::run().await
 }
@@ -18,9 +18,10 @@ use yuv_storage::LevelDB;
 use yuv_tx_attach::GraphBuilder;
 use yuv_tx_check::TxChecker;
 use yuv_types::messages::p2p::Inventory;
+use yuv_types::network::Network;
 use yuv_types::{
-    ControllerMessage, ControllerP2PMessage, GraphBuilderMessage, TxCheckerMessage,
-    TxConfirmMessage, YuvTransaction,
+    ControllerMessage, ControllerP2PMessage, GraphBuilderMessage, RpcSubscriptionMessage,
+    TxCheckerMessage, TxConfirmMessage, YuvTransaction,
 };
 
 mod common;
@@ -107,6 +108,7 @@ pub fn init_event_bus() -> EventBus {
     event_bus.register::<TxConfirmMessage>(None);
     event_bus.register::<ControllerMessage>(None);
     event_bus.register::<GraphBuilderMessage>(None);
+    event_bus.register::<RpcSubscriptionMessage>(None);
 
     event_bus
 }
@@ -127,7 +129,12 @@ fn spawn_tx_checker(
     state_storage: LevelDB,
     cancellation: CancellationToken,
 ) -> eyre::Result<()> {
-    let tx_checker = TxChecker::new(event_bus.clone(), txs_storage, state_storage);
+    let tx_checker = TxChecker::new(
+        event_bus.clone(),
+        txs_storage,
+        state_storage,
+        Network::Regtest,
+    );
 
     tokio::spawn(tx_checker.run(cancellation));
     Ok(())
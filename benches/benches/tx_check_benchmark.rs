@@ -12,7 +12,8 @@ use tokio_util::sync::CancellationToken;
 
 use yuv_storage::LevelDB;
 use yuv_tx_check::TxChecker;
-use yuv_types::{ControllerMessage, GraphBuilderMessage, TxCheckerMessage};
+use yuv_types::network::Network;
+use yuv_types::{ControllerMessage, GraphBuilderMessage, RpcSubscriptionMessage, TxCheckerMessage};
 
 use crate::tx_generator::TxGenerator;
 
@@ -57,7 +58,12 @@ async fn spawn_tx_checker(
     state_storage: LevelDB,
     cancellation: CancellationToken,
 ) -> eyre::Result<()> {
-    let tx_checker = TxChecker::new(event_bus.clone(), txs_storage, state_storage);
+    let tx_checker = TxChecker::new(
+        event_bus.clone(),
+        txs_storage,
+        state_storage,
+        Network::Regtest,
+    );
 
     tokio::spawn(tx_checker.run(cancellation));
     Ok(())
@@ -69,6 +75,7 @@ pub fn init_event_bus() -> EventBus {
     event_bus.register::<GraphBuilderMessage>(None);
     event_bus.register::<ControllerMessage>(None);
     event_bus.register::<TxCheckerMessage>(None);
+    event_bus.register::<RpcSubscriptionMessage>(None);
 
     event_bus
 }
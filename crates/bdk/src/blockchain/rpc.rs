@@ -260,7 +260,8 @@ impl ConfigurableBlockchain for RpcBlockchain {
         let blockchain_info = client.get_blockchain_info()?;
         let network = match blockchain_info.chain.as_str() {
             "main" => Network::Bitcoin,
-            "test" => Network::Testnet,
+            // Testnet4 has the same addresses as the testnet.
+            "test" | "testnet4" => Network::Testnet,
             "regtest" => Network::Regtest,
             "signet" => Network::Signet,
             _ => return Err(Error::Generic("Invalid network".to_string())),
@@ -38,10 +38,9 @@
 //! section, we will take a brief tour of BDK, summarizing the major APIs and
 //! their uses.
 //!
-//! The easiest way to get started is to add bdk to your dependencies with the default features.
-//! The default features include a simple key-value database ([`sled`](sled)) to cache
-//! blockchain data and an [electrum](https://docs.rs/electrum-client/) blockchain client to
-//! interact with the bitcoin P2P network.
+//! Unlike upstream, the default features of this vendored version include neither the key-value
+//! database ([`sled`](sled)) nor the [electrum](https://docs.rs/electrum-client/) blockchain
+//! client. Blockchain backends and databases must be enabled with their features explicitly.
 //!
 //! # Examples
 #![cfg_attr(
@@ -2,14 +2,19 @@ use async_trait::async_trait;
 use bitcoin::hashes::hex;
 use bitcoin::secp256k1;
 use log::Level::{Debug, Trace, Warn};
-use log::{debug, log_enabled, trace};
+use log::{debug, log_enabled, trace, warn};
 use serde::*;
 use std::fs::File;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
+use crate::retry::{CircuitBreaker, CircuitBreakerConfig, RetryPolicy};
 use crate::{BitcoinRpcApi, JsonRpcError};
 
+/// Error code of the node that is still loading, e.g. verifying the blocks after the restart.
+const RPC_IN_WARMUP: i32 = -28;
+
 /// The different authentication methods for the client.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -51,20 +56,54 @@ impl Auth {
     }
 }
 
+/// Default max number of blocks the endpoint can be behind the best one.
+pub const DEFAULT_MAX_BLOCK_LAG: u64 = 2;
+
 /// Client implements a JSON-RPC client for the Bitcoin Core daemon or compatible APIs.
+///
+/// The client can have several endpoints: the primary one and the
+/// [fallbacks](Client::with_fallback). The requests are sent to the active endpoint, and when
+/// it's unavailable the client fails over to the next one.
 pub struct Client {
-    client: jsonrpc::client::Client,
+    /// Endpoints of the servers, the first one is the primary.
+    endpoints: Vec<jsonrpc::client::Client>,
+    /// Index of the endpoint the requests are sent to.
+    active: AtomicUsize,
+    /// Max number of blocks the endpoint can be behind the best one to stay active.
+    max_block_lag: u64,
+    /// Deadline of each attempt of the request.
+    timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<CircuitBreaker>,
 }
 
 impl Client {
-    /// Creates a client to a bitcoind JSON-RPC server.
+    /// Creates a client to a bitcoind JSON-RPC server. Each attempt of the request fails with
+    /// [`Error::Timeout`] if it isn't finished in the `timeout`.
     ///
     /// Can only return [Err] when using cookie authentication.
     pub async fn new(auth: Auth, url: String, timeout: Option<Duration>) -> Result<Self> {
+        Self::new_with_proxy(auth, url, timeout, None).await
+    }
+
+    /// Creates a client to a bitcoind JSON-RPC server, which sends the requests through
+    /// the proxy, e.g. `socks5h://127.0.0.1:9050` to connect through Tor.
+    ///
+    /// Returns [Err] when using cookie authentication or when the proxy URL is invalid.
+    pub async fn new_with_proxy(
+        auth: Auth,
+        url: String,
+        timeout: Option<Duration>,
+        proxy: Option<String>,
+    ) -> Result<Self> {
         let mut client = jsonrpc::http::reqwest_http::Builder::new()
             .url(&url)
             .map_err(|e| Error::JsonRpc(e.into()))?;
 
+        if let Some(proxy) = proxy {
+            client = client.proxy(&proxy).map_err(|e| Error::JsonRpc(e.into()))?;
+        }
+
         if let Some((user, pass)) = auth.get_user_pass()? {
             client = client.auth(user, Some(pass));
         }
@@ -73,18 +112,90 @@ impl Client {
             client = client.timeout(timeout);
         }
 
+        let transport = client.build().map_err(|e| Error::JsonRpc(e.into()))?;
+
         Ok(Self {
-            client: jsonrpc::client::Client::with_transport(client.build()),
+            timeout,
+            ..Self::from_jsonrpc(jsonrpc::client::Client::with_transport(transport))
         })
     }
 
     pub fn from_jsonrpc(client: jsonrpc::client::Client) -> Self {
-        Self { client }
+        Self {
+            endpoints: vec![client],
+            active: AtomicUsize::new(0),
+            max_block_lag: DEFAULT_MAX_BLOCK_LAG,
+            timeout: None,
+            retry_policy: RetryPolicy::none(),
+            circuit_breaker: None,
+        }
+    }
+
+    /// Sets the policy of retrying the requests that failed because the node is unavailable.
+    /// The requests are not retried by default.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
     }
 
-    /// Get the underlying JSONRPC client.
+    /// Enables the circuit breaker that fails the requests immediately while the node is
+    /// unavailable.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(CircuitBreaker::new(config));
+        self
+    }
+
+    /// Adds the endpoints of the `fallback` client, which are used when the endpoints before
+    /// them are unavailable. The rest of the `fallback`'s settings are ignored.
+    pub fn with_fallback(mut self, fallback: Client) -> Self {
+        self.endpoints.extend(fallback.endpoints);
+        self
+    }
+
+    /// Sets the max number of blocks the endpoint can be behind the best one, see
+    /// [`Client::check_endpoints`].
+    pub fn with_max_block_lag(mut self, max_block_lag: u64) -> Self {
+        self.max_block_lag = max_block_lag;
+        self
+    }
+
+    /// Get the underlying JSONRPC client of the active endpoint.
     pub fn get_jsonrpc_client(&self) -> &jsonrpc::client::Client {
-        &self.client
+        &self.endpoints[self.active.load(Ordering::Relaxed)]
+    }
+
+    /// Checks the block heights of the endpoints, and switches to the first one that is
+    /// reachable and isn't behind the best one by more than the max block lag. So the client
+    /// returns to the primary endpoint once it's healthy again.
+    ///
+    /// Returns the heights of the endpoints, `None` for the unavailable ones.
+    pub async fn check_endpoints(&self) -> Vec<Option<u64>> {
+        let mut heights = Vec::with_capacity(self.endpoints.len());
+        for endpoint in 0..self.endpoints.len() {
+            let height = self.send::<u64>(endpoint, "getblockcount", &[], &[]).await;
+            heights.push(height.ok());
+        }
+
+        let Some(best_height) = heights.iter().flatten().max().copied() else {
+            return heights;
+        };
+
+        let healthy = heights.iter().position(|height| {
+            height.is_some_and(|height| height.saturating_add(self.max_block_lag) >= best_height)
+        });
+
+        if let Some(endpoint) = healthy {
+            self.switch_to(endpoint);
+        }
+
+        heights
+    }
+
+    fn switch_to(&self, endpoint: usize) {
+        let previous = self.active.swap(endpoint, Ordering::Relaxed);
+        if previous != endpoint {
+            warn!(target: "bitcoincore_rpc", "Switched from the endpoint {:?} to {:?}", self.endpoints[previous], self.endpoints[endpoint]);
+        }
     }
 }
 
@@ -96,16 +207,92 @@ impl BitcoinRpcApi for Client {
         cmd: &str,
         args: &[serde_json::Value],
     ) -> Result<T> {
+        if let Some(open_for) = self.circuit_breaker.as_ref().and_then(|cb| cb.open_for()) {
+            return Err(Error::CircuitOpen(open_for));
+        }
+
         let v_args: Vec<_> = args
             .iter()
             .map(serde_json::value::to_raw_value)
             .collect::<std::result::Result<_, serde_json::Error>>()?;
-        let req = self.client.build_request(cmd, &v_args[..]);
+
+        let mut retry = 0;
+        let result = loop {
+            let err = match self.send_with_failover(cmd, &v_args, args).await {
+                Err(err) if err.is_unavailable() && retry < self.retry_policy.max_retries => err,
+                result => break result,
+            };
+
+            let backoff = self.retry_policy.backoff(retry);
+            warn!(target: "bitcoincore_rpc", "JSON-RPC request {} failed, retrying in {:?}: {}", cmd, backoff, err);
+
+            tokio::time::sleep(backoff).await;
+            retry += 1;
+        };
+
+        if let Some(circuit_breaker) = &self.circuit_breaker {
+            match &result {
+                Err(err) if err.is_unavailable() => circuit_breaker.on_failure(),
+                _ => circuit_breaker.on_success(),
+            }
+        }
+
+        result
+    }
+}
+
+impl Client {
+    /// Sends the request to the active endpoint, and to the next ones while the endpoints are
+    /// unavailable. The endpoint that responded becomes active.
+    async fn send_with_failover<T: for<'a> de::Deserialize<'a>>(
+        &self,
+        cmd: &str,
+        v_args: &[Box<serde_json::value::RawValue>],
+        args: &[serde_json::Value],
+    ) -> Result<T> {
+        let active = self.active.load(Ordering::Relaxed);
+
+        let mut offset = 0;
+        loop {
+            let endpoint = (active + offset) % self.endpoints.len();
+
+            match self.send(endpoint, cmd, v_args, args).await {
+                Err(err) if err.is_unavailable() && offset + 1 < self.endpoints.len() => {
+                    warn!(target: "bitcoincore_rpc", "Endpoint {:?} is unavailable: {}", self.endpoints[endpoint], err);
+                    offset += 1;
+                }
+                result => {
+                    if offset > 0 && !matches!(&result, Err(err) if err.is_unavailable()) {
+                        self.switch_to(endpoint);
+                    }
+
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Sends one attempt of the request to the endpoint.
+    async fn send<T: for<'a> de::Deserialize<'a>>(
+        &self,
+        endpoint: usize,
+        cmd: &str,
+        v_args: &[Box<serde_json::value::RawValue>],
+        args: &[serde_json::Value],
+    ) -> Result<T> {
+        let client = &self.endpoints[endpoint];
+        let req = client.build_request(cmd, v_args);
         if log_enabled!(Debug) {
             debug!(target: "bitcoincore_rpc", "JSON-RPC request: {} {}", cmd, serde_json::Value::from(args));
         }
 
-        let resp = self.client.send_request(req).await.map_err(Error::from);
+        let resp = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, client.send_request(req))
+                .await
+                .map_err(|_| Error::Timeout(timeout))
+                .and_then(|resp| resp.map_err(Error::from)),
+            None => client.send_request(req).await.map_err(Error::from),
+        };
         log_response(cmd, &resp);
         Ok(resp?.result()?)
     }
@@ -170,6 +357,28 @@ pub enum Error {
 
     #[error("Unsupported version Bitcoin Core RPC")]
     UnsupportedVersion,
+
+    /// The request wasn't finished before the deadline.
+    #[error("request timed out after {0:?}")]
+    Timeout(Duration),
+
+    /// The request wasn't sent, as the node was unavailable for the previous requests.
+    #[error("circuit breaker is open for {0:?}, the node is unavailable")]
+    CircuitOpen(Duration),
+}
+
+impl Error {
+    /// Returns `true` if the request failed because the node is unavailable: it's not
+    /// reachable, doesn't respond in time or is still loading. Such requests can succeed later,
+    /// so the caller should wait rather than fail.
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            Error::Timeout(_) | Error::CircuitOpen(_) => true,
+            Error::JsonRpc(JsonRpcError::Transport(_)) => true,
+            Error::JsonRpc(JsonRpcError::Rpc(err)) => err.code == RPC_IN_WARMUP,
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -255,4 +464,65 @@ mod tests {
     fn test_handle_defaults() {
         test_handle_defaults_inner().unwrap();
     }
+
+    /// Transport of the endpoint at the block `height`, `None` if it's unreachable.
+    struct StubTransport(Option<u64>);
+
+    #[async_trait]
+    impl jsonrpc::Transport for StubTransport {
+        async fn send_request(
+            &self,
+            req: jsonrpc::Request<'_>,
+        ) -> std::result::Result<jsonrpc::Response, JsonRpcError> {
+            let Some(height) = self.0 else {
+                return Err(JsonRpcError::Transport("connection refused".into()));
+            };
+
+            Ok(jsonrpc::Response {
+                result: Some(serde_json::value::to_raw_value(&height)?),
+                error: None,
+                id: req.id,
+                jsonrpc: Some("2.0".into()),
+            })
+        }
+
+        async fn send_batch(
+            &self,
+            _: &[jsonrpc::Request<'_>],
+        ) -> std::result::Result<Vec<jsonrpc::Response>, JsonRpcError> {
+            unimplemented!()
+        }
+
+        fn fmt_target(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+
+    fn stub_client(height: Option<u64>) -> Client {
+        Client::from_jsonrpc(jsonrpc::Client::with_transport(StubTransport(height)))
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_reachable_endpoint() {
+        let client = stub_client(None)
+            .with_fallback(stub_client(Some(100)))
+            .with_fallback(stub_client(Some(101)));
+
+        assert_eq!(client.get_block_count().await.unwrap(), 100);
+        assert_eq!(client.active.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoints_skips_lagging_endpoint() {
+        let client = stub_client(Some(90))
+            .with_fallback(stub_client(None))
+            .with_fallback(stub_client(Some(100)))
+            .with_max_block_lag(2);
+
+        assert_eq!(
+            client.check_endpoints().await,
+            vec![Some(90), None, Some(100)]
+        );
+        assert_eq!(client.active.load(Ordering::Relaxed), 2);
+    }
 }
@@ -1,5 +1,10 @@
 mod client;
-pub use client::{Auth as BitcoinRpcAuth, Client as BitcoinRpcClient, Error, Result};
+pub use client::{
+    Auth as BitcoinRpcAuth, Client as BitcoinRpcClient, Error, Result, DEFAULT_MAX_BLOCK_LAG,
+};
+
+mod retry;
+pub use retry::{CircuitBreakerConfig, RetryPolicy};
 
 mod rpc_api;
 pub use rpc_api::{RawTx, RpcApi as BitcoinRpcApi};
@@ -0,0 +1,174 @@
+//! Retries of the failed requests and the circuit breaker that stops sending the requests to
+//! the unavailable node.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Policy of retrying the requests that failed because the node is unavailable, see
+/// [`Error::is_unavailable`](crate::Error::is_unavailable). The delay before each retry grows
+/// exponentially from `initial_backoff` up to `max_backoff`, with a random jitter of up to half
+/// of the delay, so the clients don't retry at the same time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Max delay between the retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Policy that doesn't retry the requests.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    /// Returns the delay before the retry with the number `retry`, starting from 0.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+
+        let jitter = fastrand::u64(0..=backoff.as_millis() as u64 / 2);
+
+        backoff.saturating_sub(Duration::from_millis(jitter))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Configuration of the circuit breaker. After `failure_threshold` consecutive requests failed
+/// because the node is unavailable, the circuit opens and the requests fail immediately with
+/// [`Error::CircuitOpen`](crate::Error::CircuitOpen) for the `cooldown`. Then the requests are
+/// let through again: the circuit closes once one of them succeeds, and opens again after
+/// a failure.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Number of the consecutive failed requests that open the circuit.
+    pub failure_threshold: u32,
+    /// Time the circuit stays open.
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<CircuitState>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CircuitState::default()),
+        }
+    }
+
+    /// Returns the time left until the circuit lets the request through, `None` if the request
+    /// can be sent.
+    pub(crate) fn open_for(&self) -> Option<Duration> {
+        let state = self.state.lock().expect("lock should not be poisoned");
+
+        let opened_at = state.opened_at?;
+        self.config.cooldown.checked_sub(opened_at.elapsed())
+    }
+
+    pub(crate) fn on_success(&self) {
+        let mut state = self.state.lock().expect("lock should not be poisoned");
+
+        state.failures = 0;
+        state.opened_at = None;
+    }
+
+    pub(crate) fn on_failure(&self) {
+        let mut state = self.state.lock().expect("lock should not be poisoned");
+
+        state.failures = state.failures.saturating_add(1);
+        if state.failures >= self.config.failure_threshold {
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_bounded() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+
+        for retry in 0..10 {
+            let expected =
+                (Duration::from_millis(100) * 2u32.pow(retry)).min(Duration::from_secs(1));
+            let backoff = policy.backoff(retry);
+
+            assert!(backoff <= expected);
+            assert!(backoff >= expected / 2);
+        }
+    }
+
+    #[test]
+    fn test_circuit_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(60),
+        });
+
+        breaker.on_failure();
+        assert_eq!(breaker.open_for(), None);
+
+        breaker.on_failure();
+        assert!(breaker.open_for().is_some());
+
+        breaker.on_success();
+        assert_eq!(breaker.open_for(), None);
+    }
+
+    #[test]
+    fn test_circuit_lets_request_through_after_cooldown() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            cooldown: Duration::ZERO,
+        });
+
+        breaker.on_failure();
+        assert_eq!(breaker.open_for(), None);
+    }
+}
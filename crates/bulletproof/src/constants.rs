@@ -2,12 +2,23 @@ extern crate alloc;
 
 use alloc::vec::Vec;
 
-use k256::elliptic_curve::{ff::PrimeField, group::GroupEncoding, sec1::FromEncodedPoint};
+use k256::elliptic_curve::{
+    ff::PrimeField,
+    group::GroupEncoding,
+    hash2curve::{ExpandMsgXmd, GroupDigest},
+    sec1::FromEncodedPoint,
+};
 use sha2::{Digest, Sha256};
 
 /// The size in bytes of a range proof.
 pub const RANGE_PROOF_SIZE: usize = 128;
 
+/// Max number of values in an aggregated range proof.
+pub const MAX_AGGREGATED_VALUES: usize = 16;
+
+/// Domain separation tag of the generators derived for the aggregated range proofs.
+const GENERATORS_DST: &[u8] = b"yuv-bulletproof-generators";
+
 /// The group of points generated by G.
 /// This is used to calculate the seed parameter from blinding factors.
 ///
@@ -1082,6 +1093,32 @@ lazy_static::lazy_static! {
                 .expect("valid point")
         })
         .collect::<Vec<_>>();
+
+    /// [`H_BOLD1`] extended with the points derived for the aggregated range proofs.
+    pub static ref H_BOLD_AGGREGATED: Vec<k256::ProjectivePoint> =
+        extend_generators(&H_BOLD1, b"H");
+
+    /// [`G_BOLD1`] extended with the points derived for the aggregated range proofs.
+    pub static ref G_BOLD_AGGREGATED: Vec<k256::ProjectivePoint> =
+        extend_generators(&G_BOLD1, b"G");
+}
+
+/// Extend the generators up to the size of the largest aggregated range proof by hashing the
+/// label and the index of the point to the curve, so nobody knows the discrete logarithms of them.
+fn extend_generators(points: &[k256::ProjectivePoint], label: &[u8]) -> Vec<k256::ProjectivePoint> {
+    let mut output = points.to_vec();
+    for i in points.len()..RANGE_PROOF_SIZE * MAX_AGGREGATED_VALUES {
+        let index = (i as u32).to_le_bytes();
+        let point = k256::Secp256k1::hash_from_bytes::<ExpandMsgXmd<Sha256>>(
+            &[label, &index],
+            &[GENERATORS_DST],
+        )
+        .expect("valid point");
+
+        output.push(point);
+    }
+
+    output
 }
 
 pub(crate) fn to_point(point: &[u8]) -> Option<k256::ProjectivePoint> {
@@ -8,22 +8,25 @@ use alloc::vec::Vec;
 
 // TODO: get rid of k256. Only secp256k1 and own scalar implementation can be used.
 pub use k256;
-use k256::{elliptic_curve::ff::PrimeField, FieldBytes, ProjectivePoint, PublicKey, Scalar};
+use k256::{ProjectivePoint, PublicKey, Scalar};
+use zeroize::Zeroizing;
 
+pub use constants::MAX_AGGREGATED_VALUES;
 pub use range_proof::RangeProof;
+pub use secret::SecretScalar;
 
 mod constants;
 mod range_proof;
+mod secret;
 pub mod util;
 mod vec_ops;
 mod wip;
 
 /// Generate a range proof for a value with a blinding factor.
 pub fn generate(value: u128, blinding: [u8; 32]) -> (RangeProof, ProjectivePoint) {
-    let blinding =
-        Scalar::from_repr(*FieldBytes::from_slice(&blinding)).expect("blinding is a valid scalar");
+    let blinding = secret_blinding(Zeroizing::new(blinding));
 
-    range_proof::generate(value, blinding)
+    range_proof::generate(value, &blinding)
 }
 
 /// Verify a range proof with a commitment.
@@ -31,15 +34,39 @@ pub fn verify(commit: ProjectivePoint, proof: RangeProof) -> bool {
     range_proof::verify(commit, proof)
 }
 
+/// Generate one range proof for several values with their blinding factors. Returns the proof
+/// and the commitments to the values in the same order.
+///
+/// # Panics
+///
+/// If there are no values or more than [`MAX_AGGREGATED_VALUES`] of them.
+pub fn generate_aggregated(values: &[(u128, [u8; 32])]) -> (RangeProof, Vec<ProjectivePoint>) {
+    let values = values
+        .iter()
+        .map(|(value, blinding)| (*value, secret_blinding(Zeroizing::new(*blinding))))
+        .collect::<Vec<_>>();
+
+    range_proof::generate_aggregated(&values)
+}
+
+/// Verify an aggregated range proof with the commitments in the order of the proved values.
+pub fn verify_aggregated(commits: &[ProjectivePoint], proof: RangeProof) -> bool {
+    range_proof::verify_aggregated(commits, proof)
+}
+
 /// Commit to a value with a blinding factor.
 ///
 /// v * G + r * H
 pub fn commit(value: u128, blinding: [u8; 32]) -> ProjectivePoint {
     let v = Scalar::from(value);
-    let r =
-        Scalar::from_repr(*FieldBytes::from_slice(&blinding)).expect("blinding is a valid scalar");
+    let r = secret_blinding(Zeroizing::new(blinding));
 
-    range_proof::commit(v, r)
+    range_proof::commit(v, *r.expose())
+}
+
+/// Parse the blinding factor, which bytes are zeroized after that.
+fn secret_blinding(blinding: Zeroizing<[u8; 32]>) -> SecretScalar {
+    SecretScalar::from_bytes(&blinding).expect("blinding is a valid scalar")
 }
 
 /// Verify that the sum of the commitments is equal to the verifier.
@@ -84,4 +111,41 @@ mod tests {
 
         assert!(!super::verify(wrong_commit, proof));
     }
+
+    #[test]
+    fn test_aggregated_verification() {
+        let values = [(100, [1u8; 32]), (u128::MAX, [2u8; 32]), (0, [3u8; 32])];
+
+        let (proof, commits) = super::generate_aggregated(&values);
+
+        assert_eq!(commits.len(), values.len());
+        assert_eq!(commits[0], super::commit(100, [1u8; 32]));
+        assert!(super::verify_aggregated(&commits, proof.clone()));
+
+        let de_proof =
+            RangeProof::from_bytes(&proof.to_bytes()).expect("proof is a valid range proof");
+
+        assert_eq!(proof, de_proof);
+
+        let mut wrong_commits = commits.clone();
+        wrong_commits[1] = super::commit(101, [2u8; 32]);
+
+        assert!(!super::verify_aggregated(&wrong_commits, proof.clone()));
+        assert!(!super::verify_aggregated(&commits[..2], proof.clone()));
+        assert!(!super::verify(commits[0], proof));
+    }
+
+    #[test]
+    fn test_secret_scalar_zeroize() {
+        use zeroize::Zeroize;
+
+        let mut secret =
+            super::SecretScalar::from_bytes(&[1u8; 32]).expect("bytes are a valid scalar");
+
+        assert_eq!(alloc::format!("{secret:?}"), "SecretScalar(..)");
+
+        secret.zeroize();
+
+        assert_eq!(*secret.expose(), super::Scalar::ZERO);
+    }
 }
@@ -3,11 +3,20 @@ extern crate alloc;
 use alloc::vec;
 use alloc::vec::Vec;
 
-use k256::elliptic_curve::{ff::Field, group::GroupEncoding};
+use k256::elliptic_curve::{
+    ff::Field,
+    group::GroupEncoding,
+    subtle::{Choice, ConditionallySelectable},
+};
 use merlin::Transcript;
 use rand::rngs::OsRng;
+use zeroize::Zeroizing;
 
-use crate::constants::{hash_to_point, to_point, G, G_BOLD1, H, H_BOLD1, RANGE_PROOF_SIZE};
+use crate::constants::{
+    hash_to_point, to_point, G, G_BOLD1, G_BOLD_AGGREGATED, H, H_BOLD1, H_BOLD_AGGREGATED,
+    MAX_AGGREGATED_VALUES, RANGE_PROOF_SIZE,
+};
+use crate::secret::{zeroize_terms, SecretScalar};
 use crate::vec_ops::VecOps;
 use crate::wip::{WipProof, WipStmt};
 
@@ -64,23 +73,24 @@ fn calculate_point(
 }
 
 /// Generate a range proof for a value with a blinding factor.
-pub fn generate(value: u128, blinding: k256::Scalar) -> (RangeProof, k256::ProjectivePoint) {
+pub fn generate(value: u128, blinding: &SecretScalar) -> (RangeProof, k256::ProjectivePoint) {
     let mut transcript = Transcript::new(b"range_proof");
 
     // v * G + r * H
-    let commit = commit(value.into(), blinding);
+    let commit = commit(value.into(), *blinding.expose());
 
     // alpha = X: Ω -> R
-    let alpha = k256::Scalar::random(&mut OsRng);
+    let alpha = Zeroizing::new(k256::Scalar::random(&mut OsRng));
 
-    let a_l = calculate_a_l(value);
+    let a_l = Zeroizing::new(calculate_a_l(value));
     // a_r = { x - 1 | x ∈ a_l }
-    let a_r = a_l.sub(&k256::Scalar::ONE);
+    let a_r = Zeroizing::new(a_l.sub(&k256::Scalar::ONE));
 
-    let a_terms = calculate_a_terms(alpha, &a_l, &a_r);
+    let mut a_terms = calculate_a_terms(*alpha, &a_l, &a_r, &H_BOLD1, &G_BOLD1);
 
     // a = exp(a_terms)
     let a = multiexp::multiexp(&a_terms);
+    zeroize_terms(&mut a_terms);
 
     // y = X: hash(a || v)
     let y = calculate_point(a, b"y", &mut transcript);
@@ -90,10 +100,10 @@ pub fn generate(value: u128, blinding: k256::Scalar) -> (RangeProof, k256::Proje
     let (two_descending_y, y_n_plus_one, a_hat) = calculate_a_hat(commit, a, y, z);
 
     // a_l = { x - z | x ∈ a_l}
-    let a_l = a_l.sub(&z);
-    let a_r = a_r.add_all(&two_descending_y.add(&z));
+    let a_l = Zeroizing::new(a_l.sub(&z));
+    let a_r = Zeroizing::new(a_r.add_all(&two_descending_y.add(&z)));
     // alpha = alpha + (r * y * n + 1)
-    let alpha = alpha + (blinding * y_n_plus_one);
+    let alpha = Zeroizing::new(*alpha + (blinding.expose() * y_n_plus_one));
 
     let wip_stmt = WipStmt::new(a_hat, y);
     let proof = RangeProof {
@@ -106,6 +116,12 @@ pub fn generate(value: u128, blinding: k256::Scalar) -> (RangeProof, k256::Proje
 
 /// Verify a range proof with a commitment.
 pub(crate) fn verify(commit: k256::ProjectivePoint, proof: RangeProof) -> bool {
+    // The proofs of another size, e.g. the aggregated ones, can't be verified with the
+    // generators of the single range proof.
+    if proof.wip.rounds() != RANGE_PROOF_SIZE.trailing_zeros() as usize {
+        return false;
+    }
+
     let mut transcript = Transcript::new(b"range_proof");
     let mut verifier = multiexp::BatchVerifier::new(1);
 
@@ -121,11 +137,126 @@ pub(crate) fn verify(commit: k256::ProjectivePoint, proof: RangeProof) -> bool {
     verifier.verify_vartime()
 }
 
+/// Generate an aggregated range proof for the values with the blinding factors.
+///
+/// # Panics
+///
+/// If there are no values or more than [`MAX_AGGREGATED_VALUES`] of them.
+pub fn generate_aggregated(
+    values: &[(u128, SecretScalar)],
+) -> (RangeProof, Vec<k256::ProjectivePoint>) {
+    assert!(
+        !values.is_empty() && values.len() <= MAX_AGGREGATED_VALUES,
+        "invalid number of aggregated values"
+    );
+
+    let mut transcript = Transcript::new(b"aggregated_range_proof");
+
+    let commits = values
+        .iter()
+        .map(|(value, blinding)| commit((*value).into(), *blinding.expose()))
+        .collect::<Vec<_>>();
+    append_commits(&commits, &mut transcript);
+
+    let size = aggregated_size(values.len());
+    let h_bold = H_BOLD_AGGREGATED[..size].to_vec();
+    let g_bold = G_BOLD_AGGREGATED[..size].to_vec();
+
+    let alpha = Zeroizing::new(k256::Scalar::random(&mut OsRng));
+
+    // The bits of the values one after another, padded with zero values to the power of two.
+    let mut a_l = Zeroizing::new(Vec::with_capacity(size));
+    for (value, _) in values {
+        a_l.extend(Zeroizing::new(calculate_a_l(*value)).iter());
+    }
+    a_l.resize(size, k256::Scalar::ZERO);
+    let a_r = Zeroizing::new(a_l.sub(&k256::Scalar::ONE));
+
+    let mut a_terms = calculate_a_terms(*alpha, &a_l, &a_r, &h_bold, &g_bold);
+    let a = multiexp::multiexp(&a_terms);
+    zeroize_terms(&mut a_terms);
+
+    let y = calculate_point(a, b"y", &mut transcript);
+    let z = calculate_point(a, b"z", &mut transcript);
+
+    let (d_descending_y, commit_scalars, a_hat) =
+        calculate_aggregated_a_hat(&commits, a, y, z, &h_bold, &g_bold);
+
+    let a_l = Zeroizing::new(a_l.sub(&z));
+    let a_r = Zeroizing::new(a_r.add_all(&d_descending_y.add(&z)));
+    let alpha = Zeroizing::new(
+        *alpha
+            + values
+                .iter()
+                .zip(&commit_scalars)
+                .map(|((_, blinding), scalar)| blinding.expose() * scalar)
+                .sum::<k256::Scalar>(),
+    );
+
+    let wip_stmt = WipStmt::with_generators(a_hat, y, h_bold, g_bold);
+    let proof = RangeProof {
+        a,
+        wip: wip_stmt.generate(a_l, a_r, alpha, &mut transcript),
+    };
+
+    (proof, commits)
+}
+
+/// Verify an aggregated range proof with the commitments in the order of the proved values.
+pub(crate) fn verify_aggregated(commits: &[k256::ProjectivePoint], proof: RangeProof) -> bool {
+    if commits.is_empty() || commits.len() > MAX_AGGREGATED_VALUES {
+        return false;
+    }
+
+    let size = aggregated_size(commits.len());
+    if proof.wip.rounds() != size.trailing_zeros() as usize {
+        return false;
+    }
+
+    let mut transcript = Transcript::new(b"aggregated_range_proof");
+    let mut verifier = multiexp::BatchVerifier::new(1);
+
+    append_commits(commits, &mut transcript);
+
+    let h_bold = H_BOLD_AGGREGATED[..size].to_vec();
+    let g_bold = G_BOLD_AGGREGATED[..size].to_vec();
+
+    let y = calculate_point(proof.a, b"y", &mut transcript);
+    let z = calculate_point(proof.a, b"z", &mut transcript);
+
+    let (_, _, a_hat) = calculate_aggregated_a_hat(commits, proof.a, y, z, &h_bold, &g_bold);
+
+    let stmt = WipStmt::with_generators(a_hat, y, h_bold, g_bold);
+    stmt.verify(proof.wip, &mut verifier, &mut transcript);
+
+    verifier.verify_vartime()
+}
+
+/// Number of the bits proved by an aggregated range proof of `values` values.
+fn aggregated_size(values: usize) -> usize {
+    values.next_power_of_two() * RANGE_PROOF_SIZE
+}
+
+/// Bind the transcript to the commitments of the aggregated values.
+fn append_commits(commits: &[k256::ProjectivePoint], transcript: &mut Transcript) {
+    transcript.append_u64(b"m", commits.len() as u64);
+    for commit in commits {
+        transcript.append_message(b"v", commit.to_bytes().as_ref());
+    }
+}
+
 /// A_l = {x| 0 <= x < 128, x = v >> i & 1}
+///
+/// The bits are selected in constant time, as the value is secret.
 fn calculate_a_l(value: u128) -> Vec<k256::Scalar> {
     let mut output = vec![];
     for i in 0..128 {
-        output.push(k256::Scalar::from((value >> i) & 1));
+        let bit = Choice::from(((value >> i) & 1) as u8);
+        output.push(k256::Scalar::conditional_select(
+            &k256::Scalar::ZERO,
+            &k256::Scalar::ONE,
+            bit,
+        ));
     }
 
     output
@@ -136,13 +267,15 @@ fn calculate_a_terms(
     alpha: k256::Scalar,
     a_l: &[k256::Scalar],
     a_r: &[k256::Scalar],
+    h_bold: &[k256::ProjectivePoint],
+    g_bold: &[k256::ProjectivePoint],
 ) -> Vec<(k256::Scalar, k256::ProjectivePoint)> {
     let mut output = vec![];
     for (i, a_l) in a_l.iter().enumerate() {
-        output.push((*a_l, H_BOLD1[i]));
+        output.push((*a_l, h_bold[i]));
     }
     for (i, a_r) in a_r.iter().enumerate() {
-        output.push((*a_r, G_BOLD1[i]));
+        output.push((*a_r, g_bold[i]));
     }
 
     output.push((alpha, *G));
@@ -196,3 +329,69 @@ fn calculate_a_hat(
         a + multiexp::multiexp_vartime(&a_terms),
     )
 }
+
+/// Calculate a_hat seed parameters of an aggregated range proof. Returns the `d * -Y` vector,
+/// the scalars of the commitments and a_hat.
+fn calculate_aggregated_a_hat(
+    commits: &[k256::ProjectivePoint],
+    a: k256::ProjectivePoint,
+    y: k256::Scalar,
+    z: k256::Scalar,
+    h_bold: &[k256::ProjectivePoint],
+    g_bold: &[k256::ProjectivePoint],
+) -> (Vec<k256::Scalar>, Vec<k256::Scalar>, k256::ProjectivePoint) {
+    let size = h_bold.len();
+
+    // powers = [1, 2, 2^2, ..., 2^127]
+    let powers = Vec::<k256::Scalar>::new_power(k256::Scalar::from(2u128), RANGE_PROOF_SIZE);
+
+    // z_powers = [z^2, z^4, ..., z^2m]
+    let z_square = z.square();
+    let mut z_powers = vec![z_square];
+    for j in 1..size / RANGE_PROOF_SIZE {
+        z_powers.push(z_powers[j - 1] * z_square);
+    }
+
+    // d = z^2 * powers || z^4 * powers || ... || z^2m * powers
+    let d = z_powers
+        .iter()
+        .flat_map(|z_power| powers.mul(z_power))
+        .collect::<Vec<_>>();
+
+    let mut y_vec = vec![y];
+    for i in 1..size {
+        y_vec.push(y_vec[i - 1] * y);
+    }
+
+    let y_vec_inv = y_vec.iter().rev().copied().collect::<Vec<_>>();
+    let y_inv_mul = y_vec_inv[0] * y;
+    let y_sum = y_vec.iter().sum::<k256::Scalar>();
+
+    let d_y_inv = d.mul_all(&y_vec_inv);
+    let mut a_terms = Vec::with_capacity((size * 2) + commits.len() + 1);
+    for (i, scalar) in d_y_inv.add(&z).drain(..).enumerate() {
+        a_terms.push((-z, h_bold[i]));
+        a_terms.push((scalar, g_bold[i]));
+    }
+
+    // The padding values are committed with zero blinding factors, so their commitments are
+    // identity points and are omitted.
+    let commit_scalars = z_powers
+        .iter()
+        .take(commits.len())
+        .map(|z_power| y_inv_mul * z_power)
+        .collect::<Vec<_>>();
+    for (scalar, commit) in commit_scalars.iter().zip(commits) {
+        a_terms.push((*scalar, *commit));
+    }
+
+    let last_term = (y_sum * (z - z_square)) - (d.iter().sum::<k256::Scalar>() * y_inv_mul * z);
+
+    a_terms.push((last_term, *H));
+
+    (
+        d_y_inv,
+        commit_scalars,
+        a + multiexp::multiexp_vartime(&a_terms),
+    )
+}
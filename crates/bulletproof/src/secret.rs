@@ -0,0 +1,65 @@
+//! Secret values of the range proofs, e.g. the blinding factors of the commitments.
+
+use core::fmt;
+
+use k256::{
+    elliptic_curve::{ff::PrimeField, subtle::ConstantTimeEq},
+    FieldBytes, ProjectivePoint, Scalar,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Scalar that must be kept secret. It's zeroized on drop, isn't printed by `Debug` and is
+/// compared in constant time.
+#[derive(Clone)]
+pub struct SecretScalar(Scalar);
+
+impl SecretScalar {
+    pub fn new(scalar: Scalar) -> Self {
+        Self(scalar)
+    }
+
+    /// Parse the scalar from the big-endian bytes. Returns `None` if they are out of the field.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        Option::from(Scalar::from_repr(*FieldBytes::from_slice(bytes))).map(Self)
+    }
+
+    /// The inner scalar. Copies of it aren't zeroized, so they must not outlive the wrapper.
+    pub fn expose(&self) -> &Scalar {
+        &self.0
+    }
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+impl PartialEq for SecretScalar {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for SecretScalar {}
+
+impl fmt::Debug for SecretScalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretScalar(..)")
+    }
+}
+
+/// Zeroize the secret scalars of the multiexponentiation terms.
+pub(crate) fn zeroize_terms(terms: &mut [(Scalar, ProjectivePoint)]) {
+    for (scalar, _) in terms.iter_mut() {
+        scalar.zeroize();
+    }
+}
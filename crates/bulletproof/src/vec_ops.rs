@@ -4,6 +4,9 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 /// Advanced vector operations
+///
+/// The operations branch only on the lengths of the vectors and never on the values, so they are
+/// constant-time for the secret scalars, e.g. the bits of the proven value.
 pub trait VecOps
 where
     Self: Sized,
@@ -6,9 +6,11 @@ use alloc::vec::Vec;
 use k256::elliptic_curve::{ff::Field, ff::PrimeField, group::GroupEncoding};
 use merlin::Transcript;
 use rand::rngs::OsRng;
+use zeroize::Zeroizing;
 
 use super::{
-    constants::{hash_to_point, to_point, to_scalar, G, G_BOLD1, H, H_BOLD1, RANGE_PROOF_SIZE},
+    constants::{hash_to_point, to_point, to_scalar, G, G_BOLD1, H, H_BOLD1},
+    secret::zeroize_terms,
     vec_ops::VecOps,
 };
 
@@ -17,6 +19,8 @@ use super::{
 pub struct WipStmt {
     a_hat: k256::ProjectivePoint,
     y: Vec<k256::Scalar>,
+    h_bold: Vec<k256::ProjectivePoint>,
+    g_bold: Vec<k256::ProjectivePoint>,
 }
 
 /// Indexed scalars used to simplify calculations
@@ -85,6 +89,11 @@ impl WipProof {
         bytes
     }
 
+    /// Number of the rounds of the proof.
+    pub(crate) fn rounds(&self) -> usize {
+        self.l.len()
+    }
+
     pub fn from_bytes(data: &[u8]) -> Option<Self> {
         let a = to_point(&data[..33])?;
         let b = to_point(&data[33..66])?;
@@ -117,14 +126,29 @@ impl WipProof {
 
 impl WipStmt {
     pub(crate) fn new(a_hat: k256::ProjectivePoint, y: k256::Scalar) -> Self {
-        let mut y_vec = Vec::<k256::Scalar>::with_cap(RANGE_PROOF_SIZE);
+        Self::with_generators(a_hat, y, H_BOLD1.clone(), G_BOLD1.clone())
+    }
+
+    /// Create a statement over the given generators, which number must be a power of two.
+    pub(crate) fn with_generators(
+        a_hat: k256::ProjectivePoint,
+        y: k256::Scalar,
+        h_bold: Vec<k256::ProjectivePoint>,
+        g_bold: Vec<k256::ProjectivePoint>,
+    ) -> Self {
+        let mut y_vec = Vec::<k256::Scalar>::with_cap(h_bold.len());
 
         y_vec[0] = y;
         for i in 1..y_vec.len() {
             y_vec[i] = y_vec[i - 1] * y;
         }
 
-        Self { a_hat, y: y_vec }
+        Self {
+            a_hat,
+            y: y_vec,
+            h_bold,
+            g_bold,
+        }
     }
 
     /// iteratively calculate the next generation of points
@@ -192,20 +216,21 @@ impl WipStmt {
         p_terms.push((inv_e_square, r));
     }
 
-    /// Generate a weighted inner product proof
+    /// Generate a weighted inner product proof. The witness is secret, so it and the nonces are
+    /// zeroized when they aren't needed anymore.
     pub fn generate(
         self,
-        a_l: Vec<k256::Scalar>,
-        a_r: Vec<k256::Scalar>,
-        alpha: k256::Scalar,
+        a_l: Zeroizing<Vec<k256::Scalar>>,
+        a_r: Zeroizing<Vec<k256::Scalar>>,
+        alpha: Zeroizing<k256::Scalar>,
         transcript: &mut Transcript,
     ) -> WipProof {
         let mut y = self.y;
         let h = *H;
         let g = *G;
 
-        let mut h_bold = H_BOLD1.clone();
-        let mut g_bold = G_BOLD1.clone();
+        let mut h_bold = self.h_bold;
+        let mut g_bold = self.g_bold;
 
         let mut a_l = a_l;
         let mut a_r = a_r;
@@ -214,8 +239,8 @@ impl WipStmt {
         let mut l_vec = vec![];
         let mut r_vec = vec![];
         while h_bold.len() > 1 {
-            let (a_1, a_2) = a_l.clone().split();
-            let (b_1, b_2) = a_r.clone().split();
+            let (a_1, a_2) = split_secret(&a_l);
+            let (b_1, b_2) = split_secret(&a_r);
             let (h_bold1, h_bold2) = split_points(h_bold);
             let (g_bold1, g_bold2) = split_points(g_bold);
 
@@ -224,8 +249,8 @@ impl WipStmt {
             let y_n_hat = y[n_hat - 1];
             y.shrink_to(n_hat);
 
-            let d_l = k256::Scalar::random(&mut OsRng);
-            let d_r = k256::Scalar::random(&mut OsRng);
+            let d_l = Zeroizing::new(k256::Scalar::random(&mut OsRng));
+            let d_r = Zeroizing::new(k256::Scalar::random(&mut OsRng));
 
             let c_l = a_1.wip(&b_2, &y);
             let c_r = a_2.mul(&y_n_hat).wip(&b_1, &y);
@@ -239,8 +264,9 @@ impl WipStmt {
                 .chain(b_2.iter().copied().zip(g_bold1.iter().copied()))
                 .collect::<Vec<_>>();
             l_terms.push((c_l, h));
-            l_terms.push((d_l, g));
+            l_terms.push((*d_l, g));
             let l = multiexp::multiexp(&l_terms);
+            zeroize_terms(&mut l_terms);
             l_vec.push(l);
 
             let mut r_terms = a_2
@@ -250,8 +276,9 @@ impl WipStmt {
                 .chain(b_1.iter().copied().zip(g_bold2.iter().copied()))
                 .collect::<Vec<_>>();
             r_terms.push((c_r, h));
-            r_terms.push((d_r, g));
+            r_terms.push((*d_r, g));
             let r = multiexp::multiexp(&r_terms);
+            zeroize_terms(&mut r_terms);
             r_vec.push(r);
 
             let p;
@@ -269,30 +296,32 @@ impl WipStmt {
             let p_square = p.square();
             let p_square_inv = p_inv.square();
 
-            a_l = a_1.mul(&p).add_all(&a_2.mul(&(y_n_hat * p_inv)));
-            a_r = b_1.mul(&p_inv).add_all(&b_2.mul(&p));
-            alpha += (d_l * p_square) + (d_r * p_square_inv);
+            a_l = Zeroizing::new(a_1.mul(&p).add_all(&a_2.mul(&(y_n_hat * p_inv))));
+            a_r = Zeroizing::new(b_1.mul(&p_inv).add_all(&b_2.mul(&p)));
+            *alpha += (*d_l * p_square) + (*d_r * p_square_inv);
         }
 
-        let r = k256::Scalar::random(&mut OsRng);
-        let s = k256::Scalar::random(&mut OsRng);
-        let delta = k256::Scalar::random(&mut OsRng);
-        let n = k256::Scalar::random(&mut OsRng);
+        let r = Zeroizing::new(k256::Scalar::random(&mut OsRng));
+        let s = Zeroizing::new(k256::Scalar::random(&mut OsRng));
+        let delta = Zeroizing::new(k256::Scalar::random(&mut OsRng));
+        let n = Zeroizing::new(k256::Scalar::random(&mut OsRng));
 
-        let r_y = r * y[0];
+        let r_y = Zeroizing::new(*r * y[0]);
 
-        let a_l_terms = vec![
-            (r, h_bold[0]),
-            (s, g_bold[0]),
-            ((r_y * a_r[0]) + (s * y[0] * a_l[0]), h),
-            (delta, g),
+        let mut a_l_terms = vec![
+            (*r, h_bold[0]),
+            (*s, g_bold[0]),
+            ((*r_y * a_r[0]) + (*s * y[0] * a_l[0]), h),
+            (*delta, g),
         ];
 
         let a = multiexp::multiexp(&a_l_terms);
+        zeroize_terms(&mut a_l_terms);
 
-        let a_r_terms = vec![(r_y * s, h), (n, g)];
+        let mut a_r_terms = vec![(*r_y * *s, h), (*n, g)];
 
         let b = multiexp::multiexp(&a_r_terms);
+        zeroize_terms(&mut a_r_terms);
 
         let p = sync_points(a, b, transcript);
 
@@ -301,9 +330,9 @@ impl WipStmt {
             r: r_vec,
             a,
             b,
-            r_rev: r + (a_l[0] * p),
-            s_rev: s + (a_r[0] * p),
-            delta_rev: n + (delta * p) + (alpha * p.square()),
+            r_rev: *r + (a_l[0] * p),
+            s_rev: *s + (a_r[0] * p),
+            delta_rev: *n + (*delta * p) + (*alpha * p.square()),
         }
     }
 
@@ -315,9 +344,10 @@ impl WipStmt {
         transcript: &mut Transcript,
     ) {
         let mut a_hat_terms = vec![(k256::Scalar::ONE, self.a_hat)];
+        let size = self.h_bold.len();
 
-        let mut indexed_g_bold = IndexedScalars::with_capacity(RANGE_PROOF_SIZE);
-        let mut indexed_h_bold = IndexedScalars::with_capacity(RANGE_PROOF_SIZE);
+        let mut indexed_g_bold = IndexedScalars::with_capacity(size);
+        let mut indexed_h_bold = IndexedScalars::with_capacity(size);
 
         proof.l.iter().zip(proof.r.iter()).for_each(|(l, r)| {
             let n_hat = (indexed_g_bold.positions.len() + (indexed_g_bold.positions.len() % 2)) / 2;
@@ -341,12 +371,12 @@ impl WipStmt {
             *scalar *= -a_b.square();
         }
 
-        for i in 0..RANGE_PROOF_SIZE {
-            multiexp.push((indexed_g_bold.inner[i] * proof.r_rev * a_b, H_BOLD1[i]));
+        for i in 0..size {
+            multiexp.push((indexed_g_bold.inner[i] * proof.r_rev * a_b, self.h_bold[i]));
         }
 
-        for i in 0..RANGE_PROOF_SIZE {
-            multiexp.push((indexed_h_bold.inner[i] * proof.s_rev * a_b, G_BOLD1[i]));
+        for i in 0..size {
+            multiexp.push((indexed_h_bold.inner[i] * proof.s_rev * a_b, self.g_bold[i]));
         }
 
         multiexp.push((-a_b, proof.a));
@@ -358,6 +388,15 @@ impl WipStmt {
     }
 }
 
+/// Split the secret vector into two halves, see [`VecOps::split`].
+fn split_secret(
+    vec: &[k256::Scalar],
+) -> (Zeroizing<Vec<k256::Scalar>>, Zeroizing<Vec<k256::Scalar>>) {
+    let (left, right) = Zeroizing::new(vec.to_vec()).split();
+
+    (Zeroizing::new(left), Zeroizing::new(right))
+}
+
 pub fn split_points(
     mut points: Vec<k256::ProjectivePoint>,
 ) -> (Vec<k256::ProjectivePoint>, Vec<k256::ProjectivePoint>) {
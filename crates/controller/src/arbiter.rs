@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use bitcoin::Txid;
+
+/// Origin of the transaction submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubmissionOrigin {
+    /// Transaction is submitted via JSON RPC.
+    Rpc,
+    /// Transaction is received from the P2P peer.
+    Peer(SocketAddr),
+}
+
+impl SubmissionOrigin {
+    /// Returns the address of the peer if the transaction is received from the P2P network.
+    pub fn peer(&self) -> Option<SocketAddr> {
+        match self {
+            Self::Rpc => None,
+            Self::Peer(addr) => Some(*addr),
+        }
+    }
+}
+
+impl From<Option<SocketAddr>> for SubmissionOrigin {
+    fn from(sender: Option<SocketAddr>) -> Self {
+        match sender {
+            Some(addr) => Self::Peer(addr),
+            None => Self::Rpc,
+        }
+    }
+}
+
+/// Arbiter of the transactions submissions keyed by [`Txid`].
+///
+/// The same transaction could be submitted via JSON RPC and received from several peers at
+/// the same time. The arbiter lets only the first submission into the pipeline and merges the
+/// rest into it, so each transaction is processed at most once concurrently. All the origins
+/// are recorded, so every peer that sent an invalid transaction could be punished.
+///
+/// Clones of the arbiter share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct SubmissionArbiter {
+    /// Transactions that are being processed by the pipeline with their submissions origins.
+    in_flight: Arc<Mutex<HashMap<Txid, Vec<SubmissionOrigin>>>>,
+}
+
+impl SubmissionArbiter {
+    /// Registers the submission of the transaction. Returns `true` if the transaction isn't
+    /// processed yet and should be sent to the pipeline, `false` if the submission is merged
+    /// into the one that is already in flight.
+    pub fn submit(&self, txid: Txid, origin: SubmissionOrigin) -> bool {
+        let mut in_flight = self.in_flight.lock().expect("lock should not be poisoned");
+
+        match in_flight.get_mut(&txid) {
+            Some(origins) => {
+                if !origins.contains(&origin) {
+                    origins.push(origin);
+                }
+
+                false
+            }
+            None => {
+                in_flight.insert(txid, vec![origin]);
+
+                true
+            }
+        }
+    }
+
+    /// Returns `true` if the transaction is being processed by the pipeline.
+    pub fn is_in_flight(&self, txid: &Txid) -> bool {
+        self.in_flight
+            .lock()
+            .expect("lock should not be poisoned")
+            .contains_key(txid)
+    }
+
+    /// Returns all the recorded origins of the transaction that is in flight.
+    pub fn origins(&self, txid: &Txid) -> Vec<SubmissionOrigin> {
+        self.in_flight
+            .lock()
+            .expect("lock should not be poisoned")
+            .get(txid)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Marks the transaction as processed, so it could be submitted again. Returns all the
+    /// recorded origins of the transaction.
+    pub fn complete(&self, txid: &Txid) -> Vec<SubmissionOrigin> {
+        self.in_flight
+            .lock()
+            .expect("lock should not be poisoned")
+            .remove(txid)
+            .unwrap_or_default()
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 use std::{collections::VecDeque, net::SocketAddr};
 
@@ -11,14 +11,28 @@ use tracing::trace;
 
 use yuv_p2p::client::handle::Handle as ClientHandle;
 use yuv_storage::{
-    InventoryStorage, MempoolEntryStorage, MempoolStatus, MempoolStorage, MempoolTxEntry,
-    PagesNumberStorage, PagesStorage, TransactionsStorage,
+    AddressIndexStorage, AttachStorage, ChromaInfoStorage, DelegatesStorage, FrozenPubkeysStorage,
+    FrozenTxsStorage, InventoryStorage, MempoolEntryStorage, MempoolStatus, MempoolStorage,
+    MempoolTxEntry, PagesNumberStorage, PagesStorage, PruningStorage, SupplyTreeStorage,
+    TransactionsStorage, TransferHistoryStorage, TxDependentsStorage,
 };
 use yuv_types::{
-    messages::p2p::Inventory, ControllerMessage, ControllerP2PMessage, TxConfirmMessage,
-    YuvTransaction, YuvTxType,
+    messages::p2p::{CompactYuvTxs, GetYuvTxn, Inventory},
+    ControllerMessage, ControllerP2PMessage, TxConfirmMessage, YuvTransaction, YuvTxType,
 };
-use yuv_types::{Announcement, GraphBuilderMessage, IndexerMessage, TxCheckerMessage};
+use yuv_types::{
+    Announcement, GraphBuilderMessage, IndexerMessage, RpcSubscriptionMessage, TxCheckerMessage,
+};
+
+use crate::arbiter::{SubmissionArbiter, SubmissionOrigin};
+use crate::ordering::canonical_order;
+
+mod address_index;
+mod freezes;
+mod history;
+mod pruning;
+mod rollback;
+mod supply;
 
 /// Default inventory size.
 const DEFAULT_INV_SIZE: usize = 100;
@@ -55,12 +69,51 @@ where
 
     /// Amount of transactions that fit one page.
     tx_per_page: u64,
+
+    /// Max total output amount of the transfer that can be provisionally attached before it's
+    /// mined. `None` means that zero-conf mode is disabled.
+    zero_conf_max_amount: Option<u128>,
+
+    /// Whether the unspent outputs are indexed by the keys of their owners.
+    address_index: bool,
+
+    /// Whether the changes of the balances are recorded to the transfer history of the owners.
+    history_index: bool,
+
+    /// Whether the unspent outputs of the attached transactions are counted, so the ones with
+    /// all the outputs spent could be pruned.
+    pruning: bool,
+
+    /// Merges concurrent submissions of the same transaction from RPC and P2P.
+    arbiter: SubmissionArbiter,
 }
 
 impl<TS, SS, P2P> Controller<TS, SS, P2P>
 where
-    TS: TransactionsStorage + PagesNumberStorage + PagesStorage + Send + Sync + Clone + 'static,
-    SS: InventoryStorage + MempoolStorage + MempoolEntryStorage + Send + Sync + Clone + 'static,
+    TS: TransactionsStorage
+        + PagesNumberStorage
+        + PagesStorage
+        + TxDependentsStorage
+        + SupplyTreeStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + PruningStorage
+        + AttachStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    SS: InventoryStorage
+        + MempoolStorage
+        + MempoolEntryStorage
+        + FrozenTxsStorage
+        + FrozenPubkeysStorage
+        + DelegatesStorage
+        + ChromaInfoStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
     P2P: ClientHandle + Send + Sync + Clone + 'static,
 {
     pub fn new(
@@ -76,7 +129,8 @@ where
                     TxConfirmMessage,
                     TxCheckerMessage,
                     GraphBuilderMessage,
-                    IndexerMessage
+                    IndexerMessage,
+                    RpcSubscriptionMessage
                 ],
                 &typeid![ControllerMessage],
             )
@@ -90,6 +144,11 @@ where
             event_bus,
             p2p_handle,
             tx_per_page,
+            zero_conf_max_amount: None,
+            address_index: false,
+            history_index: false,
+            pruning: false,
+            arbiter: SubmissionArbiter::default(),
         }
     }
 
@@ -107,6 +166,39 @@ where
         self
     }
 
+    /// Sets max total output amount of the transfers that are provisionally attached from the
+    /// Bitcoin mempool. `None` disables zero-conf mode.
+    pub fn set_zero_conf_max_amount(mut self, max_amount: Option<u128>) -> Self {
+        self.zero_conf_max_amount = max_amount;
+
+        self
+    }
+
+    /// Enables the index of the unspent outputs by the keys of their owners. The transactions
+    /// attached before the index is enabled are indexed by
+    /// [`build_address_index`](Self::build_address_index).
+    pub fn set_address_index(mut self, enabled: bool) -> Self {
+        self.address_index = enabled;
+
+        self
+    }
+
+    /// Enables the transfer history of the owners of the outputs by chroma. Only the
+    /// transactions attached after the index is enabled are recorded.
+    pub fn set_history_index(mut self, enabled: bool) -> Self {
+        self.history_index = enabled;
+
+        self
+    }
+
+    /// Enables the tracking of the unspent outputs for the pruning of the spent transactions.
+    /// Only the transactions attached after the pruning is enabled are pruned.
+    pub fn set_pruning(mut self, enabled: bool) -> Self {
+        self.pruning = enabled;
+
+        self
+    }
+
     /// Runs the Controller. It listens to the events from the event bus to handle and
     /// inventory interval timer to share inventory.
     pub async fn run(mut self, cancellation: CancellationToken) {
@@ -138,8 +230,9 @@ where
         }
     }
 
-    /// Handles new events from the event bus.
-    async fn handle_event(&mut self, event: ControllerMessage) -> Result<()> {
+    /// Handles new events from the event bus. Public, so the events can be handled one by one,
+    /// e.g. by the replay of the pipeline.
+    pub async fn handle_event(&mut self, event: ControllerMessage) -> Result<()> {
         use ControllerMessage as Message;
         trace!("New event: {:?}", event);
 
@@ -171,8 +264,8 @@ where
                     .await
                     .wrap_err("failed to handle partially checked transactions")?
             }
-            Message::MinedTxs(txids) => self
-                .handle_mined_txs(txids)
+            Message::MinedTxs { txs, block_height } => self
+                .handle_mined_txs(txs, block_height)
                 .await
                 .wrap_err("failed to handle mined transactions")?,
             Message::FullyCheckedTxs(txs) => self
@@ -183,11 +276,20 @@ where
                 .handle_confirmed_txs(txids)
                 .await
                 .wrap_err("failed to handle confirmed transactions")?,
+            Message::UnconfirmedTxs(txids) => self
+                .handle_unconfirmed_txs(txids)
+                .await
+                .wrap_err("failed to handle unconfirmed transactions")?,
+            Message::ExpiredTxs(txids) => self
+                .handle_expired_txs(txids)
+                .await
+                .wrap_err("failed to handle expired transactions")?,
             Message::Reorganization {
                 txs,
+                orphaned_txs,
                 new_indexing_height,
             } => self
-                .handle_reorganization(txs, new_indexing_height)
+                .handle_reorganization(txs, orphaned_txs, new_indexing_height)
                 .await
                 .wrap_err("failed to handle reorged transactions")?,
         }
@@ -210,11 +312,57 @@ where
                 .handle_new_yuv_txs(txs, Some(sender))
                 .await
                 .wrap_err("failed to handle yuv txs")?,
+            ControllerP2PMessage::CmpctYuvTxs { txs, sender } => self
+                .handle_compact_yuv_txs(txs, sender)
+                .await
+                .wrap_err("failed to handle compact yuv txs")?,
         };
 
         Ok(())
     }
 
+    /// Finishes the attachment of the transactions that were written to the pages, but the node
+    /// was stopped before they were removed from the mempool, so they aren't attached again.
+    /// Must be called before [`handle_mempool_txs`](Self::handle_mempool_txs).
+    pub async fn recover_attached_txs(&mut self) -> eyre::Result<()> {
+        let txids = self.txs_storage.get_attach_log().await?;
+        if txids.is_empty() {
+            return Ok(());
+        }
+
+        let mut recovered_txids = Vec::with_capacity(txids.len());
+        for txid in txids {
+            // The provisionally attached transaction stays in the mempool until it's mined.
+            if let Some(entry) = self.state_storage.get_mempool_entry(&txid).await? {
+                if entry.provisionally_attached {
+                    continue;
+                }
+            }
+
+            if let Some(yuv_tx) = self.txs_storage.get_yuv_tx(&txid).await? {
+                self.txs_storage.add_yuv_tx_dependencies(&yuv_tx).await?;
+                self.freeze_continuation_outputs(&[yuv_tx]).await?;
+            }
+
+            self.state_storage.delete_mempool_entry(&txid).await?;
+            recovered_txids.push(txid);
+        }
+
+        let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
+        clear_mempool(&mut raw_mempool, &recovered_txids);
+        self.state_storage.put_mempool(raw_mempool).await?;
+
+        self.txs_storage.delete_attach_log().await?;
+
+        tracing::warn!(
+            "Recovered {} transactions whose attachment was interrupted, the supply trees and \
+            the indexes may miss them",
+            recovered_txids.len()
+        );
+
+        Ok(())
+    }
+
     /// Fetch transactions from the mempool and distribute them among the workers depending on
     /// their statuses.
     pub async fn handle_mempool_txs(&mut self) -> eyre::Result<()> {
@@ -232,6 +380,8 @@ where
                 continue;
             };
 
+            self.arbiter.submit(txid, mempool_entry.sender.into());
+
             match mempool_entry.status {
                 #[allow(deprecated)]
                 MempoolStatus::Initialized | MempoolStatus::Pending => {
@@ -247,7 +397,9 @@ where
                 // If the transaction is mined or waiting to be mined, just send it back to the
                 // confrimator.
                 _ => {
-                    self.event_bus.send(TxConfirmMessage::Txs(vec![txid])).await;
+                    self.event_bus
+                        .send(TxConfirmMessage::Txs(vec![mempool_entry.yuv_tx]))
+                        .await;
                 }
             }
             handled_txs.push(txid);
@@ -257,48 +409,66 @@ where
         Ok(())
     }
 
-    /// Handles invalid transactions. It removes them from the
-    /// [`handling_txs`](Controller::handling_txs) and if the transaction was received from the
-    /// network, it will send event to the network service that the sender peer is malicious.
+    /// Handles invalid transactions. It removes them from the mempool and if the transaction
+    /// was received from the network, it will send event to the network service that the
+    /// sender peers are malicious. All the peers that submitted the transaction are punished.
     async fn handle_invalid_txs(&self, txids: Vec<Txid>) -> Result<()> {
         let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
         clear_mempool(&mut raw_mempool, &txids);
         self.state_storage.put_mempool(raw_mempool).await?;
 
+        let mut provisional_txids = Vec::new();
         for txid in &txids {
+            let entry = self.state_storage.get_mempool_entry(txid).await?;
+            if entry
+                .as_ref()
+                .is_some_and(|entry| entry.provisionally_attached)
+            {
+                provisional_txids.push(*txid);
+            }
+
+            let entry_sender = entry.and_then(|entry| entry.sender);
             self.state_storage.delete_mempool_entry(txid).await?;
 
-            let sender_opt = self
-                .state_storage
-                .get_mempool_entry(txid)
-                .await?
-                .and_then(|entry| entry.sender);
-            let Some(sender) = sender_opt else {
-                continue;
-            };
-            self.p2p_handle.ban_peer(sender).await.wrap_err_with(|| {
-                format!(
-                    "failed to punish peer; malicious_peer={:?}; tx_ids={:?}",
-                    sender, txids,
-                )
-            })?;
+            let mut senders: HashSet<SocketAddr> = self
+                .arbiter
+                .complete(txid)
+                .iter()
+                .filter_map(SubmissionOrigin::peer)
+                .collect();
+            senders.extend(entry_sender);
+
+            for sender in senders {
+                self.p2p_handle.ban_peer(sender).await.wrap_err_with(|| {
+                    format!(
+                        "failed to punish peer; malicious_peer={:?}; tx_ids={:?}",
+                        sender, txids,
+                    )
+                })?;
+            }
         }
 
+        self.detach_provisional_txs(&provisional_txids).await?;
+
         Ok(())
     }
 
-    /// Shares inventory with the network.
+    /// Shares inventory with the network. The transactions of the inventory are shared too,
+    /// so the light peers get only the ones matching their filters.
     async fn share_inv(&self) -> Result<()> {
-        let inv: Vec<Inventory> = self
-            .state_storage
-            .get_inventory()
-            .await?
-            .iter()
-            .map(|txid| Inventory::Ytx(*txid))
-            .collect();
+        let txids = self.state_storage.get_inventory().await?;
+
+        let mut txs = Vec::with_capacity(txids.len());
+        for txid in &txids {
+            if let Some(tx) = self.is_tx_exist(txid).await? {
+                txs.push(tx);
+            }
+        }
+
+        let inv: Vec<Inventory> = txids.iter().map(|txid| Inventory::Ytx(*txid)).collect();
 
         self.p2p_handle
-            .send_inv(inv.clone())
+            .send_inv(inv.clone(), txs)
             .await
             .wrap_err_with(|| format!("failed to share inventory; inv={:?}", inv))?;
 
@@ -317,6 +487,10 @@ where
         for inv_msg in inv {
             match inv_msg {
                 Inventory::Ytx(ytx_id) => {
+                    if self.is_tx_pruned(&ytx_id).await? {
+                        continue;
+                    }
+
                     let existing_tx_opt = self
                         .is_tx_exist(&ytx_id)
                         .await
@@ -393,6 +567,61 @@ where
         Ok(())
     }
 
+    /// Handles yuv txs announced with the short ids. The prefilled ones are handled as received
+    /// in full, and the ones that are neither in the mempool nor in the inventory are requested
+    /// from the sender by their indexes.
+    async fn handle_compact_yuv_txs(
+        &mut self,
+        txs: CompactYuvTxs,
+        sender: SocketAddr,
+    ) -> Result<()> {
+        let Some(short_txids) = txs.short_txid_indexes() else {
+            tracing::debug!("Received malformed compact txs from peer: {:?}", sender);
+            return Ok(());
+        };
+
+        let mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
+        let inventory = self.state_storage.get_inventory().await?;
+        let known_short_txids: HashSet<_> = mempool
+            .iter()
+            .chain(inventory.iter())
+            .map(|txid| CompactYuvTxs::short_txid(txs.nonce, txid))
+            .collect();
+
+        let missing_indexes: Vec<u32> = short_txids
+            .into_iter()
+            .filter(|(_, short_txid)| !known_short_txids.contains(short_txid))
+            .map(|(index, _)| index)
+            .collect();
+
+        let nonce = txs.nonce;
+        let prefilled: Vec<YuvTransaction> = txs.prefilled.into_iter().map(|tx| tx.tx).collect();
+        if !prefilled.is_empty() {
+            self.handle_new_yuv_txs(prefilled, Some(sender)).await?;
+        }
+
+        if !missing_indexes.is_empty() {
+            tracing::debug!(
+                "Requesting {} of the compact txs from peer {:?}",
+                missing_indexes.len(),
+                sender
+            );
+
+            self.p2p_handle
+                .send_get_yuv_txn(
+                    GetYuvTxn {
+                        nonce,
+                        indexes: missing_indexes,
+                    },
+                    sender,
+                )
+                .await
+                .wrap_err("failed to send getyuvtxn message")?;
+        }
+
+        Ok(())
+    }
+
     /// Handles yuv txs from the network. It checks if the transaction is already handled. If
     /// not, it sends the transaction to the `TxChecker`.
     async fn handle_new_yuv_txs(
@@ -404,6 +633,27 @@ where
 
         for yuv_tx in yuv_txs {
             let tx_id = yuv_tx.bitcoin_tx.txid();
+
+            // The same transaction could be received via RPC and P2P at the same time. Only the
+            // first submission goes to the pipeline, the others are merged into it.
+            if !self.arbiter.submit(tx_id, sender.into()) {
+                tracing::debug!(
+                    txid = tx_id.to_string(),
+                    origins = ?self.arbiter.origins(&tx_id),
+                    "Tx is already being handled, submission is merged"
+                );
+                continue;
+            }
+
+            if self.is_tx_pruned(&tx_id).await? {
+                tracing::debug!(
+                    txid = tx_id.to_string(),
+                    "Tx is already attached and pruned"
+                );
+                self.arbiter.complete(&tx_id);
+                continue;
+            }
+
             let existing_tx_opt = self
                 .is_tx_exist(&tx_id)
                 .await
@@ -434,6 +684,7 @@ where
 
             if !matches!(existing_tx.tx_type, YuvTxType::Announcement(_)) {
                 tracing::debug!(txid = tx_id.to_string(), "Tx exists in the storage");
+                self.arbiter.complete(&tx_id);
                 continue;
             }
 
@@ -477,14 +728,13 @@ where
                 .wrap_err("Initialized tx is not present in the mempool")?;
 
             tx_entry.status = MempoolStatus::WaitingMined;
+            yuv_txs.push(tx_entry.yuv_tx.clone());
             self.state_storage.put_mempool_entry(tx_entry).await?;
 
             tracing::debug!(
                 txid = txid.to_string(),
                 "Tx has passed the isolated check and is waiting to be mined"
             );
-
-            yuv_txs.push(txid);
         }
 
         self.event_bus.send(TxConfirmMessage::Txs(yuv_txs)).await;
@@ -532,22 +782,29 @@ where
         Ok(())
     }
 
-    /// Sends transactions that appeared in reorged blocks back to the confirmator.
+    /// Sends transactions that appeared in reorged blocks back to the confirmator. Attached
+    /// transactions from the orphan blocks are rolled back along with their descendants.
     pub async fn handle_reorganization(
         &mut self,
         txids: Vec<Txid>,
+        orphaned_txids: Vec<Txid>,
         new_indexing_height: usize,
     ) -> Result<()> {
         self.event_bus
             .send(IndexerMessage::Reorganization(new_indexing_height))
             .await;
 
+        self.rollback_attached_txs(orphaned_txids)
+            .await
+            .wrap_err("Failed to rollback attached transactions")?;
+
         if txids.is_empty() {
             return Ok(());
         }
 
         tracing::debug!("Reorged YUV transactions: {:?}", txids);
 
+        let mut yuv_txs = Vec::new();
         for txid in &txids {
             let mut entry = self
                 .state_storage
@@ -555,10 +812,11 @@ where
                 .await?
                 .wrap_err("Reorged tx is not present in the mempool")?;
             entry.status = MempoolStatus::WaitingMined;
+            yuv_txs.push(entry.yuv_tx.clone());
             self.state_storage.put_mempool_entry(entry).await?;
         }
 
-        self.event_bus.send(TxConfirmMessage::Txs(txids)).await;
+        self.event_bus.send(TxConfirmMessage::Txs(yuv_txs)).await;
 
         Ok(())
     }
@@ -599,11 +857,19 @@ where
 
     /// Handles YUV transactions that reached one confirmation and changes their statuses from
     /// `WaitingMined` to `Mined`, then adds them to the inventory so they can be broadcasted
-    /// via P2P.
-    pub async fn handle_mined_txs(&mut self, txids: Vec<Txid>) -> Result<()> {
+    /// via P2P, and announces them to the peers that support the compact relay. The positions of
+    /// the transactions in the block are stored to order them in the pages.
+    pub async fn handle_mined_txs(
+        &mut self,
+        txs: Vec<(Txid, usize)>,
+        block_height: usize,
+    ) -> Result<()> {
         let mut txids_to_share = Vec::new();
+        let mut txs_to_announce = Vec::new();
+        // Transactions received via RPC can't be known by the peers, so they are sent in full.
+        let mut prefilled = HashSet::new();
 
-        for txid in txids {
+        for (txid, block_index) in txs {
             let mut tx_entry = self
                 .state_storage
                 .get_mempool_entry(&txid)
@@ -612,9 +878,22 @@ where
 
             if !matches!(tx_entry.yuv_tx.tx_type, YuvTxType::Announcement(_)) {
                 txids_to_share.push(txid);
+                txs_to_announce.push(tx_entry.yuv_tx.clone());
+                if tx_entry.sender.is_none() {
+                    prefilled.insert(txid);
+                }
+            }
+
+            if tx_entry.status == MempoolStatus::ProvisionallyAttached {
+                tracing::debug!(
+                    txid = txid.to_string(),
+                    "Provisionally attached tx is mined and will be re-validated"
+                );
             }
 
             tx_entry.status = MempoolStatus::Mined;
+            tx_entry.block_height = Some(block_height);
+            tx_entry.block_index = Some(block_index);
             self.state_storage.put_mempool_entry(tx_entry).await?;
         }
 
@@ -624,75 +903,257 @@ where
 
         tracing::info!("Inventory has been updated with checked and mined txs");
 
+        if !txs_to_announce.is_empty() {
+            self.p2p_handle
+                .announce_yuv_txs(txs_to_announce, prefilled)
+                .await
+                .wrap_err("failed to announce mined txs")?;
+        }
+
         Ok(())
     }
 
-    /// Handles attached transactions by removing them from the mempool.
-    pub async fn handle_attached_txs(&mut self, txids: Vec<Txid>) -> Result<()> {
-        for txid in &txids {
-            tracing::info!(txid = txid.to_string(), "Tx is attached");
-            let entry = self
-                .state_storage
-                .get_mempool_entry(txid)
-                .await?
-                .wrap_err("Attaching tx is not present in the mempool")?;
+    /// Handles YUV transactions that are found in the Bitcoin mempool, but aren't mined yet. If
+    /// zero-conf mode is enabled, eligible low-value transfers are written to the storage and
+    /// appended to the pages, and their statuses change from `WaitingMined` to
+    /// `ProvisionallyAttached`. They still go through the full check and attaching once they get
+    /// enough confirmations, which moves them to their positions in the pages.
+    pub async fn handle_unconfirmed_txs(&mut self, txids: Vec<Txid>) -> Result<()> {
+        let Some(max_amount) = self.zero_conf_max_amount else {
+            return Ok(());
+        };
 
-            self.txs_storage.put_yuv_tx(entry.yuv_tx).await?;
-            self.state_storage.delete_mempool_entry(txid).await?;
-        }
+        for txid in txids {
+            let Some(mut tx_entry) = self.state_storage.get_mempool_entry(&txid).await? else {
+                continue;
+            };
+
+            if tx_entry.status != MempoolStatus::WaitingMined
+                || tx_entry.provisionally_attached
+                || !self
+                    .is_zero_conf_eligible(&tx_entry.yuv_tx, max_amount)
+                    .await?
+            {
+                continue;
+            }
 
-        // Handle that number of transactions in batch could be more than
-        // a number of transactions in page.
-        for txs in txids.chunks(self.tx_per_page as usize) {
-            self.put_txs_ids_to_page(txs)
+            // The transaction isn't mined, so it isn't finally attached and isn't kept in the
+            // attach log, see `recover_attached_txs`.
+            self.txs_storage
+                .attach_txs(&[tx_entry.yuv_tx.clone()], &[None], self.tx_per_page)
                 .await
-                .wrap_err("Failed to store transactions in pages")?;
+                .wrap_err("Failed to store provisionally attached tx in pages")?;
+            self.txs_storage.delete_attach_log().await?;
+
+            tx_entry.status = MempoolStatus::ProvisionallyAttached;
+            tx_entry.provisionally_attached = true;
+            self.state_storage.put_mempool_entry(tx_entry).await?;
+
+            tracing::info!(txid = txid.to_string(), "Tx is provisionally attached");
         }
 
-        let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
-        clear_mempool(&mut raw_mempool, &txids);
-        self.state_storage.put_mempool(raw_mempool).await?;
+        Ok(())
+    }
+
+    /// Handles YUV transactions that were waiting for the confirmation for too long. They are
+    /// removed from the mempool, which rolls the provisionally attached ones back, and can be
+    /// submitted again.
+    pub async fn handle_expired_txs(&mut self, txids: Vec<Txid>) -> Result<()> {
+        let mut rolled_back_txs = Vec::new();
+        let mut provisional_txids = Vec::new();
+
+        for txid in txids {
+            // The expired tx leaves the pipeline, so it could be submitted again.
+            self.arbiter.complete(&txid);
+
+            let Some(tx_entry) = self.state_storage.get_mempool_entry(&txid).await? else {
+                continue;
+            };
+
+            match tx_entry.status {
+                MempoolStatus::ProvisionallyAttached => tracing::info!(
+                    txid = txid.to_string(),
+                    "Provisionally attached tx wasn't mined in time and is rolled back"
+                ),
+                MempoolStatus::WaitingMined => tracing::debug!(
+                    txid = txid.to_string(),
+                    "Tx wasn't mined in time and is removed from the mempool"
+                ),
+                _ => continue,
+            }
+
+            if tx_entry.provisionally_attached {
+                provisional_txids.push(txid);
+            }
+
+            self.state_storage.delete_mempool_entry(&txid).await?;
+            rolled_back_txs.push(txid);
+        }
+
+        if !rolled_back_txs.is_empty() {
+            let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
+            clear_mempool(&mut raw_mempool, &rolled_back_txs);
+            self.state_storage.put_mempool(raw_mempool).await?;
+        }
+
+        self.detach_provisional_txs(&provisional_txids).await?;
 
         Ok(())
     }
 
-    /// Put attached transactions ids to page storage.
-    async fn put_txs_ids_to_page(&self, txids: &[Txid]) -> eyre::Result<()> {
-        let last_page_num = self
-            .txs_storage
-            .get_pages_number()
-            .await?
-            .unwrap_or_default();
+    /// Checks if the transaction can be provisionally attached: it must be a non-bulletproof
+    /// transfer with the total output amount not greater than `max_amount`, and all its parents
+    /// must be already attached. The provisionally attached parents don't count, so the unmined
+    /// transactions are never chained.
+    async fn is_zero_conf_eligible(
+        &self,
+        yuv_tx: &YuvTransaction,
+        max_amount: u128,
+    ) -> Result<bool> {
+        let YuvTxType::Transfer {
+            input_proofs,
+            output_proofs,
+        } = &yuv_tx.tx_type
+        else {
+            return Ok(false);
+        };
 
-        let mut last_page = self
-            .txs_storage
-            .get_page_by_num(last_page_num)
-            .await?
-            .unwrap_or_default();
+        // Amounts of the bulletproof transfers are hidden, so they can't be estimated.
+        #[cfg(feature = "bulletproof")]
+        if yuv_tx.is_bulletproof() {
+            return Ok(false);
+        }
 
-        // Get space that is left in current page
-        let left_space = self.tx_per_page.saturating_sub(last_page.len() as u64);
+        let total_amount = output_proofs
+            .values()
+            .try_fold(0u128, |sum, proof| sum.checked_add(proof.amount()));
+        if !total_amount.is_some_and(|amount| amount <= max_amount) {
+            return Ok(false);
+        }
 
-        // And split attached txs into two arrays, where the first ones will
-        // be stored to current page, and other in next.
-        let (in_current_page, in_next_page) = split_at(txids, left_space as usize);
+        for input in input_proofs.keys() {
+            let Some(txin) = yuv_tx.bitcoin_tx.input.get(*input as usize) else {
+                return Ok(false);
+            };
 
-        // If there is some left space to store in current page, store it:
-        if !in_current_page.is_empty() {
-            last_page.extend(in_current_page);
+            let parent_txid = txin.previous_output.txid;
+            if self.txs_storage.get_yuv_tx(&parent_txid).await?.is_none() {
+                return Ok(false);
+            }
 
-            self.txs_storage.put_page(last_page_num, last_page).await?;
+            let parent_entry = self.state_storage.get_mempool_entry(&parent_txid).await?;
+            if parent_entry.is_some_and(|entry| entry.provisionally_attached) {
+                return Ok(false);
+            }
         }
 
-        // If there is some, store them in next page, and increment the page number.
-        if !in_next_page.is_empty() {
-            let next_page_num = last_page_num + 1;
+        Ok(true)
+    }
 
-            self.txs_storage
-                .put_page(next_page_num, in_next_page.to_vec())
-                .await?;
+    /// Removes the provisionally attached transactions that won't be attached from the storage
+    /// and the pages.
+    async fn detach_provisional_txs(&self, txids: &[Txid]) -> Result<()> {
+        if txids.is_empty() {
+            return Ok(());
+        }
 
-            self.txs_storage.put_pages_number(next_page_num).await?;
+        for txid in txids {
+            self.txs_storage.delete_yuv_tx(txid).await?;
+        }
+
+        self.remove_txs_from_pages(txids).await
+    }
+
+    /// Handles attached transactions by removing them from the mempool. The transactions are
+    /// written to the pages in the [canonical order](canonical_order).
+    pub async fn handle_attached_txs(&mut self, txids: Vec<Txid>) -> Result<()> {
+        let mut entries = Vec::with_capacity(txids.len());
+
+        for txid in &txids {
+            let entry = self
+                .state_storage
+                .get_mempool_entry(txid)
+                .await?
+                .wrap_err("Attaching tx is not present in the mempool")?;
+
+            entries.push(entry);
+        }
+
+        let txids = canonical_order(
+            entries
+                .iter()
+                .map(|entry| (&entry.yuv_tx.bitcoin_tx, entry.position())),
+        );
+        let mut entries = entries
+            .into_iter()
+            .map(|entry| (entry.txid(), entry))
+            .collect::<HashMap<_, _>>();
+
+        let mut attached_txs = Vec::with_capacity(txids.len());
+        let mut positions = Vec::with_capacity(txids.len());
+        let mut block_heights = Vec::with_capacity(txids.len());
+        let mut provisional = Vec::with_capacity(txids.len());
+        for txid in &txids {
+            let entry = entries
+                .remove(txid)
+                .wrap_err("Attaching tx is not present in the mempool")?;
+
+            positions.push(entry.position());
+            provisional.push(entry.provisionally_attached);
+            block_heights.push(entry.block_height);
+            attached_txs.push(entry.yuv_tx);
+        }
+
+        // The provisionally attached transactions are moved from the end of the pages to their
+        // positions.
+        let provisional_txids = txids
+            .iter()
+            .zip(&provisional)
+            .filter_map(|(txid, provisional)| provisional.then_some(*txid))
+            .collect::<Vec<_>>();
+        self.remove_txs_from_pages(&provisional_txids).await?;
+
+        // The transactions and the pages are written atomically, and the transactions stay in
+        // the attach log until they are removed from the mempool, see `recover_attached_txs`.
+        self.txs_storage
+            .attach_txs(&attached_txs, &positions, self.tx_per_page)
+            .await
+            .wrap_err("Failed to store transactions in pages")?;
+
+        for (txid, yuv_tx) in txids.iter().zip(&attached_txs) {
+            tracing::info!(txid = txid.to_string(), "Tx is attached");
+
+            self.txs_storage.add_yuv_tx_dependencies(yuv_tx).await?;
+            self.state_storage.delete_mempool_entry(txid).await?;
+            self.arbiter.complete(txid);
+        }
+
+        self.freeze_continuation_outputs(&attached_txs)
+            .await
+            .wrap_err("Failed to freeze the continuation outputs")?;
+        self.update_supply_trees(&attached_txs)
+            .await
+            .wrap_err("Failed to update the supply trees")?;
+        self.update_address_index(&attached_txs)
+            .await
+            .wrap_err("Failed to update the address index")?;
+        self.update_transfer_history(attached_txs.iter().zip(block_heights.iter().copied()))
+            .await
+            .wrap_err("Failed to update the transfer history")?;
+        self.track_unspent_outputs(attached_txs.iter().zip(block_heights))
+            .await
+            .wrap_err("Failed to track the unspent outputs")?;
+
+        let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
+        clear_mempool(&mut raw_mempool, &txids);
+        self.state_storage.put_mempool(raw_mempool).await?;
+
+        self.txs_storage.delete_attach_log().await?;
+
+        if !attached_txs.is_empty() {
+            self.event_bus
+                .send(RpcSubscriptionMessage::AttachedTxs(attached_txs))
+                .await;
         }
 
         Ok(())
@@ -703,27 +1164,56 @@ where
         &mut self,
         announcement_txs: Vec<YuvTransaction>,
     ) -> Result<()> {
-        let txids: Vec<Txid> = announcement_txs
-            .iter()
-            .map(|tx| tx.bitcoin_tx.txid())
-            .collect();
-        for txs in txids.chunks(self.tx_per_page as usize) {
-            self.put_txs_ids_to_page(txs)
-                .await
-                .wrap_err("Failed to store announcements in pages")?;
+        let mut positions = HashMap::with_capacity(announcement_txs.len());
+        for announcement_tx in &announcement_txs {
+            let txid = announcement_tx.bitcoin_tx.txid();
+            let position = self
+                .state_storage
+                .get_mempool_entry(&txid)
+                .await?
+                .and_then(|entry| entry.position());
+
+            positions.insert(txid, position);
         }
 
+        let txids = canonical_order(
+            announcement_txs
+                .iter()
+                .map(|tx| (&tx.bitcoin_tx, positions[&tx.bitcoin_tx.txid()])),
+        );
+        let mut announcement_txs = announcement_txs
+            .into_iter()
+            .map(|tx| (tx.bitcoin_tx.txid(), tx))
+            .collect::<HashMap<_, _>>();
+        let (announcement_txs, positions): (Vec<_>, Vec<_>) = txids
+            .iter()
+            .filter_map(|txid| Some((announcement_txs.remove(txid)?, positions[txid])))
+            .unzip();
+
+        self.txs_storage
+            .attach_txs(&announcement_txs, &positions, self.tx_per_page)
+            .await
+            .wrap_err("Failed to store announcements in pages")?;
+
+        let mut freezes = Vec::new();
+        let mut chroma_announcements = Vec::new();
+
         for announcement_tx in announcement_txs {
+            match &announcement_tx.tx_type {
+                YuvTxType::Announcement(Announcement::Freeze(freeze)) => {
+                    freezes.push(freeze.clone())
+                }
+                YuvTxType::Announcement(Announcement::Chroma(chroma)) => {
+                    chroma_announcements.push(chroma.clone())
+                }
+                _ => {}
+            }
+
             let announcement_txid = announcement_tx.bitcoin_tx.txid();
             self.state_storage
                 .delete_mempool_entry(&announcement_txid)
                 .await?;
-
-            let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
-            raw_mempool.retain(|txid| *txid != announcement_txid);
-            self.state_storage.put_mempool(raw_mempool).await?;
-
-            self.txs_storage.put_yuv_tx(announcement_tx).await?;
+            self.arbiter.complete(&announcement_txid);
 
             tracing::info!(
                 txid = announcement_txid.to_string(),
@@ -731,6 +1221,26 @@ where
             );
         }
 
+        let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
+        clear_mempool(&mut raw_mempool, &txids);
+        self.state_storage.put_mempool(raw_mempool).await?;
+
+        self.txs_storage.delete_attach_log().await?;
+
+        if !freezes.is_empty() {
+            self.event_bus
+                .send(RpcSubscriptionMessage::Freezes(freezes))
+                .await;
+        }
+
+        if !chroma_announcements.is_empty() {
+            self.event_bus
+                .send(RpcSubscriptionMessage::ChromaAnnouncements(
+                    chroma_announcements,
+                ))
+                .await;
+        }
+
         Ok(())
     }
 
@@ -799,8 +1309,3 @@ fn clear_mempool<T: Eq + Hash>(raw_mempool: &mut Vec<T>, txs: &[T]) {
     let txs_set = HashSet::<&T>::from_iter(txs.iter());
     raw_mempool.retain(|txid| !txs_set.contains(txid));
 }
-
-/// Split at array without panic
-fn split_at<T>(txids: &[T], left_space: usize) -> (&[T], &[T]) {
-    txids.split_at(left_space.min(txids.len()))
-}
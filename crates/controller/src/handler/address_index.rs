@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bitcoin::{secp256k1::XOnlyPublicKey, OutPoint};
+use eyre::{eyre, Result};
+use yuv_p2p::client::handle::Handle as ClientHandle;
+use yuv_pixels::Pixel;
+use yuv_storage::{
+    AddressIndexStorage, InventoryStorage, MempoolEntryStorage, MempoolStorage, PagesNumberStorage,
+    PagesStorage, TransactionsStorage,
+};
+use yuv_types::owners::{owned_inputs, owned_outputs};
+use yuv_types::YuvTransaction;
+
+use super::Controller;
+
+/// Changes of the unspent outputs of a key.
+#[derive(Default)]
+struct AddressChanges {
+    added: Vec<(OutPoint, Pixel)>,
+    removed: Vec<OutPoint>,
+}
+
+impl<TS, SS, P2P> Controller<TS, SS, P2P>
+where
+    TS: TransactionsStorage
+        + PagesNumberStorage
+        + PagesStorage
+        + AddressIndexStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    SS: InventoryStorage + MempoolStorage + MempoolEntryStorage + Send + Sync + Clone + 'static,
+    P2P: ClientHandle + Send + Sync + Clone + 'static,
+{
+    /// Builds the address index from the pages if it wasn't built yet, so the outputs of the
+    /// transactions attached before the index was enabled are indexed too. The index isn't
+    /// updated while it's disabled, so it's built again once it's enabled back. Must be called
+    /// before [`run`](Self::run).
+    pub async fn build_address_index(&self) -> Result<()> {
+        if !self.address_index {
+            self.txs_storage.delete_address_index_built().await?;
+
+            return Ok(());
+        }
+
+        if self.txs_storage.is_address_index_built().await? {
+            return Ok(());
+        }
+
+        let last_page_num = self
+            .txs_storage
+            .get_pages_number()
+            .await?
+            .unwrap_or_default();
+
+        let mut txs_number = 0;
+        for page_num in 0..=last_page_num {
+            let page = self
+                .txs_storage
+                .get_page_by_num(page_num)
+                .await?
+                .unwrap_or_default();
+
+            let mut txs = Vec::with_capacity(page.len());
+            for txid in page {
+                // The outputs the pruned transaction spends can't be removed from the index.
+                let yuv_tx = self.txs_storage.get_yuv_tx(&txid).await?.ok_or_else(|| {
+                    eyre!(
+                        "Paged tx {} is not present in the storage, reindex the node",
+                        txid
+                    )
+                })?;
+
+                txs.push(yuv_tx);
+            }
+
+            // The parents are paged before their children, so the outputs are always added
+            // before they're removed as spent.
+            self.update_address_index(&txs).await?;
+            txs_number += txs.len();
+        }
+
+        self.txs_storage.put_address_index_built().await?;
+
+        tracing::info!(txs_number, "Built the address index from the pages");
+
+        Ok(())
+    }
+
+    /// Adds the outputs of the attached transactions to the address index, and removes the spent
+    /// ones. Does nothing if the index is disabled.
+    pub(super) async fn update_address_index(&self, attached_txs: &[YuvTransaction]) -> Result<()> {
+        if !self.address_index {
+            return Ok(());
+        }
+
+        let mut changes: HashMap<XOnlyPublicKey, AddressChanges> = HashMap::new();
+
+        for yuv_tx in attached_txs {
+            for (owner, outpoint, pixel) in owned_outputs(yuv_tx) {
+                changes
+                    .entry(owner)
+                    .or_default()
+                    .added
+                    .push((outpoint, pixel));
+            }
+        }
+
+        for yuv_tx in attached_txs {
+            for (owner, outpoint, _) in owned_inputs(yuv_tx) {
+                changes.entry(owner).or_default().removed.push(outpoint);
+            }
+        }
+
+        self.apply_address_changes(changes).await
+    }
+
+    /// Removes the outputs of the detached transactions from the address index, and returns
+    /// their inputs back, unless the inputs are the outputs of the detached transactions too.
+    pub(super) async fn revert_address_index(&self, detached_txs: &[YuvTransaction]) -> Result<()> {
+        if !self.address_index {
+            return Ok(());
+        }
+
+        let detached_txids = detached_txs
+            .iter()
+            .map(|yuv_tx| yuv_tx.bitcoin_tx.txid())
+            .collect::<HashSet<_>>();
+
+        let mut changes: HashMap<XOnlyPublicKey, AddressChanges> = HashMap::new();
+
+        for yuv_tx in detached_txs {
+            for (owner, outpoint, _) in owned_outputs(yuv_tx) {
+                changes.entry(owner).or_default().removed.push(outpoint);
+            }
+
+            for (owner, outpoint, pixel) in owned_inputs(yuv_tx) {
+                if detached_txids.contains(&outpoint.txid) {
+                    continue;
+                }
+
+                changes
+                    .entry(owner)
+                    .or_default()
+                    .added
+                    .push((outpoint, pixel));
+            }
+        }
+
+        self.apply_address_changes(changes).await
+    }
+
+    async fn apply_address_changes(
+        &self,
+        changes: HashMap<XOnlyPublicKey, AddressChanges>,
+    ) -> Result<()> {
+        for (owner, changes) in changes {
+            let mut utxos: BTreeMap<OutPoint, Pixel> =
+                self.txs_storage.get_address_utxos(&owner).await?;
+
+            utxos.extend(changes.added);
+            for outpoint in &changes.removed {
+                utxos.remove(outpoint);
+            }
+
+            self.txs_storage.put_address_utxos(&owner, utxos).await?;
+        }
+
+        Ok(())
+    }
+}
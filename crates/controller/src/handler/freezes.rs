@@ -0,0 +1,140 @@
+use bitcoin::OutPoint;
+use eyre::Result;
+use yuv_p2p::client::handle::Handle as ClientHandle;
+use yuv_storage::{
+    FrozenTxsStorage, InventoryStorage, MempoolEntryStorage, MempoolStorage, PagesNumberStorage,
+    PagesStorage, TransactionsStorage,
+};
+use yuv_tx_check::{find_continuation_outputs, partial_freeze, PartialFreeze};
+use yuv_types::{YuvTransaction, YuvTxType};
+
+use super::Controller;
+
+impl<TS, SS, P2P> Controller<TS, SS, P2P>
+where
+    TS: TransactionsStorage + PagesNumberStorage + PagesStorage + Send + Sync + Clone + 'static,
+    SS: InventoryStorage
+        + MempoolStorage
+        + MempoolEntryStorage
+        + FrozenTxsStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    P2P: ClientHandle + Send + Sync + Clone + 'static,
+{
+    /// Freezes the continuation outputs of the attached transfers for the frozen amounts of
+    /// their partially frozen inputs. The outputs that are already frozen are left as is.
+    pub(super) async fn freeze_continuation_outputs(
+        &self,
+        attached_txs: &[YuvTransaction],
+    ) -> Result<()> {
+        for yuv_tx in attached_txs {
+            for (outpoint, freeze) in self.continuation_freezes(yuv_tx).await? {
+                if self.state_storage.get_frozen_tx(&outpoint).await?.is_some() {
+                    continue;
+                }
+
+                self.state_storage
+                    .put_frozen_tx(
+                        &outpoint,
+                        freeze.freeze_txid,
+                        freeze.chroma,
+                        Some(freeze.amount),
+                        freeze.expiry_height,
+                    )
+                    .await?;
+
+                tracing::debug!(
+                    %outpoint,
+                    amount = freeze.amount,
+                    "The continuation output is frozen",
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unfreezes the continuation outputs of the detached transfers. The continuations are found
+    /// before any of them is unfrozen, as the detached transfers may spend each other.
+    pub(super) async fn unfreeze_continuation_outputs(
+        &self,
+        detached_txs: &[YuvTransaction],
+    ) -> Result<()> {
+        let mut continuations = Vec::new();
+        for yuv_tx in detached_txs {
+            continuations.extend(self.continuation_freezes(yuv_tx).await?);
+        }
+
+        for (outpoint, freeze) in continuations {
+            // The freezes announced for the output itself are left to the indexers.
+            let is_continuation = self
+                .state_storage
+                .get_frozen_tx(&outpoint)
+                .await?
+                .is_some_and(|entry| {
+                    entry.txid == freeze.freeze_txid && entry.amount == Some(freeze.amount)
+                });
+
+            if is_continuation {
+                self.state_storage.delete_frozen_tx(&outpoint).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Continuation outputs of the transfer with the frozen parts of its inputs. The transfer is
+    /// already checked, so the frozen amounts are kept in its outputs.
+    async fn continuation_freezes(
+        &self,
+        yuv_tx: &YuvTransaction,
+    ) -> Result<Vec<(OutPoint, PartialFreeze)>> {
+        let YuvTxType::Transfer { input_proofs, .. } = &yuv_tx.tx_type else {
+            return Ok(Vec::new());
+        };
+
+        let mut freezes = Vec::new();
+        for (vin, proof) in input_proofs {
+            let Some(input) = yuv_tx.bitcoin_tx.input.get(*vin as usize) else {
+                continue;
+            };
+
+            let Some(entry) = self
+                .state_storage
+                .get_frozen_tx(&input.previous_output)
+                .await?
+            else {
+                continue;
+            };
+
+            // The freezes of the other chromas are ignored by the checker. The expired ones are
+            // inherited as is, as they stay inactive.
+            if entry.chroma != proof.pixel().chroma {
+                continue;
+            }
+
+            freezes.extend(partial_freeze(&entry, proof));
+        }
+
+        if freezes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let txid = yuv_tx.bitcoin_tx.txid();
+        let Some(continuations) = find_continuation_outputs(yuv_tx, freezes) else {
+            tracing::warn!(
+                txid = txid.to_string(),
+                "Frozen amount of the attached tx is not kept in its outputs",
+            );
+
+            return Ok(Vec::new());
+        };
+
+        Ok(continuations
+            .into_iter()
+            .map(|(vout, freeze)| (OutPoint::new(txid, vout), freeze))
+            .collect())
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet};
+
+use bitcoin::{secp256k1::XOnlyPublicKey, Txid};
+use eyre::Result;
+use yuv_p2p::client::handle::Handle as ClientHandle;
+use yuv_pixels::Chroma;
+use yuv_storage::{
+    InventoryStorage, MempoolEntryStorage, MempoolStorage, PagesNumberStorage, PagesStorage,
+    TransactionsStorage, TransferHistoryEntry, TransferHistoryStorage,
+};
+use yuv_types::owners::balance_deltas;
+use yuv_types::YuvTransaction;
+
+use super::Controller;
+
+impl<TS, SS, P2P> Controller<TS, SS, P2P>
+where
+    TS: TransactionsStorage
+        + PagesNumberStorage
+        + PagesStorage
+        + TransferHistoryStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    SS: InventoryStorage + MempoolStorage + MempoolEntryStorage + Send + Sync + Clone + 'static,
+    P2P: ClientHandle + Send + Sync + Clone + 'static,
+{
+    /// Records the changes of the balances made by the attached transactions to the transfer
+    /// history of the owners. The transactions that aren't mined, so have no height, are not
+    /// recorded. Does nothing if the history index is disabled.
+    pub(super) async fn update_transfer_history<'a>(
+        &self,
+        attached_txs: impl IntoIterator<Item = (&'a YuvTransaction, Option<usize>)>,
+    ) -> Result<()> {
+        if !self.history_index {
+            return Ok(());
+        }
+
+        let mut changes: HashMap<(Chroma, XOnlyPublicKey), Vec<TransferHistoryEntry>> =
+            HashMap::new();
+
+        for (yuv_tx, block_height) in attached_txs {
+            let Some(height) = block_height else {
+                continue;
+            };
+
+            let txid = yuv_tx.bitcoin_tx.txid();
+
+            for (key, delta) in balance_deltas(yuv_tx) {
+                changes.entry(key).or_default().push(TransferHistoryEntry {
+                    height,
+                    txid,
+                    delta,
+                });
+            }
+        }
+
+        for ((chroma, owner), entries) in changes {
+            let mut history = self
+                .txs_storage
+                .get_transfer_history(&chroma, &owner)
+                .await?;
+
+            history.extend(entries);
+            // Transactions are attached in order mostly, but not after the rollbacks.
+            history.sort_by_key(|entry| entry.height);
+
+            self.txs_storage
+                .put_transfer_history(&chroma, &owner, history)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the detached transactions from the transfer history of their owners.
+    pub(super) async fn revert_transfer_history(
+        &self,
+        detached_txs: &[YuvTransaction],
+    ) -> Result<()> {
+        if !self.history_index {
+            return Ok(());
+        }
+
+        let mut changes: HashMap<(Chroma, XOnlyPublicKey), HashSet<Txid>> = HashMap::new();
+
+        for yuv_tx in detached_txs {
+            let txid = yuv_tx.bitcoin_tx.txid();
+
+            for (key, _) in balance_deltas(yuv_tx) {
+                changes.entry(key).or_default().insert(txid);
+            }
+        }
+
+        for ((chroma, owner), txids) in changes {
+            let mut history = self
+                .txs_storage
+                .get_transfer_history(&chroma, &owner)
+                .await?;
+
+            history.retain(|entry| !txids.contains(&entry.txid));
+
+            self.txs_storage
+                .put_transfer_history(&chroma, &owner, history)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+
+use bitcoin::Txid;
+use eyre::Result;
+use yuv_p2p::client::handle::Handle as ClientHandle;
+use yuv_storage::{
+    InventoryStorage, MempoolEntryStorage, MempoolStorage, PagesNumberStorage, PagesStorage,
+    PruningStorage, TransactionsStorage,
+};
+use yuv_types::YuvTransaction;
+
+use super::Controller;
+
+impl<TS, SS, P2P> Controller<TS, SS, P2P>
+where
+    TS: TransactionsStorage
+        + PagesNumberStorage
+        + PagesStorage
+        + PruningStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    SS: InventoryStorage + MempoolStorage + MempoolEntryStorage + Send + Sync + Clone + 'static,
+    P2P: ClientHandle + Send + Sync + Clone + 'static,
+{
+    /// Counts the unspent outputs of the attached transactions, and queues their parents for
+    /// pruning once the last outputs of the parents are spent. The parents spent by the
+    /// transactions that aren't mined, so have no height, are not queued. Does nothing if the
+    /// pruning is disabled.
+    pub(super) async fn track_unspent_outputs<'a>(
+        &self,
+        attached_txs: impl IntoIterator<Item = (&'a YuvTransaction, Option<usize>)>,
+    ) -> Result<()> {
+        if !self.pruning {
+            return Ok(());
+        }
+
+        for (yuv_tx, block_height) in attached_txs {
+            let outputs = yuv_tx
+                .tx_type
+                .output_proofs()
+                .map_or(0, |output_proofs| output_proofs.len());
+
+            if outputs > 0 {
+                self.txs_storage
+                    .put_unspent_outputs(&yuv_tx.bitcoin_tx.txid(), outputs as u32)
+                    .await?;
+            }
+
+            for parent in spent_parents(yuv_tx) {
+                // The parents attached before the pruning is enabled are not tracked.
+                let Some(unspent) = self.txs_storage.get_unspent_outputs(&parent).await? else {
+                    continue;
+                };
+
+                let unspent = unspent.saturating_sub(1);
+                self.txs_storage
+                    .put_unspent_outputs(&parent, unspent)
+                    .await?;
+
+                if let (0, Some(height)) = (unspent, block_height) {
+                    self.queue_prunable_tx(parent, height).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the spent outputs of the detached transactions back to their parents, unless the
+    /// parents are detached too. The parents that were queued for pruning are skipped by the
+    /// pruner, as they have unspent outputs again.
+    pub(super) async fn revert_unspent_outputs(
+        &self,
+        detached_txs: &[YuvTransaction],
+    ) -> Result<()> {
+        if !self.pruning {
+            return Ok(());
+        }
+
+        let detached_txids = detached_txs
+            .iter()
+            .map(|yuv_tx| yuv_tx.bitcoin_tx.txid())
+            .collect::<HashSet<_>>();
+
+        for yuv_tx in detached_txs {
+            self.txs_storage
+                .delete_unspent_outputs(&yuv_tx.bitcoin_tx.txid())
+                .await?;
+
+            for parent in spent_parents(yuv_tx) {
+                if detached_txids.contains(&parent) {
+                    continue;
+                }
+
+                let Some(unspent) = self.txs_storage.get_unspent_outputs(&parent).await? else {
+                    continue;
+                };
+
+                self.txs_storage
+                    .put_unspent_outputs(&parent, unspent + 1)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queues the transaction to be pruned with the block. The blocks that are already pruned
+    /// are not visited again, so the transaction is queued with the next one instead.
+    async fn queue_prunable_tx(&self, txid: Txid, height: usize) -> Result<()> {
+        let height = match self.txs_storage.get_pruning_cursor().await? {
+            Some(cursor) => height.max(cursor),
+            None => {
+                self.txs_storage.put_pruning_cursor(height).await?;
+                height
+            }
+        };
+
+        self.txs_storage.add_prunable_tx(height, txid).await?;
+
+        Ok(())
+    }
+
+    /// Returns `true` if the transaction was attached and then pruned, so it isn't handled
+    /// again.
+    pub(super) async fn is_tx_pruned(&self, txid: &Txid) -> Result<bool> {
+        Ok(self.txs_storage.get_pruned_tx(txid).await?.is_some())
+    }
+}
+
+/// Returns the ids of the parents of the transaction for each of its spent outputs.
+fn spent_parents(yuv_tx: &YuvTransaction) -> Vec<Txid> {
+    let Some(input_proofs) = yuv_tx.tx_type.input_proofs() else {
+        return Vec::new();
+    };
+
+    input_proofs
+        .keys()
+        .filter_map(|index| yuv_tx.bitcoin_tx.input.get(*index as usize))
+        .map(|input| input.previous_output.txid)
+        .collect()
+}
@@ -0,0 +1,334 @@
+use std::collections::{HashSet, VecDeque};
+
+use bitcoin::Txid;
+use eyre::Result;
+use yuv_p2p::client::handle::Handle as ClientHandle;
+use yuv_pixels::Chroma;
+use yuv_storage::{
+    AddressIndexStorage, AttachStorage, ChromaInfoStorage, DelegatesStorage, FrozenPubkeysStorage,
+    FrozenTxsStorage, InventoryStorage, MempoolEntryStorage, MempoolStatus, MempoolStorage,
+    MempoolTxEntry, PagesNumberStorage, PagesStorage, PruningStorage, SupplyTreeStorage,
+    TransactionsStorage, TransferHistoryStorage, TxDependentsStorage,
+};
+use yuv_types::announcements::{ChromaAnnouncement, ChromaInfo, UpdateChromaAnnouncement};
+use yuv_types::{
+    Announcement, RpcSubscriptionMessage, TxConfirmMessage, YuvTransaction, YuvTxType,
+};
+
+use super::Controller;
+
+impl<TS, SS, P2P> Controller<TS, SS, P2P>
+where
+    TS: TransactionsStorage
+        + PagesNumberStorage
+        + PagesStorage
+        + TxDependentsStorage
+        + SupplyTreeStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + PruningStorage
+        + AttachStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    SS: InventoryStorage
+        + MempoolStorage
+        + MempoolEntryStorage
+        + FrozenTxsStorage
+        + FrozenPubkeysStorage
+        + DelegatesStorage
+        + ChromaInfoStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    P2P: ClientHandle + Send + Sync + Clone + 'static,
+{
+    /// Detaches the attached transactions from the orphan blocks along with all their
+    /// descendants, removes them from the pages, the supply trees, the address index, the
+    /// transfer history and the unspent outputs counts, unfreezes their continuation outputs,
+    /// reverts the effects of their announcements, and sends them back to the confirmator.
+    pub(super) async fn rollback_attached_txs(&mut self, orphaned_txids: Vec<Txid>) -> Result<()> {
+        let mut queue = VecDeque::from(orphaned_txids);
+        let mut detached_txids = Vec::new();
+        let mut detached_txs = Vec::new();
+        let mut visited = HashSet::new();
+
+        while let Some(txid) = queue.pop_front() {
+            if !visited.insert(txid) {
+                continue;
+            }
+
+            let Some(yuv_tx) = self.txs_storage.get_yuv_tx(&txid).await? else {
+                continue;
+            };
+
+            queue.extend(self.txs_storage.get_tx_dependents(&txid).await?);
+
+            tracing::info!(txid = txid.to_string(), "Tx is detached");
+
+            self.txs_storage.delete_yuv_tx(&txid).await?;
+            self.txs_storage.delete_tx_position(&txid).await?;
+            // Dependencies are recorded again when the transaction's children are attached.
+            self.txs_storage.delete_tx_dependents(&txid).await?;
+            detached_txs.push(yuv_tx.clone());
+            self.state_storage
+                .put_mempool_entry(MempoolTxEntry::new(
+                    yuv_tx,
+                    MempoolStatus::WaitingMined,
+                    None,
+                ))
+                .await?;
+
+            detached_txids.push(txid);
+        }
+
+        if detached_txids.is_empty() {
+            return Ok(());
+        }
+
+        tracing::debug!("Rolled back YUV transactions: {:?}", detached_txids);
+
+        self.remove_txs_from_pages(&detached_txids).await?;
+        self.revert_supply_trees(&detached_txs).await?;
+        self.revert_address_index(&detached_txs).await?;
+        self.revert_transfer_history(&detached_txs).await?;
+        self.revert_unspent_outputs(&detached_txs).await?;
+        self.unfreeze_continuation_outputs(&detached_txs).await?;
+        self.revert_announcements(&detached_txs).await?;
+
+        let mut raw_mempool = self.state_storage.get_mempool().await?.unwrap_or_default();
+        raw_mempool.extend(detached_txids.iter().copied());
+        self.state_storage.put_mempool(raw_mempool).await?;
+
+        self.event_bus
+            .send(RpcSubscriptionMessage::DetachedTxs(detached_txs.clone()))
+            .await;
+        self.event_bus
+            .send(TxConfirmMessage::Txs(detached_txs))
+            .await;
+
+        Ok(())
+    }
+
+    /// Reverts the effects of the detached transactions on the chromas, the freezes and the
+    /// delegations, starting from the most recently attached one. The effects are applied again
+    /// by the checker when the transactions are mined.
+    ///
+    /// The state replaced by the ownership transfers, the unfreezes and the revocations isn't
+    /// kept, so it can't be restored and is left as is.
+    async fn revert_announcements(&self, detached_txs: &[YuvTransaction]) -> Result<()> {
+        for yuv_tx in detached_txs.iter().rev() {
+            let txid = yuv_tx.bitcoin_tx.txid();
+
+            match &yuv_tx.tx_type {
+                YuvTxType::Issue { announcement, .. }
+                | YuvTxType::Announcement(Announcement::Issue(announcement)) => {
+                    self.update_chroma_info(&announcement.chroma, |info| {
+                        info.total_supply = info.total_supply.saturating_sub(announcement.amount);
+                    })
+                    .await?;
+                }
+                YuvTxType::Transfer { .. } => {
+                    for burn in yuv_tx.burn_announcements() {
+                        self.update_chroma_info(&burn.chroma, |info| {
+                            info.total_burned = info.total_burned.saturating_sub(burn.amount);
+                        })
+                        .await?;
+                    }
+                }
+                YuvTxType::Announcement(Announcement::Chroma(announcement)) => {
+                    self.revert_chroma_announcement(announcement).await?;
+                }
+                YuvTxType::Announcement(Announcement::UpdateChroma(update)) => {
+                    self.revert_chroma_update(update).await?;
+                }
+                YuvTxType::Announcement(Announcement::Freeze(freeze)) => {
+                    let outpoint = freeze.freeze_outpoint();
+                    let is_frozen_by_tx = self
+                        .state_storage
+                        .get_frozen_tx(&outpoint)
+                        .await?
+                        .is_some_and(|entry| entry.txid == txid);
+
+                    if is_frozen_by_tx {
+                        self.state_storage.delete_frozen_tx(&outpoint).await?;
+                    }
+                }
+                YuvTxType::Announcement(Announcement::FreezePubkey(freeze)) if !freeze.unfreeze => {
+                    let is_frozen_by_tx = self
+                        .state_storage
+                        .get_frozen_pubkey(&freeze.chroma, &freeze.pubkey)
+                        .await?
+                        .is_some_and(|entry| entry.txid == txid);
+
+                    if is_frozen_by_tx {
+                        self.state_storage
+                            .delete_frozen_pubkey(&freeze.chroma, &freeze.pubkey)
+                            .await?;
+                    }
+                }
+                YuvTxType::Announcement(Announcement::Delegate(delegate))
+                    if !delegate.is_revoke() =>
+                {
+                    let is_delegated_by_tx = self
+                        .state_storage
+                        .get_delegate(&delegate.chroma, &delegate.delegate)
+                        .await?
+                        .is_some_and(|entry| entry.txid == txid);
+
+                    if is_delegated_by_tx {
+                        self.state_storage
+                            .delete_delegate(&delegate.chroma, &delegate.delegate)
+                            .await?;
+                    }
+                }
+                YuvTxType::Announcement(Announcement::Burn(_)) => {}
+                YuvTxType::Announcement(_) => {
+                    tracing::warn!(
+                        txid = txid.to_string(),
+                        "The effect of the detached announcement can't be reverted",
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the chroma announcement, if it is still the announced one, and the owner it has
+    /// set.
+    async fn revert_chroma_announcement(&self, announcement: &ChromaAnnouncement) -> Result<()> {
+        let owner = announcement
+            .issuers
+            .as_ref()
+            .map(|issuers| issuers.owner_script());
+
+        let Some(info) = self
+            .state_storage
+            .get_chroma_info(&announcement.chroma)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let is_announced =
+            info.announcement_version == 0 && info.announcement.as_ref() == Some(announcement);
+        if !is_announced {
+            return Ok(());
+        }
+
+        self.state_storage
+            .delete_chroma_announcement_version(&announcement.chroma, 0)
+            .await?;
+        self.update_chroma_info(&announcement.chroma, |info| {
+            info.announcement = None;
+            if owner.is_some() && info.owner == owner {
+                info.owner = None;
+            }
+        })
+        .await
+    }
+
+    /// Restores the previous version of the chroma announcement, if the `update` is the last
+    /// applied one.
+    async fn revert_chroma_update(&self, update: &UpdateChromaAnnouncement) -> Result<()> {
+        let Some(info) = self.state_storage.get_chroma_info(&update.chroma).await? else {
+            return Ok(());
+        };
+
+        let version = info.announcement_version;
+        let is_last_update = version > 0
+            && info.announcement.as_ref().is_some_and(|announcement| {
+                announcement.name == update.name && announcement.symbol == update.symbol
+            });
+        if !is_last_update {
+            return Ok(());
+        }
+
+        let Some(previous) = self
+            .state_storage
+            .get_chroma_announcement_version(&update.chroma, version - 1)
+            .await?
+        else {
+            tracing::warn!(
+                chroma = update.chroma.to_string(),
+                version,
+                "The previous version of the chroma announcement is missing",
+            );
+            return Ok(());
+        };
+
+        self.state_storage
+            .delete_chroma_announcement_version(&update.chroma, version)
+            .await?;
+        self.update_chroma_info(&update.chroma, |info| {
+            info.announcement = Some(previous);
+            info.announcement_version = version - 1;
+        })
+        .await
+    }
+
+    /// Applies the `update` to the stored info of the `chroma`, if there is one.
+    async fn update_chroma_info(
+        &self,
+        chroma: &Chroma,
+        update: impl FnOnce(&mut ChromaInfo),
+    ) -> Result<()> {
+        let Some(mut info) = self.state_storage.get_chroma_info(chroma).await? else {
+            return Ok(());
+        };
+
+        update(&mut info);
+
+        self.state_storage
+            .put_chroma_info(
+                chroma,
+                info.announcement,
+                info.total_supply,
+                info.total_burned,
+                info.announcement_version,
+                info.owner,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes the transactions from the pages. Pages are scanned starting from the last one, as
+    /// the rolled back transactions are the most recently attached ones.
+    pub(super) async fn remove_txs_from_pages(&self, txids: &[Txid]) -> Result<()> {
+        let txids: HashSet<&Txid> = txids.iter().collect();
+        let mut left = txids.len();
+
+        let last_page_num = self
+            .txs_storage
+            .get_pages_number()
+            .await?
+            .unwrap_or_default();
+
+        for page_num in (0..=last_page_num).rev() {
+            if left == 0 {
+                break;
+            }
+
+            let Some(mut page) = self.txs_storage.get_page_by_num(page_num).await? else {
+                continue;
+            };
+
+            let page_len = page.len();
+            page.retain(|txid| !txids.contains(txid));
+
+            let removed = page_len - page.len();
+            if removed == 0 {
+                continue;
+            }
+
+            left = left.saturating_sub(removed);
+            self.txs_storage.put_page(page_num, page).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bitcoin::OutPoint;
+use eyre::Result;
+use yuv_p2p::client::handle::Handle as ClientHandle;
+use yuv_pixels::Chroma;
+use yuv_storage::{
+    InventoryStorage, MempoolEntryStorage, MempoolStorage, PagesNumberStorage, PagesStorage,
+    SupplyTreeStorage, TransactionsStorage,
+};
+use yuv_types::{
+    supply::{supply_inputs, supply_outputs},
+    YuvTransaction,
+};
+
+use super::Controller;
+
+/// Changes of the unspent outputs of a chroma.
+#[derive(Default)]
+struct SupplyChanges {
+    added: Vec<(OutPoint, u128)>,
+    removed: Vec<OutPoint>,
+}
+
+impl<TS, SS, P2P> Controller<TS, SS, P2P>
+where
+    TS: TransactionsStorage
+        + PagesNumberStorage
+        + PagesStorage
+        + SupplyTreeStorage
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+    SS: InventoryStorage + MempoolStorage + MempoolEntryStorage + Send + Sync + Clone + 'static,
+    P2P: ClientHandle + Send + Sync + Clone + 'static,
+{
+    /// Adds the outputs of the attached transactions to the supply trees of their chromas, and
+    /// removes the spent ones.
+    ///
+    /// All the outputs are added before the inputs are removed, so the order of the
+    /// transactions in the batch doesn't matter.
+    pub(super) async fn update_supply_trees(&self, attached_txs: &[YuvTransaction]) -> Result<()> {
+        let mut changes: HashMap<Chroma, SupplyChanges> = HashMap::new();
+
+        for yuv_tx in attached_txs {
+            for (outpoint, pixel) in supply_outputs(yuv_tx) {
+                changes
+                    .entry(pixel.chroma)
+                    .or_default()
+                    .added
+                    .push((outpoint, pixel.luma.amount));
+            }
+        }
+
+        for yuv_tx in attached_txs {
+            for (outpoint, pixel) in supply_inputs(yuv_tx) {
+                changes
+                    .entry(pixel.chroma)
+                    .or_default()
+                    .removed
+                    .push(outpoint);
+            }
+        }
+
+        self.apply_supply_changes(changes).await
+    }
+
+    /// Removes the outputs of the detached transactions from the supply trees, and returns their
+    /// inputs back, unless the inputs are the outputs of the detached transactions too.
+    pub(super) async fn revert_supply_trees(&self, detached_txs: &[YuvTransaction]) -> Result<()> {
+        let detached_txids = detached_txs
+            .iter()
+            .map(|yuv_tx| yuv_tx.bitcoin_tx.txid())
+            .collect::<HashSet<_>>();
+
+        let mut changes: HashMap<Chroma, SupplyChanges> = HashMap::new();
+
+        for yuv_tx in detached_txs {
+            for (outpoint, pixel) in supply_outputs(yuv_tx) {
+                changes
+                    .entry(pixel.chroma)
+                    .or_default()
+                    .removed
+                    .push(outpoint);
+            }
+
+            for (outpoint, pixel) in supply_inputs(yuv_tx) {
+                if detached_txids.contains(&outpoint.txid) {
+                    continue;
+                }
+
+                changes
+                    .entry(pixel.chroma)
+                    .or_default()
+                    .added
+                    .push((outpoint, pixel.luma.amount));
+            }
+        }
+
+        self.apply_supply_changes(changes).await
+    }
+
+    async fn apply_supply_changes(&self, changes: HashMap<Chroma, SupplyChanges>) -> Result<()> {
+        for (chroma, changes) in changes {
+            let mut utxos: BTreeMap<OutPoint, u128> =
+                self.txs_storage.get_supply_utxos(&chroma).await?;
+
+            utxos.extend(changes.added);
+            for outpoint in &changes.removed {
+                utxos.remove(outpoint);
+            }
+
+            self.txs_storage.put_supply_utxos(&chroma, utxos).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,11 @@
+mod arbiter;
+pub use arbiter::{SubmissionArbiter, SubmissionOrigin};
+
 mod handler;
 pub use handler::Controller;
 
+mod ordering;
+pub use ordering::canonical_order;
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,69 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use bitcoin::{Transaction, Txid};
+use yuv_storage::TxPosition;
+
+/// Returns the ids of the transactions in the canonical order, in which they are written to
+/// the pages: by the [`TxPosition`] of the transaction in the chain, i.e. the height of its block
+/// and its index in the block. Transactions that aren't mined yet go last, parents before
+/// children, then by txid.
+///
+/// The order doesn't depend on the order the transactions are attached in, so the nodes that
+/// attach the same transactions end up with the same pages.
+pub fn canonical_order<'a>(
+    txs: impl IntoIterator<Item = (&'a Transaction, Option<TxPosition>)>,
+) -> Vec<Txid> {
+    let txs = txs
+        .into_iter()
+        .map(|(tx, position)| (tx.txid(), (tx, position_key(position))))
+        .collect::<HashMap<_, _>>();
+
+    let mut parents_left = HashMap::with_capacity(txs.len());
+    let mut children: HashMap<Txid, Vec<Txid>> = HashMap::new();
+    let mut ready = BTreeSet::new();
+
+    for (txid, (tx, position)) in &txs {
+        let parents = tx
+            .input
+            .iter()
+            .map(|input| input.previous_output.txid)
+            .filter(|parent| parent != txid && txs.contains_key(parent))
+            .collect::<HashSet<_>>();
+
+        if parents.is_empty() {
+            ready.insert((*position, *txid));
+            continue;
+        }
+
+        parents_left.insert(*txid, parents.len());
+        for parent in parents {
+            children.entry(parent).or_default().push(*txid);
+        }
+    }
+
+    // As the parents are always mined before their children, picking the smallest position out
+    // of the transactions with no pending parents keeps the positions ascending.
+    let mut order = Vec::with_capacity(txs.len());
+    while let Some((_, txid)) = ready.pop_first() {
+        order.push(txid);
+
+        for child in children.remove(&txid).unwrap_or_default() {
+            let left = parents_left
+                .get_mut(&child)
+                .expect("child should have pending parents");
+            *left -= 1;
+
+            if *left == 0 {
+                let (_, position) = txs[&child];
+                ready.insert((position, child));
+            }
+        }
+    }
+
+    order
+}
+
+/// Sort key of the position, with the transactions that aren't mined yet going last.
+fn position_key(position: Option<TxPosition>) -> (bool, Option<TxPosition>) {
+    (position.is_none(), position)
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use bitcoin::OutPoint;
+use yuv_pixels::Pixel;
+use yuv_storage::{AddressIndexStorage, AttachStorage, LevelDB};
+use yuv_tx_attach::test_util::{chroma, issuance, pubkey, transfer_of};
+
+use super::{controller, event_bus};
+
+#[tokio::test]
+async fn test_index_is_built_from_pages_attached_before_it() {
+    let storage = LevelDB::in_memory().unwrap();
+    let event_bus = event_bus();
+
+    // The transactions are paged while the index is disabled.
+    let issue = issuance(1);
+    let first = transfer_of(2, &[issue.bitcoin_tx.txid()], 100);
+    let second = transfer_of(3, &[first.bitcoin_tx.txid()], 100);
+    let txs = [issue, first, second.clone()];
+    storage.attach_txs(&txs, &[None; 3], 2).await.unwrap();
+
+    let owner = pubkey().x_only_public_key().0;
+    let indexing_controller = controller(&event_bus, &storage).set_address_index(true);
+    indexing_controller.build_address_index().await.unwrap();
+
+    let expected = BTreeMap::from([(
+        OutPoint::new(second.bitcoin_tx.txid(), 0),
+        Pixel::new(100, chroma()),
+    )]);
+    assert_eq!(storage.get_address_utxos(&owner).await.unwrap(), expected);
+    assert!(storage.is_address_index_built().await.unwrap());
+
+    // Building the built index again doesn't change it.
+    indexing_controller.build_address_index().await.unwrap();
+    assert_eq!(storage.get_address_utxos(&owner).await.unwrap(), expected);
+
+    // The index isn't updated while it's disabled, so it's built again once it's enabled.
+    controller(&event_bus, &storage)
+        .build_address_index()
+        .await
+        .unwrap();
+    assert!(!storage.is_address_index_built().await.unwrap());
+}
@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+use bitcoin::Txid;
+
+use crate::{SubmissionArbiter, SubmissionOrigin};
+
+fn txid(n: u8) -> Txid {
+    Txid::from_str(&format!("{:064x}", n)).expect("valid txid")
+}
+
+fn peer(port: u16) -> SubmissionOrigin {
+    SubmissionOrigin::Peer(([127, 0, 0, 1], port).into())
+}
+
+#[test]
+fn test_concurrent_submissions_are_merged() {
+    let arbiter = SubmissionArbiter::default();
+
+    assert!(arbiter.submit(txid(1), SubmissionOrigin::Rpc));
+    assert!(!arbiter.submit(txid(1), peer(8001)));
+    assert!(!arbiter.submit(txid(1), peer(8002)));
+    // Duplicate origins are recorded once.
+    assert!(!arbiter.submit(txid(1), peer(8001)));
+
+    assert!(arbiter.submit(txid(2), peer(8001)));
+
+    assert_eq!(
+        arbiter.origins(&txid(1)),
+        vec![SubmissionOrigin::Rpc, peer(8001), peer(8002)]
+    );
+    assert_eq!(arbiter.origins(&txid(2)), vec![peer(8001)]);
+}
+
+#[test]
+fn test_completed_tx_can_be_submitted_again() {
+    let arbiter = SubmissionArbiter::default();
+
+    assert!(arbiter.submit(txid(1), peer(8001)));
+    assert!(!arbiter.submit(txid(1), SubmissionOrigin::Rpc));
+
+    assert_eq!(
+        arbiter.complete(&txid(1)),
+        vec![peer(8001), SubmissionOrigin::Rpc]
+    );
+    assert!(!arbiter.is_in_flight(&txid(1)));
+    assert!(arbiter.origins(&txid(1)).is_empty());
+
+    assert!(arbiter.submit(txid(1), SubmissionOrigin::Rpc));
+}
+
+#[test]
+fn test_clones_share_state() {
+    let arbiter = SubmissionArbiter::default();
+    let cloned = arbiter.clone();
+
+    assert!(arbiter.submit(txid(1), SubmissionOrigin::Rpc));
+    assert!(!cloned.submit(txid(1), peer(8001)));
+    assert!(cloned.is_in_flight(&txid(1)));
+}
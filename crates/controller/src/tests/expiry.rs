@@ -0,0 +1,59 @@
+use bitcoin::{blockdata::locktime::absolute::LockTime, Transaction};
+use yuv_storage::{LevelDB, MempoolEntryStorage, MempoolStatus};
+use yuv_types::{ControllerMessage, TxCheckerMessage, YuvTransaction, YuvTxType};
+
+use super::{controller, event_bus};
+
+#[tokio::test]
+async fn test_expired_tx_can_be_resubmitted() {
+    let storage = LevelDB::in_memory().unwrap();
+    let event_bus = event_bus();
+    let checker_events = event_bus.subscribe::<TxCheckerMessage>();
+
+    let mut controller = controller(&event_bus, &storage);
+
+    let tx = YuvTransaction {
+        bitcoin_tx: Transaction {
+            version: 1,
+            lock_time: LockTime::from_height(0).expect("failed to create lock time"),
+            input: vec![],
+            output: vec![],
+        },
+        tx_type: YuvTxType::default(),
+    };
+    let txid = tx.bitcoin_tx.txid();
+
+    controller
+        .handle_event(ControllerMessage::InitializeTxs(vec![tx.clone()]))
+        .await
+        .unwrap();
+    controller
+        .handle_event(ControllerMessage::PartiallyCheckedTxs(vec![txid]))
+        .await
+        .unwrap();
+    controller
+        .handle_event(ControllerMessage::ExpiredTxs(vec![txid]))
+        .await
+        .unwrap();
+
+    assert!(
+        storage.get_mempool_entry(&txid).await.unwrap().is_none(),
+        "expired tx should be removed from the mempool"
+    );
+
+    controller
+        .handle_event(ControllerMessage::InitializeTxs(vec![tx]))
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        let TxCheckerMessage::IsolatedCheck(txs) = checker_events.recv().await.unwrap() else {
+            panic!("expected the tx to be sent to the isolated check");
+        };
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].bitcoin_tx.txid(), txid);
+    }
+
+    let entry = storage.get_mempool_entry(&txid).await.unwrap().unwrap();
+    assert_eq!(entry.status, MempoolStatus::Initialized);
+}
@@ -1,11 +1,53 @@
+mod address_index;
+mod arbiter;
+mod expiry;
+mod ordering;
+mod rollback;
+mod zero_conf;
+
 use std::collections::VecDeque;
 
+use event_bus::EventBus;
+use yuv_p2p::client::handle::OfflineHandle;
+use yuv_storage::LevelDB;
+use yuv_types::{
+    ControllerMessage, GraphBuilderMessage, IndexerMessage, RpcSubscriptionMessage,
+    TxCheckerMessage, TxConfirmMessage,
+};
+
 use crate::handler::update_inv;
+use crate::Controller;
 
 const MAX_INV_SIZE: usize = 10;
 
 type Txid = u64;
 
+fn event_bus() -> EventBus {
+    let mut event_bus = EventBus::default();
+    event_bus.register::<TxCheckerMessage>(Some(100));
+    event_bus.register::<GraphBuilderMessage>(Some(100));
+    event_bus.register::<ControllerMessage>(Some(100));
+    event_bus.register::<TxConfirmMessage>(Some(100));
+    event_bus.register::<IndexerMessage>(Some(100));
+    event_bus.register::<RpcSubscriptionMessage>(Some(100));
+
+    event_bus
+}
+
+/// Controller over the `storage` that doesn't send anything to the peers.
+fn controller(
+    event_bus: &EventBus,
+    storage: &LevelDB,
+) -> Controller<LevelDB, LevelDB, OfflineHandle> {
+    Controller::new(
+        event_bus,
+        storage.clone(),
+        storage.clone(),
+        OfflineHandle,
+        100,
+    )
+}
+
 fn do_test_update_inv_with_max(
     inv: Vec<Txid>,
     txs: Vec<Txid>,
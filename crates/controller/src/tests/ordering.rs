@@ -0,0 +1,118 @@
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    Txid, Witness,
+};
+use yuv_storage::TxPosition;
+
+use crate::canonical_order;
+
+fn at(block_height: usize, block_index: usize) -> Option<TxPosition> {
+    Some(TxPosition::new(block_height, block_index))
+}
+
+fn tx(parents: &[Txid], value: u64) -> Transaction {
+    let mut input = parents
+        .iter()
+        .map(|parent| TxIn {
+            previous_output: OutPoint::new(*parent, 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        })
+        .collect::<Vec<_>>();
+
+    if input.is_empty() {
+        input.push(TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), value as u32),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        });
+    }
+
+    Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input,
+        output: vec![TxOut {
+            value,
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+#[test]
+fn test_order_does_not_depend_on_attach_order() {
+    let first = tx(&[], 1);
+    let second = tx(&[], 2);
+    let third = tx(&[], 3);
+
+    let order = canonical_order([
+        (&first, at(10, 2)),
+        (&second, at(10, 1)),
+        (&third, at(10, 3)),
+    ]);
+    let reversed = canonical_order([
+        (&third, at(10, 3)),
+        (&second, at(10, 1)),
+        (&first, at(10, 2)),
+    ]);
+
+    assert_eq!(order, reversed);
+    assert_eq!(
+        order,
+        vec![second.txid(), first.txid(), third.txid()],
+        "same-block txs should be ordered by their index in the block"
+    );
+
+    let unmined = canonical_order([(&first, None), (&second, None), (&third, None)]);
+    let mut sorted = unmined.clone();
+    sorted.sort();
+    assert_eq!(unmined, sorted, "unmined txs should be ordered by txid");
+}
+
+#[test]
+fn test_order_is_by_position() {
+    let parent = tx(&[], 1);
+    let child = tx(&[parent.txid()], 2);
+    let grandchild = tx(&[child.txid()], 3);
+    let earlier = tx(&[], 4);
+    let unmined = tx(&[], 5);
+
+    let order = canonical_order([
+        (&unmined, None),
+        (&grandchild, at(11, 0)),
+        (&child, at(10, 4)),
+        (&parent, at(10, 3)),
+        (&earlier, at(9, 7)),
+    ]);
+
+    assert_eq!(
+        order,
+        vec![
+            earlier.txid(),
+            parent.txid(),
+            child.txid(),
+            grandchild.txid(),
+            unmined.txid()
+        ]
+    );
+}
+
+#[test]
+fn test_children_follow_parents_regardless_of_txid() {
+    let parent = tx(&[], 1);
+    let children = (2..10)
+        .map(|value| tx(&[parent.txid()], value))
+        .collect::<Vec<_>>();
+
+    let order = canonical_order(
+        children
+            .iter()
+            .map(|child| (child, None))
+            .chain([(&parent, None)]),
+    );
+
+    assert_eq!(order.len(), children.len() + 1);
+    assert_eq!(order[0], parent.txid());
+}
@@ -0,0 +1,111 @@
+use bitcoin::{blockdata::locktime::absolute::LockTime, hashes::Hash, OutPoint, Transaction, Txid};
+use yuv_storage::{
+    ChromaInfoStorage, FrozenTxsStorage, LevelDB, MempoolEntryStorage, MempoolStatus,
+    MempoolStorage, PagesStorage, TransactionsStorage,
+};
+use yuv_tx_attach::test_util::chroma;
+use yuv_types::{
+    announcements::{
+        ChromaAnnouncement, FreezeAnnouncement, IssueAnnouncement, UpdateChromaAnnouncement,
+    },
+    Announcement, ControllerMessage, YuvTransaction, YuvTxType,
+};
+
+use super::{controller, event_bus};
+
+fn announcement_tx(lock_time: u32, announcement: Announcement) -> YuvTransaction {
+    YuvTransaction {
+        bitcoin_tx: Transaction {
+            version: 1,
+            lock_time: LockTime::from_height(lock_time).expect("failed to create lock time"),
+            input: vec![],
+            output: vec![],
+        },
+        tx_type: YuvTxType::Announcement(announcement),
+    }
+}
+
+#[tokio::test]
+async fn test_rollback_reverts_announcements() {
+    let storage = LevelDB::in_memory().unwrap();
+    let event_bus = event_bus();
+
+    let mut controller = controller(&event_bus, &storage);
+
+    let chroma = chroma();
+    let chroma_announcement =
+        ChromaAnnouncement::new(chroma, "Token".into(), "TKN".into(), 8, 1000, true).unwrap();
+    let update = UpdateChromaAnnouncement::new(chroma, "New Token".into(), "NTKN".into()).unwrap();
+    let updated_announcement = update.apply(&chroma_announcement);
+    let freeze_outpoint = OutPoint::new(Txid::all_zeros(), 0);
+
+    let chroma_tx = announcement_tx(1, Announcement::Chroma(chroma_announcement.clone()));
+    let issue_tx = announcement_tx(2, Announcement::Issue(IssueAnnouncement::new(chroma, 100)));
+    let update_tx = announcement_tx(3, Announcement::UpdateChroma(update));
+    let freeze_tx = announcement_tx(
+        4,
+        Announcement::Freeze(FreezeAnnouncement::new(chroma, freeze_outpoint)),
+    );
+    let txs = [&chroma_tx, &issue_tx, &update_tx, &freeze_tx];
+    let txids = txs.map(|tx| tx.bitcoin_tx.txid());
+
+    // The state the checker leaves after the announcements are mined and attached.
+    for tx in txs {
+        storage.put_yuv_tx(tx.clone()).await.unwrap();
+    }
+    storage.put_page(0, txids.to_vec()).await.unwrap();
+    storage
+        .put_chroma_info(&chroma, Some(updated_announcement.clone()), 100, 0, 1, None)
+        .await
+        .unwrap();
+    storage
+        .put_chroma_announcement_version(&chroma, 0, chroma_announcement)
+        .await
+        .unwrap();
+    storage
+        .put_chroma_announcement_version(&chroma, 1, updated_announcement)
+        .await
+        .unwrap();
+    storage
+        .put_frozen_tx(&freeze_outpoint, txids[3], chroma, None, None)
+        .await
+        .unwrap();
+
+    controller
+        .handle_reorganization(vec![], txids.to_vec(), 0)
+        .await
+        .unwrap();
+
+    let chroma_info = storage.get_chroma_info(&chroma).await.unwrap().unwrap();
+    assert_eq!(chroma_info.announcement, None);
+    assert_eq!(chroma_info.announcement_version, 0);
+    assert_eq!(chroma_info.total_supply, 0);
+    for version in [0, 1] {
+        assert!(
+            storage
+                .get_chroma_announcement_version(&chroma, version)
+                .await
+                .unwrap()
+                .is_none(),
+            "the versions of the detached announcements should be removed"
+        );
+    }
+    assert!(
+        storage
+            .get_frozen_tx(&freeze_outpoint)
+            .await
+            .unwrap()
+            .is_none(),
+        "the freeze of the detached announcement should be removed"
+    );
+
+    assert_eq!(storage.get_page_by_num(0).await.unwrap(), Some(vec![]));
+    let mempool = storage.get_mempool().await.unwrap().unwrap_or_default();
+    for txid in &txids {
+        assert!(storage.get_yuv_tx(txid).await.unwrap().is_none());
+        assert!(mempool.contains(txid));
+
+        let entry = storage.get_mempool_entry(txid).await.unwrap().unwrap();
+        assert_eq!(entry.status, MempoolStatus::WaitingMined);
+    }
+}
@@ -0,0 +1,115 @@
+use event_bus::EventBus;
+use yuv_p2p::client::handle::OfflineHandle;
+use yuv_storage::{
+    LevelDB, MempoolEntryStorage, MempoolStatus, MempoolStorage, MempoolTxEntry, PagesStorage,
+    TransactionsStorage,
+};
+use yuv_tx_attach::test_util::{issuance, transfer_of};
+use yuv_types::{ControllerMessage, YuvTransaction};
+
+use super::{controller, event_bus};
+use crate::Controller;
+
+const ZERO_CONF_MAX_AMOUNT: u128 = 1000;
+
+/// Puts the transaction to the mempool as if it was checked and is waiting to be mined.
+async fn put_waiting_mined(storage: &LevelDB, yuv_tx: &YuvTransaction) {
+    storage
+        .put_mempool_entry(MempoolTxEntry::new(
+            yuv_tx.clone(),
+            MempoolStatus::WaitingMined,
+            None,
+        ))
+        .await
+        .unwrap();
+    storage
+        .put_mempool(vec![yuv_tx.bitcoin_tx.txid()])
+        .await
+        .unwrap();
+}
+
+/// Controller that provisionally attaches the transfers of up to [`ZERO_CONF_MAX_AMOUNT`].
+fn zero_conf_controller(
+    event_bus: &EventBus,
+    storage: &LevelDB,
+) -> Controller<LevelDB, LevelDB, OfflineHandle> {
+    controller(event_bus, storage).set_zero_conf_max_amount(Some(ZERO_CONF_MAX_AMOUNT))
+}
+
+#[tokio::test]
+async fn test_eligible_transfer_is_provisionally_attached() {
+    let storage = LevelDB::in_memory().unwrap();
+    let event_bus = event_bus();
+    let mut controller = zero_conf_controller(&event_bus, &storage);
+
+    let parent = issuance(1);
+    storage.put_yuv_tx(parent.clone()).await.unwrap();
+
+    let tx = transfer_of(2, &[parent.bitcoin_tx.txid()], 100);
+    let txid = tx.bitcoin_tx.txid();
+    put_waiting_mined(&storage, &tx).await;
+
+    controller
+        .handle_event(ControllerMessage::UnconfirmedTxs(vec![txid]))
+        .await
+        .unwrap();
+
+    assert!(storage.get_yuv_tx(&txid).await.unwrap().is_some());
+    assert_eq!(storage.get_page_by_num(0).await.unwrap(), Some(vec![txid]));
+    let entry = storage.get_mempool_entry(&txid).await.unwrap().unwrap();
+    assert_eq!(entry.status, MempoolStatus::ProvisionallyAttached);
+    assert!(entry.provisionally_attached);
+
+    controller
+        .handle_event(ControllerMessage::ExpiredTxs(vec![txid]))
+        .await
+        .unwrap();
+
+    assert!(
+        storage.get_yuv_tx(&txid).await.unwrap().is_none(),
+        "expired tx should be removed from the storage"
+    );
+    assert_eq!(storage.get_page_by_num(0).await.unwrap(), Some(vec![]));
+    assert!(storage.get_mempool_entry(&txid).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_ineligible_transfers_are_not_attached() {
+    let parent = issuance(1);
+    let cases = [
+        ("amount over the max", true, ZERO_CONF_MAX_AMOUNT + 1),
+        ("parent isn't attached", false, 100),
+    ];
+
+    for (case, parent_attached, amount) in cases {
+        let storage = LevelDB::in_memory().unwrap();
+        let event_bus = event_bus();
+        let mut controller = zero_conf_controller(&event_bus, &storage);
+
+        if parent_attached {
+            storage.put_yuv_tx(parent.clone()).await.unwrap();
+        }
+
+        let tx = transfer_of(2, &[parent.bitcoin_tx.txid()], amount);
+        let txid = tx.bitcoin_tx.txid();
+        put_waiting_mined(&storage, &tx).await;
+
+        controller
+            .handle_event(ControllerMessage::UnconfirmedTxs(vec![txid]))
+            .await
+            .unwrap();
+
+        assert!(
+            storage.get_yuv_tx(&txid).await.unwrap().is_none(),
+            "{case}: tx shouldn't be stored"
+        );
+        assert_eq!(
+            storage.get_page_by_num(0).await.unwrap(),
+            None,
+            "{case}: tx shouldn't be paged"
+        );
+        let entry = storage.get_mempool_entry(&txid).await.unwrap().unwrap();
+        assert_eq!(entry.status, MempoolStatus::WaitingMined, "{case}");
+        assert!(!entry.provisionally_attached, "{case}");
+    }
+}
@@ -0,0 +1,173 @@
+//! Encrypted backups of the wallet's YUV state.
+//!
+//! Unlike the Bitcoin outputs, the pixel proofs of the YUV outputs can't be recovered from the
+//! blockchain, so the wallet moved to another machine has to sync them from the YUV node again.
+//! The [`WalletBackup`] contains everything needed to restore the wallet without that: the
+//! wallet's key in WIF, its descriptors and the known proofs of the unspent outputs, along with
+//! the number of the node's pages they are synced from.
+//!
+//! The backup file is the [`BACKUP_MAGIC`], the [`BACKUP_VERSION`] and the random nonce, followed
+//! by the CBOR-encoded backup encrypted with ChaCha20-Poly1305. The encryption key is derived
+//! from the wallet's private key, so the same backup is restored only by the wallet with the same
+//! key, and no passphrase has to be remembered.
+use std::collections::HashMap;
+
+use bitcoin::{
+    hashes::{
+        hmac::{Hmac, HmacEngine},
+        sha256, Hash, HashEngine,
+    },
+    Network, OutPoint, PrivateKey,
+};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use eyre::{ensure, eyre, Context};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use yuv_pixels::PixelProof;
+
+use crate::PixelDescriptor;
+
+/// Magic bytes the backup file starts with.
+pub const BACKUP_MAGIC: &[u8; 4] = b"YUVB";
+
+/// Current version of the backup format.
+pub const BACKUP_VERSION: u8 = 1;
+
+/// Tag of the key derivation from the wallet's private key.
+const BACKUP_KEY_TAG: &[u8] = b"yuv-wallet-backup";
+
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = BACKUP_MAGIC.len() + 1;
+
+/// YUV state of the wallet, which is needed to restore it on another machine.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletBackup {
+    pub network: Network,
+    /// Private key of the wallet in WIF.
+    pub wif: String,
+    /// Descriptor of the Bitcoin wallet.
+    pub descriptor: String,
+    /// Watched descriptors of the pixel outputs.
+    pub pixel_descriptors: Vec<PixelDescriptor>,
+    /// Known proofs of the unspent YUV outputs of the wallet.
+    pub proofs: HashMap<OutPoint, PixelProof>,
+    /// Number of the YUV node's pages of transactions the proofs are synced from.
+    pub pages_number: u64,
+}
+
+impl WalletBackup {
+    /// Encrypt the backup with the key derived from the `private_key`.
+    pub fn encrypt(&self, private_key: &PrivateKey) -> eyre::Result<Vec<u8>> {
+        let mut plaintext = Vec::new();
+        ciborium::into_writer(self, &mut plaintext).wrap_err("Failed to encode the backup")?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let mut data = Vec::with_capacity(HEADER_LEN + NONCE_LEN + plaintext.len());
+        data.extend_from_slice(BACKUP_MAGIC);
+        data.push(BACKUP_VERSION);
+
+        let ciphertext = cipher(private_key)
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &data,
+                },
+            )
+            .map_err(|_| eyre!("Failed to encrypt the backup"))?;
+
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+
+        Ok(data)
+    }
+
+    /// Decrypt the backup with the key derived from the `private_key`.
+    pub fn decrypt(data: &[u8], private_key: &PrivateKey) -> eyre::Result<Self> {
+        ensure!(
+            data.len() > HEADER_LEN + NONCE_LEN && data.starts_with(BACKUP_MAGIC),
+            "Not a YUV wallet backup"
+        );
+
+        let (header, data) = data.split_at(HEADER_LEN);
+        let version = header[BACKUP_MAGIC.len()];
+        ensure!(
+            version == BACKUP_VERSION,
+            "Unsupported backup version {}, expected {}",
+            version,
+            BACKUP_VERSION
+        );
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = cipher(private_key)
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| {
+                eyre!("Failed to decrypt the backup, it's corrupted or made with another key")
+            })?;
+
+        ciborium::from_reader(plaintext.as_slice()).wrap_err("Failed to decode the backup")
+    }
+}
+
+/// Cipher with the key derived from the wallet's private key.
+fn cipher(private_key: &PrivateKey) -> ChaCha20Poly1305 {
+    let mut engine = HmacEngine::<sha256::Hash>::new(BACKUP_KEY_TAG);
+    engine.input(&private_key.inner.secret_bytes());
+    let key = Hmac::<sha256::Hash>::from_engine(engine);
+
+    ChaCha20Poly1305::new(Key::from_slice(key.as_byte_array()))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use yuv_pixels::Pixel;
+
+    use super::*;
+
+    fn regtest_key(byte: u8) -> PrivateKey {
+        PrivateKey::new(
+            SecretKey::from_slice(&[byte; 32]).unwrap(),
+            Network::Regtest,
+        )
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let ctx = Secp256k1::new();
+        let private_key = regtest_key(1);
+        let inner_key = private_key.public_key(&ctx).inner;
+        let descriptor =
+            PixelDescriptor::new(inner_key, Pixel::new(100, inner_key.x_only_public_key().0));
+
+        let backup = WalletBackup {
+            network: private_key.network,
+            wif: private_key.to_wif(),
+            descriptor: format!("wpkh({})", private_key.to_wif()),
+            pixel_descriptors: vec![descriptor],
+            proofs: HashMap::from([(OutPoint::null(), descriptor.proof())]),
+            pages_number: 3,
+        };
+
+        let data = backup.encrypt(&private_key).unwrap();
+        assert!(data.starts_with(BACKUP_MAGIC));
+        assert_eq!(WalletBackup::decrypt(&data, &private_key).unwrap(), backup);
+
+        assert!(WalletBackup::decrypt(&data, &regtest_key(2)).is_err());
+
+        let mut corrupted = data.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(WalletBackup::decrypt(&corrupted, &private_key).is_err());
+    }
+}
@@ -1,16 +1,17 @@
 use std::result::Result::Ok;
 use std::{ops::Deref, sync::Arc};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use bdk::{
     bitcoincore_rpc::RpcApi,
     blockchain::{
         rpc::Auth, AnyBlockchain, AnyBlockchainConfig, ConfigurableBlockchain, EsploraBlockchain,
-        RpcBlockchain,
+        GetBlockHash, GetHeight, RpcBlockchain,
     },
 };
-use bitcoin::{Network, OutPoint, Txid};
+use bitcoin::{bip158::BlockFilter, Block, BlockHash, Network, OutPoint, Txid};
+use yuv_types::network::parse_bitcoin_network;
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -30,6 +31,7 @@ pub struct BitcoinRpcConfig {
     /// Bitcoin node url
     pub url: String,
     /// Bitcoin's network
+    #[serde(deserialize_with = "deserialize_network")]
     pub network: Network,
     /// Auth params for rpc
     pub auth: Auth,
@@ -42,6 +44,7 @@ pub struct EsploraConfig {
     /// Esplora api url
     pub url: String,
     /// Bitcoin's network
+    #[serde(deserialize_with = "deserialize_network")]
     pub network: Network,
     /// It is a setting that determines when to stop fetching transactions
     /// for a set of addresses by indicating a gap of unused addresses.
@@ -50,6 +53,17 @@ pub struct EsploraConfig {
     pub stop_gap: usize,
 }
 
+/// Deserialize the Bitcoin network, including the ones the `bitcoin` crate doesn't know yet, e.g.
+/// `testnet4`.
+fn deserialize_network<'de, D>(deserializer: D) -> Result<Network, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let network = String::deserialize(deserializer)?;
+
+    parse_bitcoin_network(&network).map_err(serde::de::Error::custom)
+}
+
 /// This describes the on-chain status of the output: whether it exists at all, and if it does, whether it has been spent
 pub enum TxOutputStatus {
     /// Output was spent
@@ -73,6 +87,15 @@ pub trait BitcoinProvider: Clone {
     fn is_tx_confirmed(&self, txid: &Txid) -> eyre::Result<bool> {
         Ok(self.get_tx_confirmations(txid)? >= REQUIRED_CONFIRMATIONS)
     }
+    /// Returns the height of the best block.
+    fn get_height(&self) -> eyre::Result<u32>;
+    /// Returns the hash of the block at the `height`.
+    fn get_block_hash(&self, height: u32) -> eyre::Result<BlockHash>;
+    /// Returns the block by its hash.
+    fn get_block(&self, block_hash: &BlockHash) -> eyre::Result<Block>;
+    /// Returns the BIP158 basic compact filter of the block. Requires the Bitcoin node to be run
+    /// with `-blockfilterindex`.
+    fn get_block_filter(&self, block_hash: &BlockHash) -> eyre::Result<BlockFilter>;
 }
 
 /// Transaction confirmations amount when tx can be considered as confirmed.
@@ -83,11 +106,12 @@ pub struct AnyBitcoinProvider(Arc<AnyBlockchain>);
 
 impl AnyBitcoinProvider {
     pub fn from_blockchain(blockchain: Arc<AnyBlockchain>) -> eyre::Result<Self> {
-        if let AnyBlockchain::Electrum(_) = blockchain.deref() {
-            eyre::bail!("Unsupported bitcoin provider");
+        match blockchain.deref() {
+            AnyBlockchain::Esplora(_) | AnyBlockchain::Rpc(_) => Ok(Self(blockchain)),
+            // Other backends are available only if some crate enables them in BDK.
+            #[allow(unreachable_patterns)]
+            _ => eyre::bail!("Unsupported bitcoin provider"),
         }
-
-        Ok(Self(blockchain))
     }
 }
 
@@ -128,6 +152,7 @@ impl BitcoinProvider for AnyBitcoinProvider {
 
                 Ok(output_status)
             }
+            #[allow(unreachable_patterns)]
             _ => eyre::bail!("Unsupported bitcoin provider"),
         }
     }
@@ -140,6 +165,7 @@ impl BitcoinProvider for AnyBitcoinProvider {
         match cfg {
             AnyBlockchainConfig::Esplora(cfg) => EsploraBlockchain::from_config(&cfg)?.try_into(),
             AnyBlockchainConfig::Rpc(cfg) => RpcBlockchain::from_config(&cfg)?.try_into(),
+            #[allow(unreachable_patterns)]
             _ => eyre::bail!("Unsupported bitcoin provider"),
         }
     }
@@ -160,7 +186,39 @@ impl BitcoinProvider for AnyBitcoinProvider {
 
                 Ok(tx_info.confirmations.unwrap_or_default())
             }
+            #[allow(unreachable_patterns)]
             _ => eyre::bail!("Unsupported bitcoin provider"),
         }
     }
+
+    fn get_height(&self) -> eyre::Result<u32> {
+        Ok(GetHeight::get_height(self.0.deref())?)
+    }
+
+    fn get_block_hash(&self, height: u32) -> eyre::Result<BlockHash> {
+        Ok(GetBlockHash::get_block_hash(self.0.deref(), height as u64)?)
+    }
+
+    fn get_block(&self, block_hash: &BlockHash) -> eyre::Result<Block> {
+        match self.0.deref() {
+            AnyBlockchain::Esplora(esplora) => esplora
+                .get_block_by_hash(block_hash)?
+                .ok_or_else(|| eyre::eyre!("Block {} is not found", block_hash)),
+            AnyBlockchain::Rpc(rpc) => Ok(rpc.get_block(block_hash)?),
+            #[allow(unreachable_patterns)]
+            _ => eyre::bail!("Unsupported bitcoin provider"),
+        }
+    }
+
+    fn get_block_filter(&self, block_hash: &BlockHash) -> eyre::Result<BlockFilter> {
+        match self.0.deref() {
+            AnyBlockchain::Rpc(rpc) => {
+                let result = rpc.get_block_filter(block_hash)?;
+
+                Ok(BlockFilter::new(&result.filter))
+            }
+            // Esplora doesn't serve the compact block filters.
+            _ => eyre::bail!("Block filters are not supported by the bitcoin provider"),
+        }
+    }
 }
@@ -15,3 +15,16 @@ pub mod bitcoin_provider;
 pub use bitcoin_provider::AnyBitcoinProvider;
 
 pub mod txsigner;
+pub use txsigner::TransactionSigner;
+
+pub mod yuv_psbt;
+pub use yuv_psbt::YuvPsbt;
+
+pub mod pixel_descriptor;
+pub use pixel_descriptor::PixelDescriptor;
+
+pub mod submission;
+pub use submission::{Rejection, SubmissionState, SubmitOptions};
+
+pub mod backup;
+pub use backup::WalletBackup;
@@ -0,0 +1,186 @@
+//! Descriptors of the outputs locked by the keys tweaked with the pixels.
+//!
+//! `yuvpkh(KEY,CHROMA,AMOUNT)` describes the `P2WPKH` output with the pixel of `AMOUNT` tokens of
+//! `CHROMA` locked by the `KEY`, and `yuvpkh(KEY)` describes the output with the empty pixel, i.e.
+//! the tweaked satoshis. As the key is tweaked with the whole pixel, including the amount, the
+//! outputs can't be described by the chroma only.
+//!
+//! The descriptor is converted to the plain `wpkh` descriptor of the tweaked key, so BDK syncs
+//! the outputs like any other ones.
+use core::fmt;
+use std::str::FromStr;
+
+use bdk::{
+    descriptor::{Descriptor, DescriptorPublicKey, ExtendedDescriptor},
+    miniscript::descriptor::{SinglePub, SinglePubKey},
+};
+use bitcoin::{secp256k1, ScriptBuf};
+use eyre::{bail, eyre, Context};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use yuv_pixels::{Chroma, EmptyPixelProof, Pixel, PixelKey, PixelProof};
+
+/// Name of the pixel descriptor's function.
+pub const PIXEL_DESCRIPTOR_NAME: &str = "yuvpkh";
+
+/// Descriptor of the `P2WPKH` output locked by the `inner_key` tweaked with the `pixel`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PixelDescriptor {
+    /// Key of the owner of the output.
+    pub inner_key: secp256k1::PublicKey,
+    /// Pixel the key is tweaked with.
+    pub pixel: Pixel,
+}
+
+impl PixelDescriptor {
+    pub fn new(inner_key: secp256k1::PublicKey, pixel: Pixel) -> Self {
+        Self { inner_key, pixel }
+    }
+
+    /// Descriptor of the output with the empty pixel.
+    pub fn empty(inner_key: secp256k1::PublicKey) -> Self {
+        Self::new(inner_key, Pixel::empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pixel == Pixel::empty()
+    }
+
+    pub fn pixel_key(&self) -> eyre::Result<PixelKey> {
+        Ok(PixelKey::new(self.pixel, &self.inner_key)?)
+    }
+
+    pub fn script_pubkey(&self) -> eyre::Result<ScriptBuf> {
+        self.pixel_key()?
+            .to_p2wpkh()
+            .ok_or_else(|| eyre!("Pixel key is uncompressed"))
+    }
+
+    /// Returns the proof of the outputs described by the descriptor.
+    pub fn proof(&self) -> PixelProof {
+        if self.is_empty() {
+            return PixelProof::EmptyPixel(EmptyPixelProof::new(self.inner_key));
+        }
+
+        PixelProof::sig(self.pixel, self.inner_key)
+    }
+
+    /// Convert to the `wpkh` descriptor of the tweaked key.
+    pub fn to_descriptor(&self) -> eyre::Result<ExtendedDescriptor> {
+        let tweaked_key = DescriptorPublicKey::Single(SinglePub {
+            origin: None,
+            key: SinglePubKey::FullKey(bitcoin::PublicKey::new(*self.pixel_key()?)),
+        });
+
+        Ok(Descriptor::new_wpkh(tweaked_key)?)
+    }
+}
+
+impl fmt::Display for PixelDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "{}({})", PIXEL_DESCRIPTOR_NAME, self.inner_key);
+        }
+
+        write!(
+            f,
+            "{}({},{},{})",
+            PIXEL_DESCRIPTOR_NAME, self.inner_key, self.pixel.chroma, self.pixel.luma.amount
+        )
+    }
+}
+
+impl FromStr for PixelDescriptor {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let args = s
+            .strip_prefix(PIXEL_DESCRIPTOR_NAME)
+            .and_then(|s| s.strip_prefix('('))
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or_else(|| eyre!("Expected {}(KEY[,CHROMA,AMOUNT])", PIXEL_DESCRIPTOR_NAME))?;
+
+        let args = args.split(',').map(str::trim).collect::<Vec<_>>();
+
+        let inner_key = secp256k1::PublicKey::from_str(args[0]).wrap_err("Invalid key")?;
+
+        match args[1..] {
+            [] => Ok(Self::empty(inner_key)),
+            [chroma, amount] => {
+                let chroma = Chroma::from_str(chroma).wrap_err("Invalid chroma")?;
+                let amount = u128::from_str(amount).wrap_err("Invalid amount")?;
+
+                Ok(Self::new(inner_key, Pixel::new(amount, chroma)))
+            }
+            _ => bail!("Expected {}(KEY[,CHROMA,AMOUNT])", PIXEL_DESCRIPTOR_NAME),
+        }
+    }
+}
+
+impl Serialize for PixelDescriptor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PixelDescriptor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let descriptor = String::deserialize(deserializer)?;
+
+        Self::from_str(&descriptor).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+
+    use super::*;
+
+    fn keys() -> (secp256k1::PublicKey, secp256k1::PublicKey) {
+        let ctx = Secp256k1::new();
+
+        (
+            SecretKey::from_slice(&[1; 32]).unwrap().public_key(&ctx),
+            SecretKey::from_slice(&[2; 32]).unwrap().public_key(&ctx),
+        )
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let (inner_key, issuer_key) = keys();
+
+        for descriptor in [
+            PixelDescriptor::new(inner_key, Pixel::new(100, issuer_key.x_only_public_key().0)),
+            PixelDescriptor::empty(inner_key),
+        ] {
+            let parsed = PixelDescriptor::from_str(&descriptor.to_string()).unwrap();
+
+            assert_eq!(parsed, descriptor);
+        }
+
+        assert!(PixelDescriptor::from_str("wpkh(00)").is_err());
+        assert!(PixelDescriptor::from_str(&format!("yuvpkh({inner_key},100)")).is_err());
+    }
+
+    #[test]
+    fn test_descriptor_script_matches_pixel_key() {
+        let (inner_key, issuer_key) = keys();
+        let descriptor =
+            PixelDescriptor::new(inner_key, Pixel::new(100, issuer_key.x_only_public_key().0));
+
+        assert_eq!(
+            descriptor
+                .to_descriptor()
+                .unwrap()
+                .at_derivation_index(0)
+                .unwrap()
+                .script_pubkey(),
+            descriptor.script_pubkey().unwrap()
+        );
+        assert!(matches!(descriptor.proof(), PixelProof::Sig(_)));
+        assert!(matches!(
+            PixelDescriptor::empty(inner_key).proof(),
+            PixelProof::EmptyPixel(_)
+        ));
+    }
+}
@@ -0,0 +1,730 @@
+//! Retry-safe submission of YUV transactions.
+//!
+//! A YUV transaction is useless for its recipients if the Bitcoin transaction gets to the network,
+//! but the node never receives the proofs. That's why the [`Wallet::submit`] first asks the node
+//! to broadcast the transaction with its proofs. If that fails, the proofs are provided to the
+//! node *before* the Bitcoin transaction is broadcasted by the wallet itself, so the network never
+//! sees a YUV-less transaction. If the node rejects the transaction or its proofs, e.g. for
+//! failing the validation, the submission stops with the [`Rejection`] and the transaction is
+//! never broadcasted, as its tokens would be burnt.
+//!
+//! Each submission is persisted along with its proofs until it's completed, so the interrupted
+//! submissions can be continued with [`Wallet::resume_submissions`].
+//!
+//! [`Wallet::submit`]: crate::Wallet::submit
+//! [`Wallet::resume_submissions`]: crate::Wallet::resume_submissions
+use std::{fmt, future::Future, time::Duration};
+
+use bdk::blockchain::{Blockchain, GetTx};
+use bitcoin::{Transaction, Txid};
+use eyre::{ensure, WrapErr};
+use jsonrpsee::{
+    core::{async_trait, Error},
+    types::error::INTERNAL_ERROR_CODE,
+};
+use serde::{Deserialize, Serialize};
+use yuv_rpc_api::transactions::{YuvTransactionsRpcClient, RATE_LIMITED_CODE};
+use yuv_storage::KeyValueStorage;
+use yuv_types::YuvTransaction;
+
+use crate::bitcoin_provider::BitcoinProvider;
+
+const PENDING_SUBMISSIONS_KEY: &[u8; 19] = b"pending_submissions";
+const PENDING_SUBMISSIONS_KEY_LEN: usize = PENDING_SUBMISSIONS_KEY.len();
+
+const SUBMISSION_KEY_PREFIX: &[u8; 11] = b"submission-";
+const SUBMISSION_KEY_PREFIX_LEN: usize = SUBMISSION_KEY_PREFIX.len();
+
+/// Key of the submission of the transaction in the [`PendingSubmissionsStorage`].
+pub type SubmissionKey = (&'static [u8; SUBMISSION_KEY_PREFIX_LEN], Txid);
+
+/// Default number of attempts for each step of the submission.
+pub const DEFAULT_SUBMIT_ATTEMPTS: u32 = 3;
+
+/// Default delay between the attempts.
+pub const DEFAULT_SUBMIT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Options of the [`Wallet::submit`](crate::Wallet::submit).
+#[derive(Clone, Debug)]
+pub struct SubmitOptions {
+    /// Maximum amount of satoshis burnt in the provably unspendable outputs.
+    pub max_burn_amount: Option<u64>,
+    /// Number of attempts for each step of the submission.
+    pub attempts: u32,
+    /// Delay between the attempts.
+    pub retry_interval: Duration,
+}
+
+impl Default for SubmitOptions {
+    fn default() -> Self {
+        Self {
+            max_burn_amount: None,
+            attempts: DEFAULT_SUBMIT_ATTEMPTS,
+            retry_interval: DEFAULT_SUBMIT_RETRY_INTERVAL,
+        }
+    }
+}
+
+/// State of the transaction's submission.
+///
+/// ```text
+/// Pending ──(node broadcasts)────────────────────────────────► Completed
+///    │  │                                                          ▲
+///    │  └──(node fails)──► ProofProvided ──(wallet broadcasts)─────┘
+///    │
+///    └──(node rejects the transaction or the proofs)─────────► Rejected
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SubmissionState {
+    /// Neither the node nor the Bitcoin network has the transaction.
+    Pending,
+    /// The node has the proofs, but the Bitcoin transaction is not broadcasted yet.
+    ProofProvided,
+    /// The Bitcoin transaction is broadcasted and the node has the proofs.
+    Completed,
+    /// The node rejected the transaction, so it's never broadcasted by the wallet. The reason is
+    /// in the [`Submission::last_error`].
+    Rejected,
+}
+
+/// Submission of the YUV transaction to the node and the Bitcoin network.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Submission {
+    pub tx: YuvTransaction,
+    pub state: SubmissionState,
+    /// Maximum amount of satoshis burnt in the provably unspendable outputs.
+    pub max_burn_amount: Option<u64>,
+    /// Number of the failed attempts.
+    pub failed_attempts: u32,
+    /// Error of the last failed attempt.
+    pub last_error: Option<String>,
+}
+
+impl Submission {
+    pub fn new(tx: YuvTransaction, max_burn_amount: Option<u64>) -> Self {
+        Self {
+            tx,
+            state: SubmissionState::Pending,
+            max_burn_amount,
+            failed_attempts: 0,
+            last_error: None,
+        }
+    }
+
+    pub fn txid(&self) -> Txid {
+        self.tx.bitcoin_tx.txid()
+    }
+
+    /// Returns `true` if the submission is completed.
+    pub fn is_completed(&self) -> bool {
+        self.state == SubmissionState::Completed
+    }
+
+    /// Returns `true` if the submission is either completed or rejected, so there is nothing
+    /// left to continue.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.state,
+            SubmissionState::Completed | SubmissionState::Rejected
+        )
+    }
+
+    pub(crate) fn record_failure(&mut self, error: &eyre::Report) {
+        self.failed_attempts += 1;
+        self.last_error = Some(format!("{:#}", error));
+    }
+}
+
+/// Rejection of the transaction by the node, e.g. for failing the validation. The rejected
+/// submission is neither retried nor continued, as the broadcast of the transaction the node
+/// doesn't accept burns its tokens.
+#[derive(Debug)]
+pub struct Rejection(pub String);
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node rejected the transaction: {}", self.0)
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+/// Returns `true` if the error is the [`Rejection`] of the transaction by the node.
+pub fn is_rejection(error: &eyre::Report) -> bool {
+    error.downcast_ref::<Rejection>().is_some()
+}
+
+/// Node and Bitcoin network the transaction is submitted to.
+#[async_trait]
+pub(crate) trait SubmissionClient {
+    /// Asks the node to broadcast the transaction and to check its proofs.
+    async fn send_yuv_tx(
+        &self,
+        tx: &YuvTransaction,
+        max_burn_amount: Option<u64>,
+    ) -> eyre::Result<()>;
+
+    /// Provides the proofs of the transaction to the node.
+    async fn provide_yuv_proof(&self, tx: &YuvTransaction) -> eyre::Result<()>;
+
+    /// Broadcasts the transaction to the Bitcoin network, unless it's already there.
+    async fn broadcast(&self, tx: &Transaction) -> eyre::Result<()>;
+}
+
+/// [`SubmissionClient`] of the wallet, with its YUV node client and Bitcoin provider.
+pub(crate) struct WalletSubmissionClient<'a, YC, BP> {
+    pub(crate) yuv_client: &'a YC,
+    pub(crate) bitcoin_provider: &'a BP,
+}
+
+#[async_trait]
+impl<YC, BP> SubmissionClient for WalletSubmissionClient<'_, YC, BP>
+where
+    YC: YuvTransactionsRpcClient + Send + Sync,
+    BP: BitcoinProvider + Send + Sync,
+{
+    async fn send_yuv_tx(
+        &self,
+        tx: &YuvTransaction,
+        max_burn_amount: Option<u64>,
+    ) -> eyre::Result<()> {
+        let accepted = self
+            .yuv_client
+            .send_yuv_tx(tx.hex(), max_burn_amount)
+            .await
+            .map_err(node_error)?;
+        ensure!(
+            accepted,
+            Rejection("node didn't accept the transaction".to_string())
+        );
+
+        Ok(())
+    }
+
+    async fn provide_yuv_proof(&self, tx: &YuvTransaction) -> eyre::Result<()> {
+        let accepted = self
+            .yuv_client
+            .provide_yuv_proof(tx.clone())
+            .await
+            .map_err(node_error)?;
+        ensure!(
+            accepted,
+            Rejection("node didn't accept the proofs".to_string())
+        );
+
+        Ok(())
+    }
+
+    async fn broadcast(&self, tx: &Transaction) -> eyre::Result<()> {
+        let blockchain = self.bitcoin_provider.blockchain();
+
+        // The node could have broadcasted the transaction despite the failed response.
+        if blockchain.get_tx(&tx.txid())?.is_none() {
+            blockchain.broadcast(tx)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts the error of the node's RPC. The errors returned by the node itself are the
+/// [`Rejection`] of the transaction, except for the internal and the rate limit ones, which are
+/// worth retrying.
+fn node_error(err: Error) -> eyre::Report {
+    match err {
+        Error::Call(err) if ![INTERNAL_ERROR_CODE, RATE_LIMITED_CODE].contains(&err.code()) => {
+            Rejection(err.message().to_string()).into()
+        }
+        err => err.into(),
+    }
+}
+
+/// Returns the amount of satoshis burnt in the provably unspendable outputs of the transaction.
+pub(crate) fn burn_amount(tx: &Transaction) -> u64 {
+    tx.output
+        .iter()
+        .filter(|output| output.script_pubkey.is_provably_unspendable())
+        .map(|output| output.value)
+        .sum()
+}
+
+/// Runs the step of the submission until it succeeds, the attempts are exhausted, or the node
+/// rejects the transaction. Failed attempts are recorded to the submission.
+pub(crate) async fn retry<T, F, Fut>(
+    submission: &mut Submission,
+    options: &SubmitOptions,
+    mut step: F,
+) -> eyre::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = eyre::Result<T>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match step().await {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                submission.record_failure(&err);
+
+                if attempt >= options.attempts || is_rejection(&err) {
+                    return Err(err);
+                }
+
+                tracing::debug!(
+                    "Submission attempt {} of {} for {} failed: {:#}",
+                    attempt,
+                    options.attempts,
+                    submission.txid(),
+                    err
+                );
+
+                attempt += 1;
+                tokio::time::sleep(options.retry_interval).await;
+            }
+        }
+    }
+}
+
+/// Moves the submission through its states until it's finished. The state is persisted after
+/// each step, so the failed submission can be continued later.
+pub(crate) async fn continue_submission<C, S>(
+    client: &C,
+    storage: &S,
+    submission: &mut Submission,
+    options: &SubmitOptions,
+) -> eyre::Result<()>
+where
+    C: SubmissionClient + Sync,
+    S: PendingSubmissionsStorage + Sync,
+{
+    while !submission.is_finished() {
+        let result = submission_step(client, submission, options).await;
+        storage.put_submission(submission).await?;
+        result?;
+    }
+
+    Ok(())
+}
+
+async fn submission_step<C>(
+    client: &C,
+    submission: &mut Submission,
+    options: &SubmitOptions,
+) -> eyre::Result<()>
+where
+    C: SubmissionClient + Sync,
+{
+    match submission.state {
+        SubmissionState::Pending => {
+            let tx = submission.tx.clone();
+            let max_burn_amount = submission.max_burn_amount;
+
+            let node_broadcast = retry(submission, options, || {
+                client.send_yuv_tx(&tx, max_burn_amount)
+            })
+            .await;
+
+            match node_broadcast {
+                Ok(()) => submission.state = SubmissionState::Completed,
+                Err(err) if is_rejection(&err) => {
+                    submission.state = SubmissionState::Rejected;
+                    return Err(err);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Node failed to broadcast {}, providing the proofs first: {:#}",
+                        submission.txid(),
+                        err
+                    );
+
+                    let proofs = retry(submission, options, || client.provide_yuv_proof(&tx)).await;
+                    if let Err(err) = proofs {
+                        if is_rejection(&err) {
+                            submission.state = SubmissionState::Rejected;
+                            return Err(err);
+                        }
+
+                        return Err(err.wrap_err("failed to provide the proofs to the node"));
+                    }
+
+                    submission.state = SubmissionState::ProofProvided;
+                }
+            }
+        }
+        SubmissionState::ProofProvided => {
+            let burn_amount = burn_amount(&submission.tx.bitcoin_tx);
+            if let Some(max_burn_amount) = submission.max_burn_amount {
+                ensure!(
+                    burn_amount <= max_burn_amount,
+                    "transaction burns {} sats, which exceeds the maximum of {} sats",
+                    burn_amount,
+                    max_burn_amount
+                );
+            }
+
+            let bitcoin_tx = submission.tx.bitcoin_tx.clone();
+            retry(submission, options, || client.broadcast(&bitcoin_tx))
+                .await
+                .wrap_err("failed to broadcast the transaction")?;
+
+            submission.state = SubmissionState::Completed;
+        }
+        SubmissionState::Completed | SubmissionState::Rejected => {}
+    }
+
+    Ok(())
+}
+
+/// Storage of the submissions, which are not finished yet. Each submission is stored under its
+/// own key, and their txids are listed under the common one.
+#[async_trait]
+pub trait PendingSubmissionsStorage:
+    KeyValueStorage<&'static [u8; PENDING_SUBMISSIONS_KEY_LEN], Vec<Txid>>
+    + KeyValueStorage<SubmissionKey, Submission>
+{
+    /// Get the txids of the pending submissions.
+    async fn get_pending_submission_txids(&self) -> eyre::Result<Vec<Txid>> {
+        let txids = KeyValueStorage::<_, Vec<Txid>>::get(self, PENDING_SUBMISSIONS_KEY)
+            .await?
+            .unwrap_or_default();
+
+        Ok(txids)
+    }
+
+    async fn get_submission(&self, txid: &Txid) -> eyre::Result<Option<Submission>> {
+        let submission =
+            KeyValueStorage::<SubmissionKey, _>::get(self, (SUBMISSION_KEY_PREFIX, *txid)).await?;
+
+        Ok(submission)
+    }
+
+    async fn get_pending_submissions(&self) -> eyre::Result<Vec<Submission>> {
+        let mut submissions = Vec::new();
+        for txid in self.get_pending_submission_txids().await? {
+            if let Some(submission) = self.get_submission(&txid).await? {
+                submissions.push(submission);
+            }
+        }
+
+        Ok(submissions)
+    }
+
+    /// Stores the submission, or removes it if it's finished.
+    async fn put_submission(&self, submission: &Submission) -> eyre::Result<()> {
+        let txid = submission.txid();
+        let mut txids = self.get_pending_submission_txids().await?;
+        let is_listed = txids.contains(&txid);
+
+        if submission.is_finished() {
+            if is_listed {
+                txids.retain(|pending| *pending != txid);
+                KeyValueStorage::<_, Vec<Txid>>::put(self, PENDING_SUBMISSIONS_KEY, txids).await?;
+            }
+            KeyValueStorage::<SubmissionKey, Submission>::delete(
+                self,
+                (SUBMISSION_KEY_PREFIX, txid),
+            )
+            .await?;
+
+            return Ok(());
+        }
+
+        // The submission is written before it's listed, so the listed one is always present.
+        KeyValueStorage::<SubmissionKey, _>::put(
+            self,
+            (SUBMISSION_KEY_PREFIX, txid),
+            submission.clone(),
+        )
+        .await?;
+        if !is_listed {
+            txids.push(txid);
+            KeyValueStorage::<_, Vec<Txid>>::put(self, PENDING_SUBMISSIONS_KEY, txids).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> PendingSubmissionsStorage for T where
+    T: KeyValueStorage<&'static [u8; PENDING_SUBMISSIONS_KEY_LEN], Vec<Txid>>
+        + KeyValueStorage<SubmissionKey, Submission>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
+    use bitcoin::{absolute::LockTime, hashes::Hash, ScriptBuf, TxOut, WPubkeyHash};
+    use jsonrpsee::types::{error::INVALID_PARAMS_CODE, ErrorObject};
+    use yuv_storage::LevelDB;
+    use yuv_types::YuvTxType;
+
+    use super::*;
+
+    /// Client that answers with the queued results, `Ok` once they run out, and records the
+    /// calls.
+    #[derive(Default)]
+    struct MockClient {
+        send_results: Mutex<VecDeque<eyre::Result<()>>>,
+        proof_results: Mutex<VecDeque<eyre::Result<()>>>,
+        broadcast_results: Mutex<VecDeque<eyre::Result<()>>>,
+        calls: Mutex<Vec<&'static str>>,
+    }
+
+    impl MockClient {
+        fn answer(
+            &self,
+            call: &'static str,
+            results: &Mutex<VecDeque<eyre::Result<()>>>,
+        ) -> eyre::Result<()> {
+            self.calls.lock().unwrap().push(call);
+            results.lock().unwrap().pop_front().unwrap_or(Ok(()))
+        }
+
+        fn calls(&self) -> Vec<&'static str> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl SubmissionClient for MockClient {
+        async fn send_yuv_tx(&self, _: &YuvTransaction, _: Option<u64>) -> eyre::Result<()> {
+            self.answer("send_yuv_tx", &self.send_results)
+        }
+
+        async fn provide_yuv_proof(&self, _: &YuvTransaction) -> eyre::Result<()> {
+            self.answer("provide_yuv_proof", &self.proof_results)
+        }
+
+        async fn broadcast(&self, _: &Transaction) -> eyre::Result<()> {
+            self.answer("broadcast", &self.broadcast_results)
+        }
+    }
+
+    fn submission(outputs: Vec<TxOut>) -> Submission {
+        let bitcoin_tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: outputs,
+        };
+
+        Submission::new(YuvTransaction::new(bitcoin_tx, YuvTxType::default()), None)
+    }
+
+    fn options(attempts: u32) -> SubmitOptions {
+        SubmitOptions {
+            attempts,
+            retry_interval: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    fn rejection() -> eyre::Report {
+        Rejection("invalid proofs".to_string()).into()
+    }
+
+    #[test]
+    fn test_burn_amount() {
+        let submission = submission(vec![
+            TxOut {
+                value: 1000,
+                script_pubkey: ScriptBuf::new_op_return(&[1, 2, 3]),
+            },
+            TxOut {
+                value: 5000,
+                script_pubkey: ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::all_zeros()),
+            },
+        ]);
+
+        assert_eq!(burn_amount(&submission.tx.bitcoin_tx), 1000);
+    }
+
+    #[test]
+    fn test_node_errors_are_rejections_unless_worth_retrying() {
+        let call_error =
+            |code| Error::Call(ErrorObject::owned(code, "error", Option::<Vec<u8>>::None));
+
+        assert!(is_rejection(&node_error(call_error(INVALID_PARAMS_CODE))));
+        assert!(!is_rejection(&node_error(call_error(INTERNAL_ERROR_CODE))));
+        assert!(!is_rejection(&node_error(call_error(RATE_LIMITED_CODE))));
+        assert!(!is_rejection(&node_error(Error::RequestTimeout)));
+    }
+
+    #[tokio::test]
+    async fn test_submissions_are_stored_separately() {
+        let storage = LevelDB::in_memory().unwrap();
+        let mut first = submission(Vec::new());
+        let second = submission(vec![TxOut::default()]);
+
+        storage.put_submission(&first).await.unwrap();
+        storage.put_submission(&second).await.unwrap();
+        assert_eq!(
+            storage.get_pending_submission_txids().await.unwrap(),
+            vec![first.txid(), second.txid()]
+        );
+
+        first.state = SubmissionState::Completed;
+        storage.put_submission(&first).await.unwrap();
+        assert!(storage
+            .get_submission(&first.txid())
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            storage
+                .get_pending_submissions()
+                .await
+                .unwrap()
+                .iter()
+                .map(Submission::txid)
+                .collect::<Vec<_>>(),
+            vec![second.txid()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_records_failures() {
+        let mut submission = submission(Vec::new());
+
+        let result: eyre::Result<()> = retry(&mut submission, &options(2), || async {
+            eyre::bail!("node is down")
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(submission.failed_attempts, 2);
+        assert_eq!(submission.last_error.as_deref(), Some("node is down"));
+    }
+
+    #[tokio::test]
+    async fn test_node_broadcast_completes_submission() {
+        let storage = LevelDB::in_memory().unwrap();
+        let client = MockClient::default();
+        let mut submission = submission(Vec::new());
+
+        continue_submission(&client, &storage, &mut submission, &options(3))
+            .await
+            .unwrap();
+
+        assert_eq!(submission.state, SubmissionState::Completed);
+        assert_eq!(client.calls(), vec!["send_yuv_tx"]);
+        assert!(storage.get_pending_submissions().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_node_failure_falls_back_to_wallet_broadcast() {
+        let storage = LevelDB::in_memory().unwrap();
+        let client = MockClient::default();
+        client.send_results.lock().unwrap().extend([
+            Err(eyre::eyre!("connection refused")),
+            Err(eyre::eyre!("timeout")),
+        ]);
+        let mut submission = submission(Vec::new());
+
+        continue_submission(&client, &storage, &mut submission, &options(2))
+            .await
+            .unwrap();
+
+        assert_eq!(submission.state, SubmissionState::Completed);
+        assert_eq!(
+            client.calls(),
+            vec![
+                "send_yuv_tx",
+                "send_yuv_tx",
+                "provide_yuv_proof",
+                "broadcast"
+            ]
+        );
+        assert_eq!(submission.failed_attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_transaction_is_not_broadcasted() {
+        let storage = LevelDB::in_memory().unwrap();
+        let client = MockClient::default();
+        client
+            .send_results
+            .lock()
+            .unwrap()
+            .push_back(Err(rejection()));
+        let mut submission = submission(Vec::new());
+
+        let err = continue_submission(&client, &storage, &mut submission, &options(3))
+            .await
+            .unwrap_err();
+
+        assert!(is_rejection(&err));
+        assert_eq!(submission.state, SubmissionState::Rejected);
+        assert_eq!(
+            client.calls(),
+            vec!["send_yuv_tx"],
+            "rejection is not retried"
+        );
+        assert!(storage.get_pending_submissions().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_proofs_are_not_broadcasted() {
+        let storage = LevelDB::in_memory().unwrap();
+        let client = MockClient::default();
+        client
+            .send_results
+            .lock()
+            .unwrap()
+            .push_back(Err(eyre::eyre!("connection refused")));
+        client
+            .proof_results
+            .lock()
+            .unwrap()
+            .push_back(Err(rejection()));
+        let mut submission = submission(Vec::new());
+
+        let err = continue_submission(&client, &storage, &mut submission, &options(1))
+            .await
+            .unwrap_err();
+
+        assert!(is_rejection(&err));
+        assert_eq!(submission.state, SubmissionState::Rejected);
+        assert_eq!(client.calls(), vec!["send_yuv_tx", "provide_yuv_proof"]);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_submission_is_continued_from_its_state() {
+        let storage = LevelDB::in_memory().unwrap();
+        let client = MockClient::default();
+        client
+            .send_results
+            .lock()
+            .unwrap()
+            .push_back(Err(eyre::eyre!("connection refused")));
+        client
+            .broadcast_results
+            .lock()
+            .unwrap()
+            .push_back(Err(eyre::eyre!("bitcoin node is down")));
+        let mut submission = submission(Vec::new());
+
+        continue_submission(&client, &storage, &mut submission, &options(1))
+            .await
+            .unwrap_err();
+
+        let mut stored = storage
+            .get_submission(&submission.txid())
+            .await
+            .unwrap()
+            .expect("unfinished submission should be stored");
+        assert_eq!(stored.state, SubmissionState::ProofProvided);
+
+        let client = MockClient::default();
+        continue_submission(&client, &storage, &mut stored, &options(1))
+            .await
+            .unwrap();
+
+        assert_eq!(stored.state, SubmissionState::Completed);
+        assert_eq!(client.calls(), vec!["broadcast"]);
+        assert!(storage.get_pending_submissions().await.unwrap().is_empty());
+    }
+}
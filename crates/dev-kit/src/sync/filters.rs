@@ -0,0 +1,239 @@
+//! Scanning of the BIP158 compact block filters for the pixel outputs of the wallet.
+//!
+//! The outputs with the pixels are locked by the keys tweaked with the pixels, so a light wallet
+//! can't ask the Bitcoin node for the outputs of its address. Instead, it derives the `P2WPKH`
+//! scripts of the [`PixelKey`]s for the pixels it expects (e.g. the empty pixel, and the pixels of
+//! the payments it waits for), matches them against the block filters locally, and loads only the
+//! blocks that match.
+use std::collections::HashMap;
+
+use bitcoin::{bip158::BlockFilter, secp256k1, Block, BlockHash, OutPoint, ScriptBuf, TxOut};
+use eyre::{eyre, Context};
+use yuv_pixels::{EmptyPixelProof, Pixel, PixelKey, PixelProof};
+
+use crate::bitcoin_provider::BitcoinProvider;
+
+/// `P2WPKH` scripts of the watched pixel keys with the proofs of the outputs locked by them.
+#[derive(Clone, Debug, Default)]
+pub struct PixelScripts(HashMap<ScriptBuf, PixelProof>);
+
+impl PixelScripts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Watch the outputs with the `pixel` locked by the `inner_key`.
+    pub fn insert_pixel(
+        &mut self,
+        pixel: Pixel,
+        inner_key: secp256k1::PublicKey,
+    ) -> eyre::Result<()> {
+        let script = pixel_script(pixel, &inner_key)?;
+
+        self.0.insert(script, PixelProof::sig(pixel, inner_key));
+
+        Ok(())
+    }
+
+    /// Watch the outputs with the empty pixel, i.e. the tweaked satoshis, locked by the
+    /// `inner_key`.
+    pub fn insert_empty_pixel(&mut self, inner_key: secp256k1::PublicKey) -> eyre::Result<()> {
+        let script = pixel_script(Pixel::empty(), &inner_key)?;
+
+        self.0.insert(
+            script,
+            PixelProof::EmptyPixel(EmptyPixelProof::new(inner_key)),
+        );
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if the block filter matches any of the watched scripts. The filter may
+    /// match falsely, so the outputs of the block must be checked with [`Self::find_outputs`].
+    pub fn match_filter(&self, filter: &BlockFilter, block_hash: &BlockHash) -> eyre::Result<bool> {
+        if self.is_empty() {
+            return Ok(false);
+        }
+
+        filter
+            .match_any(block_hash, self.0.keys().map(|script| script.as_bytes()))
+            .wrap_err("Failed to match the block filter")
+    }
+
+    /// Returns the outputs of the block locked by the watched scripts.
+    pub fn find_outputs(&self, block: &Block) -> Vec<(OutPoint, TxOut, PixelProof)> {
+        let mut outputs = Vec::new();
+
+        for tx in &block.txdata {
+            let txid = tx.txid();
+
+            for (vout, output) in tx.output.iter().enumerate() {
+                let Some(proof) = self.0.get(&output.script_pubkey) else {
+                    continue;
+                };
+
+                outputs.push((
+                    OutPoint::new(txid, vout as u32),
+                    output.clone(),
+                    proof.clone(),
+                ));
+            }
+        }
+
+        outputs
+    }
+}
+
+/// Output locked by the watched pixel key found in the block.
+#[derive(Clone, Debug)]
+pub struct FilterMatch {
+    pub block_height: u32,
+    pub block_hash: BlockHash,
+    pub outpoint: OutPoint,
+    pub output: TxOut,
+    /// Proof of the output, built from the watched pixel.
+    pub proof: PixelProof,
+}
+
+/// Scan the filters of the blocks in the `from_height..=to_height` range for the watched
+/// `scripts`, and load the blocks that match to find the outputs.
+///
+/// The found outputs may be already spent, use [`BitcoinProvider::get_tx_out_status`] to check
+/// them.
+pub fn scan_block_filters<BP: BitcoinProvider>(
+    bitcoin_provider: &BP,
+    scripts: &PixelScripts,
+    from_height: u32,
+    to_height: u32,
+) -> eyre::Result<Vec<FilterMatch>> {
+    let mut matches = Vec::new();
+
+    for block_height in from_height..=to_height {
+        let block_hash = bitcoin_provider.get_block_hash(block_height)?;
+        let filter = bitcoin_provider
+            .get_block_filter(&block_hash)
+            .wrap_err_with(|| format!("Failed to get the filter of the block {}", block_hash))?;
+
+        if !scripts.match_filter(&filter, &block_hash)? {
+            continue;
+        }
+
+        tracing::debug!(block_height, %block_hash, "Block filter matched");
+
+        let block = bitcoin_provider.get_block(&block_hash)?;
+        for (outpoint, output, proof) in scripts.find_outputs(&block) {
+            matches.push(FilterMatch {
+                block_height,
+                block_hash,
+                outpoint,
+                output,
+                proof,
+            });
+        }
+    }
+
+    Ok(matches)
+}
+
+fn pixel_script(pixel: Pixel, inner_key: &secp256k1::PublicKey) -> eyre::Result<ScriptBuf> {
+    PixelKey::new(pixel, inner_key)?
+        .to_p2wpkh()
+        .ok_or_else(|| eyre!("Pixel key is uncompressed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, block, hashes::Hash, secp256k1::Secp256k1, CompactTarget, Sequence,
+        Transaction, TxIn, TxMerkleNode, Witness,
+    };
+
+    use super::*;
+
+    fn block_with_outputs(outputs: Vec<TxOut>) -> Block {
+        let coinbase = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: outputs,
+        };
+
+        Block {
+            header: block::Header {
+                version: block::Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata: vec![coinbase],
+        }
+    }
+
+    #[test]
+    fn test_filter_matches_pixel_outputs() {
+        let ctx = Secp256k1::new();
+        let inner_key = secp256k1::SecretKey::from_slice(&[1; 32])
+            .unwrap()
+            .public_key(&ctx);
+        let other_key = secp256k1::SecretKey::from_slice(&[2; 32])
+            .unwrap()
+            .public_key(&ctx);
+        let pixel = Pixel::new(100, other_key.x_only_public_key().0);
+
+        let mut scripts = PixelScripts::new();
+        scripts.insert_pixel(pixel, inner_key).unwrap();
+
+        let block = block_with_outputs(vec![
+            TxOut {
+                value: 1000,
+                script_pubkey: pixel_script(pixel, &other_key).unwrap(),
+            },
+            TxOut {
+                value: 1000,
+                script_pubkey: pixel_script(pixel, &inner_key).unwrap(),
+            },
+        ]);
+        let filter = BlockFilter::new_script_filter(&block, |_| unreachable!()).unwrap();
+
+        assert!(scripts.match_filter(&filter, &block.block_hash()).unwrap());
+
+        let outputs = scripts.find_outputs(&block);
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].0.vout, 1);
+        assert_eq!(outputs[0].2, PixelProof::sig(pixel, inner_key));
+    }
+
+    #[test]
+    fn test_filter_does_not_match_other_outputs() {
+        let ctx = Secp256k1::new();
+        let inner_key = secp256k1::SecretKey::from_slice(&[1; 32])
+            .unwrap()
+            .public_key(&ctx);
+        let other_key = secp256k1::SecretKey::from_slice(&[2; 32])
+            .unwrap()
+            .public_key(&ctx);
+
+        let mut scripts = PixelScripts::new();
+        scripts.insert_empty_pixel(inner_key).unwrap();
+
+        let block = block_with_outputs(vec![TxOut {
+            value: 1000,
+            script_pubkey: pixel_script(Pixel::empty(), &other_key).unwrap(),
+        }]);
+        let filter = BlockFilter::new_script_filter(&block, |_| unreachable!()).unwrap();
+
+        assert!(!scripts.match_filter(&filter, &block.block_hash()).unwrap());
+        assert!(scripts.find_outputs(&block).is_empty());
+    }
+}
@@ -145,6 +145,33 @@ where
                         self.user_outpoints.insert(outpoint, output_proof.clone());
                     }
                 }
+                PixelProof::Musig(proof) => {
+                    let x_only_pubkeys = proof
+                        .inner_keys
+                        .iter()
+                        .map(|key| key.x_only_public_key().0)
+                        .collect::<Vec<_>>();
+
+                    if x_only_pubkeys.contains(&self_x_only_pubkey) {
+                        self.user_outpoints.insert(outpoint, output_proof.clone());
+                    }
+                }
+                PixelProof::Timelock(proof) => {
+                    let (proof_x_key, _parity) = proof.inner_key.x_only_public_key();
+
+                    if proof_x_key == self_x_only_pubkey {
+                        self.user_outpoints.insert(outpoint, output_proof.clone());
+                    }
+                }
+                PixelProof::Htlc(proof) => {
+                    let is_participant = [proof.data.recipient_pubkey, proof.data.sender_pubkey]
+                        .iter()
+                        .any(|key| key.x_only_public_key().0 == self_x_only_pubkey);
+
+                    if is_participant {
+                        self.user_outpoints.insert(outpoint, output_proof.clone());
+                    }
+                }
                 PixelProof::Lightning(proof) => {
                     let x_only = proof.data.local_delayed_pubkey.x_only_public_key().0;
 
@@ -203,6 +230,9 @@ where
     async fn cleanup(&mut self) -> eyre::Result<Vec<(OutPoint, PixelProof)>> {
         let mut utxos = Vec::new();
 
+        self.user_outpoints
+            .retain(|outpoint, _| self.indexed_txs.get(outpoint) != Some(&true));
+
         for (outpoint, is_spent) in &self.indexed_txs {
             if *is_spent {
                 // FIXME:
@@ -227,6 +257,23 @@ where
             utxos.push((*outpoint, proof.clone()));
         }
 
+        // The outpoints found by the previous syncs or restored from a backup aren't indexed
+        // again, their spending is checked by the wallet.
+        for (outpoint, proof) in &self.user_outpoints {
+            if self.indexed_txs.contains_key(outpoint) {
+                continue;
+            }
+
+            let is_outpoint_frozen = self
+                .node_client
+                .is_yuv_txout_frozen(outpoint.txid, outpoint.vout)
+                .await?;
+
+            if !is_outpoint_frozen {
+                utxos.push((*outpoint, proof.clone()));
+            }
+        }
+
         Ok(utxos)
     }
 }
@@ -1,3 +1,5 @@
 pub mod storage;
 
 pub mod indexer;
+
+pub mod filters;
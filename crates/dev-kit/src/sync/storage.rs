@@ -5,6 +5,8 @@ use jsonrpsee::core::async_trait;
 use yuv_pixels::PixelProof;
 use yuv_storage::KeyValueStorage;
 
+use crate::PixelDescriptor;
+
 const UNSPENT_YUV_OUTPOINTS_KEY: &[u8; 15] = b"unspent_yuv_txs";
 const UNSPENT_YUV_OUTPOINTS_KEY_LEN: usize = UNSPENT_YUV_OUTPOINTS_KEY.len();
 
@@ -36,3 +38,29 @@ impl<T> UnspentYuvOutPointsStorage for T where
     T: KeyValueStorage<&'static [u8; UNSPENT_YUV_OUTPOINTS_KEY_LEN], HashMap<OutPoint, PixelProof>>
 {
 }
+
+const PIXEL_DESCRIPTORS_KEY: &[u8; 17] = b"pixel_descriptors";
+const PIXEL_DESCRIPTORS_KEY_LEN: usize = PIXEL_DESCRIPTORS_KEY.len();
+
+/// Storage of the watched [`PixelDescriptor`]s, so they are not lost between the runs.
+#[async_trait]
+pub trait PixelDescriptorsStorage:
+    KeyValueStorage<&'static [u8; PIXEL_DESCRIPTORS_KEY_LEN], Vec<PixelDescriptor>>
+{
+    async fn get_pixel_descriptors(&self) -> eyre::Result<Vec<PixelDescriptor>> {
+        let entry = self.get(PIXEL_DESCRIPTORS_KEY).await?.unwrap_or_default();
+
+        Ok(entry)
+    }
+
+    async fn put_pixel_descriptors(&self, descriptors: Vec<PixelDescriptor>) -> eyre::Result<()> {
+        self.put(PIXEL_DESCRIPTORS_KEY, descriptors).await?;
+
+        Ok(())
+    }
+}
+
+impl<T> PixelDescriptorsStorage for T where
+    T: KeyValueStorage<&'static [u8; PIXEL_DESCRIPTORS_KEY_LEN], Vec<PixelDescriptor>>
+{
+}
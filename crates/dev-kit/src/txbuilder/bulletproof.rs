@@ -9,7 +9,8 @@ use eyre::OptionExt;
 use hashbrown::HashMap;
 use yuv_pixels::{
     bulletproof_signing::{
-        create_signatures, get_commitment, tweak_signing_keys, CommitmentResult,
+        aggregate_range_proofs, create_signatures, get_commitment, tweak_signing_keys,
+        CommitmentResult,
     },
     Bulletproof, Chroma,
 };
@@ -64,6 +65,14 @@ where
 
         Ok(self)
     }
+
+    /// Prove the amounts of all the recipients with bulletproof with one aggregated range proof,
+    /// which makes the transaction much smaller. Up to [`yuv_pixels::MAX_AGGREGATED_VALUES`]
+    /// recipients are supported.
+    pub fn set_aggregate_bulletproofs(&mut self, aggregate: bool) -> &mut Self {
+        self.0.aggregate_bulletproofs = aggregate;
+        self
+    }
 }
 
 impl<YTDB, BDB> TransactionBuilder<YTDB, BDB>
@@ -114,9 +123,11 @@ where
         // Commitments is used to store range proofs, commitments and proof hashes
         // generated using the `bulletproof` crate.
         let mut commitments = Vec::new();
+        // Recipients of the transfer in the order of `commitments`.
+        let mut recipients = Vec::new();
         let network = self.inner_wallet.read().unwrap().network();
         let ctx = Secp256k1::new();
-        let sender = self.private_key.public_key(&ctx);
+        let sender = self.public_key;
 
         // Tweak the signing keys that will be used to create Schnorr signatures.
         // If the transaction is an issuance, tweaking will be skipped and dummy signatures will be generated.
@@ -132,16 +143,19 @@ where
             // If the transaction is a transfer, tweak the general signing key and chroma signing keys
             // with the generated ecdh private keys.
             let bulletproof = input_proofs.get(&outpoint).ok_or_eyre("Input not found")?;
+            let outpoint_recipients = params
+                .into_iter()
+                .map(|(_, params)| (params.recipient.to_public_key(), params.amount))
+                .collect::<Vec<_>>();
+            recipients.extend_from_slice(&outpoint_recipients);
+
             tweak_signing_keys(
-                self.private_key,
+                self.private_key()?,
                 bulletproof,
                 network,
                 &mut signing_key,
                 &mut chroma_signing_keys,
-                params
-                    .into_iter()
-                    .map(|(_, params)| (params.recipient.to_public_key(), params.amount))
-                    .collect(),
+                outpoint_recipients,
                 &mut commitments,
             )?;
         }
@@ -151,6 +165,12 @@ where
             return Ok(());
         }
 
+        // Replace the range proofs of the outputs with the aggregated one before hashing, as the
+        // lumas are the hashes of the commitments with their proofs.
+        if self.aggregate_bulletproofs {
+            aggregate_range_proofs(self.private_key()?, network, &recipients, &mut commitments)?;
+        }
+
         // The next step is to hash the input and output proofs.
         // Proof hashes are consumed by the hashing engine in the following order in case
         // there are K inputs and M outputs: hash(inp[0] || inp[1] || ... || inp[K-1] || outp[0] || outp[1] || ... || outp[M-1]),
@@ -219,7 +239,7 @@ where
                 self.outputs.push(BuilderOutput::BulletproofPixel {
                     chroma: *chroma,
                     recipient: param.recipient.to_public_key(),
-                    sender: self.private_key.public_key(ctx),
+                    sender: self.public_key,
                     luma: commitment.proof_hash.into(),
                     satoshis: param.satoshis,
                     commitment: commitment.commitment,
@@ -252,7 +272,7 @@ where
                     proof_hash,
                 },
             ) = get_commitment(
-                self.private_key,
+                self.private_key()?,
                 param.recipient.to_public_key(),
                 network,
                 param.amount,
@@ -0,0 +1,367 @@
+//! Issuance of the chromas owned by the multisig of the issuers.
+//!
+//! The node accepts the issuance only if one of its inputs spends the output of the chroma's
+//! owner, so when the ownership is transferred to the P2WSH multisig (e.g. 2-of-3 of the
+//! issuers), the issuance has to be co-signed:
+//!
+//! 1. The coordinator builds the issuance with
+//!    [`IssuanceTransactionBuilder::add_owner_multisig_input`] and
+//!    [`IssuanceTransactionBuilder::finish_cosigning_psbt`]. Its own inputs are signed, and the
+//!    owner input is signed too if the coordinator is one of the participants.
+//! 2. The [`IssuancePsbt`] with the proofs of the issuance is passed to the co-signers, which
+//!    sign the owner input with [`IssuancePsbt::sign_owner_inputs`] offline, without syncing the
+//!    wallet.
+//! 3. The coordinator collects the signed PSBTs with [`IssuancePsbt::merge_signatures`], and
+//!    extracts the issuance with [`IssuancePsbt::finalize_from_psbt`].
+//!
+//! The owner multisig is the same as the one of the [`ChromaIssuers`] declared in the chroma
+//! announcement, so the issuers of such chromas co-sign the issuances the same way.
+//!
+//! [`ChromaIssuers`]: yuv_types::announcements::ChromaIssuers
+//!
+//! [`IssuanceTransactionBuilder::add_owner_multisig_input`]: super::IssuanceTransactionBuilder::add_owner_multisig_input
+//! [`IssuanceTransactionBuilder::finish_cosigning_psbt`]: super::IssuanceTransactionBuilder::finish_cosigning_psbt
+use core::fmt;
+use std::str::FromStr;
+
+use bdk::{
+    blockchain::Blockchain,
+    miniscript::{psbt::PsbtExt, Descriptor},
+    signer::{InputSigner, SignerContext, SignerWrapper},
+    SignOptions,
+};
+use bitcoin::{
+    blockdata::script::Instruction,
+    consensus::{deserialize, serialize},
+    psbt::{self, PartiallySignedTransaction},
+    secp256k1::{self, All, Secp256k1},
+    OutPoint, PrivateKey, PublicKey,
+};
+use eyre::{bail, ensure, eyre, Context, OptionExt};
+use yuv_types::{YuvTransaction, YuvTxType};
+
+use crate::yuv_psbt::tx_type_key;
+
+/// Output of the chroma's owner locked by the P2WSH `sortedmulti` of the participants.
+pub(crate) struct OwnerMultisigInput {
+    pub(crate) outpoint: OutPoint,
+    pub(crate) participants: Vec<secp256k1::PublicKey>,
+    pub(crate) required_signatures: u8,
+}
+
+impl OwnerMultisigInput {
+    pub(crate) fn descriptor(&self) -> eyre::Result<Descriptor<PublicKey>> {
+        let participants = self
+            .participants
+            .iter()
+            .copied()
+            .map(PublicKey::new)
+            .collect();
+
+        Descriptor::new_wsh_sortedmulti(self.required_signatures as usize, participants)
+            .wrap_err("Invalid owner multisig")
+    }
+
+    /// Get the spent output from the `blockchain`, and form the PSBT input with the weight to
+    /// satisfy it.
+    pub(crate) fn to_foreign_input(
+        &self,
+        blockchain: &impl Blockchain,
+    ) -> eyre::Result<(OutPoint, psbt::Input, usize)> {
+        let descriptor = self.descriptor()?;
+
+        let tx = blockchain
+            .get_tx(&self.outpoint.txid)?
+            .ok_or_else(|| eyre!("Owner transaction {} is not found", self.outpoint.txid))?;
+        let output = tx
+            .output
+            .get(self.outpoint.vout as usize)
+            .ok_or_else(|| eyre!("Owner output {} is not found", self.outpoint))?;
+
+        ensure!(
+            output.script_pubkey == descriptor.script_pubkey(),
+            "Output {} is not locked by the owner multisig",
+            self.outpoint
+        );
+
+        let psbt_input = psbt::Input {
+            witness_utxo: Some(output.clone()),
+            witness_script: Some(descriptor.explicit_script()?),
+            ..Default::default()
+        };
+
+        Ok((
+            self.outpoint,
+            psbt_input,
+            descriptor.max_weight_to_satisfy()?,
+        ))
+    }
+}
+
+/// PSBT of the issuance with the owner multisig inputs to be co-signed, and the proofs of the
+/// issuance in the proprietary global field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IssuancePsbt {
+    pub psbt: PartiallySignedTransaction,
+}
+
+impl IssuancePsbt {
+    pub(crate) fn new(
+        mut psbt: PartiallySignedTransaction,
+        tx_type: &YuvTxType,
+    ) -> eyre::Result<Self> {
+        ensure!(
+            matches!(tx_type, YuvTxType::Issue { .. }),
+            "Only issuances can be co-signed"
+        );
+
+        psbt.proprietary.insert(tx_type_key(), serialize(tx_type));
+
+        Ok(Self { psbt })
+    }
+
+    /// Get the proofs of the issuance.
+    pub fn tx_type(&self) -> eyre::Result<YuvTxType> {
+        let value = self
+            .psbt
+            .proprietary
+            .get(&tx_type_key())
+            .ok_or_eyre("Proofs of the issuance are missing in the PSBT")?;
+
+        deserialize(value).wrap_err("Invalid proofs of the issuance")
+    }
+
+    /// Indexes of the owner multisig inputs, which are not finalized yet.
+    pub fn owner_inputs(&self) -> Vec<usize> {
+        self.psbt
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| {
+                input.witness_script.is_some() && input.final_script_witness.is_none()
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Sign the owner multisig inputs with the key of one of the participants.
+    pub fn sign_owner_inputs(
+        &mut self,
+        private_key: &PrivateKey,
+        ctx: &Secp256k1<All>,
+    ) -> eyre::Result<()> {
+        let owner_inputs = self.owner_inputs();
+        ensure!(!owner_inputs.is_empty(), "No owner multisig inputs to sign");
+
+        let pubkey = private_key.public_key(ctx).to_bytes();
+        let signer = SignerWrapper::new(*private_key, SignerContext::Segwitv0);
+
+        for index in owner_inputs {
+            let witness_script = self.psbt.inputs[index]
+                .witness_script
+                .as_ref()
+                .expect("owner inputs have the witness script");
+
+            let is_participant =
+                witness_script
+                    .instructions()
+                    .any(|instruction| match instruction {
+                        Ok(Instruction::PushBytes(bytes)) => bytes.as_bytes() == pubkey.as_slice(),
+                        _ => false,
+                    });
+            ensure!(
+                is_participant,
+                "Key is not a participant of the owner multisig of input {}",
+                index
+            );
+
+            signer.sign_input(
+                &mut self.psbt,
+                index,
+                &SignOptions {
+                    try_finalize: false,
+                    trust_witness_utxo: true,
+                    ..Default::default()
+                },
+                ctx,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Merge the signatures of the co-signer from the PSBT signed by it.
+    pub fn merge_signatures(&mut self, signed: IssuancePsbt) -> eyre::Result<()> {
+        self.psbt
+            .combine(signed.psbt)
+            .wrap_err("Signed PSBT doesn't match the issuance")
+    }
+
+    /// Form the witnesses of the owner multisig inputs from the signatures of the co-signers,
+    /// and extract the signed issuance.
+    pub fn finalize_from_psbt(mut self) -> eyre::Result<YuvTransaction> {
+        let ctx = Secp256k1::new();
+
+        for index in self.owner_inputs() {
+            self.psbt
+                .finalize_inp_mut(&ctx, index)
+                .map_err(|err| eyre!("Failed to finalize the owner input {}: {}", index, err))?;
+        }
+
+        if let Some(index) = self
+            .psbt
+            .inputs
+            .iter()
+            .position(|input| input.final_script_witness.is_none())
+        {
+            bail!("Input {} is not signed", index);
+        }
+
+        let tx_type = self.tx_type()?;
+
+        Ok(YuvTransaction {
+            bitcoin_tx: self.psbt.extract_tx(),
+            tx_type,
+        })
+    }
+}
+
+impl fmt::Display for IssuancePsbt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.psbt)
+    }
+}
+
+impl FromStr for IssuancePsbt {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let psbt =
+            PartiallySignedTransaction::from_str(s.trim()).wrap_err("Invalid issuance PSBT")?;
+
+        Ok(Self { psbt })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, hashes::Hash, secp256k1::SecretKey, Network, ScriptBuf, Sequence,
+        Transaction, TxIn, TxOut, Txid, Witness,
+    };
+    use yuv_pixels::{Chroma, Pixel, PixelProof, SigPixelProof};
+    use yuv_types::{
+        announcements::{ChromaIssuers, IssueAnnouncement},
+        ProofMap,
+    };
+
+    use super::*;
+
+    fn regtest_key(byte: u8) -> PrivateKey {
+        PrivateKey::new(
+            SecretKey::from_slice(&[byte; 32]).unwrap(),
+            Network::Regtest,
+        )
+    }
+
+    /// Issuance spending the output of the 2-of-3 owner multisig of the keys 1, 2 and 3.
+    fn issuance(ctx: &Secp256k1<All>) -> IssuancePsbt {
+        let owner = OwnerMultisigInput {
+            outpoint: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+            participants: (1..=3)
+                .map(|byte| regtest_key(byte).public_key(ctx).inner)
+                .collect(),
+            required_signatures: 2,
+        };
+        let descriptor = owner.descriptor().unwrap();
+
+        let recipient = regtest_key(4).public_key(ctx).inner;
+        let chroma = Chroma::from(recipient.x_only_public_key().0);
+
+        let unsigned_tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: owner.outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: 1000,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: 10_000,
+            script_pubkey: descriptor.script_pubkey(),
+        });
+        psbt.inputs[0].witness_script = Some(descriptor.explicit_script().unwrap());
+
+        let tx_type = YuvTxType::Issue {
+            output_proofs: Some(ProofMap::from([(
+                0,
+                PixelProof::Sig(SigPixelProof::new(Pixel::new(100, chroma), recipient)),
+            )])),
+            announcement: IssueAnnouncement {
+                chroma,
+                amount: 100,
+            },
+        };
+
+        IssuancePsbt::new(psbt, &tx_type).unwrap()
+    }
+
+    #[test]
+    fn test_cosign_issuance() {
+        let ctx = Secp256k1::new();
+        let issuance = issuance(&ctx);
+
+        let mut first = IssuancePsbt::from_str(&issuance.to_string()).unwrap();
+        first.sign_owner_inputs(&regtest_key(1), &ctx).unwrap();
+        assert!(
+            first.clone().finalize_from_psbt().is_err(),
+            "one signature of 2-of-3 multisig should not be enough"
+        );
+
+        let mut second = issuance.clone();
+        second.sign_owner_inputs(&regtest_key(3), &ctx).unwrap();
+
+        first.merge_signatures(second).unwrap();
+        let tx = first.finalize_from_psbt().unwrap();
+
+        assert_eq!(tx.tx_type, issuance.tx_type().unwrap());
+        // Empty signature for `CHECKMULTISIG`, two signatures and the witness script.
+        assert_eq!(tx.bitcoin_tx.input[0].witness.len(), 4);
+    }
+
+    #[test]
+    fn test_owner_multisig_of_chroma_issuers() {
+        let ctx = Secp256k1::new();
+        let participants: Vec<_> = (1..=3)
+            .map(|byte| regtest_key(byte).public_key(&ctx).inner)
+            .collect();
+
+        let owner = OwnerMultisigInput {
+            outpoint: OutPoint::null(),
+            participants: participants.clone(),
+            required_signatures: 2,
+        };
+        let issuers = ChromaIssuers::new(2, participants).unwrap();
+
+        assert_eq!(
+            owner.descriptor().unwrap().script_pubkey(),
+            issuers.owner_script()
+        );
+    }
+
+    #[test]
+    fn test_sign_by_not_participant() {
+        let ctx = Secp256k1::new();
+        let mut issuance = issuance(&ctx);
+
+        assert!(issuance.sign_owner_inputs(&regtest_key(4), &ctx).is_err());
+    }
+}
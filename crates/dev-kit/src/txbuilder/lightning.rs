@@ -0,0 +1,312 @@
+//! Funding of the Lightning Network channels with pixels.
+//!
+//! The funding output of the channel is the 2-of-2 P2WSH multisig of the funding keys of the
+//! parties, where the first of the sorted keys is tweaked by the pixel ([`MultisigPixelProof`]).
+//! It's built by [`LightningFundingBuilder`], which returns the funding transaction as
+//! [`LightningFunding`] with the outpoint and the proof of the funding output.
+//!
+//! The outputs of the commitment transactions spending the funding output carry the pixels of the
+//! channel's chroma too, so the LN implementation needs their proofs to provide the commitment
+//! transactions to the node. [`LightningFunding`] produces the templates of them for each
+//! commitment, by the amounts of the outputs and the keys derived for the commitment:
+//!
+//! - `to_local` output: [`LightningCommitmentProof`] with the revocation key tweaked by the pixel;
+//! - `to_remote` output: [`SigPixelProof`] of the P2WPKH of the remote payment key;
+//! - offered and received HTLC outputs: [`LightningHtlcProof`] with the remote HTLC key tweaked by
+//!   the pixel.
+//!
+//! The amounts of the commitment's outputs must sum up to the [`LightningFunding::capacity`] of
+//! the channel.
+use bdk::blockchain::Blockchain;
+use bitcoin::{
+    hashes::{hash160, ripemd160, Hash},
+    secp256k1, OutPoint, ScriptBuf,
+};
+use eyre::{ensure, OptionExt};
+use yuv_pixels::{
+    Chroma, LightningCommitmentProof, LightningHtlcData, LightningHtlcProof, MultisigPixelProof,
+    Pixel, PixelProof, SigPixelProof,
+};
+use yuv_storage::TransactionsStorage as YuvTransactionsStorage;
+use yuv_types::{dust::DEFAULT_PIXEL_SATOSHIS, YuvTransaction};
+
+use super::TransferTransactionBuilder;
+use crate::types::FeeRateStrategy;
+
+/// Default value of satoshis in the funding output.
+pub const DEFAULT_FUNDING_SATOSHIS: u64 = DEFAULT_PIXEL_SATOSHIS;
+
+/// Builder of the funding transaction of the Lightning channel with the pixel.
+pub struct LightningFundingBuilder<YuvTxsDatabase, BitcoinTxsDatabase> {
+    tx_builder: TransferTransactionBuilder<YuvTxsDatabase, BitcoinTxsDatabase>,
+    funding_pixel: Pixel,
+    holder_pubkey: secp256k1::PublicKey,
+    counterparty_pubkey: secp256k1::PublicKey,
+    satoshis: u64,
+}
+
+impl<YTDB, BDB> LightningFundingBuilder<YTDB, BDB>
+where
+    YTDB: YuvTransactionsStorage + Clone + Send + Sync + 'static,
+    BDB: bdk::database::BatchDatabase + Clone + Send,
+{
+    pub(crate) fn new(
+        tx_builder: TransferTransactionBuilder<YTDB, BDB>,
+        funding_pixel: Pixel,
+        holder_pubkey: secp256k1::PublicKey,
+        counterparty_pubkey: secp256k1::PublicKey,
+    ) -> Self {
+        Self {
+            tx_builder,
+            funding_pixel,
+            holder_pubkey,
+            counterparty_pubkey,
+            satoshis: DEFAULT_FUNDING_SATOSHIS,
+        }
+    }
+
+    /// Override the value of satoshis in the funding output.
+    pub fn set_funding_satoshis(&mut self, satoshis: u64) -> &mut Self {
+        self.satoshis = satoshis;
+        self
+    }
+
+    /// Override the fee rate strategy.
+    pub fn set_fee_rate_strategy(&mut self, fee_rate_strategy: FeeRateStrategy) -> &mut Self {
+        self.tx_builder.set_fee_rate_strategy(fee_rate_strategy);
+        self
+    }
+
+    /// Finish funding building, and create the funding transaction signed by the wallet.
+    pub async fn finish(mut self, blockchain: &impl Blockchain) -> eyre::Result<LightningFunding> {
+        ensure!(
+            self.holder_pubkey != self.counterparty_pubkey,
+            "Funding keys of the holder and the counterparty must differ"
+        );
+
+        self.tx_builder.add_multisig_recipient(
+            vec![self.holder_pubkey, self.counterparty_pubkey],
+            2,
+            self.funding_pixel.luma.amount,
+            self.funding_pixel.chroma,
+            self.satoshis,
+        );
+
+        let tx = self.tx_builder.finish(blockchain).await?;
+
+        LightningFunding::new(tx)
+    }
+}
+
+/// Funding transaction of the Lightning channel with the pixel.
+#[derive(Clone, Debug)]
+pub struct LightningFunding {
+    pub tx: YuvTransaction,
+    /// Funding output of the channel.
+    pub funding_outpoint: OutPoint,
+    /// Proof of the funding output.
+    pub funding_proof: MultisigPixelProof,
+}
+
+impl LightningFunding {
+    /// Find the 2-of-2 multisig funding output of the channel in the transaction.
+    pub fn new(tx: YuvTransaction) -> eyre::Result<Self> {
+        let output_proofs = tx
+            .tx_type
+            .output_proofs()
+            .ok_or_eyre("Funding transaction has no output proofs")?;
+
+        let (vout, funding_proof) = output_proofs
+            .iter()
+            .find_map(|(vout, proof)| match proof {
+                PixelProof::Multisig(proof) if proof.m == 2 && proof.inner_keys.len() == 2 => {
+                    Some((*vout, proof.clone()))
+                }
+                _ => None,
+            })
+            .ok_or_eyre("Funding transaction has no 2-of-2 multisig output")?;
+
+        Ok(Self {
+            funding_outpoint: OutPoint::new(tx.bitcoin_tx.txid(), vout),
+            funding_proof,
+            tx,
+        })
+    }
+
+    /// Chroma of the channel.
+    pub fn chroma(&self) -> Chroma {
+        self.funding_proof.pixel.chroma
+    }
+
+    /// Amount of the tokens in the channel.
+    pub fn capacity(&self) -> u128 {
+        self.funding_proof.pixel.luma.amount
+    }
+
+    /// Redeem script of the funding output, which is spent by the commitment transactions.
+    pub fn funding_redeem_script(&self) -> eyre::Result<ScriptBuf> {
+        Ok(self.funding_proof.to_reedem_script()?)
+    }
+
+    /// Proof of the commitment's `to_local` output with the `amount` of the channel's tokens.
+    pub fn to_local_proof(
+        &self,
+        amount: u128,
+        revocation_pubkey: secp256k1::PublicKey,
+        to_self_delay: u16,
+        local_delayed_pubkey: secp256k1::PublicKey,
+    ) -> LightningCommitmentProof {
+        LightningCommitmentProof::new(
+            self.pixel(amount),
+            revocation_pubkey,
+            to_self_delay,
+            local_delayed_pubkey,
+        )
+    }
+
+    /// Proof of the commitment's `to_remote` output with the `amount` of the channel's tokens.
+    pub fn to_remote_proof(
+        &self,
+        amount: u128,
+        remote_pubkey: secp256k1::PublicKey,
+    ) -> SigPixelProof {
+        SigPixelProof::new(self.pixel(amount), remote_pubkey)
+    }
+
+    /// Proof of the commitment's HTLC output offered by the local node, with the `amount` of the
+    /// channel's tokens. `payment_hash` is the SHA256 of the payment preimage.
+    pub fn offered_htlc_proof(
+        &self,
+        amount: u128,
+        revocation_pubkey: secp256k1::PublicKey,
+        remote_htlc_pubkey: secp256k1::PublicKey,
+        local_htlc_pubkey: secp256k1::PublicKey,
+        payment_hash: [u8; 32],
+    ) -> LightningHtlcProof {
+        LightningHtlcProof::new(
+            self.pixel(amount),
+            LightningHtlcData::offered(
+                revocation_key_hash(&revocation_pubkey),
+                remote_htlc_pubkey,
+                local_htlc_pubkey,
+                script_payment_hash(&payment_hash),
+            ),
+        )
+    }
+
+    /// Proof of the commitment's HTLC output received by the local node, with the `amount` of
+    /// the channel's tokens. `payment_hash` is the SHA256 of the payment preimage.
+    pub fn received_htlc_proof(
+        &self,
+        amount: u128,
+        revocation_pubkey: secp256k1::PublicKey,
+        remote_htlc_pubkey: secp256k1::PublicKey,
+        local_htlc_pubkey: secp256k1::PublicKey,
+        payment_hash: [u8; 32],
+        cltv_expiry: u32,
+    ) -> LightningHtlcProof {
+        LightningHtlcProof::new(
+            self.pixel(amount),
+            LightningHtlcData::received(
+                revocation_key_hash(&revocation_pubkey),
+                remote_htlc_pubkey,
+                local_htlc_pubkey,
+                script_payment_hash(&payment_hash),
+                cltv_expiry,
+            ),
+        )
+    }
+
+    fn pixel(&self, amount: u128) -> Pixel {
+        Pixel::new(amount, self.chroma())
+    }
+}
+
+/// `HASH160` of the revocation key, as it's put to the HTLC scripts.
+fn revocation_key_hash(revocation_pubkey: &secp256k1::PublicKey) -> hash160::Hash {
+    hash160::Hash::hash(&revocation_pubkey.serialize())
+}
+
+/// `RIPEMD160` of the payment hash, as it's put to the HTLC scripts.
+fn script_payment_hash(payment_hash: &[u8; 32]) -> hash160::Hash {
+    hash160::Hash::from_byte_array(ripemd160::Hash::hash(payment_hash).to_byte_array())
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime,
+        secp256k1::{Secp256k1, SecretKey},
+        Transaction, TxOut, WScriptHash,
+    };
+    use yuv_pixels::{CheckableProof, EmptyPixelProof, LightningHtlcScript};
+    use yuv_types::{ProofMap, YuvTxType};
+
+    use super::*;
+
+    fn pubkey(byte: u8) -> secp256k1::PublicKey {
+        SecretKey::from_slice(&[byte; 32])
+            .unwrap()
+            .public_key(&Secp256k1::new())
+    }
+
+    #[test]
+    fn test_funding_output_and_commitment_proofs() {
+        let chroma = Chroma::from(pubkey(1).x_only_public_key().0);
+        let funding_proof =
+            MultisigPixelProof::new(Pixel::new(1000, chroma), vec![pubkey(3), pubkey(2)], 2);
+        let change_proof = EmptyPixelProof::new(pubkey(4));
+
+        let tx = YuvTransaction {
+            bitcoin_tx: Transaction {
+                version: 2,
+                lock_time: LockTime::ZERO,
+                input: Vec::new(),
+                output: vec![
+                    TxOut {
+                        value: 1000,
+                        script_pubkey: ScriptBuf::new(),
+                    },
+                    TxOut {
+                        value: 1000,
+                        script_pubkey: funding_proof.to_script_pubkey().unwrap(),
+                    },
+                ],
+            },
+            tx_type: YuvTxType::Transfer {
+                input_proofs: ProofMap::new(),
+                output_proofs: ProofMap::from([
+                    (0, PixelProof::EmptyPixel(change_proof)),
+                    (1, PixelProof::Multisig(funding_proof.clone())),
+                ]),
+            },
+        };
+
+        let funding = LightningFunding::new(tx.clone()).unwrap();
+        assert_eq!(
+            funding.funding_outpoint,
+            OutPoint::new(tx.bitcoin_tx.txid(), 1)
+        );
+        assert_eq!(funding.capacity(), 1000);
+        assert_eq!(
+            funding.funding_redeem_script().unwrap().to_v0_p2wsh(),
+            tx.bitcoin_tx.output[1].script_pubkey
+        );
+
+        let to_local = funding.to_local_proof(600, pubkey(5), 144, pubkey(6));
+        assert_eq!(to_local.pixel, Pixel::new(600, chroma));
+
+        let htlc = funding.offered_htlc_proof(400, pubkey(5), pubkey(7), pubkey(8), [9; 32]);
+        let htlc_output = TxOut {
+            value: 1000,
+            script_pubkey: ScriptBuf::new_v0_p2wsh(&WScriptHash::from(LightningHtlcScript::from(
+                &htlc,
+            ))),
+        };
+        assert!(htlc.checked_check_by_output(&htlc_output).is_ok());
+        assert_eq!(
+            htlc.data.payment_hash.to_byte_array(),
+            ripemd160::Hash::hash(&[9; 32]).to_byte_array()
+        );
+    }
+}
@@ -1,21 +1,24 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     mem,
     sync::{Arc, RwLock},
 };
 
 use bitcoin::{
+    absolute::LockTime,
+    consensus::encode::VarInt,
+    hashes::sha256,
     key::XOnlyPublicKey,
     psbt,
     secp256k1::{self, All, Secp256k1},
-    OutPoint, PrivateKey, PublicKey, ScriptBuf, Transaction, TxOut,
+    OutPoint, PrivateKey, PublicKey, Script, ScriptBuf, Transaction, TxOut,
 };
 use eyre::{bail, eyre, Context, OptionExt};
 
 #[cfg(feature = "bulletproof")]
 use {
     bitcoin::secp256k1::schnorr::Signature,
-    yuv_pixels::{k256::ProjectivePoint, Luma, RangeProof},
+    yuv_pixels::{k256::ProjectivePoint, CommitmentProof, Luma},
     yuv_types::is_bulletproof,
 };
 
@@ -31,19 +34,24 @@ use bdk::{
 use yuv_pixels::Bulletproof;
 
 use yuv_pixels::{
-    Chroma, EmptyPixelProof, MultisigPixelProof, Pixel, PixelKey, PixelProof, SigPixelProof,
-    ToEvenPublicKey, ZERO_PUBLIC_KEY,
+    Chroma, EmptyPixelProof, HtlcData, HtlcPixelProof, MultisigPixelProof, MusigPixelProof, Pixel,
+    PixelKey, PixelProof, SigPixelProof, TimelockPixelProof, ToEvenPublicKey, PREIMAGE_SIZE,
+    ZERO_PUBLIC_KEY,
 };
 
 use yuv_storage::TransactionsStorage as YuvTransactionsStorage;
 use yuv_types::{announcements::IssueAnnouncement, AnyAnnouncement};
-use yuv_types::{ProofMap, YuvTransaction, YuvTxType};
+use yuv_types::{DustPolicy, ProofMap, YuvTransaction, YuvTxType};
 
 use crate::{
     bitcoin_provider::BitcoinProvider,
-    txsigner::TransactionSigner,
+    txsigner::{PrivateKeySigner, TransactionSigner},
     types::{FeeRateStrategy, Utxo, WeightedUtxo, YuvTxOut, YuvUtxo},
-    yuv_coin_selection::{YUVCoinSelectionAlgorithm, YuvLargestFirstCoinSelection},
+    yuv_coin_selection::{
+        exclude_frozen_utxos, FrozenOutpointsProvider, YUVCoinSelectionAlgorithm,
+        YuvLargestFirstCoinSelection,
+    },
+    yuv_psbt::YuvPsbt,
     Wallet,
 };
 
@@ -52,6 +60,38 @@ mod bulletproof;
 #[cfg(feature = "bulletproof")]
 pub use bulletproof::BulletproofRecipientParameters;
 
+mod cosign;
+pub use cosign::IssuancePsbt;
+use cosign::OwnerMultisigInput;
+
+mod lightning;
+pub use lightning::{LightningFunding, LightningFundingBuilder, DEFAULT_FUNDING_SATOSHIS};
+
+mod musig;
+pub use musig::MusigSigner;
+
+mod swap;
+pub use swap::{SwapPsbt, SwapTransactionBuilder};
+
+/// Maximum number of times the transaction is assembled again to pay the fee for the size of the
+/// signed transaction.
+const MAX_FEE_ADJUSTMENTS: usize = 2;
+
+/// Fee of the transaction assembled by the BDK wallet.
+#[derive(Clone, Copy, Debug)]
+enum TxFee {
+    Rate(BdkFeeRate),
+    Absolute(u64),
+}
+
+/// Inputs and outputs of the transaction processed for the BDK wallet.
+struct ProcessedParts {
+    input_proofs: HashMap<OutPoint, PixelProof>,
+    inputs: Vec<(OutPoint, psbt::Input, usize)>,
+    output_proofs: Vec<PixelProof>,
+    outputs: Vec<(ScriptBuf, u64)>,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 enum BuilderInput {
@@ -65,6 +105,16 @@ enum BuilderInput {
     TweakedSatoshis {
         outpoint: OutPoint,
     },
+    Musig {
+        outpoint: OutPoint,
+    },
+    HtlcClaim {
+        outpoint: OutPoint,
+        preimage: [u8; PREIMAGE_SIZE],
+    },
+    HtlcRefund {
+        outpoint: OutPoint,
+    },
     #[cfg(feature = "bulletproof")]
     BulletproofPixel {
         outpoint: OutPoint,
@@ -76,7 +126,10 @@ impl BuilderInput {
         match self {
             BuilderInput::Multisig2x2 { outpoint, .. }
             | BuilderInput::Pixel { outpoint }
-            | BuilderInput::TweakedSatoshis { outpoint } => *outpoint,
+            | BuilderInput::TweakedSatoshis { outpoint }
+            | BuilderInput::Musig { outpoint }
+            | BuilderInput::HtlcClaim { outpoint, .. }
+            | BuilderInput::HtlcRefund { outpoint } => *outpoint,
             #[cfg(feature = "bulletproof")]
             BuilderInput::BulletproofPixel { outpoint, .. } => *outpoint,
         }
@@ -103,6 +156,25 @@ enum BuilderOutput {
         participants: Vec<secp256k1::PublicKey>,
         required_signatures: u8,
     },
+    MusigPixel {
+        chroma: Chroma,
+        satoshis: u64,
+        amount: u128,
+        participants: Vec<secp256k1::PublicKey>,
+    },
+    TimelockedPixel {
+        chroma: Chroma,
+        satoshis: u64,
+        amount: u128,
+        recipient: secp256k1::PublicKey,
+        lock_height: u32,
+    },
+    HtlcPixel {
+        chroma: Chroma,
+        satoshis: u64,
+        amount: u128,
+        data: HtlcData,
+    },
     #[cfg(feature = "bulletproof")]
     BulletproofPixel {
         chroma: Chroma,
@@ -111,7 +183,7 @@ enum BuilderOutput {
         luma: Luma,
         satoshis: u64,
         commitment: ProjectivePoint,
-        proof: RangeProof,
+        proof: CommitmentProof,
         signature: Signature,
         chroma_signature: Signature,
     },
@@ -121,19 +193,38 @@ impl BuilderOutput {
     fn amount(&self) -> u128 {
         match self {
             BuilderOutput::Satoshis { .. } => 0,
-            BuilderOutput::Pixel { amount, .. } | BuilderOutput::MultisigPixel { amount, .. } => {
-                *amount
-            }
+            BuilderOutput::Pixel { amount, .. }
+            | BuilderOutput::MultisigPixel { amount, .. }
+            | BuilderOutput::MusigPixel { amount, .. }
+            | BuilderOutput::TimelockedPixel { amount, .. }
+            | BuilderOutput::HtlcPixel { amount, .. } => *amount,
             #[cfg(feature = "bulletproof")]
             BuilderOutput::BulletproofPixel { .. } => 0,
         }
     }
 
+    /// Satoshis of the output holding a pixel, `None` for the satoshis only outputs.
+    fn pixel_satoshis(&self) -> Option<u64> {
+        match self {
+            BuilderOutput::Satoshis { .. } => None,
+            BuilderOutput::Pixel { satoshis, .. }
+            | BuilderOutput::MultisigPixel { satoshis, .. }
+            | BuilderOutput::MusigPixel { satoshis, .. }
+            | BuilderOutput::TimelockedPixel { satoshis, .. }
+            | BuilderOutput::HtlcPixel { satoshis, .. } => Some(*satoshis),
+            #[cfg(feature = "bulletproof")]
+            BuilderOutput::BulletproofPixel { satoshis, .. } => Some(*satoshis),
+        }
+    }
+
     fn chroma(&self) -> Option<Chroma> {
         match self {
             BuilderOutput::Satoshis { .. } => None,
             BuilderOutput::Pixel { chroma, .. } => Some(*chroma),
             BuilderOutput::MultisigPixel { chroma, .. } => Some(*chroma),
+            BuilderOutput::MusigPixel { chroma, .. } => Some(*chroma),
+            BuilderOutput::TimelockedPixel { chroma, .. } => Some(*chroma),
+            BuilderOutput::HtlcPixel { chroma, .. } => Some(*chroma),
             #[cfg(feature = "bulletproof")]
             BuilderOutput::BulletproofPixel { chroma, .. } => Some(*chroma),
         }
@@ -153,6 +244,10 @@ struct TransactionBuilder<YuvTxsDatabase, BitcoinTxsDatabase> {
     /// Value of satoshis that will be attached to change output for YUV coins.
     change_satoshis: u64,
 
+    /// Minimal amount of satoshis of the pixel outputs, which is checked before building, so
+    /// the transaction isn't rejected by the nodes.
+    dust_policy: DustPolicy,
+
     /// The fee rate strategy. Possible values:
     /// - Estimate: The fee rate is fetched from Bitcoin RPC. If an error
     ///   occurs, the tx building process is interrupted.
@@ -162,11 +257,18 @@ struct TransactionBuilder<YuvTxsDatabase, BitcoinTxsDatabase> {
     /// NOTE: fee_rate is measured in sat/vb.
     fee_rate_strategy: FeeRateStrategy,
 
+    /// Maximum size (in bytes) of the transaction serialized to JSON, which is how the
+    /// transaction is sent to the node's RPC. If the built transaction exceeds it, building fails,
+    /// so the caller could split the transfer instead of having it rejected by the node.
+    max_request_size: Option<usize>,
+
     yuv_txs_storage: YuvTxsDatabase,
 
     /// Inner wallet which will sign result transaction.
     inner_wallet: Arc<RwLock<bdk::Wallet<BitcoinTxsDatabase>>>,
-    private_key: PrivateKey,
+    /// Private key of the user, `None` if the wallet is watch-only.
+    private_key: Option<PrivateKey>,
+    public_key: PublicKey,
 
     /// Storage of transactions outputs that could be spent
     yuv_utxos: Arc<RwLock<HashMap<OutPoint, PixelProof>>>,
@@ -183,18 +285,37 @@ struct TransactionBuilder<YuvTxsDatabase, BitcoinTxsDatabase> {
     bulletproof_outputs:
         BTreeMap<Option<OutPoint>, Vec<(Chroma, bulletproof::BulletproofRecipientParameters)>>,
 
+    /// Prove the amounts of all the bulletproof outputs with one aggregated range proof instead
+    /// of a range proof per output.
+    #[cfg(feature = "bulletproof")]
+    aggregate_bulletproofs: bool,
+
     /// Storage of inputs which will be formed into transaction inputs and
     /// proofs.
     inputs: Vec<BuilderInput>,
 
+    /// Bitcoin inputs, which are not owned by the wallet, e.g. the output of the chroma's
+    /// multisig owner, with the weights to satisfy them.
+    foreign_inputs: Vec<(OutPoint, psbt::Input, usize)>,
+
     /// Transaction Signer is responsible for signing the transaction.
-    tx_signer: TransactionSigner,
+    tx_signer: PrivateKeySigner,
 
     /// Indicated if inputs were selected by user.
     is_inputs_selected: bool,
 
     /// Instructs txbuilder to add tweaked satoshis as transaction inputs
     should_drain_tweaked_satoshis: bool,
+
+    /// Sign the Bitcoin inputs with the BDK wallet. Disabled for the swap halves, which are
+    /// signed after they are merged.
+    should_sign_bitcoin_inputs: bool,
+
+    /// Provider of the frozen outpoints, which are excluded from the coin selection.
+    frozen_outpoints_provider: Option<Arc<dyn FrozenOutpointsProvider>>,
+
+    /// Outpoints that are excluded from both YUV and Bitcoin coin selection.
+    unspendable: HashSet<OutPoint>,
 }
 
 unsafe impl<YuvTxsDatabase, BitcoinTxsDatabase> Sync
@@ -252,6 +373,8 @@ where
 pub struct IssuanceTransactionBuilder<YuvTxsDatabase, BitcoinTxsDatabase> {
     tx_builder: TransactionBuilder<YuvTxsDatabase, BitcoinTxsDatabase>,
     chroma: Chroma,
+    /// Outputs of the chroma's multisig owner, which are spent to authorize the issuance.
+    owner_inputs: Vec<OwnerMultisigInput>,
 }
 
 impl<YTDB, BDB> IssuanceTransactionBuilder<YTDB, BDB>
@@ -265,7 +388,11 @@ where
     ) -> eyre::Result<Self> {
         let tx_builder = TransactionBuilder::new(true, wallet)?;
         let chroma = chroma.unwrap_or(tx_builder.issuance_chroma());
-        Ok(Self { tx_builder, chroma })
+        Ok(Self {
+            tx_builder,
+            chroma,
+            owner_inputs: Vec::new(),
+        })
     }
 }
 
@@ -298,6 +425,22 @@ where
         self
     }
 
+    /// Fail the building if the transaction serialized to JSON is larger than the given size
+    /// (in bytes), e.g. the node's `max_request_size_kb`.
+    pub fn set_max_request_size(&mut self, max_request_size: usize) -> &mut Self {
+        self.tx_builder.set_max_request_size(max_request_size);
+
+        self
+    }
+
+    /// Override the minimal amount of satoshis of the pixel outputs, which is the one of the
+    /// wallet's network by default.
+    pub fn set_dust_policy(&mut self, dust_policy: DustPolicy) -> &mut Self {
+        self.tx_builder.set_dust_policy(dust_policy);
+
+        self
+    }
+
     // Override spending tweaked satoshis
     pub fn set_drain_tweaked_satoshis(&mut self, should_drain_tweaked_satoshis: bool) -> &mut Self {
         self.tx_builder.should_drain_tweaked_satoshis = should_drain_tweaked_satoshis;
@@ -337,11 +480,95 @@ where
         self
     }
 
+    /// Add 2-of-2 MuSig2 recipient to the transaction.
+    ///
+    /// The transaction output will be formed as P2TR output with the key
+    /// aggregated from the participants' keys and tweaked by the pixel.
+    pub fn add_musig_recipient(
+        &mut self,
+        participants: Vec<secp256k1::PublicKey>,
+        amount: u128,
+        satoshis: u64,
+    ) -> &mut Self {
+        self.tx_builder
+            .add_musig_recipient(participants, amount, self.chroma, satoshis);
+
+        self
+    }
+
+    /// Add recipient of the tokens that can't be spent before the block
+    /// height, e.g. of the vesting tokens.
+    ///
+    /// The transaction output will be formed as P2WSH output with the script
+    /// that checks the lock time, and then the signature of the tweaked key.
+    pub fn add_timelocked_recipient(
+        &mut self,
+        recipient: &secp256k1::PublicKey,
+        amount: u128,
+        satoshis: u64,
+        lock_height: u32,
+    ) -> &mut Self {
+        self.tx_builder.add_timelocked_recipient(
+            recipient,
+            amount,
+            self.chroma,
+            satoshis,
+            lock_height,
+        );
+
+        self
+    }
+
     /// Finish issuance building, and create Bitcoin transactions with attached
     /// proofs for it in [`YuvTransaction`].
     pub async fn finish(self, blockchain: &impl Blockchain) -> eyre::Result<YuvTransaction> {
         self.tx_builder.finish(blockchain).await
     }
+
+    /// Finish issuance building, and create [`YuvPsbt`] with YUV inputs to be signed by
+    /// the external signer.
+    pub async fn finish_psbt(self, blockchain: &impl Blockchain) -> eyre::Result<YuvPsbt> {
+        self.tx_builder.finish_psbt(blockchain).await
+    }
+
+    /// Spend the output of the chroma's owner, which is the P2WSH `sortedmulti` of the
+    /// `participants` with the `required_signatures`, to authorize the issuance of the chroma
+    /// owned by the multisig.
+    ///
+    /// The input is signed by the co-signers of the PSBT built with
+    /// [`Self::finish_cosigning_psbt`].
+    pub fn add_owner_multisig_input(
+        &mut self,
+        outpoint: OutPoint,
+        participants: Vec<secp256k1::PublicKey>,
+        required_signatures: u8,
+    ) -> &mut Self {
+        self.owner_inputs.push(OwnerMultisigInput {
+            outpoint,
+            participants,
+            required_signatures,
+        });
+
+        self
+    }
+
+    /// Finish issuance building, and create [`IssuancePsbt`] with the inputs of the wallet
+    /// signed, and the owner multisig inputs left to be signed by the co-signers.
+    pub async fn finish_cosigning_psbt(
+        mut self,
+        blockchain: &impl Blockchain,
+    ) -> eyre::Result<IssuancePsbt> {
+        if self.owner_inputs.is_empty() {
+            bail!("No owner multisig inputs to co-sign");
+        }
+
+        for owner_input in &self.owner_inputs {
+            let foreign_input = owner_input.to_foreign_input(blockchain)?;
+            self.tx_builder.foreign_inputs.push(foreign_input);
+        }
+
+        self.tx_builder.finish_cosigning_psbt(blockchain).await
+    }
 }
 
 pub struct TransferTransactionBuilder<YuvTxsDatabase, BitcoinTxsDatabase>(
@@ -407,12 +634,35 @@ where
         self
     }
 
+    /// Fail the building if the transaction serialized to JSON is larger than the given size
+    /// (in bytes), e.g. the node's `max_request_size_kb`.
+    pub fn set_max_request_size(&mut self, max_request_size: usize) -> &mut Self {
+        self.0.set_max_request_size(max_request_size);
+        self
+    }
+
+    /// Override the minimal and the change amounts of satoshis of the pixel outputs, which are
+    /// the ones of the wallet's network by default. Call it before `set_change_satoshis`.
+    pub fn set_dust_policy(&mut self, dust_policy: DustPolicy) -> &mut Self {
+        self.0.set_dust_policy(dust_policy);
+        self
+    }
+
     // Override spending tweaked satoshis
     pub fn set_drain_tweaked_satoshis(&mut self, should_drain_tweaked_satoshis: bool) -> &mut Self {
         self.0.should_drain_tweaked_satoshis = should_drain_tweaked_satoshis;
         self
     }
 
+    /// Exclude the outpoints frozen according to the `provider` from the coin selection.
+    pub fn set_frozen_outpoints_provider(
+        &mut self,
+        provider: impl FrozenOutpointsProvider + 'static,
+    ) -> &mut Self {
+        self.0.frozen_outpoints_provider = Some(Arc::new(provider));
+        self
+    }
+
     /// Add satoshi recipient.
     pub fn add_sats_recipient(
         &mut self,
@@ -424,6 +674,14 @@ where
         self
     }
 
+    /// Exclude the outpoint from the coin selection, e.g. when it's spent by another
+    /// transaction that was built, but isn't broadcasted yet.
+    pub fn add_unspendable(&mut self, outpoint: OutPoint) -> &mut Self {
+        self.0.unspendable.insert(outpoint);
+
+        self
+    }
+
     /// Add a 2x2 multisignature input.
     pub fn add_2x2multisig_input(
         &mut self,
@@ -453,6 +711,103 @@ where
         self
     }
 
+    /// Add 2-of-2 MuSig2 recipient to the transaction.
+    ///
+    /// The transaction output will be formed as P2TR output with the key
+    /// aggregated from the participants' keys and tweaked by the pixel.
+    pub fn add_musig_recipient(
+        &mut self,
+        participants: Vec<secp256k1::PublicKey>,
+        amount: u128,
+        chroma: Chroma,
+        satoshis: u64,
+    ) -> &mut Self {
+        self.0
+            .add_musig_recipient(participants, amount, chroma, satoshis);
+
+        self
+    }
+
+    /// Add recipient of the tokens that can't be spent before the block
+    /// height, e.g. of the vesting tokens.
+    ///
+    /// The transaction output will be formed as P2WSH output with the script
+    /// that checks the lock time, and then the signature of the tweaked key.
+    pub fn add_timelocked_recipient(
+        &mut self,
+        chroma: Chroma,
+        recipient: &secp256k1::PublicKey,
+        amount: u128,
+        satoshis: u64,
+        lock_height: u32,
+    ) -> &mut Self {
+        self.0
+            .add_timelocked_recipient(recipient, amount, chroma, satoshis, lock_height);
+
+        self
+    }
+
+    /// Add 2-of-2 MuSig2 input to the transaction with given outpoint.
+    ///
+    /// The input can't be signed by the wallet alone, so the transaction must
+    /// be finished with [`Self::finish_psbt`], and the input is signed by both
+    /// participants with [`MusigSigner`].
+    pub fn add_musig_input(&mut self, outpoint: OutPoint) -> &mut Self {
+        self.0.add_musig_input(outpoint);
+
+        self
+    }
+
+    /// Add HTLC recipient to the transaction, which claims the tokens with
+    /// the preimage of the `payment_hash`, or the wallet gets them back from
+    /// the `lock_height`.
+    ///
+    /// The transaction output will be formed as P2WSH output with the HTLC
+    /// script, that has tweaked recipient's key.
+    pub fn add_htlc_recipient(
+        &mut self,
+        chroma: Chroma,
+        recipient: &secp256k1::PublicKey,
+        amount: u128,
+        satoshis: u64,
+        payment_hash: sha256::Hash,
+        lock_height: u32,
+    ) -> &mut Self {
+        let data = HtlcData::new(
+            payment_hash,
+            *recipient,
+            self.0.public_key.inner,
+            lock_height,
+        );
+
+        self.0.add_htlc_recipient(data, amount, chroma, satoshis);
+
+        self
+    }
+
+    /// Add HTLC input to the transaction, which the wallet claims as the
+    /// recipient with the `preimage` of the payment hash.
+    pub fn add_htlc_claim_input(
+        &mut self,
+        outpoint: OutPoint,
+        preimage: [u8; PREIMAGE_SIZE],
+    ) -> &mut Self {
+        self.0
+            .inputs
+            .push(BuilderInput::HtlcClaim { outpoint, preimage });
+
+        self
+    }
+
+    /// Add HTLC input to the transaction, which the wallet refunds as the
+    /// sender. The lock time of the transaction is set to the lock height of
+    /// the HTLC, so it can be broadcasted only from that height.
+    pub fn add_htlc_refund_input(&mut self, outpoint: OutPoint) -> &mut Self {
+        self.0.inputs.push(BuilderInput::HtlcRefund { outpoint });
+
+        self
+    }
+
     /// Set flag that only selected inputs will be used for transaction
     pub fn manual_selected_only(&mut self) {
         self.0.manual_selected_only();
@@ -477,6 +832,18 @@ where
     pub async fn finish(self, blockchain: &impl Blockchain) -> eyre::Result<YuvTransaction> {
         self.0.finish(blockchain).await
     }
+
+    /// Finish transfer building, and create [`YuvPsbt`] with YUV inputs to be signed by
+    /// the external signer.
+    pub async fn finish_psbt(self, blockchain: &impl Blockchain) -> eyre::Result<YuvPsbt> {
+        self.0.finish_psbt(blockchain).await
+    }
+
+    /// Finish transfer building, and create the unsigned half of the swap, which is merged with
+    /// the counterparty's one by [`SwapTransactionBuilder`].
+    pub async fn finish_swap_half(self, blockchain: &impl Blockchain) -> eyre::Result<SwapPsbt> {
+        self.0.finish_swap_half(blockchain).await
+    }
 }
 
 impl<YTDB, BDB> TransactionBuilder<YTDB, BDB>
@@ -488,23 +855,33 @@ where
         let bitcoin_wallet = wallet.bitcoin_wallet.clone();
 
         let ctx = { bitcoin_wallet.read().unwrap().secp_ctx().clone() };
+        let dust_policy = DustPolicy::for_network(wallet.network.into());
 
         Ok(Self {
             is_issuance,
             chromas: Vec::new(),
-            change_satoshis: 1000,
+            change_satoshis: dust_policy.default_satoshis,
+            dust_policy,
             fee_rate_strategy: FeeRateStrategy::default(),
+            max_request_size: None,
             inner_wallet: bitcoin_wallet,
             private_key: wallet.signer_key,
+            public_key: wallet.pubkey,
             yuv_txs_storage: wallet.yuv_txs_storage.clone(),
             yuv_utxos: wallet.utxos.clone(),
             outputs: Vec::new(),
             #[cfg(feature = "bulletproof")]
             bulletproof_outputs: BTreeMap::new(),
+            #[cfg(feature = "bulletproof")]
+            aggregate_bulletproofs: false,
             inputs: Vec::new(),
-            tx_signer: TransactionSigner::new(ctx, wallet.signer_key),
+            tx_signer: PrivateKeySigner::new(ctx, wallet.network),
             is_inputs_selected: false,
             should_drain_tweaked_satoshis: false,
+            should_sign_bitcoin_inputs: true,
+            foreign_inputs: Vec::new(),
+            frozen_outpoints_provider: None,
+            unspendable: HashSet::new(),
         })
     }
 }
@@ -564,6 +941,80 @@ where
         self
     }
 
+    /// Add 2-of-2 MuSig2 recipient to the transaction.
+    ///
+    /// The transaction output will be formed as P2TR output with the key
+    /// aggregated from the participants' keys and tweaked by the pixel.
+    pub fn add_musig_recipient(
+        &mut self,
+        participants: Vec<secp256k1::PublicKey>,
+        amount: u128,
+        chroma: Chroma,
+        satoshis: u64,
+    ) -> &mut Self {
+        self.outputs.push(BuilderOutput::MusigPixel {
+            chroma,
+            satoshis,
+            amount,
+            participants,
+        });
+
+        self.chromas.push(chroma);
+
+        self
+    }
+
+    /// Add recipient of the tokens that can't be spent before the block
+    /// height.
+    pub fn add_timelocked_recipient(
+        &mut self,
+        recipient: &secp256k1::PublicKey,
+        amount: u128,
+        chroma: Chroma,
+        satoshis: u64,
+        lock_height: u32,
+    ) -> &mut Self {
+        self.outputs.push(BuilderOutput::TimelockedPixel {
+            chroma,
+            satoshis,
+            amount,
+            recipient: *recipient,
+            lock_height,
+        });
+
+        self.chromas.push(chroma);
+
+        self
+    }
+
+    /// Add HTLC recipient to the transaction.
+    pub fn add_htlc_recipient(
+        &mut self,
+        data: HtlcData,
+        amount: u128,
+        chroma: Chroma,
+        satoshis: u64,
+    ) -> &mut Self {
+        self.outputs.push(BuilderOutput::HtlcPixel {
+            chroma,
+            satoshis,
+            amount,
+            data,
+        });
+
+        self.chromas.push(chroma);
+
+        self
+    }
+
+    /// Add 2-of-2 MuSig2 input to the transaction with given outpoint.
+    ///
+    /// The proof will be taken from synced YUV transactions.
+    fn add_musig_input(&mut self, outpoint: OutPoint) -> &mut Self {
+        self.inputs.push(BuilderInput::Musig { outpoint });
+        self
+    }
+
     /// Add pixel input to the transaction with given outpoint.
     fn add_pixel_input(&mut self, outpoint: OutPoint) -> &mut Self {
         self.inputs.push(BuilderInput::Pixel { outpoint });
@@ -597,21 +1048,83 @@ where
         self
     }
 
+    /// Override the dust policy of the wallet's network. The change output gets the policy's
+    /// default amount of satoshis.
+    fn set_dust_policy(&mut self, dust_policy: DustPolicy) -> &mut Self {
+        self.dust_policy = dust_policy;
+        self.change_satoshis = dust_policy.default_satoshis;
+        self
+    }
+
     /// Override the fee rate strategy.
     fn set_fee_rate_strategy(&mut self, fee_rate_strategy: FeeRateStrategy) -> &mut Self {
         self.fee_rate_strategy = fee_rate_strategy;
         self
     }
 
+    /// Set the maximum size (in bytes) of the transaction serialized to JSON.
+    fn set_max_request_size(&mut self, max_request_size: usize) -> &mut Self {
+        self.max_request_size = Some(max_request_size);
+        self
+    }
+
     fn issuance_chroma(&self) -> Chroma {
+        self.public_key.to_x_only_pubkey().into()
+    }
+
+    /// Private key of the user, which is required to sign the transaction, to spend the
+    /// multisig and to create the bulletproofs.
+    pub(crate) fn private_key(&self) -> eyre::Result<PrivateKey> {
         self.private_key
-            .public_key(&Secp256k1::new())
-            .to_x_only_pubkey()
-            .into()
+            .ok_or_eyre("The wallet is watch-only, only the PSBT can be built with `finish_psbt`")
     }
 
     // === Finish transaction building ===
     async fn finish(mut self, blockchain: &impl Blockchain) -> eyre::Result<YuvTransaction> {
+        self.private_key()?;
+
+        let fee_rate = self.prepare(blockchain).await?;
+
+        self.build_tx(fee_rate).await
+    }
+
+    async fn finish_psbt(mut self, blockchain: &impl Blockchain) -> eyre::Result<YuvPsbt> {
+        let fee_rate = self.prepare(blockchain).await?;
+
+        let (psbt, tx_type, input_proofs) = self.build_psbt(fee_rate).await?;
+
+        let mut yuv_psbt = YuvPsbt::new(psbt, tx_type, &input_proofs)?;
+        yuv_psbt.max_request_size = self.max_request_size;
+
+        Ok(yuv_psbt)
+    }
+
+    async fn finish_cosigning_psbt(
+        mut self,
+        blockchain: &impl Blockchain,
+    ) -> eyre::Result<IssuancePsbt> {
+        self.private_key()?;
+
+        let fee_rate = self.prepare(blockchain).await?;
+
+        let (mut psbt, tx_type, input_proofs) = self.build_psbt(fee_rate).await?;
+
+        self.tx_signer.sign(&mut psbt, &input_proofs)?;
+
+        IssuancePsbt::new(psbt, &tx_type)
+    }
+
+    async fn finish_swap_half(mut self, blockchain: &impl Blockchain) -> eyre::Result<SwapPsbt> {
+        let fee_rate = self.prepare(blockchain).await?;
+
+        self.should_sign_bitcoin_inputs = false;
+        let (psbt, tx_type, _input_proofs) = self.build_psbt(fee_rate).await?;
+
+        SwapPsbt::new(psbt, &tx_type)
+    }
+
+    /// Estimate the fee rate and select the inputs if they weren't selected by user.
+    async fn prepare(&mut self, blockchain: &impl Blockchain) -> eyre::Result<BdkFeeRate> {
         let fee_rate = self
             .fee_rate_strategy
             .get_fee_rate(blockchain)
@@ -628,7 +1141,28 @@ where
             }
         }
 
-        self.build_tx(fee_rate).await
+        self.check_dust()?;
+
+        Ok(fee_rate)
+    }
+
+    /// Check that the pixel outputs hold at least the minimal amount of satoshis of the policy.
+    fn check_dust(&self) -> eyre::Result<()> {
+        for output in &self.outputs {
+            let Some(satoshis) = output.pixel_satoshis() else {
+                continue;
+            };
+
+            if self.dust_policy.is_dust(satoshis) {
+                bail!(
+                    "Pixel output holds {} satoshis, less than the minimum of {}",
+                    satoshis,
+                    self.dust_policy.min_satoshis
+                );
+            }
+        }
+
+        Ok(())
     }
 
     /// Fill [`Self::inputs`] with missing utxos that will be used to satisfy
@@ -670,16 +1204,28 @@ where
             )
             .await?;
 
-        let optional_utxos = {
+        let mut optional_utxos = {
             let outpoints = {
                 let yuv_utxos = self.yuv_utxos.read().unwrap();
 
-                yuv_utxos.keys().cloned().collect()
+                yuv_utxos
+                    .keys()
+                    .filter(|outpoint| !self.unspendable.contains(outpoint))
+                    .cloned()
+                    .collect()
             };
 
             self.form_weighted_utxos(outpoints, chroma).await?
         };
 
+        let frozen_amount = match &self.frozen_outpoints_provider {
+            Some(provider) => {
+                exclude_frozen_utxos(provider.as_ref(), &required_utxos, &mut optional_utxos)
+                    .await?
+            }
+            None => 0,
+        };
+
         let target_amount = output_sum.saturating_sub(input_sum);
 
         debug_assert!(target_amount > 0, "Target amount is zero");
@@ -702,6 +1248,15 @@ where
         let filled_input_sum = input_sum + selection_result.amount;
 
         if filled_input_sum < output_sum {
+            if frozen_amount > 0 {
+                bail!(
+                    "Insufficient balance: inputs sum: {} output sum: {}, {} tokens are frozen",
+                    filled_input_sum,
+                    output_sum,
+                    frozen_amount
+                );
+            }
+
             bail!(
                 "Insufficient balance: inputs sum: {} output sum: {}",
                 filled_input_sum,
@@ -722,13 +1277,11 @@ where
     fn add_change_output(&mut self, chroma: Chroma, residual_amount: u128) -> eyre::Result<()> {
         debug_assert!(residual_amount > 0, "Residual amount is zero");
 
-        let ctx = Secp256k1::new();
-
         self.outputs.push(BuilderOutput::Pixel {
             chroma,
             satoshis: self.change_satoshis,
             amount: residual_amount,
-            recipient: self.private_key.public_key(&ctx).inner,
+            recipient: self.public_key.inner,
         });
 
         Ok(())
@@ -824,7 +1377,7 @@ where
 
         tx_outs.iter_mut().skip(offset).for_each(|tx_out| {
             let (pixel_proof, script_pubkey) =
-                get_empty_pixel_proof(self.private_key.public_key(&ctx).even_public_key(&ctx))
+                get_empty_pixel_proof(self.public_key.even_public_key(&ctx))
                     .expect("Failed to get empty pixelproof");
 
             output_proofs.push(pixel_proof);
@@ -838,6 +1391,8 @@ where
         mut self,
         blockchain: &impl Blockchain,
     ) -> eyre::Result<Option<Transaction>> {
+        self.private_key()?;
+
         let fee_rate = self
             .fee_rate_strategy
             .get_fee_rate(blockchain)
@@ -901,7 +1456,7 @@ where
         let fee = fee_rate.as_sat_per_vb() as u64 * total_weight as u64;
         let output_sum = inputs_sum - fee;
 
-        let pubkey = self.private_key.public_key(&ctx);
+        let pubkey = self.public_key;
         let script_pubkey = ScriptBuf::new_v0_p2wpkh(&pubkey.wpubkey_hash().unwrap());
 
         tx_builder.add_recipient(script_pubkey, output_sum);
@@ -929,6 +1484,72 @@ where
     }
 
     async fn build_tx(mut self, fee_rate: BdkFeeRate) -> eyre::Result<YuvTransaction> {
+        let parts = self.process_parts().await?;
+
+        let mut fee = TxFee::Rate(fee_rate);
+        let mut adjustments = 0;
+        loop {
+            let (mut psbt, tx_type, input_proofs, paid_fee) = self.assemble_psbt(&parts, fee)?;
+
+            self.tx_signer.sign(&mut psbt, &input_proofs)?;
+
+            let tx = psbt.extract_tx();
+
+            let yuv_tx = YuvTransaction {
+                bitcoin_tx: tx,
+                tx_type,
+            };
+
+            check_tx_size(&yuv_tx, self.max_request_size)?;
+
+            // BDK estimates the fee with the satisfaction weights given for the YUV inputs, which
+            // may be lower than the actual witnesses, e.g. of the multisig and HTLC inputs, so the
+            // fee is checked against the size of the signed transaction, and the transaction is
+            // assembled again with the fee for that size if it's underpaid.
+            let Some(required_fee) = underpaid_fee(fee_rate, paid_fee, &yuv_tx.bitcoin_tx) else {
+                return Ok(yuv_tx);
+            };
+            if adjustments == MAX_FEE_ADJUSTMENTS {
+                bail!(
+                    "Failed to pay the fee for the size of the transaction: paid: {} required: {}",
+                    paid_fee,
+                    required_fee
+                );
+            }
+
+            tracing::debug!(
+                paid_fee,
+                required_fee,
+                "Fee of YUV transaction {} is underpaid, assembling it again",
+                yuv_tx.bitcoin_tx.txid(),
+            );
+
+            fee = TxFee::Absolute(required_fee);
+            adjustments += 1;
+        }
+    }
+
+    /// Build the PSBT with the Bitcoin inputs signed by the BDK wallet.
+    ///
+    /// Returns the PSBT, the proofs of the transaction, and the proofs of the YUV inputs, which
+    /// are left to sign, by their indexes in the PSBT.
+    async fn build_psbt(
+        &mut self,
+        fee_rate: BdkFeeRate,
+    ) -> eyre::Result<(psbt::PartiallySignedTransaction, YuvTxType, ProofMap)> {
+        let parts = self.process_parts().await?;
+
+        let (psbt, tx_type, input_proofs, _fee) =
+            self.assemble_psbt(&parts, TxFee::Rate(fee_rate))?;
+
+        Ok((psbt, tx_type, input_proofs))
+    }
+
+    /// Process the inputs and outputs of the builder for the BDK wallet.
+    ///
+    /// The processing is done once, as it extends the inputs with the bulletproof ones and
+    /// generates the bulletproofs, so the transaction could be assembled from the parts again.
+    async fn process_parts(&mut self) -> eyre::Result<ProcessedParts> {
         let ctx = Secp256k1::new();
 
         // Gather inputs as foreighn utxos with proofs for BDK wallet.
@@ -937,6 +1558,7 @@ where
 
         self.process_inputs(&ctx, &mut input_proofs, &mut inputs)
             .await?;
+        inputs.extend(self.foreign_inputs.iter().cloned());
 
         #[cfg(feature = "bulletproof")]
         if !self.bulletproof_outputs.is_empty() {
@@ -960,13 +1582,50 @@ where
             self.process_output(output, &mut output_proofs, &mut outputs)?;
         }
 
+        Ok(ProcessedParts {
+            input_proofs,
+            inputs,
+            output_proofs,
+            outputs,
+        })
+    }
+
+    /// Assemble the PSBT from the processed parts with the BDK wallet, and sign its Bitcoin
+    /// inputs.
+    ///
+    /// Returns the PSBT, the proofs of the transaction, the proofs of the YUV inputs, which are
+    /// left to sign, by their indexes in the PSBT, and the fee paid by the transaction.
+    fn assemble_psbt(
+        &self,
+        parts: &ProcessedParts,
+        fee: TxFee,
+    ) -> eyre::Result<(psbt::PartiallySignedTransaction, YuvTxType, ProofMap, u64)> {
+        let ProcessedParts {
+            input_proofs,
+            inputs,
+            output_proofs,
+            outputs,
+        } = parts;
+        let mut output_proofs = output_proofs.clone();
+
         let bitcoin_wallet = self.inner_wallet.read().unwrap();
         let mut tx_builder = bitcoin_wallet.build_tx();
 
         // Do not sort inputs and outputs to make proofs valid
         tx_builder.ordering(TxOrdering::Untouched);
         tx_builder.only_witness_utxo();
-        tx_builder.fee_rate(fee_rate);
+        match fee {
+            TxFee::Rate(fee_rate) => tx_builder.fee_rate(fee_rate),
+            TxFee::Absolute(fee) => tx_builder.fee_absolute(fee),
+        };
+        // Signal RBF, so the transaction could be replaced by `Wallet::build_yuv_fee_bump`.
+        tx_builder.enable_rbf();
+        tx_builder.unspendable(self.unspendable.iter().copied().collect());
+
+        // The HTLCs are refunded only from their lock heights.
+        if let Some(lock_height) = self.htlc_refund_lock_height(input_proofs) {
+            tx_builder.nlocktime(LockTime::from_height(lock_height)?);
+        }
 
         if self.is_issuance {
             let announcement = form_issue_announcement(output_proofs.clone())?;
@@ -975,58 +1634,74 @@ where
         }
         // Fill tx_builder with formed inputs and outputs
         for (script_pubkey, amount) in outputs {
-            tx_builder.add_recipient(script_pubkey, amount);
+            tx_builder.add_recipient(script_pubkey.clone(), *amount);
         }
         for (outpoint, psbt_input, weight) in inputs {
-            tx_builder.add_foreign_utxo(outpoint, psbt_input, weight)?;
+            tx_builder.add_foreign_utxo(*outpoint, psbt_input.clone(), *weight)?;
         }
 
         // Form transaction with satoshi inputs to satisfy conservation rules
         // of Bitcoin.
-        let (mut psbt, _details) = tx_builder.finish()?;
+        let (mut psbt, details) = tx_builder.finish()?;
+        let paid_fee = details
+            .fee
+            .ok_or_eyre("Fee of the transaction is not calculated")?;
 
         self.insert_empty_pixelproofs(&mut output_proofs, &mut psbt.unsigned_tx.output)?;
 
         let tx_type = form_tx_type(
             &psbt.unsigned_tx,
-            &input_proofs,
+            input_proofs,
             &output_proofs,
             self.is_issuance,
         )?;
 
         // Sign non YUV inputs with BDK wallet.
-        bitcoin_wallet.sign(
-            &mut psbt,
-            SignOptions {
-                try_finalize: true,
-                trust_witness_utxo: true,
-                ..Default::default()
-            },
-        )?;
+        if self.should_sign_bitcoin_inputs {
+            bitcoin_wallet.sign(
+                &mut psbt,
+                SignOptions {
+                    try_finalize: true,
+                    trust_witness_utxo: true,
+                    ..Default::default()
+                },
+            )?;
+        }
 
         // We need to sign inputs in case of transfer transaction as there are always YUV inputs.
         // We also need to sign issue transaction inputs if it spends tweaked satoshis.
-        if let YuvTxType::Transfer { input_proofs, .. } = &tx_type {
-            self.tx_signer.sign(&mut psbt, input_proofs)?;
-        } else if let YuvTxType::Issue { .. } = &tx_type {
-            // Offset is basically the number of regular Bitcoin inputs that we need to skip
-            // while constructing input proofs.
-            let offset = psbt.inputs.len() - self.inputs.len();
-            let input_proofs: ProofMap = input_proofs
-                .into_values()
-                .enumerate()
-                .map(|(index, proof)| ((index + offset) as u32, proof))
-                .collect();
-
-            self.tx_signer.sign(&mut psbt, &input_proofs)?;
-        }
+        let yuv_input_proofs = match &tx_type {
+            YuvTxType::Transfer { input_proofs, .. } => input_proofs.clone(),
+            YuvTxType::Issue { .. } => {
+                // Offset is basically the number of regular Bitcoin inputs that we need to skip
+                // while constructing input proofs.
+                let offset = psbt.inputs.len() - self.inputs.len();
+
+                input_proofs
+                    .values()
+                    .cloned()
+                    .enumerate()
+                    .map(|(index, proof)| ((index + offset) as u32, proof))
+                    .collect()
+            }
+            YuvTxType::Announcement(_) => ProofMap::new(),
+        };
 
-        let tx = psbt.extract_tx();
+        Ok((psbt, tx_type, yuv_input_proofs, paid_fee))
+    }
 
-        Ok(YuvTransaction {
-            bitcoin_tx: tx,
-            tx_type,
-        })
+    /// Returns the maximum lock height of the refunded HTLC inputs.
+    fn htlc_refund_lock_height(&self, input_proofs: &HashMap<OutPoint, PixelProof>) -> Option<u32> {
+        self.inputs
+            .iter()
+            .filter_map(|input| match input {
+                BuilderInput::HtlcRefund { outpoint } => match input_proofs.get(outpoint) {
+                    Some(PixelProof::Htlc(htlc_proof)) => Some(htlc_proof.data.lock_height),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .max()
     }
 
     /// Go through inputs, and form list of inputs for BDK wallet, and list of
@@ -1066,6 +1741,35 @@ where
                 ..Default::default()
             };
 
+            // HTLC is spent by the branch of its script, which isn't expressed by the descriptor,
+            // so the redeem script and the weight to satisfy it are set directly.
+            if let BuilderInput::HtlcClaim { .. } | BuilderInput::HtlcRefund { .. } = input {
+                let PixelProof::Htlc(htlc_proof) = &proof else {
+                    bail!("Invalid input proof type: proof is not HTLC");
+                };
+
+                if let BuilderInput::HtlcClaim { preimage, .. } = input {
+                    self.tx_signer.add_htlc_preimage(outpoint, *preimage);
+                }
+                if let Some(private_key) = self.private_key {
+                    self.tx_signer.extend_signers(HashMap::from([(
+                        self.public_key.inner.into(),
+                        private_key.inner,
+                    )]));
+                }
+
+                let redeem_script = htlc_proof.to_redeem_script()?;
+                let weight = htlc_satisfaction_weight(
+                    &redeem_script,
+                    matches!(input, BuilderInput::HtlcClaim { .. }),
+                );
+                psbt_input.witness_script = Some(redeem_script);
+
+                inputs.push((outpoint, psbt_input, weight));
+
+                continue;
+            }
+
             // Get descriptor and secret keys depending on the input type
             let (descriptor, secret_keys) =
                 self.get_descriptor_and_keys_for_input(ctx, input, &proof)?;
@@ -1108,8 +1812,10 @@ where
         // Store private keys for future signing.
         let mut keys = HashMap::new();
 
-        let pubkey1 = self.private_key.public_key(ctx);
-        keys.insert(pubkey1.inner.into(), self.private_key.inner);
+        let pubkey1 = self.public_key;
+        if let Some(private_key) = self.private_key {
+            keys.insert(pubkey1.inner.into(), private_key.inner);
+        }
 
         // Keys keys depending of input type, and create descriptors on that.
         let (descriptor, _secret_keys, _) = match input {
@@ -1130,10 +1836,23 @@ where
                 keys.insert(pubkey2.inner.into(), second_signer_key.inner);
 
                 let (tweaked_key1, key2) =
-                    sort_and_tweak(ctx, self.private_key, *second_signer_key, proof)?;
+                    sort_and_tweak(ctx, self.private_key()?, *second_signer_key, proof)?;
 
                 descriptor!(wsh(multi(2, tweaked_key1.to_public_key(), key2)))?
             }
+            BuilderInput::Musig { .. } => {
+                let PixelProof::Musig(musig_proof) = proof else {
+                    bail!("Invalid input proof type: proof is not MuSig2");
+                };
+
+                // The output is the key path only P2TR of the aggregated key tweaked by the pixel.
+                let (internal_key, _parity) = musig_proof.pixel_key()?.x_only_public_key();
+
+                descriptor!(tr(internal_key))?
+            }
+            BuilderInput::HtlcClaim { .. } | BuilderInput::HtlcRefund { .. } => {
+                bail!("HTLC inputs are not described by the descriptor")
+            }
             #[cfg(feature = "bulletproof")]
             BuilderInput::BulletproofPixel { .. } => {
                 let tweaked_pubkey = PixelKey::new_with_ctx(proof.pixel(), &pubkey1.inner, ctx)?;
@@ -1204,6 +1923,55 @@ where
 
                 (script_pubkey, *satoshis)
             }
+            // For MuSig2 pixel, form script and push proof of it to the list
+            BuilderOutput::MusigPixel {
+                chroma,
+                satoshis,
+                amount,
+                participants,
+            } => {
+                let pixel = Pixel::new(*amount, *chroma);
+
+                let musig_proof = MusigPixelProof::new(pixel, participants.clone());
+                let script_pubkey = musig_proof.to_script_pubkey()?;
+
+                output_proofs.push(PixelProof::Musig(musig_proof));
+
+                (script_pubkey, *satoshis)
+            }
+            // For HTLC pixel, form script and push proof of it to the list
+            BuilderOutput::HtlcPixel {
+                chroma,
+                satoshis,
+                amount,
+                data,
+            } => {
+                let pixel = Pixel::new(*amount, *chroma);
+
+                let htlc_proof = HtlcPixelProof::new(pixel, *data);
+                let script_pubkey = htlc_proof.to_script_pubkey()?;
+
+                output_proofs.push(PixelProof::Htlc(htlc_proof));
+
+                (script_pubkey, *satoshis)
+            }
+            // For timelocked pixel, form script and push proof of it to the list
+            BuilderOutput::TimelockedPixel {
+                chroma,
+                satoshis,
+                amount,
+                recipient,
+                lock_height,
+            } => {
+                let pixel = Pixel::new(*amount, *chroma);
+
+                let timelock_proof = TimelockPixelProof::new(pixel, *recipient, *lock_height);
+                let script_pubkey = timelock_proof.to_script_pubkey()?;
+
+                output_proofs.push(PixelProof::Timelock(timelock_proof));
+
+                (script_pubkey, *satoshis)
+            }
             // For bulletproof pixel, form script and push proof of it to the list
             #[cfg(feature = "bulletproof")]
             BuilderOutput::BulletproofPixel {
@@ -1323,6 +2091,61 @@ fn sort_and_tweak(
     Ok((key1_tweaked, public_key2))
 }
 
+/// Check that the transaction with its proofs fits into the node's request size limit.
+///
+/// Proofs are not a part of the Bitcoin transaction, so they are not paid by the fee, but they
+/// make up most of the request for bulletproof and multisig transfers.
+pub(crate) fn check_tx_size(
+    yuv_tx: &YuvTransaction,
+    max_request_size: Option<usize>,
+) -> eyre::Result<()> {
+    let json_size = yuv_tx.json_size();
+
+    tracing::debug!(
+        consensus_size = yuv_tx.consensus_size(),
+        proofs_consensus_size = yuv_tx.tx_type.consensus_size(),
+        json_size,
+        "Built YUV transaction {}",
+        yuv_tx.bitcoin_tx.txid(),
+    );
+
+    if let Some(max_request_size) = max_request_size {
+        if json_size > max_request_size {
+            bail!(
+                "Transaction size exceeds the request size limit: size: {} limit: {}",
+                json_size,
+                max_request_size
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the fee required for the size of the signed `tx` at the `fee_rate`, if the `paid_fee`
+/// is lower.
+fn underpaid_fee(fee_rate: BdkFeeRate, paid_fee: u64, tx: &Transaction) -> Option<u64> {
+    let required_fee = fee_rate.fee_vb(tx.vsize());
+
+    (paid_fee < required_fee).then_some(required_fee)
+}
+
+/// Weight of the witness that spends the HTLC by the claim or the refund branch of the
+/// `redeem_script`.
+fn htlc_satisfaction_weight(redeem_script: &Script, is_claim: bool) -> usize {
+    // Number of the witness elements and the signature with the sighash type.
+    let mut weight = 1 + 1 + 73;
+
+    weight += match is_claim {
+        // The preimage and the `OP_TRUE` selector of the branch.
+        true => 1 + PREIMAGE_SIZE + 1 + 1,
+        // The empty selector of the branch.
+        false => 1,
+    };
+
+    weight + VarInt(redeem_script.len() as u64).len() + redeem_script.len()
+}
+
 /// Generate an empty pixel proof using the given `PublicKey` and an empty `Pixel`.
 fn get_empty_pixel_proof(recipient: secp256k1::PublicKey) -> eyre::Result<(PixelProof, ScriptBuf)> {
     let pixel_key = PixelKey::new(Pixel::empty(), &recipient)?;
@@ -1396,4 +2219,26 @@ mod tests {
         check_is_sync::<TransactionBuilder<LevelDB, MemoryDatabase>>();
         check_is_send::<TransactionBuilder<LevelDB, MemoryDatabase>>();
     }
+
+    #[test]
+    fn test_underpaid_fee_is_for_signed_size() {
+        let tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut {
+                value: 1000,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        let fee_rate = BdkFeeRate::from_sat_per_vb(2.0);
+        let required_fee = 2 * tx.vsize() as u64;
+
+        assert_eq!(
+            underpaid_fee(fee_rate, required_fee - 1, &tx),
+            Some(required_fee)
+        );
+        assert_eq!(underpaid_fee(fee_rate, required_fee, &tx), None);
+        assert_eq!(underpaid_fee(fee_rate, required_fee + 1, &tx), None);
+    }
 }
@@ -0,0 +1,262 @@
+//! Signing of the 2-of-2 MuSig2 pixel inputs.
+//!
+//! The output with [`MusigPixelProof`] is locked by the single Taproot key aggregated from the
+//! keys of the participants, so neither of them can sign its spending alone. The spending
+//! transaction is built as [`YuvPsbt`] with [`TransferTransactionBuilder::add_musig_input`] and
+//! [`TransferTransactionBuilder::finish_psbt`], and is passed to the counterparty. Then both
+//! participants sign each MuSig2 input with their own [`MusigSigner`] in two rounds:
+//!
+//! 1. [`MusigSigner::new`] generates the nonce for the input, and the participants exchange the
+//!    public nonces got from [`MusigSigner::pubnonce`];
+//! 2. [`MusigSigner::sign`] with the counterparty's public nonce returns the partial signature,
+//!    and the participants exchange the partial signatures.
+//!
+//! Either of them then puts the aggregated signature to the PSBT with [`MusigSigner::finalize`],
+//! and extracts the transaction with [`YuvPsbt::finalize`].
+//!
+//! The signer must not be reused for another PSBT: the nonce is bound to the signed transaction,
+//! and it's consumed by the second round.
+//!
+//! [`TransferTransactionBuilder::add_musig_input`]: super::TransferTransactionBuilder::add_musig_input
+//! [`TransferTransactionBuilder::finish_psbt`]: super::TransferTransactionBuilder::finish_psbt
+use bitcoin::{
+    psbt::PartiallySignedTransaction,
+    secp256k1::{Message, Secp256k1, SecretKey},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot,
+};
+use eyre::{bail, ensure, eyre, Context, OptionExt};
+use rand::RngCore;
+use yuv_pixels::{
+    musig_signing::{
+        nonce_gen, MusigKeyAgg, MusigPartialSignature, MusigPubNonce, MusigSecNonce, MusigSession,
+    },
+    PixelProof,
+};
+
+use crate::yuv_psbt::YuvPsbt;
+
+/// Signer of the participant for one MuSig2 input of the [`YuvPsbt`].
+pub struct MusigSigner {
+    index: usize,
+    secret_key: SecretKey,
+    key_agg: MusigKeyAgg,
+    sighash: Message,
+    pubnonce: MusigPubNonce,
+    /// Secret nonce, which is taken by the second round.
+    secnonce: Option<MusigSecNonce>,
+    /// Session and the own partial signature, set by the second round.
+    session: Option<(MusigSession, MusigPartialSignature)>,
+}
+
+impl MusigSigner {
+    /// Start signing of the MuSig2 input `index` by the participant with the `secret_key`, and
+    /// generate the nonce of the first round.
+    pub fn new(yuv_psbt: &YuvPsbt, index: u32, secret_key: SecretKey) -> eyre::Result<Self> {
+        let input_proofs = yuv_psbt.input_proofs()?;
+        let Some(PixelProof::Musig(proof)) = input_proofs.get(&index) else {
+            bail!("Input {} is not a MuSig2 pixel input", index);
+        };
+
+        let ctx = Secp256k1::new();
+        ensure!(
+            proof.inner_keys.contains(&secret_key.public_key(&ctx)),
+            "Key is not a participant of the input {}",
+            index
+        );
+
+        let key_agg = proof.key_agg(&ctx)?;
+        let sighash = key_spend_sighash(&yuv_psbt.psbt, index as usize)?;
+
+        let mut rand = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut rand);
+
+        let (secnonce, pubnonce) = nonce_gen(&ctx, &secret_key, &key_agg, &sighash, rand)?;
+
+        Ok(Self {
+            index: index as usize,
+            secret_key,
+            key_agg,
+            sighash,
+            pubnonce,
+            secnonce: Some(secnonce),
+            session: None,
+        })
+    }
+
+    /// Public nonce to send to the counterparty at the first round.
+    pub fn pubnonce(&self) -> MusigPubNonce {
+        self.pubnonce
+    }
+
+    /// Sign the input with the public nonce of the counterparty, and return the partial signature
+    /// to send to the counterparty at the second round.
+    pub fn sign(
+        &mut self,
+        counterparty_pubnonce: MusigPubNonce,
+    ) -> eyre::Result<MusigPartialSignature> {
+        let secnonce = self
+            .secnonce
+            .take()
+            .ok_or_eyre("Input is already signed, the nonce can't be reused")?;
+
+        let ctx = Secp256k1::new();
+        let session = MusigSession::new(
+            &ctx,
+            self.key_agg.clone(),
+            &[self.pubnonce, counterparty_pubnonce],
+            &self.sighash,
+        )?;
+
+        let partial_signature = session.partial_sign(&ctx, secnonce, &self.secret_key)?;
+        self.session = Some((session, partial_signature));
+
+        Ok(partial_signature)
+    }
+
+    /// Aggregate the own partial signature with the counterparty's one, and put the signature to
+    /// the input of the PSBT.
+    pub fn finalize(
+        self,
+        yuv_psbt: &mut YuvPsbt,
+        counterparty_partial_signature: MusigPartialSignature,
+    ) -> eyre::Result<()> {
+        let (session, partial_signature) = self
+            .session
+            .ok_or_eyre("Input is not signed, the second round is not done")?;
+
+        let signature = session.aggregate(&[partial_signature, counterparty_partial_signature])?;
+
+        Secp256k1::verification_only()
+            .verify_schnorr(&signature, &self.sighash, &self.key_agg.x_only_public_key())
+            .wrap_err("Counterparty's nonce or partial signature is invalid")?;
+
+        let input = yuv_psbt
+            .psbt
+            .inputs
+            .get_mut(self.index)
+            .ok_or_else(|| eyre!("Input {} is not found in the PSBT", self.index))?;
+
+        input.tap_key_sig = Some(taproot::Signature {
+            sig: signature,
+            hash_ty: TapSighashType::Default,
+        });
+
+        Ok(())
+    }
+}
+
+/// Sighash of the key path spending of the input, which commits to all the inputs' outputs.
+fn key_spend_sighash(psbt: &PartiallySignedTransaction, index: usize) -> eyre::Result<Message> {
+    let prevouts = psbt
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| {
+            input
+                .witness_utxo
+                .clone()
+                .ok_or_else(|| eyre!("Witness UTXO of the input {} is missing", index))
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    let sighash = SighashCache::new(&psbt.unsigned_tx).taproot_key_spend_signature_hash(
+        index,
+        &Prevouts::All(&prevouts),
+        TapSighashType::Default,
+    )?;
+
+    Ok(Message::from(sighash))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+    };
+    use yuv_pixels::{Chroma, MusigPixelProof, MusigWitness, Pixel};
+    use yuv_types::{ProofMap, YuvTxType};
+
+    use super::*;
+
+    fn secret_key(byte: u8) -> SecretKey {
+        SecretKey::from_slice(&[byte; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_two_round_signing() {
+        let ctx = Secp256k1::new();
+        let (alice, bob) = (secret_key(1), secret_key(2));
+        let chroma = Chroma::from(secret_key(3).x_only_public_key(&ctx).0);
+
+        let proof = MusigPixelProof::new(
+            Pixel::new(100, chroma),
+            vec![alice.public_key(&ctx), bob.public_key(&ctx)],
+        );
+        let prevout = TxOut {
+            value: 1000,
+            script_pubkey: proof.to_script_pubkey().unwrap(),
+        };
+
+        let unsigned_tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: 900,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(prevout);
+
+        let input_proofs = ProofMap::from([(0, PixelProof::Musig(proof.clone()))]);
+        let tx_type = YuvTxType::Transfer {
+            input_proofs: input_proofs.clone(),
+            output_proofs: ProofMap::new(),
+        };
+        let mut yuv_psbt = YuvPsbt::new(psbt, tx_type, &input_proofs).unwrap();
+
+        assert!(
+            MusigSigner::new(&yuv_psbt, 0, secret_key(4)).is_err(),
+            "non-participant should not sign the input"
+        );
+
+        let mut alice_signer = MusigSigner::new(&yuv_psbt, 0, alice).unwrap();
+        let mut bob_signer = MusigSigner::new(&yuv_psbt, 0, bob).unwrap();
+
+        let alice_partial = alice_signer.sign(bob_signer.pubnonce()).unwrap();
+        let bob_partial = bob_signer.sign(alice_signer.pubnonce()).unwrap();
+
+        assert!(
+            alice_signer.sign(bob_signer.pubnonce()).is_err(),
+            "nonce should not be reused"
+        );
+
+        let mut bob_psbt = yuv_psbt.clone();
+        bob_signer.finalize(&mut bob_psbt, alice_partial).unwrap();
+        alice_signer.finalize(&mut yuv_psbt, bob_partial).unwrap();
+
+        assert_eq!(
+            yuv_psbt.psbt.inputs[0].tap_key_sig,
+            bob_psbt.psbt.inputs[0].tap_key_sig
+        );
+
+        let sighash = key_spend_sighash(&yuv_psbt.psbt, 0).unwrap();
+        let tx = yuv_psbt.finalize().unwrap();
+
+        let witness = MusigWitness::from_witness(&tx.bitcoin_tx.input[0].witness).unwrap();
+        ctx.verify_schnorr(
+            &witness.signature.sig,
+            &sighash,
+            &proof.output_key().unwrap().to_inner(),
+        )
+        .expect("signature should be valid for the output key");
+    }
+}
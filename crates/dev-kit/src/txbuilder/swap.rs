@@ -0,0 +1,356 @@
+//! Atomic swaps of the pixels of different chromas within one transaction.
+//!
+//! Each party builds the unsigned half of the swap with
+//! [`TransferTransactionBuilder::finish_swap_half`]: a transfer of the pixels it gives to the
+//! counterparty, with the Bitcoin inputs paying for its part of the transaction. The halves are
+//! merged by [`SwapTransactionBuilder`] into one transaction, so either both transfers happen or
+//! none of them. Then each party checks what it receives and signs its own inputs with
+//! [`Wallet::sign_swap`], and the last one broadcasts the transaction.
+//!
+//! The halves and the merged swap are passed between the parties as [`SwapPsbt`]s, the PSBTs with
+//! the pixel proofs of the inputs and the outputs in the proprietary fields, encoded in base64.
+//!
+//! Bulletproof transfers can't be swapped, as the signatures of their proofs commit to all the
+//! inputs and outputs of the transaction.
+//!
+//! [`TransferTransactionBuilder::finish_swap_half`]: super::TransferTransactionBuilder::finish_swap_half
+//! [`Wallet::sign_swap`]: crate::Wallet::sign_swap
+use core::fmt;
+use std::{collections::BTreeMap, str::FromStr};
+
+use bitcoin::{
+    absolute::LockTime,
+    consensus::{deserialize, serialize},
+    psbt::{raw::ProprietaryKey, PartiallySignedTransaction},
+    secp256k1, Transaction,
+};
+use eyre::{bail, ensure, eyre, Context};
+use yuv_pixels::{Chroma, PixelProof};
+use yuv_types::{ProofMap, YuvTransaction, YuvTxType};
+
+use crate::yuv_psbt::{output_pixel_proof_key, pixel_proof_key};
+
+/// PSBT of the swap or its half, with the pixel proofs of the inputs and the outputs in the
+/// proprietary fields.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapPsbt {
+    pub psbt: PartiallySignedTransaction,
+}
+
+impl SwapPsbt {
+    pub(crate) fn new(
+        mut psbt: PartiallySignedTransaction,
+        tx_type: &YuvTxType,
+    ) -> eyre::Result<Self> {
+        let YuvTxType::Transfer {
+            input_proofs,
+            output_proofs,
+        } = tx_type
+        else {
+            bail!("Only transfers can be swapped");
+        };
+
+        for (index, proof) in input_proofs {
+            let input = psbt
+                .inputs
+                .get_mut(*index as usize)
+                .ok_or_else(|| eyre!("Input {} is not found in the PSBT", index))?;
+
+            input
+                .proprietary
+                .insert(pixel_proof_key(), serialize(proof));
+        }
+
+        for (vout, proof) in output_proofs {
+            let output = psbt
+                .outputs
+                .get_mut(*vout as usize)
+                .ok_or_else(|| eyre!("Output {} is not found in the PSBT", vout))?;
+
+            output
+                .proprietary
+                .insert(output_pixel_proof_key(), serialize(proof));
+        }
+
+        Ok(Self { psbt })
+    }
+
+    /// Get the pixel proofs of the inputs by their indexes.
+    pub fn input_proofs(&self) -> eyre::Result<ProofMap> {
+        proofs(
+            self.psbt.inputs.iter().map(|input| &input.proprietary),
+            &pixel_proof_key(),
+        )
+    }
+
+    /// Get the pixel proofs of the outputs by their indexes.
+    pub fn output_proofs(&self) -> eyre::Result<ProofMap> {
+        proofs(
+            self.psbt.outputs.iter().map(|output| &output.proprietary),
+            &output_pixel_proof_key(),
+        )
+    }
+
+    /// Sum of the `chroma` amounts the swap transfers to the `recipient`.
+    pub fn received_amount(
+        &self,
+        chroma: Chroma,
+        recipient: &secp256k1::PublicKey,
+    ) -> eyre::Result<u128> {
+        let (recipient, _parity) = recipient.x_only_public_key();
+
+        let mut amount = 0u128;
+        for proof in self.output_proofs()?.values() {
+            let PixelProof::Sig(proof) = proof else {
+                continue;
+            };
+
+            if proof.pixel.chroma == chroma && proof.inner_key.x_only_public_key().0 == recipient {
+                amount = amount
+                    .checked_add(proof.pixel.luma.amount)
+                    .ok_or_else(|| eyre!("Received amount overflows"))?;
+            }
+        }
+
+        Ok(amount)
+    }
+
+    /// Extract the swap transaction, once all its inputs are signed by the parties.
+    pub fn finalize(self) -> eyre::Result<YuvTransaction> {
+        if let Some(index) = self
+            .psbt
+            .inputs
+            .iter()
+            .position(|input| input.final_script_witness.is_none())
+        {
+            bail!("Input {} is not signed", index);
+        }
+
+        let tx_type = YuvTxType::Transfer {
+            input_proofs: self.input_proofs()?,
+            output_proofs: self.output_proofs()?,
+        };
+
+        Ok(YuvTransaction {
+            bitcoin_tx: self.psbt.extract_tx(),
+            tx_type,
+        })
+    }
+}
+
+impl fmt::Display for SwapPsbt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.psbt)
+    }
+}
+
+impl FromStr for SwapPsbt {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let psbt = PartiallySignedTransaction::from_str(s.trim()).wrap_err("Invalid swap PSBT")?;
+
+        Ok(Self { psbt })
+    }
+}
+
+/// Builder of the swap transaction from the halves of the parties.
+#[derive(Default)]
+pub struct SwapTransactionBuilder {
+    halves: Vec<SwapPsbt>,
+}
+
+impl SwapTransactionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the unsigned half of the swap built by one of the parties.
+    pub fn add_half(&mut self, half: SwapPsbt) -> &mut Self {
+        self.halves.push(half);
+        self
+    }
+
+    /// Merge the halves into one swap. The inputs and the outputs of the halves are placed in
+    /// the order the halves were added.
+    pub fn finish(self) -> eyre::Result<SwapPsbt> {
+        ensure!(self.halves.len() > 1, "Swap requires at least two halves");
+
+        let mut unsigned_tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: Vec::new(),
+        };
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+
+        for (index, half) in self.halves.into_iter().enumerate() {
+            ensure!(
+                half.psbt
+                    .inputs
+                    .iter()
+                    .all(|input| input.partial_sigs.is_empty()
+                        && input.final_script_witness.is_none()),
+                "Half {} of the swap is already signed",
+                index
+            );
+
+            #[cfg(feature = "bulletproof")]
+            ensure!(
+                !half
+                    .input_proofs()?
+                    .values()
+                    .chain(half.output_proofs()?.values())
+                    .any(|proof| matches!(proof, PixelProof::Bulletproof(_))),
+                "Half {} of the swap is a bulletproof transfer, which can't be swapped",
+                index
+            );
+
+            let half_tx = half.psbt.unsigned_tx;
+            for input in &half_tx.input {
+                ensure!(
+                    !unsigned_tx
+                        .input
+                        .iter()
+                        .any(|merged| merged.previous_output == input.previous_output),
+                    "Output {} is spent by several halves of the swap",
+                    input.previous_output
+                );
+            }
+
+            ensure!(
+                half_tx.lock_time.is_same_unit(unsigned_tx.lock_time),
+                "Lock time of half {} of the swap is not a block height",
+                index
+            );
+            if half_tx.lock_time.to_consensus_u32() > unsigned_tx.lock_time.to_consensus_u32() {
+                unsigned_tx.lock_time = half_tx.lock_time;
+            }
+
+            unsigned_tx.version = unsigned_tx.version.max(half_tx.version);
+            unsigned_tx.input.extend(half_tx.input);
+            unsigned_tx.output.extend(half_tx.output);
+            inputs.extend(half.psbt.inputs);
+            outputs.extend(half.psbt.outputs);
+        }
+
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx)?;
+        psbt.inputs = inputs;
+        psbt.outputs = outputs;
+
+        Ok(SwapPsbt { psbt })
+    }
+}
+
+/// Collect the pixel proofs from the proprietary fields of the PSBT inputs or outputs.
+fn proofs<'a>(
+    fields: impl Iterator<Item = &'a BTreeMap<ProprietaryKey, Vec<u8>>>,
+    key: &ProprietaryKey,
+) -> eyre::Result<ProofMap> {
+    let mut proofs = ProofMap::new();
+
+    for (index, fields) in fields.enumerate() {
+        let Some(value) = fields.get(key) else {
+            continue;
+        };
+
+        let proof: PixelProof =
+            deserialize(value).wrap_err_with(|| format!("Invalid pixel proof {}", index))?;
+
+        proofs.insert(index as u32, proof);
+    }
+
+    Ok(proofs)
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        hashes::Hash,
+        secp256k1::{Secp256k1, SecretKey},
+        OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid, Witness,
+    };
+    use yuv_pixels::{Pixel, SigPixelProof};
+
+    use super::*;
+
+    /// Half of the swap, which transfers 100 tokens of the `chroma` from one input to the
+    /// `recipient`.
+    fn half(input: u8, chroma: Chroma, recipient: secp256k1::PublicKey) -> SwapPsbt {
+        let pixel = Pixel::new(100, chroma);
+
+        let unsigned_tx = Transaction {
+            version: 2,
+            lock_time: LockTime::from_consensus(input as u32),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::from_byte_array([input; 32]), 0),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: 1000,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+
+        let tx_type = YuvTxType::Transfer {
+            input_proofs: ProofMap::from([(
+                0,
+                PixelProof::Sig(SigPixelProof::new(pixel, recipient)),
+            )]),
+            output_proofs: ProofMap::from([(
+                0,
+                PixelProof::Sig(SigPixelProof::new(pixel, recipient)),
+            )]),
+        };
+
+        let psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx).unwrap();
+
+        SwapPsbt::new(psbt, &tx_type).unwrap()
+    }
+
+    #[test]
+    fn test_merge_halves() {
+        let ctx = Secp256k1::new();
+        let alice = SecretKey::from_slice(&[1; 32]).unwrap().public_key(&ctx);
+        let bob = SecretKey::from_slice(&[2; 32]).unwrap().public_key(&ctx);
+        let chroma_a = Chroma::from(alice.x_only_public_key().0);
+        let chroma_b = Chroma::from(bob.x_only_public_key().0);
+
+        let mut builder = SwapTransactionBuilder::new();
+        builder
+            .add_half(half(1, chroma_a, bob))
+            .add_half(half(2, chroma_b, alice));
+
+        let swap = builder.finish().unwrap();
+        let swap = SwapPsbt::from_str(&swap.to_string()).unwrap();
+
+        assert_eq!(swap.psbt.unsigned_tx.input.len(), 2);
+        assert_eq!(swap.psbt.unsigned_tx.lock_time, LockTime::from_consensus(2));
+        assert_eq!(swap.input_proofs().unwrap().len(), 2);
+        assert_eq!(swap.output_proofs().unwrap().len(), 2);
+        assert_eq!(swap.received_amount(chroma_a, &bob).unwrap(), 100);
+        assert_eq!(swap.received_amount(chroma_b, &alice).unwrap(), 100);
+        assert_eq!(swap.received_amount(chroma_a, &alice).unwrap(), 0);
+        assert!(
+            swap.finalize().is_err(),
+            "unsigned swap should not be finalized"
+        );
+    }
+
+    #[test]
+    fn test_same_input_in_both_halves() {
+        let ctx = Secp256k1::new();
+        let alice = SecretKey::from_slice(&[1; 32]).unwrap().public_key(&ctx);
+        let chroma = Chroma::from(alice.x_only_public_key().0);
+
+        let mut builder = SwapTransactionBuilder::new();
+        builder
+            .add_half(half(1, chroma, alice))
+            .add_half(half(1, chroma, alice));
+
+        assert!(builder.finish().is_err());
+    }
+}
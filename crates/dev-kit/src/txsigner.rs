@@ -9,31 +9,58 @@ use bitcoin::{
     key::XOnlyPublicKey,
     psbt::PartiallySignedTransaction,
     secp256k1::{self, All, PublicKey, Secp256k1},
-    PrivateKey, ScriptBuf,
+    Network, OutPoint, PrivateKey, ScriptBuf,
 };
 use eyre::bail;
 use yuv_pixels::{
-    MultisigPixelProof, MultisigWitness, P2WPKHWitness, Pixel, PixelPrivateKey, PixelProof,
+    HtlcPixelProof, HtlcWitness, HtlcWitnessStack, MultisigPixelProof, P2WPKHWitness, Pixel,
+    PixelPrivateKey, PixelProof, PREIMAGE_SIZE,
 };
 use yuv_types::ProofMap;
 
-pub struct TransactionSigner {
+use crate::yuv_psbt::finalize_multisig;
+
+/// Signer of the YUV inputs of the transaction.
+///
+/// The YUV inputs are locked by the keys tweaked with the pixels, so the signer gets the pixel
+/// proofs of the inputs to derive the signing keys. [`PrivateKeySigner`] signs with the keys held
+/// in memory, and the external signers, e.g. a hardware wallet, sign the [`YuvPsbt`] with the
+/// proofs in its proprietary fields.
+///
+/// [`YuvPsbt`]: crate::YuvPsbt
+pub trait TransactionSigner {
+    /// Add the signatures of the pixel tweaked keys to the `partial_sigs` of the inputs in
+    /// `input_proofs`. The signer may also finalize the inputs it has signed.
+    fn sign(
+        &self,
+        psbt: &mut PartiallySignedTransaction,
+        input_proofs: &ProofMap,
+    ) -> eyre::Result<()>;
+}
+
+/// Signer of the YUV inputs with the private keys held in memory.
+pub struct PrivateKeySigner {
     /// Secp256k1 engine is used to execute all signature operations.
     ctx: Secp256k1<All>,
-    private_key: PrivateKey,
+    network: Network,
 
     /// Key-value storage of signers that will participate in transaction
     /// signing. Where key is public key of the signer, and value is private key
     /// of the signer without any tweaking (for both keys).
     signers: HashMap<XOnlyPublicKey, secp256k1::SecretKey>,
+
+    /// Preimages of the payment hashes of the HTLC inputs that are claimed. The
+    /// HTLC inputs without the preimage are refunded.
+    htlc_preimages: HashMap<OutPoint, [u8; PREIMAGE_SIZE]>,
 }
 
-impl TransactionSigner {
-    pub fn new(ctx: Secp256k1<All>, private_key: PrivateKey) -> Self {
-        TransactionSigner {
+impl PrivateKeySigner {
+    pub fn new(ctx: Secp256k1<All>, network: Network) -> Self {
+        PrivateKeySigner {
             ctx,
-            private_key,
+            network,
             signers: HashMap::new(),
+            htlc_preimages: HashMap::new(),
         }
     }
 
@@ -41,39 +68,8 @@ impl TransactionSigner {
         self.signers.extend(signers);
     }
 
-    pub fn sign(
-        self,
-        psbt: &mut PartiallySignedTransaction,
-        input_proofs: &ProofMap,
-    ) -> Result<(), eyre::ErrReport> {
-        for (index, proof) in input_proofs {
-            match &proof {
-                PixelProof::Sig(sigproof) => {
-                    self.sign_input(sigproof.pixel, &sigproof.inner_key, psbt, *index)?;
-                }
-                PixelProof::Multisig(multisig_proof) => {
-                    self.sign_multiproof_input(multisig_proof, psbt, *index)?;
-                }
-                #[cfg(feature = "bulletproof")]
-                PixelProof::Bulletproof(proof) => {
-                    self.sign_input(proof.pixel, &proof.inner_key, psbt, *index)?;
-                }
-                PixelProof::EmptyPixel(proof) => {
-                    self.sign_input(Pixel::empty(), &proof.inner_key, psbt, *index)?;
-                }
-                PixelProof::LightningHtlc(_) | PixelProof::Lightning(_) => {
-                    bail!(
-                        r#"HTLC and Lightning inputs cannot be signed using BDK wallet. Only LDK node can
-                        spend it, as it has all required information and keys."#
-                    )
-                }
-                PixelProof::P2WSH(_p2wsh_proof) => {
-                    bail!(r#"Signing P2WSH inputs is not supported yet."#)
-                }
-            };
-        }
-
-        Ok(())
+    pub fn add_htlc_preimage(&mut self, outpoint: OutPoint, preimage: [u8; PREIMAGE_SIZE]) {
+        self.htlc_preimages.insert(outpoint, preimage);
     }
 
     /// Add witness (signatures, redeem script) for pixel multisig P2WSH input
@@ -125,7 +121,7 @@ impl TransactionSigner {
 
         for secret_key in secret_keys {
             let signer = SignerWrapper::new(
-                PrivateKey::new(secret_key, self.private_key.network),
+                PrivateKey::new(secret_key, self.network),
                 SignerContext::Segwitv0,
             );
 
@@ -146,13 +142,75 @@ impl TransactionSigner {
             .get_mut(index as usize)
             .expect("Signed input should exist");
 
-        let signatures = signed_input
+        let witness = finalize_multisig(signed_input, multisig_proof)?;
+
+        signed_input.final_script_sig = Some(ScriptBuf::new());
+        signed_input.final_script_witness = Some(witness);
+
+        Ok(())
+    }
+
+    /// Add witness (signature, branch data, redeem script) for pixel HTLC P2WSH
+    /// input. The input is claimed with the recipient's key tweaked by pixel if
+    /// the preimage is known, or refunded with the sender's key otherwise.
+    fn sign_htlc_input(
+        &self,
+        htlc_proof: &HtlcPixelProof,
+        psbt: &mut PartiallySignedTransaction,
+        index: u32,
+    ) -> eyre::Result<()> {
+        let outpoint = psbt.unsigned_tx.input[index as usize].previous_output;
+        let preimage = self.htlc_preimages.get(&outpoint).copied();
+
+        let inner_key = match preimage {
+            Some(_) => htlc_proof.data.recipient_pubkey,
+            None => htlc_proof.data.sender_pubkey,
+        };
+
+        let Some(secret_key) = self.signers.get(&XOnlyPublicKey::from(inner_key)) else {
+            bail!("Signing key for HTLC input {} is missing", outpoint);
+        };
+
+        let signing_key = match preimage {
+            Some(_) => PixelPrivateKey::new_with_ctx(htlc_proof.pixel, secret_key, &self.ctx)?.0,
+            None => *secret_key,
+        };
+        let signing_pubkey = signing_key.public_key(&self.ctx).to_public_key();
+
+        let signer = SignerWrapper::new(
+            PrivateKey::new(signing_key, self.network),
+            SignerContext::Segwitv0,
+        );
+
+        signer.sign_input(
+            psbt,
+            index as usize,
+            &SignOptions {
+                try_finalize: false,
+                trust_witness_utxo: true,
+                ..Default::default()
+            },
+            &self.ctx,
+        )?;
+
+        let signed_input = psbt
+            .inputs
+            .get_mut(index as usize)
+            .expect("Signed input should exist");
+
+        let signature = *signed_input
             .partial_sigs
-            .values()
-            .cloned()
-            .collect::<Vec<_>>();
+            .get(&signing_pubkey)
+            .expect("Signature of the input should exist");
 
-        let witness = MultisigWitness::new(signatures, multisig_proof.to_reedem_script()?);
+        let stack = match preimage {
+            Some(preimage) => HtlcWitnessStack::Claim {
+                signature,
+                preimage,
+            },
+            None => HtlcWitnessStack::Refund { signature },
+        };
+        let witness = HtlcWitness::new(stack, htlc_proof.to_redeem_script()?);
 
         signed_input.final_script_sig = Some(ScriptBuf::new());
         signed_input.final_script_witness = Some(witness.into_witness());
@@ -178,7 +236,7 @@ impl TransactionSigner {
 
         // Create a wrapper around private key which can sign transaction inputs.
         let signer = SignerWrapper::new(
-            PrivateKey::new(tweaked_key.0, self.private_key.network),
+            PrivateKey::new(tweaked_key.0, self.network),
             SignerContext::Segwitv0,
         );
 
@@ -211,3 +269,52 @@ impl TransactionSigner {
         Ok(())
     }
 }
+
+impl TransactionSigner for PrivateKeySigner {
+    fn sign(
+        &self,
+        psbt: &mut PartiallySignedTransaction,
+        input_proofs: &ProofMap,
+    ) -> eyre::Result<()> {
+        for (index, proof) in input_proofs {
+            match &proof {
+                PixelProof::Sig(sigproof) => {
+                    self.sign_input(sigproof.pixel, &sigproof.inner_key, psbt, *index)?;
+                }
+                PixelProof::Multisig(multisig_proof) => {
+                    self.sign_multiproof_input(multisig_proof, psbt, *index)?;
+                }
+                #[cfg(feature = "bulletproof")]
+                PixelProof::Bulletproof(proof) => {
+                    self.sign_input(proof.pixel, &proof.inner_key, psbt, *index)?;
+                }
+                PixelProof::EmptyPixel(proof) => {
+                    self.sign_input(Pixel::empty(), &proof.inner_key, psbt, *index)?;
+                }
+                PixelProof::LightningHtlc(_) | PixelProof::Lightning(_) => {
+                    bail!(
+                        r#"HTLC and Lightning inputs cannot be signed using BDK wallet. Only LDK node can
+                        spend it, as it has all required information and keys."#
+                    )
+                }
+                PixelProof::P2WSH(_p2wsh_proof) => {
+                    bail!(r#"Signing P2WSH inputs is not supported yet."#)
+                }
+                PixelProof::Musig(_) => {
+                    bail!(
+                        r#"MuSig2 inputs are signed by both participants in two rounds. Build the
+                        transaction as PSBT and sign the inputs with MusigSigner."#
+                    )
+                }
+                PixelProof::Timelock(_) => {
+                    bail!(r#"Signing timelocked inputs is not supported yet."#)
+                }
+                PixelProof::Htlc(htlc_proof) => {
+                    self.sign_htlc_input(htlc_proof, psbt, *index)?;
+                }
+            };
+        }
+
+        Ok(())
+    }
+}
@@ -10,7 +10,7 @@ use bitcoin::ScriptBuf;
 
 use eyre::Context;
 use serde::{Deserialize, Serialize};
-use yuv_pixels::{Chroma, Luma, Pixel};
+use yuv_pixels::{Chroma, Luma, Pixel, PixelProof};
 
 /// Confirmation target in blocks to use in the `estimatesmartfee` RPC method.
 const DEFAULT_TARGET: usize = 2;
@@ -603,3 +603,11 @@ mod tests {
         assert!((fee.as_sat_per_vb() - 1.0).abs() < f32::EPSILON);
     }
 }
+
+/// Unspent output of the wallet labeled with the proof, if it's locked by the pixel key.
+#[derive(Debug, Clone)]
+pub struct LabeledUtxo {
+    pub utxo: bdk::LocalUtxo,
+    /// Proof of the output found by the pixel descriptor, `None` for the plain Bitcoin outputs.
+    pub proof: Option<PixelProof>,
+}
@@ -11,18 +11,20 @@ use bdk::{
     },
     database::{MemoryDatabase, SqliteDatabase},
     descriptor,
+    descriptor::template::DescriptorTemplateOut,
     miniscript::ToPublicKey,
     wallet::wallet_name_from_descriptor,
     Balance, LocalUtxo, SignOptions,
 };
 use bitcoin::{
     secp256k1::{self, All, Secp256k1},
-    Address, Network, OutPoint, PrivateKey, PublicKey,
+    Address, Network, OutPoint, PrivateKey, PublicKey, Txid,
 };
-use eyre::{bail, eyre, Context};
+use eyre::{bail, ensure, eyre, Context};
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use yuv_pixels::{
-    Chroma, LightningCommitmentProof, Pixel, PixelProof, ToEvenPublicKey, ZERO_PUBLIC_KEY,
+    Chroma, LightningCommitmentProof, Pixel, PixelKey, PixelProof, ProofOfReserves, ReserveOutput,
+    ToEvenPublicKey, ZERO_PUBLIC_KEY,
 };
 
 use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
@@ -30,17 +32,29 @@ use yuv_storage::{
     FlushStrategy, LevelDB, LevelDbOptions, PagesNumberStorage,
     TransactionsStorage as YuvTransactionsStorage,
 };
-use yuv_types::{Announcement, YuvTransaction};
+use yuv_types::{Announcement, DustPolicy, ProofMap, YuvTransaction, YuvTxType};
 
 use crate::{
+    backup::WalletBackup,
     bitcoin_provider::{BitcoinProvider, BitcoinProviderConfig, TxOutputStatus},
     database::wrapper::DatabaseWrapper,
-    sync::{indexer::YuvTransactionsIndexer, storage::UnspentYuvOutPointsStorage},
+    pixel_descriptor::PixelDescriptor,
+    submission::{
+        continue_submission, PendingSubmissionsStorage, Submission, SubmitOptions,
+        WalletSubmissionClient,
+    },
+    sync::{
+        filters::{scan_block_filters, FilterMatch, PixelScripts},
+        indexer::YuvTransactionsIndexer,
+        storage::{PixelDescriptorsStorage, UnspentYuvOutPointsStorage},
+    },
     txbuilder::{
-        get_output_from_storage, IssuanceTransactionBuilder, SweepTransactionBuilder,
-        TransferTransactionBuilder,
+        get_output_from_storage, IssuanceTransactionBuilder, LightningFundingBuilder, SwapPsbt,
+        SweepTransactionBuilder, TransferTransactionBuilder,
     },
-    types::{FeeRateStrategy, YuvBalances},
+    txsigner::{PrivateKeySigner, TransactionSigner},
+    types::{FeeRateStrategy, LabeledUtxo, YuvBalances},
+    yuv_coin_selection::YuvNodeFrozenOutpoints,
     AnyBitcoinProvider,
 };
 
@@ -230,8 +244,10 @@ pub struct Wallet<YuvRpcClient, YuvTxsDB, BitcoinProvider, BitcoinTxsDB> {
     /// Global wallet context used for internal operations on curve.
     pub(crate) secp_ctx: Secp256k1<All>,
 
-    /// Private key of the user.
-    pub(crate) signer_key: PrivateKey,
+    /// Private key of the user, `None` for the watch-only wallet.
+    pub(crate) signer_key: Option<PrivateKey>,
+    /// Public key of the user.
+    pub(crate) pubkey: PublicKey,
     pub(crate) network: Network,
 
     /// Internal storage for YUV UTXOs.
@@ -248,6 +264,12 @@ pub struct Wallet<YuvRpcClient, YuvTxsDB, BitcoinProvider, BitcoinTxsDB> {
 
     /// Bitcoin wallet
     pub(crate) bitcoin_wallet: Arc<RwLock<bdk::Wallet<BitcoinTxsDB>>>,
+
+    /// Descriptors of the pixel outputs synced with the Bitcoin wallet.
+    pub(crate) pixel_descriptors: Arc<RwLock<Vec<PixelDescriptor>>>,
+
+    /// Unspent outputs found by the pixel descriptors, with their proofs.
+    pub(crate) pixel_utxos: Arc<RwLock<HashMap<OutPoint, (LocalUtxo, PixelProof)>>>,
 }
 
 impl<YC, YTDB, BP, BTDB> Wallet<YC, YTDB, BP, BTDB>
@@ -256,6 +278,8 @@ where
     YTDB: YuvTransactionsStorage
         + PagesNumberStorage
         + UnspentYuvOutPointsStorage
+        + PixelDescriptorsStorage
+        + PendingSubmissionsStorage
         + Clone
         + Send
         + Sync
@@ -271,23 +295,77 @@ where
         bitcoin_provider: BP,
         bitcoin_txs_storage: BTDB,
     ) -> eyre::Result<Self> {
-        let bitcoin_wallet = bdk::Wallet::<BTDB>::new(
+        let secp_ctx = Secp256k1::new();
+        let pubkey = privkey.public_key(&secp_ctx);
+
+        Self::with_descriptor(
             descriptor!(wpkh(privkey))?,
+            Some(privkey),
+            pubkey,
+            network,
+            yuv_client,
+            yuv_txs_storage,
+            bitcoin_provider,
+            bitcoin_txs_storage,
+        )
+    }
+
+    /// Create the watch-only wallet, which knows only the public key of the user.
+    ///
+    /// The wallet syncs the outputs, tracks the balances and derives the addresses like the
+    /// regular one, but it can't sign. The transactions are built with the `finish_psbt` of the
+    /// builders, and the PSBTs are signed by an external device or service with [`YuvPsbt::sign`]
+    /// or [`YuvPsbt::combine`].
+    ///
+    /// [`YuvPsbt::sign`]: crate::YuvPsbt::sign
+    /// [`YuvPsbt::combine`]: crate::YuvPsbt::combine
+    pub fn new_watch_only(
+        pubkey: PublicKey,
+        network: Network,
+        yuv_client: YC,
+        yuv_txs_storage: YTDB,
+        bitcoin_provider: BP,
+        bitcoin_txs_storage: BTDB,
+    ) -> eyre::Result<Self> {
+        Self::with_descriptor(
+            descriptor!(wpkh(pubkey))?,
             None,
+            pubkey,
             network,
+            yuv_client,
+            yuv_txs_storage,
+            bitcoin_provider,
             bitcoin_txs_storage,
         )
-        .wrap_err("Failed to initialize wallet")?;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn with_descriptor(
+        descriptor: DescriptorTemplateOut,
+        signer_key: Option<PrivateKey>,
+        pubkey: PublicKey,
+        network: Network,
+        yuv_client: YC,
+        yuv_txs_storage: YTDB,
+        bitcoin_provider: BP,
+        bitcoin_txs_storage: BTDB,
+    ) -> eyre::Result<Self> {
+        let bitcoin_wallet =
+            bdk::Wallet::<BTDB>::new(descriptor, None, network, bitcoin_txs_storage)
+                .wrap_err("Failed to initialize wallet")?;
 
         Ok(Self {
             secp_ctx: Secp256k1::new(),
-            signer_key: privkey,
+            signer_key,
+            pubkey,
             network,
             utxos: Arc::new(RwLock::new(HashMap::new())),
             yuv_client,
             yuv_txs_storage,
             bitcoin_provider,
             bitcoin_wallet: Arc::new(RwLock::new(bitcoin_wallet)),
+            pixel_descriptors: Arc::new(RwLock::new(Vec::new())),
+            pixel_utxos: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
@@ -299,6 +377,14 @@ where
                 .write()
                 .unwrap()
                 .sync(&self.bitcoin_provider.blockchain(), opts.inner)?;
+
+            // Watch the descriptors persisted by the previous runs, e.g. restored from a backup.
+            for descriptor in self.yuv_txs_storage.get_pixel_descriptors().await? {
+                self.watch_pixel_descriptor(descriptor)?;
+            }
+
+            self.sync_pixel_descriptors()
+                .wrap_err("Failed to sync the pixel descriptors")?;
         }
 
         // Skip syncing of YUV wallet if we don't need that.
@@ -306,10 +392,7 @@ where
             return Ok(());
         }
 
-        let pubkey = self
-            .signer_key
-            .public_key(&self.secp_ctx)
-            .even_public_key(&self.secp_ctx);
+        let pubkey = self.pubkey.even_public_key(&self.secp_ctx);
 
         let utxos = YuvTransactionsIndexer::new(
             self.yuv_client.clone(),
@@ -328,14 +411,215 @@ where
         Ok(())
     }
 
+    /// Watch the outputs described by the pixel descriptor. The outputs are discovered on the
+    /// next [`Wallet::sync`] of the Bitcoin wallet, and listed by [`Wallet::list_unspent`].
+    pub fn watch_pixel_descriptor(&self, descriptor: PixelDescriptor) -> eyre::Result<()> {
+        let mut descriptors = self
+            .pixel_descriptors
+            .write()
+            .map_err(|_| eyre!("Poisoned lock"))?;
+
+        if !descriptors.contains(&descriptor) {
+            descriptors.push(descriptor);
+        }
+
+        Ok(())
+    }
+
+    /// Export the encrypted backup of the wallet's YUV state, which can be restored by the wallet
+    /// with the same key with [`Wallet::restore_backup`]. See [`backup`] for the format.
+    ///
+    /// [`backup`]: crate::backup
+    pub async fn export_backup(&self) -> eyre::Result<Vec<u8>> {
+        let signer_key = self.signer_key()?;
+
+        let pixel_descriptors = self
+            .pixel_descriptors
+            .read()
+            .map_err(|_| eyre!("Poisoned lock"))?
+            .clone();
+
+        let proofs = self
+            .utxos
+            .read()
+            .map_err(|_| eyre!("Poisoned lock"))?
+            .clone();
+
+        let pages_number = self
+            .yuv_txs_storage
+            .get_pages_number()
+            .await?
+            .unwrap_or_default();
+
+        let backup = WalletBackup {
+            network: self.network,
+            wif: signer_key.to_wif(),
+            descriptor: format!("wpkh({})", signer_key.to_wif()),
+            pixel_descriptors,
+            proofs,
+            pages_number,
+        };
+
+        backup.encrypt(&signer_key)
+    }
+
+    /// Restore the YUV state from the backup exported by [`Wallet::export_backup`]. The
+    /// descriptors and the proofs are persisted, so the next [`Wallet::sync`] continues from
+    /// the backup instead of syncing the proofs from the YUV node again.
+    pub async fn restore_backup(&self, data: &[u8]) -> eyre::Result<WalletBackup> {
+        let signer_key = self.signer_key()?;
+        let backup = WalletBackup::decrypt(data, &signer_key)?;
+
+        ensure!(
+            backup.network == self.network,
+            "The backup is made for {}, but the wallet is on {}",
+            backup.network,
+            self.network
+        );
+        ensure!(
+            backup.wif == signer_key.to_wif(),
+            "The backup is made for another key"
+        );
+
+        let mut descriptors = self.yuv_txs_storage.get_pixel_descriptors().await?;
+        for descriptor in &backup.pixel_descriptors {
+            self.watch_pixel_descriptor(*descriptor)?;
+
+            if !descriptors.contains(descriptor) {
+                descriptors.push(*descriptor);
+            }
+        }
+        self.yuv_txs_storage
+            .put_pixel_descriptors(descriptors)
+            .await?;
+
+        let mut unspent = self.yuv_txs_storage.get_unspent_yuv_outpoints().await?;
+        unspent.extend(backup.proofs.clone());
+        self.yuv_txs_storage
+            .put_unspent_yuv_outpoints(unspent)
+            .await?;
+
+        let pages_number = self
+            .yuv_txs_storage
+            .get_pages_number()
+            .await?
+            .unwrap_or_default();
+        if backup.pages_number > pages_number {
+            self.yuv_txs_storage
+                .put_pages_number(backup.pages_number)
+                .await?;
+        }
+
+        self.utxos
+            .write()
+            .map_err(|_| eyre!("Poisoned lock"))?
+            .extend(backup.proofs.clone());
+
+        Ok(backup)
+    }
+
+    /// Sync the watch-only BDK wallets of the pixel descriptors, and collect their unspent
+    /// outputs labeled with the proofs.
+    fn sync_pixel_descriptors(&self) -> eyre::Result<()> {
+        let descriptors = self
+            .pixel_descriptors
+            .read()
+            .map_err(|_| eyre!("Poisoned lock"))?
+            .clone();
+
+        let blockchain = self.bitcoin_provider.blockchain();
+
+        let mut pixel_utxos = HashMap::new();
+        for descriptor in descriptors {
+            let wallet = bdk::Wallet::new(
+                descriptor.to_descriptor()?,
+                None,
+                self.network,
+                MemoryDatabase::default(),
+            )
+            .wrap_err_with(|| format!("Failed to initialize the wallet for {}", descriptor))?;
+
+            wallet.sync(&blockchain, bdk::SyncOptions::default())?;
+
+            let proof = descriptor.proof();
+            for utxo in wallet.list_unspent()? {
+                pixel_utxos.insert(utxo.outpoint, (utxo, proof.clone()));
+            }
+        }
+
+        *self
+            .pixel_utxos
+            .write()
+            .map_err(|_| eyre!("Poisoned lock"))? = pixel_utxos;
+
+        Ok(())
+    }
+
+    /// Scan the BIP158 compact block filters from `from_height` to the best block for the
+    /// unspent outputs with the `pixels` and the empty pixel locked by the wallet's key, without
+    /// requesting the YUV node.
+    ///
+    /// As the keys of the outputs are tweaked with the pixels, only the outputs with the known
+    /// pixels can be found. The Bitcoin node must serve the block filters.
+    pub fn scan_block_filters(
+        &self,
+        pixels: impl IntoIterator<Item = Pixel>,
+        from_height: u32,
+    ) -> eyre::Result<Vec<FilterMatch>> {
+        let inner_key = self.public_key().inner;
+
+        let mut scripts = PixelScripts::new();
+        scripts.insert_empty_pixel(inner_key)?;
+        for pixel in pixels {
+            scripts.insert_pixel(pixel, inner_key)?;
+        }
+
+        let to_height = self.bitcoin_provider.get_height()?;
+        let matches = scan_block_filters(&self.bitcoin_provider, &scripts, from_height, to_height)?;
+
+        let mut unspent = Vec::with_capacity(matches.len());
+        for filter_match in matches {
+            let output_status = self
+                .bitcoin_provider
+                .get_tx_out_status(filter_match.outpoint)
+                .wrap_err("failed to get tx output")?;
+
+            if let TxOutputStatus::Unspent = output_status {
+                unspent.push(filter_match);
+            }
+        }
+
+        Ok(unspent)
+    }
+
     pub fn address(&self) -> eyre::Result<Address> {
-        let addr = Address::p2wpkh(&self.signer_key.public_key(&self.secp_ctx), self.network)?;
+        let addr = Address::p2wpkh(&self.pubkey, self.network)?;
+
+        Ok(addr)
+    }
+
+    /// Address of the output with the `pixel` locked by the wallet's key tweaked with it.
+    pub fn pixel_address(&self, pixel: Pixel) -> eyre::Result<Address> {
+        let pixel_key = PixelKey::new_with_ctx(pixel, &self.pubkey.inner, &self.secp_ctx)?;
+        let addr = Address::p2wpkh(&PublicKey::new(*pixel_key), self.network)?;
 
         Ok(addr)
     }
 
     pub fn public_key(&self) -> PublicKey {
-        self.signer_key.public_key(&self.secp_ctx)
+        self.pubkey
+    }
+
+    /// Returns `true` if the wallet knows only the public key of the user, see
+    /// [`Wallet::new_watch_only`].
+    pub fn is_watch_only(&self) -> bool {
+        self.signer_key.is_none()
+    }
+
+    /// Private key of the user, if the wallet is not watch-only.
+    pub(crate) fn signer_key(&self) -> eyre::Result<PrivateKey> {
+        self.signer_key
+            .ok_or_else(|| eyre!("The wallet is watch-only, the private key is unknown"))
     }
 
     pub fn bitcoin_provider(&self) -> BP {
@@ -404,8 +688,16 @@ where
                 // additional information.
                 //
                 // `LightningHtlc` and `Multisig` are usually spent by Lightning node and not by user.
+                // `Musig` is spent only together with the counterparty.
+                // `Timelock` is spent only after the lock height by the transaction with the
+                // lock time, which the wallet doesn't build yet.
+                // `Htlc` is claimed with the preimage or refunded after the lock height, so it's
+                // spent explicitly by the HTLC inputs of the builder.
                 PixelProof::LightningHtlc(..)
                 | PixelProof::Multisig(..)
+                | PixelProof::Musig(..)
+                | PixelProof::Timelock(..)
+                | PixelProof::Htlc(..)
                 | PixelProof::P2WSH(..) => {}
             }
         }
@@ -467,6 +759,27 @@ where
         Ok(self.bitcoin_wallet.read().unwrap().list_unspent()?)
     }
 
+    /// Get all unspent outputs of the Bitcoin wallet and of the watched pixel descriptors. The
+    /// outputs found by the pixel descriptors are labeled with their proofs.
+    pub fn list_unspent(&self) -> eyre::Result<Vec<LabeledUtxo>> {
+        let mut utxos = self
+            .bitcoin_utxos()?
+            .into_iter()
+            .map(|utxo| LabeledUtxo { utxo, proof: None })
+            .collect::<Vec<_>>();
+
+        let pixel_utxos = self
+            .pixel_utxos
+            .read()
+            .map_err(|_| eyre!("Poisoned lock"))?;
+        utxos.extend(pixel_utxos.values().map(|(utxo, proof)| LabeledUtxo {
+            utxo: utxo.clone(),
+            proof: Some(proof.clone()),
+        }));
+
+        Ok(utxos)
+    }
+
     /// Get all unspent YUV transactions outputs with given [`Chroma`].
     pub fn utxos_by_chroma(&self, chroma: Chroma) -> Vec<(OutPoint, u128)> {
         let utxos = self.yuv_utxos();
@@ -506,6 +819,67 @@ where
         self.utxos(|utxo| utxo.1.is_empty_pixelproof())
     }
 
+    /// Sign the `challenge` with the keys of the wallet's outputs with the `chroma`, to prove
+    /// the ownership of the tokens without moving them. The proof is checked with
+    /// [`yuv_pixels::verify_proof_of_reserves`].
+    ///
+    /// All the unspent outputs of the chroma are attested, unless the `outpoints` are given.
+    pub fn prove_reserves(
+        &self,
+        chroma: Chroma,
+        challenge: impl Into<String>,
+        outpoints: Option<&[OutPoint]>,
+    ) -> eyre::Result<ProofOfReserves> {
+        let signer_key = self.signer_key()?;
+        let challenge = challenge.into();
+
+        let utxos = self.yuv_utxos();
+        let mut selected = match outpoints {
+            Some(outpoints) => outpoints
+                .iter()
+                .map(|outpoint| {
+                    let proof = utxos
+                        .get(outpoint)
+                        .ok_or_else(|| eyre!("Output {} is not owned by the wallet", outpoint))?;
+
+                    Ok((*outpoint, proof))
+                })
+                .collect::<eyre::Result<Vec<_>>>()?,
+            None => utxos
+                .iter()
+                .filter(|(_, proof)| proof.pixel().chroma == chroma)
+                .map(|(outpoint, proof)| (*outpoint, proof))
+                .collect(),
+        };
+        selected.sort_by_key(|(outpoint, _)| *outpoint);
+
+        let mut outputs = Vec::with_capacity(selected.len());
+        for (outpoint, proof) in selected {
+            let PixelProof::Sig(proof) = proof else {
+                bail!("Output {} is not a single signature one", outpoint);
+            };
+            ensure!(
+                proof.pixel.chroma == chroma,
+                "Output {} has tokens of another chroma",
+                outpoint
+            );
+
+            outputs.push(ReserveOutput::sign(
+                &self.secp_ctx,
+                &challenge,
+                outpoint,
+                proof.pixel,
+                &signer_key.inner,
+            )?);
+        }
+
+        Ok(ProofOfReserves {
+            chroma,
+            challenge,
+            outputs,
+        })
+    }
+
     /// Return [`YuvTxType::Transfer`] transaction builder for creating
     /// transaction by YUV protocol.
     ///
@@ -513,7 +887,7 @@ where
     pub fn build_transfer(&self) -> eyre::Result<TransferTransactionBuilder<YTDB, BTDB>> {
         // Even though the probability of obtaining the key corresponding to the zero chroma is
         // miserably low, it is prohibited to send transfer transactions from the burn wallet.
-        let pubkey = self.signer_key.public_key(&self.secp_ctx);
+        let pubkey = self.pubkey;
         if pubkey == *ZERO_PUBLIC_KEY {
             bail!(
                 "Cannot transfer from the burn wallet, pubkey={}",
@@ -521,7 +895,10 @@ where
             );
         }
 
-        TransferTransactionBuilder::try_from(self)
+        let mut builder = TransferTransactionBuilder::try_from(self)?;
+        builder.set_frozen_outpoints_provider(YuvNodeFrozenOutpoints::new(self.yuv_client.clone()));
+
+        Ok(builder)
     }
 
     /// Return [`YuvTxType::Issue`] transaction builder for creating
@@ -541,6 +918,223 @@ where
         SweepTransactionBuilder::try_from(self)
     }
 
+    /// Sign the inputs of the wallet in the swap merged by the [`SwapTransactionBuilder`].
+    ///
+    /// The inputs of the counterparty must be known from the synced YUV transactions with the
+    /// same proofs, otherwise the swap could be rejected by the YUV nodes after its Bitcoin
+    /// transaction is mined. The amounts received by the wallet should be checked with
+    /// [`SwapPsbt::received_amount`] before signing.
+    ///
+    /// [`SwapTransactionBuilder`]: crate::txbuilder::SwapTransactionBuilder
+    pub async fn sign_swap(&self, swap: &mut SwapPsbt) -> eyre::Result<()> {
+        let signer_key = self.signer_key()?;
+        let (self_x_only_pubkey, _parity) = self.pubkey.inner.x_only_public_key();
+
+        let mut own_input_proofs = ProofMap::new();
+        for (index, proof) in swap.input_proofs()? {
+            let inner_key = match &proof {
+                PixelProof::Sig(proof) => proof.inner_key,
+                PixelProof::EmptyPixel(proof) => proof.inner_key,
+                _ => bail!("Input {} of the swap is not a single signature one", index),
+            };
+
+            if inner_key.x_only_public_key().0 == self_x_only_pubkey {
+                own_input_proofs.insert(index, proof);
+                continue;
+            }
+
+            let outpoint = swap.psbt.unsigned_tx.input[index as usize].previous_output;
+            let (synced_proof, output) = get_output_from_storage(&self.yuv_txs_storage, outpoint)
+                .await
+                .wrap_err_with(|| format!("Input {} of the swap is unknown", outpoint))?;
+
+            ensure!(
+                synced_proof == proof
+                    && swap.psbt.inputs[index as usize].witness_utxo.as_ref() == Some(&output),
+                "Input {} of the swap doesn't match the synced one",
+                outpoint
+            );
+        }
+
+        self.bitcoin_wallet.read().unwrap().sign(
+            &mut swap.psbt,
+            SignOptions {
+                try_finalize: true,
+                trust_witness_utxo: true,
+                ..Default::default()
+            },
+        )?;
+
+        let mut signer = PrivateKeySigner::new(self.secp_ctx.clone(), self.network);
+        signer.extend_signers(HashMap::from([(self_x_only_pubkey, signer_key.inner)]));
+        signer.sign(&mut swap.psbt, &own_input_proofs)?;
+
+        Ok(())
+    }
+
+    /// Return the transfer builder of the transaction that replaces the unconfirmed transfer
+    /// `txid` with a higher fee (RBF).
+    ///
+    /// The replacement spends the same YUV inputs and recreates the pixel outputs in the same
+    /// order, and the proofs are generated for the new transaction. The Bitcoin inputs and the
+    /// change are selected again with the fee rate set on the builder, which must be higher than
+    /// the fee rate of the replaced transaction.
+    pub async fn build_yuv_fee_bump(
+        &self,
+        txid: Txid,
+    ) -> eyre::Result<TransferTransactionBuilder<YTDB, BTDB>> {
+        let yuv_tx = self.find_yuv_tx(txid).await?;
+        let YuvTxType::Transfer {
+            input_proofs,
+            output_proofs,
+        } = &yuv_tx.tx_type
+        else {
+            bail!("Only transfers can be bumped, {} is not a transfer", txid);
+        };
+
+        let change_key = self.change_key();
+
+        let mut builder = self.build_transfer()?;
+        builder.manual_selected_only();
+
+        for (index, proof) in input_proofs {
+            let input = yuv_tx
+                .bitcoin_tx
+                .input
+                .get(*index as usize)
+                .ok_or_else(|| eyre!("Input {} of {} is missing", index, txid))?;
+
+            match proof {
+                PixelProof::Sig(_) | PixelProof::EmptyPixel(_) => {
+                    builder.add_pixel_input(input.previous_output);
+                }
+                _ => bail!(
+                    "Input {} of {} can't be bumped, only single signature inputs are supported",
+                    index,
+                    txid
+                ),
+            }
+        }
+
+        for (vout, proof) in output_proofs {
+            let output = yuv_tx
+                .bitcoin_tx
+                .output
+                .get(*vout as usize)
+                .ok_or_else(|| eyre!("Output {} of {} is missing", vout, txid))?;
+
+            match proof {
+                PixelProof::Sig(sig) => builder.add_recipient(
+                    sig.pixel.chroma,
+                    &sig.inner_key,
+                    sig.pixel.luma.amount,
+                    output.value,
+                ),
+                // The Bitcoin change is added by the builder again.
+                PixelProof::EmptyPixel(empty) if empty.inner_key == change_key => continue,
+                PixelProof::EmptyPixel(empty) => {
+                    builder.add_sats_recipient(&empty.inner_key, output.value)
+                }
+                PixelProof::Multisig(multisig) => builder.add_multisig_recipient(
+                    multisig.inner_keys.clone(),
+                    multisig.m,
+                    multisig.pixel.luma.amount,
+                    multisig.pixel.chroma,
+                    output.value,
+                ),
+                PixelProof::Musig(musig) => builder.add_musig_recipient(
+                    musig.inner_keys.clone(),
+                    musig.pixel.luma.amount,
+                    musig.pixel.chroma,
+                    output.value,
+                ),
+                PixelProof::Timelock(timelock) => builder.add_timelocked_recipient(
+                    timelock.pixel.chroma,
+                    &timelock.inner_key,
+                    timelock.pixel.luma.amount,
+                    output.value,
+                    timelock.lock_height,
+                ),
+                PixelProof::Htlc(htlc) => builder.add_htlc_recipient(
+                    htlc.pixel.chroma,
+                    &htlc.data.recipient_pubkey,
+                    htlc.pixel.luma.amount,
+                    output.value,
+                    htlc.data.payment_hash,
+                    htlc.data.lock_height,
+                ),
+                _ => bail!(
+                    "Output {} of {} can't be bumped, its proof type is not supported",
+                    vout,
+                    txid
+                ),
+            };
+        }
+
+        Ok(builder)
+    }
+
+    /// Return the transfer builder of the child transaction that spends the Bitcoin change of the
+    /// unconfirmed transaction `txid` (CPFP), so the miners get the fees of both transactions
+    /// only if they confirm the parent.
+    ///
+    /// The fee rate set on the builder must be high enough to raise the average fee rate of the
+    /// parent and the child.
+    pub async fn build_yuv_cpfp(
+        &self,
+        txid: Txid,
+    ) -> eyre::Result<TransferTransactionBuilder<YTDB, BTDB>> {
+        let yuv_tx = self.find_yuv_tx(txid).await?;
+        let change_key = self.change_key();
+
+        let change_vout = yuv_tx
+            .tx_type
+            .output_proofs()
+            .and_then(|proofs| {
+                proofs.iter().rev().find_map(|(vout, proof)| match proof {
+                    PixelProof::EmptyPixel(empty) if empty.inner_key == change_key => Some(*vout),
+                    _ => None,
+                })
+            })
+            .ok_or_else(|| eyre!("Transaction {} has no change output to spend", txid))?;
+
+        let change_value = yuv_tx
+            .bitcoin_tx
+            .output
+            .get(change_vout as usize)
+            .ok_or_else(|| eyre!("Output {} of {} is missing", change_vout, txid))?
+            .value;
+
+        // The parent isn't attached by the node until it's confirmed, so the proof of the change
+        // is taken from the local storage.
+        self.yuv_txs_storage.put_yuv_tx(yuv_tx).await?;
+
+        let mut builder = self.build_transfer()?;
+        builder.manual_selected_only();
+        builder
+            .add_pixel_input(OutPoint::new(txid, change_vout))
+            .add_sats_recipient(&change_key, change_value);
+
+        Ok(builder)
+    }
+
+    /// Find the transaction among the pending submissions or the synced transactions.
+    async fn find_yuv_tx(&self, txid: Txid) -> eyre::Result<YuvTransaction> {
+        if let Some(submission) = self.yuv_txs_storage.get_submission(&txid).await? {
+            return Ok(submission.tx);
+        }
+
+        self.yuv_txs_storage
+            .get_yuv_tx(&txid)
+            .await?
+            .ok_or_else(|| eyre!("Transaction {} is not found", txid))
+    }
+
+    /// Key of the empty pixel outputs the Bitcoin change of the built transactions is sent to.
+    pub fn change_key(&self) -> secp256k1::PublicKey {
+        self.public_key().even_public_key(&self.secp_ctx)
+    }
+
     /// Create funding lightning transaction from:
     ///
     /// * `funding_pixel` - chroma and amount that will be in Lightning Network
@@ -570,26 +1164,40 @@ where
     ) -> eyre::Result<YuvTransaction> {
         let fee_rate_strategy = fee_rate_strategy.unwrap_or(DEFAULT_FEE_RATE_STRATEGY);
 
-        let mut tx_builder = self
-            .build_transfer()
+        let mut builder = self
+            .build_lightning_funding(funding_pixel, holder_pubkey, counterparty_pubkey)
             .wrap_err("failed to init transaction builder")?;
 
-        tx_builder
-            .add_multisig_recipient(
-                vec![holder_pubkey, counterparty_pubkey],
-                2,
-                funding_pixel.luma.amount,
-                funding_pixel.chroma,
-                satoshis,
-            )
+        builder
+            .set_funding_satoshis(satoshis)
             .set_fee_rate_strategy(fee_rate_strategy);
 
-        let yuv_transaction = tx_builder
+        let funding = builder
             .finish(&self.bitcoin_provider.blockchain())
             .await
             .wrap_err("failed to build yuv transaction")?;
 
-        Ok(yuv_transaction)
+        Ok(funding.tx)
+    }
+
+    /// Return the builder of the Lightning channel's funding transaction with the
+    /// `funding_pixel` locked by the 2-of-2 multisig of the `holder_pubkey` and the
+    /// `counterparty_pubkey`. The built [`LightningFunding`] produces the templates of the
+    /// proofs of the channel's commitment transactions.
+    ///
+    /// [`LightningFunding`]: crate::txbuilder::LightningFunding
+    pub fn build_lightning_funding(
+        &self,
+        funding_pixel: Pixel,
+        holder_pubkey: secp256k1::PublicKey,
+        counterparty_pubkey: secp256k1::PublicKey,
+    ) -> eyre::Result<LightningFundingBuilder<YTDB, BTDB>> {
+        Ok(LightningFundingBuilder::new(
+            self.build_transfer()?,
+            funding_pixel,
+            holder_pubkey,
+            counterparty_pubkey,
+        ))
     }
 
     /// Create a simple transfer to given recipient with given pixel.
@@ -605,8 +1213,9 @@ where
             .build_transfer()
             .wrap_err("failed to init transaction builder")?;
 
+        let satoshis = DustPolicy::for_network(self.network.into()).default_satoshis;
         tx_builder
-            .add_recipient(pixel.chroma, &recipient, pixel.luma.amount, 1000)
+            .add_recipient(pixel.chroma, &recipient, pixel.luma.amount, satoshis)
             .set_fee_rate_strategy(fee_rate_strategy);
 
         let yuv_tx = tx_builder
@@ -617,6 +1226,74 @@ where
         Ok(yuv_tx)
     }
 
+    /// Submit the YUV transaction to the node and the Bitcoin network, so that the transaction
+    /// is never broadcasted without its proofs provided to the node. See [`submission`] for the
+    /// details.
+    ///
+    /// The submission is persisted until it's completed. If the submission fails, it can be
+    /// continued later with [`Wallet::resume_submissions`].
+    ///
+    /// [`submission`]: crate::submission
+    pub async fn submit(
+        &self,
+        tx: YuvTransaction,
+        options: SubmitOptions,
+    ) -> eyre::Result<Submission> {
+        let mut submission = Submission::new(tx, options.max_burn_amount);
+        self.yuv_txs_storage.put_submission(&submission).await?;
+
+        continue_submission(
+            &self.submission_client(),
+            &self.yuv_txs_storage,
+            &mut submission,
+            &options,
+        )
+        .await
+        .wrap_err_with(|| format!("failed to submit transaction {}", submission.txid()))?;
+
+        Ok(submission)
+    }
+
+    /// Continue the persisted submissions, which were not finished. Returns the submissions
+    /// with their updated states.
+    pub async fn resume_submissions(
+        &self,
+        options: SubmitOptions,
+    ) -> eyre::Result<Vec<Submission>> {
+        let submissions = self.yuv_txs_storage.get_pending_submissions().await?;
+
+        let client = self.submission_client();
+        let mut resumed = Vec::with_capacity(submissions.len());
+        for mut submission in submissions {
+            let result =
+                continue_submission(&client, &self.yuv_txs_storage, &mut submission, &options)
+                    .await;
+            if let Err(err) = result {
+                tracing::warn!(
+                    "Failed to resume the submission of {}: {:#}",
+                    submission.txid(),
+                    err
+                );
+            }
+
+            resumed.push(submission);
+        }
+
+        Ok(resumed)
+    }
+
+    /// Get the submissions, which are not finished yet.
+    pub async fn pending_submissions(&self) -> eyre::Result<Vec<Submission>> {
+        self.yuv_txs_storage.get_pending_submissions().await
+    }
+
+    fn submission_client(&self) -> WalletSubmissionClient<'_, YC, BP> {
+        WalletSubmissionClient {
+            yuv_client: &self.yuv_client,
+            bitcoin_provider: &self.bitcoin_provider,
+        }
+    }
+
     /// Create YUV [`Announcement`] transaction for given [`Announcement`].
     pub fn create_announcement_tx(
         &self,
@@ -624,6 +1301,11 @@ where
         fee_rate_strategy: FeeRateStrategy,
         blockchain: &impl Blockchain,
     ) -> eyre::Result<YuvTransaction> {
+        ensure!(
+            !self.is_watch_only(),
+            "The watch-only wallet can't sign the announcement"
+        );
+
         let tx = {
             let wallet = self.bitcoin_wallet.read().unwrap();
             let mut builder = wallet.build_tx();
@@ -1,7 +1,12 @@
+use std::collections::HashSet;
+
 use crate::types::{Utxo, WeightedUtxo};
 use bdk::Error;
-use bitcoin::Script;
+use bitcoin::{OutPoint, Script};
+use eyre::bail;
+use jsonrpsee::core::async_trait;
 use yuv_pixels::Chroma;
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
 
 /// Default coin selection algorithm used by transaction buileder if not
 /// overridden
@@ -135,6 +140,101 @@ impl YUVCoinSelectionAlgorithm for YUVOldestFirstCoinSelection {
     }
 }
 
+/// Provider of the frozen YUV outpoints. The node rejects the transactions that spend them, so
+/// they must not be selected as inputs.
+#[async_trait]
+pub trait FrozenOutpointsProvider: Send + Sync {
+    /// Returns the frozen outpoints among the given ones.
+    async fn frozen_outpoints(&self, outpoints: &[OutPoint]) -> eyre::Result<HashSet<OutPoint>>;
+}
+
+/// Fixed set of the frozen outpoints, e.g. collected from the freeze announcements.
+#[async_trait]
+impl FrozenOutpointsProvider for HashSet<OutPoint> {
+    async fn frozen_outpoints(&self, outpoints: &[OutPoint]) -> eyre::Result<HashSet<OutPoint>> {
+        Ok(outpoints
+            .iter()
+            .filter(|outpoint| self.contains(outpoint))
+            .copied()
+            .collect())
+    }
+}
+
+/// [`FrozenOutpointsProvider`] that asks the YUV node whether the outpoints are frozen.
+#[derive(Debug, Clone)]
+pub struct YuvNodeFrozenOutpoints<YC>(YC);
+
+impl<YC> YuvNodeFrozenOutpoints<YC> {
+    pub fn new(yuv_client: YC) -> Self {
+        Self(yuv_client)
+    }
+}
+
+#[async_trait]
+impl<YC> FrozenOutpointsProvider for YuvNodeFrozenOutpoints<YC>
+where
+    YC: YuvTransactionsRpcClient + Send + Sync,
+{
+    async fn frozen_outpoints(&self, outpoints: &[OutPoint]) -> eyre::Result<HashSet<OutPoint>> {
+        let mut frozen = HashSet::new();
+
+        for outpoint in outpoints {
+            if self
+                .0
+                .is_yuv_txout_frozen(outpoint.txid, outpoint.vout)
+                .await?
+            {
+                frozen.insert(*outpoint);
+            }
+        }
+
+        Ok(frozen)
+    }
+}
+
+/// Exclude the frozen UTXOs from the `optional_utxos` before the coin selection, and return the
+/// amount of the excluded tokens.
+///
+/// Fails if any of the `required_utxos` is frozen, as the transaction spending it would be
+/// rejected anyway.
+pub async fn exclude_frozen_utxos(
+    provider: &dyn FrozenOutpointsProvider,
+    required_utxos: &[WeightedUtxo],
+    optional_utxos: &mut Vec<WeightedUtxo>,
+) -> eyre::Result<u128> {
+    let outpoints = required_utxos
+        .iter()
+        .chain(optional_utxos.iter())
+        .map(|wu| wu.utxo.outpoint())
+        .collect::<Vec<_>>();
+
+    let frozen = provider.frozen_outpoints(&outpoints).await?;
+    if frozen.is_empty() {
+        return Ok(0);
+    }
+
+    if let Some(wu) = required_utxos
+        .iter()
+        .find(|wu| frozen.contains(&wu.utxo.outpoint()))
+    {
+        bail!("Input {} is frozen and can't be spent", wu.utxo.outpoint());
+    }
+
+    let mut frozen_amount = 0u128;
+    optional_utxos.retain(|wu| {
+        if !frozen.contains(&wu.utxo.outpoint()) {
+            return true;
+        }
+
+        tracing::debug!(outpoint = %wu.utxo.outpoint(), "Skipping frozen UTXO");
+        frozen_amount = frozen_amount.saturating_add(wu.utxo.yuv_txout().pixel.luma.amount);
+
+        false
+    });
+
+    Ok(frozen_amount)
+}
+
 fn select_sorted_utxos(
     utxos: impl Iterator<Item = (bool, WeightedUtxo)>,
     target_amount: u128,
@@ -259,4 +359,28 @@ mod test {
         assert_eq!(result.selected.len(), 3);
         assert_eq!(result.selected_amount(), 790_000);
     }
+
+    #[tokio::test]
+    async fn test_frozen_utxos_are_excluded() {
+        let mut utxos = get_test_utxos();
+        let frozen = HashSet::from([utxos[0].utxo.outpoint()]);
+
+        let frozen_amount = exclude_frozen_utxos(&frozen, &[], &mut utxos)
+            .await
+            .unwrap();
+
+        assert_eq!(frozen_amount, 500_000);
+        assert_eq!(utxos.len(), 2);
+        assert!(utxos.iter().all(|wu| !frozen.contains(&wu.utxo.outpoint())));
+    }
+
+    #[tokio::test]
+    async fn test_frozen_required_utxo_fails() {
+        let required_utxos = get_test_utxos();
+        let frozen = HashSet::from([required_utxos[1].utxo.outpoint()]);
+
+        let result = exclude_frozen_utxos(&frozen, &required_utxos, &mut Vec::new()).await;
+
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,518 @@
+//! PSBT of a YUV transaction for external signers.
+//!
+//! YUV inputs are locked by the keys tweaked with the pixels, so a signer needs the pixel proof of
+//! each input to derive the signing key. The proofs are put to the proprietary fields of the PSBT
+//! inputs (prefix [`PSBT_YUV_PREFIX`], subtype [`PSBT_IN_YUV_PIXEL_PROOF`], consensus encoded
+//! [`PixelProof`] as a value).
+//!
+//! The signer only adds the signatures of the tweaked keys to the `partial_sigs` of the inputs,
+//! and the witnesses are formed by [`YuvPsbt::finalize`]. The PSBTs built by the watch-only wallet
+//! have the Bitcoin inputs unsigned as well, which are signed by the same signer and finalized in
+//! the same way. A hardware wallet (e.g. through HWI)
+//! can sign the YUV inputs only if its firmware applies the pixel tweak from the proprietary field.
+//!
+//! The 2-of-2 MuSig2 inputs are signed by the participants with [`MusigSigner`] instead, which
+//! puts the aggregated signature to the `tap_key_sig` of the input.
+//!
+//! [`MusigSigner`]: crate::txbuilder::MusigSigner
+use bitcoin::{
+    consensus::{deserialize, serialize},
+    psbt::{raw::ProprietaryKey, PartiallySignedTransaction},
+    ScriptBuf,
+};
+use eyre::{bail, eyre, Context};
+use yuv_pixels::{
+    MultisigPixelProof, MultisigScript, MultisigWitness, MusigWitness, P2WPKHWitness, PixelProof,
+};
+use yuv_types::{ProofMap, YuvTransaction, YuvTxType};
+
+use crate::{txbuilder::check_tx_size, txsigner::TransactionSigner};
+
+/// Prefix of the proprietary PSBT fields with the YUV metadata.
+pub const PSBT_YUV_PREFIX: &[u8] = b"yuv";
+
+/// Subtype of the proprietary PSBT input field with the [`PixelProof`] of the input.
+pub const PSBT_IN_YUV_PIXEL_PROOF: u8 = 0x00;
+
+/// Subtype of the proprietary PSBT output field with the [`PixelProof`] of the output.
+pub const PSBT_OUT_YUV_PIXEL_PROOF: u8 = 0x00;
+
+/// Subtype of the proprietary PSBT global field with the consensus encoded [`YuvTxType`] of the
+/// transaction.
+pub const PSBT_GLOBAL_YUV_TX_TYPE: u8 = 0x00;
+
+/// Partially signed YUV transaction: the Bitcoin inputs are signed by the wallet, and the YUV
+/// inputs are waiting for the signatures of the external signer.
+#[derive(Clone, Debug)]
+pub struct YuvPsbt {
+    /// PSBT with the pixel proofs of the YUV inputs in the proprietary fields.
+    pub psbt: PartiallySignedTransaction,
+    pub tx_type: YuvTxType,
+    /// Maximum size (in bytes) of the finalized transaction serialized to JSON, set by the
+    /// builder.
+    pub(crate) max_request_size: Option<usize>,
+}
+
+impl YuvPsbt {
+    pub(crate) fn new(
+        mut psbt: PartiallySignedTransaction,
+        tx_type: YuvTxType,
+        input_proofs: &ProofMap,
+    ) -> eyre::Result<Self> {
+        for (index, proof) in input_proofs {
+            let input = psbt
+                .inputs
+                .get_mut(*index as usize)
+                .ok_or_else(|| eyre!("Input {} is not found in the PSBT", index))?;
+
+            input
+                .proprietary
+                .insert(pixel_proof_key(), serialize(proof));
+        }
+
+        Ok(Self {
+            psbt,
+            tx_type,
+            max_request_size: None,
+        })
+    }
+
+    /// Get the pixel proofs of the YUV inputs from the proprietary fields of the PSBT.
+    pub fn input_proofs(&self) -> eyre::Result<ProofMap> {
+        let key = pixel_proof_key();
+        let mut input_proofs = ProofMap::new();
+
+        for (index, input) in self.psbt.inputs.iter().enumerate() {
+            let Some(value) = input.proprietary.get(&key) else {
+                continue;
+            };
+
+            let proof: PixelProof = deserialize(value)
+                .wrap_err_with(|| format!("Invalid pixel proof of the input {}", index))?;
+
+            input_proofs.insert(index as u32, proof);
+        }
+
+        Ok(input_proofs)
+    }
+
+    /// Sign the YUV inputs with the `signer`.
+    pub fn sign(&mut self, signer: &impl TransactionSigner) -> eyre::Result<()> {
+        let input_proofs = self.input_proofs()?;
+
+        signer.sign(&mut self.psbt, &input_proofs)
+    }
+
+    /// Merge the signatures from the PSBT signed by the external signer.
+    pub fn combine(&mut self, signed: PartiallySignedTransaction) -> eyre::Result<()> {
+        self.psbt
+            .combine(signed)
+            .wrap_err("Signed PSBT doesn't match the YUV transaction")
+    }
+
+    /// Form the witnesses of the YUV inputs from the signatures, and extract the signed
+    /// YUV transaction. Fails if the transaction exceeds the request size limit set on the
+    /// builder.
+    pub fn finalize(mut self) -> eyre::Result<YuvTransaction> {
+        for (index, proof) in self.input_proofs()? {
+            let input = &mut self.psbt.inputs[index as usize];
+
+            let witness = match &proof {
+                PixelProof::Sig(_) | PixelProof::EmptyPixel(_) => finalize_p2wpkh(input)?,
+                #[cfg(feature = "bulletproof")]
+                PixelProof::Bulletproof(_) => finalize_p2wpkh(input)?,
+                PixelProof::Multisig(multisig_proof) => finalize_multisig(input, multisig_proof)?,
+                PixelProof::Musig(_) => finalize_musig(input)?,
+                PixelProof::LightningHtlc(_)
+                | PixelProof::Lightning(_)
+                | PixelProof::P2WSH(_)
+                | PixelProof::Timelock(_)
+                | PixelProof::Htlc(_) => {
+                    bail!("Finalizing input {} of this type is not supported", index)
+                }
+            };
+
+            input.final_script_witness = Some(witness);
+            input.final_script_sig = Some(ScriptBuf::new());
+        }
+
+        // Bitcoin inputs signed by the external signer, e.g. for the watch-only wallet.
+        for input in &mut self.psbt.inputs {
+            if input.final_script_witness.is_some() || input.partial_sigs.is_empty() {
+                continue;
+            }
+
+            input.final_script_witness = Some(finalize_p2wpkh(input)?);
+            input.final_script_sig = Some(ScriptBuf::new());
+        }
+
+        if let Some(index) = self
+            .psbt
+            .inputs
+            .iter()
+            .position(|input| input.final_script_witness.is_none())
+        {
+            bail!("Input {} is not signed", index);
+        }
+
+        let yuv_tx = YuvTransaction {
+            bitcoin_tx: self.psbt.extract_tx(),
+            tx_type: self.tx_type,
+        };
+
+        check_tx_size(&yuv_tx, self.max_request_size)?;
+
+        Ok(yuv_tx)
+    }
+}
+
+/// Key of the proprietary PSBT input field with the pixel proof.
+pub fn pixel_proof_key() -> ProprietaryKey {
+    ProprietaryKey {
+        prefix: PSBT_YUV_PREFIX.to_vec(),
+        subtype: PSBT_IN_YUV_PIXEL_PROOF,
+        key: Vec::new(),
+    }
+}
+
+/// Key of the proprietary PSBT output field with the pixel proof.
+pub fn output_pixel_proof_key() -> ProprietaryKey {
+    ProprietaryKey {
+        prefix: PSBT_YUV_PREFIX.to_vec(),
+        subtype: PSBT_OUT_YUV_PIXEL_PROOF,
+        key: Vec::new(),
+    }
+}
+
+/// Key of the proprietary PSBT global field with the proofs of the transaction.
+pub fn tx_type_key() -> ProprietaryKey {
+    ProprietaryKey {
+        prefix: PSBT_YUV_PREFIX.to_vec(),
+        subtype: PSBT_GLOBAL_YUV_TX_TYPE,
+        key: Vec::new(),
+    }
+}
+
+/// Form the witness from the signature of the key the output is locked by.
+fn finalize_p2wpkh(input: &bitcoin::psbt::Input) -> eyre::Result<bitcoin::Witness> {
+    let script_pubkey = &input
+        .witness_utxo
+        .as_ref()
+        .ok_or_else(|| eyre!("Witness UTXO is missing"))?
+        .script_pubkey;
+
+    let (pubkey, signature) = input
+        .partial_sigs
+        .iter()
+        .find(|(pubkey, _)| {
+            pubkey
+                .wpubkey_hash()
+                .is_some_and(|hash| ScriptBuf::new_v0_p2wpkh(&hash) == *script_pubkey)
+        })
+        .ok_or_else(|| eyre!("Signature of the tweaked key is missing"))?;
+
+    Ok(P2WPKHWitness::new(*signature, pubkey.inner).into())
+}
+
+/// Form the witness from the signatures of the keys of the redeem script, in the order of the keys
+/// in the script, as `OP_CHECKMULTISIG` requires. The signatures of other keys are ignored.
+pub(crate) fn finalize_multisig(
+    input: &bitcoin::psbt::Input,
+    multisig_proof: &MultisigPixelProof,
+) -> eyre::Result<bitcoin::Witness> {
+    let redeem_script = multisig_proof.to_reedem_script()?;
+    let script = MultisigScript::from_script(&redeem_script)?;
+
+    let signatures = script
+        .pubkeys
+        .iter()
+        .filter_map(|pubkey| input.partial_sigs.get(&bitcoin::PublicKey::new(*pubkey)))
+        .take(multisig_proof.m as usize)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if signatures.len() < multisig_proof.m as usize {
+        bail!(
+            "Not enough signatures for multisig pixel: {} < {}",
+            signatures.len(),
+            multisig_proof.m
+        );
+    }
+
+    let witness = MultisigWitness::new(signatures, redeem_script);
+
+    Ok(witness.into_witness())
+}
+
+/// Form the key path witness from the signature aggregated by the participants.
+fn finalize_musig(input: &bitcoin::psbt::Input) -> eyre::Result<bitcoin::Witness> {
+    let signature = input
+        .tap_key_sig
+        .ok_or_else(|| eyre!("Aggregated MuSig2 signature is missing"))?;
+
+    Ok(MusigWitness::new(signature).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::{
+        signer::{InputSigner, SignerContext, SignerWrapper},
+        SignOptions,
+    };
+    use std::collections::HashMap;
+
+    use bitcoin::{
+        absolute::LockTime,
+        ecdsa,
+        secp256k1::{Message, Secp256k1, SecretKey},
+        Network, OutPoint, PrivateKey, Sequence, Transaction, TxIn, TxOut, Witness,
+    };
+    use yuv_pixels::{Pixel, PixelKey, PixelPrivateKey, SigPixelProof};
+
+    use super::*;
+    use crate::txsigner::PrivateKeySigner;
+
+    /// Signs the YUV inputs with the single key, as an external signer would do.
+    struct KeySigner(SecretKey);
+
+    impl TransactionSigner for KeySigner {
+        fn sign(
+            &self,
+            psbt: &mut PartiallySignedTransaction,
+            input_proofs: &ProofMap,
+        ) -> eyre::Result<()> {
+            let ctx = Secp256k1::new();
+
+            for (index, proof) in input_proofs {
+                let PixelProof::Sig(proof) = proof else {
+                    bail!("Only sig inputs are supported");
+                };
+
+                let tweaked_key = PixelPrivateKey::new_with_ctx(proof.pixel, &self.0, &ctx)?;
+                let signer = SignerWrapper::new(
+                    PrivateKey::new(tweaked_key.0, Network::Regtest),
+                    SignerContext::Segwitv0,
+                );
+
+                signer.sign_input(
+                    psbt,
+                    *index as usize,
+                    &SignOptions {
+                        try_finalize: false,
+                        trust_witness_utxo: true,
+                        ..Default::default()
+                    },
+                    &ctx,
+                )?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn yuv_psbt(secret_key: &SecretKey) -> YuvPsbt {
+        let ctx = Secp256k1::new();
+        let inner_key = secret_key.public_key(&ctx);
+        let pixel = Pixel::new(100, inner_key.x_only_public_key().0);
+        let pixel_key = PixelKey::new(pixel, &inner_key).unwrap();
+
+        let script_pubkey =
+            ScriptBuf::new_v0_p2wpkh(&bitcoin::PublicKey::new(*pixel_key).wpubkey_hash().unwrap());
+
+        let unsigned_tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: 900,
+                script_pubkey: script_pubkey.clone(),
+            }],
+        };
+
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: 1000,
+            script_pubkey,
+        });
+
+        let input_proofs =
+            ProofMap::from([(0, PixelProof::Sig(SigPixelProof::new(pixel, inner_key)))]);
+
+        YuvPsbt::new(psbt, YuvTxType::default(), &input_proofs).unwrap()
+    }
+
+    #[test]
+    fn test_input_proofs_are_stored_in_proprietary_fields() {
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let yuv_psbt = yuv_psbt(&secret_key);
+
+        let input_proofs = yuv_psbt.input_proofs().unwrap();
+
+        assert_eq!(input_proofs.len(), 1);
+        assert!(matches!(input_proofs.get(&0), Some(PixelProof::Sig(_))));
+    }
+
+    #[test]
+    fn test_finalize_with_external_signatures() {
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let mut yuv_psbt = yuv_psbt(&secret_key);
+
+        assert!(
+            yuv_psbt.clone().finalize().is_err(),
+            "unsigned input should not be finalized"
+        );
+
+        // Sign the copy of the PSBT, as it would be done by the external signer, and get the
+        // signatures back.
+        let mut signed = yuv_psbt.clone();
+        signed.sign(&KeySigner(secret_key)).unwrap();
+        yuv_psbt.combine(signed.psbt).unwrap();
+
+        let yuv_tx = yuv_psbt.finalize().unwrap();
+
+        assert_eq!(yuv_tx.bitcoin_tx.input[0].witness.len(), 2);
+    }
+
+    #[test]
+    fn test_finalize_externally_signed_bitcoin_inputs() {
+        let ctx = Secp256k1::new();
+        let private_key =
+            PrivateKey::new(SecretKey::from_slice(&[2; 32]).unwrap(), Network::Regtest);
+        let script_pubkey =
+            ScriptBuf::new_v0_p2wpkh(&private_key.public_key(&ctx).wpubkey_hash().unwrap());
+
+        let unsigned_tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: 900,
+                script_pubkey: script_pubkey.clone(),
+            }],
+        };
+
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: 1000,
+            script_pubkey,
+        });
+
+        // The Bitcoin input isn't signed by the watch-only wallet.
+        let mut yuv_psbt = YuvPsbt::new(psbt, YuvTxType::default(), &ProofMap::new()).unwrap();
+        assert!(yuv_psbt.clone().finalize().is_err());
+
+        SignerWrapper::new(private_key, SignerContext::Segwitv0)
+            .sign_input(
+                &mut yuv_psbt.psbt,
+                0,
+                &SignOptions {
+                    try_finalize: false,
+                    trust_witness_utxo: true,
+                    ..Default::default()
+                },
+                &ctx,
+            )
+            .unwrap();
+
+        let yuv_tx = yuv_psbt.finalize().unwrap();
+
+        assert_eq!(yuv_tx.bitcoin_tx.input[0].witness.len(), 2);
+    }
+
+    #[test]
+    fn test_sign_with_private_key_signer() {
+        let ctx = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let mut yuv_psbt = yuv_psbt(&secret_key);
+
+        let mut signer = PrivateKeySigner::new(ctx.clone(), Network::Regtest);
+        signer.extend_signers(HashMap::from([(
+            secret_key.x_only_public_key(&ctx).0,
+            secret_key,
+        )]));
+        yuv_psbt.sign(&signer).unwrap();
+
+        let yuv_tx = yuv_psbt.finalize().unwrap();
+
+        assert_eq!(yuv_tx.bitcoin_tx.input[0].witness.len(), 2);
+    }
+
+    #[test]
+    fn test_finalize_checks_tx_size() {
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let mut yuv_psbt = yuv_psbt(&secret_key);
+        yuv_psbt.sign(&KeySigner(secret_key)).unwrap();
+
+        let mut limited = yuv_psbt.clone();
+        limited.max_request_size = Some(1);
+        assert!(
+            limited.finalize().is_err(),
+            "transaction larger than the limit should not be finalized"
+        );
+
+        yuv_psbt.max_request_size = Some(usize::MAX);
+        assert!(yuv_psbt.finalize().is_ok());
+    }
+
+    #[test]
+    fn test_finalize_multisig_orders_signatures_by_script_keys() {
+        let ctx = Secp256k1::new();
+        let inner_keys = [1, 2, 3]
+            .map(|byte| SecretKey::from_slice(&[byte; 32]).unwrap().public_key(&ctx))
+            .to_vec();
+        let pixel = Pixel::new(100, inner_keys[0].x_only_public_key().0);
+        let multisig_proof = MultisigPixelProof::new(pixel, inner_keys, 2);
+
+        let script_keys = MultisigScript::from_script(&multisig_proof.to_reedem_script().unwrap())
+            .unwrap()
+            .pubkeys;
+
+        let signature = |byte: u8| {
+            let secret_key = SecretKey::from_slice(&[byte; 32]).unwrap();
+            let message = Message::from_slice(&[byte; 32]).unwrap();
+
+            ecdsa::Signature::sighash_all(ctx.sign_ecdsa(&message, &secret_key))
+        };
+        let foreign_key = SecretKey::from_slice(&[4; 32]).unwrap().public_key(&ctx);
+
+        // Signatures of the last and the first keys of the script, and of the key that isn't in
+        // the script.
+        let mut input = bitcoin::psbt::Input::default();
+        for (pubkey, signature) in [
+            (script_keys[2], signature(12)),
+            (foreign_key, signature(14)),
+            (script_keys[0], signature(10)),
+        ] {
+            input
+                .partial_sigs
+                .insert(bitcoin::PublicKey::new(pubkey), signature);
+        }
+
+        let witness = finalize_multisig(&input, &multisig_proof).unwrap();
+
+        assert_eq!(
+            witness.iter().collect::<Vec<_>>(),
+            vec![
+                &signature(10).serialize()[..],
+                &signature(12).serialize()[..],
+                multisig_proof.to_reedem_script().unwrap().as_bytes(),
+            ]
+        );
+
+        // The signature of the foreign key doesn't count.
+        input
+            .partial_sigs
+            .remove(&bitcoin::PublicKey::new(script_keys[2]));
+        assert!(finalize_multisig(&input, &multisig_proof).is_err());
+    }
+}
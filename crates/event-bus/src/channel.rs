@@ -0,0 +1,267 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+#[cfg(feature = "spill")]
+use std::sync::Mutex;
+
+use flume::{self as channel, RecvError, SendError, TrySendError};
+
+use crate::{event_bus::BusEvent, ChannelMetrics, ChannelPolicy, OverflowPolicy};
+
+#[cfg(feature = "spill")]
+use crate::spill::SpillQueue;
+
+type InnerSender = channel::Sender<Box<dyn BusEvent>>;
+type InnerReceiver = channel::Receiver<Box<dyn BusEvent>>;
+
+/// Channel of the event type with its overflow policy and counters, shared by the senders and
+/// receivers of the event.
+pub(crate) struct Channel {
+    event: &'static str,
+    capacity: Option<usize>,
+    overflow: OverflowPolicy,
+    tx: InnerSender,
+    rx: InnerReceiver,
+    #[cfg(feature = "spill")]
+    spill: Option<Mutex<SpillQueue>>,
+
+    sent: AtomicU64,
+    blocked_sends: AtomicU64,
+    dropped: AtomicU64,
+    spilled: AtomicU64,
+    max_depth: AtomicUsize,
+}
+
+impl Channel {
+    pub(crate) fn new(event: &'static str, policy: ChannelPolicy) -> std::io::Result<Self> {
+        let (tx, rx) = match policy.capacity {
+            Some(size) => channel::bounded::<Box<dyn BusEvent>>(size),
+            None => channel::unbounded::<Box<dyn BusEvent>>(),
+        };
+
+        // Only the full channel overflows, and the channel without a buffer is always full.
+        let overflow = match policy.capacity {
+            Some(size) if size > 0 => policy.overflow,
+            _ => OverflowPolicy::Block,
+        };
+
+        #[cfg(feature = "spill")]
+        let spill = match &overflow {
+            OverflowPolicy::SpillToDisk(config) => {
+                Some(Mutex::new(SpillQueue::open(config, event)?))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            event,
+            capacity: policy.capacity,
+            overflow,
+            tx,
+            rx,
+            #[cfg(feature = "spill")]
+            spill,
+            sent: AtomicU64::new(0),
+            blocked_sends: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            spilled: AtomicU64::new(0),
+            max_depth: AtomicUsize::new(0),
+        })
+    }
+
+    /// Sends the event to the channel, handling the overflow according to the policy.
+    pub(crate) async fn send(
+        &self,
+        event: Box<dyn BusEvent>,
+    ) -> Result<(), SendError<Box<dyn BusEvent>>> {
+        let result = match &self.overflow {
+            OverflowPolicy::Block => self.send_blocking(event).await,
+            OverflowPolicy::DropOldest => self.send_dropping_oldest(event),
+            #[cfg(feature = "spill")]
+            OverflowPolicy::SpillToDisk(_) => self.send_spilling(event).await,
+        };
+
+        if result.is_ok() {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+            self.max_depth.fetch_max(self.rx.len(), Ordering::Relaxed);
+        }
+
+        result
+    }
+
+    /// Sends the event to the channel without waiting for the space in it. If the channel is full
+    /// and the policy would make the sender wait, the event is returned back.
+    pub(crate) fn send_now(
+        &self,
+        event: Box<dyn BusEvent>,
+    ) -> Result<(), TrySendError<Box<dyn BusEvent>>> {
+        let result = match &self.overflow {
+            OverflowPolicy::DropOldest => self
+                .send_dropping_oldest(event)
+                .map_err(|SendError(event)| TrySendError::Disconnected(event)),
+            // The spilled events are received first, so the event can't skip the queue.
+            #[cfg(feature = "spill")]
+            OverflowPolicy::SpillToDisk(_) if self.spilled_len() > 0 => {
+                Err(TrySendError::Full(event))
+            }
+            _ => self.tx.try_send(event),
+        };
+
+        if result.is_ok() {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+            self.max_depth.fetch_max(self.rx.len(), Ordering::Relaxed);
+        }
+
+        result
+    }
+
+    async fn send_blocking(
+        &self,
+        event: Box<dyn BusEvent>,
+    ) -> Result<(), SendError<Box<dyn BusEvent>>> {
+        let event = match self.tx.try_send(event) {
+            Ok(()) => return Ok(()),
+            Err(TrySendError::Full(event)) => event,
+            Err(TrySendError::Disconnected(event)) => return Err(SendError(event)),
+        };
+
+        self.blocked_sends.fetch_add(1, Ordering::Relaxed);
+
+        self.tx.send_async(event).await
+    }
+
+    fn send_dropping_oldest(
+        &self,
+        mut event: Box<dyn BusEvent>,
+    ) -> Result<(), SendError<Box<dyn BusEvent>>> {
+        loop {
+            event = match self.tx.try_send(event) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(event)) => event,
+                Err(TrySendError::Disconnected(event)) => return Err(SendError(event)),
+            };
+
+            // The receiver could take the event in the meantime, so it's not dropped.
+            if self.rx.try_recv().is_ok() {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Sends the event to the channel if it has space and there are no spilled events, otherwise
+    /// appends it to the spill queue, so the events are received in the order they are sent.
+    #[cfg(feature = "spill")]
+    async fn send_spilling(
+        &self,
+        event: Box<dyn BusEvent>,
+    ) -> Result<(), SendError<Box<dyn BusEvent>>> {
+        let spill = self.spill.as_ref().expect("spill queue must be opened");
+
+        let event = {
+            let mut queue = spill.lock().expect("lock should not be poisoned");
+
+            let event = if queue.is_empty() {
+                match self.tx.try_send(event) {
+                    Ok(()) => return Ok(()),
+                    Err(TrySendError::Full(event)) => event,
+                    Err(TrySendError::Disconnected(event)) => return Err(SendError(event)),
+                }
+            } else {
+                event
+            };
+
+            match queue.push(event.as_ref()) {
+                Ok(()) => {
+                    self.spilled.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+                // The event isn't lost if it can't be spilled, the sender waits instead.
+                Err(_) => event,
+            }
+        };
+
+        self.blocked_sends.fetch_add(1, Ordering::Relaxed);
+
+        self.tx.send_async(event).await
+    }
+
+    /// Receives the event from the channel, and then from the spill queue once the channel is
+    /// drained.
+    pub(crate) async fn recv(&self) -> Result<Box<dyn BusEvent>, RecvError> {
+        #[cfg(feature = "spill")]
+        if let Some(spill) = &self.spill {
+            if let Ok(event) = self.rx.try_recv() {
+                return Ok(event);
+            }
+
+            let mut queue = spill.lock().expect("lock should not be poisoned");
+
+            // The sender could put the event to the channel before the queue is locked.
+            if let Ok(event) = self.rx.try_recv() {
+                return Ok(event);
+            }
+
+            if let Some(event) = queue.pop() {
+                return Ok(event);
+            }
+        }
+
+        self.rx.recv_async().await
+    }
+
+    /// Number of the events waiting in the channel and the spill queue.
+    pub(crate) fn len(&self) -> usize {
+        self.rx.len() + self.spilled_len()
+    }
+
+    pub(crate) fn metrics(&self) -> ChannelMetrics {
+        ChannelMetrics {
+            event: self.event.to_string(),
+            overflow: self.overflow.name().to_string(),
+            capacity: self.capacity,
+            depth: self.rx.len(),
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+            spilled_depth: self.spilled_len(),
+            sent: self.sent.load(Ordering::Relaxed),
+            blocked_sends: self.blocked_sends.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed) + self.spill_lost(),
+            spilled: self.spilled.load(Ordering::Relaxed),
+        }
+    }
+
+    #[cfg(feature = "spill")]
+    fn spilled_len(&self) -> usize {
+        self.spill.as_ref().map_or(0, |spill| {
+            spill.lock().expect("lock should not be poisoned").len()
+        })
+    }
+
+    #[cfg(not(feature = "spill"))]
+    fn spilled_len(&self) -> usize {
+        0
+    }
+
+    #[cfg(feature = "spill")]
+    fn spill_lost(&self) -> u64 {
+        self.spill.as_ref().map_or(0, |spill| {
+            spill.lock().expect("lock should not be poisoned").lost()
+        })
+    }
+
+    #[cfg(not(feature = "spill"))]
+    fn spill_lost(&self) -> u64 {
+        0
+    }
+}
+
+impl Debug for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Channel")
+            .field("event", &self.event)
+            .field("capacity", &self.capacity)
+            .field("overflow", &self.overflow)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,67 @@
+use core::fmt::Display;
+
+use flume as channel;
+
+/// Result of the handling of the event, with the error formatted by the receiver.
+pub type CompletionResult = Result<(), String>;
+
+/// Handle carried by the event, so the receiver can report to the sender that the work requested
+/// by the event is done, see [`Completion::new`].
+///
+/// The event is cloned for the receivers, so only the first reported result is delivered. The
+/// default handle reports to nobody.
+#[derive(Clone, Debug, Default)]
+pub struct Completion(Option<channel::Sender<CompletionResult>>);
+
+/// Waits for the result reported with the [`Completion`].
+#[derive(Debug)]
+pub struct CompletionWaiter(channel::Receiver<CompletionResult>);
+
+impl Completion {
+    /// Creates the handle to put to the event and the waiter for its result.
+    ///
+    /// ```
+    /// use event_bus::{Completion, EventBus};
+    /// use event_bus_macros::Event;
+    ///
+    /// #[derive(Clone, Event)]
+    /// struct Work(Completion);
+    ///
+    /// tokio_test::block_on(async {
+    ///     let mut event_bus = EventBus::default();
+    ///     event_bus.register::<Work>(None);
+    ///     let receiver = event_bus.subscribe::<Work>();
+    ///
+    ///     let (completion, waiter) = Completion::new();
+    ///     event_bus.send(Work(completion)).await;
+    ///
+    ///     let Work(completion) = receiver.recv().await.unwrap();
+    ///     completion.complete(&Ok::<(), String>(()));
+    ///
+    ///     assert_eq!(waiter.wait().await, Ok(()));
+    /// });
+    /// ```
+    pub fn new() -> (Self, CompletionWaiter) {
+        let (tx, rx) = channel::bounded(1);
+
+        (Self(Some(tx)), CompletionWaiter(rx))
+    }
+
+    /// Reports the result of the handling to the sender, if it waits for it.
+    pub fn complete<E: Display>(&self, result: &Result<(), E>) {
+        if let Some(tx) = &self.0 {
+            let _ = tx.try_send(result.as_ref().map(|_| ()).map_err(|err| err.to_string()));
+        }
+    }
+}
+
+impl CompletionWaiter {
+    /// Waits for the result of the handling. Returns an error if all the copies of the event are
+    /// dropped without the result, e.g. by the overflow policy of the channel.
+    pub async fn wait(self) -> CompletionResult {
+        self.0
+            .recv_async()
+            .await
+            .unwrap_or_else(|_| Err("the event is dropped without being handled".to_string()))
+    }
+}
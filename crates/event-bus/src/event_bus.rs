@@ -1,18 +1,16 @@
-use crate::{tid, Receiver};
+use crate::{channel::Channel, tid, ChannelPolicy, EventBusMetrics, Receiver};
 
 use std::{
-    any::{Any, TypeId},
+    any::{type_name, Any, TypeId},
     collections::HashMap,
     fmt::{Debug, Display, Formatter},
+    sync::Arc,
 };
 
 use flume as channel;
 
 pub type EventBusResult<T> = Result<T, Error>;
 
-type InnerSender = channel::Sender<Box<dyn BusEvent>>;
-type InnerReceiver = channel::Receiver<Box<dyn BusEvent>>;
-
 /// Trait for events that can be sent through event bus. Use [`event_bus_macros::Event`] derive
 /// macro to implement it.
 ///
@@ -62,11 +60,14 @@ pub trait BusEvent: Send {
 /// ```
 #[derive(Clone, Default, Debug)]
 pub struct EventBus {
-    /// Map of event type id to channel sender.
-    txs: HashMap<TypeId, InnerSender>,
+    /// Map of event type id to channel the bus can send to.
+    txs: HashMap<TypeId, Arc<Channel>>,
+
+    /// Map of event type id to channel the bus can subscribe to.
+    rxs: HashMap<TypeId, Arc<Channel>>,
 
-    /// Map of event type id to channel receiver.
-    rxs: HashMap<TypeId, InnerReceiver>,
+    /// Metrics of all the registered channels, shared with the extracted buses.
+    metrics: EventBusMetrics,
 }
 
 impl EventBus {
@@ -75,19 +76,45 @@ impl EventBus {
     ///
     /// It is possible to specify channel size as optional parameter. If channel size is not specified
     pub fn register<E: BusEvent + Clone + 'static>(&mut self, channel_size: Option<usize>) -> bool {
+        let policy = ChannelPolicy {
+            capacity: channel_size,
+            ..Default::default()
+        };
+
+        self.register_with_policy::<E>(policy)
+            .expect("blocking channel is always registered")
+    }
+
+    /// Register channel for specified event type with the [`ChannelPolicy`] that defines what
+    /// happens to the sent events when the channel is full, so a slow receiver doesn't stall the
+    /// senders. If channel is already registered, method will return true otherwise false.
+    ///
+    /// If the [`OverflowPolicy::SpillToDisk`](crate::OverflowPolicy) is created for another event
+    /// type, method will return [`Error::PolicyForOtherEvent`], and if the spill file can't be
+    /// created, [`Error::SpillFile`].
+    pub fn register_with_policy<E: BusEvent + Clone + 'static>(
+        &mut self,
+        policy: ChannelPolicy,
+    ) -> EventBusResult<bool> {
         if self.txs.contains_key(&tid::<E>()) {
-            return true;
+            return Ok(true);
         }
 
-        let (tx, rx) = match channel_size {
-            Some(size) => channel::bounded::<Box<dyn BusEvent>>(size),
-            None => channel::unbounded::<Box<dyn BusEvent>>(),
-        };
+        #[cfg(feature = "spill")]
+        if let crate::OverflowPolicy::SpillToDisk(config) = &policy.overflow {
+            if !config.is_for::<E>() {
+                return Err(Error::PolicyForOtherEvent);
+            }
+        }
+
+        let event = event_name::<E>();
+        let channel = Arc::new(Channel::new(event, policy).map_err(Error::SpillFile)?);
 
-        self.txs.insert(tid::<E>(), tx);
-        self.rxs.insert(tid::<E>(), rx);
+        self.txs.insert(tid::<E>(), channel.clone());
+        self.rxs.insert(tid::<E>(), channel.clone());
+        self.metrics.insert(event, channel);
 
-        false
+        Ok(false)
     }
 
     /// Extract subset of channels from existing event bus. If channel for specified event type
@@ -96,11 +123,18 @@ impl EventBus {
     /// Use [`typeid`](`crate::typeid`) macros for vec of event type ids.
     pub fn extract(&self, tx_ids: &[TypeId], rx_ids: &[TypeId]) -> EventBusResult<Self> {
         Ok(Self {
-            txs: new_hashmap_with::<InnerSender>(&self.txs, tx_ids)?,
-            rxs: new_hashmap_with::<InnerReceiver>(&self.rxs, rx_ids)?,
+            txs: new_hashmap_with(&self.txs, tx_ids)?,
+            rxs: new_hashmap_with(&self.rxs, rx_ids)?,
+            metrics: self.metrics.clone(),
         })
     }
 
+    /// Returns the handle to the metrics of all the channels registered in the bus, including
+    /// the ones that weren't extracted.
+    pub fn metrics(&self) -> EventBusMetrics {
+        self.metrics.clone()
+    }
+
     /// Subscribe to channel by returning [`Receiver`] for specified event type. If channel for
     /// specified event type doesn't exist, method will panic. Use [`EventBus::try_subscribe`] to
     /// avoid panic.
@@ -129,8 +163,9 @@ impl EventBus {
     /// Send event to channel. If channels for specified event isn't registered
     /// ([`EventBus::register`]), method will panic. Use [`EventBus::try_send`] to avoid panic.
     ///
-    /// If channel size is specified and channel is full, the method will block until there is a
-    /// space in channel.
+    /// If channel size is specified and channel is full, the event is handled according to the
+    /// channel's [`OverflowPolicy`](crate::OverflowPolicy), by default the method will block until
+    /// there is a space in channel.
     pub async fn send<E: BusEvent + 'static>(&self, event: E) {
         let channel = self
             .txs
@@ -138,7 +173,7 @@ impl EventBus {
             .expect("channel for event must be presented");
 
         channel
-            .send_async(Box::new(event))
+            .send(Box::new(event))
             .await
             .expect("async channel already closed");
     }
@@ -152,12 +187,54 @@ impl EventBus {
             .ok_or(Error::ChannelForTypeIdDoesntExist)?;
 
         channel
-            .send_async(Box::new(event))
+            .send(Box::new(event))
             .await
             .map_err(Error::ChannelSend)?;
 
         Ok(())
     }
+
+    /// Send event to channel without waiting. If the channel is full and its
+    /// [`OverflowPolicy`](crate::OverflowPolicy) would make the sender wait, the event is
+    /// dropped and method will return [`Error::ChannelFull`]. If channels for specified event
+    /// isn't registered method will return [`Error::ChannelForTypeIdDoesntExist`].
+    ///
+    /// Use it for the events the sender must not be slowed down by, like the notifications.
+    ///
+    /// # Examples
+    /// ```
+    /// use event_bus::{EventBus, Error};
+    /// use event_bus_macros::Event;
+    ///
+    /// #[derive(Clone, Event)]
+    /// struct MyEvent;
+    ///
+    /// let mut event_bus = EventBus::default();
+    /// event_bus.register::<MyEvent>(Some(1));
+    ///
+    /// assert!(event_bus.send_now(MyEvent).is_ok());
+    /// assert!(matches!(event_bus.send_now(MyEvent), Err(Error::ChannelFull)));
+    /// ```
+    pub fn send_now<E: BusEvent + 'static>(&self, event: E) -> EventBusResult<()> {
+        let channel = self
+            .txs
+            .get(&tid::<E>())
+            .ok_or(Error::ChannelForTypeIdDoesntExist)?;
+
+        channel.send_now(Box::new(event)).map_err(|err| match err {
+            channel::TrySendError::Full(_) => Error::ChannelFull,
+            channel::TrySendError::Disconnected(event) => {
+                Error::ChannelSend(channel::SendError(event))
+            }
+        })
+    }
+}
+
+/// Short name of the event type without the module path.
+fn event_name<E: 'static>() -> &'static str {
+    let name = type_name::<E>();
+
+    name.rsplit("::").next().unwrap_or(name)
 }
 
 fn new_hashmap_with<Channel: Clone>(
@@ -182,6 +259,9 @@ fn new_hashmap_with<Channel: Clone>(
 pub enum Error {
     ChannelSend(channel::SendError<Box<dyn BusEvent>>),
     ChannelForTypeIdDoesntExist,
+    ChannelFull,
+    PolicyForOtherEvent,
+    SpillFile(std::io::Error),
 }
 
 impl Display for Error {
@@ -193,6 +273,15 @@ impl Display for Error {
             Self::ChannelForTypeIdDoesntExist => {
                 write!(f, "channel for event id doesn't exist")
             }
+            Self::ChannelFull => {
+                write!(f, "channel is full, event is dropped")
+            }
+            Self::PolicyForOtherEvent => {
+                write!(f, "channel policy is created for another event type")
+            }
+            Self::SpillFile(inner) => {
+                write!(f, "failed to create spill file: {inner}")
+            }
         }
     }
 }
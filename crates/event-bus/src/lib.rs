@@ -4,10 +4,21 @@ mod event_bus;
 pub use crate::event_bus::{BusEvent, Error, EventBus};
 use std::any::TypeId;
 
+mod channel;
+mod completion;
 mod macros;
+mod metrics;
+mod policy;
 mod receiver;
+#[cfg(feature = "spill")]
+mod spill;
 
+pub use crate::completion::{Completion, CompletionResult, CompletionWaiter};
+pub use crate::metrics::{ChannelMetrics, EventBusMetrics};
+pub use crate::policy::{ChannelPolicy, OverflowPolicy};
 pub use crate::receiver::Receiver;
+#[cfg(feature = "spill")]
+pub use crate::spill::SpillToDisk;
 
 pub use event_bus_macros::Event;
 
@@ -0,0 +1,60 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::channel::Channel;
+
+/// Metrics of the event channel. The growing `depth` of the channel points to the slow receiver,
+/// and the growing `blocked_sends` to the senders that are stalled by it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelMetrics {
+    /// Name of the event type.
+    pub event: String,
+    /// Name of the [`OverflowPolicy`](crate::OverflowPolicy) of the channel.
+    pub overflow: String,
+    /// Size of the channel, `None` if it's unbounded.
+    pub capacity: Option<usize>,
+    /// Number of the events waiting in the channel.
+    pub depth: usize,
+    /// The largest number of the events that were waiting in the channel.
+    pub max_depth: usize,
+    /// Number of the events waiting in the spill file.
+    pub spilled_depth: usize,
+    /// Number of the sent events.
+    pub sent: u64,
+    /// Number of the sends that waited for the space in the full channel.
+    pub blocked_sends: u64,
+    /// Number of the events dropped because the channel was full, or lost in the spill file.
+    pub dropped: u64,
+    /// Number of the events written to the spill file.
+    pub spilled: u64,
+}
+
+/// Shared handle to the metrics of the channels registered in the [`EventBus`] and the buses
+/// extracted from it.
+///
+/// [`EventBus`]: crate::EventBus
+#[derive(Debug, Clone, Default)]
+pub struct EventBusMetrics(Arc<RwLock<BTreeMap<&'static str, Arc<Channel>>>>);
+
+impl EventBusMetrics {
+    /// Returns the metrics of all the channels sorted by the event's name.
+    pub fn list(&self) -> Vec<ChannelMetrics> {
+        self.0
+            .read()
+            .expect("lock should not be poisoned")
+            .values()
+            .map(|channel| channel.metrics())
+            .collect()
+    }
+
+    pub(crate) fn insert(&self, event: &'static str, channel: Arc<Channel>) {
+        self.0
+            .write()
+            .expect("lock should not be poisoned")
+            .insert(event, channel);
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(feature = "spill")]
+use crate::spill::SpillToDisk;
+
+/// Policy of the channel registered with [`EventBus::register_with_policy`].
+///
+/// [`EventBus::register_with_policy`]: crate::EventBus::register_with_policy
+#[derive(Clone, Debug, Default)]
+pub struct ChannelPolicy {
+    /// Size of the channel, unbounded if `None`.
+    pub capacity: Option<usize>,
+    /// What to do with the sent event when the channel is full. Applies only to the bounded
+    /// channels with non-zero capacity, the rest always use [`OverflowPolicy::Block`].
+    pub overflow: OverflowPolicy,
+}
+
+impl ChannelPolicy {
+    /// Bounded channel that blocks the sender when it's full.
+    pub fn bounded(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity),
+            overflow: OverflowPolicy::Block,
+        }
+    }
+
+    pub fn unbounded() -> Self {
+        Self::default()
+    }
+
+    pub fn with_overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+/// What to do with the sent event when the channel is full.
+#[derive(Clone, Debug, Default)]
+pub enum OverflowPolicy {
+    /// Wait until the receiver takes an event from the channel.
+    #[default]
+    Block,
+    /// Drop the oldest event in the channel to make room for the sent one. Suits the events
+    /// that are useless once they are stale, e.g. notifications, as the sender never waits for
+    /// a slow receiver.
+    DropOldest,
+    /// Write the sent event to a file, and give it to the receiver once the channel is drained.
+    /// The sender never waits for a slow receiver, and no events are lost while the node is
+    /// running, but the events are read back from the disk.
+    #[cfg(feature = "spill")]
+    SpillToDisk(SpillToDisk),
+}
+
+impl OverflowPolicy {
+    /// Name of the policy reported in the [`ChannelMetrics`](crate::ChannelMetrics).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::DropOldest => "drop_oldest",
+            #[cfg(feature = "spill")]
+            Self::SpillToDisk(_) => "spill_to_disk",
+        }
+    }
+}
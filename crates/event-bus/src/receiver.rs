@@ -1,21 +1,21 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use channel::RecvError;
 use flume as channel;
 
-use crate::event_bus::BusEvent;
+use crate::channel::Channel;
 
 /// Wrapper for async channel receiver.
 /// It encapsulates the logic of downcasting the event to the specified type.
 #[derive(Clone)]
 pub struct Receiver<E: Clone> {
-    inner: channel::Receiver<Box<dyn BusEvent>>,
+    inner: Arc<Channel>,
     __event: PhantomData<E>,
 }
 
 impl<E: Clone + 'static> Receiver<E> {
-    /// Create new receiver from async channel receiver.
-    pub fn new(inner: channel::Receiver<Box<dyn BusEvent>>) -> Self {
+    /// Create new receiver from the event channel.
+    pub(crate) fn new(inner: Arc<Channel>) -> Self {
         Self {
             inner,
             __event: Default::default(),
@@ -24,7 +24,7 @@ impl<E: Clone + 'static> Receiver<E> {
 
     /// Receive event from channel.
     pub async fn recv(&self) -> Result<E, RecvError> {
-        let event_raw = self.inner.recv_async().await?;
+        let event_raw = self.inner.recv().await?;
 
         let event_any = event_raw.as_any();
 
@@ -35,9 +35,10 @@ impl<E: Clone + 'static> Receiver<E> {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.inner.is_empty()
+        self.len() == 0
     }
 
+    /// Number of the events waiting in the channel, including the spilled ones.
     pub fn len(&self) -> usize {
         self.inner.len()
     }
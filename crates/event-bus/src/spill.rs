@@ -0,0 +1,192 @@
+use std::{
+    any::TypeId,
+    fmt::{Debug, Formatter},
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{event_bus::BusEvent, tid};
+
+type Encode = Arc<dyn Fn(&dyn BusEvent) -> io::Result<Vec<u8>> + Send + Sync>;
+type Decode = Arc<dyn Fn(&[u8]) -> io::Result<Box<dyn BusEvent>> + Send + Sync>;
+
+/// Configuration of the [`OverflowPolicy::SpillToDisk`](crate::OverflowPolicy::SpillToDisk)
+/// for the event type `E`. The events are encoded with CBOR to the `<dir>/<event>.spill` file,
+/// which is truncated when the channel is registered, so the events spilled before the restart
+/// are not received.
+#[derive(Clone)]
+pub struct SpillToDisk {
+    dir: PathBuf,
+    event: TypeId,
+    encode: Encode,
+    decode: Decode,
+}
+
+impl SpillToDisk {
+    pub fn new<E>(dir: impl Into<PathBuf>) -> Self
+    where
+        E: BusEvent + Serialize + DeserializeOwned + 'static,
+    {
+        let encode: Encode = Arc::new(|event| {
+            let event = event
+                .as_any()
+                .downcast_ref::<E>()
+                .expect("spilled event must be of the registered type");
+
+            let mut bytes = Vec::new();
+            ciborium::into_writer(event, &mut bytes).map_err(io::Error::other)?;
+
+            Ok(bytes)
+        });
+
+        let decode: Decode = Arc::new(|bytes| {
+            let event: E = ciborium::from_reader(bytes).map_err(io::Error::other)?;
+
+            Ok(Box::new(event))
+        });
+
+        Self {
+            dir: dir.into(),
+            event: tid::<E>(),
+            encode,
+            decode,
+        }
+    }
+
+    /// Returns `true` if the config is created for the event type `E`.
+    pub(crate) fn is_for<E: 'static>(&self) -> bool {
+        self.event == tid::<E>()
+    }
+}
+
+impl Debug for SpillToDisk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpillToDisk")
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+/// FIFO queue of the encoded events in the file. Each event is prefixed with its length, the
+/// events are appended to the end of the file and read from the read position. The file is
+/// truncated once all the events are read.
+pub(crate) struct SpillQueue {
+    file: File,
+    read_pos: u64,
+    write_pos: u64,
+    len: usize,
+    lost: u64,
+    encode: Encode,
+    decode: Decode,
+}
+
+impl SpillQueue {
+    /// Creates the empty queue in the file for the event with the `name`.
+    pub(crate) fn open(config: &SpillToDisk, name: &str) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(config.dir.join(format!("{name}.spill")))?;
+
+        Ok(Self {
+            file,
+            read_pos: 0,
+            write_pos: 0,
+            len: 0,
+            lost: 0,
+            encode: config.encode.clone(),
+            decode: config.decode.clone(),
+        })
+    }
+
+    pub(crate) fn push(&mut self, event: &dyn BusEvent) -> io::Result<()> {
+        let bytes = (self.encode)(event)?;
+
+        let mut record = Vec::with_capacity(bytes.len() + 4);
+        record.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        record.extend_from_slice(&bytes);
+
+        self.file.seek(SeekFrom::Start(self.write_pos))?;
+        self.file.write_all(&record)?;
+
+        self.write_pos += record.len() as u64;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Returns the oldest event, `None` if the queue is empty. The events that can't be read
+    /// back are skipped and counted as [lost](SpillQueue::lost).
+    pub(crate) fn pop(&mut self) -> Option<Box<dyn BusEvent>> {
+        while self.len > 0 {
+            let bytes = match self.read_record() {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    // The read position is unknown, so the rest of the queue is lost.
+                    self.lost += self.len as u64;
+                    self.len = 0;
+                    self.reset();
+                    return None;
+                }
+            };
+
+            self.len -= 1;
+            if self.len == 0 {
+                self.reset();
+            }
+
+            match (self.decode)(&bytes) {
+                Ok(event) => return Some(event),
+                Err(_) => self.lost += 1,
+            }
+        }
+
+        None
+    }
+
+    fn read_record(&mut self) -> io::Result<Vec<u8>> {
+        self.file.seek(SeekFrom::Start(self.read_pos))?;
+
+        let mut len = [0u8; 4];
+        self.file.read_exact(&mut len)?;
+        let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+        self.file.read_exact(&mut bytes)?;
+
+        self.read_pos += (bytes.len() + len.len()) as u64;
+
+        Ok(bytes)
+    }
+
+    /// Truncates the file of the empty queue, so it doesn't grow while the events are spilled
+    /// and read back.
+    fn reset(&mut self) {
+        // If the file isn't truncated, the next records are just written after the old ones.
+        if self.file.set_len(0).is_ok() {
+            self.read_pos = 0;
+            self.write_pos = 0;
+        } else {
+            self.read_pos = self.write_pos;
+        }
+    }
+
+    /// Number of the spilled events that couldn't be read back.
+    pub(crate) fn lost(&self) -> u64 {
+        self.lost
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
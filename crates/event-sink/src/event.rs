@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use yuv_types::RpcSubscriptionMessage;
+
+/// Kinds of the events forwarded to the sink, each one is published to its own topic.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkEvent {
+    /// Transaction is attached to the graph.
+    Attached,
+    /// Chroma is announced.
+    ChromaAnnouncement,
+    /// Output is frozen by the issuer.
+    Freeze,
+    /// Attached transaction is rolled back by the reorg, and waits for the confirmation again.
+    Reorged,
+    /// Attached issuance isn't signed by the owner the chroma had when it was mined.
+    Invalidated,
+}
+
+impl SinkEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Attached => "attached",
+            Self::ChromaAnnouncement => "chroma_announcement",
+            Self::Freeze => "freeze",
+            Self::Reorged => "reorged",
+            Self::Invalidated => "invalidated",
+        }
+    }
+
+    /// Returns the kind of the events in the message, `None` if they aren't forwarded.
+    pub(crate) fn from_message(message: &RpcSubscriptionMessage) -> Option<Self> {
+        match message {
+            RpcSubscriptionMessage::AttachedTxs(_) => Some(Self::Attached),
+            RpcSubscriptionMessage::ChromaAnnouncements(_) => Some(Self::ChromaAnnouncement),
+            RpcSubscriptionMessage::Freezes(_) => Some(Self::Freeze),
+            RpcSubscriptionMessage::DetachedTxs(_) => Some(Self::Reorged),
+            RpcSubscriptionMessage::InvalidatedTxs(_) => Some(Self::Invalidated),
+            // Unconfirmed transfers can be rejected later, so they aren't forwarded.
+            RpcSubscriptionMessage::UnconfirmedTransfers(_) => None,
+        }
+    }
+}
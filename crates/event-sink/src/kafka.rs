@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eyre::{eyre, Context};
+use rdkafka::{
+    message::{Header, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord},
+    ClientConfig,
+};
+use yuv_storage::EventSinkMessage;
+
+use crate::{Publisher, EVENT_ID_HEADER};
+
+/// Publishes the events to Kafka. The messages are keyed by the transaction or the chroma, so
+/// the events about the same one land in the same partition in order.
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+    timeout: Duration,
+}
+
+impl KafkaPublisher {
+    /// Creates the idempotent producer that waits for all the in-sync replicas to acknowledge
+    /// the messages. `brokers` is the comma-separated list of the bootstrap servers.
+    pub fn new(brokers: &str, timeout: Duration) -> eyre::Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .set("message.timeout.ms", timeout.as_millis().to_string())
+            .create()
+            .wrap_err("failed to create the Kafka producer")?;
+
+        Ok(Self { producer, timeout })
+    }
+}
+
+#[async_trait]
+impl Publisher for KafkaPublisher {
+    async fn publish(&self, message: &EventSinkMessage) -> eyre::Result<()> {
+        let id = message.id.to_string();
+        let record = FutureRecord::to(&message.topic)
+            .key(&message.key)
+            .payload(&message.payload)
+            .headers(OwnedHeaders::new().insert(Header {
+                key: EVENT_ID_HEADER,
+                value: Some(&id),
+            }));
+
+        self.producer
+            .send(record, self.timeout)
+            .await
+            .map_err(|(err, _)| eyre!("failed to publish to Kafka: {err}"))?;
+
+        Ok(())
+    }
+}
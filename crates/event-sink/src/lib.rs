@@ -0,0 +1,20 @@
+#![doc = include_str!("../README.md")]
+
+mod event;
+pub use event::SinkEvent;
+
+mod publisher;
+pub use publisher::{Publisher, EVENT_ID_HEADER};
+
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+
+#[cfg(feature = "nats")]
+mod nats;
+#[cfg(feature = "nats")]
+pub use nats::NatsPublisher;
+
+mod sink;
+pub use sink::{EventSink, DEFAULT_MAX_QUEUE_SIZE};
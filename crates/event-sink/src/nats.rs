@@ -0,0 +1,48 @@
+use async_nats::{header::NATS_MESSAGE_ID, jetstream, HeaderMap};
+use async_trait::async_trait;
+use eyre::Context;
+use yuv_storage::EventSinkMessage;
+
+use crate::{Publisher, EVENT_ID_HEADER};
+
+/// Publishes the events to the NATS JetStream, whose streams must cover the subjects of the
+/// events. The id of the event is sent as `Nats-Msg-Id`, so the stream drops the duplicates
+/// within its deduplication window.
+pub struct NatsPublisher {
+    jetstream: jetstream::Context,
+}
+
+impl NatsPublisher {
+    pub async fn connect(url: &str) -> eyre::Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .wrap_err("failed to connect to NATS")?;
+
+        Ok(Self {
+            jetstream: jetstream::new(client),
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for NatsPublisher {
+    async fn publish(&self, message: &EventSinkMessage) -> eyre::Result<()> {
+        let id = message.id.to_string();
+        let mut headers = HeaderMap::new();
+        headers.insert(NATS_MESSAGE_ID, id.as_str());
+        headers.insert(EVENT_ID_HEADER, id.as_str());
+
+        self.jetstream
+            .publish_with_headers(
+                message.topic.clone(),
+                headers,
+                message.payload.clone().into(),
+            )
+            .await
+            .wrap_err("failed to publish to NATS")?
+            .await
+            .wrap_err("NATS didn't acknowledge the event")?;
+
+        Ok(())
+    }
+}
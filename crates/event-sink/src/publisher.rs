@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+use yuv_storage::EventSinkMessage;
+
+/// Header with the id of the event, the same for all the attempts to publish it, so the
+/// consumers can skip the duplicates.
+pub const EVENT_ID_HEADER: &str = "yuv-event-id";
+
+/// Client of the message broker the events are published to.
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    /// Publishes the message, and returns only after the broker acknowledges it.
+    async fn publish(&self, message: &EventSinkMessage) -> eyre::Result<()>;
+}
@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::{broadcast, broadcast::error::RecvError};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use yuv_storage::{EventSinkMessage, EventSinkQueue, EventSinkStorage};
+use yuv_types::RpcSubscriptionMessage;
+
+use crate::{Publisher, SinkEvent};
+
+/// Number of the unpublished events after which the oldest ones are dropped.
+pub const DEFAULT_MAX_QUEUE_SIZE: usize = 100_000;
+
+/// How often the failed events are retried.
+const RETRY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Max number of the events published at once.
+const MAX_MESSAGES_PER_ROUND: usize = 1000;
+/// Delay before the first retry, doubled with each failed round.
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// JSON payload of the event.
+#[derive(Serialize)]
+struct Payload<'a, T> {
+    /// Id of the event, the same for all the attempts to publish it.
+    id: u64,
+    event: SinkEvent,
+    data: &'a T,
+}
+
+/// Forwards the attached transactions, the announcements and the reorgs to the message broker,
+/// e.g. Kafka or NATS, for the data pipelines.
+///
+/// The events are queued in the storage before publishing, and removed only after the broker
+/// acknowledges them, so they survive the node restarts and the broker outages, and are
+/// delivered at least once.
+pub struct EventSink<S> {
+    publisher: Box<dyn Publisher>,
+    /// Topics of the forwarded events, the events without the topic are skipped.
+    topics: BTreeMap<SinkEvent, String>,
+    storage: S,
+    max_queue_size: usize,
+    queue: EventSinkQueue,
+    /// Delay of the next retry, `None` if the last round was published successfully.
+    retry_delay: Option<Duration>,
+    next_attempt_at: Instant,
+}
+
+impl<S> EventSink<S>
+where
+    S: EventSinkStorage + Send + Sync + 'static,
+{
+    pub fn new(
+        publisher: Box<dyn Publisher>,
+        topics: BTreeMap<SinkEvent, String>,
+        storage: S,
+    ) -> Self {
+        Self {
+            publisher,
+            topics,
+            storage,
+            max_queue_size: DEFAULT_MAX_QUEUE_SIZE,
+            queue: EventSinkQueue::default(),
+            retry_delay: None,
+            next_attempt_at: Instant::now(),
+        }
+    }
+
+    pub fn with_max_queue_size(mut self, max_queue_size: usize) -> Self {
+        self.max_queue_size = max_queue_size;
+        self
+    }
+
+    /// Queues the `events` that have the topics and publishes them until cancelled.
+    pub async fn run(
+        mut self,
+        mut events: broadcast::Receiver<RpcSubscriptionMessage>,
+        cancellation: CancellationToken,
+    ) {
+        match self.storage.get_event_sink_queue().await {
+            Ok(queue) => self.queue = queue,
+            Err(err) => {
+                tracing::error!("Failed to load the event sink queue: {}", err);
+                cancellation.cancel();
+                return;
+            }
+        }
+
+        let mut timer = tokio::time::interval(RETRY_CHECK_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = events.recv() => {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::error!(
+                                "Event sink is lagging, {} events are not forwarded",
+                                skipped,
+                            );
+                            continue;
+                        }
+                        Err(RecvError::Closed) => {
+                            tracing::trace!("All incoming events senders are dropped");
+                            return;
+                        }
+                    };
+
+                    if let Err(err) = self.enqueue(event).await {
+                        tracing::error!("Failed to queue the events for the sink: {:?}", err);
+                    }
+                }
+                _ = timer.tick() => {}
+                _ = cancellation.cancelled() => {
+                    tracing::trace!("Cancellation received, stopping event sink");
+                    return;
+                }
+            }
+
+            if let Err(err) = self.publish_due().await {
+                tracing::error!("Failed to publish the events to the sink: {:?}", err);
+            }
+        }
+    }
+
+    async fn enqueue(&mut self, event: RpcSubscriptionMessage) -> eyre::Result<()> {
+        let messages = self.messages(&event)?;
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        self.queue.messages.extend(messages);
+
+        while self.queue.messages.len() > self.max_queue_size {
+            if let Some(dropped) = self.queue.messages.pop_front() {
+                tracing::warn!(
+                    id = dropped.id,
+                    topic = dropped.topic,
+                    "Event sink queue is full, the oldest event is dropped"
+                );
+            }
+        }
+
+        self.storage
+            .put_event_sink_queue(self.queue.clone())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the messages about the event, one per transaction or announcement, with the ids
+    /// assigned.
+    fn messages(&mut self, event: &RpcSubscriptionMessage) -> eyre::Result<Vec<EventSinkMessage>> {
+        let Some(kind) = SinkEvent::from_message(event) else {
+            return Ok(Vec::new());
+        };
+        let Some(topic) = self.topics.get(&kind) else {
+            return Ok(Vec::new());
+        };
+
+        // The transactions are keyed by their ids, and the announcements by their chromas.
+        let entries = match event {
+            RpcSubscriptionMessage::AttachedTxs(txs)
+            | RpcSubscriptionMessage::DetachedTxs(txs)
+            | RpcSubscriptionMessage::InvalidatedTxs(txs) => txs
+                .iter()
+                .map(|tx| Ok((tx.bitcoin_tx.txid().to_string(), serde_json::to_value(tx)?)))
+                .collect::<serde_json::Result<Vec<_>>>()?,
+            RpcSubscriptionMessage::ChromaAnnouncements(announcements) => announcements
+                .iter()
+                .map(|announcement| {
+                    Ok((
+                        announcement.chroma.to_string(),
+                        serde_json::to_value(announcement)?,
+                    ))
+                })
+                .collect::<serde_json::Result<Vec<_>>>()?,
+            RpcSubscriptionMessage::Freezes(freezes) => freezes
+                .iter()
+                .map(|freeze| Ok((freeze.chroma.to_string(), serde_json::to_value(freeze)?)))
+                .collect::<serde_json::Result<Vec<_>>>()?,
+            RpcSubscriptionMessage::UnconfirmedTransfers(_) => Vec::new(),
+        };
+
+        let mut messages = Vec::with_capacity(entries.len());
+        for (key, data) in entries {
+            let id = self.queue.next_id;
+            self.queue.next_id += 1;
+
+            messages.push(EventSinkMessage {
+                id,
+                topic: topic.clone(),
+                key,
+                payload: serde_json::to_string(&Payload {
+                    id,
+                    event: kind,
+                    data: &data,
+                })?,
+            });
+        }
+
+        Ok(messages)
+    }
+
+    /// Publishes the queued events unless the retry is not due yet. The failed events are kept
+    /// in the queue, and retried with the exponential backoff.
+    async fn publish_due(&mut self) -> eyre::Result<()> {
+        if self.queue.messages.is_empty() || Instant::now() < self.next_attempt_at {
+            return Ok(());
+        }
+
+        let batch = self
+            .queue
+            .messages
+            .iter()
+            .take(MAX_MESSAGES_PER_ROUND)
+            .collect::<Vec<_>>();
+
+        let results =
+            futures::future::join_all(batch.iter().map(|message| self.publisher.publish(message)))
+                .await;
+
+        let mut published = HashSet::new();
+        let mut last_error = None;
+        for (message, result) in batch.iter().zip(results) {
+            match result {
+                Ok(()) => {
+                    published.insert(message.id);
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        if !published.is_empty() {
+            self.queue
+                .messages
+                .retain(|message| !published.contains(&message.id));
+            self.storage
+                .put_event_sink_queue(self.queue.clone())
+                .await?;
+        }
+
+        match last_error {
+            Some(err) => {
+                let delay = self
+                    .retry_delay
+                    .map_or(BASE_RETRY_DELAY, |delay| (delay * 2).min(MAX_RETRY_DELAY));
+                self.retry_delay = Some(delay);
+                self.next_attempt_at = Instant::now() + delay;
+
+                tracing::warn!(
+                    pending = self.queue.messages.len(),
+                    "Failed to publish the events, retrying in {} secs: {}",
+                    delay.as_secs(),
+                    err
+                );
+            }
+            None => self.retry_delay = None,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use bitcoin::{hashes::Hash, OutPoint, Txid};
+    use yuv_pixels::Chroma;
+    use yuv_storage::LevelDB;
+    use yuv_types::announcements::FreezeAnnouncement;
+
+    use super::*;
+
+    const CHROMA: &str = "93bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+
+    /// Publisher that fails the first `failures` messages.
+    struct FlakyPublisher {
+        failures: Mutex<usize>,
+        published: Arc<Mutex<Vec<EventSinkMessage>>>,
+    }
+
+    #[async_trait]
+    impl Publisher for FlakyPublisher {
+        async fn publish(&self, message: &EventSinkMessage) -> eyre::Result<()> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                eyre::bail!("broker is unavailable");
+            }
+
+            self.published.lock().unwrap().push(message.clone());
+
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_with_retries() {
+        let published = Arc::new(Mutex::new(Vec::new()));
+        let publisher = FlakyPublisher {
+            failures: Mutex::new(1),
+            published: Arc::clone(&published),
+        };
+        let storage = LevelDB::in_memory().unwrap();
+        let topics = BTreeMap::from([(SinkEvent::Freeze, "yuv.freezes".to_string())]);
+
+        let mut sink = EventSink::new(Box::new(publisher), topics, storage.clone());
+
+        let chroma = Chroma::from_str(CHROMA).unwrap();
+        let freezes = vec![
+            FreezeAnnouncement::new(chroma, OutPoint::new(Txid::all_zeros(), 0)),
+            FreezeAnnouncement::new(chroma, OutPoint::new(Txid::all_zeros(), 1)),
+        ];
+
+        // The events without the topic are skipped.
+        sink.enqueue(RpcSubscriptionMessage::DetachedTxs(Vec::new()))
+            .await
+            .unwrap();
+        sink.enqueue(RpcSubscriptionMessage::Freezes(freezes))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_event_sink_queue().await.unwrap().messages.len(),
+            2
+        );
+
+        sink.publish_due().await.unwrap();
+        assert_eq!(published.lock().unwrap().len(), 1);
+        assert_eq!(
+            storage.get_event_sink_queue().await.unwrap().messages.len(),
+            1
+        );
+
+        // The failed event isn't retried until the delay passes.
+        sink.publish_due().await.unwrap();
+        assert_eq!(published.lock().unwrap().len(), 1);
+
+        sink.next_attempt_at = Instant::now();
+        sink.publish_due().await.unwrap();
+
+        let queue = storage.get_event_sink_queue().await.unwrap();
+        assert!(queue.messages.is_empty());
+        assert_eq!(queue.next_id, 2);
+
+        let published = published.lock().unwrap();
+        assert_eq!(
+            published
+                .iter()
+                .map(|message| message.id)
+                .collect::<Vec<_>>(),
+            vec![1, 0]
+        );
+        assert_eq!(published[0].topic, "yuv.freezes");
+        assert_eq!(published[0].key, CHROMA);
+
+        let payload: serde_json::Value = serde_json::from_str(&published[0].payload).unwrap();
+        assert_eq!(payload["id"], 1);
+        assert_eq!(payload["event"], "freeze");
+        assert_eq!(
+            payload["data"]["outpoint"],
+            format!("{}:1", Txid::all_zeros())
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use bitcoin::{Address, PublicKey};
+use yuv_pixels::PixelKey;
+
+use crate::types::{parse_network, parse_public_key};
+use crate::{FfiError, Pixel};
+
+/// Returns the `P2WPKH` address of the `public_key`, where the wallet receives the bitcoins.
+#[uniffi::export]
+pub fn p2wpkh_address(public_key: String, network: String) -> Result<String, FfiError> {
+    let public_key = PublicKey::new(parse_public_key(&public_key)?);
+    let address = Address::p2wpkh(&public_key, parse_network(&network)?)
+        .map_err(|err| FfiError::invalid_argument("public key", err))?;
+
+    Ok(address.to_string())
+}
+
+/// Returns the address of the output with the `pixel` owned by the `public_key`, which is the
+/// `P2WPKH` address of the key tweaked by the pixel.
+#[uniffi::export]
+pub fn pixel_address(
+    pixel: Pixel,
+    public_key: String,
+    network: String,
+) -> Result<String, FfiError> {
+    let pixel_key = PixelKey::new(pixel.parse()?, &parse_public_key(&public_key)?)
+        .map_err(|err| FfiError::invalid_argument("pixel", err))?;
+
+    p2wpkh_address(pixel_key.to_string(), network)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+
+    use super::*;
+
+    const CHROMA: &str = "93bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+    const SECRET: &str = "f9e17ee5b837fece0695f9782253604586ab1daf42ecf2762573243c7a6979f4";
+
+    #[test]
+    fn test_pixel_address() {
+        let public_key = SecretKey::from_str(SECRET)
+            .unwrap()
+            .public_key(&Secp256k1::new())
+            .to_string();
+        let network = "regtest".to_string();
+
+        let pixel = Pixel {
+            chroma: CHROMA.to_string(),
+            amount: "1000".to_string(),
+        };
+        let other_pixel = Pixel {
+            amount: "1001".to_string(),
+            ..pixel.clone()
+        };
+
+        let key_address = p2wpkh_address(public_key.clone(), network.clone()).unwrap();
+        let first = pixel_address(pixel, public_key.clone(), network.clone()).unwrap();
+        let second = pixel_address(other_pixel, public_key, network.clone()).unwrap();
+
+        // The keys are tweaked by the whole pixel, including the amount.
+        assert_ne!(key_address, first);
+        assert_ne!(first, second);
+
+        assert!(matches!(
+            p2wpkh_address("not a key".to_string(), network),
+            Err(FfiError::InvalidArgument(_))
+        ));
+    }
+}
@@ -0,0 +1,42 @@
+use bitcoin::ScriptBuf;
+use yuv_types::announcements::announcement_from_script;
+
+use crate::FfiError;
+
+/// Decodes the announcement from the hex of the `OP_RETURN` script, and returns it as JSON, in
+/// the same format as the YUV node returns it.
+#[uniffi::export]
+pub fn decode_announcement(script: String) -> Result<String, FfiError> {
+    let script = hex::decode(script).map_err(|err| FfiError::invalid_argument("script", err))?;
+    let announcement = announcement_from_script(&ScriptBuf::from_bytes(script))
+        .map_err(|err| FfiError::invalid_argument("script", err))?;
+
+    serde_json::to_string(&announcement).map_err(|err| FfiError::invalid_argument("script", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use bitcoin::{hashes::Hash, OutPoint, Txid};
+    use yuv_pixels::Chroma;
+    use yuv_types::{announcements::FreezeAnnouncement, Announcement};
+
+    use super::*;
+
+    const CHROMA: &str = "93bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+
+    #[test]
+    fn test_decode_announcement() {
+        let announcement = Announcement::Freeze(FreezeAnnouncement::new(
+            Chroma::from_str(CHROMA).unwrap(),
+            OutPoint::new(Txid::all_zeros(), 1),
+        ));
+
+        let json = decode_announcement(hex::encode(announcement.to_script().as_bytes())).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Announcement>(&json).unwrap(),
+            announcement
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use yuv_tx_check::verify_yuv_transaction_chain;
+use yuv_types::bundle::ProofBundle;
+
+use crate::{FfiError, Pixel};
+
+/// Output proven by the proof bundle.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct VerifiedOutput {
+    pub txid: String,
+    pub vout: u32,
+    /// Pixel of the output, `None` if it has only the satoshis.
+    pub pixel: Option<Pixel>,
+    /// JSON of the output's proof, e.g. to check that the output is owned by the wallet.
+    pub proof: String,
+}
+
+/// Verifies the hex encoded proof bundle without the node, see `exportproofbundle`, and returns
+/// the output it proves.
+///
+/// The bundle proves only that its transactions are valid YUV ones, whether they are mined and
+/// the output is unspent should be checked separately, e.g. with Esplora.
+#[uniffi::export]
+pub fn verify_proof_bundle(bundle: String) -> Result<VerifiedOutput, FfiError> {
+    let bundle = ProofBundle::from_hex(bundle.trim())
+        .map_err(|err| FfiError::InvalidBundle(err.to_string()))?;
+    let outpoint = bundle.outpoint;
+
+    if bundle.target_tx().is_none() {
+        return Err(FfiError::InvalidBundle(format!(
+            "the bundle doesn't end with the transaction of {}",
+            outpoint
+        )));
+    }
+
+    let state = verify_yuv_transaction_chain(&bundle.txs)
+        .map_err(|err| FfiError::InvalidBundle(err.to_string()))?;
+    let proof = state.get(&outpoint).ok_or_else(|| {
+        FfiError::InvalidBundle(format!(
+            "the output {} is spent by the bundle or has no tokens",
+            outpoint
+        ))
+    })?;
+
+    Ok(VerifiedOutput {
+        txid: outpoint.txid.to_string(),
+        vout: outpoint.vout,
+        pixel: (!proof.is_empty_pixelproof()).then(|| proof.pixel().into()),
+        proof: serde_json::to_string(proof).map_err(|err| FfiError::Wallet(err.to_string()))?,
+    })
+}
@@ -0,0 +1,37 @@
+use core::fmt;
+
+/// Error of the bindings, only its message gets to the Kotlin and Swift code.
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum FfiError {
+    /// The argument can't be parsed, e.g. the key or the amount.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    /// The proof bundle is malformed or its transactions are invalid.
+    #[error("Invalid proof bundle: {0}")]
+    InvalidBundle(String),
+
+    /// The wallet failed to sync, or to build or submit the transaction.
+    #[error("Wallet error: {0}")]
+    Wallet(String),
+}
+
+impl FfiError {
+    pub(crate) fn invalid_argument(name: &str, err: impl fmt::Display) -> Self {
+        Self::InvalidArgument(format!("{}: {}", name, err))
+    }
+}
+
+impl From<eyre::Report> for FfiError {
+    fn from(err: eyre::Report) -> Self {
+        // The causes are joined, as the bindings get only the message.
+        let message = err
+            .chain()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(": ");
+
+        Self::Wallet(message)
+    }
+}
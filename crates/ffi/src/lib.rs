@@ -0,0 +1,21 @@
+#![doc = include_str!("../README.md")]
+
+uniffi::setup_scaffolding!();
+
+mod address;
+pub use address::{p2wpkh_address, pixel_address};
+
+mod announcement;
+pub use announcement::decode_announcement;
+
+mod bundle;
+pub use bundle::{verify_proof_bundle, VerifiedOutput};
+
+mod error;
+pub use error::FfiError;
+
+mod types;
+pub use types::{Pixel, Recipient};
+
+mod wallet;
+pub use wallet::{Wallet, WalletConfig};
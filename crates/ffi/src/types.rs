@@ -0,0 +1,66 @@
+use core::str::FromStr;
+
+use bitcoin::{secp256k1, Network};
+use yuv_pixels::Chroma;
+
+use crate::FfiError;
+
+/// Pixel of the output: the amount and the chroma of the tokens.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Pixel {
+    /// Hex of the issuer's x-only key, or its Taproot address.
+    pub chroma: String,
+    /// Decimal amount of the tokens.
+    pub amount: String,
+}
+
+impl Pixel {
+    pub(crate) fn parse(&self) -> Result<yuv_pixels::Pixel, FfiError> {
+        Ok(yuv_pixels::Pixel::new(
+            parse_amount(&self.amount)?,
+            parse_chroma(&self.chroma)?,
+        ))
+    }
+}
+
+impl From<yuv_pixels::Pixel> for Pixel {
+    fn from(pixel: yuv_pixels::Pixel) -> Self {
+        Self {
+            chroma: pixel.chroma.to_string(),
+            amount: pixel.luma.amount.to_string(),
+        }
+    }
+}
+
+/// Recipient of the tokens in the issuance or the transfer.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Recipient {
+    /// Hex of the recipient's public key.
+    pub public_key: String,
+    /// Decimal amount of the tokens.
+    pub amount: String,
+    /// Satoshis in the recipient's output.
+    pub satoshis: u64,
+}
+
+pub(crate) fn parse_network(network: &str) -> Result<Network, FfiError> {
+    Network::from_str(network).map_err(|err| FfiError::invalid_argument("network", err))
+}
+
+pub(crate) fn parse_public_key(public_key: &str) -> Result<secp256k1::PublicKey, FfiError> {
+    secp256k1::PublicKey::from_str(public_key)
+        .map_err(|err| FfiError::invalid_argument("public key", err))
+}
+
+pub(crate) fn parse_amount(amount: &str) -> Result<u128, FfiError> {
+    u128::from_str(amount).map_err(|err| FfiError::invalid_argument("amount", err))
+}
+
+/// Parses the chroma from the hex of the x-only key or the Taproot address.
+pub(crate) fn parse_chroma(chroma: &str) -> Result<Chroma, FfiError> {
+    if let Ok(chroma) = Chroma::from_address(chroma) {
+        return Ok(chroma);
+    }
+
+    Chroma::from_str(chroma).map_err(|err| FfiError::invalid_argument("chroma", err))
+}
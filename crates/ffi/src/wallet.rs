@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bitcoin::PrivateKey;
+use ydk::bitcoin_provider::{BitcoinProviderConfig, EsploraConfig};
+use ydk::wallet::{StorageWallet, StorageWalletConfig, SyncOptions};
+use ydk::SubmitOptions;
+use yuv_types::YuvTransaction;
+
+use crate::types::{parse_amount, parse_chroma, parse_network, parse_public_key};
+use crate::{FfiError, Pixel, Recipient};
+
+/// Configuration of the [`Wallet`].
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WalletConfig {
+    /// Private key of the user in WIF.
+    pub private_key: String,
+    /// `bitcoin`, `testnet`, `signet` or `regtest`.
+    pub network: String,
+    /// URL of the Esplora API the Bitcoin wallet is synced with.
+    pub esplora_url: String,
+    /// Number of the consecutive unused addresses after which the Esplora sync stops.
+    pub stop_gap: u64,
+    /// URL of the YUV node RPC API.
+    pub yuv_url: String,
+    /// Directory where the wallet stores the transactions, e.g. the app's files directory.
+    pub storage_path: String,
+}
+
+/// Dev-kit wallet that stores the transactions in the local storage.
+#[derive(uniffi::Object)]
+pub struct Wallet(StorageWallet);
+
+#[uniffi::export(async_runtime = "tokio")]
+impl Wallet {
+    #[uniffi::constructor]
+    pub async fn new(config: WalletConfig) -> Result<Arc<Self>, FfiError> {
+        let network = parse_network(&config.network)?;
+        let privkey = PrivateKey::from_wif(&config.private_key)
+            .map_err(|err| FfiError::invalid_argument("private key", err))?;
+
+        let wallet = StorageWallet::from_storage_config(StorageWalletConfig {
+            inner: ydk::wallet::WalletConfig {
+                privkey,
+                network,
+                bitcoin_provider: BitcoinProviderConfig::Esplora(EsploraConfig {
+                    url: config.esplora_url,
+                    network,
+                    stop_gap: config.stop_gap as usize,
+                }),
+                yuv_url: config.yuv_url,
+            },
+            storage_path: PathBuf::from(config.storage_path),
+        })
+        .await?;
+
+        Ok(Arc::new(Self(wallet)))
+    }
+
+    /// Syncs the Bitcoin and YUV UTXOs of the wallet.
+    pub async fn sync(&self) -> Result<(), FfiError> {
+        self.0.sync(SyncOptions::default()).await?;
+
+        Ok(())
+    }
+
+    /// `P2WPKH` address of the wallet's key.
+    pub fn address(&self) -> Result<String, FfiError> {
+        Ok(self.0.address()?.to_string())
+    }
+
+    /// Address of the output with the `pixel` owned by the wallet.
+    pub fn pixel_address(&self, pixel: Pixel) -> Result<String, FfiError> {
+        Ok(self.0.pixel_address(pixel.parse()?)?.to_string())
+    }
+
+    /// Amounts of the tokens of each chroma owned by the wallet.
+    pub async fn balances(&self) -> Result<Vec<Pixel>, FfiError> {
+        let balances = self.0.balances().await?;
+
+        Ok(balances
+            .yuv
+            .into_iter()
+            .map(|(chroma, amount)| yuv_pixels::Pixel::new(amount, chroma).into())
+            .collect())
+    }
+
+    /// Builds and signs the issuance of the wallet's tokens to the `recipients`, returns the hex
+    /// of the YUV transaction to [`Wallet::submit`].
+    pub async fn build_issuance(&self, recipients: Vec<Recipient>) -> Result<String, FfiError> {
+        let mut builder = self.0.build_issuance(None)?;
+        for recipient in recipients {
+            builder.add_recipient(
+                &parse_public_key(&recipient.public_key)?,
+                parse_amount(&recipient.amount)?,
+                recipient.satoshis,
+            );
+        }
+
+        let tx = builder
+            .finish(&self.0.bitcoin_provider().blockchain())
+            .await?;
+
+        Ok(tx.hex())
+    }
+
+    /// Builds and signs the transfer of the tokens of the `chroma` to the `recipients`, returns
+    /// the hex of the YUV transaction to [`Wallet::submit`].
+    pub async fn build_transfer(
+        &self,
+        chroma: String,
+        recipients: Vec<Recipient>,
+    ) -> Result<String, FfiError> {
+        let chroma = parse_chroma(&chroma)?;
+
+        let mut builder = self.0.build_transfer()?;
+        for recipient in recipients {
+            builder.add_recipient(
+                chroma,
+                &parse_public_key(&recipient.public_key)?,
+                parse_amount(&recipient.amount)?,
+                recipient.satoshis,
+            );
+        }
+
+        let tx = builder
+            .finish(&self.0.bitcoin_provider().blockchain())
+            .await?;
+
+        Ok(tx.hex())
+    }
+
+    /// Provides the proofs of the hex encoded YUV transaction to the node and broadcasts it,
+    /// returns its id.
+    pub async fn submit(&self, tx: String) -> Result<String, FfiError> {
+        let tx = YuvTransaction::from_hex(tx)
+            .map_err(|err| FfiError::invalid_argument("transaction", err))?;
+
+        let submission = self.0.submit(tx, SubmitOptions::default()).await?;
+
+        Ok(submission.txid().to_string())
+    }
+}
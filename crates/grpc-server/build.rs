@@ -0,0 +1,12 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the vendored `protoc`, so the crate builds without the protobuf compiler installed.
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/yuv.proto"], &["proto"])?;
+
+    println!("cargo:rerun-if-changed=proto/yuv.proto");
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Conversions between the generated protobuf types and the JSON RPC ones.
+use std::str::FromStr;
+
+use bitcoin::consensus::{deserialize, serialize};
+use bitcoin::Txid;
+use jsonrpsee::types::{
+    error::{INVALID_PARAMS_CODE, INVALID_REQUEST_CODE},
+    ErrorObjectOwned,
+};
+use tonic::Status;
+use yuv_rpc_api::transactions::{
+    GetRawYuvTransactionResponseHex, YuvTransactionResponse, YuvTransactionStatus,
+    RESPONSE_TOO_LARGE_CODE, TX_PRUNED_CODE,
+};
+use yuv_types::YuvTransaction;
+
+use crate::proto;
+
+/// Converts the error of the JSON RPC method to the gRPC status with the same message.
+pub(crate) fn status_from_rpc_error(err: ErrorObjectOwned) -> Status {
+    let message = err.message().to_string();
+
+    match err.code() {
+        INVALID_REQUEST_CODE | INVALID_PARAMS_CODE => Status::invalid_argument(message),
+        RESPONSE_TOO_LARGE_CODE => Status::resource_exhausted(message),
+        TX_PRUNED_CODE => Status::not_found(message),
+        _ => Status::internal(message),
+    }
+}
+
+pub(crate) fn parse_txid(txid: &str) -> Result<Txid, Status> {
+    Txid::from_str(txid).map_err(|err| Status::invalid_argument(format!("Invalid txid: {err}")))
+}
+
+pub(crate) fn decode_yuv_tx(
+    yuv_tx: Option<proto::YuvTransaction>,
+) -> Result<YuvTransaction, Status> {
+    let yuv_tx = yuv_tx.ok_or_else(|| Status::invalid_argument("Transaction is missing"))?;
+
+    deserialize(&yuv_tx.raw)
+        .map_err(|err| Status::invalid_argument(format!("Invalid YUV transaction: {err}")))
+}
+
+pub(crate) fn encode_yuv_tx(yuv_tx: YuvTransactionResponse) -> proto::YuvTransaction {
+    proto::YuvTransaction {
+        raw: serialize(&YuvTransaction::from(yuv_tx)),
+    }
+}
+
+impl From<YuvTransactionStatus> for proto::TransactionStatus {
+    #[allow(deprecated)]
+    fn from(status: YuvTransactionStatus) -> Self {
+        match status {
+            YuvTransactionStatus::None => Self::None,
+            YuvTransactionStatus::Initialized => Self::Initialized,
+            YuvTransactionStatus::WaitingMined => Self::WaitingMined,
+            YuvTransactionStatus::Mined => Self::Mined,
+            YuvTransactionStatus::Attaching => Self::Attaching,
+            YuvTransactionStatus::Attached => Self::Attached,
+            YuvTransactionStatus::Pending => Self::Pending,
+            YuvTransactionStatus::ProvisionallyAttached => Self::ProvisionallyAttached,
+        }
+    }
+}
+
+impl From<GetRawYuvTransactionResponseHex> for proto::GetYuvTransactionResponse {
+    fn from(response: GetRawYuvTransactionResponseHex) -> Self {
+        Self {
+            status: proto::TransactionStatus::from(response.status).into(),
+            transaction: response.data.map(encode_yuv_tx),
+        }
+    }
+}
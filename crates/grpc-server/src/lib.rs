@@ -0,0 +1,98 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bitcoin_client::BitcoinRpcClient;
+use event_bus::EventBus;
+use tokio_util::sync::CancellationToken;
+
+use yuv_rpc_server::{
+    subscriptions::SubscriptionsController, transactions::TransactionsController,
+};
+use yuv_storage::{
+    AddressIndexStorage, BlockIndexerStorage, ChromaInfoStorage, FrozenTxsStorage,
+    InvalidTxsStorage, MempoolEntryStorage, MempoolStorage, PagesStorage, PruningStorage,
+    SupplyTreeStorage, TransactionsStorage, TransferHistoryStorage, TxDependentsStorage,
+    WalletProofsStorage,
+};
+use yuv_types::network::Network;
+
+use crate::proto::yuv_transactions_server::YuvTransactionsServer;
+use crate::transactions::TransactionsService;
+
+mod convert;
+pub mod transactions;
+
+/// Code generated from `proto/yuv.proto`.
+pub mod proto {
+    tonic::include_proto!("yuv.v1");
+}
+
+pub struct GrpcServerConfig {
+    /// Address at which the server will listen for incoming connections.
+    pub address: SocketAddr,
+    /// Max number of items to request/process per incoming request.
+    pub max_items_per_request: usize,
+    /// Network the node runs on.
+    pub network: Network,
+}
+
+/// Runs YUV Node's gRPC server.
+///
+/// The methods are served by the same [`TransactionsController`] as the JSON RPC ones, and the
+/// attached transactions are streamed from the `subscriptions` controller, which must be run by
+/// the caller, see [`SubscriptionsController::run`].
+pub async fn run_server<TS, SS>(
+    GrpcServerConfig {
+        address,
+        max_items_per_request,
+        network,
+    }: GrpcServerConfig,
+    txs_storage: TS,
+    state_storage: SS,
+    full_event_bus: EventBus,
+    bitcoin_client: Arc<BitcoinRpcClient>,
+    subscriptions: SubscriptionsController,
+    cancellation: CancellationToken,
+) -> eyre::Result<()>
+where
+    TS: TransactionsStorage
+        + PagesStorage
+        + SupplyTreeStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + TxDependentsStorage
+        + PruningStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    SS: FrozenTxsStorage
+        + ChromaInfoStorage
+        + MempoolEntryStorage
+        + MempoolStorage
+        + InvalidTxsStorage
+        + BlockIndexerStorage
+        + WalletProofsStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    let controller = TransactionsController::new(
+        txs_storage,
+        full_event_bus,
+        state_storage,
+        bitcoin_client,
+        max_items_per_request,
+        network,
+    );
+
+    let service = TransactionsService::new(controller, subscriptions);
+
+    tonic::transport::Server::builder()
+        .add_service(YuvTransactionsServer::new(service))
+        .serve_with_shutdown(address, cancellation.cancelled())
+        .await?;
+
+    Ok(())
+}
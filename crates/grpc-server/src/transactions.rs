@@ -0,0 +1,175 @@
+use std::pin::Pin;
+
+use bitcoin_client::BitcoinRpcClient;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    Stream, StreamExt,
+};
+use tonic::{Request, Response, Status};
+
+use yuv_rpc_api::transactions::YuvTransactionsRpcServer;
+use yuv_rpc_server::{
+    subscriptions::SubscriptionsController, transactions::TransactionsController,
+};
+use yuv_storage::{
+    AddressIndexStorage, BlockIndexerStorage, ChromaInfoStorage, FrozenTxsStorage,
+    InvalidTxsStorage, MempoolEntryStorage, MempoolStorage, PagesStorage, PruningStorage,
+    SupplyTreeStorage, TransactionsStorage, TransferHistoryStorage, TxDependentsStorage,
+    WalletProofsStorage,
+};
+
+use crate::convert::{decode_yuv_tx, encode_yuv_tx, parse_txid, status_from_rpc_error};
+use crate::proto::{self, yuv_transactions_server::YuvTransactions};
+
+/// gRPC service for transactions. Delegates the requests to the [`TransactionsController`] of
+/// the JSON RPC server, so both APIs behave the same.
+pub struct TransactionsService<TS, SS> {
+    controller: TransactionsController<TS, SS, BitcoinRpcClient>,
+    subscriptions: SubscriptionsController,
+}
+
+impl<TS, SS> TransactionsService<TS, SS> {
+    pub fn new(
+        controller: TransactionsController<TS, SS, BitcoinRpcClient>,
+        subscriptions: SubscriptionsController,
+    ) -> Self {
+        Self {
+            controller,
+            subscriptions,
+        }
+    }
+}
+
+type AttachedTransactionsStream =
+    Pin<Box<dyn Stream<Item = Result<proto::YuvTransaction, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl<TS, SS> YuvTransactions for TransactionsService<TS, SS>
+where
+    TS: TransactionsStorage
+        + PagesStorage
+        + SupplyTreeStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + TxDependentsStorage
+        + PruningStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    SS: FrozenTxsStorage
+        + ChromaInfoStorage
+        + MempoolEntryStorage
+        + MempoolStorage
+        + InvalidTxsStorage
+        + BlockIndexerStorage
+        + WalletProofsStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    async fn get_yuv_transaction(
+        &self,
+        request: Request<proto::GetYuvTransactionRequest>,
+    ) -> Result<Response<proto::GetYuvTransactionResponse>, Status> {
+        let txid = parse_txid(&request.into_inner().txid)?;
+
+        let response = self
+            .controller
+            .get_yuv_transaction(txid)
+            .await
+            .map_err(status_from_rpc_error)?;
+
+        Ok(Response::new(response.into()))
+    }
+
+    async fn get_list_yuv_transactions(
+        &self,
+        request: Request<proto::GetListYuvTransactionsRequest>,
+    ) -> Result<Response<proto::GetListYuvTransactionsResponse>, Status> {
+        let txids = request
+            .into_inner()
+            .txids
+            .iter()
+            .map(|txid| parse_txid(txid))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let transactions = self
+            .controller
+            .get_list_yuv_transactions(txids)
+            .await
+            .map_err(status_from_rpc_error)?;
+
+        Ok(Response::new(proto::GetListYuvTransactionsResponse {
+            transactions: transactions.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn list_yuv_transactions(
+        &self,
+        request: Request<proto::ListYuvTransactionsRequest>,
+    ) -> Result<Response<proto::ListYuvTransactionsResponse>, Status> {
+        let transactions = self
+            .controller
+            .list_yuv_transactions(request.into_inner().page)
+            .await
+            .map_err(status_from_rpc_error)?;
+
+        Ok(Response::new(proto::ListYuvTransactionsResponse {
+            transactions: transactions.into_iter().map(encode_yuv_tx).collect(),
+        }))
+    }
+
+    async fn provide_yuv_proof(
+        &self,
+        request: Request<proto::ProvideYuvProofRequest>,
+    ) -> Result<Response<proto::ProvideYuvProofResponse>, Status> {
+        let yuv_tx = decode_yuv_tx(request.into_inner().transaction)?;
+
+        let accepted = self
+            .controller
+            .provide_yuv_proof(yuv_tx)
+            .await
+            .map_err(status_from_rpc_error)?;
+
+        Ok(Response::new(proto::ProvideYuvProofResponse { accepted }))
+    }
+
+    async fn send_yuv_transaction(
+        &self,
+        request: Request<proto::SendYuvTransactionRequest>,
+    ) -> Result<Response<proto::SendYuvTransactionResponse>, Status> {
+        let request = request.into_inner();
+        let yuv_tx = decode_yuv_tx(request.transaction)?;
+
+        let sent = self
+            .controller
+            .send_yuv_tx(yuv_tx.hex(), request.max_burn_amount)
+            .await
+            .map_err(status_from_rpc_error)?;
+
+        Ok(Response::new(proto::SendYuvTransactionResponse { sent }))
+    }
+
+    type SubscribeAttachedTransactionsStream = AttachedTransactionsStream;
+
+    async fn subscribe_attached_transactions(
+        &self,
+        _request: Request<proto::SubscribeAttachedTransactionsRequest>,
+    ) -> Result<Response<Self::SubscribeAttachedTransactionsStream>, Status> {
+        let stream = BroadcastStream::new(self.subscriptions.attached_txs()).filter_map(|item| {
+            match item {
+                Ok(yuv_tx) => Some(Ok(encode_yuv_tx(yuv_tx))),
+                // Slow subscriber has missed some notifications, continue from the oldest
+                // available one.
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    tracing::warn!("Subscriber is lagging, skipped {skipped} notifications");
+                    None
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use bitcoin_client::{json::GetBlockTxResult, BitcoinRpcApi, BitcoinRpcClient};
@@ -8,39 +9,98 @@ use tracing::instrument;
 
 use super::{
     events::{FetchLoadedBlockEvent, IndexBlocksEvent, LoadBlockEvent},
-    loading_progress::LoadingProgress,
     worker::Worker,
 };
 
-/// Manager for loading blocks from Bitcoin network
+/// Manager for loading blocks from Bitcoin network.
+///
+/// Keeps a sliding window of blocks: up to `concurrency` blocks are loaded at the same time, and
+/// up to `window_size` blocks are either loading or waiting for the rest of the window. The
+/// blocks are received out of order, but delivered to the `Indexer` in order, one batch per
+/// window, see [`BlockWindow`].
 pub struct BlockLoader {
     /// Bitcoin RPC Client
     bitcoin_client: Arc<BitcoinRpcClient>,
-    /// The workers number, that will load blocks
-    workers_number: usize,
-    /// The size of the chunk that will be send to the `Indexer`
-    chunk_size: usize,
-    /// Loaded blocks of the chunk
-    loaded_blocks: Vec<GetBlockTxResult>,
+    /// Number of workers, i.e. concurrent requests, that will load blocks
+    concurrency: usize,
+    /// Blocks that are loading or not yet delivered to the `Indexer`
+    window: BlockWindow,
     /// Task tracker for workers
     task_tracker: TaskTracker,
-    /// Loading progress, contains loading process of the chunk
-    loading_progress: LoadingProgress,
+}
+
+/// Window of the blocks that are loaded out of order and delivered to the `Indexer` in order.
+struct BlockWindow {
+    /// The maximum number of blocks in the window
+    size: usize,
+    /// Loaded blocks that wait for the rest of the window to be loaded, by height
+    loaded_blocks: BTreeMap<usize, GetBlockTxResult>,
+    /// Height of the next block to deliver to the `Indexer`, i.e. the start of the window
+    next_height: usize,
+}
+
+impl BlockWindow {
+    fn new(size: usize) -> Self {
+        Self {
+            size: size.max(1),
+            loaded_blocks: BTreeMap::new(),
+            next_height: 0,
+        }
+    }
+
+    /// Starts the window from the `height`, e.g. on the restart from the last indexed block. The
+    /// blocks loaded before are dropped.
+    fn restart(&mut self, height: usize) {
+        self.next_height = height;
+        self.loaded_blocks.clear();
+    }
+
+    /// Returns the height of the last block of the window, which is not higher than the
+    /// `last_height`.
+    fn end(&self, last_height: usize) -> usize {
+        last_height.min(self.next_height + self.size - 1)
+    }
+
+    fn insert(&mut self, block: GetBlockTxResult) {
+        self.loaded_blocks.insert(block.block_data.height, block);
+    }
+
+    /// Returns all the blocks of the window in order and moves the window forward, if all of
+    /// them are loaded.
+    fn take_window(&mut self, last_height: usize) -> Option<Vec<GetBlockTxResult>> {
+        let end = self.end(last_height);
+        let loaded = self.loaded_blocks.range(self.next_height..=end).count();
+        if end < self.next_height || loaded < end + 1 - self.next_height {
+            return None;
+        }
+
+        Some(self.take_loaded())
+    }
+
+    /// Returns the sequential loaded blocks from the start of the window and moves the window
+    /// forward, so the blocks after a missing one are never delivered.
+    fn take_loaded(&mut self) -> Vec<GetBlockTxResult> {
+        let mut blocks = Vec::new();
+        while let Some(block) = self.loaded_blocks.remove(&self.next_height) {
+            blocks.push(block);
+            self.next_height += 1;
+        }
+
+        blocks
+    }
 }
 
 impl BlockLoader {
     pub fn new(
         bitcoin_client: Arc<BitcoinRpcClient>,
-        workers_number: usize,
-        chunk_size: usize,
+        concurrency: usize,
+        window_size: usize,
     ) -> Self {
         Self {
             bitcoin_client,
-            workers_number,
-            chunk_size,
-            loaded_blocks: Vec::with_capacity(chunk_size),
+            concurrency: concurrency.max(1),
+            window: BlockWindow::new(window_size),
             task_tracker: TaskTracker::new(),
-            loading_progress: LoadingProgress::default(),
         }
     }
 }
@@ -53,7 +113,7 @@ impl BlockLoader {
         time_to_sleep: u64,
         cancellation: CancellationToken,
     ) {
-        for _ in 0..self.workers_number {
+        for _ in 0..self.concurrency {
             let worker = Worker::new(
                 self.bitcoin_client.clone(),
                 loaded_block_sender.clone(),
@@ -67,8 +127,8 @@ impl BlockLoader {
         self.task_tracker.close();
     }
 
-    /// Handles loaded of failed blocks. In case of loaded block savess it in `loaded_blocks`, in case of
-    /// failed block sends it to `Worker` to load it again.
+    /// Handles loaded of failed blocks. In case of loaded block saves it in the `window`, in
+    /// case of failed block sends it to `Worker` to load it again.
     #[instrument(skip_all)]
     async fn handle_fetch_event(
         &mut self,
@@ -78,8 +138,7 @@ impl BlockLoader {
         match event {
             FetchLoadedBlockEvent::Loaded(block) => {
                 tracing::trace!("Received block with height {}", block.block_data.height);
-                self.loaded_blocks.push(*block);
-                self.loading_progress.update_received_blocks();
+                self.window.insert(*block);
             }
             FetchLoadedBlockEvent::FailedBlock(block_height) => {
                 tracing::debug!("Resend failed block with height: {}", block_height);
@@ -92,70 +151,37 @@ impl BlockLoader {
         Ok(())
     }
 
-    /// Sends the height of block, which `Workers` should load
-    #[instrument(skip_all)]
-    async fn send_load_blocks(
-        &mut self,
-        load_block_sender: &flume::Sender<LoadBlockEvent>,
-        blocks_chunk: &[usize],
-    ) -> eyre::Result<()> {
-        for block_height in blocks_chunk {
-            tracing::trace!("Send block to workers: {}", block_height);
-            load_block_sender
-                .send_async(LoadBlockEvent::LoadBlock(*block_height))
-                .await?;
-        }
-
-        Ok(())
-    }
-
-    /// Handles loaded blocks. Stops execution when `received_block` is equal `chunk_size`
-    async fn handle_loaded_blocks(
+    /// Sends the blocks of the window to the `Indexer` in one batch once all of them are loaded,
+    /// and moves the window forward.
+    async fn send_loaded_window(
         &mut self,
-        loaded_block_listener: &mut mpsc::Receiver<FetchLoadedBlockEvent>,
-        load_block_sender: flume::Sender<LoadBlockEvent>,
-        chunk_size: usize,
+        sender_to_indexer: &mpsc::Sender<IndexBlocksEvent>,
+        last_height: usize,
     ) -> eyre::Result<()> {
-        while let Some(event) = loaded_block_listener.recv().await {
-            self.handle_fetch_event(event, &load_block_sender).await?;
+        let Some(blocks) = self.window.take_window(last_height) else {
+            return Ok(());
+        };
 
-            if self.loading_progress.finish_chunk(&chunk_size) {
-                self.loading_progress.next_chunk();
-                break;
-            }
-        }
+        sender_to_indexer
+            .send(IndexBlocksEvent::LoadedBlocks(blocks))
+            .await?;
 
         Ok(())
     }
 
-    /// Sends loaded blocks to `Indexer` before it has sorted the loaded blocks.
+    /// Sends the sequential loaded blocks from the start of the window to the `Indexer`, so it
+    /// restarts from the first missing block.
     async fn send_loaded_blocks(
         &mut self,
-        sender_to_indexer: mpsc::Sender<IndexBlocksEvent>,
+        sender_to_indexer: &mpsc::Sender<IndexBlocksEvent>,
     ) -> eyre::Result<()> {
-        self.loaded_blocks
-            .sort_by(|a, b| a.block_data.height.cmp(&b.block_data.height));
-
-        let blocks_not_sequantial_index =
-            self.loaded_blocks
-                .windows(2)
-                .enumerate()
-                .find_map(|(index, blocks)| {
-                    if blocks[0].block_data.height + 1 != blocks[1].block_data.height {
-                        Some(index)
-                    } else {
-                        None
-                    }
-                });
-
-        if let Some(index) = blocks_not_sequantial_index {
-            self.loaded_blocks.truncate(index + 1);
+        let blocks = self.window.take_loaded();
+        if blocks.is_empty() {
+            return Ok(());
         }
 
         sender_to_indexer
-            .send(IndexBlocksEvent::LoadedBlocks(
-                self.loaded_blocks.drain(..).collect(),
-            ))
+            .send(IndexBlocksEvent::LoadedBlocks(blocks))
             .await?;
 
         Ok(())
@@ -176,8 +202,9 @@ impl BlockLoader {
         Ok(())
     }
 
-    /// Handles new blocks from `Bitcoin` network. When `BlockLoader` finished loading new blocks
-    /// it sends `FinishLoading` them to `Indexer` and stops workers.
+    /// Handles new blocks from `Bitcoin` network. Requests the blocks of the window from the
+    /// `Workers`, and delivers them to the `Indexer` in order, one batch per window. When
+    /// `BlockLoader` finished loading new blocks it sends `FinishLoading` to `Indexer`.
     async fn handle_new_blocks(
         &mut self,
         load_block_sender: &flume::Sender<LoadBlockEvent>,
@@ -185,22 +212,27 @@ impl BlockLoader {
         loaded_block_listener: &mut mpsc::Receiver<FetchLoadedBlockEvent>,
         start_height: usize,
     ) -> eyre::Result<()> {
-        let confirmed_height = self.bitcoin_client.get_block_count().await?;
+        let confirmed_height = self.bitcoin_client.get_block_count().await? as usize;
 
-        let blocks_to_load = (start_height..=(confirmed_height as usize)).collect::<Vec<usize>>();
+        self.window.restart(start_height);
+        let mut next_to_request = start_height;
 
-        for blocks_chunk in blocks_to_load.chunks(self.chunk_size) {
-            self.send_load_blocks(load_block_sender, blocks_chunk)
-                .await?;
+        while self.window.next_height <= confirmed_height {
+            while next_to_request <= self.window.end(confirmed_height) {
+                tracing::trace!("Send block to workers: {}", next_to_request);
+                load_block_sender
+                    .send_async(LoadBlockEvent::LoadBlock(next_to_request))
+                    .await?;
+                next_to_request += 1;
+            }
 
-            self.handle_loaded_blocks(
-                loaded_block_listener,
-                load_block_sender.clone(),
-                blocks_chunk.len(),
-            )
-            .await?;
+            let Some(event) = loaded_block_listener.recv().await else {
+                eyre::bail!("All the workers are stopped");
+            };
 
-            self.send_loaded_blocks(sender_to_indexer.clone()).await?;
+            self.handle_fetch_event(event, load_block_sender).await?;
+            self.send_loaded_window(&sender_to_indexer, confirmed_height)
+                .await?;
         }
 
         sender_to_indexer
@@ -218,11 +250,13 @@ impl BlockLoader {
         time_to_sleep: u64,
         cancellation: CancellationToken,
     ) -> eyre::Result<()> {
+        // There are never more than `window_size` blocks requested at once, so the channels
+        // don't block the loader.
         let (load_block_sender, load_block_receiver) =
-            flume::bounded::<LoadBlockEvent>(self.chunk_size);
+            flume::bounded::<LoadBlockEvent>(self.window.size);
 
         let (loaded_block_sender, mut loaded_block_listener) =
-            mpsc::channel::<FetchLoadedBlockEvent>(self.chunk_size);
+            mpsc::channel::<FetchLoadedBlockEvent>(self.window.size);
 
         self.run_workers(
             load_block_receiver,
@@ -232,17 +266,17 @@ impl BlockLoader {
         );
 
         select! {
-            _ = self.handle_new_blocks(
+            result = self.handle_new_blocks(
                 &load_block_sender,
                 sender_to_indexer.clone(),
                 &mut loaded_block_listener,
                 load_from_height,
-            ) => {}
+            ) => result?,
 
             _ = cancellation.cancelled() => {
                 tracing::info!("Block loader cancelled. Finishing receiving blocks");
                 self.handle_remained_blocks(&mut loaded_block_listener, &load_block_sender).await?;
-                self.send_loaded_blocks(sender_to_indexer.clone()).await?;
+                self.send_loaded_blocks(&sender_to_indexer).await?;
 
                 sender_to_indexer
                     .send(IndexBlocksEvent::Cancelled)
@@ -261,3 +295,98 @@ impl BlockLoader {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash;
+    use bitcoin::{BlockHash, TxMerkleNode};
+    use bitcoin_client::json::BlockData;
+
+    use super::*;
+
+    fn block(height: usize) -> GetBlockTxResult {
+        GetBlockTxResult {
+            block_data: BlockData {
+                hash: BlockHash::all_zeros(),
+                confirmations: 1,
+                size: 0,
+                strippedsize: None,
+                weight: 0,
+                height,
+                version: 1,
+                version_hex: None,
+                merkleroot: TxMerkleNode::all_zeros(),
+                time: 0,
+                mediantime: None,
+                nonce: 0,
+                bits: String::new(),
+                difficulty: 0.0,
+                chainwork: Vec::new(),
+                n_tx: 0,
+                previousblockhash: None,
+                nextblockhash: None,
+            },
+            tx: Vec::new(),
+        }
+    }
+
+    fn heights(blocks: Option<Vec<GetBlockTxResult>>) -> Option<Vec<usize>> {
+        blocks.map(|blocks| blocks.iter().map(|block| block.block_data.height).collect())
+    }
+
+    #[test]
+    fn test_window_is_delivered_in_order_once_loaded() {
+        const LAST_HEIGHT: usize = 6;
+
+        let mut window = BlockWindow::new(3);
+        window.restart(0);
+        assert_eq!(window.end(LAST_HEIGHT), 2);
+
+        window.insert(block(2));
+        window.insert(block(0));
+        assert_eq!(heights(window.take_window(LAST_HEIGHT)), None);
+
+        window.insert(block(1));
+        assert_eq!(
+            heights(window.take_window(LAST_HEIGHT)),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(window.end(LAST_HEIGHT), 5);
+
+        for height in [5, 4, 3] {
+            window.insert(block(height));
+        }
+        assert_eq!(
+            heights(window.take_window(LAST_HEIGHT)),
+            Some(vec![3, 4, 5])
+        );
+
+        // The last window ends at the last block.
+        assert_eq!(window.end(LAST_HEIGHT), 6);
+        window.insert(block(6));
+        assert_eq!(heights(window.take_window(LAST_HEIGHT)), Some(vec![6]));
+    }
+
+    #[test]
+    fn test_restart_continues_from_first_missing_block() {
+        let mut window = BlockWindow::new(4);
+        window.restart(10);
+
+        // The loading is cancelled before the block 11 is loaded, so only the blocks before it
+        // are delivered, and the indexing is restarted from it.
+        window.insert(block(12));
+        window.insert(block(10));
+        assert_eq!(heights(Some(window.take_loaded())), Some(vec![10]));
+        assert_eq!(window.next_height, 11);
+
+        window.restart(window.next_height);
+        assert_eq!(window.end(100), 14);
+        for height in [14, 13, 11] {
+            window.insert(block(height));
+        }
+        assert_eq!(heights(window.take_window(100)), None);
+
+        window.insert(block(12));
+        assert_eq!(heights(window.take_window(100)), Some(vec![11, 12, 13, 14]));
+    }
+}
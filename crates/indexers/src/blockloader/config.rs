@@ -2,22 +2,23 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Clone)]
 pub struct BlockLoaderConfig {
-    /// Number of workers which will load blocks
-    #[serde(default = "default_workers_number")]
-    pub workers_number: usize,
-    /// The size of chunk which contains heights of blocks to load
-    #[serde(default = "default_buffer_size")]
-    pub chunk_size: usize,
+    /// Number of concurrent `getblock` requests, i.e. the number of workers which load blocks
+    #[serde(default = "default_concurrency", alias = "workers_number")]
+    pub concurrency: usize,
+    /// The maximum number of blocks that are requested or loaded but not yet delivered to the
+    /// indexer. Blocks are loaded out of order within the window, and delivered in order
+    #[serde(default = "default_window_size", alias = "chunk_size")]
+    pub window_size: usize,
     /// Sleep the worker for seconds when the worker exceeds the rate limit
     #[serde(default = "default_worker_time_sleep")]
     pub worker_time_sleep: usize,
 }
 
-fn default_workers_number() -> usize {
+fn default_concurrency() -> usize {
     10
 }
 
-fn default_buffer_size() -> usize {
+fn default_window_size() -> usize {
     1000
 }
 
@@ -28,8 +29,8 @@ fn default_worker_time_sleep() -> usize {
 impl Default for BlockLoaderConfig {
     fn default() -> Self {
         Self {
-            workers_number: default_workers_number(),
-            chunk_size: default_buffer_size(),
+            concurrency: default_concurrency(),
+            window_size: default_window_size(),
             worker_time_sleep: default_worker_time_sleep(),
         }
     }
@@ -6,7 +6,5 @@ pub use events::IndexBlocksEvent;
 
 mod worker;
 
-mod loading_progress;
-
 mod config;
 pub use config::BlockLoaderConfig;
@@ -0,0 +1,777 @@
+//! Checkpoints for the fast initial sync of the node.
+//!
+//! A [`Checkpoint`] is a snapshot of the attached transactions (in the order of the pages) and of
+//! the chromas' state at some block. A node with the empty storage can import a checkpoint signed
+//! by one of the trusted signers instead of indexing the history from the YUV genesis block, and
+//! continue indexing from the next block after the checkpoint.
+//!
+//! The address index and the transfer history are rebuilt from the imported transactions if
+//! they're enabled. The history needs the heights of the blocks the transactions are mined in, so
+//! the checkpoint without them is refused when the history index is enabled.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::secp256k1::{
+    ecdsa::Signature, Message, PublicKey, Secp256k1, SecretKey, XOnlyPublicKey,
+};
+use bitcoin::{BlockHash, OutPoint};
+use bitcoin_client::BitcoinRpcApi;
+use eyre::{bail, eyre, Context};
+use serde::{Deserialize, Serialize};
+use yuv_pixels::Chroma;
+use yuv_storage::{
+    AddressIndexStorage, AttachStorage, BlockIndexerStorage, ChromaInfoStorage, DelegatesStorage,
+    FrozenPubkeysStorage, FrozenTxsStorage, IsIndexedStorage, PagesNumberStorage, PagesStorage,
+    SupplyTreeStorage, TransactionsStorage, TransferHistoryEntry, TransferHistoryStorage,
+    TxPosition,
+};
+use yuv_types::{
+    announcements::{ChromaAnnouncement, ChromaInfo},
+    owners::{balance_deltas, owned_utxos},
+    supply::{supply_inputs, supply_outputs},
+    Announcement, YuvTransaction, YuvTxType,
+};
+
+/// Parameters of the checkpoint to import before the initial indexing.
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointParams {
+    /// Path to the file with the [`SignedCheckpoint`].
+    pub path: PathBuf,
+    /// Expected hash of the checkpoint. If set, the checkpoint with a different hash is rejected
+    /// even if it's signed by the trusted signer.
+    pub hash: Option<sha256::Hash>,
+    /// Public keys of the signers whose checkpoints are trusted.
+    pub trusted_signers: Vec<PublicKey>,
+    /// Whether to rebuild the index of the unspent outputs by the keys of their owners.
+    pub address_index: bool,
+    /// Whether to rebuild the transfer history of the owners.
+    pub history_index: bool,
+}
+
+/// Snapshot of the node's state at the block with `block_hash`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Height of the last block included in the checkpoint.
+    pub block_height: usize,
+    /// Hash of the last block included in the checkpoint.
+    pub block_hash: BlockHash,
+    /// Attached transactions in the order of the pages.
+    pub txs: Vec<YuvTransaction>,
+    /// Positions of the `txs` in the chain. The transactions paged before their positions were
+    /// recorded have none.
+    #[serde(default)]
+    pub positions: Vec<Option<TxPosition>>,
+    /// State of the chromas the transactions are related to.
+    pub chromas: Vec<(Chroma, ChromaInfo)>,
+}
+
+impl Checkpoint {
+    /// Collect the checkpoint from the storages of the stopped node.
+    pub async fn export<TS, SS>(txs_storage: &TS, state_storage: &SS) -> eyre::Result<Self>
+    where
+        TS: TransactionsStorage + PagesStorage + PagesNumberStorage + AttachStorage + Send + Sync,
+        SS: BlockIndexerStorage + ChromaInfoStorage + Send + Sync,
+    {
+        let block_hash = state_storage
+            .get_last_indexed_hash()
+            .await?
+            .ok_or_else(|| eyre!("The node hasn't indexed any blocks yet"))?;
+        let block_height = state_storage
+            .get_last_indexed_height()
+            .await?
+            .ok_or_else(|| eyre!("The height of the last indexed block is missing"))?;
+
+        let last_page_num = txs_storage.get_pages_number().await?.unwrap_or_default();
+
+        let mut txs = Vec::new();
+        let mut positions = Vec::new();
+        for page_num in 0..=last_page_num {
+            let page = txs_storage
+                .get_page_by_num(page_num)
+                .await?
+                .unwrap_or_default();
+
+            for txid in page {
+                let yuv_tx = txs_storage
+                    .get_yuv_tx(&txid)
+                    .await?
+                    .ok_or_else(|| eyre!("Paged tx {} is not present in the storage", txid))?;
+
+                txs.push(yuv_tx);
+                positions.push(txs_storage.get_tx_position(&txid).await?);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut chromas = Vec::new();
+        for chroma in txs.iter().flat_map(related_chromas) {
+            if !seen.insert(chroma) {
+                continue;
+            }
+
+            if let Some(chroma_info) = state_storage.get_chroma_info(&chroma).await? {
+                chromas.push((chroma, chroma_info));
+            }
+        }
+
+        Ok(Self {
+            block_height,
+            block_hash,
+            txs,
+            positions,
+            chromas,
+        })
+    }
+
+    /// Sign the checkpoint with the `secret_key`.
+    pub fn sign(&self, secret_key: &SecretKey) -> eyre::Result<SignedCheckpoint> {
+        let mut data = Vec::new();
+        ciborium::into_writer(self, &mut data).wrap_err("Failed to encode the checkpoint")?;
+
+        let ctx = Secp256k1::signing_only();
+        let signature = ctx.sign_ecdsa(
+            &Message::from_hashed_data::<sha256::Hash>(&data),
+            secret_key,
+        );
+
+        Ok(SignedCheckpoint {
+            signer: secret_key.public_key(&ctx),
+            signature,
+            data,
+        })
+    }
+}
+
+/// Encoded [`Checkpoint`] with the signature of its hash.
+///
+/// The signature is made over the encoded bytes, so the checkpoint is verified before it's
+/// decoded, and the hash doesn't depend on the encoding of the decoded values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedCheckpoint {
+    /// Public key of the signer.
+    pub signer: PublicKey,
+    /// Signature of the `data`'s SHA256 hash.
+    pub signature: Signature,
+    /// CBOR encoded [`Checkpoint`].
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl SignedCheckpoint {
+    /// Read the signed checkpoint from the file.
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let file = std::fs::File::open(path)
+            .wrap_err_with(|| format!("Failed to open the checkpoint {}", path.display()))?;
+
+        ciborium::from_reader(std::io::BufReader::new(file)).wrap_err("Invalid checkpoint file")
+    }
+
+    /// Write the signed checkpoint to the file.
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        let file = std::fs::File::create(path)
+            .wrap_err_with(|| format!("Failed to create the checkpoint {}", path.display()))?;
+
+        ciborium::into_writer(self, std::io::BufWriter::new(file))
+            .wrap_err("Failed to write the checkpoint")
+    }
+
+    /// Returns the SHA256 hash of the encoded checkpoint.
+    pub fn hash(&self) -> sha256::Hash {
+        sha256::Hash::hash(&self.data)
+    }
+
+    /// Check that the checkpoint is signed by one of the trusted signers and matches the expected
+    /// hash, and decode it.
+    pub fn verify(&self, params: &CheckpointParams) -> eyre::Result<Checkpoint> {
+        if !params.trusted_signers.contains(&self.signer) {
+            bail!("Checkpoint signer {} is not trusted", self.signer);
+        }
+
+        if let Some(expected_hash) = params.hash {
+            let hash = self.hash();
+            if hash != expected_hash {
+                bail!(
+                    "Checkpoint hash mismatch: expected {}, got {}",
+                    expected_hash,
+                    hash
+                );
+            }
+        }
+
+        Secp256k1::verification_only()
+            .verify_ecdsa(
+                &Message::from_hashed_data::<sha256::Hash>(&self.data),
+                &self.signature,
+                &self.signer,
+            )
+            .wrap_err("Invalid checkpoint signature")?;
+
+        ciborium::from_reader(self.data.as_slice()).wrap_err("Failed to decode the checkpoint")
+    }
+}
+
+/// Import the checkpoint from [`CheckpointParams`] if the node hasn't indexed any blocks yet.
+///
+/// The checkpoint is accepted only if it's signed by the trusted signer and its block is in the
+/// best chain of the Bitcoin node, and if it has the heights of all the transactions that change
+/// the balances when the history index is enabled. Returns the hash of the imported checkpoint,
+/// or `None` if the storage is already indexed.
+pub async fn import_checkpoint<TS, SS, BC>(
+    params: &CheckpointParams,
+    bitcoin_client: &BC,
+    txs_storage: &TS,
+    state_storage: &SS,
+    tx_per_page: u64,
+) -> eyre::Result<Option<sha256::Hash>>
+where
+    TS: TransactionsStorage
+        + PagesStorage
+        + PagesNumberStorage
+        + SupplyTreeStorage
+        + AttachStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + Send
+        + Sync,
+    SS: BlockIndexerStorage
+        + IsIndexedStorage
+        + ChromaInfoStorage
+        + FrozenTxsStorage
+        + FrozenPubkeysStorage
+        + DelegatesStorage
+        + Send
+        + Sync,
+    BC: BitcoinRpcApi + Send + Sync,
+{
+    if state_storage.get_last_indexed_hash().await?.is_some() {
+        tracing::info!("Storage is already indexed, skipping the checkpoint");
+
+        return Ok(None);
+    }
+
+    let signed_checkpoint = SignedCheckpoint::read(&params.path)?;
+    let hash = signed_checkpoint.hash();
+    let checkpoint = signed_checkpoint.verify(params)?;
+
+    let best_chain_hash = bitcoin_client
+        .get_block_hash(checkpoint.block_height as u64)
+        .await?;
+    if best_chain_hash != checkpoint.block_hash {
+        bail!(
+            "Checkpoint block {} at height {} is not in the best chain",
+            checkpoint.block_hash,
+            checkpoint.block_height
+        );
+    }
+
+    let positions = (0..checkpoint.txs.len())
+        .map(|index| checkpoint.positions.get(index).copied().flatten())
+        .collect::<Vec<_>>();
+
+    // The history is ordered by the heights, and the transactions with no height are skipped
+    // as unmined by the controller, so they would be missing from the history.
+    if params.history_index {
+        let unknown_height = checkpoint
+            .txs
+            .iter()
+            .zip(&positions)
+            .find(|(yuv_tx, position)| position.is_none() && !balance_deltas(yuv_tx).is_empty());
+
+        if let Some((yuv_tx, _)) = unknown_height {
+            bail!(
+                "Checkpoint has no height of the tx {}, so the transfer history can't be rebuilt",
+                yuv_tx.bitcoin_tx.txid()
+            );
+        }
+    }
+
+    // All the outputs are added before the spent ones are removed, so the supply trees don't
+    // depend on the order of the transactions.
+    let mut supply_utxos: HashMap<Chroma, BTreeMap<OutPoint, u128>> = HashMap::new();
+    for yuv_tx in &checkpoint.txs {
+        for (outpoint, pixel) in supply_outputs(yuv_tx) {
+            supply_utxos
+                .entry(pixel.chroma)
+                .or_default()
+                .insert(outpoint, pixel.luma.amount);
+        }
+    }
+    for yuv_tx in &checkpoint.txs {
+        for (outpoint, pixel) in supply_inputs(yuv_tx) {
+            if let Some(utxos) = supply_utxos.get_mut(&pixel.chroma) {
+                utxos.remove(&outpoint);
+            }
+        }
+    }
+
+    let address_utxos = if params.address_index {
+        owned_utxos(&checkpoint.txs)
+    } else {
+        BTreeMap::new()
+    };
+
+    let mut history: HashMap<(Chroma, XOnlyPublicKey), Vec<TransferHistoryEntry>> = HashMap::new();
+    if params.history_index {
+        for (yuv_tx, position) in checkpoint.txs.iter().zip(&positions) {
+            let Some(position) = position else {
+                continue;
+            };
+
+            for (key, delta) in balance_deltas(yuv_tx) {
+                history.entry(key).or_default().push(TransferHistoryEntry {
+                    height: position.block_height,
+                    txid: yuv_tx.bitcoin_tx.txid(),
+                    delta,
+                });
+            }
+        }
+    }
+
+    let mut txids = Vec::with_capacity(checkpoint.txs.len());
+    let mut versions: HashMap<Chroma, Vec<ChromaAnnouncement>> = HashMap::new();
+    for (yuv_tx, position) in checkpoint.txs.into_iter().zip(positions) {
+        let txid = yuv_tx.bitcoin_tx.txid();
+
+        // The checkpoint has only the last version of the chroma announcement, so the previous
+        // ones are rebuilt from the announcements. Only the first chroma announcement is applied,
+        // as in the checker.
+        match &yuv_tx.tx_type {
+            YuvTxType::Announcement(Announcement::Chroma(announcement)) => {
+                versions
+                    .entry(announcement.chroma)
+                    .or_insert_with(|| vec![announcement.clone()]);
+            }
+            YuvTxType::Announcement(Announcement::UpdateChroma(update)) => {
+                if let Some(chroma_versions) = versions.get_mut(&update.chroma) {
+                    let last = &chroma_versions[chroma_versions.len() - 1];
+                    chroma_versions.push(update.apply(last));
+                }
+            }
+            _ => {}
+        }
+
+        if let YuvTxType::Announcement(Announcement::Freeze(freeze)) = &yuv_tx.tx_type {
+            let freeze_outpoint = freeze.freeze_outpoint();
+
+            // Only the first freeze of the outpoint is applied, as in the checker, unless it has
+            // expired by the checkpoint's block.
+            let is_frozen = state_storage
+                .get_frozen_tx(&freeze_outpoint)
+                .await?
+                .is_some_and(|entry| !entry.is_expired(checkpoint.block_height));
+            if !is_frozen {
+                state_storage
+                    .put_frozen_tx(
+                        &freeze_outpoint,
+                        txid,
+                        freeze.chroma,
+                        freeze.amount,
+                        freeze.expiry_height,
+                    )
+                    .await?;
+            }
+        }
+
+        // The transactions are in the order they were attached, so the last freeze or unfreeze
+        // of the key wins, as in the checker.
+        if let YuvTxType::Announcement(Announcement::FreezePubkey(freeze)) = &yuv_tx.tx_type {
+            if freeze.unfreeze {
+                state_storage
+                    .delete_frozen_pubkey(&freeze.chroma, &freeze.pubkey)
+                    .await?;
+            } else if state_storage
+                .get_frozen_pubkey(&freeze.chroma, &freeze.pubkey)
+                .await?
+                .is_none()
+            {
+                state_storage
+                    .put_frozen_pubkey(&freeze.chroma, &freeze.pubkey, txid)
+                    .await?;
+            }
+        }
+
+        // The delegations are replaced by the following ones, so the last one wins.
+        if let YuvTxType::Announcement(Announcement::Delegate(delegate)) = &yuv_tx.tx_type {
+            if delegate.is_revoke() {
+                state_storage
+                    .delete_delegate(&delegate.chroma, &delegate.delegate)
+                    .await?;
+            } else {
+                state_storage
+                    .put_delegate(
+                        &delegate.chroma,
+                        &delegate.delegate,
+                        delegate.capabilities,
+                        txid,
+                    )
+                    .await?;
+            }
+        }
+
+        txs_storage.put_yuv_tx(yuv_tx).await?;
+        if let Some(position) = position {
+            txs_storage.put_tx_position(&txid, position).await?;
+        }
+        txids.push(txid);
+    }
+
+    let tx_per_page = tx_per_page.max(1) as usize;
+    let mut pages_number = 0;
+    for (page_num, page) in txids.chunks(tx_per_page).enumerate() {
+        txs_storage.put_page(page_num as u64, page.to_vec()).await?;
+        pages_number += 1;
+    }
+    txs_storage
+        .put_pages_number(pages_number.saturating_sub(1))
+        .await?;
+
+    for (chroma, utxos) in supply_utxos {
+        txs_storage.put_supply_utxos(&chroma, utxos).await?;
+    }
+
+    for (owner, utxos) in address_utxos {
+        txs_storage.put_address_utxos(&owner, utxos).await?;
+    }
+    if params.address_index {
+        txs_storage.put_address_index_built().await?;
+    }
+
+    for ((chroma, owner), mut entries) in history {
+        // The sort is stable, so the transactions of the same block keep the order of the pages.
+        entries.sort_by_key(|entry| entry.height);
+        txs_storage
+            .put_transfer_history(&chroma, &owner, entries)
+            .await?;
+    }
+
+    for (chroma, chroma_versions) in versions {
+        for (version, announcement) in chroma_versions.into_iter().enumerate() {
+            state_storage
+                .put_chroma_announcement_version(&chroma, version as u32, announcement)
+                .await?;
+        }
+    }
+
+    for (chroma, chroma_info) in &checkpoint.chromas {
+        state_storage
+            .put_chroma_info(
+                chroma,
+                chroma_info.announcement.clone(),
+                chroma_info.total_supply,
+                chroma_info.total_burned,
+                chroma_info.announcement_version,
+                chroma_info.owner.clone(),
+            )
+            .await?;
+    }
+
+    state_storage
+        .put_last_indexed_hash(checkpoint.block_hash)
+        .await?;
+    state_storage
+        .put_last_indexed_height(checkpoint.block_height)
+        .await?;
+    state_storage.put_is_indexed().await?;
+
+    tracing::info!(
+        %hash,
+        height = checkpoint.block_height,
+        block_hash = %checkpoint.block_hash,
+        txs_number = txids.len(),
+        "Imported the checkpoint"
+    );
+
+    Ok(Some(hash))
+}
+
+/// Returns the chromas whose [`ChromaInfo`] could be changed by the transaction.
+pub fn related_chromas(yuv_tx: &YuvTransaction) -> Vec<Chroma> {
+    match &yuv_tx.tx_type {
+        YuvTxType::Issue { announcement, .. } => vec![announcement.chroma],
+        YuvTxType::Transfer { .. } => yuv_tx
+            .burn_announcements()
+            .into_iter()
+            .map(|burn| burn.chroma)
+            .collect(),
+        YuvTxType::Announcement(announcement) => match announcement {
+            Announcement::Chroma(announcement) => vec![announcement.chroma],
+            Announcement::Issue(announcement) => vec![announcement.chroma],
+            Announcement::TransferOwnership(announcement) => vec![announcement.chroma],
+            Announcement::UpdateChroma(announcement) => vec![announcement.chroma],
+            Announcement::Burn(announcement) => vec![announcement.chroma],
+            Announcement::Freeze(_) | Announcement::FreezePubkey(_) | Announcement::Delegate(_) => {
+                Vec::new()
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::absolute::LockTime;
+    use bitcoin::{Transaction, TxIn};
+    use bitcoin_client::MockRpcApi;
+    use yuv_pixels::{Pixel, PixelProof};
+    use yuv_storage::LevelDB;
+    use yuv_types::announcements::IssueAnnouncement;
+
+    use super::*;
+
+    fn secret(byte: u8) -> SecretKey {
+        SecretKey::from_slice(&[byte; 32]).expect("valid secret key")
+    }
+
+    fn pubkey(byte: u8) -> PublicKey {
+        secret(byte).public_key(&Secp256k1::new())
+    }
+
+    fn chroma() -> Chroma {
+        Chroma::from(pubkey(1).x_only_public_key().0)
+    }
+
+    fn bitcoin_tx(lock_time: u32, input: Vec<TxIn>) -> Transaction {
+        Transaction {
+            version: 2,
+            lock_time: LockTime::from_consensus(lock_time),
+            input,
+            output: Vec::new(),
+        }
+    }
+
+    /// Issuance of 100 tokens to the key `2`, and the transfer of 60 of them to the key `3`.
+    fn issue_and_transfer() -> (YuvTransaction, YuvTransaction) {
+        let issue = YuvTransaction::new(
+            bitcoin_tx(1, Vec::new()),
+            YuvTxType::Issue {
+                output_proofs: Some(BTreeMap::from([(
+                    0,
+                    PixelProof::sig(Pixel::new(100, chroma()), pubkey(2)),
+                )])),
+                announcement: IssueAnnouncement::new(chroma(), 100),
+            },
+        );
+
+        let input = TxIn {
+            previous_output: OutPoint::new(issue.bitcoin_tx.txid(), 0),
+            ..Default::default()
+        };
+        let transfer = YuvTransaction::new(
+            bitcoin_tx(2, vec![input]),
+            YuvTxType::Transfer {
+                input_proofs: BTreeMap::from([(
+                    0,
+                    PixelProof::sig(Pixel::new(100, chroma()), pubkey(2)),
+                )]),
+                output_proofs: BTreeMap::from([
+                    (0, PixelProof::sig(Pixel::new(60, chroma()), pubkey(3))),
+                    (1, PixelProof::sig(Pixel::new(40, chroma()), pubkey(2))),
+                ]),
+            },
+        );
+
+        (issue, transfer)
+    }
+
+    /// Storage of the node that has indexed the `txs` mined at the `heights`.
+    async fn indexed_storage(txs: &[YuvTransaction], heights: &[usize]) -> LevelDB {
+        let storage = LevelDB::in_memory().unwrap();
+
+        let positions = heights
+            .iter()
+            .map(|height| Some(TxPosition::new(*height, 0)))
+            .collect::<Vec<_>>();
+        storage.attach_txs(txs, &positions, 100).await.unwrap();
+        storage
+            .put_chroma_info(&chroma(), None, 100, 0, 0, None)
+            .await
+            .unwrap();
+        storage
+            .put_last_indexed_hash(BlockHash::all_zeros())
+            .await
+            .unwrap();
+        storage
+            .put_last_indexed_height(heights[heights.len() - 1])
+            .await
+            .unwrap();
+
+        storage
+    }
+
+    fn params(path: PathBuf) -> CheckpointParams {
+        CheckpointParams {
+            path,
+            hash: None,
+            trusted_signers: vec![pubkey(1)],
+            address_index: true,
+            history_index: true,
+        }
+    }
+
+    /// Writes the `checkpoint` signed by the key `1` to the file with the `name`, and imports it
+    /// to the empty storage.
+    async fn import(
+        checkpoint: &Checkpoint,
+        name: &str,
+    ) -> (eyre::Result<Option<sha256::Hash>>, LevelDB) {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        checkpoint.sign(&secret(1)).unwrap().write(&path).unwrap();
+
+        let block_hash = checkpoint.block_hash;
+        let mut bitcoin_client = MockRpcApi::new();
+        bitcoin_client
+            .expect_get_block_hash()
+            .returning(move |_| Ok(block_hash));
+
+        let storage = LevelDB::in_memory().unwrap();
+        let result = import_checkpoint(
+            &params(path.clone()),
+            &bitcoin_client,
+            &storage,
+            &storage,
+            100,
+        )
+        .await;
+        std::fs::remove_file(path).unwrap();
+
+        (result, storage)
+    }
+
+    #[tokio::test]
+    async fn test_imported_checkpoint_matches_exported_state() {
+        let (issue, transfer) = issue_and_transfer();
+        let txs = [issue.clone(), transfer.clone()];
+        let source = indexed_storage(&txs, &[10, 11]).await;
+
+        let checkpoint = Checkpoint::export(&source, &source).await.unwrap();
+        let signed = checkpoint.sign(&secret(1)).unwrap();
+        let decoded = signed.verify(&params(PathBuf::new())).unwrap();
+        assert_eq!(decoded.txs, txs);
+
+        let (result, storage) = import(&checkpoint, "yuv-checkpoint-round-trip").await;
+        assert_eq!(result.unwrap(), Some(signed.hash()));
+
+        assert_eq!(
+            storage.get_page_by_num(0).await.unwrap(),
+            source.get_page_by_num(0).await.unwrap()
+        );
+        for yuv_tx in &txs {
+            let txid = yuv_tx.bitcoin_tx.txid();
+            assert_eq!(
+                storage.get_yuv_tx(&txid).await.unwrap(),
+                Some(yuv_tx.clone())
+            );
+            assert_eq!(
+                storage.get_tx_position(&txid).await.unwrap(),
+                source.get_tx_position(&txid).await.unwrap()
+            );
+        }
+        assert_eq!(
+            storage.get_chroma_info(&chroma()).await.unwrap(),
+            source.get_chroma_info(&chroma()).await.unwrap()
+        );
+        assert_eq!(storage.get_last_indexed_height().await.unwrap(), Some(11));
+
+        let transfer_txid = transfer.bitcoin_tx.txid();
+        let (sender, recipient) = (
+            pubkey(2).x_only_public_key().0,
+            pubkey(3).x_only_public_key().0,
+        );
+        assert_eq!(
+            storage.get_address_utxos(&sender).await.unwrap(),
+            BTreeMap::from([(OutPoint::new(transfer_txid, 1), Pixel::new(40, chroma()))])
+        );
+        assert_eq!(
+            storage.get_address_utxos(&recipient).await.unwrap(),
+            BTreeMap::from([(OutPoint::new(transfer_txid, 0), Pixel::new(60, chroma()))])
+        );
+
+        assert!(storage.is_address_index_built().await.unwrap());
+
+        let entry = |height, txid, delta| TransferHistoryEntry {
+            height,
+            txid,
+            delta,
+        };
+        assert_eq!(
+            storage
+                .get_transfer_history(&chroma(), &sender)
+                .await
+                .unwrap(),
+            vec![
+                entry(10, issue.bitcoin_tx.txid(), 100),
+                entry(11, transfer_txid, -60),
+            ]
+        );
+        assert_eq!(
+            storage
+                .get_transfer_history(&chroma(), &recipient)
+                .await
+                .unwrap(),
+            vec![entry(11, transfer_txid, 60)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_without_heights_is_refused_with_history_index() {
+        let (issue, transfer) = issue_and_transfer();
+        let source = indexed_storage(&[issue, transfer], &[10, 11]).await;
+
+        let mut checkpoint = Checkpoint::export(&source, &source).await.unwrap();
+        checkpoint.positions = Vec::new();
+
+        let (result, storage) = import(&checkpoint, "yuv-checkpoint-no-heights").await;
+        assert!(result.is_err(), "history can't be rebuilt without heights");
+        assert_eq!(storage.get_last_indexed_hash().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_of_untrusted_signer_is_rejected() {
+        let (issue, transfer) = issue_and_transfer();
+        let source = indexed_storage(&[issue, transfer], &[10, 11]).await;
+        let checkpoint = Checkpoint::export(&source, &source).await.unwrap();
+
+        let signed = checkpoint.sign(&secret(2)).unwrap();
+        let err = signed.verify(&params(PathBuf::new())).unwrap_err();
+        assert!(err.to_string().contains("is not trusted"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_tampered_checkpoint_is_rejected() {
+        let (issue, transfer) = issue_and_transfer();
+        let source = indexed_storage(&[issue, transfer], &[10, 11]).await;
+        let checkpoint = Checkpoint::export(&source, &source).await.unwrap();
+
+        let mut signed = checkpoint.sign(&secret(1)).unwrap();
+        let last = signed.data.len() - 1;
+        signed.data[last] ^= 1;
+
+        let err = signed.verify(&params(PathBuf::new())).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid checkpoint signature"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_with_unexpected_hash_is_rejected() {
+        let (issue, transfer) = issue_and_transfer();
+        let source = indexed_storage(&[issue, transfer], &[10, 11]).await;
+        let checkpoint = Checkpoint::export(&source, &source).await.unwrap();
+
+        let signed = checkpoint.sign(&secret(1)).unwrap();
+        let params = CheckpointParams {
+            hash: Some(sha256::Hash::all_zeros()),
+            ..params(PathBuf::new())
+        };
+
+        let err = signed.verify(&params).unwrap_err();
+        assert!(err.to_string().contains("hash mismatch"), "{err}");
+        assert!(signed
+            .verify(&CheckpointParams {
+                hash: Some(signed.hash()),
+                ..params
+            })
+            .is_ok());
+    }
+}
@@ -109,8 +109,8 @@ where
 
         let block_loader = BlockLoader::new(
             bitcoin_client,
-            block_loader_config.workers_number,
-            block_loader_config.chunk_size,
+            block_loader_config.concurrency,
+            block_loader_config.window_size,
         );
 
         let (sender_to_indexer, rx_indexer) = mpsc::channel(LOADED_BLOCKS_CHANNEL_SIZE);
@@ -239,6 +239,19 @@ where
             }
 
             if let Err(err) = self.handle_new_blocks().await {
+                // The unavailable node doesn't stop the indexer, which waits for it instead.
+                if is_node_unavailable(&err) {
+                    tracing::warn!(
+                        "Bitcoin node is unavailable, indexing is paused for {}s error={:#}",
+                        RESTART_ATTEMPT_INTERVAL.as_secs(),
+                        err
+                    );
+
+                    timer.reset_after(RESTART_ATTEMPT_INTERVAL);
+
+                    continue;
+                }
+
                 if restart_number >= MAX_NUMBER_OF_RESTART_ATTEMPTS {
                     tracing::error!("Indexer restart attempts number exceeded");
                     break;
@@ -353,7 +366,8 @@ where
         Ok(())
     }
 
-    /// Takes block, indexes it and puts its hash to storage as a `last_indexed_hash`.
+    /// Takes block, indexes it and puts its hash and height to storage as a `last_indexed_hash`
+    /// and `last_indexed_height`.
     async fn index_block(&mut self, block: &GetBlockTxResult) -> eyre::Result<()> {
         for indexer in self.subindexers.iter_mut() {
             indexer
@@ -365,6 +379,9 @@ where
         self.storage
             .put_last_indexed_hash(block.block_data.hash)
             .await?;
+        self.storage
+            .put_last_indexed_height(block.block_data.height)
+            .await?;
 
         Ok(())
     }
@@ -437,3 +454,12 @@ where
             .wrap_err("failed to get block info by hash")
     }
 }
+
+/// Returns `true` if the error is caused by the unavailable Bitcoin node.
+fn is_node_unavailable(err: &eyre::Report) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<bitcoin_client::Error>()
+            .is_some_and(bitcoin_client::Error::is_unavailable)
+    })
+}
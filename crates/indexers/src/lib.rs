@@ -10,5 +10,10 @@ pub use indexer::BitcoinBlockIndexer;
 mod subindexer;
 pub use subindexer::{AnnouncementsIndexer, ConfirmationIndexer, Subindexer};
 
+mod checkpoint;
+pub use checkpoint::{
+    import_checkpoint, related_chromas, Checkpoint, CheckpointParams, SignedCheckpoint,
+};
+
 mod blockloader;
 pub use blockloader::{BlockLoader, BlockLoaderConfig};
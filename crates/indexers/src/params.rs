@@ -2,13 +2,18 @@ use std::time::Duration;
 
 use bitcoin::BlockHash;
 
+use crate::CheckpointParams;
+
 /// Parameters to specify for initial indexing of blocks,
 /// that node have skipped.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct IndexingParams {
     /// The hash of block from which indexing should start if
     /// there is no last indexed block hash in storage.
     pub starting_block_hash: Option<BlockHash>,
+    /// The checkpoint to import instead of indexing the history if
+    /// there is no last indexed block hash in storage.
+    pub checkpoint: Option<CheckpointParams>,
 }
 
 /// Parameters that are passed to the `run` method of the indexer.
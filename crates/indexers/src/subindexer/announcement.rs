@@ -5,7 +5,11 @@ use async_trait::async_trait;
 use bitcoin_client::json::GetBlockTxResult;
 use event_bus::{typeid, EventBus};
 use yuv_types::announcements::{announcement_from_script, ParseOpReturnError};
-use yuv_types::{network::Network, ControllerMessage, YuvTransaction, YuvTxType};
+use yuv_types::messages::OwnerChange;
+use yuv_types::{
+    network::Network, Announcement, ControllerMessage, RevalidatorMessage, YuvTransaction,
+    YuvTxType,
+};
 
 use super::Subindexer;
 
@@ -14,6 +18,8 @@ pub struct AnnouncementsIndexer {
     /// Event bus to notify controller about new announcements.
     event_bus: EventBus,
     network: Network,
+    /// Event bus to notify the revalidator about the owner changes, if it's enabled.
+    revalidation_bus: Option<EventBus>,
 }
 
 impl AnnouncementsIndexer {
@@ -22,12 +28,28 @@ impl AnnouncementsIndexer {
             .extract(&typeid![ControllerMessage], &[])
             .expect("message to message handler must be registered");
 
-        Self { event_bus, network }
+        Self {
+            event_bus,
+            network,
+            revalidation_bus: None,
+        }
+    }
+
+    /// Notify the revalidator about the announcements that change the owners of the chromas, so
+    /// the issuances mined after them are re-checked.
+    pub fn with_revalidation(mut self, full_event_bus: &EventBus) -> Self {
+        let revalidation_bus = full_event_bus
+            .extract(&typeid![RevalidatorMessage], &[])
+            .expect("message to revalidator must be registered");
+
+        self.revalidation_bus = Some(revalidation_bus);
+        self
     }
 
     /// Finds announcements in a block and sends them to message handler.
     async fn find_announcements(&self, block: &GetBlockTxResult) -> eyre::Result<()> {
         let mut txs = Vec::new();
+        let mut owner_changes = Vec::new();
 
         // For each transaction, try to find announcements.
         for tx in &block.tx {
@@ -41,6 +63,20 @@ impl AnnouncementsIndexer {
             // push it to announcements.
             for output in tx.output.iter() {
                 match announcement_from_script(&output.script_pubkey) {
+                    // Burn announcements are checked as a part of the transfer they are
+                    // included in, so they are not a separate transaction.
+                    Ok(Announcement::Burn(_)) => {}
+                    // Updates of the chroma metadata are checked against the owner of the chroma
+                    // and applied by the checker, which keeps each applied one as the next version
+                    // of the chroma announcement.
+                    Ok(Announcement::UpdateChroma(update)) => {
+                        tracing::debug!(
+                            chroma = update.chroma.to_string(),
+                            "Found chroma metadata update in tx {}",
+                            tx.txid()
+                        );
+                        announcement_opt = Some(Announcement::UpdateChroma(update));
+                    }
                     Ok(announcement) => {
                         announcement_opt = Some(announcement.clone());
                     }
@@ -64,6 +100,22 @@ impl AnnouncementsIndexer {
                 continue;
             }
 
+            // The freeze that has already expired would never be active, so it's not tracked.
+            if let Announcement::Freeze(freeze) = &announcement {
+                if freeze.is_expired(block.block_data.height) {
+                    tracing::debug!(
+                        "Skipping freeze announcement in tx {}, it has expired at height {:?}",
+                        tx.txid(),
+                        freeze.expiry_height,
+                    );
+                    continue;
+                }
+            }
+
+            if let Some(change) = owner_change(&announcement, block.block_data.height) {
+                owner_changes.push(change);
+            }
+
             txs.push(YuvTransaction {
                 bitcoin_tx: tx.clone(),
                 tx_type: YuvTxType::Announcement(announcement),
@@ -76,13 +128,141 @@ impl AnnouncementsIndexer {
                 .await;
         }
 
+        if let Some(revalidation_bus) = &self.revalidation_bus {
+            if !owner_changes.is_empty() {
+                revalidation_bus
+                    .send(RevalidatorMessage::OwnerChanges(owner_changes))
+                    .await;
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Change of the chroma's owner by the announcement, which is applied only if the announcement
+/// passes the check.
+fn owner_change(announcement: &Announcement, height: usize) -> Option<OwnerChange> {
+    let (chroma, owner) = match announcement {
+        Announcement::TransferOwnership(transfer) => (transfer.chroma, transfer.new_owner.clone()),
+        Announcement::Chroma(chroma) => (chroma.chroma, chroma.issuers.as_ref()?.owner_script()),
+        _ => return None,
+    };
+
+    Some(OwnerChange {
+        chroma,
+        owner,
+        height,
+    })
+}
+
 #[async_trait]
 impl Subindexer for AnnouncementsIndexer {
     async fn index(&mut self, block: &GetBlockTxResult) -> eyre::Result<()> {
         self.find_announcements(block).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash;
+    use bitcoin::{
+        absolute::LockTime, BlockHash, OutPoint, Transaction, TxIn, TxMerkleNode, TxOut, Txid,
+    };
+    use bitcoin_client::json::BlockData;
+    use yuv_pixels::Chroma;
+    use yuv_types::activation::ProtocolFeature;
+
+    use super::*;
+
+    fn block(height: usize, tx: Vec<Transaction>) -> GetBlockTxResult {
+        GetBlockTxResult {
+            block_data: BlockData {
+                hash: BlockHash::all_zeros(),
+                confirmations: 1,
+                size: 0,
+                strippedsize: None,
+                weight: 0,
+                height,
+                version: 1,
+                version_hex: None,
+                merkleroot: TxMerkleNode::all_zeros(),
+                time: 0,
+                mediantime: None,
+                nonce: 0,
+                bits: String::new(),
+                difficulty: 0.0,
+                chainwork: Vec::new(),
+                n_tx: tx.len(),
+                previousblockhash: None,
+                nextblockhash: None,
+            },
+            tx,
+        }
+    }
+
+    fn update_chroma_tx() -> Transaction {
+        let chroma = Chroma::from_address(
+            "bcrt1p4v5dxtlzrrfuk57nxr3d6gwmtved47ulc55kcsk30h93e43ma2eqvrek30",
+        )
+        .unwrap();
+        let update =
+            Announcement::update_chroma_announcement(chroma, "New Token".into(), "NTKN".into())
+                .unwrap();
+
+        Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::all_zeros(), 0),
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: 0,
+                script_pubkey: update.to_script(),
+            }],
+        }
+    }
+
+    fn event_bus() -> EventBus {
+        let mut event_bus = EventBus::default();
+        event_bus.register::<ControllerMessage>(None);
+
+        event_bus
+    }
+
+    #[tokio::test]
+    async fn test_chroma_update_is_sent_to_controller() {
+        let event_bus = event_bus();
+        let events = event_bus.subscribe::<ControllerMessage>();
+        let mut indexer = AnnouncementsIndexer::new(&event_bus, Network::Regtest);
+
+        let tx = update_chroma_tx();
+        indexer.index(&block(1, vec![tx.clone()])).await.unwrap();
+
+        let ControllerMessage::InitializeTxs(txs) = events.recv().await.unwrap() else {
+            panic!("expected the announcements to be sent for the check");
+        };
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].bitcoin_tx, tx);
+        assert!(matches!(
+            txs[0].tx_type,
+            YuvTxType::Announcement(Announcement::UpdateChroma(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_chroma_update_before_activation_is_skipped() {
+        let event_bus = event_bus();
+        let events = event_bus.subscribe::<ControllerMessage>();
+        let mut indexer = AnnouncementsIndexer::new(&event_bus, Network::Bitcoin);
+
+        let height = ProtocolFeature::UpdateChromaAnnouncement.activation_height(Network::Bitcoin);
+        indexer
+            .index(&block(height - 1, vec![update_chroma_tx()]))
+            .await
+            .unwrap();
+
+        assert!(events.is_empty(), "update is not active yet");
+    }
+}
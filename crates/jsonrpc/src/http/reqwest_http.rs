@@ -25,6 +25,9 @@ pub struct ReqwestHttpTransport {
     timeout: Duration,
     /// The value of the `Authorization` HTTP header, i.e., a base64 encoding of 'user:password'.
     auth: Option<String>,
+    /// HTTP client that keeps the pool of the connections to the server, so they are reused
+    /// by the requests.
+    client: reqwest::Client,
 }
 
 impl Default for ReqwestHttpTransport {
@@ -33,6 +36,7 @@ impl Default for ReqwestHttpTransport {
             url: format!("{}:{}", DEFAULT_URL, DEFAULT_PORT),
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECONDS),
             auth: None,
+            client: reqwest::Client::new(),
         }
     }
 }
@@ -55,7 +59,7 @@ impl ReqwestHttpTransport {
             );
         }
 
-        let response = reqwest::Client::new().execute(request).await?;
+        let response = self.client.execute(request).await?;
         Ok(serde_json::from_str(&response.text().await?)?)
     }
 
@@ -91,6 +95,10 @@ impl Transport for ReqwestHttpTransport {
 #[derive(Clone, Debug)]
 pub struct Builder {
     tp: ReqwestHttpTransport,
+    /// Proxy to send the requests through, e.g. `socks5h://127.0.0.1:9050`.
+    proxy: Option<reqwest::Proxy>,
+    /// Max number of the idle connections kept in the pool.
+    pool_max_idle: Option<usize>,
 }
 
 impl Builder {
@@ -98,6 +106,8 @@ impl Builder {
     pub fn new() -> Builder {
         Builder {
             tp: ReqwestHttpTransport::new(),
+            proxy: None,
+            pool_max_idle: None,
         }
     }
 
@@ -124,9 +134,35 @@ impl Builder {
         self
     }
 
+    /// Sends all the requests through the proxy. Supports `http`, `https`, `socks5` and
+    /// `socks5h` schemes, the latter resolves the host names on the proxy side.
+    pub fn proxy(mut self, url: &str) -> Result<Self, Error> {
+        self.proxy = Some(reqwest::Proxy::all(url)?);
+        Ok(self)
+    }
+
+    /// Sets the max number of the idle connections to the server kept in the pool. By default,
+    /// the number isn't limited.
+    pub fn pool_max_idle(mut self, max_idle: usize) -> Self {
+        self.pool_max_idle = Some(max_idle);
+        self
+    }
+
     /// Builds the final [`ReqwestHttpTransport`].
-    pub fn build(self) -> ReqwestHttpTransport {
-        self.tp
+    pub fn build(mut self) -> Result<ReqwestHttpTransport, Error> {
+        let mut client = reqwest::Client::builder();
+
+        if let Some(proxy) = self.proxy {
+            client = client.proxy(proxy);
+        }
+
+        if let Some(max_idle) = self.pool_max_idle {
+            client = client.pool_max_idle_per_host(max_idle);
+        }
+
+        self.tp.client = client.build()?;
+
+        Ok(self.tp)
     }
 }
 
@@ -195,7 +231,33 @@ mod tests {
             .url("http://localhost:22")
             .unwrap()
             .auth("user".to_string(), None)
-            .build();
+            .build()
+            .unwrap();
+        let _ = Client::with_transport(tp);
+    }
+
+    #[test]
+    fn construct_with_proxy() {
+        let tp = Builder::new()
+            .url("http://localhost:22")
+            .unwrap()
+            .proxy("socks5h://127.0.0.1:9050")
+            .unwrap()
+            .build()
+            .unwrap();
+        let _ = Client::with_transport(tp);
+
+        assert!(Builder::new().proxy("not a proxy url").is_err());
+    }
+
+    #[test]
+    fn construct_with_pool() {
+        let tp = Builder::new()
+            .url("http://localhost:22")
+            .unwrap()
+            .pool_max_idle(4)
+            .build()
+            .unwrap();
         let _ = Client::with_transport(tp);
     }
 }
@@ -0,0 +1,289 @@
+//! Passphrase-encrypted storage of the private keys, so they don't sit in the configs in
+//! plaintext.
+//!
+//! The keystore file is the [`KEYSTORE_MAGIC`], the [`KEYSTORE_VERSION`], the random salt and
+//! nonce, followed by the [`Secrets`] encrypted with ChaCha20-Poly1305: the secret key and the
+//! UTF-8 mnemonic, if any. The encryption key is derived from the passphrase with Argon2id. The
+//! file is readable by its owner only.
+//!
+//! The passphrase is taken from the [`PASSPHRASE_ENV`] environment variable, e.g. for the node
+//! running as a service, or asked interactively otherwise.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use argon2::Argon2;
+use bitcoin::secp256k1::{
+    self,
+    rand::{thread_rng, RngCore},
+    SecretKey,
+};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+/// Magic bytes the keystore file starts with.
+pub const KEYSTORE_MAGIC: &[u8; 4] = b"YUVK";
+
+/// Current version of the keystore format.
+pub const KEYSTORE_VERSION: u8 = 1;
+
+/// Environment variable with the passphrase of the keystore.
+pub const PASSPHRASE_ENV: &str = "YUV_KEYSTORE_PASSPHRASE";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = KEYSTORE_MAGIC.len() + 1;
+const SECRET_KEY_LEN: usize = 32;
+
+/// Secrets stored in the keystore.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secrets {
+    pub secret_key: SecretKey,
+    /// BIP39 mnemonic the key is derived from, so it isn't kept in plaintext either.
+    pub mnemonic: Option<String>,
+}
+
+impl Secrets {
+    pub fn new(secret_key: SecretKey) -> Self {
+        Self {
+            secret_key,
+            mnemonic: None,
+        }
+    }
+
+    pub fn with_mnemonic(mut self, mnemonic: Option<String>) -> Self {
+        self.mnemonic = mnemonic;
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.secret_key.secret_bytes().to_vec();
+        if let Some(mnemonic) = &self.mnemonic {
+            bytes.extend_from_slice(mnemonic.as_bytes());
+        }
+
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < SECRET_KEY_LEN {
+            return Err(Error::InvalidFormat);
+        }
+
+        let (secret_key, mnemonic) = bytes.split_at(SECRET_KEY_LEN);
+        let mnemonic = match mnemonic {
+            [] => None,
+            mnemonic => {
+                Some(String::from_utf8(mnemonic.to_vec()).map_err(|_| Error::InvalidFormat)?)
+            }
+        };
+
+        Ok(Self {
+            secret_key: SecretKey::from_slice(secret_key)?,
+            mnemonic,
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to access the keystore: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Not a YUV keystore")]
+    InvalidFormat,
+
+    #[error("Unsupported keystore version {0}, expected {}", KEYSTORE_VERSION)]
+    UnsupportedVersion(u8),
+
+    #[error("Failed to derive the key from the passphrase: {0}")]
+    Kdf(String),
+
+    #[error("Failed to encrypt the key")]
+    Encryption,
+
+    #[error("Wrong passphrase or the keystore is corrupted")]
+    WrongPassphrase,
+
+    #[error("Invalid secret key: {0}")]
+    InvalidKey(#[from] secp256k1::Error),
+
+    #[error("Passphrases don't match")]
+    PassphraseMismatch,
+}
+
+/// Encrypt the secrets with the key derived from the passphrase.
+pub fn encrypt(secrets: &Secrets, passphrase: &str) -> Result<Vec<u8>, Error> {
+    let mut salt = [0u8; SALT_LEN];
+    thread_rng().fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    thread_rng().fill_bytes(&mut nonce);
+
+    let mut data = Vec::with_capacity(HEADER_LEN + SALT_LEN + NONCE_LEN + 48);
+    data.extend_from_slice(KEYSTORE_MAGIC);
+    data.push(KEYSTORE_VERSION);
+
+    let ciphertext = cipher(passphrase, &salt)?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &secrets.to_bytes(),
+                aad: &data,
+            },
+        )
+        .map_err(|_| Error::Encryption)?;
+
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+
+    Ok(data)
+}
+
+/// Decrypt the secrets with the key derived from the passphrase.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Secrets, Error> {
+    if data.len() <= HEADER_LEN + SALT_LEN + NONCE_LEN || !data.starts_with(KEYSTORE_MAGIC) {
+        return Err(Error::InvalidFormat);
+    }
+
+    let (header, data) = data.split_at(HEADER_LEN);
+    let version = header[KEYSTORE_MAGIC.len()];
+    if version != KEYSTORE_VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    let (salt, data) = data.split_at(SALT_LEN);
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+    let plaintext = cipher(passphrase, salt)?
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| Error::WrongPassphrase)?;
+
+    Secrets::from_bytes(&plaintext)
+}
+
+/// Encrypt the secrets and write them to the new keystore file, which only its owner can read.
+pub fn save(path: &Path, secrets: &Secrets, passphrase: &str) -> Result<(), Error> {
+    let data = encrypt(secrets, passphrase)?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(&data)?;
+
+    Ok(())
+}
+
+/// Read the keystore file and decrypt the secrets from it.
+pub fn load(path: &Path, passphrase: &str) -> Result<Secrets, Error> {
+    decrypt(&fs::read(path)?, passphrase)
+}
+
+/// Decrypt the secrets from the keystore file with the passphrase from [`PASSPHRASE_ENV`], or
+/// asked interactively if it's not set.
+pub fn unlock(path: &Path) -> Result<Secrets, Error> {
+    let passphrase = match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => {
+            rpassword::prompt_password(format!("Passphrase of the keystore {}: ", path.display()))?
+        }
+    };
+
+    load(path, &passphrase)
+}
+
+/// Ask the new passphrase interactively twice, or take it from [`PASSPHRASE_ENV`] if it's set.
+pub fn new_passphrase() -> Result<String, Error> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+
+    let passphrase = rpassword::prompt_password("New passphrase of the keystore: ")?;
+    let confirmation = rpassword::prompt_password("Repeat the passphrase: ")?;
+    if passphrase != confirmation {
+        return Err(Error::PassphraseMismatch);
+    }
+
+    Ok(passphrase)
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305, Error> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| Error::Kdf(err.to_string()))?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets() -> Secrets {
+        Secrets::new(SecretKey::from_slice(&[1; 32]).unwrap())
+    }
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let data = encrypt(&secrets(), "passphrase").unwrap();
+
+        assert!(decrypt(&data, "passphrase").unwrap() == secrets());
+    }
+
+    #[test]
+    fn test_keystore_roundtrip_with_mnemonic() {
+        let secrets = secrets().with_mnemonic(Some("abandon ".repeat(11) + "about"));
+
+        let data = encrypt(&secrets, "passphrase").unwrap();
+
+        assert!(decrypt(&data, "passphrase").unwrap() == secrets);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_keystore_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("yuv-keystore-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        save(&path, &secrets(), "passphrase").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        let loaded = load(&path, "passphrase");
+        // The existing files are never overwritten.
+        let overwritten = save(&path, &secrets(), "passphrase");
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(loaded.unwrap() == secrets());
+        assert!(overwritten.is_err());
+    }
+
+    #[test]
+    fn test_keystore_wrong_passphrase() {
+        let data = encrypt(&secrets(), "passphrase").unwrap();
+
+        assert!(matches!(
+            decrypt(&data, "another passphrase"),
+            Err(Error::WrongPassphrase)
+        ));
+        assert!(matches!(
+            decrypt(&data[..HEADER_LEN], "passphrase"),
+            Err(Error::InvalidFormat)
+        ));
+    }
+}
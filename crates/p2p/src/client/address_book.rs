@@ -0,0 +1,211 @@
+//! Persistence of the address book in the node's storage.
+use std::net;
+use std::time::Duration;
+
+use bitcoin::network::{constants::ServiceFlags, Address};
+use flume as chan;
+use tokio_util::sync::CancellationToken;
+use yuv_storage::{PeerAddressBookStorage, PeerAddressEntry, PeerBanEntry};
+
+use crate::{
+    client::error::Error,
+    client::handle::Handle,
+    client::peer::Cache,
+    common::peer::{AddressBook, KnownAddress, Source, Store},
+    fsm::handler::Command,
+    net::{proxy, LocalTime},
+};
+
+/// Interval between the address book snapshots.
+pub const ADDRESS_BOOK_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long the client waits for the address book to be stored on shutdown.
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Load the persisted addresses into the cache. Returns the persisted bans.
+pub(crate) async fn load<S: PeerAddressBookStorage>(
+    storage: &S,
+    peers: &mut Cache,
+) -> Result<Vec<(net::IpAddr, LocalTime)>, Error> {
+    let addresses = storage.get_peer_addresses().await?;
+
+    tracing::debug!("Loaded {} addresses from the address book", addresses.len());
+
+    for entry in addresses {
+        let mut known_address = KnownAddress::new(
+            Address::new(&entry.addr, ServiceFlags::from(entry.services)),
+            Source::Imported,
+            entry.last_active.map(LocalTime::from_secs),
+        );
+        known_address.last_success = entry.last_success.map(LocalTime::from_secs);
+
+        peers.insert(&entry.addr, known_address);
+    }
+
+    let bans = storage
+        .get_peer_bans()
+        .await?
+        .into_iter()
+        .map(|ban| (ban.ip, LocalTime::from_secs(ban.banned_until)))
+        .collect();
+
+    Ok(bans)
+}
+
+/// Store the snapshot of the address book.
+pub(crate) async fn save<S: PeerAddressBookStorage>(
+    storage: &S,
+    address_book: AddressBook,
+) -> Result<(), Error> {
+    let addresses = address_book
+        .addresses
+        .into_iter()
+        .filter_map(|ka| {
+            let addr = ka.addr.socket_addr().ok()?;
+            // Onion peers are taken from the configuration on each start.
+            if proxy::is_onion(&addr) {
+                return None;
+            }
+
+            Some(PeerAddressEntry {
+                addr,
+                services: ka.addr.services.to_u64(),
+                last_active: ka.last_active.map(|time| time.as_secs()),
+                last_success: ka.last_success.map(|time| time.as_secs()),
+            })
+        })
+        .collect();
+
+    let bans = address_book
+        .bans
+        .into_iter()
+        .map(|(ip, banned_until)| PeerBanEntry {
+            ip,
+            banned_until: banned_until.as_secs(),
+        })
+        .collect();
+
+    storage.put_peer_addresses(addresses).await?;
+    storage.put_peer_bans(bans).await?;
+
+    Ok(())
+}
+
+/// Request the snapshot of the address book from the running client and store it.
+pub(crate) async fn flush<S, H>(storage: &S, handle: &H) -> Result<(), Error>
+where
+    S: PeerAddressBookStorage,
+    H: Handle,
+{
+    let (transmit, receive) = chan::bounded(1);
+    handle.command(Command::GetAddressBook(transmit)).await?;
+    let address_book = receive.recv_async().await?;
+
+    save(storage, address_book).await
+}
+
+/// Periodically store the snapshot of the address book until cancelled. The last snapshot is
+/// stored on cancellation, and only then the `reactor_cancellation` stops the client, so the
+/// addresses learned since the previous snapshot aren't lost.
+pub(crate) async fn run_persistence<S, H>(
+    storage: S,
+    handle: H,
+    cancellation: CancellationToken,
+    reactor_cancellation: CancellationToken,
+) where
+    S: PeerAddressBookStorage,
+    H: Handle,
+{
+    loop {
+        tokio::select! {
+            _ = cancellation.cancelled() => break,
+            _ = tokio::time::sleep(ADDRESS_BOOK_FLUSH_INTERVAL) => {},
+        }
+
+        if let Err(err) = flush(&storage, &handle).await {
+            tracing::error!("Failed to store the address book: {}", err);
+        }
+    }
+
+    match tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, flush(&storage, &handle)).await {
+        Ok(Ok(())) => tracing::debug!("Stored the address book on shutdown"),
+        Ok(Err(err)) => tracing::error!("Failed to store the address book on shutdown: {}", err),
+        Err(_) => tracing::error!("Timed out storing the address book on shutdown"),
+    }
+
+    reactor_cancellation.cancel();
+}
+
+#[cfg(test)]
+mod tests {
+    use yuv_storage::LevelDB;
+
+    use super::*;
+    use crate::client::handle::MockHandle;
+
+    fn address_book() -> AddressBook {
+        let addr = ([8, 8, 8, 8], 8333).into();
+        let mut known_address = KnownAddress::new(
+            Address::new(&addr, ServiceFlags::NETWORK),
+            Source::Imported,
+            Some(LocalTime::from_secs(1_700_000_000)),
+        );
+        known_address.last_success = Some(LocalTime::from_secs(1_700_000_100));
+
+        AddressBook {
+            addresses: vec![known_address],
+            bans: vec![(
+                "9.9.9.9".parse().unwrap(),
+                LocalTime::from_secs(2_000_000_000),
+            )],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_address_book_roundtrip() {
+        let storage = LevelDB::in_memory().unwrap();
+        let address_book = address_book();
+
+        save(&storage, address_book.clone()).await.unwrap();
+
+        let mut peers = Cache::new();
+        let bans = load(&storage, &mut peers).await.unwrap();
+
+        assert_eq!(bans, address_book.bans);
+        let addr = address_book.addresses[0].addr.socket_addr().unwrap();
+        let loaded = peers.get(&addr).expect("address should be loaded");
+        assert_eq!(loaded.addr.services, ServiceFlags::NETWORK);
+        assert_eq!(loaded.last_active, address_book.addresses[0].last_active);
+        assert_eq!(loaded.last_success, address_book.addresses[0].last_success);
+    }
+
+    #[tokio::test]
+    async fn test_address_book_is_stored_before_client_stops() {
+        let storage = LevelDB::in_memory().unwrap();
+
+        let mut handle = MockHandle::new();
+        handle.expect_command().times(1).returning(|command| {
+            if let Command::GetAddressBook(reply) = command {
+                reply.send(address_book()).unwrap();
+            }
+
+            Ok(())
+        });
+
+        let cancellation = CancellationToken::new();
+        let reactor_cancellation = CancellationToken::new();
+        cancellation.cancel();
+
+        run_persistence(
+            storage.clone(),
+            handle,
+            cancellation,
+            reactor_cancellation.clone(),
+        )
+        .await;
+
+        assert!(reactor_cancellation.is_cancelled());
+        assert_eq!(storage.get_peer_addresses().await.unwrap().len(), 1);
+        assert_eq!(storage.get_peer_bans().await.unwrap().len(), 1);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::time::SystemTime;
 use std::{net, time};
@@ -7,26 +8,31 @@ use flume as chan;
 
 use bitcoin;
 use bitcoin::network::{constants::ServiceFlags, Address};
+use bitcoin::Txid;
 
 use event_bus::{typeid, EventBus};
 use tokio_util::sync::CancellationToken;
+use yuv_storage::PeerAddressBookStorage;
 use yuv_types::network::Network;
 use yuv_types::{
-    messages::p2p::{Inventory, NetworkMessage},
+    messages::p2p::{GetYuvTxn, Inventory, NetworkMessage},
     ControllerMessage, YuvTransaction,
 };
 
 use crate::{
+    client::address_book,
     client::error::Error,
     client::handle,
     client::peer::Cache,
     client::service::Service,
     common::peer::{KnownAddress, Source, Store},
     common::time::{AdjustedTime, RefClock},
+    fsm::auth::AuthConfig,
     fsm::handler,
     fsm::handler::PeerId,
     fsm::handler::{Command, Limits, Peer},
-    net::{NetReactor, NetWaker},
+    fsm::ratelimit::RateLimits,
+    net::{NetReactor, NetWaker, OnionAddr, ProxyConfig},
 };
 
 use super::boot_nodes::insert_boot_nodes;
@@ -38,12 +44,29 @@ pub struct P2PConfig {
     pub network: Network,
     /// Peers to connect
     pub connect: Vec<SocketAddr>,
+    /// DNS seeds to discover peers with.
+    pub dns_seeds: Vec<String>,
     /// Client listen address.
     pub listen: SocketAddr,
     /// User agent string.
     pub user_agent: &'static str,
     /// Configured limits (inbound/outbound connections).
     pub limits: Limits,
+    /// SOCKS5 proxy to establish the outbound connections through.
+    pub proxy: Option<SocketAddr>,
+    /// Onion peers to connect through the proxy.
+    pub connect_onion: Vec<OnionAddr>,
+    /// Peer authentication.
+    pub auth: AuthConfig,
+    /// Limits of the messages a peer can send.
+    pub rate_limits: RateLimits,
+    /// Whether to reconcile the inventories with the supporting peers instead of sending
+    /// them in full.
+    pub reconciliation: bool,
+    /// Whether to announce the transactions with the short ids to the supporting peers.
+    pub compact_relay: bool,
+    /// Whether to compress the transactions sent to the supporting peers.
+    pub compression: bool,
 }
 
 impl P2PConfig {
@@ -54,18 +77,69 @@ impl P2PConfig {
         connect: Vec<net::SocketAddr>,
         max_inb: usize,
         max_outb: usize,
+        dns_seeds: Vec<String>,
     ) -> Self {
         Self {
             network,
             limits: Limits {
                 max_outbound_peers: max_outb,
                 max_inbound_peers: max_inb,
+                ..Limits::default()
             },
             listen,
             connect,
+            dns_seeds,
             ..Self::default()
         }
     }
+
+    /// Establish the outbound connections through the SOCKS5 proxy and connect to the
+    /// onion peers.
+    pub fn with_proxy(mut self, proxy: SocketAddr, connect_onion: Vec<OnionAddr>) -> Self {
+        self.proxy = Some(proxy);
+        self.connect_onion = connect_onion;
+        self
+    }
+
+    /// Authenticate with the key and accept the inventories only from the trusted peers, see
+    /// [`AuthConfig`].
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Maintain the outbound connections more often while there are fewer of them than
+    /// `min_outbound_peers`.
+    pub fn with_min_outbound_peers(mut self, min_outbound_peers: usize) -> Self {
+        self.limits.min_outbound_peers = min_outbound_peers;
+        self
+    }
+
+    /// Limit the messages a peer can send, see [`RateLimits`].
+    pub fn with_rate_limits(mut self, rate_limits: RateLimits) -> Self {
+        self.rate_limits = rate_limits;
+        self
+    }
+
+    /// Reconcile the inventories with the peers that support it by exchanging the sketches,
+    /// so only the difference between the inventories is sent.
+    pub fn with_reconciliation(mut self, reconciliation: bool) -> Self {
+        self.reconciliation = reconciliation;
+        self
+    }
+
+    /// Announce the mined transactions with the short ids to the peers that support it, so
+    /// they request only the transactions they don't have.
+    pub fn with_compact_relay(mut self, compact_relay: bool) -> Self {
+        self.compact_relay = compact_relay;
+        self
+    }
+
+    /// Compress the large transaction messages sent to the peers that support it.
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
 }
 
 impl Default for P2PConfig {
@@ -73,29 +147,55 @@ impl Default for P2PConfig {
         Self {
             network: Network::Bitcoin,
             connect: Vec::new(),
+            dns_seeds: Vec::new(),
             listen: ([0, 0, 0, 0], 0).into(),
             user_agent: handler::USER_AGENT,
             limits: Limits::default(),
+            proxy: None,
+            connect_onion: Vec::new(),
+            auth: AuthConfig::default(),
+            rate_limits: RateLimits::default(),
+            reconciliation: false,
+            compact_relay: false,
+            compression: false,
         }
     }
 }
 
 /// Runs a pre-loaded client.
-pub struct P2PClient<R: NetReactor> {
+pub struct P2PClient<R: NetReactor, S> {
     handle: Handle<R::Waker>,
     service: Service<Cache, RefClock<AdjustedTime<SocketAddr>>>,
     listen: SocketAddr,
     commands: chan::Receiver<Command>,
     reactor: R,
+    /// Storage of the address book, which survives the node restarts.
+    storage: S,
 }
 
-impl<R: NetReactor> P2PClient<R> {
-    /// Create a new client.
-    pub fn new(config: P2PConfig, full_event_bus: &EventBus) -> Result<Self, Error> {
+impl<R: NetReactor, S: PeerAddressBookStorage + Send + Sync + 'static> P2PClient<R, S> {
+    /// Create a new client. Known addresses and bans are loaded from the storage.
+    pub async fn new(
+        config: P2PConfig,
+        full_event_bus: &EventBus,
+        storage: S,
+    ) -> Result<Self, Error> {
         let (commands_tx, commands_rx) = chan::unbounded::<Command>();
 
         let (listening_send, listening) = chan::bounded(1);
-        let reactor = <R as NetReactor>::new(listening_send)?;
+        let proxy = config.proxy.map(|address| {
+            let mut proxy = ProxyConfig::new(address);
+            for onion in &config.connect_onion {
+                proxy.add_onion_peer(onion.clone());
+            }
+            proxy
+        });
+
+        if proxy.is_some() && !config.dns_seeds.is_empty() {
+            tracing::warn!("DNS seeds are disabled, as they can't be resolved through the proxy");
+        }
+
+        let reactor = <R as NetReactor>::new(listening_send, proxy)?;
 
         let event_bus = full_event_bus
             .extract(&typeid![ControllerMessage], &typeid![])
@@ -106,14 +206,16 @@ impl<R: NetReactor> P2PClient<R> {
         let rng = fastrand::Rng::new();
 
         let mut peers = Cache::new();
+        let bans = address_book::load(&storage, &mut peers).await?;
 
         insert_boot_nodes(&mut peers, config.network);
 
-        for addr in &config.connect {
+        let onion_peers = config.connect_onion.iter().map(OnionAddr::to_socket_addr);
+        for addr in config.connect.iter().copied().chain(onion_peers) {
             peers.insert(
-                addr,
+                &addr,
                 KnownAddress::new(
-                    Address::new(addr, ServiceFlags::NONE),
+                    Address::new(&addr, ServiceFlags::NONE),
                     Source::Imported,
                     None,
                 ),
@@ -122,6 +224,7 @@ impl<R: NetReactor> P2PClient<R> {
 
         let p2p_service = Service::new(
             peers,
+            bans,
             RefClock::from(clock),
             rng,
             config.clone(),
@@ -143,15 +246,30 @@ impl<R: NetReactor> P2PClient<R> {
             commands: commands_rx,
             reactor,
             service: p2p_service,
+            storage,
         })
     }
 
-    /// Run a pre-loaded p2p client.
+    /// Run a pre-loaded p2p client. The address book is periodically stored while the client
+    /// is running, and once more on the cancellation before the client stops.
     pub async fn run(mut self, cancellation: CancellationToken) {
-        let result = self
-            .reactor
-            .run(&self.listen, self.service, self.commands, cancellation)
-            .await;
+        let reactor_cancellation = CancellationToken::new();
+        let persistence = address_book::run_persistence(
+            self.storage,
+            self.handle.clone(),
+            cancellation,
+            reactor_cancellation.clone(),
+        );
+
+        let (result, _) = tokio::join!(
+            self.reactor.run(
+                &self.listen,
+                self.service,
+                self.commands,
+                reactor_cancellation
+            ),
+            persistence,
+        );
 
         if let Err(e) = result {
             tracing::error!("P2P is down. P2P client run error: {}", e);
@@ -215,8 +333,12 @@ impl<W: NetWaker> handle::Handle for Handle<W> {
         }
     }
 
-    async fn send_inv(&self, inv: Vec<Inventory>) -> Result<(), handle::Error> {
-        self.command(Command::SendInv(inv)).await?;
+    async fn send_inv(
+        &self,
+        inv: Vec<Inventory>,
+        txs: Vec<YuvTransaction>,
+    ) -> Result<(), handle::Error> {
+        self.command(Command::SendInv(inv, txs)).await?;
 
         Ok(())
     }
@@ -238,6 +360,27 @@ impl<W: NetWaker> handle::Handle for Handle<W> {
         Ok(())
     }
 
+    async fn announce_yuv_txs(
+        &self,
+        txs: Vec<YuvTransaction>,
+        prefilled: HashSet<Txid>,
+    ) -> Result<(), handle::Error> {
+        self.command(Command::AnnounceYuvTransactions(txs, prefilled))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn send_get_yuv_txn(
+        &self,
+        request: GetYuvTxn,
+        addr: PeerId,
+    ) -> Result<(), handle::Error> {
+        self.command(Command::SendGetYuvTxn(request, addr)).await?;
+
+        Ok(())
+    }
+
     async fn ban_peer(&self, addr: SocketAddr) -> Result<(), handle::Error> {
         self.command(Command::BanPeer(addr)).await
     }
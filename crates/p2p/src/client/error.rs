@@ -18,6 +18,9 @@ pub enum Error {
     /// An error coming from the peer store.
     #[error("error loading peers: {0}")]
     PeerStore(io::Error),
+    /// An error coming from the address book storage.
+    #[error("address book storage error: {0}")]
+    AddressBook(#[from] yuv_storage::KeyValueError),
     /// A communication channel error.
     #[error("command channel disconnected")]
     Channel,
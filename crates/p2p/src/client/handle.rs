@@ -1,14 +1,16 @@
 //! Node handles are created from nodes by users of the library, to communicate with the underlying
 //! protocol instance.
+use std::collections::HashSet;
 use std::net;
 use std::net::SocketAddr;
 
 use async_trait::async_trait;
+use bitcoin::Txid;
 use flume as chan;
 use thiserror::Error;
 
 use yuv_types::{
-    messages::p2p::{Inventory, NetworkMessage},
+    messages::p2p::{GetYuvTxn, Inventory, NetworkMessage},
     YuvTransaction,
 };
 
@@ -60,7 +62,11 @@ pub trait Handle: Sized + Send + Sync + Clone {
     /// Send a message to a random *outbound* peer. Return the chosen
     /// peer or nothing if no peer was available.
     async fn query(&self, msg: NetworkMessage) -> Result<Option<net::SocketAddr>, Error>;
-    async fn send_inv(&self, txids: Vec<Inventory>) -> Result<(), handle::Error>;
+    async fn send_inv(
+        &self,
+        txids: Vec<Inventory>,
+        txs: Vec<YuvTransaction>,
+    ) -> Result<(), handle::Error>;
     async fn send_get_data(&self, txids: Vec<Inventory>, addr: PeerId)
         -> Result<(), handle::Error>;
     async fn send_yuv_txs(
@@ -68,9 +74,86 @@ pub trait Handle: Sized + Send + Sync + Clone {
         txs: Vec<YuvTransaction>,
         addr: PeerId,
     ) -> Result<(), handle::Error>;
+    /// Announce the transactions with the short ids to the peers that support the compact
+    /// relay. The `prefilled` ones are sent in full.
+    async fn announce_yuv_txs(
+        &self,
+        txs: Vec<YuvTransaction>,
+        prefilled: HashSet<Txid>,
+    ) -> Result<(), handle::Error>;
+    async fn send_get_yuv_txn(&self, request: GetYuvTxn, addr: PeerId)
+        -> Result<(), handle::Error>;
     async fn ban_peer(&self, addr: SocketAddr) -> Result<(), handle::Error>;
 }
 
+/// Handle of the node that doesn't connect to the P2P network, e.g. the standalone indexer. It has
+/// no peers, so the messages are dropped and the queries get no response.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OfflineHandle;
+
+#[async_trait]
+impl Handle for OfflineHandle {
+    async fn command(&self, _cmd: Command) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn broadcast(
+        &self,
+        _msg: NetworkMessage,
+        _predicate: fn(Peer) -> bool,
+    ) -> Result<Vec<net::SocketAddr>, Error> {
+        Ok(Vec::new())
+    }
+
+    async fn query(&self, _msg: NetworkMessage) -> Result<Option<net::SocketAddr>, Error> {
+        Ok(None)
+    }
+
+    async fn send_inv(
+        &self,
+        _txids: Vec<Inventory>,
+        _txs: Vec<YuvTransaction>,
+    ) -> Result<(), handle::Error> {
+        Ok(())
+    }
+
+    async fn send_get_data(
+        &self,
+        _txids: Vec<Inventory>,
+        _addr: PeerId,
+    ) -> Result<(), handle::Error> {
+        Ok(())
+    }
+
+    async fn send_yuv_txs(
+        &self,
+        _txs: Vec<YuvTransaction>,
+        _addr: PeerId,
+    ) -> Result<(), handle::Error> {
+        Ok(())
+    }
+
+    async fn announce_yuv_txs(
+        &self,
+        _txs: Vec<YuvTransaction>,
+        _prefilled: HashSet<Txid>,
+    ) -> Result<(), handle::Error> {
+        Ok(())
+    }
+
+    async fn send_get_yuv_txn(
+        &self,
+        _request: GetYuvTxn,
+        _addr: PeerId,
+    ) -> Result<(), handle::Error> {
+        Ok(())
+    }
+
+    async fn ban_peer(&self, _addr: SocketAddr) -> Result<(), handle::Error> {
+        Ok(())
+    }
+}
+
 #[cfg(any(test, feature = "mocks"))]
 mockall::mock! {
     pub Handle {}
@@ -88,7 +171,11 @@ mockall::mock! {
             predicate: fn(Peer) -> bool,
         ) -> Result<Vec<net::SocketAddr>, Error>;
         async fn query(&self, msg: NetworkMessage) -> Result<Option<net::SocketAddr>, Error>;
-        async fn send_inv(&self, txids: Vec<Inventory>) -> Result<(), handle::Error>;
+        async fn send_inv(
+            &self,
+            txids: Vec<Inventory>,
+            txs: Vec<YuvTransaction>,
+        ) -> Result<(), handle::Error>;
         async fn send_get_data(&self, txids: Vec<Inventory>, addr: PeerId)
             -> Result<(), handle::Error>;
         async fn send_yuv_txs(
@@ -96,6 +183,13 @@ mockall::mock! {
             txs: Vec<YuvTransaction>,
             addr: PeerId,
         ) -> Result<(), handle::Error>;
+        async fn announce_yuv_txs(
+            &self,
+            txs: Vec<YuvTransaction>,
+            prefilled: HashSet<Txid>,
+        ) -> Result<(), handle::Error>;
+        async fn send_get_yuv_txn(&self, request: GetYuvTxn, addr: PeerId)
+            -> Result<(), handle::Error>;
         async fn ban_peer(&self, addr: SocketAddr) -> Result<(), handle::Error>;
     }
 }
@@ -1,7 +1,11 @@
 //! Nakamoto's client library.
 #![allow(clippy::inconsistent_struct_constructor)]
 #![allow(clippy::type_complexity)]
+mod address_book;
+pub use address_book::ADDRESS_BOOK_FLUSH_INTERVAL;
 mod controller;
+pub use crate::fsm::auth::AuthConfig;
+pub use crate::fsm::ratelimit::{DroppedMessages, RateLimits};
 pub use controller::*;
 mod boot_nodes;
 mod error;
@@ -5,6 +5,7 @@ use std::borrow::{Borrow, Cow};
 use std::collections::HashMap;
 use std::net;
 use tracing::{debug, error};
+use yuv_types::messages::p2p::NetworkMessage;
 
 use crate::{
     client,
@@ -27,6 +28,7 @@ impl<P: peer::Store + Send, C: AdjustedClock<net::SocketAddr> + Sync + Send> Ser
     /// Create a new client service.
     pub fn new(
         peers: P,
+        bans: Vec<(net::IpAddr, LocalTime)>,
         clock: C,
         rng: fastrand::Rng,
         config: P2PConfig,
@@ -43,6 +45,15 @@ impl<P: peer::Store + Send, C: AdjustedClock<net::SocketAddr> + Sync + Send> Ser
                     connect: config.connect,
                     user_agent: config.user_agent,
                     limits: config.limits,
+                    dns_seeds: config.dns_seeds,
+                    // The seeds would be resolved bypassing the proxy.
+                    dns_seeding: config.proxy.is_none(),
+                    bans,
+                    auth: config.auth,
+                    rate_limits: config.rate_limits,
+                    reconciliation: config.reconciliation,
+                    compact_relay: config.compact_relay,
+                    compression: config.compression,
 
                     ..fsm::handler::Config::default()
                 },
@@ -89,7 +100,39 @@ where
 
         loop {
             match inbox.decode_next() {
-                Ok(Some(msg)) => self.machine.message_received(addr, Cow::Owned(msg)).await,
+                Ok(Some((mut msg, mut size))) => {
+                    if let NetworkMessage::ComprYuvTx(data) = &msg.payload {
+                        if !self.machine.peermgr.is_compression_enabled(addr) {
+                            self.machine.disconnect(
+                                *addr,
+                                fsm::handler::DisconnectReason::PeerMisbehaving(
+                                    "`compryuvtx` from peer without compression",
+                                ),
+                            );
+                            return;
+                        }
+
+                        match client::stream::decompress(data) {
+                            // Rate limits apply to the decompressed size.
+                            Ok((txs, decompressed_size)) => {
+                                msg.payload = NetworkMessage::YuvTx(txs);
+                                size = size.max(decompressed_size);
+                            }
+                            Err(err) => {
+                                error!("Invalid compressed message from {}. Error: {}", addr, err);
+                                self.machine
+                                    .disconnect(*addr, fsm::handler::DisconnectReason::DecodeError);
+                                return;
+                            }
+                        }
+                    }
+
+                    if self.machine.is_rate_limited(addr, &msg, size) {
+                        continue;
+                    }
+
+                    self.machine.message_received(addr, Cow::Owned(msg)).await
+                }
                 Ok(None) => break,
                 Err(err) => {
                     error!("Invalid message received from {}. Error: {}", addr, err);
@@ -149,12 +192,24 @@ where
     }
 }
 
-impl<P, C> Iterator for Service<P, C> {
+impl<P, C> Iterator for Service<P, C>
+where
+    P: peer::Store + Send,
+    C: AdjustedClock<net::SocketAddr> + Sync + Send,
+{
     type Item = Io<Vec<u8>, crate::fsm::event::Event, fsm::handler::DisconnectReason>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.machine.next() {
-            Some(Io::Write(addr, msg)) => {
+            Some(Io::Write(addr, mut msg)) => {
+                if let NetworkMessage::YuvTx(txs) = &msg.payload {
+                    if self.machine.peermgr.is_compression_enabled(&addr) {
+                        if let Some(payload) = client::stream::compress(txs) {
+                            msg.payload = payload;
+                        }
+                    }
+                }
+
                 let mut buf = Vec::new();
 
                 msg.consensus_encode(&mut buf)
@@ -2,7 +2,13 @@
 use std::io;
 
 use bitcoin::consensus::{deserialize_partial, encode::Error};
-use yuv_types::messages::p2p::RawNetworkMessage;
+use yuv_types::messages::p2p::{decode_yuv_txs, encode_yuv_txs, NetworkMessage, RawNetworkMessage};
+use yuv_types::YuvTransaction;
+
+/// Minimum size of the `yuvtx` payload in bytes to be compressed.
+pub const MIN_COMPRESSED_SIZE: usize = 1024;
+/// Maximum size of the decompressed `yuvtx` payload in bytes, the same as of the messages.
+pub const MAX_DECOMPRESSED_SIZE: usize = 5_000_000;
 
 /// Message stream decoder.
 ///
@@ -25,12 +31,13 @@ impl Decoder {
         self.unparsed.extend_from_slice(bytes);
     }
 
-    /// Decode and return the next message. Returns [`None`] if nothing was decoded.
-    pub fn decode_next(&mut self) -> Result<Option<RawNetworkMessage>, Error> {
+    /// Decode and return the next message with its size in bytes. Returns [`None`] if nothing
+    /// was decoded.
+    pub fn decode_next(&mut self) -> Result<Option<(RawNetworkMessage, usize)>, Error> {
         match deserialize_partial(self.unparsed.as_slice()) {
             Ok((msg, index)) => {
                 self.unparsed.drain(..index);
-                Ok(Some(msg))
+                Ok(Some((msg, index)))
             }
 
             Err(Error::Io(ref err)) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
@@ -38,3 +45,54 @@ impl Decoder {
         }
     }
 }
+
+/// Compress the transactions into the `compryuvtx` message. Returns [`None`] if the payload is
+/// too small to be compressed or isn't reduced by the compression.
+pub fn compress(txs: &[YuvTransaction]) -> Option<NetworkMessage> {
+    let payload = encode_yuv_txs(txs);
+    if payload.len() < MIN_COMPRESSED_SIZE {
+        return None;
+    }
+
+    let compressed = zstd::bulk::compress(&payload, zstd::DEFAULT_COMPRESSION_LEVEL).ok()?;
+    if compressed.len() >= payload.len() {
+        return None;
+    }
+
+    Some(NetworkMessage::ComprYuvTx(compressed))
+}
+
+/// Decompress the payload of the `compryuvtx` message. Returns the transactions with the size of
+/// the decompressed payload in bytes. Payloads decompressed over [`MAX_DECOMPRESSED_SIZE`] are
+/// rejected without allocating more than that.
+pub fn decompress(data: &[u8]) -> Result<(Vec<YuvTransaction>, usize), Error> {
+    let payload = zstd::bulk::decompress(data, MAX_DECOMPRESSED_SIZE).map_err(Error::Io)?;
+    let txs = decode_yuv_txs(&payload)?;
+
+    Ok((txs, payload.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompress_round_trip() {
+        let payload = encode_yuv_txs(&[]);
+        let compressed = zstd::bulk::compress(&payload, zstd::DEFAULT_COMPRESSION_LEVEL).unwrap();
+
+        let (txs, size) = decompress(&compressed).unwrap();
+        assert!(txs.is_empty());
+        assert_eq!(size, payload.len());
+
+        assert!(compress(&[]).is_none(), "small payloads aren't compressed");
+    }
+
+    #[test]
+    fn test_decompress_rejects_bomb() {
+        let bomb = zstd::bulk::compress(&vec![0; MAX_DECOMPRESSED_SIZE + 1], 1).unwrap();
+        assert!(bomb.len() < MIN_COMPRESSED_SIZE);
+
+        assert!(decompress(&bomb).is_err());
+    }
+}
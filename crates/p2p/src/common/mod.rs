@@ -3,4 +3,5 @@
 pub mod collections;
 pub mod network;
 pub mod peer;
+pub mod sketch;
 pub mod time;
@@ -124,6 +124,15 @@ impl KnownAddress {
     }
 }
 
+/// Snapshot of the address book: known addresses and active bans.
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    /// Known peer addresses.
+    pub addresses: Vec<KnownAddress>,
+    /// Banned IP addresses with the time until which they are banned.
+    pub bans: Vec<(net::IpAddr, LocalTime)>,
+}
+
 /// Source of peer addresses.
 pub trait AddressSource {
     /// Sample a random peer address. Returns `None` if there are no addresses left.
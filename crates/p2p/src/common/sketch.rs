@@ -0,0 +1,382 @@
+//! Sketches of the sets of 32-bit elements for the set reconciliation.
+//!
+//! The sketch is a PinSketch: the odd power sums of the elements in `GF(2^32)`. Merging the
+//! sketches of two sets gives the sketch of their symmetric difference, which can be decoded
+//! back to the elements if there are at most `capacity` of them. So two peers can find the
+//! difference of their sets by exchanging `capacity` numbers, no matter how large the sets are.
+
+/// Lower bits of the field modulus, `x^32 + x^7 + x^3 + x^2 + 1`.
+const MODULUS: u32 = 0x8D;
+
+/// Polynomial over `GF(2^32)`, coefficients are stored from the lowest degree. Trimmed
+/// polynomials have no leading zeros, so the zero polynomial is empty.
+type Poly = Vec<u32>;
+
+/// Sketch of the set of nonzero 32-bit elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sketch {
+    syndromes: Vec<u32>,
+}
+
+impl Sketch {
+    /// Create an empty sketch that can decode up to `capacity` elements.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            syndromes: vec![0; capacity],
+        }
+    }
+
+    /// Create a sketch from the syndromes received from the peer.
+    pub fn from_syndromes(syndromes: Vec<u32>) -> Self {
+        Self { syndromes }
+    }
+
+    /// Maximum number of elements the sketch can decode.
+    pub fn capacity(&self) -> usize {
+        self.syndromes.len()
+    }
+
+    pub fn syndromes(&self) -> &[u32] {
+        &self.syndromes
+    }
+
+    pub fn into_syndromes(self) -> Vec<u32> {
+        self.syndromes
+    }
+
+    /// Add the element to the sketch. Adding the same element twice removes it, and zero
+    /// can't be added at all.
+    pub fn add(&mut self, element: u32) {
+        if element == 0 {
+            return;
+        }
+
+        let square = mul(element, element);
+        let mut power = element;
+        for syndrome in &mut self.syndromes {
+            *syndrome ^= power;
+            power = mul(power, square);
+        }
+    }
+
+    /// Merge the sketch of the other set, so this one becomes the sketch of the symmetric
+    /// difference of the sets. The sketches are expected to have the same capacity, otherwise
+    /// the result has the smaller one.
+    pub fn merge(&mut self, other: &Sketch) {
+        self.syndromes.truncate(other.syndromes.len());
+        for (syndrome, other) in self.syndromes.iter_mut().zip(&other.syndromes) {
+            *syndrome ^= other;
+        }
+    }
+
+    /// Recover the elements of the sketch. Returns `None` if there are more of them than the
+    /// capacity of the sketch.
+    pub fn decode(&self) -> Option<Vec<u32>> {
+        let capacity = self.capacity();
+
+        // Power sums `S_1..S_2c`, where the even ones are the squares: `S_2i = S_i^2`.
+        let mut sums = vec![0; 2 * capacity];
+        for (i, syndrome) in self.syndromes.iter().enumerate() {
+            sums[2 * i] = *syndrome;
+        }
+        for i in 1..=capacity {
+            sums[2 * i - 1] = mul(sums[i - 1], sums[i - 1]);
+        }
+
+        let (locator, len) = berlekamp_massey(&sums);
+        if len > capacity {
+            return None;
+        }
+        if len == 0 {
+            return Some(Vec::new());
+        }
+        if locator[len] == 0 {
+            return None;
+        }
+
+        // Roots of the reversed locator are the elements.
+        let reversed = locator.iter().rev().copied().collect::<Vec<_>>();
+        let mut elements = Vec::with_capacity(len);
+        if !find_roots(&monic(&reversed), &mut elements) || elements.len() != len {
+            return None;
+        }
+
+        let mut check = Sketch::new(capacity);
+        for element in &elements {
+            check.add(*element);
+        }
+        if check != *self {
+            return None;
+        }
+
+        Some(elements)
+    }
+}
+
+/// Multiplication in `GF(2^32)`.
+fn mul(mut a: u32, mut b: u32) -> u32 {
+    let mut result = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            result ^= a;
+        }
+        b >>= 1;
+
+        let carry = a >> 31;
+        a <<= 1;
+        if carry == 1 {
+            a ^= MODULUS;
+        }
+    }
+
+    result
+}
+
+/// Inverse of the nonzero element in `GF(2^32)`, which is `a^(2^32 - 2)`.
+fn inv(a: u32) -> u32 {
+    let mut result = 1;
+    let mut base = a;
+    let mut exp = u32::MAX - 1;
+    while exp != 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// Find the shortest linear recurrence of the power sums. Returns the connection polynomial
+/// of `len + 1` coefficients and the `len` of the recurrence.
+fn berlekamp_massey(sums: &[u32]) -> (Poly, usize) {
+    let mut connection: Poly = vec![1];
+    let mut prev: Poly = vec![1];
+    let mut len = 0;
+    let mut shift = 1;
+    let mut prev_discrepancy = 1;
+
+    for n in 0..sums.len() {
+        let mut discrepancy = sums[n];
+        for i in 1..=len.min(connection.len() - 1) {
+            discrepancy ^= mul(connection[i], sums[n - i]);
+        }
+
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let coef = mul(discrepancy, inv(prev_discrepancy));
+        let temp = connection.clone();
+        if connection.len() < prev.len() + shift {
+            connection.resize(prev.len() + shift, 0);
+        }
+        for (i, c) in prev.iter().enumerate() {
+            connection[i + shift] ^= mul(coef, *c);
+        }
+
+        if 2 * len <= n {
+            len = n + 1 - len;
+            prev = temp;
+            prev_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+
+    connection.resize(len + 1, 0);
+
+    (connection, len)
+}
+
+/// Find the roots of the monic polynomial with the Berlekamp trace algorithm. Returns `false`
+/// if the polynomial doesn't split into the distinct linear factors.
+fn find_roots(poly: &[u32], roots: &mut Vec<u32>) -> bool {
+    match poly.len() - 1 {
+        0 => return true,
+        1 => {
+            roots.push(poly[0]);
+            return true;
+        }
+        _ => {}
+    }
+
+    for k in 0..32 {
+        // Trace of `2^k * z` modulo the polynomial, which is either 0 or 1 at each root, so
+        // the GCD with it splits the polynomial.
+        let mut power = rem(&[0, 1 << k], poly);
+        let mut trace = power.clone();
+        for _ in 1..32 {
+            power = rem(&poly_mul(&power, &power), poly);
+            poly_add_assign(&mut trace, &power);
+        }
+
+        let factor = gcd(poly, &trace);
+        if factor.len() > 1 && factor.len() < poly.len() {
+            let (quotient, _) = div_rem(poly, &factor);
+            return find_roots(&factor, roots) && find_roots(&quotient, roots);
+        }
+    }
+
+    false
+}
+
+fn trim(poly: &mut Poly) {
+    while poly.last() == Some(&0) {
+        poly.pop();
+    }
+}
+
+fn monic(poly: &[u32]) -> Poly {
+    let lead_inv = inv(*poly.last().expect("polynomial is not zero"));
+    poly.iter().map(|c| mul(*c, lead_inv)).collect()
+}
+
+fn poly_add_assign(poly: &mut Poly, other: &[u32]) {
+    if poly.len() < other.len() {
+        poly.resize(other.len(), 0);
+    }
+    for (c, other) in poly.iter_mut().zip(other) {
+        *c ^= other;
+    }
+    trim(poly);
+}
+
+fn poly_mul(a: &[u32], b: &[u32]) -> Poly {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut result = vec![0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        if *x == 0 {
+            continue;
+        }
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] ^= mul(*x, *y);
+        }
+    }
+    trim(&mut result);
+
+    result
+}
+
+/// Quotient and remainder of the division by the monic polynomial.
+fn div_rem(poly: &[u32], divisor: &[u32]) -> (Poly, Poly) {
+    let mut remainder = poly.to_vec();
+    trim(&mut remainder);
+
+    let degree = divisor.len() - 1;
+    if remainder.len() <= degree {
+        return (Vec::new(), remainder);
+    }
+
+    let mut quotient = vec![0; remainder.len() - degree];
+    while remainder.len() > degree {
+        let lead = *remainder.last().expect("remainder is not zero");
+        let shift = remainder.len() - 1 - degree;
+        quotient[shift] = lead;
+        for (i, c) in divisor.iter().enumerate() {
+            remainder[shift + i] ^= mul(lead, *c);
+        }
+        trim(&mut remainder);
+    }
+    trim(&mut quotient);
+
+    (quotient, remainder)
+}
+
+fn rem(poly: &[u32], divisor: &[u32]) -> Poly {
+    div_rem(poly, divisor).1
+}
+
+fn gcd(a: &[u32], b: &[u32]) -> Poly {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    trim(&mut a);
+    trim(&mut b);
+
+    while !b.is_empty() {
+        let divisor = monic(&b);
+        b = rem(&a, &divisor);
+        a = divisor;
+    }
+
+    if a.is_empty() {
+        a
+    } else {
+        monic(&a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(rng: &fastrand::Rng, n: usize) -> Vec<u32> {
+        (0..n).map(|_| rng.u32(1..)).collect()
+    }
+
+    fn sketch(capacity: usize, elements: &[u32]) -> Sketch {
+        let mut sketch = Sketch::new(capacity);
+        for element in elements {
+            sketch.add(*element);
+        }
+        sketch
+    }
+
+    #[test]
+    fn test_field_inverse() {
+        let rng = fastrand::Rng::with_seed(1);
+        for a in elements(&rng, 100) {
+            assert_eq!(mul(a, inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_sketch_decodes_difference() {
+        let rng = fastrand::Rng::with_seed(2);
+
+        for capacity in [1, 2, 4, 8, 16, 32, 64] {
+            let common = elements(&rng, 100);
+            let difference = elements(&rng, rng.usize(..=capacity));
+            let split = rng.usize(..=difference.len());
+
+            let ours = [&common[..], &difference[..split]].concat();
+            let theirs = [&common[..], &difference[split..]].concat();
+
+            let mut merged = sketch(capacity, &ours);
+            merged.merge(&sketch(capacity, &theirs));
+
+            let mut decoded = merged.decode().expect("difference must be decoded");
+            decoded.sort_unstable();
+            let mut expected = difference.clone();
+            expected.sort_unstable();
+            assert_eq!(decoded, expected, "capacity {}", capacity);
+        }
+    }
+
+    #[test]
+    fn test_sketch_of_equal_sets_is_empty() {
+        let rng = fastrand::Rng::with_seed(3);
+        let set = elements(&rng, 50);
+
+        let mut merged = sketch(8, &set);
+        merged.merge(&sketch(8, &set));
+
+        assert_eq!(merged.decode(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_sketch_fails_over_capacity() {
+        let rng = fastrand::Rng::with_seed(4);
+
+        for _ in 0..10 {
+            let set = elements(&rng, 12);
+            assert_eq!(sketch(8, &set).decode(), None);
+        }
+    }
+}
@@ -7,11 +7,11 @@ use std::net::SocketAddr;
 use tracing::trace;
 
 use crate::{
-    common::peer::{AddressSource, KnownAddress, Source, Store},
+    common::peer::{AddressBook, AddressSource, KnownAddress, Source, Store},
     common::time::Clock,
     fsm,
     fsm::output::{Io, Outbox},
-    net::{Disconnect, LocalDuration, LocalTime},
+    net::{proxy, Disconnect, LocalDuration, LocalTime},
 };
 
 use super::output::{SetTimer, Wire};
@@ -32,6 +32,14 @@ const MAX_ADDR_ADDRESSES: usize = 1000;
 /// Maximum number of addresses we store for a given address range.
 const MAX_RANGE_SIZE: usize = 256;
 
+/// Misbehavior score starting from which the peer is banned.
+pub const BAN_SCORE_THRESHOLD: u32 = 100;
+/// How long the misbehaving peer stays banned.
+pub const BAN_DURATION: LocalDuration = LocalDuration::from_mins(24 * 60);
+/// Maximum number of the peers with the misbehavior scores. The lowest score is forgotten to
+/// make room for a new one.
+pub const MAX_SCORED_PEERS: usize = 1000;
+
 /// An event emitted by the address manager.
 #[derive(Debug, Clone)]
 pub enum Event {
@@ -113,7 +121,13 @@ impl<P: Store, U, C> AddressManager<P, U, C> {
 pub struct AddressManager<P, U, C> {
     /// Peer address store.
     peers: P,
-    bans: HashSet<net::IpAddr>,
+    /// Banned IP addresses with the time until which they are banned.
+    bans: HashMap<net::IpAddr, LocalTime>,
+    /// Misbehavior scores of the peers that aren't banned yet, at most [`MAX_SCORED_PEERS`].
+    scores: HashMap<net::IpAddr, u32>,
+    /// Configured peers, which are reconnected regardless of their misbehavior, so they are
+    /// never scored, banned, or removed from the address book.
+    persistent: HashSet<SocketAddr>,
     address_ranges: HashMap<u8, HashSet<SocketAddr>>,
     connected: HashSet<SocketAddr>,
     sources: HashSet<net::SocketAddr>,
@@ -158,6 +172,10 @@ impl<P: Store, U: Wire<Event> + SetTimer, C: Clock> AddressManager<P, U, C> {
         for range in self.address_ranges.values() {
             let ix = self.rng.usize(..range.len());
             let ip = range.iter().nth(ix).expect("index must be present");
+            // Onion peers are known only to this node.
+            if proxy::is_onion(ip) {
+                continue;
+            }
             let ka = self.peers.get(ip).expect("address must exist");
 
             addrs.push((
@@ -254,13 +272,17 @@ impl<P: Store, U: Wire<Event> + SetTimer, C: Clock> AddressManager<P, U, C> {
             // Disconnected peers cannot be used as a source for new addresses.
             self.sources.remove(addr);
 
+            if self.persistent.contains(addr) {
+                return;
+            }
+
             // If the reason for disconnecting the peer suggests that we shouldn't try to
             // connect to this peer again, then remove the peer from the address book.
             // Otherwise, we leave it in the address buckets so that it can be chosen
             // in the future.
             if let Disconnect::StateMachine(r) = reason {
                 if !r.is_transient() {
-                    self.ban(addr);
+                    self.misbehaved(addr, r.ban_score());
                 }
             } else if reason.is_dial_err() || reason.is_banned() {
                 self.ban(addr);
@@ -269,8 +291,12 @@ impl<P: Store, U: Wire<Event> + SetTimer, C: Clock> AddressManager<P, U, C> {
     }
 
     fn idle(&mut self) {
-        // If it's been a while, save addresses to store.
-        self.last_idle = Some(self.clock.local_time());
+        let time = self.clock.local_time();
+
+        // Forgive the peers whose bans have expired.
+        self.bans.retain(|_, banned_until| *banned_until > time);
+
+        self.last_idle = Some(time);
         self.outbox.set_timer(IDLE_TIMEOUT);
     }
 }
@@ -281,7 +307,9 @@ impl<P: Store, U: Wire<Event>, C: Clock> AddressManager<P, U, C> {
         let addrs = peers.iter().map(|(addr, _)| *addr).collect::<Vec<_>>();
         let mut addrmgr = Self {
             peers,
-            bans: HashSet::new(),
+            bans: HashMap::new(),
+            scores: HashMap::new(),
+            persistent: HashSet::new(),
             address_ranges: HashMap::new(),
             connected: HashSet::new(),
             sources: HashSet::new(),
@@ -310,9 +338,72 @@ impl<P: Store, U: Wire<Event>, C: Clock> AddressManager<P, U, C> {
         self.peers.is_empty() || self.address_ranges.is_empty()
     }
 
-    /// Whether peen is banned
+    /// Whether peer is banned
     pub fn is_banned(&self, addr: &SocketAddr) -> bool {
-        self.bans.contains(&addr.ip())
+        self.bans.get(&addr.ip()).map_or(false, |banned_until| {
+            *banned_until > self.clock.local_time()
+        })
+    }
+
+    /// Add bans, e.g. the ones that were persisted before the restart. Expired bans are skipped.
+    pub fn import_bans(&mut self, bans: impl IntoIterator<Item = (net::IpAddr, LocalTime)>) {
+        let time = self.clock.local_time();
+
+        self.bans.extend(
+            bans.into_iter()
+                .filter(|(_, banned_until)| *banned_until > time),
+        );
+    }
+
+    /// Set the configured peers, which are never scored, banned, or removed from the address
+    /// book.
+    pub fn set_persistent(&mut self, peers: impl IntoIterator<Item = SocketAddr>) {
+        self.persistent = peers.into_iter().collect();
+    }
+
+    /// Snapshot of the known addresses and active bans.
+    pub fn address_book(&self) -> AddressBook {
+        let time = self.clock.local_time();
+
+        AddressBook {
+            addresses: self.peers.iter().map(|(_, ka)| ka.clone()).collect(),
+            bans: self
+                .bans
+                .iter()
+                .filter(|(_, banned_until)| **banned_until > time)
+                .map(|(ip, banned_until)| (*ip, *banned_until))
+                .collect(),
+        }
+    }
+
+    /// Add the misbehavior score to the peer. The peer is banned for [`BAN_DURATION`] once its
+    /// score reaches [`BAN_SCORE_THRESHOLD`].
+    pub fn misbehaved(&mut self, addr: &SocketAddr, score: u32) {
+        if self.persistent.contains(addr) {
+            return;
+        }
+
+        if self.scores.len() >= MAX_SCORED_PEERS && !self.scores.contains_key(&addr.ip()) {
+            let lowest = self
+                .scores
+                .iter()
+                .min_by_key(|(_, score)| **score)
+                .map(|(ip, _)| *ip);
+
+            if let Some(ip) = lowest {
+                self.scores.remove(&ip);
+            }
+        }
+
+        let total = self.scores.entry(addr.ip()).or_default();
+        *total = total.saturating_add(score);
+
+        trace!("Peer {} misbehaved, score = {}", addr, total);
+
+        if *total >= BAN_SCORE_THRESHOLD {
+            self.scores.remove(&addr.ip());
+            self.ban(addr);
+        }
     }
 
     /// Called when we received an `addr` message from a peer.
@@ -352,7 +443,7 @@ impl<P: Store, U: Wire<Event>, C: Clock> AddressManager<P, U, C> {
             };
 
             // No banned addresses.
-            if self.bans.contains(&socket_addr.ip()) {
+            if self.is_banned(&socket_addr) {
                 continue;
             }
 
@@ -361,6 +452,11 @@ impl<P: Store, U: Wire<Event>, C: Clock> AddressManager<P, U, C> {
                 continue;
             }
 
+            // No onion addresses, they can't be dialed without the onion host name.
+            if proxy::is_onion(&socket_addr) {
+                continue;
+            }
+
             // Record the address, and ignore addresses we already know.
             // Note that this should never overwrite an existing address.
             if !self.peers.insert(
@@ -485,23 +581,22 @@ impl<P: Store, U: Wire<Event>, C: Clock> AddressManager<P, U, C> {
         key
     }
 
-    /// Remove an address from the address book and prevent it from being sampled again.
-    fn ban(&mut self, addr: &SocketAddr) -> bool {
+    /// Remove an address from the address book and prevent it from being sampled again
+    /// for [`BAN_DURATION`].
+    fn ban(&mut self, addr: &SocketAddr) {
         let key = addr_key(&addr.ip());
 
+        self.peers.remove(addr);
+        self.bans
+            .insert(addr.ip(), self.clock.local_time() + BAN_DURATION);
+
         if let Some(range) = self.address_ranges.get_mut(&key) {
             range.remove(addr);
 
-            // TODO: Persist bans.
-            self.peers.remove(addr);
-            self.bans.insert(addr.ip());
-
             if range.is_empty() {
                 self.address_ranges.remove(&key);
             }
-            return true;
         }
-        false
     }
 }
 
@@ -568,3 +663,109 @@ pub fn addr_key(ip: &net::IpAddr) -> u8 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use yuv_types::network::Network;
+
+    use super::*;
+    use crate::fsm::handler::{DisconnectReason, MISBEHAVIOR_SCORE};
+
+    type TestAddressManager = AddressManager<HashMap<SocketAddr, KnownAddress>, Outbox, LocalTime>;
+
+    fn addrmgr() -> TestAddressManager {
+        let mut addrmgr = AddressManager::new(
+            fastrand::Rng::with_seed(1),
+            HashMap::new(),
+            Outbox::new(Network::Regtest),
+            LocalTime::now(),
+        );
+        addrmgr.initialize();
+
+        addrmgr
+    }
+
+    fn addr(id: u32) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::from(0x0808_0000 + id)), 8333)
+    }
+
+    #[test]
+    fn test_peer_is_banned_once_score_reaches_threshold() {
+        let mut addrmgr = addrmgr();
+
+        addrmgr.misbehaved(&addr(1), MISBEHAVIOR_SCORE);
+        assert!(!addrmgr.is_banned(&addr(1)));
+
+        addrmgr.misbehaved(&addr(1), MISBEHAVIOR_SCORE);
+        assert!(addrmgr.is_banned(&addr(1)));
+        assert!(
+            addrmgr.scores.is_empty(),
+            "score of the banned peer is dropped"
+        );
+
+        let address_book = addrmgr.address_book();
+        assert_eq!(address_book.bans.len(), 1);
+        assert_eq!(address_book.bans[0].0, addr(1).ip());
+    }
+
+    #[test]
+    fn test_lowest_score_is_evicted_when_scores_are_full() {
+        let mut addrmgr = addrmgr();
+
+        addrmgr.misbehaved(&addr(0), 1);
+        for id in 1..MAX_SCORED_PEERS as u32 {
+            addrmgr.misbehaved(&addr(id), 2);
+        }
+        assert_eq!(addrmgr.scores.len(), MAX_SCORED_PEERS);
+
+        let newcomer = addr(MAX_SCORED_PEERS as u32);
+        addrmgr.misbehaved(&newcomer, 2);
+
+        assert_eq!(addrmgr.scores.len(), MAX_SCORED_PEERS);
+        assert!(!addrmgr.scores.contains_key(&addr(0).ip()));
+        assert_eq!(addrmgr.scores.get(&newcomer.ip()), Some(&2));
+
+        // The known peer's score is added to without evicting anyone.
+        addrmgr.misbehaved(&addr(1), 2);
+        assert_eq!(addrmgr.scores.len(), MAX_SCORED_PEERS);
+        assert_eq!(addrmgr.scores.get(&addr(1).ip()), Some(&4));
+    }
+
+    #[test]
+    fn test_persistent_peer_is_never_banned() {
+        let mut addrmgr = addrmgr();
+        let persistent = addr(1);
+        let discovered = addr(2);
+        addrmgr.set_persistent([persistent]);
+
+        for peer in [persistent, discovered] {
+            addrmgr.peer_connected(&peer);
+            addrmgr.peer_disconnected(
+                &peer,
+                Disconnect::StateMachine(DisconnectReason::PeerServices(ServiceFlags::NONE)),
+            );
+        }
+        addrmgr.misbehaved(&persistent, BAN_SCORE_THRESHOLD);
+
+        assert!(!addrmgr.is_banned(&persistent));
+        assert!(addrmgr.is_banned(&discovered));
+        assert!(!addrmgr.scores.contains_key(&persistent.ip()));
+    }
+
+    #[test]
+    fn test_expired_bans_are_not_imported() {
+        let mut addrmgr = addrmgr();
+        let now = LocalTime::now();
+
+        addrmgr.import_bans([
+            (addr(1).ip(), now + BAN_DURATION),
+            (addr(2).ip(), now - LocalDuration::from_secs(1)),
+        ]);
+
+        assert!(addrmgr.is_banned(&addr(1)));
+        assert!(!addrmgr.is_banned(&addr(2)));
+        assert_eq!(addrmgr.address_book().bans.len(), 1);
+    }
+}
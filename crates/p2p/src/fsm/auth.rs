@@ -0,0 +1,97 @@
+//! Peer authentication.
+//!
+//! Nodes with the configured key send the `yuvauth` message after the `version` one, with the
+//! signature of the nonce of the handshake, and sign their inventories to the authenticated
+//! peers with the same key. So a node can accept the inventories and the transactions only from
+//! the trusted peers, e.g. the members of its federation, and ignore the spam from the others.
+//!
+//! Only the origin of the messages is authenticated, the messages themselves aren't encrypted.
+use std::collections::HashSet;
+
+use bitcoin::consensus::serialize;
+use bitcoin::hashes::sha256;
+use bitcoin::secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use yuv_types::messages::p2p::{Inventory, PeerAuth, SignedInventory};
+
+use crate::net::Link;
+
+/// Tag of the signed `yuvauth` data, so the signature can't be used for anything else.
+const AUTH_TAG: &[u8] = b"yuvauth";
+/// Tag of the signed `signedinv` data.
+const INV_TAG: &[u8] = b"signedinv";
+
+/// Peer authentication configuration.
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    /// Key to authenticate with and to sign the inventories. If not set, the node doesn't
+    /// authenticate itself.
+    pub secret_key: Option<SecretKey>,
+    /// Keys of the peers whose inventories and transactions are accepted. If empty, they are
+    /// accepted from all the peers.
+    pub trusted_peers: HashSet<PublicKey>,
+}
+
+impl AuthConfig {
+    /// Check whether the messages from the peer authenticated with the `key` are accepted.
+    pub fn is_trusted(&self, key: Option<&PublicKey>) -> bool {
+        self.trusted_peers.is_empty() || key.is_some_and(|key| self.trusted_peers.contains(key))
+    }
+}
+
+/// Sign the handshake `nonce` with the key. `link` is the signer's link to the peer, so the
+/// peer can't send the signature back as its own.
+pub fn sign_auth(secret_key: &SecretKey, nonce: u64, link: Link) -> PeerAuth {
+    let ctx = Secp256k1::signing_only();
+
+    PeerAuth {
+        pubkey: secret_key.public_key(&ctx),
+        signature: ctx.sign_ecdsa(&auth_message(nonce, link.is_outbound()), secret_key),
+    }
+}
+
+/// Verify the `yuvauth` of the peer. `link` is our link to the peer.
+pub fn verify_auth(auth: &PeerAuth, nonce: u64, link: Link) -> bool {
+    Secp256k1::verification_only()
+        .verify_ecdsa(
+            &auth_message(nonce, link.is_inbound()),
+            &auth.signature,
+            &auth.pubkey,
+        )
+        .is_ok()
+}
+
+/// Sign the inventory to the peer with the handshake `nonce`.
+pub fn sign_inv(secret_key: &SecretKey, nonce: u64, inv: Vec<Inventory>) -> SignedInventory {
+    let signature = Secp256k1::signing_only().sign_ecdsa(&inv_message(nonce, &inv), secret_key);
+
+    SignedInventory { inv, signature }
+}
+
+/// Verify the inventory signed by the peer authenticated with the `pubkey`.
+pub fn verify_inv(signed_inv: &SignedInventory, nonce: u64, pubkey: &PublicKey) -> bool {
+    Secp256k1::verification_only()
+        .verify_ecdsa(
+            &inv_message(nonce, &signed_inv.inv),
+            &signed_inv.signature,
+            pubkey,
+        )
+        .is_ok()
+}
+
+fn auth_message(nonce: u64, is_initiator: bool) -> Message {
+    let mut data = AUTH_TAG.to_vec();
+    data.extend_from_slice(&nonce.to_le_bytes());
+    data.push(is_initiator as u8);
+
+    Message::from_hashed_data::<sha256::Hash>(&data)
+}
+
+fn inv_message(nonce: u64, inv: &[Inventory]) -> Message {
+    let mut data = INV_TAG.to_vec();
+    data.extend_from_slice(&nonce.to_le_bytes());
+    for item in inv {
+        data.extend(serialize(item));
+    }
+
+    Message::from_hashed_data::<sha256::Hash>(&data)
+}
@@ -3,25 +3,30 @@ use std::{borrow::Cow, collections::HashSet, fmt, net, net::SocketAddr, sync::Ar
 
 use async_trait::async_trait;
 use bitcoin::network::Magic;
+use bitcoin::Txid;
 use bitcoin::{locktime::absolute::Height, network::constants::ServiceFlags, network::Address};
 use flume as chan;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 use event_bus::{typeid, EventBus};
-use yuv_types::messages::p2p::{Inventory, NetworkMessage, RawNetworkMessage};
+use yuv_types::messages::p2p::{
+    CompactYuvTxs, GetYuvTxn, Inventory, NetworkMessage, RawNetworkMessage,
+};
 use yuv_types::network::Network;
 use yuv_types::{ControllerMessage, ControllerP2PMessage, YuvTransaction};
 
 use crate::fsm::output::Outbox;
 use crate::{
     common::peer,
-    common::peer::AddressSource,
+    common::peer::{AddressBook, AddressSource},
     common::time::AdjustedClock,
-    fsm::addrmgr::AddressManager,
+    fsm::addrmgr::{self, AddressManager},
+    fsm::auth::AuthConfig,
     fsm::event::Event,
-    fsm::invmgr::InventoryManager,
+    fsm::invmgr::{self, InventoryManager},
     fsm::peermgr::PeerManager,
     fsm::pingmgr::PingManager,
+    fsm::ratelimit::{DroppedMessages, Limited, RateLimit, RateLimiter, RateLimits},
     fsm::{output, peermgr, pingmgr},
     net::{Disconnect, Link, LocalDuration, LocalTime},
 };
@@ -29,15 +34,24 @@ use crate::{
 pub type PeerId = net::SocketAddr;
 
 /// Peer-to-peer protocol version.
-pub const PROTOCOL_VERSION: u32 = 100000;
+pub const PROTOCOL_VERSION: u32 = 100001;
+/// Minimum protocol version of the peers.
+pub const MIN_PROTOCOL_VERSION: u32 = 100000;
+/// Protocol version since which the transactions can be compressed.
+pub const COMPRESSION_VERSION: u32 = 100001;
 /// User agent included in `version` messages.
 pub const USER_AGENT: &str = concat!("/yuv:", env!("CARGO_PKG_VERSION"), "/");
+/// Misbehavior score for the protocol violations that could be caused by a faulty, but honest
+/// peer. Peer is banned after the second one.
+pub const MISBEHAVIOR_SCORE: u32 = addrmgr::BAN_SCORE_THRESHOLD / 2;
 
 /// Configured limits.
 #[derive(Debug, Clone)]
 pub struct Limits {
     /// Target outbound peer connections.
     pub max_outbound_peers: usize,
+    /// Minimum outbound peer connections, below which they are maintained more often.
+    pub min_outbound_peers: usize,
     /// Maximum inbound peer connections.
     pub max_inbound_peers: usize,
 }
@@ -46,6 +60,7 @@ impl Default for Limits {
     fn default() -> Self {
         Self {
             max_outbound_peers: peermgr::TARGET_OUTBOUND_PEERS,
+            min_outbound_peers: peermgr::MIN_OUTBOUND_PEERS,
             max_inbound_peers: peermgr::MAX_INBOUND_PEERS,
         }
     }
@@ -132,6 +147,15 @@ impl DisconnectReason {
     pub fn is_transient(&self) -> bool {
         matches!(self, Self::ConnectionLimit | Self::PeerTimeout(_))
     }
+
+    /// Misbehavior score the peer gets for being disconnected with this reason. Reasons that
+    /// aren't transient get the peer banned at once, except for the protocol violations.
+    pub fn ban_score(&self) -> u32 {
+        match self {
+            Self::PeerMisbehaving(_) | Self::PeerMagic(_) | Self::DecodeError => MISBEHAVIOR_SCORE,
+            _ => addrmgr::BAN_SCORE_THRESHOLD,
+        }
+    }
 }
 
 impl From<DisconnectReason> for crate::net::Disconnect<DisconnectReason> {
@@ -212,6 +236,8 @@ pub struct StateMachine<P, C> {
     pub peermgr: PeerManager<Outbox, C>,
     /// Inventory manager.
     invmgr: InventoryManager<Outbox>,
+    /// Rate limiter of the messages from the peers.
+    ratelimiter: RateLimiter,
     /// Network-adjusted clock.
     pub clock: C,
     /// Last time a "tick" was triggered.
@@ -243,6 +269,22 @@ pub struct Config {
     pub ping_timeout: LocalDuration,
     /// Configured limits.
     pub limits: Limits,
+    /// DNS seeds to discover peers with.
+    pub dns_seeds: Vec<String>,
+    /// Whether to discover peers with the DNS seeds.
+    pub dns_seeding: bool,
+    /// Bans to restore, e.g. from the persisted address book.
+    pub bans: Vec<(net::IpAddr, LocalTime)>,
+    /// Peer authentication.
+    pub auth: AuthConfig,
+    /// Limits of the messages a peer can send.
+    pub rate_limits: RateLimits,
+    /// Whether to reconcile the inventories with the supporting peers.
+    pub reconciliation: bool,
+    /// Whether to announce the transactions with the short ids to the supporting peers.
+    pub compact_relay: bool,
+    /// Whether to compress the transactions sent to the supporting peers.
+    pub compression: bool,
 }
 
 impl Default for Config {
@@ -256,6 +298,14 @@ impl Default for Config {
             ping_timeout: pingmgr::PING_TIMEOUT,
             user_agent: USER_AGENT,
             limits: Limits::default(),
+            dns_seeds: Vec::new(),
+            dns_seeding: true,
+            bans: Vec::new(),
+            auth: AuthConfig::default(),
+            rate_limits: RateLimits::default(),
+            reconciliation: false,
+            compact_relay: false,
+            compression: false,
         }
     }
 }
@@ -283,14 +333,24 @@ pub enum Command {
     Disconnect(SocketAddr),
     /// Import addresses into the address book.
     ImportAddresses(Vec<Address>),
-    /// Send Inv message to the desired peer
-    SendInv(Vec<Inventory>),
+    /// Send Inv message to the peers. The transactions of the inventory are matched against
+    /// the filters of the light peers.
+    SendInv(Vec<Inventory>, Vec<YuvTransaction>),
     /// Send GetData message to the desired peer
     SendGetData(Vec<Inventory>, SocketAddr),
     /// Send GetData message to the desired peer
     SendYuvTransactions(Vec<YuvTransaction>, SocketAddr),
+    /// Announce the transactions with the short ids to the peers that support the compact
+    /// relay. The transactions from the set are sent in full.
+    AnnounceYuvTransactions(Vec<YuvTransaction>, HashSet<Txid>),
+    /// Request the missing transactions of the compact announcement from the desired peer
+    SendGetYuvTxn(GetYuvTxn, SocketAddr),
     /// Forbid some peer to connect to us
     BanPeer(SocketAddr),
+    /// Get a snapshot of the address book.
+    GetAddressBook(chan::Sender<AddressBook>),
+    /// Get the numbers of the messages dropped due to the rate limits.
+    GetDroppedMessages(chan::Sender<DroppedMessages>),
 }
 
 impl fmt::Debug for Command {
@@ -299,15 +359,21 @@ impl fmt::Debug for Command {
             Self::GetPeers(flags, _) => write!(f, "GetPeers({})", flags),
             Self::Broadcast(msg, _, _) => write!(f, "Broadcast({:?})", msg),
             Self::Query(msg, _) => write!(f, "Query({:?})", msg),
-            Self::SendInv(msg) => write!(f, "SendInv({:?})", msg),
+            Self::SendInv(msg, _) => write!(f, "SendInv({:?})", msg),
             Self::SendGetData(msg, addr) => write!(f, "SendGetData({:?}) to {:?}", msg, addr),
             Self::SendYuvTransactions(msg, addr) => {
                 write!(f, "SendYuvTransactions({:?}) to {:?}", msg, addr)
             }
+            Self::AnnounceYuvTransactions(msg, prefilled) => {
+                write!(f, "AnnounceYuvTransactions({:?}, {:?})", msg, prefilled)
+            }
+            Self::SendGetYuvTxn(msg, addr) => write!(f, "SendGetYuvTxn({:?}) to {:?}", msg, addr),
             Self::Connect(addr) => write!(f, "Connect({})", addr),
             Self::Disconnect(addr) => write!(f, "Disconnect({})", addr),
             Self::ImportAddresses(addrs) => write!(f, "ImportAddresses({:?})", addrs),
             Self::BanPeer(addr) => write!(f, "BanPeer({:?})", addr),
+            Self::GetAddressBook(_) => write!(f, "GetAddressBook"),
+            Self::GetDroppedMessages(_) => write!(f, "GetDroppedMessages"),
         }
     }
 }
@@ -350,8 +416,8 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> StateMachine
                     peer::Source::Imported,
                 );
             }
-            Command::SendInv(txids) => {
-                self.broadcast(NetworkMessage::Inv(txids), |_| true);
+            Command::SendInv(txids, txs) => {
+                self.send_inv(txids, txs);
             }
             Command::SendGetData(txids, addr) => {
                 self.send(NetworkMessage::GetData(txids), addr);
@@ -359,10 +425,20 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> StateMachine
             Command::SendYuvTransactions(txs, addr) => {
                 self.send(NetworkMessage::YuvTx(txs), addr);
             }
+            Command::AnnounceYuvTransactions(txs, prefilled) => {
+                self.announce_yuv_txs(txs, prefilled);
+            }
+            Command::SendGetYuvTxn(request, addr) => {
+                self.send(NetworkMessage::GetYuvTxn(request), addr);
+            }
             Command::BanPeer(addr) => {
-                self.addrmgr
-                    .peer_disconnected(&addr, Disconnect::PeerBanned);
-                self.peermgr.disconnect(addr, DisconnectReason::PeerBanned);
+                self.ban_peer(addr);
+            }
+            Command::GetAddressBook(reply) => {
+                reply.send_async(self.addrmgr.address_book()).await.ok();
+            }
+            Command::GetDroppedMessages(reply) => {
+                reply.send_async(self.ratelimiter.dropped()).await.ok();
             }
         }
     }
@@ -407,6 +483,11 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> crate::net::
 
         match msg.payload.clone() {
             NetworkMessage::Inv(inv) => {
+                if !self.peermgr.is_trusted(&addr) {
+                    debug!(target: "p2p", "Ignored inventory from untrusted peer {}", addr);
+                    return;
+                }
+
                 self.event_bus
                     .send(ControllerMessage::P2P(ControllerP2PMessage::Inv {
                         inv,
@@ -422,7 +503,24 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> crate::net::
                     }))
                     .await;
             }
+            NetworkMessage::SignedInv(signed_inv) => {
+                let Some(inv) = self.peermgr.received_signed_inv(&addr, signed_inv) else {
+                    return;
+                };
+
+                self.event_bus
+                    .send(ControllerMessage::P2P(ControllerP2PMessage::Inv {
+                        inv,
+                        sender: addr,
+                    }))
+                    .await;
+            }
             NetworkMessage::YuvTx(txs) => {
+                if !self.peermgr.is_trusted(&addr) {
+                    debug!(target: "p2p", "Ignored transactions from untrusted peer {}", addr);
+                    return;
+                }
+
                 self.event_bus
                     .send(ControllerMessage::P2P(ControllerP2PMessage::YuvTx {
                         txs,
@@ -461,6 +559,101 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> crate::net::
             NetworkMessage::Version(msg) => {
                 self.peermgr.received_version(&addr, msg, &mut self.addrmgr);
             }
+            NetworkMessage::Auth(msg) => {
+                self.peermgr.received_auth(&addr, msg);
+            }
+            NetworkMessage::SendRecon => {
+                self.peermgr.received_sendrecon(&addr);
+            }
+            NetworkMessage::Sketch(sketch) => {
+                let Some(salt) = self.peermgr.reconciliation_salt(&addr) else {
+                    self.disconnect(
+                        addr,
+                        DisconnectReason::PeerMisbehaving("`sketch` from non-reconciling peer"),
+                    );
+                    return;
+                };
+
+                if sketch.syndromes.len() > invmgr::MAX_SKETCH_CAPACITY {
+                    self.disconnect(
+                        addr,
+                        DisconnectReason::PeerMisbehaving("`sketch` capacity exceeds the limit"),
+                    );
+                    return;
+                }
+
+                let (inv, diff) = self.invmgr.received_sketch(salt, sketch);
+                if !inv.is_empty() {
+                    self.send_inv_to(inv, addr);
+                }
+                self.send(NetworkMessage::ReconcilDiff(diff), addr);
+            }
+            NetworkMessage::ReconcilDiff(diff) => {
+                let Some(salt) = self.peermgr.reconciliation_salt(&addr) else {
+                    self.disconnect(
+                        addr,
+                        DisconnectReason::PeerMisbehaving(
+                            "`reconcildiff` from non-reconciling peer",
+                        ),
+                    );
+                    return;
+                };
+
+                let inv = self.invmgr.received_diff(&addr, salt, diff);
+                if !inv.is_empty() {
+                    self.send_inv_to(inv, addr);
+                }
+            }
+            NetworkMessage::SendCmpct => {
+                self.peermgr.received_sendcmpct(&addr);
+            }
+            NetworkMessage::SendCompr => {
+                self.peermgr.received_sendcompr(&addr);
+            }
+            NetworkMessage::FilterLoad(filter) => {
+                if filter.chromas.len() > peermgr::MAX_FILTER_CHROMAS
+                    || filter.script_prefixes.len() > peermgr::MAX_FILTER_SCRIPT_PREFIXES
+                {
+                    self.disconnect(
+                        addr,
+                        DisconnectReason::PeerMisbehaving("`filterload` exceeds the limits"),
+                    );
+                    return;
+                }
+
+                self.peermgr.received_filterload(&addr, filter);
+            }
+            NetworkMessage::CmpctYuvTxs(txs) => {
+                if !self.peermgr.is_trusted(&addr) {
+                    debug!(target: "p2p", "Ignored transactions from untrusted peer {}", addr);
+                    return;
+                }
+
+                self.event_bus
+                    .send(ControllerMessage::P2P(ControllerP2PMessage::CmpctYuvTxs {
+                        txs,
+                        sender: addr,
+                    }))
+                    .await;
+            }
+            NetworkMessage::GetYuvTxn(request) => {
+                let Some(inv) = self.invmgr.requested_txs(&request) else {
+                    debug!(
+                        target: "p2p",
+                        "Ignored request of unknown announcement {} from {}",
+                        request.nonce,
+                        addr
+                    );
+                    return;
+                };
+
+                self.event_bus
+                    .send(ControllerMessage::P2P(ControllerP2PMessage::GetData {
+                        inv,
+                        sender: addr,
+                    }))
+                    .await;
+            }
             NetworkMessage::GetAddr => {
                 self.addrmgr.received_getaddr(&addr);
             }
@@ -504,6 +697,7 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> crate::net::
             .peer_disconnected(addr, &mut self.addrmgr, reason)
             .await;
         self.invmgr.peer_disconnected(addr);
+        self.ratelimiter.peer_disconnected(addr);
     }
 
     fn is_disconnected(&mut self, addr: SocketAddr) -> bool {
@@ -534,12 +728,17 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> crate::net::
             let target = self.peermgr.config.target_outbound_peers;
             let max_inbound = self.peermgr.config.max_inbound_peers;
             let addresses = self.addrmgr.len();
+            let dropped = self.ratelimiter.dropped();
 
             let mut msg = Vec::new();
 
             msg.push(format!("inbound = {}/{}", inbound, max_inbound));
             msg.push(format!("connecting = {}/{}", connecting, target));
             msg.push(format!("addresses = {}", addresses));
+            msg.push(format!(
+                "dropped = {}/{}/{} (inv/getdata/yuvtx)",
+                dropped.inv, dropped.getdata, dropped.txs
+            ));
 
             debug!(target: "p2p", "{}", msg.join(", "));
 
@@ -580,6 +779,14 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> StateMachine
             user_agent,
             required_services,
             limits,
+            dns_seeds,
+            dns_seeding,
+            bans,
+            auth,
+            rate_limits,
+            reconciliation,
+            compact_relay,
+            compression,
         } = config;
 
         let outbox = Outbox::new(network);
@@ -588,21 +795,30 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> StateMachine
             peermgr::Config {
                 protocol_version: PROTOCOL_VERSION,
                 whitelist,
-                persistent: connect,
+                persistent: connect.clone(),
                 target_outbound_peers: limits.max_outbound_peers,
+                min_outbound_peers: limits.min_outbound_peers,
                 max_inbound_peers: limits.max_inbound_peers,
                 retry_max_wait: LocalDuration::from_mins(60),
                 retry_min_wait: LocalDuration::from_secs(1),
                 required_services,
                 services,
                 user_agent,
+                dns_seeds,
+                dns_seeding,
+                auth,
+                reconciliation,
+                compact_relay,
+                compression,
             },
             rng.clone(),
             outbox.clone(),
             clock.clone(),
             network,
         );
-        let addrmgr = AddressManager::new(rng.clone(), peers, outbox.clone(), clock.clone());
+        let mut addrmgr = AddressManager::new(rng.clone(), peers, outbox.clone(), clock.clone());
+        addrmgr.import_bans(bans);
+        addrmgr.set_persistent(connect);
         let invmgr = InventoryManager::new(outbox.clone());
 
         Self {
@@ -612,6 +828,7 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> StateMachine
             pingmgr,
             peermgr,
             invmgr,
+            ratelimiter: RateLimiter::new(rate_limits),
             last_tick: LocalTime::default(),
             rng,
             outbox,
@@ -628,6 +845,50 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> StateMachine
         self.peermgr.disconnect(addr, reason);
     }
 
+    /// Ban a peer temporarily and disconnect from it.
+    pub fn ban_peer(&mut self, addr: PeerId) {
+        self.addrmgr
+            .peer_disconnected(&addr, Disconnect::PeerBanned);
+        self.peermgr.disconnect(addr, DisconnectReason::PeerBanned);
+    }
+
+    /// Check the message of `size` bytes from the peer against the rate limits. Returns `true`
+    /// if the message must be dropped. The peer that exceeds the limits too often is banned.
+    pub fn is_rate_limited(&mut self, addr: &PeerId, msg: &RawNetworkMessage, size: usize) -> bool {
+        let (kind, amount) = match &msg.payload {
+            NetworkMessage::Inv(inv) => (Limited::Inv, inv.len()),
+            NetworkMessage::SignedInv(signed_inv) => (Limited::Inv, signed_inv.inv.len()),
+            NetworkMessage::Sketch(sketch) => (Limited::Inv, sketch.syndromes.len()),
+            NetworkMessage::ReconcilDiff(_) => (Limited::GetData, 1),
+            NetworkMessage::CmpctYuvTxs(_) => (Limited::TxBytes, size),
+            NetworkMessage::GetYuvTxn(_) => (Limited::GetData, 1),
+            NetworkMessage::GetData(_) => (Limited::GetData, 1),
+            NetworkMessage::YuvTx(_) => (Limited::TxBytes, size),
+            _ => return false,
+        };
+
+        match self
+            .ratelimiter
+            .check(*addr, kind, amount as u64, self.clock.local_time())
+        {
+            RateLimit::Allowed => false,
+            RateLimit::Dropped => {
+                debug!(
+                    target: "p2p",
+                    "Dropped {} from {} due to the rate limits",
+                    msg.payload.cmd(),
+                    addr
+                );
+                true
+            }
+            RateLimit::Ban => {
+                warn!(target: "p2p", "Peer {} keeps exceeding the rate limits, banning it", addr);
+                self.ban_peer(*addr);
+                true
+            }
+        }
+    }
+
     /// Send a message to all negotiated peers matching the predicate.
     fn broadcast<Q>(&mut self, msg: NetworkMessage, predicate: Q) -> Vec<PeerId>
     where
@@ -646,6 +907,106 @@ impl<P: peer::Store + Send, C: AdjustedClock<PeerId> + Sync + Send> StateMachine
         peers
     }
 
+    /// Send the inventory to all negotiated peers, signed to the authenticated ones.
+    ///
+    /// The outbound peers that support the reconciliation get the sketch of the inventory
+    /// instead, and the inbound ones get only the difference when they send their sketches.
+    /// The light peers get only the items whose transactions match their filters.
+    fn send_inv(&mut self, inv: Vec<Inventory>, txs: Vec<YuvTransaction>) -> Vec<PeerId> {
+        self.invmgr.inventory_shared(&inv, txs);
+
+        let messages = self
+            .peermgr
+            .peers()
+            .filter(|(peer_info, _)| peer_info.is_negotiated())
+            .filter_map(|(peer_info, connection)| {
+                let addr = connection.socket.addr;
+                if let Some(filter) = &peer_info.filter {
+                    let inv = self.invmgr.filtered(&inv, filter);
+                    if inv.is_empty() {
+                        return None;
+                    }
+
+                    return Some((addr, self.peermgr.inventory_message(peer_info, inv)));
+                }
+
+                let msg = match peer_info.reconciliation_salt() {
+                    Some(salt) if connection.link.is_outbound() => {
+                        NetworkMessage::Sketch(self.invmgr.sketch(&addr, salt))
+                    }
+                    Some(_) => return None,
+                    None => self.peermgr.inventory_message(peer_info, inv.clone()),
+                };
+
+                Some((addr, msg))
+            })
+            .collect::<Vec<_>>();
+
+        messages
+            .into_iter()
+            .map(|(addr, msg)| self.send(msg, addr))
+            .collect()
+    }
+
+    /// Announce the transactions with the short ids to the negotiated peers that support the
+    /// compact relay. The `prefilled` ones are sent in full, as the peers are unlikely to have
+    /// them, and the rest can be requested by the peers with `getyuvtxn`. The light peers learn
+    /// about the transactions from the filtered inventory instead.
+    fn announce_yuv_txs(
+        &mut self,
+        txs: Vec<YuvTransaction>,
+        prefilled: HashSet<Txid>,
+    ) -> Vec<PeerId> {
+        let peers = self
+            .peermgr
+            .peers()
+            .filter(|(peer_info, _)| {
+                peer_info.is_negotiated() && peer_info.compact_relay && peer_info.filter.is_none()
+            })
+            .map(|(_, connection)| connection.socket.addr)
+            .collect::<Vec<_>>();
+
+        if peers.is_empty() || txs.is_empty() {
+            return Vec::new();
+        }
+
+        let nonce = self.rng.u64(..);
+        self.invmgr
+            .txs_announced(nonce, txs.iter().map(|tx| tx.bitcoin_tx.txid()).collect());
+
+        let msg = CompactYuvTxs::new(nonce, txs, |tx| prefilled.contains(&tx.bitcoin_tx.txid()));
+        for addr in &peers {
+            self.outbox
+                .message(*addr, NetworkMessage::CmpctYuvTxs(msg.clone()));
+        }
+
+        peers
+    }
+
+    /// Send the inventory to the peer, signed if it's authenticated, and filtered if it's a
+    /// light peer.
+    fn send_inv_to(&mut self, inv: Vec<Inventory>, addr: PeerId) {
+        let msg = self
+            .peermgr
+            .peers()
+            .find(|(_, connection)| connection.socket.addr == addr)
+            .and_then(|(peer_info, _)| {
+                let inv = match &peer_info.filter {
+                    Some(filter) => self.invmgr.filtered(&inv, filter),
+                    None => inv,
+                };
+                if inv.is_empty() {
+                    return None;
+                }
+
+                Some(self.peermgr.inventory_message(peer_info, inv))
+            });
+
+        if let Some(msg) = msg {
+            self.send(msg, addr);
+        }
+    }
+
     /// Send a message to the desired peer
     fn send(&mut self, msg: NetworkMessage, addr: PeerId) -> PeerId {
         self.outbox.message(addr, msg);
@@ -2,19 +2,53 @@
 //! Takes care of sending and fetching inventories.
 //!
 //! ## Handling of reverted blocks
+//!
+//! ## Reconciliation of inventories
+//!
+//! Instead of sending the full inventory, the outbound side of the connection sends the
+//! [`Sketch`] of the short ids of its inventory. The inbound side merges it with the sketch
+//! of its own inventory and decodes the difference, then sends the items the outbound side
+//! is missing and asks for the ones it's missing itself. If the difference can't be decoded,
+//! both sides fall back to sending the full inventories, and the next sketch is larger.
+//!
+//! ## Compact relay
+//!
+//! The transactions announced with the short ids are remembered, so the peers can request the
+//! ones they are missing by their indexes in the announcement.
+//!
+//! ## Filtered relay
+//!
+//! The light peers that have sent a [`ChromaFilter`] get only the items of the inventory whose
+//! transactions match it, and neither the sketches nor the compact announcements.
 
+use bitcoin::hashes::{siphash24, Hash};
 use bitcoin::{BlockHash, Txid};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use std::net::SocketAddr;
 
+use yuv_types::messages::p2p::{
+    ChromaFilter, GetYuvTxn, Inventory, InventorySketch, ReconciliationDiff,
+};
+use yuv_types::YuvTransaction;
+
 use super::output::{SetTimer, Wire};
 use crate::{
     common::collections::AddressBook,
+    common::sketch::Sketch,
     fsm::handler::{PeerId, Socket},
     net::{LocalDuration, LocalTime},
 };
 
+/// Capacity of the first sketch sent to a peer.
+pub const DEFAULT_SKETCH_CAPACITY: usize = 16;
+/// Minimum capacity of the sketches sent to a peer.
+pub const MIN_SKETCH_CAPACITY: usize = 8;
+/// Maximum capacity of the sketches, as the decoding time grows quadratically with it.
+pub const MAX_SKETCH_CAPACITY: usize = 64;
+/// Number of the last compact announcements whose transactions can be requested.
+pub const MAX_ANNOUNCEMENTS: usize = 16;
+
 /// An event emitted by the inventory manager.
 #[derive(Debug, Clone)]
 pub enum Event {
@@ -47,6 +81,9 @@ pub struct Peer {
     /// Number of times a certain block was requested.
     requests: HashMap<BlockHash, usize>,
 
+    /// Capacity of the next sketch sent to the peer.
+    sketch_capacity: usize,
+
     /// Peer socket.
     _socket: Socket,
 }
@@ -63,6 +100,12 @@ impl Peer {
 pub struct InventoryManager<U> {
     /// Peer map.
     peers: AddressBook<PeerId, Peer>,
+    /// Inventory shared with the peers last time, which is reconciled with their sketches.
+    inventory: Vec<Inventory>,
+    /// Transactions of the shared inventory, matched against the filters of the light peers.
+    inventory_txs: HashMap<Txid, YuvTransaction>,
+    /// Nonces and txids of the last compact announcements, from the oldest one.
+    announcements: VecDeque<(u64, Vec<Txid>)>,
 
     last_tick: Option<LocalTime>,
     upstream: U,
@@ -73,6 +116,9 @@ impl<U: Wire<Event> + SetTimer> InventoryManager<U> {
     pub fn new(upstream: U) -> Self {
         Self {
             peers: AddressBook::new(),
+            inventory: Vec::new(),
+            inventory_txs: HashMap::new(),
+            announcements: VecDeque::new(),
             last_tick: None,
             upstream,
         }
@@ -85,6 +131,7 @@ impl<U: Wire<Event> + SetTimer> InventoryManager<U> {
             socket.addr,
             Peer {
                 requests: HashMap::new(),
+                sketch_capacity: DEFAULT_SKETCH_CAPACITY,
                 _socket: socket,
             },
         );
@@ -99,8 +146,167 @@ impl<U: Wire<Event> + SetTimer> InventoryManager<U> {
         self.peers.contains_key(addr)
     }
 
+    /// Called when the inventory is shared with the peers.
+    pub fn inventory_shared(&mut self, inv: &[Inventory], txs: Vec<YuvTransaction>) {
+        self.inventory = inv.to_vec();
+        self.inventory_txs = txs
+            .into_iter()
+            .map(|tx| (tx.bitcoin_tx.txid(), tx))
+            .collect();
+    }
+
+    /// Items of the inventory whose transactions match the filter. Items without the known
+    /// transactions are skipped.
+    pub fn filtered(&self, inv: &[Inventory], filter: &ChromaFilter) -> Vec<Inventory> {
+        inv.iter()
+            .filter(|item| {
+                let Inventory::Ytx(txid) = item;
+                self.inventory_txs
+                    .get(txid)
+                    .is_some_and(|tx| filter.matches(tx))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Sketch of the shared inventory to reconcile with the peer.
+    pub fn sketch(&self, addr: &PeerId, salt: u64) -> InventorySketch {
+        let capacity = self
+            .peers
+            .get(addr)
+            .map_or(DEFAULT_SKETCH_CAPACITY, |peer| peer.sketch_capacity);
+
+        let mut sketch = Sketch::new(capacity);
+        for item in &self.inventory {
+            sketch.add(short_id(salt, item));
+        }
+
+        InventorySketch {
+            syndromes: sketch.into_syndromes(),
+        }
+    }
+
+    /// Called when a sketch was received from the peer. Returns the items of the shared
+    /// inventory the peer is missing, and the reply with the short ids of the items we are
+    /// missing. If the difference can't be decoded, the full inventory is returned.
+    pub fn received_sketch(
+        &self,
+        salt: u64,
+        sketch: InventorySketch,
+    ) -> (Vec<Inventory>, ReconciliationDiff) {
+        let short_ids = self.short_ids(salt);
+
+        let mut merged = Sketch::new(sketch.syndromes.len());
+        for short_id in short_ids.keys() {
+            merged.add(*short_id);
+        }
+        merged.merge(&Sketch::from_syndromes(sketch.syndromes));
+
+        let Some(difference) = merged.decode() else {
+            let diff = ReconciliationDiff {
+                success: false,
+                difference: 0,
+                short_ids: Vec::new(),
+            };
+
+            return (self.inventory.clone(), diff);
+        };
+
+        let difference_len = difference.len() as u32;
+        let (ours, missing): (Vec<u32>, Vec<u32>) = difference
+            .into_iter()
+            .partition(|short_id| short_ids.contains_key(short_id));
+
+        let diff = ReconciliationDiff {
+            success: true,
+            difference: difference_len,
+            short_ids: missing,
+        };
+        let inv = ours
+            .iter()
+            .filter_map(|short_id| short_ids.get(short_id).cloned())
+            .collect();
+
+        (inv, diff)
+    }
+
+    /// Called when the result of the reconciliation of our sketch was received from the peer.
+    /// Returns the items of the shared inventory the peer has asked for, or the full inventory
+    /// if the reconciliation failed. The capacity of the next sketch is adjusted to the size of
+    /// the difference.
+    pub fn received_diff(
+        &mut self,
+        addr: &PeerId,
+        salt: u64,
+        diff: ReconciliationDiff,
+    ) -> Vec<Inventory> {
+        if let Some(peer) = self.peers.get_mut(addr) {
+            peer.sketch_capacity = if diff.success {
+                (diff.difference as usize * 2).clamp(MIN_SKETCH_CAPACITY, MAX_SKETCH_CAPACITY)
+            } else {
+                (peer.sketch_capacity * 2).min(MAX_SKETCH_CAPACITY)
+            };
+        }
+
+        if !diff.success {
+            return self.inventory.clone();
+        }
+
+        let short_ids = self.short_ids(salt);
+
+        diff.short_ids
+            .iter()
+            .filter_map(|short_id| short_ids.get(short_id).cloned())
+            .collect()
+    }
+
+    /// Called when the transactions are announced with the short ids.
+    pub fn txs_announced(&mut self, nonce: u64, txids: Vec<Txid>) {
+        if self.announcements.len() >= MAX_ANNOUNCEMENTS {
+            self.announcements.pop_front();
+        }
+        self.announcements.push_back((nonce, txids));
+    }
+
+    /// Inventory of the announced transactions requested by the peer. Returns `None` if the
+    /// announcement is unknown or already forgotten. Indexes out of the announcement are
+    /// skipped.
+    pub fn requested_txs(&self, request: &GetYuvTxn) -> Option<Vec<Inventory>> {
+        let (_, txids) = self
+            .announcements
+            .iter()
+            .find(|(nonce, _)| *nonce == request.nonce)?;
+
+        let inv = request
+            .indexes
+            .iter()
+            .filter_map(|index| txids.get(*index as usize))
+            .map(|txid| Inventory::Ytx(*txid))
+            .collect();
+
+        Some(inv)
+    }
+
+    /// Short ids of the shared inventory items.
+    fn short_ids(&self, salt: u64) -> HashMap<u32, Inventory> {
+        self.inventory
+            .iter()
+            .map(|item| (short_id(salt, item), item.clone()))
+            .collect()
+    }
+
     fn schedule_tick(&mut self) {
         self.last_tick = None; // Disable rate-limiting for the next tick.
         self.upstream.set_timer(LocalDuration::from_secs(1));
     }
 }
+
+/// Short id of the inventory item in the reconciliation. It's salted per connection, so the
+/// collisions can't be crafted for all the peers at once.
+fn short_id(salt: u64, item: &Inventory) -> u32 {
+    let Inventory::Ytx(txid) = item;
+    let hash = siphash24::Hash::hash_with_keys(salt, 0, txid.as_byte_array()).as_u64();
+
+    // Zero can't be added to the sketch.
+    ((hash >> 32) as u32 ^ hash as u32).max(1)
+}
@@ -1,7 +1,9 @@
 pub(crate) mod addrmgr;
+pub(crate) mod auth;
 pub(crate) mod event;
 pub(crate) mod handler;
 pub(crate) mod invmgr;
 pub(crate) mod output;
 pub(crate) mod peermgr;
 pub(crate) mod pingmgr;
+pub(crate) mod ratelimit;
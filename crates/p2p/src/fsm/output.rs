@@ -8,7 +8,7 @@
 use std::sync::{Arc, Mutex};
 use std::{collections::VecDeque, net};
 use tracing::debug;
-use yuv_types::messages::p2p::{NetworkMessage, RawNetworkMessage};
+use yuv_types::messages::p2p::{NetworkMessage, PeerAuth, RawNetworkMessage};
 use yuv_types::network::Network;
 
 use crate::{
@@ -67,6 +67,18 @@ pub trait Wire<E> {
     /// Send a BIP-339 `ytxidack` message.
     fn ytxidack(&mut self, addr: PeerId) -> &mut Self;
 
+    /// Send a `yuvauth` message.
+    fn auth(&mut self, addr: PeerId, auth: PeerAuth) -> &mut Self;
+
+    /// Send a `sendrecon` message.
+    fn send_recon(&mut self, addr: PeerId) -> &mut Self;
+
+    /// Send a `sendcmpct` message.
+    fn send_cmpct(&mut self, addr: PeerId) -> &mut Self;
+
+    /// Send a `sendcompr` message.
+    fn send_compr(&mut self, addr: PeerId) -> &mut Self;
+
     // Ping/pong ///////////////////////////////////////////////////////////////
 
     /// Send a `ping` message.
@@ -195,6 +207,26 @@ impl<E: Into<Event> + std::fmt::Display> Wire<E> for Outbox {
         self
     }
 
+    fn auth(&mut self, addr: PeerId, auth: PeerAuth) -> &mut Self {
+        self.message(addr, NetworkMessage::Auth(auth));
+        self
+    }
+
+    fn send_recon(&mut self, addr: PeerId) -> &mut Self {
+        self.message(addr, NetworkMessage::SendRecon);
+        self
+    }
+
+    fn send_cmpct(&mut self, addr: PeerId) -> &mut Self {
+        self.message(addr, NetworkMessage::SendCmpct);
+        self
+    }
+
+    fn send_compr(&mut self, addr: PeerId) -> &mut Self {
+        self.message(addr, NetworkMessage::SendCompr);
+        self
+    }
+
     fn ping(&mut self, addr: net::SocketAddr, nonce: u64) -> &Self {
         self.message(addr, NetworkMessage::Ping(nonce));
         self
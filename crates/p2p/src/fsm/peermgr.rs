@@ -10,7 +10,10 @@ use trust_dns_resolver::{
 
 use bitcoin::{
     network::address::Address, network::constants::ServiceFlags,
-    network::message_network::VersionMessage,
+    network::message_network::VersionMessage, secp256k1::PublicKey,
+};
+use yuv_types::messages::p2p::{
+    ChromaFilter, Inventory, NetworkMessage, PeerAuth, SignedInventory,
 };
 use yuv_types::network::Network;
 
@@ -18,6 +21,7 @@ use crate::{
     common::peer::{AddressSource, Source},
     common::time::Clock,
     fsm::addrmgr::is_local,
+    fsm::auth::{self, AuthConfig},
     fsm::handler::{DisconnectReason, Whitelist},
     fsm::handler::{PeerId, Socket},
     net::{Disconnect as NetDisconnect, Link, LocalDuration, LocalTime},
@@ -31,10 +35,19 @@ pub const HANDSHAKE_TIMEOUT: LocalDuration = LocalDuration::from_secs(12);
 pub const CONNECTION_TIMEOUT: LocalDuration = LocalDuration::from_secs(6);
 /// Time to wait until idle.
 pub const IDLE_TIMEOUT: LocalDuration = LocalDuration::from_mins(1);
+/// Time to wait until the connections are maintained again while there are fewer outbound
+/// peers than the minimum.
+pub const RECONNECT_TIMEOUT: LocalDuration = LocalDuration::from_secs(10);
 /// Target number of concurrent outbound peer connections.
 pub const TARGET_OUTBOUND_PEERS: usize = 8;
+/// Minimum number of outbound peer connections, below which they are maintained more often.
+pub const MIN_OUTBOUND_PEERS: usize = 2;
 /// Maximum number of inbound peer connections.
 pub const MAX_INBOUND_PEERS: usize = 16;
+/// Maximum number of the chromas in the filter of a light peer.
+pub const MAX_FILTER_CHROMAS: usize = 1000;
+/// Maximum number of the script prefixes in the filter of a light peer.
+pub const MAX_FILTER_SCRIPT_PREFIXES: usize = 100;
 
 /// A time offset, in seconds.
 type TimeOffset = i64;
@@ -62,6 +75,13 @@ pub enum Event {
         /// Protocol version.
         version: u32,
     },
+    /// A peer has authenticated with its key.
+    Authenticated {
+        /// The peer's id.
+        addr: PeerId,
+        /// The key the peer is authenticated with.
+        pubkey: PublicKey,
+    },
     /// Connecting to a peer found from the specified source.
     Connecting(PeerId, Source, ServiceFlags),
     /// Connection attempt failed.
@@ -91,6 +111,9 @@ impl std::fmt::Display for Event {
                 "{}: Peer negotiated with services {}",
                 addr, services
             ),
+            Self::Authenticated { addr, pubkey } => {
+                write!(fmt, "{}: Peer authenticated with key {}", addr, pubkey)
+            }
             Self::Connecting(addr, source, services) => {
                 write!(
                     fmt,
@@ -124,6 +147,8 @@ pub struct Config {
     pub required_services: ServiceFlags,
     /// Target number of outbound peer connections.
     pub target_outbound_peers: usize,
+    /// Minimum number of outbound peer connections.
+    pub min_outbound_peers: usize,
     /// Maximum number of inbound peer connections.
     pub max_inbound_peers: usize,
     /// Maximum time to wait between reconnection attempts.
@@ -132,6 +157,20 @@ pub struct Config {
     pub retry_min_wait: LocalDuration,
     /// Our user agent.
     pub user_agent: &'static str,
+    /// DNS seeds to discover peers with. Seeds are hostnames with an optional port, e.g.
+    /// `seed.example.com` or `seed.example.com:8002`.
+    pub dns_seeds: Vec<String>,
+    /// Whether to discover peers with the DNS seeds. The seeds are resolved directly, so they are
+    /// disabled when the connections are established through the proxy.
+    pub dns_seeding: bool,
+    /// Peer authentication.
+    pub auth: AuthConfig,
+    /// Whether to reconcile the inventories with the supporting peers.
+    pub reconciliation: bool,
+    /// Whether to announce the transactions with the short ids to the supporting peers.
+    pub compact_relay: bool,
+    /// Whether to compress the transactions sent to the supporting peers.
+    pub compression: bool,
 }
 
 /// Peer negotiation (handshake) state.
@@ -196,6 +235,20 @@ pub struct PeerInfo {
     pub wtxidrelay: bool,
     /// Whether this peer use YUV protocol
     pub ytxidrelay: bool,
+    /// Key the peer is authenticated with, if it has sent a valid `yuvauth`.
+    pub auth_key: Option<PublicKey>,
+    /// Whether the inventories are reconciled with this peer, i.e. both sides have sent
+    /// `sendrecon`.
+    pub reconciliation: bool,
+    /// Whether the transactions are announced to this peer with the short ids, i.e. both sides
+    /// have sent `sendcmpct`.
+    pub compact_relay: bool,
+    /// Whether the transactions sent to this peer are compressed, i.e. both sides have sent
+    /// `sendcompr`.
+    pub compression: bool,
+    /// Filter of the transactions relayed to this peer, if it's a light peer that has sent
+    /// `filterload`.
+    pub filter: Option<ChromaFilter>,
     /// Peer nonce. Used to detect self-connections.
     nonce: u64,
     /// Peer handshake state.
@@ -207,6 +260,12 @@ impl PeerInfo {
     pub fn is_negotiated(&self) -> bool {
         matches!(self.state, HandshakeState::Verack { .. })
     }
+
+    /// Salt of the short ids in the inventory reconciliation with the peer, if it's supported.
+    /// The nonce of the outbound side is used, so both sides have the same one.
+    pub fn reconciliation_salt(&self) -> Option<u64> {
+        self.reconciliation.then_some(self.nonce)
+    }
 }
 
 /// Manages peer connections and handshake.
@@ -218,7 +277,9 @@ pub struct PeerManager<U, C> {
     last_idle: Option<LocalTime>,
     /// Connection states.
     peers_storage: HashMap<SocketAddr, Peer>,
-    /// Peers that have been disconnected and a retry attempt is scheduled.
+    /// Outbound peers that have been disconnected, with the time before which they aren't
+    /// connected to again and the number of the failed attempts. Persistent peers are
+    /// reconnected at that time, the rest can be sampled again after it.
     disconnected: HashMap<net::SocketAddr, (Option<LocalTime>, usize)>,
     /// Bitcoin network type
     network: Network,
@@ -264,16 +325,40 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
         self.maintain_connections(addrs).await;
     }
 
-    /// A persistent peer has been disconnected.
-    fn persistent_disconnected(&mut self, addr: &net::SocketAddr, local_time: LocalTime) {
-        let (retry_at, attempts) = self.disconnected.entry(*addr).or_default();
-        let delay = LocalDuration::from_secs(2u64.saturating_pow(*attempts as u32))
+    /// An outbound peer has been disconnected, or the connection to it has failed. The next
+    /// attempt is delayed exponentially, so the dead peers aren't hammered.
+    fn outbound_disconnected(&mut self, addr: &net::SocketAddr, local_time: LocalTime) {
+        let attempts = self
+            .disconnected
+            .get(addr)
+            .map_or(0, |(_, attempts)| *attempts);
+        let delay = self.retry_delay(attempts);
+
+        self.disconnected
+            .insert(*addr, (Some(local_time + delay), attempts + 1));
+
+        if self.config.persistent.contains(addr) {
+            self.upstream.set_timer(delay);
+        }
+    }
+
+    /// Delay before the next connection attempt: `2^attempts` seconds within the configured
+    /// bounds, with up to a half of it taken off at random, so the peers that went down at the
+    /// same time aren't reconnected all at once.
+    fn retry_delay(&mut self, attempts: usize) -> LocalDuration {
+        let backoff = LocalDuration::from_secs(2u64.saturating_pow(attempts as u32))
             .clamp(self.config.retry_min_wait, self.config.retry_max_wait);
+        let jitter = self.rng.u128(..=backoff.as_millis() / 2);
 
-        *retry_at = Some(local_time + delay);
-        *attempts += 1;
+        LocalDuration::from_millis(backoff.as_millis() - jitter).max(self.config.retry_min_wait)
+    }
 
-        self.upstream.set_timer(delay);
+    /// Check whether the next connection attempt to the peer is delayed by the backoff.
+    fn is_backing_off(&self, addr: &PeerId, local_time: LocalTime) -> bool {
+        matches!(
+            self.disconnected.get(addr),
+            Some((Some(retry_at), _)) if *retry_at > local_time
+        )
     }
 
     /// Maintain persistent peer connections.
@@ -282,6 +367,9 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
         let mut reconnect = Vec::new();
 
         for (addr, (retry_at, _)) in &mut self.disconnected {
+            if !self.config.persistent.contains(addr) {
+                continue;
+            }
             if let Some(t) = retry_at {
                 if *t <= local_time {
                     *retry_at = None;
@@ -299,12 +387,17 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
 
     /// Maintain peers that we accepted from other nodes
     pub fn maintain_newcome<A: AddressSource>(&mut self, addrs: &mut A) {
+        let local_time = self.clock.local_time();
+
         while let Some(addr) = addrs.sample_with(|ka| {
             matches!(ka.source, Source::Peer(_))
                 | matches!(ka.source, Source::Imported)
                 | matches!(ka.source, Source::Dns)
         }) {
             if let Ok(socket_addr) = addr.0.socket_addr() {
+                if self.is_backing_off(&socket_addr, local_time) {
+                    continue;
+                }
                 self.whitelist(socket_addr);
                 self.connect(&socket_addr);
             }
@@ -333,7 +426,6 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
                 peer: None,
             },
         );
-        self.disconnected.remove(&addr);
 
         match link {
             Link::Inbound => {
@@ -379,11 +471,17 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
                 self.upstream.event(Event::ConnectionFailed(*addr, err));
             }
         }
-        self.peers_storage.remove(addr);
-
-        if self.config.persistent.contains(addr) {
-            self.persistent_disconnected(addr, local_time);
-        } else {
+        let outbound = match self.peers_storage.remove(addr) {
+            Some(Peer::Connecting { .. }) => true,
+            Some(Peer::Connected { conn, .. }) => conn.link.is_outbound(),
+            None => false,
+        };
+        let persistent = self.config.persistent.contains(addr);
+
+        if outbound || persistent {
+            self.outbound_disconnected(addr, local_time);
+        }
+        if !persistent {
             // If an outbound peer disconnected, we should make sure to maintain
             // our target outbound connection count.
             self.maintain_connections(addrs).await;
@@ -481,7 +579,7 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
                 self.config.whitelist.contains(&addr.ip(), &user_agent) || is_local(&addr.ip());
 
             // Don't support peers with too old of a protocol version.
-            if version < crate::fsm::handler::PROTOCOL_VERSION {
+            if version < crate::fsm::handler::MIN_PROTOCOL_VERSION {
                 return Err(DisconnectReason::PeerProtocolVersion(version));
             }
 
@@ -532,6 +630,22 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
                         .set_timer(HANDSHAKE_TIMEOUT);
                 }
             }
+            if let Some(secret_key) = &self.config.auth.secret_key {
+                self.upstream.auth(
+                    conn.socket.addr,
+                    auth::sign_auth(secret_key, nonce, conn.link),
+                );
+            }
+            if self.config.reconciliation {
+                self.upstream.send_recon(conn.socket.addr);
+            }
+            if self.config.compact_relay {
+                self.upstream.send_cmpct(conn.socket.addr);
+            }
+            if self.config.compression && version >= crate::fsm::handler::COMPRESSION_VERSION {
+                self.upstream.send_compr(conn.socket.addr);
+            }
+
             let conn = conn.clone();
             let persistent = self.config.persistent.contains(&conn.socket.addr);
 
@@ -550,6 +664,11 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
                         version: u32::min(self.config.protocol_version, version),
                         wtxidrelay: false,
                         ytxidrelay: true, // for now we assume that every node supports YUV protocol by default
+                        auth_key: None,
+                        reconciliation: false,
+                        compact_relay: false,
+                        compression: false,
+                        filter: None,
                     }),
                 },
             );
@@ -558,6 +677,165 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
         Ok(())
     }
 
+    /// Called when a `yuvauth` message was received.
+    pub fn received_auth(&mut self, addr: &PeerId, msg: PeerAuth) {
+        let Some(Peer::Connected {
+            peer: Some(peer),
+            conn,
+        }) = self.peers_storage.get_mut(addr)
+        else {
+            self._disconnect(
+                *addr,
+                DisconnectReason::PeerMisbehaving("`yuvauth` must be received after `version`"),
+            );
+            return;
+        };
+
+        if peer.auth_key.is_some() {
+            self._disconnect(
+                *addr,
+                DisconnectReason::PeerMisbehaving("duplicate `yuvauth` message received"),
+            );
+            return;
+        }
+
+        if !auth::verify_auth(&msg, peer.nonce, conn.link) {
+            self._disconnect(
+                *addr,
+                DisconnectReason::PeerMisbehaving("invalid `yuvauth` signature"),
+            );
+            return;
+        }
+
+        peer.auth_key = Some(msg.pubkey);
+        self.upstream.event(Event::Authenticated {
+            addr: *addr,
+            pubkey: msg.pubkey,
+        });
+    }
+
+    /// Called when a `signedinv` message was received. Returns the inventory if the signature
+    /// is valid and the peer is trusted.
+    pub fn received_signed_inv(
+        &mut self,
+        addr: &PeerId,
+        msg: SignedInventory,
+    ) -> Option<Vec<Inventory>> {
+        let (nonce, auth_key) = self
+            .peers()
+            .find(|(_, conn)| conn.socket.addr == *addr)
+            .map(|(peer, _)| (peer.nonce, peer.auth_key))?;
+
+        let Some(auth_key) = auth_key else {
+            self._disconnect(
+                *addr,
+                DisconnectReason::PeerMisbehaving("`signedinv` from unauthenticated peer"),
+            );
+            return None;
+        };
+
+        if !auth::verify_inv(&msg, nonce, &auth_key) {
+            self._disconnect(
+                *addr,
+                DisconnectReason::PeerMisbehaving("invalid `signedinv` signature"),
+            );
+            return None;
+        }
+
+        if !self.config.auth.is_trusted(Some(&auth_key)) {
+            debug!(
+                "{}: Ignored inventory from untrusted key {}",
+                addr, auth_key
+            );
+            return None;
+        }
+
+        Some(msg.inv)
+    }
+
+    /// Called when a `sendrecon` message was received. The reconciliation is enabled if we
+    /// support it too.
+    pub fn received_sendrecon(&mut self, addr: &PeerId) {
+        if let Some(Peer::Connected {
+            peer: Some(peer),
+            conn: _,
+        }) = self.peers_storage.get_mut(addr)
+        {
+            peer.reconciliation = self.config.reconciliation;
+        }
+    }
+
+    /// Called when a `sendcmpct` message was received. The compact relay is enabled if we
+    /// support it too.
+    pub fn received_sendcmpct(&mut self, addr: &PeerId) {
+        if let Some(Peer::Connected {
+            peer: Some(peer),
+            conn: _,
+        }) = self.peers_storage.get_mut(addr)
+        {
+            peer.compact_relay = self.config.compact_relay;
+        }
+    }
+
+    /// Called when a `sendcompr` message was received. The compression is enabled if we
+    /// support it too.
+    pub fn received_sendcompr(&mut self, addr: &PeerId) {
+        if let Some(Peer::Connected {
+            peer: Some(peer),
+            conn: _,
+        }) = self.peers_storage.get_mut(addr)
+        {
+            peer.compression =
+                self.config.compression && peer.version >= crate::fsm::handler::COMPRESSION_VERSION;
+        }
+    }
+
+    /// Called when a `filterload` message was received. Only the transactions matching the
+    /// filter are relayed to the peer from now on.
+    pub fn received_filterload(&mut self, addr: &PeerId, filter: ChromaFilter) {
+        if let Some(Peer::Connected {
+            peer: Some(peer),
+            conn: _,
+        }) = self.peers_storage.get_mut(addr)
+        {
+            peer.filter = Some(filter);
+        }
+    }
+
+    /// Check whether the transactions sent to and from the peer are compressed.
+    pub fn is_compression_enabled(&self, addr: &PeerId) -> bool {
+        self.peers()
+            .any(|(peer, conn)| conn.socket.addr == *addr && peer.compression)
+    }
+
+    /// Salt of the short ids in the inventory reconciliation with the peer, if it's supported.
+    pub fn reconciliation_salt(&self, addr: &PeerId) -> Option<u64> {
+        self.peers()
+            .find(|(_, conn)| conn.socket.addr == *addr)
+            .and_then(|(peer, _)| peer.reconciliation_salt())
+    }
+
+    /// Check whether the inventories and transactions from the peer are accepted.
+    pub fn is_trusted(&self, addr: &PeerId) -> bool {
+        let auth_key = self
+            .peers()
+            .find(|(_, conn)| conn.socket.addr == *addr)
+            .and_then(|(peer, _)| peer.auth_key);
+
+        self.config.auth.is_trusted(auth_key.as_ref())
+    }
+
+    /// Create an inventory message for the peer. The inventory is signed if we have the key
+    /// and the peer is authenticated, so it supports the signed inventories.
+    pub fn inventory_message(&self, peer: &PeerInfo, inv: Vec<Inventory>) -> NetworkMessage {
+        match (&self.config.auth.secret_key, peer.auth_key) {
+            (Some(secret_key), Some(_)) => {
+                NetworkMessage::SignedInv(auth::sign_inv(secret_key, peer.nonce, inv))
+            }
+            _ => NetworkMessage::Inv(inv),
+        }
+    }
+
     pub fn received_ytxidack(&mut self, addr: &PeerId, local_time: LocalTime) {
         if let Some(Peer::Connected {
             peer: Some(peer),
@@ -596,6 +874,8 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
                 });
 
                 peer.state = HandshakeState::Verack { since: local_time };
+                // The peer is alive, so the backoff starts over the next time.
+                self.disconnected.remove(addr);
 
                 return Some((peer.clone(), conn.clone()));
             } else {
@@ -655,9 +935,17 @@ impl<U: Wire<Event> + SetTimer + Connect + Disconnect, C: Clock + Sync> PeerMana
             self._disconnect(addr, DisconnectReason::PeerDropped);
         }
 
-        if local_time - self.last_idle.unwrap_or_default() >= IDLE_TIMEOUT {
+        // Connections are maintained more often while there are too few outbound peers.
+        let outbound = self.negotiated(Link::Outbound).count() + self.connecting().count();
+        let timeout = if outbound < self.config.min_outbound_peers {
+            RECONNECT_TIMEOUT
+        } else {
+            IDLE_TIMEOUT
+        };
+
+        if local_time - self.last_idle.unwrap_or_default() >= timeout {
             self.maintain_connections(addrs).await;
-            self.upstream.set_timer(IDLE_TIMEOUT);
+            self.upstream.set_timer(timeout);
             self.last_idle = Some(local_time);
         }
 
@@ -842,37 +1130,39 @@ impl<U: Connect + Disconnect + SetTimer + Wire<Event>, C: Clock + Sync> PeerMana
         usize::min(max - total, target - (primary + unknown))
     }
 
-    /// List of DNS seeds (for now it`s just Bitcoin hardcoded seeds)
-    fn get_dns_seed(&self) -> &[&str] {
+    /// List of DNS seeds. Configured seeds take precedence over the hardcoded ones.
+    fn get_dns_seed(&self) -> Vec<String> {
+        if !self.config.dns_seeding {
+            return Vec::new();
+        }
+
+        if !self.config.dns_seeds.is_empty() {
+            return self.config.dns_seeds.clone();
+        }
+
         // TODO: add YUV seednodes
-        match self.network {
+        let seeds: &[&str] = match self.network {
             Network::Bitcoin => &[],
             Network::Testnet => &[],
+            Network::Testnet4 => &[],
             Network::Regtest => &[],
             Network::Signet => &[],
             Network::Mutiny => &[],
-        }
+            Network::Custom(_) => &[],
+        };
+
+        seeds.iter().map(|seed| seed.to_string()).collect()
     }
 
     /// Bitcoin ports (for now we use them for DNS seeds)
     fn get_port(&self) -> u16 {
-        match self.network {
-            Network::Bitcoin => 8333,
-            Network::Testnet => 18333,
-            Network::Regtest => 18444,
-            Network::Signet => 38333,
-            Network::Mutiny => 38332,
-        }
+        self.network.default_port()
     }
 
-    /// Attempt to maintain a certain number of outbound peers.
+    /// Attempt to maintain a certain number of outbound peers. Persistent peers are retried
+    /// separately, but are counted towards the target, so the rest of the connections are made
+    /// to the discovered peers.
     async fn maintain_connections<A: AddressSource>(&mut self, addrs: &mut A) {
-        // If we have persistent peers configured, we don't use this mechanism for maintaining
-        // connections. Instead, we retry the configured peers.
-        if !self.config.persistent.is_empty() {
-            return;
-        }
-
         let delta = self.delta();
         let negotiated = self.negotiated(Link::Outbound).count();
         let target = self.config.target_outbound_peers;
@@ -897,6 +1187,10 @@ impl<U: Connect + Disconnect + SetTimer + Wire<Event>, C: Clock + Sync> PeerMana
                 if let Ok(sockaddr) = addr.socket_addr() {
                     debug_assert!(!self.is_connected(&sockaddr));
 
+                    if self.is_backing_off(&sockaddr, self.clock.local_time()) {
+                        continue;
+                    }
+
                     if self.connect(&sockaddr) {
                         connecting.insert(sockaddr);
                         self.upstream
@@ -907,30 +1201,26 @@ impl<U: Connect + Disconnect + SetTimer + Wire<Event>, C: Clock + Sync> PeerMana
                 let resolver =
                     TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
 
-                // For now we just use Bitcoin seeds.
-                if self.get_dns_seed().is_empty() {
+                let dns_seeds = self.get_dns_seed();
+                if dns_seeds.is_empty() {
                     debug!("Tried to get more addresses from DNS seeds, however, there`s no DNS seeds provided");
                     break;
                 }
 
-                let seed_ind = self.rng.usize(0..self.get_dns_seed().len());
-                let dns_seed = self
-                    .get_dns_seed()
-                    .get(seed_ind)
-                    .expect("must return dns seed");
-
                 // Get random dns seed
-                match resolver.lookup_ip(dns_seed.to_string()).await {
+                let seed_ind = self.rng.usize(0..dns_seeds.len());
+                let dns_seed = dns_seeds.get(seed_ind).expect("must return dns seed");
+                let (host, port) = split_dns_seed(dns_seed);
+                let port = port.unwrap_or_else(|| self.get_port());
+
+                match resolver.lookup_ip(host).await {
                     Ok(response) => {
                         let ips: Vec<(u32, Address)> = response
                             .iter()
                             .map(|addr| {
                                 (
                                     LocalTime::now().as_secs() as u32,
-                                    Address::new(
-                                        &SocketAddr::new(addr, self.get_port()),
-                                        ServiceFlags::NONE,
-                                    ),
+                                    Address::new(&SocketAddr::new(addr, port), ServiceFlags::NONE),
                                 )
                             })
                             .collect();
@@ -956,3 +1246,14 @@ impl<U: Connect + Disconnect + SetTimer + Wire<Event>, C: Clock + Sync> PeerMana
         })
     }
 }
+
+/// Split the DNS seed into the hostname and the optional port.
+fn split_dns_seed(seed: &str) -> (&str, Option<u16>) {
+    match seed.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => (host, Some(port)),
+            Err(_) => (seed, None),
+        },
+        None => (seed, None),
+    }
+}
@@ -0,0 +1,189 @@
+//! Per-peer rate limiting of the messages that are expensive to handle.
+//!
+//! Every peer has a token bucket for the inventory items, the `getdata` messages and the bytes of
+//! the `yuvtx` messages. The buckets are refilled at the configured rate and hold a few seconds
+//! of it, so short bursts are allowed. Messages that don't fit into the bucket are dropped, and
+//! the peer that keeps sending them is banned.
+use std::collections::HashMap;
+
+use crate::fsm::handler::PeerId;
+use crate::net::LocalTime;
+
+/// Number of seconds of the rate the buckets hold.
+pub const BURST_SECS: u32 = 5;
+
+/// Limits of the messages a peer can send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Max number of the announced inventory items per second.
+    pub inv_items_per_sec: u32,
+    /// Max number of the `getdata` messages per second.
+    pub getdata_per_sec: u32,
+    /// Max size of the `yuvtx` messages in bytes per second.
+    pub tx_bytes_per_sec: u32,
+    /// Number of the dropped messages after which the peer is banned.
+    pub ban_threshold: u32,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            inv_items_per_sec: 1_000,
+            getdata_per_sec: 10,
+            tx_bytes_per_sec: 1024 * 1024,
+            ban_threshold: 50,
+        }
+    }
+}
+
+/// Kind of the rate limited messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limited {
+    /// `inv` and `signedinv` messages, limited by the number of items.
+    Inv,
+    /// `getdata` messages.
+    GetData,
+    /// `yuvtx` messages, limited by the size.
+    TxBytes,
+}
+
+/// Result of the rate limit check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimit {
+    /// Message is within the limits.
+    Allowed,
+    /// Message exceeds the limits and must be dropped.
+    Dropped,
+    /// Message exceeds the limits and the peer has exceeded them too many times.
+    Ban,
+}
+
+/// Numbers of the messages dropped due to the rate limits since the start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DroppedMessages {
+    /// Dropped `inv` and `signedinv` messages.
+    pub inv: u64,
+    /// Dropped `getdata` messages.
+    pub getdata: u64,
+    /// Dropped `yuvtx` messages.
+    pub txs: u64,
+}
+
+impl DroppedMessages {
+    fn record(&mut self, kind: Limited) {
+        match kind {
+            Limited::Inv => self.inv += 1,
+            Limited::GetData => self.getdata += 1,
+            Limited::TxBytes => self.txs += 1,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens added per second.
+    rate: f64,
+    /// Max number of tokens.
+    capacity: f64,
+    tokens: f64,
+    last_refill: LocalTime,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, now: LocalTime) -> Self {
+        let capacity = rate as f64 * BURST_SECS as f64;
+
+        Self {
+            rate: rate as f64,
+            capacity,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    /// Take the `amount` of tokens. Returns `false` if there are not enough of them.
+    fn take(&mut self, amount: u64, now: LocalTime) -> bool {
+        if now > self.last_refill {
+            let elapsed = (now - self.last_refill).as_millis() as f64 / 1000.0;
+
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+            self.last_refill = now;
+        }
+
+        if amount as f64 > self.tokens {
+            return false;
+        }
+
+        self.tokens -= amount as f64;
+        true
+    }
+}
+
+#[derive(Debug)]
+struct PeerBuckets {
+    inv: TokenBucket,
+    getdata: TokenBucket,
+    tx_bytes: TokenBucket,
+    /// Number of the dropped messages of the peer.
+    dropped: u32,
+}
+
+/// Rate limiter of the messages from the connected peers.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    peers: HashMap<PeerId, PeerBuckets>,
+    dropped: DroppedMessages,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter.
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            peers: HashMap::new(),
+            dropped: DroppedMessages::default(),
+        }
+    }
+
+    /// Check the message of the `kind` from the peer, where `amount` is the number of the
+    /// inventory items or the bytes of the transactions.
+    pub fn check(&mut self, addr: PeerId, kind: Limited, amount: u64, now: LocalTime) -> RateLimit {
+        let limits = self.limits;
+        let buckets = self.peers.entry(addr).or_insert_with(|| PeerBuckets {
+            inv: TokenBucket::new(limits.inv_items_per_sec, now),
+            getdata: TokenBucket::new(limits.getdata_per_sec, now),
+            tx_bytes: TokenBucket::new(limits.tx_bytes_per_sec, now),
+            dropped: 0,
+        });
+
+        let bucket = match kind {
+            Limited::Inv => &mut buckets.inv,
+            Limited::GetData => &mut buckets.getdata,
+            Limited::TxBytes => &mut buckets.tx_bytes,
+        };
+
+        if bucket.take(amount, now) {
+            return RateLimit::Allowed;
+        }
+
+        self.dropped.record(kind);
+        buckets.dropped += 1;
+
+        if buckets.dropped >= limits.ban_threshold {
+            return RateLimit::Ban;
+        }
+
+        RateLimit::Dropped
+    }
+
+    /// Forget the buckets of the disconnected peer.
+    pub fn peer_disconnected(&mut self, addr: &PeerId) {
+        self.peers.remove(addr);
+    }
+
+    /// Numbers of the dropped messages.
+    pub fn dropped(&self) -> DroppedMessages {
+        self.dropped
+    }
+}
@@ -7,20 +7,24 @@ use std::{
     fmt,
     hash::Hash,
     io, net,
-    net::{SocketAddr, TcpListener},
+    net::SocketAddr,
     sync::Arc,
 };
 
 use async_trait::async_trait;
 use flume as chan;
-use flume::Receiver;
-use popol::Event;
 use tokio_util::sync::CancellationToken;
 
+pub use proxy::{OnionAddr, ProxyConfig};
+#[cfg(feature = "quic")]
+pub use quic::{QuicWaker, ReactorQuic};
 pub use reactor::{ReactorTcp, Waker};
 pub use time::{LocalDuration, LocalTime};
 
 pub mod error;
+pub mod proxy;
+#[cfg(feature = "quic")]
+pub mod quic;
 pub mod reactor;
 mod socket;
 pub mod time;
@@ -30,6 +34,8 @@ pub enum Source<Id: PeerId> {
     Peer(Id),
     Listener,
     Waker,
+    /// Handshakes with the proxy are completed.
+    Dialer,
 }
 
 /// Link direction of the peer connection.
@@ -204,8 +210,12 @@ pub trait NetReactor<Id: PeerId = net::SocketAddr> {
     type Waker: NetWaker;
 
     /// Create a new reactor, initializing it with a publisher for service events,
-    /// a channel to receive commands, and a channel to shut it down.
-    fn new(listening: chan::Sender<net::SocketAddr>) -> Result<Self, io::Error>
+    /// a channel to receive commands, and a channel to shut it down. If the proxy is
+    /// set, the outbound connections are established through it.
+    fn new(
+        listening: chan::Sender<net::SocketAddr>,
+        proxy: Option<ProxyConfig>,
+    ) -> Result<Self, io::Error>
     where
         Self: Sized;
 
@@ -228,63 +238,12 @@ pub trait NetReactor<Id: PeerId = net::SocketAddr> {
         S: Service<Id> + Send + Sync,
         S::DisconnectReason: Into<Disconnect<S::DisconnectReason>> + Send + Sync;
 
-    async fn listen_connections(
-        &mut self,
-        listen_addrs: &SocketAddr,
-    ) -> Result<Option<net::TcpListener>, error::Error>;
-
-    async fn handle_new_source<S>(
-        &mut self,
-        n: usize,
-        events: &mut Vec<Event<Source<Id>>>,
-        service: &mut S,
-        commands: &Receiver<S::Command>,
-        listener: &Option<net::TcpListener>,
-    ) -> Result<(), error::Error>
-    where
-        S: Service<Id> + Send + Sync,
-        S::DisconnectReason: Into<Disconnect<S::DisconnectReason>> + Send + Sync;
-
-    /// Handles Peer source event that came to the reactor. Returns true, if event is invalid
-    async fn handle_peer_source_event(
-        &mut self,
-        addr: Id,
-        event: &Event<Source<Id>>,
-    ) -> Result<bool, error::Error>;
-
-    /// Handles Listener source that came to the reactor
-    async fn handle_listener_source<S>(
-        &mut self,
-        listener: &Option<TcpListener>,
-        service: &mut S,
-    ) -> Result<(), error::Error>
-    where
-        S: Service<Id> + Send + Sync;
-
-    /// Handles Peer source event that came to the reactor. Returns true, if shutdown command was received
-    async fn handle_waker_source<S>(
-        &mut self,
-        service: &mut S,
-        commands: &Receiver<S::Command>,
-        event: Event<Source<Id>>,
-    ) where
-        S: Service<Id> + Send + Sync;
-
     /// Return a new waker.
     ///
     /// The reactor can provide multiple wakers such that multiple user threads may wake
     /// the event loop.
     fn waker(&self) -> Self::Waker;
 
-    async fn add_connection<S>(
-        &mut self,
-        service: &mut S,
-        socket_addr: SocketAddr,
-        conn: net::TcpStream,
-    ) -> Result<(), error::Error>
-    where
-        S: Service<Id> + Send + Sync;
-
     /// Checks if provided peer is in storages. Used for testing
     fn is_peer_connected(&mut self, addr: SocketAddr) -> bool;
     fn is_peer_disconnected(&mut self, addr: SocketAddr) -> bool;
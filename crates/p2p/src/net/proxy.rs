@@ -0,0 +1,379 @@
+//! Outbound connections through the SOCKS5 proxy ([RFC 1928]), e.g. Tor.
+//!
+//! Peers are identified by their socket addresses, so the Tor v3 onion addresses are mapped to
+//! the addresses from the OnionCat range (`fd87:d87e:eb43::/48`). The reactor dials such
+//! addresses through the proxy by the onion host name.
+//!
+//! [RFC 1928]: https://datatracker.ietf.org/doc/html/rfc1928
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, Read, Write},
+    net::{Ipv6Addr, SocketAddr, TcpStream},
+    str::FromStr,
+    time::Duration,
+};
+
+use thiserror::Error;
+
+/// Maximum time to wait for each step of the proxy handshake.
+pub const PROXY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Prefix of the OnionCat range, which the onion addresses are mapped to.
+const ONIONCAT_PREFIX: [u8; 6] = [0xfd, 0x87, 0xd8, 0x7e, 0xeb, 0x43];
+
+/// Length of the base32-encoded Tor v3 onion host without the `.onion` suffix.
+const ONION_V3_HOST_LEN: usize = 56;
+/// Version byte of the Tor v3 onion address.
+const ONION_V3_VERSION: u8 = 0x03;
+
+const SOCKS_VERSION: u8 = 0x05;
+const SOCKS_NO_AUTH: u8 = 0x00;
+const SOCKS_CMD_CONNECT: u8 = 0x01;
+const SOCKS_ATYP_IPV4: u8 = 0x01;
+const SOCKS_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS_ATYP_IPV6: u8 = 0x04;
+const SOCKS_REPLY_SUCCEEDED: u8 = 0x00;
+
+/// Configuration of the outbound connections through the SOCKS5 proxy.
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    /// Address of the SOCKS5 proxy. All the outbound connections are established through it.
+    pub address: SocketAddr,
+    /// Onion peers by their OnionCat addresses.
+    pub onion_peers: HashMap<SocketAddr, OnionAddr>,
+}
+
+impl ProxyConfig {
+    pub fn new(address: SocketAddr) -> Self {
+        Self {
+            address,
+            onion_peers: HashMap::new(),
+        }
+    }
+
+    /// Adds the onion peer, which can be dialed by its OnionCat address.
+    pub fn add_onion_peer(&mut self, onion: OnionAddr) {
+        self.onion_peers.insert(onion.to_socket_addr(), onion);
+    }
+
+    /// Connects to the peer through the proxy. Returns the blocking stream.
+    pub(crate) fn connect(&self, addr: &SocketAddr) -> io::Result<TcpStream> {
+        let target = match self.onion_peers.get(addr) {
+            Some(onion) => Target::Domain(onion.host(), onion.port()),
+            None if is_onion(addr) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrNotAvailable,
+                    "unknown onion address",
+                ))
+            }
+            None => Target::Addr(*addr),
+        };
+
+        connect(&self.address, target, PROXY_TIMEOUT)
+    }
+}
+
+/// Target of the connection through the proxy.
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    Addr(SocketAddr),
+    /// Host name, which is resolved by the proxy.
+    Domain(&'a str, u16),
+}
+
+/// Connects to the target through the SOCKS5 proxy without authentication.
+pub fn connect(proxy: &SocketAddr, target: Target, timeout: Duration) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect_timeout(proxy, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    stream.write_all(&[SOCKS_VERSION, 1, SOCKS_NO_AUTH])?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    if reply != [SOCKS_VERSION, SOCKS_NO_AUTH] {
+        return Err(invalid_data(
+            "proxy requires an unsupported authentication method",
+        ));
+    }
+
+    let mut request = vec![SOCKS_VERSION, SOCKS_CMD_CONNECT, 0x00];
+    let port = match target {
+        Target::Addr(SocketAddr::V4(addr)) => {
+            request.push(SOCKS_ATYP_IPV4);
+            request.extend_from_slice(&addr.ip().octets());
+            addr.port()
+        }
+        Target::Addr(SocketAddr::V6(addr)) => {
+            request.push(SOCKS_ATYP_IPV6);
+            request.extend_from_slice(&addr.ip().octets());
+            addr.port()
+        }
+        Target::Domain(host, port) => {
+            let len = u8::try_from(host.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "host is too long"))?;
+            request.push(SOCKS_ATYP_DOMAIN);
+            request.push(len);
+            request.extend_from_slice(host.as_bytes());
+            port
+        }
+    };
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(invalid_data("invalid proxy reply version"));
+    }
+    if reply[1] != SOCKS_REPLY_SUCCEEDED {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("proxy failed to connect: {}", reply_message(reply[1])),
+        ));
+    }
+
+    // The address bound by the proxy is not used.
+    let bound_addr_len = match reply[3] {
+        SOCKS_ATYP_IPV4 => 4,
+        SOCKS_ATYP_IPV6 => 16,
+        SOCKS_ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => return Err(invalid_data("invalid proxy reply address type")),
+    };
+    let mut bound_addr = vec![0u8; bound_addr_len + 2];
+    stream.read_exact(&mut bound_addr)?;
+
+    Ok(stream)
+}
+
+fn reply_message(code: u8) -> &'static str {
+    match code {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Checks whether the address is from the OnionCat range, i.e. represents the onion peer.
+pub fn is_onion(addr: &SocketAddr) -> bool {
+    match addr {
+        SocketAddr::V6(addr) => addr.ip().octets().starts_with(&ONIONCAT_PREFIX),
+        SocketAddr::V4(_) => false,
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum OnionAddrError {
+    #[error("onion address must have a port")]
+    MissingPort,
+    #[error("invalid port: {0}")]
+    InvalidPort(String),
+    #[error("not a Tor v3 onion address: {0}")]
+    InvalidHost(String),
+}
+
+/// Tor v3 onion service address, e.g. `<56 base32 characters>.onion:8333`.
+///
+/// The checksum of the address is not verified, invalid addresses are rejected by the proxy.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnionAddr {
+    host: String,
+    port: u16,
+    /// First bytes of the onion service's public key.
+    key_prefix: [u8; 10],
+}
+
+impl OnionAddr {
+    /// Returns the host name including the `.onion` suffix.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the address from the OnionCat range, which identifies the onion peer.
+    pub fn to_socket_addr(&self) -> SocketAddr {
+        let mut octets = [0u8; 16];
+        octets[..6].copy_from_slice(&ONIONCAT_PREFIX);
+        octets[6..].copy_from_slice(&self.key_prefix);
+
+        SocketAddr::new(Ipv6Addr::from(octets).into(), self.port)
+    }
+}
+
+impl FromStr for OnionAddr {
+    type Err = OnionAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, port) = s.rsplit_once(':').ok_or(OnionAddrError::MissingPort)?;
+        let port = port
+            .parse()
+            .map_err(|_| OnionAddrError::InvalidPort(port.to_string()))?;
+
+        let host = host.to_ascii_lowercase();
+        let invalid_host = || OnionAddrError::InvalidHost(host.clone());
+
+        let encoded = host.strip_suffix(".onion").ok_or_else(invalid_host)?;
+        if encoded.len() != ONION_V3_HOST_LEN {
+            return Err(invalid_host());
+        }
+
+        // The decoded address is `public key (32 bytes) || checksum (2 bytes) || version`.
+        let decoded = decode_base32(encoded).ok_or_else(invalid_host)?;
+        if decoded.last() != Some(&ONION_V3_VERSION) {
+            return Err(invalid_host());
+        }
+
+        let mut key_prefix = [0u8; 10];
+        key_prefix.copy_from_slice(&decoded[..10]);
+
+        Ok(Self {
+            host,
+            port,
+            key_prefix,
+        })
+    }
+}
+
+impl fmt::Display for OnionAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
+/// Decodes the lowercase base32 ([RFC 4648]) string without padding.
+///
+/// [RFC 4648]: https://datatracker.ietf.org/doc/html/rfc4648#section-6
+fn decode_base32(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len() * 5 / 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+
+    for c in s.bytes() {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+
+        buffer = (buffer << 5) | value as u16;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    const ONION_HOST: &str = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion";
+
+    #[test]
+    fn test_onion_addr_parsing() {
+        let onion = OnionAddr::from_str(&format!("{}:8333", ONION_HOST)).unwrap();
+
+        assert_eq!(onion.host(), ONION_HOST);
+        assert_eq!(onion.port(), 8333);
+        assert_eq!(onion.to_string(), format!("{}:8333", ONION_HOST));
+
+        let addr = onion.to_socket_addr();
+        assert!(is_onion(&addr));
+        assert_eq!(addr.port(), 8333);
+
+        assert_eq!(
+            OnionAddr::from_str(ONION_HOST),
+            Err(OnionAddrError::MissingPort)
+        );
+        assert!(OnionAddr::from_str("example.onion:8333").is_err());
+        assert!(OnionAddr::from_str("127.0.0.1:8333").is_err());
+        assert!(!is_onion(&"[2001:db8::1]:8333".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_connect_through_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).unwrap();
+            assert_eq!(greeting, [SOCKS_VERSION, 1, SOCKS_NO_AUTH]);
+            stream.write_all(&[SOCKS_VERSION, SOCKS_NO_AUTH]).unwrap();
+
+            let mut request = [0u8; 5];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(
+                request,
+                [
+                    SOCKS_VERSION,
+                    SOCKS_CMD_CONNECT,
+                    0x00,
+                    SOCKS_ATYP_DOMAIN,
+                    ONION_HOST.len() as u8
+                ]
+            );
+            let mut host = vec![0u8; ONION_HOST.len()];
+            stream.read_exact(&mut host).unwrap();
+            assert_eq!(host, ONION_HOST.as_bytes());
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut port).unwrap();
+            assert_eq!(u16::from_be_bytes(port), 8333);
+
+            stream
+                .write_all(&[
+                    SOCKS_VERSION,
+                    SOCKS_REPLY_SUCCEEDED,
+                    0x00,
+                    SOCKS_ATYP_IPV4,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                ])
+                .unwrap();
+            stream.write_all(b"ping").unwrap();
+        });
+
+        let mut config = ProxyConfig::new(proxy);
+        let onion = OnionAddr::from_str(&format!("{}:8333", ONION_HOST)).unwrap();
+        config.add_onion_peer(onion.clone());
+
+        let mut stream = config.connect(&onion.to_socket_addr()).unwrap();
+        let mut message = [0u8; 4];
+        stream.read_exact(&mut message).unwrap();
+        assert_eq!(&message, b"ping");
+
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,638 @@
+//! QUIC reactor. Runs the service over the QUIC connections instead of the TCP ones.
+//!
+//! Each connection has a bidirectional stream the messages are sent over in order, so the
+//! handshake and the rest of the small messages keep the order the protocol relies on. The
+//! messages larger than [`LARGE_MESSAGE_SIZE`] are sent over their own unidirectional streams,
+//! so a lost packet of a large transaction relay doesn't hold back the other messages.
+//!
+//! The messages are framed the same way as over TCP, and are passed to the service whole.
+//!
+//! Nb. The TLS certificates are self-signed and aren't verified, as QUIC is used only as the
+//! transport here. The peers are authenticated by the protocol itself, see
+//! [`AuthConfig`](crate::client::AuthConfig).
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    io, net,
+    net::SocketAddr,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use flume as chan;
+use flume::Receiver;
+use tokio::select;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, trace};
+
+use crate::net::proxy::ProxyConfig;
+use crate::net::time::TimeoutManager;
+use crate::net::{
+    error::Error, Disconnect, Io, Link, LocalDuration, LocalTime, NetReactor, NetWaker, PeerId,
+    Service,
+};
+
+/// Maximum amount of time to wait for i/o.
+const WAIT_TIMEOUT: LocalDuration = LocalDuration::from_secs(5);
+/// Interval of the QUIC keep-alive packets, so the idle connections aren't closed between
+/// the pings of the protocol.
+const KEEP_ALIVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+/// Size of the message header: magic, command, payload length and checksum.
+const HEADER_SIZE: usize = 24;
+/// Offset of the payload length in the message header.
+const PAYLOAD_LEN_OFFSET: usize = 16;
+/// Maximum size of the message payload, the same as the one accepted by the decoder.
+const MAX_PAYLOAD_SIZE: usize = 5_000_000;
+/// Messages larger than this are sent over their own unidirectional streams.
+pub const LARGE_MESSAGE_SIZE: usize = 16 * 1024;
+/// Application protocol negotiated with the peers.
+const ALPN: &[u8] = b"yuv";
+/// Name in the self-signed certificates.
+const SERVER_NAME: &str = "yuv";
+
+pub type ReactorQuic = QuicReactor;
+
+/// Waker of the QUIC reactor. The reactor receives the commands as soon as they are sent, so
+/// there is nothing to wake.
+#[derive(Clone)]
+pub struct QuicWaker;
+
+impl NetWaker for QuicWaker {
+    fn wake(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Event sent to the reactor by the connection tasks.
+enum QuicEvent {
+    /// Connection is established and its bidirectional stream is open.
+    Connected {
+        connection: quinn::Connection,
+        link: Link,
+        /// Messages to write to the peer.
+        outbox: chan::Sender<Vec<u8>>,
+    },
+    /// Outbound connection couldn't be established.
+    ConnectFailed { addr: SocketAddr, err: io::Error },
+    /// Message is received from the peer.
+    Message {
+        addr: SocketAddr,
+        /// Stable id of the connection the message is received over.
+        id: usize,
+        message: Vec<u8>,
+    },
+    /// Connection is closed.
+    Disconnected {
+        addr: SocketAddr,
+        /// Stable id of the closed connection.
+        id: usize,
+        err: io::Error,
+    },
+}
+
+/// Established connection with a peer.
+struct Peer {
+    connection: quinn::Connection,
+    /// Messages to write to the peer.
+    outbox: chan::Sender<Vec<u8>>,
+}
+
+/// A single-threaded reactor driving the QUIC connections.
+pub struct QuicReactor<Id: PeerId = net::SocketAddr> {
+    peers: HashMap<Id, Peer>,
+    connecting: HashSet<Id>,
+    timeouts: TimeoutManager<()>,
+    listening: chan::Sender<net::SocketAddr>,
+    endpoint: Option<quinn::Endpoint>,
+    events: (chan::Sender<QuicEvent>, chan::Receiver<QuicEvent>),
+}
+
+#[async_trait]
+impl<Id: PeerId + Send + Sync> NetReactor<Id> for QuicReactor<Id> {
+    type Waker = QuicWaker;
+
+    /// Construct a new reactor, given a channel to send events on. The outbound connections
+    /// can't be established through the proxy, as it doesn't relay UDP.
+    fn new(
+        listening: chan::Sender<net::SocketAddr>,
+        proxy: Option<ProxyConfig>,
+    ) -> Result<Self, io::Error> {
+        if proxy.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "QUIC transport doesn't support the proxy",
+            ));
+        }
+
+        Ok(Self {
+            peers: HashMap::new(),
+            connecting: HashSet::new(),
+            timeouts: TimeoutManager::new(LocalDuration::from_secs(1)),
+            listening,
+            endpoint: None,
+            events: chan::unbounded(),
+        })
+    }
+
+    /// Run the given service with the reactor.
+    async fn run<S>(
+        &mut self,
+        listen_addrs: &SocketAddr,
+        mut service: S,
+        commands: Receiver<S::Command>,
+        cancellation: CancellationToken,
+    ) -> Result<(), Error>
+    where
+        S: Service<Id> + Send + Sync,
+        S::DisconnectReason: Into<Disconnect<S::DisconnectReason>> + Send + Sync,
+    {
+        let endpoint = self.listen(listen_addrs).await?;
+        let accepting = tokio::spawn(accept_connections(endpoint.clone(), self.events.0.clone()));
+
+        let local_time = SystemTime::now().into();
+        service.initialize(local_time).await;
+
+        self.process(&mut service, local_time).await;
+
+        let events = self.events.1.clone();
+        // Timeouts populated by `TimeoutManager::wake`.
+        let mut timeouts: Vec<()> = Vec::with_capacity(32);
+
+        let result = loop {
+            let wait = self
+                .timeouts
+                .next(SystemTime::now())
+                .map_or(WAIT_TIMEOUT, |next| next.min(WAIT_TIMEOUT));
+
+            select! {
+                _ = cancellation.cancelled() => {
+                    trace!("Reactor cancelled");
+                    break Ok(());
+                }
+                event = events.recv_async() => {
+                    let event = event.expect("reactor holds the sender");
+                    service.tick(SystemTime::now().into());
+                    self.handle_event(event, &mut service).await;
+                }
+                command = commands.recv_async() => {
+                    let Ok(command) = command else {
+                        break Ok(());
+                    };
+                    service.tick(SystemTime::now().into());
+                    service.command_received(command).await;
+                }
+                _ = tokio::time::sleep(wait.into()) => {
+                    let local_time = SystemTime::now().into();
+                    service.tick(local_time);
+
+                    // Nb. As in the TCP reactor, it doesn't matter which timeouts have
+                    // expired. As long as *something* timed out, we wake the service.
+                    self.timeouts.wake(local_time, &mut timeouts);
+                    if !timeouts.is_empty() {
+                        timeouts.clear();
+                        service.timer_expired().await;
+                    }
+                }
+            }
+
+            self.process(&mut service, SystemTime::now().into()).await;
+        };
+
+        accepting.abort();
+        endpoint.close(0u32.into(), b"shutdown");
+
+        result
+    }
+
+    fn waker(&self) -> Self::Waker {
+        QuicWaker
+    }
+
+    fn is_peer_connected(&mut self, addr: SocketAddr) -> bool {
+        self.peers.contains_key(&addr.into())
+    }
+
+    fn is_peer_disconnected(&mut self, addr: SocketAddr) -> bool {
+        !self.connecting.contains(&addr.into()) && !self.peers.contains_key(&addr.into())
+    }
+}
+
+impl<Id: PeerId + Send + Sync> QuicReactor<Id> {
+    /// Listen for connections on the given address. The same endpoint is used for the
+    /// outbound connections.
+    async fn listen(&mut self, listen_addr: &SocketAddr) -> Result<quinn::Endpoint, Error> {
+        let mut endpoint = quinn::Endpoint::server(server_config()?, *listen_addr)?;
+        endpoint.set_default_client_config(client_config());
+
+        let local_addr = endpoint.local_addr()?;
+        self.listening.send_async(local_addr).await.ok();
+        self.endpoint = Some(endpoint.clone());
+
+        debug!(target: "net", "Listening incoming QUIC connections on {}", local_addr);
+
+        Ok(endpoint)
+    }
+
+    /// Handle the event from the connection tasks.
+    async fn handle_event<S: Service<Id>>(&mut self, event: QuicEvent, service: &mut S) {
+        match event {
+            QuicEvent::Connected {
+                connection,
+                link,
+                outbox,
+            } => {
+                let socket_addr = connection.remote_address();
+                let addr = Id::from(socket_addr);
+
+                // The connection attempt may have timed out in the meantime.
+                if link.is_outbound() && !self.connecting.remove(&addr) {
+                    connection.close(0u32.into(), b"timeout");
+                    return;
+                }
+
+                trace!("{}: Established QUIC connection ({:?})", socket_addr, link);
+
+                let local_addr = self.local_addr();
+                let peer = Peer { connection, outbox };
+                if let Some(previous) = self.peers.insert(addr.clone(), peer) {
+                    previous.connection.close(0u32.into(), b"replaced");
+                }
+
+                service.connected(addr, &local_addr, link);
+            }
+            QuicEvent::ConnectFailed { addr, err } => {
+                let addr = Id::from(addr);
+
+                if self.connecting.remove(&addr) {
+                    service
+                        .disconnected(&addr, Disconnect::ConnectionError(Arc::new(err)))
+                        .await;
+                }
+            }
+            QuicEvent::Message { addr, id, message } => {
+                let addr = Id::from(addr);
+
+                // Messages of the replaced connections are dropped.
+                if self.is_current(&addr, id) {
+                    service
+                        .message_received(&addr, Cow::Borrowed(&message[..]))
+                        .await;
+                }
+            }
+            QuicEvent::Disconnected { addr, id, err } => {
+                let addr = Id::from(addr);
+
+                if self.is_current(&addr, id) {
+                    self.peers.remove(&addr);
+                    service
+                        .disconnected(&addr, Disconnect::ConnectionError(Arc::new(err)))
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Process service state machine outputs.
+    pub async fn process<S>(&mut self, service: &mut S, local_time: LocalTime)
+    where
+        S: Service<Id>,
+        S::DisconnectReason: Into<Disconnect<S::DisconnectReason>>,
+    {
+        // Note that there may be messages destined for a peer that has since been
+        // disconnected.
+        while let Some(out) = service.next() {
+            match out {
+                Io::Write(addr, bytes) => {
+                    if let Some(peer) = self.peers.get(&addr) {
+                        peer.outbox.send(bytes).ok();
+                    }
+                }
+                Io::Connect(addr) => self.handle_connect_process(addr, service).await,
+                Io::Disconnect(addr, reason) => {
+                    self.connecting.remove(&addr);
+                    if let Some(peer) = self.peers.remove(&addr) {
+                        peer.connection.close(0u32.into(), b"disconnected");
+                    }
+
+                    service.disconnected(&addr, reason.into()).await;
+                }
+                Io::SetTimer(timeout) => {
+                    self.timeouts.register((), local_time + timeout);
+                }
+                Io::Event(event) => {
+                    trace!("Event: {:?}", event);
+                }
+            }
+        }
+    }
+
+    async fn handle_connect_process<S: Service<Id>>(&mut self, addr: Id, service: &mut S) {
+        let socket_addr = addr.to_socket_addr();
+
+        if self.connecting.contains(&addr) || self.peers.contains_key(&addr) {
+            // Ignore. We are already establishing a connection with this peer.
+            return;
+        }
+
+        let Some(endpoint) = &self.endpoint else {
+            error!(target: "net", "{}: Dial error: endpoint is not started", socket_addr);
+            return;
+        };
+
+        match endpoint.connect(socket_addr, SERVER_NAME) {
+            Ok(connecting) => {
+                self.connecting.insert(addr.clone());
+                service.attempted(&addr);
+
+                tokio::spawn(dial(socket_addr, connecting, self.events.0.clone()));
+            }
+            Err(err) => {
+                error!(target: "net", "{}: Dial error: {}", socket_addr, err);
+
+                service
+                    .disconnected(
+                        &addr,
+                        Disconnect::DialError(Arc::new(io::Error::other(err))),
+                    )
+                    .await;
+            }
+        }
+    }
+
+    /// Check whether the connection with the given stable id is the current one of the peer.
+    fn is_current(&self, addr: &Id, id: usize) -> bool {
+        self.peers
+            .get(addr)
+            .is_some_and(|peer| peer.connection.stable_id() == id)
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        self.endpoint
+            .as_ref()
+            .and_then(|endpoint| endpoint.local_addr().ok())
+            .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0)))
+    }
+}
+
+/// Accept the inbound connections until the endpoint is closed.
+async fn accept_connections(endpoint: quinn::Endpoint, events: chan::Sender<QuicEvent>) {
+    while let Some(connecting) = endpoint.accept().await {
+        let events = events.clone();
+
+        tokio::spawn(async move {
+            let result = async {
+                let connection = connecting.await?;
+                // The stream becomes visible once the peer sends its `version` message.
+                let streams = connection.accept_bi().await?;
+
+                Ok::<_, quinn::ConnectionError>((connection, streams))
+            }
+            .await;
+
+            match result {
+                Ok((connection, streams)) => {
+                    run_connection(connection, streams, Link::Inbound, events).await
+                }
+                Err(err) => trace!(target: "net", "Accept error: {}", err),
+            }
+        });
+    }
+}
+
+/// Establish the outbound connection and run it.
+async fn dial(addr: SocketAddr, connecting: quinn::Connecting, events: chan::Sender<QuicEvent>) {
+    let result = async {
+        let connection = connecting.await?;
+        let streams = connection.open_bi().await?;
+
+        Ok::<_, quinn::ConnectionError>((connection, streams))
+    }
+    .await;
+
+    match result {
+        Ok((connection, streams)) => {
+            run_connection(connection, streams, Link::Outbound, events).await
+        }
+        Err(err) => {
+            trace!(target: "net", "{}: Connection error: {}", addr, err);
+
+            let err = io::Error::other(err);
+            events
+                .send_async(QuicEvent::ConnectFailed { addr, err })
+                .await
+                .ok();
+        }
+    }
+}
+
+/// Announce the established connection to the reactor, then relay the messages between the
+/// streams and the reactor until the connection is closed.
+async fn run_connection(
+    connection: quinn::Connection,
+    (mut send, mut recv): (quinn::SendStream, quinn::RecvStream),
+    link: Link,
+    events: chan::Sender<QuicEvent>,
+) {
+    let addr = connection.remote_address();
+    let id = connection.stable_id();
+    let (outbox, messages) = chan::unbounded();
+
+    let connected = QuicEvent::Connected {
+        connection: connection.clone(),
+        link,
+        outbox,
+    };
+    if events.send_async(connected).await.is_err() {
+        return;
+    }
+
+    let result = select! {
+        result = write_messages(&connection, &mut send, messages) => result,
+        result = read_messages(addr, id, &mut recv, &events) => result,
+        result = accept_messages(addr, id, &connection, &events) => result,
+    };
+
+    connection.close(0u32.into(), b"closed");
+
+    let err = result
+        .err()
+        .unwrap_or_else(|| io::Error::from(io::ErrorKind::ConnectionReset));
+    events
+        .send_async(QuicEvent::Disconnected { addr, id, err })
+        .await
+        .ok();
+}
+
+/// Write the messages from the reactor until it drops the peer.
+async fn write_messages(
+    connection: &quinn::Connection,
+    send: &mut quinn::SendStream,
+    messages: chan::Receiver<Vec<u8>>,
+) -> io::Result<()> {
+    while let Ok(message) = messages.recv_async().await {
+        if message.len() <= LARGE_MESSAGE_SIZE {
+            send.write_all(&message).await.map_err(io::Error::other)?;
+            continue;
+        }
+
+        let connection = connection.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let mut stream = connection.open_uni().await.map_err(io::Error::other)?;
+                stream.write_all(&message).await.map_err(io::Error::other)?;
+                stream.finish().await.map_err(io::Error::other)
+            }
+            .await;
+
+            if let Err(err) = result {
+                trace!(target: "net", "{}: Write error: {}", connection.remote_address(), err);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Read the messages from the bidirectional stream, one by one.
+async fn read_messages(
+    addr: SocketAddr,
+    id: usize,
+    recv: &mut quinn::RecvStream,
+    events: &chan::Sender<QuicEvent>,
+) -> io::Result<()> {
+    loop {
+        let mut message = vec![0; HEADER_SIZE];
+        recv.read_exact(&mut message)
+            .await
+            .map_err(io::Error::other)?;
+
+        let payload_len = payload_len(&message)?;
+        message.resize(HEADER_SIZE + payload_len, 0);
+        recv.read_exact(&mut message[HEADER_SIZE..])
+            .await
+            .map_err(io::Error::other)?;
+
+        let event = QuicEvent::Message { addr, id, message };
+        if events.send_async(event).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// Read the large messages, each from its own unidirectional stream.
+async fn accept_messages(
+    addr: SocketAddr,
+    id: usize,
+    connection: &quinn::Connection,
+    events: &chan::Sender<QuicEvent>,
+) -> io::Result<()> {
+    loop {
+        let mut stream = connection.accept_uni().await.map_err(io::Error::other)?;
+        let events = events.clone();
+
+        tokio::spawn(async move {
+            match stream.read_to_end(HEADER_SIZE + MAX_PAYLOAD_SIZE).await {
+                Ok(message) => {
+                    let event = QuicEvent::Message { addr, id, message };
+                    events.send_async(event).await.ok();
+                }
+                Err(err) => trace!(target: "net", "{}: Read error: {}", addr, err),
+            }
+        });
+    }
+}
+
+/// Payload length from the message header.
+fn payload_len(header: &[u8]) -> io::Result<usize> {
+    let bytes = header[PAYLOAD_LEN_OFFSET..PAYLOAD_LEN_OFFSET + 4]
+        .try_into()
+        .expect("header contains the payload length");
+    let payload_len = u32::from_le_bytes(bytes) as usize;
+
+    if payload_len > MAX_PAYLOAD_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message payload of {} bytes is too large", payload_len),
+        ));
+    }
+
+    Ok(payload_len)
+}
+
+/// Server configuration with a fresh self-signed certificate.
+fn server_config() -> io::Result<quinn::ServerConfig> {
+    let cert = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])
+        .map_err(io::Error::other)?;
+    let cert_der = cert.serialize_der().map_err(io::Error::other)?;
+    let key_der = cert.serialize_private_key_der();
+
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![rustls::Certificate(cert_der)],
+            rustls::PrivateKey(key_der),
+        )
+        .map_err(io::Error::other)?;
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+
+    let mut config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    config.transport_config(Arc::new(transport_config()));
+
+    Ok(config)
+}
+
+/// Client configuration that accepts any server certificate, see the module docs.
+fn client_config() -> quinn::ClientConfig {
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+
+    let mut config = quinn::ClientConfig::new(Arc::new(crypto));
+    config.transport_config(Arc::new(transport_config()));
+
+    config
+}
+
+fn transport_config() -> quinn::TransportConfig {
+    let mut config = quinn::TransportConfig::default();
+    config.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+
+    config
+}
+
+/// Certificate verifier that accepts any server certificate.
+struct SkipServerVerification;
+
+impl rustls::client::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_len() {
+        let mut header = vec![0; HEADER_SIZE];
+        header[PAYLOAD_LEN_OFFSET..PAYLOAD_LEN_OFFSET + 4].copy_from_slice(&1024u32.to_le_bytes());
+        assert_eq!(payload_len(&header).unwrap(), 1024);
+
+        let too_large = (MAX_PAYLOAD_SIZE as u32 + 1).to_le_bytes();
+        header[PAYLOAD_LEN_OFFSET..PAYLOAD_LEN_OFFSET + 4].copy_from_slice(&too_large);
+        assert!(payload_len(&header).is_err());
+    }
+}
@@ -20,6 +20,7 @@ use tokio::select;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, trace};
 
+use crate::net::proxy::ProxyConfig;
 use crate::net::socket::Socket;
 use crate::net::time::TimeoutManager;
 use crate::net::{
@@ -42,8 +43,11 @@ pub type ReactorTcp = Reactor<net::TcpStream>;
 pub struct Waker(Arc<popol::Waker>);
 
 impl Waker {
-    fn new<Id: PeerId>(sources: &mut popol::Sources<Source<Id>>) -> io::Result<Self> {
-        let waker = Arc::new(popol::Waker::new(sources, Source::Waker)?);
+    fn new<Id: PeerId>(
+        sources: &mut popol::Sources<Source<Id>>,
+        source: Source<Id>,
+    ) -> io::Result<Self> {
+        let waker = Arc::new(popol::Waker::new(sources, source)?);
 
         Ok(Self(waker))
     }
@@ -63,6 +67,17 @@ pub struct Reactor<R: Write + Read, Id: PeerId = net::SocketAddr> {
     waker: Waker,
     timeouts: TimeoutManager<()>,
     listening: chan::Sender<net::SocketAddr>,
+    /// Proxy to establish the outbound connections through.
+    proxy: Option<ProxyConfig>,
+    /// Peers whose connections are being established through the proxy.
+    dialing: HashSet<Id>,
+    /// Connections established through the proxy, sent by the handshake threads.
+    dialed: (
+        chan::Sender<(Id, io::Result<net::TcpStream>)>,
+        chan::Receiver<(Id, io::Result<net::TcpStream>)>,
+    ),
+    /// Wakes the reactor up once a handshake with the proxy is completed.
+    dialer_waker: Waker,
 }
 
 /// The `R` parameter represents the underlying stream type, eg. `net::TcpStream`.
@@ -92,15 +107,19 @@ impl<R: Write + Read + AsRawFd, Id: PeerId> Reactor<R, Id> {
 }
 
 #[async_trait]
-impl<Id: PeerId + Send + Sync> NetReactor<Id> for Reactor<net::TcpStream, Id> {
+impl<Id: PeerId + Send + Sync + 'static> NetReactor<Id> for Reactor<net::TcpStream, Id> {
     type Waker = Waker;
 
     /// Construct a new reactor, given a channel to send events on.
-    fn new(listening: chan::Sender<net::SocketAddr>) -> Result<Self, io::Error> {
+    fn new(
+        listening: chan::Sender<net::SocketAddr>,
+        proxy: Option<ProxyConfig>,
+    ) -> Result<Self, io::Error> {
         let peers = HashMap::new();
 
         let mut sources = popol::Sources::new();
-        let waker = Waker::new(&mut sources)?;
+        let waker = Waker::new(&mut sources, Source::Waker)?;
+        let dialer_waker = Waker::new(&mut sources, Source::Dialer)?;
         let timeouts = TimeoutManager::new(LocalDuration::from_secs(1));
         let connecting = HashSet::new();
 
@@ -111,6 +130,10 @@ impl<Id: PeerId + Send + Sync> NetReactor<Id> for Reactor<net::TcpStream, Id> {
             waker,
             timeouts,
             listening,
+            proxy,
+            dialing: HashSet::new(),
+            dialed: chan::unbounded(),
+            dialer_waker,
         })
     }
 
@@ -153,6 +176,25 @@ impl<Id: PeerId + Send + Sync> NetReactor<Id> for Reactor<net::TcpStream, Id> {
         }
     }
 
+    /// Return a new waker.
+    ///
+    /// Used to wake up the main event loop.
+    fn waker(&self) -> Self::Waker {
+        self.waker.clone()
+    }
+
+    fn is_peer_connected(&mut self, addr: SocketAddr) -> bool {
+        self.sources.get(&Source::Peer(addr.into())).is_some()
+            || self.peers.get(&addr.into()).is_some()
+    }
+
+    fn is_peer_disconnected(&mut self, addr: SocketAddr) -> bool {
+        !self.connecting.contains(&addr.into())
+            || self.sources.get(&Source::Peer(addr.into())).is_none()
+    }
+}
+
+impl<Id: PeerId + Send + Sync + 'static> Reactor<net::TcpStream, Id> {
     async fn listen_connections(
         &mut self,
         listen_addr: &SocketAddr,
@@ -206,6 +248,7 @@ impl<Id: PeerId + Send + Sync> NetReactor<Id> for Reactor<net::TcpStream, Id> {
                 }
                 Source::Listener => self.handle_listener_source(listener, service).await?,
                 Source::Waker => self.handle_waker_source(service, commands, event).await,
+                Source::Dialer => self.handle_dialer_source(service, event).await,
             }
         }
 
@@ -277,11 +320,17 @@ impl<Id: PeerId + Send + Sync> NetReactor<Id> for Reactor<net::TcpStream, Id> {
         }
     }
 
-    /// Return a new waker.
-    ///
-    /// Used to wake up the main event loop.
-    fn waker(&self) -> Self::Waker {
-        self.waker.clone()
+    /// Registers the connections established through the proxy.
+    async fn handle_dialer_source<S>(&mut self, service: &mut S, event: Event<Source<Id>>)
+    where
+        S: Service<Id> + Send + Sync,
+    {
+        popol::Waker::reset(event.source).ok();
+
+        while let Ok((addr, result)) = self.dialed.1.try_recv() {
+            self.dialing.remove(&addr);
+            self.handle_dial_result(addr, result, service).await;
+        }
     }
 
     async fn add_connection<S>(
@@ -307,18 +356,6 @@ impl<Id: PeerId + Send + Sync> NetReactor<Id> for Reactor<net::TcpStream, Id> {
         Ok(())
     }
 
-    fn is_peer_connected(&mut self, addr: SocketAddr) -> bool {
-        self.sources.get(&Source::Peer(addr.into())).is_some()
-            || self.peers.get(&addr.into()).is_some()
-    }
-
-    fn is_peer_disconnected(&mut self, addr: SocketAddr) -> bool {
-        !self.connecting.contains(&addr.into())
-            || self.sources.get(&Source::Peer(addr.into())).is_none()
-    }
-}
-
-impl<Id: PeerId + Send + Sync> Reactor<net::TcpStream, Id> {
     async fn handle<S>(
         &mut self,
         events: &mut Vec<Event<Source<Id>>>,
@@ -409,9 +446,39 @@ impl<Id: PeerId + Send + Sync> Reactor<net::TcpStream, Id> {
     }
 
     async fn handle_connect_process<S: Service<Id>>(&mut self, addr: Id, service: &mut S) {
+        let Some(proxy) = &self.proxy else {
+            let result = dial(&addr.to_socket_addr());
+            self.handle_dial_result(addr, result, service).await;
+            return;
+        };
+
+        // The handshake with the proxy is blocking, so it's done on a separate thread and
+        // the connection is registered once it's established.
+        if !self.dialing.insert(addr.clone()) {
+            return;
+        }
+
+        let proxy = proxy.clone();
+        let dialed = self.dialed.0.clone();
+        let waker = self.dialer_waker.clone();
+        std::thread::spawn(move || {
+            let result = dial_proxy(&proxy, &addr.to_socket_addr());
+
+            if dialed.send((addr, result)).is_ok() {
+                waker.wake().ok();
+            }
+        });
+    }
+
+    async fn handle_dial_result<S: Service<Id>>(
+        &mut self,
+        addr: Id,
+        result: io::Result<net::TcpStream>,
+        service: &mut S,
+    ) {
         let socket_addr = addr.to_socket_addr();
 
-        match dial(&socket_addr) {
+        match result {
             Ok(stream) => {
                 trace!("{:#?}", stream);
 
@@ -564,6 +631,21 @@ fn dial(addr: &SocketAddr) -> Result<net::TcpStream, io::Error> {
     Ok(sock.into())
 }
 
+/// Connect to a peer through the proxy.
+///
+/// Nb. The proxy handshake is blocking and takes at most
+/// [`PROXY_TIMEOUT`](crate::net::proxy::PROXY_TIMEOUT) per step, so it's called outside of the
+/// reactor's thread. Once established, the connection is treated as a regular outbound one.
+fn dial_proxy(proxy: &ProxyConfig, addr: &SocketAddr) -> Result<net::TcpStream, io::Error> {
+    let stream = proxy.connect(addr)?;
+
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    stream.set_nonblocking(true)?;
+
+    Ok(stream)
+}
+
 /// Listen for connections on the given address.
 fn listen<A: net::ToSocketAddrs>(addr: A) -> Result<net::TcpListener, Error> {
     let sock = net::TcpListener::bind(addr)?;
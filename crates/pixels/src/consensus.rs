@@ -1,4 +1,4 @@
-use alloc::boxed::Box;
+use alloc::{boxed::Box, string::String};
 use bitcoin::consensus::{encode::Error as EncodeError, Decodable, Encodable};
 use core2::io;
 
@@ -6,8 +6,8 @@ use core2::io;
 use crate::proof::bulletproof::Bulletproof;
 use crate::{
     proof::{p2wpkh::P2WPKHProof, p2wsh::P2WSHProof, PixelProof},
-    EmptyPixelProof, LightningCommitmentProof, LightningHtlcProof, MultisigPixelProof, Pixel,
-    PIXEL_SIZE,
+    EmptyPixelProof, HtlcPixelProof, LightningCommitmentProof, LightningHtlcProof,
+    MultisigPixelProof, MusigPixelProof, Pixel, TimelockPixelProof, PIXEL_SIZE,
 };
 
 /// Pixel proof flags
@@ -19,6 +19,9 @@ const LIGHTNING_HTLC_FLAG: u8 = 3u8;
 const BULLETPROOF_FLAG: u8 = 4u8;
 const EMPTY_PIXEL_FLAG: u8 = 5u8;
 const P2WSH_FLAG: u8 = 6u8;
+const MUSIG_FLAG: u8 = 7u8;
+const TIMELOCK_FLAG: u8 = 8u8;
+const HTLC_FLAG: u8 = 9u8;
 
 impl Encodable for Pixel {
     fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
@@ -70,6 +73,18 @@ impl Encodable for PixelProof {
                 len += LIGHTNING_HTLC_FLAG.consensus_encode(writer)?;
                 len += proof.consensus_encode(writer)?;
             }
+            PixelProof::Musig(proof) => {
+                len += MUSIG_FLAG.consensus_encode(writer)?;
+                len += proof.consensus_encode(writer)?;
+            }
+            PixelProof::Timelock(proof) => {
+                len += TIMELOCK_FLAG.consensus_encode(writer)?;
+                len += proof.consensus_encode(writer)?;
+            }
+            PixelProof::Htlc(proof) => {
+                len += HTLC_FLAG.consensus_encode(writer)?;
+                len += proof.consensus_encode(writer)?;
+            }
         }
 
         Ok(len)
@@ -110,11 +125,37 @@ impl Decodable for PixelProof {
                 let proof: LightningHtlcProof = Decodable::consensus_decode(reader)?;
                 Ok(PixelProof::LightningHtlc(proof))
             }
+            MUSIG_FLAG => {
+                let proof: MusigPixelProof = Decodable::consensus_decode(reader)?;
+                Ok(PixelProof::Musig(proof))
+            }
+            TIMELOCK_FLAG => {
+                let proof: TimelockPixelProof = Decodable::consensus_decode(reader)?;
+                Ok(PixelProof::Timelock(proof))
+            }
+            HTLC_FLAG => {
+                let proof: HtlcPixelProof = Decodable::consensus_decode(reader)?;
+                Ok(PixelProof::Htlc(proof))
+            }
             _ => Err(EncodeError::ParseFailed("Unknown pixel proof")),
         }
     }
 }
 
+impl PixelProof {
+    /// Returns the consensus encoded proof in hex.
+    pub fn hex(&self) -> String {
+        hex::encode(bitcoin::consensus::serialize(self))
+    }
+
+    /// Decodes the proof from the consensus encoding in hex.
+    pub fn from_hex(hex: &str) -> Result<Self, EncodeError> {
+        let bytes = hex::decode(hex).map_err(|_| EncodeError::ParseFailed("Invalid hex"))?;
+
+        bitcoin::consensus::deserialize(&bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::vec;
@@ -123,24 +164,27 @@ mod tests {
 
     use bitcoin::{
         consensus::{Decodable, Encodable},
-        hashes::hash160,
+        hashes::{hash160, sha256, Hash},
         key::XOnlyPublicKey,
         secp256k1,
     };
     use once_cell::sync::Lazy;
 
-    #[cfg(feature = "bulletproof")]
-    use crate::Bulletproof;
     use crate::LightningCommitmentProof;
     use crate::MultisigPixelProof;
+    use crate::MusigPixelProof;
     use crate::Pixel;
     use crate::PixelProof;
     use crate::SigPixelProof;
+    use crate::TimelockPixelProof;
     use crate::{
         proof::common::lightning::{commitment::script::ToLocalScript, htlc},
         LightningHtlcData,
     };
+    #[cfg(feature = "bulletproof")]
+    use crate::{Bulletproof, CommitmentProof};
     use crate::{Chroma, LightningHtlcProof};
+    use crate::{HtlcData, HtlcPixelProof};
     #[cfg(feature = "bulletproof")]
     use bitcoin::secp256k1::schnorr::Signature;
 
@@ -297,6 +341,55 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "bulletproof")]
+    fn test_aggregated_bulletproof_consensus_encode() {
+        let chroma = Chroma::new(*X_ONLY_PUBKEY);
+        let pixel = Pixel::new(100, chroma);
+
+        let (range_proof, points) =
+            bulletproof::generate_aggregated(&[(100, BLINDING), (200, BLINDING)]);
+
+        let proofs = [
+            Bulletproof::new(
+                pixel,
+                *PUBKEY,
+                *PUBKEY,
+                points[0],
+                CommitmentProof::Aggregated {
+                    aggregated: range_proof,
+                },
+                *SIG,
+                *SIG,
+            ),
+            Bulletproof::new(
+                pixel,
+                *PUBKEY,
+                *PUBKEY,
+                points[1],
+                CommitmentProof::InAggregated,
+                *SIG,
+                *SIG,
+            ),
+        ];
+
+        for proof in proofs {
+            let mut bytes = Vec::new();
+
+            proof
+                .consensus_encode(&mut bytes)
+                .expect("failed to encode the proof");
+
+            let decoded_proof = Bulletproof::consensus_decode(&mut bytes.as_slice())
+                .expect("failed to decode the proof");
+
+            assert_eq!(
+                proof, decoded_proof,
+                "Converting back and forth should work"
+            );
+        }
+    }
+
     #[test]
     fn test_pixel_proofs_consensus_encode() {
         let chroma = Chroma::new(*X_ONLY_PUBKEY);
@@ -326,6 +419,12 @@ mod tests {
                     htlc::HtlcScriptKind::Received { cltv_expiry: 100 },
                 ),
             )),
+            PixelProof::Musig(MusigPixelProof::new(pixel, vec![*PUBKEY, *PUBKEY])),
+            PixelProof::Timelock(TimelockPixelProof::new(pixel, *PUBKEY, 850_000)),
+            PixelProof::Htlc(HtlcPixelProof::new(
+                pixel,
+                HtlcData::new(sha256::Hash::hash(&[7; 32]), *PUBKEY, *PUBKEY, 850_000),
+            )),
             #[cfg(feature = "bulletproof")]
             PixelProof::Bulletproof(Box::new(Bulletproof::new(
                 pixel,
@@ -352,9 +451,27 @@ mod tests {
                 proof, &decoded_proof,
                 "Converting back and forth should work"
             );
+
+            let hex = proof.hex();
+            assert_eq!(hex, ::hex::encode(&bytes));
+            assert_eq!(
+                &PixelProof::from_hex(&hex).expect("failed to decode the proof from hex"),
+                proof,
+                "Converting to hex and back should work"
+            );
         }
     }
 
+    #[test]
+    fn test_pixel_proof_from_malformed_hex() {
+        let proof = PixelProof::Sig(SigPixelProof::new(Pixel::new(100, *X_ONLY_PUBKEY), *PUBKEY));
+        let hex = proof.hex();
+
+        assert!(PixelProof::from_hex("not a hex").is_err());
+        assert!(PixelProof::from_hex(&hex[..hex.len() - 2]).is_err());
+        assert!(PixelProof::from_hex(&(hex + "00")).is_err());
+    }
+
     #[test]
     fn test_pixel_consensus_parsing() {
         let pixel = Pixel::new(100, *X_ONLY_PUBKEY);
@@ -373,4 +490,37 @@ mod tests {
             "Converting back and forth should work"
         );
     }
+
+    #[test]
+    fn test_pixel_proof_sizes() {
+        let chroma = Chroma::new(*X_ONLY_PUBKEY);
+        let pixel = Pixel::new(100, chroma);
+
+        let sig_proof = PixelProof::Sig(SigPixelProof::new(pixel, *PUBKEY));
+        let multisig_proof = PixelProof::Multisig(MultisigPixelProof::new(
+            pixel,
+            vec![*PUBKEY, *PUBKEY, *PUBKEY],
+            2,
+        ));
+
+        for proof in [&sig_proof, &multisig_proof] {
+            let mut bytes = Vec::new();
+            let len = proof
+                .consensus_encode(&mut bytes)
+                .expect("failed to encode the proof");
+
+            assert_eq!(proof.consensus_size(), len);
+            assert_eq!(proof.consensus_size(), bytes.len());
+
+            #[cfg(feature = "serde")]
+            assert_eq!(
+                proof.json_size(),
+                serde_json::to_string(proof)
+                    .expect("failed to serialize the proof")
+                    .len()
+            );
+        }
+
+        assert!(multisig_proof.consensus_size() > sig_proof.consensus_size());
+    }
 }
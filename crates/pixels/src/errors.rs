@@ -6,11 +6,14 @@ use bitcoin::secp256k1;
 
 #[cfg(feature = "bulletproof")]
 use crate::proof::bulletproof::errors::BulletproofError;
+use crate::proof::common::htlc::errors::HtlcPixelProofError;
 use crate::proof::common::lightning::commitment::errors::LightningCommitmentProofError;
 use crate::proof::common::lightning::htlc::LightningHtlcProofError;
 use crate::proof::common::multisig::errors::MultisigPixelProofError;
+use crate::proof::musig::errors::MusigPixelProofError;
 use crate::proof::p2wpkh::errors::P2WPKHProofError;
 use crate::proof::p2wsh::errors::P2WSHProofError;
+use crate::proof::timelock::errors::TimelockPixelProofError;
 use crate::{CHROMA_SIZE, PIXEL_SIZE};
 
 #[derive(Debug)]
@@ -198,6 +201,12 @@ pub enum PixelProofError {
 
     LightningHtlc(LightningHtlcProofError),
 
+    Musig(MusigPixelProofError),
+
+    Timelock(TimelockPixelProofError),
+
+    Htlc(HtlcPixelProofError),
+
     #[cfg(feature = "bulletproof")]
     /// Bulletproof error
     Bulletproof(BulletproofError),
@@ -209,6 +218,24 @@ impl From<MultisigPixelProofError> for PixelProofError {
     }
 }
 
+impl From<MusigPixelProofError> for PixelProofError {
+    fn from(v: MusigPixelProofError) -> Self {
+        Self::Musig(v)
+    }
+}
+
+impl From<TimelockPixelProofError> for PixelProofError {
+    fn from(v: TimelockPixelProofError) -> Self {
+        Self::Timelock(v)
+    }
+}
+
+impl From<HtlcPixelProofError> for PixelProofError {
+    fn from(v: HtlcPixelProofError) -> Self {
+        Self::Htlc(v)
+    }
+}
+
 impl From<LightningHtlcProofError> for PixelProofError {
     fn from(v: LightningHtlcProofError) -> Self {
         Self::LightningHtlc(v)
@@ -230,6 +257,9 @@ impl fmt::Display for PixelProofError {
             PixelProofError::Multisig(e) => write!(f, "Multisig: {}", e),
             PixelProofError::Lightning(e) => write!(f, "Lightning: {}", e),
             PixelProofError::LightningHtlc(e) => write!(f, "LightningHtlc: {}", e),
+            PixelProofError::Musig(e) => write!(f, "Musig: {}", e),
+            PixelProofError::Timelock(e) => write!(f, "Timelock: {}", e),
+            PixelProofError::Htlc(e) => write!(f, "Htlc: {}", e),
             #[cfg(feature = "bulletproof")]
             PixelProofError::Bulletproof(e) => write!(f, "Bulletproof: {}", e),
         }
@@ -246,6 +276,9 @@ impl std::error::Error for PixelProofError {
             PixelProofError::Multisig(e) => Some(e),
             PixelProofError::Lightning(e) => Some(e),
             PixelProofError::LightningHtlc(e) => Some(e),
+            PixelProofError::Musig(e) => Some(e),
+            PixelProofError::Timelock(e) => Some(e),
+            PixelProofError::Htlc(e) => Some(e),
             #[cfg(feature = "bulletproof")]
             PixelProofError::Bulletproof(e) => Some(e),
         }
@@ -9,6 +9,8 @@ use bitcoin::{
 
 use core::ops::Deref;
 
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
 use crate::errors::PixelKeyError;
 use crate::PixelHash;
 
@@ -91,7 +93,7 @@ fn pixelhash_pubkey_hash(pxh: &PixelHash, pubkey: &secp256k1::PublicKey) -> Sha2
 }
 
 /// The same as [`pixelhash_pubkey_hash`], but returns the scalar.
-fn pixel_hash_pubkey_scalar(
+pub(crate) fn pixel_hash_pubkey_scalar(
     pxh: &PixelHash,
     pubkey: &secp256k1::PublicKey,
 ) -> Result<Scalar, PixelKeyError> {
@@ -105,6 +107,8 @@ fn pixel_hash_pubkey_scalar(
 /// Defined as: `Sk_{B} + hash(PXH || Pk)`, where `Sk_{B}` - is
 /// a secret key of current owner of the coin, `PXH` is
 /// [`PixelHash`], and `Pk` is derived from `Sk` public key.
+///
+/// The key is erased from the memory on drop.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PixelPrivateKey(pub secp256k1::SecretKey);
@@ -154,12 +158,29 @@ impl PixelPrivateKey {
         // (Sk_{B} + hash(PXH, P_{B})) mod P, where `P` curve order.
         //
         // `add_tweak` also does the `mod P` operation
-        let spending_key = inner_key.add_tweak(&pxh_b)?;
+        let spending_key = inner_key.add_tweak(&pxh_b);
+
+        // Erase the copy of the owner's key.
+        inner_key.non_secure_erase();
+
+        Ok(Self(spending_key?))
+    }
+}
 
-        Ok(Self(spending_key))
+impl Zeroize for PixelPrivateKey {
+    fn zeroize(&mut self) {
+        self.0.non_secure_erase();
     }
 }
 
+impl Drop for PixelPrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PixelPrivateKey {}
+
 /// This traits adds ability to types from external libraries to always return
 /// public key with even parity.
 pub trait ToEvenPublicKey {
@@ -5,7 +5,9 @@ extern crate alloc;
 
 #[cfg(feature = "bulletproof")]
 pub use bulletproof::{
-    generate as generate_bulletproof, k256, verify as verify_bulletproof, RangeProof,
+    generate as generate_bulletproof, generate_aggregated as generate_aggregated_bulletproof, k256,
+    verify as verify_bulletproof, verify_aggregated as verify_aggregated_bulletproof, RangeProof,
+    MAX_AGGREGATED_VALUES,
 };
 pub use errors::{
     ChromaParseError, LumaParseError, PixelKeyError, PixelParseError, PixelProofError,
@@ -17,7 +19,12 @@ pub use pixel::{
 };
 #[cfg(feature = "bulletproof")]
 pub use proof::bulletproof::{
-    errors::BulletproofError, signing as bulletproof_signing, Bulletproof,
+    errors::BulletproofError, signing as bulletproof_signing, Bulletproof, CommitmentProof,
+};
+pub use proof::common::htlc::{
+    script::{HtlcData, HtlcScript, PREIMAGE_SIZE},
+    witness::{HtlcWitness, HtlcWitnessStack},
+    HtlcPixelProof,
 };
 pub use proof::common::lightning::commitment::{
     witness::{LightningCommitmentWitness, LightningCommitmentWitnessStack},
@@ -26,11 +33,23 @@ pub use proof::common::lightning::commitment::{
 pub use proof::common::lightning::htlc::{
     HtlcScriptKind, LightningHtlcData, LightningHtlcProof, LightningHtlcScript,
 };
-pub use proof::common::multisig::{witness::MultisigWitness, MultisigPixelProof};
+pub use proof::common::multisig::{
+    script::MultisigScript, witness::MultisigWitness, MultisigPixelProof,
+};
 pub use proof::empty::EmptyPixelProof;
+pub use proof::musig::{
+    signing as musig_signing, witness::MusigWitness, MusigPixelProof, MUSIG_PARTICIPANTS,
+};
 pub use proof::p2wpkh::{witness::P2WPKHWitness, P2WPKHProof, SigPixelProof};
 pub use proof::p2wsh::{witness::P2WSHWitness, P2WSHProof};
+pub use proof::timelock::{
+    witness::{TimelockWitness, TimelockWitnessStack},
+    TimelockPixelProof,
+};
 pub use proof::{CheckableProof, PixelProof};
+pub use reserves::{
+    verify_proof_of_reserves, ProofOfReserves, ProofOfReservesError, ReserveOutput,
+};
 pub use tweakable::Tweakable;
 
 #[cfg(not(any(feature = "std", feature = "no-std")))]
@@ -44,6 +63,7 @@ mod hash;
 mod keys;
 mod pixel;
 mod proof;
+mod reserves;
 mod tweakable;
 
 #[cfg(all(feature = "serde", feature = "bulletproof"))]
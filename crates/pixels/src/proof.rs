@@ -7,17 +7,22 @@ use bitcoin::{secp256k1, ScriptBuf, TxIn, TxOut};
 use crate::errors::PixelProofError;
 use crate::{LightningCommitmentProof, MultisigPixelProof, P2WPKHProof, Pixel};
 
+use self::common::htlc::HtlcPixelProof;
 use self::common::lightning::htlc::LightningHtlcProof;
 use self::empty::EmptyPixelProof;
+use self::musig::MusigPixelProof;
 use self::p2wpkh::SigPixelProof;
 use self::p2wsh::P2WSHProof;
+use self::timelock::TimelockPixelProof;
 
 #[cfg(feature = "bulletproof")]
 pub mod bulletproof;
 pub mod common;
 pub mod empty;
+pub mod musig;
 pub mod p2wpkh;
 pub mod p2wsh;
+pub mod timelock;
 
 /// The proof of ownership that user brings to check and attach particular transaction.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -47,6 +52,17 @@ pub enum PixelProof {
     /// The proof for arbitary P2WSH address script.
     P2WSH(Box<p2wsh::P2WSHProof>),
 
+    /// Pixel proof for 2-of-2 multisignature that uses the Taproot key
+    /// aggregated by MuSig2.
+    Musig(MusigPixelProof),
+
+    /// Pixel proof of the output that can't be spent before the block height.
+    Timelock(TimelockPixelProof),
+
+    /// Proof of the HTLC output outside of the Lightning Network, e.g. for the
+    /// atomic swaps.
+    Htlc(HtlcPixelProof),
+
     /// The bulletproof with a corresponsing Pedersen commitment
     #[cfg(feature = "bulletproof")]
     Bulletproof(alloc::boxed::Box<bulletproof::Bulletproof>),
@@ -64,6 +80,9 @@ impl PixelProof {
             Self::Multisig(proof) => proof.pixel,
             Self::Lightning(proof) => proof.pixel,
             Self::LightningHtlc(proof) => proof.pixel,
+            Self::Musig(proof) => proof.pixel,
+            Self::Timelock(proof) => proof.pixel,
+            Self::Htlc(proof) => proof.pixel,
         }
     }
 
@@ -96,6 +115,20 @@ impl PixelProof {
         self.amount() == 0
     }
 
+    /// Returns the exact size of the proof in the consensus encoding in bytes.
+    #[cfg(feature = "consensus")]
+    pub fn consensus_size(&self) -> usize {
+        bitcoin::consensus::serialize(self).len()
+    }
+
+    /// Returns the exact size of the proof serialized to JSON in bytes.
+    #[cfg(feature = "serde")]
+    pub fn json_size(&self) -> usize {
+        serde_json::to_vec(self)
+            .expect("Proof should serialize to JSON")
+            .len()
+    }
+
     #[cfg(feature = "bulletproof")]
     pub fn bulletproof(bulletproof: bulletproof::Bulletproof) -> Self {
         Self::Bulletproof(alloc::boxed::Box::new(bulletproof))
@@ -118,6 +151,16 @@ impl PixelProof {
         matches!(self, Self::EmptyPixel(_))
     }
 
+    /// Returns the height of the block from which the transaction can spend the output with the
+    /// proof by the `txin`, `None` if the spending isn't timelocked.
+    pub fn input_lock_height(&self, txin: &TxIn) -> Option<u32> {
+        match self {
+            Self::Timelock(proof) => Some(proof.lock_height),
+            Self::Htlc(proof) => proof.refund_lock_height(txin),
+            _ => None,
+        }
+    }
+
     #[cfg(feature = "bulletproof")]
     pub fn get_bulletproof(&self) -> Option<&bulletproof::Bulletproof> {
         match self {
@@ -160,6 +203,9 @@ impl CheckableProof for PixelProof {
             Self::Multisig(proof) => proof.checked_check_by_input(txin)?,
             Self::Lightning(proof) => proof.checked_check_by_input(txin)?,
             Self::LightningHtlc(proof) => proof.checked_check_by_input(txin)?,
+            Self::Musig(proof) => proof.checked_check_by_input(txin)?,
+            Self::Timelock(proof) => proof.checked_check_by_input(txin)?,
+            Self::Htlc(proof) => proof.checked_check_by_input(txin)?,
             #[cfg(feature = "bulletproof")]
             Self::Bulletproof(bulletproof) => bulletproof.checked_check_by_input(txin)?,
         };
@@ -175,6 +221,9 @@ impl CheckableProof for PixelProof {
             Self::Lightning(proof) => proof.checked_check_by_output(txout)?,
             Self::LightningHtlc(proof) => proof.checked_check_by_output(txout)?,
             Self::P2WSH(proof) => proof.checked_check_by_output(txout)?,
+            Self::Musig(proof) => proof.checked_check_by_output(txout)?,
+            Self::Timelock(proof) => proof.checked_check_by_output(txout)?,
+            Self::Htlc(proof) => proof.checked_check_by_output(txout)?,
             #[cfg(feature = "bulletproof")]
             Self::Bulletproof(bulletproof) => bulletproof.checked_check_by_output(txout)?,
         };
@@ -13,7 +13,64 @@ use core2::io;
 
 use crate::Pixel;
 
-use super::Bulletproof;
+use super::{Bulletproof, CommitmentProof};
+
+/// Tag of the [`CommitmentProof::Aggregated`] proofs.
+const AGGREGATED_TAG: u8 = 1;
+/// Tag of the [`CommitmentProof::InAggregated`] proofs.
+const IN_AGGREGATED_TAG: u8 = 2;
+
+/// The single range proofs are encoded as before, while the other ones are encoded as an empty
+/// proof followed by the tag, so the proofs encoded before the aggregation are still decodable.
+impl Encodable for CommitmentProof {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        match self {
+            CommitmentProof::Single(proof) => {
+                len += proof.to_bytes().consensus_encode(writer)?;
+            }
+            CommitmentProof::Aggregated { aggregated } => {
+                len += Vec::<u8>::new().consensus_encode(writer)?;
+                len += AGGREGATED_TAG.consensus_encode(writer)?;
+                len += aggregated.to_bytes().consensus_encode(writer)?;
+            }
+            CommitmentProof::InAggregated => {
+                len += Vec::<u8>::new().consensus_encode(writer)?;
+                len += IN_AGGREGATED_TAG.consensus_encode(writer)?;
+            }
+        }
+
+        Ok(len)
+    }
+}
+
+impl Decodable for CommitmentProof {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, Error> {
+        let bytes: Vec<u8> = Decodable::consensus_decode(reader)?;
+        if !bytes.is_empty() {
+            return decode_range_proof(&bytes).map(CommitmentProof::Single);
+        }
+
+        let tag: u8 = Decodable::consensus_decode(reader)?;
+        match tag {
+            AGGREGATED_TAG => {
+                let bytes: Vec<u8> = Decodable::consensus_decode(reader)?;
+
+                Ok(CommitmentProof::Aggregated {
+                    aggregated: decode_range_proof(&bytes)?,
+                })
+            }
+            IN_AGGREGATED_TAG => Ok(CommitmentProof::InAggregated),
+            _ => Err(Error::ParseFailed("Unknown commitment proof tag")),
+        }
+    }
+}
+
+fn decode_range_proof(bytes: &[u8]) -> Result<RangeProof, Error> {
+    RangeProof::from_bytes(bytes)
+        .ok_or_else(|| Error::ParseFailed("Failed to parse the range proof"))
+}
 
 impl Encodable for Bulletproof {
     fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
@@ -28,7 +85,7 @@ impl Encodable for Bulletproof {
         let commitment_bytes = self.commitment.to_bytes();
         len += commitment_bytes.to_vec().consensus_encode(writer)?;
 
-        len += self.proof.to_bytes().consensus_encode(writer)?;
+        len += self.proof.consensus_encode(writer)?;
 
         len += writer.write(self.signature.as_ref())?;
 
@@ -59,9 +116,7 @@ impl Decodable for Bulletproof {
         let mut bytes = [0u8; PUBLIC_KEY_SIZE];
         reader.read_exact(&mut bytes)?;
 
-        let bytes: Vec<u8> = Decodable::consensus_decode(reader)?;
-        let proof: RangeProof = RangeProof::from_bytes(bytes.as_slice())
-            .ok_or_else(|| Error::ParseFailed("Failed to parse the range proof"))?;
+        let proof: CommitmentProof = Decodable::consensus_decode(reader)?;
 
         let mut bytes = [0u8; SCHNORR_SIGNATURE_SIZE];
         reader.read_exact(&mut bytes)?;
@@ -18,6 +18,9 @@ pub enum BulletproofError {
     PublicKeyMismatch,
 
     LumaMismatch,
+
+    /// The commitments can't be proven by one aggregated range proof
+    InvalidAggregation,
 }
 
 impl From<PixelKeyError> for BulletproofError {
@@ -47,6 +50,10 @@ impl core::fmt::Display for BulletproofError {
                 "The public key in the witness does not match the public key in the script"
             ),
             Self::LumaMismatch => write!(f, "Luma doesn't match the proof and commitment"),
+            Self::InvalidAggregation => write!(
+                f,
+                "The commitments can't be proven by one aggregated range proof"
+            ),
         }
     }
 }
@@ -61,6 +68,7 @@ impl std::error::Error for BulletproofError {
             Self::PublicKeyMismatch => None,
             Self::ScriptMismatch => None,
             Self::LumaMismatch => None,
+            Self::InvalidAggregation => None,
         }
     }
 }
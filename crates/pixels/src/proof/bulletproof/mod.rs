@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use bitcoin::{
     ecdsa::Signature,
     hashes::{sha256::Hash as Sha256Hash, Hash as BitcoinHash, HashEngine},
@@ -43,19 +45,75 @@ pub struct Bulletproof {
         )
     )]
     pub commitment: ProjectivePoint,
-    /// Bulletproof proof itself .
-    #[cfg_attr(
-        feature = "serde",
-        serde(
-            serialize_with = "rangeproof_to_hex",
-            deserialize_with = "hex_to_rangeproof"
-        )
-    )]
-    pub proof: RangeProof,
+    /// Range proof of the commitment.
+    pub proof: CommitmentProof,
     pub signature: SchnorrSignature,
     pub chroma_signature: SchnorrSignature,
 }
 
+/// Range proof of the commitment of a [`Bulletproof`].
+///
+/// The commitments of several outputs of the transaction can be proven by one aggregated range
+/// proof, which is much smaller than the proofs of each of them. It is carried by one of the
+/// outputs and covers the commitments of all the outputs with [`CommitmentProof::Aggregated`]
+/// and [`CommitmentProof::InAggregated`] proofs in the order of their vouts, so it is verified
+/// with the whole transaction, see [`bulletproof::verify_aggregated`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum CommitmentProof {
+    /// Range proof of this commitment only.
+    Single(
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                serialize_with = "rangeproof_to_hex",
+                deserialize_with = "hex_to_rangeproof"
+            )
+        )]
+        RangeProof,
+    ),
+    /// Aggregated range proof of the commitments of the transaction outputs.
+    Aggregated {
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                serialize_with = "rangeproof_to_hex",
+                deserialize_with = "hex_to_rangeproof"
+            )
+        )]
+        aggregated: RangeProof,
+    },
+    /// Commitment is proven by the aggregated range proof of another output.
+    InAggregated,
+}
+
+impl CommitmentProof {
+    /// Bytes of the range proof, empty for [`CommitmentProof::InAggregated`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Single(proof) | Self::Aggregated { aggregated: proof } => proof.to_bytes(),
+            Self::InAggregated => Vec::new(),
+        }
+    }
+
+    /// Hash of the commitment and the proof, which is used as the luma of the pixel.
+    pub fn proof_hash(&self, commitment: ProjectivePoint) -> [u8; 32] {
+        let mut hash_engine = Sha256Hash::engine();
+
+        hash_engine.input(&commitment.to_bytes());
+        hash_engine.input(&self.to_bytes());
+
+        Sha256Hash::from_engine(hash_engine).to_byte_array()
+    }
+}
+
+impl From<RangeProof> for CommitmentProof {
+    fn from(proof: RangeProof) -> Self {
+        Self::Single(proof)
+    }
+}
+
 impl From<Bulletproof> for PixelProof {
     fn from(value: Bulletproof) -> Self {
         Self::Bulletproof(alloc::boxed::Box::new(value))
@@ -81,7 +139,7 @@ impl Bulletproof {
         inner_key: secp256k1::PublicKey,
         sender_key: secp256k1::PublicKey,
         commitment: ProjectivePoint,
-        proof: RangeProof,
+        proof: impl Into<CommitmentProof>,
         signature: SchnorrSignature,
         chroma_signature: SchnorrSignature,
     ) -> Self {
@@ -90,7 +148,7 @@ impl Bulletproof {
             inner_key,
             sender_key,
             commitment,
-            proof,
+            proof: proof.into(),
             signature,
             chroma_signature,
         }
@@ -112,15 +170,19 @@ impl Bulletproof {
     }
 
     pub(crate) fn check_luma(&self) -> bool {
-        let mut hash_engine = Sha256Hash::engine();
-
-        hash_engine.input(&self.commitment.to_bytes());
-        hash_engine.input(&self.proof.to_bytes());
+        Luma::from(self.proof.proof_hash(self.commitment)) == self.pixel.luma
+    }
 
-        let bytes = Sha256Hash::from_engine(hash_engine);
-        let value_proof_hash = bytes.to_byte_array();
+    /// Verify the range proof of the commitment. The aggregated range proofs can't be verified
+    /// without the other outputs of the transaction, so they are skipped here.
+    fn check_range_proof(&self) -> Result<(), BulletproofError> {
+        if let CommitmentProof::Single(proof) = &self.proof {
+            if !bulletproof::verify(self.commitment, proof.clone()) {
+                return Err(BulletproofError::InvalidRangeProof);
+            }
+        }
 
-        Luma::from(value_proof_hash) == self.pixel.luma
+        Ok(())
     }
 }
 
@@ -132,11 +194,7 @@ impl CheckableProof for Bulletproof {
 
         self.check_by_parsed_witness_data(&data.signature, &data.pubkey)?;
 
-        if !bulletproof::verify(self.commitment, self.proof.clone()) {
-            return Err(BulletproofError::InvalidRangeProof);
-        }
-
-        Ok(())
+        self.check_range_proof()
     }
 
     fn checked_check_by_output(&self, txout: &TxOut) -> Result<(), Self::Error> {
@@ -154,11 +212,7 @@ impl CheckableProof for Bulletproof {
             return Err(BulletproofError::LumaMismatch);
         }
 
-        if !bulletproof::verify(self.commitment, self.proof.clone()) {
-            return Err(BulletproofError::InvalidRangeProof);
-        }
-
-        Ok(())
+        self.check_range_proof()
     }
 }
 
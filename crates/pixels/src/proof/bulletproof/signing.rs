@@ -5,13 +5,14 @@ use bitcoin::{
     secp256k1::{self, schnorr::Signature, All, Secp256k1},
     PrivateKey, PublicKey,
 };
-use bulletproof::{k256::ProjectivePoint, util::ecdh, RangeProof};
+use bulletproof::{k256::ProjectivePoint, util::ecdh, MAX_AGGREGATED_VALUES};
 use hashbrown::{hash_map::Entry, HashMap};
+use zeroize::{Zeroize, Zeroizing};
 
-use crate::{Bulletproof, BulletproofError, Chroma};
+use crate::{Bulletproof, BulletproofError, Chroma, CommitmentProof};
 
 pub struct CommitmentResult {
-    pub proof: RangeProof,
+    pub proof: CommitmentProof,
     pub commitment: ProjectivePoint,
     pub proof_hash: [u8; 32],
 }
@@ -82,16 +83,11 @@ pub fn get_commitment(
     network: bitcoin::Network,
     amount: u128,
 ) -> Result<(PrivateKey, CommitmentResult), BulletproofError> {
-    let dh_key =
-        ecdh(private_key, public_key, network).map_err(|_e| BulletproofError::InvalidRangeProof)?;
-    let raw_dh_key: [u8; 32] = dh_key
-        .to_bytes()
-        .as_slice()
-        .try_into()
-        .map_err(|_e| BulletproofError::InvalidRangeProof)?;
+    let (dh_key, raw_dh_key) = get_blinding(private_key, public_key, network)?;
 
-    let (proof, commitment) = bulletproof::generate(amount, raw_dh_key);
-    let proof_hash = bulletproof::util::proof_hash(commitment, proof.clone());
+    let (proof, commitment) = bulletproof::generate(amount, *raw_dh_key);
+    let proof = CommitmentProof::Single(proof);
+    let proof_hash = proof.proof_hash(commitment);
 
     Ok((
         dh_key,
@@ -103,6 +99,61 @@ pub fn get_commitment(
     ))
 }
 
+/// Replace the range proofs of the `commitments` to the amounts sent to the `recipients` with one
+/// aggregated range proof, which is carried by the first commitment. The `commitments` must be
+/// in the order of the `recipients` and of the transaction outputs.
+pub fn aggregate_range_proofs(
+    private_key: PrivateKey,
+    network: bitcoin::Network,
+    recipients: &[(PublicKey, u128)],
+    commitments: &mut [(Chroma, CommitmentResult)],
+) -> Result<(), BulletproofError> {
+    if recipients.len() != commitments.len() || recipients.len() > MAX_AGGREGATED_VALUES {
+        return Err(BulletproofError::InvalidAggregation);
+    }
+
+    if recipients.is_empty() {
+        return Ok(());
+    }
+
+    let mut values = recipients
+        .iter()
+        .map(|(recipient, amount)| {
+            let (mut dh_key, raw_dh_key) = get_blinding(private_key, *recipient, network)?;
+            dh_key.inner.non_secure_erase();
+
+            Ok((*amount, *raw_dh_key))
+        })
+        .collect::<Result<Vec<_>, BulletproofError>>()?;
+
+    let (proof, aggregated_commitments) = bulletproof::generate_aggregated(&values);
+
+    for (_, blinding) in values.iter_mut() {
+        blinding.zeroize();
+    }
+
+    for (i, ((_, result), commitment)) in commitments
+        .iter_mut()
+        .zip(aggregated_commitments)
+        .enumerate()
+    {
+        if result.commitment != commitment {
+            return Err(BulletproofError::InvalidAggregation);
+        }
+
+        result.proof = if i == 0 {
+            CommitmentProof::Aggregated {
+                aggregated: proof.clone(),
+            }
+        } else {
+            CommitmentProof::InAggregated
+        };
+        result.proof_hash = result.proof.proof_hash(commitment);
+    }
+
+    Ok(())
+}
+
 /// Generate the general signature and chroma signatures.
 pub fn create_signatures(
     ctx: &Secp256k1<All>,
@@ -139,6 +190,20 @@ pub fn create_signatures(
     Ok((signature, chroma_signatures))
 }
 
+/// Derive the ECDH key, which bytes are the blinding factor of the commitment. The bytes are
+/// zeroized on drop.
+fn get_blinding(
+    private_key: PrivateKey,
+    public_key: PublicKey,
+    network: bitcoin::Network,
+) -> Result<(PrivateKey, Zeroizing<[u8; 32]>), BulletproofError> {
+    let dh_key =
+        ecdh(private_key, public_key, network).map_err(|_e| BulletproofError::InvalidRangeProof)?;
+    let raw_dh_key = Zeroizing::new(dh_key.inner.secret_bytes());
+
+    Ok((dh_key, raw_dh_key))
+}
+
 fn tweak(
     tweak: &secp256k1::Scalar,
     signing_key: &mut Option<secp256k1::SecretKey>,
@@ -0,0 +1,60 @@
+use bitcoin::{
+    consensus::{Decodable, Encodable},
+    hashes::{sha256, Hash},
+    key::constants::PUBLIC_KEY_SIZE,
+    secp256k1,
+};
+use core2::io;
+
+use crate::{HtlcPixelProof, Pixel};
+
+use super::script::HtlcData;
+
+impl Encodable for HtlcPixelProof {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = self.pixel.consensus_encode(writer)?;
+
+        len += writer.write(self.data.payment_hash.as_byte_array())?;
+
+        len += writer.write(&self.data.recipient_pubkey.serialize())?;
+
+        len += writer.write(&self.data.sender_pubkey.serialize())?;
+
+        len += self.data.lock_height.consensus_encode(writer)?;
+
+        Ok(len)
+    }
+}
+
+impl Decodable for HtlcPixelProof {
+    fn consensus_decode<R: io::Read + ?Sized>(
+        reader: &mut R,
+    ) -> Result<Self, bitcoin::consensus::encode::Error> {
+        let pixel: Pixel = Decodable::consensus_decode(reader)?;
+
+        let mut bytes = [0u8; sha256::Hash::LEN];
+        reader.read_exact(&mut bytes)?;
+        let payment_hash = sha256::Hash::from_byte_array(bytes);
+
+        let recipient_pubkey = decode_pubkey(reader)?;
+        let sender_pubkey = decode_pubkey(reader)?;
+
+        let lock_height: u32 = Decodable::consensus_decode(reader)?;
+
+        Ok(HtlcPixelProof::new(
+            pixel,
+            HtlcData::new(payment_hash, recipient_pubkey, sender_pubkey, lock_height),
+        ))
+    }
+}
+
+fn decode_pubkey<R: io::Read + ?Sized>(
+    reader: &mut R,
+) -> Result<secp256k1::PublicKey, bitcoin::consensus::encode::Error> {
+    let mut bytes = [0u8; PUBLIC_KEY_SIZE];
+    reader.read_exact(&mut bytes)?;
+
+    secp256k1::PublicKey::from_slice(&bytes).map_err(|_| {
+        bitcoin::consensus::encode::Error::ParseFailed("Failed to parse the public key")
+    })
+}
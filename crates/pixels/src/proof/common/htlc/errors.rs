@@ -0,0 +1,78 @@
+use alloc::fmt;
+
+use crate::{proof::p2wsh::errors::P2WSHWitnessParseError, PixelKeyError};
+
+#[derive(Debug)]
+pub enum HtlcPixelProofError {
+    /// Failed to create pixel key
+    PixelKeyError(PixelKeyError),
+
+    /// Invalid witness data
+    InvalidWitnessData(P2WSHWitnessParseError),
+
+    /// The lock is a timestamp instead of the block height
+    LockHeightIsTimestamp(u32),
+
+    /// Sequence of the refund input disables the `nLockTime` of the transaction
+    LockTimeDisabled,
+
+    /// Preimage in the witness doesn't match the payment hash
+    PreimageMismatch,
+
+    /// Redeem script mismatch
+    RedeemScriptMismatch,
+
+    /// Provided and expected script pubkeys mismatch
+    ScriptPubKeyMismatch,
+}
+
+impl fmt::Display for HtlcPixelProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HtlcPixelProofError::PixelKeyError(e) => {
+                write!(f, "Failed to create pixel key: {}", e)
+            }
+            HtlcPixelProofError::InvalidWitnessData(e) => {
+                write!(f, "Invalid witness data: {}", e)
+            }
+            HtlcPixelProofError::LockHeightIsTimestamp(lock) => {
+                write!(f, "Lock {} is a timestamp, not a block height", lock)
+            }
+            HtlcPixelProofError::LockTimeDisabled => {
+                write!(f, "Input sequence disables the lock time")
+            }
+            HtlcPixelProofError::PreimageMismatch => {
+                write!(f, "Preimage doesn't match the payment hash")
+            }
+            HtlcPixelProofError::RedeemScriptMismatch => write!(f, "Redeem script mismatch"),
+            HtlcPixelProofError::ScriptPubKeyMismatch => write!(f, "Script pubkey mismatch"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for HtlcPixelProofError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HtlcPixelProofError::PixelKeyError(e) => Some(e),
+            HtlcPixelProofError::InvalidWitnessData(e) => Some(e),
+            HtlcPixelProofError::LockHeightIsTimestamp(_) => None,
+            HtlcPixelProofError::LockTimeDisabled => None,
+            HtlcPixelProofError::PreimageMismatch => None,
+            HtlcPixelProofError::RedeemScriptMismatch => None,
+            HtlcPixelProofError::ScriptPubKeyMismatch => None,
+        }
+    }
+}
+
+impl From<PixelKeyError> for HtlcPixelProofError {
+    fn from(err: PixelKeyError) -> Self {
+        HtlcPixelProofError::PixelKeyError(err)
+    }
+}
+
+impl From<P2WSHWitnessParseError> for HtlcPixelProofError {
+    fn from(err: P2WSHWitnessParseError) -> Self {
+        HtlcPixelProofError::InvalidWitnessData(err)
+    }
+}
@@ -0,0 +1,225 @@
+//! Implementation of the proof of the Hash Time Locked Contract (HTLC) output
+//! that carries the pixel outside of the Lightning Network, e.g. for the
+//! atomic swaps between the chromas or against the Bitcoin.
+
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::{absolute::LOCK_TIME_THRESHOLD, ScriptBuf, TxIn, TxOut};
+
+use crate::{CheckableProof, Pixel, PixelKey};
+
+use self::errors::HtlcPixelProofError;
+use self::script::{HtlcData, HtlcScript};
+use self::witness::{HtlcWitness, HtlcWitnessStack};
+
+#[cfg(feature = "consensus")]
+pub mod consensus;
+pub mod errors;
+pub mod script;
+pub mod witness;
+
+/// Pixel proof of the P2WSH output with the [`HtlcScript`], which the
+/// recipient claims by revealing the preimage of the payment hash, or the
+/// sender refunds after the lock height.
+///
+/// The key of the recipient is tweaked by the pixel, as the tokens are
+/// transferred to them by the contract.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HtlcPixelProof {
+    /// Pixel that is locked by HTLC.
+    pub pixel: Pixel,
+
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub data: HtlcData,
+}
+
+impl CheckableProof for HtlcPixelProof {
+    type Error = HtlcPixelProofError;
+
+    /// Check that the input provides the expected redeem script, and the
+    /// preimage of the payment hash for the claim, or enables the `nLockTime`
+    /// of the transaction for the refund.
+    ///
+    /// The lock time itself is a property of the whole transaction, so it's
+    /// checked by the transaction checker with [`HtlcPixelProof::refund_lock_height`].
+    fn checked_check_by_input(&self, txin: &TxIn) -> Result<(), Self::Error> {
+        let parsed_witness = HtlcWitness::from_witness(&txin.witness)?;
+
+        if parsed_witness.redeem_script != self.to_redeem_script()? {
+            return Err(HtlcPixelProofError::RedeemScriptMismatch);
+        }
+
+        match parsed_witness.stack {
+            HtlcWitnessStack::Claim { preimage, .. } => {
+                if sha256::Hash::hash(&preimage) != self.data.payment_hash {
+                    return Err(HtlcPixelProofError::PreimageMismatch);
+                }
+            }
+            HtlcWitnessStack::Refund { .. } => {
+                if !txin.sequence.enables_absolute_lock_time() {
+                    return Err(HtlcPixelProofError::LockTimeDisabled);
+                }
+            }
+        }
+
+        // TODO: check signature.
+
+        Ok(())
+    }
+
+    /// Check the proof by transaction output by comparing expected and got `script_pubkey`.
+    fn checked_check_by_output(&self, txout: &TxOut) -> Result<(), Self::Error> {
+        if txout.script_pubkey != self.to_script_pubkey()? {
+            return Err(HtlcPixelProofError::ScriptPubKeyMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+impl HtlcPixelProof {
+    pub fn new(pixel: impl Into<Pixel>, data: HtlcData) -> Self {
+        Self {
+            pixel: pixel.into(),
+            data,
+        }
+    }
+
+    /// Tweak the recipient's key and create the redeem script from it and the other data.
+    pub fn to_redeem_script(&self) -> Result<ScriptBuf, HtlcPixelProofError> {
+        if self.data.lock_height >= LOCK_TIME_THRESHOLD {
+            return Err(HtlcPixelProofError::LockHeightIsTimestamp(
+                self.data.lock_height,
+            ));
+        }
+
+        let pixel_key = PixelKey::new(self.pixel, &self.data.recipient_pubkey)?;
+
+        let script = HtlcScript {
+            recipient_pubkey: *pixel_key,
+            ..self.data
+        };
+
+        Ok(script.to_script())
+    }
+
+    pub fn to_script_pubkey(&self) -> Result<ScriptBuf, HtlcPixelProofError> {
+        self.to_redeem_script().map(|script| script.to_v0_p2wsh())
+    }
+
+    /// Returns the lock height if the input refunds the output to the sender, `None` if it's
+    /// claimed by the recipient or its witness is invalid.
+    pub fn refund_lock_height(&self, txin: &TxIn) -> Option<u32> {
+        let parsed_witness = HtlcWitness::from_witness(&txin.witness).ok()?;
+
+        match parsed_witness.stack {
+            HtlcWitnessStack::Refund { .. } => Some(self.data.lock_height),
+            HtlcWitnessStack::Claim { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::ecdsa::Signature;
+    use bitcoin::secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+    use bitcoin::Sequence;
+
+    use super::*;
+    use crate::Chroma;
+
+    const LOCK_HEIGHT: u32 = 850_000;
+    const PREIMAGE: [u8; 32] = [7; 32];
+
+    fn pubkey(byte: u8) -> PublicKey {
+        SecretKey::from_slice(&[byte; 32])
+            .unwrap()
+            .public_key(&Secp256k1::new())
+    }
+
+    fn proof() -> HtlcPixelProof {
+        HtlcPixelProof::new(
+            Pixel::new(100u128, Chroma::from(pubkey(1))),
+            HtlcData::new(
+                sha256::Hash::hash(&PREIMAGE),
+                pubkey(2),
+                pubkey(3),
+                LOCK_HEIGHT,
+            ),
+        )
+    }
+
+    fn signature() -> Signature {
+        let ctx = Secp256k1::new();
+        let secret = SecretKey::from_slice(&[2; 32]).unwrap();
+
+        Signature::sighash_all(ctx.sign_ecdsa(&Message::from_slice(&[1; 32]).unwrap(), &secret))
+    }
+
+    fn spending_input(proof: &HtlcPixelProof, stack: HtlcWitnessStack, sequence: Sequence) -> TxIn {
+        let witness = HtlcWitness::new(stack, proof.to_redeem_script().unwrap());
+
+        TxIn {
+            sequence,
+            witness: witness.into_witness(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_by_output() {
+        let proof = proof();
+        let txout = TxOut {
+            value: 1000,
+            script_pubkey: proof.to_script_pubkey().unwrap(),
+        };
+
+        assert!(proof.checked_check_by_output(&txout).is_ok());
+
+        let mut other = proof.clone();
+        other.data.payment_hash = sha256::Hash::hash(&[8; 32]);
+        assert!(matches!(
+            other.checked_check_by_output(&txout),
+            Err(HtlcPixelProofError::ScriptPubKeyMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_claim_requires_preimage() {
+        let proof = proof();
+
+        let claim = |preimage| HtlcWitnessStack::Claim {
+            signature: signature(),
+            preimage,
+        };
+
+        let txin = spending_input(&proof, claim(PREIMAGE), Sequence::MAX);
+        assert!(proof.checked_check_by_input(&txin).is_ok());
+        assert_eq!(proof.refund_lock_height(&txin), None);
+
+        let txin = spending_input(&proof, claim([8; 32]), Sequence::MAX);
+        assert!(matches!(
+            proof.checked_check_by_input(&txin),
+            Err(HtlcPixelProofError::PreimageMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_refund_enables_lock_time() {
+        let proof = proof();
+
+        let refund = || HtlcWitnessStack::Refund {
+            signature: signature(),
+        };
+
+        let txin = spending_input(&proof, refund(), Sequence::ENABLE_LOCKTIME_NO_RBF);
+        assert!(proof.checked_check_by_input(&txin).is_ok());
+        assert_eq!(proof.refund_lock_height(&txin), Some(LOCK_HEIGHT));
+
+        let txin = spending_input(&proof, refund(), Sequence::MAX);
+        assert!(matches!(
+            proof.checked_check_by_input(&txin),
+            Err(HtlcPixelProofError::LockTimeDisabled)
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+//! Script of the Hash Time Locked Contract (HTLC) output that can be claimed
+//! by the recipient with the preimage of the payment hash, or refunded to the
+//! sender after the block height:
+//!
+//! ```text
+//! OP_IF
+//!    # To recipient with the preimage.
+//!    OP_SIZE 32 OP_EQUALVERIFY
+//!    OP_SHA256 <payment_hash> OP_EQUALVERIFY
+//!    <recipient_pubkey>
+//! OP_ELSE
+//!    # To sender after timeout.
+//!    <lock_height> OP_CHECKLOCKTIMEVERIFY OP_DROP
+//!    <sender_pubkey>
+//! OP_ENDIF
+//! OP_CHECKSIG
+//! ```
+//!
+//! Unlike [`LightningHtlcScript`](crate::LightningHtlcScript), the script
+//! doesn't depend on the Lightning channel, so the same contract can be
+//! created on the other chain or in the other chroma for the atomic swap.
+
+use bitcoin::{
+    blockdata::{
+        opcodes::all::{
+            OP_CHECKSIG, OP_CLTV, OP_DROP, OP_ELSE, OP_ENDIF, OP_EQUALVERIFY, OP_IF, OP_SHA256,
+            OP_SIZE,
+        },
+        script::Builder,
+    },
+    hashes::{sha256, Hash},
+    secp256k1::PublicKey,
+    ScriptBuf,
+};
+
+/// Size of the preimage of the payment hash in bytes.
+pub const PREIMAGE_SIZE: usize = 32;
+
+pub type HtlcData = HtlcScript;
+
+/// Data of the HTLC output script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HtlcScript {
+    /// SHA256 hash of the preimage the recipient claims the output with.
+    pub payment_hash: sha256::Hash,
+
+    /// Public key of the recipient.
+    ///
+    /// By rules of the YUV protocol, this key is always tweaked by pixel in
+    /// script.
+    pub recipient_pubkey: PublicKey,
+
+    /// Public key of the sender, which gets the output back after timeout.
+    pub sender_pubkey: PublicKey,
+
+    /// Height of the block from which the output can be refunded.
+    pub lock_height: u32,
+}
+
+impl HtlcScript {
+    pub fn new(
+        payment_hash: sha256::Hash,
+        recipient_pubkey: PublicKey,
+        sender_pubkey: PublicKey,
+        lock_height: u32,
+    ) -> Self {
+        Self {
+            payment_hash,
+            recipient_pubkey,
+            sender_pubkey,
+            lock_height,
+        }
+    }
+
+    pub fn to_script(&self) -> ScriptBuf {
+        Builder::new()
+            .push_opcode(OP_IF)
+            .push_opcode(OP_SIZE)
+            .push_int(PREIMAGE_SIZE as i64)
+            .push_opcode(OP_EQUALVERIFY)
+            .push_opcode(OP_SHA256)
+            .push_slice(self.payment_hash.to_byte_array())
+            .push_opcode(OP_EQUALVERIFY)
+            .push_slice(self.recipient_pubkey.serialize())
+            .push_opcode(OP_ELSE)
+            .push_int(self.lock_height as i64)
+            .push_opcode(OP_CLTV)
+            .push_opcode(OP_DROP)
+            .push_slice(self.sender_pubkey.serialize())
+            .push_opcode(OP_ENDIF)
+            .push_opcode(OP_CHECKSIG)
+            .into_script()
+    }
+}
+
+impl From<&HtlcScript> for ScriptBuf {
+    fn from(value: &HtlcScript) -> Self {
+        value.to_script()
+    }
+}
+
+impl From<HtlcScript> for ScriptBuf {
+    fn from(value: HtlcScript) -> Self {
+        value.to_script()
+    }
+}
@@ -0,0 +1,92 @@
+use alloc::{format, vec, vec::Vec};
+use bitcoin::ecdsa::Signature as EcdsaSig;
+
+use crate::alloc::string::ToString;
+use crate::proof::p2wsh::{
+    errors::P2WSHWitnessParseError,
+    witness::{FromWitnessStack, IntoWitnessStack, P2WSHWitness},
+};
+
+use super::script::PREIMAGE_SIZE;
+
+pub type HtlcWitness = P2WSHWitness<HtlcWitnessStack>;
+
+/// Data that spends the HTLC output by one of the branches of the script.
+///
+/// The witness stack looks like this:
+///
+/// - `<signature> <preimage> 0x01` for the claim by the recipient;
+/// - `<signature> <>` for the refund to the sender.
+#[derive(Debug, PartialEq, Eq)]
+pub enum HtlcWitnessStack {
+    /// Claim by the recipient with the preimage of the payment hash.
+    Claim {
+        /// Signature created by the tweaked key of the recipient.
+        signature: EcdsaSig,
+        preimage: [u8; PREIMAGE_SIZE],
+    },
+
+    /// Refund to the sender after timeout.
+    Refund {
+        /// Signature created by the key of the sender.
+        signature: EcdsaSig,
+    },
+}
+
+impl HtlcWitnessStack {
+    pub fn signature(&self) -> &EcdsaSig {
+        match self {
+            Self::Claim { signature, .. } | Self::Refund { signature } => signature,
+        }
+    }
+}
+
+impl FromWitnessStack for HtlcWitnessStack {
+    fn from_witness_stack(stack: &[Vec<u8>]) -> Result<Self, P2WSHWitnessParseError> {
+        use P2WSHWitnessParseError as Error;
+
+        let parse_signature = |bytes: &[u8]| {
+            EcdsaSig::from_slice(bytes)
+                .map_err(|_| Error::Custom("invalid signature in witness".to_string()))
+        };
+
+        match stack {
+            [signature, preimage, selector] if selector.as_slice() == [0x01] => {
+                let preimage = preimage.as_slice().try_into().map_err(|_| {
+                    Error::Custom(format!(
+                        "invalid preimage length should be {}, got {}",
+                        PREIMAGE_SIZE,
+                        preimage.len()
+                    ))
+                })?;
+
+                Ok(Self::Claim {
+                    signature: parse_signature(signature)?,
+                    preimage,
+                })
+            }
+            [signature, selector] if selector.is_empty() => Ok(Self::Refund {
+                signature: parse_signature(signature)?,
+            }),
+            _ => Err(Error::Custom(
+                "invalid witness, expected claim or refund stack".to_string(),
+            )),
+        }
+    }
+}
+
+impl IntoWitnessStack for HtlcWitnessStack {
+    fn into_witness_stack(self) -> Vec<Vec<u8>> {
+        match self {
+            Self::Claim {
+                signature,
+                preimage,
+            } => vec![
+                signature.serialize().to_vec(),
+                preimage.to_vec(),
+                vec![0x01],
+            ],
+            Self::Refund { signature } => vec![signature.serialize().to_vec(), Vec::new()],
+        }
+    }
+}
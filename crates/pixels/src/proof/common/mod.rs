@@ -1,5 +1,6 @@
 //! This module provides implementations of proofs for common types of P2WSH
 //! outputs.
 
+pub mod htlc;
 pub mod lightning;
 pub mod multisig;
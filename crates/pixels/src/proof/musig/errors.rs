@@ -0,0 +1,156 @@
+use alloc::fmt;
+use bitcoin::secp256k1;
+
+use crate::PixelKeyError;
+
+#[derive(Debug)]
+pub enum MusigPixelProofError {
+    /// Error related to tweaking the aggregated key
+    PixelKeyError(PixelKeyError),
+
+    /// Failed to aggregate the keys of the participants
+    Musig(MusigError),
+
+    /// The number of inner keys is not the number of the participants.
+    InvalidNumberOfInnerKeys(usize, usize),
+
+    /// Failed to parse witness
+    WitnessParseError(MusigWitnessParseError),
+
+    /// Provided and expected script pubkeys mismatch
+    ScriptPubKeyMismatch,
+}
+
+impl From<PixelKeyError> for MusigPixelProofError {
+    fn from(e: PixelKeyError) -> Self {
+        MusigPixelProofError::PixelKeyError(e)
+    }
+}
+
+impl From<MusigError> for MusigPixelProofError {
+    fn from(e: MusigError) -> Self {
+        MusigPixelProofError::Musig(e)
+    }
+}
+
+impl From<MusigWitnessParseError> for MusigPixelProofError {
+    fn from(e: MusigWitnessParseError) -> Self {
+        MusigPixelProofError::WitnessParseError(e)
+    }
+}
+
+impl fmt::Display for MusigPixelProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MusigPixelProofError::PixelKeyError(e) => write!(f, "Pixel key error: {}", e),
+            MusigPixelProofError::Musig(e) => write!(f, "MuSig2 error: {}", e),
+            MusigPixelProofError::InvalidNumberOfInnerKeys(actual, expected) => write!(
+                f,
+                "Invalid number of inner keys: expected {}, got {}",
+                expected, actual
+            ),
+            MusigPixelProofError::WitnessParseError(e) => {
+                write!(f, "Witness parse error: {}", e)
+            }
+            MusigPixelProofError::ScriptPubKeyMismatch => write!(f, "Script pubkey mismatch"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for MusigPixelProofError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MusigPixelProofError::PixelKeyError(e) => Some(e),
+            MusigPixelProofError::Musig(e) => Some(e),
+            MusigPixelProofError::InvalidNumberOfInnerKeys(_, _) => None,
+            MusigPixelProofError::WitnessParseError(e) => Some(e),
+            MusigPixelProofError::ScriptPubKeyMismatch => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MusigWitnessParseError {
+    /// Invalid Schnorr signature in the witness
+    InvalidSignature,
+
+    /// Stack in witness has invalid length
+    StackLengthMismatch,
+}
+
+impl fmt::Display for MusigWitnessParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MusigWitnessParseError::InvalidSignature => write!(f, "Invalid signature"),
+            MusigWitnessParseError::StackLengthMismatch => {
+                write!(f, "Invalid witness structure")
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for MusigWitnessParseError {}
+
+#[derive(Debug)]
+pub enum MusigError {
+    /// No public keys to aggregate
+    NoKeys,
+
+    /// No public nonces or partial signatures to aggregate
+    NothingToAggregate,
+
+    /// The signer is not one of the participants
+    NotParticipant,
+
+    /// The secret key is not the one the nonce was generated for
+    SecretKeyMismatch,
+
+    /// Invalid bytes of the public nonce
+    InvalidNonce,
+
+    /// Invalid bytes of the partial signature
+    InvalidPartialSignature,
+
+    /// The hash is out of the curve order, which happens with negligible probability
+    ScalarOutOfRange,
+
+    /// Failed operation on the curve
+    Secp256k1(secp256k1::Error),
+}
+
+impl From<secp256k1::Error> for MusigError {
+    fn from(e: secp256k1::Error) -> Self {
+        MusigError::Secp256k1(e)
+    }
+}
+
+impl fmt::Display for MusigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MusigError::NoKeys => write!(f, "No public keys to aggregate"),
+            MusigError::NothingToAggregate => {
+                write!(f, "No public nonces or partial signatures to aggregate")
+            }
+            MusigError::NotParticipant => write!(f, "The signer is not a participant"),
+            MusigError::SecretKeyMismatch => {
+                write!(f, "The secret key doesn't match the one of the nonce")
+            }
+            MusigError::InvalidNonce => write!(f, "Invalid public nonce"),
+            MusigError::InvalidPartialSignature => write!(f, "Invalid partial signature"),
+            MusigError::ScalarOutOfRange => write!(f, "Hash is out of the curve order"),
+            MusigError::Secp256k1(e) => write!(f, "Secp256k1 error: {}", e),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for MusigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MusigError::Secp256k1(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Implementation of the MuSig2 proof of the 2-of-2 multisig output with the single Taproot key.
+
+use alloc::vec::Vec;
+use bitcoin::key::TweakedPublicKey;
+use bitcoin::secp256k1::{self, Parity, Secp256k1, Verification};
+use bitcoin::taproot::TapTweakHash;
+use bitcoin::{ScriptBuf, TxIn, TxOut};
+
+use crate::keys::pixel_hash_pubkey_scalar;
+use crate::{CheckableProof, Pixel, PixelHash, PixelKey};
+
+use self::errors::MusigPixelProofError;
+use self::signing::MusigKeyAgg;
+use self::witness::MusigWitness;
+
+#[cfg(feature = "consensus")]
+pub mod consensus;
+pub mod errors;
+pub mod signing;
+pub mod witness;
+
+/// Number of the participants of the MuSig2 output.
+pub const MUSIG_PARTICIPANTS: usize = 2;
+
+/// Pixel proof of the 2-of-2 multisig output, where the keys of the participants are aggregated
+/// by MuSig2 into the single Taproot key.
+///
+/// The aggregated key is tweaked by the pixel the same way as the owner's key of the
+/// [`PixelKey`], and the result is the internal key of the Taproot output without the script
+/// path. Unlike the [`MultisigPixelProof`], the output looks like any other P2TR output, and it's
+/// spent by the single Schnorr signature the participants produce together (see [`signing`]).
+///
+/// [`MultisigPixelProof`]: crate::MultisigPixelProof
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MusigPixelProof {
+    /// Pixel that tweaks the aggregated key.
+    pub pixel: Pixel,
+
+    /// Public keys of the participants, sorted lexicographically.
+    pub inner_keys: Vec<secp256k1::PublicKey>,
+}
+
+impl CheckableProof for MusigPixelProof {
+    type Error = MusigPixelProofError;
+
+    /// Check that the input is spent by the key path with a single signature.
+    fn checked_check_by_input(&self, txin: &TxIn) -> Result<(), Self::Error> {
+        let _data = MusigWitness::from_witness(&txin.witness)?;
+
+        // TODO: verify signature.
+
+        Ok(())
+    }
+
+    /// Check the proof by transaction output by comparing expected and got `script_pubkey`.
+    fn checked_check_by_output(&self, txout: &TxOut) -> Result<(), Self::Error> {
+        if txout.script_pubkey != self.to_script_pubkey()? {
+            return Err(MusigPixelProofError::ScriptPubKeyMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+impl MusigPixelProof {
+    pub fn new(pixel: impl Into<Pixel>, mut inner_keys: Vec<secp256k1::PublicKey>) -> Self {
+        // Sort public keys lexigraphically, so the aggregated key doesn't depend on their order.
+        inner_keys.sort();
+
+        Self {
+            pixel: pixel.into(),
+            inner_keys,
+        }
+    }
+
+    /// Key of the participants aggregated by MuSig2, not tweaked by the pixel.
+    pub fn aggregated_key(&self) -> Result<secp256k1::PublicKey, MusigPixelProofError> {
+        let ctx = Secp256k1::verification_only();
+
+        Ok(self.untweaked_key_agg(&ctx)?.public_key())
+    }
+
+    /// Internal key of the Taproot output, which is the aggregated key tweaked by the pixel.
+    pub fn pixel_key(&self) -> Result<PixelKey, MusigPixelProofError> {
+        Ok(PixelKey::new(self.pixel, &self.aggregated_key()?)?)
+    }
+
+    /// Context of the key aggregation with the pixel and the Taproot tweaks applied, which the
+    /// participants sign the spending of the output with.
+    pub fn key_agg<C: Verification>(
+        &self,
+        ctx: &Secp256k1<C>,
+    ) -> Result<MusigKeyAgg, MusigPixelProofError> {
+        let mut key_agg = self.untweaked_key_agg(ctx)?;
+
+        // The same tweak as the one of the `PixelKey`: hash(PXH, P) * G, where `P` is the
+        // aggregated key with even Y.
+        let even_key = key_agg.x_only_public_key().public_key(Parity::Even);
+        let pixel_tweak = pixel_hash_pubkey_scalar(&PixelHash::from(self.pixel), &even_key)?;
+        key_agg.apply_xonly_tweak(ctx, &pixel_tweak)?;
+
+        // Taproot output without the script path commits to the internal key only.
+        let taproot_tweak =
+            TapTweakHash::from_key_and_tweak(key_agg.x_only_public_key(), None).to_scalar();
+        key_agg.apply_xonly_tweak(ctx, &taproot_tweak)?;
+
+        Ok(key_agg)
+    }
+
+    /// Output key of the Taproot output, which verifies the aggregated signature.
+    pub fn output_key(&self) -> Result<TweakedPublicKey, MusigPixelProofError> {
+        let ctx = Secp256k1::verification_only();
+
+        Ok(TweakedPublicKey::dangerous_assume_tweaked(
+            self.key_agg(&ctx)?.x_only_public_key(),
+        ))
+    }
+
+    pub fn to_script_pubkey(&self) -> Result<ScriptBuf, MusigPixelProofError> {
+        Ok(ScriptBuf::new_v1_p2tr_tweaked(self.output_key()?))
+    }
+
+    fn untweaked_key_agg<C: Verification>(
+        &self,
+        ctx: &Secp256k1<C>,
+    ) -> Result<MusigKeyAgg, MusigPixelProofError> {
+        if self.inner_keys.len() != MUSIG_PARTICIPANTS {
+            return Err(MusigPixelProofError::InvalidNumberOfInnerKeys(
+                self.inner_keys.len(),
+                MUSIG_PARTICIPANTS,
+            ));
+        }
+
+        Ok(MusigKeyAgg::new(ctx, &self.inner_keys)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use bitcoin::secp256k1::{Message, SecretKey};
+
+    use super::signing::{nonce_gen, MusigSession};
+    use super::*;
+    use crate::Chroma;
+
+    fn secret_key(byte: u8) -> SecretKey {
+        SecretKey::from_slice(&[byte; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_output_key_is_tweaked_pixel_key() {
+        let ctx = Secp256k1::new();
+
+        for byte in 1..8u8 {
+            let chroma = Chroma::from(secret_key(byte + 50).x_only_public_key(&ctx).0);
+            let proof = MusigPixelProof::new(
+                Pixel::new(100 * byte as u128, chroma),
+                vec![
+                    secret_key(byte).public_key(&ctx),
+                    secret_key(byte + 100).public_key(&ctx),
+                ],
+            );
+
+            let internal_key = proof.pixel_key().unwrap().x_only_public_key().0;
+
+            assert_eq!(
+                proof.to_script_pubkey().unwrap(),
+                ScriptBuf::new_v1_p2tr(&ctx, internal_key, None),
+                "output key should be the pixel key tweaked by Taproot"
+            );
+        }
+    }
+
+    #[test]
+    fn test_participants_sign_spending() {
+        let ctx = Secp256k1::new();
+        let chroma = Chroma::from(secret_key(3).x_only_public_key(&ctx).0);
+        let secret_keys = [secret_key(1), secret_key(2)];
+
+        let proof = MusigPixelProof::new(
+            Pixel::new(1000, chroma),
+            secret_keys.iter().map(|key| key.public_key(&ctx)).collect(),
+        );
+
+        let key_agg = proof.key_agg(&ctx).unwrap();
+        let msg = Message::from_slice(&[4; 32]).unwrap();
+
+        let (first_secnonce, first_pubnonce) =
+            nonce_gen(&ctx, &secret_keys[0], &key_agg, &msg, [5; 32]).unwrap();
+        let (second_secnonce, second_pubnonce) =
+            nonce_gen(&ctx, &secret_keys[1], &key_agg, &msg, [6; 32]).unwrap();
+
+        let session =
+            MusigSession::new(&ctx, key_agg, &[first_pubnonce, second_pubnonce], &msg).unwrap();
+
+        let partial_signatures = [
+            session
+                .partial_sign(&ctx, first_secnonce, &secret_keys[0])
+                .unwrap(),
+            session
+                .partial_sign(&ctx, second_secnonce, &secret_keys[1])
+                .unwrap(),
+        ];
+
+        let signature = session.aggregate(&partial_signatures).unwrap();
+
+        ctx.verify_schnorr(&signature, &msg, &proof.output_key().unwrap().to_inner())
+            .expect("aggregated signature should be valid for the output key");
+    }
+
+    #[test]
+    fn test_invalid_number_of_participants() {
+        let ctx = Secp256k1::new();
+        let chroma = Chroma::from(secret_key(3).x_only_public_key(&ctx).0);
+
+        let proof = MusigPixelProof::new(
+            Pixel::new(1000, chroma),
+            vec![secret_key(1).public_key(&ctx)],
+        );
+
+        assert!(matches!(
+            proof.to_script_pubkey(),
+            Err(MusigPixelProofError::InvalidNumberOfInnerKeys(1, 2))
+        ));
+    }
+}
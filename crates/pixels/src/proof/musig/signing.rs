@@ -0,0 +1,508 @@
+//! MuSig2 signing of the [`MusigPixelProof`] outputs, as specified in BIP-327.
+//!
+//! The spending of the output is signed by the participants in two rounds:
+//!
+//! 1. Each participant generates the nonce with [`nonce_gen`], keeps the [`MusigSecNonce`] in
+//!    secret and sends the [`MusigPubNonce`] to the others.
+//! 2. Once all the public nonces are received, each participant creates the [`MusigSession`] from
+//!    them and the message, signs it with [`MusigSession::partial_sign`] and sends the
+//!    [`MusigPartialSignature`] to the others.
+//!
+//! Any participant can then aggregate the partial signatures into the Schnorr signature valid for
+//! the output key of the proof with [`MusigSession::aggregate`].
+//!
+//! The secret nonce is consumed by the partial signing, so it's never reused for another message.
+//!
+//! [`MusigPixelProof`]: super::MusigPixelProof
+use alloc::vec::Vec;
+
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::key::XOnlyPublicKey;
+use bitcoin::secp256k1::{
+    constants::{MESSAGE_SIZE, PUBLIC_KEY_SIZE},
+    schnorr, Message, Parity, PublicKey, Scalar, Secp256k1, SecretKey, Signing, Verification,
+};
+
+use super::errors::MusigError;
+
+/// Size of the serialized [`MusigPubNonce`] in bytes.
+pub const MUSIG_PUB_NONCE_SIZE: usize = 2 * PUBLIC_KEY_SIZE;
+
+/// Context of the MuSig2 key aggregation: the aggregated key with the tweaks applied to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MusigKeyAgg {
+    /// Public keys of the participants in the order they are aggregated.
+    pubkeys: Vec<PublicKey>,
+    /// Aggregated key with the tweaks applied.
+    aggregated_key: PublicKey,
+    /// If set, the accumulated sign of the aggregated key is negative (`gacc` of BIP-327).
+    negated: bool,
+    /// Accumulated tweak, `None` if it's zero (`tacc` of BIP-327).
+    tweak: Option<SecretKey>,
+}
+
+impl MusigKeyAgg {
+    /// Aggregate the public keys of the participants. The order of the keys matters.
+    pub fn new<C: Verification>(
+        ctx: &Secp256k1<C>,
+        pubkeys: &[PublicKey],
+    ) -> Result<Self, MusigError> {
+        if pubkeys.is_empty() {
+            return Err(MusigError::NoKeys);
+        }
+
+        let mut points = Vec::with_capacity(pubkeys.len());
+        for pubkey in pubkeys {
+            let point = match key_agg_coefficient(pubkeys, pubkey)? {
+                Some(coefficient) => pubkey.mul_tweak(ctx, &coefficient)?,
+                None => *pubkey,
+            };
+
+            points.push(point);
+        }
+
+        let points = points.iter().collect::<Vec<_>>();
+        let aggregated_key = PublicKey::combine_keys(&points)?;
+
+        Ok(Self {
+            pubkeys: pubkeys.to_vec(),
+            aggregated_key,
+            negated: false,
+            tweak: None,
+        })
+    }
+
+    /// Apply the x-only `tweak` to the aggregated key, as it's done by the Taproot and the pixel
+    /// tweaks: `Q' = even(Q) + t * G`.
+    pub fn apply_xonly_tweak<C: Verification>(
+        &mut self,
+        ctx: &Secp256k1<C>,
+        tweak: &Scalar,
+    ) -> Result<(), MusigError> {
+        let mut aggregated_key = self.aggregated_key;
+
+        if !has_even_y(&aggregated_key) {
+            aggregated_key = aggregated_key.negate(ctx);
+            self.negated = !self.negated;
+            self.tweak = self.tweak.map(SecretKey::negate);
+        }
+
+        self.aggregated_key = aggregated_key.add_exp_tweak(ctx, tweak)?;
+
+        // Both the sum and the tweak itself fail to become a secret key only if they are zero.
+        self.tweak = match self.tweak {
+            Some(accumulated) => accumulated.add_tweak(tweak).ok(),
+            None => SecretKey::from_slice(&tweak.to_be_bytes()).ok(),
+        };
+
+        Ok(())
+    }
+
+    /// Aggregated key with the tweaks applied.
+    pub fn public_key(&self) -> PublicKey {
+        self.aggregated_key
+    }
+
+    /// X-only aggregated key with the tweaks applied, which verifies the aggregated signature.
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.aggregated_key.x_only_public_key().0
+    }
+
+    /// Public keys of the participants.
+    pub fn pubkeys(&self) -> &[PublicKey] {
+        &self.pubkeys
+    }
+}
+
+/// Secret nonce of the participant, which must never be reused or shared.
+#[derive(Debug)]
+pub struct MusigSecNonce {
+    k1: SecretKey,
+    k2: SecretKey,
+    /// Public key of the participant the nonce is generated for.
+    pubkey: PublicKey,
+}
+
+impl Drop for MusigSecNonce {
+    fn drop(&mut self) {
+        self.k1.non_secure_erase();
+        self.k2.non_secure_erase();
+    }
+}
+
+/// Public nonce of the participant, which is sent to the others at the first round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MusigPubNonce {
+    pub r1: PublicKey,
+    pub r2: PublicKey,
+}
+
+impl MusigPubNonce {
+    pub fn serialize(&self) -> [u8; MUSIG_PUB_NONCE_SIZE] {
+        let mut bytes = [0u8; MUSIG_PUB_NONCE_SIZE];
+
+        bytes[..PUBLIC_KEY_SIZE].copy_from_slice(&self.r1.serialize());
+        bytes[PUBLIC_KEY_SIZE..].copy_from_slice(&self.r2.serialize());
+
+        bytes
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, MusigError> {
+        if bytes.len() != MUSIG_PUB_NONCE_SIZE {
+            return Err(MusigError::InvalidNonce);
+        }
+
+        let r1 = PublicKey::from_slice(&bytes[..PUBLIC_KEY_SIZE])
+            .map_err(|_| MusigError::InvalidNonce)?;
+        let r2 = PublicKey::from_slice(&bytes[PUBLIC_KEY_SIZE..])
+            .map_err(|_| MusigError::InvalidNonce)?;
+
+        Ok(Self { r1, r2 })
+    }
+}
+
+/// Partial signature of the participant, which is sent to the others at the second round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MusigPartialSignature(SecretKey);
+
+impl MusigPartialSignature {
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0.secret_bytes()
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, MusigError> {
+        SecretKey::from_slice(bytes)
+            .map(Self)
+            .map_err(|_| MusigError::InvalidPartialSignature)
+    }
+}
+
+/// Generate the nonce of the participant with the `secret_key` for signing the `msg` with the
+/// aggregated key of the `key_agg`.
+///
+/// `rand` must be 32 bytes of fresh randomness, the nonce is derived from it and the signing data.
+pub fn nonce_gen<C: Signing>(
+    ctx: &Secp256k1<C>,
+    secret_key: &SecretKey,
+    key_agg: &MusigKeyAgg,
+    msg: &Message,
+    rand: [u8; 32],
+) -> Result<(MusigSecNonce, MusigPubNonce), MusigError> {
+    let pubkey = secret_key.public_key(ctx);
+
+    // Mixing in the secret key keeps the nonce unpredictable even with the weak randomness.
+    let mut rand = tagged_hash("MuSig/aux", &[&rand]);
+    for (byte, secret) in rand.iter_mut().zip(secret_key.secret_bytes()) {
+        *byte ^= secret;
+    }
+
+    let aggregated_key = key_agg.x_only_public_key().serialize();
+    let msg_len = (MESSAGE_SIZE as u64).to_be_bytes();
+
+    let nonce = |index: u8| {
+        let hash = tagged_hash(
+            "MuSig/nonce",
+            &[
+                &rand,
+                &[PUBLIC_KEY_SIZE as u8],
+                &pubkey.serialize(),
+                &[aggregated_key.len() as u8],
+                &aggregated_key,
+                // The message is always present.
+                &[1],
+                &msg_len,
+                &msg[..],
+                // No extra input.
+                &0u32.to_be_bytes(),
+                &[index],
+            ],
+        );
+
+        SecretKey::from_slice(&hash).map_err(|_| MusigError::ScalarOutOfRange)
+    };
+
+    let k1 = nonce(0)?;
+    let k2 = nonce(1)?;
+
+    let pubnonce = MusigPubNonce {
+        r1: k1.public_key(ctx),
+        r2: k2.public_key(ctx),
+    };
+
+    Ok((MusigSecNonce { k1, k2, pubkey }, pubnonce))
+}
+
+/// Signing session of the message, created by each participant from the public nonces of all the
+/// participants.
+#[derive(Debug, Clone)]
+pub struct MusigSession {
+    key_agg: MusigKeyAgg,
+    /// Nonce coefficient.
+    b: Scalar,
+    /// Final nonce of the signature.
+    r: PublicKey,
+    /// Challenge of the signature.
+    e: Scalar,
+}
+
+impl MusigSession {
+    pub fn new<C: Verification>(
+        ctx: &Secp256k1<C>,
+        key_agg: MusigKeyAgg,
+        pubnonces: &[MusigPubNonce],
+        msg: &Message,
+    ) -> Result<Self, MusigError> {
+        if pubnonces.is_empty() {
+            return Err(MusigError::NothingToAggregate);
+        }
+
+        let r1 = pubnonces.iter().map(|nonce| &nonce.r1).collect::<Vec<_>>();
+        let r2 = pubnonces.iter().map(|nonce| &nonce.r2).collect::<Vec<_>>();
+
+        // The aggregated nonce at infinity can be produced only by the malicious participant, so
+        // the session is aborted instead.
+        let r1 = PublicKey::combine_keys(&r1)?;
+        let r2 = PublicKey::combine_keys(&r2)?;
+
+        let aggregated_key = key_agg.x_only_public_key().serialize();
+
+        let b = hash_to_scalar(tagged_hash(
+            "MuSig/noncecoef",
+            &[&r1.serialize(), &r2.serialize(), &aggregated_key, &msg[..]],
+        ))?;
+
+        let r = r1.combine(&r2.mul_tweak(ctx, &b)?)?;
+
+        let e = hash_to_scalar(tagged_hash(
+            "BIP0340/challenge",
+            &[
+                &r.x_only_public_key().0.serialize(),
+                &aggregated_key,
+                &msg[..],
+            ],
+        ))?;
+
+        Ok(Self { key_agg, b, r, e })
+    }
+
+    /// Sign the session's message with the `secret_key` of the participant, consuming the secret
+    /// nonce generated for it.
+    pub fn partial_sign<C: Signing>(
+        &self,
+        ctx: &Secp256k1<C>,
+        secnonce: MusigSecNonce,
+        secret_key: &SecretKey,
+    ) -> Result<MusigPartialSignature, MusigError> {
+        let pubkey = secret_key.public_key(ctx);
+        if pubkey != secnonce.pubkey {
+            return Err(MusigError::SecretKeyMismatch);
+        }
+
+        if !self.key_agg.pubkeys.contains(&pubkey) {
+            return Err(MusigError::NotParticipant);
+        }
+
+        let (mut k1, mut k2) = (secnonce.k1, secnonce.k2);
+        if !has_even_y(&self.r) {
+            k1 = k1.negate();
+            k2 = k2.negate();
+        }
+
+        // d = g * gacc * sk, where `g` is the sign of the aggregated key.
+        let mut d = *secret_key;
+        if has_even_y(&self.key_agg.aggregated_key) == self.key_agg.negated {
+            d = d.negate();
+        }
+
+        // s = k1 + b * k2 + e * a * d
+        let mut ead = d.mul_tweak(&self.e)?;
+        if let Some(coefficient) = key_agg_coefficient(&self.key_agg.pubkeys, &pubkey)? {
+            ead = ead.mul_tweak(&coefficient)?;
+        }
+
+        let s = k1
+            .add_tweak(&Scalar::from(k2.mul_tweak(&self.b)?))?
+            .add_tweak(&Scalar::from(ead))?;
+
+        Ok(MusigPartialSignature(s))
+    }
+
+    /// Aggregate the partial signatures of all the participants into the Schnorr signature.
+    pub fn aggregate(
+        &self,
+        partial_signatures: &[MusigPartialSignature],
+    ) -> Result<schnorr::Signature, MusigError> {
+        let (first, rest) = partial_signatures
+            .split_first()
+            .ok_or(MusigError::NothingToAggregate)?;
+
+        let mut s = first.0;
+        for partial_signature in rest {
+            s = s.add_tweak(&Scalar::from(partial_signature.0))?;
+        }
+
+        // s = sum(s_i) + e * g * tacc
+        if let Some(tweak) = self.key_agg.tweak {
+            let mut etweak = tweak.mul_tweak(&self.e)?;
+            if !has_even_y(&self.key_agg.aggregated_key) {
+                etweak = etweak.negate();
+            }
+
+            s = s.add_tweak(&Scalar::from(etweak))?;
+        }
+
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.r.x_only_public_key().0.serialize());
+        bytes[32..].copy_from_slice(&s.secret_bytes());
+
+        Ok(schnorr::Signature::from_slice(&bytes)?)
+    }
+}
+
+/// Coefficient of the `pubkey` in the aggregated key, `None` if it's one.
+fn key_agg_coefficient(
+    pubkeys: &[PublicKey],
+    pubkey: &PublicKey,
+) -> Result<Option<Scalar>, MusigError> {
+    // The second distinct key has the coefficient one.
+    if pubkeys.iter().find(|key| **key != pubkeys[0]) == Some(pubkey) {
+        return Ok(None);
+    }
+
+    let serialized = pubkeys.iter().map(PublicKey::serialize).collect::<Vec<_>>();
+    let serialized = serialized
+        .iter()
+        .map(|key| key.as_slice())
+        .collect::<Vec<_>>();
+    let list_hash = tagged_hash("KeyAgg list", &serialized);
+
+    hash_to_scalar(tagged_hash(
+        "KeyAgg coefficient",
+        &[&list_hash, &pubkey.serialize()],
+    ))
+    .map(Some)
+}
+
+/// BIP-340 tagged hash of the concatenation of the `data`.
+fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = sha256::Hash::hash(tag.as_bytes());
+
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag_hash.as_byte_array());
+    engine.input(tag_hash.as_byte_array());
+    for chunk in data {
+        engine.input(chunk);
+    }
+
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+/// The hashes are out of the curve order with negligible probability, so they aren't reduced.
+fn hash_to_scalar(hash: [u8; 32]) -> Result<Scalar, MusigError> {
+    Scalar::from_be_bytes(hash).map_err(|_| MusigError::ScalarOutOfRange)
+}
+
+fn has_even_y(key: &PublicKey) -> bool {
+    key.x_only_public_key().1 == Parity::Even
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::str::FromStr;
+
+    use bitcoin::secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+    use super::*;
+
+    #[test]
+    fn test_key_agg_vectors() {
+        // Test vectors of BIP-327.
+        let pubkeys = [
+            "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66",
+        ]
+        .map(|pubkey| PublicKey::from_str(pubkey).unwrap());
+
+        let ctx = Secp256k1::verification_only();
+
+        for (keys, expected) in [
+            (
+                vec![pubkeys[0], pubkeys[1], pubkeys[2]],
+                "90539eede565f5d054f32cc0c220126889ed1e5d193baf15aef344fe59d4610c",
+            ),
+            (
+                vec![pubkeys[2], pubkeys[1], pubkeys[0]],
+                "6204de8b083426dc6eaf9502d27024d53fc826bf7d2012148a0575435df54b2b",
+            ),
+            (
+                vec![pubkeys[0], pubkeys[0], pubkeys[0]],
+                "b436e3bad62b8cd409969a224731c193d051162d8c5ae8b109306127da3aa935",
+            ),
+        ] {
+            let key_agg = MusigKeyAgg::new(&ctx, &keys).unwrap();
+
+            assert_eq!(key_agg.x_only_public_key().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let ctx = Secp256k1::new();
+
+        for byte in 1..8u8 {
+            let secret_keys = [
+                SecretKey::from_slice(&[byte; 32]).unwrap(),
+                SecretKey::from_slice(&[byte + 100; 32]).unwrap(),
+            ];
+            let pubkeys = secret_keys.map(|secret_key| secret_key.public_key(&ctx));
+
+            let mut key_agg = MusigKeyAgg::new(&ctx, &pubkeys).unwrap();
+            key_agg
+                .apply_xonly_tweak(&ctx, &Scalar::from_be_bytes([byte; 32]).unwrap())
+                .unwrap();
+            key_agg
+                .apply_xonly_tweak(&ctx, &Scalar::from_be_bytes([byte + 1; 32]).unwrap())
+                .unwrap();
+
+            let msg = Message::from_slice(&[byte + 2; 32]).unwrap();
+
+            let (secnonces, pubnonces): (Vec<_>, Vec<_>) = secret_keys
+                .iter()
+                .enumerate()
+                .map(|(i, secret_key)| {
+                    nonce_gen(&ctx, secret_key, &key_agg, &msg, [byte + i as u8; 32]).unwrap()
+                })
+                .unzip();
+
+            let session = MusigSession::new(&ctx, key_agg.clone(), &pubnonces, &msg).unwrap();
+
+            let partial_signatures = secnonces
+                .into_iter()
+                .zip(&secret_keys)
+                .map(|(secnonce, secret_key)| {
+                    session.partial_sign(&ctx, secnonce, secret_key).unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            let signature = session.aggregate(&partial_signatures).unwrap();
+
+            ctx.verify_schnorr(&signature, &msg, &key_agg.x_only_public_key())
+                .expect("aggregated signature should be valid");
+
+            let (_, foreign) = nonce_gen(&ctx, &secret_keys[0], &key_agg, &msg, [0; 32]).unwrap();
+            let session =
+                MusigSession::new(&ctx, key_agg.clone(), &[pubnonces[0], foreign], &msg).unwrap();
+            let signature = session.aggregate(&partial_signatures).unwrap();
+
+            assert!(
+                ctx.verify_schnorr(&signature, &msg, &key_agg.x_only_public_key())
+                    .is_err(),
+                "signature with the mismatched nonces should be invalid"
+            );
+        }
+    }
+}
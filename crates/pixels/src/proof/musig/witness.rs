@@ -0,0 +1,48 @@
+use alloc::vec::Vec;
+use bitcoin::{taproot::Signature, Witness};
+
+use super::errors::MusigWitnessParseError;
+
+/// Data that spends a MuSig2 output by the key path.
+pub struct MusigWitness {
+    /// Signature aggregated from the partial signatures of the participants.
+    pub signature: Signature,
+}
+
+impl MusigWitness {
+    pub fn new(signature: Signature) -> Self {
+        Self { signature }
+    }
+
+    /// Parse a witness into a [`MusigWitness`].
+    pub fn from_witness(witness: &Witness) -> Result<Self, MusigWitnessParseError> {
+        let stack = witness.iter().collect::<Vec<_>>();
+
+        let [signature] = stack.as_slice() else {
+            return Err(MusigWitnessParseError::StackLengthMismatch);
+        };
+
+        let signature = Signature::from_slice(signature)
+            .map_err(|_| MusigWitnessParseError::InvalidSignature)?;
+
+        Ok(Self { signature })
+    }
+}
+
+impl TryFrom<&Witness> for MusigWitness {
+    type Error = MusigWitnessParseError;
+
+    fn try_from(witness: &Witness) -> Result<Self, Self::Error> {
+        MusigWitness::from_witness(witness)
+    }
+}
+
+impl From<MusigWitness> for Witness {
+    fn from(value: MusigWitness) -> Self {
+        let mut witness = Witness::new();
+
+        witness.push(value.signature.to_vec());
+
+        witness
+    }
+}
@@ -0,0 +1,38 @@
+use bitcoin::{
+    consensus::{Decodable, Encodable},
+    key::constants::PUBLIC_KEY_SIZE,
+    secp256k1,
+};
+use core2::io;
+
+use crate::{Pixel, TimelockPixelProof};
+
+impl Encodable for TimelockPixelProof {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = self.pixel.consensus_encode(writer)?;
+
+        len += writer.write(&self.inner_key.serialize())?;
+
+        len += self.lock_height.consensus_encode(writer)?;
+
+        Ok(len)
+    }
+}
+
+impl Decodable for TimelockPixelProof {
+    fn consensus_decode<R: io::Read + ?Sized>(
+        reader: &mut R,
+    ) -> Result<Self, bitcoin::consensus::encode::Error> {
+        let pixel: Pixel = Decodable::consensus_decode(reader)?;
+
+        let mut bytes = [0u8; PUBLIC_KEY_SIZE];
+        reader.read_exact(&mut bytes)?;
+        let inner_key = secp256k1::PublicKey::from_slice(&bytes).map_err(|_| {
+            bitcoin::consensus::encode::Error::ParseFailed("Failed to parse the public key")
+        })?;
+
+        let lock_height: u32 = Decodable::consensus_decode(reader)?;
+
+        Ok(TimelockPixelProof::new(pixel, inner_key, lock_height))
+    }
+}
@@ -0,0 +1,71 @@
+use alloc::fmt;
+
+use crate::{proof::p2wsh::errors::P2WSHWitnessParseError, PixelKeyError};
+
+#[derive(Debug)]
+pub enum TimelockPixelProofError {
+    /// Failed to create pixel key
+    PixelKeyError(PixelKeyError),
+
+    /// Invalid witness data
+    InvalidWitnessData(P2WSHWitnessParseError),
+
+    /// The lock is a timestamp instead of the block height
+    LockHeightIsTimestamp(u32),
+
+    /// Sequence of the input disables the `nLockTime` of the transaction
+    LockTimeDisabled,
+
+    /// Redeem script mismatch
+    RedeemScriptMismatch,
+
+    /// Provided and expected script pubkeys mismatch
+    ScriptPubKeyMismatch,
+}
+
+impl fmt::Display for TimelockPixelProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimelockPixelProofError::PixelKeyError(e) => {
+                write!(f, "Failed to create pixel key: {}", e)
+            }
+            TimelockPixelProofError::InvalidWitnessData(e) => {
+                write!(f, "Invalid witness data: {}", e)
+            }
+            TimelockPixelProofError::LockHeightIsTimestamp(lock) => {
+                write!(f, "Lock {} is a timestamp, not a block height", lock)
+            }
+            TimelockPixelProofError::LockTimeDisabled => {
+                write!(f, "Input sequence disables the lock time")
+            }
+            TimelockPixelProofError::RedeemScriptMismatch => write!(f, "Redeem script mismatch"),
+            TimelockPixelProofError::ScriptPubKeyMismatch => write!(f, "Script pubkey mismatch"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for TimelockPixelProofError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TimelockPixelProofError::PixelKeyError(e) => Some(e),
+            TimelockPixelProofError::InvalidWitnessData(e) => Some(e),
+            TimelockPixelProofError::LockHeightIsTimestamp(_) => None,
+            TimelockPixelProofError::LockTimeDisabled => None,
+            TimelockPixelProofError::RedeemScriptMismatch => None,
+            TimelockPixelProofError::ScriptPubKeyMismatch => None,
+        }
+    }
+}
+
+impl From<PixelKeyError> for TimelockPixelProofError {
+    fn from(err: PixelKeyError) -> Self {
+        TimelockPixelProofError::PixelKeyError(err)
+    }
+}
+
+impl From<P2WSHWitnessParseError> for TimelockPixelProofError {
+    fn from(err: P2WSHWitnessParseError) -> Self {
+        TimelockPixelProofError::InvalidWitnessData(err)
+    }
+}
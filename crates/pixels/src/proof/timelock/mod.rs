@@ -0,0 +1,190 @@
+//! Implementation of the proof of the output that can't be spent before the block height, e.g.
+//! to distribute the vesting tokens.
+
+use bitcoin::absolute::LOCK_TIME_THRESHOLD;
+use bitcoin::{secp256k1, ScriptBuf, TxIn, TxOut};
+
+use crate::{CheckableProof, Pixel, PixelKey};
+
+use self::errors::TimelockPixelProofError;
+use self::script::TimelockScript;
+use self::witness::TimelockWitness;
+
+#[cfg(feature = "consensus")]
+pub mod consensus;
+pub mod errors;
+pub mod script;
+pub mod witness;
+
+/// Pixel proof of the P2WSH output that is locked until the block height by
+/// `OP_CHECKLOCKTIMEVERIFY`, and then can be spent by the owner's key tweaked
+/// by the pixel.
+///
+/// The script of the output is [`TimelockScript`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimelockPixelProof {
+    /// Pixel that tweaks the owner's key.
+    pub pixel: Pixel,
+
+    /// Public key of the owner.
+    pub inner_key: secp256k1::PublicKey,
+
+    /// Height of the block from which the output can be spent.
+    pub lock_height: u32,
+}
+
+impl CheckableProof for TimelockPixelProof {
+    type Error = TimelockPixelProofError;
+
+    /// Check that the input provides the expected redeem script and enables
+    /// the `nLockTime` of the transaction, which is then checked by the script.
+    ///
+    /// The lock time itself is a property of the whole transaction, so it's
+    /// checked by the transaction checker with [`PixelProof::input_lock_height`].
+    ///
+    /// [`PixelProof::input_lock_height`]: crate::PixelProof::input_lock_height
+    fn checked_check_by_input(&self, txin: &TxIn) -> Result<(), Self::Error> {
+        let parsed_witness = TimelockWitness::from_witness(&txin.witness)?;
+
+        if parsed_witness.redeem_script != self.to_redeem_script()? {
+            return Err(TimelockPixelProofError::RedeemScriptMismatch);
+        }
+
+        if !txin.sequence.enables_absolute_lock_time() {
+            return Err(TimelockPixelProofError::LockTimeDisabled);
+        }
+
+        // TODO: check signature.
+
+        Ok(())
+    }
+
+    /// Check the proof by transaction output by comparing expected and got `script_pubkey`.
+    fn checked_check_by_output(&self, txout: &TxOut) -> Result<(), Self::Error> {
+        if txout.script_pubkey != self.to_script_pubkey()? {
+            return Err(TimelockPixelProofError::ScriptPubKeyMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+impl TimelockPixelProof {
+    pub fn new(pixel: impl Into<Pixel>, inner_key: secp256k1::PublicKey, lock_height: u32) -> Self {
+        Self {
+            pixel: pixel.into(),
+            inner_key,
+            lock_height,
+        }
+    }
+
+    /// Tweak the owner's key and create the redeem script from it and the lock height.
+    pub fn to_redeem_script(&self) -> Result<ScriptBuf, TimelockPixelProofError> {
+        if self.lock_height >= LOCK_TIME_THRESHOLD {
+            return Err(TimelockPixelProofError::LockHeightIsTimestamp(
+                self.lock_height,
+            ));
+        }
+
+        let pixel_key = PixelKey::new(self.pixel, &self.inner_key)?;
+
+        Ok(TimelockScript::new(self.lock_height, *pixel_key).to_script())
+    }
+
+    pub fn to_script_pubkey(&self) -> Result<ScriptBuf, TimelockPixelProofError> {
+        self.to_redeem_script().map(|script| script.to_v0_p2wsh())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::ecdsa::Signature;
+    use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use bitcoin::{Sequence, Witness};
+
+    use super::witness::TimelockWitnessStack;
+    use super::*;
+    use crate::Chroma;
+
+    const LOCK_HEIGHT: u32 = 850_000;
+
+    fn proof() -> TimelockPixelProof {
+        let ctx = Secp256k1::new();
+        let secret = SecretKey::from_slice(&[1; 32]).unwrap();
+        let chroma = Chroma::from(secret.public_key(&ctx));
+
+        TimelockPixelProof::new(
+            Pixel::new(100u128, chroma),
+            secret.public_key(&ctx),
+            LOCK_HEIGHT,
+        )
+    }
+
+    fn spending_input(proof: &TimelockPixelProof, sequence: Sequence) -> TxIn {
+        let ctx = Secp256k1::new();
+        let secret = SecretKey::from_slice(&[2; 32]).unwrap();
+        let signature = ctx.sign_ecdsa(&Message::from_slice(&[1; 32]).unwrap(), &secret);
+
+        let witness = TimelockWitness::new(
+            TimelockWitnessStack::new(Signature::sighash_all(signature)),
+            proof.to_redeem_script().unwrap(),
+        );
+
+        TxIn {
+            sequence,
+            witness: witness.into_witness(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_by_output() {
+        let proof = proof();
+        let txout = TxOut {
+            value: 1000,
+            script_pubkey: proof.to_script_pubkey().unwrap(),
+        };
+
+        assert!(proof.checked_check_by_output(&txout).is_ok());
+
+        let other = TimelockPixelProof::new(proof.pixel, proof.inner_key, LOCK_HEIGHT + 1);
+        assert!(matches!(
+            other.checked_check_by_output(&txout),
+            Err(TimelockPixelProofError::ScriptPubKeyMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_check_by_input() {
+        let proof = proof();
+
+        let txin = spending_input(&proof, Sequence::ENABLE_LOCKTIME_NO_RBF);
+        assert!(proof.checked_check_by_input(&txin).is_ok());
+
+        let txin = spending_input(&proof, Sequence::MAX);
+        assert!(matches!(
+            proof.checked_check_by_input(&txin),
+            Err(TimelockPixelProofError::LockTimeDisabled)
+        ));
+
+        let txin = TxIn {
+            witness: Witness::new(),
+            ..spending_input(&proof, Sequence::ENABLE_LOCKTIME_NO_RBF)
+        };
+        assert!(proof.checked_check_by_input(&txin).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_lock_is_rejected() {
+        let mut proof = proof();
+        proof.lock_height = LOCK_TIME_THRESHOLD;
+
+        assert!(matches!(
+            proof.to_script_pubkey(),
+            Err(TimelockPixelProofError::LockHeightIsTimestamp(
+                LOCK_TIME_THRESHOLD
+            ))
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+use bitcoin::{
+    blockdata::{
+        opcodes::all::{OP_CHECKSIG, OP_CLTV, OP_DROP},
+        script::Builder,
+    },
+    secp256k1::PublicKey,
+    ScriptBuf,
+};
+
+/// Script of the output that can't be spent before the block height.
+///
+/// ```text
+/// <lock_height>
+/// OP_CHECKLOCKTIMEVERIFY
+/// OP_DROP
+/// <pubkey>
+/// OP_CHECKSIG
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TimelockScript {
+    /// Height of the block from which the output can be spent.
+    pub lock_height: u32,
+
+    /// Public key of the owner.
+    ///
+    /// By rules of the YUV protocol, this key is always tweaked by pixel in
+    /// script.
+    pub pubkey: PublicKey,
+}
+
+impl TimelockScript {
+    pub fn new(lock_height: u32, pubkey: PublicKey) -> Self {
+        Self {
+            lock_height,
+            pubkey,
+        }
+    }
+
+    pub fn to_script(&self) -> ScriptBuf {
+        Builder::new()
+            .push_int(self.lock_height as i64)
+            .push_opcode(OP_CLTV)
+            .push_opcode(OP_DROP)
+            .push_slice(self.pubkey.serialize())
+            .push_opcode(OP_CHECKSIG)
+            .into_script()
+    }
+}
+
+impl From<&TimelockScript> for ScriptBuf {
+    fn from(value: &TimelockScript) -> Self {
+        value.to_script()
+    }
+}
+
+impl From<TimelockScript> for ScriptBuf {
+    fn from(value: TimelockScript) -> Self {
+        value.to_script()
+    }
+}
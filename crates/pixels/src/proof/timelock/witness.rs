@@ -0,0 +1,47 @@
+use alloc::{format, vec, vec::Vec};
+use bitcoin::ecdsa::Signature as EcdsaSig;
+
+use crate::alloc::string::ToString;
+use crate::proof::p2wsh::{
+    errors::P2WSHWitnessParseError,
+    witness::{FromWitnessStack, IntoWitnessStack, P2WSHWitness},
+};
+
+pub type TimelockWitness = P2WSHWitness<TimelockWitnessStack>;
+
+/// Data that spends the timelocked output: the signature of the owner.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TimelockWitnessStack {
+    /// Signature created by the tweaked key of the owner.
+    pub signature: EcdsaSig,
+}
+
+impl TimelockWitnessStack {
+    pub fn new(signature: EcdsaSig) -> Self {
+        Self { signature }
+    }
+}
+
+impl FromWitnessStack for TimelockWitnessStack {
+    fn from_witness_stack(stack: &[Vec<u8>]) -> Result<Self, P2WSHWitnessParseError> {
+        use P2WSHWitnessParseError as Error;
+
+        let [signature] = stack else {
+            return Err(Error::Custom(format!(
+                "invalid witness length should be 1, got {}",
+                stack.len()
+            )));
+        };
+
+        let signature = EcdsaSig::from_slice(signature)
+            .map_err(|_| Error::Custom("invalid signature in witness".to_string()))?;
+
+        Ok(Self { signature })
+    }
+}
+
+impl IntoWitnessStack for TimelockWitnessStack {
+    fn into_witness_stack(self) -> Vec<Vec<u8>> {
+        vec![self.signature.serialize().to_vec()]
+    }
+}
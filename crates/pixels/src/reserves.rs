@@ -0,0 +1,247 @@
+//! Proof of reserves: the attestation that the owner of the YUV UTXOs can spend them, made
+//! without moving the funds.
+//!
+//! Each output is attested with the ECDSA signature of the challenge message, made with the
+//! private key tweaked by the output's pixel, so it's verified with the same [`PixelKey`] that
+//! locks the output. The message commits to the challenge, the outpoint and the pixel, so the
+//! signatures can't be reused for the other challenges or outputs.
+//!
+//! The signatures prove only the ownership of the keys, the verifier should also check that the
+//! outputs exist and are unspent, e.g. with the YUV node.
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use bitcoin::hashes::{sha256::Hash as Sha256Hash, Hash, HashEngine};
+use bitcoin::secp256k1::{self, ecdsa::Signature, Message, Secp256k1, Signing, Verification};
+use bitcoin::OutPoint;
+
+use crate::{Chroma, Pixel, PixelHash, PixelKey, PixelKeyError, PixelPrivateKey};
+
+/// Tag of the challenge message, so it can't be confused with the transaction's sighash.
+const MESSAGE_TAG: &[u8] = b"yuv-proof-of-reserves";
+
+/// Signed attestation of the YUV UTXOs of the [`Chroma`] owned by the custodian.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProofOfReserves {
+    /// Chroma of the tokens in all the outputs.
+    pub chroma: Chroma,
+    /// Message chosen by the verifier, e.g. the name of the auditor and the date, so the proof
+    /// can't be made in advance.
+    pub challenge: String,
+    /// Attested outputs.
+    pub outputs: Vec<ReserveOutput>,
+}
+
+/// Output attested in the [`ProofOfReserves`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReserveOutput {
+    pub outpoint: OutPoint,
+    /// Pixel of the output.
+    pub pixel: Pixel,
+    /// Key of the owner, which is tweaked by the pixel into the key of the output.
+    pub inner_key: secp256k1::PublicKey,
+    /// Signature of the challenge message by the key of the output.
+    pub signature: Signature,
+}
+
+impl ReserveOutput {
+    /// Signs the `challenge` for the output with the `pixel` owned by the `inner_key`.
+    pub fn sign<C: Signing>(
+        ctx: &Secp256k1<C>,
+        challenge: &str,
+        outpoint: OutPoint,
+        pixel: Pixel,
+        inner_key: &secp256k1::SecretKey,
+    ) -> Result<Self, PixelKeyError> {
+        let pixel_private_key = PixelPrivateKey::new_with_ctx(pixel, inner_key, ctx)?;
+        let message = challenge_message(challenge, &outpoint, &pixel);
+
+        Ok(Self {
+            outpoint,
+            pixel,
+            inner_key: inner_key.public_key(ctx),
+            signature: ctx.sign_ecdsa(&message, &pixel_private_key),
+        })
+    }
+
+    /// Key that locks the output.
+    pub fn pixel_key<C: Signing + Verification>(
+        &self,
+        ctx: &Secp256k1<C>,
+    ) -> Result<PixelKey, PixelKeyError> {
+        PixelKey::new_with_ctx(self.pixel, &self.inner_key, ctx)
+    }
+}
+
+/// Verifies the signatures of the [`ProofOfReserves`], and returns the total amount of the
+/// attested tokens.
+pub fn verify_proof_of_reserves(proof: &ProofOfReserves) -> Result<u128, ProofOfReservesError> {
+    let ctx = Secp256k1::new();
+
+    let mut outpoints = BTreeSet::new();
+    let mut total: u128 = 0;
+
+    for output in &proof.outputs {
+        if !outpoints.insert(output.outpoint) {
+            return Err(ProofOfReservesError::DuplicateOutput(output.outpoint));
+        }
+
+        if output.pixel.chroma != proof.chroma {
+            return Err(ProofOfReservesError::ChromaMismatch(output.outpoint));
+        }
+
+        let pixel_key = output.pixel_key(&ctx)?;
+        let message = challenge_message(&proof.challenge, &output.outpoint, &output.pixel);
+
+        ctx.verify_ecdsa(&message, &output.signature, &pixel_key)
+            .map_err(|_| ProofOfReservesError::InvalidSignature(output.outpoint))?;
+
+        total = total
+            .checked_add(output.pixel.luma.amount)
+            .ok_or(ProofOfReservesError::AmountOverflow)?;
+    }
+
+    Ok(total)
+}
+
+/// Calculates: `sha256(tag || sha256(challenge) || txid || vout || PXH)`.
+fn challenge_message(challenge: &str, outpoint: &OutPoint, pixel: &Pixel) -> Message {
+    let mut hash_engine = Sha256Hash::engine();
+
+    hash_engine.input(MESSAGE_TAG);
+    hash_engine.input(Sha256Hash::hash(challenge.as_bytes()).as_byte_array());
+    hash_engine.input(outpoint.txid.as_byte_array());
+    hash_engine.input(&outpoint.vout.to_le_bytes());
+    hash_engine.input(PixelHash::from(pixel).as_byte_array());
+
+    let hash = Sha256Hash::from_engine(hash_engine);
+
+    Message::from_slice(hash.as_byte_array()).expect("hash should be 32 bytes")
+}
+
+#[derive(Debug)]
+pub enum ProofOfReservesError {
+    /// The output is attested more than once.
+    DuplicateOutput(OutPoint),
+
+    /// Chroma of the output's pixel is not the one of the proof.
+    ChromaMismatch(OutPoint),
+
+    /// Signature of the output doesn't match its key.
+    InvalidSignature(OutPoint),
+
+    /// Failed to derive the key of the output.
+    PixelKey(PixelKeyError),
+
+    /// Total amount of the outputs overflows.
+    AmountOverflow,
+}
+
+impl fmt::Display for ProofOfReservesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateOutput(outpoint) => write!(f, "Output {} is duplicated", outpoint),
+            Self::ChromaMismatch(outpoint) => {
+                write!(f, "Chroma of the output {} doesn't match", outpoint)
+            }
+            Self::InvalidSignature(outpoint) => {
+                write!(f, "Invalid signature of the output {}", outpoint)
+            }
+            Self::PixelKey(e) => write!(f, "Pixel key error: {}", e),
+            Self::AmountOverflow => write!(f, "Total amount overflows"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for ProofOfReservesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::PixelKey(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<PixelKeyError> for ProofOfReservesError {
+    fn from(err: PixelKeyError) -> Self {
+        Self::PixelKey(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+    use core::str::FromStr;
+
+    use bitcoin::secp256k1::SecretKey;
+    use bitcoin::Txid;
+
+    use super::*;
+
+    const CHROMA: &str = "93bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+    const SECRET: &str = "f9e17ee5b837fece0695f9782253604586ab1daf42ecf2762573243c7a6979f4";
+    const CHALLENGE: &str = "Audit of 2024-06-30";
+
+    fn proof() -> ProofOfReserves {
+        let ctx = Secp256k1::new();
+        let chroma = Chroma::from_str(CHROMA).unwrap();
+        let secret = SecretKey::from_str(SECRET).unwrap();
+
+        let outputs = [(0, 100), (1, 250)]
+            .into_iter()
+            .map(|(vout, amount)| {
+                let outpoint = OutPoint::new(Txid::all_zeros(), vout);
+                ReserveOutput::sign(
+                    &ctx,
+                    CHALLENGE,
+                    outpoint,
+                    Pixel::new(amount, chroma),
+                    &secret,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        ProofOfReserves {
+            chroma,
+            challenge: CHALLENGE.to_string(),
+            outputs,
+        }
+    }
+
+    #[test]
+    fn test_verify_proof_of_reserves() {
+        assert_eq!(verify_proof_of_reserves(&proof()).unwrap(), 350);
+    }
+
+    #[test]
+    fn test_verify_invalid_proof_of_reserves() {
+        let mut other_challenge = proof();
+        other_challenge.challenge = "Audit of 2024-03-31".to_string();
+        assert!(matches!(
+            verify_proof_of_reserves(&other_challenge),
+            Err(ProofOfReservesError::InvalidSignature(_))
+        ));
+
+        // The signature is bound to the amount of the output.
+        let mut other_amount = proof();
+        other_amount.outputs[0].pixel.luma.amount = 1000;
+        assert!(matches!(
+            verify_proof_of_reserves(&other_amount),
+            Err(ProofOfReservesError::InvalidSignature(_))
+        ));
+
+        let mut duplicated = proof();
+        duplicated.outputs = vec![duplicated.outputs[0].clone(); 2];
+        assert!(matches!(
+            verify_proof_of_reserves(&duplicated),
+            Err(ProofOfReservesError::DuplicateOutput(_))
+        ));
+    }
+}
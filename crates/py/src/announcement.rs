@@ -0,0 +1,16 @@
+use bitcoin::ScriptBuf;
+use pyo3::prelude::*;
+use yuv_types::announcements::announcement_from_script;
+
+use crate::convert::{to_py, value_error};
+
+/// Parses the announcement from the hex of the `OP_RETURN` script, and returns it as a dict, in
+/// the same format as the YUV node returns it.
+#[pyfunction]
+pub(crate) fn parse_announcement(py: Python<'_>, script: &str) -> PyResult<PyObject> {
+    let script = hex::decode(script).map_err(value_error)?;
+    let announcement =
+        announcement_from_script(&ScriptBuf::from_bytes(script)).map_err(value_error)?;
+
+    to_py(py, &announcement)
+}
@@ -0,0 +1,169 @@
+use jsonrpsee::core::{client::ClientT, params::ArrayParams};
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use pyo3::prelude::*;
+use pyo3_asyncio::tokio::{future_into_py, get_runtime};
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+
+use crate::convert::{from_py, parse_chroma, parse_txid, rpc_error, to_py};
+use crate::transaction::PyYuvTransaction;
+
+/// Async client of the YUV node's RPC API. The methods return the awaitables, which raise
+/// `RpcError` if the node returns the error.
+#[pyclass(module = "yuv_py")]
+pub struct RpcClient {
+    client: HttpClient,
+}
+
+#[pymethods]
+impl RpcClient {
+    #[new]
+    fn new(url: &str) -> PyResult<Self> {
+        // The client's connections are driven by the runtime the awaitables are run on.
+        let _guard = get_runtime().enter();
+        let client = HttpClientBuilder::default().build(url).map_err(rpc_error)?;
+
+        Ok(Self { client })
+    }
+
+    /// Calls the RPC `method` with the list of the `params`, e.g. the one without the typed
+    /// wrapper, and returns the result as `json.loads` does.
+    #[pyo3(signature = (method, params = None))]
+    fn call<'py>(
+        &self,
+        py: Python<'py>,
+        method: String,
+        params: Option<&PyAny>,
+    ) -> PyResult<&'py PyAny> {
+        let params: Vec<serde_json::Value> = match params {
+            Some(params) => from_py(py, params)?,
+            None => Vec::new(),
+        };
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            let mut rpc_params = ArrayParams::new();
+            for param in params {
+                rpc_params.insert(param).map_err(rpc_error)?;
+            }
+
+            let result: serde_json::Value = client
+                .request(&method, rpc_params)
+                .await
+                .map_err(rpc_error)?;
+
+            Python::with_gil(|py| to_py(py, &result))
+        })
+    }
+
+    /// Returns the status of the transaction, and the transaction itself if the node has it.
+    fn get_yuv_transaction<'py>(&self, py: Python<'py>, txid: &str) -> PyResult<&'py PyAny> {
+        let txid = parse_txid(txid)?;
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            let response = client.get_yuv_transaction(txid).await.map_err(rpc_error)?;
+
+            Python::with_gil(|py| {
+                let status = to_py(py, &response.status)?;
+                let tx = response.data.map(|tx| PyYuvTransaction(tx.into()));
+
+                Ok((status, tx).into_py(py))
+            })
+        })
+    }
+
+    /// Returns the page of the attached transactions, starting from `0`.
+    fn list_yuv_transactions<'py>(&self, py: Python<'py>, page: u64) -> PyResult<&'py PyAny> {
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            let txs = client
+                .list_yuv_transactions(page)
+                .await
+                .map_err(rpc_error)?;
+
+            Ok(txs
+                .into_iter()
+                .map(|tx| PyYuvTransaction(tx.into()))
+                .collect::<Vec<_>>())
+        })
+    }
+
+    /// Provides the proofs of the transaction to the node without broadcasting it.
+    fn provide_yuv_proof<'py>(
+        &self,
+        py: Python<'py>,
+        tx: PyYuvTransaction,
+    ) -> PyResult<&'py PyAny> {
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            client.provide_yuv_proof(tx.0).await.map_err(rpc_error)
+        })
+    }
+
+    /// Provides the proofs of the transaction to the node and broadcasts it.
+    #[pyo3(signature = (tx, max_burn_amount = None))]
+    fn send_yuv_transaction<'py>(
+        &self,
+        py: Python<'py>,
+        tx: PyYuvTransaction,
+        max_burn_amount: Option<u64>,
+    ) -> PyResult<&'py PyAny> {
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            client
+                .send_yuv_tx(tx.0.hex(), max_burn_amount)
+                .await
+                .map_err(rpc_error)
+        })
+    }
+
+    fn is_yuv_txout_frozen<'py>(
+        &self,
+        py: Python<'py>,
+        txid: &str,
+        vout: u32,
+    ) -> PyResult<&'py PyAny> {
+        let txid = parse_txid(txid)?;
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            client
+                .is_yuv_txout_frozen(txid, vout)
+                .await
+                .map_err(rpc_error)
+        })
+    }
+
+    /// Returns the info about the chroma as a dict, `None` if the node doesn't know it.
+    fn get_chroma_info<'py>(&self, py: Python<'py>, chroma: &str) -> PyResult<&'py PyAny> {
+        let chroma = parse_chroma(chroma)?;
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            let info = client.get_chroma_info(chroma).await.map_err(rpc_error)?;
+
+            Python::with_gil(|py| to_py(py, &info))
+        })
+    }
+
+    /// Returns the hex encoded proof bundle of the output, `None` if the output isn't attached.
+    fn export_proof_bundle<'py>(
+        &self,
+        py: Python<'py>,
+        txid: &str,
+        vout: u32,
+    ) -> PyResult<&'py PyAny> {
+        let txid = parse_txid(txid)?;
+        let client = self.client.clone();
+
+        future_into_py(py, async move {
+            client
+                .export_proof_bundle(txid, vout)
+                .await
+                .map_err(rpc_error)
+        })
+    }
+}
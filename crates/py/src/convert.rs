@@ -0,0 +1,49 @@
+//! Conversions between the Rust values and the Python objects.
+use core::fmt;
+use core::str::FromStr;
+
+use bitcoin::Txid;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use yuv_pixels::Chroma;
+
+use crate::RpcError;
+
+/// Converts the value to the Python object, the same as `json.loads` of its JSON does.
+pub(crate) fn to_py(py: Python<'_>, value: &impl Serialize) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(value_error)?;
+
+    Ok(py.import("json")?.call_method1("loads", (json,))?.into())
+}
+
+/// Converts the Python object to the value, the same as `json.dumps` of the object does.
+pub(crate) fn from_py<T: DeserializeOwned>(py: Python<'_>, object: &PyAny) -> PyResult<T> {
+    let json: String = py
+        .import("json")?
+        .call_method1("dumps", (object,))?
+        .extract()?;
+
+    serde_json::from_str(&json).map_err(value_error)
+}
+
+pub(crate) fn parse_txid(txid: &str) -> PyResult<Txid> {
+    Txid::from_str(txid).map_err(value_error)
+}
+
+/// Parses the chroma from the hex of the x-only key or the Taproot address.
+pub(crate) fn parse_chroma(chroma: &str) -> PyResult<Chroma> {
+    if let Ok(chroma) = Chroma::from_address(chroma) {
+        return Ok(chroma);
+    }
+
+    Chroma::from_str(chroma).map_err(value_error)
+}
+
+pub(crate) fn value_error(err: impl fmt::Display) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+pub(crate) fn rpc_error(err: impl fmt::Display) -> PyErr {
+    RpcError::new_err(err.to_string())
+}
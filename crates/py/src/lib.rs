@@ -0,0 +1,27 @@
+#![doc = include_str!("../README.md")]
+
+use pyo3::prelude::*;
+
+mod announcement;
+mod client;
+mod convert;
+mod proof;
+mod transaction;
+
+pyo3::create_exception!(
+    yuv_py,
+    RpcError,
+    pyo3::exceptions::PyException,
+    "Error returned by the YUV node, or the failure to reach it."
+);
+
+#[pymodule]
+fn yuv_py(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<transaction::PyYuvTransaction>()?;
+    m.add_class::<proof::PyPixelProof>()?;
+    m.add_class::<client::RpcClient>()?;
+    m.add_function(wrap_pyfunction!(announcement::parse_announcement, m)?)?;
+    m.add("RpcError", py.get_type::<RpcError>())?;
+
+    Ok(())
+}
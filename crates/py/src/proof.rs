@@ -0,0 +1,58 @@
+use bitcoin::consensus::encode::{deserialize, serialize_hex};
+use pyo3::prelude::*;
+use yuv_pixels::PixelProof;
+
+use crate::convert::{to_py, value_error};
+
+/// Proof that the output of the Bitcoin transaction holds the pixel, or that the input spends it.
+#[pyclass(name = "PixelProof", module = "yuv_py")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyPixelProof(pub(crate) PixelProof);
+
+#[pymethods]
+impl PyPixelProof {
+    /// Parses the consensus encoded proof.
+    #[staticmethod]
+    fn from_hex(hex: &str) -> PyResult<Self> {
+        let bytes = hex::decode(hex).map_err(value_error)?;
+
+        Ok(Self(deserialize(&bytes).map_err(value_error)?))
+    }
+
+    fn hex(&self) -> String {
+        serialize_hex(&self.0)
+    }
+
+    /// Parses the proof from the JSON returned by the node.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Ok(Self(serde_json::from_str(json).map_err(value_error)?))
+    }
+
+    fn json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.0).map_err(value_error)
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.0)
+    }
+
+    /// Hex of the issuer's x-only key, `None` if the output has only the satoshis.
+    #[getter]
+    fn chroma(&self) -> Option<String> {
+        (!self.0.is_empty_pixelproof()).then(|| self.0.pixel().chroma.to_string())
+    }
+
+    #[getter]
+    fn amount(&self) -> u128 {
+        self.0.amount()
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("PixelProof({})", self.json()?))
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use bitcoin::consensus::encode::serialize_hex;
+use pyo3::prelude::*;
+use yuv_types::{ProofMap, YuvTransaction, YuvTxType};
+
+use crate::convert::{to_py, value_error};
+use crate::proof::PyPixelProof;
+
+/// Bitcoin transaction with the proofs of its inputs and outputs.
+#[pyclass(name = "YuvTransaction", module = "yuv_py")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyYuvTransaction(pub(crate) YuvTransaction);
+
+#[pymethods]
+impl PyYuvTransaction {
+    /// Parses the consensus encoded transaction, e.g. returned by `getyuvtransaction`.
+    #[staticmethod]
+    fn from_hex(hex: &str) -> PyResult<Self> {
+        Ok(Self(
+            YuvTransaction::from_hex(hex.to_string()).map_err(value_error)?,
+        ))
+    }
+
+    fn hex(&self) -> String {
+        self.0.hex()
+    }
+
+    /// Parses the transaction from the JSON returned by the node.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Ok(Self(serde_json::from_str(json).map_err(value_error)?))
+    }
+
+    fn json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.0).map_err(value_error)
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.0)
+    }
+
+    #[getter]
+    fn txid(&self) -> String {
+        self.0.bitcoin_tx.txid().to_string()
+    }
+
+    /// `issue`, `transfer` or `announcement`.
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match self.0.tx_type {
+            YuvTxType::Issue { .. } => "issue",
+            YuvTxType::Transfer { .. } => "transfer",
+            YuvTxType::Announcement(_) => "announcement",
+        }
+    }
+
+    /// Hex of the Bitcoin transaction without the proofs.
+    #[getter]
+    fn bitcoin_tx(&self) -> String {
+        serialize_hex(&self.0.bitcoin_tx)
+    }
+
+    /// Proofs of the inputs by their indexes, empty if the transaction isn't a transfer.
+    fn input_proofs(&self) -> BTreeMap<u32, PyPixelProof> {
+        proofs(self.0.tx_type.input_proofs())
+    }
+
+    /// Proofs of the outputs by their indexes.
+    fn output_proofs(&self) -> BTreeMap<u32, PyPixelProof> {
+        proofs(self.0.tx_type.output_proofs())
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn __repr__(&self) -> String {
+        format!("YuvTransaction({}, {})", self.kind(), self.txid())
+    }
+}
+
+fn proofs(proofs: Option<&ProofMap>) -> BTreeMap<u32, PyPixelProof> {
+    proofs
+        .into_iter()
+        .flatten()
+        .map(|(index, proof)| (*index, PyPixelProof(proof.clone())))
+        .collect()
+}
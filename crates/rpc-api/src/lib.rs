@@ -1 +1,4 @@
+#[cfg(feature = "client")]
+pub mod negotiation;
+pub mod subscriptions;
 pub mod transactions;
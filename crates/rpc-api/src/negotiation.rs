@@ -0,0 +1,76 @@
+//! Negotiation of the RPC API with the node, so the clients can degrade gracefully against the
+//! older nodes.
+//!
+//! The nodes released before the capabilities discovery don't know
+//! [`getcapabilities`](crate::transactions::YuvTransactionsRpcClient::get_capabilities), and
+//! are treated as the ones with the API version `0`, without the optional features and with the
+//! unknown limits.
+
+use jsonrpsee::core::Error;
+use jsonrpsee::types::error::METHOD_NOT_FOUND_CODE;
+
+use crate::transactions::{
+    GetCapabilitiesResponse, RpcFeatures, RpcLimits, YuvTransactionsRpcClient,
+};
+
+/// Version of the RPC API of the nodes released before the capabilities discovery.
+pub const LEGACY_API_VERSION: u32 = 0;
+
+/// Capabilities of the node the client talks to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeCapabilities {
+    /// Version of the node's RPC API.
+    pub api_version: u32,
+    /// Optional features enabled on the node.
+    pub features: RpcFeatures,
+    /// Limits of the requests to the node, `None` if the node doesn't report them.
+    pub limits: Option<RpcLimits>,
+}
+
+impl NodeCapabilities {
+    /// Capabilities of the nodes released before the capabilities discovery.
+    pub fn legacy() -> Self {
+        Self {
+            api_version: LEGACY_API_VERSION,
+            features: RpcFeatures::default(),
+            limits: None,
+        }
+    }
+
+    /// Check whether the node's RPC API is at least of the `version`.
+    pub fn supports_api(&self, version: u32) -> bool {
+        self.api_version >= version
+    }
+
+    /// Max number of the items in one request, `default` if the node doesn't report it.
+    pub fn max_items_per_request(&self, default: usize) -> usize {
+        self.limits
+            .as_ref()
+            .map_or(default, |limits| limits.max_items_per_request)
+    }
+}
+
+impl From<GetCapabilitiesResponse> for NodeCapabilities {
+    fn from(response: GetCapabilitiesResponse) -> Self {
+        Self {
+            api_version: response.api_version,
+            features: response.features,
+            limits: Some(response.limits),
+        }
+    }
+}
+
+/// Discovers the capabilities of the node. The nodes that don't know the discovery get the
+/// [legacy](NodeCapabilities::legacy) capabilities, while the rest of the errors are returned.
+pub async fn negotiate<C>(client: &C) -> Result<NodeCapabilities, Error>
+where
+    C: YuvTransactionsRpcClient + Sync,
+{
+    match client.get_capabilities().await {
+        Ok(response) => Ok(response.into()),
+        Err(Error::Call(err)) if err.code() == METHOD_NOT_FOUND_CODE => {
+            Ok(NodeCapabilities::legacy())
+        }
+        Err(err) => Err(err),
+    }
+}
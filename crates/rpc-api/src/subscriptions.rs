@@ -0,0 +1,21 @@
+use crate::transactions::{YuvTransactionResponse, YuvTransactionStatus};
+
+#[cfg(any(feature = "client", feature = "server"))]
+mod rpc;
+#[cfg(any(feature = "client", feature = "server"))]
+pub use self::rpc::*;
+
+/// Notification of the [`subscribeUnconfirmedTransfers`] subscription about the transfer to the
+/// watched keys.
+///
+/// [`subscribeUnconfirmedTransfers`]: YuvSubscriptionsRpcServer::subscribe_unconfirmed_transfers
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct UnconfirmedTransferNotification {
+    /// Always [`YuvTransactionStatus::WaitingMined`]: the transfer has passed only the isolated
+    /// check, so it still may be rejected by the full check or never get mined.
+    pub status: YuvTransactionStatus,
+    /// Outputs of the transfer that belong to the watched keys.
+    pub vouts: Vec<u32>,
+    pub tx: YuvTransactionResponse,
+}
@@ -0,0 +1,54 @@
+use jsonrpsee::proc_macros::rpc;
+
+#[cfg(feature = "server")]
+use jsonrpsee::core::SubscriptionResult;
+
+use bitcoin::secp256k1::PublicKey;
+use yuv_types::announcements::{ChromaAnnouncement, FreezeAnnouncement};
+
+use crate::subscriptions::UnconfirmedTransferNotification;
+use crate::transactions::YuvTransactionResponse;
+
+/// RPC subscriptions to the node's state changes. Available only over WebSocket connections.
+#[cfg_attr(all(feature = "client", not(feature = "server")), rpc(client))]
+#[cfg_attr(all(feature = "server", not(feature = "client")), rpc(server))]
+#[cfg_attr(all(feature = "server", feature = "client"), rpc(server, client))]
+#[async_trait::async_trait]
+pub trait YuvSubscriptionsRpc {
+    /// Subscribe to the YUV transactions that are attached by the node.
+    #[subscription(
+        name = "subscribeAttachedTxs",
+        unsubscribe = "unsubscribeAttachedTxs",
+        item = YuvTransactionResponse
+    )]
+    async fn subscribe_attached_txs(&self) -> SubscriptionResult;
+
+    /// Subscribe to the freeze announcements that are accepted by the node.
+    #[subscription(
+        name = "subscribeFreezes",
+        unsubscribe = "unsubscribeFreezes",
+        item = FreezeAnnouncement
+    )]
+    async fn subscribe_freezes(&self) -> SubscriptionResult;
+
+    /// Subscribe to the chroma announcements that are accepted by the node.
+    #[subscription(
+        name = "subscribeChromaAnnouncements",
+        unsubscribe = "unsubscribeChromaAnnouncements",
+        item = ChromaAnnouncement
+    )]
+    async fn subscribe_chroma_announcements(&self) -> SubscriptionResult;
+
+    /// Subscribe to the transfers to the `watch_keys` right after they pass the isolated check,
+    /// without waiting for the confirmation. The notified transfers are not confirmed, and
+    /// still may be rejected.
+    #[subscription(
+        name = "subscribeUnconfirmedTransfers",
+        unsubscribe = "unsubscribeUnconfirmedTransfers",
+        item = UnconfirmedTransferNotification
+    )]
+    async fn subscribe_unconfirmed_transfers(
+        &self,
+        watch_keys: Vec<PublicKey>,
+    ) -> SubscriptionResult;
+}
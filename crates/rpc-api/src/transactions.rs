@@ -1,6 +1,12 @@
-use bitcoin::{BlockHash, Transaction, Txid};
+use bitcoin::{BlockHash, OutPoint, ScriptBuf, Transaction, Txid};
+use event_bus::ChannelMetrics;
 use serde::Deserialize;
-use yuv_storage::MempoolStatus;
+use yuv_pixels::Chroma;
+use yuv_scheduler::JobStatus;
+use yuv_storage::{MempoolStatus, MempoolTxEntry, TransferHistoryEntry, TxRejection};
+use yuv_types::activation::ActivationStatus;
+use yuv_types::announcements::{ChromaAnnouncement, ChromaInfo};
+use yuv_types::supply::{InclusionProof, SupplyNode};
 use yuv_types::{YuvTransaction, YuvTxType};
 
 #[cfg(any(feature = "client", feature = "server"))]
@@ -32,6 +38,10 @@ pub enum YuvTransactionStatus {
     Attached,
     /// TODO: This status is used for `get_raw_yuv_transaction` only and will soon be removed.
     Pending,
+    /// Transaction is found, it's raw data is provided, it hasn't appeared in the blockchain yet,
+    /// but it's low-value transfer which is accepted by the node provisionally. It will be fully
+    /// checked once it gets enough confirmations.
+    ProvisionallyAttached,
 }
 
 #[allow(deprecated)]
@@ -43,6 +53,7 @@ impl From<MempoolStatus> for YuvTransactionStatus {
             MempoolStatus::Mined => Self::Mined,
             MempoolStatus::Attaching => Self::Attaching,
             MempoolStatus::Pending => Self::Pending,
+            MempoolStatus::ProvisionallyAttached => Self::ProvisionallyAttached,
         }
     }
 }
@@ -107,6 +118,418 @@ where
     }
 }
 
+/// Item of the response for [`getyuvtransactionsbyids`] RPC method. Contains the status of the
+/// requested transaction and its hex encoded proofs if the transaction is known to the node.
+///
+/// [`getyuvtransactionsbyids`]: YuvTransactionsRpcServer::get_yuv_transactions_by_ids
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct YuvTransactionProofsResponse {
+    pub txid: Txid,
+    pub status: YuvTransactionStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[serde(
+        serialize_with = "opt_tx_type_to_hex",
+        deserialize_with = "opt_hex_to_tx_type"
+    )]
+    pub tx_type: Option<YuvTxType>,
+}
+
+impl YuvTransactionProofsResponse {
+    pub fn new(txid: Txid, status: YuvTransactionStatus, tx_type: Option<YuvTxType>) -> Self {
+        Self {
+            txid,
+            status,
+            tx_type,
+        }
+    }
+}
+
+pub fn opt_tx_type_to_hex<S>(tx_type: &Option<YuvTxType>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match tx_type {
+        Some(tx_type) => serializer.serialize_str(&tx_type.hex()),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn opt_hex_to_tx_type<'de, D>(deserializer: D) -> Result<Option<YuvTxType>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|hex| YuvTxType::from_hex(hex).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Response for [`getactivationstatus`](YuvTransactionsRpcServer::get_activation_status) RPC
+/// method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GetActivationStatusResponse {
+    /// Height of the last block indexed by the node.
+    pub height: usize,
+    /// Activation statuses of the protocol features at the `height`.
+    pub features: Vec<ActivationStatus>,
+}
+
+/// Response for [`getnodestatus`](YuvTransactionsRpcServer::get_node_status) RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GetNodeStatusResponse {
+    /// Statuses of the node's scheduled maintenance jobs.
+    pub jobs: Vec<JobStatus>,
+    /// Metrics of the node's internal event channels.
+    pub channels: Vec<ChannelMetrics>,
+    /// Number of the calls rejected by the rate limits of the RPC server.
+    #[serde(default)]
+    pub rate_limits: Vec<RateLimitMetrics>,
+    /// Space saved by the proofs deduplication, `None` if the proofs aren't deduplicated.
+    #[serde(default)]
+    pub proofs_dedup: Option<ProofsDedupMetrics>,
+}
+
+/// Version of the RPC API, increased when the existing methods change incompatibly. The methods
+/// and the features added later are discovered with the capabilities instead.
+pub const RPC_API_VERSION: u32 = 1;
+
+/// Response for [`getapiversion`](YuvTransactionsRpcServer::get_api_version) RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GetApiVersionResponse {
+    /// Version of the node's RPC API, see [`RPC_API_VERSION`].
+    pub api_version: u32,
+    /// Version of the node's software, e.g. `0.4.0`.
+    pub node_version: String,
+}
+
+/// Response for [`getcapabilities`](YuvTransactionsRpcServer::get_capabilities) RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GetCapabilitiesResponse {
+    /// Version of the node's RPC API, see [`RPC_API_VERSION`].
+    pub api_version: u32,
+    /// Optional features enabled on the node.
+    pub features: RpcFeatures,
+    /// Limits of the requests to the node.
+    pub limits: RpcLimits,
+}
+
+/// Optional features of the node. The features unknown to the client are ignored, and the ones
+/// unknown to the node are disabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RpcFeatures {
+    /// Node is built with the support of the bulletproof (confidential) pixel proofs.
+    pub bulletproof: bool,
+    /// Outputs with the pixel proofs of the Taproot keys, i.e. the MuSig ones, are active at the
+    /// height of the last indexed block.
+    pub p2tr_proofs: bool,
+    /// Subscriptions to the node's events are served over WebSocket.
+    pub subscriptions: bool,
+    /// Unspent outputs are indexed by the keys of their owners, see
+    /// [`listutxosbypubkey`](YuvTransactionsRpcServer::list_utxos_by_pubkey).
+    pub address_index: bool,
+    /// Transfer history of the owners is recorded, see
+    /// [`listtransfers`](YuvTransactionsRpcServer::list_transfers).
+    pub history_index: bool,
+    /// Snapshots of the storage can be created, see
+    /// [`createsnapshot`](YuvTransactionsRpcServer::create_snapshot).
+    pub snapshots: bool,
+}
+
+/// Limits of the requests to the node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct RpcLimits {
+    /// Max number of the items, e.g. the transactions, in one request.
+    pub max_items_per_request: usize,
+    /// Max number of the transactions requested in one batch.
+    pub max_batch_size: usize,
+    /// Max size of the JSON serialized response in bytes.
+    pub max_response_size: usize,
+    /// Max number of the parents sent along with the transaction.
+    pub max_bundle_parents: usize,
+    /// Max size of the transaction with its parents in bytes.
+    pub max_bundle_size: usize,
+}
+
+/// Number of the calls of the method rejected by the rate limits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct RateLimitMetrics {
+    /// Name of the method, `*` for the limit of all the requests.
+    pub method: String,
+    /// Number of the rejected calls.
+    pub rejected: u64,
+}
+
+/// Sizes of the proofs in the storage with the deduplicated proofs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ProofsDedupMetrics {
+    /// Total size of the proofs in bytes as if they were stored without deduplication.
+    pub logical_bytes: u64,
+    /// Size of the proofs in bytes that are actually stored.
+    pub stored_bytes: u64,
+    /// Number of bytes saved by the deduplication.
+    pub saved_bytes: u64,
+}
+
+/// Response for [`createsnapshot`](YuvTransactionsRpcServer::create_snapshot) RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct CreateSnapshotResponse {
+    /// Directory of the created snapshot.
+    pub dir: String,
+    /// Height of the last block indexed at the moment of the snapshot.
+    pub block_height: usize,
+    /// Hash of the last block indexed at the moment of the snapshot.
+    pub block_hash: BlockHash,
+    /// Unix timestamp of the snapshot in seconds.
+    pub created_at: u64,
+}
+
+/// Response for [`getyuvtxoutfreeze`](YuvTransactionsRpcServer::get_yuv_txout_freeze) RPC
+/// method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct YuvTxOutFreezeResponse {
+    /// Id of the transaction that froze the output.
+    pub freeze_txid: Txid,
+    pub chroma: Chroma,
+    /// Frozen amount of tokens if the output is frozen partially. Only the rest of the tokens can
+    /// be spent, and the frozen amount must be kept in the output of the same owner.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub frozen_amount: Option<u128>,
+    /// Height of the block starting from which the output is unfrozen, if the freeze expires.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expiry_height: Option<u32>,
+}
+
+/// Response for [`getchromainfo`](YuvTransactionsRpcServer::get_chroma_info) RPC method. Has
+/// the same fields as the [`ChromaInfo`] along with the remaining supply, so the clients that
+/// expect the [`ChromaInfo`] can still parse it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GetChromaInfoResponse {
+    /// Chroma announcement of the token, `None` if the token wasn't announced.
+    pub announcement: Option<ChromaAnnouncement>,
+    /// Total amount of the issued tokens.
+    pub total_supply: u128,
+    /// Script of the current owner of the chroma, `None` if the ownership wasn't transferred.
+    pub owner: Option<ScriptBuf>,
+    /// Total amount of the tokens burnt with the burn announcements.
+    #[serde(default)]
+    pub total_burned: u128,
+    /// Number of the update chroma announcements applied to the `announcement`.
+    #[serde(default)]
+    pub announcement_version: u32,
+    /// Amount of the tokens that can still be issued, `None` if the supply isn't limited. See
+    /// [`ChromaInfo::remaining_supply`].
+    #[serde(default)]
+    pub remaining_supply: Option<u128>,
+}
+
+impl From<ChromaInfo> for GetChromaInfoResponse {
+    fn from(info: ChromaInfo) -> Self {
+        Self {
+            remaining_supply: info.remaining_supply(),
+            announcement: info.announcement,
+            total_supply: info.total_supply,
+            owner: info.owner,
+            total_burned: info.total_burned,
+            announcement_version: info.announcement_version,
+        }
+    }
+}
+
+/// Response for [`getsupplycommitment`](YuvTransactionsRpcServer::get_supply_commitment) RPC
+/// method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GetSupplyCommitmentResponse {
+    pub chroma: Chroma,
+    /// Root of the chroma's supply tree. Its sum is the circulating supply.
+    pub root: SupplyNode,
+    /// Number of the unspent outputs in the tree.
+    pub utxos_number: usize,
+}
+
+/// Response for [`getinclusionproof`](YuvTransactionsRpcServer::get_inclusion_proof) RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GetInclusionProofResponse {
+    pub chroma: Chroma,
+    /// Proof of the inclusion of the output into the chroma's supply tree.
+    pub proof: InclusionProof,
+    /// Root of the chroma's supply tree the proof leads to.
+    pub root: SupplyNode,
+}
+
+/// Item of the response for [`listutxosbypubkey`] RPC method.
+///
+/// [`listutxosbypubkey`]: YuvTransactionsRpcServer::list_utxos_by_pubkey
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct PubkeyUtxoResponse {
+    pub outpoint: OutPoint,
+    pub chroma: Chroma,
+    pub amount: u128,
+}
+
+/// Response for [`estimateyuvtransfer`](YuvTransactionsRpcServer::estimate_yuv_transfer) RPC
+/// method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct EstimateYuvTransferResponse {
+    /// Outputs selected to be spent by the transfer.
+    pub inputs: Vec<OutPoint>,
+    /// Amount of tokens returned to the sender, zero if the transfer has no change output.
+    pub change: u128,
+    /// Estimated virtual size of the transfer in vbytes.
+    pub vsize: u64,
+    /// Satoshis required in each of the token outputs, so they aren't dust.
+    pub dust_per_output: u64,
+    /// Fee rate the fee is estimated with in sat/vB.
+    pub fee_rate: u64,
+    /// Fee of the transfer in satoshis.
+    pub fee: u64,
+    /// Satoshis required by the transfer: the fee and the satoshis of the token outputs.
+    pub total_satoshis: u64,
+}
+
+/// Response for [`listtransfers`](YuvTransactionsRpcServer::list_transfers) RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ListTransfersResponse {
+    /// Changes of the balance in the order of the heights.
+    pub transfers: Vec<TransferHistoryEntry>,
+    /// Cursor to request the next transfers with, `None` if there are no more.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub next_cursor: Option<usize>,
+}
+
+/// Response for [`getmempoolentry`](YuvTransactionsRpcServer::get_mempool_entry) RPC method, and
+/// the entry of the [`listmempool`](YuvTransactionsRpcServer::list_mempool) one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct MempoolEntryResponse {
+    pub txid: Txid,
+    /// Stage of the handling: `initialized` before the check, `waiting_mined` and `mined` while
+    /// waiting for the confirmations, `attaching` while waiting for the parents, and so on.
+    pub status: YuvTransactionStatus,
+    /// Height of the block the transaction is mined in, `None` if it isn't mined yet.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub block_height: Option<usize>,
+    /// Whether the transaction is received from the peers, not provided via RPC.
+    pub from_peer: bool,
+    /// Reason the transaction is rejected for, only for the ones that are removed from the
+    /// mempool as invalid.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub rejection: Option<TxRejection>,
+}
+
+impl From<&MempoolTxEntry> for MempoolEntryResponse {
+    fn from(entry: &MempoolTxEntry) -> Self {
+        Self {
+            txid: entry.txid(),
+            status: entry.status.clone().into(),
+            block_height: entry.block_height,
+            from_peer: entry.sender.is_some(),
+            rejection: None,
+        }
+    }
+}
+
+/// Response for [`listmempool`](YuvTransactionsRpcServer::list_mempool) RPC method.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ListMempoolResponse {
+    /// Entries in the order they were added to the mempool.
+    pub entries: Vec<MempoolEntryResponse>,
+    /// Cursor to request the next entries with, `None` if there are no more.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub next_cursor: Option<usize>,
+}
+
+/// Kind of the transaction in the [`TxGraphResponse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxGraphNodeKind {
+    Issue,
+    Transfer,
+    Announcement,
+}
+
+impl From<&YuvTxType> for TxGraphNodeKind {
+    fn from(tx_type: &YuvTxType) -> Self {
+        match tx_type {
+            YuvTxType::Issue { .. } => Self::Issue,
+            YuvTxType::Transfer { .. } => Self::Transfer,
+            YuvTxType::Announcement(_) => Self::Announcement,
+        }
+    }
+}
+
+/// Transaction in the [`TxGraphResponse`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TxGraphNode {
+    pub txid: Txid,
+    pub kind: TxGraphNodeKind,
+    /// Distance from the requested transaction, `0` for the requested one.
+    pub depth: u32,
+}
+
+/// Output of the `parent` transaction spent by the input of the `child` one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TxGraphEdge {
+    pub parent: Txid,
+    /// Index of the parent's output.
+    pub vout: u32,
+    pub child: Txid,
+    /// Index of the child's input that spends the output.
+    pub input: u32,
+    /// Chroma of the tokens in the output.
+    pub chroma: Chroma,
+    /// Amount of the tokens in the output.
+    pub amount: u128,
+}
+
+/// Response for [`gettxancestry`] and [`gettxdescendants`] RPC methods: the graph of the
+/// transactions that the requested one spends the outputs of, or the ones that spend its
+/// outputs.
+///
+/// [`gettxancestry`]: YuvTransactionsRpcServer::get_tx_ancestry
+/// [`gettxdescendants`]: YuvTransactionsRpcServer::get_tx_descendants
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TxGraphResponse {
+    /// Transactions in the order of their depth, starting with the requested one.
+    pub nodes: Vec<TxGraphNode>,
+    /// Edges from the nodes that are not at the max depth.
+    pub edges: Vec<TxGraphEdge>,
+    /// Ancestors that are referenced by the edges, but aren't stored by the node, e.g. pruned.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub missing: Vec<Txid>,
+    /// Whether the graph is cut by the depth or the node's limit of the items per request, so
+    /// the nodes at the max depth have more edges.
+    pub truncated: bool,
+}
+
+/// Error code returned when the serialized response exceeds the node's response size limit.
+pub const RESPONSE_TOO_LARGE_CODE: i32 = -32011;
+
+/// Data of the [`RESPONSE_TOO_LARGE_CODE`] error, so the client could split the request
+/// into smaller batches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct SizeLimitExceeded {
+    /// Size of the JSON serialized response in bytes.
+    pub size: usize,
+    /// Max size of the response in bytes.
+    pub limit: usize,
+}
+
+/// Error code returned when the requested transaction was attached, but then pruned from the
+/// node's storage, as all its outputs are spent.
+pub const TX_PRUNED_CODE: i32 = -32012;
+
+/// Data of the [`TX_PRUNED_CODE`] error.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TxPruned {
+    pub txid: Txid,
+    /// Height of the block the last output of the transaction is spent in.
+    pub spent_height: usize,
+}
+
+/// Error code returned when the client exceeds the rate limit of the node's RPC server, with
+/// the HTTP status `429 Too Many Requests`.
+pub const RATE_LIMITED_CODE: i32 = -32013;
+
 /// Response for [`emulateyuvtransaction`](YuvTransactionsRpcServer::emulate_yuv_transaction) RPC
 /// method that is defined for returning reason of transaction rejection.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -125,6 +548,31 @@ impl EmulateYuvTransactionResponse {
     }
 }
 
+/// YUV transaction in one of the encodings accepted by [`convertyuvtx`] RPC method.
+///
+/// [`convertyuvtx`]: YuvTransactionsRpcServer::convert_yuv_tx
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum EncodedYuvTransaction {
+    /// Consensus encoded transaction in hex.
+    Hex(String),
+    /// JSON encoded transaction.
+    Json(YuvTransaction),
+}
+
+/// Response for [`convertyuvtx`] RPC method that contains the transaction in all the
+/// supported encodings.
+///
+/// [`convertyuvtx`]: YuvTransactionsRpcServer::convert_yuv_tx
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ConvertYuvTransactionResponse {
+    pub txid: Txid,
+    /// Consensus encoded transaction in hex.
+    pub hex: String,
+    /// JSON encoded transaction.
+    pub json: YuvTransaction,
+}
+
 /// A wrapper around [`bitcoin::blockdata::transaction`] that contains `Txid`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct TransactionResponse {
@@ -1,15 +1,22 @@
+use bitcoin::secp256k1::PublicKey;
 use jsonrpsee::proc_macros::rpc;
 
 #[cfg(feature = "server")]
 use jsonrpsee::core::RpcResult;
 
-use yuv_pixels::Chroma;
-use yuv_types::announcements::ChromaInfo;
+use yuv_pixels::{Chroma, PixelProof};
+use yuv_storage::TxRejection;
 use yuv_types::YuvTransaction;
 
 use crate::transactions::{
-    BlockHash, EmulateYuvTransactionResponse, GetRawYuvTransactionResponseJson,
-    ProvideYuvProofRequest, Txid, YuvTransactionResponse,
+    BlockHash, ConvertYuvTransactionResponse, CreateSnapshotResponse,
+    EmulateYuvTransactionResponse, EncodedYuvTransaction, EstimateYuvTransferResponse,
+    GetActivationStatusResponse, GetApiVersionResponse, GetCapabilitiesResponse,
+    GetChromaInfoResponse, GetInclusionProofResponse, GetNodeStatusResponse,
+    GetRawYuvTransactionResponseJson, GetSupplyCommitmentResponse, ListMempoolResponse,
+    ListTransfersResponse, MempoolEntryResponse, ProvideYuvProofRequest, PubkeyUtxoResponse,
+    TxGraphResponse, Txid, YuvTransactionProofsResponse, YuvTransactionResponse,
+    YuvTransactionStatus, YuvTxOutFreezeResponse,
 };
 
 use super::GetRawYuvTransactionResponseHex;
@@ -38,6 +45,25 @@ pub trait YuvTransactionsRpc {
     async fn provide_list_yuv_proofs(&self, proofs: Vec<ProvideYuvProofRequest>)
         -> RpcResult<bool>;
 
+    /// Provide YUV transactions on behalf of the wallet, so they are listed for it by
+    /// `listwalletyuvproofs`. The calls with the wallet's API key are always made on behalf of
+    /// the key's wallet, whatever `wallet` is passed.
+    #[method(name = "providewalletyuvproofs")]
+    async fn provide_wallet_yuv_proofs(
+        &self,
+        wallet: String,
+        yuv_txs: Vec<YuvTransaction>,
+    ) -> RpcResult<bool>;
+
+    /// List the ids of the transactions provided on behalf of the wallet, in the order they were
+    /// provided, by pages of the max batch size.
+    #[method(name = "listwalletyuvproofs")]
+    async fn list_wallet_yuv_proofs(
+        &self,
+        wallet: String,
+        page: Option<u64>,
+    ) -> RpcResult<Vec<Txid>>;
+
     /// Get YUV transaction by id and return its proofs.
     #[method(name = "getrawyuvtransaction")]
     #[deprecated(since = "0.6.0", note = "use `getyuvtransaction` instead")]
@@ -66,6 +92,17 @@ pub trait YuvTransactionsRpc {
         txids: Vec<Txid>,
     ) -> RpcResult<Vec<GetRawYuvTransactionResponseHex>>;
 
+    /// Get proofs and statuses of YUV transactions by their ids in one request. Unlike
+    /// [`getlistyuvtransactions`], returns an entry for each of the requested ids, even if the
+    /// transaction is unknown to the node.
+    ///
+    /// [`getlistyuvtransactions`]: YuvTransactionsRpcServer::get_list_yuv_transactions
+    #[method(name = "getyuvtransactionsbyids")]
+    async fn get_yuv_transactions_by_ids(
+        &self,
+        txids: Vec<Txid>,
+    ) -> RpcResult<Vec<YuvTransactionProofsResponse>>;
+
     /// Get transaction list by page number.
     #[method(name = "listyuvtransactions")]
     async fn list_yuv_transactions(&self, page: u64) -> RpcResult<Vec<YuvTransactionResponse>>;
@@ -83,10 +120,31 @@ pub trait YuvTransactionsRpc {
     #[method(name = "sendyuvtransaction")]
     async fn send_yuv_tx(&self, yuv_tx: String, max_burn_amount: Option<u64>) -> RpcResult<bool>;
 
+    /// Send YUV transaction HEX to Bitcoin network along with the HEX of its parents. The node
+    /// verifies the transaction against the provided parents instead of the ones from its
+    /// storage, so the nodes with the pruned history could forward it. The parents aren't
+    /// stored, their number and size are limited by the node.
+    #[method(name = "sendrawyuvtxwithparents", aliases = ["sendRawYuvTxWithParents"])]
+    async fn send_raw_yuv_tx_with_parents(
+        &self,
+        yuv_tx: String,
+        parents: Vec<String>,
+        max_burn_amount: Option<u64>,
+    ) -> RpcResult<bool>;
+
     /// Check if YUV transaction is frozen or not.
     #[method(name = "isyuvtxoutfrozen")]
     async fn is_yuv_txout_frozen(&self, txid: Txid, vout: u32) -> RpcResult<bool>;
 
+    /// Get the freeze of the YUV transaction output, including the frozen amount if the output
+    /// is frozen partially.
+    #[method(name = "getyuvtxoutfreeze")]
+    async fn get_yuv_txout_freeze(
+        &self,
+        txid: Txid,
+        vout: u32,
+    ) -> RpcResult<Option<YuvTxOutFreezeResponse>>;
+
     /// Emulate transaction check and attach without actuall broadcasting or
     /// mining it to the network.
     ///
@@ -98,7 +156,150 @@ pub trait YuvTransactionsRpc {
         yuv_tx: YuvTransaction,
     ) -> RpcResult<EmulateYuvTransactionResponse>;
 
-    /// Get the [ChromaInfo] that contains the information about the token.
+    /// Get the information about the token along with the amount of the tokens that can still
+    /// be issued.
     #[method(name = "getchromainfo")]
-    async fn get_chroma_info(&self, chroma: Chroma) -> RpcResult<Option<ChromaInfo>>;
+    async fn get_chroma_info(&self, chroma: Chroma) -> RpcResult<Option<GetChromaInfoResponse>>;
+
+    /// Get the root of the sparse Merkle sum tree of the chroma's unspent outputs, which commits
+    /// to the outputs and to the circulating supply.
+    #[method(name = "getsupplycommitment")]
+    async fn get_supply_commitment(&self, chroma: Chroma)
+        -> RpcResult<GetSupplyCommitmentResponse>;
+
+    /// Get the proof of the inclusion of the unspent output into the supply tree of its chroma.
+    /// Returns `None` if the output is spent or doesn't hold the pixel counted in the supply.
+    #[method(name = "getinclusionproof")]
+    async fn get_inclusion_proof(
+        &self,
+        txid: Txid,
+        vout: u32,
+    ) -> RpcResult<Option<GetInclusionProofResponse>>;
+
+    /// List the unspent outputs owned by the key, optionally of the `chroma` only. Requires the
+    /// address index to be enabled on the node.
+    #[method(name = "listutxosbypubkey", aliases = ["listUtxosByPubkey"])]
+    async fn list_utxos_by_pubkey(
+        &self,
+        pubkey: PublicKey,
+        chroma: Option<Chroma>,
+    ) -> RpcResult<Vec<PubkeyUtxoResponse>>;
+
+    /// Estimate the fee and satoshis of the transfer of the chroma's tokens to the recipients,
+    /// one amount per recipient. The outputs to spend are selected from the `utxos`, or from the
+    /// ones owned by the `sender` if they aren't provided, which requires the address index to
+    /// be enabled on the node.
+    #[method(name = "estimateyuvtransfer", aliases = ["estimateYuvTransfer"])]
+    async fn estimate_yuv_transfer(
+        &self,
+        recipients: Vec<PublicKey>,
+        chroma: Chroma,
+        amounts: Vec<u128>,
+        utxos: Option<Vec<PubkeyUtxoResponse>>,
+        sender: Option<PublicKey>,
+    ) -> RpcResult<EstimateYuvTransferResponse>;
+
+    /// Get the balance of the key in the chroma's tokens at the `height`, summed from the transfer
+    /// history. Requires the history index to be enabled on the node.
+    #[method(name = "getbalanceat", aliases = ["getBalanceAt"])]
+    async fn get_balance_at(
+        &self,
+        pubkey: PublicKey,
+        chroma: Chroma,
+        height: usize,
+    ) -> RpcResult<u128>;
+
+    /// List the changes of the balance of the key in the chroma's tokens made by the transfers
+    /// mined in the `from_height..=to_height` range. The transfers are paginated, the next page
+    /// is requested with the `next_cursor` of the previous one. Requires the history index to be
+    /// enabled on the node.
+    #[method(name = "listtransfers", aliases = ["listTransfers"])]
+    async fn list_transfers(
+        &self,
+        pubkey: PublicKey,
+        chroma: Chroma,
+        from_height: usize,
+        to_height: Option<usize>,
+        cursor: Option<usize>,
+    ) -> RpcResult<ListTransfersResponse>;
+
+    /// Get the graph of the attached transactions whose outputs the transaction spends, back to
+    /// their issuances or up to `depth` levels, to trace the provenance of its tokens. Returns
+    /// `null` if the transaction isn't attached.
+    #[method(name = "gettxancestry", aliases = ["getTxAncestry"])]
+    async fn get_tx_ancestry(&self, txid: Txid, depth: u32) -> RpcResult<Option<TxGraphResponse>>;
+
+    /// Get the graph of the attached transactions that spend the transaction's outputs, up to
+    /// `depth` levels. Returns `null` if the transaction isn't attached.
+    #[method(name = "gettxdescendants", aliases = ["getTxDescendants"])]
+    async fn get_tx_descendants(
+        &self,
+        txid: Txid,
+        depth: u32,
+    ) -> RpcResult<Option<TxGraphResponse>>;
+
+    /// Export the proof bundle of the output: its transaction and all the ancestors back to the
+    /// issuances, consensus encoded in hex, so the receiver can validate the output without the
+    /// node. Returns `null` if the output isn't attached.
+    #[method(name = "exportproofbundle", aliases = ["exportProofBundle"])]
+    async fn export_proof_bundle(&self, txid: Txid, vout: u32) -> RpcResult<Option<String>>;
+
+    /// Get activation statuses of the consensus-affecting protocol features at the height of
+    /// the last indexed block.
+    #[method(name = "getactivationstatus")]
+    async fn get_activation_status(&self) -> RpcResult<GetActivationStatusResponse>;
+
+    /// Get the status of the node's scheduled maintenance jobs.
+    #[method(name = "getnodestatus")]
+    async fn get_node_status(&self) -> RpcResult<GetNodeStatusResponse>;
+
+    /// Get the version of the node's RPC API and software.
+    #[method(name = "getapiversion", aliases = ["getApiVersion"])]
+    async fn get_api_version(&self) -> RpcResult<GetApiVersionResponse>;
+
+    /// Get the version of the node's RPC API, the optional features enabled on the node and the
+    /// limits of the requests, so the clients can adapt to the node.
+    #[method(name = "getcapabilities", aliases = ["getCapabilities"])]
+    async fn get_capabilities(&self) -> RpcResult<GetCapabilitiesResponse>;
+
+    /// Get the entry of the transaction in the node's mempool, i.e. the stage of its handling.
+    /// For the rejected transactions, the entry has the `none` status and the rejection. Returns
+    /// `null` if the transaction isn't handled by the node now.
+    #[method(name = "getmempoolentry", aliases = ["getMempoolEntry"])]
+    async fn get_mempool_entry(&self, txid: Txid) -> RpcResult<Option<MempoolEntryResponse>>;
+
+    /// List the entries of the node's mempool, optionally with the `status` only. The entries are
+    /// paginated, the next page is requested with the `next_cursor` of the previous one. As the
+    /// mempool changes between the requests, the entries can be skipped or repeated.
+    #[method(name = "listmempool", aliases = ["listMempool"])]
+    async fn list_mempool(
+        &self,
+        status: Option<YuvTransactionStatus>,
+        cursor: Option<usize>,
+    ) -> RpcResult<ListMempoolResponse>;
+
+    /// Get the reason the transaction is rejected for by the checks, `null` if it isn't rejected.
+    #[method(name = "getrejectionreason", aliases = ["getRejectionReason"])]
+    async fn get_rejection_reason(&self, txid: Txid) -> RpcResult<Option<TxRejection>>;
+
+    /// Create the consistent snapshot of the node's storage in the `dir` on the node's host, so
+    /// it could be backed up without stopping the node. The directory must not exist. Requires
+    /// the LevelDB storage backend.
+    #[method(name = "createsnapshot", aliases = ["createSnapshot"])]
+    async fn create_snapshot(&self, dir: String) -> RpcResult<CreateSnapshotResponse>;
+
+    /// Decode the consensus encoded pixel proof from hex.
+    #[method(name = "decodeproof")]
+    async fn decode_proof(&self, proof: String) -> RpcResult<PixelProof>;
+
+    /// Encode the pixel proof with the consensus encoding to hex.
+    #[method(name = "encodeproof")]
+    async fn encode_proof(&self, proof: PixelProof) -> RpcResult<String>;
+
+    /// Convert the YUV transaction from hex to JSON or vice versa.
+    #[method(name = "convertyuvtx")]
+    async fn convert_yuv_tx(
+        &self,
+        yuv_tx: EncodedYuvTransaction,
+    ) -> RpcResult<ConvertYuvTransactionResponse>;
 }
@@ -0,0 +1,410 @@
+//! Authentication of the RPC requests with the HTTP Basic or Bearer credentials, checked only
+//! for the protected methods, so the read-only ones can stay public.
+//!
+//! The calls over WebSocket can't be checked one by one, so the WebSocket connections, including
+//! the subscriptions, always require the credentials, as the wallet methods are always protected.
+//!
+//! ## Wallet keys
+//!
+//! The tenants of a hosted node authenticate with their own Bearer tokens, which grant access
+//! only to the [`WALLET_METHODS`] and the public ones. The `wallet` param of the wallet methods
+//! is set to the label of the key, so a tenant can't act on behalf of the others. The wallet
+//! methods require either a wallet key or the credentials, whatever the protected methods are,
+//! and the server doesn't serve them at all without the config. Wallet keys can't open the
+//! WebSocket connections.
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::{
+    header::{AUTHORIZATION, CONTENT_LENGTH, WWW_AUTHENTICATE},
+    Body, HeaderMap, Request, Response, StatusCode,
+};
+use serde_json::Value;
+use tower::{Layer, Service};
+
+use crate::request::{
+    is_upgrade_request, payload_too_large, read_body, BoxError, BoxFuture, Calls,
+};
+
+/// Methods that change the state of the node, protected by default.
+pub const DEFAULT_PROTECTED_METHODS: &[&str] = &[
+    "providelistyuvproofs",
+    "provideyuvproof",
+    "provideyuvproofshort",
+    "sendrawyuvtransaction",
+    "sendyuvtransaction",
+    "sendrawyuvtxwithparents",
+    "createsnapshot",
+    "providewalletyuvproofs",
+    "listwalletyuvproofs",
+];
+
+/// Methods called on behalf of the wallet, whose first param is the wallet label.
+pub const WALLET_METHODS: &[&str] = &["providewalletyuvproofs", "listwalletyuvproofs"];
+
+/// Name that protects all the methods if it's in the protected ones.
+pub const ALL_METHODS: &str = "*";
+
+/// Credentials the clients authenticate with.
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    /// `Authorization: Basic <base64 of username:password>`.
+    Basic { username: String, password: String },
+    /// `Authorization: Bearer <token>`.
+    Bearer { token: String },
+}
+
+impl Credentials {
+    fn authorization(&self) -> String {
+        match self {
+            Self::Basic { username, password } => {
+                format!("Basic {}", base64::encode(format!("{username}:{password}")))
+            }
+            Self::Bearer { token } => format!("Bearer {token}"),
+        }
+    }
+
+    fn challenge(&self) -> &'static str {
+        match self {
+            Self::Basic { .. } => "Basic realm=\"yuv\"",
+            Self::Bearer { .. } => "Bearer realm=\"yuv\"",
+        }
+    }
+}
+
+/// Bearer token of the tenant's wallet.
+#[derive(Debug, Clone)]
+pub struct WalletKey {
+    /// Label of the wallet the calls are made on behalf of.
+    pub label: String,
+    /// `Authorization: Bearer <token>`.
+    pub token: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthConfig {
+    /// Credentials the clients authenticate with.
+    pub credentials: Credentials,
+    /// Methods that require the credentials, all of them if it contains [`ALL_METHODS`]. The
+    /// [`WALLET_METHODS`] are protected even if they aren't in the set.
+    pub protected_methods: HashSet<String>,
+    /// Keys of the tenants' wallets, which grant access only to the [`WALLET_METHODS`].
+    pub wallet_keys: Vec<WalletKey>,
+}
+
+impl AuthConfig {
+    /// Creates the config that protects the [`DEFAULT_PROTECTED_METHODS`].
+    pub fn new(credentials: Credentials) -> Self {
+        Self {
+            credentials,
+            protected_methods: DEFAULT_PROTECTED_METHODS
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+            wallet_keys: Vec::new(),
+        }
+    }
+
+    pub fn with_protected_methods(mut self, methods: impl IntoIterator<Item = String>) -> Self {
+        self.protected_methods = methods.into_iter().collect();
+        self
+    }
+
+    pub fn with_wallet_keys(mut self, wallet_keys: Vec<WalletKey>) -> Self {
+        self.wallet_keys = wallet_keys;
+        self
+    }
+
+    fn is_protected(&self, method: &str) -> bool {
+        WALLET_METHODS.contains(&method)
+            || self.protected_methods.contains(ALL_METHODS)
+            || self.protected_methods.contains(method)
+    }
+}
+
+/// Middleware of the RPC server that rejects the calls of the protected methods without the
+/// credentials with `401 Unauthorized`. Without the config, all the requests are passed through.
+#[derive(Debug, Clone)]
+pub struct AuthLayer {
+    config: Option<Arc<Authorization>>,
+    max_body_size: usize,
+}
+
+impl AuthLayer {
+    /// Creates the layer, which reads at most `max_body_size` bytes of the unauthenticated
+    /// requests to find the methods they call.
+    pub fn new(config: Option<AuthConfig>, max_body_size: usize) -> Self {
+        let config = config.map(|config| {
+            Arc::new(Authorization {
+                expected: config.credentials.authorization(),
+                wallets: config
+                    .wallet_keys
+                    .iter()
+                    .map(|key| {
+                        let credentials = Credentials::Bearer {
+                            token: key.token.clone(),
+                        };
+
+                        (credentials.authorization(), key.label.clone())
+                    })
+                    .collect(),
+                config,
+            })
+        });
+
+        Self {
+            config,
+            max_body_size,
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = Auth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Auth {
+            inner,
+            config: self.config.clone(),
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Authorization {
+    config: AuthConfig,
+    /// Expected value of the `Authorization` header.
+    expected: String,
+    /// Expected values of the `Authorization` header with the wallet keys and their labels.
+    wallets: Vec<(String, String)>,
+}
+
+impl Authorization {
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(AUTHORIZATION)
+            .is_some_and(|value| constant_time_eq(value.as_bytes(), self.expected.as_bytes()))
+    }
+
+    /// Label of the wallet whose key the request is authenticated with.
+    fn wallet(&self, headers: &HeaderMap) -> Option<String> {
+        let value = headers.get(AUTHORIZATION)?;
+
+        self.wallets
+            .iter()
+            .find(|(expected, _)| constant_time_eq(value.as_bytes(), expected.as_bytes()))
+            .map(|(_, label)| label.clone())
+    }
+
+    fn unauthorized(&self) -> Response<Body> {
+        let mut response = Response::new(Body::from("Unauthorized"));
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+        response.headers_mut().insert(
+            WWW_AUTHENTICATE,
+            self.config
+                .credentials
+                .challenge()
+                .parse()
+                .expect("valid header"),
+        );
+
+        response
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Auth<S> {
+    inner: S,
+    config: Option<Arc<Authorization>>,
+    max_body_size: usize,
+}
+
+impl<S> Service<Request<Body>> for Auth<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let Some(auth) = self.config.clone() else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        if auth.is_authorized(request.headers()) {
+            return Box::pin(self.inner.call(request));
+        }
+
+        let wallet = auth.wallet(request.headers());
+        if is_upgrade_request(&request) {
+            return Box::pin(std::future::ready(Ok(auth.unauthorized())));
+        }
+
+        // The service that is ready is taken, the clone is left for the next calls.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let max_body_size = self.max_body_size;
+
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let Some(mut bytes) = read_body(body, max_body_size).await? else {
+                return Ok(payload_too_large());
+            };
+
+            if let Some(label) = wallet {
+                let Some(scoped) = scope_to_wallet(&auth.config, &bytes, &label) else {
+                    return Ok(auth.unauthorized());
+                };
+
+                parts.headers.insert(CONTENT_LENGTH, scoped.len().into());
+                bytes = scoped;
+            } else if Calls::parse(&bytes)
+                .methods
+                .iter()
+                .any(|method| auth.config.is_protected(method))
+            {
+                return Ok(auth.unauthorized());
+            }
+
+            inner
+                .call(Request::from_parts(parts, Body::from(bytes)))
+                .await
+        })
+    }
+}
+
+/// Makes the calls of the [`WALLET_METHODS`] on behalf of the wallet by setting their first
+/// param to its label. Returns `None` if the other protected methods are called. Invalid requests
+/// are left as is, they are rejected by the server.
+fn scope_to_wallet(config: &AuthConfig, body: &[u8], label: &str) -> Option<Vec<u8>> {
+    let Ok(mut request) = serde_json::from_slice::<Value>(body) else {
+        return Some(body.to_vec());
+    };
+
+    let is_forbidden =
+        |method: &String| !WALLET_METHODS.contains(&method.as_str()) && config.is_protected(method);
+    if Calls::from_value(&request).methods.iter().any(is_forbidden) {
+        return None;
+    }
+
+    let calls: Vec<&mut Value> = match &mut request {
+        Value::Array(calls) => calls.iter_mut().collect(),
+        call => vec![call],
+    };
+
+    for call in calls {
+        let Some(call) = call.as_object_mut() else {
+            continue;
+        };
+        let is_wallet_method = call
+            .get("method")
+            .and_then(Value::as_str)
+            .is_some_and(|method| WALLET_METHODS.contains(&method));
+        if !is_wallet_method {
+            continue;
+        }
+
+        let label = Value::String(label.to_string());
+        match call
+            .entry("params")
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            Value::Array(params) if params.is_empty() => params.push(label),
+            Value::Array(params) => params[0] = label,
+            Value::Object(params) => {
+                params.insert("wallet".to_string(), label);
+            }
+            _ => {}
+        }
+    }
+
+    serde_json::to_vec(&request).ok()
+}
+
+/// Compares the credentials in the time that doesn't depend on the position of the first
+/// mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protected_methods() {
+        let credentials = Credentials::Bearer {
+            token: "secret".to_string(),
+        };
+
+        let config = AuthConfig::new(credentials.clone());
+        assert!(config.is_protected("sendyuvtransaction"));
+        assert!(!config.is_protected("getyuvtransaction"));
+
+        let config =
+            AuthConfig::new(credentials.clone()).with_protected_methods([ALL_METHODS.into()]);
+        assert!(config.is_protected("getyuvtransaction"));
+
+        let config = AuthConfig::new(credentials).with_protected_methods([]);
+        assert!(!config.is_protected("sendyuvtransaction"));
+        for method in WALLET_METHODS {
+            assert!(config.is_protected(method));
+        }
+    }
+
+    #[test]
+    fn test_authorization_header() {
+        let credentials = Credentials::Basic {
+            username: "admin".to_string(),
+            password: "123".to_string(),
+        };
+
+        assert_eq!(credentials.authorization(), "Basic YWRtaW46MTIz");
+    }
+
+    #[test]
+    fn test_scope_to_wallet() {
+        let config = AuthConfig::new(Credentials::Bearer {
+            token: "secret".to_string(),
+        });
+        let scope = |body: &str| {
+            scope_to_wallet(&config, body.as_bytes(), "alice")
+                .map(|bytes| serde_json::from_slice::<Value>(&bytes).unwrap())
+        };
+
+        let single = r#"{"method":"listwalletyuvproofs","params":["bob",1],"id":1}"#;
+        assert_eq!(
+            scope(single).unwrap()["params"],
+            serde_json::json!(["alice", 1])
+        );
+
+        let named = r#"{"method":"listwalletyuvproofs","params":{"wallet":"bob"},"id":1}"#;
+        assert_eq!(scope(named).unwrap()["params"]["wallet"], "alice");
+
+        let batch = r#"[{"method":"getyuvtransaction"},{"method":"listwalletyuvproofs"}]"#;
+        assert_eq!(
+            scope(batch).unwrap()[1]["params"],
+            serde_json::json!(["alice"])
+        );
+
+        let protected = r#"[{"method":"listwalletyuvproofs"},{"method":"createsnapshot"}]"#;
+        assert!(scope(protected).is_none());
+
+        let config = AuthConfig::new(Credentials::Bearer {
+            token: "secret".to_string(),
+        })
+        .with_protected_methods([]);
+        let body = r#"{"method":"providewalletyuvproofs","params":["bob",[]],"id":1}"#;
+        let scoped = scope_to_wallet(&config, body.as_bytes(), "alice").unwrap();
+        let scoped = serde_json::from_slice::<Value>(&scoped).unwrap();
+        assert_eq!(scoped["params"], serde_json::json!(["alice", []]));
+    }
+}
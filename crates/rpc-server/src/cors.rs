@@ -0,0 +1,151 @@
+//! Handling of the [CORS] requests, so the web wallets can call the node from the browsers.
+//!
+//! [CORS]: https://developer.mozilla.org/en-US/docs/Web/HTTP/CORS
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::{
+    header::{
+        HeaderValue, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+        ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS,
+        ORIGIN, VARY,
+    },
+    Body, Method, Request, Response, StatusCode,
+};
+use tower::{Layer, Service};
+
+use crate::request::{BoxError, BoxFuture};
+
+/// Origin that allows any origin if it's in the allowed ones.
+pub const ANY_ORIGIN: &str = "*";
+
+/// How long the browsers can cache the answer to the preflight request, in seconds.
+const PREFLIGHT_MAX_AGE_SECS: u32 = 86400;
+
+/// Middleware of the RPC server that answers the preflight requests from the allowed origins,
+/// and adds the CORS headers to the responses to them. The requests from the other origins are
+/// passed through without the headers, so the browsers don't let the pages read the responses.
+#[derive(Debug, Clone)]
+pub struct CorsLayer {
+    allowed_origins: Arc<Vec<String>>,
+}
+
+impl CorsLayer {
+    pub fn new(allowed_origins: Vec<String>) -> Self {
+        Self {
+            allowed_origins: Arc::new(allowed_origins),
+        }
+    }
+}
+
+impl<S> Layer<S> for CorsLayer {
+    type Service = Cors<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Cors {
+            inner,
+            allowed_origins: self.allowed_origins.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cors<S> {
+    inner: S,
+    allowed_origins: Arc<Vec<String>>,
+}
+
+impl<S> Cors<S> {
+    /// Returns the value of `Access-Control-Allow-Origin` for the request, `None` if it's not a
+    /// CORS request or its origin isn't allowed.
+    fn allow_origin(&self, request: &Request<Body>) -> Option<HeaderValue> {
+        let origin = request.headers().get(ORIGIN)?;
+
+        if self
+            .allowed_origins
+            .iter()
+            .any(|allowed| allowed == ANY_ORIGIN)
+        {
+            return Some(HeaderValue::from_static(ANY_ORIGIN));
+        }
+
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed.as_bytes() == origin.as_bytes())
+            .then(|| origin.clone())
+    }
+}
+
+impl<S> Service<Request<Body>> for Cors<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let is_cors_request = request.headers().contains_key(ORIGIN);
+        let allow_origin = self.allow_origin(&request);
+
+        if is_cors_request && request.method() == Method::OPTIONS {
+            let response = preflight_response(&request, allow_origin);
+            return Box::pin(std::future::ready(Ok(response)));
+        }
+
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let mut response = future.await?;
+
+            if let Some(allow_origin) = allow_origin {
+                let headers = response.headers_mut();
+                headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+                headers.insert(VARY, HeaderValue::from_static("Origin"));
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+fn preflight_response(
+    request: &Request<Body>,
+    allow_origin: Option<HeaderValue>,
+) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+
+    let Some(allow_origin) = allow_origin else {
+        *response.status_mut() = StatusCode::FORBIDDEN;
+        return response;
+    };
+
+    *response.status_mut() = StatusCode::NO_CONTENT;
+
+    let headers = response.headers_mut();
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+    headers.insert(
+        ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static("POST, OPTIONS"),
+    );
+    headers.insert(
+        ACCESS_CONTROL_ALLOW_HEADERS,
+        request
+            .headers()
+            .get(ACCESS_CONTROL_REQUEST_HEADERS)
+            .cloned()
+            .unwrap_or_else(|| HeaderValue::from_static("content-type, authorization")),
+    );
+    headers.insert(
+        ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from(PREFLIGHT_MAX_AGE_SECS),
+    );
+    headers.insert(VARY, HeaderValue::from_static("Origin"));
+
+    response
+}
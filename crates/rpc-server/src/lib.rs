@@ -1,17 +1,44 @@
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bitcoin_client::BitcoinRpcClient;
 use event_bus::EventBus;
-use jsonrpsee::server::Server;
+use eyre::Context;
+use jsonrpsee::server::{BatchRequestConfig, Server};
+use tokio::net::TcpListener;
 use tokio_util::sync::CancellationToken;
 
-use yuv_rpc_api::transactions::YuvTransactionsRpcServer;
+use yuv_rpc_api::{
+    subscriptions::YuvSubscriptionsRpcServer, transactions::YuvTransactionsRpcServer,
+};
+use yuv_scheduler::JobStatuses;
 use yuv_storage::{
-    ChromaInfoStorage, FrozenTxsStorage, MempoolEntryStorage, PagesStorage, TransactionsStorage,
+    AddressIndexStorage, BlockIndexerStorage, ChromaInfoStorage, FrozenTxsStorage,
+    InvalidTxsStorage, MempoolEntryStorage, MempoolStorage, PagesStorage, PruningStorage,
+    Snapshotter, SupplyTreeStorage, TransactionsStorage, TransferHistoryStorage,
+    TxDependentsStorage, WalletProofsStorage,
 };
+use yuv_types::network::Network;
 
+use crate::auth::{AuthConfig, AuthLayer};
+use crate::cors::CorsLayer;
+use crate::limits::{RateLimitConfig, RateLimitLayer, RejectedCalls, TimeoutLayer};
+use crate::subscriptions::SubscriptionsController;
 use crate::transactions::TransactionsController;
 
+pub mod auth;
+pub mod cors;
+pub mod limits;
+mod request;
+mod proxy;
+#[cfg(feature = "rest")]
+pub mod rest;
+pub mod subscriptions;
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(feature = "otel")]
+pub mod trace_context;
 pub mod transactions;
 
 pub struct ServerConfig {
@@ -21,45 +48,198 @@ pub struct ServerConfig {
     pub max_items_per_request: usize,
     /// Max size of incoming request in kilobytes.
     pub max_request_size_kb: u32,
+    /// Max size of outgoing response in kilobytes.
+    pub max_response_size_kb: u32,
+    /// Max number of transactions per batch request.
+    pub max_batch_size: usize,
+    /// Max number of the parents sent along with the transaction.
+    pub max_bundle_parents: usize,
+    /// Max size of the transaction with its parents in bytes.
+    pub max_bundle_size: usize,
+    /// Network the node runs on.
+    pub network: Network,
+    /// Whether the controller indexes the unspent outputs by the keys of their owners.
+    pub address_index: bool,
+    /// Whether the controller records the transfer history of the owners.
+    pub history_index: bool,
+    /// Authentication of the calls of the protected methods. If `None`, all the methods are
+    /// public.
+    pub auth: Option<AuthConfig>,
+    /// Certificate and key to serve the connections over TLS with. Requires the `tls` feature.
+    pub tls: Option<TlsConfig>,
+    /// Origins allowed to call the server from the browsers, see [`cors`].
+    pub cors_allowed_origins: Vec<String>,
+    /// Limits of the requests and the calls of the methods per client, see [`limits`].
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Max time of handling the HTTP request.
+    pub request_timeout: Option<Duration>,
+    /// Max number of the simultaneous connections.
+    pub max_connections: u32,
+    /// Max number of the calls in the batch request.
+    pub max_batch_requests: Option<u32>,
+}
+
+pub struct TlsConfig {
+    /// Path to the PEM encoded certificate chain.
+    pub cert_path: PathBuf,
+    /// Path to the PEM encoded private key.
+    pub key_path: PathBuf,
 }
 
 /// Runs YUV Node's RPC server.
+///
+/// The server accepts both HTTP and WebSocket connections on the same address. Subscriptions
+/// are available only over WebSocket, and are served from the `subscriptions` controller, which
+/// must be run by the caller, see [`SubscriptionsController::run`]. The `snapshotter` serves
+/// `createsnapshot`, which is rejected if it's `None`.
+///
+/// With the `auth` config, the calls of the protected methods require the credentials, see
+/// [`auth`], and without it the wallet methods are disabled. The server listens on the loopback
+/// address, and the connections accepted on the `address` are forwarded to it by the front that
+/// tells the middlewares the addresses of the clients, over TLS with the `tls` config.
 pub async fn run_server<TS, SS>(
     ServerConfig {
         address,
         max_items_per_request,
         max_request_size_kb,
+        max_response_size_kb,
+        max_batch_size,
+        max_bundle_parents,
+        max_bundle_size,
+        network,
+        address_index,
+        history_index,
+        auth,
+        tls,
+        cors_allowed_origins,
+        rate_limit,
+        request_timeout,
+        max_connections,
+        max_batch_requests,
     }: ServerConfig,
     txs_storage: TS,
     state_storage: SS,
     full_event_bus: EventBus,
     bitcoin_client: Arc<BitcoinRpcClient>,
+    job_statuses: JobStatuses,
+    snapshotter: Option<Snapshotter>,
+    subscriptions: SubscriptionsController,
     cancellation: CancellationToken,
 ) -> eyre::Result<()>
 where
-    TS: TransactionsStorage + PagesStorage + Clone + Send + Sync + 'static,
-    SS: FrozenTxsStorage + ChromaInfoStorage + MempoolEntryStorage + Clone + Send + Sync + 'static,
+    TS: TransactionsStorage
+        + PagesStorage
+        + SupplyTreeStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + TxDependentsStorage
+        + PruningStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    SS: FrozenTxsStorage
+        + ChromaInfoStorage
+        + MempoolEntryStorage
+        + MempoolStorage
+        + InvalidTxsStorage
+        + BlockIndexerStorage
+        + WalletProofsStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
 {
     // The multiplication of average transaction size and max number of items
     // per request approximately gives the maximum JSON RPC request size.
     //
     // See `providelistyuvproofs`
 
-    let server = Server::builder()
+    let max_request_size = max_request_size_kb as usize * 1024;
+    let rejected_calls = RejectedCalls::default();
+    // Without the authentication, the wallet of the caller is unknown.
+    let wallet_methods = auth.is_some();
+
+    let middleware = tower::ServiceBuilder::new();
+
+    #[cfg(feature = "otel")]
+    let middleware = middleware.layer(trace_context::TraceContextLayer);
+
+    // The preflight requests are answered before the rest, as they have no credentials.
+    let middleware = middleware
+        .layer(CorsLayer::new(cors_allowed_origins))
+        .layer(RateLimitLayer::new(
+            rate_limit,
+            rejected_calls.clone(),
+            max_request_size,
+        ))
+        .layer(TimeoutLayer::new(request_timeout))
+        .layer(AuthLayer::new(auth, max_request_size));
+
+    let batch_requests_config = match max_batch_requests {
+        Some(limit) => BatchRequestConfig::Limit(limit),
+        None => BatchRequestConfig::Unlimited,
+    };
+
+    let builder = Server::builder()
         .max_request_body_size(max_request_size_kb * 1024)
-        .build(address)
-        .await?;
-
-    let handle = server.start(
-        TransactionsController::new(
-            txs_storage,
-            full_event_bus,
-            state_storage,
-            bitcoin_client,
-            max_items_per_request,
-        )
-        .into_rpc(),
-    );
+        .max_response_body_size(max_response_size_kb * 1024)
+        .max_connections(max_connections)
+        .set_batch_request_config(batch_requests_config)
+        .set_middleware(middleware);
+
+    let listener = TcpListener::bind(&address)
+        .await
+        .wrap_err_with(|| format!("Failed to listen on {}", address))?;
+    let server = builder.build("127.0.0.1:0").await?;
+    let upstream = server.local_addr()?;
+
+    match tls {
+        #[cfg(feature = "tls")]
+        Some(tls_config) => {
+            let acceptor = tls::load_acceptor(&tls_config)?;
+
+            tokio::spawn(proxy::run_proxy(
+                listener,
+                upstream,
+                move |stream| acceptor.accept(stream),
+                cancellation.clone(),
+            ));
+        }
+        #[cfg(not(feature = "tls"))]
+        Some(_) => eyre::bail!("TLS requires the node to be built with the `tls` feature"),
+        None => {
+            tokio::spawn(proxy::run_proxy(
+                listener,
+                upstream,
+                |stream| std::future::ready(Ok(stream)),
+                cancellation.clone(),
+            ));
+        }
+    }
+
+    let mut methods = TransactionsController::new(
+        txs_storage,
+        full_event_bus,
+        state_storage,
+        bitcoin_client,
+        max_items_per_request,
+        network,
+    )
+    .with_max_batch_size(max_batch_size)
+    .with_max_bundle_parents(max_bundle_parents)
+    .with_max_bundle_size(max_bundle_size)
+    .with_max_response_size(max_response_size_kb as usize * 1024)
+    .with_job_statuses(job_statuses)
+    .with_address_index(address_index)
+    .with_history_index(history_index)
+    .with_snapshotter(snapshotter)
+    .with_rejected_calls(rejected_calls)
+    .with_wallet_methods(wallet_methods)
+    .into_rpc();
+    methods.merge(subscriptions.into_rpc())?;
+
+    let handle = server.start(methods);
 
     // Await until stop message received
     cancellation.cancelled().await;
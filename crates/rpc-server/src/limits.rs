@@ -0,0 +1,527 @@
+//! Limits of the rate and the time of the RPC requests.
+//!
+//! The rate is limited with the token buckets per client, for all the requests and for the calls
+//! of each method with the quota. The methods are limited only over HTTP, as the calls over
+//! WebSocket can't be checked one by one: opening the connection counts as one request, and the
+//! calls made over it aren't limited. With the [authentication](crate::auth), the WebSocket
+//! connections require the credentials, so only the trusted clients can bypass the limits of the
+//! methods.
+//!
+//! The clients are told apart by the addresses of the peers, which the [front](crate::proxy) of
+//! the server passes in the [`PEER_ADDR`] header. Behind a reverse proxy, all the requests come
+//! from the proxy, so with `trust_forwarded_for` the clients are told apart by the last address
+//! in the `X-Forwarded-For` header set by it, and by the peer if there is no header. Otherwise,
+//! the header can be forged by the clients, so it's ignored.
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use hyper::{
+    header::{HeaderValue, CONTENT_TYPE, RETRY_AFTER},
+    Body, Request, Response, StatusCode,
+};
+use serde_json::{json, Value};
+use tower::{Layer, Service};
+use yuv_rpc_api::transactions::{RateLimitMetrics, RATE_LIMITED_CODE};
+
+use crate::proxy::PEER_ADDR;
+use crate::request::{
+    is_upgrade_request, payload_too_large, read_body, BoxError, BoxFuture, Calls,
+};
+
+/// Header with the addresses of the client and the proxies the request went through.
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Number of the buckets after which the ones of the idle clients are removed.
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Name of the limit of all the requests in the [`RejectedCalls`].
+pub const ALL_REQUESTS: &str = "*";
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Limit of all the requests from a client, `None` if only the methods are limited.
+    pub requests: Option<Quota>,
+    /// Limits of the calls of the methods from a client.
+    pub methods: HashMap<String, Quota>,
+    /// Whether the clients are told apart by the `X-Forwarded-For` header instead of the
+    /// addresses of the peers. Enable it only if the node is reachable through the reverse proxy
+    /// only.
+    pub trust_forwarded_for: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Quota {
+    /// Max number of requests per second.
+    pub requests_per_sec: f64,
+    /// Max number of requests at once, after being idle.
+    pub burst: u32,
+}
+
+/// Shared counters of the calls rejected by the rate limits, reported by `getnodestatus`.
+#[derive(Debug, Clone, Default)]
+pub struct RejectedCalls(Arc<Mutex<BTreeMap<String, u64>>>);
+
+impl RejectedCalls {
+    /// Returns the counters sorted by the method's name.
+    pub fn list(&self) -> Vec<RateLimitMetrics> {
+        self.0
+            .lock()
+            .expect("lock should not be poisoned")
+            .iter()
+            .map(|(method, rejected)| RateLimitMetrics {
+                method: method.clone(),
+                rejected: *rejected,
+            })
+            .collect()
+    }
+
+    fn increment(&self, method: &str) {
+        *self
+            .0
+            .lock()
+            .expect("lock should not be poisoned")
+            .entry(method.to_string())
+            .or_default() += 1;
+    }
+}
+
+/// Middleware of the RPC server that answers the requests above the limits with
+/// `429 Too Many Requests` and the [`RATE_LIMITED_CODE`] JSON-RPC error.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: Option<Arc<RateLimiter>>,
+    max_body_size: usize,
+}
+
+impl RateLimitLayer {
+    /// Creates the layer, which passes all the requests through without the config, and reads
+    /// at most `max_body_size` bytes of the requests to find the methods they call.
+    pub fn new(
+        config: Option<RateLimitConfig>,
+        rejected_calls: RejectedCalls,
+        max_body_size: usize,
+    ) -> Self {
+        Self {
+            limiter: config.map(|config| {
+                Arc::new(RateLimiter {
+                    config,
+                    buckets: Mutex::new(HashMap::new()),
+                    rejected_calls,
+                })
+            }),
+            max_body_size,
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+/// Client, `None` if its address is unknown, and the limited method, `None` for all the requests.
+type BucketKey = (Option<IpAddr>, Option<String>);
+
+#[derive(Debug)]
+struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<BucketKey, Bucket>>,
+    rejected_calls: RejectedCalls,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    fn client<B>(&self, request: &Request<B>) -> Option<IpAddr> {
+        let forwarded_for = self
+            .config
+            .trust_forwarded_for
+            .then(|| last_address(request, X_FORWARDED_FOR))
+            .flatten();
+
+        forwarded_for.or_else(|| last_address(request, PEER_ADDR))
+    }
+
+    /// Takes `count` tokens from the bucket, returns `false` if it doesn't have them.
+    fn try_acquire(&self, key: BucketKey, quota: &Quota, count: usize) -> bool {
+        let now = Instant::now();
+        let burst = f64::from(quota.burst.max(1));
+
+        let mut buckets = self.buckets.lock().expect("lock should not be poisoned");
+
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            // The buckets that are full again are the same as the new ones.
+            let config = &self.config;
+            buckets.retain(|(_, method), bucket| {
+                let quota = match method {
+                    Some(method) => config.methods.get(method),
+                    None => config.requests.as_ref(),
+                };
+
+                quota.is_some_and(|quota| {
+                    let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+                    bucket.tokens + elapsed * quota.requests_per_sec < f64::from(quota.burst)
+                })
+            });
+        }
+
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: burst,
+            updated_at: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * quota.requests_per_sec).min(burst);
+        bucket.updated_at = now;
+
+        if bucket.tokens < count as f64 {
+            return false;
+        }
+
+        bucket.tokens -= count as f64;
+        true
+    }
+
+    /// Returns the first method whose quota the calls exceed.
+    fn exceeded_method<'a>(&self, client: Option<IpAddr>, calls: &'a Calls) -> Option<&'a str> {
+        let mut counts = BTreeMap::<&str, usize>::new();
+        for method in &calls.methods {
+            *counts.entry(method).or_default() += 1;
+        }
+
+        counts.into_iter().find_map(|(method, count)| {
+            let quota = self.config.methods.get(method)?;
+
+            (!self.try_acquire((client, Some(method.to_string())), quota, count)).then_some(method)
+        })
+    }
+
+    fn rejected(&self, client: Option<IpAddr>, method: &str, id: Value) -> Response<Body> {
+        self.rejected_calls.increment(method);
+        tracing::debug!(?client, method, "RPC call is rejected by the rate limit");
+
+        let message = if method == ALL_REQUESTS {
+            "Rate limit is exceeded".to_string()
+        } else {
+            format!("Rate limit of {} is exceeded", method)
+        };
+
+        let body = json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": RATE_LIMITED_CODE,
+                "message": message,
+            },
+            "id": id,
+        });
+
+        let mut response = Response::new(Body::from(body.to_string()));
+        *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("1"));
+
+        response
+    }
+}
+
+/// Last address in the comma-separated list of the header.
+fn last_address<B>(request: &Request<B>, header: &str) -> Option<IpAddr> {
+    request
+        .headers()
+        .get_all(header)
+        .iter()
+        .next_back()?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    limiter: Option<Arc<RateLimiter>>,
+    max_body_size: usize,
+}
+
+impl<S> Service<Request<Body>> for RateLimit<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let Some(limiter) = self.limiter.clone() else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        let client = limiter.client(&request);
+
+        if let Some(quota) = &limiter.config.requests {
+            if !limiter.try_acquire((client, None), quota, 1) {
+                let response = limiter.rejected(client, ALL_REQUESTS, Value::Null);
+                return Box::pin(std::future::ready(Ok(response)));
+            }
+        }
+
+        if limiter.config.methods.is_empty() || is_upgrade_request(&request) {
+            return Box::pin(self.inner.call(request));
+        }
+
+        // The service that is ready is taken, the clone is left for the next calls.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let max_body_size = self.max_body_size;
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let Some(bytes) = read_body(body, max_body_size).await? else {
+                return Ok(payload_too_large());
+            };
+
+            let calls = Calls::parse(&bytes);
+            if let Some(method) = limiter.exceeded_method(client, &calls) {
+                return Ok(limiter.rejected(client, method, calls.id.clone()));
+            }
+
+            inner
+                .call(Request::from_parts(parts, Body::from(bytes)))
+                .await
+        })
+    }
+}
+
+/// Middleware of the RPC server that answers the requests that aren't handled in time with
+/// `503 Service Unavailable`. The WebSocket connections aren't limited, as they are upgraded
+/// before the calls.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeoutLayer {
+    timeout: Option<Duration>,
+}
+
+impl TimeoutLayer {
+    /// Creates the layer, which doesn't limit the time of the requests without the `timeout`.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self { timeout }
+    }
+}
+
+impl<S> Layer<S> for TimeoutLayer {
+    type Service = Timeout<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Timeout {
+            inner,
+            timeout: self.timeout,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timeout<S> {
+    inner: S,
+    timeout: Option<Duration>,
+}
+
+impl<S> Service<Request<Body>> for Timeout<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let future = self.inner.call(request);
+        let Some(timeout) = self.timeout else {
+            return Box::pin(future);
+        };
+
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, future).await {
+                Ok(result) => result,
+                Err(_) => {
+                    let mut response = Response::new(Body::from("Request timed out"));
+                    *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+                    Ok(response)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::{CONNECTION, UPGRADE};
+
+    use super::*;
+
+    fn limiter(trust_forwarded_for: bool) -> RateLimiter {
+        RateLimiter {
+            config: RateLimitConfig {
+                requests: Some(Quota {
+                    requests_per_sec: 1.0,
+                    burst: 1,
+                }),
+                methods: HashMap::new(),
+                trust_forwarded_for,
+            },
+            buckets: Mutex::new(HashMap::new()),
+            rejected_calls: RejectedCalls::default(),
+        }
+    }
+
+    fn request(peer: &str, forwarded_for: Option<&str>) -> Request<()> {
+        let mut builder = Request::builder().header(PEER_ADDR, peer);
+        if let Some(forwarded_for) = forwarded_for {
+            builder = builder.header(X_FORWARDED_FOR, forwarded_for);
+        }
+
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn test_client_is_the_peer() {
+        let limiter = limiter(false);
+
+        let forged = request("10.0.0.1", Some("1.1.1.1"));
+        assert_eq!(limiter.client(&forged), Some("10.0.0.1".parse().unwrap()));
+        assert_eq!(limiter.client(&Request::new(())), None);
+    }
+
+    #[test]
+    fn test_client_behind_trusted_proxy() {
+        let limiter = limiter(true);
+
+        let forwarded = request("127.0.0.1", Some("1.1.1.1, 2.2.2.2"));
+        assert_eq!(limiter.client(&forwarded), Some("2.2.2.2".parse().unwrap()));
+
+        let direct = request("127.0.0.1", None);
+        assert_eq!(limiter.client(&direct), Some("127.0.0.1".parse().unwrap()));
+    }
+
+    /// Server that answers all the requests with `200 OK`.
+    #[derive(Clone)]
+    struct Server;
+
+    impl Service<Request<Body>> for Server {
+        type Response = Response<Body>;
+        type Error = BoxError;
+        type Future = std::future::Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: Request<Body>) -> Self::Future {
+            std::future::ready(Ok(Response::new(Body::empty())))
+        }
+    }
+
+    fn service(config: RateLimitConfig) -> RateLimit<Server> {
+        RateLimitLayer::new(Some(config), RejectedCalls::default(), 1024).layer(Server)
+    }
+
+    fn call(peer: &str, upgrade: bool) -> Request<Body> {
+        let mut builder = Request::builder().header(PEER_ADDR, peer);
+        if upgrade {
+            builder = builder
+                .header(CONNECTION, "upgrade")
+                .header(UPGRADE, "websocket");
+        }
+
+        builder
+            .body(Body::from(r#"{"method":"provideyuvproof","id":1}"#))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_method_limits_over_http_only() {
+        let quota = Quota {
+            requests_per_sec: 1.0,
+            burst: 1,
+        };
+        let mut service = service(RateLimitConfig {
+            requests: None,
+            methods: HashMap::from([("provideyuvproof".to_string(), quota)]),
+            trust_forwarded_for: false,
+        });
+
+        let mut status = |request| {
+            let response = service.call(request);
+            async move { response.await.unwrap().status() }
+        };
+
+        assert_eq!(status(call("10.0.0.1", false)).await, StatusCode::OK);
+        assert_eq!(
+            status(call("10.0.0.1", false)).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        // The buckets of the methods are per client.
+        assert_eq!(status(call("10.0.0.2", false)).await, StatusCode::OK);
+        // The calls over WebSocket aren't seen, so the methods don't limit the connections.
+        assert_eq!(status(call("10.0.0.1", true)).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_request_limit_over_websocket() {
+        let mut service = service(RateLimitConfig {
+            requests: Some(Quota {
+                requests_per_sec: 1.0,
+                burst: 1,
+            }),
+            methods: HashMap::new(),
+            trust_forwarded_for: false,
+        });
+
+        let response = service.call(call("10.0.0.1", true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = service.call(call("10.0.0.1", true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_buckets_per_peer() {
+        let limiter = limiter(false);
+        let quota = limiter.config.requests.unwrap();
+
+        let alice = limiter.client(&request("10.0.0.1", None));
+        let bob = limiter.client(&request("10.0.0.2", None));
+
+        assert!(limiter.try_acquire((alice, None), &quota, 1));
+        assert!(!limiter.try_acquire((alice, None), &quota, 1));
+        assert!(limiter.try_acquire((bob, None), &quota, 1));
+    }
+}
@@ -0,0 +1,152 @@
+//! Front of the RPC server that serves the connections accepted on the public address, plain or
+//! [TLS](crate::tls) ones.
+//!
+//! The middlewares of the server don't see the address of the peer, so the front serves the HTTP
+//! connections itself, marks each request with the [`PEER_ADDR`] header and forwards it to the
+//! server on the loopback address. The header sent by the clients is overwritten. The WebSocket
+//! connections are forwarded after the upgrade is accepted by the server.
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+
+use hyper::{
+    client::HttpConnector,
+    header::HeaderValue,
+    server::conn::Http,
+    service::service_fn,
+    upgrade::{self, OnUpgrade},
+    Body, Client, Request, Response, StatusCode, Uri,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream},
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::request::{is_upgrade_request, BoxError};
+
+/// Header with the IP address of the peer the request is received from.
+pub(crate) const PEER_ADDR: &str = "x-yuv-peer-addr";
+
+/// Client of the RPC server on the loopback address.
+pub(crate) type UpstreamClient = Client<HttpConnector, Body>;
+
+/// Accepts the connections with the `listener` and forwards their requests to the RPC server at
+/// the `upstream` address until cancelled. The `accept` prepares the accepted streams for HTTP,
+/// e.g. performs the TLS handshake.
+pub(crate) async fn run_proxy<A, F, IO>(
+    listener: TcpListener,
+    upstream: SocketAddr,
+    accept: A,
+    cancellation: CancellationToken,
+) where
+    A: Fn(TcpStream) -> F + Clone + Send + 'static,
+    F: Future<Output = io::Result<IO>> + Send,
+    IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let client = UpstreamClient::new();
+
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = cancellation.cancelled() => return,
+        };
+
+        let (stream, peer) = match accepted {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                tracing::warn!("Failed to accept the RPC connection: {}", err);
+                continue;
+            }
+        };
+
+        let accept = accept.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            let io = match accept(stream).await {
+                Ok(io) => io,
+                Err(err) => {
+                    tracing::debug!(%peer, "Failed to accept the RPC connection: {}", err);
+                    return;
+                }
+            };
+
+            if let Err(err) = serve_connection(io, peer, upstream, client).await {
+                tracing::debug!(%peer, "RPC connection is closed with error: {}", err);
+            }
+        });
+    }
+}
+
+/// Serves the HTTP requests of the connection with the `peer` by forwarding them to the server
+/// at the `upstream` address.
+async fn serve_connection<IO>(
+    io: IO,
+    peer: SocketAddr,
+    upstream: SocketAddr,
+    client: UpstreamClient,
+) -> Result<(), hyper::Error>
+where
+    IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let service = service_fn(move |request| forward(request, peer, upstream, client.clone()));
+
+    Http::new()
+        .serve_connection(io, service)
+        .with_upgrades()
+        .await
+}
+
+async fn forward(
+    mut request: Request<Body>,
+    peer: SocketAddr,
+    upstream: SocketAddr,
+    client: UpstreamClient,
+) -> Result<Response<Body>, BoxError> {
+    let downstream_upgrade = is_upgrade_request(&request).then(|| upgrade::on(&mut request));
+
+    let path = request
+        .uri()
+        .path_and_query()
+        .map_or("/", |path| path.as_str());
+    *request.uri_mut() = Uri::try_from(format!("http://{upstream}{path}"))?;
+    request.headers_mut().insert(
+        PEER_ADDR,
+        HeaderValue::from_str(&peer.ip().to_string()).expect("IP address is a valid header"),
+    );
+
+    let mut response = match client.request(request).await {
+        Ok(response) => response,
+        Err(err) => {
+            tracing::debug!(%peer, "Failed to forward the RPC request: {}", err);
+
+            let mut response = Response::new(Body::from("RPC server is not available"));
+            *response.status_mut() = StatusCode::BAD_GATEWAY;
+            return Ok(response);
+        }
+    };
+
+    if let Some(downstream) = downstream_upgrade {
+        if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+            let upstream = upgrade::on(&mut response);
+            tokio::spawn(tunnel(downstream, upstream, peer));
+        }
+    }
+
+    Ok(response)
+}
+
+/// Copies the frames of the upgraded connections in both directions until one of them is closed.
+async fn tunnel(downstream: OnUpgrade, upstream: OnUpgrade, peer: SocketAddr) {
+    let (mut downstream, mut upstream) = match tokio::try_join!(downstream, upstream) {
+        Ok(upgraded) => upgraded,
+        Err(err) => {
+            tracing::debug!(%peer, "Failed to upgrade the RPC connection: {}", err);
+            return;
+        }
+    };
+
+    if let Err(err) = tokio::io::copy_bidirectional(&mut downstream, &mut upstream).await {
+        tracing::debug!(%peer, "WebSocket connection is closed with error: {}", err);
+    }
+}
@@ -0,0 +1,108 @@
+//! Helpers of the middlewares that inspect the JSON-RPC calls of the HTTP requests.
+use std::error::Error as StdError;
+use std::future::Future;
+use std::pin::Pin;
+
+use hyper::{
+    body::HttpBody,
+    header::{CONNECTION, UPGRADE},
+    Body, Request, Response, StatusCode,
+};
+use serde_json::Value;
+
+pub(crate) type BoxError = Box<dyn StdError + Send + Sync + 'static>;
+
+pub(crate) type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Calls of the single or the batch JSON-RPC request.
+#[derive(Debug, Default)]
+pub(crate) struct Calls {
+    /// Methods of the calls in the order of the request.
+    pub methods: Vec<String>,
+    /// Id of the single call, `null` for the batch.
+    pub id: Value,
+}
+
+impl Calls {
+    /// Parses the calls of the request. Invalid requests have no calls, they are rejected by the
+    /// server.
+    pub fn parse(body: &[u8]) -> Self {
+        serde_json::from_slice::<Value>(body)
+            .map(|request| Self::from_value(&request))
+            .unwrap_or_default()
+    }
+
+    /// Gets the calls of the already parsed request.
+    pub fn from_value(request: &Value) -> Self {
+        let (calls, id) = match request {
+            Value::Array(calls) => (calls.iter().collect::<Vec<_>>(), Value::Null),
+            call => (vec![call], call.get("id").cloned().unwrap_or_default()),
+        };
+
+        let methods = calls
+            .into_iter()
+            .filter_map(|call| call.get("method").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect();
+
+        Self { methods, id }
+    }
+}
+
+/// Reads the body of the request, `None` if it's larger than `max_size` bytes.
+pub(crate) async fn read_body(
+    mut body: Body,
+    max_size: usize,
+) -> Result<Option<Vec<u8>>, BoxError> {
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > max_size {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(bytes))
+}
+
+pub(crate) fn payload_too_large() -> Response<Body> {
+    let mut response = Response::new(Body::from("Request is too large"));
+    *response.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+
+    response
+}
+
+pub(crate) fn is_upgrade_request<B>(request: &Request<B>) -> bool {
+    let has_header = |name, value: &str| {
+        request.headers().get_all(name).iter().any(|header| {
+            header
+                .to_str()
+                .is_ok_and(|header| header.to_ascii_lowercase().contains(value))
+        })
+    };
+
+    has_header(CONNECTION, "upgrade") || has_header(UPGRADE, "websocket")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_calls() {
+        let single = br#"{"jsonrpc":"2.0","method":"sendyuvtransaction","params":[],"id":1}"#;
+        let batch = br#"[{"method":"getyuvtransaction"},{"method":"provideyuvproof"}]"#;
+
+        let calls = Calls::parse(single);
+        assert_eq!(calls.methods, vec!["sendyuvtransaction"]);
+        assert_eq!(calls.id, Value::from(1));
+
+        let calls = Calls::parse(batch);
+        assert_eq!(calls.methods, vec!["getyuvtransaction", "provideyuvproof"]);
+        assert_eq!(calls.id, Value::Null);
+
+        assert!(Calls::parse(b"not json").methods.is_empty());
+    }
+}
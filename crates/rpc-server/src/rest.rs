@@ -0,0 +1,255 @@
+//! Read-only REST gateway to the RPC methods for the block explorers and frontends.
+//!
+//! Endpoints:
+//!
+//! - `GET /tx/{txid}` - the YUV transaction in JSON and its status, see `getyuvtransaction`;
+//! - `GET /txs/{page}` - the page of the attached YUV transactions, see `listyuvtransactions`;
+//! - `GET /chroma/{chroma}` - the information about the token, see `getchromainfo`;
+//! - `GET /chroma/{chroma}/supply` - the supply commitment of the token, see
+//!   `getsupplycommitment`;
+//! - `GET /pubkey/{pubkey}/utxos[/{chroma}]` - the unspent outputs of the key, optionally of the
+//!   token only, see `listutxosbypubkey`.
+//!
+//! The requests are handled by the same [`TransactionsController`] as the RPC ones, and the
+//! responses are the results of the corresponding RPC methods. Unknown transactions and tokens
+//! are answered with `404 Not Found`, the errors with `{"error": "<message>"}`.
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bitcoin::{secp256k1::PublicKey, Txid};
+use bitcoin_client::BitcoinRpcClient;
+use event_bus::EventBus;
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use jsonrpsee::types::{error::INVALID_REQUEST_CODE, ErrorObjectOwned};
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use yuv_pixels::Chroma;
+use yuv_rpc_api::transactions::{
+    GetRawYuvTransactionResponseJson, YuvTransactionStatus, YuvTransactionsRpcServer,
+    RESPONSE_TOO_LARGE_CODE, TX_PRUNED_CODE,
+};
+use yuv_storage::{
+    AddressIndexStorage, BlockIndexerStorage, ChromaInfoStorage, FrozenTxsStorage,
+    InvalidTxsStorage, MempoolEntryStorage, MempoolStorage, PagesStorage, PruningStorage,
+    SupplyTreeStorage, TransactionsStorage, TransferHistoryStorage, TxDependentsStorage,
+    WalletProofsStorage,
+};
+use yuv_types::network::Network;
+
+use crate::transactions::TransactionsController;
+
+pub struct RestConfig {
+    /// Address at which the gateway will listen for incoming connections.
+    pub address: SocketAddr,
+    /// Max number of items to request/process per incoming request.
+    pub max_items_per_request: usize,
+    /// Max size of outgoing response in kilobytes.
+    pub max_response_size_kb: u32,
+    /// Network the node runs on.
+    pub network: Network,
+    /// Whether the controller indexes the unspent outputs by the keys of their owners.
+    pub address_index: bool,
+}
+
+/// Runs YUV Node's REST gateway.
+pub async fn run_rest_server<TS, SS>(
+    RestConfig {
+        address,
+        max_items_per_request,
+        max_response_size_kb,
+        network,
+        address_index,
+    }: RestConfig,
+    txs_storage: TS,
+    state_storage: SS,
+    full_event_bus: EventBus,
+    bitcoin_client: Arc<BitcoinRpcClient>,
+    cancellation: CancellationToken,
+) -> eyre::Result<()>
+where
+    TS: TransactionsStorage
+        + PagesStorage
+        + SupplyTreeStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + TxDependentsStorage
+        + PruningStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    SS: FrozenTxsStorage
+        + ChromaInfoStorage
+        + MempoolEntryStorage
+        + MempoolStorage
+        + InvalidTxsStorage
+        + BlockIndexerStorage
+        + WalletProofsStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    let controller = Arc::new(
+        TransactionsController::new(
+            txs_storage,
+            full_event_bus,
+            state_storage,
+            bitcoin_client,
+            max_items_per_request,
+            network,
+        )
+        .with_max_response_size(max_response_size_kb as usize * 1024)
+        .with_address_index(address_index),
+    );
+
+    let make_service = make_service_fn(move |_| {
+        let controller = controller.clone();
+
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let controller = controller.clone();
+
+                async move {
+                    let response = handle_request(controller.as_ref(), request).await;
+
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+
+    Server::try_bind(&address)?
+        .serve(make_service)
+        .with_graceful_shutdown(cancellation.cancelled())
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_request<C: YuvTransactionsRpcServer>(
+    controller: &C,
+    request: Request<Body>,
+) -> Response<Body> {
+    if request.method() != Method::GET {
+        return error_response(StatusCode::METHOD_NOT_ALLOWED, "Only GET is allowed");
+    }
+
+    let segments = request
+        .uri()
+        .path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    match segments.as_slice() {
+        ["tx", txid] => {
+            let txid = match Txid::from_str(txid) {
+                Ok(txid) => txid,
+                Err(err) => return error_response(StatusCode::BAD_REQUEST, err),
+            };
+
+            match controller.get_yuv_transaction(txid).await {
+                Ok(response) if response.status == YuvTransactionStatus::None => {
+                    error_response(StatusCode::NOT_FOUND, "Transaction is not found")
+                }
+                Ok(response) => json_response(&GetRawYuvTransactionResponseJson::new(
+                    response.status,
+                    response.data,
+                )),
+                Err(err) => rpc_error_response(err),
+            }
+        }
+        ["txs", page] => {
+            let page = match u64::from_str(page) {
+                Ok(page) => page,
+                Err(err) => return error_response(StatusCode::BAD_REQUEST, err),
+            };
+
+            match controller.list_yuv_transactions(page).await {
+                Ok(txs) => json_response(&txs),
+                Err(err) => rpc_error_response(err),
+            }
+        }
+        ["chroma", chroma] => {
+            let chroma = match Chroma::from_str(chroma) {
+                Ok(chroma) => chroma,
+                Err(err) => return error_response(StatusCode::BAD_REQUEST, err),
+            };
+
+            match controller.get_chroma_info(chroma).await {
+                Ok(Some(chroma_info)) => json_response(&chroma_info),
+                Ok(None) => error_response(StatusCode::NOT_FOUND, "Chroma is not found"),
+                Err(err) => rpc_error_response(err),
+            }
+        }
+        ["chroma", chroma, "supply"] => {
+            let chroma = match Chroma::from_str(chroma) {
+                Ok(chroma) => chroma,
+                Err(err) => return error_response(StatusCode::BAD_REQUEST, err),
+            };
+
+            match controller.get_supply_commitment(chroma).await {
+                Ok(commitment) => json_response(&commitment),
+                Err(err) => rpc_error_response(err),
+            }
+        }
+        ["pubkey", pubkey, "utxos", chroma @ ..] if chroma.len() <= 1 => {
+            let pubkey = match PublicKey::from_str(pubkey) {
+                Ok(pubkey) => pubkey,
+                Err(err) => return error_response(StatusCode::BAD_REQUEST, err),
+            };
+
+            let chroma = match chroma.first().map(|chroma| Chroma::from_str(chroma)) {
+                Some(Ok(chroma)) => Some(chroma),
+                Some(Err(err)) => return error_response(StatusCode::BAD_REQUEST, err),
+                None => None,
+            };
+
+            match controller.list_utxos_by_pubkey(pubkey, chroma).await {
+                Ok(utxos) => json_response(&utxos),
+                Err(err) => rpc_error_response(err),
+            }
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Unknown endpoint"),
+    }
+}
+
+fn json_response<T: Serialize>(body: &T) -> Response<Body> {
+    match serde_json::to_vec(body) {
+        Ok(body) => response(StatusCode::OK, body),
+        Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, err),
+    }
+}
+
+/// Converts the error of the RPC method to the response with the matching status code.
+fn rpc_error_response(err: ErrorObjectOwned) -> Response<Body> {
+    let status = match err.code() {
+        INVALID_REQUEST_CODE => StatusCode::BAD_REQUEST,
+        RESPONSE_TOO_LARGE_CODE => StatusCode::PAYLOAD_TOO_LARGE,
+        TX_PRUNED_CODE => StatusCode::GONE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    error_response(status, err.message())
+}
+
+fn error_response(status: StatusCode, message: impl ToString) -> Response<Body> {
+    let body = serde_json::json!({ "error": message.to_string() });
+
+    response(status, body.to_string().into_bytes())
+}
+
+fn response(status: StatusCode, body: Vec<u8>) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("response parts are valid")
+}
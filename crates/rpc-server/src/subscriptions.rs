@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use bitcoin::secp256k1::{PublicKey, XOnlyPublicKey};
+use event_bus::{typeid, EventBus};
+use jsonrpsee::{core::SubscriptionResult, PendingSubscriptionSink, SubscriptionMessage};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+
+use yuv_pixels::PixelProof;
+use yuv_rpc_api::{
+    subscriptions::{UnconfirmedTransferNotification, YuvSubscriptionsRpcServer},
+    transactions::{YuvTransactionResponse, YuvTransactionStatus},
+};
+use yuv_types::{
+    announcements::{ChromaAnnouncement, FreezeAnnouncement},
+    RpcSubscriptionMessage, YuvTransaction,
+};
+
+/// Default number of notifications that could be buffered for each subscription kind
+/// before the slow subscribers start to lag.
+pub const DEFAULT_SUBSCRIPTION_CAPACITY: usize = 1000;
+
+/// Controller for the RPC subscriptions.
+///
+/// Receives [`RpcSubscriptionMessage`]s from the event bus and broadcasts them to all
+/// the subscribed clients.
+#[derive(Clone)]
+pub struct SubscriptionsController {
+    /// Sender of the attached transactions notifications.
+    attached_txs: broadcast::Sender<YuvTransactionResponse>,
+    /// Sender of the accepted freeze announcements notifications.
+    freezes: broadcast::Sender<FreezeAnnouncement>,
+    /// Sender of the accepted chroma announcements notifications.
+    chroma_announcements: broadcast::Sender<ChromaAnnouncement>,
+    /// Sender of the isolated checked transfers notifications, which are filtered by the watch
+    /// keys of each subscriber.
+    unconfirmed_transfers: broadcast::Sender<YuvTransaction>,
+    /// Sender of all the received events, for the services outside of the RPC server.
+    events: broadcast::Sender<RpcSubscriptionMessage>,
+}
+
+impl SubscriptionsController {
+    pub fn new(capacity: usize) -> Self {
+        let (attached_txs, _) = broadcast::channel(capacity);
+        let (freezes, _) = broadcast::channel(capacity);
+        let (chroma_announcements, _) = broadcast::channel(capacity);
+        let (unconfirmed_transfers, _) = broadcast::channel(capacity);
+        let (events, _) = broadcast::channel(capacity);
+
+        Self {
+            attached_txs,
+            freezes,
+            chroma_announcements,
+            unconfirmed_transfers,
+            events,
+        }
+    }
+
+    /// Listens to the [`RpcSubscriptionMessage`]s from the event bus and forwards them to
+    /// the subscribers until cancellation is received.
+    pub async fn run(self, full_event_bus: EventBus, cancellation: CancellationToken) {
+        let event_bus = full_event_bus
+            .extract(&typeid![], &typeid![RpcSubscriptionMessage])
+            .expect("event channels must be presented");
+
+        let events = event_bus.subscribe::<RpcSubscriptionMessage>();
+
+        loop {
+            tokio::select! {
+                event_received = events.recv() => {
+                    let Ok(event) = event_received else {
+                        tracing::trace!("All incoming event senders are dropped");
+                        return;
+                    };
+
+                    self.handle_event(event);
+                }
+                _ = cancellation.cancelled() => {
+                    tracing::trace!("Cancellation received, stopping subscriptions controller");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns the receiver of the attached transactions notifications, for the subscribers
+    /// outside of the JSON RPC server.
+    pub fn attached_txs(&self) -> broadcast::Receiver<YuvTransactionResponse> {
+        self.attached_txs.subscribe()
+    }
+
+    /// Returns the receiver of all the events the subscriptions are built from, for the services
+    /// that need the events the clients can't subscribe to, e.g. the detached transactions.
+    pub fn events(&self) -> broadcast::Receiver<RpcSubscriptionMessage> {
+        self.events.subscribe()
+    }
+
+    /// Broadcasts the event to the subscribers. Sending fails only if there are no
+    /// subscribers, so the errors are ignored.
+    fn handle_event(&self, event: RpcSubscriptionMessage) {
+        let _ = self.events.send(event.clone());
+
+        match event {
+            RpcSubscriptionMessage::AttachedTxs(txs) => {
+                for tx in txs {
+                    let _ = self.attached_txs.send(tx.into());
+                }
+            }
+            RpcSubscriptionMessage::Freezes(freezes) => {
+                for freeze in freezes {
+                    let _ = self.freezes.send(freeze);
+                }
+            }
+            RpcSubscriptionMessage::ChromaAnnouncements(announcements) => {
+                for announcement in announcements {
+                    let _ = self.chroma_announcements.send(announcement);
+                }
+            }
+            RpcSubscriptionMessage::UnconfirmedTransfers(txs) => {
+                for tx in txs {
+                    let _ = self.unconfirmed_transfers.send(tx);
+                }
+            }
+            RpcSubscriptionMessage::DetachedTxs(_) | RpcSubscriptionMessage::InvalidatedTxs(_) => {}
+        }
+    }
+}
+
+#[async_trait]
+impl YuvSubscriptionsRpcServer for SubscriptionsController {
+    async fn subscribe_attached_txs(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        pipe_from_broadcast(pending, self.attached_txs.subscribe()).await
+    }
+
+    async fn subscribe_freezes(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        pipe_from_broadcast(pending, self.freezes.subscribe()).await
+    }
+
+    async fn subscribe_chroma_announcements(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        pipe_from_broadcast(pending, self.chroma_announcements.subscribe()).await
+    }
+
+    async fn subscribe_unconfirmed_transfers(
+        &self,
+        pending: PendingSubscriptionSink,
+        watch_keys: Vec<PublicKey>,
+    ) -> SubscriptionResult {
+        let watch_keys = watch_keys
+            .iter()
+            .map(|key| key.x_only_public_key().0)
+            .collect::<HashSet<_>>();
+
+        pipe_filtered_from_broadcast(pending, self.unconfirmed_transfers.subscribe(), move |tx| {
+            let vouts = watched_vouts(&tx, &watch_keys);
+            if vouts.is_empty() {
+                return None;
+            }
+
+            Some(UnconfirmedTransferNotification {
+                status: YuvTransactionStatus::WaitingMined,
+                vouts,
+                tx: tx.into(),
+            })
+        })
+        .await
+    }
+}
+
+/// Returns the outputs of the transaction that belong to any of the watched keys.
+fn watched_vouts(tx: &YuvTransaction, watch_keys: &HashSet<XOnlyPublicKey>) -> Vec<u32> {
+    let Some(output_proofs) = tx.tx_type.output_proofs() else {
+        return Vec::new();
+    };
+
+    let mut vouts = output_proofs
+        .iter()
+        .filter(|(_, proof)| {
+            proof_keys(proof)
+                .iter()
+                .any(|key| watch_keys.contains(&key.x_only_public_key().0))
+        })
+        .map(|(vout, _)| *vout)
+        .collect::<Vec<_>>();
+    vouts.sort_unstable();
+
+    vouts
+}
+
+/// Returns the keys the output with the proof is spendable by. Outputs of the other types are
+/// not matched against the watch keys.
+fn proof_keys(proof: &PixelProof) -> Vec<PublicKey> {
+    match proof {
+        PixelProof::Sig(proof) => vec![proof.inner_key],
+        PixelProof::Multisig(proof) => proof.inner_keys.clone(),
+        PixelProof::Musig(proof) => proof.inner_keys.clone(),
+        PixelProof::Timelock(proof) => vec![proof.inner_key],
+        PixelProof::Htlc(proof) => vec![proof.data.recipient_pubkey, proof.data.sender_pubkey],
+        _ => Vec::new(),
+    }
+}
+
+/// Accepts the subscription and sends every item received from the broadcast channel to
+/// the subscriber until either the subscriber disconnects or the channel is closed.
+async fn pipe_from_broadcast<T>(
+    pending: PendingSubscriptionSink,
+    rx: broadcast::Receiver<T>,
+) -> SubscriptionResult
+where
+    T: Serialize + Clone,
+{
+    pipe_filtered_from_broadcast(pending, rx, Some).await
+}
+
+/// Same as [`pipe_from_broadcast`], but sends only the items for which `filter_map` returns
+/// `Some`.
+async fn pipe_filtered_from_broadcast<T, U, F>(
+    pending: PendingSubscriptionSink,
+    mut rx: broadcast::Receiver<T>,
+    filter_map: F,
+) -> SubscriptionResult
+where
+    T: Clone,
+    U: Serialize,
+    F: Fn(T) -> Option<U>,
+{
+    let sink = pending.accept().await?;
+
+    loop {
+        tokio::select! {
+            _ = sink.closed() => return Ok(()),
+            item = rx.recv() => {
+                let item = match item {
+                    Ok(item) => item,
+                    // Slow subscriber has missed some notifications, continue from the oldest
+                    // available one.
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            method = sink.method_name(),
+                            "Subscriber is lagging, skipped {skipped} notifications",
+                        );
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return Ok(()),
+                };
+
+                let Some(item) = filter_map(item) else {
+                    continue;
+                };
+
+                sink.send(SubscriptionMessage::from_json(&item)?).await?;
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! TLS termination of the RPC server's connections.
+//!
+//! The RPC server listens on the loopback address, and the TLS connections accepted on the
+//! public address are served by the [front](crate::proxy) after the handshake, so both HTTP and
+//! WebSocket are served over TLS.
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+use eyre::{bail, Context};
+use tokio_rustls::{
+    rustls::{self, Certificate, PrivateKey},
+    TlsAcceptor,
+};
+
+use crate::TlsConfig;
+
+/// Loads the certificate chain and the private key, and creates the acceptor of the TLS
+/// connections with them.
+pub(crate) fn load_acceptor(config: &TlsConfig) -> eyre::Result<TlsAcceptor> {
+    let certs = rustls_pemfile::certs(&mut open(&config.cert_path)?)
+        .wrap_err("Failed to read the TLS certificates")?
+        .into_iter()
+        .map(Certificate)
+        .collect::<Vec<_>>();
+    if certs.is_empty() {
+        bail!("No certificates found in {}", config.cert_path.display());
+    }
+
+    let key = load_private_key(&config.key_path)?;
+
+    let server_config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .wrap_err("Invalid TLS certificate or key")?;
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn load_private_key(path: &Path) -> eyre::Result<PrivateKey> {
+    let mut reader = open(path)?;
+
+    while let Some(item) =
+        rustls_pemfile::read_one(&mut reader).wrap_err("Failed to read the TLS private key")?
+    {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => continue,
+        }
+    }
+
+    bail!("No private key found in {}", path.display())
+}
+
+fn open(path: &Path) -> eyre::Result<BufReader<File>> {
+    let file = File::open(path).wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+
+    Ok(BufReader::new(file))
+}
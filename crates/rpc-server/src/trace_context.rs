@@ -0,0 +1,70 @@
+//! Propagation of the [W3C trace context] of the RPC requests, so the spans of the request's
+//! handling continue the caller's trace in the exported traces.
+//!
+//! [W3C trace context]: https://www.w3.org/TR/trace-context/
+use std::task::{Context, Poll};
+
+use hyper::{HeaderMap, Request};
+use opentelemetry::{global, propagation::Extractor};
+use tower::{Layer, Service};
+use tracing::{instrument::Instrumented, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Middleware of the RPC server that handles each request in the `rpc_request` span, whose
+/// parent is the trace context from the request's `traceparent` header. Requests without the
+/// header start the new trace.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceContextLayer;
+
+impl<S> Layer<S> for TraceContextLayer {
+    type Service = TraceContext<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceContext { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceContext<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<B>> for TraceContext<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let parent = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(request.headers()))
+        });
+
+        let span = tracing::info_span!(
+            "rpc_request",
+            http.method = request.method().as_str(),
+            http.path = request.uri().path(),
+        );
+        span.set_parent(parent);
+
+        self.inner.call(request).instrument(span)
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
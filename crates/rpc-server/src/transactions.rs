@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use bitcoin::{Amount, BlockHash, OutPoint, Txid};
+use bitcoin::{secp256k1::PublicKey, Amount, BlockHash, OutPoint, Txid};
 use bitcoin_client::BitcoinRpcApi;
 use event_bus::{typeid, EventBus};
 use jsonrpsee::{
@@ -9,27 +9,89 @@ use jsonrpsee::{
         ErrorObject, ErrorObjectOwned,
     },
 };
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 use std::sync::Arc;
-use yuv_pixels::Chroma;
+use tokio::sync::Mutex;
+use yuv_pixels::{Chroma, PixelProof};
 use yuv_rpc_api::transactions::{
-    EmulateYuvTransactionResponse, GetRawYuvTransactionResponseHex,
-    GetRawYuvTransactionResponseJson, ProvideYuvProofRequest, YuvTransactionResponse,
-    YuvTransactionStatus, YuvTransactionsRpcServer,
+    ConvertYuvTransactionResponse, CreateSnapshotResponse, EmulateYuvTransactionResponse,
+    EncodedYuvTransaction, EstimateYuvTransferResponse, GetActivationStatusResponse,
+    GetApiVersionResponse, GetCapabilitiesResponse, GetChromaInfoResponse,
+    GetInclusionProofResponse, GetNodeStatusResponse, GetRawYuvTransactionResponseHex,
+    GetRawYuvTransactionResponseJson, GetSupplyCommitmentResponse, ListMempoolResponse,
+    ListTransfersResponse, MempoolEntryResponse, ProofsDedupMetrics, ProvideYuvProofRequest,
+    PubkeyUtxoResponse, RpcFeatures, RpcLimits, SizeLimitExceeded, TxGraphEdge, TxGraphNode,
+    TxGraphResponse, TxPruned, YuvTransactionProofsResponse, YuvTransactionResponse,
+    YuvTransactionStatus, YuvTransactionsRpcServer, YuvTxOutFreezeResponse,
+    RESPONSE_TOO_LARGE_CODE, RPC_API_VERSION, TX_PRUNED_CODE,
 };
+use yuv_scheduler::JobStatuses;
 use yuv_storage::{
-    ChromaInfoStorage, FrozenTxsStorage, KeyValueError, MempoolEntryStorage, PagesStorage,
-    TransactionsStorage,
+    AddressIndexStorage, BlockIndexerStorage, ChromaInfoStorage, FrozenTxsStorage,
+    InvalidTxsStorage, KeyValueError, MempoolEntryStorage, MempoolStorage, PagesStorage,
+    ProofsDedupStatsStorage, PruningStorage, Snapshotter, SupplyTreeStorage, TransactionsStorage,
+    TransferHistoryEntry, TransferHistoryStorage, TxDependentsStorage, TxRejection, TxsEncoding,
+    WalletProofsStorage,
+};
+use yuv_tx_check::{
+    check_transaction, find_continuation_outputs, partial_freeze,
+    verify_yuv_transaction_with_parents, CheckError, PartialFreeze,
 };
-use yuv_tx_check::{check_transaction, CheckError};
 use yuv_types::{
-    announcements::ChromaInfo, ControllerMessage, ProofMap, YuvTransaction, YuvTxType,
+    activation::{activation_statuses, ProtocolFeature},
+    bundle::ProofBundle,
+    network::Network,
+    supply::supply_outputs,
+    ControllerMessage, ProofMap, YuvTransaction, YuvTxType,
 };
 
+use crate::limits::RejectedCalls;
+
+/// Default max number of transactions that could be requested in one batch.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
+/// Default max size of the response in bytes.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Default max number of the parents sent along with the transaction.
+pub const DEFAULT_MAX_BUNDLE_PARENTS: usize = 25;
+
+/// Default max size of the transaction with its parents in bytes.
+pub const DEFAULT_MAX_BUNDLE_SIZE: usize = 1024 * 1024;
+
+/// Confirmation target in blocks of the fee rate the transfers are estimated with.
+pub const FEE_ESTIMATE_CONF_TARGET: u16 = 6;
+
+/// Fee rate in sat/vB used if the Bitcoin node can't estimate it, e.g. on regtest.
+const MIN_FEE_RATE: u64 = 1;
+
+/// Virtual size of the version, locktime, segwit marker and counts of the transaction.
+const TX_OVERHEAD_VSIZE: u64 = 11;
+
+/// Virtual size of the P2WPKH input.
+const P2WPKH_INPUT_VSIZE: u64 = 68;
+
+/// Virtual size of the P2WPKH output.
+const P2WPKH_OUTPUT_VSIZE: u64 = 31;
+
+/// Smallest non-dust value of the P2WPKH output at the 3 sat/vB dust fee rate, the same the
+/// dev-kit puts into the token outputs.
+const P2WPKH_MIN_NON_DUST: u64 = 295;
+
 // TODO: Rename to "RpcController"
 /// Controller for transactions from RPC.
 pub struct TransactionsController<TransactionsStorage, StateStorage, BitcoinClient> {
     /// Max items per request
     max_items_per_request: usize,
+    /// Max number of transactions per batch request.
+    max_batch_size: usize,
+    /// Max size of the JSON serialized response in bytes.
+    max_response_size: usize,
+    /// Max number of the parents sent along with the transaction.
+    max_bundle_parents: usize,
+    /// Max size of the transaction with its parents in bytes.
+    max_bundle_size: usize,
     /// Internal storage of transactions.
     txs_storage: TransactionsStorage,
     /// Internal state storage.
@@ -38,6 +100,23 @@ pub struct TransactionsController<TransactionsStorage, StateStorage, BitcoinClie
     event_bus: EventBus,
     /// Bitcoin RPC Client.
     bitcoin_client: Arc<BitcoinClient>,
+    /// Network the node runs on. Determines the activation heights of the protocol features.
+    network: Network,
+    /// Statuses of the node's scheduled jobs.
+    job_statuses: JobStatuses,
+    /// Whether the controller indexes the unspent outputs by the keys of their owners.
+    address_index: bool,
+    /// Whether the controller records the transfer history of the owners.
+    history_index: bool,
+    /// Creates the snapshots of the storage, `None` if the storage backend doesn't support them.
+    snapshotter: Option<Snapshotter>,
+    /// Calls rejected by the rate limits of the server.
+    rejected_calls: RejectedCalls,
+    /// Whether the wallet methods are served, which requires the wallet keys to authenticate
+    /// the tenants.
+    wallet_methods: bool,
+    /// Serializes the updates of the wallets' proof lists, as they are read-modify-write.
+    wallet_proofs_lock: Arc<Mutex<()>>,
 }
 
 impl<TS, SS, BC> TransactionsController<TS, SS, BC>
@@ -52,6 +131,7 @@ where
         state_storage: SS,
         bitcoin_client: Arc<BC>,
         max_items_per_request: usize,
+        network: Network,
     ) -> Self {
         let event_bus = full_event_bus
             .extract(&typeid![ControllerMessage], &typeid![])
@@ -59,17 +139,98 @@ where
 
         Self {
             max_items_per_request,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            max_bundle_parents: DEFAULT_MAX_BUNDLE_PARENTS,
+            max_bundle_size: DEFAULT_MAX_BUNDLE_SIZE,
             txs_storage: storage,
             event_bus,
             state_storage,
             bitcoin_client,
+            network,
+            job_statuses: JobStatuses::default(),
+            address_index: false,
+            history_index: false,
+            snapshotter: None,
+            rejected_calls: RejectedCalls::default(),
+            wallet_methods: false,
+            wallet_proofs_lock: Arc::new(Mutex::new(())),
         }
     }
+
+    /// Sets max number of transactions per batch request.
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Sets max size of the JSON serialized response in bytes.
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Sets max number of the parents sent along with the transaction.
+    pub fn with_max_bundle_parents(mut self, max_bundle_parents: usize) -> Self {
+        self.max_bundle_parents = max_bundle_parents;
+        self
+    }
+
+    /// Sets max size of the transaction with its parents in bytes.
+    pub fn with_max_bundle_size(mut self, max_bundle_size: usize) -> Self {
+        self.max_bundle_size = max_bundle_size;
+        self
+    }
+
+    /// Sets the statuses of the scheduled jobs to report in `getnodestatus`.
+    pub fn with_job_statuses(mut self, job_statuses: JobStatuses) -> Self {
+        self.job_statuses = job_statuses;
+        self
+    }
+
+    /// Sets whether the address index is enabled, so `listutxosbypubkey` could be served.
+    pub fn with_address_index(mut self, enabled: bool) -> Self {
+        self.address_index = enabled;
+        self
+    }
+
+    /// Sets whether the history index is enabled, so `getbalanceat` and `listtransfers` could
+    /// be served.
+    pub fn with_history_index(mut self, enabled: bool) -> Self {
+        self.history_index = enabled;
+        self
+    }
+
+    /// Sets the snapshotter of the storage, so `createsnapshot` could be served.
+    pub fn with_snapshotter(mut self, snapshotter: Option<Snapshotter>) -> Self {
+        self.snapshotter = snapshotter;
+        self
+    }
+
+    /// Sets the counters of the calls rejected by the rate limits, reported by `getnodestatus`.
+    pub fn with_rejected_calls(mut self, rejected_calls: RejectedCalls) -> Self {
+        self.rejected_calls = rejected_calls;
+        self
+    }
+
+    /// Sets whether the wallet methods are served. They must be enabled only if the requests are
+    /// authenticated, otherwise any client could act on behalf of any wallet.
+    pub fn with_wallet_methods(mut self, enabled: bool) -> Self {
+        self.wallet_methods = enabled;
+        self
+    }
 }
 
 impl<TS, SS, BC> TransactionsController<TS, SS, BC>
 where
-    TS: TransactionsStorage + PagesStorage + Send + Sync + 'static,
+    TS: TransactionsStorage
+        + PagesStorage
+        + TransferHistoryStorage
+        + TxDependentsStorage
+        + PruningStorage
+        + Send
+        + Sync
+        + 'static,
     SS: FrozenTxsStorage + ChromaInfoStorage + Send + Sync + 'static,
     BC: BitcoinRpcApi + Send + Sync + 'static,
 {
@@ -90,13 +251,327 @@ where
 
         Ok(())
     }
+
+    /// Returns an error with the measured size and the limit if the JSON serialized response
+    /// doesn't fit into the response size limit, so the client could split the request.
+    fn check_response_size<T: serde::Serialize>(&self, response: &T) -> RpcResult<()> {
+        let size = serde_json::to_vec(response)
+            .map_err(|err| {
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    err.to_string(),
+                    Option::<Vec<u8>>::None,
+                )
+            })?
+            .len();
+
+        if size > self.max_response_size {
+            return Err(self.response_too_large(size));
+        }
+
+        Ok(())
+    }
+
+    fn response_too_large(&self, size: usize) -> ErrorObjectOwned {
+        ErrorObject::owned(
+            RESPONSE_TOO_LARGE_CODE,
+            format!(
+                "Response is too large: size: {} limit: {}",
+                size, self.max_response_size
+            ),
+            Some(SizeLimitExceeded {
+                size,
+                limit: self.max_response_size,
+            }),
+        )
+    }
+
+    /// Returns the attached transaction, or an error if it was pruned, so the client could tell
+    /// the removed history from the unknown transactions.
+    async fn get_attached_tx(&self, txid: &Txid) -> RpcResult<Option<YuvTransaction>> {
+        let storage_error = |e: KeyValueError| {
+            ErrorObject::owned(INTERNAL_ERROR_CODE, e.to_string(), Option::<Vec<u8>>::None)
+        };
+
+        let tx = self
+            .txs_storage
+            .get_yuv_tx(txid)
+            .await
+            .map_err(storage_error)?;
+        if tx.is_some() {
+            return Ok(tx);
+        }
+
+        let pruned = self
+            .txs_storage
+            .get_pruned_tx(txid)
+            .await
+            .map_err(storage_error)?;
+
+        match pruned {
+            Some(spent_height) => Err(ErrorObject::owned(
+                TX_PRUNED_CODE,
+                format!(
+                    "Transaction {txid} is pruned, its outputs are spent at height {spent_height}"
+                ),
+                Some(TxPruned {
+                    txid: *txid,
+                    spent_height,
+                }),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns an error if the wallet methods aren't served by the node.
+    fn check_wallet_methods(&self) -> RpcResult<()> {
+        if !self.wallet_methods {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                "Wallet methods are disabled on the node, as the authentication is not configured",
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the transfer history of the key, or an error if the history index is disabled.
+    async fn transfer_history(
+        &self,
+        pubkey: &PublicKey,
+        chroma: &Chroma,
+    ) -> RpcResult<Vec<TransferHistoryEntry>> {
+        if !self.history_index {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                "History index is disabled on the node",
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        self.txs_storage
+            .get_transfer_history(chroma, &pubkey.x_only_public_key().0)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get transfer history: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })
+    }
+
+    /// Walks the graph of the attached transactions from the `txid` in the `direction` up to
+    /// the `depth` levels or the max items per request.
+    async fn tx_graph(
+        &self,
+        txid: Txid,
+        depth: u32,
+        direction: GraphDirection,
+    ) -> RpcResult<Option<TxGraphResponse>> {
+        let storage_error = |e: KeyValueError| {
+            tracing::error!("Failed to get the transactions graph: {e}");
+            ErrorObject::owned(
+                INTERNAL_ERROR_CODE,
+                "Storage is not available",
+                Option::<Vec<u8>>::None,
+            )
+        };
+
+        let Some(root) = self.get_attached_tx(&txid).await? else {
+            return Ok(None);
+        };
+
+        let mut response = TxGraphResponse {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            missing: Vec::new(),
+            truncated: false,
+        };
+        // The children are loaded to find the edges, so they aren't loaded again.
+        let mut loaded = HashMap::from([(txid, root)]);
+        let mut visited = HashSet::from([txid]);
+        let mut queue = VecDeque::from([(txid, 0)]);
+
+        while let Some((txid, tx_depth)) = queue.pop_front() {
+            if response.nodes.len() == self.max_items_per_request {
+                response.truncated = true;
+                break;
+            }
+
+            let tx = match loaded.remove(&txid) {
+                Some(tx) => tx,
+                None => match self
+                    .txs_storage
+                    .get_yuv_tx(&txid)
+                    .await
+                    .map_err(storage_error)?
+                {
+                    Some(tx) => tx,
+                    None => {
+                        response.missing.push(txid);
+                        continue;
+                    }
+                },
+            };
+
+            response.nodes.push(TxGraphNode {
+                txid,
+                kind: (&tx.tx_type).into(),
+                depth: tx_depth,
+            });
+
+            let children = match direction {
+                GraphDirection::Ancestors => Vec::new(),
+                GraphDirection::Descendants => self
+                    .txs_storage
+                    .get_tx_dependents(&txid)
+                    .await
+                    .map_err(storage_error)?,
+            };
+
+            if tx_depth == depth {
+                response.truncated |= match direction {
+                    GraphDirection::Ancestors => !spending_edges(&tx).is_empty(),
+                    GraphDirection::Descendants => !children.is_empty(),
+                };
+                continue;
+            }
+
+            let edges = match direction {
+                GraphDirection::Ancestors => spending_edges(&tx),
+                GraphDirection::Descendants => {
+                    let mut edges = Vec::new();
+                    for child_id in children {
+                        let Some(child) = self
+                            .txs_storage
+                            .get_yuv_tx(&child_id)
+                            .await
+                            .map_err(storage_error)?
+                        else {
+                            continue;
+                        };
+
+                        edges.extend(
+                            spending_edges(&child)
+                                .into_iter()
+                                .filter(|edge| edge.parent == txid),
+                        );
+                        if !visited.contains(&child_id) {
+                            loaded.insert(child_id, child);
+                        }
+                    }
+
+                    edges
+                }
+            };
+
+            for edge in edges {
+                let next = match direction {
+                    GraphDirection::Ancestors => edge.parent,
+                    GraphDirection::Descendants => edge.child,
+                };
+                if visited.insert(next) {
+                    queue.push_back((next, tx_depth + 1));
+                }
+
+                response.edges.push(edge);
+            }
+        }
+
+        self.check_response_size(&response)?;
+
+        Ok(Some(response))
+    }
+
+    /// Collects the transaction of the output and all its ancestors back to the issuances, with
+    /// the parents before their children.
+    async fn proof_bundle(&self, outpoint: OutPoint) -> RpcResult<Option<ProofBundle>> {
+        let Some(tx) = self.get_attached_tx(&outpoint.txid).await? else {
+            return Ok(None);
+        };
+        let has_output = tx
+            .tx_type
+            .output_proofs()
+            .is_some_and(|proofs| proofs.contains_key(&outpoint.vout));
+        if !has_output {
+            return Ok(None);
+        }
+
+        // The bundle is returned in hex, which is twice as large as the encoded transactions.
+        let mut size = tx.consensus_size() * 2;
+        let mut loaded = HashMap::from([(outpoint.txid, tx)]);
+        let mut visited = HashSet::from([outpoint.txid]);
+        let mut txs = Vec::new();
+
+        // Depth-first walk, the transaction is added after all its parents are.
+        let mut stack = vec![(outpoint.txid, false)];
+        while let Some((txid, parents_added)) = stack.pop() {
+            if parents_added {
+                txs.extend(loaded.remove(&txid));
+                continue;
+            }
+
+            if !loaded.contains_key(&txid) {
+                let Some(tx) = self.get_attached_tx(&txid).await? else {
+                    tracing::error!("Ancestor {txid} of the attached transaction is missing");
+                    return Err(ErrorObject::owned(
+                        INTERNAL_ERROR_CODE,
+                        format!("Ancestor {txid} is missing"),
+                        Option::<Vec<u8>>::None,
+                    ));
+                };
+
+                size += tx.consensus_size() * 2;
+                if size > self.max_response_size {
+                    return Err(self.response_too_large(size));
+                }
+
+                loaded.insert(txid, tx);
+            }
+
+            stack.push((txid, true));
+            for parent in spending_edges(&loaded[&txid])
+                .into_iter()
+                .map(|edge| edge.parent)
+            {
+                if visited.insert(parent) {
+                    stack.push((parent, false));
+                }
+            }
+        }
+
+        Ok(Some(ProofBundle::new(outpoint, txs)))
+    }
 }
 
 #[async_trait]
 impl<TS, SS, BC> YuvTransactionsRpcServer for TransactionsController<TS, SS, BC>
 where
-    TS: TransactionsStorage + PagesStorage + Clone + Send + Sync + 'static,
-    SS: FrozenTxsStorage + ChromaInfoStorage + MempoolEntryStorage + Clone + Send + Sync + 'static,
+    TS: TransactionsStorage
+        + PagesStorage
+        + SupplyTreeStorage
+        + AddressIndexStorage
+        + TransferHistoryStorage
+        + TxDependentsStorage
+        + PruningStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    SS: FrozenTxsStorage
+        + ChromaInfoStorage
+        + MempoolEntryStorage
+        + MempoolStorage
+        + InvalidTxsStorage
+        + BlockIndexerStorage
+        + WalletProofsStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     BC: BitcoinRpcApi + Send + Sync + 'static,
 {
     /// Handle new YUV transaction with proof to check.
@@ -167,6 +642,68 @@ where
         Ok(true)
     }
 
+    async fn provide_wallet_yuv_proofs(
+        &self,
+        wallet: String,
+        yuv_txs: Vec<YuvTransaction>,
+    ) -> RpcResult<bool> {
+        self.check_wallet_methods()?;
+
+        if yuv_txs.len() > self.max_items_per_request {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                format!(
+                    "Too many yuv_txs, max amount is {}",
+                    self.max_items_per_request
+                ),
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        let txids = yuv_txs.iter().map(|tx| tx.bitcoin_tx.txid()).collect();
+
+        // Send message to message handler to wait its confirmation.
+        self.send_txs_to_confirm(yuv_txs).await?;
+
+        let _guard = self.wallet_proofs_lock.lock().await;
+        self.state_storage
+            .add_wallet_proofs(&wallet, txids)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to add wallet proofs: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })?;
+
+        Ok(true)
+    }
+
+    async fn list_wallet_yuv_proofs(
+        &self,
+        wallet: String,
+        page: Option<u64>,
+    ) -> RpcResult<Vec<Txid>> {
+        self.check_wallet_methods()?;
+
+        let limit = self.max_batch_size as u64;
+        let offset = page.unwrap_or_default().saturating_mul(limit);
+
+        self.state_storage
+            .get_wallet_proofs(&wallet, offset, limit)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get wallet proofs: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })
+    }
+
     async fn get_raw_yuv_transaction(
         &self,
         txid: Txid,
@@ -191,9 +728,7 @@ where
             ));
         }
 
-        let tx = self.txs_storage.get_yuv_tx(&txid).await.map_err(|e| {
-            ErrorObject::owned(INTERNAL_ERROR_CODE, e.to_string(), Option::<Vec<u8>>::None)
-        })?;
+        let tx = self.get_attached_tx(&txid).await?;
 
         match tx {
             Some(tx) => Ok(GetRawYuvTransactionResponseJson::new(
@@ -228,9 +763,7 @@ where
             ));
         }
 
-        let tx = self.txs_storage.get_yuv_tx(&txid).await.map_err(|e| {
-            ErrorObject::owned(INTERNAL_ERROR_CODE, e.to_string(), Option::<Vec<u8>>::None)
-        })?;
+        let tx = self.get_attached_tx(&txid).await?;
 
         match tx {
             Some(tx) => Ok(GetRawYuvTransactionResponseHex::new(
@@ -262,15 +795,15 @@ where
         let mut result = Vec::new();
 
         for txid in &txids {
-            let tx = self.txs_storage.get_yuv_tx(txid).await.map_err(|e| {
-                ErrorObject::owned(INTERNAL_ERROR_CODE, e.to_string(), Option::<Vec<u8>>::None)
-            })?;
+            let tx = self.get_attached_tx(txid).await?;
 
             if let Some(tx) = tx {
                 result.push(tx.into())
             };
         }
 
+        self.check_response_size(&result)?;
+
         Ok(result)
     }
 
@@ -295,6 +828,64 @@ where
             result.push(self.get_yuv_transaction(txid).await?)
         }
 
+        self.check_response_size(&result)?;
+
+        Ok(result)
+    }
+
+    async fn get_yuv_transactions_by_ids(
+        &self,
+        txids: Vec<Txid>,
+    ) -> RpcResult<Vec<YuvTransactionProofsResponse>> {
+        if txids.len() > self.max_batch_size {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                format!("Too many txids, max batch size is {}", self.max_batch_size),
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        let mut result = Vec::with_capacity(txids.len());
+
+        for txid in txids {
+            let mempool_entry = self
+                .state_storage
+                .get_mempool_entry(&txid)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to get mempool entry: {e}");
+                    ErrorObject::owned(
+                        INTERNAL_ERROR_CODE,
+                        "Storage is not available",
+                        Option::<Vec<u8>>::None,
+                    )
+                })?;
+
+            if let Some(entry) = mempool_entry {
+                result.push(YuvTransactionProofsResponse::new(
+                    txid,
+                    entry.status.into(),
+                    Some(entry.yuv_tx.tx_type),
+                ));
+                continue;
+            }
+
+            let tx = self.get_attached_tx(&txid).await?;
+
+            let response = match tx {
+                Some(tx) => YuvTransactionProofsResponse::new(
+                    txid,
+                    YuvTransactionStatus::Attached,
+                    Some(tx.tx_type),
+                ),
+                None => YuvTransactionProofsResponse::new(txid, YuvTransactionStatus::None, None),
+            };
+
+            result.push(response);
+        }
+
+        self.check_response_size(&result)?;
+
         Ok(result)
     }
 
@@ -323,11 +914,13 @@ where
             match self.txs_storage.get_yuv_tx(&txid).await {
                 // if everything is ok, push transaction to result.
                 Ok(Some(tx)) => res.push(tx.into()),
-                // if transaction not found, then it's not valid.
+                // if transaction not found, then it's either pruned or not valid.
                 //
                 // TODO: Maybe we should return error here?
                 Ok(None) => {
-                    tracing::error!("Transaction with id {txid} not found in page storage");
+                    if !matches!(self.txs_storage.get_pruned_tx(&txid).await, Ok(Some(_))) {
+                        tracing::error!("Transaction with id {txid} not found in page storage");
+                    }
                     continue;
                 }
                 // if we failed to get transaction, then storage is not available.
@@ -340,6 +933,8 @@ where
             }
         }
 
+        self.check_response_size(&res)?;
+
         Ok(res)
     }
 
@@ -375,6 +970,66 @@ where
         Ok(true)
     }
 
+    /// Verify signed YUV transaction against its parents, send it to Bitcoin network and validate
+    /// it after it's confirmed.
+    async fn send_raw_yuv_tx_with_parents(
+        &self,
+        yuv_tx: String,
+        parents: Vec<String>,
+        max_burn_amount: Option<u64>,
+    ) -> RpcResult<bool> {
+        if parents.len() > self.max_bundle_parents {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                format!(
+                    "Too many parents, max number is {}",
+                    self.max_bundle_parents
+                ),
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        // Each byte is encoded with two hex characters.
+        let bundle_size = (yuv_tx.len() + parents.iter().map(String::len).sum::<usize>()) / 2;
+        if bundle_size > self.max_bundle_size {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                format!(
+                    "Bundle is too large: {} bytes, max size is {}",
+                    bundle_size, self.max_bundle_size
+                ),
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        let parse_hex = |hex: &String| {
+            YuvTransaction::from_hex(hex.clone()).map_err(|err| {
+                tracing::error!("Failed to parse YUV tx hex: {err}");
+                ErrorObjectOwned::owned(
+                    INVALID_REQUEST_CODE,
+                    "Hex parse error",
+                    Option::<Vec<u8>>::None,
+                )
+            })
+        };
+
+        let tx = parse_hex(&yuv_tx)?;
+        let parents = parents
+            .iter()
+            .map(parse_hex)
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        verify_yuv_transaction_with_parents(&tx, &parents).map_err(|err| {
+            ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                err.to_string(),
+                Option::<Vec<u8>>::None,
+            )
+        })?;
+
+        self.send_yuv_tx(yuv_tx, max_burn_amount).await
+    }
+
     /// Send signed raw YUV transaction to Bitcoin network and validate it after it's confirmed.
     ///
     /// NOTE: this method will soon accept only hex encoded YUV txs.
@@ -405,6 +1060,16 @@ where
     }
 
     async fn is_yuv_txout_frozen(&self, txid: Txid, vout: u32) -> RpcResult<bool> {
+        let freeze = self.get_yuv_txout_freeze(txid, vout).await?;
+
+        Ok(freeze.is_some())
+    }
+
+    async fn get_yuv_txout_freeze(
+        &self,
+        txid: Txid,
+        vout: u32,
+    ) -> RpcResult<Option<YuvTxOutFreezeResponse>> {
         let freeze_entry = self
             .state_storage
             .get_frozen_tx(&OutPoint::new(txid, vout))
@@ -418,8 +1083,36 @@ where
                 )
             })?;
 
-        Ok(freeze_entry.is_some())
-    }
+        let Some(freeze_entry) = freeze_entry else {
+            return Ok(None);
+        };
+
+        let height = self
+            .state_storage
+            .get_last_indexed_height()
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get last indexed height: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })?
+            .unwrap_or_default();
+
+        // The expired freeze is inactive, so the output is not frozen anymore.
+        if freeze_entry.is_expired(height) {
+            return Ok(None);
+        }
+
+        Ok(Some(YuvTxOutFreezeResponse {
+            freeze_txid: freeze_entry.txid,
+            chroma: freeze_entry.chroma,
+            frozen_amount: freeze_entry.amount,
+            expiry_height: freeze_entry.expiry_height,
+        }))
+    }
 
     /// Check that transaction could be accpeted by node.
     ///
@@ -452,8 +1145,9 @@ where
         }
     }
 
-    async fn get_chroma_info(&self, chroma: Chroma) -> RpcResult<Option<ChromaInfo>> {
-        self.state_storage
+    async fn get_chroma_info(&self, chroma: Chroma) -> RpcResult<Option<GetChromaInfoResponse>> {
+        let chroma_info = self
+            .state_storage
             .get_chroma_info(&chroma)
             .await
             .map_err(|e| {
@@ -463,8 +1157,621 @@ where
                     "Storage is not available",
                     Option::<Vec<u8>>::None,
                 )
+            })?;
+
+        Ok(chroma_info.map(GetChromaInfoResponse::from))
+    }
+
+    async fn get_supply_commitment(
+        &self,
+        chroma: Chroma,
+    ) -> RpcResult<GetSupplyCommitmentResponse> {
+        let tree = self
+            .txs_storage
+            .get_supply_tree(&chroma)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get supply tree: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })?;
+
+        Ok(GetSupplyCommitmentResponse {
+            chroma,
+            root: tree.root(),
+            utxos_number: tree.len(),
+        })
+    }
+
+    async fn get_inclusion_proof(
+        &self,
+        txid: Txid,
+        vout: u32,
+    ) -> RpcResult<Option<GetInclusionProofResponse>> {
+        let storage_error = |e: KeyValueError| {
+            tracing::error!("Failed to get inclusion proof: {e}");
+            ErrorObject::owned(
+                INTERNAL_ERROR_CODE,
+                "Storage is not available",
+                Option::<Vec<u8>>::None,
+            )
+        };
+
+        let Some(yuv_tx) = self
+            .txs_storage
+            .get_yuv_tx(&txid)
+            .await
+            .map_err(storage_error)?
+        else {
+            return Ok(None);
+        };
+
+        let outpoint = OutPoint::new(txid, vout);
+        let Some((_, pixel)) = supply_outputs(&yuv_tx)
+            .into_iter()
+            .find(|(output, _)| *output == outpoint)
+        else {
+            return Ok(None);
+        };
+
+        let tree = self
+            .txs_storage
+            .get_supply_tree(&pixel.chroma)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(tree
+            .inclusion_proof(&outpoint)
+            .map(|proof| GetInclusionProofResponse {
+                chroma: pixel.chroma,
+                proof,
+                root: tree.root(),
+            }))
+    }
+
+    async fn list_utxos_by_pubkey(
+        &self,
+        pubkey: PublicKey,
+        chroma: Option<Chroma>,
+    ) -> RpcResult<Vec<PubkeyUtxoResponse>> {
+        if !self.address_index {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                "Address index is disabled on the node",
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        let utxos = self
+            .txs_storage
+            .get_address_utxos(&pubkey.x_only_public_key().0)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get address utxos: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })?;
+
+        Ok(utxos
+            .into_iter()
+            .filter(|(_, pixel)| chroma.map_or(true, |chroma| pixel.chroma == chroma))
+            .map(|(outpoint, pixel)| PubkeyUtxoResponse {
+                outpoint,
+                chroma: pixel.chroma,
+                amount: pixel.luma.amount,
+            })
+            .collect())
+    }
+
+    async fn estimate_yuv_transfer(
+        &self,
+        recipients: Vec<PublicKey>,
+        chroma: Chroma,
+        amounts: Vec<u128>,
+        utxos: Option<Vec<PubkeyUtxoResponse>>,
+        sender: Option<PublicKey>,
+    ) -> RpcResult<EstimateYuvTransferResponse> {
+        if recipients.is_empty() || recipients.len() != amounts.len() {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                "Each recipient must have the amount",
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        if recipients.len() > self.max_items_per_request {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                "Too many recipients",
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        let utxos = match (utxos, sender) {
+            (Some(utxos), _) => utxos,
+            (None, Some(sender)) => self.list_utxos_by_pubkey(sender, Some(chroma)).await?,
+            (None, None) => {
+                return Err(ErrorObject::owned(
+                    INVALID_REQUEST_CODE,
+                    "Either the outputs or the sender must be provided",
+                    Option::<Vec<u8>>::None,
+                ))
+            }
+        };
+
+        let amount = amounts
+            .iter()
+            .try_fold(0u128, |sum, amount| sum.checked_add(*amount))
+            .ok_or_else(|| {
+                ErrorObject::owned(
+                    INVALID_REQUEST_CODE,
+                    "Total amount overflows",
+                    Option::<Vec<u8>>::None,
+                )
+            })?;
+
+        let utxos = utxos
+            .into_iter()
+            .filter(|utxo| utxo.chroma == chroma)
+            .map(|utxo| (utxo.outpoint, utxo.amount))
+            .collect();
+
+        let Some((inputs, change)) = select_utxos(utxos, amount) else {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                "Insufficient balance of the chroma",
+                Option::<Vec<u8>>::None,
+            ));
+        };
+
+        let fee_rate = self
+            .bitcoin_client
+            .estimate_smart_fee(FEE_ESTIMATE_CONF_TARGET, None)
+            .await
+            .map_err(|err| {
+                tracing::error!("Failed to estimate the fee rate: {err}");
+                ErrorObjectOwned::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Service is dead",
+                    Option::<Vec<u8>>::None,
+                )
+            })?
+            .fee_rate
+            .map_or(MIN_FEE_RATE, |fee_rate| {
+                // The estimation is in sat/kvB.
+                fee_rate.to_sat().div_ceil(1000).max(MIN_FEE_RATE)
+            });
+
+        // The satoshis of the token outputs and the fee are funded with one more input, and
+        // their change is returned with one more output.
+        let token_outputs = (recipients.len() + usize::from(change > 0)) as u64;
+        let vsize = TX_OVERHEAD_VSIZE
+            + (inputs.len() as u64 + 1) * P2WPKH_INPUT_VSIZE
+            + (token_outputs + 1) * P2WPKH_OUTPUT_VSIZE;
+        let fee = vsize * fee_rate;
+
+        Ok(EstimateYuvTransferResponse {
+            inputs,
+            change,
+            vsize,
+            dust_per_output: P2WPKH_MIN_NON_DUST,
+            fee_rate,
+            fee,
+            total_satoshis: token_outputs * P2WPKH_MIN_NON_DUST + fee,
+        })
+    }
+
+    async fn get_balance_at(
+        &self,
+        pubkey: PublicKey,
+        chroma: Chroma,
+        height: usize,
+    ) -> RpcResult<u128> {
+        let history = self.transfer_history(&pubkey, &chroma).await?;
+
+        let balance = history
+            .iter()
+            .take_while(|entry| entry.height <= height)
+            .map(|entry| entry.delta)
+            .sum::<i128>();
+
+        // The balance is negative only if the history is incomplete, i.e. the index was enabled
+        // after the key received the tokens.
+        Ok(balance.max(0) as u128)
+    }
+
+    async fn list_transfers(
+        &self,
+        pubkey: PublicKey,
+        chroma: Chroma,
+        from_height: usize,
+        to_height: Option<usize>,
+        cursor: Option<usize>,
+    ) -> RpcResult<ListTransfersResponse> {
+        let history = self.transfer_history(&pubkey, &chroma).await?;
+
+        let start = history
+            .partition_point(|entry| entry.height < from_height)
+            .max(cursor.unwrap_or_default());
+        let end = match to_height {
+            Some(to_height) => history.partition_point(|entry| entry.height <= to_height),
+            None => history.len(),
+        };
+
+        if start >= end {
+            return Ok(ListTransfersResponse {
+                transfers: Vec::new(),
+                next_cursor: None,
+            });
+        }
+
+        let page_end = end.min(start + self.max_items_per_request);
+
+        Ok(ListTransfersResponse {
+            transfers: history[start..page_end].to_vec(),
+            next_cursor: (page_end < end).then_some(page_end),
+        })
+    }
+
+    async fn get_tx_ancestry(&self, txid: Txid, depth: u32) -> RpcResult<Option<TxGraphResponse>> {
+        self.tx_graph(txid, depth, GraphDirection::Ancestors).await
+    }
+
+    async fn get_tx_descendants(
+        &self,
+        txid: Txid,
+        depth: u32,
+    ) -> RpcResult<Option<TxGraphResponse>> {
+        self.tx_graph(txid, depth, GraphDirection::Descendants)
+            .await
+    }
+
+    async fn export_proof_bundle(&self, txid: Txid, vout: u32) -> RpcResult<Option<String>> {
+        let bundle = self.proof_bundle(OutPoint::new(txid, vout)).await?;
+
+        Ok(bundle.map(|bundle| bundle.hex()))
+    }
+
+    async fn get_activation_status(&self) -> RpcResult<GetActivationStatusResponse> {
+        let height = self
+            .state_storage
+            .get_last_indexed_height()
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get last indexed height: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })?
+            .unwrap_or_default();
+
+        Ok(GetActivationStatusResponse {
+            height,
+            features: activation_statuses(self.network, height),
+        })
+    }
+
+    async fn get_api_version(&self) -> RpcResult<GetApiVersionResponse> {
+        Ok(GetApiVersionResponse {
+            api_version: RPC_API_VERSION,
+            node_version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
+
+    async fn get_capabilities(&self) -> RpcResult<GetCapabilitiesResponse> {
+        let height = self
+            .state_storage
+            .get_last_indexed_height()
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get last indexed height: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })?
+            .unwrap_or_default();
+
+        let features = RpcFeatures {
+            bulletproof: cfg!(feature = "bulletproof"),
+            p2tr_proofs: ProtocolFeature::MusigOutputs.is_active(self.network, height),
+            subscriptions: true,
+            address_index: self.address_index,
+            history_index: self.history_index,
+            snapshots: self.snapshotter.is_some(),
+        };
+        let limits = RpcLimits {
+            max_items_per_request: self.max_items_per_request,
+            max_batch_size: self.max_batch_size,
+            max_response_size: self.max_response_size,
+            max_bundle_parents: self.max_bundle_parents,
+            max_bundle_size: self.max_bundle_size,
+        };
+
+        Ok(GetCapabilitiesResponse {
+            api_version: RPC_API_VERSION,
+            features,
+            limits,
+        })
+    }
+
+    async fn get_node_status(&self) -> RpcResult<GetNodeStatusResponse> {
+        let proofs_dedup = if self.txs_storage.txs_encoding() == TxsEncoding::ProofsDedup {
+            let stats = self
+                .txs_storage
+                .get_proofs_dedup_stats()
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to get proofs dedup stats: {e}");
+                    ErrorObject::owned(
+                        INTERNAL_ERROR_CODE,
+                        "Storage is not available",
+                        Option::<Vec<u8>>::None,
+                    )
+                })?;
+
+            Some(ProofsDedupMetrics {
+                logical_bytes: stats.logical_bytes,
+                stored_bytes: stats.stored_bytes,
+                saved_bytes: stats.saved_bytes(),
             })
+        } else {
+            None
+        };
+
+        Ok(GetNodeStatusResponse {
+            jobs: self.job_statuses.list(),
+            channels: self.event_bus.metrics().list(),
+            rate_limits: self.rejected_calls.list(),
+            proofs_dedup,
+        })
     }
+
+    async fn get_mempool_entry(&self, txid: Txid) -> RpcResult<Option<MempoolEntryResponse>> {
+        let entry = self
+            .state_storage
+            .get_mempool_entry(&txid)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get mempool entry: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })?;
+
+        if let Some(entry) = entry {
+            return Ok(Some(MempoolEntryResponse::from(&entry)));
+        }
+
+        let rejection = self.get_rejection_reason(txid).await?;
+
+        Ok(rejection.map(|rejection| MempoolEntryResponse {
+            txid,
+            status: YuvTransactionStatus::None,
+            block_height: None,
+            from_peer: false,
+            rejection: Some(rejection),
+        }))
+    }
+
+    async fn list_mempool(
+        &self,
+        status: Option<YuvTransactionStatus>,
+        cursor: Option<usize>,
+    ) -> RpcResult<ListMempoolResponse> {
+        let storage_error = |e: KeyValueError| {
+            tracing::error!("Failed to get mempool: {e}");
+            ErrorObject::owned(
+                INTERNAL_ERROR_CODE,
+                "Storage is not available",
+                Option::<Vec<u8>>::None,
+            )
+        };
+
+        let mempool = self
+            .state_storage
+            .get_mempool()
+            .await
+            .map_err(storage_error)?
+            .unwrap_or_default();
+
+        let mut entries = Vec::new();
+        for (index, txid) in mempool.iter().enumerate().skip(cursor.unwrap_or_default()) {
+            if entries.len() == self.max_items_per_request {
+                return Ok(ListMempoolResponse {
+                    entries,
+                    next_cursor: Some(index),
+                });
+            }
+
+            // The transaction could be handled after the mempool was read.
+            let Some(entry) = self
+                .state_storage
+                .get_mempool_entry(txid)
+                .await
+                .map_err(storage_error)?
+            else {
+                continue;
+            };
+
+            let entry = MempoolEntryResponse::from(&entry);
+            if status
+                .as_ref()
+                .map_or(true, |status| *status == entry.status)
+            {
+                entries.push(entry);
+            }
+        }
+
+        Ok(ListMempoolResponse {
+            entries,
+            next_cursor: None,
+        })
+    }
+
+    async fn get_rejection_reason(&self, txid: Txid) -> RpcResult<Option<TxRejection>> {
+        self.state_storage
+            .get_tx_rejection(txid)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get rejection reason: {e}");
+                ErrorObject::owned(
+                    INTERNAL_ERROR_CODE,
+                    "Storage is not available",
+                    Option::<Vec<u8>>::None,
+                )
+            })
+    }
+
+    async fn create_snapshot(&self, dir: String) -> RpcResult<CreateSnapshotResponse> {
+        let Some(snapshotter) = &self.snapshotter else {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                "Snapshots are supported by the LevelDB storage only",
+                Option::<Vec<u8>>::None,
+            ));
+        };
+
+        if Path::new(&dir).exists() {
+            return Err(ErrorObject::owned(
+                INVALID_REQUEST_CODE,
+                format!("Snapshot directory {dir} already exists"),
+                Option::<Vec<u8>>::None,
+            ));
+        }
+
+        let metadata = snapshotter.create(Path::new(&dir)).await.map_err(|e| {
+            tracing::error!("Failed to create the snapshot in {dir}: {e:?}");
+            ErrorObject::owned(
+                INTERNAL_ERROR_CODE,
+                format!("Failed to create the snapshot: {e}"),
+                Option::<Vec<u8>>::None,
+            )
+        })?;
+
+        tracing::info!(
+            block_height = metadata.block_height,
+            "Created the snapshot in {dir}"
+        );
+
+        Ok(CreateSnapshotResponse {
+            dir,
+            block_height: metadata.block_height,
+            block_hash: metadata.block_hash,
+            created_at: metadata.created_at,
+        })
+    }
+
+    async fn decode_proof(&self, proof: String) -> RpcResult<PixelProof> {
+        decode_proof(&proof)
+    }
+
+    async fn encode_proof(&self, proof: PixelProof) -> RpcResult<String> {
+        Ok(proof.hex())
+    }
+
+    async fn convert_yuv_tx(
+        &self,
+        yuv_tx: EncodedYuvTransaction,
+    ) -> RpcResult<ConvertYuvTransactionResponse> {
+        convert_yuv_tx(yuv_tx)
+    }
+}
+
+/// Decodes the consensus encoded proof from hex.
+fn decode_proof(hex: &str) -> RpcResult<PixelProof> {
+    PixelProof::from_hex(hex).map_err(|err| {
+        ErrorObjectOwned::owned(
+            INVALID_REQUEST_CODE,
+            format!("Proof is malformed: {err}"),
+            Option::<Vec<u8>>::None,
+        )
+    })
+}
+
+/// Converts the transaction to all the supported encodings.
+fn convert_yuv_tx(yuv_tx: EncodedYuvTransaction) -> RpcResult<ConvertYuvTransactionResponse> {
+    let yuv_tx = match yuv_tx {
+        EncodedYuvTransaction::Hex(hex) => YuvTransaction::from_hex(hex).map_err(|err| {
+            ErrorObjectOwned::owned(
+                INVALID_REQUEST_CODE,
+                format!("Hex parse error: {err}"),
+                Option::<Vec<u8>>::None,
+            )
+        })?,
+        EncodedYuvTransaction::Json(yuv_tx) => yuv_tx,
+    };
+
+    Ok(ConvertYuvTransactionResponse {
+        txid: yuv_tx.bitcoin_tx.txid(),
+        hex: yuv_tx.hex(),
+        json: yuv_tx,
+    })
+}
+
+/// Direction of the walk over the graph of the transactions.
+#[derive(Debug, Clone, Copy)]
+enum GraphDirection {
+    /// From the children to the parents whose outputs they spend.
+    Ancestors,
+    /// From the parents to the children that spend their outputs.
+    Descendants,
+}
+
+/// Selects the outputs to spend the `amount` of tokens from, the largest first, so the transfer
+/// has fewer inputs. Returns the selected outputs with the change, or `None` if the outputs
+/// aren't enough.
+fn select_utxos(mut utxos: Vec<(OutPoint, u128)>, amount: u128) -> Option<(Vec<OutPoint>, u128)> {
+    utxos.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    let mut selected = Vec::new();
+    let mut sum = 0u128;
+    for (outpoint, value) in utxos {
+        if sum >= amount && !selected.is_empty() {
+            break;
+        }
+
+        selected.push(outpoint);
+        sum = sum.saturating_add(value);
+    }
+
+    (sum >= amount && !selected.is_empty()).then(|| (selected, sum - amount))
+}
+
+/// Returns the edges from the parents' outputs that the transfer's inputs with the proofs spend.
+fn spending_edges(tx: &YuvTransaction) -> Vec<TxGraphEdge> {
+    let YuvTxType::Transfer { input_proofs, .. } = &tx.tx_type else {
+        return Vec::new();
+    };
+
+    let child = tx.bitcoin_tx.txid();
+    input_proofs
+        .iter()
+        .filter_map(|(input, proof)| {
+            let outpoint = tx.bitcoin_tx.input.get(*input as usize)?.previous_output;
+            let pixel = proof.pixel();
+
+            Some(TxGraphEdge {
+                parent: outpoint.txid,
+                vout: outpoint.vout,
+                child,
+                input: *input,
+                chroma: pixel.chroma,
+                amount: pixel.luma.amount,
+            })
+        })
+        .collect()
 }
 
 /// Entity that emulates transactions by checking if the one violates any of
@@ -501,6 +1808,9 @@ pub enum EmulateYuvTransactionError {
     #[error("Parent transaction is frozen: {txid}:{vout}")]
     ParentTransactionFrozen { txid: Txid, vout: u32 },
 
+    #[error("Frozen amount of the partially frozen parents is not kept")]
+    FrozenAmountSpent,
+
     #[error("Storage is not available: {0}")]
     StorageNotAvailable(#[from] KeyValueError),
 }
@@ -508,7 +1818,7 @@ pub enum EmulateYuvTransactionError {
 impl<TXS, FZS> TransactionEmulator<TXS, FZS>
 where
     TXS: TransactionsStorage + Send + Sync + 'static,
-    FZS: FrozenTxsStorage + Send + Sync + 'static,
+    FZS: FrozenTxsStorage + BlockIndexerStorage + Send + Sync + 'static,
 {
     pub fn new(txs_storage: TXS, frozen_txs_storage: FZS) -> Self {
         Self {
@@ -530,19 +1840,22 @@ where
             return Ok(());
         };
 
-        self.check_parents(parents).await?;
+        self.check_parents(yuv_tx, parents).await?;
 
         Ok(())
     }
 
-    /// Check that all parent transactions are not spent or frozen.
+    /// Check that all parent transactions are not spent or frozen, and that the frozen amounts of
+    /// the partially frozen ones are kept in the outputs.
     async fn check_parents(
         &self,
-        parents: Vec<OutPoint>,
+        yuv_tx: &YuvTransaction,
+        parents: Vec<(OutPoint, &PixelProof)>,
     ) -> Result<(), EmulateYuvTransactionError> {
         use EmulateYuvTransactionError as Error;
 
-        for parent in parents {
+        let mut partial_freezes = Vec::new();
+        for (parent, proof) in parents {
             let tx_entry = self.txs_storage.get_yuv_tx(&parent.txid).await?;
 
             // Return an error if parent transaction not found.
@@ -563,28 +1876,52 @@ where
             }
 
             // Return an error if parent transaction output is already frozen.
-            self.is_parent_frozen(parent).await?;
+            partial_freezes.extend(self.is_parent_frozen(parent, proof).await?);
+        }
+
+        if !partial_freezes.is_empty()
+            && find_continuation_outputs(yuv_tx, partial_freezes).is_none()
+        {
+            return Err(Error::FrozenAmountSpent);
         }
 
         Ok(())
     }
 
-    /// Check if parent UTXO is frozen or not.
-    async fn is_parent_frozen(&self, parent: OutPoint) -> Result<(), EmulateYuvTransactionError> {
-        let freeze_entry = self.frozen_txs_storage.get_frozen_tx(&parent).await?;
+    /// Check if parent UTXO is frozen or not. Returns the frozen part of the partially frozen
+    /// one, as its unfrozen remainder can be spent.
+    async fn is_parent_frozen(
+        &self,
+        parent: OutPoint,
+        proof: &PixelProof,
+    ) -> Result<Option<PartialFreeze>, EmulateYuvTransactionError> {
+        let Some(freeze_entry) = self.frozen_txs_storage.get_frozen_tx(&parent).await? else {
+            return Ok(None);
+        };
+
+        let height = self
+            .frozen_txs_storage
+            .get_last_indexed_height()
+            .await?
+            .unwrap_or_default();
 
-        if freeze_entry.is_some() {
-            Err(EmulateYuvTransactionError::ParentTransactionFrozen {
+        // The freeze of the other chroma is ignored by the tx checker, and the expired freeze
+        // is inactive.
+        if freeze_entry.chroma != proof.pixel().chroma || freeze_entry.is_expired(height) {
+            return Ok(None);
+        }
+
+        match partial_freeze(&freeze_entry, proof) {
+            Some(freeze) => Ok(Some(freeze)),
+            None => Err(EmulateYuvTransactionError::ParentTransactionFrozen {
                 txid: parent.txid,
                 vout: parent.vout,
-            })
-        } else {
-            Ok(())
+            }),
         }
     }
 }
 
-fn extract_parents(yuv_tx: &YuvTransaction) -> Option<Vec<OutPoint>> {
+fn extract_parents(yuv_tx: &YuvTransaction) -> Option<Vec<(OutPoint, &PixelProof)>> {
     match &yuv_tx.tx_type {
         // Issuance check was above, so we skip it.
         YuvTxType::Issue { .. } => None,
@@ -601,8 +1938,11 @@ fn extract_parents(yuv_tx: &YuvTransaction) -> Option<Vec<OutPoint>> {
     }
 }
 
-/// Extract outpoint from inputs that are in the input proofs.
-fn collect_transfer_parents(yuv_tx: &YuvTransaction, input_proofs: &ProofMap) -> Vec<OutPoint> {
+/// Extract outpoint from inputs that are in the input proofs along with their proofs.
+fn collect_transfer_parents<'a>(
+    yuv_tx: &YuvTransaction,
+    input_proofs: &'a ProofMap,
+) -> Vec<(OutPoint, &'a PixelProof)> {
     yuv_tx
         .bitcoin_tx
         .input
@@ -611,7 +1951,69 @@ fn collect_transfer_parents(yuv_tx: &YuvTransaction, input_proofs: &ProofMap) ->
         .filter_map(|(vin, input)| {
             input_proofs
                 .get(&(vin as u32))
-                .map(|_proof| input.previous_output)
+                .map(|proof| (input.previous_output, proof))
         })
         .collect::<Vec<_>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime,
+        secp256k1::{Secp256k1, SecretKey},
+        ScriptBuf, Transaction, TxOut,
+    };
+    use yuv_pixels::{Pixel, SigPixelProof};
+
+    use super::*;
+
+    fn proof() -> PixelProof {
+        let pubkey = SecretKey::from_slice(&[1; 32])
+            .unwrap()
+            .public_key(&Secp256k1::new());
+
+        PixelProof::Sig(SigPixelProof::new(
+            Pixel::new(100, pubkey.x_only_public_key().0),
+            pubkey,
+        ))
+    }
+
+    #[test]
+    fn test_proof_hex_roundtrip() {
+        let proof = proof();
+
+        assert_eq!(decode_proof(&proof.hex()).unwrap(), proof);
+
+        let err = decode_proof("00").unwrap_err();
+        assert_eq!(err.code(), INVALID_REQUEST_CODE);
+    }
+
+    #[test]
+    fn test_yuv_tx_conversion_roundtrip() {
+        let yuv_tx = YuvTransaction {
+            bitcoin_tx: Transaction {
+                version: 2,
+                lock_time: LockTime::ZERO,
+                input: vec![],
+                output: vec![TxOut {
+                    value: 1000,
+                    script_pubkey: ScriptBuf::new(),
+                }],
+            },
+            tx_type: YuvTxType::Transfer {
+                input_proofs: ProofMap::new(),
+                output_proofs: ProofMap::from([(0, proof())]),
+            },
+        };
+
+        let from_json = convert_yuv_tx(EncodedYuvTransaction::Json(yuv_tx.clone())).unwrap();
+        assert_eq!(from_json.txid, yuv_tx.bitcoin_tx.txid());
+        assert_eq!(from_json.json, yuv_tx);
+
+        let from_hex = convert_yuv_tx(EncodedYuvTransaction::Hex(from_json.hex.clone())).unwrap();
+        assert_eq!(from_hex, from_json);
+
+        let err = convert_yuv_tx(EncodedYuvTransaction::Hex("00".into())).unwrap_err();
+        assert_eq!(err.code(), INVALID_REQUEST_CODE);
+    }
+}
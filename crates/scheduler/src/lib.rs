@@ -0,0 +1,10 @@
+#![doc = include_str!("../README.md")]
+
+mod schedule;
+pub use schedule::{CronSchedule, Schedule, ScheduleParseError};
+
+mod scheduler;
+pub use scheduler::{Job, Scheduler, SchedulerError};
+
+mod status;
+pub use status::{JobResult, JobStatus, JobStatuses};
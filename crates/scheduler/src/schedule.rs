@@ -0,0 +1,370 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeDelta, Timelike, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// How far in the future the next run of the cron schedule is looked up. Expressions that don't
+/// match any time in this window (e.g. `0 0 31 2 *`) never run.
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ScheduleParseError {
+    #[error("invalid interval '{0}', expected a number with one of the units: s, m, h, d")]
+    InvalidInterval(String),
+    #[error("interval must be greater than zero")]
+    ZeroInterval,
+    #[error("unknown shortcut '{0}'")]
+    UnknownShortcut(String),
+    #[error("cron expression must have 5 fields, got {0}")]
+    InvalidFieldsNumber(usize),
+    #[error("invalid {field} field '{value}'")]
+    InvalidField { field: &'static str, value: String },
+}
+
+/// Schedule of the job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// Run the job with the fixed interval.
+    Every(Duration),
+    /// Run the job at the times that match the cron expression.
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    /// Returns the time of the next run strictly after the given time, if there is one.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => after.checked_add_signed(TimeDelta::from_std(*interval).ok()?),
+            Self::Cron(cron) => cron.next_after(after),
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ScheduleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(interval) = s.strip_prefix("@every") {
+            return parse_interval(interval.trim()).map(Self::Every);
+        }
+
+        let expression = match s {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            _ if s.starts_with('@') => {
+                return Err(ScheduleParseError::UnknownShortcut(s.to_string()))
+            }
+            _ => s,
+        };
+
+        CronSchedule::from_str(expression).map(Self::Cron)
+    }
+}
+
+impl Display for Schedule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Every(interval) => write!(f, "@every {}s", interval.as_secs()),
+            Self::Cron(cron) => write!(f, "{}", cron.expression),
+        }
+    }
+}
+
+impl Serialize for Schedule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Schedule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+fn parse_interval(s: &str) -> Result<Duration, ScheduleParseError> {
+    let invalid = || ScheduleParseError::InvalidInterval(s.to_string());
+
+    let unit_start = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (value, unit) = s.split_at(unit_start);
+    let value: u64 = value.parse().map_err(|_| invalid())?;
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => return Err(invalid()),
+    };
+
+    if value == 0 {
+        return Err(ScheduleParseError::ZeroInterval);
+    }
+
+    value
+        .checked_mul(multiplier)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
+
+/// Five-field cron expression: `<minute> <hour> <day of month> <month> <day of week>` in UTC.
+///
+/// Each field is stored as a bit mask of the matching values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// As in the classic cron, if both day fields are restricted, the day matches if any of
+    /// them matches.
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Returns the time of the next run strictly after the given time, if there is one in the
+    /// next five years.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let after = after.naive_utc();
+        let mut time =
+            after.date().and_hms_opt(after.hour(), after.minute(), 0)? + TimeDelta::minutes(1);
+        let deadline = time + TimeDelta::days(MAX_LOOKAHEAD_DAYS);
+
+        while time < deadline {
+            if !matches(self.months, time.month()) {
+                let (year, month) = match time.month() {
+                    12 => (time.year() + 1, 1),
+                    month => (time.year(), month + 1),
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if !self.matches_day(&time) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if !matches(self.hours, time.hour()) {
+                time = time.date().and_hms_opt(time.hour(), 0, 0)? + TimeDelta::hours(1);
+                continue;
+            }
+
+            if !matches(self.minutes, time.minute()) {
+                time += TimeDelta::minutes(1);
+                continue;
+            }
+
+            return Some(time.and_utc());
+        }
+
+        None
+    }
+
+    fn matches_day(&self, time: &NaiveDateTime) -> bool {
+        let day_of_month = matches(self.days_of_month, time.day());
+        let day_of_week = matches(self.days_of_week, time.weekday().num_days_from_sunday());
+
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = ScheduleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(ScheduleParseError::InvalidFieldsNumber(fields.len()));
+        };
+
+        // Sunday could be either 0 or 7, so 7 is folded into 0.
+        let mut days_of_week_mask = parse_field("day of week", days_of_week, 0..=7)?;
+        if days_of_week_mask & (1 << 7) != 0 {
+            days_of_week_mask = (days_of_week_mask | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes: parse_field("minute", minutes, 0..=59)?,
+            hours: parse_field("hour", hours, 0..=23)?,
+            days_of_month: parse_field("day of month", days_of_month, 1..=31)?,
+            months: parse_field("month", months, 1..=12)?,
+            days_of_week: days_of_week_mask,
+            any_day_of_month: days_of_month.starts_with('*'),
+            any_day_of_week: days_of_week.starts_with('*'),
+        })
+    }
+}
+
+fn matches(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Parses the cron field into the bit mask of the matching values.
+fn parse_field(
+    field: &'static str,
+    value: &str,
+    bounds: RangeInclusive<u32>,
+) -> Result<u64, ScheduleParseError> {
+    let invalid = || ScheduleParseError::InvalidField {
+        field,
+        value: value.to_string(),
+    };
+    let parse_number = |s: &str| {
+        s.parse::<u32>()
+            .ok()
+            .filter(|number| bounds.contains(number))
+            .ok_or_else(invalid)
+    };
+
+    let mut mask = 0u64;
+
+    for part in value.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+
+        if step == 0 {
+            return Err(invalid());
+        }
+
+        let (start, end) = match range {
+            "*" => (*bounds.start(), *bounds.end()),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (parse_number(start)?, parse_number(end)?),
+                // `5/15` means "every 15 starting from 5".
+                None if step > 1 => (parse_number(range)?, *bounds.end()),
+                None => {
+                    let number = parse_number(range)?;
+                    (number, number)
+                }
+            },
+        };
+
+        if start > end {
+            return Err(invalid());
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_every() {
+        assert_eq!(
+            "@every 30s".parse::<Schedule>(),
+            Ok(Schedule::Every(Duration::from_secs(30)))
+        );
+        assert_eq!(
+            "@every 2h".parse::<Schedule>(),
+            Ok(Schedule::Every(Duration::from_secs(2 * 60 * 60)))
+        );
+        assert_eq!(
+            "@every 0s".parse::<Schedule>(),
+            Err(ScheduleParseError::ZeroInterval)
+        );
+        assert!("@every 5".parse::<Schedule>().is_err());
+        assert!("@every 5w".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn test_parse_cron() {
+        assert!("* * * * *".parse::<Schedule>().is_ok());
+        assert!("*/15 0-6,22 1 */2 1-5".parse::<Schedule>().is_ok());
+        assert_eq!(
+            "* * * *".parse::<Schedule>(),
+            Err(ScheduleParseError::InvalidFieldsNumber(4))
+        );
+        assert!("60 * * * *".parse::<Schedule>().is_err());
+        assert!("* * 0 * *".parse::<Schedule>().is_err());
+        assert!("*/0 * * * *".parse::<Schedule>().is_err());
+        assert!("5-1 * * * *".parse::<Schedule>().is_err());
+        assert!("@yearly".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let every_15_min: Schedule = "*/15 * * * *".parse().unwrap();
+        assert_eq!(
+            every_15_min.next_after(time(2024, 1, 1, 10, 7)),
+            Some(time(2024, 1, 1, 10, 15))
+        );
+        assert_eq!(
+            every_15_min.next_after(time(2024, 1, 1, 10, 15)),
+            Some(time(2024, 1, 1, 10, 30))
+        );
+        assert_eq!(
+            every_15_min.next_after(time(2024, 12, 31, 23, 50)),
+            Some(time(2025, 1, 1, 0, 0))
+        );
+
+        // 2024-01-05 is Friday, so the next working day is Monday.
+        let working_days: Schedule = "0 3 * * 1-5".parse().unwrap();
+        assert_eq!(
+            working_days.next_after(time(2024, 1, 5, 4, 0)),
+            Some(time(2024, 1, 8, 3, 0))
+        );
+
+        // Sunday as 7.
+        let sundays: Schedule = "30 12 * * 7".parse().unwrap();
+        assert_eq!(
+            sundays.next_after(time(2024, 1, 1, 0, 0)),
+            Some(time(2024, 1, 7, 12, 30))
+        );
+
+        // Both days restricted: either the 10th or Monday.
+        let either_day: Schedule = "0 0 10 * 1".parse().unwrap();
+        assert_eq!(
+            either_day.next_after(time(2024, 1, 8, 1, 0)),
+            Some(time(2024, 1, 10, 0, 0))
+        );
+        assert_eq!(
+            either_day.next_after(time(2024, 1, 10, 1, 0)),
+            Some(time(2024, 1, 15, 0, 0))
+        );
+
+        let leap_day: Schedule = "0 0 29 2 *".parse().unwrap();
+        assert_eq!(
+            leap_day.next_after(time(2024, 3, 1, 0, 0)),
+            Some(time(2028, 2, 29, 0, 0))
+        );
+
+        let never: Schedule = "0 0 31 2 *".parse().unwrap();
+        assert_eq!(never.next_after(time(2024, 1, 1, 0, 0)), None);
+    }
+}
@@ -0,0 +1,245 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+use crate::{JobResult, JobStatus, JobStatuses, Schedule};
+
+/// Periodic job run by the [`Scheduler`].
+#[async_trait]
+pub trait Job: Send + Sync + 'static {
+    async fn run(&self) -> eyre::Result<()>;
+}
+
+#[async_trait]
+impl<F, Fut> Job for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = eyre::Result<()>> + Send + 'static,
+{
+    async fn run(&self) -> eyre::Result<()> {
+        self().await
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchedulerError {
+    #[error("job '{0}' is already registered")]
+    DuplicateJob(String),
+}
+
+struct ScheduledJob {
+    name: String,
+    schedule: Schedule,
+    job: Arc<dyn Job>,
+}
+
+/// Runs the registered jobs according to their schedules until cancelled.
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<ScheduledJob>,
+    statuses: JobStatuses,
+}
+
+impl Scheduler {
+    /// Registers the job under the unique name.
+    pub fn add_job(
+        &mut self,
+        name: impl Into<String>,
+        schedule: Schedule,
+        job: impl Job,
+    ) -> Result<&mut Self, SchedulerError> {
+        let name = name.into();
+        if self.statuses.contains(&name) {
+            return Err(SchedulerError::DuplicateJob(name));
+        }
+
+        self.statuses
+            .insert(JobStatus::new(name.clone(), schedule.to_string()));
+        self.jobs.push(ScheduledJob {
+            name,
+            schedule,
+            job: Arc::new(job),
+        });
+
+        Ok(self)
+    }
+
+    /// Returns the handle to the statuses of the registered jobs.
+    pub fn statuses(&self) -> JobStatuses {
+        self.statuses.clone()
+    }
+
+    /// Runs the jobs until cancelled. On cancellation, waits for the running jobs to finish.
+    pub async fn run(self, cancellation: CancellationToken) {
+        let tracker = TaskTracker::new();
+
+        for job in self.jobs {
+            tracker.spawn(run_job(job, self.statuses.clone(), cancellation.clone()));
+        }
+
+        tracker.close();
+        tracker.wait().await;
+
+        tracing::trace!("Cancellation received, scheduler is stopped");
+    }
+}
+
+async fn run_job(job: ScheduledJob, statuses: JobStatuses, cancellation: CancellationToken) {
+    let mut next_run = job.schedule.next_after(Utc::now());
+
+    while let Some(run_at) = next_run {
+        statuses.update(&job.name, |status| {
+            status.next_run_at = Some(run_at.timestamp())
+        });
+
+        let delay = (run_at - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = cancellation.cancelled() => return,
+            _ = tokio::time::sleep(delay) => {},
+        }
+
+        let started_at = Utc::now();
+        statuses.update(&job.name, |status| {
+            status.running = true;
+            status.last_started_at = Some(started_at.timestamp());
+        });
+
+        tracing::debug!(job = job.name.as_str(), "Running scheduled job");
+
+        let timer = Instant::now();
+        let result = match job.job.run().await {
+            Ok(()) => JobResult::Success,
+            Err(err) => {
+                tracing::error!(job = job.name.as_str(), "Scheduled job failed: {:#}", err);
+                JobResult::Failure {
+                    error: format!("{:#}", err),
+                }
+            }
+        };
+        let duration = timer.elapsed();
+
+        // Runs are never overlapped, so the runs that should have started while the job was
+        // running are skipped.
+        let now = Utc::now();
+        let mut skipped_runs = 0;
+        next_run = job.schedule.next_after(run_at);
+        while let Some(missed_run) = next_run.filter(|next_run| *next_run <= now) {
+            skipped_runs += 1;
+            next_run = job.schedule.next_after(missed_run);
+        }
+
+        if skipped_runs > 0 {
+            tracing::warn!(
+                job = job.name.as_str(),
+                "Scheduled job took {:?}, skipped {} overlapping runs",
+                duration,
+                skipped_runs
+            );
+        }
+
+        statuses.update(&job.name, |status| {
+            status.running = false;
+            status.last_duration_ms = Some(duration.as_millis() as u64);
+            status.last_result = Some(result);
+            status.skipped_runs += skipped_runs;
+            status.next_run_at = None;
+        });
+    }
+
+    tracing::warn!(job = job.name.as_str(), "Scheduled job has no more runs");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_job_runs_and_reports_status() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut scheduler = Scheduler::default();
+
+        let job_runs = runs.clone();
+        scheduler
+            .add_job(
+                "counter",
+                Schedule::Every(Duration::from_millis(10)),
+                move || {
+                    let runs = job_runs.clone();
+                    async move {
+                        if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                            eyre::bail!("first run fails");
+                        }
+                        Ok(())
+                    }
+                },
+            )
+            .unwrap();
+
+        assert!(matches!(
+            scheduler.add_job(
+                "counter",
+                Schedule::Every(Duration::from_secs(1)),
+                || async { Ok(()) }
+            ),
+            Err(SchedulerError::DuplicateJob(_))
+        ));
+
+        let statuses = scheduler.statuses();
+        let cancellation = CancellationToken::new();
+        let handle = tokio::spawn(scheduler.run(cancellation.clone()));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        cancellation.cancel();
+        handle.await.unwrap();
+
+        assert!(runs.load(Ordering::SeqCst) >= 2);
+
+        let status = &statuses.list()[0];
+        assert_eq!(status.name, "counter");
+        assert!(!status.running);
+        assert!(status.last_started_at.is_some());
+        assert_eq!(status.last_result, Some(JobResult::Success));
+    }
+
+    #[tokio::test]
+    async fn test_running_job_finishes_on_shutdown() {
+        let finished = Arc::new(AtomicUsize::new(0));
+        let mut scheduler = Scheduler::default();
+
+        let job_finished = finished.clone();
+        scheduler
+            .add_job(
+                "slow",
+                Schedule::Every(Duration::from_millis(10)),
+                move || {
+                    let finished = job_finished.clone();
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        finished.fetch_add(1, Ordering::SeqCst);
+                        Ok(())
+                    }
+                },
+            )
+            .unwrap();
+
+        let statuses = scheduler.statuses();
+        let cancellation = CancellationToken::new();
+        let handle = tokio::spawn(scheduler.run(cancellation.clone()));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(statuses.list()[0].running);
+
+        cancellation.cancel();
+        handle.await.unwrap();
+
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+        assert!(!statuses.list()[0].running);
+    }
+}
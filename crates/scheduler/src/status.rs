@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+/// Result of the job's run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum JobResult {
+    Success,
+    Failure { error: String },
+}
+
+/// Status of the scheduled job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobStatus {
+    /// Unique name of the job.
+    pub name: String,
+    /// Schedule of the job.
+    pub schedule: String,
+    /// Whether the job is running right now.
+    pub running: bool,
+    /// Unix timestamp (in seconds) of the last run's start.
+    pub last_started_at: Option<i64>,
+    /// Duration of the last finished run in milliseconds.
+    pub last_duration_ms: Option<u64>,
+    /// Result of the last finished run.
+    pub last_result: Option<JobResult>,
+    /// Unix timestamp (in seconds) of the next run.
+    pub next_run_at: Option<i64>,
+    /// Number of runs skipped because the previous run was still in progress.
+    pub skipped_runs: u64,
+}
+
+impl JobStatus {
+    pub(crate) fn new(name: String, schedule: String) -> Self {
+        Self {
+            name,
+            schedule,
+            running: false,
+            last_started_at: None,
+            last_duration_ms: None,
+            last_result: None,
+            next_run_at: None,
+            skipped_runs: 0,
+        }
+    }
+}
+
+/// Shared handle to the statuses of the scheduled jobs.
+#[derive(Debug, Clone, Default)]
+pub struct JobStatuses(Arc<RwLock<BTreeMap<String, JobStatus>>>);
+
+impl JobStatuses {
+    /// Returns the statuses of all the jobs sorted by the job's name.
+    pub fn list(&self) -> Vec<JobStatus> {
+        self.0
+            .read()
+            .expect("lock should not be poisoned")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn insert(&self, status: JobStatus) {
+        self.0
+            .write()
+            .expect("lock should not be poisoned")
+            .insert(status.name.clone(), status);
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.0
+            .read()
+            .expect("lock should not be poisoned")
+            .contains_key(name)
+    }
+
+    pub(crate) fn update(&self, name: &str, f: impl FnOnce(&mut JobStatus)) {
+        if let Some(status) = self
+            .0
+            .write()
+            .expect("lock should not be poisoned")
+            .get_mut(name)
+        {
+            f(status);
+        }
+    }
+}
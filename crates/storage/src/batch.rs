@@ -0,0 +1,51 @@
+use serde::Serialize;
+
+use crate::traits::cbor_to_vec;
+use crate::KeyValueResult;
+
+/// Operation of the [`WriteBatch`] with the encoded key and value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOp {
+    Put { key: Vec<u8>, value: Vec<u8> },
+    Delete { key: Vec<u8> },
+}
+
+/// Writes to the storage that are committed atomically by
+/// [`KeyValueStorage::write_batch`](crate::KeyValueStorage::write_batch), so the node killed in
+/// the middle of the update never leaves the storage with only a part of it.
+#[derive(Debug, Default, Clone)]
+pub struct WriteBatch {
+    ops: Vec<BatchOp>,
+}
+
+impl WriteBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put<K: Serialize, V: Serialize>(&mut self, key: K, value: V) -> KeyValueResult<()> {
+        self.ops.push(BatchOp::Put {
+            key: cbor_to_vec(key)?,
+            value: cbor_to_vec(value)?,
+        });
+
+        Ok(())
+    }
+
+    pub fn delete<K: Serialize>(&mut self, key: K) -> KeyValueResult<()> {
+        self.ops.push(BatchOp::Delete {
+            key: cbor_to_vec(key)?,
+        });
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Returns the operations in the order they were added.
+    pub fn into_ops(self) -> Vec<BatchOp> {
+        self.ops
+    }
+}
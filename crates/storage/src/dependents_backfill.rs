@@ -0,0 +1,89 @@
+use crate::{KeyValueResult, PagesStorage, TransactionsStorage, TxDependentsStorage};
+
+/// Records the dependents of the transactions attached before the [`TxDependentsStorage`] index
+/// was added, so they are rolled back along with their parents. Runs once, and returns the number
+/// of the recorded transfers.
+///
+/// The dependents are recorded idempotently, so the interrupted backfill is started over on the
+/// next start.
+pub async fn backfill_tx_dependents<S>(storage: &S) -> KeyValueResult<usize>
+where
+    S: TransactionsStorage + PagesStorage + TxDependentsStorage + Send + Sync,
+{
+    if storage.is_tx_dependents_backfilled().await? {
+        return Ok(0);
+    }
+
+    let mut backfilled = 0;
+    let pages_number = storage.get_pages_number().await?.unwrap_or_default();
+    for page_num in 0..=pages_number {
+        let Some(page) = storage.get_page_by_num(page_num).await? else {
+            continue;
+        };
+
+        for txid in page {
+            // Pruned transactions are never rolled back.
+            let Some(yuv_tx) = storage.get_yuv_tx(&txid).await? else {
+                continue;
+            };
+
+            if yuv_tx.tx_type.input_proofs().is_some() {
+                storage.add_yuv_tx_dependencies(&yuv_tx).await?;
+                backfilled += 1;
+            }
+        }
+    }
+
+    storage.put_tx_dependents_backfilled().await?;
+
+    Ok(backfilled)
+}
+
+#[cfg(all(test, feature = "leveldb"))]
+mod tests {
+    use bitcoin::Txid;
+    use yuv_types::YuvTransaction;
+
+    use super::*;
+    use crate::LevelDB;
+
+    fn transfer() -> YuvTransaction {
+        serde_json::from_str(include_str!(
+            "../../tx-check/src/tests/assets/singlechroma_valid_transfer.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dependents_are_backfilled_once() {
+        tokio_test::block_on(async {
+            let storage = LevelDB::in_memory().unwrap();
+
+            let tx = transfer();
+            let txid = tx.bitcoin_tx.txid();
+            let parents: Vec<Txid> = tx
+                .bitcoin_tx
+                .input
+                .iter()
+                .map(|input| input.previous_output.txid)
+                .collect();
+
+            storage.put_yuv_tx(tx).await.unwrap();
+            storage.put_page(0, vec![txid]).await.unwrap();
+
+            assert_eq!(backfill_tx_dependents(&storage).await.unwrap(), 1);
+            for parent in &parents {
+                assert_eq!(storage.get_tx_dependents(parent).await.unwrap(), vec![txid]);
+            }
+
+            // The dependents of the transactions attached later are recorded on the attachment.
+            storage.delete_tx_dependents(&parents[0]).await.unwrap();
+            assert_eq!(backfill_tx_dependents(&storage).await.unwrap(), 0);
+            assert!(storage
+                .get_tx_dependents(&parents[0])
+                .await
+                .unwrap()
+                .is_empty());
+        });
+    }
+}
@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::traits::pages::PagesNumberStorage;
+use crate::traits::{ChromaInfoStorage, IsIndexedStorage, MempoolStorage, PagesStorage};
+
+use crate::impls::leveldb::LevelDB;
+#[cfg(feature = "postgres")]
+use crate::impls::postgres::Postgres;
+use crate::MempoolEntryStorage;
+use crate::{
+    traits::{FrozenTxsStorage, InvalidTxsStorage, InventoryStorage, TransactionsStorage},
+    BlockIndexerStorage, KeyValueStorage, TxsEncoding, WriteBatch,
+};
+
+/// Storage with the backend selected at runtime, e.g. by the node's config.
+#[derive(Clone)]
+pub enum AnyStorage {
+    LevelDB(LevelDB),
+    #[cfg(feature = "postgres")]
+    Postgres(Postgres),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnyStorageError {
+    #[error(transparent)]
+    LevelDB(rusty_leveldb::Status),
+    #[cfg(feature = "postgres")]
+    #[error(transparent)]
+    Postgres(sqlx::Error),
+}
+
+impl AnyStorage {
+    /// Returns the LevelDB storage, `None` if another backend is used.
+    pub fn as_leveldb(&self) -> Option<&LevelDB> {
+        match self {
+            Self::LevelDB(db) => Some(db),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(_) => None,
+        }
+    }
+}
+
+impl From<LevelDB> for AnyStorage {
+    fn from(storage: LevelDB) -> Self {
+        Self::LevelDB(storage)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<Postgres> for AnyStorage {
+    fn from(storage: Postgres) -> Self {
+        Self::Postgres(storage)
+    }
+}
+
+#[async_trait]
+impl<K, V> KeyValueStorage<K, V> for AnyStorage
+where
+    K: Serialize + Send + Sync + 'static,
+    V: Serialize + for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    type Error = AnyStorageError;
+
+    async fn raw_put(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), Self::Error> {
+        match self {
+            Self::LevelDB(db) => KeyValueStorage::<K, V>::raw_put(db, key, value)
+                .await
+                .map_err(AnyStorageError::LevelDB),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(db) => KeyValueStorage::<K, V>::raw_put(db, key, value)
+                .await
+                .map_err(AnyStorageError::Postgres),
+        }
+    }
+
+    async fn raw_get(&self, key: Vec<u8>) -> Result<Option<Vec<u8>>, Self::Error> {
+        match self {
+            Self::LevelDB(db) => KeyValueStorage::<K, V>::raw_get(db, key)
+                .await
+                .map_err(AnyStorageError::LevelDB),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(db) => KeyValueStorage::<K, V>::raw_get(db, key)
+                .await
+                .map_err(AnyStorageError::Postgres),
+        }
+    }
+
+    async fn raw_delete(&self, key: Vec<u8>) -> Result<(), Self::Error> {
+        match self {
+            Self::LevelDB(db) => KeyValueStorage::<K, V>::raw_delete(db, key)
+                .await
+                .map_err(AnyStorageError::LevelDB),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(db) => KeyValueStorage::<K, V>::raw_delete(db, key)
+                .await
+                .map_err(AnyStorageError::Postgres),
+        }
+    }
+
+    async fn raw_write_batch(&self, batch: WriteBatch) -> Result<(), Self::Error> {
+        match self {
+            Self::LevelDB(db) => KeyValueStorage::<K, V>::raw_write_batch(db, batch)
+                .await
+                .map_err(AnyStorageError::LevelDB),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(db) => KeyValueStorage::<K, V>::raw_write_batch(db, batch)
+                .await
+                .map_err(AnyStorageError::Postgres),
+        }
+    }
+
+    async fn flush(&self) -> Result<(), Self::Error> {
+        match self {
+            Self::LevelDB(db) => KeyValueStorage::<K, V>::flush(db)
+                .await
+                .map_err(AnyStorageError::LevelDB),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(db) => KeyValueStorage::<K, V>::flush(db)
+                .await
+                .map_err(AnyStorageError::Postgres),
+        }
+    }
+
+    async fn compact(&self) -> Result<(), Self::Error> {
+        match self {
+            Self::LevelDB(db) => KeyValueStorage::<K, V>::compact(db)
+                .await
+                .map_err(AnyStorageError::LevelDB),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(db) => KeyValueStorage::<K, V>::compact(db)
+                .await
+                .map_err(AnyStorageError::Postgres),
+        }
+    }
+}
+
+impl TransactionsStorage for AnyStorage {
+    fn txs_encoding(&self) -> TxsEncoding {
+        match self {
+            Self::LevelDB(db) => db.txs_encoding(),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(db) => db.txs_encoding(),
+        }
+    }
+}
+
+impl InvalidTxsStorage for AnyStorage {}
+
+impl InventoryStorage for AnyStorage {}
+
+impl PagesNumberStorage for AnyStorage {}
+
+impl PagesStorage for AnyStorage {}
+
+impl BlockIndexerStorage for AnyStorage {}
+
+impl FrozenTxsStorage for AnyStorage {}
+
+impl crate::FrozenPubkeysStorage for AnyStorage {}
+
+impl crate::DelegatesStorage for AnyStorage {}
+
+impl ChromaInfoStorage for AnyStorage {}
+
+impl MempoolStorage for AnyStorage {}
+
+impl MempoolEntryStorage for AnyStorage {}
+
+impl IsIndexedStorage for AnyStorage {}
+
+impl crate::TxDependentsStorage for AnyStorage {}
+
+impl crate::SupplyTreeStorage for AnyStorage {}
+
+impl crate::AddressIndexStorage for AnyStorage {}
+
+impl crate::TransferHistoryStorage for AnyStorage {}
+
+impl crate::PeerAddressBookStorage for AnyStorage {}
+
+impl crate::PruningStorage for AnyStorage {}
+
+impl crate::WalletProofsStorage for AnyStorage {}
+
+impl crate::AttachStorage for AnyStorage {}
+
+impl crate::WebhookQueueStorage for AnyStorage {}
+impl crate::EventSinkStorage for AnyStorage {}
+
+impl crate::ProofsDedupStatsStorage for AnyStorage {}
+
+impl crate::ProofsStorage for AnyStorage {}
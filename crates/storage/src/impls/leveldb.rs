@@ -1,9 +1,13 @@
-use std::path::PathBuf;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use rusty_leveldb::AsyncDB;
 use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedRwLockWriteGuard, RwLock};
 
 use crate::traits::pages::PagesNumberStorage;
 use crate::traits::{ChromaInfoStorage, IsIndexedStorage, MempoolStorage, PagesStorage};
@@ -11,7 +15,7 @@ use crate::traits::{ChromaInfoStorage, IsIndexedStorage, MempoolStorage, PagesSt
 use crate::MempoolEntryStorage;
 use crate::{
     traits::{FrozenTxsStorage, InvalidTxsStorage, InventoryStorage, TransactionsStorage},
-    BlockIndexerStorage, KeyValueStorage,
+    BatchOp, BlockIndexerStorage, KeyValueStorage, TxsEncoding, WriteBatch,
 };
 
 pub const DEFAULT_FLUSH_PERIOD_SECS: u64 = 600;
@@ -27,12 +31,33 @@ pub enum FlushStrategy {
     Disabled,
 }
 
+/// Name of the database's lock file, which is held by the open database and is not copied.
+const LOCK_FILE: &str = "LOCK";
+
 #[derive(Clone)]
-pub struct LevelDB(rusty_leveldb::AsyncDB);
+pub struct LevelDB {
+    db: rusty_leveldb::AsyncDB,
+    /// Directory of the database, `None` for the in-memory one.
+    path: Option<PathBuf>,
+    /// Held for reading by each write, and for writing while the writes are paused, e.g. while
+    /// the files of the database are copied.
+    writes: Arc<RwLock<()>>,
+    /// Encoding of the written transactions, see [`TransactionsStorage::txs_encoding`].
+    txs_encoding: TxsEncoding,
+}
 
 impl LevelDB {
-    fn new(db: rusty_leveldb::AsyncDB, flush_strategy: FlushStrategy) -> Self {
-        let db = Self(db);
+    fn new(
+        db: rusty_leveldb::AsyncDB,
+        path: Option<PathBuf>,
+        flush_strategy: FlushStrategy,
+    ) -> Self {
+        let db = Self {
+            db,
+            path,
+            writes: Arc::new(RwLock::new(())),
+            txs_encoding: TxsEncoding::default(),
+        };
 
         if let FlushStrategy::Ticker {
             period: flush_period,
@@ -48,7 +73,8 @@ impl LevelDB {
         tokio::spawn(async move {
             loop {
                 tokio::time::sleep(Duration::from_secs(flush_period)).await;
-                if (self.0.flush().await).is_ok() {}
+                let _writes = self.writes.read().await;
+                if (self.db.flush().await).is_ok() {}
             }
         });
     }
@@ -59,8 +85,8 @@ impl LevelDB {
             ..Default::default()
         };
 
-        let db = AsyncDB::new(config.path, opt)?;
-        Ok(Self::new(db, config.flush_strategy))
+        let db = AsyncDB::new(&config.path, opt)?;
+        Ok(Self::new(db, Some(config.path), config.flush_strategy))
     }
 
     pub fn in_memory() -> eyre::Result<Self> {
@@ -68,10 +94,64 @@ impl LevelDB {
 
         let db = AsyncDB::new("yuv-db", opt)?;
 
-        Ok(Self::new(db, FlushStrategy::Disabled))
+        Ok(Self::new(db, None, FlushStrategy::Disabled))
+    }
+
+    /// Sets the encoding of the written transactions. The transactions that are already stored
+    /// must be converted with the [`migrate_txs_encoding`](crate::migrate_txs_encoding).
+    pub fn with_txs_encoding(mut self, txs_encoding: TxsEncoding) -> Self {
+        self.txs_encoding = txs_encoding;
+        self
+    }
+
+    /// Pauses the writes to the database until the returned guard is dropped. The pending
+    /// writes are finished first, and the reads are not paused.
+    pub async fn pause_writes(&self) -> OwnedRwLockWriteGuard<()> {
+        self.writes.clone().write_owned().await
+    }
+
+    /// Copies the files of the database to the `dir`, which is created if it doesn't exist. The
+    /// table files are never modified once written, so they are hard-linked if the `dir` is on
+    /// the same filesystem. The writes must be paused and flushed, so the copy is consistent.
+    pub async fn copy_files(&self, dir: &Path) -> eyre::Result<()> {
+        let Some(path) = self.path.clone() else {
+            eyre::bail!("in-memory database can't be copied");
+        };
+
+        let dir = dir.to_path_buf();
+        tokio::task::spawn_blocking(move || copy_db_files(&path, &dir)).await??;
+
+        Ok(())
     }
 }
 
+/// Copies the files of the database from `src` to `dst`, skipping the lock file.
+fn copy_db_files(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() || entry.file_name() == LOCK_FILE {
+            continue;
+        }
+
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+
+        let is_table = matches!(
+            from.extension().and_then(|ext| ext.to_str()),
+            Some("ldb" | "sst")
+        );
+        if is_table && fs::hard_link(&from, &to).is_ok() {
+            continue;
+        }
+
+        fs::copy(&from, &to)?;
+    }
+
+    Ok(())
+}
+
 #[async_trait]
 impl<K, V> KeyValueStorage<K, V> for LevelDB
 where
@@ -81,23 +161,48 @@ where
     type Error = rusty_leveldb::Status;
 
     async fn raw_put(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), Self::Error> {
-        self.0.put(key, value).await
+        let _writes = self.writes.read().await;
+        self.db.put(key, value).await
     }
 
     async fn raw_get(&self, key: Vec<u8>) -> Result<Option<Vec<u8>>, Self::Error> {
-        self.0.get(key).await
+        self.db.get(key).await
     }
 
     async fn raw_delete(&self, key: Vec<u8>) -> Result<(), Self::Error> {
-        self.0.delete(key).await
+        let _writes = self.writes.read().await;
+        self.db.delete(key).await
+    }
+
+    async fn raw_write_batch(&self, batch: WriteBatch) -> Result<(), Self::Error> {
+        let mut leveldb_batch = rusty_leveldb::WriteBatch::new();
+        for op in batch.into_ops() {
+            match op {
+                BatchOp::Put { key, value } => leveldb_batch.put(&key, &value),
+                BatchOp::Delete { key } => leveldb_batch.delete(&key),
+            }
+        }
+
+        let _writes = self.writes.read().await;
+        self.db.write(leveldb_batch, false).await
     }
 
     async fn flush(&self) -> Result<(), Self::Error> {
-        self.0.flush().await
+        self.db.flush().await
+    }
+
+    /// Compacts the whole key range, as the deleted entries are only dropped on compaction.
+    async fn compact(&self) -> Result<(), Self::Error> {
+        let _writes = self.writes.read().await;
+        self.db.compact_range(&[0x00], &[0xff]).await
     }
 }
 
-impl TransactionsStorage for LevelDB {}
+impl TransactionsStorage for LevelDB {
+    fn txs_encoding(&self) -> TxsEncoding {
+        self.txs_encoding
+    }
+}
 
 impl InvalidTxsStorage for LevelDB {}
 
@@ -111,6 +216,10 @@ impl BlockIndexerStorage for LevelDB {}
 
 impl FrozenTxsStorage for LevelDB {}
 
+impl crate::FrozenPubkeysStorage for LevelDB {}
+
+impl crate::DelegatesStorage for LevelDB {}
+
 impl ChromaInfoStorage for LevelDB {}
 
 impl MempoolStorage for LevelDB {}
@@ -118,3 +227,26 @@ impl MempoolStorage for LevelDB {}
 impl MempoolEntryStorage for LevelDB {}
 
 impl IsIndexedStorage for LevelDB {}
+
+impl crate::TxDependentsStorage for LevelDB {}
+
+impl crate::SupplyTreeStorage for LevelDB {}
+
+impl crate::AddressIndexStorage for LevelDB {}
+
+impl crate::TransferHistoryStorage for LevelDB {}
+
+impl crate::PeerAddressBookStorage for LevelDB {}
+
+impl crate::PruningStorage for LevelDB {}
+
+impl crate::WalletProofsStorage for LevelDB {}
+
+impl crate::AttachStorage for LevelDB {}
+
+impl crate::WebhookQueueStorage for LevelDB {}
+impl crate::EventSinkStorage for LevelDB {}
+
+impl crate::ProofsDedupStatsStorage for LevelDB {}
+
+impl crate::ProofsStorage for LevelDB {}
@@ -1,2 +1,6 @@
 #[cfg(feature = "leveldb")]
+pub mod any;
+#[cfg(feature = "leveldb")]
 pub mod leveldb;
+#[cfg(feature = "postgres")]
+pub mod postgres;
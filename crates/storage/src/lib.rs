@@ -2,13 +2,48 @@
 mod traits;
 pub use traits::KeyValueError;
 pub use traits::{
-    BlockIndexerStorage, ChromaInfoStorage, FrozenTxsStorage, InvalidTxsStorage, InventoryStorage,
-    IsIndexedStorage, KeyValueResult, KeyValueStorage, MempoolEntryStorage, MempoolStatus,
-    MempoolStorage, MempoolTxEntry, PagesNumberStorage, PagesStorage, TransactionsStorage,
+    AddressIndexStorage, AttachStorage, BlockIndexerStorage, ChromaInfoStorage, DelegateEntry,
+    DelegatesStorage, EventSinkMessage, EventSinkQueue, EventSinkStorage, FrozenPubkeysStorage,
+    FrozenTxsStorage, InvalidTxsStorage, InventoryStorage, IsIndexedStorage, KeyValueResult,
+    KeyValueStorage, MempoolEntryStorage, MempoolStatus, MempoolStorage, MempoolTxEntry,
+    PagesNumberStorage, PagesStorage, PeerAddressBookStorage, PeerAddressEntry, PeerBanEntry,
+    ProofEntry, ProofHash, ProofRefMap, ProofsDedupStats, ProofsDedupStatsStorage, ProofsStorage,
+    PruningStorage, PubkeyFreezeEntry, RejectionReason, StoredYuvTransaction, StoredYuvTxType,
+    SupplyTreeStorage, TransactionsStorage, TransferHistoryEntry, TransferHistoryStorage,
+    TxDependentsStorage, TxFreezeEntry, TxPosition, TxRejection, TxsEncoding, WalletProofsStorage,
+    WebhookDelivery, WebhookQueue, WebhookQueueStorage,
 };
 
+mod batch;
+pub use batch::{BatchOp, WriteBatch};
+
+mod pages_check;
+pub use pages_check::{check_pages, PagesCheck};
+
+mod txs_migration;
+pub use txs_migration::migrate_txs_encoding;
+
+mod dependents_backfill;
+pub use dependents_backfill::backfill_tx_dependents;
+
+mod pruner;
+pub use pruner::{Pruner, DEFAULT_PRUNING_RETENTION_BLOCKS};
+
 mod impls;
 #[cfg(feature = "leveldb")]
+pub use impls::any::{AnyStorage, AnyStorageError};
+#[cfg(feature = "leveldb")]
 pub use impls::leveldb::{
     FlushStrategy, LevelDB, Options as LevelDbOptions, DEFAULT_FLUSH_PERIOD_SECS,
 };
+#[cfg(feature = "postgres")]
+pub use impls::postgres::{Options as PostgresOptions, Postgres};
+
+#[cfg(feature = "leveldb")]
+mod snapshot;
+#[cfg(feature = "leveldb")]
+pub use snapshot::{
+    restore_snapshot, validate_restored_snapshot, SnapshotMetadata, Snapshotter,
+    RESTORED_SNAPSHOT_FILE, SNAPSHOT_METADATA_FILE, SNAPSHOT_VERSION, STATE_STORAGE_DIR,
+    TXS_STORAGE_DIR,
+};
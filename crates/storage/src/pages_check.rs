@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+
+use crate::{KeyValueResult, PagesStorage, PruningStorage, TransactionsStorage, WriteBatch};
+
+/// Result of the [`check_pages`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PagesCheck {
+    /// Number of the transactions in the pages after the repair.
+    pub txs_number: usize,
+    /// Number of the removed ids of the transactions that are missing in the storage.
+    pub missing_txs: usize,
+    /// Number of the removed ids that are already in the previous pages.
+    pub duplicate_txs: usize,
+    /// Number of the pages that were written after the last one, but not counted.
+    pub uncounted_pages: u64,
+}
+
+impl PagesCheck {
+    /// Returns `true` if the pages were inconsistent and are repaired.
+    pub fn is_repaired(&self) -> bool {
+        self.missing_txs > 0 || self.duplicate_txs > 0 || self.uncounted_pages > 0
+    }
+}
+
+/// Checks that the pages reference only the stored or pruned transactions, each only once, and
+/// that all the written pages are counted. The inconsistent pages, left by the node killed in the
+/// middle of the attachment by the previous versions, are repaired in one atomic batch.
+pub async fn check_pages<S>(storage: &S) -> KeyValueResult<PagesCheck>
+where
+    S: TransactionsStorage + PagesStorage + PruningStorage + Send + Sync,
+{
+    let mut check = PagesCheck::default();
+    let mut batch = WriteBatch::new();
+
+    let mut pages_number = storage.get_pages_number().await?.unwrap_or_default();
+    while storage.get_page_by_num(pages_number + 1).await?.is_some() {
+        pages_number += 1;
+        check.uncounted_pages += 1;
+    }
+
+    if check.uncounted_pages > 0 {
+        storage.batch_put_pages_number(&mut batch, pages_number)?;
+    }
+
+    let mut seen = HashSet::new();
+    for page_num in 0..=pages_number {
+        let Some(page) = storage.get_page_by_num(page_num).await? else {
+            continue;
+        };
+
+        let mut checked_page = Vec::with_capacity(page.len());
+        for txid in &page {
+            if !seen.insert(*txid) {
+                check.duplicate_txs += 1;
+                continue;
+            }
+
+            let is_stored = storage.get_yuv_tx(txid).await?.is_some()
+                || storage.get_pruned_tx(txid).await?.is_some();
+            if !is_stored {
+                check.missing_txs += 1;
+                continue;
+            }
+
+            checked_page.push(*txid);
+        }
+
+        check.txs_number += checked_page.len();
+        if checked_page.len() != page.len() {
+            storage.batch_put_page(&mut batch, page_num, checked_page)?;
+        }
+    }
+
+    if !batch.is_empty() {
+        storage.write_pages_batch(batch).await?;
+    }
+
+    Ok(check)
+}
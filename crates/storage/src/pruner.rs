@@ -0,0 +1,94 @@
+use bitcoin::Txid;
+
+use crate::{
+    BlockIndexerStorage, KeyValueResult, PruningStorage, TransactionsStorage, TxDependentsStorage,
+};
+
+/// Default number of the recent blocks whose spent transactions are kept.
+pub const DEFAULT_PRUNING_RETENTION_BLOCKS: usize = 1000;
+
+/// Max number of the blocks whose transactions are pruned in one run, so a run on the node that
+/// was not pruned for a long time doesn't take too long.
+const MAX_BLOCKS_PER_RUN: usize = 10_000;
+
+/// Deletes the attached transactions whose outputs are all spent more than `retention` blocks
+/// ago, and marks them as pruned. The transactions are queued by the controller as their last
+/// outputs are spent, so only the ones attached with the pruning enabled are pruned.
+#[derive(Clone)]
+pub struct Pruner<TxsStorage, StateStorage> {
+    txs_storage: TxsStorage,
+    state_storage: StateStorage,
+    /// Number of the recent blocks whose spent transactions are kept, so the reorganizations
+    /// shallower than that could be rolled back.
+    retention: usize,
+}
+
+impl<TS, SS> Pruner<TS, SS>
+where
+    TS: TransactionsStorage + TxDependentsStorage + PruningStorage + Send + Sync,
+    SS: BlockIndexerStorage + Send + Sync,
+{
+    pub fn new(txs_storage: TS, state_storage: SS, retention: usize) -> Self {
+        Self {
+            txs_storage,
+            state_storage,
+            retention,
+        }
+    }
+
+    /// Prunes the queued transactions of the blocks below the retention window, and compacts
+    /// the storage if any were pruned. Returns the number of the pruned transactions.
+    pub async fn prune(&self) -> KeyValueResult<usize> {
+        let Some(cursor) = self.txs_storage.get_pruning_cursor().await? else {
+            // Nothing is queued yet.
+            return Ok(0);
+        };
+
+        let Some(last_indexed_height) = self.state_storage.get_last_indexed_height().await? else {
+            return Ok(0);
+        };
+
+        let Some(target) = last_indexed_height.checked_sub(self.retention) else {
+            return Ok(0);
+        };
+
+        if cursor > target {
+            return Ok(0);
+        }
+
+        let target = target.min(cursor + MAX_BLOCKS_PER_RUN - 1);
+
+        let mut pruned = 0;
+        for height in cursor..=target {
+            for txid in self.txs_storage.get_prunable_txs(height).await? {
+                if self.prune_tx(&txid, height).await? {
+                    pruned += 1;
+                }
+            }
+
+            self.txs_storage.delete_prunable_txs(height).await?;
+            self.txs_storage.put_pruning_cursor(height + 1).await?;
+        }
+
+        if pruned > 0 {
+            self.txs_storage.compact_pruned().await?;
+        }
+
+        Ok(pruned)
+    }
+
+    /// Prunes the transaction, unless its outputs were returned back by the rollback of the
+    /// spending transactions after it was queued.
+    async fn prune_tx(&self, txid: &Txid, height: usize) -> KeyValueResult<bool> {
+        if self.txs_storage.get_unspent_outputs(txid).await? != Some(0) {
+            return Ok(false);
+        }
+
+        self.txs_storage.delete_yuv_tx(txid).await?;
+        self.txs_storage.delete_tx_dependents(txid).await?;
+        self.txs_storage.delete_unspent_outputs(txid).await?;
+        self.txs_storage.put_pruned_tx(txid, height).await?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,195 @@
+//! Point-in-time snapshots of the node's LevelDB storages, taken without stopping the node.
+//!
+//! The snapshot directory contains the copies of the transactions and state databases, and the
+//! [`SnapshotMetadata`] with the last indexed block at the moment of the snapshot. The writes to
+//! both databases are paused while their files are copied, so the copies are consistent with
+//! each other, and the table files are hard-linked when possible, so the pause is short.
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bitcoin::BlockHash;
+use eyre::{bail, eyre, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::{BlockIndexerStorage, KeyValueStorage, LevelDB};
+
+/// Version of the snapshot format.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Name of the file with the [`SnapshotMetadata`] in the snapshot directory.
+pub const SNAPSHOT_METADATA_FILE: &str = "snapshot.json";
+
+/// Name of the file with the metadata of the restored snapshot in the storage directory. The file
+/// is removed once the restored storage is validated on the start of the node.
+pub const RESTORED_SNAPSHOT_FILE: &str = "restored-snapshot.json";
+
+/// Directory of the transactions database in the snapshot and storage directories.
+pub const TXS_STORAGE_DIR: &str = "transactions";
+
+/// Directory of the state database in the snapshot and storage directories.
+pub const STATE_STORAGE_DIR: &str = "state";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotMetadata {
+    pub version: u32,
+    /// Unix timestamp of the snapshot in seconds.
+    pub created_at: u64,
+    /// Height of the last block indexed at the moment of the snapshot.
+    pub block_height: usize,
+    /// Hash of the last block indexed at the moment of the snapshot.
+    pub block_hash: BlockHash,
+}
+
+impl SnapshotMetadata {
+    /// Reads the metadata from the file, and checks that its version is supported.
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let bytes =
+            fs::read(path).wrap_err_with(|| format!("failed to read the snapshot {path:?}"))?;
+
+        let metadata: Self = serde_json::from_slice(&bytes)
+            .wrap_err_with(|| format!("snapshot metadata {path:?} is malformed"))?;
+
+        if metadata.version != SNAPSHOT_VERSION {
+            bail!(
+                "unsupported snapshot version {}, expected {SNAPSHOT_VERSION}",
+                metadata.version
+            );
+        }
+
+        Ok(metadata)
+    }
+
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+
+        fs::write(path, bytes).wrap_err_with(|| format!("failed to write {path:?}"))
+    }
+}
+
+/// Creates the snapshots of the running node's storages.
+#[derive(Clone)]
+pub struct Snapshotter {
+    txs_storage: LevelDB,
+    state_storage: LevelDB,
+}
+
+impl Snapshotter {
+    pub fn new(txs_storage: LevelDB, state_storage: LevelDB) -> Self {
+        Self {
+            txs_storage,
+            state_storage,
+        }
+    }
+
+    /// Creates the snapshot in the `dir`, which must not exist. The writes to the storages are
+    /// paused until the files are copied.
+    pub async fn create(&self, dir: &Path) -> eyre::Result<SnapshotMetadata> {
+        if dir.exists() {
+            bail!("snapshot directory {dir:?} already exists");
+        }
+
+        let _txs_writes = self.txs_storage.pause_writes().await;
+        let _state_writes = self.state_storage.pause_writes().await;
+
+        KeyValueStorage::<(), ()>::flush(&self.txs_storage).await?;
+        KeyValueStorage::<(), ()>::flush(&self.state_storage).await?;
+
+        let (Some(block_height), Some(block_hash)) = (
+            self.state_storage.get_last_indexed_height().await?,
+            self.state_storage.get_last_indexed_hash().await?,
+        ) else {
+            bail!("no blocks are indexed yet");
+        };
+
+        let metadata = SnapshotMetadata {
+            version: SNAPSHOT_VERSION,
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            block_height,
+            block_hash,
+        };
+
+        let result = self.copy_files(dir, &metadata).await;
+        if result.is_err() {
+            // Don't leave the partial snapshot, which could be restored by mistake.
+            let _ = fs::remove_dir_all(dir);
+        }
+
+        result.map(|()| metadata)
+    }
+
+    async fn copy_files(&self, dir: &Path, metadata: &SnapshotMetadata) -> eyre::Result<()> {
+        self.txs_storage
+            .copy_files(&dir.join(TXS_STORAGE_DIR))
+            .await
+            .wrap_err("failed to copy the transactions storage")?;
+
+        self.state_storage
+            .copy_files(&dir.join(STATE_STORAGE_DIR))
+            .await
+            .wrap_err("failed to copy the state storage")?;
+
+        metadata.write(&dir.join(SNAPSHOT_METADATA_FILE))
+    }
+}
+
+/// Copies the snapshot from the `snapshot_dir` to the empty `storage_dir` of the stopped node,
+/// and marks the storage as restored, so it's validated on the start of the node.
+pub fn restore_snapshot(snapshot_dir: &Path, storage_dir: &Path) -> eyre::Result<SnapshotMetadata> {
+    let metadata = SnapshotMetadata::read(&snapshot_dir.join(SNAPSHOT_METADATA_FILE))?;
+
+    for name in [TXS_STORAGE_DIR, STATE_STORAGE_DIR] {
+        let dir = storage_dir.join(name);
+        if dir.exists() && fs::read_dir(&dir)?.next().is_some() {
+            bail!("storage {dir:?} is not empty");
+        }
+    }
+
+    for name in [TXS_STORAGE_DIR, STATE_STORAGE_DIR] {
+        let (from, to) = (snapshot_dir.join(name), storage_dir.join(name));
+        fs::create_dir_all(&to)?;
+
+        for entry in fs::read_dir(&from).wrap_err_with(|| format!("failed to read {from:?}"))? {
+            let entry = entry?;
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+
+    metadata.write(&storage_dir.join(RESTORED_SNAPSHOT_FILE))?;
+
+    Ok(metadata)
+}
+
+/// Checks that the storage restored from the snapshot is at the snapshot's block, and removes
+/// the mark of the restored storage. Returns the snapshot's metadata, `None` if the storage
+/// wasn't restored.
+pub async fn validate_restored_snapshot<S>(
+    storage_dir: &Path,
+    state_storage: &S,
+) -> eyre::Result<Option<SnapshotMetadata>>
+where
+    S: BlockIndexerStorage + Send + Sync,
+{
+    let path = storage_dir.join(RESTORED_SNAPSHOT_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let metadata = SnapshotMetadata::read(&path)?;
+
+    let block_height = state_storage.get_last_indexed_height().await?;
+    let block_hash = state_storage.get_last_indexed_hash().await?;
+
+    if block_height != Some(metadata.block_height) || block_hash != Some(metadata.block_hash) {
+        return Err(eyre!(
+            "restored storage is at block {block_hash:?} ({block_height:?}), but the snapshot \
+            is at block {} ({})",
+            metadata.block_hash,
+            metadata.block_height,
+        ));
+    }
+
+    fs::remove_file(&path).wrap_err_with(|| format!("failed to remove {path:?}"))?;
+
+    Ok(Some(metadata))
+}
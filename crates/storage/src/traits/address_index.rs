@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use bitcoin::{secp256k1::XOnlyPublicKey, OutPoint};
+use serde_bytes::ByteArray;
+use yuv_pixels::Pixel;
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const KEY_PREFIX: &str = "addr-";
+const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
+
+/// Size of the serialized x-only public key.
+const XONLY_KEY_SIZE: usize = 32;
+
+const KEY_SIZE: usize = KEY_PREFIX_SIZE + XONLY_KEY_SIZE;
+
+const BUILT_KEY_SIZE: usize = 19;
+const BUILT_KEY: &[u8; BUILT_KEY_SIZE] = b"address-index-built";
+
+fn address_key(pubkey: &XOnlyPublicKey) -> ByteArray<KEY_SIZE> {
+    let mut bytes = [0u8; KEY_SIZE];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(KEY_PREFIX.as_bytes());
+    bytes[KEY_PREFIX_SIZE..].copy_from_slice(&pubkey.serialize());
+
+    ByteArray::new(bytes)
+}
+
+/// Unspent pixel outputs of the attached transactions by the keys of their owners. The keys are
+/// x-only, so the outputs are found regardless of the parity of the owner's key:
+///
+/// - `b"addr-"` + [`XOnlyPublicKey`]: pixels of the unspent outputs by their outpoints.
+/// - `b"address-index-built"`: set when the index has all the paged transactions, so it doesn't
+///   need to be built from the pages.
+#[async_trait]
+pub trait AddressIndexStorage:
+    KeyValueStorage<ByteArray<KEY_SIZE>, BTreeMap<OutPoint, Pixel>>
+    + KeyValueStorage<[u8; BUILT_KEY_SIZE], ()>
+{
+    async fn get_address_utxos(
+        &self,
+        pubkey: &XOnlyPublicKey,
+    ) -> KeyValueResult<BTreeMap<OutPoint, Pixel>> {
+        self.get(address_key(pubkey))
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    /// Put the unspent outputs of the key. The entry is deleted if there are none.
+    async fn put_address_utxos(
+        &self,
+        pubkey: &XOnlyPublicKey,
+        utxos: BTreeMap<OutPoint, Pixel>,
+    ) -> KeyValueResult<()> {
+        if utxos.is_empty() {
+            return KeyValueStorage::<ByteArray<KEY_SIZE>, BTreeMap<OutPoint, Pixel>>::delete(
+                self,
+                address_key(pubkey),
+            )
+            .await;
+        }
+
+        self.put(address_key(pubkey), utxos).await
+    }
+
+    async fn is_address_index_built(&self) -> KeyValueResult<bool> {
+        let built: Option<()> = self.get(*BUILT_KEY).await?;
+
+        Ok(built.is_some())
+    }
+
+    async fn put_address_index_built(&self) -> KeyValueResult<()> {
+        self.put(*BUILT_KEY, ()).await
+    }
+
+    async fn delete_address_index_built(&self) -> KeyValueResult<()> {
+        KeyValueStorage::<[u8; BUILT_KEY_SIZE], ()>::delete(self, *BUILT_KEY).await
+    }
+}
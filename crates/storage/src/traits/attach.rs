@@ -0,0 +1,300 @@
+use std::mem::size_of;
+
+use async_trait::async_trait;
+use bitcoin::{hashes::Hash, Txid};
+use serde_bytes::ByteArray;
+use yuv_types::YuvTransaction;
+
+use crate::{KeyValueResult, KeyValueStorage, PagesStorage, TransactionsStorage, WriteBatch};
+
+const ATTACH_LOG_KEY_SIZE: usize = 10;
+const ATTACH_LOG_KEY: &[u8; ATTACH_LOG_KEY_SIZE] = b"attach-log";
+
+const CLEAN_SHUTDOWN_KEY_SIZE: usize = 14;
+const CLEAN_SHUTDOWN_KEY: &[u8; CLEAN_SHUTDOWN_KEY_SIZE] = b"clean-shutdown";
+
+const TX_POSITION_KEY_PREFIX: &str = "txpos-";
+const TX_POSITION_KEY_PREFIX_SIZE: usize = TX_POSITION_KEY_PREFIX.len();
+
+/// Position key size is 6(`TX_POSITION_KEY_PREFIX:[u8; 6]`) + 32(`Txid`) = 38 bytes long
+const TX_POSITION_KEY_SIZE: usize = TX_POSITION_KEY_PREFIX_SIZE + size_of::<Txid>();
+
+fn tx_position_key(txid: &Txid) -> ByteArray<TX_POSITION_KEY_SIZE> {
+    let mut bytes = [0u8; TX_POSITION_KEY_SIZE];
+
+    bytes[..TX_POSITION_KEY_PREFIX_SIZE].copy_from_slice(TX_POSITION_KEY_PREFIX.as_bytes());
+    bytes[TX_POSITION_KEY_PREFIX_SIZE..].copy_from_slice(txid.as_raw_hash().as_byte_array());
+
+    ByteArray::new(bytes)
+}
+
+/// Position of the mined transaction in the chain. The attached transactions are ordered in the
+/// pages by their positions.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct TxPosition {
+    /// Height of the block the transaction is mined in.
+    pub block_height: usize,
+    /// Index of the transaction in the block.
+    pub block_index: usize,
+}
+
+impl TxPosition {
+    pub fn new(block_height: usize, block_index: usize) -> Self {
+        Self {
+            block_height,
+            block_index,
+        }
+    }
+}
+
+/// Atomic attachment of the transactions to the pages:
+///
+/// - `b"attach-log"`: ids of the transactions that are written to the pages, but the rest of
+///   their attachment, e.g. the removal from the mempool, isn't finished yet.
+/// - `b"clean-shutdown"`: set when the node is stopped gracefully, and removed on the start, so
+///   the pages are checked only after the crash.
+/// - `b"txpos-"` + [`Txid`]: [`TxPosition`] of the paged transaction.
+#[async_trait]
+pub trait AttachStorage:
+    TransactionsStorage
+    + PagesStorage
+    + KeyValueStorage<[u8; ATTACH_LOG_KEY_SIZE], Vec<Txid>>
+    + KeyValueStorage<[u8; CLEAN_SHUTDOWN_KEY_SIZE], ()>
+    + KeyValueStorage<ByteArray<TX_POSITION_KEY_SIZE>, TxPosition>
+{
+    /// Write the transactions, add their ids to the pages, and record them in the attach log in
+    /// one atomic batch, so the pages never reference the missing transactions and never miss
+    /// the written ones.
+    ///
+    /// The transactions are given in the canonical order along with the `positions` of the mined
+    /// ones. The paged transactions mined after the attached ones are moved after them, so the
+    /// pages don't depend on the batches the transactions are attached in. The transactions with
+    /// no position are appended to the end.
+    async fn attach_txs(
+        &self,
+        txs: &[YuvTransaction],
+        positions: &[Option<TxPosition>],
+        tx_per_page: u64,
+    ) -> KeyValueResult<()> {
+        let mut batch = WriteBatch::new();
+
+        let mut page_num = self.get_pages_number().await?.unwrap_or_default();
+        let mut page = self.get_page_by_num(page_num).await?.unwrap_or_default();
+
+        // Paged transactions mined after the earliest attached one, in the reverse order.
+        let mut moved = Vec::new();
+        if let Some(earliest) = positions.iter().flatten().min() {
+            loop {
+                let Some(txid) = page.last() else {
+                    if page_num == 0 {
+                        break;
+                    }
+
+                    page_num -= 1;
+                    page = self.get_page_by_num(page_num).await?.unwrap_or_default();
+                    continue;
+                };
+
+                // The transactions paged before their positions were recorded stay in place.
+                match self.get_tx_position(txid).await? {
+                    Some(position) if position > *earliest => {
+                        moved.push((*txid, Some(position)));
+                        page.pop();
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        let mut attach_log = self.get_attach_log().await?;
+
+        let mut paged = moved;
+        paged.reverse();
+        for (index, tx) in txs.iter().enumerate() {
+            let txid = tx.bitcoin_tx.txid();
+            self.batch_put_yuv_tx(&mut batch, tx.clone()).await?;
+
+            let position = positions.get(index).copied().flatten();
+            if let Some(position) = position {
+                batch.put(tx_position_key(&txid), position)?;
+            }
+
+            paged.push((txid, position));
+            attach_log.push(txid);
+        }
+
+        // The sort is stable, so the transactions with no position keep the canonical order.
+        paged.sort_by_key(|(_, position)| (position.is_none(), *position));
+
+        let page_changed = !paged.is_empty();
+        for (txid, _) in paged {
+            if page.len() as u64 >= tx_per_page {
+                self.batch_put_page(&mut batch, page_num, std::mem::take(&mut page))?;
+                page_num += 1;
+                self.batch_put_pages_number(&mut batch, page_num)?;
+            }
+
+            page.push(txid);
+        }
+
+        if page_changed {
+            self.batch_put_page(&mut batch, page_num, page)?;
+        }
+
+        batch.put(*ATTACH_LOG_KEY, attach_log)?;
+
+        self.write_pages_batch(batch).await
+    }
+
+    async fn get_tx_position(&self, txid: &Txid) -> KeyValueResult<Option<TxPosition>> {
+        KeyValueStorage::<ByteArray<TX_POSITION_KEY_SIZE>, TxPosition>::get(
+            self,
+            tx_position_key(txid),
+        )
+        .await
+    }
+
+    async fn put_tx_position(&self, txid: &Txid, position: TxPosition) -> KeyValueResult<()> {
+        KeyValueStorage::<ByteArray<TX_POSITION_KEY_SIZE>, TxPosition>::put(
+            self,
+            tx_position_key(txid),
+            position,
+        )
+        .await
+    }
+
+    async fn delete_tx_position(&self, txid: &Txid) -> KeyValueResult<()> {
+        KeyValueStorage::<ByteArray<TX_POSITION_KEY_SIZE>, TxPosition>::delete(
+            self,
+            tx_position_key(txid),
+        )
+        .await
+    }
+
+    async fn get_attach_log(&self) -> KeyValueResult<Vec<Txid>> {
+        self.get(*ATTACH_LOG_KEY)
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    async fn delete_attach_log(&self) -> KeyValueResult<()> {
+        KeyValueStorage::<[u8; ATTACH_LOG_KEY_SIZE], Vec<Txid>>::delete(self, *ATTACH_LOG_KEY).await
+    }
+
+    async fn is_clean_shutdown(&self) -> KeyValueResult<bool> {
+        let clean_shutdown: Option<()> = self.get(*CLEAN_SHUTDOWN_KEY).await?;
+
+        Ok(clean_shutdown.is_some())
+    }
+
+    async fn put_clean_shutdown(&self) -> KeyValueResult<()> {
+        self.put(*CLEAN_SHUTDOWN_KEY, ()).await
+    }
+
+    async fn delete_clean_shutdown(&self) -> KeyValueResult<()> {
+        KeyValueStorage::<[u8; CLEAN_SHUTDOWN_KEY_SIZE], ()>::delete(self, *CLEAN_SHUTDOWN_KEY)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "leveldb"))]
+mod tests {
+    use bitcoin::absolute::LockTime;
+
+    use super::*;
+    use crate::LevelDB;
+
+    fn transfer(lock_time: u32) -> YuvTransaction {
+        let mut tx: YuvTransaction = serde_json::from_str(include_str!(
+            "../../../tx-check/src/tests/assets/singlechroma_valid_transfer.json"
+        ))
+        .unwrap();
+        tx.bitcoin_tx.lock_time = LockTime::from_consensus(lock_time);
+
+        tx
+    }
+
+    async fn paged_txids(storage: &LevelDB) -> Vec<Txid> {
+        let pages_number = storage
+            .get_pages_number()
+            .await
+            .unwrap()
+            .unwrap_or_default();
+
+        let mut txids = Vec::new();
+        for page_num in 0..=pages_number {
+            txids.extend(
+                storage
+                    .get_page_by_num(page_num)
+                    .await
+                    .unwrap()
+                    .unwrap_or_default(),
+            );
+        }
+
+        txids
+    }
+
+    #[test]
+    fn test_pages_do_not_depend_on_batches() {
+        tokio_test::block_on(async {
+            let txs = (1..=5).map(transfer).collect::<Vec<_>>();
+            let positions = [(10, 1), (10, 3), (11, 0), (12, 2), (12, 5)]
+                .map(|(height, index)| Some(TxPosition::new(height, index)));
+            let txids = txs
+                .iter()
+                .map(|tx| tx.bitcoin_tx.txid())
+                .collect::<Vec<_>>();
+
+            // The transactions mined earlier are attached in the later batches.
+            let storage = LevelDB::in_memory().unwrap();
+            storage
+                .attach_txs(&txs[3..], &positions[3..], 2)
+                .await
+                .unwrap();
+            storage
+                .attach_txs(&txs[1..3], &positions[1..3], 2)
+                .await
+                .unwrap();
+            storage
+                .attach_txs(&txs[..1], &positions[..1], 2)
+                .await
+                .unwrap();
+
+            assert_eq!(paged_txids(&storage).await, txids);
+            assert_eq!(storage.get_pages_number().await.unwrap(), Some(2));
+            assert_eq!(
+                storage.get_page_by_num(0).await.unwrap(),
+                Some(txids[..2].to_vec())
+            );
+            assert_eq!(
+                storage.get_tx_position(&txids[2]).await.unwrap(),
+                positions[2]
+            );
+
+            // The transactions with no position are appended to the end.
+            let unmined_tx = transfer(6);
+            storage
+                .attach_txs(&[unmined_tx.clone()], &[None], 2)
+                .await
+                .unwrap();
+
+            let single_batch_storage = LevelDB::in_memory().unwrap();
+            single_batch_storage
+                .attach_txs(&txs, &positions, 2)
+                .await
+                .unwrap();
+            single_batch_storage
+                .attach_txs(&[unmined_tx.clone()], &[None], 2)
+                .await
+                .unwrap();
+
+            let mut expected = txids;
+            expected.push(unmined_tx.bitcoin_tx.txid());
+            assert_eq!(paged_txids(&storage).await, expected);
+            assert_eq!(paged_txids(&single_batch_storage).await, expected);
+        });
+    }
+}
@@ -1,3 +1,5 @@
+use std::mem::size_of;
+
 use async_trait::async_trait;
 
 use bitcoin::ScriptBuf;
@@ -21,15 +23,43 @@ fn get_storage_key(chroma: &Chroma) -> ByteArray<KEY_SIZE> {
     ByteArray::new(bytes)
 }
 
-/// It is a key-value storage for the [`ChromaAnnouncement`] and total supply.
+const VERSION_KEY_PREFIX: &str = "chrmv-";
+const VERSION_KEY_PREFIX_SIZE: usize = VERSION_KEY_PREFIX.len();
+
+const VERSION_KEY_SIZE: usize = VERSION_KEY_PREFIX_SIZE + CHROMA_SIZE + size_of::<u32>();
+
+fn get_version_storage_key(chroma: &Chroma, version: u32) -> ByteArray<VERSION_KEY_SIZE> {
+    let mut bytes = [0u8; VERSION_KEY_SIZE];
+
+    bytes[..VERSION_KEY_PREFIX_SIZE].copy_from_slice(VERSION_KEY_PREFIX.as_bytes());
+    bytes[VERSION_KEY_PREFIX_SIZE..VERSION_KEY_PREFIX_SIZE + CHROMA_SIZE]
+        .copy_from_slice(&chroma.to_bytes());
+    bytes[VERSION_KEY_PREFIX_SIZE + CHROMA_SIZE..].copy_from_slice(&version.to_be_bytes());
+
+    ByteArray::new(bytes)
+}
+
+/// It is a key-value storage for the [`ChromaAnnouncement`] with its version, total supply and
+/// total burnt amount.
 ///
 /// - key: `b"chrm-"` + [`Chroma`]
 /// - value: [`ChromaInfo`][`ChromaAnnouncement`]
+///
+/// Each version of the [`ChromaAnnouncement`], i.e. the initial one and the ones updated with the
+/// [`UpdateChromaAnnouncement`]s, is kept along with it:
+///
+/// - key: `b"chrmv-"` + [`Chroma`] + `u32` version
+/// - value: [`ChromaAnnouncement`]
+///
+/// [`UpdateChromaAnnouncement`]: yuv_types::announcements::UpdateChromaAnnouncement
 #[async_trait]
-pub trait ChromaInfoStorage: KeyValueStorage<ByteArray<KEY_SIZE>, ChromaInfo> {
+pub trait ChromaInfoStorage:
+    KeyValueStorage<ByteArray<KEY_SIZE>, ChromaInfo>
+    + KeyValueStorage<ByteArray<VERSION_KEY_SIZE>, ChromaAnnouncement>
+{
     /// Get the [`ChromaAnnouncement`] for the given [`Chroma`].
     async fn get_chroma_info(&self, chroma: &Chroma) -> KeyValueResult<Option<ChromaInfo>> {
-        self.get(get_storage_key(chroma)).await
+        KeyValueStorage::<ByteArray<KEY_SIZE>, ChromaInfo>::get(self, get_storage_key(chroma)).await
     }
 
     /// Put the [`ChromaAnnouncement`] for the given [`Chroma`].
@@ -38,16 +68,61 @@ pub trait ChromaInfoStorage: KeyValueStorage<ByteArray<KEY_SIZE>, ChromaInfo> {
         chroma: &Chroma,
         announcement: Option<ChromaAnnouncement>,
         total_supply: u128,
+        total_burned: u128,
+        announcement_version: u32,
         owner: Option<ScriptBuf>,
     ) -> KeyValueResult<()> {
-        self.put(
+        KeyValueStorage::<ByteArray<KEY_SIZE>, ChromaInfo>::put(
+            self,
             get_storage_key(chroma),
             ChromaInfo {
                 announcement,
                 total_supply,
                 owner,
+                total_burned,
+                announcement_version,
             },
         )
         .await
     }
+
+    /// Get the `version` of the [`ChromaAnnouncement`] for the given [`Chroma`].
+    async fn get_chroma_announcement_version(
+        &self,
+        chroma: &Chroma,
+        version: u32,
+    ) -> KeyValueResult<Option<ChromaAnnouncement>> {
+        KeyValueStorage::<ByteArray<VERSION_KEY_SIZE>, ChromaAnnouncement>::get(
+            self,
+            get_version_storage_key(chroma, version),
+        )
+        .await
+    }
+
+    /// Put the `version` of the [`ChromaAnnouncement`] for the given [`Chroma`].
+    async fn put_chroma_announcement_version(
+        &self,
+        chroma: &Chroma,
+        version: u32,
+        announcement: ChromaAnnouncement,
+    ) -> KeyValueResult<()> {
+        KeyValueStorage::<ByteArray<VERSION_KEY_SIZE>, ChromaAnnouncement>::put(
+            self,
+            get_version_storage_key(chroma, version),
+            announcement,
+        )
+        .await
+    }
+
+    async fn delete_chroma_announcement_version(
+        &self,
+        chroma: &Chroma,
+        version: u32,
+    ) -> KeyValueResult<()> {
+        KeyValueStorage::<ByteArray<VERSION_KEY_SIZE>, ChromaAnnouncement>::delete(
+            self,
+            get_version_storage_key(chroma, version),
+        )
+        .await
+    }
 }
@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use bitcoin::{secp256k1, secp256k1::constants::PUBLIC_KEY_SIZE, Txid};
+use serde_bytes::ByteArray;
+use yuv_pixels::{Chroma, CHROMA_SIZE};
+use yuv_types::announcements::DelegateCapabilities;
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const KEY_PREFIX: &str = "dlgt-";
+const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
+
+/// Delegates storage key size is:
+///
+/// 5 bytes (`KEY_PREFIX`) + 32 bytes (`chroma`) + 33 bytes (`pubkey`) = 70 bytes long
+const DELEGATE_STORAGE_KEY_SIZE: usize = KEY_PREFIX_SIZE + CHROMA_SIZE + PUBLIC_KEY_SIZE;
+
+fn delegate_storage_key(
+    chroma: &Chroma,
+    delegate: &secp256k1::PublicKey,
+) -> ByteArray<DELEGATE_STORAGE_KEY_SIZE> {
+    let mut bytes = [0u8; DELEGATE_STORAGE_KEY_SIZE];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(KEY_PREFIX.as_bytes());
+    bytes[KEY_PREFIX_SIZE..KEY_PREFIX_SIZE + CHROMA_SIZE].copy_from_slice(&chroma.to_bytes());
+    bytes[KEY_PREFIX_SIZE + CHROMA_SIZE..].copy_from_slice(&delegate.serialize());
+
+    ByteArray::new(bytes)
+}
+
+/// Storage of the public keys the chroma's capabilities are delegated to.
+///
+/// - key: `b"dlgt-"` + [`Chroma`] + public key
+/// - value: [`DelegateEntry`]
+#[async_trait]
+pub trait DelegatesStorage:
+    KeyValueStorage<ByteArray<DELEGATE_STORAGE_KEY_SIZE>, DelegateEntry>
+{
+    /// Get the capabilities of the `chroma` delegated to the `delegate`, if there are any.
+    async fn get_delegate(
+        &self,
+        chroma: &Chroma,
+        delegate: &secp256k1::PublicKey,
+    ) -> KeyValueResult<Option<DelegateEntry>> {
+        self.get(delegate_storage_key(chroma, delegate)).await
+    }
+
+    /// Grant the `capabilities` of the `chroma` to the `delegate`, replacing the previous ones.
+    async fn put_delegate(
+        &self,
+        chroma: &Chroma,
+        delegate: &secp256k1::PublicKey,
+        capabilities: DelegateCapabilities,
+        delegate_txid: Txid,
+    ) -> KeyValueResult<()> {
+        self.put(
+            delegate_storage_key(chroma, delegate),
+            DelegateEntry::new(delegate_txid, capabilities),
+        )
+        .await
+    }
+
+    /// Revoke all the capabilities of the `chroma` delegated to the `delegate`.
+    async fn delete_delegate(
+        &self,
+        chroma: &Chroma,
+        delegate: &secp256k1::PublicKey,
+    ) -> KeyValueResult<()> {
+        self.delete(delegate_storage_key(chroma, delegate)).await
+    }
+}
+
+/// Storage entry of the delegate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct DelegateEntry {
+    /// Identifier of the transaction that granted the capabilities.
+    pub txid: Txid,
+    /// The granted capabilities.
+    pub capabilities: DelegateCapabilities,
+}
+
+impl DelegateEntry {
+    pub fn new(txid: Txid, capabilities: DelegateCapabilities) -> Self {
+        Self { txid, capabilities }
+    }
+}
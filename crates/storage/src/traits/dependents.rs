@@ -0,0 +1,91 @@
+use std::mem::size_of;
+
+use async_trait::async_trait;
+use bitcoin::{hashes::Hash, Txid};
+use serde_bytes::ByteArray;
+use yuv_types::{YuvTransaction, YuvTxType};
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const KEY_PREFIX: &str = "deps-";
+const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
+
+/// Dependents storage key size is 5(`KEY_PREFIX:[u8; 5]`) + 32(`Txid`) = 37 bytes long
+const DEPENDENTS_KEY_SIZE: usize = KEY_PREFIX_SIZE + size_of::<Txid>();
+
+const BACKFILLED_KEY_SIZE: usize = 15;
+/// Key for the [`KeyValueStorage`] where the completion of the
+/// [`backfill_tx_dependents`](crate::backfill_tx_dependents) is recorded.
+const BACKFILLED_KEY: &[u8; BACKFILLED_KEY_SIZE] = b"deps-backfilled";
+
+fn dependents_key(txid: &Txid) -> ByteArray<DEPENDENTS_KEY_SIZE> {
+    let mut bytes = [0u8; DEPENDENTS_KEY_SIZE];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(KEY_PREFIX.as_bytes());
+    bytes[KEY_PREFIX_SIZE..].copy_from_slice(txid.as_raw_hash().as_byte_array());
+
+    ByteArray::new(bytes)
+}
+
+/// Inverse dependency graph of the attached transactions:
+///
+/// - key: `b"deps-"` + parent [`Txid`]
+/// - value: ids of the attached transactions that spend the parent's outputs.
+#[async_trait]
+pub trait TxDependentsStorage:
+    KeyValueStorage<ByteArray<DEPENDENTS_KEY_SIZE>, Vec<Txid>>
+    + KeyValueStorage<[u8; BACKFILLED_KEY_SIZE], bool>
+{
+    async fn get_tx_dependents(&self, txid: &Txid) -> KeyValueResult<Vec<Txid>> {
+        self.get(dependents_key(txid))
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    /// Records the attached transfer as a dependent of its parents, so it could be found when
+    /// one of the parents is rolled back.
+    async fn add_yuv_tx_dependencies(&self, yuv_tx: &YuvTransaction) -> KeyValueResult<()> {
+        let YuvTxType::Transfer { input_proofs, .. } = &yuv_tx.tx_type else {
+            return Ok(());
+        };
+
+        let child_id = yuv_tx.bitcoin_tx.txid();
+        for input in input_proofs.keys() {
+            let Some(parent) = yuv_tx.bitcoin_tx.input.get(*input as usize) else {
+                debug_assert!(false, "Input proof index is out of bounds");
+                continue;
+            };
+
+            self.add_tx_dependent(&parent.previous_output.txid, child_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn add_tx_dependent(&self, parent: &Txid, child: Txid) -> KeyValueResult<()> {
+        let mut dependents = self.get_tx_dependents(parent).await?;
+        if dependents.contains(&child) {
+            return Ok(());
+        }
+
+        dependents.push(child);
+        self.put(dependents_key(parent), dependents).await
+    }
+
+    async fn delete_tx_dependents(&self, txid: &Txid) -> KeyValueResult<()> {
+        self.delete(dependents_key(txid)).await
+    }
+
+    /// Whether the dependents of the transactions attached before the index was added are
+    /// recorded.
+    async fn is_tx_dependents_backfilled(&self) -> KeyValueResult<bool> {
+        self.get(*BACKFILLED_KEY)
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    async fn put_tx_dependents_backfilled(&self) -> KeyValueResult<()> {
+        self.put(*BACKFILLED_KEY, true).await
+    }
+}
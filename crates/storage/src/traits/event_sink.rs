@@ -0,0 +1,44 @@
+use std::collections::VecDeque;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const EVENT_SINK_QUEUE_KEY_SIZE: usize = 16;
+const EVENT_SINK_QUEUE_KEY: &[u8; EVENT_SINK_QUEUE_KEY_SIZE] = b"event-sink-queue";
+
+/// Event waiting to be published to the event sink, e.g. Kafka or NATS.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EventSinkMessage {
+    /// Unique id of the event, the same for all the attempts to publish it.
+    pub id: u64,
+    /// Topic or subject the event is published to.
+    pub topic: String,
+    /// Key of the message, e.g. the id of the transaction.
+    pub key: String,
+    /// JSON payload of the event.
+    pub payload: String,
+}
+
+/// Queue of the events that aren't acknowledged by the event sink yet, which survives the node
+/// restarts.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventSinkQueue {
+    /// Id of the next event.
+    pub next_id: u64,
+    pub messages: VecDeque<EventSinkMessage>,
+}
+
+#[async_trait]
+pub trait EventSinkStorage:
+    KeyValueStorage<[u8; EVENT_SINK_QUEUE_KEY_SIZE], EventSinkQueue>
+{
+    async fn get_event_sink_queue(&self) -> KeyValueResult<EventSinkQueue> {
+        Ok(self.get(*EVENT_SINK_QUEUE_KEY).await?.unwrap_or_default())
+    }
+
+    async fn put_event_sink_queue(&self, queue: EventSinkQueue) -> KeyValueResult<()> {
+        self.put(*EVENT_SINK_QUEUE_KEY, queue).await
+    }
+}
@@ -35,16 +35,24 @@ pub trait FrozenTxsStorage:
         self.get(frozen_tx_storage_key(outpoint)).await
     }
 
+    /// Freeze the output. If the `amount` is set, only that amount of the output is frozen. If
+    /// the `expiry_height` is set, the output is unfrozen starting from the block at that height.
     async fn put_frozen_tx(
         &self,
         outpoint: &OutPoint,
         freeze_tx_id: Txid,
         chroma: Chroma,
+        amount: Option<u128>,
+        expiry_height: Option<u32>,
     ) -> KeyValueResult<()> {
-        let freeze_entry = TxFreezeEntry::new(freeze_tx_id, chroma);
+        let freeze_entry = TxFreezeEntry::new(freeze_tx_id, chroma, amount, expiry_height);
         self.put(frozen_tx_storage_key(outpoint), freeze_entry)
             .await
     }
+
+    async fn delete_frozen_tx(&self, outpoint: &OutPoint) -> KeyValueResult<()> {
+        self.delete(frozen_tx_storage_key(outpoint)).await
+    }
 }
 
 /// Storage entry that stores the transaction identifiers that tried to freeze the output.
@@ -55,10 +63,40 @@ pub struct TxFreezeEntry {
 
     /// Chroma of the output to freeze.
     pub chroma: Chroma,
+
+    /// Frozen amount of the partially frozen output. If not set, the whole output is frozen.
+    #[serde(default)]
+    pub amount: Option<u128>,
+
+    /// Height of the block starting from which the output is unfrozen. If not set, the output is
+    /// frozen forever.
+    #[serde(default)]
+    pub expiry_height: Option<u32>,
 }
 
 impl TxFreezeEntry {
-    pub fn new(txid: Txid, chroma: Chroma) -> Self {
-        Self { txid, chroma }
+    pub fn new(
+        txid: Txid,
+        chroma: Chroma,
+        amount: Option<u128>,
+        expiry_height: Option<u32>,
+    ) -> Self {
+        Self {
+            txid,
+            chroma,
+            amount,
+            expiry_height,
+        }
+    }
+
+    /// Return `true` if only the part of the output is frozen.
+    pub fn is_partial(&self) -> bool {
+        self.amount.is_some()
+    }
+
+    /// Return `true` if the freeze is no longer active at the given block height.
+    pub fn is_expired(&self, height: usize) -> bool {
+        self.expiry_height
+            .is_some_and(|expiry_height| height >= expiry_height as usize)
     }
 }
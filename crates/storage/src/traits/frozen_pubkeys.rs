@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use bitcoin::{secp256k1, secp256k1::constants::PUBLIC_KEY_SIZE, Txid};
+use serde_bytes::ByteArray;
+use yuv_pixels::{Chroma, CHROMA_SIZE};
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const KEY_PREFIX: &str = "frzp-";
+const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
+
+/// Frozen public keys storage key size is:
+///
+/// 5 bytes (`KEY_PREFIX`) + 32 bytes (`chroma`) + 33 bytes (`pubkey`) = 70 bytes long
+const FROZEN_PUBKEY_STORAGE_KEY_SIZE: usize = KEY_PREFIX_SIZE + CHROMA_SIZE + PUBLIC_KEY_SIZE;
+
+fn frozen_pubkey_storage_key(
+    chroma: &Chroma,
+    pubkey: &secp256k1::PublicKey,
+) -> ByteArray<FROZEN_PUBKEY_STORAGE_KEY_SIZE> {
+    let mut bytes = [0u8; FROZEN_PUBKEY_STORAGE_KEY_SIZE];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(KEY_PREFIX.as_bytes());
+    bytes[KEY_PREFIX_SIZE..KEY_PREFIX_SIZE + CHROMA_SIZE].copy_from_slice(&chroma.to_bytes());
+    bytes[KEY_PREFIX_SIZE + CHROMA_SIZE..].copy_from_slice(&pubkey.serialize());
+
+    ByteArray::new(bytes)
+}
+
+/// Storage of the public keys whose pixels of the chroma are frozen.
+///
+/// - key: `b"frzp-"` + [`Chroma`] + public key
+/// - value: [`PubkeyFreezeEntry`]
+#[async_trait]
+pub trait FrozenPubkeysStorage:
+    KeyValueStorage<ByteArray<FROZEN_PUBKEY_STORAGE_KEY_SIZE>, PubkeyFreezeEntry>
+{
+    /// Get the freeze of the `pubkey`'s pixels of the `chroma`, if they are frozen.
+    async fn get_frozen_pubkey(
+        &self,
+        chroma: &Chroma,
+        pubkey: &secp256k1::PublicKey,
+    ) -> KeyValueResult<Option<PubkeyFreezeEntry>> {
+        self.get(frozen_pubkey_storage_key(chroma, pubkey)).await
+    }
+
+    /// Freeze the `pubkey`'s pixels of the `chroma`.
+    async fn put_frozen_pubkey(
+        &self,
+        chroma: &Chroma,
+        pubkey: &secp256k1::PublicKey,
+        freeze_txid: Txid,
+    ) -> KeyValueResult<()> {
+        self.put(
+            frozen_pubkey_storage_key(chroma, pubkey),
+            PubkeyFreezeEntry::new(freeze_txid),
+        )
+        .await
+    }
+
+    /// Unfreeze the `pubkey`'s pixels of the `chroma`.
+    async fn delete_frozen_pubkey(
+        &self,
+        chroma: &Chroma,
+        pubkey: &secp256k1::PublicKey,
+    ) -> KeyValueResult<()> {
+        self.delete(frozen_pubkey_storage_key(chroma, pubkey)).await
+    }
+}
+
+/// Storage entry of the frozen public key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct PubkeyFreezeEntry {
+    /// Identifier of the transaction that froze the public key.
+    pub txid: Txid,
+}
+
+impl PubkeyFreezeEntry {
+    pub fn new(txid: Txid) -> Self {
+        Self { txid }
+    }
+}
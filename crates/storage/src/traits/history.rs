@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use bitcoin::{secp256k1::XOnlyPublicKey, Txid};
+use serde_bytes::ByteArray;
+use yuv_pixels::{Chroma, CHROMA_SIZE};
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const KEY_PREFIX: &str = "hist-";
+const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
+
+/// Size of the serialized x-only public key.
+const XONLY_KEY_SIZE: usize = 32;
+
+const KEY_SIZE: usize = KEY_PREFIX_SIZE + CHROMA_SIZE + XONLY_KEY_SIZE;
+
+fn history_key(chroma: &Chroma, pubkey: &XOnlyPublicKey) -> ByteArray<KEY_SIZE> {
+    let mut bytes = [0u8; KEY_SIZE];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(KEY_PREFIX.as_bytes());
+    bytes[KEY_PREFIX_SIZE..KEY_PREFIX_SIZE + CHROMA_SIZE].copy_from_slice(&chroma.to_bytes());
+    bytes[KEY_PREFIX_SIZE + CHROMA_SIZE..].copy_from_slice(&pubkey.serialize());
+
+    ByteArray::new(bytes)
+}
+
+/// Change of the balance of the key made by the attached transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TransferHistoryEntry {
+    /// Height of the block the transaction is mined in.
+    pub height: usize,
+    pub txid: Txid,
+    /// Received amount minus the spent one.
+    pub delta: i128,
+}
+
+/// Transfer history of the attached transactions by the chroma and the x-only key of the owner:
+///
+/// - key: `b"hist-"` + [`Chroma`] + [`XOnlyPublicKey`]
+/// - value: changes of the balance sorted by the height.
+#[async_trait]
+pub trait TransferHistoryStorage:
+    KeyValueStorage<ByteArray<KEY_SIZE>, Vec<TransferHistoryEntry>>
+{
+    async fn get_transfer_history(
+        &self,
+        chroma: &Chroma,
+        pubkey: &XOnlyPublicKey,
+    ) -> KeyValueResult<Vec<TransferHistoryEntry>> {
+        self.get(history_key(chroma, pubkey))
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    /// Put the transfer history of the key. The entry is deleted if the history is empty.
+    async fn put_transfer_history(
+        &self,
+        chroma: &Chroma,
+        pubkey: &XOnlyPublicKey,
+        history: Vec<TransferHistoryEntry>,
+    ) -> KeyValueResult<()> {
+        if history.is_empty() {
+            return self.delete(history_key(chroma, pubkey)).await;
+        }
+
+        self.put(history_key(chroma, pubkey), history).await
+    }
+}
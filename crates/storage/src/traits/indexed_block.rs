@@ -10,8 +10,14 @@ const IS_INDEXED_KEY: &[u8; IS_INDEXED_KEY_SIZE] = b"07-22-2024-bugfix";
 const INDEXED_BLOCK_KEY_SIZE: usize = 13;
 const INDEXED_BLOCK_KEY: &[u8; INDEXED_BLOCK_KEY_SIZE] = b"indexed_block";
 
+const INDEXED_HEIGHT_KEY_SIZE: usize = 14;
+const INDEXED_HEIGHT_KEY: &[u8; INDEXED_HEIGHT_KEY_SIZE] = b"indexed_height";
+
 #[async_trait]
-pub trait BlockIndexerStorage: KeyValueStorage<[u8; INDEXED_BLOCK_KEY_SIZE], BlockHash> {
+pub trait BlockIndexerStorage:
+    KeyValueStorage<[u8; INDEXED_BLOCK_KEY_SIZE], BlockHash>
+    + KeyValueStorage<[u8; INDEXED_HEIGHT_KEY_SIZE], usize>
+{
     async fn get_last_indexed_hash(&self) -> KeyValueResult<Option<BlockHash>> {
         Ok(self.get(*INDEXED_BLOCK_KEY).await?)
     }
@@ -19,6 +25,15 @@ pub trait BlockIndexerStorage: KeyValueStorage<[u8; INDEXED_BLOCK_KEY_SIZE], Blo
     async fn put_last_indexed_hash(&self, block_hash: BlockHash) -> KeyValueResult<()> {
         self.put(*INDEXED_BLOCK_KEY, block_hash).await
     }
+
+    /// Height of the last indexed block. Used to determine which protocol features are active.
+    async fn get_last_indexed_height(&self) -> KeyValueResult<Option<usize>> {
+        Ok(self.get(*INDEXED_HEIGHT_KEY).await?)
+    }
+
+    async fn put_last_indexed_height(&self, height: usize) -> KeyValueResult<()> {
+        self.put(*INDEXED_HEIGHT_KEY, height).await
+    }
 }
 
 #[async_trait]
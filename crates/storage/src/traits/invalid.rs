@@ -1,3 +1,4 @@
+use std::fmt;
 use std::mem::size_of;
 
 use async_trait::async_trait;
@@ -10,11 +11,19 @@ use crate::{KeyValueResult, KeyValueStorage};
 const KEY_PREFIX: &str = "inv-";
 const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
 
+const REJECTION_KEY_PREFIX: &str = "rejection-";
+const REJECTION_KEY_PREFIX_SIZE: usize = REJECTION_KEY_PREFIX.len();
+
 /// Invalid transactions key size is:
 ///
 /// 4 bytes (`INVALID_TXS_PREFIX`) + 32 bytes (`txid`) = 36 bytes long
 const INVALID_TXS_KEY_SIZE: usize = KEY_PREFIX_SIZE + size_of::<Txid>();
 
+/// Rejections key size is:
+///
+/// 10 bytes (`REJECTION_KEY_PREFIX`) + 32 bytes (`txid`) = 42 bytes long
+const REJECTION_KEY_SIZE: usize = REJECTION_KEY_PREFIX_SIZE + size_of::<Txid>();
+
 fn invalid_txs_storage_key(txid: Txid) -> ByteArray<INVALID_TXS_KEY_SIZE> {
     let mut bytes = [0u8; INVALID_TXS_KEY_SIZE];
 
@@ -24,28 +33,112 @@ fn invalid_txs_storage_key(txid: Txid) -> ByteArray<INVALID_TXS_KEY_SIZE> {
     ByteArray::new(bytes)
 }
 
+fn rejection_storage_key(txid: Txid) -> ByteArray<REJECTION_KEY_SIZE> {
+    let mut bytes = [0u8; REJECTION_KEY_SIZE];
+
+    bytes[..REJECTION_KEY_PREFIX_SIZE].copy_from_slice(REJECTION_KEY_PREFIX.as_bytes());
+    bytes[REJECTION_KEY_PREFIX_SIZE..].copy_from_slice(txid.as_raw_hash().as_byte_array());
+
+    ByteArray::new(bytes)
+}
+
+/// Check the transaction failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    /// Proofs, signatures or amounts of the transaction are invalid.
+    IsolatedCheck,
+    /// Transaction uses the protocol feature that isn't active yet.
+    FeatureNotActive,
+    /// Issuance isn't signed by the owner of the chroma or exceeds its max supply.
+    InvalidIssuance,
+    /// Announcement isn't signed by the owner of the chroma or isn't allowed by it.
+    InvalidAnnouncement,
+    /// Transfer burns more tokens than there are in circulation.
+    BurnExceedsSupply,
+    /// Transfer spends the frozen tokens.
+    FrozenInput,
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IsolatedCheck => write!(f, "transaction failed the isolated check"),
+            Self::FeatureNotActive => write!(f, "protocol feature is not active"),
+            Self::InvalidIssuance => write!(f, "issuance is invalid"),
+            Self::InvalidAnnouncement => write!(f, "announcement is invalid"),
+            Self::BurnExceedsSupply => write!(f, "burnt amount exceeds the circulating supply"),
+            Self::FrozenInput => write!(f, "transfer spends frozen tokens"),
+        }
+    }
+}
+
+/// Rejection of the invalid transaction, kept so the wallets can learn why their submissions
+/// failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TxRejection {
+    pub reason: RejectionReason,
+    /// Human-readable details of the rejection.
+    pub message: String,
+}
+
+impl TxRejection {
+    pub fn new(reason: RejectionReason, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<RejectionReason> for TxRejection {
+    fn from(reason: RejectionReason) -> Self {
+        Self::new(reason, reason.to_string())
+    }
+}
+
 #[async_trait]
 pub trait InvalidTxsStorage:
     KeyValueStorage<ByteArray<INVALID_TXS_KEY_SIZE>, YuvTransaction>
+    + KeyValueStorage<ByteArray<REJECTION_KEY_SIZE>, TxRejection>
 {
     async fn get_invalid_tx(&self, txid: Txid) -> KeyValueResult<Option<YuvTransaction>> {
         self.get(invalid_txs_storage_key(txid)).await
     }
 
-    async fn put_invalid_tx(&self, tx: YuvTransaction) -> KeyValueResult<()> {
-        self.put(invalid_txs_storage_key(tx.bitcoin_tx.txid()), tx)
-            .await
+    async fn put_invalid_tx(
+        &self,
+        tx: YuvTransaction,
+        rejection: TxRejection,
+    ) -> KeyValueResult<()> {
+        let txid = tx.bitcoin_tx.txid();
+
+        self.put(invalid_txs_storage_key(txid), tx).await?;
+        self.put(rejection_storage_key(txid), rejection).await
     }
 
-    async fn put_invalid_txs(&self, txs: Vec<YuvTransaction>) -> KeyValueResult<()> {
-        for tx in txs {
-            self.put_invalid_tx(tx).await?;
+    async fn put_invalid_txs(&self, txs: Vec<(YuvTransaction, TxRejection)>) -> KeyValueResult<()> {
+        for (tx, rejection) in txs {
+            self.put_invalid_tx(tx, rejection).await?;
         }
 
         Ok(())
     }
 
+    async fn get_tx_rejection(&self, txid: Txid) -> KeyValueResult<Option<TxRejection>> {
+        self.get(rejection_storage_key(txid)).await
+    }
+
     async fn delete_invalid_tx(&self, txid: Txid) -> KeyValueResult<()> {
-        self.delete(invalid_txs_storage_key(txid)).await
+        KeyValueStorage::<ByteArray<INVALID_TXS_KEY_SIZE>, YuvTransaction>::delete(
+            self,
+            invalid_txs_storage_key(txid),
+        )
+        .await?;
+        KeyValueStorage::<ByteArray<REJECTION_KEY_SIZE>, TxRejection>::delete(
+            self,
+            rejection_storage_key(txid),
+        )
+        .await
     }
 }
@@ -6,7 +6,7 @@ use bitcoin::{hashes::Hash, Txid};
 use serde_bytes::ByteArray;
 use yuv_types::YuvTransaction;
 
-use crate::{KeyValueResult, KeyValueStorage};
+use crate::{KeyValueResult, KeyValueStorage, TxPosition};
 
 const MEMPOOL_KEY_SIZE: usize = 8;
 const MEMPOOL_KEY: &[u8; MEMPOOL_KEY_SIZE] = b"mempool-";
@@ -55,6 +55,9 @@ pub trait MempoolEntryStorage:
 /// Consists of:
 /// - yuv_tx: full YUV transaction data [`YuvTransaction`].
 /// - status: current status of the transaction [`MempoolStatus`].
+/// - block_height: height of the block the transaction is mined in.
+/// - block_index: index of the transaction in the block.
+/// - provisionally_attached: whether the transaction is in the pages before it's mined.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct MempoolTxEntry {
     /// YUV transaction itself.
@@ -65,6 +68,17 @@ pub struct MempoolTxEntry {
     /// * Some if transactions received from p2p network
     /// * None if transactions received via json rpc
     pub sender: Option<SocketAddr>,
+    /// Height of the block the transaction is mined in. `None` if it isn't mined yet. Used to
+    /// order the attached transactions in the pages.
+    #[serde(default)]
+    pub block_height: Option<usize>,
+    /// Index of the transaction in the block it is mined in. `None` if it isn't mined yet.
+    #[serde(default)]
+    pub block_index: Option<usize>,
+    /// Whether the transaction is written to the storage and the pages by the zero-conf mode
+    /// before it's mined. It's removed from them if it never gets attached in full.
+    #[serde(default)]
+    pub provisionally_attached: bool,
 }
 
 impl MempoolTxEntry {
@@ -73,9 +87,17 @@ impl MempoolTxEntry {
             yuv_tx,
             status,
             sender,
+            block_height: None,
+            block_index: None,
+            provisionally_attached: false,
         }
     }
 
+    /// Returns the position of the mined transaction in the chain.
+    pub fn position(&self) -> Option<TxPosition> {
+        Some(TxPosition::new(self.block_height?, self.block_index?))
+    }
+
     /// Returns the [`Txid`] of the entry's YUV transaction.
     pub fn txid(&self) -> Txid {
         self.yuv_tx.bitcoin_tx.txid()
@@ -98,4 +120,7 @@ pub enum MempoolStatus {
     Attaching,
     #[deprecated]
     Pending,
+    /// Low-value transfer that is accepted from the Bitcoin mempool before it's mined. It's
+    /// re-validated once mined, or rolled back if it never gets mined.
+    ProvisionallyAttached,
 }
@@ -4,10 +4,12 @@ use async_trait::async_trait;
 
 mod transactions;
 use serde::{de::DeserializeOwned, Serialize};
-pub use transactions::TransactionsStorage;
+pub use transactions::{StoredYuvTransaction, StoredYuvTxType, TransactionsStorage, TxsEncoding};
+
+use crate::WriteBatch;
 
 mod invalid;
-pub use invalid::InvalidTxsStorage;
+pub use invalid::{InvalidTxsStorage, RejectionReason, TxRejection};
 
 mod mempool;
 pub use mempool::{MempoolEntryStorage, MempoolStatus, MempoolStorage, MempoolTxEntry};
@@ -23,11 +25,52 @@ mod indexed_block;
 pub use indexed_block::{BlockIndexerStorage, IsIndexedStorage};
 
 mod frozen;
-pub use frozen::FrozenTxsStorage;
+pub use frozen::{FrozenTxsStorage, TxFreezeEntry};
+
+mod frozen_pubkeys;
+pub use frozen_pubkeys::{FrozenPubkeysStorage, PubkeyFreezeEntry};
+
+mod delegates;
+pub use delegates::{DelegateEntry, DelegatesStorage};
 
 mod chroma_info;
 pub use chroma_info::ChromaInfoStorage;
 
+mod dependents;
+pub use dependents::TxDependentsStorage;
+
+mod supply;
+pub use supply::SupplyTreeStorage;
+
+mod address_index;
+pub use address_index::AddressIndexStorage;
+
+mod history;
+pub use history::{TransferHistoryEntry, TransferHistoryStorage};
+
+mod peers;
+pub use peers::{PeerAddressBookStorage, PeerAddressEntry, PeerBanEntry};
+
+mod pruning;
+pub use pruning::PruningStorage;
+
+mod attach;
+pub use attach::{AttachStorage, TxPosition};
+
+mod webhooks;
+pub use webhooks::{WebhookDelivery, WebhookQueue, WebhookQueueStorage};
+
+mod event_sink;
+pub use event_sink::{EventSinkMessage, EventSinkQueue, EventSinkStorage};
+
+mod wallet_proofs;
+pub use wallet_proofs::WalletProofsStorage;
+
+mod proofs;
+pub use proofs::{
+    ProofEntry, ProofHash, ProofRefMap, ProofsDedupStats, ProofsDedupStatsStorage, ProofsStorage,
+};
+
 pub type KeyValueResult<T> = Result<T, KeyValueError>;
 
 #[async_trait]
@@ -41,9 +84,17 @@ where
     async fn raw_put(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), Self::Error>;
     async fn raw_get(&self, key: Vec<u8>) -> Result<Option<Vec<u8>>, Self::Error>;
     async fn raw_delete(&self, key: Vec<u8>) -> Result<(), Self::Error>;
+    /// Applies all the operations of the batch atomically.
+    async fn raw_write_batch(&self, batch: WriteBatch) -> Result<(), Self::Error>;
 
     async fn flush(&self) -> Result<(), Self::Error>;
 
+    /// Reclaims the space of the deleted entries. Does nothing by default, as most of the
+    /// backends do it by themselves.
+    async fn compact(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     async fn put(&self, key: K, value: V) -> KeyValueResult<()> {
         let key = cbor_to_vec(key)?;
         let value = cbor_to_vec(value)?;
@@ -77,9 +128,17 @@ where
             .await
             .map_err(|err| KeyValueError::Storage(Box::new(err)))
     }
+
+    async fn write_batch(&self, batch: WriteBatch) -> KeyValueResult<()> {
+        self.raw_write_batch(batch)
+            .await
+            .map_err(|err| KeyValueError::Storage(Box::new(err)))
+    }
 }
 
-fn cbor_to_vec<K: Serialize>(key: K) -> Result<Vec<u8>, ciborium::ser::Error<io::Error>> {
+pub(crate) fn cbor_to_vec<K: Serialize>(
+    key: K,
+) -> Result<Vec<u8>, ciborium::ser::Error<io::Error>> {
     let mut buf = Vec::new();
     ciborium::into_writer(&key, &mut buf)?;
     Ok(buf)
@@ -97,6 +156,8 @@ pub enum KeyValueError {
     Encoding(ciborium::ser::Error<io::Error>),
     #[error("Storage error: {0}")]
     Storage(Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("Referenced proof {0} is missing")]
+    MissingProof(ProofHash),
 }
 
 impl From<ciborium::de::Error<io::Error>> for KeyValueError {
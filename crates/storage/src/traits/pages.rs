@@ -1,6 +1,6 @@
 use std::mem::size_of;
 
-use crate::{KeyValueResult, KeyValueStorage};
+use crate::{KeyValueResult, KeyValueStorage, WriteBatch};
 use async_trait::async_trait;
 use bitcoin::Txid;
 
@@ -25,6 +25,14 @@ pub trait PagesNumberStorage: KeyValueStorage<[u8; PAGES_NUMBER_KEY_SIZE], u64>
     async fn get_pages_number(&self) -> KeyValueResult<Option<u64>> {
         Ok(self.get(*PAGES_NUMBER_KEY).await?)
     }
+
+    fn batch_put_pages_number(
+        &self,
+        batch: &mut WriteBatch,
+        pages_number: u64,
+    ) -> KeyValueResult<()> {
+        batch.put(*PAGES_NUMBER_KEY, pages_number)
+    }
 }
 
 fn page_key(page_num: u64) -> [u8; PAGE_KEY_SIZE] {
@@ -44,7 +52,25 @@ pub trait PagesStorage:
         self.put(page_key(page_num), page).await
     }
 
+    fn batch_put_page(
+        &self,
+        batch: &mut WriteBatch,
+        page_num: u64,
+        page: Vec<Txid>,
+    ) -> KeyValueResult<()> {
+        batch.put(page_key(page_num), page)
+    }
+
     async fn get_page_by_num(&self, num: u64) -> KeyValueResult<Option<Vec<Txid>>> {
         Ok(self.get(page_key(num)).await?)
     }
+
+    async fn delete_page(&self, page_num: u64) -> KeyValueResult<()> {
+        self.delete(page_key(page_num)).await
+    }
+
+    /// Commit the batch with the updates of the pages atomically.
+    async fn write_pages_batch(&self, batch: WriteBatch) -> KeyValueResult<()> {
+        KeyValueStorage::<[u8; PAGE_KEY_SIZE], Vec<Txid>>::write_batch(self, batch).await
+    }
 }
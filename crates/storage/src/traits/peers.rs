@@ -0,0 +1,55 @@
+use std::net::{IpAddr, SocketAddr};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const PEER_ADDRESSES_KEY_SIZE: usize = 14;
+const PEER_ADDRESSES_KEY: &[u8; PEER_ADDRESSES_KEY_SIZE] = b"peer-addresses";
+
+const PEER_BANS_KEY_SIZE: usize = 9;
+const PEER_BANS_KEY: &[u8; PEER_BANS_KEY_SIZE] = b"peer-bans";
+
+/// Known address of the P2P peer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PeerAddressEntry {
+    pub addr: SocketAddr,
+    /// Services advertised by the peer.
+    pub services: u64,
+    /// Unix timestamp (in seconds) of the last time the peer was seen alive.
+    pub last_active: Option<u64>,
+    /// Unix timestamp (in seconds) of the last successful handshake with the peer.
+    pub last_success: Option<u64>,
+}
+
+/// Ban of the misbehaving P2P peer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PeerBanEntry {
+    pub ip: IpAddr,
+    /// Unix timestamp (in seconds) until which the peer is banned.
+    pub banned_until: u64,
+}
+
+/// Address book of the P2P client, which survives the node restarts.
+#[async_trait]
+pub trait PeerAddressBookStorage:
+    KeyValueStorage<[u8; PEER_ADDRESSES_KEY_SIZE], Vec<PeerAddressEntry>>
+    + KeyValueStorage<[u8; PEER_BANS_KEY_SIZE], Vec<PeerBanEntry>>
+{
+    async fn get_peer_addresses(&self) -> KeyValueResult<Vec<PeerAddressEntry>> {
+        Ok(self.get(*PEER_ADDRESSES_KEY).await?.unwrap_or_default())
+    }
+
+    async fn put_peer_addresses(&self, addresses: Vec<PeerAddressEntry>) -> KeyValueResult<()> {
+        self.put(*PEER_ADDRESSES_KEY, addresses).await
+    }
+
+    async fn get_peer_bans(&self) -> KeyValueResult<Vec<PeerBanEntry>> {
+        Ok(self.get(*PEER_BANS_KEY).await?.unwrap_or_default())
+    }
+
+    async fn put_peer_bans(&self, bans: Vec<PeerBanEntry>) -> KeyValueResult<()> {
+        self.put(*PEER_BANS_KEY, bans).await
+    }
+}
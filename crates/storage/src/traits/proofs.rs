@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use bitcoin::hashes::{sha256, Hash};
+use serde_bytes::ByteArray;
+use yuv_pixels::PixelProof;
+use yuv_types::ProofMap;
+
+use super::cbor_to_vec;
+use crate::{KeyValueError, KeyValueResult, KeyValueStorage};
+
+const KEY_PREFIX: &str = "prf-";
+const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
+
+/// Proof storage key size is 4(`KEY_PREFIX:[u8; 4]`) + 32(`ProofHash`) = 36 bytes long
+const PROOF_STORAGE_KEY_SIZE: usize = KEY_PREFIX_SIZE + sha256::Hash::LEN;
+
+const DEDUP_STATS_KEY_SIZE: usize = 15;
+/// Key for the [`KeyValueStorage`] where the proofs deduplication statistics are stored.
+const DEDUP_STATS_KEY: &[u8; DEDUP_STATS_KEY_SIZE] = b"prf-dedup-stats";
+
+/// Content address of the [`PixelProof`]: SHA256 hash of its serialized representation.
+pub type ProofHash = sha256::Hash;
+
+/// Maps inputs or outputs ids to the content addresses of their [`PixelProof`]s.
+pub type ProofRefMap = BTreeMap<u32, ProofHash>;
+
+fn proof_storage_key(hash: &ProofHash) -> ByteArray<PROOF_STORAGE_KEY_SIZE> {
+    let mut bytes = [0u8; PROOF_STORAGE_KEY_SIZE];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(KEY_PREFIX.as_bytes());
+    bytes[KEY_PREFIX_SIZE..].copy_from_slice(hash.as_byte_array());
+
+    ByteArray::new(bytes)
+}
+
+/// Storage entry of the deduplicated proof.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ProofEntry {
+    /// The proof itself.
+    pub proof: PixelProof,
+    /// Number of transactions' inputs and outputs that reference the proof. When it reaches
+    /// zero, the proof is removed from the storage.
+    pub refs: u64,
+    /// Size of the serialized proof in bytes.
+    pub size: u64,
+}
+
+/// Measurements of the space saved by the proofs deduplication.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct ProofsDedupStats {
+    /// Total size of the proofs in bytes as if they were stored without deduplication.
+    pub logical_bytes: u64,
+    /// Size of the proofs in bytes that are actually stored.
+    pub stored_bytes: u64,
+}
+
+impl ProofsDedupStats {
+    /// Number of bytes saved by the deduplication.
+    pub fn saved_bytes(&self) -> u64 {
+        self.logical_bytes.saturating_sub(self.stored_bytes)
+    }
+
+    /// Ratio of the logical size to the stored one. `1.0` means that nothing was saved.
+    pub fn dedup_ratio(&self) -> f64 {
+        if self.stored_bytes == 0 {
+            return 1.0;
+        }
+
+        self.logical_bytes as f64 / self.stored_bytes as f64
+    }
+}
+
+#[async_trait]
+pub trait ProofsDedupStatsStorage:
+    KeyValueStorage<[u8; DEDUP_STATS_KEY_SIZE], ProofsDedupStats>
+{
+    async fn get_proofs_dedup_stats(&self) -> KeyValueResult<ProofsDedupStats> {
+        self.get(*DEDUP_STATS_KEY)
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    async fn put_proofs_dedup_stats(&self, stats: ProofsDedupStats) -> KeyValueResult<()> {
+        self.put(*DEDUP_STATS_KEY, stats).await
+    }
+}
+
+/// Content-addressed storage of the [`PixelProof`]s with the reference counting.
+///
+/// - key: `b"prf-"` + [`ProofHash`]
+/// - value: [`ProofEntry`]
+///
+/// Byte-identical proofs are stored once, transactions store only the [`ProofHash`]es. Each
+/// [`ProofsStorage::acquire_proof`] call must be paired with a [`ProofsStorage::release_proof`]
+/// one when the referencing transaction is removed.
+#[async_trait]
+pub trait ProofsStorage:
+    KeyValueStorage<ByteArray<PROOF_STORAGE_KEY_SIZE>, ProofEntry> + ProofsDedupStatsStorage
+{
+    async fn get_proof(&self, hash: &ProofHash) -> KeyValueResult<Option<PixelProof>> {
+        self.get(proof_storage_key(hash))
+            .await
+            .map(|entry| entry.map(|entry| entry.proof))
+    }
+
+    /// Store the proof if it is not stored yet and increment its references counter.
+    async fn acquire_proof(&self, proof: PixelProof) -> KeyValueResult<ProofHash> {
+        let bytes = cbor_to_vec(&proof)?;
+        let hash = sha256::Hash::hash(&bytes);
+        let key = proof_storage_key(&hash);
+
+        let mut stats = self.get_proofs_dedup_stats().await?;
+        let entry = match self.get(key).await? {
+            Some(mut entry) => {
+                entry.refs += 1;
+                entry
+            }
+            None => {
+                stats.stored_bytes += bytes.len() as u64;
+
+                ProofEntry {
+                    proof,
+                    refs: 1,
+                    size: bytes.len() as u64,
+                }
+            }
+        };
+        stats.logical_bytes += entry.size;
+
+        self.put(key, entry).await?;
+        self.put_proofs_dedup_stats(stats).await?;
+
+        Ok(hash)
+    }
+
+    /// Decrement the references counter of the proof and remove it if there are no references
+    /// left.
+    async fn release_proof(&self, hash: &ProofHash) -> KeyValueResult<()> {
+        let key = proof_storage_key(hash);
+
+        let Some(mut entry) = self.get(key).await? else {
+            return Ok(());
+        };
+
+        let mut stats = self.get_proofs_dedup_stats().await?;
+        stats.logical_bytes = stats.logical_bytes.saturating_sub(entry.size);
+
+        entry.refs = entry.refs.saturating_sub(1);
+        if entry.refs == 0 {
+            stats.stored_bytes = stats.stored_bytes.saturating_sub(entry.size);
+            self.delete(key).await?;
+        } else {
+            self.put(key, entry).await?;
+        }
+
+        self.put_proofs_dedup_stats(stats).await
+    }
+
+    async fn acquire_proofs(&self, proofs: ProofMap) -> KeyValueResult<ProofRefMap> {
+        let mut refs = ProofRefMap::new();
+
+        for (index, proof) in proofs {
+            refs.insert(index, self.acquire_proof(proof).await?);
+        }
+
+        Ok(refs)
+    }
+
+    async fn release_proofs(&self, refs: &ProofRefMap) -> KeyValueResult<()> {
+        for hash in refs.values() {
+            self.release_proof(hash).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the references into the proofs. Returns [`KeyValueError::MissingProof`] if any
+    /// of the referenced proofs is not found.
+    async fn resolve_proofs(&self, refs: ProofRefMap) -> KeyValueResult<ProofMap> {
+        let mut proofs = ProofMap::new();
+
+        for (index, hash) in refs {
+            let proof = self
+                .get_proof(&hash)
+                .await?
+                .ok_or(KeyValueError::MissingProof(hash))?;
+
+            proofs.insert(index, proof);
+        }
+
+        Ok(proofs)
+    }
+}
@@ -0,0 +1,131 @@
+use std::mem::size_of;
+
+use async_trait::async_trait;
+use bitcoin::{hashes::Hash, Txid};
+use serde_bytes::ByteArray;
+
+use crate::{KeyValueError, KeyValueResult, KeyValueStorage};
+
+const UNSPENT_KEY_PREFIX: &str = "unspent-";
+const UNSPENT_KEY_PREFIX_SIZE: usize = UNSPENT_KEY_PREFIX.len();
+
+/// Unspent outputs key size is 8(`UNSPENT_KEY_PREFIX:[u8; 8]`) + 32(`Txid`) = 40 bytes long
+const UNSPENT_KEY_SIZE: usize = UNSPENT_KEY_PREFIX_SIZE + size_of::<Txid>();
+
+const PRUNED_KEY_PREFIX: &str = "pruned-";
+const PRUNED_KEY_PREFIX_SIZE: usize = PRUNED_KEY_PREFIX.len();
+
+/// Pruned transaction key size is 7(`PRUNED_KEY_PREFIX:[u8; 7]`) + 32(`Txid`) = 39 bytes long
+const PRUNED_KEY_SIZE: usize = PRUNED_KEY_PREFIX_SIZE + size_of::<Txid>();
+
+const PRUNABLE_KEY_PREFIX: &str = "prunable-";
+const PRUNABLE_KEY_PREFIX_SIZE: usize = PRUNABLE_KEY_PREFIX.len();
+
+/// Prunable transactions key size is 9(`PRUNABLE_KEY_PREFIX:[u8; 9]`) + 8(`height:u64`) = 17
+/// bytes long
+const PRUNABLE_KEY_SIZE: usize = PRUNABLE_KEY_PREFIX_SIZE + size_of::<u64>();
+
+const PRUNING_CURSOR_KEY_SIZE: usize = 14;
+const PRUNING_CURSOR_KEY: &[u8; PRUNING_CURSOR_KEY_SIZE] = b"pruning-cursor";
+
+fn unspent_key(txid: &Txid) -> ByteArray<UNSPENT_KEY_SIZE> {
+    let mut bytes = [0u8; UNSPENT_KEY_SIZE];
+
+    bytes[..UNSPENT_KEY_PREFIX_SIZE].copy_from_slice(UNSPENT_KEY_PREFIX.as_bytes());
+    bytes[UNSPENT_KEY_PREFIX_SIZE..].copy_from_slice(txid.as_raw_hash().as_byte_array());
+
+    ByteArray::new(bytes)
+}
+
+fn pruned_key(txid: &Txid) -> ByteArray<PRUNED_KEY_SIZE> {
+    let mut bytes = [0u8; PRUNED_KEY_SIZE];
+
+    bytes[..PRUNED_KEY_PREFIX_SIZE].copy_from_slice(PRUNED_KEY_PREFIX.as_bytes());
+    bytes[PRUNED_KEY_PREFIX_SIZE..].copy_from_slice(txid.as_raw_hash().as_byte_array());
+
+    ByteArray::new(bytes)
+}
+
+fn prunable_key(height: usize) -> [u8; PRUNABLE_KEY_SIZE] {
+    let mut bytes = [0u8; PRUNABLE_KEY_SIZE];
+
+    bytes[..PRUNABLE_KEY_PREFIX_SIZE].copy_from_slice(PRUNABLE_KEY_PREFIX.as_bytes());
+    bytes[PRUNABLE_KEY_PREFIX_SIZE..].copy_from_slice(&(height as u64).to_be_bytes());
+
+    bytes
+}
+
+/// State of the pruning of the attached transactions whose outputs are all spent:
+///
+/// - `b"unspent-"` + [`Txid`]: number of the unspent outputs of the attached transaction.
+/// - `b"prunable-"` + height: transactions whose last output is spent in the block.
+/// - `b"pruning-cursor"`: height of the next block whose transactions are pruned.
+/// - `b"pruned-"` + [`Txid`]: height of the block the last output of the pruned transaction is
+///   spent in.
+#[async_trait]
+pub trait PruningStorage:
+    KeyValueStorage<ByteArray<UNSPENT_KEY_SIZE>, u32>
+    + KeyValueStorage<ByteArray<PRUNED_KEY_SIZE>, usize>
+    + KeyValueStorage<[u8; PRUNABLE_KEY_SIZE], Vec<Txid>>
+    + KeyValueStorage<[u8; PRUNING_CURSOR_KEY_SIZE], usize>
+{
+    /// Get the number of the unspent outputs of the transaction, `None` if the transaction's
+    /// outputs are not tracked.
+    async fn get_unspent_outputs(&self, txid: &Txid) -> KeyValueResult<Option<u32>> {
+        self.get(unspent_key(txid)).await
+    }
+
+    async fn put_unspent_outputs(&self, txid: &Txid, unspent: u32) -> KeyValueResult<()> {
+        self.put(unspent_key(txid), unspent).await
+    }
+
+    async fn delete_unspent_outputs(&self, txid: &Txid) -> KeyValueResult<()> {
+        KeyValueStorage::<ByteArray<UNSPENT_KEY_SIZE>, u32>::delete(self, unspent_key(txid)).await
+    }
+
+    async fn get_prunable_txs(&self, height: usize) -> KeyValueResult<Vec<Txid>> {
+        self.get(prunable_key(height))
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    async fn add_prunable_tx(&self, height: usize, txid: Txid) -> KeyValueResult<()> {
+        let mut txids = self.get_prunable_txs(height).await?;
+        if txids.contains(&txid) {
+            return Ok(());
+        }
+
+        txids.push(txid);
+        self.put(prunable_key(height), txids).await
+    }
+
+    async fn delete_prunable_txs(&self, height: usize) -> KeyValueResult<()> {
+        KeyValueStorage::<[u8; PRUNABLE_KEY_SIZE], Vec<Txid>>::delete(self, prunable_key(height))
+            .await
+    }
+
+    async fn get_pruning_cursor(&self) -> KeyValueResult<Option<usize>> {
+        self.get(*PRUNING_CURSOR_KEY).await
+    }
+
+    async fn put_pruning_cursor(&self, height: usize) -> KeyValueResult<()> {
+        self.put(*PRUNING_CURSOR_KEY, height).await
+    }
+
+    /// Get the height of the block the last output of the pruned transaction is spent in,
+    /// `None` if the transaction is not pruned.
+    async fn get_pruned_tx(&self, txid: &Txid) -> KeyValueResult<Option<usize>> {
+        self.get(pruned_key(txid)).await
+    }
+
+    async fn put_pruned_tx(&self, txid: &Txid, height: usize) -> KeyValueResult<()> {
+        self.put(pruned_key(txid), height).await
+    }
+
+    /// Reclaims the space of the pruned entries, if the backend doesn't do it by itself.
+    async fn compact_pruned(&self) -> KeyValueResult<()> {
+        KeyValueStorage::<ByteArray<PRUNED_KEY_SIZE>, usize>::compact(self)
+            .await
+            .map_err(|err| KeyValueError::Storage(Box::new(err)))
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use bitcoin::OutPoint;
+use serde_bytes::ByteArray;
+use yuv_pixels::{Chroma, CHROMA_SIZE};
+use yuv_types::supply::SupplyTree;
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const KEY_PREFIX: &str = "supl-";
+const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
+
+const KEY_SIZE: usize = KEY_PREFIX_SIZE + CHROMA_SIZE;
+
+fn supply_key(chroma: &Chroma) -> ByteArray<KEY_SIZE> {
+    let mut bytes = [0u8; KEY_SIZE];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(KEY_PREFIX.as_bytes());
+    bytes[KEY_PREFIX_SIZE..].copy_from_slice(&chroma.to_bytes());
+
+    ByteArray::new(bytes)
+}
+
+/// Unspent pixel outputs of the attached transactions by chroma, which are the leaves of the
+/// chroma's [`SupplyTree`]:
+///
+/// - key: `b"supl-"` + [`Chroma`]
+/// - value: amounts of the unspent outputs by their outpoints.
+#[async_trait]
+pub trait SupplyTreeStorage:
+    KeyValueStorage<ByteArray<KEY_SIZE>, BTreeMap<OutPoint, u128>>
+{
+    async fn get_supply_utxos(&self, chroma: &Chroma) -> KeyValueResult<BTreeMap<OutPoint, u128>> {
+        self.get(supply_key(chroma))
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    async fn get_supply_tree(&self, chroma: &Chroma) -> KeyValueResult<SupplyTree> {
+        let utxos = self.get_supply_utxos(chroma).await?;
+
+        Ok(SupplyTree::new(utxos))
+    }
+
+    /// Put the unspent outputs of the chroma. The entry is deleted if there are none.
+    async fn put_supply_utxos(
+        &self,
+        chroma: &Chroma,
+        utxos: BTreeMap<OutPoint, u128>,
+    ) -> KeyValueResult<()> {
+        if utxos.is_empty() {
+            return self.delete(supply_key(chroma)).await;
+        }
+
+        self.put(supply_key(chroma), utxos).await
+    }
+}
@@ -3,9 +3,9 @@ use std::mem::size_of;
 use async_trait::async_trait;
 use bitcoin::{hashes::Hash, Txid};
 use serde_bytes::ByteArray;
-use yuv_types::YuvTransaction;
+use yuv_types::{announcements::IssueAnnouncement, Announcement, YuvTransaction, YuvTxType};
 
-use crate::{KeyValueResult, KeyValueStorage};
+use crate::{KeyValueResult, KeyValueStorage, ProofRefMap, ProofsStorage, WriteBatch};
 
 const KEY_PREFIX: &str = "txs-";
 const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
@@ -13,6 +13,10 @@ const KEY_PREFIX_SIZE: usize = KEY_PREFIX.len();
 /// Transaction storage key size is 4(`TXS_PREFIX:[u8; 4]`) + 32(`Txid`) = 36 bytes long
 const TXS_STORAGE_KEY_SIZE: usize = KEY_PREFIX_SIZE + size_of::<Txid>();
 
+const TXS_ENCODING_KEY_SIZE: usize = 12;
+/// Key for the [`KeyValueStorage`] where the encoding of the stored transactions is recorded.
+const TXS_ENCODING_KEY: &[u8; TXS_ENCODING_KEY_SIZE] = b"txs-encoding";
+
 fn tx_storage_key(txid: &Txid) -> ByteArray<TXS_STORAGE_KEY_SIZE> {
     let mut bytes = [0u8; TXS_STORAGE_KEY_SIZE];
 
@@ -22,19 +26,284 @@ fn tx_storage_key(txid: &Txid) -> ByteArray<TXS_STORAGE_KEY_SIZE> {
     ByteArray::new(bytes)
 }
 
+/// How the transactions are stored.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum TxsEncoding {
+    /// Transactions are stored with their proofs.
+    #[default]
+    Plain,
+    /// Proofs are stored once in the [`ProofsStorage`], and the transactions store only the
+    /// references to them, see [`StoredYuvTransaction`].
+    ProofsDedup,
+}
+
+impl TxsEncoding {
+    pub fn from_proofs_dedup(proofs_dedup: bool) -> Self {
+        if proofs_dedup {
+            Self::ProofsDedup
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+/// Storage of the YUV transactions.
+///
+/// - key: `b"txs-"` + [`Txid`]
+/// - value: [`YuvTransaction`] or [`StoredYuvTransaction`], depending on the
+///   [`TransactionsStorage::txs_encoding`] of the storage.
+///
+/// The encoding is chosen when the storage is opened. The transactions stored with another
+/// encoding are converted by the [`migrate_txs_encoding`](crate::migrate_txs_encoding).
 #[async_trait]
 pub trait TransactionsStorage:
     KeyValueStorage<ByteArray<TXS_STORAGE_KEY_SIZE>, YuvTransaction>
+    + KeyValueStorage<ByteArray<TXS_STORAGE_KEY_SIZE>, StoredYuvTransaction>
+    + KeyValueStorage<[u8; TXS_ENCODING_KEY_SIZE], TxsEncoding>
+    + ProofsStorage
 {
+    /// Encoding of the transactions that are written to the storage.
+    fn txs_encoding(&self) -> TxsEncoding {
+        TxsEncoding::Plain
+    }
+
     async fn get_yuv_tx(&self, txid: &Txid) -> KeyValueResult<Option<YuvTransaction>> {
-        self.get(tx_storage_key(txid)).await
+        self.get_encoded_yuv_tx(txid, self.txs_encoding()).await
     }
 
     async fn put_yuv_tx(&self, tx: YuvTransaction) -> KeyValueResult<()> {
-        self.put(tx_storage_key(&tx.bitcoin_tx.txid()), tx).await
+        self.put_encoded_yuv_tx(tx, self.txs_encoding()).await
+    }
+
+    /// Add the transaction to the batch instead of writing it right away. With the deduplicated
+    /// proofs, the references to its proofs are acquired right away, as the proofs are shared
+    /// with the other transactions.
+    async fn batch_put_yuv_tx(
+        &self,
+        batch: &mut WriteBatch,
+        tx: YuvTransaction,
+    ) -> KeyValueResult<()> {
+        let key = tx_storage_key(&tx.bitcoin_tx.txid());
+
+        match self.txs_encoding() {
+            TxsEncoding::Plain => batch.put(key, tx),
+            TxsEncoding::ProofsDedup => batch.put(key, self.replace_stored_yuv_tx(tx).await?),
+        }
     }
 
     async fn delete_yuv_tx(&self, txid: &Txid) -> KeyValueResult<()> {
-        self.delete(tx_storage_key(txid)).await
+        self.delete_encoded_yuv_tx(txid, self.txs_encoding()).await
+    }
+
+    /// Get the transaction stored with the `encoding`.
+    async fn get_encoded_yuv_tx(
+        &self,
+        txid: &Txid,
+        encoding: TxsEncoding,
+    ) -> KeyValueResult<Option<YuvTransaction>> {
+        if encoding == TxsEncoding::Plain {
+            // Fully qualified, as the keys of the stored transactions have the same type.
+            return KeyValueStorage::<ByteArray<TXS_STORAGE_KEY_SIZE>, YuvTransaction>::get(
+                self,
+                tx_storage_key(txid),
+            )
+            .await;
+        }
+
+        let Some(stored_tx) = self.get_stored_yuv_tx(txid).await? else {
+            return Ok(None);
+        };
+
+        let tx_type = match stored_tx.tx_type {
+            StoredYuvTxType::Issue {
+                output_proofs,
+                announcement,
+            } => YuvTxType::Issue {
+                output_proofs: match output_proofs {
+                    Some(refs) => Some(self.resolve_proofs(refs).await?),
+                    None => None,
+                },
+                announcement,
+            },
+            StoredYuvTxType::Transfer {
+                input_proofs,
+                output_proofs,
+            } => YuvTxType::Transfer {
+                input_proofs: self.resolve_proofs(input_proofs).await?,
+                output_proofs: self.resolve_proofs(output_proofs).await?,
+            },
+            StoredYuvTxType::Announcement(announcement) => YuvTxType::Announcement(announcement),
+        };
+
+        Ok(Some(YuvTransaction::new(stored_tx.bitcoin_tx, tx_type)))
+    }
+
+    /// Write the transaction with the `encoding`.
+    async fn put_encoded_yuv_tx(
+        &self,
+        tx: YuvTransaction,
+        encoding: TxsEncoding,
+    ) -> KeyValueResult<()> {
+        let key = tx_storage_key(&tx.bitcoin_tx.txid());
+
+        match encoding {
+            TxsEncoding::Plain => {
+                KeyValueStorage::<ByteArray<TXS_STORAGE_KEY_SIZE>, YuvTransaction>::put(
+                    self, key, tx,
+                )
+                .await
+            }
+            TxsEncoding::ProofsDedup => {
+                let stored_tx = self.replace_stored_yuv_tx(tx).await?;
+                self.put_stored_yuv_tx(stored_tx).await
+            }
+        }
+    }
+
+    /// Delete the transaction stored with the `encoding`, releasing the references to its
+    /// proofs.
+    async fn delete_encoded_yuv_tx(
+        &self,
+        txid: &Txid,
+        encoding: TxsEncoding,
+    ) -> KeyValueResult<()> {
+        if encoding == TxsEncoding::ProofsDedup {
+            if let Some(stored_tx) = self.get_stored_yuv_tx(txid).await? {
+                self.release_stored_yuv_tx(&stored_tx).await?;
+            }
+        }
+
+        KeyValueStorage::<ByteArray<TXS_STORAGE_KEY_SIZE>, YuvTransaction>::delete(
+            self,
+            tx_storage_key(txid),
+        )
+        .await
+    }
+
+    /// Store the proofs of the transaction that replaces the stored one with the same id, and
+    /// return it with the references to them.
+    async fn replace_stored_yuv_tx(
+        &self,
+        tx: YuvTransaction,
+    ) -> KeyValueResult<StoredYuvTransaction> {
+        // The same transaction could be stored more than once, so the references of the
+        // previous version are released first to keep the counters correct.
+        if let Some(stored_tx) = self.get_stored_yuv_tx(&tx.bitcoin_tx.txid()).await? {
+            self.release_stored_yuv_tx(&stored_tx).await?;
+        }
+
+        self.acquire_stored_yuv_tx(tx).await
+    }
+
+    /// Store the proofs of the transaction, and return it with the references to them.
+    async fn acquire_stored_yuv_tx(
+        &self,
+        tx: YuvTransaction,
+    ) -> KeyValueResult<StoredYuvTransaction> {
+        let tx_type = match tx.tx_type {
+            YuvTxType::Issue {
+                output_proofs,
+                announcement,
+            } => StoredYuvTxType::Issue {
+                output_proofs: match output_proofs {
+                    Some(proofs) => Some(self.acquire_proofs(proofs).await?),
+                    None => None,
+                },
+                announcement,
+            },
+            YuvTxType::Transfer {
+                input_proofs,
+                output_proofs,
+            } => StoredYuvTxType::Transfer {
+                input_proofs: self.acquire_proofs(input_proofs).await?,
+                output_proofs: self.acquire_proofs(output_proofs).await?,
+            },
+            YuvTxType::Announcement(announcement) => StoredYuvTxType::Announcement(announcement),
+        };
+
+        Ok(StoredYuvTransaction {
+            bitcoin_tx: tx.bitcoin_tx,
+            tx_type,
+        })
+    }
+
+    /// Release the references of the stored transaction to its proofs.
+    async fn release_stored_yuv_tx(&self, stored_tx: &StoredYuvTransaction) -> KeyValueResult<()> {
+        match &stored_tx.tx_type {
+            StoredYuvTxType::Issue {
+                output_proofs: Some(refs),
+                ..
+            } => self.release_proofs(refs).await,
+            StoredYuvTxType::Transfer {
+                input_proofs,
+                output_proofs,
+            } => {
+                self.release_proofs(input_proofs).await?;
+                self.release_proofs(output_proofs).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the transaction as it is stored with the deduplicated proofs, without resolving the
+    /// proofs references.
+    async fn get_stored_yuv_tx(&self, txid: &Txid) -> KeyValueResult<Option<StoredYuvTransaction>> {
+        KeyValueStorage::<ByteArray<TXS_STORAGE_KEY_SIZE>, StoredYuvTransaction>::get(
+            self,
+            tx_storage_key(txid),
+        )
+        .await
     }
+
+    /// Write the transaction with the deduplicated proofs as is. The references to its proofs
+    /// must be already acquired.
+    async fn put_stored_yuv_tx(&self, stored_tx: StoredYuvTransaction) -> KeyValueResult<()> {
+        KeyValueStorage::<ByteArray<TXS_STORAGE_KEY_SIZE>, StoredYuvTransaction>::put(
+            self,
+            tx_storage_key(&stored_tx.bitcoin_tx.txid()),
+            stored_tx,
+        )
+        .await
+    }
+
+    /// Encoding of the transactions that are already in the storage. The storages written
+    /// before the encoding was recorded have the [`TxsEncoding::Plain`] one.
+    async fn get_stored_txs_encoding(&self) -> KeyValueResult<TxsEncoding> {
+        KeyValueStorage::<[u8; TXS_ENCODING_KEY_SIZE], TxsEncoding>::get(self, *TXS_ENCODING_KEY)
+            .await
+            .map(|encoding| encoding.unwrap_or_default())
+    }
+
+    async fn put_stored_txs_encoding(&self, encoding: TxsEncoding) -> KeyValueResult<()> {
+        KeyValueStorage::<[u8; TXS_ENCODING_KEY_SIZE], TxsEncoding>::put(
+            self,
+            *TXS_ENCODING_KEY,
+            encoding,
+        )
+        .await
+    }
+}
+
+/// Storage representation of the [`YuvTransaction`] where proofs are replaced with the
+/// references to the [`ProofsStorage`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct StoredYuvTransaction {
+    pub bitcoin_tx: bitcoin::Transaction,
+    pub tx_type: StoredYuvTxType,
+}
+
+/// Storage representation of the [`YuvTxType`]. See [`StoredYuvTransaction`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum StoredYuvTxType {
+    Issue {
+        output_proofs: Option<ProofRefMap>,
+        announcement: IssueAnnouncement,
+    },
+    Transfer {
+        input_proofs: ProofRefMap,
+        output_proofs: ProofRefMap,
+    },
+    Announcement(Announcement),
 }
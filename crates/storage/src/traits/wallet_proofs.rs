@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use bitcoin::{
+    hashes::{sha256, Hash},
+    Txid,
+};
+use serde_bytes::ByteArray;
+
+use crate::{KeyValueResult, KeyValueStorage, WriteBatch};
+
+const COUNT_KEY_PREFIX: &str = "wpcn-";
+const INDEX_KEY_PREFIX: &str = "wpix-";
+const TX_KEY_PREFIX: &str = "wptx-";
+const KEY_PREFIX_SIZE: usize = COUNT_KEY_PREFIX.len();
+
+/// Count key size is 5(`COUNT_KEY_PREFIX:[u8; 5]`) + 32(SHA256 of the label) = 37 bytes long
+const COUNT_KEY_SIZE: usize = KEY_PREFIX_SIZE + sha256::Hash::LEN;
+
+/// Index key size is 37(count key) + 8(`index:u64`) = 45 bytes long
+const INDEX_KEY_SIZE: usize = COUNT_KEY_SIZE + std::mem::size_of::<u64>();
+
+/// Transaction key size is 37(count key) + 32(`Txid`) = 69 bytes long
+const TX_KEY_SIZE: usize = COUNT_KEY_SIZE + 32;
+
+fn wallet_key<const N: usize>(prefix: &str, label: &str, suffix: &[u8]) -> ByteArray<N> {
+    let mut bytes = [0u8; N];
+
+    bytes[..KEY_PREFIX_SIZE].copy_from_slice(prefix.as_bytes());
+    bytes[KEY_PREFIX_SIZE..COUNT_KEY_SIZE]
+        .copy_from_slice(sha256::Hash::hash(label.as_bytes()).as_byte_array());
+    bytes[COUNT_KEY_SIZE..].copy_from_slice(suffix);
+
+    ByteArray::new(bytes)
+}
+
+fn count_key(label: &str) -> ByteArray<COUNT_KEY_SIZE> {
+    wallet_key(COUNT_KEY_PREFIX, label, &[])
+}
+
+fn index_key(label: &str, index: u64) -> ByteArray<INDEX_KEY_SIZE> {
+    wallet_key(INDEX_KEY_PREFIX, label, &index.to_be_bytes())
+}
+
+fn tx_key(label: &str, txid: &Txid) -> ByteArray<TX_KEY_SIZE> {
+    wallet_key(TX_KEY_PREFIX, label, txid.as_byte_array())
+}
+
+/// Transactions whose proofs were provided via RPC by the wallets of the node's tenants, so each
+/// of them could list only its own ones. Every transaction of the wallet has its own entries, so
+/// the lists are neither read nor written in full:
+///
+/// - key: `b"wpcn-"` + SHA256 of the wallet label, value: number of the wallet's transactions;
+/// - key: `b"wpix-"` + SHA256 of the wallet label + index, value: [`Txid`] provided at the index
+///   in the order of the provision;
+/// - key: `b"wptx-"` + SHA256 of the wallet label + [`Txid`], value: index of the transaction.
+///
+/// The additions to the same wallet must be serialized by the caller, as they increment the
+/// number of the wallet's transactions.
+#[async_trait]
+pub trait WalletProofsStorage:
+    KeyValueStorage<ByteArray<COUNT_KEY_SIZE>, u64>
+    + KeyValueStorage<ByteArray<INDEX_KEY_SIZE>, Txid>
+    + KeyValueStorage<ByteArray<TX_KEY_SIZE>, u64>
+{
+    /// Number of the transactions provided by the wallet.
+    async fn get_wallet_proofs_count(&self, label: &str) -> KeyValueResult<u64> {
+        KeyValueStorage::<ByteArray<COUNT_KEY_SIZE>, u64>::get(self, count_key(label))
+            .await
+            .map(|res| res.unwrap_or_default())
+    }
+
+    /// Ids of the transactions provided by the wallet, at most `limit` of them starting from the
+    /// `offset`, in the order they were provided.
+    async fn get_wallet_proofs(
+        &self,
+        label: &str,
+        offset: u64,
+        limit: u64,
+    ) -> KeyValueResult<Vec<Txid>> {
+        let count = self.get_wallet_proofs_count(label).await?;
+        let end = offset.saturating_add(limit).min(count);
+
+        let mut txids = Vec::new();
+        for index in offset..end {
+            let txid = KeyValueStorage::<ByteArray<INDEX_KEY_SIZE>, Txid>::get(
+                self,
+                index_key(label, index),
+            )
+            .await?;
+
+            txids.extend(txid);
+        }
+
+        Ok(txids)
+    }
+
+    /// Check whether the transaction was provided by the wallet.
+    async fn has_wallet_proof(&self, label: &str, txid: &Txid) -> KeyValueResult<bool> {
+        KeyValueStorage::<ByteArray<TX_KEY_SIZE>, u64>::get(self, tx_key(label, txid))
+            .await
+            .map(|res| res.is_some())
+    }
+
+    /// Add the transactions to the ones provided by the wallet, skipping the already known ones.
+    /// The entries of the new transactions and the updated number are written atomically.
+    async fn add_wallet_proofs(&self, label: &str, txids: Vec<Txid>) -> KeyValueResult<()> {
+        let mut count = self.get_wallet_proofs_count(label).await?;
+        let mut batch = WriteBatch::new();
+
+        let mut added = Vec::new();
+        for txid in txids {
+            if added.contains(&txid) || self.has_wallet_proof(label, &txid).await? {
+                continue;
+            }
+
+            batch.put(index_key(label, count), txid)?;
+            batch.put(tx_key(label, &txid), count)?;
+            added.push(txid);
+            count += 1;
+        }
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        batch.put(count_key(label), count)?;
+
+        KeyValueStorage::<ByteArray<COUNT_KEY_SIZE>, u64>::write_batch(self, batch).await
+    }
+}
+
+#[cfg(all(test, feature = "leveldb"))]
+mod tests {
+    use super::*;
+    use crate::LevelDB;
+
+    fn txid(byte: u8) -> Txid {
+        Txid::from_byte_array([byte; 32])
+    }
+
+    #[test]
+    fn test_wallet_proofs_are_separate_entries() {
+        tokio_test::block_on(async {
+            let storage = LevelDB::in_memory().unwrap();
+
+            storage
+                .add_wallet_proofs("alice", vec![txid(1), txid(2), txid(1)])
+                .await
+                .unwrap();
+            storage
+                .add_wallet_proofs("alice", vec![txid(2), txid(3)])
+                .await
+                .unwrap();
+            storage
+                .add_wallet_proofs("bob", vec![txid(4)])
+                .await
+                .unwrap();
+
+            assert_eq!(storage.get_wallet_proofs_count("alice").await.unwrap(), 3);
+            assert_eq!(
+                storage.get_wallet_proofs("alice", 0, 10).await.unwrap(),
+                vec![txid(1), txid(2), txid(3)]
+            );
+            assert_eq!(
+                storage.get_wallet_proofs("alice", 1, 1).await.unwrap(),
+                vec![txid(2)]
+            );
+            assert!(storage
+                .get_wallet_proofs("alice", 5, 10)
+                .await
+                .unwrap()
+                .is_empty());
+
+            assert!(storage.has_wallet_proof("bob", &txid(4)).await.unwrap());
+            assert!(!storage.has_wallet_proof("bob", &txid(1)).await.unwrap());
+        });
+    }
+}
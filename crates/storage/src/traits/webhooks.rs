@@ -0,0 +1,44 @@
+use std::collections::VecDeque;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{KeyValueResult, KeyValueStorage};
+
+const WEBHOOK_QUEUE_KEY_SIZE: usize = 13;
+const WEBHOOK_QUEUE_KEY: &[u8; WEBHOOK_QUEUE_KEY_SIZE] = b"webhook-queue";
+
+/// Notification waiting to be delivered to the webhook.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    /// Unique id of the notification, the same for all the attempts to deliver it.
+    pub id: u64,
+    pub url: String,
+    /// Name of the event, e.g. `attached`.
+    pub event: String,
+    /// JSON payload of the notification.
+    pub body: String,
+    /// Number of the failed attempts.
+    pub attempts: u32,
+    /// Unix timestamp (in seconds) of the next attempt.
+    pub next_attempt_at: u64,
+}
+
+/// Queue of the webhook notifications, which survives the node restarts.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookQueue {
+    /// Id of the next notification.
+    pub next_id: u64,
+    pub deliveries: VecDeque<WebhookDelivery>,
+}
+
+#[async_trait]
+pub trait WebhookQueueStorage: KeyValueStorage<[u8; WEBHOOK_QUEUE_KEY_SIZE], WebhookQueue> {
+    async fn get_webhook_queue(&self) -> KeyValueResult<WebhookQueue> {
+        Ok(self.get(*WEBHOOK_QUEUE_KEY).await?.unwrap_or_default())
+    }
+
+    async fn put_webhook_queue(&self, queue: WebhookQueue) -> KeyValueResult<()> {
+        self.put(*WEBHOOK_QUEUE_KEY, queue).await
+    }
+}
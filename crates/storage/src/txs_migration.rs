@@ -0,0 +1,188 @@
+use bitcoin::Txid;
+
+use crate::{
+    KeyValueError, KeyValueResult, MempoolStorage, PagesStorage, TransactionsStorage, TxsEncoding,
+};
+
+/// Converts the stored transactions to the [`TransactionsStorage::txs_encoding`] of the storage,
+/// if they are stored with another one, and records the new encoding. Returns the number of the
+/// converted transactions.
+///
+/// The attached transactions are found in the pages, and the pending ones in the mempool of the
+/// `state_storage`. If the conversion is interrupted, it's resumed on the next start, skipping the
+/// transactions that are already converted.
+pub async fn migrate_txs_encoding<TS, SS>(
+    txs_storage: &TS,
+    state_storage: &SS,
+) -> KeyValueResult<usize>
+where
+    TS: TransactionsStorage + PagesStorage + Send + Sync,
+    SS: MempoolStorage + Send + Sync,
+{
+    let from = txs_storage.get_stored_txs_encoding().await?;
+    let to = txs_storage.txs_encoding();
+    if from == to {
+        return Ok(0);
+    }
+
+    let mut txids = Vec::new();
+    let pages_number = txs_storage.get_pages_number().await?.unwrap_or_default();
+    for page_num in 0..=pages_number {
+        if let Some(page) = txs_storage.get_page_by_num(page_num).await? {
+            txids.extend(page);
+        }
+    }
+    txids.extend(state_storage.get_mempool().await?.unwrap_or_default());
+
+    let mut migrated = 0;
+    for txid in txids {
+        if convert_tx(txs_storage, &txid, from, to).await? {
+            migrated += 1;
+        }
+    }
+
+    txs_storage.put_stored_txs_encoding(to).await?;
+
+    Ok(migrated)
+}
+
+/// Converts the transaction from the `from` encoding to the `to` one. Returns `false` if the
+/// transaction is pruned, or is already converted.
+async fn convert_tx<TS>(
+    txs_storage: &TS,
+    txid: &Txid,
+    from: TxsEncoding,
+    to: TxsEncoding,
+) -> KeyValueResult<bool>
+where
+    TS: TransactionsStorage + Send + Sync,
+{
+    let tx = match txs_storage.get_encoded_yuv_tx(txid, from).await {
+        Ok(Some(tx)) => tx,
+        Ok(None) => return Ok(false),
+        // The encodings differ, so the already converted transaction can't be decoded with the
+        // previous one.
+        Err(KeyValueError::Decoding(_)) => return Ok(false),
+        Err(err) => return Err(err),
+    };
+
+    match to {
+        TxsEncoding::ProofsDedup => {
+            let stored_tx = txs_storage.acquire_stored_yuv_tx(tx).await?;
+            txs_storage.put_stored_yuv_tx(stored_tx).await?;
+        }
+        TxsEncoding::Plain => {
+            let stored_tx = txs_storage.get_stored_yuv_tx(txid).await?;
+            txs_storage
+                .put_encoded_yuv_tx(tx, TxsEncoding::Plain)
+                .await?;
+
+            // Released after the transaction is rewritten, so the interrupted conversion can
+            // only leave the unreferenced proofs behind.
+            if let Some(stored_tx) = stored_tx {
+                txs_storage.release_stored_yuv_tx(&stored_tx).await?;
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(all(test, feature = "leveldb"))]
+mod tests {
+    use bitcoin::absolute::LockTime;
+    use yuv_types::YuvTransaction;
+
+    use super::*;
+    use crate::{LevelDB, ProofsDedupStats, ProofsDedupStatsStorage};
+
+    fn transfer(lock_time: u32) -> YuvTransaction {
+        let mut tx: YuvTransaction = serde_json::from_str(include_str!(
+            "../../tx-check/src/tests/assets/singlechroma_valid_transfer.json"
+        ))
+        .unwrap();
+        tx.bitcoin_tx.lock_time = LockTime::from_consensus(lock_time);
+
+        tx
+    }
+
+    #[test]
+    fn test_txs_encoding_migration_roundtrip() {
+        tokio_test::block_on(async {
+            let storage = LevelDB::in_memory().unwrap();
+
+            // Different transactions with the same proofs: one attached, and one pending.
+            let attached_tx = transfer(1);
+            let pending_tx = transfer(2);
+            let attached_txid = attached_tx.bitcoin_tx.txid();
+            let pending_txid = pending_tx.bitcoin_tx.txid();
+
+            storage.put_yuv_tx(attached_tx.clone()).await.unwrap();
+            storage.put_yuv_tx(pending_tx.clone()).await.unwrap();
+            storage.put_page(0, vec![attached_txid]).await.unwrap();
+            storage.put_mempool(vec![pending_txid]).await.unwrap();
+
+            let dedup_storage = storage.clone().with_txs_encoding(TxsEncoding::ProofsDedup);
+            assert_eq!(
+                migrate_txs_encoding(&dedup_storage, &storage)
+                    .await
+                    .unwrap(),
+                2
+            );
+            assert_eq!(
+                migrate_txs_encoding(&dedup_storage, &storage)
+                    .await
+                    .unwrap(),
+                0
+            );
+
+            assert_eq!(
+                dedup_storage.get_stored_txs_encoding().await.unwrap(),
+                TxsEncoding::ProofsDedup
+            );
+            assert!(dedup_storage
+                .get_stored_yuv_tx(&attached_txid)
+                .await
+                .unwrap()
+                .is_some());
+            assert_eq!(
+                dedup_storage.get_yuv_tx(&attached_txid).await.unwrap(),
+                Some(attached_tx.clone())
+            );
+            assert_eq!(
+                dedup_storage.get_yuv_tx(&pending_txid).await.unwrap(),
+                Some(pending_tx.clone())
+            );
+
+            let stats = dedup_storage.get_proofs_dedup_stats().await.unwrap();
+            assert!(stats.stored_bytes > 0);
+            assert_eq!(stats.logical_bytes, stats.stored_bytes * 2);
+
+            let plain_storage = storage.clone().with_txs_encoding(TxsEncoding::Plain);
+            assert_eq!(
+                migrate_txs_encoding(&plain_storage, &storage)
+                    .await
+                    .unwrap(),
+                2
+            );
+
+            assert_eq!(
+                plain_storage.get_stored_txs_encoding().await.unwrap(),
+                TxsEncoding::Plain
+            );
+            assert_eq!(
+                plain_storage.get_yuv_tx(&attached_txid).await.unwrap(),
+                Some(attached_tx)
+            );
+            assert_eq!(
+                plain_storage.get_yuv_tx(&pending_txid).await.unwrap(),
+                Some(pending_tx)
+            );
+            assert_eq!(
+                plain_storage.get_proofs_dedup_stats().await.unwrap(),
+                ProofsDedupStats::default(),
+                "all the proofs should be released"
+            );
+        });
+    }
+}
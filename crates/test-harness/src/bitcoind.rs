@@ -0,0 +1,227 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use bitcoin::{Address, Amount, BlockHash, Txid};
+use bitcoin_client::{BitcoinRpcApi, BitcoinRpcAuth, BitcoinRpcClient};
+use eyre::{bail, Context};
+use tempfile::TempDir;
+use tokio::process::Command;
+
+use crate::util::{free_local_address, wait_until, Subprocess};
+
+/// Environment variable with the path to the `bitcoind` binary.
+pub const BITCOIND_EXE_ENV: &str = "BITCOIND_EXE";
+
+/// Credentials of the RPC of the spawned nodes.
+const RPC_USERNAME: &str = "yuv";
+const RPC_PASSWORD: &str = "yuv";
+
+/// Wallet of the node that mines the blocks and funds the addresses.
+const WALLET_NAME: &str = "yuv-test-harness";
+
+/// Number of blocks after which the coinbase outputs can be spent.
+const COINBASE_MATURITY: u64 = 100;
+
+#[derive(Debug, Clone)]
+pub struct BitcoindConfig {
+    /// Path to the `bitcoind` binary. Defaults to [`BITCOIND_EXE_ENV`] if it's set, or to the
+    /// `bitcoind` from the `PATH`.
+    pub executable: PathBuf,
+    /// Additional arguments of the node, e.g. `-blockfilterindex=1`.
+    pub args: Vec<String>,
+    /// How long to wait for the node's RPC to become available.
+    pub startup_timeout: Duration,
+}
+
+impl Default for BitcoindConfig {
+    fn default() -> Self {
+        Self {
+            executable: std::env::var_os(BITCOIND_EXE_ENV)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("bitcoind")),
+            args: Vec::new(),
+            startup_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Regtest Bitcoin node, either spawned as the subprocess with the temporary data directory, or
+/// an already running one, e.g. from the Nigiri or the Docker Compose setup.
+///
+/// The spawned node is killed when dropped.
+pub struct Bitcoind {
+    rpc_url: String,
+    username: String,
+    password: String,
+    /// Address of the P2P of the spawned node.
+    p2p_address: Option<SocketAddr>,
+    /// Client of the harness' wallet.
+    client: BitcoinRpcClient,
+    /// Address the blocks are mined to.
+    mining_address: Address,
+
+    subprocess: Option<Subprocess>,
+}
+
+impl Bitcoind {
+    /// Spawns the regtest node with the temporary data directory on the free local ports.
+    pub async fn spawn(config: BitcoindConfig) -> eyre::Result<Self> {
+        let datadir = TempDir::new()?;
+        let rpc_address = free_local_address()?;
+        let p2p_address = free_local_address()?;
+
+        let child = Command::new(&config.executable)
+            .arg("-regtest")
+            .arg(format!("-datadir={}", datadir.path().display()))
+            .arg(format!("-rpcport={}", rpc_address.port()))
+            .arg(format!("-port={}", p2p_address.port()))
+            .arg(format!("-rpcuser={RPC_USERNAME}"))
+            .arg(format!("-rpcpassword={RPC_PASSWORD}"))
+            .args([
+                "-server=1",
+                "-txindex=1",
+                "-fallbackfee=0.0001",
+                "-printtoconsole=0",
+            ])
+            .args(&config.args)
+            .stdout(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .wrap_err_with(|| format!("failed to spawn {}", config.executable.display()))?;
+
+        let rpc_url = format!("http://{rpc_address}");
+        let node_client = client(&rpc_url, RPC_USERNAME, RPC_PASSWORD).await?;
+
+        if !wait_until(config.startup_timeout, || async {
+            node_client.get_block_count().await.is_ok()
+        })
+        .await
+        {
+            bail!("bitcoind didn't start in {:?}", config.startup_timeout);
+        }
+
+        node_client
+            .create_wallet(WALLET_NAME, None, None, None, None)
+            .await?;
+
+        let mut bitcoind =
+            Self::with_wallet(rpc_url, RPC_USERNAME, RPC_PASSWORD, Some(p2p_address)).await?;
+        bitcoind.subprocess = Some(Subprocess::new(child, datadir));
+
+        Ok(bitcoind)
+    }
+
+    /// Connects to the running regtest node, and creates or loads the harness' wallet on it.
+    pub async fn connect(rpc_url: &str, username: &str, password: &str) -> eyre::Result<Self> {
+        let node_client = client(rpc_url, username, password).await?;
+
+        let loaded = node_client.list_wallets().await?;
+        if !loaded.iter().any(|wallet| wallet == WALLET_NAME)
+            && node_client.load_wallet(WALLET_NAME).await.is_err()
+        {
+            node_client
+                .create_wallet(WALLET_NAME, None, None, None, None)
+                .await?;
+        }
+
+        Self::with_wallet(rpc_url.to_string(), username, password, None).await
+    }
+
+    async fn with_wallet(
+        rpc_url: String,
+        username: &str,
+        password: &str,
+        p2p_address: Option<SocketAddr>,
+    ) -> eyre::Result<Self> {
+        let wallet_url = format!("{rpc_url}/wallet/{WALLET_NAME}");
+        let client = client(&wallet_url, username, password).await?;
+
+        let mining_address = client.get_new_address(None, None).await?.assume_checked();
+
+        Ok(Self {
+            rpc_url,
+            username: username.to_string(),
+            password: password.to_string(),
+            p2p_address,
+            client,
+            mining_address,
+            subprocess: None,
+        })
+    }
+
+    /// URL of the node's RPC.
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    /// User name and password of the node's RPC.
+    pub fn credentials(&self) -> (&str, &str) {
+        (&self.username, &self.password)
+    }
+
+    /// Address of the P2P of the spawned node, to connect the other nodes to it.
+    pub fn p2p_address(&self) -> Option<SocketAddr> {
+        self.p2p_address
+    }
+
+    /// Client of the harness' wallet on the node.
+    pub fn client(&self) -> &BitcoinRpcClient {
+        &self.client
+    }
+
+    pub async fn block_count(&self) -> eyre::Result<u64> {
+        Ok(self.client.get_block_count().await?)
+    }
+
+    /// Mines `blocks` to the harness' wallet.
+    pub async fn mine_blocks(&self, blocks: u64) -> eyre::Result<Vec<BlockHash>> {
+        Ok(self
+            .client
+            .generate_to_address(blocks, &self.mining_address)
+            .await?)
+    }
+
+    /// Sends the `amount` to the `address` from the harness' wallet, mining the blocks to the
+    /// wallet first if it doesn't have enough funds. The transaction is left in the mempool.
+    pub async fn fund_address(&self, address: &Address, amount: Amount) -> eyre::Result<Txid> {
+        let balance = self.client.get_balance(Some(1), None).await?;
+        if balance < amount {
+            self.mine_blocks(COINBASE_MATURITY + 1).await?;
+        }
+
+        let txid = self
+            .client
+            .call(
+                "sendtoaddress",
+                &[address.to_string().into(), amount.to_btc().into()],
+            )
+            .await?;
+
+        Ok(txid)
+    }
+
+    /// Stops the spawned node and waits for it to exit. The running node the harness is
+    /// connected to is left running.
+    pub async fn stop(mut self) -> eyre::Result<()> {
+        let Some(subprocess) = self.subprocess.take() else {
+            return Ok(());
+        };
+
+        if self.client.stop().await.is_err() {
+            return subprocess.kill().await;
+        }
+
+        subprocess.wait().await
+    }
+}
+
+async fn client(url: &str, username: &str, password: &str) -> eyre::Result<BitcoinRpcClient> {
+    let auth = BitcoinRpcAuth::UserPass {
+        username: username.to_string(),
+        password: password.to_string(),
+    };
+
+    Ok(BitcoinRpcClient::new(auth, url.to_string(), None).await?)
+}
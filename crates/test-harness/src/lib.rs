@@ -0,0 +1,235 @@
+#![doc = include_str!("../README.md")]
+
+use std::time::Duration;
+
+use bdk::blockchain::rpc::Auth;
+use bitcoin::{secp256k1, Address, Amount, BlockHash, Network, PrivateKey, Txid};
+use eyre::{bail, OptionExt};
+use tokio::time::Instant;
+use ydk::{
+    bitcoin_provider::{BitcoinProvider, BitcoinProviderConfig, BitcoinRpcConfig},
+    types::FeeRateStrategy,
+    wallet::{MemoryWallet, SyncOptions, WalletConfig},
+};
+use yuv_rpc_api::transactions::{YuvTransactionStatus, YuvTransactionsRpcClient};
+use yuv_types::YuvTransaction;
+
+mod bitcoind;
+pub use bitcoind::{Bitcoind, BitcoindConfig, BITCOIND_EXE_ENV};
+
+mod yuvd;
+pub use yuvd::{Yuvd, YuvdConfig, YuvdMode, YUVD_EXE_ENV};
+
+mod util;
+use util::POLL_INTERVAL;
+
+/// Default timeout of [`TestHarness::wait_for_attach`].
+pub const DEFAULT_ATTACH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Satoshis funded to the issuer by [`TestHarness::issue_chroma`].
+const ISSUER_FUNDING: Amount = Amount::from_sat(100_000);
+/// Satoshis of the issued output.
+const ISSUED_SATOSHIS: u64 = 1000;
+const FEE_RATE_STRATEGY: FeeRateStrategy = FeeRateStrategy::Manual { fee_rate: 1.0 };
+
+/// Regtest bitcoind with the YUV nodes connected to it and to each other.
+pub struct TestHarness {
+    bitcoind: Bitcoind,
+    nodes: Vec<Yuvd>,
+}
+
+impl TestHarness {
+    /// Spawns the bitcoind and one YUV node with the default configs.
+    pub async fn start() -> eyre::Result<Self> {
+        Self::start_with(BitcoindConfig::default(), YuvdConfig::default(), 1).await
+    }
+
+    /// Spawns the bitcoind and the `nodes` YUV nodes, each of them connected to the previous
+    /// ones over P2P.
+    pub async fn start_with(
+        bitcoind: BitcoindConfig,
+        yuvd: YuvdConfig,
+        nodes: usize,
+    ) -> eyre::Result<Self> {
+        let bitcoind = Bitcoind::spawn(bitcoind).await?;
+
+        Self::with_bitcoind(bitcoind, yuvd, nodes).await
+    }
+
+    /// Spawns the `nodes` YUV nodes connected to the `bitcoind`, e.g. the one that is
+    /// [connected](Bitcoind::connect) to the running node.
+    pub async fn with_bitcoind(
+        bitcoind: Bitcoind,
+        yuvd: YuvdConfig,
+        nodes: usize,
+    ) -> eyre::Result<Self> {
+        // The indexer of the YUV node waits for the first blocks.
+        if bitcoind.block_count().await? == 0 {
+            bitcoind.mine_blocks(1).await?;
+        }
+
+        let mut harness = Self {
+            bitcoind,
+            nodes: Vec::with_capacity(nodes),
+        };
+
+        for _ in 0..nodes {
+            let mut config = yuvd.clone();
+            config
+                .bootnodes
+                .extend(harness.nodes.iter().map(Yuvd::p2p_address));
+
+            let node = Yuvd::spawn(config, &harness.bitcoind).await?;
+            harness.nodes.push(node);
+        }
+
+        Ok(harness)
+    }
+
+    pub fn bitcoind(&self) -> &Bitcoind {
+        &self.bitcoind
+    }
+
+    /// The first YUV node, which the helpers send the transactions to.
+    pub fn yuvd(&self) -> &Yuvd {
+        &self.nodes[0]
+    }
+
+    pub fn nodes(&self) -> &[Yuvd] {
+        &self.nodes
+    }
+
+    /// Mines `blocks` with the bitcoind.
+    pub async fn mine_blocks(&self, blocks: u64) -> eyre::Result<Vec<BlockHash>> {
+        self.bitcoind.mine_blocks(blocks).await
+    }
+
+    /// Sends the `amount` to the `address` and mines the transaction.
+    pub async fn fund_address(&self, address: &Address, amount: Amount) -> eyre::Result<Txid> {
+        let txid = self.bitcoind.fund_address(address, amount).await?;
+        self.mine_blocks(1).await?;
+
+        Ok(txid)
+    }
+
+    /// Returns the wallet of the `private_key` that uses the bitcoind and the first YUV node.
+    pub async fn wallet(&self, private_key: PrivateKey) -> eyre::Result<MemoryWallet> {
+        let (username, password) = self.bitcoind.credentials();
+
+        let wallet = MemoryWallet::from_config(WalletConfig {
+            privkey: private_key,
+            network: Network::Regtest,
+            bitcoin_provider: BitcoinProviderConfig::BitcoinRpc(BitcoinRpcConfig {
+                url: self.bitcoind.rpc_url().to_string(),
+                network: Network::Regtest,
+                auth: Auth::UserPass {
+                    username: username.to_string(),
+                    password: password.to_string(),
+                },
+                start_time: 0,
+            }),
+            yuv_url: self.yuvd().rpc_url().to_string(),
+        })
+        .await?;
+
+        Ok(wallet)
+    }
+
+    /// Issues the `amount` of the `issuer`'s chroma to the `recipient`, and mines the blocks
+    /// needed to attach the issuance. The issuer is funded with the satoshis for the fees.
+    ///
+    /// Returns the issuance, which can be waited for with [`TestHarness::wait_for_attach`].
+    pub async fn issue_chroma(
+        &self,
+        issuer: &MemoryWallet,
+        recipient: &secp256k1::PublicKey,
+        amount: u128,
+    ) -> eyre::Result<YuvTransaction> {
+        self.fund_address(&issuer.address()?, ISSUER_FUNDING)
+            .await?;
+        issuer.sync(SyncOptions::default()).await?;
+
+        let mut builder = issuer.build_issuance(None)?;
+        builder
+            .add_recipient(recipient, amount, ISSUED_SATOSHIS)
+            .set_fee_rate_strategy(FEE_RATE_STRATEGY);
+
+        let issuance = builder
+            .finish(&issuer.bitcoin_provider().blockchain())
+            .await?;
+
+        self.yuvd()
+            .client()
+            .send_raw_yuv_tx(issuance.clone(), None)
+            .await?;
+        self.mine_blocks(self.yuvd().confirmations().into()).await?;
+
+        Ok(issuance)
+    }
+
+    /// Waits for the transaction to be attached by all the YUV nodes. Fails if the transaction
+    /// is rejected, or isn't attached in the `timeout`.
+    pub async fn wait_for_attach(
+        &self,
+        txid: Txid,
+        timeout: Duration,
+    ) -> eyre::Result<YuvTransaction> {
+        let deadline = Instant::now() + timeout;
+
+        let mut attached = None;
+        for node in &self.nodes {
+            attached = Some(wait_for_attach_by(node, txid, deadline).await?);
+        }
+
+        attached.ok_or_eyre("No YUV nodes to wait for the tx")
+    }
+
+    /// Stops the YUV nodes and the bitcoind.
+    pub async fn stop(self) -> eyre::Result<()> {
+        for node in self.nodes {
+            node.stop().await?;
+        }
+
+        self.bitcoind.stop().await
+    }
+}
+
+async fn wait_for_attach_by(
+    node: &Yuvd,
+    txid: Txid,
+    deadline: Instant,
+) -> eyre::Result<YuvTransaction> {
+    loop {
+        let response = node.client().get_raw_yuv_transaction(txid).await?;
+
+        match response.status {
+            YuvTransactionStatus::Attached => {
+                if let Some(tx) = response.data {
+                    return Ok(tx.into());
+                }
+            }
+            YuvTransactionStatus::None => {
+                if let Some(rejection) = node.client().get_rejection_reason(txid).await? {
+                    bail!(
+                        "Tx {} is rejected by {}: {}",
+                        txid,
+                        node.rpc_url(),
+                        rejection.message
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        if Instant::now() >= deadline {
+            bail!(
+                "Tx {} isn't attached by {} in time, its status is {:?}",
+                txid,
+                node.rpc_url(),
+                response.status
+            );
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
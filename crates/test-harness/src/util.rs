@@ -0,0 +1,65 @@
+use std::future::Future;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+use std::time::Duration;
+
+use tempfile::TempDir;
+use tokio::process::Child;
+use tokio::time::Instant;
+
+/// How often the nodes are polled while waiting for them.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Spawned node with its data directory, which is removed when dropped.
+pub(crate) struct Subprocess {
+    child: Child,
+    _datadir: TempDir,
+}
+
+impl Subprocess {
+    pub fn new(child: Child, datadir: TempDir) -> Self {
+        Self {
+            child,
+            _datadir: datadir,
+        }
+    }
+
+    pub async fn wait(mut self) -> eyre::Result<()> {
+        self.child.wait().await?;
+
+        Ok(())
+    }
+
+    pub async fn kill(mut self) -> eyre::Result<()> {
+        self.child.kill().await?;
+
+        Ok(())
+    }
+}
+
+/// Returns the local address with the port that is free at the moment.
+pub(crate) fn free_local_address() -> eyre::Result<SocketAddr> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+
+    Ok(listener.local_addr()?)
+}
+
+/// Polls the `condition` until it's true, returns `false` if the `timeout` is reached first.
+pub(crate) async fn wait_until<F, Fut>(timeout: Duration, mut condition: F) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if condition().await {
+            return true;
+        }
+
+        if Instant::now() >= deadline {
+            return false;
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
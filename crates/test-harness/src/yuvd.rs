@@ -0,0 +1,238 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use eyre::{bail, Context};
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use tempfile::TempDir;
+use tokio::process::Command;
+use yuv_rpc_api::transactions::YuvTransactionsRpcClient;
+
+use crate::util::{free_local_address, wait_until, Subprocess};
+use crate::Bitcoind;
+
+/// Environment variable with the path to the `yuvd` binary.
+pub const YUVD_EXE_ENV: &str = "YUVD_EXE";
+
+/// Name of the node's config in its data directory.
+const CONFIG_FILE: &str = "yuvd.toml";
+
+/// How the YUV node is run.
+#[derive(Debug, Clone)]
+pub enum YuvdMode {
+    /// The `yuvd` binary in the subprocess.
+    Subprocess {
+        /// Path to the binary. Defaults to [`YUVD_EXE_ENV`] if it's set, or to the `yuvd` from
+        /// the `PATH`.
+        executable: PathBuf,
+    },
+    /// The node's services in the test's runtime, so the binary isn't needed and the node can be
+    /// debugged together with the test.
+    #[cfg(feature = "in-process")]
+    InProcess,
+}
+
+impl Default for YuvdMode {
+    fn default() -> Self {
+        Self::Subprocess {
+            executable: std::env::var_os(YUVD_EXE_ENV)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("yuvd")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct YuvdConfig {
+    pub mode: YuvdMode,
+    /// Number of confirmations after which the transactions are checked and attached.
+    pub confirmations: u8,
+    /// How often the node polls the Bitcoin node for the new blocks.
+    pub polling_period: Duration,
+    /// P2P addresses of the YUV nodes to connect to.
+    pub bootnodes: Vec<SocketAddr>,
+    /// Sections appended to the generated config, e.g. `[controller]`. The `[p2p]`, `[rpc]`,
+    /// `[storage]`, `[bnode]` and `[indexer]` sections are generated and can't be repeated.
+    pub extra_config: String,
+    /// How long to wait for the node's RPC to become available.
+    pub startup_timeout: Duration,
+}
+
+impl Default for YuvdConfig {
+    fn default() -> Self {
+        Self {
+            mode: YuvdMode::default(),
+            confirmations: 1,
+            polling_period: Duration::from_secs(1),
+            bootnodes: Vec::new(),
+            extra_config: String::new(),
+            startup_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl YuvdConfig {
+    /// Returns the TOML config of the node connected to the `bitcoind`.
+    fn to_toml(
+        &self,
+        bitcoind: &Bitcoind,
+        rpc_address: SocketAddr,
+        p2p_address: SocketAddr,
+        storage_path: &Path,
+    ) -> String {
+        let (username, password) = bitcoind.credentials();
+        let bootnodes = self
+            .bootnodes
+            .iter()
+            .map(|address| format!("\"{address}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            r#"network = "regtest"
+
+[p2p]
+address = "{p2p_address}"
+bootnodes = [{bootnodes}]
+
+[rpc]
+address = "{rpc_address}"
+
+[storage]
+path = {storage_path:?}
+create_if_missing = true
+
+[bnode]
+url = "{bitcoind_url}"
+auth = {{ username = "{username}", password = "{password}" }}
+
+[indexer]
+polling_period = {{ secs = {polling_secs}, nanos = {polling_nanos} }}
+confirmations_number = {confirmations}
+
+{extra_config}
+"#,
+            bitcoind_url = bitcoind.rpc_url(),
+            polling_secs = self.polling_period.as_secs(),
+            polling_nanos = self.polling_period.subsec_nanos(),
+            confirmations = self.confirmations,
+            extra_config = self.extra_config,
+        )
+    }
+}
+
+enum Process {
+    Subprocess(Subprocess),
+    #[cfg(feature = "in-process")]
+    InProcess {
+        node: yuvd::Node,
+        _datadir: TempDir,
+    },
+}
+
+/// YUV node connected to the regtest [`Bitcoind`], with the storage in the temporary directory.
+///
+/// The node in the subprocess is killed when dropped.
+pub struct Yuvd {
+    rpc_url: String,
+    p2p_address: SocketAddr,
+    client: HttpClient,
+    confirmations: u8,
+
+    process: Process,
+}
+
+impl Yuvd {
+    /// Starts the node on the free local ports.
+    pub async fn spawn(config: YuvdConfig, bitcoind: &Bitcoind) -> eyre::Result<Self> {
+        let datadir = TempDir::new()?;
+        let rpc_address = free_local_address()?;
+        let p2p_address = free_local_address()?;
+
+        let config_path = datadir.path().join(CONFIG_FILE);
+        let toml = config.to_toml(
+            bitcoind,
+            rpc_address,
+            p2p_address,
+            &datadir.path().join("storage"),
+        );
+        tokio::fs::write(&config_path, toml).await?;
+
+        let process = match &config.mode {
+            YuvdMode::Subprocess { executable } => {
+                let child = Command::new(executable)
+                    .arg("run")
+                    .arg("--config")
+                    .arg(&config_path)
+                    .stdout(Stdio::null())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .wrap_err_with(|| format!("failed to spawn {}", executable.display()))?;
+
+                Process::Subprocess(Subprocess::new(child, datadir))
+            }
+            #[cfg(feature = "in-process")]
+            YuvdMode::InProcess => {
+                let node = yuvd::Node::new(yuvd::NodeConfig::from_path(config_path)?).await?;
+                node.run().await?;
+
+                Process::InProcess {
+                    node,
+                    _datadir: datadir,
+                }
+            }
+        };
+
+        let rpc_url = format!("http://{rpc_address}");
+        let client = HttpClientBuilder::new().build(&rpc_url)?;
+
+        if !wait_until(config.startup_timeout, || async {
+            client.list_yuv_transactions(0).await.is_ok()
+        })
+        .await
+        {
+            bail!("yuvd didn't start in {:?}", config.startup_timeout);
+        }
+
+        Ok(Self {
+            rpc_url,
+            p2p_address,
+            client,
+            confirmations: config.confirmations,
+            process,
+        })
+    }
+
+    /// URL of the node's RPC, e.g. for the [`ydk`] wallets.
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    /// Address of the node's P2P, to connect the other nodes to it.
+    pub fn p2p_address(&self) -> SocketAddr {
+        self.p2p_address
+    }
+
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    /// Number of confirmations after which the node attaches the transactions.
+    pub fn confirmations(&self) -> u8 {
+        self.confirmations
+    }
+
+    /// Stops the node. The subprocess is killed, and the in-process node is shut down
+    /// gracefully, waiting for it to finish handling the requests.
+    pub async fn stop(self) -> eyre::Result<()> {
+        match self.process {
+            Process::Subprocess(subprocess) => subprocess.kill().await,
+            #[cfg(feature = "in-process")]
+            Process::InProcess { node, .. } => {
+                node.shutdown().await;
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use bitcoin::{secp256k1::Secp256k1, Amount, PrivateKey};
+use ydk::wallet::SyncOptions;
+use yuv_test_harness::{TestHarness, DEFAULT_ATTACH_TIMEOUT};
+
+const ISSUER: &str = "cQb7JarJTBoeu6eLvyDnHYNr6Hz4AuAnELutxcY478ySZy2i29FA";
+const RECIPIENT: &str = "cUrMc62nnFeQuzXb26KPizCJQPp7449fsPsqn5NCHTwahSvqqRkV";
+
+const ISSUE_AMOUNT: u128 = 10_000;
+
+#[tokio::test]
+#[ignore = "spawns bitcoind and yuvd"]
+async fn test_issue_chroma() -> eyre::Result<()> {
+    let harness = TestHarness::start().await?;
+
+    let issuer = harness.wallet(PrivateKey::from_wif(ISSUER)?).await?;
+    let recipient_key = PrivateKey::from_wif(RECIPIENT)?;
+    let recipient = harness.wallet(recipient_key).await?;
+
+    let issuance = harness
+        .issue_chroma(
+            &issuer,
+            &recipient_key.public_key(&Secp256k1::new()).inner,
+            ISSUE_AMOUNT,
+        )
+        .await?;
+
+    let attached = harness
+        .wait_for_attach(issuance.bitcoin_tx.txid(), DEFAULT_ATTACH_TIMEOUT)
+        .await?;
+    assert_eq!(attached.bitcoin_tx.txid(), issuance.bitcoin_tx.txid());
+
+    recipient.sync(SyncOptions::default()).await?;
+    let balances = recipient.balances().await?;
+    assert_eq!(balances.yuv.values().sum::<u128>(), ISSUE_AMOUNT);
+
+    harness.stop().await
+}
+
+#[tokio::test]
+#[ignore = "spawns bitcoind and yuvd"]
+async fn test_fund_address() -> eyre::Result<()> {
+    let harness = TestHarness::start().await?;
+
+    let wallet = harness.wallet(PrivateKey::from_wif(ISSUER)?).await?;
+    let height = harness.bitcoind().block_count().await?;
+
+    harness
+        .fund_address(&wallet.address()?, Amount::from_sat(50_000))
+        .await?;
+    assert!(harness.bitcoind().block_count().await? > height);
+
+    wallet.sync(SyncOptions::bitcoin_only()).await?;
+    assert_eq!(wallet.bitcoin_balances()?.confirmed, 50_000);
+
+    harness.stop().await
+}
@@ -7,11 +7,17 @@ use bitcoin::Txid;
 use event_bus::{typeid, EventBus};
 use eyre::WrapErr;
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use yuv_storage::{PagesStorage, TransactionsStorage};
 
 use yuv_types::{ControllerMessage, GraphBuilderMessage, ProofMap, YuvTransaction, YuvTxType};
 
+#[cfg(test)]
+mod proptests;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 /// Service which handles attaching of transactions to the graph.
 ///
 /// Accepts batches of checked transactions, and attaches
@@ -36,18 +42,52 @@ pub struct GraphBuilder<TransactionStorage> {
     /// transaction was stored.
     stored_txs: HashMap<Txid, (YuvTransaction, SystemTime)>,
 
-    /// Period of time after which [`Self`] will cleanup transactions
-    /// that are _too old_.
-    cleanup_period: Duration,
-
     /// Period of time, after which we consider transaction _too old_
     /// or _outdated_.
     tx_outdated_duration: Duration,
+
+    /// Limits of the graph of the stored transactions.
+    limits: GraphLimits,
 }
 
-const DURATION_ONE_HOUR: Duration = Duration::from_secs(60 * 60);
 const DURATION_ONE_DAY: Duration = Duration::from_secs(60 * 60 * 24);
 
+/// Limits of the graph of transactions that are waiting for their parents, so a peer can't make
+/// the [`GraphBuilder`] store an unbounded number of them.
+///
+/// Transactions that exceed the limits are evicted along with the stored transactions that depend
+/// on them, and are reported to the controller as invalid, so their senders are punished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphLimits {
+    /// Max number of the stored transactions.
+    pub max_pending_txs: usize,
+    /// Max length of a chain of the stored transactions that depend on each other.
+    pub max_depth: usize,
+    /// Max number of the stored transactions that depend on the same parent.
+    pub max_fanout: usize,
+}
+
+impl Default for GraphLimits {
+    fn default() -> Self {
+        Self {
+            max_pending_txs: 10_000,
+            max_depth: 100,
+            max_fanout: 1_000,
+        }
+    }
+}
+
+/// Limit of the [`GraphLimits`] exceeded by a transaction.
+#[derive(Debug, thiserror::Error)]
+enum LimitExceeded {
+    #[error("max number of pending txs {0} is reached")]
+    PendingTxs(usize),
+    #[error("chain of pending txs is longer than {0}")]
+    Depth(usize),
+    #[error("parent {0} has more than {1} pending children")]
+    Fanout(Txid, usize),
+}
+
 impl<TS> GraphBuilder<TS>
 where
     TS: TransactionsStorage + PagesStorage + Send + Sync + 'static,
@@ -63,18 +103,11 @@ where
             inverse_deps: Default::default(),
             deps: Default::default(),
             stored_txs: Default::default(),
-            cleanup_period: DURATION_ONE_HOUR,
             tx_outdated_duration: DURATION_ONE_DAY,
+            limits: GraphLimits::default(),
         }
     }
 
-    /// Set period of time after each [`Self`] will delete all transactions
-    /// _outdated_ transactions, see ([`self`](Self)) for more info.
-    pub fn with_cleanup_period(mut self, period: Duration) -> Self {
-        self.cleanup_period = period;
-        self
-    }
-
     /// Set time duration after which transaction is considered _outdated_
     /// for more info see [`self`](Self).
     pub fn with_outdated_duration(mut self, duration: Duration) -> Self {
@@ -82,10 +115,15 @@ where
         self
     }
 
+    /// Set limits of the graph of the stored transactions, see [`GraphLimits`].
+    pub fn with_limits(mut self, limits: GraphLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Starts attach incoming [`transactions`](YuvTransaction).
     pub async fn run(mut self, cancellation: CancellationToken) {
         let events = self.event_bus.subscribe::<GraphBuilderMessage>();
-        let mut timer = tokio::time::interval(self.cleanup_period);
 
         loop {
             tokio::select! {
@@ -103,22 +141,24 @@ where
                     tracing::trace!("Cancellation received, stopping graph builder");
                     return;
                 },
-                _ = timer.tick() => {
-                    if let Err(err) = self.handle_cleanup().await {
-                        tracing::error!("Failed to do cleanup: {:?}", err);
-                    }
-                }
             }
         }
     }
 
-    /// Handles incoming [`events`](GraphBuilderMessage).
-    async fn handle_event(&mut self, event: GraphBuilderMessage) -> eyre::Result<()> {
+    /// Handles incoming [`events`](GraphBuilderMessage). Public, so the events can be handled
+    /// one by one, e.g. by the replay of the pipeline.
+    pub async fn handle_event(&mut self, event: GraphBuilderMessage) -> eyre::Result<()> {
         match event {
             GraphBuilderMessage::CheckedTxs(txs) => self
                 .attach_txs(&txs)
                 .await
                 .wrap_err("failed to attach transactions")?,
+            GraphBuilderMessage::CleanUp(completion) => {
+                let result = self.handle_cleanup().await;
+                completion.complete(&result);
+
+                result.wrap_err("failed to do cleanup")?
+            }
         }
 
         Ok(())
@@ -126,8 +166,12 @@ where
 
     /// Clean up transactions that are _outdated_ and all transactions that are related to them.
     async fn handle_cleanup(&mut self) -> eyre::Result<()> {
-        let now = SystemTime::now();
+        self.cleanup_at(SystemTime::now())
+    }
 
+    /// Clean up transactions that are _outdated_ at the point in time `now`, see
+    /// [`with_outdated_duration`](Self::with_outdated_duration).
+    pub fn cleanup_at(&mut self, now: SystemTime) -> eyre::Result<()> {
         let mut outdated_txs = Vec::new();
 
         for (txid, (_, created_at)) in self.stored_txs.iter() {
@@ -142,14 +186,15 @@ where
 
         for txid in outdated_txs {
             tracing::debug!("Tx {} is outdated", txid);
-            self.remove_outdated_tx(txid).await?;
+            self.remove_tx_with_descendants(txid);
         }
 
         Ok(())
     }
 
-    /// Remove outdated transaction from storage and all transactions that are related to it.
-    async fn remove_outdated_tx(&mut self, txid: Txid) -> eyre::Result<()> {
+    /// Remove transaction from storage and all transactions that are related to it. Returns
+    /// the removed transactions.
+    fn remove_tx_with_descendants(&mut self, txid: Txid) -> HashSet<Txid> {
         let mut txs_to_remove = vec![txid];
 
         let mut removed_txs_set = HashSet::<Txid>::new();
@@ -173,7 +218,7 @@ where
             }
         }
 
-        Ok(())
+        removed_txs_set
     }
 
     /// Remove tx from all inverse deps. If there is no inverse deps left, then remove it.
@@ -202,15 +247,32 @@ where
     ///
     /// If transaction can be attached, then it is stored in [`TransactionsStorage`].
     pub async fn attach_txs(&mut self, checked_txs: &[YuvTransaction]) -> eyre::Result<()> {
+        self.attach_txs_at(checked_txs, SystemTime::now()).await
+    }
+
+    /// The same as [`attach_txs`](Self::attach_txs), but the transactions that have to wait for
+    /// their parents are stored as received at the point in time `now`.
+    pub async fn attach_txs_at(
+        &mut self,
+        checked_txs: &[YuvTransaction],
+        now: SystemTime,
+    ) -> eyre::Result<()> {
         let mut queued_txs = HashSet::new();
         let mut attached_txs = Vec::new();
+        let mut evicted_txs = Vec::new();
 
         for yuv_tx in checked_txs {
             let child_id = yuv_tx.bitcoin_tx.txid();
+            let span = tracing::info_span!(
+                "tx",
+                txid = child_id.to_string(),
+                chroma = yuv_tx.chroma().map(|chroma| chroma.to_string()),
+            );
 
             match &yuv_tx.tx_type {
                 // if issuance is attached, there is no reason to wait for it's parents.
                 YuvTxType::Issue { .. } => {
+                    span.in_scope(|| tracing::debug!("Issuance is attached"));
                     attached_txs.push(yuv_tx.bitcoin_tx.txid());
 
                     let Some(ids) = self.inverse_deps.remove(&child_id) else {
@@ -227,7 +289,10 @@ where
                         child_id,
                         &mut queued_txs,
                         &mut attached_txs,
+                        &mut evicted_txs,
+                        now,
                     )
+                    .instrument(span)
                     .await
                     .wrap_err("Failed handling of transfer")?;
                 }
@@ -277,6 +342,12 @@ where
 
         self.handle_fully_attached_txs(attached_txs).await?;
 
+        if !evicted_txs.is_empty() {
+            self.event_bus
+                .send(ControllerMessage::InvalidTxs(evicted_txs))
+                .await;
+        }
+
         Ok(())
     }
 
@@ -330,6 +401,10 @@ where
     ///
     /// If parent of the current tx is attached, skip adding to deps, if all
     /// are attached, then attach current transaction too.
+    ///
+    /// If the transaction has to be stored, but exceeds the [`GraphLimits`], it
+    /// is evicted along with the stored transactions that depend on it.
+    #[allow(clippy::too_many_arguments)]
     async fn handle_transfer(
         &mut self,
         input_proofs: &ProofMap,
@@ -337,7 +412,11 @@ where
         child_id: Txid,
         queued_txs: &mut HashSet<Txid>,
         attached_txs: &mut Vec<Txid>,
+        evicted_txs: &mut Vec<Txid>,
+        now: SystemTime,
     ) -> eyre::Result<()> {
+        let mut missing_parents = HashSet::new();
+
         for input in input_proofs.keys() {
             let Some(parent) = yuv_tx.bitcoin_tx.input.get(*input as usize) else {
                 debug_assert!(false, "Output proof index is out of bounds");
@@ -350,22 +429,38 @@ where
                 || self.tx_storage.get_yuv_tx(&parent_txid).await?.is_some();
 
             if !is_attached {
-                // If there is no parent transaction in the storage, then
-                // we need to find it in checked txs or wait for it (add to storage).
-                self.inverse_deps
-                    .entry(parent_txid)
-                    .or_default()
-                    .insert(child_id);
-
-                self.deps.entry(child_id).or_default().insert(parent_txid);
+                missing_parents.insert(parent_txid);
             }
         }
 
+        if !missing_parents.is_empty() && !self.stored_txs.contains_key(&child_id) {
+            if let Err(err) = self.check_limits(child_id, &missing_parents) {
+                tracing::warn!(txid = child_id.to_string(), "Tx is evicted: {}", err);
+                let evicted = self.remove_tx_with_descendants(child_id);
+                queued_txs.retain(|txid| !evicted.contains(txid));
+                evicted_txs.extend(evicted);
+
+                return Ok(());
+            }
+        }
+
+        for parent_txid in missing_parents {
+            // If there is no parent transaction in the storage, then
+            // we need to find it in checked txs or wait for it (add to storage).
+            self.inverse_deps
+                .entry(parent_txid)
+                .or_default()
+                .insert(child_id);
+
+            self.deps.entry(child_id).or_default().insert(parent_txid);
+        }
+
         // May be, we already removed all deps that are attached, so we can check if we can add child
         let all_parents_attached = self.deps.entry(child_id).or_default().is_empty();
 
         if all_parents_attached {
             // If all parents are attached, then we can attach this transaction.
+            tracing::debug!("Tx is attached");
             attached_txs.push(yuv_tx.bitcoin_tx.txid());
 
             self.deps.remove(&child_id);
@@ -382,11 +477,76 @@ where
         }
 
         // If not all parents are attached, then we need to wait for them.
-        self.stored_txs
-            .insert(child_id, (yuv_tx.clone(), SystemTime::now()));
+        tracing::debug!("Tx is waiting for its parents");
+        self.stored_txs.insert(child_id, (yuv_tx.clone(), now));
+
+        Ok(())
+    }
+
+    /// Check that storing the transaction with the not attached parents doesn't exceed the
+    /// [`GraphLimits`].
+    fn check_limits(
+        &self,
+        txid: Txid,
+        missing_parents: &HashSet<Txid>,
+    ) -> Result<(), LimitExceeded> {
+        let limits = &self.limits;
+
+        if self.stored_txs.len() >= limits.max_pending_txs {
+            return Err(LimitExceeded::PendingTxs(limits.max_pending_txs));
+        }
+
+        for parent in missing_parents {
+            let children = self.inverse_deps.get(parent).map_or(0, HashSet::len);
+
+            if children >= limits.max_fanout {
+                return Err(LimitExceeded::Fanout(*parent, limits.max_fanout));
+            }
+        }
+
+        let mut memo = HashMap::new();
+        let ancestors = missing_parents
+            .iter()
+            .filter(|parent| self.stored_txs.contains_key(parent))
+            .map(|parent| 1 + self.chain_length(parent, &self.deps, &mut memo))
+            .max()
+            .unwrap_or(0);
+
+        let mut memo = HashMap::new();
+        let descendants = self.chain_length(&txid, &self.inverse_deps, &mut memo);
+
+        if ancestors + 1 + descendants > limits.max_depth {
+            return Err(LimitExceeded::Depth(limits.max_depth));
+        }
 
         Ok(())
     }
+
+    /// Length of the longest chain of the stored transactions that starts from the transaction
+    /// and follows the `edges`, not counting the transaction itself.
+    fn chain_length(
+        &self,
+        txid: &Txid,
+        edges: &HashMap<Txid, HashSet<Txid>>,
+        memo: &mut HashMap<Txid, usize>,
+    ) -> usize {
+        if let Some(length) = memo.get(txid) {
+            return *length;
+        }
+
+        let length = edges
+            .get(txid)
+            .into_iter()
+            .flatten()
+            .filter(|next| self.stored_txs.contains_key(next))
+            .map(|next| 1 + self.chain_length(next, edges, memo))
+            .max()
+            .unwrap_or(0);
+
+        memo.insert(*txid, length);
+
+        length
+    }
 }
 
 #[cfg(test)]
@@ -397,12 +557,13 @@ mod tests {
         blockdata::locktime::absolute::LockTime, secp256k1::Secp256k1, PrivateKey, PublicKey,
         Sequence, Transaction, Witness,
     };
+    use event_bus::Completion;
     use once_cell::sync::Lazy;
     use yuv_controller::Controller;
     use yuv_p2p::client::handle::MockHandle;
     use yuv_pixels::{Pixel, PixelProof, SigPixelProof};
     use yuv_storage::{LevelDB, MempoolEntryStorage, MempoolStatus, MempoolTxEntry};
-    use yuv_types::{IndexerMessage, TxCheckerMessage, TxConfirmMessage};
+    use yuv_types::{IndexerMessage, RpcSubscriptionMessage, TxCheckerMessage, TxConfirmMessage};
 
     use super::*;
 
@@ -426,10 +587,14 @@ mod tests {
         event_bus.register::<ControllerMessage>(Some(100));
         event_bus.register::<TxConfirmMessage>(Some(100));
         event_bus.register::<IndexerMessage>(Some(100));
+        event_bus.register::<RpcSubscriptionMessage>(Some(100));
 
         let mut mocked_p2p = MockHandle::new();
         // Just expect all messages to be sent successfully
-        mocked_p2p.expect_send_inv().times(..).returning(|_| Ok(()));
+        mocked_p2p
+            .expect_send_inv()
+            .times(..)
+            .returning(|_, _| Ok(()));
         mocked_p2p
             .expect_send_get_data()
             .times(..)
@@ -670,9 +835,7 @@ mod tests {
 
         let graph_builder = GraphBuilder::new(storage.clone(), &event_bus);
 
-        let mut graph_builder = graph_builder
-            .with_cleanup_period(Duration::from_secs(0))
-            .with_outdated_duration(Duration::from_secs(0));
+        let mut graph_builder = graph_builder.with_outdated_duration(Duration::from_secs(0));
 
         let tx1 = YuvTransaction {
             bitcoin_tx: Transaction {
@@ -869,4 +1032,94 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_depth_limit() -> eyre::Result<()> {
+        let storage = LevelDB::in_memory().unwrap();
+
+        let mut event_bus = EventBus::default();
+        event_bus.register::<GraphBuilderMessage>(Some(100));
+        event_bus.register::<ControllerMessage>(Some(100));
+
+        let mut graph_builder =
+            GraphBuilder::new(storage.clone(), &event_bus).with_limits(GraphLimits {
+                max_depth: 2,
+                ..Default::default()
+            });
+
+        // Parent of the chain is unknown, so all the txs have to wait for it.
+        let mut parent = bitcoin::OutPoint::new(
+            Txid::from_str("f1bd42d6e3b0e6e4fbd4a7a1c5b1f8d2e6c3a9b0d1e2f3a4b5c6d7e8f9a0b1c2")
+                .expect("valid txid"),
+            0,
+        );
+
+        let mut txs = Vec::new();
+        for version in 1..=3 {
+            let tx = YuvTransaction {
+                bitcoin_tx: Transaction {
+                    version,
+                    lock_time: LockTime::from_height(0).expect("failed to create lock time"),
+                    input: vec![bitcoin::TxIn {
+                        previous_output: parent,
+                        script_sig: bitcoin::ScriptBuf::default(),
+                        sequence: Sequence(0),
+                        witness: Witness::default(),
+                    }],
+                    output: vec![],
+                },
+
+                tx_type: YuvTxType::Transfer {
+                    input_proofs: BTreeMap::from([(0, DUMMY_PIXEL_PROOF.clone())]),
+                    output_proofs: Default::default(),
+                },
+            };
+
+            parent = bitcoin::OutPoint::new(tx.bitcoin_tx.txid(), 0);
+            txs.push(tx);
+        }
+
+        let events = event_bus.subscribe::<ControllerMessage>();
+
+        graph_builder.attach_txs(&txs).await?;
+
+        assert!(graph_builder
+            .stored_txs
+            .contains_key(&txs[0].bitcoin_tx.txid()));
+        assert!(graph_builder
+            .stored_txs
+            .contains_key(&txs[1].bitcoin_tx.txid()));
+        assert!(
+            !graph_builder
+                .stored_txs
+                .contains_key(&txs[2].bitcoin_tx.txid()),
+            "Tx that exceeds the depth limit must be evicted"
+        );
+
+        let ControllerMessage::InvalidTxs(evicted_txs) = events.recv().await? else {
+            panic!("Evicted txs must be reported");
+        };
+        assert_eq!(evicted_txs, vec![txs[2].bitcoin_tx.txid()]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_event_reports_completion() {
+        let storage = LevelDB::in_memory().unwrap();
+
+        let mut event_bus = EventBus::default();
+        event_bus.register::<GraphBuilderMessage>(Some(100));
+        event_bus.register::<ControllerMessage>(Some(100));
+
+        let mut graph_builder = GraphBuilder::new(storage, &event_bus);
+
+        let (completion, waiter) = Completion::new();
+        graph_builder
+            .handle_event(GraphBuilderMessage::CleanUp(completion))
+            .await
+            .unwrap();
+
+        assert_eq!(waiter.wait().await, Ok(()));
+    }
 }
@@ -0,0 +1,206 @@
+//! Property tests of the [`GraphBuilder`]: random graphs of issuances and transfers are
+//! delivered in random order and batches, and the builder must attach exactly the transactions
+//! whose history is known, whatever the order is.
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bitcoin::hashes::Hash;
+use bitcoin::Txid;
+use event_bus::EventBus;
+use proptest::prelude::*;
+use proptest::sample::Index;
+use yuv_storage::{LevelDB, TransactionsStorage};
+use yuv_types::{ControllerMessage, GraphBuilderMessage, YuvTransaction};
+
+use crate::test_util::{attachable, issuance, transfer};
+use crate::GraphBuilder;
+
+const MAX_TXS: usize = 16;
+const MAX_PARENTS: usize = 3;
+const MAX_BATCH_SIZE: usize = 4;
+const OUTDATED_DURATION: Duration = Duration::from_secs(60);
+
+/// Generated graph in the topological order, parents first.
+fn graph() -> impl Strategy<Value = Vec<YuvTransaction>> {
+    let node = (
+        any::<bool>(),
+        prop::collection::vec(any::<Index>(), 0..=MAX_PARENTS),
+        prop::bool::weighted(0.1),
+    );
+
+    prop::collection::vec(node, 1..=MAX_TXS).prop_map(|nodes| {
+        let mut txs: Vec<YuvTransaction> = Vec::with_capacity(nodes.len());
+
+        for (id, (is_issuance, parents, has_missing_parent)) in nodes.into_iter().enumerate() {
+            if is_issuance || id == 0 {
+                txs.push(issuance(id as u32));
+                continue;
+            }
+
+            let mut parents = parents
+                .into_iter()
+                .map(|parent| txs[parent.index(id)].bitcoin_tx.txid())
+                .collect::<Vec<_>>();
+
+            // Parent that is never delivered, so the tx and its descendants can't be attached.
+            if has_missing_parent {
+                parents.push(Txid::hash(&id.to_le_bytes()));
+            }
+
+            txs.push(transfer(id as u32, &parents));
+        }
+
+        txs
+    })
+}
+
+/// Generated graph, and its transactions in random order split into batches.
+fn delivery() -> impl Strategy<Value = (Vec<YuvTransaction>, Vec<Vec<YuvTransaction>>)> {
+    graph()
+        .prop_flat_map(|txs| {
+            (
+                Just(txs.clone()),
+                Just(txs).prop_shuffle(),
+                prop::collection::vec(1..=MAX_BATCH_SIZE, MAX_TXS),
+            )
+        })
+        .prop_map(|(txs, shuffled, sizes)| {
+            let mut rest = shuffled.as_slice();
+            let mut batches = Vec::new();
+
+            for size in sizes {
+                if rest.is_empty() {
+                    break;
+                }
+
+                let (batch, tail) = rest.split_at(size.min(rest.len()));
+                batches.push(batch.to_vec());
+                rest = tail;
+            }
+
+            (txs, batches)
+        })
+}
+
+fn start_time() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+}
+
+/// Builder that attached the batches, with the ids of the attached transactions in the order
+/// they were reported.
+struct Attached {
+    graph_builder: GraphBuilder<LevelDB>,
+    txids: Vec<Txid>,
+}
+
+/// Attaches the batches one by one, a second apart, and stores the attached transactions as the
+/// controller does, so the next batches find them.
+fn attach(batches: &[Vec<YuvTransaction>]) -> Attached {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime should build");
+
+    runtime.block_on(async {
+        let storage = LevelDB::in_memory().unwrap();
+
+        let mut event_bus = EventBus::default();
+        event_bus.register::<GraphBuilderMessage>(Some(100));
+        event_bus.register::<ControllerMessage>(Some(100));
+
+        let events = event_bus.subscribe::<ControllerMessage>();
+        let mut graph_builder = GraphBuilder::new(storage.clone(), &event_bus)
+            .with_outdated_duration(OUTDATED_DURATION);
+
+        let txs = batches
+            .iter()
+            .flatten()
+            .map(|tx| (tx.bitcoin_tx.txid(), tx))
+            .collect::<HashMap<_, _>>();
+
+        let mut txids = Vec::new();
+        for (i, batch) in batches.iter().enumerate() {
+            let now = start_time() + Duration::from_secs(i as u64);
+            graph_builder.attach_txs_at(batch, now).await.unwrap();
+
+            while !events.is_empty() {
+                match events.recv().await.unwrap() {
+                    ControllerMessage::AttachedTxs(attached) => {
+                        for txid in &attached {
+                            storage.put_yuv_tx(txs[txid].clone()).await.unwrap();
+                        }
+                        txids.extend(attached);
+                    }
+                    event => panic!("Unexpected event: {:?}", event),
+                }
+            }
+        }
+
+        Attached {
+            graph_builder,
+            txids,
+        }
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn test_attaches_exactly_attachable_txs((txs, batches) in delivery()) {
+        let Attached { graph_builder, txids } = attach(&batches);
+
+        let attached = txids.iter().copied().collect::<HashSet<_>>();
+        prop_assert_eq!(attached.len(), txids.len(), "Tx is attached twice");
+        prop_assert_eq!(&attached, &attachable(&txs, &HashSet::new()));
+
+        let pending = txs
+            .iter()
+            .map(|tx| tx.bitcoin_tx.txid())
+            .filter(|txid| !attached.contains(txid))
+            .collect::<HashSet<_>>();
+        prop_assert_eq!(graph_builder.pending_txs(), pending);
+
+        if let Err(err) = graph_builder.check_invariants() {
+            return Err(TestCaseError::fail(err));
+        }
+    }
+
+    #[test]
+    fn test_attach_is_order_independent((txs, batches) in delivery()) {
+        let shuffled = attach(&batches);
+        let in_order = attach(&[txs]);
+
+        prop_assert_eq!(
+            shuffled.txids.into_iter().collect::<HashSet<_>>(),
+            in_order.txids.into_iter().collect::<HashSet<_>>()
+        );
+        prop_assert_eq!(
+            shuffled.graph_builder.pending_txs(),
+            in_order.graph_builder.pending_txs()
+        );
+    }
+
+    #[test]
+    fn test_cleanup_leaves_no_deps((_txs, batches) in delivery()) {
+        let Attached { mut graph_builder, .. } = attach(&batches);
+
+        // Nothing is outdated right after the last batch.
+        let pending = graph_builder.pending_txs();
+        let last_batch_at = start_time() + Duration::from_secs(batches.len() as u64);
+        graph_builder.cleanup_at(last_batch_at).unwrap();
+        prop_assert_eq!(graph_builder.pending_txs(), pending);
+
+        graph_builder
+            .cleanup_at(last_batch_at + OUTDATED_DURATION + Duration::from_secs(1))
+            .unwrap();
+
+        prop_assert!(graph_builder.stored_txs.is_empty());
+        prop_assert!(graph_builder.deps.is_empty(), "Deps leaked: {:?}", graph_builder.deps);
+        prop_assert!(
+            graph_builder.inverse_deps.is_empty(),
+            "Inverse deps leaked: {:?}",
+            graph_builder.inverse_deps
+        );
+    }
+}
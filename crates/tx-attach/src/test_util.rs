@@ -0,0 +1,174 @@
+//! Helpers to test the [`GraphBuilder`]: the transactions of the synthetic graphs, the reference
+//! model of the attachable transactions and the checks of the builder's internal state.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bitcoin::absolute::LockTime;
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use bitcoin::{OutPoint, Transaction, TxIn, Txid};
+use yuv_pixels::{Chroma, Pixel, PixelProof};
+use yuv_types::announcements::IssueAnnouncement;
+use yuv_types::{YuvTransaction, YuvTxType};
+
+use crate::GraphBuilder;
+
+/// Key that owns the pixels of the synthetic transactions.
+pub fn pubkey() -> PublicKey {
+    SecretKey::from_slice(&[1; 32])
+        .expect("valid secret key")
+        .public_key(&Secp256k1::new())
+}
+
+/// Chroma issued by the [`pubkey`].
+pub fn chroma() -> Chroma {
+    Chroma::from(pubkey().x_only_public_key().0)
+}
+
+/// Bitcoin transaction whose id is unique for the `id`.
+fn bitcoin_tx(id: u32, parents: &[Txid]) -> Transaction {
+    Transaction {
+        version: 2,
+        lock_time: LockTime::from_consensus(id),
+        input: parents
+            .iter()
+            .map(|parent| TxIn {
+                previous_output: OutPoint::new(*parent, 0),
+                ..Default::default()
+            })
+            .collect(),
+        output: Vec::new(),
+    }
+}
+
+/// Issuance, which is attached without waiting for any parents.
+pub fn issuance(id: u32) -> YuvTransaction {
+    YuvTransaction::new(
+        bitcoin_tx(id, &[]),
+        YuvTxType::Issue {
+            output_proofs: None,
+            announcement: IssueAnnouncement::new(chroma(), 100),
+        },
+    )
+}
+
+/// Transfer that spends the outputs of the `parents`.
+pub fn transfer(id: u32, parents: &[Txid]) -> YuvTransaction {
+    let proof = PixelProof::sig(Pixel::new(100, chroma()), pubkey());
+
+    YuvTransaction::new(
+        bitcoin_tx(id, parents),
+        YuvTxType::Transfer {
+            input_proofs: (0..parents.len() as u32)
+                .map(|input| (input, proof.clone()))
+                .collect::<BTreeMap<_, _>>(),
+            output_proofs: Default::default(),
+        },
+    )
+}
+
+/// Transfer that spends the outputs of the `parents` and sends the `amount` to its first output.
+pub fn transfer_of(id: u32, parents: &[Txid], amount: u128) -> YuvTransaction {
+    let mut tx = transfer(id, parents);
+    if let YuvTxType::Transfer { output_proofs, .. } = &mut tx.tx_type {
+        output_proofs.insert(0, PixelProof::sig(Pixel::new(amount, chroma()), pubkey()));
+    }
+
+    tx
+}
+
+/// Reference model of the [`GraphBuilder`]: the ids of the `txs` that can be attached, when the
+/// transactions `attached` before are known. The order of the `txs` doesn't matter.
+pub fn attachable(txs: &[YuvTransaction], attached: &HashSet<Txid>) -> HashSet<Txid> {
+    let parents = txs
+        .iter()
+        .map(|tx| {
+            let parents = match &tx.tx_type {
+                YuvTxType::Transfer { input_proofs, .. } => input_proofs
+                    .keys()
+                    .filter_map(|input| tx.bitcoin_tx.input.get(*input as usize))
+                    .map(|input| input.previous_output.txid)
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            (tx.bitcoin_tx.txid(), parents)
+        })
+        .collect::<HashMap<Txid, Vec<Txid>>>();
+
+    let mut result = attached.clone();
+    loop {
+        let newly_attached = parents
+            .iter()
+            .filter(|(txid, parents)| {
+                !result.contains(*txid) && parents.iter().all(|parent| result.contains(parent))
+            })
+            .map(|(txid, _)| *txid)
+            .collect::<Vec<_>>();
+
+        if newly_attached.is_empty() {
+            break;
+        }
+
+        result.extend(newly_attached);
+    }
+
+    result.retain(|txid| parents.contains_key(txid));
+    result
+}
+
+impl<TS> GraphBuilder<TS> {
+    /// Ids of the transactions that are waiting for their parents.
+    pub fn pending_txs(&self) -> HashSet<Txid> {
+        self.stored_txs.keys().copied().collect()
+    }
+
+    /// Checks that the dependencies of the waiting transactions are consistent: each of them has
+    /// the parents it waits for, the inverse dependencies mirror the dependencies, and there are
+    /// no entries of the transactions that aren't waiting.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        for (txid, parents) in &self.deps {
+            if !self.stored_txs.contains_key(txid) {
+                return Err(format!("deps of {} that isn't waiting", txid));
+            }
+
+            if parents.is_empty() {
+                return Err(format!("{} is waiting without parents", txid));
+            }
+
+            for parent in parents {
+                let is_mirrored = self
+                    .inverse_deps
+                    .get(parent)
+                    .is_some_and(|children| children.contains(txid));
+
+                if !is_mirrored {
+                    return Err(format!("no inverse dep of {} on {}", txid, parent));
+                }
+            }
+        }
+
+        for (parent, children) in &self.inverse_deps {
+            if children.is_empty() {
+                return Err(format!("empty inverse deps of {}", parent));
+            }
+
+            for child in children {
+                let is_mirrored = self
+                    .deps
+                    .get(child)
+                    .is_some_and(|parents| parents.contains(parent));
+
+                if !is_mirrored {
+                    return Err(format!("no dep of {} on {}", child, parent));
+                }
+            }
+        }
+
+        for txid in self.stored_txs.keys() {
+            if !self.deps.contains_key(txid) {
+                return Err(format!("{} is waiting without deps", txid));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 use bitcoin::Txid;
-use yuv_storage::{ChromaInfoStorage, FrozenTxsStorage, InvalidTxsStorage, TransactionsStorage};
+use yuv_storage::{
+    ChromaInfoStorage, DelegatesStorage, FrozenPubkeysStorage, FrozenTxsStorage, InvalidTxsStorage,
+    TransactionsStorage,
+};
 use yuv_types::announcements::{
-    ChromaAnnouncement, FreezeAnnouncement, IssueAnnouncement, TransferOwnershipAnnouncement,
+    BurnAnnouncement, ChromaAnnouncement, DelegateAnnouncement, FreezeAnnouncement,
+    FreezePubkeyAnnouncement, IssueAnnouncement, TransferOwnershipAnnouncement,
+    UpdateChromaAnnouncement,
 };
 
 use crate::TxChecker;
@@ -9,9 +14,19 @@ use crate::TxChecker;
 impl<TS, SS> TxChecker<TS, SS>
 where
     TS: TransactionsStorage + Clone + Send + Sync + 'static,
-    SS: InvalidTxsStorage + FrozenTxsStorage + ChromaInfoStorage + Clone + Send + Sync + 'static,
+    SS: InvalidTxsStorage
+        + FrozenTxsStorage
+        + FrozenPubkeysStorage
+        + DelegatesStorage
+        + ChromaInfoStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
 {
-    /// Update chroma announcements in storage.
+    /// Add the chroma announcement to the storage, and keep it as its initial version. If the
+    /// announcement declares the issuer set, the multisig of the issuers becomes the owner of the
+    /// chroma.
     pub(crate) async fn add_chroma_announcements(
         &self,
         announcement: &ChromaAnnouncement,
@@ -21,7 +36,7 @@ where
             .get_chroma_info(&announcement.chroma)
             .await?;
 
-        let (total_supply, owner) = if let Some(chroma_info) = chroma_info {
+        let (total_supply, total_burned, owner) = if let Some(chroma_info) = chroma_info {
             if chroma_info.announcement.is_some() {
                 tracing::debug!(
                     "Chroma announcement for Chroma {} already exist",
@@ -31,19 +46,34 @@ where
                 return Ok(());
             }
 
-            (chroma_info.total_supply, chroma_info.owner)
+            (
+                chroma_info.total_supply,
+                chroma_info.total_burned,
+                chroma_info.owner,
+            )
         } else {
-            (0, None)
+            (0, 0, None)
         };
 
+        let owner = announcement
+            .issuers
+            .as_ref()
+            .map(|issuers| issuers.owner_script())
+            .or(owner);
+
         self.state_storage
             .put_chroma_info(
                 &announcement.chroma,
                 Some(announcement.clone()),
                 total_supply,
+                total_burned,
+                0,
                 owner,
             )
             .await?;
+        self.state_storage
+            .put_chroma_announcement_version(&announcement.chroma, 0, announcement.clone())
+            .await?;
 
         tracing::debug!(
             "Chroma announcement for Chroma {} is added",
@@ -53,15 +83,17 @@ where
         Ok(())
     }
 
-    /// Set freeze entry for the given outpoint in the freeze storage.
+    /// Set freeze entry for the given outpoint in the freeze storage. The outpoint can be frozen
+    /// again only if its previous freeze has expired by the given block height.
     pub(crate) async fn update_freezes(
         &self,
         txid: Txid,
         freeze: &FreezeAnnouncement,
+        height: usize,
     ) -> eyre::Result<()> {
         let freeze_outpoint = &freeze.freeze_outpoint();
         let freeze_entry = self.state_storage.get_frozen_tx(freeze_outpoint).await?;
-        if let Some(freeze_entry) = freeze_entry {
+        if let Some(freeze_entry) = freeze_entry.filter(|entry| !entry.is_expired(height)) {
             tracing::debug!(
                 txid = freeze.freeze_txid().to_string(),
                 vout = freeze.freeze_vout(),
@@ -73,18 +105,113 @@ where
         }
 
         self.state_storage
-            .put_frozen_tx(freeze_outpoint, txid, freeze.chroma)
+            .put_frozen_tx(
+                freeze_outpoint,
+                txid,
+                freeze.chroma,
+                freeze.amount,
+                freeze.expiry_height,
+            )
             .await?;
 
         tracing::debug!(
             txid = freeze.freeze_txid().to_string(),
             vout = freeze.freeze_vout(),
+            amount = ?freeze.amount,
+            expiry_height = ?freeze.expiry_height,
             "The outpoint is frozen",
         );
 
         Ok(())
     }
 
+    /// Freeze or unfreeze the pixels of the announced public key.
+    pub(crate) async fn update_pubkey_freezes(
+        &self,
+        txid: Txid,
+        freeze: &FreezePubkeyAnnouncement,
+    ) -> eyre::Result<()> {
+        if freeze.unfreeze {
+            self.state_storage
+                .delete_frozen_pubkey(&freeze.chroma, &freeze.pubkey)
+                .await?;
+
+            tracing::debug!(
+                chroma = %freeze.chroma,
+                pubkey = %freeze.pubkey,
+                "The public key is unfrozen",
+            );
+
+            return Ok(());
+        }
+
+        if let Some(freeze_entry) = self
+            .state_storage
+            .get_frozen_pubkey(&freeze.chroma, &freeze.pubkey)
+            .await?
+        {
+            tracing::debug!(
+                chroma = %freeze.chroma,
+                pubkey = %freeze.pubkey,
+                "Public key was previously frozen in tx {:?}",
+                freeze_entry.txid
+            );
+
+            return Ok(());
+        }
+
+        self.state_storage
+            .put_frozen_pubkey(&freeze.chroma, &freeze.pubkey, txid)
+            .await?;
+
+        tracing::debug!(
+            chroma = %freeze.chroma,
+            pubkey = %freeze.pubkey,
+            "The public key is frozen",
+        );
+
+        Ok(())
+    }
+
+    /// Grant the announced capabilities to the delegate, or revoke them if there are none.
+    pub(crate) async fn update_delegates(
+        &self,
+        txid: Txid,
+        delegate: &DelegateAnnouncement,
+    ) -> eyre::Result<()> {
+        if delegate.is_revoke() {
+            self.state_storage
+                .delete_delegate(&delegate.chroma, &delegate.delegate)
+                .await?;
+
+            tracing::debug!(
+                chroma = %delegate.chroma,
+                delegate = %delegate.delegate,
+                "The delegation is revoked",
+            );
+
+            return Ok(());
+        }
+
+        self.state_storage
+            .put_delegate(
+                &delegate.chroma,
+                &delegate.delegate,
+                delegate.capabilities,
+                txid,
+            )
+            .await?;
+
+        tracing::debug!(
+            chroma = %delegate.chroma,
+            delegate = %delegate.delegate,
+            capabilities = %delegate.capabilities,
+            "The capabilities are delegated",
+        );
+
+        Ok(())
+    }
+
     pub(crate) async fn update_supply(&self, issue: &IssueAnnouncement) -> eyre::Result<()> {
         if let Some(chroma_info) = self.state_storage.get_chroma_info(&issue.chroma).await? {
             self.state_storage
@@ -92,6 +219,8 @@ where
                     &issue.chroma,
                     chroma_info.announcement,
                     chroma_info.total_supply + issue.amount,
+                    chroma_info.total_burned,
+                    chroma_info.announcement_version,
                     chroma_info.owner,
                 )
                 .await?;
@@ -100,7 +229,7 @@ where
         }
 
         self.state_storage
-            .put_chroma_info(&issue.chroma, None, issue.amount, None)
+            .put_chroma_info(&issue.chroma, None, issue.amount, 0, 0, None)
             .await?;
 
         tracing::debug!("Updated supply for chroma {}", issue.chroma);
@@ -112,23 +241,103 @@ where
         &self,
         transfer_ownership: &TransferOwnershipAnnouncement,
     ) -> eyre::Result<()> {
-        let chroma_info_opt = self
+        let chroma_info = self
             .state_storage
             .get_chroma_info(&transfer_ownership.chroma)
-            .await?;
-
-        let (announcement, total_supply) = chroma_info_opt.map_or((None, 0), |chroma_info| {
-            (chroma_info.announcement, chroma_info.total_supply)
-        });
+            .await?
+            .unwrap_or_default();
 
         self.state_storage
             .put_chroma_info(
                 &transfer_ownership.chroma,
-                announcement,
-                total_supply,
+                chroma_info.announcement,
+                chroma_info.total_supply,
+                chroma_info.total_burned,
+                chroma_info.announcement_version,
                 Some(transfer_ownership.new_owner.clone()),
             )
             .await?;
         Ok(())
     }
+
+    /// Add the burnt amount to the total burnt amount of the chroma.
+    pub(crate) async fn update_burned(&self, burn: &BurnAnnouncement) -> eyre::Result<()> {
+        let chroma_info = self
+            .state_storage
+            .get_chroma_info(&burn.chroma)
+            .await?
+            .unwrap_or_default();
+
+        let Some(total_burned) = chroma_info.total_burned.checked_add(burn.amount) else {
+            eyre::bail!("Burnt amount of chroma {} overflows", burn.chroma);
+        };
+
+        self.state_storage
+            .put_chroma_info(
+                &burn.chroma,
+                chroma_info.announcement,
+                chroma_info.total_supply,
+                total_burned,
+                chroma_info.announcement_version,
+                chroma_info.owner,
+            )
+            .await?;
+
+        tracing::debug!(
+            "Updated burnt amount for chroma {}: +{}",
+            burn.chroma,
+            burn.amount
+        );
+
+        Ok(())
+    }
+
+    /// Replace the name and the symbol of the chroma announcement with the updated ones, and keep
+    /// it as the next version of the announcement. The chroma announcement must be present.
+    pub(crate) async fn update_chroma_metadata(
+        &self,
+        update: &UpdateChromaAnnouncement,
+    ) -> eyre::Result<()> {
+        let chroma_info = self
+            .state_storage
+            .get_chroma_info(&update.chroma)
+            .await?
+            .unwrap_or_default();
+
+        let Some(announcement) = chroma_info.announcement else {
+            eyre::bail!(
+                "Chroma announcement for Chroma {} is missing",
+                update.chroma
+            );
+        };
+
+        let announcement = update.apply(&announcement);
+        let announcement_version = chroma_info.announcement_version + 1;
+
+        self.state_storage
+            .put_chroma_announcement_version(
+                &update.chroma,
+                announcement_version,
+                announcement.clone(),
+            )
+            .await?;
+        self.state_storage
+            .put_chroma_info(
+                &update.chroma,
+                Some(announcement),
+                chroma_info.total_supply,
+                chroma_info.total_burned,
+                announcement_version,
+                chroma_info.owner,
+            )
+            .await?;
+
+        tracing::debug!(
+            "Chroma announcement for Chroma {} is updated to version {}",
+            update.chroma,
+            announcement_version
+        );
+
+        Ok(())
+    }
 }
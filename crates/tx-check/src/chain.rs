@@ -0,0 +1,197 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bitcoin::{OutPoint, TxOut, Txid};
+use yuv_pixels::{CheckableProof, Chroma, PixelProof};
+use yuv_types::{YuvTransaction, YuvTxType};
+
+use crate::{
+    errors::{ChainError, CheckError},
+    isolated_checks::{check_transaction, find_owner_in_txinputs},
+};
+
+/// Outputs of the verified bundle of transactions, see [`verify_yuv_transaction_chain`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifiedState {
+    /// Outputs of the bundle that aren't spent by its transactions, with their proofs.
+    pub unspent: BTreeMap<OutPoint, PixelProof>,
+}
+
+impl VerifiedState {
+    /// Returns the proof of the unspent output, `None` if the bundle doesn't prove it.
+    pub fn get(&self, outpoint: &OutPoint) -> Option<&PixelProof> {
+        self.unspent.get(outpoint)
+    }
+
+    /// Returns the amount of the `chroma` in the unspent outputs.
+    pub fn balance(&self, chroma: &Chroma) -> u128 {
+        self.unspent
+            .values()
+            .filter(|proof| !proof.is_empty_pixelproof() && proof.pixel().chroma == *chroma)
+            .map(|proof| proof.pixel().luma.amount)
+            .sum()
+    }
+}
+
+/// Verifies the bundle of transactions that makes the whole history of the outputs, from the
+/// issuances, without the node, e.g. in the mobile wallet that receives the bundle out of band.
+///
+/// Each transaction is checked with [`check_transaction`], the issuances must be signed by the
+/// issuer, and the inputs of the transfers must spend the outputs of the bundle with the same
+/// pixels, at most once. The order of the transactions doesn't matter.
+///
+/// The check doesn't need the storage, so it can't tell whether the transactions are mined and
+/// the outputs aren't frozen, which should be checked separately, e.g. with the SPV proofs. The
+/// issuances of the chromas whose ownership was transferred are rejected, as their owners can't
+/// be known without the announcements.
+pub fn verify_yuv_transaction_chain(txs: &[YuvTransaction]) -> Result<VerifiedState, ChainError> {
+    let mut txs_by_id = HashMap::<Txid, &YuvTransaction>::new();
+    let mut outputs = HashMap::new();
+    for tx in txs {
+        let txid = tx.bitcoin_tx.txid();
+        if txs_by_id.insert(txid, tx).is_some() {
+            return Err(ChainError::DuplicateTransaction(txid));
+        }
+
+        check_transaction(tx).map_err(|error| ChainError::InvalidTransaction { txid, error })?;
+
+        if let YuvTxType::Issue { announcement, .. } = &tx.tx_type {
+            let owner = find_owner_in_txinputs(&tx.bitcoin_tx.input, &announcement.chroma, None);
+
+            if !matches!(owner, Ok(Some(_))) {
+                return Err(ChainError::InvalidTransaction {
+                    txid,
+                    error: CheckError::IssuerNotOwner,
+                });
+            }
+        }
+
+        let Some(output_proofs) = tx.tx_type.output_proofs() else {
+            continue;
+        };
+
+        for (vout, proof) in output_proofs {
+            outputs.insert(OutPoint::new(txid, *vout), proof);
+        }
+    }
+
+    let mut spent = HashSet::new();
+    for tx in txs {
+        let YuvTxType::Transfer { input_proofs, .. } = &tx.tx_type else {
+            continue;
+        };
+        let txid = tx.bitcoin_tx.txid();
+
+        for (input, proof) in input_proofs {
+            let Some(txin) = tx.bitcoin_tx.input.get(*input as usize) else {
+                return Err(ChainError::InvalidTransaction {
+                    txid,
+                    error: CheckError::ProofMappedToNotExistingInputOutput,
+                });
+            };
+            let parent = txin.previous_output;
+
+            let Some(parent_proof) = outputs.get(&parent) else {
+                return Err(ChainError::MissingParent { txid, parent });
+            };
+
+            let parent_output = txs_by_id
+                .get(&parent.txid)
+                .and_then(|tx| tx.bitcoin_tx.output.get(parent.vout as usize));
+
+            if !is_parent_proof(proof, parent_proof, parent_output) {
+                return Err(ChainError::ParentProofMismatch {
+                    txid,
+                    input: *input,
+                });
+            }
+
+            if !spent.insert(parent) {
+                return Err(ChainError::DoubleSpend(parent));
+            }
+        }
+    }
+
+    let unspent = outputs
+        .into_iter()
+        .filter(|(outpoint, _)| !spent.contains(outpoint))
+        .map(|(outpoint, proof)| (outpoint, proof.clone()))
+        .collect();
+
+    Ok(VerifiedState { unspent })
+}
+
+/// Verifies the transaction against its parents provided along with it instead of the ones from
+/// the storage, e.g. on the gateway node with the pruned history that forwards the transactions.
+///
+/// The transaction and its parents are checked with [`check_transaction`], and the inputs of the
+/// transfer must spend the outputs of the parents with the same pixels. Unlike
+/// [`verify_yuv_transaction_chain`], the history of the parents isn't verified. The parents that
+/// aren't spent by the transaction are rejected, so the bundle can't be padded with them.
+pub fn verify_yuv_transaction_with_parents(
+    tx: &YuvTransaction,
+    parents: &[YuvTransaction],
+) -> Result<(), ChainError> {
+    let txid = tx.bitcoin_tx.txid();
+    check_transaction(tx).map_err(|error| ChainError::InvalidTransaction { txid, error })?;
+
+    let mut parents_by_id = HashMap::<Txid, &YuvTransaction>::new();
+    for parent in parents {
+        let parent_id = parent.bitcoin_tx.txid();
+        if parents_by_id.insert(parent_id, parent).is_some() {
+            return Err(ChainError::DuplicateTransaction(parent_id));
+        }
+
+        check_transaction(parent).map_err(|error| ChainError::InvalidTransaction {
+            txid: parent_id,
+            error,
+        })?;
+    }
+
+    let mut spent_parents = HashSet::new();
+    if let YuvTxType::Transfer { input_proofs, .. } = &tx.tx_type {
+        for (input, proof) in input_proofs {
+            let Some(txin) = tx.bitcoin_tx.input.get(*input as usize) else {
+                return Err(ChainError::InvalidTransaction {
+                    txid,
+                    error: CheckError::ProofMappedToNotExistingInputOutput,
+                });
+            };
+            let parent = txin.previous_output;
+
+            let parent_tx = parents_by_id.get(&parent.txid);
+            let Some(parent_proof) = parent_tx
+                .and_then(|parent_tx| parent_tx.tx_type.output_proofs())
+                .and_then(|output_proofs| output_proofs.get(&parent.vout))
+            else {
+                return Err(ChainError::MissingParent { txid, parent });
+            };
+            let parent_output = parent_tx
+                .and_then(|parent_tx| parent_tx.bitcoin_tx.output.get(parent.vout as usize));
+
+            if !is_parent_proof(proof, parent_proof, parent_output) {
+                return Err(ChainError::ParentProofMismatch {
+                    txid,
+                    input: *input,
+                });
+            }
+
+            spent_parents.insert(parent.txid);
+        }
+    }
+
+    if let Some(unspent) = parents_by_id.keys().find(|id| !spent_parents.contains(*id)) {
+        return Err(ChainError::UnspentParent(*unspent));
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the input proof is the one that locks the parent's output.
+fn is_parent_proof(
+    proof: &PixelProof,
+    parent_proof: &PixelProof,
+    parent_output: Option<&TxOut>,
+) -> bool {
+    proof.pixel() == parent_proof.pixel()
+        && parent_output.is_some_and(|output| proof.checked_check_by_output(output).is_ok())
+}
@@ -1,6 +1,6 @@
-use bitcoin::{ecdsa::Error as EcdsaSigError, Txid};
+use bitcoin::{ecdsa::Error as EcdsaSigError, OutPoint, Txid};
 
-use yuv_pixels::{PixelProof, PixelProofError};
+use yuv_pixels::{Chroma, PixelProof, PixelProofError};
 
 /// Errors that can occur during the transaction checking.
 #[derive(thiserror::Error, Debug)]
@@ -66,6 +66,10 @@ pub enum CheckError {
     #[error("Mixed bulletproofs and non-bulletproofs")]
     MixedBulletproofsAndNonBulletproofs,
 
+    /// Aggregated range proof of the bulletproof outputs is missing, duplicated or invalid.
+    #[error("Invalid aggregated range proof")]
+    InvalidAggregatedRangeProof,
+
     /// To verify transaction, at least one commitment is needed.
     #[error("To verify transaction, at least one commitment is needed")]
     AtLeastOneCommitment,
@@ -100,6 +104,47 @@ pub enum CheckError {
 
     #[error("Trying to spend from the burn address")]
     BurntTokensSpending,
+
+    /// Lock time of the transaction is less than the lock height of the timelocked input.
+    #[error("Input {vin} can't be spent before the block {lock_height}")]
+    InputTimelocked { vin: u32, lock_height: u32 },
+
+    #[error("Transaction has more than one burn announcement for chroma {0}")]
+    DuplicateBurnAnnouncement(Chroma),
+
+    /// Pixel output holds less satoshis than the [`DustPolicy`](yuv_types::DustPolicy) allows.
+    #[error("Output {vout} holds {satoshis} satoshis, less than the minimum of {min_satoshis}")]
+    DustOutput {
+        vout: u32,
+        satoshis: u64,
+        min_satoshis: u64,
+    },
+}
+
+/// Errors of the [`verify_yuv_transaction_chain`](crate::verify_yuv_transaction_chain) and the
+/// [`verify_yuv_transaction_with_parents`](crate::verify_yuv_transaction_with_parents).
+#[derive(thiserror::Error, Debug)]
+pub enum ChainError {
+    #[error("Transaction {txid} is invalid: {error}")]
+    InvalidTransaction { txid: Txid, error: CheckError },
+
+    #[error("Transaction {0} is duplicated")]
+    DuplicateTransaction(Txid),
+
+    /// The input spends the output that is not in the bundle, so its history can't be verified.
+    #[error("Parent {parent} of the transaction {txid} is missing")]
+    MissingParent { txid: Txid, parent: OutPoint },
+
+    /// The input proof doesn't match the proof of the spent output.
+    #[error("Proof of the input {input} of the transaction {txid} doesn't match the parent's one")]
+    ParentProofMismatch { txid: Txid, input: u32 },
+
+    #[error("Output {0} is spent more than once")]
+    DoubleSpend(OutPoint),
+
+    /// None of the outputs of the provided parent is spent by the transaction.
+    #[error("Parent {0} isn't spent by the transaction")]
+    UnspentParent(Txid),
 }
 
 /// [`TransactionChecker`](crate::TransactionChecker) errors.
@@ -0,0 +1,120 @@
+//! Partial freezes of the outputs.
+//!
+//! Only the unfrozen remainder of the partially frozen output can be spent. The frozen amount must
+//! be kept in the continuation output: the output of the spending transaction with the same owner
+//! and chroma, which becomes frozen for that amount once the transaction is attached.
+//!
+//! The outputs owned by the public keys frozen for the chroma can't be spent at all.
+//!
+//! The expirable freezes are inactive starting from their expiry height, and the continuation
+//! outputs keep the expiry height of the freeze.
+use bitcoin::{secp256k1::PublicKey, Txid};
+use yuv_pixels::{Chroma, PixelProof};
+use yuv_storage::TxFreezeEntry;
+use yuv_types::YuvTransaction;
+
+/// Freeze state of the output that is being spent.
+pub(crate) enum FreezeState {
+    NotFrozen,
+    Frozen,
+    PartiallyFrozen(PartialFreeze),
+}
+
+/// Frozen part of the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialFreeze {
+    /// Identifier of the transaction that froze the output.
+    pub freeze_txid: Txid,
+    pub chroma: Chroma,
+    /// Owner of the frozen output.
+    pub owner: PublicKey,
+    /// Frozen amount of tokens.
+    pub amount: u128,
+    /// Height of the block starting from which the frozen amount is unfrozen.
+    pub expiry_height: Option<u32>,
+}
+
+/// Public keys owning the output by its proof, whose freezes make the output frozen.
+pub(crate) fn owner_pubkeys(proof: &PixelProof) -> Vec<PublicKey> {
+    match proof {
+        PixelProof::Sig(proof) => vec![proof.inner_key],
+        PixelProof::Multisig(proof) => proof.inner_keys.clone(),
+        PixelProof::Musig(proof) => proof.inner_keys.clone(),
+        PixelProof::Timelock(proof) => vec![proof.inner_key],
+        PixelProof::Htlc(proof) => vec![proof.data.recipient_pubkey, proof.data.sender_pubkey],
+        #[cfg(feature = "bulletproof")]
+        PixelProof::Bulletproof(proof) => vec![proof.inner_key],
+        _ => Vec::new(),
+    }
+}
+
+/// Frozen part of the output with the `proof` by the active freeze `entry` of the same chroma.
+/// Returns `None` if the whole output is frozen: the freeze isn't partial, the output has several
+/// owners, or nothing is left to spend.
+pub fn partial_freeze(entry: &TxFreezeEntry, proof: &PixelProof) -> Option<PartialFreeze> {
+    let amount = entry.amount?;
+
+    let PixelProof::Sig(proof) = proof else {
+        return None;
+    };
+    if amount >= proof.pixel.luma.amount {
+        return None;
+    }
+
+    Some(PartialFreeze {
+        freeze_txid: entry.txid,
+        chroma: entry.chroma,
+        owner: proof.inner_key,
+        amount,
+        expiry_height: entry.expiry_height,
+    })
+}
+
+/// Find the continuation outputs for the partially frozen inputs of the transaction. The frozen
+/// amounts of the inputs with the same owner and chroma are summed up and must be kept in a single
+/// output, which is the first one with enough tokens.
+///
+/// Returns `None` if any of the frozen amounts is not kept.
+pub fn find_continuation_outputs(
+    tx: &YuvTransaction,
+    freezes: Vec<PartialFreeze>,
+) -> Option<Vec<(u32, PartialFreeze)>> {
+    let mut merged: Vec<PartialFreeze> = Vec::new();
+    for freeze in freezes {
+        match merged
+            .iter_mut()
+            .find(|merged| merged.owner == freeze.owner && merged.chroma == freeze.chroma)
+        {
+            Some(merged) => {
+                merged.amount = merged.amount.checked_add(freeze.amount)?;
+                // The merged amount is frozen until the latest of the expiry heights, and forever
+                // if any of the freezes doesn't expire.
+                merged.expiry_height = merged
+                    .expiry_height
+                    .zip(freeze.expiry_height)
+                    .map(|(merged, expiry_height)| merged.max(expiry_height));
+            }
+            None => merged.push(freeze),
+        }
+    }
+
+    let output_proofs = tx.tx_type.output_proofs()?;
+
+    let mut continuations: Vec<(u32, PartialFreeze)> = Vec::with_capacity(merged.len());
+    for freeze in merged {
+        let (vout, _) = output_proofs.iter().find(|(vout, proof)| {
+            let PixelProof::Sig(proof) = proof else {
+                return false;
+            };
+
+            proof.inner_key == freeze.owner
+                && proof.pixel.chroma == freeze.chroma
+                && proof.pixel.luma.amount >= freeze.amount
+                && !continuations.iter().any(|(used, _)| used == *vout)
+        })?;
+
+        continuations.push((*vout, freeze));
+    }
+
+    Some(continuations)
+}
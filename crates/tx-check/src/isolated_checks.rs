@@ -1,18 +1,22 @@
 use std::collections::HashMap;
 
 use bitcoin::{
-    self, key::Secp256k1, secp256k1::All, AddressType, ScriptBuf, Transaction, TxIn, TxOut, Witness,
+    self,
+    absolute::LockTime,
+    key::Secp256k1,
+    secp256k1::{All, PublicKey},
+    AddressType, ScriptBuf, Transaction, TxIn, TxOut, Witness,
 };
 
 #[cfg(feature = "bulletproof")]
 use {
     bitcoin::{
         hashes::{sha256, Hash, HashEngine},
-        secp256k1::{Message, PublicKey},
+        secp256k1::Message,
     },
     yuv_pixels::{
         k256::{elliptic_curve::group::GroupEncoding, ProjectivePoint},
-        Bulletproof,
+        verify_aggregated_bulletproof, Bulletproof, CommitmentProof,
     },
     yuv_types::is_bulletproof,
 };
@@ -20,8 +24,11 @@ use {
 use yuv_pixels::{
     CheckableProof, Chroma, P2WPKHWitness, Pixel, PixelKey, PixelProof, ToEvenPublicKey,
 };
-use yuv_types::{announcements::ChromaInfo, AnyAnnouncement, ProofMap};
-use yuv_types::{announcements::IssueAnnouncement, YuvTransaction, YuvTxType};
+use yuv_types::{announcements::ChromaInfo, AnyAnnouncement, DustPolicy, ProofMap};
+use yuv_types::{
+    announcements::{BurnAnnouncement, IssueAnnouncement},
+    YuvTransaction, YuvTxType,
+};
 
 use crate::{errors::CheckError, script_parser::SpendingCondition};
 
@@ -42,6 +49,35 @@ pub fn check_transaction(yuv_tx: &YuvTransaction) -> Result<(), CheckError> {
     }
 }
 
+/// Checks that the pixel outputs of the transaction hold at least the minimal amount of satoshis
+/// of the policy. Outputs with the empty pixel proofs don't carry any pixel, so they are skipped.
+pub fn check_dust(yuv_tx: &YuvTransaction, policy: &DustPolicy) -> Result<(), CheckError> {
+    let Some(output_proofs) = yuv_tx.tx_type.output_proofs() else {
+        return Ok(());
+    };
+
+    for (vout, proof) in output_proofs {
+        if proof.is_empty_pixelproof() {
+            continue;
+        }
+
+        let Some(output) = yuv_tx.bitcoin_tx.output.get(*vout as usize) else {
+            return Err(CheckError::ProofMappedToNotExistingInputOutput);
+        };
+
+        let satoshis = output.value;
+        if policy.is_dust(satoshis) {
+            return Err(CheckError::DustOutput {
+                vout: *vout,
+                satoshis,
+                min_satoshis: policy.min_satoshis,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn check_issue_isolated(
     tx: &Transaction,
     output_proofs_opt: &Option<ProofMap>,
@@ -81,7 +117,7 @@ pub(crate) fn check_issue_isolated(
 
     #[cfg(feature = "bulletproof")]
     if is_bulletproof(output_proofs.values().collect::<Vec<&PixelProof>>()) {
-        return Ok(());
+        return check_aggregated_range_proof(output_proofs);
     }
 
     let total_amount = output_proofs
@@ -145,6 +181,8 @@ pub(crate) fn check_transfer_isolated(
             })?;
     }
 
+    check_input_timelocks(tx, &gathered_inputs)?;
+
     for ProofForCheck {
         inner,
         vout,
@@ -160,9 +198,13 @@ pub(crate) fn check_transfer_isolated(
             })?;
     }
 
+    check_burn_announcements(tx, &gathered_outputs)?;
+
     #[cfg(feature = "bulletproof")]
     if let Some((inputs_bulletproof, outputs_bulletproof)) = extract_bulletproofs(inputs, outputs)?
     {
+        check_aggregated_range_proof(outputs)?;
+
         return check_bulletproof_conservation_rules(inputs_bulletproof, outputs_bulletproof);
     }
 
@@ -171,6 +213,85 @@ pub(crate) fn check_transfer_isolated(
     Ok(())
 }
 
+/// Check that the lock time of the transaction is a block height not less than the lock height
+/// of each of its timelocked inputs, e.g. of the HTLC refunds.
+pub(crate) fn check_input_timelocks(
+    tx: &Transaction,
+    inputs: &[ProofForCheck<&TxIn>],
+) -> Result<(), CheckError> {
+    for ProofForCheck {
+        inner,
+        vout,
+        statement: txin,
+    } in inputs
+    {
+        let Some(lock_height) = inner.input_lock_height(txin) else {
+            continue;
+        };
+
+        let is_unlocked = matches!(
+            tx.lock_time,
+            LockTime::Blocks(height) if height.to_consensus_u32() >= lock_height
+        );
+
+        if !is_unlocked {
+            return Err(CheckError::InputTimelocked {
+                vin: *vout,
+                lock_height,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the amount in each [`BurnAnnouncement`] of the transfer is equal to the sum of the
+/// burn pixels of its chroma in the outputs. Burns without an announcement are still valid, but
+/// they are not counted in the burnt supply of the chroma.
+fn check_burn_announcements(
+    tx: &Transaction,
+    outputs: &[ProofForCheck<&TxOut>],
+) -> Result<(), CheckError> {
+    let mut announced_amounts: HashMap<Chroma, u128> = HashMap::new();
+
+    for output in tx.output.iter() {
+        let Ok(announcement) = BurnAnnouncement::from_script(&output.script_pubkey) else {
+            continue;
+        };
+
+        if announced_amounts
+            .insert(announcement.chroma, announcement.amount)
+            .is_some()
+        {
+            return Err(CheckError::DuplicateBurnAnnouncement(announcement.chroma));
+        }
+    }
+
+    if announced_amounts.is_empty() {
+        return Ok(());
+    }
+
+    let mut burnt_amounts: HashMap<Chroma, u128> = HashMap::new();
+    for proof in outputs.iter().filter(|proof| proof.inner.is_burn()) {
+        let pixel = proof.inner.pixel();
+
+        *burnt_amounts.entry(pixel.chroma).or_insert(0) += pixel.luma.amount;
+    }
+
+    for (chroma, announced_amount) in announced_amounts {
+        let burnt_amount = burnt_amounts.get(&chroma).copied().unwrap_or_default();
+
+        if announced_amount != burnt_amount {
+            return Err(CheckError::AnnouncedAmountDoesNotMatch(
+                announced_amount,
+                burnt_amount,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 fn check_number_of_proofs(bitcoin_tx: &Transaction, proofs: &ProofMap) -> Result<(), CheckError> {
     if bitcoin_tx
         .output
@@ -325,6 +446,15 @@ pub(crate) fn find_owner_in_txinputs<'a>(
     Ok(None)
 }
 
+/// Public keys that signed the P2WPKH inputs.
+pub(crate) fn p2wpkh_signers(inputs: &[TxIn]) -> Vec<PublicKey> {
+    inputs
+        .iter()
+        .filter_map(|input| P2WPKHWitness::from_witness(&input.witness).ok())
+        .map(|witness| witness.pubkey)
+        .collect()
+}
+
 fn handle_p2wpkh_input(ctx: &Secp256k1<All>, witness: &Witness, chroma: &Chroma) -> bool {
     let Ok(witness) = P2WPKHWitness::from_witness(witness) else {
         return false;
@@ -399,6 +529,40 @@ fn proof_map_to_bulletproofs(
         .collect::<Result<Option<Vec<Bulletproof>>, CheckError>>()
 }
 
+/// Check the aggregated range proof of the bulletproof outputs. It's carried by one of them and
+/// covers the commitments of all the outputs that don't have their own range proofs, in the order
+/// of their vouts.
+#[cfg(feature = "bulletproof")]
+fn check_aggregated_range_proof(outputs: &ProofMap) -> Result<(), CheckError> {
+    let mut aggregated_proof = None;
+    let mut commitments = Vec::new();
+
+    for bulletproof in outputs.values().filter_map(PixelProof::get_bulletproof) {
+        match &bulletproof.proof {
+            CommitmentProof::Single(_) => continue,
+            CommitmentProof::Aggregated { aggregated } => {
+                if aggregated_proof.replace(aggregated).is_some() {
+                    return Err(CheckError::InvalidAggregatedRangeProof);
+                }
+            }
+            CommitmentProof::InAggregated => {}
+        }
+
+        commitments.push(bulletproof.commitment);
+    }
+
+    if commitments.is_empty() {
+        return Ok(());
+    }
+
+    let proof = aggregated_proof.ok_or(CheckError::InvalidAggregatedRangeProof)?;
+    if !verify_aggregated_bulletproof(&commitments, proof.clone()) {
+        return Err(CheckError::InvalidAggregatedRangeProof);
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "bulletproof")]
 fn check_bulletproof_conservation_rules(
     inputs_proofs: Vec<yuv_pixels::Bulletproof>,
@@ -1,14 +1,23 @@
 #![doc = include_str!("../README.md")]
 
 mod errors;
-pub use errors::CheckError;
+pub use errors::{ChainError, CheckError};
 
 mod isolated_checks;
-pub use isolated_checks::check_transaction;
+pub use isolated_checks::{check_dust, check_transaction};
+
+mod chain;
+pub use chain::{verify_yuv_transaction_chain, verify_yuv_transaction_with_parents, VerifiedState};
 
 mod service;
 pub use service::TxChecker;
 
+mod revalidation;
+pub use revalidation::{Revalidator, MAX_PENDING_RUNS};
+
+mod freezes;
+pub use freezes::{find_continuation_outputs, partial_freeze, PartialFreeze};
+
 mod announcements;
 mod script_parser;
 
@@ -0,0 +1,260 @@
+//! Re-validation of the attached issuances after the owner of their chroma has changed.
+//!
+//! The announcements that change the owner of the chroma, i.e. the transfer of the ownership and
+//! the chroma announcement with the issuer set, can be applied after the issuances mined after
+//! them, e.g. if they waited longer for the confirmation. Such issuances were checked against the
+//! previous owner, so the [`Revalidator`] re-checks them once the announcement is applied, and
+//! notifies about the ones that aren't signed by the new owner with
+//! [`RpcSubscriptionMessage::InvalidatedTxs`]. The invalidated issuances are left attached, as
+//! their outputs could be already spent.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use bitcoin::Txid;
+use bitcoin_client::BitcoinRpcApi;
+use event_bus::{typeid, EventBus};
+use eyre::{Context, Result};
+use tokio_util::sync::CancellationToken;
+use yuv_pixels::Chroma;
+use yuv_storage::{ChromaInfoStorage, SupplyTreeStorage, TransactionsStorage};
+use yuv_types::announcements::ChromaInfo;
+use yuv_types::messages::OwnerChange;
+use yuv_types::{RevalidatorMessage, RpcSubscriptionMessage, YuvTransaction, YuvTxType};
+
+use crate::isolated_checks::find_owner_in_txinputs;
+
+/// Number of the runs the owner change waits to be applied before it's dropped, e.g. as the
+/// announcement is invalid.
+pub const MAX_PENDING_RUNS: usize = 10;
+
+/// Re-checks the attached issuances of the chromas whose owners were changed by the
+/// announcements found by the indexer.
+pub struct Revalidator<TxsStorage, StateStorage, BitcoinClient> {
+    /// Storage of the attached transactions and the unspent outputs of the chromas.
+    txs_storage: TxsStorage,
+
+    /// Storage of the chromas' owners.
+    state_storage: StateStorage,
+
+    /// Finds the heights of the blocks the issuances are mined in.
+    bitcoin_client: Arc<BitcoinClient>,
+
+    /// Event bus for simplifying communication with services.
+    event_bus: EventBus,
+
+    /// Last owner changes of the chromas waiting to be applied, along with the number of the
+    /// runs they have waited for.
+    pending: HashMap<Chroma, (OwnerChange, usize)>,
+}
+
+impl<TS, SS, BC> Revalidator<TS, SS, BC>
+where
+    TS: TransactionsStorage + SupplyTreeStorage + Send + Sync + 'static,
+    SS: ChromaInfoStorage + Send + Sync + 'static,
+    BC: BitcoinRpcApi + Send + Sync + 'static,
+{
+    pub fn new(
+        full_event_bus: &EventBus,
+        txs_storage: TS,
+        state_storage: SS,
+        bitcoin_client: Arc<BC>,
+    ) -> Self {
+        let event_bus = full_event_bus
+            .extract(
+                &typeid![RpcSubscriptionMessage],
+                &typeid![RevalidatorMessage],
+            )
+            .expect("event channels must be presented");
+
+        Self {
+            txs_storage,
+            state_storage,
+            bitcoin_client,
+            event_bus,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub async fn run(mut self, cancellation: CancellationToken) {
+        let events = self.event_bus.subscribe::<RevalidatorMessage>();
+
+        loop {
+            tokio::select! {
+                event_received = events.recv() => {
+                    let Ok(event) = event_received else {
+                        tracing::trace!("All incoming events senders are dropped");
+                        return;
+                    };
+
+                    // The re-validation is retried on the next run, so the failures don't stop
+                    // the node.
+                    if let Err(err) = self.handle_event(event).await {
+                        tracing::error!("Failed to handle an event: {:?}", err);
+                    }
+                }
+                _ = cancellation.cancelled() => {
+                    tracing::trace!("Cancellation received, stopping Revalidator");
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn handle_event(&mut self, event: RevalidatorMessage) -> Result<()> {
+        match event {
+            RevalidatorMessage::OwnerChanges(changes) => self.add_owner_changes(changes),
+            RevalidatorMessage::Run(completion) => {
+                let result = self.revalidate_applied().await;
+                completion.complete(&result);
+
+                result.wrap_err("failed to revalidate the issuances")?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queues the owner changes. Only the last change of the chroma is kept, as the issuances
+    /// mined after it must be signed by its owner.
+    pub fn add_owner_changes(&mut self, changes: Vec<OwnerChange>) {
+        for change in changes {
+            let is_last = self
+                .pending
+                .get(&change.chroma)
+                .map_or(true, |(pending, _)| change.height >= pending.height);
+
+            if is_last {
+                self.pending.insert(change.chroma, (change, 0));
+            }
+        }
+    }
+
+    /// Re-checks the issuances of the owner changes that are already applied, i.e. the owner of
+    /// the chroma is the one set by the change. The rest of the changes wait for the next run.
+    pub async fn revalidate_applied(&mut self) -> Result<()> {
+        let mut invalidated = Vec::new();
+
+        let chromas: Vec<Chroma> = self.pending.keys().copied().collect();
+        for chroma in chromas {
+            let Some((change, runs)) = self.pending.remove(&chroma) else {
+                continue;
+            };
+
+            let chroma_info = self
+                .state_storage
+                .get_chroma_info(&chroma)
+                .await?
+                .filter(|info| info.owner.as_ref() == Some(&change.owner));
+            let Some(chroma_info) = chroma_info else {
+                if runs + 1 < MAX_PENDING_RUNS {
+                    self.pending.insert(chroma, (change, runs + 1));
+                } else {
+                    tracing::debug!(
+                        chroma = %chroma,
+                        "Owner change at height {} is not applied, dropping it",
+                        change.height,
+                    );
+                }
+
+                continue;
+            };
+
+            invalidated.extend(self.revalidate_issuances(&change, chroma_info).await?);
+        }
+
+        if !invalidated.is_empty() {
+            self.event_bus
+                .send(RpcSubscriptionMessage::InvalidatedTxs(invalidated))
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Re-checks the attached issuances of the chroma whose outputs are unspent. Returns the
+    /// ones that are invalid after the owner change.
+    async fn revalidate_issuances(
+        &self,
+        change: &OwnerChange,
+        chroma_info: ChromaInfo,
+    ) -> Result<Vec<YuvTransaction>> {
+        let txids: BTreeSet<Txid> = self
+            .txs_storage
+            .get_supply_utxos(&change.chroma)
+            .await?
+            .keys()
+            .map(|outpoint| outpoint.txid)
+            .collect();
+
+        let mut invalidated = Vec::new();
+        for txid in txids {
+            let Some(tx) = self.txs_storage.get_yuv_tx(&txid).await? else {
+                continue;
+            };
+            if !matches!(tx.tx_type, YuvTxType::Issue { .. }) {
+                continue;
+            }
+
+            let height = self.block_height(&txid).await?;
+            if is_valid_after_change(&tx, height, change, &chroma_info)? {
+                continue;
+            }
+
+            tracing::warn!(
+                txid = txid.to_string(),
+                chroma = %change.chroma,
+                "Attached issuance is invalid: it's not signed by the owner set at height {}",
+                change.height,
+            );
+
+            invalidated.push(tx);
+        }
+
+        Ok(invalidated)
+    }
+
+    /// Height of the block the transaction is mined in, `None` if it isn't mined yet.
+    async fn block_height(&self, txid: &Txid) -> Result<Option<usize>> {
+        let tx_info = self
+            .bitcoin_client
+            .get_raw_transaction_info(txid, None)
+            .await
+            .wrap_err_with(|| format!("failed to get the transaction {txid}"))?;
+
+        let Some(block_hash) = tx_info.blockhash else {
+            return Ok(None);
+        };
+
+        let header = self
+            .bitcoin_client
+            .get_block_header_info(&block_hash)
+            .await
+            .wrap_err_with(|| format!("failed to get the block header {block_hash}"))?;
+
+        Ok(Some(header.height))
+    }
+}
+
+/// Checks that the issuance mined at the `height` is valid after the owner change, i.e. it's
+/// mined before the change or is signed by the new owner. The issuances mined in the same block
+/// as the announcement are considered valid, as their order in the block isn't known, and the
+/// ones that aren't mined yet have to be signed by the new owner.
+pub(crate) fn is_valid_after_change(
+    tx: &YuvTransaction,
+    height: Option<usize>,
+    change: &OwnerChange,
+    chroma_info: &ChromaInfo,
+) -> Result<bool> {
+    if height.is_some_and(|height| height <= change.height) {
+        return Ok(true);
+    }
+
+    let owner_input = find_owner_in_txinputs(
+        &tx.bitcoin_tx.input,
+        &change.chroma,
+        Some(chroma_info.clone()),
+    )?;
+
+    Ok(owner_input.is_some())
+}
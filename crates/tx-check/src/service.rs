@@ -6,22 +6,31 @@ use event_bus::{typeid, EventBus};
 use eyre::{Context, Result};
 
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use yuv_pixels::{Chroma, PixelProof};
-use yuv_storage::{ChromaInfoStorage, FrozenTxsStorage, InvalidTxsStorage, TransactionsStorage};
+use yuv_storage::{
+    BlockIndexerStorage, ChromaInfoStorage, DelegatesStorage, FrozenPubkeysStorage,
+    FrozenTxsStorage, InvalidTxsStorage, MempoolEntryStorage, RejectionReason, TransactionsStorage,
+    TxRejection,
+};
+use yuv_types::activation::ProtocolFeature;
 use yuv_types::announcements::{
-    ChromaAnnouncement, ChromaInfo, FreezeAnnouncement, IssueAnnouncement,
-    TransferOwnershipAnnouncement,
+    BurnAnnouncement, ChromaAnnouncement, DelegateAnnouncement, DelegateCapabilities,
+    FreezeAnnouncement, FreezePubkeyAnnouncement, IssueAnnouncement, TransferOwnershipAnnouncement,
+    UpdateChromaAnnouncement,
 };
 use yuv_types::messages::p2p::Inventory;
+use yuv_types::network::Network;
 use yuv_types::{
-    Announcement, ControllerMessage, GraphBuilderMessage, ProofMap, TxCheckerMessage,
-    YuvTransaction, YuvTxType,
+    Announcement, ControllerMessage, DustPolicy, GraphBuilderMessage, ProofMap,
+    RpcSubscriptionMessage, TxCheckerMessage, YuvTransaction, YuvTxType,
 };
 
-use crate::check_transaction;
 use crate::errors::CheckError;
-use crate::isolated_checks::find_owner_in_txinputs;
+use crate::freezes::{find_continuation_outputs, owner_pubkeys, partial_freeze, FreezeState};
+use crate::isolated_checks::{find_owner_in_txinputs, p2wpkh_signers};
+use crate::{check_dust, check_transaction};
 
 /// Async implementation of [`TxChecker`] for node implementation.
 ///
@@ -37,17 +46,42 @@ pub struct TxChecker<TxsStorage, StateStorage> {
 
     /// Event bus for simplifying communication with services
     event_bus: EventBus,
+
+    /// Network the node runs on. Determines the activation heights of the protocol features.
+    network: Network,
+
+    /// Minimal amount of satoshis of the pixel outputs, checked before the confirmation.
+    dust_policy: DustPolicy,
 }
 
 impl<TS, SS> TxChecker<TS, SS>
 where
     TS: TransactionsStorage + Clone + Send + Sync + 'static,
-    SS: InvalidTxsStorage + FrozenTxsStorage + ChromaInfoStorage + Clone + Send + Sync + 'static,
+    SS: InvalidTxsStorage
+        + FrozenTxsStorage
+        + FrozenPubkeysStorage
+        + DelegatesStorage
+        + ChromaInfoStorage
+        + BlockIndexerStorage
+        + MempoolEntryStorage
+        + Clone
+        + Send
+        + Sync
+        + 'static,
 {
-    pub fn new(full_event_bus: EventBus, txs_storage: TS, state_storage: SS) -> Self {
+    pub fn new(
+        full_event_bus: EventBus,
+        txs_storage: TS,
+        state_storage: SS,
+        network: Network,
+    ) -> Self {
         let event_bus = full_event_bus
             .extract(
-                &typeid![GraphBuilderMessage, ControllerMessage],
+                &typeid![
+                    GraphBuilderMessage,
+                    ControllerMessage,
+                    RpcSubscriptionMessage
+                ],
                 &typeid![TxCheckerMessage],
             )
             .expect("event channels must be presented");
@@ -56,9 +90,17 @@ where
             event_bus,
             txs_storage,
             state_storage,
+            network,
+            dust_policy: DustPolicy::for_network(network),
         }
     }
 
+    /// Set the policy of the pixel outputs' satoshis, which is the network's one by default.
+    pub fn with_dust_policy(mut self, dust_policy: DustPolicy) -> Self {
+        self.dust_policy = dust_policy;
+        self
+    }
+
     pub async fn run(mut self, cancellation: CancellationToken) {
         let events = self.event_bus.subscribe::<TxCheckerMessage>();
 
@@ -86,7 +128,9 @@ where
         }
     }
 
-    async fn handle_event(&mut self, event: TxCheckerMessage) -> Result<()> {
+    /// Handles the event received from the event bus. Public, so the events can be handled one
+    /// by one, e.g. by the replay of the pipeline.
+    pub async fn handle_event(&mut self, event: TxCheckerMessage) -> Result<()> {
         match event {
             TxCheckerMessage::FullCheck(txs) => self
                 .check_txs_full(txs)
@@ -117,15 +161,19 @@ where
         tracing::debug!("Checking txs full: {:?}", txids);
 
         for (tx, sender) in txs {
-            let is_valid = self
+            let span = tx_span(&tx);
+            let rejection = self
                 .check_transaction(tx.clone(), sender, &mut checked_txs, &mut not_found_parents)
+                .instrument(span.clone())
                 .await?;
 
-            if !is_valid {
-                invalid_txs.push(tx.clone());
+            if let Some(reason) = rejection {
+                span.in_scope(|| tracing::debug!("Tx failed the full check: {}", reason));
+                invalid_txs.push((tx.clone(), reason.into()));
                 continue;
             }
 
+            span.in_scope(|| tracing::debug!("Tx passed the full check"));
             checked_txs.insert(tx.bitcoin_tx.txid(), tx);
         }
 
@@ -162,22 +210,48 @@ where
     /// Partially check the transactions, i.e. perform the isolated check. It informs the controller about the invalid
     /// transactions. It also sends valid [`YuvTxType::Issue`] and [`YuvTxType::Transfer`]
     /// transactions to the tx confirmator.
+    ///
+    /// Valid transfers are also sent to the RPC subscribers without waiting for the confirmation,
+    /// so the receivers can learn about the incoming transfers as soon as possible. The
+    /// notification is dropped if the subscriptions channel is full, so a slow subscriber doesn't
+    /// hold the check back.
     pub async fn check_txs_isolated(&mut self, txs: Vec<YuvTransaction>) -> Result<()> {
         let mut checked_txs = Vec::new();
+        let mut unconfirmed_transfers = Vec::new();
         let mut invalid_txs = Vec::new();
 
         let txids: Vec<Txid> = txs.iter().map(|tx| tx.bitcoin_tx.txid()).collect();
         tracing::debug!("Checking txs isolated: {:?}", txids);
 
         for tx in txs {
-            let is_valid = check_transaction(&tx).is_ok();
+            let _span = tx_span(&tx).entered();
 
-            if !is_valid {
-                invalid_txs.push(tx.clone());
+            let result = check_transaction(&tx).and_then(|_| check_dust(&tx, &self.dust_policy));
+            if let Err(err) = result {
+                tracing::debug!("Tx failed the isolated check: {}", err);
+                let rejection = TxRejection::new(RejectionReason::IsolatedCheck, err.to_string());
+                invalid_txs.push((tx.clone(), rejection));
                 continue;
             }
 
+            tracing::debug!("Tx passed the isolated check");
+
             checked_txs.push(tx.bitcoin_tx.txid());
+
+            if matches!(tx.tx_type, YuvTxType::Transfer { .. }) {
+                unconfirmed_transfers.push(tx);
+            }
+        }
+
+        // Notify the subscribers first, as the confirmation takes at least a block:
+        if !unconfirmed_transfers.is_empty() {
+            let message = RpcSubscriptionMessage::UnconfirmedTransfers(unconfirmed_transfers);
+            if let Err(err) = self.event_bus.send_now(message) {
+                tracing::debug!(
+                    "Unconfirmed transfers are not sent to the subscribers: {}",
+                    err
+                );
+            }
         }
 
         // Send checked transactions for confirmation:
@@ -193,12 +267,20 @@ where
         Ok(())
     }
 
-    async fn handle_invalid_txs(&self, invalid_txs: Vec<YuvTransaction>) -> Result<()> {
+    /// Informs the controller about the invalid transactions, and stores them with the reasons
+    /// of the rejections.
+    async fn handle_invalid_txs(
+        &self,
+        invalid_txs: Vec<(YuvTransaction, TxRejection)>,
+    ) -> Result<()> {
         if invalid_txs.is_empty() {
             return Ok(());
         }
 
-        let invalid_txs_ids = invalid_txs.iter().map(|tx| tx.bitcoin_tx.txid()).collect();
+        let invalid_txs_ids = invalid_txs
+            .iter()
+            .map(|(tx, _)| tx.bitcoin_tx.txid())
+            .collect();
         self.event_bus
             .send(ControllerMessage::InvalidTxs(invalid_txs_ids))
             .await;
@@ -209,27 +291,74 @@ where
     }
 
     /// Do the corresponding checks for the transaction based on its type.
+    ///
+    /// Returns the reason of the rejection, `None` if the transaction is valid.
     async fn check_transaction(
         &mut self,
         tx: YuvTransaction,
         sender: Option<SocketAddr>,
         checked_txs: &mut BTreeMap<Txid, YuvTransaction>,
         not_found_parents: &mut HashMap<SocketAddr, Vec<Txid>>,
-    ) -> Result<bool> {
-        let is_valid = match &tx.tx_type {
-            YuvTxType::Issue { announcement, .. } => self.check_issuance(&tx, announcement).await?,
+    ) -> Result<Option<RejectionReason>> {
+        if !self.check_activation(&tx).await? {
+            return Ok(Some(RejectionReason::FeatureNotActive));
+        }
+
+        let rejection = match &tx.tx_type {
+            YuvTxType::Issue { announcement, .. } => {
+                (!self.check_issuance(&tx, announcement).await?)
+                    .then_some(RejectionReason::InvalidIssuance)
+            }
             YuvTxType::Announcement(announcement) => {
-                self.check_announcements(&tx, announcement).await?
+                (!self.check_announcements(&tx, announcement).await?)
+                    .then_some(RejectionReason::InvalidAnnouncement)
             }
             YuvTxType::Transfer {
                 ref input_proofs, ..
             } => {
-                self.check_transfer(&tx, sender, input_proofs, checked_txs, not_found_parents)
+                let burns = tx.burn_announcements();
+
+                if !self.check_burns(&tx, &burns).await? {
+                    return Ok(Some(RejectionReason::BurnExceedsSupply));
+                }
+
+                if !self
+                    .check_transfer(&tx, sender, input_proofs, checked_txs, not_found_parents)
                     .await?
+                {
+                    return Ok(Some(RejectionReason::FrozenInput));
+                }
+
+                for burn in &burns {
+                    self.update_burned(burn).await?;
+                }
+
+                None
             }
         };
 
-        Ok(is_valid)
+        Ok(rejection)
+    }
+
+    /// Check that all the protocol features the transaction relies on are active at the height
+    /// of the block the transaction is mined in.
+    async fn check_activation(&self, tx: &YuvTransaction) -> Result<bool> {
+        let height = self.tx_block_height(&tx.bitcoin_tx.txid()).await?;
+
+        for feature in ProtocolFeature::required_by(tx) {
+            if !feature.is_active(self.network, height) {
+                tracing::info!(
+                    "Tx {} is invalid: feature {} is not active at height {}",
+                    tx.bitcoin_tx.txid(),
+                    feature,
+                    height,
+                );
+
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
     }
 
     async fn check_issuance(
@@ -254,6 +383,9 @@ where
         checked_txs: &BTreeMap<Txid, YuvTransaction>,
         not_found_parents: &mut HashMap<SocketAddr, Vec<Txid>>,
     ) -> Result<bool> {
+        let mut partial_freezes = Vec::new();
+        let height = self.last_indexed_height().await?;
+
         for (parent_id, proof) in input_proofs {
             let Some(txin) = tx.bitcoin_tx.input.get(*parent_id as usize) else {
                 return Err(CheckError::InputNotFound.into());
@@ -261,14 +393,18 @@ where
 
             let parent = txin.previous_output;
 
-            if self.is_output_frozen(&parent, proof).await? {
-                tracing::info!(
-                    "Transfer tx {} is invalid: output {} is frozen",
-                    tx.bitcoin_tx.txid(),
-                    parent,
-                );
+            match self.output_freeze(&parent, proof, height).await? {
+                FreezeState::NotFrozen => {}
+                FreezeState::Frozen => {
+                    tracing::info!(
+                        "Transfer tx {} is invalid: output {} is frozen",
+                        tx.bitcoin_tx.txid(),
+                        parent,
+                    );
 
-                return Ok(false);
+                    return Ok(false);
+                }
+                FreezeState::PartiallyFrozen(freeze) => partial_freezes.push(freeze),
             }
 
             let is_in_storage = self.txs_storage.get_yuv_tx(&parent.txid).await?.is_some();
@@ -280,26 +416,104 @@ where
             }
         }
 
+        if partial_freezes.is_empty() {
+            return Ok(true);
+        }
+
+        // The continuation outputs are frozen by the controller once the transaction is
+        // attached, so the checked transactions that are never attached don't freeze anything.
+        if find_continuation_outputs(tx, partial_freezes).is_none() {
+            tracing::info!(
+                "Transfer tx {} is invalid: frozen amount of the partially frozen inputs is not kept in the continuation outputs",
+                tx.bitcoin_tx.txid(),
+            );
+
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// Check that the [`BurnAnnouncement`]s of the transfer don't burn more tokens than there are
+    /// in circulation, and the burnt total of the chroma doesn't overflow. The burnt amounts
+    /// themselves are checked against the burn pixels in the isolated checks.
+    async fn check_burns(&self, tx: &YuvTransaction, burns: &[BurnAnnouncement]) -> Result<bool> {
+        for burn in burns {
+            let chroma_info = self
+                .state_storage
+                .get_chroma_info(&burn.chroma)
+                .await?
+                .unwrap_or_default();
+
+            let circulating_supply = chroma_info
+                .total_supply
+                .saturating_sub(chroma_info.total_burned);
+
+            if burn.amount > circulating_supply {
+                tracing::info!(
+                    "Transfer tx {} is invalid: burnt amount {} of chroma {} is higher than the circulating supply {}",
+                    tx.bitcoin_tx.txid(),
+                    burn.amount,
+                    burn.chroma,
+                    circulating_supply,
+                );
+
+                return Ok(false);
+            }
+
+            if chroma_info.total_burned.checked_add(burn.amount).is_none() {
+                tracing::info!(
+                    "Transfer tx {} is invalid: burnt amount of chroma {} overflows",
+                    tx.bitcoin_tx.txid(),
+                    burn.chroma,
+                );
+
+                return Ok(false);
+            }
+        }
+
         Ok(true)
     }
 
-    /// Check if transaction is frozen.
-    async fn is_output_frozen(&self, outpoint: &OutPoint, proof: &PixelProof) -> Result<bool> {
+    /// Get the freeze state of the output at the given block height.
+    async fn output_freeze(
+        &self,
+        outpoint: &OutPoint,
+        proof: &PixelProof,
+        height: usize,
+    ) -> Result<FreezeState> {
         let chroma = &proof.pixel().chroma;
 
         if let Some(chroma_info) = self.state_storage.get_chroma_info(chroma).await? {
             if let Some(announcement) = chroma_info.announcement {
                 if !announcement.is_freezable {
-                    return Ok(false);
+                    return Ok(FreezeState::NotFrozen);
                 }
             }
         }
 
+        for pubkey in owner_pubkeys(proof) {
+            if let Some(freeze_entry) = self
+                .state_storage
+                .get_frozen_pubkey(chroma, &pubkey)
+                .await?
+            {
+                tracing::debug!(
+                    tx = freeze_entry.txid.to_string(),
+                    "Output {} is frozen: its owner {} is frozen",
+                    outpoint,
+                    pubkey,
+                );
+
+                return Ok(FreezeState::Frozen);
+            }
+        }
+
         let freeze_entry = self.state_storage.get_frozen_tx(outpoint).await?;
 
         // Owner hasn't attempted to freeze this output, so it's not frozen:
         let Some(freeze_entry) = freeze_entry else {
-            return Ok(false);
+            return Ok(FreezeState::NotFrozen);
         };
 
         let freeze_txid = freeze_entry.txid;
@@ -311,10 +525,20 @@ where
 
             self.txs_storage.delete_yuv_tx(&freeze_txid).await?;
 
-            return Ok(false);
+            return Ok(FreezeState::NotFrozen);
         }
 
-        Ok(true)
+        // The expired freeze is inactive, no unfreeze is needed:
+        if freeze_entry.is_expired(height) {
+            return Ok(FreezeState::NotFrozen);
+        }
+
+        // Only the outputs with a single owner can be spent partially, and there must be
+        // something left to spend.
+        match partial_freeze(&freeze_entry, proof) {
+            Some(freeze) => Ok(FreezeState::PartiallyFrozen(freeze)),
+            None => Ok(FreezeState::Frozen),
+        }
     }
 
     /// Check that all the [`Announcement`]s in transcation are valid.
@@ -345,6 +569,26 @@ where
                 self.check_transfer_ownership_announcement(tx, announcement)
                     .await
             }
+            Announcement::UpdateChroma(announcement) => {
+                self.check_update_chroma_announcement(tx, announcement)
+                    .await
+            }
+            Announcement::FreezePubkey(announcement) => {
+                self.check_freeze_pubkey_announcement(tx, announcement)
+                    .await
+            }
+            Announcement::Delegate(announcement) => {
+                self.check_delegate_announcement(tx, announcement).await
+            }
+            // Burn announcements are only valid as a part of the transfer that burns the tokens.
+            Announcement::Burn(_) => {
+                tracing::info!(
+                    "Burn announcement tx {} is invalid: burn announcement must be included in a transfer",
+                    tx.bitcoin_tx.txid(),
+                );
+
+                Ok(false)
+            }
         }
     }
 
@@ -353,6 +597,10 @@ where
     /// The chroma announcement is considered valid if:
     /// 1. One of the inputs of the announcement transaction is signed by the issuer of the chroma.
     /// 2. Max supply is bigger than the current total supply.
+    ///
+    /// If the announcement declares the m-of-n issuer set, the P2WSH multisig of the issuers
+    /// becomes the owner of the chroma, so the following announcements have to spend its output
+    /// with the signatures of the threshold of the issuers.
     async fn check_chroma_announcement(
         &self,
         announcement_tx: &YuvTransaction,
@@ -403,7 +651,7 @@ where
     /// can just skip it because it doesn't break the protocol's rules.
     /// 2. The output that is being frozen is an existing YUV output.
     /// 3. One of the inputs of the announcement freeze transaction is signed by the owner of the
-    /// chroma that is being frozen.
+    /// chroma that is being frozen, or by the delegate with the freeze capability.
     /// 4. The freezes are allowed by the Chroma announcement.
     async fn check_freeze_announcement(
         &self,
@@ -428,20 +676,107 @@ where
         }
 
         // Check signer of the freeze tx is issuer of the chroma which frozen tx has.
-        let owner_input = self
-            .find_owner_in_txinputs(&announcement_tx.bitcoin_tx.input, &chroma)
+        let is_authorized = self
+            .is_authorized_in_txinputs(
+                &announcement_tx.bitcoin_tx.input,
+                &chroma,
+                DelegateCapabilities::FREEZE,
+            )
             .await?;
-        if owner_input.is_none() {
+        if !is_authorized {
             tracing::info!(
                 tx = freeze_txid.to_string(),
-                "Freeze tx is invalid: none of the inputs has owner, removing it",
+                "Freeze tx is invalid: none of the inputs has owner or delegate, removing it",
+            );
+
+            return Ok(false);
+        }
+
+        let height = self.last_indexed_height().await?;
+        self.update_freezes(announcement_tx.bitcoin_tx.txid(), announcement, height)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Check that [FreezePubkeyAnnouncement] is valid.
+    ///
+    /// The freeze pubkey announcement is considered valid if:
+    /// 1. One of the inputs of the announcement transaction is signed by the owner of the chroma,
+    /// or by the delegate with the freeze or unfreeze capability, depending on the action.
+    /// 2. The freezes are allowed by the Chroma announcement.
+    ///
+    /// Freezing the already frozen key or unfreezing the key that isn't frozen doesn't change
+    /// anything, but it doesn't break the protocol's rules either.
+    async fn check_freeze_pubkey_announcement(
+        &self,
+        announcement_tx: &YuvTransaction,
+        announcement: &FreezePubkeyAnnouncement,
+    ) -> Result<bool> {
+        let txid = announcement_tx.bitcoin_tx.txid();
+        let chroma = announcement.chroma;
+
+        if let Some(chroma_info) = self.state_storage.get_chroma_info(&chroma).await? {
+            if let Some(chroma_announcement) = chroma_info.announcement {
+                if !chroma_announcement.is_freezable {
+                    tracing::info!(
+                        "Freeze pubkey tx {} is invalid: chroma {} doesn't allow freezes, removing it",
+                        txid,
+                        chroma,
+                    );
+
+                    return Ok(false);
+                }
+            }
+        }
+
+        let capability = if announcement.unfreeze {
+            DelegateCapabilities::UNFREEZE
+        } else {
+            DelegateCapabilities::FREEZE
+        };
+        let is_authorized = self
+            .is_authorized_in_txinputs(&announcement_tx.bitcoin_tx.input, &chroma, capability)
+            .await?;
+        if !is_authorized {
+            tracing::info!(
+                tx = txid.to_string(),
+                "Freeze pubkey tx is invalid: none of the inputs has owner or delegate, removing it",
             );
 
             return Ok(false);
         }
 
-        self.update_freezes(announcement_tx.bitcoin_tx.txid(), announcement)
+        self.update_pubkey_freezes(txid, announcement).await?;
+
+        Ok(true)
+    }
+
+    /// Check that [DelegateAnnouncement] is valid.
+    ///
+    /// The delegate announcement is considered valid if one of the inputs of the announcement
+    /// transaction is signed by the owner of the chroma. The delegates can't delegate their
+    /// capabilities further.
+    async fn check_delegate_announcement(
+        &self,
+        announcement_tx: &YuvTransaction,
+        announcement: &DelegateAnnouncement,
+    ) -> Result<bool> {
+        let txid = announcement_tx.bitcoin_tx.txid();
+
+        let owner_input = self
+            .find_owner_in_txinputs(&announcement_tx.bitcoin_tx.input, &announcement.chroma)
             .await?;
+        if owner_input.is_none() {
+            tracing::info!(
+                tx = txid.to_string(),
+                "Delegate tx is invalid: none of the inputs has owner, removing it",
+            );
+
+            return Ok(false);
+        }
+
+        self.update_delegates(txid, announcement).await?;
 
         Ok(true)
     }
@@ -493,21 +828,16 @@ where
         }
 
         let chroma_info_opt = self.state_storage.get_chroma_info(chroma).await?;
-        if let Some(ChromaInfo {
-            announcement: Some(ChromaAnnouncement { max_supply, .. }),
-            total_supply,
-            ..
-        }) = chroma_info_opt
-        {
-            let new_total_supply = total_supply + issue_amount;
-
-            if max_supply != 0 && max_supply < new_total_supply {
+        let remaining_supply = chroma_info_opt
+            .as_ref()
+            .and_then(|chroma_info| chroma_info.remaining_supply());
+        if let Some(remaining_supply) = remaining_supply {
+            if issue_amount > remaining_supply {
                 tracing::info!(
-                    "Issue announcement tx {} is invalid: current supply {} + announcement amount {} is higher than the max supply {}",
+                    "Issue announcement tx {} is invalid: amount {} exceeds remaining supply {}",
                     announcement_tx.txid(),
-                    total_supply,
                     issue_amount,
-                    max_supply,
+                    remaining_supply,
                 );
 
                 return Ok(false);
@@ -550,6 +880,76 @@ where
         Ok(true)
     }
 
+    /// Check that [UpdateChromaAnnouncement] is valid.
+    ///
+    /// The update chroma announcement is considered valid if:
+    /// 1. One of the inputs of the announcement transaction is signed by the current owner of the
+    /// chroma.
+    /// 2. The chroma is already announced with the [ChromaAnnouncement].
+    async fn check_update_chroma_announcement(
+        &self,
+        announcement_yuv_tx: &YuvTransaction,
+        announcement: &UpdateChromaAnnouncement,
+    ) -> Result<bool> {
+        let announcement_tx = &announcement_yuv_tx.bitcoin_tx;
+        let chroma = &announcement.chroma;
+
+        let owner_input = self
+            .find_owner_in_txinputs(&announcement_tx.input, chroma)
+            .await?;
+        if owner_input.is_none() {
+            tracing::debug!(
+                tx = announcement_tx.txid().to_string(),
+                "Update chroma announcement tx is invalid: none of the inputs has owner, removing it",
+            );
+
+            return Ok(false);
+        }
+
+        let is_announced = self
+            .state_storage
+            .get_chroma_info(chroma)
+            .await?
+            .is_some_and(|chroma_info| chroma_info.announcement.is_some());
+        if !is_announced {
+            tracing::info!(
+                "Update chroma announcement tx {} is invalid: chroma {} is not announced",
+                announcement_tx.txid(),
+                chroma,
+            );
+
+            return Ok(false);
+        }
+
+        self.update_chroma_metadata(announcement).await?;
+
+        Ok(true)
+    }
+
+    /// Height of the block the transaction is mined in. The transactions that aren't mined yet
+    /// can be mined in the next block at the earliest.
+    async fn tx_block_height(&self, txid: &Txid) -> Result<usize> {
+        let block_height = self
+            .state_storage
+            .get_mempool_entry(txid)
+            .await?
+            .and_then(|entry| entry.block_height);
+
+        match block_height {
+            Some(block_height) => Ok(block_height),
+            None => Ok(self.last_indexed_height().await? + 1),
+        }
+    }
+
+    /// Height of the last indexed block.
+    async fn last_indexed_height(&self) -> Result<usize> {
+        Ok(self
+            .state_storage
+            .get_last_indexed_height()
+            .await?
+            .unwrap_or_default())
+    }
+
     /// Find owner of the `Chroma` in the inputs.
     async fn find_owner_in_txinputs<'a>(
         &self,
@@ -560,4 +960,45 @@ where
 
         find_owner_in_txinputs(inputs, chroma, chroma_info)
     }
+
+    /// Check that one of the inputs is signed by the owner of the chroma, or by the delegate the
+    /// `capability` of the chroma is granted to.
+    async fn is_authorized_in_txinputs(
+        &self,
+        inputs: &[TxIn],
+        chroma: &Chroma,
+        capability: DelegateCapabilities,
+    ) -> eyre::Result<bool> {
+        if self.find_owner_in_txinputs(inputs, chroma).await?.is_some() {
+            return Ok(true);
+        }
+
+        for signer in p2wpkh_signers(inputs) {
+            let Some(entry) = self.state_storage.get_delegate(chroma, &signer).await? else {
+                continue;
+            };
+
+            if entry.capabilities.contains(capability) {
+                tracing::debug!(
+                    chroma = %chroma,
+                    delegate = %signer,
+                    "Input is signed by the delegate granted in tx {}",
+                    entry.txid,
+                );
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// Span of the transaction's check, so its logs are correlated with the rest of the pipeline.
+fn tx_span(tx: &YuvTransaction) -> tracing::Span {
+    tracing::info_span!(
+        "tx",
+        txid = tx.bitcoin_tx.txid().to_string(),
+        chroma = tx.chroma().map(|chroma| chroma.to_string()),
+    )
 }
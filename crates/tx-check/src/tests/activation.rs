@@ -0,0 +1,96 @@
+use bitcoin::TxOut;
+use yuv_storage::{
+    BlockIndexerStorage, InvalidTxsStorage, MempoolEntryStorage, MempoolStatus, MempoolTxEntry,
+    RejectionReason,
+};
+use yuv_types::activation::ProtocolFeature;
+use yuv_types::announcements::BurnAnnouncement;
+use yuv_types::AnyAnnouncement;
+
+use super::*;
+
+/// Transfer that relies on the [`ProtocolFeature::BurnAnnouncement`].
+fn transfer_with_burn_announcement() -> YuvTransaction {
+    let mut tx = VALID_SINGLECHROMA_TRANSFER.clone();
+    let chroma = tx.tx_type.output_proofs().unwrap()[&0].pixel().chroma;
+
+    tx.bitcoin_tx.output.push(TxOut {
+        value: 0,
+        script_pubkey: BurnAnnouncement::new(chroma, 0).to_script(),
+    });
+
+    tx
+}
+
+/// Fully checks the transfer on the mainnet with the last indexed block at the `indexed_height`,
+/// and returns the message sent to the controller along with the storage. The transfer is mined
+/// at the `block_height`, or isn't mined yet if it's `None`.
+async fn check_transfer_at(
+    indexed_height: usize,
+    block_height: Option<usize>,
+) -> (ControllerMessage, LevelDB) {
+    let (mut checker, storage, event_bus) = test_checker(Network::Bitcoin);
+
+    let tx = transfer_with_burn_announcement();
+    storage
+        .put_last_indexed_height(indexed_height)
+        .await
+        .unwrap();
+
+    let mut entry = MempoolTxEntry::new(tx.clone(), MempoolStatus::Mined, None);
+    entry.block_height = block_height;
+    storage.put_mempool_entry(entry).await.unwrap();
+
+    let events = event_bus.subscribe::<ControllerMessage>();
+    checker.check_txs_full(vec![(tx, None)]).await.unwrap();
+
+    (events.recv().await.unwrap(), storage)
+}
+
+fn activation_height() -> usize {
+    ProtocolFeature::BurnAnnouncement.activation_height(Network::Bitcoin)
+}
+
+#[tokio::test]
+async fn test_feature_is_checked_at_the_block_of_the_tx() {
+    // The indexer lags behind the block the transfer is mined in.
+    let (message, _storage) =
+        check_transfer_at(activation_height() - 10, Some(activation_height())).await;
+
+    assert!(
+        matches!(message, ControllerMessage::FullyCheckedTxs(_)),
+        "feature is active at the block of the transfer"
+    );
+}
+
+#[tokio::test]
+async fn test_feature_is_not_active_before_its_block() {
+    let (message, storage) =
+        check_transfer_at(activation_height(), Some(activation_height() - 1)).await;
+
+    assert!(
+        matches!(message, ControllerMessage::InvalidTxs(_)),
+        "feature isn't active at the block of the transfer"
+    );
+
+    let txid = transfer_with_burn_announcement().bitcoin_tx.txid();
+    let rejection = storage.get_tx_rejection(txid).await.unwrap().unwrap();
+    assert_eq!(rejection.reason, RejectionReason::FeatureNotActive);
+}
+
+#[tokio::test]
+async fn test_unmined_tx_is_checked_at_the_next_block() {
+    let (message, _storage) = check_transfer_at(activation_height() - 1, None).await;
+
+    assert!(
+        matches!(message, ControllerMessage::FullyCheckedTxs(_)),
+        "feature is active at the next block"
+    );
+
+    let (message, _storage) = check_transfer_at(activation_height() - 2, None).await;
+
+    assert!(
+        matches!(message, ControllerMessage::InvalidTxs(_)),
+        "feature isn't active at the next block"
+    );
+}
@@ -0,0 +1,141 @@
+use bitcoin::TxOut;
+use yuv_pixels::{Pixel, PixelKey, PixelProof, ZERO_PUBLIC_KEY};
+use yuv_storage::ChromaInfoStorage;
+use yuv_types::{announcements::BurnAnnouncement, AnyAnnouncement, YuvTxType};
+
+use super::*;
+use crate::errors::CheckError;
+
+fn with_burn_announcements(amounts: &[u128]) -> YuvTransaction {
+    let mut tx = VALID_SINGLECHROMA_TRANSFER.clone();
+    let chroma = tx.tx_type.output_proofs().unwrap()[&0].pixel().chroma;
+
+    for amount in amounts {
+        tx.bitcoin_tx.output.push(TxOut {
+            value: 0,
+            script_pubkey: BurnAnnouncement::new(chroma, *amount).to_script(),
+        });
+    }
+
+    tx
+}
+
+#[test]
+fn test_burn_announcement_matches_burn_pixels() {
+    let tx = with_burn_announcements(&[0]);
+
+    assert!(check_transaction(&tx).is_ok(), "nothing is burnt");
+}
+
+#[test]
+fn test_burn_announcement_without_burn_pixels_is_rejected() {
+    let tx = with_burn_announcements(&[100]);
+
+    assert!(matches!(
+        check_transaction(&tx),
+        Err(CheckError::AnnouncedAmountDoesNotMatch(100, 0))
+    ));
+}
+
+#[test]
+fn test_duplicate_burn_announcements_are_rejected() {
+    let tx = with_burn_announcements(&[0, 0]);
+
+    assert!(matches!(
+        check_transaction(&tx),
+        Err(CheckError::DuplicateBurnAnnouncement(_))
+    ));
+}
+
+/// Transfer that burns the pixel of its first output and announces the burn. Returns the
+/// transfer along with the burnt pixel.
+fn burning_transfer() -> (YuvTransaction, Pixel) {
+    let mut tx = VALID_SINGLECHROMA_TRANSFER.clone();
+    let YuvTxType::Transfer { output_proofs, .. } = &mut tx.tx_type else {
+        panic!("expected the tx to be a transfer");
+    };
+
+    let pixel = output_proofs[&0].pixel();
+    output_proofs.insert(0, PixelProof::sig(pixel, ZERO_PUBLIC_KEY.inner));
+
+    tx.bitcoin_tx.output[0].script_pubkey = PixelKey::new(pixel, &ZERO_PUBLIC_KEY.inner)
+        .unwrap()
+        .to_p2wpkh()
+        .unwrap();
+    tx.bitcoin_tx.output.push(TxOut {
+        value: 0,
+        script_pubkey: BurnAnnouncement::new(pixel.chroma, pixel.luma.amount).to_script(),
+    });
+
+    (tx, pixel)
+}
+
+/// Fully checks the burning transfer of the chroma with the `total_supply` of which the
+/// `total_burned` is already burnt, and returns the message sent to the controller along with
+/// the burnt total of the chroma after the check.
+async fn check_burning_transfer(
+    total_supply: u128,
+    total_burned: u128,
+) -> (ControllerMessage, u128) {
+    let (mut checker, storage, event_bus) = test_checker(Network::Regtest);
+
+    let (tx, pixel) = burning_transfer();
+    storage
+        .put_chroma_info(&pixel.chroma, None, total_supply, total_burned, 0, None)
+        .await
+        .unwrap();
+
+    let events = event_bus.subscribe::<ControllerMessage>();
+    checker.check_txs_full(vec![(tx, None)]).await.unwrap();
+
+    let chroma_info = storage
+        .get_chroma_info(&pixel.chroma)
+        .await
+        .unwrap()
+        .unwrap();
+
+    (events.recv().await.unwrap(), chroma_info.total_burned)
+}
+
+#[test]
+fn test_burn_of_pixels_is_announced() {
+    let (tx, pixel) = burning_transfer();
+
+    assert!(
+        pixel.luma.amount > 0,
+        "the pixel should have tokens to burn"
+    );
+    assert!(check_transaction(&tx).is_ok(), "burnt amount is announced");
+}
+
+#[tokio::test]
+async fn test_checker_accepts_burn_and_updates_burnt_total() {
+    let (_, pixel) = burning_transfer();
+    let total_supply = pixel.luma.amount * 10;
+
+    let (message, total_burned) = check_burning_transfer(total_supply, 100).await;
+
+    assert!(
+        matches!(message, ControllerMessage::FullyCheckedTxs(_)),
+        "burnt amount is in circulation"
+    );
+    assert_eq!(total_burned, 100 + pixel.luma.amount);
+}
+
+#[tokio::test]
+async fn test_checker_rejects_burn_above_circulating_supply() {
+    let (_, pixel) = burning_transfer();
+    let total_supply = pixel.luma.amount * 10;
+    let total_burned = total_supply - pixel.luma.amount + 1;
+
+    let (message, stored_burned) = check_burning_transfer(total_supply, total_burned).await;
+
+    assert!(
+        matches!(message, ControllerMessage::InvalidTxs(_)),
+        "burnt amount is higher than the circulating supply"
+    );
+    assert_eq!(
+        stored_burned, total_burned,
+        "rejected burn should not be counted"
+    );
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use bitcoin::{absolute::LockTime, Transaction, TxOut};
+use yuv_pixels::{Pixel, PixelKey, PixelProof};
+use yuv_types::{announcements::IssueAnnouncement, AnyAnnouncement, YuvTxType};
+
+use super::*;
+use crate::{
+    errors::ChainError, verify_yuv_transaction_chain, verify_yuv_transaction_with_parents,
+};
+
+const AMOUNT: u128 = 100;
+
+fn pixel_output(pixel: Pixel, owner: &PublicKey) -> TxOut {
+    TxOut {
+        value: 1000,
+        script_pubkey: PixelKey::new(pixel, owner).unwrap().to_p2wpkh().unwrap(),
+    }
+}
+
+/// Issuance of the tokens to the owner of the key `2`.
+fn issuance() -> YuvTransaction {
+    let pixel = Pixel::new(AMOUNT, chroma());
+    let announcement = IssueAnnouncement::new(chroma(), AMOUNT);
+
+    let bitcoin_tx = Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![p2wpkh_input(OutPoint::default(), pubkey(1))],
+        output: vec![
+            pixel_output(pixel, &pubkey(2)),
+            TxOut {
+                value: 0,
+                script_pubkey: announcement.to_script(),
+            },
+        ],
+    };
+
+    YuvTransaction::new(
+        bitcoin_tx,
+        YuvTxType::Issue {
+            output_proofs: Some(BTreeMap::from([(0, PixelProof::sig(pixel, pubkey(2)))])),
+            announcement,
+        },
+    )
+}
+
+/// Transfer of the tokens from the owner of the key `2` to the one of the key `3`.
+fn transfer(parent: OutPoint, pixel: Pixel) -> YuvTransaction {
+    let pixel_key = PixelKey::new(pixel, &pubkey(2)).unwrap();
+
+    let bitcoin_tx = Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![p2wpkh_input(parent, *pixel_key)],
+        output: vec![pixel_output(pixel, &pubkey(3))],
+    };
+
+    YuvTransaction::new(
+        bitcoin_tx,
+        YuvTxType::Transfer {
+            input_proofs: BTreeMap::from([(0, PixelProof::sig(pixel, pubkey(2)))]),
+            output_proofs: BTreeMap::from([(0, PixelProof::sig(pixel, pubkey(3)))]),
+        },
+    )
+}
+
+#[test]
+fn test_verify_transaction_chain() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    let transfer = transfer(parent, Pixel::new(AMOUNT, chroma()));
+
+    // The order of the transactions doesn't matter.
+    let state = verify_yuv_transaction_chain(&[transfer.clone(), issuance]).unwrap();
+
+    let outpoint = OutPoint::new(transfer.bitcoin_tx.txid(), 0);
+    assert_eq!(state.unspent.len(), 1);
+    assert_eq!(
+        state.get(&outpoint),
+        Some(&PixelProof::sig(Pixel::new(AMOUNT, chroma()), pubkey(3)))
+    );
+    assert_eq!(state.balance(&chroma()), AMOUNT);
+}
+
+#[test]
+fn test_verify_transaction_chain_without_parent() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    let transfer = transfer(parent, Pixel::new(AMOUNT, chroma()));
+
+    assert!(matches!(
+        verify_yuv_transaction_chain(&[transfer]),
+        Err(ChainError::MissingParent { parent: missing, .. }) if missing == parent
+    ));
+}
+
+#[test]
+fn test_verify_transaction_chain_with_other_parent_proof() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    // The transfer spends more tokens than the issuance has in the output.
+    let transfer = transfer(parent, Pixel::new(AMOUNT * 2, chroma()));
+
+    assert!(matches!(
+        verify_yuv_transaction_chain(&[issuance, transfer]),
+        Err(ChainError::ParentProofMismatch { input: 0, .. })
+    ));
+}
+
+#[test]
+fn test_verify_transaction_chain_with_double_spend() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    let transfer = transfer(parent, Pixel::new(AMOUNT, chroma()));
+
+    // The other transfer spends the same output to the other output.
+    let mut other_transfer = transfer.clone();
+    other_transfer.bitcoin_tx.output[0].value += 1;
+
+    assert!(matches!(
+        verify_yuv_transaction_chain(&[issuance, transfer, other_transfer]),
+        Err(ChainError::DoubleSpend(spent)) if spent == parent
+    ));
+}
+
+#[test]
+fn test_verify_transaction_with_parents() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    let transfer = transfer(parent, Pixel::new(AMOUNT, chroma()));
+
+    verify_yuv_transaction_with_parents(&transfer, &[issuance]).unwrap();
+}
+
+#[test]
+fn test_verify_transaction_with_parents_without_parent() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    let transfer = transfer(parent, Pixel::new(AMOUNT, chroma()));
+
+    assert!(matches!(
+        verify_yuv_transaction_with_parents(&transfer, &[]),
+        Err(ChainError::MissingParent { parent: missing, .. }) if missing == parent
+    ));
+}
+
+#[test]
+fn test_verify_transaction_with_parents_with_other_parent_proof() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    let transfer = transfer(parent, Pixel::new(AMOUNT * 2, chroma()));
+
+    assert!(matches!(
+        verify_yuv_transaction_with_parents(&transfer, &[issuance]),
+        Err(ChainError::ParentProofMismatch { input: 0, .. })
+    ));
+}
+
+#[test]
+fn test_verify_transaction_with_unspent_parent() {
+    let issuance = issuance();
+    let parent = OutPoint::new(issuance.bitcoin_tx.txid(), 0);
+    let transfer = transfer(parent, Pixel::new(AMOUNT, chroma()));
+
+    // The other issuance isn't spent by the transfer, so it only pads the bundle.
+    let mut other_issuance = issuance.clone();
+    other_issuance.bitcoin_tx.output[0].value += 1;
+    let other_txid = other_issuance.bitcoin_tx.txid();
+
+    assert!(matches!(
+        verify_yuv_transaction_with_parents(&transfer, &[issuance, other_issuance]),
+        Err(ChainError::UnspentParent(txid)) if txid == other_txid
+    ));
+}
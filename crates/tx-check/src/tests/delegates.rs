@@ -0,0 +1,100 @@
+use bitcoin::{absolute::LockTime, hashes::Hash, Transaction, TxOut, Txid};
+use yuv_storage::{DelegatesStorage, FrozenTxsStorage};
+use yuv_types::{
+    announcements::{DelegateCapabilities, FreezeAnnouncement},
+    Announcement, YuvTxType,
+};
+
+use super::*;
+use crate::isolated_checks::p2wpkh_signers;
+
+#[test]
+fn test_p2wpkh_signers() {
+    // The input with the non-P2WPKH witness doesn't have a signer.
+    let other_input = TxIn {
+        witness: Witness::from_slice(&[vec![1; 64]]),
+        ..Default::default()
+    };
+    let inputs = [
+        p2wpkh_input(OutPoint::default(), pubkey(2)),
+        other_input,
+        p2wpkh_input(OutPoint::default(), pubkey(3)),
+    ];
+
+    assert_eq!(p2wpkh_signers(&inputs), vec![pubkey(2), pubkey(3)]);
+}
+
+fn frozen_outpoint() -> OutPoint {
+    OutPoint::new(Txid::all_zeros(), 0)
+}
+
+/// Announcement tx freezing the [`frozen_outpoint`], signed by the `signer`.
+fn freeze_tx(signer: PublicKey) -> YuvTransaction {
+    let announcement = Announcement::Freeze(FreezeAnnouncement::new(chroma(), frozen_outpoint()));
+
+    let bitcoin_tx = Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![p2wpkh_input(OutPoint::default(), signer)],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: announcement.to_script(),
+        }],
+    };
+
+    YuvTransaction::new(bitcoin_tx, YuvTxType::Announcement(announcement))
+}
+
+/// Fully checks the freeze signed by the delegate the `capabilities` of the chroma are granted
+/// to, and returns the message sent to the controller along with the storage.
+async fn check_delegated_freeze(
+    capabilities: DelegateCapabilities,
+) -> (ControllerMessage, LevelDB) {
+    let (mut checker, storage, event_bus) = test_checker(Network::Regtest);
+
+    let delegate = pubkey(2);
+    storage
+        .put_delegate(&chroma(), &delegate, capabilities, Txid::all_zeros())
+        .await
+        .unwrap();
+
+    let events = event_bus.subscribe::<ControllerMessage>();
+    checker
+        .check_txs_full(vec![(freeze_tx(delegate), None)])
+        .await
+        .unwrap();
+
+    (events.recv().await.unwrap(), storage)
+}
+
+#[tokio::test]
+async fn test_freeze_signed_by_delegate_is_accepted() {
+    let (message, storage) = check_delegated_freeze(DelegateCapabilities::FREEZE).await;
+
+    assert!(
+        matches!(message, ControllerMessage::FullyCheckedTxs(_)),
+        "freeze is signed by the delegate with the freeze capability"
+    );
+
+    let entry = storage
+        .get_frozen_tx(&frozen_outpoint())
+        .await
+        .unwrap()
+        .expect("outpoint should be frozen");
+    assert_eq!(entry.txid, freeze_tx(pubkey(2)).bitcoin_tx.txid());
+}
+
+#[tokio::test]
+async fn test_freeze_signed_by_delegate_without_capability_is_rejected() {
+    let (message, storage) = check_delegated_freeze(DelegateCapabilities::UNFREEZE).await;
+
+    assert!(
+        matches!(message, ControllerMessage::InvalidTxs(_)),
+        "delegate has only the unfreeze capability"
+    );
+    assert!(storage
+        .get_frozen_tx(&frozen_outpoint())
+        .await
+        .unwrap()
+        .is_none());
+}
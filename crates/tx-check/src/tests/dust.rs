@@ -0,0 +1,31 @@
+use yuv_types::{network::Network, DustPolicy};
+
+use super::VALID_SINGLECHROMA_TRANSFER;
+use crate::{check_dust, errors::CheckError};
+
+#[test]
+fn test_outputs_above_dust_limit_pass() {
+    let policy = DustPolicy::for_network(Network::Bitcoin);
+
+    assert!(check_dust(&VALID_SINGLECHROMA_TRANSFER, &policy).is_ok());
+}
+
+#[test]
+fn test_dust_pixel_output_is_rejected() {
+    let mut tx = VALID_SINGLECHROMA_TRANSFER.clone();
+    tx.bitcoin_tx.output[0].value = 1;
+
+    let policy = DustPolicy {
+        min_satoshis: 2,
+        default_satoshis: 1000,
+    };
+
+    assert!(matches!(
+        check_dust(&tx, &policy),
+        Err(CheckError::DustOutput {
+            vout: 0,
+            satoshis: 1,
+            min_satoshis: 2,
+        })
+    ));
+}
@@ -0,0 +1,134 @@
+use bitcoin::{hashes::Hash, Txid};
+use yuv_pixels::PixelProof;
+use yuv_storage::FrozenTxsStorage;
+
+use super::*;
+use crate::freezes::{find_continuation_outputs, owner_pubkeys, PartialFreeze};
+
+fn input_freeze(amount: u128) -> PartialFreeze {
+    let proofs = VALID_SINGLECHROMA_TRANSFER.tx_type.input_proofs().unwrap();
+    let PixelProof::Sig(proof) = proofs.get(&0).unwrap() else {
+        panic!("expected the input to have a sig proof");
+    };
+
+    PartialFreeze {
+        freeze_txid: Txid::all_zeros(),
+        chroma: proof.pixel.chroma,
+        owner: proof.inner_key,
+        amount,
+        expiry_height: None,
+    }
+}
+
+#[test]
+fn test_continuation_output_keeps_frozen_amount() {
+    let continuations =
+        find_continuation_outputs(&VALID_SINGLECHROMA_TRANSFER, vec![input_freeze(9000)])
+            .expect("change output should keep the frozen amount");
+
+    assert_eq!(continuations, vec![(1, input_freeze(9000))]);
+}
+
+#[test]
+fn test_frozen_amounts_of_same_owner_are_merged() {
+    let continuations = find_continuation_outputs(
+        &VALID_SINGLECHROMA_TRANSFER,
+        vec![input_freeze(5000), input_freeze(4000)],
+    )
+    .expect("change output should keep the merged frozen amount");
+
+    assert_eq!(continuations, vec![(1, input_freeze(9000))]);
+}
+
+#[test]
+fn test_merged_freezes_expire_with_the_latest_one() {
+    let expirable_freeze = |amount, expiry_height| PartialFreeze {
+        expiry_height: Some(expiry_height),
+        ..input_freeze(amount)
+    };
+
+    let continuations = find_continuation_outputs(
+        &VALID_SINGLECHROMA_TRANSFER,
+        vec![expirable_freeze(5000, 100), expirable_freeze(4000, 200)],
+    )
+    .expect("change output should keep the frozen amount");
+
+    assert_eq!(continuations, vec![(1, expirable_freeze(9000, 200))]);
+
+    let continuations = find_continuation_outputs(
+        &VALID_SINGLECHROMA_TRANSFER,
+        vec![expirable_freeze(5000, 100), input_freeze(4000)],
+    )
+    .expect("change output should keep the frozen amount");
+
+    assert_eq!(continuations, vec![(1, input_freeze(9000))]);
+}
+
+#[test]
+fn test_spending_frozen_amount_is_rejected() {
+    let continuations =
+        find_continuation_outputs(&VALID_SINGLECHROMA_TRANSFER, vec![input_freeze(9500)]);
+
+    assert!(continuations.is_none(), "frozen amount is spent");
+}
+
+#[test]
+fn test_owner_pubkeys_of_sig_proof() {
+    let proofs = VALID_SINGLECHROMA_TRANSFER.tx_type.input_proofs().unwrap();
+    let proof = proofs.get(&0).unwrap();
+
+    assert_eq!(owner_pubkeys(proof), vec![input_freeze(0).owner]);
+}
+
+/// Fully checks the transfer whose input is frozen for the `frozen_amount`, and returns the
+/// message sent to the controller along with the storage.
+async fn check_partially_frozen_transfer(frozen_amount: u128) -> (ControllerMessage, LevelDB) {
+    let (mut checker, storage, event_bus) = test_checker(Network::Regtest);
+
+    let tx = VALID_SINGLECHROMA_TRANSFER.clone();
+    let input = tx.bitcoin_tx.input[0].previous_output;
+    let freeze = input_freeze(frozen_amount);
+    storage
+        .put_frozen_tx(
+            &input,
+            freeze.freeze_txid,
+            freeze.chroma,
+            Some(freeze.amount),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let events = event_bus.subscribe::<ControllerMessage>();
+    checker.check_txs_full(vec![(tx, None)]).await.unwrap();
+
+    (events.recv().await.unwrap(), storage)
+}
+
+#[tokio::test]
+async fn test_checker_accepts_kept_frozen_amount_without_freezing() {
+    let (message, storage) = check_partially_frozen_transfer(9000).await;
+
+    assert!(
+        matches!(message, ControllerMessage::FullyCheckedTxs(_)),
+        "transfer keeps the frozen amount"
+    );
+
+    // The continuation output is frozen by the controller once the transfer is attached.
+    let continuation = OutPoint::new(VALID_SINGLECHROMA_TRANSFER.bitcoin_tx.txid(), 1);
+    assert!(storage
+        .get_frozen_tx(&continuation)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_checker_rejects_spent_frozen_amount() {
+    let (message, _storage) = check_partially_frozen_transfer(9500).await;
+
+    assert!(
+        matches!(message, ControllerMessage::InvalidTxs(_)),
+        "transfer spends the frozen amount"
+    );
+}
@@ -0,0 +1,32 @@
+use yuv_types::announcements::{ChromaInfo, ChromaIssuers};
+
+use super::*;
+use crate::isolated_checks::find_owner_in_txinputs;
+
+/// Input spending the P2WSH output with the `redeem_script` and two dummy signatures.
+fn p2wsh_input(redeem_script: &[u8]) -> TxIn {
+    TxIn {
+        witness: Witness::from_slice(&[vec![], vec![1; 71], vec![2; 71], redeem_script.to_vec()]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_issuers_multisig_is_owner() {
+    let issuers = ChromaIssuers::new(2, vec![pubkey(1), pubkey(2), pubkey(3)]).unwrap();
+    let chroma = chroma();
+    let chroma_info = ChromaInfo {
+        owner: Some(issuers.owner_script()),
+        ..Default::default()
+    };
+
+    let inputs = [p2wsh_input(issuers.redeem_script().as_bytes())];
+    let owner_input = find_owner_in_txinputs(&inputs, &chroma, Some(chroma_info.clone()));
+    assert!(owner_input.unwrap().is_some());
+
+    // The multisig of the other issuer set doesn't own the chroma.
+    let other_issuers = ChromaIssuers::new(1, vec![pubkey(1), pubkey(2), pubkey(3)]).unwrap();
+    let inputs = [p2wsh_input(other_issuers.redeem_script().as_bytes())];
+    let owner_input = find_owner_in_txinputs(&inputs, &chroma, Some(chroma_info));
+    assert!(owner_input.unwrap().is_none());
+}
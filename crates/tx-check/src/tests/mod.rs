@@ -1,9 +1,32 @@
+use bitcoin::{
+    ecdsa::Signature,
+    secp256k1::{Message, PublicKey, Secp256k1, SecretKey},
+    OutPoint, TxIn, Witness,
+};
+use event_bus::EventBus;
 use once_cell::sync::Lazy;
-use yuv_types::YuvTransaction;
+use yuv_pixels::{Chroma, P2WPKHWitness};
+use yuv_storage::LevelDB;
+use yuv_types::network::Network;
+use yuv_types::{
+    ControllerMessage, GraphBuilderMessage, RpcSubscriptionMessage, TxCheckerMessage,
+    YuvTransaction,
+};
 
-use crate::check_transaction;
+use crate::{check_transaction, TxChecker};
 
+mod activation;
+mod burns;
+mod chain;
+mod delegates;
+mod dust;
+mod freezes;
+mod issuers;
+mod revalidation;
 mod script_parser;
+mod subscriptions;
+mod timelocks;
+mod update_chroma;
 
 static VALID_MULTICHROMA_TRANSFER: Lazy<YuvTransaction> = Lazy::new(|| {
     serde_json::from_str::<YuvTransaction>(include_str!("./assets/multichroma_valid_transfer.json"))
@@ -17,6 +40,59 @@ static VALID_SINGLECHROMA_TRANSFER: Lazy<YuvTransaction> = Lazy::new(|| {
     .expect("JSON was not well-formatted")
 });
 
+/// Checker of the `network` over the in-memory storage, along with the storage and the bus to
+/// subscribe to the messages it sends.
+fn test_checker(network: Network) -> (TxChecker<LevelDB, LevelDB>, LevelDB, EventBus) {
+    test_checker_with_subscriptions(network, 100)
+}
+
+/// Same as [`test_checker`], but the channel of the RPC subscriptions fits only `capacity`
+/// messages.
+fn test_checker_with_subscriptions(
+    network: Network,
+    capacity: usize,
+) -> (TxChecker<LevelDB, LevelDB>, LevelDB, EventBus) {
+    let storage = LevelDB::in_memory().unwrap();
+
+    let mut event_bus = EventBus::default();
+    event_bus.register::<TxCheckerMessage>(Some(100));
+    event_bus.register::<GraphBuilderMessage>(Some(100));
+    event_bus.register::<ControllerMessage>(Some(100));
+    event_bus.register::<RpcSubscriptionMessage>(Some(capacity));
+
+    let checker = TxChecker::new(event_bus.clone(), storage.clone(), storage.clone(), network);
+
+    (checker, storage, event_bus)
+}
+
+fn secret(byte: u8) -> SecretKey {
+    SecretKey::from_slice(&[byte; 32]).unwrap()
+}
+
+fn pubkey(byte: u8) -> PublicKey {
+    secret(byte).public_key(&Secp256k1::new())
+}
+
+/// Chroma of the key `1`.
+fn chroma() -> Chroma {
+    Chroma::from(pubkey(1).x_only_public_key().0)
+}
+
+/// Input spending the P2WPKH output of the `pubkey`, with a dummy signature.
+fn p2wpkh_input(previous_output: OutPoint, pubkey: PublicKey) -> TxIn {
+    let ctx = Secp256k1::new();
+    let signature = ctx.sign_ecdsa(&Message::from_slice(&[1; 32]).unwrap(), &secret(1));
+
+    TxIn {
+        previous_output,
+        witness: Witness::from(P2WPKHWitness::new(
+            Signature::sighash_all(signature),
+            pubkey,
+        )),
+        ..Default::default()
+    }
+}
+
 static INVALID_MULTICHROMA_TRANSFER: Lazy<YuvTransaction> = Lazy::new(|| {
     serde_json::from_str::<YuvTransaction>(include_str!(
         "./assets/multichroma_invalid_transfer.json"
@@ -0,0 +1,57 @@
+use bitcoin::{absolute::LockTime, Transaction};
+use yuv_types::announcements::{ChromaInfo, ChromaIssuers, IssueAnnouncement};
+use yuv_types::messages::OwnerChange;
+use yuv_types::YuvTxType;
+
+use super::*;
+use crate::revalidation::is_valid_after_change;
+
+/// Issuance spending the P2WSH output with the `redeem_script` and two dummy signatures.
+fn issuance(chroma: Chroma, redeem_script: &[u8]) -> YuvTransaction {
+    let input = TxIn {
+        witness: Witness::from_slice(&[vec![], vec![1; 71], vec![2; 71], redeem_script.to_vec()]),
+        ..Default::default()
+    };
+
+    YuvTransaction::new(
+        Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![input],
+            output: Vec::new(),
+        },
+        YuvTxType::Issue {
+            output_proofs: None,
+            announcement: IssueAnnouncement::new(chroma, 100),
+        },
+    )
+}
+
+#[test]
+fn test_issuance_after_owner_change() {
+    let chroma = chroma();
+    let issuers = ChromaIssuers::new(2, vec![pubkey(1), pubkey(2), pubkey(3)]).unwrap();
+    let change = OwnerChange {
+        chroma,
+        owner: issuers.owner_script(),
+        height: 100,
+    };
+    let chroma_info = ChromaInfo {
+        owner: Some(issuers.owner_script()),
+        ..Default::default()
+    };
+
+    // Issuance of the new owner is valid at any height.
+    let tx = issuance(chroma, issuers.redeem_script().as_bytes());
+    assert!(is_valid_after_change(&tx, Some(101), &change, &chroma_info).unwrap());
+    assert!(is_valid_after_change(&tx, None, &change, &chroma_info).unwrap());
+
+    // Issuance of the other owner is valid only if it's mined before the change, or in the same
+    // block.
+    let other_issuers = ChromaIssuers::new(1, vec![pubkey(1), pubkey(2), pubkey(3)]).unwrap();
+    let tx = issuance(chroma, other_issuers.redeem_script().as_bytes());
+    assert!(is_valid_after_change(&tx, Some(99), &change, &chroma_info).unwrap());
+    assert!(is_valid_after_change(&tx, Some(100), &change, &chroma_info).unwrap());
+    assert!(!is_valid_after_change(&tx, Some(101), &change, &chroma_info).unwrap());
+    assert!(!is_valid_after_change(&tx, None, &change, &chroma_info).unwrap());
+}
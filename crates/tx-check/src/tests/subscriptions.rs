@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use super::*;
+
+#[tokio::test]
+async fn test_full_subscriptions_channel_does_not_block_isolated_check() {
+    // Nobody receives the notifications, so the channel is full after the first one.
+    let (mut checker, _storage, event_bus) = test_checker_with_subscriptions(Network::Bitcoin, 1);
+
+    let events = event_bus.subscribe::<ControllerMessage>();
+    let subscriptions = event_bus.subscribe::<RpcSubscriptionMessage>();
+
+    let tx = VALID_SINGLECHROMA_TRANSFER.clone();
+    for _ in 0..3 {
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            checker.check_txs_isolated(vec![tx.clone()]),
+        )
+        .await
+        .expect("isolated check should not wait for the subscribers")
+        .unwrap();
+
+        let ControllerMessage::PartiallyCheckedTxs(txids) = events.recv().await.unwrap() else {
+            panic!("expected the checked txs to be sent to the controller");
+        };
+        assert_eq!(txids, vec![tx.bitcoin_tx.txid()]);
+    }
+
+    let RpcSubscriptionMessage::UnconfirmedTransfers(transfers) =
+        subscriptions.recv().await.unwrap()
+    else {
+        panic!("expected the unconfirmed transfers notification");
+    };
+    assert_eq!(transfers, vec![tx]);
+}
@@ -0,0 +1,121 @@
+use bitcoin::{
+    absolute::LockTime,
+    hashes::{sha256, Hash},
+    Sequence, Transaction,
+};
+use yuv_pixels::{
+    HtlcData, HtlcPixelProof, HtlcWitness, HtlcWitnessStack, Pixel, PixelProof, TimelockPixelProof,
+};
+use yuv_types::ProofMap;
+
+use super::*;
+use crate::{
+    errors::CheckError,
+    isolated_checks::{check_input_timelocks, extract_from_iterable_by_proof_map},
+};
+
+const LOCK_HEIGHT: u32 = 850_000;
+const PREIMAGE: [u8; 32] = [7; 32];
+
+fn pixel() -> Pixel {
+    Pixel::new(100u128, chroma())
+}
+
+fn htlc_proof() -> HtlcPixelProof {
+    HtlcPixelProof::new(
+        pixel(),
+        HtlcData::new(
+            sha256::Hash::hash(&PREIMAGE),
+            pubkey(2),
+            pubkey(3),
+            LOCK_HEIGHT,
+        ),
+    )
+}
+
+/// Input spending the HTLC output by the `stack`, with a dummy signature.
+fn htlc_input(stack: HtlcWitnessStack) -> TxIn {
+    let witness = HtlcWitness::new(stack, htlc_proof().to_redeem_script().unwrap());
+
+    TxIn {
+        sequence: Sequence::ENABLE_LOCKTIME_NO_RBF,
+        witness: witness.into_witness(),
+        ..Default::default()
+    }
+}
+
+fn signature() -> Signature {
+    let ctx = Secp256k1::new();
+    let signature = ctx.sign_ecdsa(&Message::from_slice(&[1; 32]).unwrap(), &secret(2));
+
+    Signature::sighash_all(signature)
+}
+
+fn check(lock_time: LockTime, input: TxIn, proof: PixelProof) -> Result<(), CheckError> {
+    let tx = Transaction {
+        version: 2,
+        lock_time,
+        input: vec![TxIn::default(), input],
+        output: Vec::new(),
+    };
+    let inputs = ProofMap::from([(0, PixelProof::sig(pixel(), pubkey(2))), (1, proof)]);
+
+    let gathered_inputs = extract_from_iterable_by_proof_map(&inputs, &tx.input)?;
+
+    check_input_timelocks(&tx, &gathered_inputs)
+}
+
+fn height(height: u32) -> LockTime {
+    LockTime::from_height(height).unwrap()
+}
+
+#[test]
+fn test_timelocked_input_is_spent_from_lock_height() {
+    let proof = || PixelProof::Timelock(TimelockPixelProof::new(pixel(), pubkey(2), LOCK_HEIGHT));
+
+    assert!(check(height(LOCK_HEIGHT), TxIn::default(), proof()).is_ok());
+    assert!(matches!(
+        check(height(LOCK_HEIGHT - 1), TxIn::default(), proof()),
+        Err(CheckError::InputTimelocked {
+            vin: 1,
+            lock_height: LOCK_HEIGHT
+        })
+    ));
+}
+
+#[test]
+fn test_timelocked_input_is_not_spent_by_timestamp() {
+    let proof = PixelProof::Timelock(TimelockPixelProof::new(pixel(), pubkey(2), LOCK_HEIGHT));
+    let lock_time = LockTime::from_time(1_700_000_000).unwrap();
+
+    assert!(matches!(
+        check(lock_time, TxIn::default(), proof),
+        Err(CheckError::InputTimelocked { .. })
+    ));
+}
+
+#[test]
+fn test_htlc_refund_is_timelocked() {
+    let refund = || {
+        htlc_input(HtlcWitnessStack::Refund {
+            signature: signature(),
+        })
+    };
+    let proof = || PixelProof::Htlc(htlc_proof());
+
+    assert!(check(height(LOCK_HEIGHT), refund(), proof()).is_ok());
+    assert!(matches!(
+        check(height(LOCK_HEIGHT - 1), refund(), proof()),
+        Err(CheckError::InputTimelocked { vin: 1, .. })
+    ));
+}
+
+#[test]
+fn test_htlc_claim_is_not_timelocked() {
+    let claim = htlc_input(HtlcWitnessStack::Claim {
+        signature: signature(),
+        preimage: PREIMAGE,
+    });
+
+    assert!(check(LockTime::ZERO, claim, PixelProof::Htlc(htlc_proof())).is_ok());
+}
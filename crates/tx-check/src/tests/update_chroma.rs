@@ -0,0 +1,121 @@
+use bitcoin::{absolute::LockTime, Transaction, TxOut};
+use yuv_storage::ChromaInfoStorage;
+use yuv_types::{
+    announcements::{ChromaAnnouncement, UpdateChromaAnnouncement},
+    Announcement, YuvTxType,
+};
+
+use super::*;
+
+fn chroma_announcement() -> ChromaAnnouncement {
+    ChromaAnnouncement::new(chroma(), "Token".into(), "TKN".into(), 8, 1000, true).unwrap()
+}
+
+/// Announcement tx with the update of the chroma's metadata, signed by the `signer`.
+fn update_tx(signer: PublicKey) -> YuvTransaction {
+    let update =
+        UpdateChromaAnnouncement::new(chroma(), "New Token".into(), "NTKN".into()).unwrap();
+    let announcement = Announcement::UpdateChroma(update);
+
+    let bitcoin_tx = Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![p2wpkh_input(OutPoint::default(), signer)],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: announcement.to_script(),
+        }],
+    };
+
+    YuvTransaction::new(bitcoin_tx, YuvTxType::Announcement(announcement))
+}
+
+/// Fully checks the update of the chroma signed by the `signer`, and returns the message sent
+/// to the controller along with the storage. The chroma is announced if `is_announced` is set.
+async fn check_update(signer: PublicKey, is_announced: bool) -> (ControllerMessage, LevelDB) {
+    let (mut checker, storage, event_bus) = test_checker(Network::Regtest);
+
+    if is_announced {
+        storage
+            .put_chroma_info(&chroma(), Some(chroma_announcement()), 0, 0, 0, None)
+            .await
+            .unwrap();
+        storage
+            .put_chroma_announcement_version(&chroma(), 0, chroma_announcement())
+            .await
+            .unwrap();
+    }
+
+    let events = event_bus.subscribe::<ControllerMessage>();
+    checker
+        .check_txs_full(vec![(update_tx(signer), None)])
+        .await
+        .unwrap();
+
+    (events.recv().await.unwrap(), storage)
+}
+
+#[tokio::test]
+async fn test_owner_update_is_stored_as_next_version() {
+    let (message, storage) = check_update(pubkey(1), true).await;
+
+    assert!(
+        matches!(message, ControllerMessage::FullyCheckedTxs(_)),
+        "update is signed by the owner"
+    );
+
+    let updated = ChromaAnnouncement {
+        name: "New Token".into(),
+        symbol: "NTKN".into(),
+        ..chroma_announcement()
+    };
+    let chroma_info = storage.get_chroma_info(&chroma()).await.unwrap().unwrap();
+    assert_eq!(chroma_info.announcement, Some(updated.clone()));
+    assert_eq!(chroma_info.announcement_version, 1);
+
+    assert_eq!(
+        storage
+            .get_chroma_announcement_version(&chroma(), 0)
+            .await
+            .unwrap(),
+        Some(chroma_announcement()),
+        "initial version should be kept"
+    );
+    assert_eq!(
+        storage
+            .get_chroma_announcement_version(&chroma(), 1)
+            .await
+            .unwrap(),
+        Some(updated)
+    );
+}
+
+#[tokio::test]
+async fn test_update_not_signed_by_owner_is_rejected() {
+    let (message, storage) = check_update(pubkey(2), true).await;
+
+    assert!(
+        matches!(message, ControllerMessage::InvalidTxs(_)),
+        "update isn't signed by the owner"
+    );
+
+    let chroma_info = storage.get_chroma_info(&chroma()).await.unwrap().unwrap();
+    assert_eq!(chroma_info.announcement, Some(chroma_announcement()));
+    assert_eq!(chroma_info.announcement_version, 0);
+    assert!(storage
+        .get_chroma_announcement_version(&chroma(), 1)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_update_of_unannounced_chroma_is_rejected() {
+    let (message, storage) = check_update(pubkey(1), false).await;
+
+    assert!(
+        matches!(message, ControllerMessage::InvalidTxs(_)),
+        "chroma isn't announced"
+    );
+    assert!(storage.get_chroma_info(&chroma()).await.unwrap().is_none());
+}
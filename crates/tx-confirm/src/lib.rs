@@ -7,7 +7,11 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio_util::sync::CancellationToken;
-use yuv_types::{ControllerMessage, TxConfirmMessage};
+use tracing::Instrument;
+use yuv_types::{ControllerMessage, TxConfirmMessage, YuvTransaction};
+
+mod policy;
+pub use policy::{ConfirmationPolicy, ConfirmationTier};
 
 /// `TxConfirmator` is responsible for waiting confirmations of transactions in Bitcoin.
 pub struct TxConfirmator<BC>
@@ -17,15 +21,26 @@ where
     event_bus: EventBus,
     bitcoin_client: Arc<BC>,
     /// Confirmations queue. Contains transactions that are waiting confirmation.
-    queue: HashMap<Txid, SystemTime>,
+    queue: HashMap<Txid, WaitingTx>,
     /// Max time that transaction can wait confirmation before it will be removed from the queue.
     max_confirmation_time: Duration,
-    /// Interval between waiting txs clean up.
-    clean_up_interval: Duration,
-    /// Contains the number of confirmations required to consider a transaction as confirmed.
-    confirmations_number: u8,
+    /// Number of confirmations required to consider a transaction as confirmed, depending on
+    /// its amount.
+    policy: ConfirmationPolicy,
     /// Contains the latest indexed blocks and is used to handle reorgs.
     latest_blocks: VecDeque<BlockInfo>,
+    /// If set, the transactions that are found in the Bitcoin mempool are reported to the
+    /// controller before they are mined, so they can be provisionally attached.
+    accept_unconfirmed: bool,
+}
+
+/// Transaction that is waiting for the confirmation.
+#[derive(Debug, Clone, Copy)]
+struct WaitingTx {
+    /// Time when the transaction was added to the queue.
+    created_at: SystemTime,
+    /// Number of confirmations required for the transaction.
+    confirmations: u8,
 }
 
 /// An abstraction over `GetBlockTxResult` that is used by the `TxConfirmator` to keep track
@@ -34,6 +49,8 @@ where
 struct BlockInfo {
     /// Hash of the block.
     hash: BlockHash,
+    /// Height of the block.
+    height: usize,
     /// Transactions inside the block.
     txs: Vec<Txid>,
 }
@@ -43,6 +60,7 @@ impl From<GetBlockTxResult> for BlockInfo {
         let txs = block_result.tx.iter().map(|tx| tx.txid()).collect();
         Self {
             hash: block_result.block_data.hash,
+            height: block_result.block_data.height,
             txs,
         }
     }
@@ -56,7 +74,6 @@ where
         event_bus: &EventBus,
         bitcoin_client: Arc<BC>,
         max_confirmation_time: Duration,
-        clean_up_interval: Duration,
         confirmations_number: u8,
     ) -> Self {
         let event_bus = event_bus
@@ -68,14 +85,25 @@ where
             queue: Default::default(),
             max_confirmation_time,
             bitcoin_client,
-            clean_up_interval,
-            confirmations_number,
+            policy: ConfirmationPolicy::fixed(confirmations_number),
             latest_blocks: Default::default(),
+            accept_unconfirmed: false,
         }
     }
 
+    /// Sets the policy of the required confirmations, which overrides the `confirmations_number`.
+    pub fn with_confirmation_policy(mut self, policy: ConfirmationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Enables or disables reporting of the unconfirmed transactions to the controller.
+    pub fn with_accept_unconfirmed(mut self, accept_unconfirmed: bool) -> Self {
+        self.accept_unconfirmed = accept_unconfirmed;
+        self
+    }
+
     pub async fn run(mut self, cancellation_token: CancellationToken) {
-        let mut clean_up_timer = tokio::time::interval(self.clean_up_interval);
         let events = self.event_bus.subscribe::<TxConfirmMessage>();
 
         loop {
@@ -91,11 +119,6 @@ where
                         cancellation_token.cancel();
                     };
                 },
-                _ = clean_up_timer.tick() => {
-                    if let Err(err) = self.clean_up_waiting_txs().await {
-                        tracing::error!("failed to handle waiting transactions: {:#}", err);
-                    };
-                },
                 _ = cancellation_token.cancelled() => {
                     tracing::trace!("cancellation received, stopping confirmator");
                     return;
@@ -104,14 +127,28 @@ where
         }
     }
 
-    async fn handle_event(&mut self, event: TxConfirmMessage) -> eyre::Result<()> {
+    /// Handles the event received from the event bus. Public, so the events can be handled one
+    /// by one, e.g. by the replay of the pipeline.
+    pub async fn handle_event(&mut self, event: TxConfirmMessage) -> eyre::Result<()> {
         match event {
-            TxConfirmMessage::Txs(txids) => {
-                for txid in txids {
-                    self.handle_tx_to_confirm(txid).await?;
+            TxConfirmMessage::Txs(txs) => {
+                for tx in txs {
+                    let txid = tx.bitcoin_tx.txid();
+                    self.handle_tx_to_confirm(&tx)
+                        .instrument(tx_span(&txid))
+                        .await?;
                 }
             }
             TxConfirmMessage::Block(block) => self.handle_new_block(*block).await?,
+            TxConfirmMessage::CleanUp(completion) => {
+                let result = self.clean_up_waiting_txs().await;
+                completion.complete(&result);
+
+                // Failed clean up is not critical, so it's not propagated to stop the node.
+                if let Err(err) = result {
+                    tracing::error!("failed to handle waiting transactions: {:#}", err);
+                }
+            }
         }
 
         Ok(())
@@ -142,28 +179,36 @@ where
             }
         };
 
-        let block_info = block.into();
-        let mined_txs = self.extract_waiting_txs_from_block(&block_info);
+        let block_info: BlockInfo = block.into();
+        let mined_txs = self.extract_mined_txs_from_block(&block_info);
         self.latest_blocks.push_back(block_info.clone());
-        self.handle_mined_txs(mined_txs).await?;
-
-        // If there is a block that reached enough confirmations, send its txs to the
-        // tx checker for a full check.
-        if self.latest_blocks.len() == self.confirmations_number as usize {
-            let confirmed_block = self
-                .latest_blocks
-                .pop_front()
-                .expect("at least one block should be present");
-
-            let yuv_txs = self.extract_waiting_txs_from_block(&confirmed_block);
-            if !yuv_txs.is_empty() {
-                self.new_confirmed_txs(&yuv_txs).await;
-
-                tracing::debug!(
-                    block_hash = confirmed_block.hash.to_string(),
-                    "New block confirmed",
-                );
-            }
+        self.handle_mined_txs(mined_txs, block_info.height).await?;
+
+        // Send the txs that reached enough confirmations to the tx checker for a full check.
+        // The last block has one confirmation, and each block before it has one more.
+        let mut confirmed_txs = Vec::new();
+        for (depth, block) in self.latest_blocks.iter().rev().enumerate() {
+            confirmed_txs.extend(
+                block
+                    .txs
+                    .iter()
+                    .filter(|txid| {
+                        self.queue
+                            .get(txid)
+                            .is_some_and(|tx| tx.confirmations as usize <= depth + 1)
+                    })
+                    .copied(),
+            );
+        }
+
+        if !confirmed_txs.is_empty() {
+            self.new_confirmed_txs(&confirmed_txs).await;
+        }
+
+        // The blocks that reached the max number of confirmations have no txs to wait for.
+        let max_confirmations = self.policy.max_confirmations().max(1) as usize;
+        while self.latest_blocks.len() >= max_confirmations {
+            self.latest_blocks.pop_front();
         }
 
         Ok(())
@@ -171,8 +216,14 @@ where
 
     /// Handle new transaction to confirm it. If transaction is already confirmed, then it will be
     /// sent to the `TxChecker`. Otherwise it will be added to the queue.
-    async fn handle_tx_to_confirm(&mut self, txid: Txid) -> eyre::Result<()> {
-        self.queue.entry(txid).or_insert(SystemTime::now());
+    async fn handle_tx_to_confirm(&mut self, yuv_tx: &YuvTransaction) -> eyre::Result<()> {
+        let txid = yuv_tx.bitcoin_tx.txid();
+        let required_confirmations = self.policy.required_confirmations(yuv_tx.amount());
+
+        self.queue.entry(txid).or_insert(WaitingTx {
+            created_at: SystemTime::now(),
+            confirmations: required_confirmations,
+        });
 
         let got_tx_result = self
             .bitcoin_client
@@ -187,13 +238,29 @@ where
             res => res?,
         };
 
-        if let Some(confirmations) = tx.confirmations {
-            self.handle_mined_txs(vec![txid]).await?;
+        tracing::debug!(
+            confirmations = required_confirmations,
+            "Tx is waiting for the confirmation"
+        );
+
+        if let (Some(confirmations), Some(block_hash)) = (tx.confirmations, tx.blockhash) {
+            let block = self.bitcoin_client.get_block_info(&block_hash).await?;
+            let Some(block_index) = block.tx.iter().position(|block_txid| *block_txid == txid)
+            else {
+                bail!("Mined tx {} is not found in its block {}", txid, block_hash);
+            };
+
+            self.handle_mined_txs(vec![(txid, block_index)], block.block_data.height)
+                .await?;
 
-            if confirmations >= self.confirmations_number as u32 {
+            if confirmations >= required_confirmations as u32 {
                 self.new_confirmed_txs(&[txid]).await;
                 return Ok(());
             }
+        } else if self.accept_unconfirmed {
+            self.event_bus
+                .send(ControllerMessage::UnconfirmedTxs(vec![txid]))
+                .await;
         }
 
         Ok(())
@@ -202,6 +269,9 @@ where
     async fn handle_reorg(&mut self, new_block: &GetBlockTxResult) -> eyre::Result<()> {
         // List of transactions that are members of orphan blocks and should be handled again.
         let mut reorged_txs = Vec::new();
+        // List of transactions that are members of orphan blocks, but aren't waiting for the
+        // confirmation. Already attached ones should be rolled back.
+        let mut orphaned_txs = Vec::new();
         let mut prev_block_hash = new_block.block_data.previousblockhash;
         let mut new_indexing_height = new_block.block_data.height;
 
@@ -230,6 +300,12 @@ where
             prev_block_hash = prev_block.block_data.previousblockhash;
 
             let current_block_reorged_txs = self.extract_waiting_txs_from_block(&last_block);
+            orphaned_txs.extend(
+                last_block
+                    .txs
+                    .into_iter()
+                    .filter(|txid| !current_block_reorged_txs.contains(txid)),
+            );
             reorged_txs.extend(current_block_reorged_txs);
         }
 
@@ -240,6 +316,7 @@ where
         self.event_bus
             .send(ControllerMessage::Reorganization {
                 txs: reorged_txs,
+                orphaned_txs,
                 new_indexing_height,
             })
             .await;
@@ -247,16 +324,31 @@ where
         Ok(())
     }
 
-    async fn handle_mined_txs(&self, txids: Vec<Txid>) -> eyre::Result<()> {
-        if !txids.is_empty() {
+    async fn handle_mined_txs(
+        &self,
+        txs: Vec<(Txid, usize)>,
+        block_height: usize,
+    ) -> eyre::Result<()> {
+        if !txs.is_empty() {
             self.event_bus
-                .send(ControllerMessage::MinedTxs(txids))
+                .send(ControllerMessage::MinedTxs { txs, block_height })
                 .await;
         }
 
         Ok(())
     }
 
+    /// Waiting transactions of the block with their indexes in it.
+    fn extract_mined_txs_from_block(&self, block: &BlockInfo) -> Vec<(Txid, usize)> {
+        block
+            .txs
+            .iter()
+            .enumerate()
+            .filter(|(_, txid)| self.queue.contains_key(txid))
+            .map(|(block_index, txid)| (*txid, block_index))
+            .collect()
+    }
+
     fn extract_waiting_txs_from_block(&self, block: &BlockInfo) -> Vec<Txid> {
         block
             .txs
@@ -274,24 +366,34 @@ where
             return Ok(());
         }
 
+        let mut expired_txs = Vec::new();
+
         // Remove transactions that are waiting confirmation for too long.
-        for (txid, created_at) in self.queue.clone().into_iter() {
-            if created_at.elapsed().unwrap() > self.max_confirmation_time {
-                tracing::debug!(
-                    "Transaction {:?} is waiting confirmation for too long. Removing from queue.",
-                    txid
-                );
+        for (txid, waiting_tx) in self.queue.clone().into_iter() {
+            if waiting_tx.created_at.elapsed().unwrap() > self.max_confirmation_time {
+                tx_span(&txid).in_scope(|| {
+                    tracing::debug!(
+                        "Transaction is waiting confirmation for too long. Removing from queue."
+                    )
+                });
 
                 self.queue.remove(&txid);
+                expired_txs.push(txid);
             }
         }
 
+        if !expired_txs.is_empty() {
+            self.event_bus
+                .send(ControllerMessage::ExpiredTxs(expired_txs))
+                .await;
+        }
+
         Ok(())
     }
 
     async fn new_confirmed_txs(&mut self, yuv_tx_ids: &[Txid]) {
-        tracing::debug!("Transactions confirmed: {:?}", yuv_tx_ids);
         for tx_id in yuv_tx_ids {
+            tx_span(tx_id).in_scope(|| tracing::debug!("Tx is confirmed"));
             self.queue.remove(tx_id);
         }
 
@@ -300,3 +402,9 @@ where
             .await;
     }
 }
+
+/// Span of the transaction's confirmation, so its logs are correlated with the rest of the
+/// pipeline. The chroma isn't known to the confirmator, which handles only the ids.
+fn tx_span(txid: &Txid) -> tracing::Span {
+    tracing::info_span!("tx", txid = txid.to_string())
+}
@@ -0,0 +1,102 @@
+/// Number of confirmations required for the transactions whose amount is below the bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationTier {
+    /// Upper bound of the transaction's amount, exclusive.
+    pub below: u128,
+    /// Number of confirmations required for the transactions in the tier.
+    pub confirmations: u8,
+}
+
+impl ConfirmationTier {
+    pub fn new(below: u128, confirmations: u8) -> Self {
+        Self {
+            below,
+            confirmations,
+        }
+    }
+}
+
+/// Table of the confirmations required for the transactions depending on their amount, e.g.
+/// 1 confirmation below 1000 units, 3 below 1000000 and 6 for the rest. Small transfers are
+/// attached faster, while the large ones stay conservative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmationPolicy {
+    /// Tiers sorted by their bounds.
+    tiers: Vec<ConfirmationTier>,
+    /// Number of confirmations required for the transactions above all the tiers, and for the
+    /// ones whose amount is hidden.
+    confirmations: u8,
+}
+
+impl ConfirmationPolicy {
+    /// Creates the policy that requires the same number of confirmations for all the
+    /// transactions.
+    pub fn fixed(confirmations: u8) -> Self {
+        Self {
+            tiers: Vec::new(),
+            confirmations,
+        }
+    }
+
+    /// Sets the tiers of the policy. The transaction falls into the tier with the lowest bound
+    /// above its amount.
+    pub fn with_tiers(mut self, mut tiers: Vec<ConfirmationTier>) -> Self {
+        tiers.sort_by_key(|tier| tier.below);
+        self.tiers = tiers;
+        self
+    }
+
+    /// Returns the number of confirmations required for the transaction with the `amount`,
+    /// `None` if the amount is hidden.
+    pub fn required_confirmations(&self, amount: Option<u128>) -> u8 {
+        let Some(amount) = amount else {
+            return self.confirmations;
+        };
+
+        self.tiers
+            .iter()
+            .find(|tier| amount < tier.below)
+            .map_or(self.confirmations, |tier| tier.confirmations)
+    }
+
+    /// Returns the largest number of confirmations that can be required by the policy.
+    pub fn max_confirmations(&self) -> u8 {
+        self.tiers
+            .iter()
+            .map(|tier| tier.confirmations)
+            .fold(self.confirmations, u8::max)
+    }
+}
+
+impl Default for ConfirmationPolicy {
+    fn default() -> Self {
+        Self::fixed(yuv_types::DEFAULT_CONFIRMATIONS_NUMBER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_confirmations() {
+        let policy = ConfirmationPolicy::fixed(6).with_tiers(vec![
+            ConfirmationTier::new(1_000_000, 3),
+            ConfirmationTier::new(1_000, 1),
+        ]);
+
+        assert_eq!(policy.required_confirmations(Some(0)), 1);
+        assert_eq!(policy.required_confirmations(Some(999)), 1);
+        assert_eq!(policy.required_confirmations(Some(1_000)), 3);
+        assert_eq!(policy.required_confirmations(Some(1_000_000)), 6);
+        assert_eq!(policy.required_confirmations(None), 6);
+        assert_eq!(policy.max_confirmations(), 6);
+    }
+
+    #[test]
+    fn test_max_confirmations_above_default() {
+        let policy = ConfirmationPolicy::fixed(1).with_tiers(vec![ConfirmationTier::new(10, 2)]);
+
+        assert_eq!(policy.max_confirmations(), 2);
+    }
+}
@@ -0,0 +1,397 @@
+//! Height-based activation of the consensus-affecting protocol features.
+//!
+//! Every [`ProtocolFeature`] has an activation height per [`Network`]. Starting from that height
+//! the new rules are enforced by the nodes, and before it the transactions that use the feature
+//! are rejected. The schedule is the same for all the nodes, so they switch to the new rules
+//! simultaneously. The schedule of the [`Network::Custom`] is set in its parameters, see
+//! [`ActivationHeights`].
+
+use alloc::vec::Vec;
+use core::fmt;
+use yuv_pixels::PixelProof;
+
+use crate::network::Network;
+use crate::{Announcement, YuvTransaction, YuvTxType};
+
+/// Consensus-affecting features of the protocol that are activated at some block height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ProtocolFeature {
+    /// [`TransferOwnershipAnnouncement`](crate::announcements::TransferOwnershipAnnouncement).
+    TransferOwnershipAnnouncement,
+    /// Transfers with the bulletproof (confidential) pixel proofs.
+    Bulletproofs,
+    /// [`FreezeAnnouncement`](crate::announcements::FreezeAnnouncement)s that freeze only the
+    /// part of the output's amount.
+    PartialFreezes,
+    /// [`BurnAnnouncement`](crate::announcements::BurnAnnouncement)s in the transfers.
+    BurnAnnouncement,
+    /// [`UpdateChromaAnnouncement`](crate::announcements::UpdateChromaAnnouncement).
+    UpdateChromaAnnouncement,
+    /// [`FreezePubkeyAnnouncement`](crate::announcements::FreezePubkeyAnnouncement).
+    FreezePubkeyAnnouncement,
+    /// [`FreezeAnnouncement`](crate::announcements::FreezeAnnouncement)s that expire at some
+    /// block height.
+    ExpirableFreezes,
+    /// [`ChromaAnnouncement`](crate::announcements::ChromaAnnouncement)s that declare the m-of-n
+    /// issuer set owning the chroma.
+    MultisigIssuers,
+    /// Pixel outputs with the [`MusigPixelProof`](yuv_pixels::MusigPixelProof)s.
+    MusigOutputs,
+    /// [`DelegateAnnouncement`](crate::announcements::DelegateAnnouncement).
+    DelegateAnnouncement,
+    /// Pixel outputs with the [`TimelockPixelProof`](yuv_pixels::TimelockPixelProof)s.
+    TimelockOutputs,
+    /// Pixel outputs with the [`HtlcPixelProof`](yuv_pixels::HtlcPixelProof)s.
+    HtlcOutputs,
+}
+
+impl ProtocolFeature {
+    /// All the known protocol features.
+    pub const ALL: [ProtocolFeature; 12] = [
+        Self::TransferOwnershipAnnouncement,
+        Self::Bulletproofs,
+        Self::PartialFreezes,
+        Self::BurnAnnouncement,
+        Self::UpdateChromaAnnouncement,
+        Self::FreezePubkeyAnnouncement,
+        Self::ExpirableFreezes,
+        Self::MultisigIssuers,
+        Self::MusigOutputs,
+        Self::DelegateAnnouncement,
+        Self::TimelockOutputs,
+        Self::HtlcOutputs,
+    ];
+
+    /// Returns the height of the block starting from which the feature is active.
+    pub fn activation_height(&self, network: Network) -> usize {
+        match network {
+            Network::Signet => return ActivationHeights::SIGNET.get(*self),
+            Network::Custom(params) => return params.activation_heights.get(*self),
+            _ => {}
+        }
+
+        match self {
+            Self::TransferOwnershipAnnouncement => match network {
+                Network::Bitcoin => 855_000,
+                Network::Testnet => 2_830_000,
+                Network::Testnet4 => 40_000,
+                Network::Mutiny => 1_200_000,
+                _ => 0,
+            },
+            Self::Bulletproofs => 0,
+            Self::PartialFreezes => match network {
+                Network::Bitcoin => 920_000,
+                Network::Testnet => 4_350_000,
+                Network::Testnet4 => 60_000,
+                Network::Mutiny => 1_900_000,
+                _ => 0,
+            },
+            Self::BurnAnnouncement => match network {
+                Network::Bitcoin => 925_000,
+                Network::Testnet => 4_400_000,
+                Network::Testnet4 => 61_000,
+                Network::Mutiny => 1_950_000,
+                _ => 0,
+            },
+            Self::UpdateChromaAnnouncement => match network {
+                Network::Bitcoin => 930_000,
+                Network::Testnet => 4_450_000,
+                Network::Testnet4 => 62_000,
+                Network::Mutiny => 2_000_000,
+                _ => 0,
+            },
+            Self::FreezePubkeyAnnouncement => match network {
+                Network::Bitcoin => 935_000,
+                Network::Testnet => 4_500_000,
+                Network::Testnet4 => 63_000,
+                Network::Mutiny => 2_050_000,
+                _ => 0,
+            },
+            Self::ExpirableFreezes => match network {
+                Network::Bitcoin => 940_000,
+                Network::Testnet => 4_550_000,
+                Network::Testnet4 => 64_000,
+                Network::Mutiny => 2_100_000,
+                _ => 0,
+            },
+            Self::MultisigIssuers => match network {
+                Network::Bitcoin => 945_000,
+                Network::Testnet => 4_600_000,
+                Network::Testnet4 => 65_000,
+                Network::Mutiny => 2_150_000,
+                _ => 0,
+            },
+            Self::MusigOutputs => match network {
+                Network::Bitcoin => 950_000,
+                Network::Testnet => 4_650_000,
+                Network::Testnet4 => 66_000,
+                Network::Mutiny => 2_200_000,
+                _ => 0,
+            },
+            Self::DelegateAnnouncement => match network {
+                Network::Bitcoin => 955_000,
+                Network::Testnet => 4_700_000,
+                Network::Testnet4 => 67_000,
+                Network::Mutiny => 2_250_000,
+                _ => 0,
+            },
+            Self::TimelockOutputs => match network {
+                Network::Bitcoin => 960_000,
+                Network::Testnet => 4_750_000,
+                Network::Testnet4 => 68_000,
+                Network::Mutiny => 2_300_000,
+                _ => 0,
+            },
+            Self::HtlcOutputs => match network {
+                Network::Bitcoin => 965_000,
+                Network::Testnet => 4_800_000,
+                Network::Testnet4 => 69_000,
+                Network::Mutiny => 2_350_000,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Returns `true` if the feature is active at the given block height.
+    pub fn is_active(&self, network: Network, height: usize) -> bool {
+        height >= self.activation_height(network)
+    }
+
+    /// Returns the features that must be active for the transaction to be accepted.
+    pub fn required_by(tx: &YuvTransaction) -> Vec<ProtocolFeature> {
+        let mut features = Vec::new();
+
+        match &tx.tx_type {
+            YuvTxType::Announcement(Announcement::Chroma(chroma)) if chroma.issuers.is_some() => {
+                features.push(Self::MultisigIssuers);
+            }
+            YuvTxType::Announcement(Announcement::TransferOwnership(_)) => {
+                features.push(Self::TransferOwnershipAnnouncement);
+            }
+            YuvTxType::Announcement(Announcement::Freeze(freeze)) => {
+                if freeze.is_partial() {
+                    features.push(Self::PartialFreezes);
+                }
+
+                if freeze.is_expirable() {
+                    features.push(Self::ExpirableFreezes);
+                }
+            }
+            YuvTxType::Announcement(Announcement::Burn(_)) => {
+                features.push(Self::BurnAnnouncement);
+            }
+            YuvTxType::Announcement(Announcement::UpdateChroma(_)) => {
+                features.push(Self::UpdateChromaAnnouncement);
+            }
+            YuvTxType::Announcement(Announcement::FreezePubkey(_)) => {
+                features.push(Self::FreezePubkeyAnnouncement);
+            }
+            YuvTxType::Announcement(Announcement::Delegate(_)) => {
+                features.push(Self::DelegateAnnouncement);
+            }
+            YuvTxType::Transfer { .. } if !tx.burn_announcements().is_empty() => {
+                features.push(Self::BurnAnnouncement);
+            }
+            _ => {}
+        }
+
+        let has_output = |matches: fn(&PixelProof) -> bool| {
+            tx.tx_type
+                .output_proofs()
+                .is_some_and(|proofs| proofs.values().any(matches))
+        };
+
+        if has_output(|proof| matches!(proof, PixelProof::Musig(_))) {
+            features.push(Self::MusigOutputs);
+        }
+
+        if has_output(|proof| matches!(proof, PixelProof::Timelock(_))) {
+            features.push(Self::TimelockOutputs);
+        }
+
+        if has_output(|proof| matches!(proof, PixelProof::Htlc(_))) {
+            features.push(Self::HtlcOutputs);
+        }
+
+        #[cfg(feature = "bulletproof")]
+        if tx.is_bulletproof() {
+            features.push(Self::Bulletproofs);
+        }
+
+        features
+    }
+}
+
+/// Activation heights of all the [`ProtocolFeature`]s, e.g. the ones of the
+/// [`CustomNetwork`](crate::network::CustomNetwork). The missing heights are `0`, so the features
+/// are active from the start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ActivationHeights {
+    pub transfer_ownership_announcement: usize,
+    pub bulletproofs: usize,
+    pub partial_freezes: usize,
+    pub burn_announcement: usize,
+    pub update_chroma_announcement: usize,
+    pub freeze_pubkey_announcement: usize,
+    pub expirable_freezes: usize,
+    pub multisig_issuers: usize,
+    pub musig_outputs: usize,
+    pub delegate_announcement: usize,
+    pub timelock_outputs: usize,
+    pub htlc_outputs: usize,
+}
+
+impl ActivationHeights {
+    /// Activation heights of the Bitcoin signet.
+    pub const SIGNET: Self = Self {
+        transfer_ownership_announcement: 160_000,
+        bulletproofs: 0,
+        partial_freezes: 200_000,
+        burn_announcement: 202_500,
+        update_chroma_announcement: 205_000,
+        freeze_pubkey_announcement: 207_500,
+        expirable_freezes: 210_000,
+        multisig_issuers: 212_500,
+        musig_outputs: 215_000,
+        delegate_announcement: 217_500,
+        timelock_outputs: 220_000,
+        htlc_outputs: 222_500,
+    };
+
+    /// Returns the activation height of the `feature`.
+    pub fn get(&self, feature: ProtocolFeature) -> usize {
+        match feature {
+            ProtocolFeature::TransferOwnershipAnnouncement => self.transfer_ownership_announcement,
+            ProtocolFeature::Bulletproofs => self.bulletproofs,
+            ProtocolFeature::PartialFreezes => self.partial_freezes,
+            ProtocolFeature::BurnAnnouncement => self.burn_announcement,
+            ProtocolFeature::UpdateChromaAnnouncement => self.update_chroma_announcement,
+            ProtocolFeature::FreezePubkeyAnnouncement => self.freeze_pubkey_announcement,
+            ProtocolFeature::ExpirableFreezes => self.expirable_freezes,
+            ProtocolFeature::MultisigIssuers => self.multisig_issuers,
+            ProtocolFeature::MusigOutputs => self.musig_outputs,
+            ProtocolFeature::DelegateAnnouncement => self.delegate_announcement,
+            ProtocolFeature::TimelockOutputs => self.timelock_outputs,
+            ProtocolFeature::HtlcOutputs => self.htlc_outputs,
+        }
+    }
+}
+
+impl fmt::Display for ProtocolFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TransferOwnershipAnnouncement => write!(f, "transfer_ownership_announcement"),
+            Self::Bulletproofs => write!(f, "bulletproofs"),
+            Self::PartialFreezes => write!(f, "partial_freezes"),
+            Self::BurnAnnouncement => write!(f, "burn_announcement"),
+            Self::UpdateChromaAnnouncement => write!(f, "update_chroma_announcement"),
+            Self::FreezePubkeyAnnouncement => write!(f, "freeze_pubkey_announcement"),
+            Self::ExpirableFreezes => write!(f, "expirable_freezes"),
+            Self::MultisigIssuers => write!(f, "multisig_issuers"),
+            Self::MusigOutputs => write!(f, "musig_outputs"),
+            Self::DelegateAnnouncement => write!(f, "delegate_announcement"),
+            Self::TimelockOutputs => write!(f, "timelock_outputs"),
+            Self::HtlcOutputs => write!(f, "htlc_outputs"),
+        }
+    }
+}
+
+/// Activation state of the [`ProtocolFeature`] at some block height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActivationStatus {
+    pub feature: ProtocolFeature,
+    /// Height of the block starting from which the feature is active.
+    pub activation_height: usize,
+    /// Is the feature active at the requested height.
+    pub is_active: bool,
+}
+
+impl ActivationStatus {
+    pub fn new(feature: ProtocolFeature, network: Network, height: usize) -> Self {
+        Self {
+            feature,
+            activation_height: feature.activation_height(network),
+            is_active: feature.is_active(network, height),
+        }
+    }
+}
+
+/// Returns activation statuses of all the [`ProtocolFeature`]s at the given block height.
+pub fn activation_statuses(network: Network, height: usize) -> Vec<ActivationStatus> {
+    ProtocolFeature::ALL
+        .iter()
+        .map(|feature| ActivationStatus::new(*feature, network, height))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::CustomNetwork;
+
+    #[test]
+    fn test_feature_is_active_from_activation_height() {
+        let feature = ProtocolFeature::TransferOwnershipAnnouncement;
+        let height = feature.activation_height(Network::Bitcoin);
+
+        assert!(!feature.is_active(Network::Bitcoin, height - 1));
+        assert!(feature.is_active(Network::Bitcoin, height));
+        assert!(feature.is_active(Network::Bitcoin, height + 1));
+
+        // Features are active from the start on the local networks.
+        assert!(feature.is_active(Network::Regtest, 0));
+    }
+
+    #[test]
+    fn test_activation_statuses() {
+        let statuses = activation_statuses(Network::Testnet, 0);
+
+        assert_eq!(statuses.len(), ProtocolFeature::ALL.len());
+        assert_eq!(
+            statuses[0],
+            ActivationStatus {
+                feature: ProtocolFeature::TransferOwnershipAnnouncement,
+                activation_height: 2_830_000,
+                is_active: false,
+            }
+        );
+        assert!(statuses[1].is_active);
+    }
+
+    #[test]
+    fn test_testnet4_has_own_activation_heights() {
+        for feature in ProtocolFeature::ALL {
+            if feature == ProtocolFeature::Bulletproofs {
+                continue;
+            }
+
+            let height = feature.activation_height(Network::Testnet4);
+            assert_ne!(height, 0, "{feature} should not be active from the start");
+            assert!(!feature.is_active(Network::Testnet4, height - 1));
+        }
+    }
+
+    #[test]
+    fn test_signet_and_custom_network_activation_heights() {
+        let feature = ProtocolFeature::PartialFreezes;
+
+        assert_eq!(
+            feature.activation_height(Network::Signet),
+            ActivationHeights::SIGNET.partial_freezes
+        );
+
+        // Features are active from the start on the custom network, unless their heights are set.
+        let mut params = CustomNetwork::default();
+        assert!(feature.is_active(Network::Custom(params), 0));
+
+        params.activation_heights.partial_freezes = 1_000;
+        assert!(!feature.is_active(Network::Custom(params), 999));
+        assert!(feature.is_active(Network::Custom(params), 1_000));
+        assert!(ProtocolFeature::BurnAnnouncement.is_active(Network::Custom(params), 0));
+    }
+}
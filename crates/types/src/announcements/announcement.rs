@@ -2,14 +2,18 @@ use alloc::string::String;
 use alloc::vec::Vec;
 
 use bitcoin::blockdata::script::Builder;
-use bitcoin::OutPoint;
 use bitcoin::{script::PushBytesBuf, Script, ScriptBuf};
+use bitcoin::{secp256k1, OutPoint};
 use yuv_pixels::Chroma;
 
 use core::fmt;
 
+use super::burn::BurnAnnouncement;
 use super::chroma::ChromaAnnouncementParseError;
+use super::delegate::{DelegateAnnouncement, DelegateCapabilities};
+use super::freeze_pubkey::FreezePubkeyAnnouncement;
 use super::transfer_ownership::TransferOwnershipAnnouncement;
+use super::update_chroma::{UpdateChromaAnnouncement, UpdateChromaAnnouncementParseError};
 use crate::{
     announcements::{
         issue::IssueAnnouncement, parse_op_return_script, ChromaAnnouncement, FreezeAnnouncement,
@@ -56,6 +60,10 @@ pub enum Announcement {
     Freeze(FreezeAnnouncement),
     Issue(IssueAnnouncement),
     TransferOwnership(TransferOwnershipAnnouncement),
+    Burn(BurnAnnouncement),
+    UpdateChroma(UpdateChromaAnnouncement),
+    FreezePubkey(FreezePubkeyAnnouncement),
+    Delegate(DelegateAnnouncement),
 }
 
 impl Announcement {
@@ -66,6 +74,10 @@ impl Announcement {
             Self::Freeze(inner) => inner,
             Self::Issue(inner) => inner,
             Self::TransferOwnership(inner) => inner,
+            Self::Burn(inner) => inner,
+            Self::UpdateChroma(inner) => inner,
+            Self::FreezePubkey(inner) => inner,
+            Self::Delegate(inner) => inner,
         }
     }
 
@@ -88,6 +100,20 @@ impl Announcement {
     pub fn to_bytes(&self) -> Vec<u8> {
         self.inner().to_bytes()
     }
+
+    /// Returns the chroma the announcement is made for.
+    pub fn chroma(&self) -> Chroma {
+        match self {
+            Self::Chroma(inner) => inner.chroma,
+            Self::Freeze(inner) => inner.chroma,
+            Self::Issue(inner) => inner.chroma,
+            Self::TransferOwnership(inner) => inner.chroma,
+            Self::Burn(inner) => inner.chroma,
+            Self::UpdateChroma(inner) => inner.chroma,
+            Self::FreezePubkey(inner) => inner.chroma,
+            Self::Delegate(inner) => inner.chroma,
+        }
+    }
 }
 
 // Convenience methods
@@ -123,6 +149,52 @@ impl Announcement {
     pub fn freeze_announcement(chroma: impl Into<Chroma>, outpoint: OutPoint) -> Self {
         Self::Freeze(FreezeAnnouncement::new(chroma.into(), outpoint))
     }
+
+    /// A wrapper to create a [`FreezePubkeyAnnouncement`] from the given arguments.
+    pub fn freeze_pubkey_announcement(
+        chroma: impl Into<Chroma>,
+        pubkey: secp256k1::PublicKey,
+        unfreeze: bool,
+    ) -> Self {
+        let chroma = chroma.into();
+
+        Self::FreezePubkey(if unfreeze {
+            FreezePubkeyAnnouncement::new_unfreeze(chroma, pubkey)
+        } else {
+            FreezePubkeyAnnouncement::new(chroma, pubkey)
+        })
+    }
+
+    /// A wrapper to create a [`DelegateAnnouncement`] from the given arguments.
+    pub fn delegate_announcement(
+        chroma: impl Into<Chroma>,
+        delegate: secp256k1::PublicKey,
+        capabilities: DelegateCapabilities,
+    ) -> Self {
+        Self::Delegate(DelegateAnnouncement::new(
+            chroma.into(),
+            delegate,
+            capabilities,
+        ))
+    }
+
+    /// A wrapper to create a [`BurnAnnouncement`] from the given arguments.
+    pub fn burn_announcement(chroma: impl Into<Chroma>, amount: u128) -> Self {
+        Self::Burn(BurnAnnouncement::new(chroma.into(), amount))
+    }
+
+    /// A wrapper to create an [`UpdateChromaAnnouncement`] from the given arguments.
+    pub fn update_chroma_announcement(
+        chroma: impl Into<Chroma>,
+        name: String,
+        symbol: String,
+    ) -> Result<Self, UpdateChromaAnnouncementParseError> {
+        Ok(Self::UpdateChroma(UpdateChromaAnnouncement::new(
+            chroma.into(),
+            name,
+            symbol,
+        )?))
+    }
 }
 
 impl fmt::Display for Announcement {
@@ -132,6 +204,10 @@ impl fmt::Display for Announcement {
             Self::Freeze(_) => write!(f, "FreezeAnnouncement"),
             Self::Issue(_) => write!(f, "IssueAnnouncement"),
             Self::TransferOwnership(_) => write!(f, "TransferOwnershipAnnouncement"),
+            Self::Burn(_) => write!(f, "BurnAnnouncement"),
+            Self::UpdateChroma(_) => write!(f, "UpdateChromaAnnouncement"),
+            Self::FreezePubkey(_) => write!(f, "FreezePubkeyAnnouncement"),
+            Self::Delegate(_) => write!(f, "DelegateAnnouncement"),
         }
     }
 }
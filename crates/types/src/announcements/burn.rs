@@ -0,0 +1,184 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use core::fmt;
+use core::mem::size_of;
+
+use crate::{activation::ProtocolFeature, network::Network, Announcement, AnyAnnouncement};
+use yuv_pixels::{Chroma, ChromaParseError, CHROMA_SIZE};
+
+use crate::announcements::{AnnouncementKind, AnnouncementParseError};
+
+/// Two bytes that represent the [`BurnAnnouncement`]'s kind.
+pub const BURN_ANNOUNCEMENT_KIND: AnnouncementKind = [0, 4];
+/// The size of burn announcement data in bytes.
+pub const BURN_ANNOUNCEMENT_SIZE: usize = CHROMA_SIZE + size_of::<u128>();
+
+/// Burn announcement. This announcement is used to declare that the transfer transaction it's
+/// included in burns the tokens, i.e. sends them to the [burn pixels]. The burnt amount is
+/// excluded from the circulating supply of the chroma.
+///
+/// Unlike other announcements, it's not valid on its own and is checked along with the proofs of
+/// the transfer: the announced amount must be equal to the sum of the burn pixels of the chroma.
+///
+/// # Structure
+///
+/// - `chroma` - 32 bytes [`Chroma`].
+/// - `amount` - 16 bytes u128 amount of burnt tokens in this transaction.
+///
+/// [burn pixels]: yuv_pixels::PixelProof::is_burn
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BurnAnnouncement {
+    /// Chroma of the burnt tokens.
+    pub chroma: Chroma,
+    /// The amount of burnt tokens in this announcement.
+    pub amount: u128,
+}
+
+impl BurnAnnouncement {
+    /// Create a new burn announcement.
+    pub fn new(chroma: Chroma, amount: u128) -> Self {
+        Self { chroma, amount }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde(name = "burn_announcement"))]
+impl AnyAnnouncement for BurnAnnouncement {
+    fn kind(&self) -> AnnouncementKind {
+        BURN_ANNOUNCEMENT_KIND
+    }
+
+    fn minimal_block_height(&self, network: Network) -> usize {
+        ProtocolFeature::BurnAnnouncement.activation_height(network)
+    }
+
+    fn from_announcement_data_bytes(data: &[u8]) -> Result<Self, AnnouncementParseError> {
+        if data.len() != BURN_ANNOUNCEMENT_SIZE {
+            return Err(BurnAnnouncementParseError::InvalidSize(data.len()))?;
+        }
+
+        let chroma =
+            Chroma::from_bytes(&data[..CHROMA_SIZE]).map_err(BurnAnnouncementParseError::from)?;
+        let amount = u128::from_le_bytes(data[CHROMA_SIZE..].try_into().unwrap());
+
+        Ok(Self { chroma, amount })
+    }
+
+    fn to_announcement_data_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BURN_ANNOUNCEMENT_SIZE);
+
+        bytes.extend_from_slice(&self.chroma.to_bytes());
+        bytes.extend_from_slice(&self.amount.to_le_bytes());
+
+        bytes
+    }
+}
+
+impl From<BurnAnnouncement> for Announcement {
+    fn from(announcement: BurnAnnouncement) -> Self {
+        Self::Burn(announcement)
+    }
+}
+
+/// Errors that can occur when parsing [`BurnAnnouncement`].
+#[derive(Debug)]
+pub enum BurnAnnouncementParseError {
+    InvalidSize(usize),
+    InvalidChroma(ChromaParseError),
+}
+
+impl fmt::Display for BurnAnnouncementParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(
+                f,
+                "invalid bytes size should be {}, got {}",
+                BURN_ANNOUNCEMENT_SIZE, size
+            ),
+            Self::InvalidChroma(e) => {
+                write!(f, "invalid chroma: {}", e)
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for BurnAnnouncementParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidChroma(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ChromaParseError> for BurnAnnouncementParseError {
+    fn from(err: ChromaParseError) -> Self {
+        Self::InvalidChroma(err)
+    }
+}
+
+impl From<BurnAnnouncementParseError> for AnnouncementParseError {
+    fn from(err: BurnAnnouncementParseError) -> Self {
+        AnnouncementParseError::InvalidAnnouncementData(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        announcements::{
+            announcement_from_script, burn::BURN_ANNOUNCEMENT_SIZE, AnnouncementParseError,
+            BurnAnnouncement,
+        },
+        Announcement, AnyAnnouncement,
+    };
+    use alloc::format;
+    use yuv_pixels::Chroma;
+
+    pub const TEST_CHROMA: &str =
+        "bcrt1p4v5dxtlzrrfuk57nxr3d6gwmtved47ulc55kcsk30h93e43ma2eqvrek30";
+
+    #[test]
+    fn test_serialize_deserialize() {
+        let test_announcement = BurnAnnouncement::new(
+            Chroma::from_address(TEST_CHROMA).expect("valid chroma"),
+            10000,
+        );
+
+        let data = test_announcement.to_announcement_data_bytes();
+        assert_eq!(
+            BurnAnnouncement::from_announcement_data_bytes(&data).unwrap(),
+            test_announcement
+        );
+
+        let announcement_script = test_announcement.to_script();
+        assert_eq!(
+            BurnAnnouncement::from_script(&announcement_script).unwrap(),
+            test_announcement
+        );
+        assert_eq!(
+            announcement_from_script(&announcement_script).unwrap(),
+            Announcement::Burn(test_announcement)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_bytes() {
+        match BurnAnnouncement::from_announcement_data_bytes(&[0]) {
+            Err(AnnouncementParseError::InvalidAnnouncementData(err)) => {
+                assert_eq!(
+                    err,
+                    format!(
+                        "invalid bytes size should be {}, got {}",
+                        BURN_ANNOUNCEMENT_SIZE, 1
+                    )
+                );
+            }
+            err => {
+                panic!("Unexpected result: {:?}", err);
+            }
+        }
+    }
+}
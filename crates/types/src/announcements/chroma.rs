@@ -1,8 +1,10 @@
-use crate::{network::Network, Announcement, AnyAnnouncement};
+use crate::{activation::ProtocolFeature, network::Network, Announcement, AnyAnnouncement};
 use alloc::string::{FromUtf8Error, String, ToString};
 use alloc::vec::Vec;
 use alloc::{format, vec};
+use bitcoin::blockdata::{opcodes::all::OP_CHECKMULTISIG, script::Builder};
 use bitcoin::consensus::{encode, ReadExt};
+use bitcoin::secp256k1::{self, constants::PUBLIC_KEY_SIZE};
 use bitcoin::ScriptBuf;
 use core::fmt;
 use core::mem::size_of;
@@ -25,9 +27,12 @@ pub const MIN_SYMBOL_SIZE: usize = 3;
 /// The minimum size of the [`ChromaAnnouncement`] in bytes.
 pub const MIN_CHROMA_ANNOUNCEMENT_SIZE: usize =
     CHROMA_SIZE + 1 + MIN_NAME_SIZE + 1 + MIN_SYMBOL_SIZE + 1 + 16 + 1;
-/// The maxim size of the [`ChromaAnnouncement`] in bytes.
+/// The maxim size of the [`ChromaAnnouncement`] without the issuer set in bytes.
 pub const MAX_CHROMA_ANNOUNCEMENT_SIZE: usize =
     CHROMA_SIZE + 1 + MAX_NAME_SIZE + 1 + MAX_SYMBOL_SIZE + 1 + 16 + 1;
+/// The maximum number of the issuers in [`ChromaIssuers`], so the announcement with the issuer set
+/// still fits into the single 520 bytes push of the `OP_RETURN` script.
+pub const MAX_ISSUERS: usize = 13;
 
 /// Chroma's initial announcement from the issuer. It contains the information about the token and
 /// issuer.
@@ -41,6 +46,10 @@ pub const MAX_CHROMA_ANNOUNCEMENT_SIZE: usize =
 /// - `decimal` - 1 byte number of decimal places for the token (u8).
 /// - `max_supply` - 16 bytes maximum supply of the token (u128).
 /// - `is_freezable` - 1 byte indicates whether the token can be freezed or not by the issuer (bool).
+/// - `issuers` - optional m-of-n issuer set:
+///     - `threshold` - 1 byte number of the issuers' signatures required (m).
+///     - `issuers_number` - 1 byte number of the issuers (n).
+///     - `pubkeys` - n * 33 bytes compressed public keys of the issuers.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChromaAnnouncement {
@@ -57,14 +66,111 @@ pub struct ChromaAnnouncement {
     pub max_supply: u128,
     /// Indicates whether the token can be freezed or not by the issuer.
     pub is_freezable: bool,
+    /// The m-of-n issuer set that owns the chroma after the announcement. If set, the issuance,
+    /// freeze and ownership transfer announcements have to be signed by the threshold of the
+    /// issuers.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub issuers: Option<ChromaIssuers>,
 }
 
+/// The m-of-n set of the issuers that own the chroma.
+///
+/// The chroma's owner becomes the P2WSH of the `sortedmulti` script of the issuers' keys, so the
+/// owner input of the announcements is spent only with the signatures of `threshold` issuers.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChromaIssuers {
+    /// The number of the issuers' signatures required to sign the announcement.
+    pub threshold: u8,
+    /// The public keys of the issuers.
+    pub pubkeys: Vec<secp256k1::PublicKey>,
+}
+
+impl ChromaIssuers {
+    /// Create a new [`ChromaIssuers`] set, which requires `threshold` signatures of `pubkeys`.
+    pub fn new(
+        threshold: u8,
+        pubkeys: Vec<secp256k1::PublicKey>,
+    ) -> Result<Self, ChromaAnnouncementParseError> {
+        if pubkeys.is_empty() || pubkeys.len() > MAX_ISSUERS {
+            return Err(ChromaAnnouncementParseError::InvalidIssuersNumber);
+        }
+
+        if threshold == 0 || threshold as usize > pubkeys.len() {
+            return Err(ChromaAnnouncementParseError::InvalidIssuersThreshold);
+        }
+
+        for (i, pubkey) in pubkeys.iter().enumerate() {
+            if pubkeys[..i].contains(pubkey) {
+                return Err(ChromaAnnouncementParseError::DuplicateIssuer);
+            }
+        }
+
+        Ok(Self { threshold, pubkeys })
+    }
+
+    /// The `sortedmulti` script of the issuers: the keys are sorted by their serialization, as in
+    /// the `wsh(sortedmulti(...))` descriptors.
+    pub fn redeem_script(&self) -> ScriptBuf {
+        let mut pubkeys = self
+            .pubkeys
+            .iter()
+            .map(|pubkey| pubkey.serialize())
+            .collect::<Vec<_>>();
+        pubkeys.sort_unstable();
+
+        let mut builder = Builder::new().push_int(self.threshold as i64);
+        for pubkey in pubkeys {
+            builder = builder.push_slice(pubkey);
+        }
+
+        builder
+            .push_int(self.pubkeys.len() as i64)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script()
+    }
+
+    /// The script of the chroma's owner: the P2WSH of the [`ChromaIssuers::redeem_script`].
+    pub fn owner_script(&self) -> ScriptBuf {
+        ScriptBuf::new_v0_p2wsh(&self.redeem_script().wscript_hash())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ChromaInfo {
     pub announcement: Option<ChromaAnnouncement>,
     pub total_supply: u128,
     pub owner: Option<ScriptBuf>,
+    /// Total amount of the tokens burnt with the [`BurnAnnouncement`]s. The circulating supply
+    /// is `total_supply - total_burned`.
+    ///
+    /// [`BurnAnnouncement`]: crate::announcements::BurnAnnouncement
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub total_burned: u128,
+    /// Number of the [`UpdateChromaAnnouncement`]s applied to the `announcement`. The initial
+    /// announcement has the version 0.
+    ///
+    /// [`UpdateChromaAnnouncement`]: crate::announcements::UpdateChromaAnnouncement
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub announcement_version: u32,
+}
+
+impl ChromaInfo {
+    /// Amount of the tokens that can still be issued, i.e. the max supply of the announcement
+    /// minus the total supply. `None` if the supply isn't limited, i.e. the chroma isn't
+    /// announced or its max supply is zero.
+    pub fn remaining_supply(&self) -> Option<u128> {
+        let max_supply = self.announcement.as_ref()?.max_supply;
+        if max_supply == 0 {
+            return None;
+        }
+
+        Some(max_supply.saturating_sub(self.total_supply))
+    }
 }
 
 impl ChromaAnnouncement {
@@ -92,10 +198,17 @@ impl ChromaAnnouncement {
             decimal,
             max_supply,
             is_freezable,
+            issuers: None,
         };
 
         Ok(result)
     }
+
+    /// Declare the m-of-n issuer set that owns the chroma.
+    pub fn with_issuers(mut self, issuers: ChromaIssuers) -> Self {
+        self.issuers = Some(issuers);
+        self
+    }
 }
 
 #[cfg_attr(feature = "serde", typetag::serde(name = "chroma_announcement"))]
@@ -104,7 +217,11 @@ impl AnyAnnouncement for ChromaAnnouncement {
         CHROMA_ANNOUNCEMENT_KIND
     }
 
-    fn minimal_block_height(&self, _network: Network) -> usize {
+    fn minimal_block_height(&self, network: Network) -> usize {
+        if self.issuers.is_some() {
+            return ProtocolFeature::MultisigIssuers.activation_height(network);
+        }
+
         // For the default, innitial announcements, there is no minimal block height.
         0
     }
@@ -178,6 +295,13 @@ impl AnyAnnouncement for ChromaAnnouncement {
             .read_u8()
             .map_err(|err| wrap_io_error(err, "failed to read is freezable"))?;
 
+        // Read the optional issuer set
+        let issuers = if (cursor.position() as usize) < data.len() {
+            Some(read_issuers(&mut cursor)?)
+        } else {
+            None
+        };
+
         let announcement = ChromaAnnouncement {
             chroma,
             name,
@@ -185,6 +309,7 @@ impl AnyAnnouncement for ChromaAnnouncement {
             decimal,
             max_supply,
             is_freezable: is_freezable != 0,
+            issuers,
         };
 
         Ok(announcement)
@@ -202,10 +327,49 @@ impl AnyAnnouncement for ChromaAnnouncement {
         result.extend_from_slice(&self.max_supply.to_le_bytes());
         result.push(if self.is_freezable { 1 } else { 0 });
 
+        if let Some(issuers) = &self.issuers {
+            result.push(issuers.threshold);
+            result.push(issuers.pubkeys.len() as u8);
+            for pubkey in &issuers.pubkeys {
+                result.extend_from_slice(&pubkey.serialize());
+            }
+        }
+
         result
     }
 }
 
+/// Read the issuer set that follows the chroma's info in the announcement.
+fn read_issuers(cursor: &mut Cursor<&[u8]>) -> Result<ChromaIssuers, ChromaAnnouncementParseError> {
+    let threshold = cursor
+        .read_u8()
+        .map_err(|err| wrap_io_error(err, "failed to read the issuers threshold"))?;
+
+    let issuers_number = cursor
+        .read_u8()
+        .map_err(|err| wrap_io_error(err, "failed to read the issuers number"))?
+        as usize;
+
+    if issuers_number == 0 || issuers_number > MAX_ISSUERS {
+        return Err(ChromaAnnouncementParseError::InvalidIssuersNumber);
+    }
+
+    let mut pubkeys = Vec::with_capacity(issuers_number);
+    for _ in 0..issuers_number {
+        let mut pubkey_bytes = [0u8; PUBLIC_KEY_SIZE];
+        cursor
+            .read_exact(&mut pubkey_bytes)
+            .map_err(|err| wrap_io_error(err, "failed to read the issuer public key"))?;
+
+        pubkeys.push(
+            secp256k1::PublicKey::from_slice(&pubkey_bytes)
+                .map_err(ChromaAnnouncementParseError::InvalidIssuerPubkey)?,
+        );
+    }
+
+    ChromaIssuers::new(threshold, pubkeys)
+}
+
 impl From<ChromaAnnouncement> for Announcement {
     fn from(value: ChromaAnnouncement) -> Self {
         Self::Chroma(value)
@@ -228,6 +392,14 @@ pub enum ChromaAnnouncementParseError {
     InvalidNameLength,
     /// Invalid chroma.
     InvalidChroma(ChromaParseError),
+    /// The number of the issuers is zero or more than [`MAX_ISSUERS`].
+    InvalidIssuersNumber,
+    /// The threshold of the issuers is zero or more than the number of the issuers.
+    InvalidIssuersThreshold,
+    /// The same public key is declared as the issuer twice.
+    DuplicateIssuer,
+    /// Invalid public key of the issuer.
+    InvalidIssuerPubkey(secp256k1::Error),
 }
 
 #[cfg(not(feature = "no-std"))]
@@ -236,6 +408,7 @@ impl std::error::Error for ChromaAnnouncementParseError {
         match self {
             Self::InvalidUtf8String(e) => Some(e),
             Self::InvalidChroma(e) => Some(e),
+            Self::InvalidIssuerPubkey(e) => Some(e),
             _ => None,
         }
     }
@@ -262,6 +435,17 @@ impl fmt::Display for ChromaAnnouncementParseError {
                 "the length of the name is invalid, it must be between {} and {}",
                 MIN_NAME_SIZE, MAX_NAME_SIZE
             ),
+            Self::InvalidIssuersNumber => write!(
+                _f,
+                "the number of the issuers is invalid, it must be between 1 and {}",
+                MAX_ISSUERS
+            ),
+            Self::InvalidIssuersThreshold => write!(
+                _f,
+                "the threshold of the issuers is invalid, it must be between 1 and the number of the issuers"
+            ),
+            Self::DuplicateIssuer => write!(_f, "the issuers must be unique"),
+            Self::InvalidIssuerPubkey(e) => write!(_f, "invalid issuer public key: {}", e),
         }
     }
 }
@@ -307,6 +491,7 @@ mod tests {
     use crate::announcements::{announcement_from_bytes, announcement_from_script};
     use alloc::string::ToString;
     use bitcoin::ScriptBuf;
+    use core::str::FromStr;
 
     pub const TEST_CHROMA: &str =
         "bcrt1p4v5dxtlzrrfuk57nxr3d6gwmtved47ulc55kcsk30h93e43ma2eqvrek30";
@@ -327,6 +512,7 @@ mod tests {
                     decimal: 2,
                     max_supply: 1_000_000,
                     is_freezable: true,
+                    issuers: None,
                 },
                 expect_error: false,
             },
@@ -338,6 +524,7 @@ mod tests {
                     decimal: 255,
                     max_supply: 18_446_744_073_709_551_615,
                     is_freezable: true,
+                    issuers: None,
                 },
                 expect_error: false,
             },
@@ -349,6 +536,7 @@ mod tests {
                     decimal: 2,
                     max_supply: 1_000_000,
                     is_freezable: false,
+                    issuers: None,
                 },
                 expect_error: false,
             },
@@ -360,6 +548,7 @@ mod tests {
                     decimal: 2,
                     max_supply: 1_000_000,
                     is_freezable: true,
+                    issuers: None,
                 },
                 expect_error: true,
             },
@@ -371,6 +560,7 @@ mod tests {
                     decimal: 2,
                     max_supply: 1_000_000,
                     is_freezable: true,
+                    issuers: None,
                 },
                 expect_error: true,
             },
@@ -382,6 +572,7 @@ mod tests {
                     decimal: 2,
                     max_supply: 1_000_000,
                     is_freezable: true,
+                    issuers: None,
                 },
                 expect_error: true,
             },
@@ -393,6 +584,7 @@ mod tests {
                     decimal: 2,
                     max_supply: 1_000_000,
                     is_freezable: true,
+                    issuers: None,
                 },
                 expect_error: true,
             },
@@ -477,6 +669,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serialize_deserialize_issuers() {
+        let pubkeys = [
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+        ]
+        .iter()
+        .map(|pubkey| secp256k1::PublicKey::from_str(pubkey).expect("valid pubkey"))
+        .collect::<Vec<_>>();
+
+        let announcement = ChromaAnnouncement::new(
+            Chroma::from_address(TEST_CHROMA).expect("valid chroma"),
+            "TokenName".to_string(),
+            "TNK".to_string(),
+            2,
+            1_000_000,
+            true,
+        )
+        .unwrap()
+        .with_issuers(ChromaIssuers::new(2, pubkeys.clone()).unwrap());
+
+        let data = announcement.to_announcement_data_bytes();
+        assert_eq!(
+            ChromaAnnouncement::from_announcement_data_bytes(&data).unwrap(),
+            announcement
+        );
+        assert_eq!(
+            ChromaAnnouncement::from_script(&announcement.to_script()).unwrap(),
+            announcement
+        );
+
+        let mut reversed = pubkeys.clone();
+        reversed.reverse();
+        assert_eq!(
+            ChromaIssuers::new(2, reversed).unwrap().owner_script(),
+            announcement.issuers.unwrap().owner_script(),
+            "owner script should not depend on the order of the issuers"
+        );
+
+        assert!(ChromaIssuers::new(0, pubkeys.clone()).is_err());
+        assert!(ChromaIssuers::new(4, pubkeys.clone()).is_err());
+        assert!(ChromaIssuers::new(1, vec![pubkeys[0], pubkeys[0]]).is_err());
+        assert!(ChromaIssuers::new(1, Vec::new()).is_err());
+    }
+
     #[test]
     fn test_backward_compatibility() {
         struct TestData {
@@ -508,4 +746,33 @@ mod tests {
             assert!(ChromaAnnouncement::from_script(&announcement.script).is_ok());
         }
     }
+
+    #[test]
+    fn test_remaining_supply() {
+        let announcement = ChromaAnnouncement::new(
+            Chroma::from_address(TEST_CHROMA).expect("valid chroma"),
+            "Test Token".to_string(),
+            "TST".to_string(),
+            2,
+            1_000,
+            true,
+        )
+        .expect("valid announcement");
+
+        let mut info = ChromaInfo {
+            announcement: Some(announcement),
+            total_supply: 400,
+            ..Default::default()
+        };
+        assert_eq!(info.remaining_supply(), Some(600));
+
+        info.total_supply = 1_000;
+        assert_eq!(info.remaining_supply(), Some(0));
+
+        info.announcement.as_mut().unwrap().max_supply = 0;
+        assert_eq!(info.remaining_supply(), None);
+
+        info.announcement = None;
+        assert_eq!(info.remaining_supply(), None);
+    }
 }
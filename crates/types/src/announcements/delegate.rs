@@ -0,0 +1,317 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use bitcoin::secp256k1::{self, constants::PUBLIC_KEY_SIZE};
+use core::fmt;
+use core::ops::BitOr;
+use yuv_pixels::{Chroma, ChromaParseError, CHROMA_SIZE};
+
+use crate::{activation::ProtocolFeature, network::Network, Announcement, AnyAnnouncement};
+
+use crate::announcements::{AnnouncementKind, AnnouncementParseError};
+
+/// Two bytes that represent the [`DelegateAnnouncement`]'s kind.
+pub const DELEGATE_ANNOUNCEMENT_KIND: AnnouncementKind = [0, 7];
+/// Size of the [`DelegateAnnouncement`] in bytes.
+pub const DELEGATE_ANNOUNCEMENT_SIZE: usize = CHROMA_SIZE + PUBLIC_KEY_SIZE + 1;
+
+/// Set of the chroma's capabilities the owner grants to the delegate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct DelegateCapabilities(u8);
+
+impl DelegateCapabilities {
+    /// No capabilities, the delegation is revoked.
+    pub const NONE: Self = Self(0);
+    /// Freeze the chroma's outputs and public keys.
+    pub const FREEZE: Self = Self(1 << 0);
+    /// Unfreeze the chroma's public keys.
+    pub const UNFREEZE: Self = Self(1 << 1);
+    /// All the known capabilities.
+    pub const ALL: Self = Self(Self::FREEZE.0 | Self::UNFREEZE.0);
+
+    /// Returns the capabilities from the bits, or `None` if some of the bits are unknown.
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        (bits & !Self::ALL.0 == 0).then_some(Self(bits))
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Return `true` if all the `other` capabilities are granted.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Return `true` if no capabilities are granted.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for DelegateCapabilities {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Display for DelegateCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "none");
+        }
+
+        let names = [(Self::FREEZE, "freeze"), (Self::UNFREEZE, "unfreeze")]
+            .into_iter()
+            .filter(|(capability, _)| self.contains(*capability))
+            .map(|(_, name)| name)
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", names.join(","))
+    }
+}
+
+/// Delegation of the chroma's capabilities to the public key, declared by the owner of the chroma.
+///
+/// The delegate can freeze or unfreeze the chroma's pixels, depending on the granted
+/// capabilities, by signing the announcement transaction with the P2WPKH input of the key, while
+/// the ownership of the chroma stays with the owner. The announcement replaces the capabilities
+/// previously granted to the same key, so the delegation is revoked by the empty capabilities.
+///
+/// # Structure
+///
+/// - `chroma` - 32 bytes [`Chroma`].
+/// - `delegate` - 33 bytes compressed public key of the delegate.
+/// - `capabilities` - 1 byte [`DelegateCapabilities`] bits.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DelegateAnnouncement {
+    /// The chroma whose capabilities are delegated.
+    pub chroma: Chroma,
+    /// The public key the capabilities are granted to.
+    pub delegate: secp256k1::PublicKey,
+    /// The granted capabilities.
+    pub capabilities: DelegateCapabilities,
+}
+
+impl DelegateAnnouncement {
+    /// Create a new announcement that grants the `capabilities` to the `delegate`.
+    pub fn new(
+        chroma: Chroma,
+        delegate: secp256k1::PublicKey,
+        capabilities: DelegateCapabilities,
+    ) -> Self {
+        Self {
+            chroma,
+            delegate,
+            capabilities,
+        }
+    }
+
+    /// Create a new announcement that revokes all the capabilities of the `delegate`.
+    pub fn new_revoke(chroma: Chroma, delegate: secp256k1::PublicKey) -> Self {
+        Self::new(chroma, delegate, DelegateCapabilities::NONE)
+    }
+
+    /// Return `true` if the announcement revokes the delegation.
+    pub fn is_revoke(&self) -> bool {
+        self.capabilities.is_empty()
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde(name = "delegate_announcement"))]
+impl AnyAnnouncement for DelegateAnnouncement {
+    fn kind(&self) -> AnnouncementKind {
+        DELEGATE_ANNOUNCEMENT_KIND
+    }
+
+    fn minimal_block_height(&self, network: Network) -> usize {
+        ProtocolFeature::DelegateAnnouncement.activation_height(network)
+    }
+
+    fn from_announcement_data_bytes(data: &[u8]) -> Result<Self, AnnouncementParseError> {
+        if data.len() != DELEGATE_ANNOUNCEMENT_SIZE {
+            return Err(DelegateAnnouncementParseError::InvalidSize(data.len()))?;
+        }
+
+        let chroma = Chroma::from_bytes(&data[..CHROMA_SIZE])
+            .map_err(DelegateAnnouncementParseError::from)?;
+
+        let delegate =
+            secp256k1::PublicKey::from_slice(&data[CHROMA_SIZE..CHROMA_SIZE + PUBLIC_KEY_SIZE])
+                .map_err(DelegateAnnouncementParseError::from)?;
+
+        let bits = data[CHROMA_SIZE + PUBLIC_KEY_SIZE];
+        let capabilities = DelegateCapabilities::from_bits(bits)
+            .ok_or(DelegateAnnouncementParseError::InvalidCapabilities(bits))?;
+
+        Ok(Self {
+            chroma,
+            delegate,
+            capabilities,
+        })
+    }
+
+    fn to_announcement_data_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(DELEGATE_ANNOUNCEMENT_SIZE);
+
+        bytes.extend_from_slice(&self.chroma.to_bytes());
+        bytes.extend_from_slice(&self.delegate.serialize());
+        bytes.push(self.capabilities.bits());
+
+        bytes
+    }
+}
+
+impl From<DelegateAnnouncement> for Announcement {
+    fn from(value: DelegateAnnouncement) -> Self {
+        Self::Delegate(value)
+    }
+}
+
+/// Errors that can occur when parsing [`DelegateAnnouncement`].
+#[derive(Debug)]
+pub enum DelegateAnnouncementParseError {
+    /// The size of the announcement data is not [`DELEGATE_ANNOUNCEMENT_SIZE`].
+    InvalidSize(usize),
+    /// Invalid chroma.
+    InvalidChroma(ChromaParseError),
+    /// Invalid public key of the delegate.
+    InvalidPubkey(secp256k1::Error),
+    /// The capabilities byte has unknown bits set.
+    InvalidCapabilities(u8),
+}
+
+impl fmt::Display for DelegateAnnouncementParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(
+                f,
+                "invalid bytes size should be {}, got {}",
+                DELEGATE_ANNOUNCEMENT_SIZE, size
+            ),
+            Self::InvalidChroma(e) => write!(f, "invalid chroma: {}", e),
+            Self::InvalidPubkey(e) => write!(f, "invalid public key: {}", e),
+            Self::InvalidCapabilities(bits) => {
+                write!(f, "invalid delegate capabilities: {:#04x}", bits)
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for DelegateAnnouncementParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidChroma(e) => Some(e),
+            Self::InvalidPubkey(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ChromaParseError> for DelegateAnnouncementParseError {
+    fn from(err: ChromaParseError) -> Self {
+        Self::InvalidChroma(err)
+    }
+}
+
+impl From<secp256k1::Error> for DelegateAnnouncementParseError {
+    fn from(err: secp256k1::Error) -> Self {
+        Self::InvalidPubkey(err)
+    }
+}
+
+impl From<DelegateAnnouncementParseError> for AnnouncementParseError {
+    fn from(err: DelegateAnnouncementParseError) -> Self {
+        AnnouncementParseError::InvalidAnnouncementData(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::announcements::delegate::DELEGATE_ANNOUNCEMENT_SIZE;
+    use crate::announcements::{
+        announcement_from_script, AnnouncementParseError, DelegateAnnouncement,
+        DelegateCapabilities,
+    };
+    use crate::{Announcement, AnyAnnouncement};
+    use alloc::format;
+    use alloc::string::ToString;
+    use bitcoin::secp256k1::PublicKey;
+    use core::str::FromStr;
+    use yuv_pixels::Chroma;
+
+    pub const TEST_CHROMA: &str =
+        "bcrt1p4v5dxtlzrrfuk57nxr3d6gwmtved47ulc55kcsk30h93e43ma2eqvrek30";
+    pub const TEST_PUBKEY: &str =
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_serialize_deserialize() {
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+        let pubkey = PublicKey::from_str(TEST_PUBKEY).expect("valid pubkey");
+
+        for announcement in [
+            DelegateAnnouncement::new(chroma, pubkey, DelegateCapabilities::FREEZE),
+            DelegateAnnouncement::new(chroma, pubkey, DelegateCapabilities::ALL),
+            DelegateAnnouncement::new_revoke(chroma, pubkey),
+        ] {
+            let data_bytes = announcement.to_announcement_data_bytes();
+            assert_eq!(data_bytes.len(), DELEGATE_ANNOUNCEMENT_SIZE);
+            assert_eq!(
+                DelegateAnnouncement::from_announcement_data_bytes(&data_bytes).unwrap(),
+                announcement
+            );
+
+            let parsed_announcement = announcement_from_script(&announcement.to_script()).unwrap();
+            assert_eq!(Announcement::Delegate(announcement), parsed_announcement);
+        }
+    }
+
+    #[test]
+    fn parse_invalid_bytes() {
+        match DelegateAnnouncement::from_announcement_data_bytes(&[0]) {
+            Err(AnnouncementParseError::InvalidAnnouncementData(err)) => {
+                assert_eq!(
+                    err,
+                    format!(
+                        "invalid bytes size should be {}, got 1",
+                        DELEGATE_ANNOUNCEMENT_SIZE
+                    )
+                );
+            }
+            err => panic!("Unexpected result: {:?}", err),
+        }
+
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+        let pubkey = PublicKey::from_str(TEST_PUBKEY).expect("valid pubkey");
+        let mut data_bytes =
+            DelegateAnnouncement::new_revoke(chroma, pubkey).to_announcement_data_bytes();
+        *data_bytes.last_mut().unwrap() = 0b100;
+
+        match DelegateAnnouncement::from_announcement_data_bytes(&data_bytes) {
+            Err(AnnouncementParseError::InvalidAnnouncementData(err)) => {
+                assert_eq!(err, "invalid delegate capabilities: 0x04");
+            }
+            err => panic!("Unexpected result: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = DelegateCapabilities::FREEZE | DelegateCapabilities::UNFREEZE;
+
+        assert_eq!(capabilities, DelegateCapabilities::ALL);
+        assert!(capabilities.contains(DelegateCapabilities::FREEZE));
+        assert!(!DelegateCapabilities::FREEZE.contains(DelegateCapabilities::UNFREEZE));
+        assert!(DelegateCapabilities::NONE.is_empty());
+        assert_eq!(DelegateCapabilities::from_bits(0b11), Some(capabilities));
+        assert_eq!(DelegateCapabilities::from_bits(0b1000), None);
+        assert_eq!(capabilities.to_string(), "freeze,unfreeze");
+    }
+}
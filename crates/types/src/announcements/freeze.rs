@@ -4,7 +4,7 @@ use core::fmt;
 use core::mem::size_of;
 use yuv_pixels::{Chroma, ChromaParseError, CHROMA_SIZE};
 
-use crate::{network::Network, Announcement, AnyAnnouncement};
+use crate::{activation::ProtocolFeature, network::Network, Announcement, AnyAnnouncement};
 use bitcoin::hashes::Hash;
 use bitcoin::{OutPoint, Txid};
 
@@ -18,8 +18,19 @@ pub const FREEZE_ANNOUNCEMENT_KIND: AnnouncementKind = [0, 1];
 const TX_ID_SIZE: usize = size_of::<Txid>();
 /// Size of vout in bytes.
 const VOUT_SIZE: usize = size_of::<u32>();
+/// Size of amount in bytes.
+const AMOUNT_SIZE: usize = size_of::<u128>();
+/// Size of expiry height in bytes.
+const EXPIRY_HEIGHT_SIZE: usize = size_of::<u32>();
 /// Size of freeze entry in bytes.
 pub const FREEZE_ENTRY_SIZE: usize = TX_ID_SIZE + VOUT_SIZE + CHROMA_SIZE;
+/// Size of partial freeze entry in bytes.
+pub const PARTIAL_FREEZE_ENTRY_SIZE: usize = FREEZE_ENTRY_SIZE + AMOUNT_SIZE;
+/// Size of expirable freeze entry in bytes.
+pub const EXPIRABLE_FREEZE_ENTRY_SIZE: usize = FREEZE_ENTRY_SIZE + EXPIRY_HEIGHT_SIZE;
+/// Size of expirable partial freeze entry in bytes.
+pub const EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE: usize =
+    PARTIAL_FREEZE_ENTRY_SIZE + EXPIRY_HEIGHT_SIZE;
 
 /// Freeze announcement. It appears when issuer declares that tx is frozen.
 ///
@@ -28,6 +39,10 @@ pub const FREEZE_ENTRY_SIZE: usize = TX_ID_SIZE + VOUT_SIZE + CHROMA_SIZE;
 /// - `txid` - 32 bytes [`Txid`] of the frozen transaction.
 /// - `vout` - 4 bytes u32 number of the transaction's output that is frozen.
 /// - `chroma` - 32 bytes [`Chroma`].
+/// - `amount` - optional 16 bytes little-endian u128 amount of the frozen tokens for partial
+/// freezes, encoded as the amounts of the other announcements.
+/// - `expiry_height` - optional 4 bytes u32 height of the block starting from which the freeze is
+/// no longer active.
 ///
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -36,12 +51,65 @@ pub struct FreezeAnnouncement {
     pub chroma: Chroma,
     /// The outpoint of the transaction that is frozen.
     pub outpoint: OutPoint,
+    /// The amount of tokens that is frozen. If not set, the whole output is frozen.
+    ///
+    /// Only the remainder of the partially frozen output can be spent, while the frozen amount
+    /// is kept in the continuation output owned by the same key.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub amount: Option<u128>,
+    /// The height of the block starting from which the output is unfrozen. If not set, the output
+    /// is frozen forever.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub expiry_height: Option<u32>,
 }
 
 impl FreezeAnnouncement {
     /// Create a new freeze announcement.
     pub fn new(chroma: Chroma, outpoint: OutPoint) -> Self {
-        Self { chroma, outpoint }
+        Self {
+            chroma,
+            outpoint,
+            amount: None,
+            expiry_height: None,
+        }
+    }
+
+    /// Create a new freeze announcement that freezes only the given amount of the output.
+    pub fn new_partial(chroma: Chroma, outpoint: OutPoint, amount: u128) -> Self {
+        Self {
+            chroma,
+            outpoint,
+            amount: Some(amount),
+            expiry_height: None,
+        }
+    }
+
+    /// Set the height of the block starting from which the output is unfrozen.
+    pub fn with_expiry_height(mut self, expiry_height: u32) -> Self {
+        self.expiry_height = Some(expiry_height);
+        self
+    }
+
+    /// Return `true` if only the part of the output is frozen.
+    pub fn is_partial(&self) -> bool {
+        self.amount.is_some()
+    }
+
+    /// Return `true` if the freeze expires at some block height.
+    pub fn is_expirable(&self) -> bool {
+        self.expiry_height.is_some()
+    }
+
+    /// Return `true` if the freeze is no longer active at the given block height.
+    pub fn is_expired(&self, height: usize) -> bool {
+        self.expiry_height
+            .is_some_and(|expiry_height| height >= expiry_height as usize)
     }
 
     /// Return the transaction id of the frozen transaction.
@@ -66,15 +134,29 @@ impl AnyAnnouncement for FreezeAnnouncement {
         FREEZE_ANNOUNCEMENT_KIND
     }
 
-    fn minimal_block_height(&self, _network: Network) -> usize {
+    fn minimal_block_height(&self, network: Network) -> usize {
+        let mut height = 0;
+
+        if self.is_partial() {
+            height = height.max(ProtocolFeature::PartialFreezes.activation_height(network));
+        }
+
+        if self.is_expirable() {
+            height = height.max(ProtocolFeature::ExpirableFreezes.activation_height(network));
+        }
+
         // For the default, innitial announcements, there is no minimal block height.
-        0
+        height
     }
 
     fn from_announcement_data_bytes(data: &[u8]) -> Result<Self, AnnouncementParseError> {
-        if data.len() != FREEZE_ENTRY_SIZE {
-            return Err(FreezeAnnouncementParseError::InvalidSize(data.len()))?;
-        }
+        let (is_partial, is_expirable) = match data.len() {
+            FREEZE_ENTRY_SIZE => (false, false),
+            PARTIAL_FREEZE_ENTRY_SIZE => (true, false),
+            EXPIRABLE_FREEZE_ENTRY_SIZE => (false, true),
+            EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE => (true, true),
+            size => return Err(FreezeAnnouncementParseError::InvalidSize(size))?,
+        };
 
         let txid = Txid::from_slice(&data[..TX_ID_SIZE])
             .map_err(FreezeAnnouncementParseError::InvalidTxHash)?;
@@ -86,19 +168,48 @@ impl AnyAnnouncement for FreezeAnnouncement {
 
         let outpoint = OutPoint::new(txid, vout);
 
-        let chroma = Chroma::from_bytes(&data[TX_ID_SIZE + VOUT_SIZE..])
+        let chroma = Chroma::from_bytes(&data[TX_ID_SIZE + VOUT_SIZE..FREEZE_ENTRY_SIZE])
             .map_err(FreezeAnnouncementParseError::from)?;
 
-        Ok(Self { chroma, outpoint })
+        let amount = is_partial.then(|| {
+            u128::from_le_bytes(
+                data[FREEZE_ENTRY_SIZE..PARTIAL_FREEZE_ENTRY_SIZE]
+                    .try_into()
+                    .expect("Size is checked"),
+            )
+        });
+
+        let expiry_height = is_expirable.then(|| {
+            u32::from_be_bytes(
+                data[data.len() - EXPIRY_HEIGHT_SIZE..]
+                    .try_into()
+                    .expect("Size is checked"),
+            )
+        });
+
+        Ok(Self {
+            chroma,
+            outpoint,
+            amount,
+            expiry_height,
+        })
     }
 
     fn to_announcement_data_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(FREEZE_ENTRY_SIZE);
+        let mut bytes = Vec::with_capacity(EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE);
 
         bytes.extend_from_slice(&self.outpoint.txid[..]);
         bytes.extend_from_slice(&self.outpoint.vout.to_be_bytes());
         bytes.extend_from_slice(&self.chroma.to_bytes());
 
+        if let Some(amount) = self.amount {
+            bytes.extend_from_slice(&amount.to_le_bytes());
+        }
+
+        if let Some(expiry_height) = self.expiry_height {
+            bytes.extend_from_slice(&expiry_height.to_be_bytes());
+        }
+
         bytes
     }
 }
@@ -124,8 +235,12 @@ impl fmt::Display for FreezeAnnouncementParseError {
         match self {
             FreezeAnnouncementParseError::InvalidSize(size) => write!(
                 f,
-                "invalid bytes size should be {}, got {}",
-                FREEZE_ENTRY_SIZE, size
+                "invalid bytes size should be {}, {}, {} or {}, got {}",
+                FREEZE_ENTRY_SIZE,
+                EXPIRABLE_FREEZE_ENTRY_SIZE,
+                PARTIAL_FREEZE_ENTRY_SIZE,
+                EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE,
+                size
             ),
             FreezeAnnouncementParseError::InvalidTxHash(e) => write!(f, "invalid tx hash: {}", e),
             FreezeAnnouncementParseError::InvalidChroma(e) => {
@@ -165,7 +280,10 @@ impl From<FreezeAnnouncementParseError> for AnnouncementParseError {
 
 #[cfg(test)]
 mod test {
-    use crate::announcements::freeze::FREEZE_ENTRY_SIZE;
+    use crate::announcements::freeze::{
+        EXPIRABLE_FREEZE_ENTRY_SIZE, EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE, FREEZE_ENTRY_SIZE,
+        PARTIAL_FREEZE_ENTRY_SIZE,
+    };
     use crate::announcements::{
         announcement_from_bytes, announcement_from_script, AnnouncementParseError,
         FreezeAnnouncement,
@@ -191,7 +309,7 @@ mod test {
 
         let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
 
-        let announcement = FreezeAnnouncement { chroma, outpoint };
+        let announcement = FreezeAnnouncement::new(chroma, outpoint);
 
         let data_bytes = announcement.to_announcement_data_bytes();
         let parsed_announcement =
@@ -208,6 +326,68 @@ mod test {
         assert_eq!(Announcement::Freeze(announcement), parsed_announcement);
     }
 
+    #[test]
+    fn test_serialize_deserialize_partial() {
+        let outpoint = OutPoint {
+            txid: Txid::from_str(TEST_TXID).unwrap(),
+            vout: 34,
+        };
+
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+
+        let announcement = FreezeAnnouncement::new_partial(chroma, outpoint, 1000);
+
+        let data_bytes = announcement.to_announcement_data_bytes();
+        assert_eq!(data_bytes.len(), PARTIAL_FREEZE_ENTRY_SIZE);
+        assert_eq!(
+            data_bytes[FREEZE_ENTRY_SIZE..],
+            1000u128.to_le_bytes(),
+            "amount is little-endian"
+        );
+
+        let parsed_announcement =
+            FreezeAnnouncement::from_announcement_data_bytes(&data_bytes).unwrap();
+        assert_eq!(announcement, parsed_announcement);
+        assert_eq!(parsed_announcement.amount, Some(1000));
+
+        let parsed_announcement = announcement_from_script(&announcement.to_script()).unwrap();
+        assert_eq!(Announcement::Freeze(announcement), parsed_announcement);
+    }
+
+    #[test]
+    fn test_serialize_deserialize_expirable() {
+        let outpoint = OutPoint {
+            txid: Txid::from_str(TEST_TXID).unwrap(),
+            vout: 34,
+        };
+
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+
+        for (announcement, size) in [
+            (
+                FreezeAnnouncement::new(chroma, outpoint).with_expiry_height(900_000),
+                EXPIRABLE_FREEZE_ENTRY_SIZE,
+            ),
+            (
+                FreezeAnnouncement::new_partial(chroma, outpoint, 1000).with_expiry_height(900_000),
+                EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE,
+            ),
+        ] {
+            let data_bytes = announcement.to_announcement_data_bytes();
+            assert_eq!(data_bytes.len(), size);
+
+            let parsed_announcement =
+                FreezeAnnouncement::from_announcement_data_bytes(&data_bytes).unwrap();
+            assert_eq!(announcement, parsed_announcement);
+            assert_eq!(parsed_announcement.expiry_height, Some(900_000));
+            assert!(!parsed_announcement.is_expired(899_999));
+            assert!(parsed_announcement.is_expired(900_000));
+
+            let parsed_announcement = announcement_from_script(&announcement.to_script()).unwrap();
+            assert_eq!(Announcement::Freeze(announcement), parsed_announcement);
+        }
+    }
+
     #[test]
     fn parse_invalid_bytes() {
         struct TestData {
@@ -218,13 +398,25 @@ mod test {
         let test_vector = vec![
             TestData {
                 bytes: vec![0],
-                err: format!("invalid bytes size should be {}, got 1", FREEZE_ENTRY_SIZE)
-                    .to_string(),
+                err: format!(
+                    "invalid bytes size should be {}, {}, {} or {}, got 1",
+                    FREEZE_ENTRY_SIZE,
+                    EXPIRABLE_FREEZE_ENTRY_SIZE,
+                    PARTIAL_FREEZE_ENTRY_SIZE,
+                    EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE
+                )
+                .to_string(),
             },
             TestData {
                 bytes: vec![0; 37],
-                err: format!("invalid bytes size should be {}, got 37", FREEZE_ENTRY_SIZE)
-                    .to_string(),
+                err: format!(
+                    "invalid bytes size should be {}, {}, {} or {}, got 37",
+                    FREEZE_ENTRY_SIZE,
+                    EXPIRABLE_FREEZE_ENTRY_SIZE,
+                    PARTIAL_FREEZE_ENTRY_SIZE,
+                    EXPIRABLE_PARTIAL_FREEZE_ENTRY_SIZE
+                )
+                .to_string(),
             },
         ];
 
@@ -0,0 +1,249 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use bitcoin::secp256k1::{self, constants::PUBLIC_KEY_SIZE};
+use core::fmt;
+use yuv_pixels::{Chroma, ChromaParseError, CHROMA_SIZE};
+
+use crate::{activation::ProtocolFeature, network::Network, Announcement, AnyAnnouncement};
+
+use crate::announcements::{AnnouncementKind, AnnouncementParseError};
+
+/// Two bytes that represent the [`FreezePubkeyAnnouncement`]'s kind.
+pub const FREEZE_PUBKEY_ANNOUNCEMENT_KIND: AnnouncementKind = [0, 6];
+/// Size of the [`FreezePubkeyAnnouncement`] in bytes.
+pub const FREEZE_PUBKEY_ANNOUNCEMENT_SIZE: usize = CHROMA_SIZE + PUBLIC_KEY_SIZE + 1;
+
+/// Action byte of the freeze.
+const FREEZE_ACTION: u8 = 0;
+/// Action byte of the unfreeze.
+const UNFREEZE_ACTION: u8 = 1;
+
+/// Freeze of all the chroma's pixels owned by the public key, declared by the owner of the chroma.
+///
+/// Unlike the [`FreezeAnnouncement`], it doesn't require to enumerate the outputs of the key: the
+/// transfers spending any output of the chroma owned by the frozen key are rejected, until the
+/// key is unfrozen by the same announcement with the `unfreeze` flag.
+///
+/// # Structure
+///
+/// - `chroma` - 32 bytes [`Chroma`].
+/// - `pubkey` - 33 bytes compressed public key of the owner of the frozen pixels.
+/// - `action` - 1 byte, `0` to freeze the key and `1` to unfreeze it.
+///
+/// [`FreezeAnnouncement`]: crate::announcements::FreezeAnnouncement
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreezePubkeyAnnouncement {
+    /// The chroma to freeze.
+    pub chroma: Chroma,
+    /// The public key whose pixels are frozen.
+    pub pubkey: secp256k1::PublicKey,
+    /// If set, the previously frozen key is unfrozen.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unfreeze: bool,
+}
+
+impl FreezePubkeyAnnouncement {
+    /// Create a new announcement that freezes the pixels of the `pubkey`.
+    pub fn new(chroma: Chroma, pubkey: secp256k1::PublicKey) -> Self {
+        Self {
+            chroma,
+            pubkey,
+            unfreeze: false,
+        }
+    }
+
+    /// Create a new announcement that unfreezes the pixels of the `pubkey`.
+    pub fn new_unfreeze(chroma: Chroma, pubkey: secp256k1::PublicKey) -> Self {
+        Self {
+            chroma,
+            pubkey,
+            unfreeze: true,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde(name = "freeze_pubkey_announcement"))]
+impl AnyAnnouncement for FreezePubkeyAnnouncement {
+    fn kind(&self) -> AnnouncementKind {
+        FREEZE_PUBKEY_ANNOUNCEMENT_KIND
+    }
+
+    fn minimal_block_height(&self, network: Network) -> usize {
+        ProtocolFeature::FreezePubkeyAnnouncement.activation_height(network)
+    }
+
+    fn from_announcement_data_bytes(data: &[u8]) -> Result<Self, AnnouncementParseError> {
+        if data.len() != FREEZE_PUBKEY_ANNOUNCEMENT_SIZE {
+            return Err(FreezePubkeyAnnouncementParseError::InvalidSize(data.len()))?;
+        }
+
+        let chroma = Chroma::from_bytes(&data[..CHROMA_SIZE])
+            .map_err(FreezePubkeyAnnouncementParseError::from)?;
+
+        let pubkey =
+            secp256k1::PublicKey::from_slice(&data[CHROMA_SIZE..CHROMA_SIZE + PUBLIC_KEY_SIZE])
+                .map_err(FreezePubkeyAnnouncementParseError::from)?;
+
+        let unfreeze = match data[CHROMA_SIZE + PUBLIC_KEY_SIZE] {
+            FREEZE_ACTION => false,
+            UNFREEZE_ACTION => true,
+            action => Err(FreezePubkeyAnnouncementParseError::InvalidAction(action))?,
+        };
+
+        Ok(Self {
+            chroma,
+            pubkey,
+            unfreeze,
+        })
+    }
+
+    fn to_announcement_data_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(FREEZE_PUBKEY_ANNOUNCEMENT_SIZE);
+
+        bytes.extend_from_slice(&self.chroma.to_bytes());
+        bytes.extend_from_slice(&self.pubkey.serialize());
+        bytes.push(if self.unfreeze {
+            UNFREEZE_ACTION
+        } else {
+            FREEZE_ACTION
+        });
+
+        bytes
+    }
+}
+
+impl From<FreezePubkeyAnnouncement> for Announcement {
+    fn from(value: FreezePubkeyAnnouncement) -> Self {
+        Self::FreezePubkey(value)
+    }
+}
+
+/// Errors that can occur when parsing [`FreezePubkeyAnnouncement`].
+#[derive(Debug)]
+pub enum FreezePubkeyAnnouncementParseError {
+    /// The size of the announcement data is not [`FREEZE_PUBKEY_ANNOUNCEMENT_SIZE`].
+    InvalidSize(usize),
+    /// Invalid chroma.
+    InvalidChroma(ChromaParseError),
+    /// Invalid public key.
+    InvalidPubkey(secp256k1::Error),
+    /// The action byte is neither freeze nor unfreeze.
+    InvalidAction(u8),
+}
+
+impl fmt::Display for FreezePubkeyAnnouncementParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(
+                f,
+                "invalid bytes size should be {}, got {}",
+                FREEZE_PUBKEY_ANNOUNCEMENT_SIZE, size
+            ),
+            Self::InvalidChroma(e) => write!(f, "invalid chroma: {}", e),
+            Self::InvalidPubkey(e) => write!(f, "invalid public key: {}", e),
+            Self::InvalidAction(action) => write!(f, "invalid freeze action: {}", action),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for FreezePubkeyAnnouncementParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidChroma(e) => Some(e),
+            Self::InvalidPubkey(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ChromaParseError> for FreezePubkeyAnnouncementParseError {
+    fn from(err: ChromaParseError) -> Self {
+        Self::InvalidChroma(err)
+    }
+}
+
+impl From<secp256k1::Error> for FreezePubkeyAnnouncementParseError {
+    fn from(err: secp256k1::Error) -> Self {
+        Self::InvalidPubkey(err)
+    }
+}
+
+impl From<FreezePubkeyAnnouncementParseError> for AnnouncementParseError {
+    fn from(err: FreezePubkeyAnnouncementParseError) -> Self {
+        AnnouncementParseError::InvalidAnnouncementData(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::announcements::freeze_pubkey::FREEZE_PUBKEY_ANNOUNCEMENT_SIZE;
+    use crate::announcements::{
+        announcement_from_script, AnnouncementParseError, FreezePubkeyAnnouncement,
+    };
+    use crate::{Announcement, AnyAnnouncement};
+    use alloc::format;
+    use bitcoin::secp256k1::PublicKey;
+    use core::str::FromStr;
+    use yuv_pixels::Chroma;
+
+    pub const TEST_CHROMA: &str =
+        "bcrt1p4v5dxtlzrrfuk57nxr3d6gwmtved47ulc55kcsk30h93e43ma2eqvrek30";
+    pub const TEST_PUBKEY: &str =
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_serialize_deserialize() {
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+        let pubkey = PublicKey::from_str(TEST_PUBKEY).expect("valid pubkey");
+
+        for announcement in [
+            FreezePubkeyAnnouncement::new(chroma, pubkey),
+            FreezePubkeyAnnouncement::new_unfreeze(chroma, pubkey),
+        ] {
+            let data_bytes = announcement.to_announcement_data_bytes();
+            assert_eq!(data_bytes.len(), FREEZE_PUBKEY_ANNOUNCEMENT_SIZE);
+            assert_eq!(
+                FreezePubkeyAnnouncement::from_announcement_data_bytes(&data_bytes).unwrap(),
+                announcement
+            );
+
+            let parsed_announcement = announcement_from_script(&announcement.to_script()).unwrap();
+            assert_eq!(
+                Announcement::FreezePubkey(announcement),
+                parsed_announcement
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid_bytes() {
+        match FreezePubkeyAnnouncement::from_announcement_data_bytes(&[0]) {
+            Err(AnnouncementParseError::InvalidAnnouncementData(err)) => {
+                assert_eq!(
+                    err,
+                    format!(
+                        "invalid bytes size should be {}, got 1",
+                        FREEZE_PUBKEY_ANNOUNCEMENT_SIZE
+                    )
+                );
+            }
+            err => panic!("Unexpected result: {:?}", err),
+        }
+
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+        let pubkey = PublicKey::from_str(TEST_PUBKEY).expect("valid pubkey");
+        let mut data_bytes =
+            FreezePubkeyAnnouncement::new(chroma, pubkey).to_announcement_data_bytes();
+        *data_bytes.last_mut().unwrap() = 2;
+
+        match FreezePubkeyAnnouncement::from_announcement_data_bytes(&data_bytes) {
+            Err(AnnouncementParseError::InvalidAnnouncementData(err)) => {
+                assert_eq!(err, "invalid freeze action: 2");
+            }
+            err => panic!("Unexpected result: {:?}", err),
+        }
+    }
+}
@@ -9,24 +9,42 @@ use bitcoin::blockdata::opcodes::All as Opcodes;
 use bitcoin::blockdata::script;
 use bitcoin::blockdata::script::Instruction;
 use bitcoin::Script;
+pub use burn::{BurnAnnouncement, BurnAnnouncementParseError, BURN_ANNOUNCEMENT_KIND};
 pub use chroma::{
-    ChromaAnnouncement, ChromaInfo, CHROMA_ANNOUNCEMENT_KIND, MAX_CHROMA_ANNOUNCEMENT_SIZE,
-    MAX_NAME_SIZE, MAX_SYMBOL_SIZE, MIN_CHROMA_ANNOUNCEMENT_SIZE, MIN_NAME_SIZE, MIN_SYMBOL_SIZE,
+    ChromaAnnouncement, ChromaInfo, ChromaIssuers, CHROMA_ANNOUNCEMENT_KIND,
+    MAX_CHROMA_ANNOUNCEMENT_SIZE, MAX_ISSUERS, MAX_NAME_SIZE, MAX_SYMBOL_SIZE,
+    MIN_CHROMA_ANNOUNCEMENT_SIZE, MIN_NAME_SIZE, MIN_SYMBOL_SIZE,
 };
 use core::fmt;
+pub use delegate::{
+    DelegateAnnouncement, DelegateAnnouncementParseError, DelegateCapabilities,
+    DELEGATE_ANNOUNCEMENT_KIND,
+};
 pub use freeze::{FreezeAnnouncement, FreezeAnnouncementParseError, FREEZE_ANNOUNCEMENT_KIND};
 
+pub use freeze_pubkey::{
+    FreezePubkeyAnnouncement, FreezePubkeyAnnouncementParseError, FREEZE_PUBKEY_ANNOUNCEMENT_KIND,
+};
+
 pub use issue::{IssueAnnouncement, ISSUE_ANNOUNCEMENT_KIND};
 
 pub use transfer_ownership::{TransferOwnershipAnnouncement, TRANSFER_OWNERSHIP_ANNOUNCEMENT_KIND};
 
+pub use update_chroma::{
+    UpdateChromaAnnouncement, UpdateChromaAnnouncementParseError, UPDATE_CHROMA_ANNOUNCEMENT_KIND,
+};
+
 use crate::announcements::announcement::ANNOUNCEMENT_INSTRUCTION_NUMBER;
 
 mod announcement;
+mod burn;
 mod chroma;
+mod delegate;
 mod freeze;
+mod freeze_pubkey;
 mod issue;
 mod transfer_ownership;
+mod update_chroma;
 
 /// Parse the bytes into an [`Announcement`] without specification of the [announcement kind].
 ///
@@ -62,6 +80,18 @@ pub fn announcement_from_bytes(bytes: &[u8]) -> Result<Announcement, Announcemen
         TRANSFER_OWNERSHIP_ANNOUNCEMENT_KIND => Ok(Announcement::TransferOwnership(
             TransferOwnershipAnnouncement::from_announcement_data_bytes(announcement_data)?,
         )),
+        BURN_ANNOUNCEMENT_KIND => Ok(Announcement::Burn(
+            BurnAnnouncement::from_announcement_data_bytes(announcement_data)?,
+        )),
+        UPDATE_CHROMA_ANNOUNCEMENT_KIND => Ok(Announcement::UpdateChroma(
+            UpdateChromaAnnouncement::from_announcement_data_bytes(announcement_data)?,
+        )),
+        FREEZE_PUBKEY_ANNOUNCEMENT_KIND => Ok(Announcement::FreezePubkey(
+            FreezePubkeyAnnouncement::from_announcement_data_bytes(announcement_data)?,
+        )),
+        DELEGATE_ANNOUNCEMENT_KIND => Ok(Announcement::Delegate(
+            DelegateAnnouncement::from_announcement_data_bytes(announcement_data)?,
+        )),
         _ => Err(AnnouncementParseError::UnknownAnnouncementKind),
     }
 }
@@ -1,4 +1,4 @@
-use crate::{network::Network, Announcement, AnyAnnouncement};
+use crate::{activation::ProtocolFeature, network::Network, Announcement, AnyAnnouncement};
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use bitcoin::{consensus::encode, ScriptBuf};
@@ -9,10 +9,6 @@ use yuv_pixels::{Chroma, ChromaParseError, CHROMA_SIZE};
 
 use crate::announcements::{AnnouncementKind, AnnouncementParseError};
 
-const MAINNET_MINIMAL_BLOCK: usize = 855_000;
-const TESTNET_MINIMAL_BLOCK: usize = 2_830_000;
-const MUTINY_MINIMAL_BLOCK: usize = 1_200_000;
-
 /// Two bytes that represent the [`TransferOwnershipAnnouncement`]'s kind.
 pub const TRANSFER_OWNERSHIP_ANNOUNCEMENT_KIND: AnnouncementKind = [0, 3];
 /// Maximum script length in bytes, which is restricted by maximum `OP_RETURN` data size.
@@ -56,12 +52,7 @@ impl AnyAnnouncement for TransferOwnershipAnnouncement {
     }
 
     fn minimal_block_height(&self, network: Network) -> usize {
-        match network {
-            Network::Bitcoin => MAINNET_MINIMAL_BLOCK,
-            Network::Testnet => TESTNET_MINIMAL_BLOCK,
-            Network::Mutiny => MUTINY_MINIMAL_BLOCK,
-            _ => 0,
-        }
+        ProtocolFeature::TransferOwnershipAnnouncement.activation_height(network)
     }
 
     fn from_announcement_data_bytes(data: &[u8]) -> Result<Self, AnnouncementParseError> {
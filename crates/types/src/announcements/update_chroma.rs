@@ -0,0 +1,336 @@
+use crate::{activation::ProtocolFeature, network::Network, Announcement, AnyAnnouncement};
+use alloc::string::{FromUtf8Error, String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use bitcoin::consensus::ReadExt;
+use core::fmt;
+use core2::io::{Cursor, Read};
+use yuv_pixels::{Chroma, ChromaParseError, CHROMA_SIZE};
+
+use crate::announcements::{
+    AnnouncementKind, AnnouncementParseError, ChromaAnnouncement, MAX_NAME_SIZE, MAX_SYMBOL_SIZE,
+    MIN_NAME_SIZE, MIN_SYMBOL_SIZE,
+};
+
+/// Two bytes that represent the [`UpdateChromaAnnouncement`]'s kind.
+pub const UPDATE_CHROMA_ANNOUNCEMENT_KIND: AnnouncementKind = [0, 5];
+/// The minimum size of the [`UpdateChromaAnnouncement`] in bytes.
+pub const MIN_UPDATE_CHROMA_ANNOUNCEMENT_SIZE: usize =
+    CHROMA_SIZE + 1 + MIN_NAME_SIZE + 1 + MIN_SYMBOL_SIZE;
+/// The maximum size of the [`UpdateChromaAnnouncement`] in bytes.
+pub const MAX_UPDATE_CHROMA_ANNOUNCEMENT_SIZE: usize =
+    CHROMA_SIZE + 1 + MAX_NAME_SIZE + 1 + MAX_SYMBOL_SIZE;
+
+/// Update of the chroma's metadata from the current owner of the chroma. It replaces the name and
+/// the symbol of the initial [`ChromaAnnouncement`]. The decimals, the maximum supply and the
+/// freezability can't be changed, as the existing balances and issuances depend on them.
+///
+/// # Structure
+///
+/// - `chroma` - 32 bytes [`Chroma`].
+/// - `name` - 1 + [3 - 20] bytes new name of the token. Where the first byte is the length of the
+/// name.
+/// - `symbol` - 1 + [3 - 6] bytes new symbol of the token. Where the first byte is the length of
+/// the symbol.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdateChromaAnnouncement {
+    /// The token's [`Chroma`].
+    pub chroma: Chroma,
+    /// The new name of the token.
+    pub name: String,
+    /// The new symbol of the token.
+    pub symbol: String,
+}
+
+impl UpdateChromaAnnouncement {
+    /// Create a new [`UpdateChromaAnnouncement`].
+    pub fn new(
+        chroma: Chroma,
+        name: String,
+        symbol: String,
+    ) -> Result<Self, UpdateChromaAnnouncementParseError> {
+        if !(MIN_NAME_SIZE..=MAX_NAME_SIZE).contains(&name.len()) {
+            return Err(UpdateChromaAnnouncementParseError::InvalidNameLength);
+        }
+
+        if !(MIN_SYMBOL_SIZE..=MAX_SYMBOL_SIZE).contains(&symbol.len()) {
+            return Err(UpdateChromaAnnouncementParseError::InvalidSymbolLength);
+        }
+
+        Ok(Self {
+            chroma,
+            name,
+            symbol,
+        })
+    }
+
+    /// Returns the next version of the `announcement` with the updated name and symbol.
+    pub fn apply(&self, announcement: &ChromaAnnouncement) -> ChromaAnnouncement {
+        ChromaAnnouncement {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            ..announcement.clone()
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde(name = "update_chroma_announcement"))]
+impl AnyAnnouncement for UpdateChromaAnnouncement {
+    fn kind(&self) -> AnnouncementKind {
+        UPDATE_CHROMA_ANNOUNCEMENT_KIND
+    }
+
+    fn minimal_block_height(&self, network: Network) -> usize {
+        ProtocolFeature::UpdateChromaAnnouncement.activation_height(network)
+    }
+
+    fn from_announcement_data_bytes(data: &[u8]) -> Result<Self, AnnouncementParseError> {
+        use UpdateChromaAnnouncementParseError as Error;
+
+        if data.len() < MIN_UPDATE_CHROMA_ANNOUNCEMENT_SIZE
+            || data.len() > MAX_UPDATE_CHROMA_ANNOUNCEMENT_SIZE
+        {
+            return Err(Error::InvalidSize(data.len()))?;
+        }
+
+        let mut cursor = Cursor::new(data);
+
+        let mut chroma_bytes = [0u8; CHROMA_SIZE];
+        cursor
+            .read_exact(&mut chroma_bytes)
+            .map_err(|err| wrap_io_error(err, "failed to read the chroma"))?;
+
+        let chroma = Chroma::from_bytes(&chroma_bytes).map_err(Error::from)?;
+
+        let name_len = cursor
+            .read_u8()
+            .map_err(|err| wrap_io_error(err, "failed to read the name length"))?
+            as usize;
+
+        if !(MIN_NAME_SIZE..=MAX_NAME_SIZE).contains(&name_len) {
+            Err(Error::InvalidNameLength)?;
+        }
+
+        let mut name_bytes = vec![0; name_len];
+        cursor
+            .read_exact(&mut name_bytes)
+            .map_err(|err| wrap_io_error(err, "failed to read the name"))?;
+
+        let name = String::from_utf8(name_bytes).map_err(Error::from)?;
+
+        let symbol_len = cursor
+            .read_u8()
+            .map_err(|err| wrap_io_error(err, "failed to read the symbol length"))?
+            as usize;
+
+        if !(MIN_SYMBOL_SIZE..=MAX_SYMBOL_SIZE).contains(&symbol_len) {
+            Err(Error::InvalidSymbolLength)?;
+        }
+
+        let mut symbol_bytes = vec![0; symbol_len];
+        cursor
+            .read_exact(&mut symbol_bytes)
+            .map_err(|err| wrap_io_error(err, "failed to read the symbol"))?;
+
+        let symbol = String::from_utf8(symbol_bytes).map_err(Error::from)?;
+
+        if cursor.position() as usize != data.len() {
+            Err(Error::InvalidSize(data.len()))?;
+        }
+
+        Ok(Self {
+            chroma,
+            name,
+            symbol,
+        })
+    }
+
+    fn to_announcement_data_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAX_UPDATE_CHROMA_ANNOUNCEMENT_SIZE);
+
+        bytes.extend_from_slice(&self.chroma.to_bytes());
+        bytes.push(self.name.len() as u8);
+        bytes.extend_from_slice(self.name.as_bytes());
+        bytes.push(self.symbol.len() as u8);
+        bytes.extend_from_slice(self.symbol.as_bytes());
+
+        bytes
+    }
+}
+
+impl From<UpdateChromaAnnouncement> for Announcement {
+    fn from(value: UpdateChromaAnnouncement) -> Self {
+        Self::UpdateChroma(value)
+    }
+}
+
+/// Errors that can occur when parsing [`UpdateChromaAnnouncement`].
+#[derive(Debug)]
+pub enum UpdateChromaAnnouncementParseError {
+    /// The size of the announcement data is out of the
+    /// [`MIN_UPDATE_CHROMA_ANNOUNCEMENT_SIZE`]..=[`MAX_UPDATE_CHROMA_ANNOUNCEMENT_SIZE`] range, or
+    /// doesn't match the lengths of the name and the symbol.
+    InvalidSize(usize),
+    /// Announcement data is incorrectly encoded.
+    InvalidAnnouncementData(String),
+    /// The string is not a valid UTF-8 string.
+    InvalidUtf8String(FromUtf8Error),
+    /// The length of the name is less than [`MIN_NAME_SIZE`] or more than [`MAX_NAME_SIZE`].
+    InvalidNameLength,
+    /// The length of the symbol is less than [`MIN_SYMBOL_SIZE`] or more than [`MAX_SYMBOL_SIZE`].
+    InvalidSymbolLength,
+    /// Invalid chroma.
+    InvalidChroma(ChromaParseError),
+}
+
+impl fmt::Display for UpdateChromaAnnouncementParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(
+                f,
+                "invalid bytes size, should be between {} and {}, got {}",
+                MIN_UPDATE_CHROMA_ANNOUNCEMENT_SIZE, MAX_UPDATE_CHROMA_ANNOUNCEMENT_SIZE, size
+            ),
+            Self::InvalidAnnouncementData(e) => write!(f, "invalid announcement data: {}", e),
+            Self::InvalidUtf8String(e) => write!(f, "invalid utf-8 string: {}", e),
+            Self::InvalidNameLength => write!(
+                f,
+                "the length of the name is invalid, it must be between {} and {}",
+                MIN_NAME_SIZE, MAX_NAME_SIZE
+            ),
+            Self::InvalidSymbolLength => write!(
+                f,
+                "the length of the symbol is invalid, it must be between {} and {}",
+                MIN_SYMBOL_SIZE, MAX_SYMBOL_SIZE
+            ),
+            Self::InvalidChroma(e) => write!(f, "invalid chroma: {}", e),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for UpdateChromaAnnouncementParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidUtf8String(e) => Some(e),
+            Self::InvalidChroma(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<FromUtf8Error> for UpdateChromaAnnouncementParseError {
+    fn from(err: FromUtf8Error) -> Self {
+        Self::InvalidUtf8String(err)
+    }
+}
+
+impl From<ChromaParseError> for UpdateChromaAnnouncementParseError {
+    fn from(err: ChromaParseError) -> Self {
+        Self::InvalidChroma(err)
+    }
+}
+
+impl From<UpdateChromaAnnouncementParseError> for AnnouncementParseError {
+    fn from(err: UpdateChromaAnnouncementParseError) -> Self {
+        AnnouncementParseError::InvalidAnnouncementData(err.to_string())
+    }
+}
+
+/// Wrap Error with InvalidAnnouncementData and the given message.
+fn wrap_io_error(err: impl fmt::Display, message: &str) -> UpdateChromaAnnouncementParseError {
+    UpdateChromaAnnouncementParseError::InvalidAnnouncementData(format!("{}: {}", message, err))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        announcements::{
+            announcement_from_script, update_chroma::MIN_UPDATE_CHROMA_ANNOUNCEMENT_SIZE,
+            AnnouncementParseError, ChromaAnnouncement, UpdateChromaAnnouncement,
+        },
+        Announcement, AnyAnnouncement,
+    };
+    use alloc::{format, string::ToString};
+    use yuv_pixels::Chroma;
+
+    pub const TEST_CHROMA: &str =
+        "bcrt1p4v5dxtlzrrfuk57nxr3d6gwmtved47ulc55kcsk30h93e43ma2eqvrek30";
+
+    #[test]
+    fn test_serialize_deserialize() {
+        let test_announcement = UpdateChromaAnnouncement::new(
+            Chroma::from_address(TEST_CHROMA).expect("valid chroma"),
+            "New Token Name".to_string(),
+            "NTN".to_string(),
+        )
+        .expect("valid announcement");
+
+        let data = test_announcement.to_announcement_data_bytes();
+        assert_eq!(
+            UpdateChromaAnnouncement::from_announcement_data_bytes(&data).unwrap(),
+            test_announcement
+        );
+
+        let announcement_script = test_announcement.to_script();
+        assert_eq!(
+            announcement_from_script(&announcement_script).unwrap(),
+            Announcement::UpdateChroma(test_announcement)
+        );
+    }
+
+    #[test]
+    fn test_apply_replaces_only_name_and_symbol() {
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+        let announcement = ChromaAnnouncement::new(
+            chroma,
+            "Token".to_string(),
+            "TKN".to_string(),
+            8,
+            1000,
+            true,
+        )
+        .expect("valid announcement");
+        let update =
+            UpdateChromaAnnouncement::new(chroma, "New Token".to_string(), "NTKN".to_string())
+                .expect("valid announcement");
+
+        assert_eq!(
+            update.apply(&announcement),
+            ChromaAnnouncement {
+                name: "New Token".to_string(),
+                symbol: "NTKN".to_string(),
+                ..announcement
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        let chroma = Chroma::from_address(TEST_CHROMA).expect("valid chroma");
+
+        assert!(
+            UpdateChromaAnnouncement::new(chroma, "AB".to_string(), "ABC".to_string()).is_err()
+        );
+        assert!(
+            UpdateChromaAnnouncement::new(chroma, "ABC".to_string(), "ABCDEFG".to_string())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_bytes() {
+        match UpdateChromaAnnouncement::from_announcement_data_bytes(&[0]) {
+            Err(AnnouncementParseError::InvalidAnnouncementData(err)) => {
+                assert!(err.starts_with(&format!(
+                    "invalid bytes size, should be between {}",
+                    MIN_UPDATE_CHROMA_ANNOUNCEMENT_SIZE
+                )));
+            }
+            err => {
+                panic!("Unexpected result: {:?}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Proof bundle: the output together with all the YUV transactions needed to validate it, from
+//! the issuances of its tokens, so the receiver can validate the output without the node.
+//!
+//! The bundle is consensus encoded as:
+//!
+//! | Field      | Size     | Description                                                 |
+//! |------------|----------|-------------------------------------------------------------|
+//! | `version`  | 1 byte   | Version of the format, [`PROOF_BUNDLE_VERSION`].            |
+//! | `outpoint` | 36 bytes | Output the bundle proves.                                   |
+//! | `txs`      | variable | Number of the transactions as `u32`, then the transactions. |
+//!
+//! The transactions are ordered so the parents come before their children, and the transaction
+//! of the output is the last one.
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use bitcoin::consensus::encode::Error as EncodeError;
+use bitcoin::consensus::{Decodable, Encodable};
+use bitcoin::OutPoint;
+use core2::io;
+use hex::FromHexError;
+
+use crate::consensus::YuvTxsWrapper;
+use crate::YuvTransaction;
+
+/// Current version of the proof bundle format.
+pub const PROOF_BUNDLE_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofBundle {
+    /// Output the bundle proves.
+    pub outpoint: OutPoint,
+    /// Transaction of the output and its ancestors, parents first.
+    pub txs: Vec<YuvTransaction>,
+}
+
+impl ProofBundle {
+    pub fn new(outpoint: OutPoint, txs: Vec<YuvTransaction>) -> Self {
+        Self { outpoint, txs }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bitcoin::consensus::serialize(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofBundleParseError> {
+        let mut reader = io::Cursor::new(bytes);
+        let bundle = Self::consensus_decode(&mut reader)?;
+
+        if reader.position() as usize != bytes.len() {
+            return Err(ProofBundleParseError::TrailingBytes);
+        }
+
+        Ok(bundle)
+    }
+
+    pub fn hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, ProofBundleParseError> {
+        Self::from_bytes(&hex::decode(hex)?)
+    }
+
+    /// Transaction of the output, the last one in the bundle.
+    pub fn target_tx(&self) -> Option<&YuvTransaction> {
+        self.txs
+            .last()
+            .filter(|tx| tx.bitcoin_tx.txid() == self.outpoint.txid)
+    }
+}
+
+impl Encodable for ProofBundle {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += PROOF_BUNDLE_VERSION.consensus_encode(writer)?;
+        len += self.outpoint.consensus_encode(writer)?;
+        len += (self.txs.len() as u32).consensus_encode(writer)?;
+        for tx in &self.txs {
+            len += tx.consensus_encode(writer)?;
+        }
+
+        Ok(len)
+    }
+}
+
+impl Decodable for ProofBundle {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let version: u8 = Decodable::consensus_decode(reader)?;
+        if version != PROOF_BUNDLE_VERSION {
+            return Err(EncodeError::ParseFailed("unsupported proof bundle version"));
+        }
+
+        let outpoint: OutPoint = Decodable::consensus_decode(reader)?;
+        let YuvTxsWrapper(txs) = Decodable::consensus_decode(reader)?;
+
+        Ok(Self { outpoint, txs })
+    }
+}
+
+/// Error that can occur when parsing the [`ProofBundle`].
+#[derive(Debug)]
+pub enum ProofBundleParseError {
+    /// Wrong hex data.
+    Hex(FromHexError),
+    /// The bundle is malformed or has an unsupported version.
+    Encoding(EncodeError),
+    /// The data has bytes after the bundle.
+    TrailingBytes,
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for ProofBundleParseError {}
+
+impl fmt::Display for ProofBundleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hex(err) => write!(f, "Invalid hex data: {}", err),
+            Self::Encoding(err) => write!(f, "The proof bundle is malformed: {}", err),
+            Self::TrailingBytes => write!(f, "The proof bundle has trailing bytes"),
+        }
+    }
+}
+
+impl From<FromHexError> for ProofBundleParseError {
+    fn from(err: FromHexError) -> Self {
+        Self::Hex(err)
+    }
+}
+
+impl From<EncodeError> for ProofBundleParseError {
+    fn from(err: EncodeError) -> Self {
+        Self::Encoding(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_proof_bundle_encoding() {
+        let issue = serde_json::from_str::<YuvTransaction>(include_str!("./assets/issue.json"))
+            .expect("JSON was not well-formatted");
+        let transfer =
+            serde_json::from_str::<YuvTransaction>(include_str!("./assets/transfer.json"))
+                .expect("JSON was not well-formatted");
+
+        let outpoint = OutPoint::new(transfer.bitcoin_tx.txid(), 0);
+        let bundle = ProofBundle::new(outpoint, vec![issue, transfer]);
+
+        let bytes = bundle.to_bytes();
+        assert_eq!(bytes[0], PROOF_BUNDLE_VERSION);
+        assert_eq!(ProofBundle::from_hex(&bundle.hex()).unwrap(), bundle);
+        assert!(bundle.target_tx().is_some());
+
+        let mut unknown_version = bytes.clone();
+        unknown_version[0] = PROOF_BUNDLE_VERSION + 1;
+        assert!(matches!(
+            ProofBundle::from_bytes(&unknown_version),
+            Err(ProofBundleParseError::Encoding(_))
+        ));
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(matches!(
+            ProofBundle::from_bytes(&trailing),
+            Err(ProofBundleParseError::TrailingBytes)
+        ));
+    }
+}
@@ -1,5 +1,7 @@
+//! Consensus encoding of the YUV transactions and the P2P messages.
 use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
+use core::fmt;
 
 use bitcoin::consensus::encode::Error as EncodeError;
 use bitcoin::consensus::{encode, Decodable, Encodable};
@@ -9,9 +11,16 @@ use core2::io;
 use yuv_pixels::PixelProof;
 
 use crate::announcements::IssueAnnouncement;
-#[cfg(all(feature = "messages", feature = "std"))]
-use crate::messages::p2p::Inventory;
 use crate::{Announcement, ProofMap, YuvTransaction, YuvTxType};
+#[cfg(all(feature = "messages", feature = "std"))]
+use {
+    crate::messages::p2p::{
+        ChromaFilter, CompactYuvTxs, GetYuvTxn, Inventory, InventorySketch, PeerAuth,
+        PrefilledYuvTx, ReconciliationDiff, ShortTxid, SignedInventory,
+    },
+    bitcoin::secp256k1::{ecdsa::Signature, PublicKey},
+    yuv_pixels::Chroma,
+};
 
 const ISSUE_CONSENSUS_FLAG: u8 = 0u8;
 const TRANSFER_CONSENSUS_FLAG: u8 = 1u8;
@@ -234,6 +243,97 @@ impl Decodable for YuvTxsWrapper {
     }
 }
 
+/// Checks that the `value` survives the consensus encoding round trip, and returns its encoding.
+///
+/// The value is encoded, decoded back and encoded again. The check fails if the encoder reports
+/// the wrong length, the decoder doesn't consume the whole encoding, or either the decoded value
+/// or its encoding differ from the original ones. Used by the fuzz targets and the property
+/// tests to keep the wire format stable.
+pub fn round_trip_check<T>(value: &T) -> Result<Vec<u8>, RoundTripError>
+where
+    T: Encodable + Decodable + PartialEq,
+{
+    let bytes = encode_checked(value)?;
+
+    let mut reader = io::Cursor::new(bytes.as_slice());
+    let decoded = T::consensus_decode(&mut reader).map_err(RoundTripError::Decode)?;
+
+    let consumed = reader.position() as usize;
+    if consumed != bytes.len() {
+        return Err(RoundTripError::TrailingBytes {
+            consumed,
+            total: bytes.len(),
+        });
+    }
+
+    if decoded != *value {
+        return Err(RoundTripError::ValueMismatch);
+    }
+
+    if encode_checked(&decoded)? != bytes {
+        return Err(RoundTripError::EncodingMismatch);
+    }
+
+    Ok(bytes)
+}
+
+fn encode_checked<T: Encodable>(value: &T) -> Result<Vec<u8>, RoundTripError> {
+    let mut bytes = Vec::new();
+    let reported = value
+        .consensus_encode(&mut bytes)
+        .map_err(RoundTripError::Encode)?;
+
+    if reported != bytes.len() {
+        return Err(RoundTripError::LengthMismatch {
+            reported,
+            written: bytes.len(),
+        });
+    }
+
+    Ok(bytes)
+}
+
+/// Error of the [`round_trip_check`].
+#[derive(Debug)]
+pub enum RoundTripError {
+    /// Failed to encode the value.
+    Encode(io::Error),
+    /// Failed to decode the encoded value.
+    Decode(EncodeError),
+    /// The encoder returned the length that differs from the number of the written bytes.
+    LengthMismatch { reported: usize, written: usize },
+    /// The decoder didn't consume all the bytes of the encoding.
+    TrailingBytes { consumed: usize, total: usize },
+    /// The decoded value isn't equal to the original one.
+    ValueMismatch,
+    /// The decoded value is encoded to the other bytes.
+    EncodingMismatch,
+}
+
+impl fmt::Display for RoundTripError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(err) => write!(f, "Failed to encode the value: {}", err),
+            Self::Decode(err) => write!(f, "Failed to decode the value: {}", err),
+            Self::LengthMismatch { reported, written } => write!(
+                f,
+                "Encoder reported {} bytes, but {} bytes were written",
+                reported, written
+            ),
+            Self::TrailingBytes { consumed, total } => write!(
+                f,
+                "Decoder consumed {} of {} bytes of the encoding",
+                consumed, total
+            ),
+            Self::ValueMismatch => write!(f, "Decoded value differs from the original one"),
+            Self::EncodingMismatch => write!(f, "Decoded value has the other encoding"),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-std"))]
+impl std::error::Error for RoundTripError {}
+
 #[cfg(all(feature = "messages", feature = "std"))]
 impl Encodable for Inventory {
     fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
@@ -293,6 +393,294 @@ impl Decodable for InventoryWrapper {
     }
 }
 
+/// Compact signature is encoded as two 32 byte halves, as there is no [`Encodable`] for the
+/// 64 byte arrays.
+#[cfg(all(feature = "messages", feature = "std"))]
+struct SignatureWrapper(Signature);
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for SignatureWrapper {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let bytes = self.0.serialize_compact();
+
+        let mut len = 0;
+        for half in bytes.chunks_exact(32) {
+            let half: [u8; 32] = half.try_into().expect("compact signature is 64 bytes");
+            len += half.consensus_encode(writer)?;
+        }
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for SignatureWrapper {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let first: [u8; 32] = Decodable::consensus_decode(reader)?;
+        let second: [u8; 32] = Decodable::consensus_decode(reader)?;
+
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&first);
+        bytes[32..].copy_from_slice(&second);
+
+        Signature::from_compact(&bytes)
+            .map(SignatureWrapper)
+            .map_err(|_| EncodeError::ParseFailed("Invalid signature"))
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for PeerAuth {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += self.pubkey.serialize().consensus_encode(writer)?;
+        len += SignatureWrapper(self.signature).consensus_encode(writer)?;
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for PeerAuth {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let pubkey: [u8; 33] = Decodable::consensus_decode(reader)?;
+        let pubkey = PublicKey::from_slice(&pubkey)
+            .map_err(|_| EncodeError::ParseFailed("Invalid public key"))?;
+        let SignatureWrapper(signature) = Decodable::consensus_decode(reader)?;
+
+        Ok(PeerAuth { pubkey, signature })
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for SignedInventory {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += InventoryWrapper(self.inv.clone()).consensus_encode(writer)?;
+        len += SignatureWrapper(self.signature).consensus_encode(writer)?;
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for SignedInventory {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let InventoryWrapper(inv) = Decodable::consensus_decode(reader)?;
+        let SignatureWrapper(signature) = Decodable::consensus_decode(reader)?;
+
+        Ok(SignedInventory { inv, signature })
+    }
+}
+
+/// List of the `u32` values, as there is no [`Encodable`] for `Vec<u32>`.
+#[cfg(all(feature = "messages", feature = "std"))]
+struct U32sWrapper(Vec<u32>);
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for U32sWrapper {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += (self.0.len() as u32).consensus_encode(writer)?;
+
+        for value in &self.0 {
+            len += value.consensus_encode(writer)?;
+        }
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for U32sWrapper {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let len: u32 = Decodable::consensus_decode(reader)?;
+
+        let values: Vec<u32> = (0..len)
+            .map(|_i| Decodable::consensus_decode(reader))
+            .collect::<Result<Vec<_>, EncodeError>>()?;
+
+        Ok(U32sWrapper(values))
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for InventorySketch {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        U32sWrapper(self.syndromes.clone()).consensus_encode(writer)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for InventorySketch {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let U32sWrapper(syndromes) = Decodable::consensus_decode(reader)?;
+
+        Ok(InventorySketch { syndromes })
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for ReconciliationDiff {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += self.success.consensus_encode(writer)?;
+        len += self.difference.consensus_encode(writer)?;
+        len += U32sWrapper(self.short_ids.clone()).consensus_encode(writer)?;
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for ReconciliationDiff {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let success = Decodable::consensus_decode(reader)?;
+        let difference = Decodable::consensus_decode(reader)?;
+        let U32sWrapper(short_ids) = Decodable::consensus_decode(reader)?;
+
+        Ok(ReconciliationDiff {
+            success,
+            difference,
+            short_ids,
+        })
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for PrefilledYuvTx {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += self.index.consensus_encode(writer)?;
+        len += self.tx.consensus_encode(writer)?;
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for PrefilledYuvTx {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let index = Decodable::consensus_decode(reader)?;
+        let tx = Decodable::consensus_decode(reader)?;
+
+        Ok(PrefilledYuvTx { index, tx })
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for CompactYuvTxs {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += self.nonce.consensus_encode(writer)?;
+
+        len += (self.short_ids.len() as u32).consensus_encode(writer)?;
+        for short_id in &self.short_ids {
+            len += short_id.consensus_encode(writer)?;
+        }
+
+        len += (self.prefilled.len() as u32).consensus_encode(writer)?;
+        for prefilled in &self.prefilled {
+            len += prefilled.consensus_encode(writer)?;
+        }
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for CompactYuvTxs {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let nonce = Decodable::consensus_decode(reader)?;
+
+        let len: u32 = Decodable::consensus_decode(reader)?;
+        let short_ids: Vec<ShortTxid> = (0..len)
+            .map(|_i| Decodable::consensus_decode(reader))
+            .collect::<Result<Vec<_>, EncodeError>>()?;
+
+        let len: u32 = Decodable::consensus_decode(reader)?;
+        let prefilled: Vec<PrefilledYuvTx> = (0..len)
+            .map(|_i| Decodable::consensus_decode(reader))
+            .collect::<Result<Vec<_>, EncodeError>>()?;
+
+        Ok(CompactYuvTxs {
+            nonce,
+            short_ids,
+            prefilled,
+        })
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for GetYuvTxn {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += self.nonce.consensus_encode(writer)?;
+        len += U32sWrapper(self.indexes.clone()).consensus_encode(writer)?;
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for GetYuvTxn {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let nonce = Decodable::consensus_decode(reader)?;
+        let U32sWrapper(indexes) = Decodable::consensus_decode(reader)?;
+
+        Ok(GetYuvTxn { nonce, indexes })
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Encodable for ChromaFilter {
+    fn consensus_encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut len = 0;
+
+        len += (self.chromas.len() as u32).consensus_encode(writer)?;
+        for chroma in &self.chromas {
+            len += chroma.to_bytes().consensus_encode(writer)?;
+        }
+
+        len += (self.script_prefixes.len() as u32).consensus_encode(writer)?;
+        for prefix in &self.script_prefixes {
+            len += prefix.consensus_encode(writer)?;
+        }
+
+        Ok(len)
+    }
+}
+
+#[cfg(all(feature = "messages", feature = "std"))]
+impl Decodable for ChromaFilter {
+    fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let len: u32 = Decodable::consensus_decode(reader)?;
+        let chromas: Vec<Chroma> = (0..len)
+            .map(|_i| {
+                let bytes: [u8; 32] = Decodable::consensus_decode(reader)?;
+                Chroma::from_bytes(&bytes).map_err(|_| EncodeError::ParseFailed("Invalid chroma"))
+            })
+            .collect::<Result<Vec<_>, EncodeError>>()?;
+
+        let len: u32 = Decodable::consensus_decode(reader)?;
+        let script_prefixes: Vec<Vec<u8>> = (0..len)
+            .map(|_i| Decodable::consensus_decode(reader))
+            .collect::<Result<Vec<_>, EncodeError>>()?;
+
+        Ok(ChromaFilter {
+            chromas,
+            script_prefixes,
+        })
+    }
+}
+
 #[cfg(all(test, feature = "serde", feature = "messages", feature = "std"))]
 mod tests {
     extern crate serde_json;
@@ -303,7 +691,16 @@ mod tests {
     use bitcoin::consensus::{Decodable, Encodable};
     use once_cell::sync::Lazy;
 
-    use crate::{messages::p2p::Inventory, YuvTransaction};
+    use bitcoin::hashes::sha256;
+    use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+
+    use crate::{
+        messages::p2p::{
+            decode_yuv_txs, encode_yuv_txs, ChromaFilter, CompactYuvTxs, GetYuvTxn, Inventory,
+            InventorySketch, PeerAuth, ReconciliationDiff, SignedInventory,
+        },
+        YuvTransaction,
+    };
 
     static YUV_TXS: Lazy<Vec<YuvTransaction>> = Lazy::new(|| {
         vec![
@@ -346,4 +743,387 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn test_signed_messages_consensus_encode() {
+        let ctx = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[1u8; 32]).expect("valid secret key");
+        let signature = ctx.sign_ecdsa(
+            &Message::from_hashed_data::<sha256::Hash>(b"nonce"),
+            &secret_key,
+        );
+
+        let auth = PeerAuth {
+            pubkey: secret_key.public_key(&ctx),
+            signature,
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        auth.consensus_encode(&mut bytes)
+            .expect("failed to encode the auth");
+        assert_eq!(bytes.len(), 33 + 64);
+
+        let decoded_auth =
+            PeerAuth::consensus_decode(&mut bytes.as_slice()).expect("failed to decode the auth");
+        assert_eq!(auth, decoded_auth, "Converting back and forth should work");
+
+        let signed_inv = SignedInventory {
+            inv: YUV_TXS
+                .iter()
+                .map(|tx| Inventory::Ytx(tx.bitcoin_tx.txid()))
+                .collect(),
+            signature,
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        signed_inv
+            .consensus_encode(&mut bytes)
+            .expect("failed to encode the signed inventory");
+
+        let decoded_signed_inv = SignedInventory::consensus_decode(&mut bytes.as_slice())
+            .expect("failed to decode the signed inventory");
+        assert_eq!(
+            signed_inv, decoded_signed_inv,
+            "Converting back and forth should work"
+        );
+    }
+
+    #[test]
+    fn test_reconciliation_messages_consensus_encode() {
+        let sketch = InventorySketch {
+            syndromes: vec![1, 0xdead_beef, u32::MAX],
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        sketch
+            .consensus_encode(&mut bytes)
+            .expect("failed to encode the sketch");
+        assert_eq!(bytes.len(), 4 + 3 * 4);
+
+        let decoded_sketch = InventorySketch::consensus_decode(&mut bytes.as_slice())
+            .expect("failed to decode the sketch");
+        assert_eq!(
+            sketch, decoded_sketch,
+            "Converting back and forth should work"
+        );
+
+        let diff = ReconciliationDiff {
+            success: true,
+            difference: 3,
+            short_ids: vec![42, 7],
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        diff.consensus_encode(&mut bytes)
+            .expect("failed to encode the diff");
+
+        let decoded_diff = ReconciliationDiff::consensus_decode(&mut bytes.as_slice())
+            .expect("failed to decode the diff");
+        assert_eq!(diff, decoded_diff, "Converting back and forth should work");
+    }
+
+    #[test]
+    fn test_compact_relay_messages_consensus_encode() {
+        let compact = CompactYuvTxs::new(42, YUV_TXS.clone(), |tx| {
+            tx.bitcoin_tx.txid() == YUV_TXS[1].bitcoin_tx.txid()
+        });
+        assert_eq!(compact.len(), YUV_TXS.len());
+        assert_eq!(compact.prefilled.len(), 1);
+        assert_eq!(
+            compact.short_txid_indexes(),
+            Some(vec![(
+                0,
+                CompactYuvTxs::short_txid(42, &YUV_TXS[0].bitcoin_tx.txid())
+            )])
+        );
+
+        let mut bytes: Vec<u8> = Vec::new();
+        compact
+            .consensus_encode(&mut bytes)
+            .expect("failed to encode the compact txs");
+
+        let decoded_compact = CompactYuvTxs::consensus_decode(&mut bytes.as_slice())
+            .expect("failed to decode the compact txs");
+        assert_eq!(
+            compact, decoded_compact,
+            "Converting back and forth should work"
+        );
+
+        let get_txn = GetYuvTxn {
+            nonce: 42,
+            indexes: vec![0, 3],
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        get_txn
+            .consensus_encode(&mut bytes)
+            .expect("failed to encode the request");
+
+        let decoded_get_txn = GetYuvTxn::consensus_decode(&mut bytes.as_slice())
+            .expect("failed to decode the request");
+        assert_eq!(
+            get_txn, decoded_get_txn,
+            "Converting back and forth should work"
+        );
+    }
+
+    #[test]
+    fn test_compact_txs_with_invalid_prefilled_indexes() {
+        let mut compact = CompactYuvTxs::new(42, YUV_TXS.clone(), |_| true);
+        assert_eq!(compact.short_txid_indexes(), Some(Vec::new()));
+
+        compact.prefilled.reverse();
+        assert_eq!(compact.short_txid_indexes(), None);
+
+        compact.prefilled.reverse();
+        compact.prefilled[1].index = YUV_TXS.len() as u32;
+        assert_eq!(compact.short_txid_indexes(), None);
+    }
+
+    #[test]
+    fn test_yuv_txs_payload_round_trip() {
+        let payload = encode_yuv_txs(&YUV_TXS);
+
+        let decoded_txs = decode_yuv_txs(&payload).expect("failed to decode the payload");
+        assert_eq!(
+            *YUV_TXS, decoded_txs,
+            "Converting back and forth should work"
+        );
+
+        assert!(decode_yuv_txs(&payload[..payload.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_chroma_filter_consensus_encode() {
+        let filter = ChromaFilter {
+            chromas: vec![YUV_TXS[1].chroma().expect("issuance has the chroma")],
+            script_prefixes: vec![vec![0x00, 0x14]],
+        };
+
+        let mut bytes: Vec<u8> = Vec::new();
+        filter
+            .consensus_encode(&mut bytes)
+            .expect("failed to encode the filter");
+
+        let decoded_filter = ChromaFilter::consensus_decode(&mut bytes.as_slice())
+            .expect("failed to decode the filter");
+        assert_eq!(
+            filter, decoded_filter,
+            "Converting back and forth should work"
+        );
+
+        assert!(filter.matches(&YUV_TXS[1]));
+        assert!(!ChromaFilter::default().matches(&YUV_TXS[1]));
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod round_trip_tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use bitcoin::absolute::LockTime;
+    use bitcoin::hashes::{hash160, sha256, Hash};
+    use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use bitcoin::{OutPoint, ScriptBuf, Transaction, TxIn, TxOut, Txid};
+    use yuv_pixels::{
+        Chroma, EmptyPixelProof, HtlcData, HtlcPixelProof, HtlcScriptKind,
+        LightningCommitmentProof, LightningHtlcData, LightningHtlcProof, MultisigPixelProof,
+        MusigPixelProof, Pixel, PixelProof, TimelockPixelProof,
+    };
+
+    use super::*;
+    use crate::announcements::{
+        BurnAnnouncement, ChromaAnnouncement, DelegateAnnouncement, DelegateCapabilities,
+        FreezeAnnouncement, FreezePubkeyAnnouncement, TransferOwnershipAnnouncement,
+        UpdateChromaAnnouncement,
+    };
+
+    /// Seeds of the keys, the values are checked for each of them.
+    const SEEDS: [u8; 4] = [1, 2, 0x7f, 0xc0];
+    const AMOUNTS: [u128; 4] = [0, 1, u64::MAX as u128 + 1, u128::MAX];
+
+    fn pubkey(seed: u8) -> PublicKey {
+        SecretKey::from_slice(&[seed; 32])
+            .expect("valid secret key")
+            .public_key(&Secp256k1::new())
+    }
+
+    fn chroma(seed: u8) -> Chroma {
+        Chroma::from(pubkey(seed).x_only_public_key().0)
+    }
+
+    fn proofs(seed: u8, amount: u128) -> Vec<PixelProof> {
+        let pixel = Pixel::new(amount, chroma(seed));
+        let (key, other_key) = (pubkey(seed), pubkey(seed.wrapping_add(1)));
+        let hash = hash160::Hash::hash(&[seed]);
+
+        vec![
+            PixelProof::EmptyPixel(EmptyPixelProof::new(key)),
+            PixelProof::sig(pixel, key),
+            PixelProof::Multisig(MultisigPixelProof::new(pixel, vec![key, other_key], 2)),
+            PixelProof::Lightning(LightningCommitmentProof::new(
+                pixel,
+                key,
+                u16::from(seed),
+                other_key,
+            )),
+            PixelProof::LightningHtlc(LightningHtlcProof::new(
+                pixel,
+                LightningHtlcData::new(hash, key, other_key, hash, HtlcScriptKind::Offered),
+            )),
+            PixelProof::LightningHtlc(LightningHtlcProof::new(
+                pixel,
+                LightningHtlcData::new(
+                    hash,
+                    key,
+                    other_key,
+                    hash,
+                    HtlcScriptKind::Received {
+                        cltv_expiry: u32::from(seed),
+                    },
+                ),
+            )),
+            PixelProof::p2wsh(pixel, key, ScriptBuf::from(vec![seed; seed as usize])),
+            PixelProof::Musig(MusigPixelProof::new(pixel, vec![key, other_key])),
+            PixelProof::Timelock(TimelockPixelProof::new(pixel, key, u32::from(seed))),
+            PixelProof::Htlc(HtlcPixelProof::new(
+                pixel,
+                HtlcData::new(sha256::Hash::hash(&[seed]), key, other_key, u32::from(seed)),
+            )),
+        ]
+    }
+
+    fn announcements(seed: u8, amount: u128) -> Vec<Announcement> {
+        let chroma = chroma(seed);
+        let outpoint = OutPoint::new(Txid::hash(&[seed]), u32::from(seed));
+
+        vec![
+            ChromaAnnouncement::new(
+                chroma,
+                "Token".to_string(),
+                "TKN".to_string(),
+                seed,
+                amount,
+                seed % 2 == 0,
+            )
+            .expect("valid announcement")
+            .into(),
+            FreezeAnnouncement::new(chroma, outpoint).into(),
+            FreezeAnnouncement::new_partial(chroma, outpoint, amount).into(),
+            IssueAnnouncement::new(chroma, amount).into(),
+            TransferOwnershipAnnouncement::new(chroma, ScriptBuf::from(vec![seed; 22])).into(),
+            BurnAnnouncement::new(chroma, amount).into(),
+            UpdateChromaAnnouncement::new(chroma, "New token".to_string(), "NTKN".to_string())
+                .expect("valid announcement")
+                .into(),
+            FreezePubkeyAnnouncement::new(chroma, pubkey(seed)).into(),
+            FreezePubkeyAnnouncement::new_unfreeze(chroma, pubkey(seed)).into(),
+            DelegateAnnouncement::new(chroma, pubkey(seed), DelegateCapabilities::ALL).into(),
+            DelegateAnnouncement::new_revoke(chroma, pubkey(seed)).into(),
+        ]
+    }
+
+    fn bitcoin_tx(seed: u8) -> Transaction {
+        Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::hash(&[seed]), 0),
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: u64::from(seed),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_pixel_proofs_round_trip() {
+        for seed in SEEDS {
+            for amount in AMOUNTS {
+                for proof in proofs(seed, amount) {
+                    round_trip_check(&proof).expect("proof should round trip");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_announcements_round_trip() {
+        for seed in SEEDS {
+            for amount in AMOUNTS {
+                for announcement in announcements(seed, amount) {
+                    round_trip_check(&announcement).expect("announcement should round trip");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_yuv_transactions_round_trip() {
+        for seed in SEEDS {
+            for amount in AMOUNTS {
+                let proofs = proofs(seed, amount)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, proof)| (i as u32, proof))
+                    .collect::<BTreeMap<_, _>>();
+
+                let mut tx_types = vec![
+                    YuvTxType::Issue {
+                        output_proofs: Some(proofs.clone()),
+                        announcement: IssueAnnouncement::new(chroma(seed), amount),
+                    },
+                    YuvTxType::Issue {
+                        output_proofs: None,
+                        announcement: IssueAnnouncement::new(chroma(seed), amount),
+                    },
+                    YuvTxType::Transfer {
+                        input_proofs: proofs.clone(),
+                        output_proofs: proofs,
+                    },
+                ];
+                tx_types.extend(
+                    announcements(seed, amount)
+                        .into_iter()
+                        .map(YuvTxType::Announcement),
+                );
+
+                for tx_type in tx_types {
+                    let tx = YuvTransaction::new(bitcoin_tx(seed), tx_type);
+                    round_trip_check(&tx).expect("transaction should round trip");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_check_detects_unstable_encoding() {
+        /// Value that reports the wrong length of its encoding.
+        #[derive(Debug, PartialEq)]
+        struct WrongLength;
+
+        impl Encodable for WrongLength {
+            fn consensus_encode<W: io::Write + ?Sized>(
+                &self,
+                writer: &mut W,
+            ) -> Result<usize, io::Error> {
+                0u8.consensus_encode(writer)?;
+                Ok(2)
+            }
+        }
+
+        impl Decodable for WrongLength {
+            fn consensus_decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+                let _: u8 = Decodable::consensus_decode(reader)?;
+                Ok(WrongLength)
+            }
+        }
+
+        assert!(matches!(
+            round_trip_check(&WrongLength),
+            Err(RoundTripError::LengthMismatch {
+                reported: 2,
+                written: 1
+            })
+        ));
+    }
 }
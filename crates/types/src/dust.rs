@@ -0,0 +1,68 @@
+//! Minimal amount of satoshis held by the pixel outputs.
+//!
+//! Every pixel is carried by a Bitcoin output, so the output must hold enough satoshis to be
+//! relayed by the Bitcoin nodes, i.e. not to be dust. The [`DustPolicy`] sets both that minimum,
+//! which is enforced by the nodes before the transactions are confirmed, and the default amount
+//! the wallets put into the new pixel outputs.
+
+use crate::network::Network;
+
+/// Default amount of satoshis put into the new pixel outputs.
+pub const DEFAULT_PIXEL_SATOSHIS: u64 = 1000;
+
+/// Dust limit of the largest standard segwit output (P2WSH or P2TR) at the default dust relay
+/// fee rate of Bitcoin Core.
+pub const SEGWIT_DUST_LIMIT: u64 = 330;
+
+/// Minimal and default amounts of satoshis of the pixel outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DustPolicy {
+    /// Pixel outputs with less satoshis are rejected.
+    pub min_satoshis: u64,
+    /// Amount of satoshis put into the new pixel outputs if not set explicitly.
+    pub default_satoshis: u64,
+}
+
+impl DustPolicy {
+    /// Policy of the network. Outputs of the public networks must not be dust, while on regtest
+    /// and the custom networks any amount is allowed.
+    pub fn for_network(network: Network) -> Self {
+        let min_satoshis = match network {
+            Network::Regtest | Network::Custom(_) => 0,
+            _ => SEGWIT_DUST_LIMIT,
+        };
+
+        Self {
+            min_satoshis,
+            default_satoshis: DEFAULT_PIXEL_SATOSHIS,
+        }
+    }
+
+    /// Check whether the output with the `satoshis` is dust under the policy.
+    pub fn is_dust(&self, satoshis: u64) -> bool {
+        satoshis < self.min_satoshis
+    }
+}
+
+impl Default for DustPolicy {
+    fn default() -> Self {
+        Self::for_network(Network::Bitcoin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dust_policy_for_network() {
+        let mainnet = DustPolicy::for_network(Network::Bitcoin);
+        assert!(mainnet.is_dust(SEGWIT_DUST_LIMIT - 1));
+        assert!(!mainnet.is_dust(SEGWIT_DUST_LIMIT));
+        assert!(!mainnet.is_dust(mainnet.default_satoshis));
+
+        let regtest = DustPolicy::for_network(Network::Regtest);
+        assert!(!regtest.is_dust(0));
+    }
+}
@@ -4,10 +4,11 @@ extern crate alloc;
 extern crate core;
 
 pub use announcements::{Announcement, AnyAnnouncement};
+pub use dust::DustPolicy;
 #[cfg(all(feature = "messages", feature = "std"))]
 pub use messages::{
-    ControllerMessage, ControllerP2PMessage, GraphBuilderMessage, IndexerMessage, TxCheckerMessage,
-    TxConfirmMessage,
+    ControllerMessage, ControllerP2PMessage, GraphBuilderMessage, IndexerMessage,
+    RevalidatorMessage, RpcSubscriptionMessage, TxCheckerMessage, TxConfirmMessage,
 };
 #[cfg(feature = "bulletproof")]
 pub use proofs::is_bulletproof;
@@ -17,12 +18,18 @@ pub use transactions::{YuvTransaction, YuvTxType};
 #[cfg(not(any(feature = "std", feature = "no-std")))]
 compile_error!("at least one of the `std` or `no-std` features must be enabled");
 
+pub mod activation;
 pub mod announcements;
+#[cfg(feature = "consensus")]
+pub mod bundle;
+pub mod dust;
 pub mod network;
+pub mod owners;
+pub mod supply;
 mod transactions;
 
 #[cfg(feature = "consensus")]
-mod consensus;
+pub mod consensus;
 #[cfg(all(feature = "messages", feature = "std"))]
 pub mod messages;
 
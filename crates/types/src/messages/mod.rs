@@ -1,13 +1,15 @@
 use alloc::vec::Vec;
-use bitcoin::Txid;
+use bitcoin::{ScriptBuf, Txid};
 use bitcoin_client::json::GetBlockTxResult;
 use core::fmt::Debug;
-use event_bus::Event;
+use event_bus::{Completion, Event};
 use std::net::SocketAddr;
+use yuv_pixels::Chroma;
 
+use crate::announcements::{ChromaAnnouncement, FreezeAnnouncement};
 use crate::YuvTransaction;
 
-use self::p2p::Inventory;
+use self::p2p::{CompactYuvTxs, Inventory};
 
 pub mod p2p;
 
@@ -28,14 +30,29 @@ pub enum ControllerMessage {
     /// Tranactions that passed the full check and are ready to be sent to tx attacher.
     FullyCheckedTxs(Vec<YuvTransaction>),
     /// Share transactions with one confirmation with the P2P peers.
-    MinedTxs(Vec<Txid>),
+    MinedTxs {
+        /// Ids of the transactions with their indexes in the block.
+        txs: Vec<(Txid, usize)>,
+        /// Height of the block the transactions are mined in.
+        block_height: usize,
+    },
     /// Send confirmed transactions to the tx checker for a full check.
     ConfirmedTxs(Vec<Txid>),
+    /// Transactions that are found in the Bitcoin mempool, but are not mined yet. Sent only if
+    /// the confirmator accepts unconfirmed transactions.
+    UnconfirmedTxs(Vec<Txid>),
+    /// Transactions that were waiting for the confirmation for too long and were removed from
+    /// the confirmator's queue.
+    ExpiredTxs(Vec<Txid>),
     /// Send signed transactions for on-chain confirmation.
     InitializeTxs(Vec<YuvTransaction>),
     /// Handle a reorg.
     Reorganization {
+        /// Transactions from the orphan blocks that were waiting for the confirmation.
         txs: Vec<Txid>,
+        /// The rest of the transactions from the orphan blocks. Some of them could be already
+        /// attached, so they must be rolled back along with their descendants.
+        orphaned_txs: Vec<Txid>,
         new_indexing_height: usize,
     },
     /// New inventory to share with peers.
@@ -65,10 +82,18 @@ pub enum ControllerP2PMessage {
         /// Address of the sender.
         sender: SocketAddr,
     },
+    /// Transactions announced with the short ids.
+    CmpctYuvTxs {
+        txs: CompactYuvTxs,
+        /// Address of the sender.
+        sender: SocketAddr,
+    },
 }
 
-/// Message to TxChecker service.
+/// Message to TxChecker service. Serializable, so the messages can be spilled to disk when the
+/// TxChecker falls behind.
 #[derive(Clone, Debug, Event)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TxCheckerMessage {
     /// New transactions to pass the full check. The transactions come along with the peer id of
     /// the sender:
@@ -84,15 +109,22 @@ pub enum TxCheckerMessage {
 pub enum GraphBuilderMessage {
     /// Transactions to attach that already have been checked.
     CheckedTxs(Vec<YuvTransaction>),
+    /// Clean up outdated transactions that are waiting for their parents. Sent by the scheduler,
+    /// which waits for the completion.
+    CleanUp(Completion),
 }
 
 /// Message to ConfirmationIndexer.
 #[derive(Clone, Debug, Event)]
 pub enum TxConfirmMessage {
-    /// Transactions that should be confirmed before sending to the tx checker.
-    Txs(Vec<Txid>),
+    /// Transactions that should be confirmed before sending to the tx checker. The number of
+    /// the required confirmations depends on the transaction's amount.
+    Txs(Vec<YuvTransaction>),
     /// Transactions that are confirmed.
     Block(Box<GetBlockTxResult>),
+    /// Clean up transactions that are waiting for confirmation for too long. Sent by the
+    /// scheduler, which waits for the completion.
+    CleanUp(Completion),
 }
 
 /// Message to Indexer service.
@@ -101,3 +133,46 @@ pub enum IndexerMessage {
     /// New height to index blocks from. Sent from the controller in case of reorg.
     Reorganization(usize),
 }
+
+/// Message to the Revalidator service.
+#[derive(Clone, Debug, Event)]
+pub enum RevalidatorMessage {
+    /// Changes of the chromas' owners by the announcements found in the indexed block. Sent
+    /// before the announcements are checked and applied.
+    OwnerChanges(Vec<OwnerChange>),
+    /// Re-check the attached issuances of the applied owner changes. Sent by the scheduler,
+    /// which waits for the completion.
+    Run(Completion),
+}
+
+/// Change of the chroma's owner by the transfer of the ownership, or by the chroma announcement
+/// with the issuer set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnerChange {
+    pub chroma: Chroma,
+    /// Script of the new owner.
+    pub owner: ScriptBuf,
+    /// Height of the block the announcement is mined in.
+    pub height: usize,
+}
+
+/// Message to the RPC server's subscriptions. Describes the node's state changes that are
+/// streamed to the subscribed clients.
+#[derive(Clone, Debug, Event)]
+pub enum RpcSubscriptionMessage {
+    /// Transactions that were attached to the graph and stored.
+    AttachedTxs(Vec<YuvTransaction>),
+    /// Freeze announcements that passed the full check.
+    Freezes(Vec<FreezeAnnouncement>),
+    /// Chroma announcements that passed the full check.
+    ChromaAnnouncements(Vec<ChromaAnnouncement>),
+    /// Transfers from the Bitcoin mempool that passed the isolated check. They are sent right
+    /// away, so they are neither confirmed nor fully checked yet.
+    UnconfirmedTransfers(Vec<YuvTransaction>),
+    /// Attached transactions that were rolled back by the reorg, along with their descendants.
+    /// They wait for the confirmation again.
+    DetachedTxs(Vec<YuvTransaction>),
+    /// Attached issuances that turned out to be invalid on the re-validation, as they aren't
+    /// signed by the owner the chroma had when they were mined. They are left attached.
+    InvalidatedTxs(Vec<YuvTransaction>),
+}
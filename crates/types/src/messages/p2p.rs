@@ -6,6 +6,7 @@ use crate::consensus::{InventoryWrapper, YuvTxsWrapper};
 
 use crate::YuvTransaction;
 use alloc::vec::Vec;
+use yuv_pixels::Chroma;
 
 #[cfg(feature = "consensus")]
 use alloc::vec;
@@ -16,18 +17,201 @@ use bitcoin::consensus::{
     Decodable, Encodable,
 };
 
+use bitcoin::hashes::{siphash24, Hash};
 use bitcoin::network::{message::CommandString, message_network::VersionMessage, Address, Magic};
+use bitcoin::secp256k1::{ecdsa::Signature, PublicKey};
 use bitcoin::Txid;
 
 #[cfg(feature = "consensus")]
 const MAX_MSG_SIZE: u64 = 5_000_000;
 
+/// Size of the short transaction ids in the compact relay.
+pub const SHORT_TXID_SIZE: usize = 6;
+
+/// Short id of the transaction in the [`CompactYuvTxs`].
+pub type ShortTxid = [u8; SHORT_TXID_SIZE];
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Inventory {
     /// Yuv tx ids
     Ytx(Txid),
 }
 
+/// Proof that the peer owns the key, sent after the `version` message. The signature is made
+/// over the nonce of the `version` messages, so it can't be replayed in other connections.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PeerAuth {
+    /// Public key the peer is authenticated with.
+    pub pubkey: PublicKey,
+    /// Signature of the handshake nonce.
+    pub signature: Signature,
+}
+
+/// Inventory signed with the key the sender is authenticated with.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignedInventory {
+    pub inv: Vec<Inventory>,
+    /// Signature of the inventory and the handshake nonce.
+    pub signature: Signature,
+}
+
+/// Sketch of the sender's inventory, which the receiver reconciles with its own one to find
+/// the difference between them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InventorySketch {
+    /// Odd power sums of the short ids of the inventory. The sketch can recover up to as many
+    /// differing items as there are syndromes.
+    pub syndromes: Vec<u32>,
+}
+
+/// Result of the reconciliation of the received [`InventorySketch`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReconciliationDiff {
+    /// Whether the difference was recovered from the sketch. If not, the sender of the sketch
+    /// is expected to send its full inventory.
+    pub success: bool,
+    /// Size of the recovered difference, used to pick the capacity of the next sketch.
+    pub difference: u32,
+    /// Short ids of the sketch sender's inventory items missing on the receiver.
+    pub short_ids: Vec<u32>,
+}
+
+/// Transaction of the [`CompactYuvTxs`] that is sent in full.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PrefilledYuvTx {
+    /// Index of the transaction in the announced batch.
+    pub index: u32,
+    pub tx: YuvTransaction,
+}
+
+/// Batch of YUV transactions, e.g. the ones mined in a block, announced with the short ids of
+/// the transactions the receiver likely has, and the rest of them sent in full.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CompactYuvTxs {
+    /// Random nonce the short ids are salted with. Identifies the batch in [`GetYuvTxn`].
+    pub nonce: u64,
+    /// Short ids of the transactions that aren't prefilled, in the order of the batch.
+    pub short_ids: Vec<ShortTxid>,
+    /// Transactions sent in full, in the order of the batch.
+    pub prefilled: Vec<PrefilledYuvTx>,
+}
+
+impl CompactYuvTxs {
+    /// Create the announcement of the transactions. The `prefill` ones are sent in full.
+    pub fn new(
+        nonce: u64,
+        txs: Vec<YuvTransaction>,
+        prefill: impl Fn(&YuvTransaction) -> bool,
+    ) -> Self {
+        let mut short_ids = Vec::new();
+        let mut prefilled = Vec::new();
+
+        for (index, tx) in txs.into_iter().enumerate() {
+            if prefill(&tx) {
+                prefilled.push(PrefilledYuvTx {
+                    index: index as u32,
+                    tx,
+                });
+            } else {
+                short_ids.push(Self::short_txid(nonce, &tx.bitcoin_tx.txid()));
+            }
+        }
+
+        Self {
+            nonce,
+            short_ids,
+            prefilled,
+        }
+    }
+
+    /// Short id of the transaction: the first 6 bytes of the SipHash-2-4 of the txid salted with
+    /// the nonce.
+    pub fn short_txid(nonce: u64, txid: &Txid) -> ShortTxid {
+        let hash = siphash24::Hash::hash_with_keys(nonce, !nonce, txid.as_byte_array());
+
+        let mut short_txid = [0u8; SHORT_TXID_SIZE];
+        short_txid.copy_from_slice(&hash.as_u64().to_le_bytes()[..SHORT_TXID_SIZE]);
+        short_txid
+    }
+
+    /// Number of the transactions in the batch.
+    pub fn len(&self) -> usize {
+        self.short_ids.len() + self.prefilled.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Indexes in the batch of the transactions sent by the short ids. Returns `None` if the
+    /// indexes of the prefilled transactions are out of the batch or not in the order.
+    pub fn short_txid_indexes(&self) -> Option<Vec<(u32, ShortTxid)>> {
+        let len = self.len() as u32;
+
+        let mut prev = None;
+        for prefilled in &self.prefilled {
+            if prefilled.index >= len || prev.is_some_and(|prev| prefilled.index <= prev) {
+                return None;
+            }
+            prev = Some(prefilled.index);
+        }
+
+        let mut prefilled = self.prefilled.iter().map(|tx| tx.index).peekable();
+        let mut short_ids = self.short_ids.iter();
+        let mut indexes = Vec::with_capacity(self.short_ids.len());
+        for index in 0..len {
+            if prefilled.next_if_eq(&index).is_some() {
+                continue;
+            }
+            indexes.push((index, *short_ids.next()?));
+        }
+
+        Some(indexes)
+    }
+}
+
+/// Request of the transactions of the [`CompactYuvTxs`] the receiver is missing.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GetYuvTxn {
+    /// Nonce of the announced batch.
+    pub nonce: u64,
+    /// Indexes of the missing transactions in the batch.
+    pub indexes: Vec<u32>,
+}
+
+/// Filter of the transactions relayed to the light peer that has sent it.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ChromaFilter {
+    /// Chromas of the matching transactions.
+    pub chromas: Vec<Chroma>,
+    /// Prefixes of the output scripts of the matching transactions.
+    pub script_prefixes: Vec<Vec<u8>>,
+}
+
+impl ChromaFilter {
+    /// Check whether the transaction is relayed to the peer, i.e. any of its outputs has one
+    /// of the chromas or a script starting with one of the prefixes.
+    pub fn matches(&self, tx: &YuvTransaction) -> bool {
+        let mut chromas = tx
+            .tx_type
+            .output_proofs()
+            .into_iter()
+            .flat_map(|proofs| proofs.values())
+            .map(|proof| proof.pixel().chroma)
+            .chain(tx.chroma());
+
+        if chromas.any(|chroma| self.chromas.contains(&chroma)) {
+            return true;
+        }
+
+        tx.bitcoin_tx.output.iter().any(|output| {
+            self.script_prefixes
+                .iter()
+                .any(|prefix| output.script_pubkey.as_bytes().starts_with(prefix))
+        })
+    }
+}
+
 /// Raw message which is sent between peers
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct RawNetworkMessage {
@@ -73,6 +257,39 @@ pub enum NetworkMessage {
     /// YTXIDACK method (acknowledges the support of YUV protocol )
     Ytxidack,
 
+    /// YUVAUTH method. Authenticates the peer with its key
+    Auth(PeerAuth),
+
+    /// SIGNEDINV method. Contains list of recent transaction ids signed by the authenticated peer
+    SignedInv(SignedInventory),
+
+    /// SENDRECON method (defines whether the node supports the inventory reconciliation)
+    SendRecon,
+
+    /// SKETCH method. Contains the sketch of the sender's inventory
+    Sketch(InventorySketch),
+
+    /// RECONCILDIFF method. Contains the result of the reconciliation of the received sketch
+    ReconcilDiff(ReconciliationDiff),
+
+    /// SENDCMPCT method (defines whether the node supports the compact relay)
+    SendCmpct,
+
+    /// CMPCTYUVTXS method. Contains the batch of transactions announced with the short ids
+    CmpctYuvTxs(CompactYuvTxs),
+
+    /// GETYUVTXN method. Contains the indexes of the missing transactions of the batch
+    GetYuvTxn(GetYuvTxn),
+
+    /// SENDCOMPR method (defines whether the node supports the compressed transactions)
+    SendCompr,
+
+    /// COMPRYUVTX method. Contains the zstd compressed payload of the YUVTX message
+    ComprYuvTx(Vec<u8>),
+
+    /// FILTERLOAD method. Contains the filter of the transactions relayed to the light peer
+    FilterLoad(ChromaFilter),
+
     /// Any other message.
     Unknown {
         /// The command of this message.
@@ -97,6 +314,17 @@ impl NetworkMessage {
             NetworkMessage::YtxidRelay => "ytxidrelay",
             NetworkMessage::Ytxidack => "ytxidack",
             NetworkMessage::GetAddr => "getaddr",
+            NetworkMessage::Auth(_) => "yuvauth",
+            NetworkMessage::SignedInv(_) => "signedinv",
+            NetworkMessage::SendRecon => "sendrecon",
+            NetworkMessage::Sketch(_) => "sketch",
+            NetworkMessage::ReconcilDiff(_) => "reconcildiff",
+            NetworkMessage::SendCmpct => "sendcmpct",
+            NetworkMessage::CmpctYuvTxs(_) => "cmpctyuvtxs",
+            NetworkMessage::GetYuvTxn(_) => "getyuvtxn",
+            NetworkMessage::SendCompr => "sendcompr",
+            NetworkMessage::ComprYuvTx(_) => "compryuvtx",
+            NetworkMessage::FilterLoad(_) => "filterload",
 
             _ => "unknown",
         }
@@ -134,8 +362,19 @@ impl Encodable for RawNetworkMessage {
             | NetworkMessage::WtxidRelay
             | NetworkMessage::YtxidRelay
             | NetworkMessage::Ytxidack
-            | NetworkMessage::GetAddr => vec![],
+            | NetworkMessage::GetAddr
+            | NetworkMessage::SendRecon
+            | NetworkMessage::SendCmpct
+            | NetworkMessage::SendCompr => vec![],
             NetworkMessage::Version(ref dat) => serialize_consensus(dat),
+            NetworkMessage::Auth(ref dat) => serialize_consensus(dat),
+            NetworkMessage::SignedInv(ref dat) => serialize_consensus(dat),
+            NetworkMessage::Sketch(ref dat) => serialize_consensus(dat),
+            NetworkMessage::ReconcilDiff(ref dat) => serialize_consensus(dat),
+            NetworkMessage::CmpctYuvTxs(ref dat) => serialize_consensus(dat),
+            NetworkMessage::GetYuvTxn(ref dat) => serialize_consensus(dat),
+            NetworkMessage::ComprYuvTx(ref dat) => dat.clone(),
+            NetworkMessage::FilterLoad(ref dat) => serialize_consensus(dat),
             NetworkMessage::Unknown {
                 payload: ref dat, ..
             } => serialize_consensus(dat),
@@ -155,6 +394,18 @@ pub fn serialize_consensus<T: Encodable + ?Sized>(data: &T) -> Vec<u8> {
     encoder
 }
 
+/// Encode the transactions as the payload of the `yuvtx` message.
+#[cfg(feature = "consensus")]
+pub fn encode_yuv_txs(txs: &[YuvTransaction]) -> Vec<u8> {
+    serialize_consensus(&YuvTxsWrapper(txs.to_vec()))
+}
+
+/// Decode the transactions from the payload of the `yuvtx` message.
+#[cfg(feature = "consensus")]
+pub fn decode_yuv_txs(payload: &[u8]) -> Result<Vec<YuvTransaction>, encode::Error> {
+    Ok(YuvTxsWrapper::consensus_decode(&mut &payload[..])?.0)
+}
+
 #[cfg(feature = "consensus")]
 impl Decodable for RawNetworkMessage {
     fn consensus_decode_from_finite_reader<R: io::Read + ?Sized>(
@@ -182,6 +433,19 @@ impl Decodable for RawNetworkMessage {
             "ytxidrelay" => NetworkMessage::YtxidRelay,
             "ytxidack" => NetworkMessage::Ytxidack,
             "getaddr" => NetworkMessage::GetAddr,
+            "yuvauth" => NetworkMessage::Auth(Decodable::consensus_decode(&mut mem_d)?),
+            "signedinv" => NetworkMessage::SignedInv(Decodable::consensus_decode(&mut mem_d)?),
+            "sendrecon" => NetworkMessage::SendRecon,
+            "sketch" => NetworkMessage::Sketch(Decodable::consensus_decode(&mut mem_d)?),
+            "reconcildiff" => {
+                NetworkMessage::ReconcilDiff(Decodable::consensus_decode(&mut mem_d)?)
+            }
+            "sendcmpct" => NetworkMessage::SendCmpct,
+            "cmpctyuvtxs" => NetworkMessage::CmpctYuvTxs(Decodable::consensus_decode(&mut mem_d)?),
+            "getyuvtxn" => NetworkMessage::GetYuvTxn(Decodable::consensus_decode(&mut mem_d)?),
+            "sendcompr" => NetworkMessage::SendCompr,
+            "compryuvtx" => NetworkMessage::ComprYuvTx(mem_d.into_inner()),
+            "filterload" => NetworkMessage::FilterLoad(Decodable::consensus_decode(&mut mem_d)?),
             _ => NetworkMessage::Unknown {
                 command: cmd,
                 payload: mem_d.into_inner(),
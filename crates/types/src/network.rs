@@ -4,14 +4,24 @@ use core::{fmt::Display, str::FromStr};
 use alloc::string::String;
 use bitcoin::{BlockHash, Network as BitcoinNetwork};
 
+use crate::activation::ActivationHeights;
+
 /// Mutiny network magic.
 pub const MUTINY_MAGIC: [u8; 4] = [0xCB, 0x2D, 0xDF, 0xA5];
+/// Testnet4 network magic, which the `bitcoin` crate doesn't know yet.
+pub const TESTNET4_MAGIC: [u8; 4] = [0x1C, 0x16, 0x3F, 0x28];
+/// Default port of the custom networks, the one of `Regtest`.
+pub const CUSTOM_DEFAULT_PORT: u16 = 18444;
 /// YUV genesis block for `Mainnet`.
 const MAINNET_GENESIS_BLOCK: &str =
     "00000000000000000000cde86faf8ea6994e4ca31ed351e55912f617f5dd8ee8";
 /// YUV genesis block for `Testnet`.
 const TESTNET_GENESIS_BLOCK: &str =
     "000000008ce763d0e9906fc5b50acdd7c8ddc5b1413b1b526f386500628a505c";
+/// YUV genesis block for `Testnet4`. The network was reset after the YUV launch, so the indexing
+/// starts from its very first block.
+const TESTNET4_GENESIS_BLOCK: &str =
+    "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043";
 /// YUV genesis block for `Mutiny`.
 const MUTINY_GENESIS_BLOCK: &str =
     "000002d06087e074a71f1e8e805dcb3264fa9ff4700250ba3f0e95ab05e61afa";
@@ -22,30 +32,82 @@ const MUTINY_GENESIS_BLOCK: &str =
 pub enum Network {
     Bitcoin,
     Testnet,
+    /// The testnet reset of BIP94. It has the addresses of the `Testnet`, but its own magic.
+    Testnet4,
     Signet,
     Regtest,
 
     // Custom Bitcoin network types:
     Mutiny,
+    /// Regtest-like network with its own parameters, e.g. a shared staging environment. The
+    /// protocol features are active from the start, unless their activation heights are set.
+    Custom(CustomNetwork),
+}
+
+/// Parameters of the [`Network::Custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomNetwork {
+    /// Magic of the P2P messages, so the nodes of different networks don't connect to each other.
+    pub magic: [u8; 4],
+    /// Default P2P port.
+    #[cfg_attr(feature = "serde", serde(default = "default_custom_port"))]
+    pub port: u16,
+    /// Block that contains the very first YUV transaction, the indexing starts from it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub genesis_block: Option<BlockHash>,
+    /// Heights starting from which the protocol features are active.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub activation_heights: ActivationHeights,
+}
+
+impl Default for CustomNetwork {
+    fn default() -> Self {
+        Self {
+            magic: BitcoinNetwork::Regtest.magic().to_bytes(),
+            port: CUSTOM_DEFAULT_PORT,
+            genesis_block: None,
+            activation_heights: ActivationHeights::default(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn default_custom_port() -> u16 {
+    CUSTOM_DEFAULT_PORT
 }
 
 impl Network {
     pub fn to_bitcoin_network(&self) -> BitcoinNetwork {
         match self {
             Network::Bitcoin => BitcoinNetwork::Bitcoin,
-            Network::Testnet => BitcoinNetwork::Testnet,
+            Network::Testnet | Network::Testnet4 => BitcoinNetwork::Testnet,
             Network::Signet => BitcoinNetwork::Signet,
-            Network::Regtest => BitcoinNetwork::Regtest,
+            Network::Regtest | Network::Custom(_) => BitcoinNetwork::Regtest,
             _ => BitcoinNetwork::Testnet,
         }
     }
 
     pub fn magic(&self) -> Magic {
-        // Mutiny network has custom network magic.
-        if let Network::Mutiny = self {
-            Magic::from_bytes(MUTINY_MAGIC)
-        } else {
-            self.to_bitcoin_network().magic()
+        match self {
+            // Mutiny network has custom network magic.
+            Network::Mutiny => Magic::from_bytes(MUTINY_MAGIC),
+            Network::Testnet4 => Magic::from_bytes(TESTNET4_MAGIC),
+            Network::Custom(params) => Magic::from_bytes(params.magic),
+            _ => self.to_bitcoin_network().magic(),
+        }
+    }
+
+    /// Default P2P port of the network, which is the one of the Bitcoin nodes.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Network::Bitcoin => 8333,
+            Network::Testnet => 18333,
+            Network::Testnet4 => 48333,
+            Network::Regtest => 18444,
+            Network::Signet => 38333,
+            Network::Mutiny => 38332,
+            Network::Custom(params) => params.port,
         }
     }
 
@@ -56,8 +118,14 @@ impl Network {
     /// List of supported networks:
     /// - `network::Bitcoin`
     /// - `network::Testnet`
+    /// - `network::Testnet4`
     /// - `network::Mutiny`
+    /// - `network::Custom`, if the genesis block is set in its parameters
     pub fn yuv_genesis_block(&self) -> Option<BlockHash> {
+        if let Network::Custom(params) = self {
+            return params.genesis_block;
+        }
+
         let Some(network_str) = self.get_block_by_network() else {
             return None;
         };
@@ -69,6 +137,7 @@ impl Network {
         match self {
             Network::Bitcoin => Some(MAINNET_GENESIS_BLOCK.into()),
             Network::Testnet => Some(TESTNET_GENESIS_BLOCK.into()),
+            Network::Testnet4 => Some(TESTNET4_GENESIS_BLOCK.into()),
             Network::Mutiny => Some(MUTINY_GENESIS_BLOCK.into()),
             _ => None,
         }
@@ -92,7 +161,7 @@ impl From<BitcoinNetwork> for Network {
         match network {
             BitcoinNetwork::Bitcoin => Self::Bitcoin,
             BitcoinNetwork::Testnet => Self::Testnet,
-            BitcoinNetwork::Signet => Self::Testnet,
+            BitcoinNetwork::Signet => Self::Signet,
             BitcoinNetwork::Regtest => Self::Regtest,
             _ => Self::Regtest,
         }
@@ -106,14 +175,22 @@ impl FromStr for Network {
         match s {
             "bitcoin" => Ok(Self::Bitcoin),
             "testnet" => Ok(Self::Testnet),
+            "testnet4" => Ok(Self::Testnet4),
             "regtest" => Ok(Self::Regtest),
             "signet" => Ok(Self::Signet),
             "mutiny" => Ok(Self::Mutiny),
+            "custom" => Ok(Self::Custom(CustomNetwork::default())),
             _ => Err(NetworkParseError::UnknownType),
         }
     }
 }
 
+/// Parse the Bitcoin network, including the ones the `bitcoin` crate doesn't know yet, which are
+/// mapped to the known ones with the same addresses, e.g. `testnet4` to the testnet.
+pub fn parse_bitcoin_network(s: &str) -> Result<BitcoinNetwork, NetworkParseError> {
+    Network::from_str(s).map(|network| network.to_bitcoin_network())
+}
+
 #[derive(Debug)]
 pub enum NetworkParseError {
     UnknownType,
@@ -135,3 +212,45 @@ impl std::error::Error for NetworkParseError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use bitcoin::secp256k1::XOnlyPublicKey;
+    use yuv_pixels::Chroma;
+
+    use super::*;
+
+    #[test]
+    fn test_testnet4_has_testnet_addresses_and_own_magic() {
+        let testnet4 = Network::from_str("testnet4").unwrap();
+
+        assert_eq!(testnet4.to_bitcoin_network(), BitcoinNetwork::Testnet);
+        assert_eq!(testnet4.magic(), Magic::from_bytes(TESTNET4_MAGIC));
+        assert_ne!(testnet4.magic(), Network::Testnet.magic());
+        assert_eq!(
+            parse_bitcoin_network("testnet4").unwrap(),
+            BitcoinNetwork::Testnet
+        );
+        assert_eq!(
+            testnet4.yuv_genesis_block(),
+            Some(BlockHash::from_str(TESTNET4_GENESIS_BLOCK).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_testnet4_chroma_address() {
+        let xonly = XOnlyPublicKey::from_str(
+            "0677b5829356bb5e0c0808478ac150a500ceab4894d09854b0f75fbe7b4162f8",
+        )
+        .unwrap();
+        let chroma = Chroma::from(xonly);
+
+        let address = chroma
+            .to_address(Network::Testnet4.to_bitcoin_network())
+            .to_string();
+
+        assert!(address.starts_with("tb1p"));
+        assert_eq!(Chroma::from_address(&address).unwrap(), chroma);
+    }
+}
@@ -0,0 +1,108 @@
+//! Owners of the pixels of the transactions: the keys the unspent outputs and the changes of the
+//! balances are indexed by.
+//!
+//! Only the outputs with the tokens locked by single or multiple signatures have owners. The keys
+//! are x-only, so the outputs are found regardless of the parity of the owner's key.
+use alloc::collections::BTreeMap;
+use alloc::{vec, vec::Vec};
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+use bitcoin::OutPoint;
+use yuv_pixels::{Chroma, Pixel, PixelProof};
+
+use crate::YuvTransaction;
+
+/// Returns the outputs of the transaction with the keys of their owners.
+pub fn owned_outputs(yuv_tx: &YuvTransaction) -> Vec<(XOnlyPublicKey, OutPoint, Pixel)> {
+    let Some(output_proofs) = yuv_tx.tx_type.output_proofs() else {
+        return Vec::new();
+    };
+
+    let txid = yuv_tx.bitcoin_tx.txid();
+
+    output_proofs
+        .iter()
+        .flat_map(|(vout, proof)| owned_pixels(OutPoint::new(txid, *vout), proof))
+        .collect()
+}
+
+/// Returns the inputs of the transaction with the keys of their owners.
+pub fn owned_inputs(yuv_tx: &YuvTransaction) -> Vec<(XOnlyPublicKey, OutPoint, Pixel)> {
+    let Some(input_proofs) = yuv_tx.tx_type.input_proofs() else {
+        return Vec::new();
+    };
+
+    input_proofs
+        .iter()
+        .filter_map(|(index, proof)| {
+            let input = yuv_tx.bitcoin_tx.input.get(*index as usize)?;
+
+            Some(owned_pixels(input.previous_output, proof))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Returns the unspent outputs of the transactions by the keys of their owners. All the outputs
+/// are added before the spent ones are removed, so the result doesn't depend on the order of the
+/// transactions.
+pub fn owned_utxos<'a>(
+    txs: impl IntoIterator<Item = &'a YuvTransaction> + Clone,
+) -> BTreeMap<XOnlyPublicKey, BTreeMap<OutPoint, Pixel>> {
+    let mut utxos: BTreeMap<XOnlyPublicKey, BTreeMap<OutPoint, Pixel>> = BTreeMap::new();
+
+    for yuv_tx in txs.clone() {
+        for (owner, outpoint, pixel) in owned_outputs(yuv_tx) {
+            utxos.entry(owner).or_default().insert(outpoint, pixel);
+        }
+    }
+
+    for yuv_tx in txs {
+        for (owner, outpoint, _) in owned_inputs(yuv_tx) {
+            if let Some(owner_utxos) = utxos.get_mut(&owner) {
+                owner_utxos.remove(&outpoint);
+            }
+        }
+    }
+
+    utxos.retain(|_, owner_utxos| !owner_utxos.is_empty());
+    utxos
+}
+
+/// Returns the changes of the balances made by the transaction by the chroma and the owner.
+pub fn balance_deltas(yuv_tx: &YuvTransaction) -> BTreeMap<(Chroma, XOnlyPublicKey), i128> {
+    let mut deltas: BTreeMap<(Chroma, XOnlyPublicKey), i128> = BTreeMap::new();
+
+    for (owner, _, pixel) in owned_outputs(yuv_tx) {
+        *deltas.entry((pixel.chroma, owner)).or_default() += pixel.luma.amount as i128;
+    }
+
+    for (owner, _, pixel) in owned_inputs(yuv_tx) {
+        *deltas.entry((pixel.chroma, owner)).or_default() -= pixel.luma.amount as i128;
+    }
+
+    deltas
+}
+
+/// Returns the pixel of the output for each of its owners.
+fn owned_pixels(outpoint: OutPoint, proof: &PixelProof) -> Vec<(XOnlyPublicKey, OutPoint, Pixel)> {
+    if proof.is_empty_pixelproof() {
+        return Vec::new();
+    }
+
+    let owners = match proof {
+        PixelProof::Sig(proof) => vec![proof.inner_key],
+        PixelProof::Multisig(proof) => proof.inner_keys.clone(),
+        PixelProof::Musig(proof) => proof.inner_keys.clone(),
+        PixelProof::Timelock(proof) => vec![proof.inner_key],
+        PixelProof::Htlc(proof) => vec![proof.data.recipient_pubkey, proof.data.sender_pubkey],
+        _ => return Vec::new(),
+    };
+
+    let pixel = proof.pixel();
+
+    owners
+        .iter()
+        .map(|owner| (owner.x_only_public_key().0, outpoint, pixel))
+        .collect()
+}
@@ -0,0 +1,328 @@
+//! Sparse Merkle sum tree of the unspent pixels of a chroma.
+//!
+//! Every unspent pixel output is a leaf at the path given by the hash of its outpoint, and every
+//! node commits to the hashes and the sums of the amounts of its children, so the root commits to
+//! both the set of the unspent outputs and the circulating supply of the chroma. The subtrees
+//! with a single leaf are replaced by the leaf itself, so the depth of the tree is logarithmic in
+//! the number of leaves.
+//!
+//! An [`InclusionProof`] proves that the output with the given amount is a part of the supply
+//! committed by the root, without the rest of the outputs.
+use alloc::vec::Vec;
+
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::OutPoint;
+use yuv_pixels::{Pixel, PixelProof, ZERO_PUBLIC_KEY};
+
+use crate::YuvTransaction;
+
+/// Tag of the leaf hash preimage.
+const LEAF_TAG: u8 = 0x00;
+/// Tag of the internal node hash preimage.
+const NODE_TAG: u8 = 0x01;
+
+/// Number of bits in the leaf path.
+const PATH_BITS: usize = 256;
+
+/// Node of the supply tree: the hash of the subtree and the sum of its amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SupplyNode {
+    pub hash: sha256::Hash,
+    pub sum: u128,
+}
+
+impl SupplyNode {
+    /// Node of the empty subtree.
+    pub fn empty() -> Self {
+        Self {
+            hash: sha256::Hash::all_zeros(),
+            sum: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::empty()
+    }
+
+    /// Leaf of the unspent output with the `amount` of tokens.
+    pub fn leaf(outpoint: &OutPoint, amount: u128) -> Self {
+        let mut engine = sha256::Hash::engine();
+        engine.input(&[LEAF_TAG]);
+        engine.input(&leaf_path(outpoint));
+        engine.input(&amount.to_be_bytes());
+
+        Self {
+            hash: sha256::Hash::from_engine(engine),
+            sum: amount,
+        }
+    }
+
+    /// Parent of the `left` and `right` nodes. The parent of the empty nodes is empty.
+    pub fn parent(left: &Self, right: &Self) -> Self {
+        if left.is_empty() && right.is_empty() {
+            return Self::empty();
+        }
+
+        let mut engine = sha256::Hash::engine();
+        engine.input(&[NODE_TAG]);
+        engine.input(left.hash.as_byte_array());
+        engine.input(&left.sum.to_be_bytes());
+        engine.input(right.hash.as_byte_array());
+        engine.input(&right.sum.to_be_bytes());
+
+        Self {
+            hash: sha256::Hash::from_engine(engine),
+            sum: left.sum.saturating_add(right.sum),
+        }
+    }
+}
+
+/// Sparse Merkle sum tree of the unspent pixels of a chroma.
+#[derive(Debug, Clone, Default)]
+pub struct SupplyTree {
+    /// Leaves sorted by their paths.
+    leaves: Vec<([u8; 32], OutPoint, u128)>,
+}
+
+impl SupplyTree {
+    /// Build the tree from the unspent outputs and their amounts.
+    pub fn new(utxos: impl IntoIterator<Item = (OutPoint, u128)>) -> Self {
+        let mut leaves = utxos
+            .into_iter()
+            .map(|(outpoint, amount)| (leaf_path(&outpoint), outpoint, amount))
+            .collect::<Vec<_>>();
+
+        leaves.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        leaves.dedup_by(|a, b| a.0 == b.0);
+
+        Self { leaves }
+    }
+
+    /// Number of the unspent outputs in the tree.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Root of the tree. Its sum is the circulating supply of the chroma.
+    pub fn root(&self) -> SupplyNode {
+        subtree_root(&self.leaves, 0)
+    }
+
+    /// Build the proof of the inclusion of the `outpoint` into the tree, or `None` if the
+    /// output is not in the tree.
+    pub fn inclusion_proof(&self, outpoint: &OutPoint) -> Option<InclusionProof> {
+        let path = leaf_path(outpoint);
+        let index = self
+            .leaves
+            .binary_search_by(|leaf| leaf.0.cmp(&path))
+            .ok()?;
+        let amount = self.leaves[index].2;
+
+        let mut siblings = Vec::new();
+        let mut leaves = self.leaves.as_slice();
+        let mut depth = 0;
+
+        while leaves.len() > 1 {
+            let split = leaves.partition_point(|leaf| !path_bit(&leaf.0, depth));
+            let (left, right) = leaves.split_at(split);
+
+            if path_bit(&path, depth) {
+                siblings.push(subtree_root(left, depth + 1));
+                leaves = right;
+            } else {
+                siblings.push(subtree_root(right, depth + 1));
+                leaves = left;
+            }
+
+            depth += 1;
+        }
+
+        // Siblings are collected from the root, but applied from the leaf.
+        siblings.reverse();
+
+        Some(InclusionProof {
+            outpoint: *outpoint,
+            amount,
+            siblings,
+        })
+    }
+}
+
+/// Proof of the inclusion of the unspent output into the [`SupplyTree`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InclusionProof {
+    pub outpoint: OutPoint,
+    /// Amount of tokens in the output.
+    pub amount: u128,
+    /// Siblings of the nodes on the path from the leaf to the root.
+    pub siblings: Vec<SupplyNode>,
+}
+
+impl InclusionProof {
+    /// Compute the root of the tree from the leaf and the siblings.
+    pub fn root(&self) -> SupplyNode {
+        let path = leaf_path(&self.outpoint);
+        let depth = self.siblings.len();
+
+        self.siblings.iter().enumerate().fold(
+            SupplyNode::leaf(&self.outpoint, self.amount),
+            |node, (i, sibling)| {
+                if path_bit(&path, depth - 1 - i) {
+                    SupplyNode::parent(sibling, &node)
+                } else {
+                    SupplyNode::parent(&node, sibling)
+                }
+            },
+        )
+    }
+
+    /// Check that the proof leads to the `root`.
+    pub fn verify(&self, root: &SupplyNode) -> bool {
+        self.siblings.len() <= PATH_BITS && self.root() == *root
+    }
+}
+
+/// Returns the outputs of the transaction that are counted in the supply.
+pub fn supply_outputs(yuv_tx: &YuvTransaction) -> Vec<(OutPoint, Pixel)> {
+    let Some(output_proofs) = yuv_tx.tx_type.output_proofs() else {
+        return Vec::new();
+    };
+
+    let txid = yuv_tx.bitcoin_tx.txid();
+
+    output_proofs
+        .iter()
+        .filter_map(|(vout, proof)| Some((OutPoint::new(txid, *vout), supply_pixel(proof)?)))
+        .collect()
+}
+
+/// Returns the inputs of the transaction that are counted in the supply.
+pub fn supply_inputs(yuv_tx: &YuvTransaction) -> Vec<(OutPoint, Pixel)> {
+    let Some(input_proofs) = yuv_tx.tx_type.input_proofs() else {
+        return Vec::new();
+    };
+
+    input_proofs
+        .iter()
+        .filter_map(|(index, proof)| {
+            let input = yuv_tx.bitcoin_tx.input.get(*index as usize)?;
+
+            Some((input.previous_output, supply_pixel(proof)?))
+        })
+        .collect()
+}
+
+/// Returns the pixel of the proof if it's counted in the supply. The empty pixels, the pixels
+/// with the hidden amounts and the burnt pixels are not.
+fn supply_pixel(proof: &PixelProof) -> Option<Pixel> {
+    if proof.is_empty_pixelproof() {
+        return None;
+    }
+
+    #[cfg(feature = "bulletproof")]
+    if proof.is_bulletproof() {
+        return None;
+    }
+
+    if let PixelProof::Sig(sig) = proof {
+        if sig.inner_key == ZERO_PUBLIC_KEY.inner {
+            return None;
+        }
+    }
+
+    Some(proof.pixel())
+}
+
+/// Path of the output's leaf, i.e. the hash of the outpoint.
+fn leaf_path(outpoint: &OutPoint) -> [u8; 32] {
+    let mut engine = sha256::Hash::engine();
+    engine.input(outpoint.txid.as_byte_array());
+    engine.input(&outpoint.vout.to_le_bytes());
+
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+/// Returns `true` if the path goes to the right at the `depth`.
+fn path_bit(path: &[u8; 32], depth: usize) -> bool {
+    path[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+/// Root of the subtree at the `depth` with the sorted `leaves`.
+fn subtree_root(leaves: &[([u8; 32], OutPoint, u128)], depth: usize) -> SupplyNode {
+    match leaves {
+        [] => SupplyNode::empty(),
+        [(_, outpoint, amount)] => SupplyNode::leaf(outpoint, *amount),
+        _ => {
+            let split = leaves.partition_point(|leaf| !path_bit(&leaf.0, depth));
+            let (left, right) = leaves.split_at(split);
+
+            SupplyNode::parent(
+                &subtree_root(left, depth + 1),
+                &subtree_root(right, depth + 1),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use bitcoin::Txid;
+
+    use super::*;
+
+    fn outpoint(vout: u32) -> OutPoint {
+        OutPoint::new(
+            Txid::from_str("a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90")
+                .unwrap(),
+            vout,
+        )
+    }
+
+    #[test]
+    fn test_root_sums_amounts() {
+        let tree = SupplyTree::new((0..10).map(|vout| (outpoint(vout), 100)));
+
+        assert_eq!(tree.len(), 10);
+        assert_eq!(tree.root().sum, 1000);
+        assert!(SupplyTree::default().root().is_empty());
+    }
+
+    #[test]
+    fn test_root_does_not_depend_on_order() {
+        let utxos = (0..10).map(|vout| (outpoint(vout), vout as u128 + 1));
+
+        assert_eq!(
+            SupplyTree::new(utxos.clone()).root(),
+            SupplyTree::new(utxos.rev()).root()
+        );
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let tree = SupplyTree::new((0..10).map(|vout| (outpoint(vout), vout as u128 + 1)));
+        let root = tree.root();
+
+        for vout in 0..10 {
+            let proof = tree.inclusion_proof(&outpoint(vout)).unwrap();
+
+            assert_eq!(proof.amount, vout as u128 + 1);
+            assert!(proof.verify(&root));
+
+            let forged = InclusionProof {
+                amount: proof.amount + 1,
+                ..proof
+            };
+            assert!(!forged.verify(&root));
+        }
+
+        assert!(tree.inclusion_proof(&outpoint(10)).is_none());
+    }
+}
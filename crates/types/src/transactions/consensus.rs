@@ -23,6 +23,12 @@ impl YuvTransaction {
         YuvTransaction::consensus_decode(&mut reader)
             .map_err(|_err| YuvTransactionParseError::InvalidTx)
     }
+
+    /// Returns the exact size of the transaction in the consensus encoding in bytes. The hex
+    /// encoded transaction is twice as large.
+    pub fn consensus_size(&self) -> usize {
+        bitcoin::consensus::serialize(self).len()
+    }
 }
 
 impl YuvTxType {
@@ -40,6 +46,11 @@ impl YuvTxType {
         YuvTxType::consensus_decode(&mut reader)
             .map_err(|_err| YuvTransactionParseError::InvalidProofs)
     }
+
+    /// Returns the exact size of the proofs in the consensus encoding in bytes.
+    pub fn consensus_size(&self) -> usize {
+        bitcoin::consensus::serialize(self).len()
+    }
 }
 
 /// Error that can occur when converting hex data in a `YuvTransaction` and vice versa.
@@ -1,6 +1,10 @@
 use bitcoin::Transaction;
+use yuv_pixels::Chroma;
 
-use crate::announcements::{Announcement, IssueAnnouncement};
+use alloc::vec::Vec;
+
+use crate::announcements::{Announcement, BurnAnnouncement, IssueAnnouncement};
+use crate::AnyAnnouncement;
 use crate::ProofMap;
 
 #[cfg(feature = "consensus")]
@@ -27,6 +31,14 @@ impl YuvTransaction {
         }
     }
 
+    /// Returns the exact size of the transaction serialized to JSON in bytes.
+    #[cfg(feature = "serde")]
+    pub fn json_size(&self) -> usize {
+        serde_json::to_vec(self)
+            .expect("Transaction should serialize to JSON")
+            .len()
+    }
+
     /// Checks if the transaction is bulletproof.
     ///
     /// Returns `true` if it is a bulletproof transaction, `false` otherwise.
@@ -54,6 +66,54 @@ impl YuvTransaction {
 
         false
     }
+
+    /// Returns the chroma of the transaction: the announced one, or the chroma of the first
+    /// output of the transfer. `None` if the transaction has no outputs with proofs.
+    pub fn chroma(&self) -> Option<Chroma> {
+        match &self.tx_type {
+            YuvTxType::Issue { announcement, .. } => Some(announcement.chroma),
+            YuvTxType::Announcement(announcement) => Some(announcement.chroma()),
+            YuvTxType::Transfer { output_proofs, .. } => output_proofs
+                .values()
+                .next()
+                .map(|proof| proof.pixel().chroma),
+        }
+    }
+
+    /// Returns the total amount of the transaction: the issued amount, or the sum of the
+    /// transfer's outputs. Announcements move no tokens, so their amount is zero. `None` if the
+    /// amounts are hidden by the bulletproofs.
+    pub fn amount(&self) -> Option<u128> {
+        #[cfg(feature = "bulletproof")]
+        if self.is_bulletproof() {
+            return None;
+        }
+
+        let amount = match &self.tx_type {
+            YuvTxType::Issue { announcement, .. } => announcement.amount,
+            YuvTxType::Announcement(_) => 0,
+            YuvTxType::Transfer { output_proofs, .. } => {
+                output_proofs.values().fold(0u128, |sum, proof| {
+                    sum.saturating_add(proof.pixel().luma.amount)
+                })
+            }
+        };
+
+        Some(amount)
+    }
+
+    /// Returns the [`BurnAnnouncement`]s from the `OP_RETURN` outputs of the transfer.
+    pub fn burn_announcements(&self) -> Vec<BurnAnnouncement> {
+        if !matches!(self.tx_type, YuvTxType::Transfer { .. }) {
+            return Vec::new();
+        }
+
+        self.bitcoin_tx
+            .output
+            .iter()
+            .filter_map(|output| BurnAnnouncement::from_script(&output.script_pubkey).ok())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -72,6 +132,14 @@ pub enum YuvTxType {
 }
 
 impl YuvTxType {
+    /// Returns the exact size of the proofs serialized to JSON in bytes.
+    #[cfg(feature = "serde")]
+    pub fn json_size(&self) -> usize {
+        serde_json::to_vec(self)
+            .expect("Proofs should serialize to JSON")
+            .len()
+    }
+
     /// Return output proofs if possible
     pub fn output_proofs(&self) -> Option<&ProofMap> {
         match self {
@@ -0,0 +1,38 @@
+use core::str::FromStr;
+
+use bitcoin::secp256k1::{PublicKey, SecretKey};
+use wasm_bindgen::prelude::*;
+use yuv_pixels::{PixelKey, PixelPrivateKey};
+
+use crate::JsPixel;
+
+/// Tweaks the owner's `innerKey` by the pixel into the key that locks the output, returns the
+/// hex of the compressed key.
+#[wasm_bindgen(js_name = pixelKey)]
+pub fn pixel_key(pixel: &JsPixel, inner_key: &str) -> Result<String, JsError> {
+    let inner_key = PublicKey::from_str(inner_key)?;
+
+    Ok(PixelKey::new(pixel.0, &inner_key)?.to_string())
+}
+
+/// Tweaks the owner's `secretKey` by the pixel into the key that signs the spending of the
+/// output, returns the hex of the key.
+#[wasm_bindgen(js_name = pixelPrivateKey)]
+pub fn pixel_private_key(pixel: &JsPixel, secret_key: &str) -> Result<String, JsError> {
+    let secret_key = SecretKey::from_str(secret_key)?;
+
+    Ok(PixelPrivateKey::new(pixel.0, &secret_key)?
+        .display_secret()
+        .to_string())
+}
+
+/// Returns the hex of the `P2WPKH` script of the output with the pixel owned by the `innerKey`.
+#[wasm_bindgen(js_name = pixelScript)]
+pub fn pixel_script(pixel: &JsPixel, inner_key: &str) -> Result<String, JsError> {
+    let inner_key = PublicKey::from_str(inner_key)?;
+    let script = PixelKey::new(pixel.0, &inner_key)?
+        .to_p2wpkh()
+        .ok_or_else(|| JsError::new("Pixel key is not compressed"))?;
+
+    Ok(hex::encode(script.as_bytes()))
+}
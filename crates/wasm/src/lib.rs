@@ -0,0 +1,13 @@
+#![doc = include_str!("../README.md")]
+
+mod keys;
+pub use keys::{pixel_key, pixel_private_key, pixel_script};
+
+mod pixel;
+pub use pixel::JsPixel;
+
+mod proof;
+pub use proof::JsPixelProof;
+
+mod transaction;
+pub use transaction::JsYuvTransaction;
@@ -0,0 +1,86 @@
+use core::str::FromStr;
+
+use bitcoin::Network;
+use wasm_bindgen::prelude::*;
+use yuv_pixels::{Chroma, Pixel, PixelHash};
+
+/// Pixel of the output: the amount and the chroma of the tokens.
+#[wasm_bindgen(js_name = Pixel)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsPixel(pub(crate) Pixel);
+
+#[wasm_bindgen(js_class = Pixel)]
+impl JsPixel {
+    /// Creates the pixel with the decimal `amount` of the tokens of the `chroma`, which is the
+    /// hex of the issuer's x-only key or its Taproot address.
+    #[wasm_bindgen(constructor)]
+    pub fn new(amount: &str, chroma: &str) -> Result<JsPixel, JsError> {
+        let amount = u128::from_str(amount)?;
+
+        Ok(Self(Pixel::new(amount, parse_chroma(chroma)?)))
+    }
+
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<JsPixel, JsError> {
+        Ok(Self(Pixel::from_bytes(&hex::decode(hex)?)?))
+    }
+
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0.to_bytes())
+    }
+
+    /// Decimal amount of the tokens.
+    #[wasm_bindgen(getter)]
+    pub fn amount(&self) -> String {
+        self.0.luma.amount.to_string()
+    }
+
+    /// Hex of the issuer's x-only key.
+    #[wasm_bindgen(getter)]
+    pub fn chroma(&self) -> String {
+        self.0.chroma.to_string()
+    }
+
+    /// Taproot address of the chroma in the `network`, e.g. `bitcoin` or `regtest`.
+    #[wasm_bindgen(js_name = chromaAddress)]
+    pub fn chroma_address(&self, network: &str) -> Result<String, JsError> {
+        let network = Network::from_str(network)?;
+
+        Ok(self.0.chroma.to_address(network).to_string())
+    }
+
+    /// Hex of the `PXH` hash the keys of the output are tweaked by.
+    #[wasm_bindgen(js_name = pixelHash)]
+    pub fn pixel_hash(&self) -> String {
+        PixelHash::from(&self.0).to_string()
+    }
+}
+
+/// Parses the chroma from the hex of the x-only key or the Taproot address.
+pub(crate) fn parse_chroma(chroma: &str) -> Result<Chroma, JsError> {
+    if let Ok(chroma) = Chroma::from_address(chroma) {
+        return Ok(chroma);
+    }
+
+    Ok(Chroma::from_str(chroma)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROMA: &str = "93bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+
+    #[test]
+    fn test_pixel_encoding() {
+        let pixel = JsPixel::new("340282366920938463463374607431768211455", CHROMA).unwrap();
+        assert_eq!(pixel.amount(), u128::MAX.to_string());
+        assert_eq!(pixel.chroma(), CHROMA);
+
+        let address = pixel.chroma_address("regtest").unwrap();
+        assert_eq!(JsPixel::new("1", &address).unwrap().chroma(), CHROMA);
+
+        assert_eq!(JsPixel::from_hex(&pixel.to_hex()).unwrap(), pixel);
+    }
+}
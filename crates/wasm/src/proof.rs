@@ -0,0 +1,123 @@
+use core::str::FromStr;
+
+use bitcoin::consensus::encode::{deserialize, serialize_hex};
+use bitcoin::secp256k1::PublicKey;
+use wasm_bindgen::prelude::*;
+use yuv_pixels::{CheckableProof, PixelProof};
+
+use crate::{transaction::parse_bitcoin_tx, JsPixel};
+
+/// Proof that the output of the Bitcoin transaction holds the pixel, or that the input spends it.
+#[wasm_bindgen(js_name = PixelProof)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsPixelProof(pub(crate) PixelProof);
+
+#[wasm_bindgen(js_class = PixelProof)]
+impl JsPixelProof {
+    /// Creates the single signature proof of the `P2WPKH` output with the pixel owned by the
+    /// `innerKey`.
+    pub fn sig(pixel: &JsPixel, inner_key: &str) -> Result<JsPixelProof, JsError> {
+        let inner_key = PublicKey::from_str(inner_key)?;
+
+        Ok(Self(PixelProof::sig(pixel.0, inner_key)))
+    }
+
+    /// Parses the consensus encoded proof.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<JsPixelProof, JsError> {
+        Ok(Self(deserialize(&hex::decode(hex)?)?))
+    }
+
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        serialize_hex(&self.0)
+    }
+
+    /// Parses the proof of any kind from the JSON returned by the node.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<JsPixelProof, JsError> {
+        Ok(Self(serde_json::from_str(json)?))
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&self.0)?)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn pixel(&self) -> JsPixel {
+        JsPixel(self.0.pixel())
+    }
+
+    /// Checks that the output `vout` of the hex encoded Bitcoin transaction is locked by the
+    /// proof, throws the reason if it isn't.
+    #[wasm_bindgen(js_name = checkByOutput)]
+    pub fn check_by_output(&self, tx: &str, vout: u32) -> Result<(), JsError> {
+        let tx = parse_bitcoin_tx(tx)?;
+        let output = tx
+            .output
+            .get(vout as usize)
+            .ok_or_else(|| JsError::new(&format!("Transaction has no output {}", vout)))?;
+
+        Ok(self.0.checked_check_by_output(output)?)
+    }
+
+    /// Checks that the input `vin` of the hex encoded Bitcoin transaction spends the output
+    /// with the proof, throws the reason if it doesn't.
+    #[wasm_bindgen(js_name = checkByInput)]
+    pub fn check_by_input(&self, tx: &str, vin: u32) -> Result<(), JsError> {
+        let tx = parse_bitcoin_tx(tx)?;
+        let input = tx
+            .input
+            .get(vin as usize)
+            .ok_or_else(|| JsError::new(&format!("Transaction has no input {}", vin)))?;
+
+        Ok(self.0.checked_check_by_input(input)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::absolute::LockTime;
+    use bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use bitcoin::{ScriptBuf, Transaction, TxIn, TxOut};
+
+    use super::*;
+    use crate::pixel_script;
+
+    const CHROMA: &str = "93bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+    const SECRET: &str = "f9e17ee5b837fece0695f9782253604586ab1daf42ecf2762573243c7a6979f4";
+
+    #[test]
+    fn test_sig_proof_check_by_output() {
+        let ctx = Secp256k1::new();
+        let inner_key = SecretKey::from_str(SECRET)
+            .unwrap()
+            .public_key(&ctx)
+            .to_string();
+
+        let pixel = JsPixel::new("1000", CHROMA).unwrap();
+        let proof = JsPixelProof::sig(&pixel, &inner_key).unwrap();
+
+        let script =
+            ScriptBuf::from_bytes(hex::decode(pixel_script(&pixel, &inner_key).unwrap()).unwrap());
+        let tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![TxOut {
+                value: 1000,
+                script_pubkey: script,
+            }],
+        };
+
+        proof.check_by_output(&serialize_hex(&tx), 0).unwrap();
+
+        assert_eq!(JsPixelProof::from_hex(&proof.to_hex()).unwrap(), proof);
+        assert_eq!(
+            JsPixelProof::from_json(&proof.to_json().unwrap()).unwrap(),
+            proof
+        );
+        assert_eq!(proof.pixel(), pixel);
+    }
+}
@@ -0,0 +1,88 @@
+use bitcoin::consensus::encode::deserialize;
+use bitcoin::Transaction;
+use wasm_bindgen::prelude::*;
+use yuv_pixels::CheckableProof;
+use yuv_types::YuvTransaction;
+
+use crate::JsPixelProof;
+
+/// Bitcoin transaction with the proofs of its inputs and outputs.
+#[wasm_bindgen(js_name = YuvTransaction)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsYuvTransaction(pub(crate) YuvTransaction);
+
+#[wasm_bindgen(js_class = YuvTransaction)]
+impl JsYuvTransaction {
+    /// Parses the consensus encoded transaction, e.g. returned by `getrawyuvtransaction`.
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Result<JsYuvTransaction, JsError> {
+        Ok(Self(YuvTransaction::from_hex(hex.to_string())?))
+    }
+
+    #[wasm_bindgen(js_name = toHex)]
+    pub fn to_hex(&self) -> String {
+        self.0.hex()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn txid(&self) -> String {
+        self.0.bitcoin_tx.txid().to_string()
+    }
+
+    /// Hex of the Bitcoin transaction without the proofs.
+    #[wasm_bindgen(js_name = bitcoinTx)]
+    pub fn bitcoin_tx(&self) -> String {
+        bitcoin::consensus::encode::serialize_hex(&self.0.bitcoin_tx)
+    }
+
+    /// Proof of the output `vout`, `undefined` if the output has no proof.
+    #[wasm_bindgen(js_name = outputProof)]
+    pub fn output_proof(&self, vout: u32) -> Option<JsPixelProof> {
+        let proof = self.0.tx_type.output_proofs()?.get(&vout)?;
+
+        Some(JsPixelProof(proof.clone()))
+    }
+
+    /// Proof of the output spent by the input `vin`, `undefined` if the input has no proof.
+    #[wasm_bindgen(js_name = inputProof)]
+    pub fn input_proof(&self, vin: u32) -> Option<JsPixelProof> {
+        let proof = self.0.tx_type.input_proofs()?.get(&vin)?;
+
+        Some(JsPixelProof(proof.clone()))
+    }
+
+    /// Checks the proofs of all the inputs and outputs by the Bitcoin transaction, throws the
+    /// reason if any of them is invalid.
+    ///
+    /// The rest of the checks, e.g. that the transfer doesn't create the tokens, are done by the
+    /// node with `yuv-tx-check`.
+    #[wasm_bindgen(js_name = checkProofs)]
+    pub fn check_proofs(&self) -> Result<(), JsError> {
+        let tx = &self.0.bitcoin_tx;
+
+        for (vin, proof) in self.0.tx_type.input_proofs().into_iter().flatten() {
+            let input = tx
+                .input
+                .get(*vin as usize)
+                .ok_or_else(|| JsError::new(&format!("Transaction has no input {}", vin)))?;
+
+            proof.checked_check_by_input(input)?;
+        }
+
+        for (vout, proof) in self.0.tx_type.output_proofs().into_iter().flatten() {
+            let output = tx
+                .output
+                .get(*vout as usize)
+                .ok_or_else(|| JsError::new(&format!("Transaction has no output {}", vout)))?;
+
+            proof.checked_check_by_output(output)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the hex encoded Bitcoin transaction.
+pub(crate) fn parse_bitcoin_tx(hex: &str) -> Result<Transaction, JsError> {
+    Ok(deserialize(&hex::decode(hex)?)?)
+}
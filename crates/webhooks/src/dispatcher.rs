@@ -0,0 +1,354 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+use eyre::{bail, Context};
+use reqwest::header::CONTENT_TYPE;
+use tokio::sync::{broadcast, broadcast::error::RecvError, Mutex};
+use tokio_util::sync::CancellationToken;
+use yuv_storage::{TransactionsStorage, WebhookDelivery, WebhookQueue, WebhookQueueStorage};
+use yuv_types::RpcSubscriptionMessage;
+
+use crate::{Notification, Webhook, WebhookEvent};
+
+/// Number of the attempts to deliver the notification after which it's dropped.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+/// Number of the queued notifications after which the oldest ones are dropped.
+pub const DEFAULT_MAX_QUEUE_SIZE: usize = 10_000;
+/// Time to wait for the response of the webhook.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header with the HMAC-SHA256 signature of the payload, see [`sign`].
+pub const SIGNATURE_HEADER: &str = "x-yuv-signature";
+const EVENT_HEADER: &str = "x-yuv-event";
+const DELIVERY_HEADER: &str = "x-yuv-delivery";
+
+/// How often the queue is checked for the notifications to deliver.
+const DELIVERY_INTERVAL: Duration = Duration::from_secs(1);
+/// Max number of the notifications delivered at once.
+const MAX_DELIVERIES_PER_ROUND: usize = 100;
+/// Delay before the first retry, doubled with each failed attempt.
+const BASE_RETRY_DELAY_SECS: u64 = 5;
+const MAX_RETRY_DELAY_SECS: u64 = 3600;
+
+/// Posts the notifications about the attached, frozen and reorged transactions to the
+/// [`Webhook`]s whose filters they match.
+///
+/// The notifications are queued in the storage before the delivery, so they survive the node
+/// restarts, and are retried with the exponential backoff until the webhook answers with a
+/// successful status.
+pub struct WebhookDispatcher<TS, SS> {
+    webhooks: Vec<Webhook>,
+    txs_storage: TS,
+    state_storage: SS,
+    client: reqwest::Client,
+    max_attempts: u32,
+    max_queue_size: usize,
+    /// Queue loaded from the storage, written back after each change.
+    queue: Arc<Mutex<WebhookQueue>>,
+}
+
+impl<TS, SS> WebhookDispatcher<TS, SS>
+where
+    TS: TransactionsStorage + Send + Sync + 'static,
+    SS: WebhookQueueStorage + Send + Sync + 'static,
+{
+    pub fn new(webhooks: Vec<Webhook>, txs_storage: TS, state_storage: SS) -> Self {
+        Self {
+            webhooks,
+            txs_storage,
+            state_storage,
+            client: reqwest::Client::new(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            max_queue_size: DEFAULT_MAX_QUEUE_SIZE,
+            queue: Arc::new(Mutex::new(WebhookQueue::default())),
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_max_queue_size(mut self, max_queue_size: usize) -> Self {
+        self.max_queue_size = max_queue_size;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> eyre::Result<Self> {
+        self.client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .wrap_err("failed to build the HTTP client")?;
+
+        Ok(self)
+    }
+
+    /// Queues the notifications about the `events` and delivers them until cancelled.
+    pub async fn run(
+        self,
+        mut events: broadcast::Receiver<RpcSubscriptionMessage>,
+        cancellation: CancellationToken,
+    ) {
+        match self.state_storage.get_webhook_queue().await {
+            Ok(queue) => *self.queue.lock().await = queue,
+            Err(err) => {
+                tracing::error!("Failed to load the webhook queue: {}", err);
+                cancellation.cancel();
+                return;
+            }
+        }
+
+        let mut timer = tokio::time::interval(DELIVERY_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = events.recv() => {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::warn!(
+                                "Webhook dispatcher is lagging, skipped {} events",
+                                skipped,
+                            );
+                            continue;
+                        }
+                        Err(RecvError::Closed) => {
+                            tracing::trace!("All incoming events senders are dropped");
+                            return;
+                        }
+                    };
+
+                    if let Err(err) = self.handle_event(event).await {
+                        tracing::error!("Failed to queue the webhook notifications: {:?}", err);
+                    }
+                }
+                _ = timer.tick() => {
+                    if let Err(err) = self.deliver_due().await {
+                        tracing::error!("Failed to deliver the webhook notifications: {:?}", err);
+                    }
+                }
+                _ = cancellation.cancelled() => {
+                    tracing::trace!("Cancellation received, stopping webhook dispatcher");
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn handle_event(&self, event: RpcSubscriptionMessage) -> eyre::Result<()> {
+        let notifications = match event {
+            RpcSubscriptionMessage::AttachedTxs(txs) => txs
+                .iter()
+                .map(|tx| Notification::from_tx(WebhookEvent::Attached, tx))
+                .collect(),
+            RpcSubscriptionMessage::DetachedTxs(txs) => txs
+                .iter()
+                .map(|tx| Notification::from_tx(WebhookEvent::Reorged, tx))
+                .collect(),
+            RpcSubscriptionMessage::InvalidatedTxs(txs) => txs
+                .iter()
+                .map(|tx| Notification::from_tx(WebhookEvent::Invalidated, tx))
+                .collect(),
+            RpcSubscriptionMessage::Freezes(freezes) => {
+                let mut notifications = Vec::with_capacity(freezes.len());
+                for freeze in freezes {
+                    let frozen_tx = self.txs_storage.get_yuv_tx(&freeze.outpoint.txid).await?;
+                    notifications.push(Notification::from_freeze(freeze, frozen_tx.as_ref()));
+                }
+
+                notifications
+            }
+            RpcSubscriptionMessage::ChromaAnnouncements(_)
+            | RpcSubscriptionMessage::UnconfirmedTransfers(_) => return Ok(()),
+        };
+
+        self.enqueue(notifications).await
+    }
+
+    async fn enqueue(&self, notifications: Vec<Notification>) -> eyre::Result<()> {
+        let mut queue = self.queue.lock().await;
+        let mut is_changed = false;
+        let now = unix_now();
+
+        for mut notification in notifications {
+            let webhooks = self
+                .webhooks
+                .iter()
+                .filter(|webhook| webhook.matches(&notification))
+                .collect::<Vec<_>>();
+            if webhooks.is_empty() {
+                continue;
+            }
+
+            notification.id = queue.next_id;
+            queue.next_id += 1;
+
+            let body = serde_json::to_string(&notification)?;
+            for webhook in webhooks {
+                queue.deliveries.push_back(WebhookDelivery {
+                    id: notification.id,
+                    url: webhook.url.clone(),
+                    event: notification.event.as_str().to_string(),
+                    body: body.clone(),
+                    attempts: 0,
+                    next_attempt_at: now,
+                });
+            }
+
+            is_changed = true;
+        }
+
+        if !is_changed {
+            return Ok(());
+        }
+
+        while queue.deliveries.len() > self.max_queue_size {
+            if let Some(dropped) = queue.deliveries.pop_front() {
+                tracing::warn!(
+                    id = dropped.id,
+                    url = dropped.url,
+                    "Webhook queue is full, the oldest notification is dropped"
+                );
+            }
+        }
+
+        self.state_storage.put_webhook_queue(queue.clone()).await?;
+
+        Ok(())
+    }
+
+    /// Delivers the notifications whose attempt is due, and reschedules the failed ones.
+    async fn deliver_due(&self) -> eyre::Result<()> {
+        let now = unix_now();
+        let due = {
+            let queue = self.queue.lock().await;
+            queue
+                .deliveries
+                .iter()
+                .filter(|delivery| delivery.next_attempt_at <= now)
+                .take(MAX_DELIVERIES_PER_ROUND)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        if due.is_empty() {
+            return Ok(());
+        }
+
+        let results =
+            futures::future::join_all(due.iter().map(|delivery| self.deliver(delivery))).await;
+
+        let mut queue = self.queue.lock().await;
+        for (delivery, result) in due.iter().zip(results) {
+            let Some(position) = queue
+                .deliveries
+                .iter()
+                .position(|queued| queued.id == delivery.id && queued.url == delivery.url)
+            else {
+                continue;
+            };
+
+            let Err(err) = result else {
+                queue.deliveries.remove(position);
+                continue;
+            };
+
+            let queued = &mut queue.deliveries[position];
+            queued.attempts += 1;
+
+            if queued.attempts >= self.max_attempts {
+                tracing::warn!(
+                    id = delivery.id,
+                    url = delivery.url,
+                    "Webhook notification is dropped after {} attempts: {}",
+                    queued.attempts,
+                    err
+                );
+                queue.deliveries.remove(position);
+                continue;
+            }
+
+            tracing::debug!(
+                id = delivery.id,
+                url = delivery.url,
+                "Failed to deliver the webhook notification: {}",
+                err
+            );
+            queued.next_attempt_at = now + retry_delay(queued.attempts);
+        }
+
+        self.state_storage.put_webhook_queue(queue.clone()).await?;
+
+        Ok(())
+    }
+
+    async fn deliver(&self, delivery: &WebhookDelivery) -> eyre::Result<()> {
+        let mut request = self
+            .client
+            .post(&delivery.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, delivery.id);
+
+        let secret = self
+            .webhooks
+            .iter()
+            .find(|webhook| webhook.url == delivery.url)
+            .and_then(|webhook| webhook.secret.as_ref());
+        if let Some(secret) = secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &delivery.body));
+        }
+
+        let response = request.body(delivery.body.clone()).send().await?;
+        if !response.status().is_success() {
+            bail!("webhook answered with {}", response.status());
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the value of the [`SIGNATURE_HEADER`]: `sha256=` followed by the hex of the
+/// HMAC-SHA256 of the payload with the webhook's secret.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(body.as_bytes());
+
+    format!("sha256={}", hmac::Hmac::<sha256::Hash>::from_engine(engine))
+}
+
+fn retry_delay(attempts: u32) -> u64 {
+    BASE_RETRY_DELAY_SECS
+        .saturating_mul(1u64 << attempts.saturating_sub(1).min(16))
+        .min(MAX_RETRY_DELAY_SECS)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // HMAC-SHA256 test vector from RFC 4231.
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), 5);
+        assert_eq!(retry_delay(2), 10);
+        assert_eq!(retry_delay(3), 20);
+        assert_eq!(retry_delay(20), MAX_RETRY_DELAY_SECS);
+    }
+}
@@ -0,0 +1,13 @@
+#![doc = include_str!("../README.md")]
+
+mod webhook;
+pub use webhook::{Webhook, WebhookEvent};
+
+mod notification;
+pub use notification::Notification;
+
+mod dispatcher;
+pub use dispatcher::{
+    sign, WebhookDispatcher, DEFAULT_MAX_ATTEMPTS, DEFAULT_MAX_QUEUE_SIZE, DEFAULT_TIMEOUT,
+    SIGNATURE_HEADER,
+};
@@ -0,0 +1,95 @@
+use std::collections::BTreeSet;
+
+use bitcoin::{secp256k1::PublicKey, Txid};
+use serde::{Deserialize, Serialize};
+use yuv_pixels::{Chroma, PixelProof};
+use yuv_types::{announcements::FreezeAnnouncement, YuvTransaction, YuvTxType};
+
+use crate::WebhookEvent;
+
+/// JSON payload of the webhook notification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Id of the notification, the same for all the webhooks and all the attempts to deliver it,
+    /// so the receivers can skip the duplicates.
+    pub id: u64,
+    pub event: WebhookEvent,
+    /// Id of the attached or reorged transaction, or of the one whose output is frozen.
+    pub txid: Txid,
+    /// Chromas of the tokens the transaction moves.
+    pub chromas: BTreeSet<Chroma>,
+    /// Keys that own the inputs and outputs of the transaction, or the frozen output.
+    pub pubkeys: BTreeSet<PublicKey>,
+    /// Announcement of the freeze, only for the [`WebhookEvent::Frozen`] notifications.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub freeze: Option<FreezeAnnouncement>,
+}
+
+impl Notification {
+    /// Creates the notification about the attached or reorged transaction. The id is assigned
+    /// when the notification is queued.
+    pub(crate) fn from_tx(event: WebhookEvent, tx: &YuvTransaction) -> Self {
+        let mut chromas = BTreeSet::new();
+        if let YuvTxType::Issue { announcement, .. } = &tx.tx_type {
+            chromas.insert(announcement.chroma);
+        }
+
+        let mut pubkeys = BTreeSet::new();
+        let proofs = tx
+            .tx_type
+            .input_proofs()
+            .into_iter()
+            .chain(tx.tx_type.output_proofs())
+            .flat_map(|proofs| proofs.values())
+            .filter(|proof| !proof.is_empty_pixelproof());
+
+        for proof in proofs {
+            chromas.insert(proof.pixel().chroma);
+            pubkeys.extend(proof_keys(proof));
+        }
+
+        Self {
+            id: 0,
+            event,
+            txid: tx.bitcoin_tx.txid(),
+            chromas,
+            pubkeys,
+            freeze: None,
+        }
+    }
+
+    /// Creates the notification about the freeze. The keys are taken from the proof of the
+    /// frozen output, if the transaction is known to the node.
+    pub(crate) fn from_freeze(
+        freeze: FreezeAnnouncement,
+        frozen_tx: Option<&YuvTransaction>,
+    ) -> Self {
+        let pubkeys = frozen_tx
+            .and_then(|tx| tx.tx_type.output_proofs())
+            .and_then(|proofs| proofs.get(&freeze.outpoint.vout))
+            .map(|proof| proof_keys(proof).into_iter().collect())
+            .unwrap_or_default();
+
+        Self {
+            id: 0,
+            event: WebhookEvent::Frozen,
+            txid: freeze.outpoint.txid,
+            chromas: BTreeSet::from([freeze.chroma]),
+            pubkeys,
+            freeze: Some(freeze),
+        }
+    }
+}
+
+/// Returns the keys the output with the proof is spendable by. Outputs of the other types are
+/// not matched against the keys of the webhooks.
+fn proof_keys(proof: &PixelProof) -> Vec<PublicKey> {
+    match proof {
+        PixelProof::Sig(proof) => vec![proof.inner_key],
+        PixelProof::Multisig(proof) => proof.inner_keys.clone(),
+        PixelProof::Musig(proof) => proof.inner_keys.clone(),
+        PixelProof::Timelock(proof) => vec![proof.inner_key],
+        PixelProof::Htlc(proof) => vec![proof.data.recipient_pubkey, proof.data.sender_pubkey],
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeSet;
+
+use bitcoin::secp256k1::PublicKey;
+use serde::{Deserialize, Serialize};
+use yuv_pixels::Chroma;
+
+use crate::Notification;
+
+/// Event the webhooks are notified about.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// Transaction is attached to the graph.
+    Attached,
+    /// Output of the transaction is frozen by the issuer.
+    Frozen,
+    /// Attached transaction is rolled back by the reorg, and waits for the confirmation again.
+    Reorged,
+    /// Attached issuance isn't signed by the owner the chroma had when it was mined.
+    Invalidated,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Attached => "attached",
+            Self::Frozen => "frozen",
+            Self::Reorged => "reorged",
+            Self::Invalidated => "invalidated",
+        }
+    }
+}
+
+/// URL the notifications are posted to, along with the filters of the notifications. The empty
+/// filter matches all of them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub url: String,
+    /// Secret the payloads are signed with, see [`sign`](crate::sign).
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Events to notify about.
+    #[serde(default)]
+    pub events: BTreeSet<WebhookEvent>,
+    /// Chromas whose tokens the transactions have to move.
+    #[serde(default)]
+    pub chromas: BTreeSet<Chroma>,
+    /// Keys that have to own the inputs or outputs of the transactions.
+    #[serde(default)]
+    pub pubkeys: BTreeSet<PublicKey>,
+}
+
+impl Webhook {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: None,
+            events: BTreeSet::new(),
+            chromas: BTreeSet::new(),
+            pubkeys: BTreeSet::new(),
+        }
+    }
+
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    pub fn with_events(mut self, events: impl IntoIterator<Item = WebhookEvent>) -> Self {
+        self.events = events.into_iter().collect();
+        self
+    }
+
+    pub fn with_chromas(mut self, chromas: impl IntoIterator<Item = Chroma>) -> Self {
+        self.chromas = chromas.into_iter().collect();
+        self
+    }
+
+    pub fn with_pubkeys(mut self, pubkeys: impl IntoIterator<Item = PublicKey>) -> Self {
+        self.pubkeys = pubkeys.into_iter().collect();
+        self
+    }
+
+    /// Checks the notification against the filters. The keys are compared by their X coordinate
+    /// only, as the parity of the keys in the proofs may differ from the configured ones.
+    pub fn matches(&self, notification: &Notification) -> bool {
+        if !self.events.is_empty() && !self.events.contains(&notification.event) {
+            return false;
+        }
+
+        if !self.chromas.is_empty()
+            && !notification
+                .chromas
+                .iter()
+                .any(|chroma| self.chromas.contains(chroma))
+        {
+            return false;
+        }
+
+        self.pubkeys.is_empty()
+            || notification.pubkeys.iter().any(|pubkey| {
+                self.pubkeys
+                    .iter()
+                    .any(|watched| watched.x_only_public_key().0 == pubkey.x_only_public_key().0)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bitcoin::{hashes::Hash, Txid};
+
+    use super::*;
+
+    const PUBKEY: &str = "0393bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+    const CHROMA: &str = "93bd2ac1aafed1aa6f8b9fb8c1aca4dbe3cba5a8da83cef5bd5ca99e1bdb1a9c";
+    const OTHER_CHROMA: &str = "5510996bdb5271f84896eb42ea5b6c4ba3bd96f90a605c70a7f2b402f0afdad0";
+
+    fn notification() -> Notification {
+        Notification {
+            id: 1,
+            event: WebhookEvent::Attached,
+            txid: Txid::all_zeros(),
+            chromas: BTreeSet::from([Chroma::from_str(CHROMA).unwrap()]),
+            pubkeys: BTreeSet::from([PublicKey::from_str(PUBKEY).unwrap()]),
+            freeze: None,
+        }
+    }
+
+    #[test]
+    fn test_webhook_filters() {
+        let notification = notification();
+        let pubkey = PublicKey::from_str(PUBKEY).unwrap();
+
+        assert!(Webhook::new("http://localhost").matches(&notification));
+
+        let webhook = Webhook::new("http://localhost").with_events([WebhookEvent::Frozen]);
+        assert!(!webhook.matches(&notification));
+
+        let webhook = Webhook::new("http://localhost")
+            .with_chromas([Chroma::from_str(OTHER_CHROMA).unwrap()]);
+        assert!(!webhook.matches(&notification));
+
+        // The keys with the other parity are matched too.
+        let (xonly, _) = pubkey.x_only_public_key();
+        let negated = PublicKey::from_x_only_public_key(xonly, bitcoin::secp256k1::Parity::Even);
+        let webhook = Webhook::new("http://localhost")
+            .with_events([WebhookEvent::Attached])
+            .with_pubkeys([negated]);
+        assert!(webhook.matches(&notification));
+    }
+}
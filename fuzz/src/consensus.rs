@@ -0,0 +1,215 @@
+//! Targets of the consensus encoding: the values decoded from the fuzz input and the ones
+//! generated from it must survive the [`round_trip_check`].
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use bitcoin::absolute::LockTime;
+use bitcoin::consensus::{Decodable, Encodable};
+use bitcoin::{Transaction, TxIn, TxOut};
+use yuv_pixels::{
+    EmptyPixelProof, HtlcData, HtlcPixelProof, HtlcScriptKind, LightningCommitmentProof,
+    LightningHtlcData, LightningHtlcProof, MultisigPixelProof, MusigPixelProof, PixelProof,
+    TimelockPixelProof,
+};
+use yuv_types::announcements::{
+    Announcement, BurnAnnouncement, ChromaAnnouncement, DelegateAnnouncement, DelegateCapabilities,
+    FreezeAnnouncement, FreezePubkeyAnnouncement, IssueAnnouncement, TransferOwnershipAnnouncement,
+    UpdateChromaAnnouncement, MAX_NAME_SIZE, MAX_SYMBOL_SIZE, MIN_NAME_SIZE, MIN_SYMBOL_SIZE,
+};
+use yuv_types::consensus::round_trip_check;
+use yuv_types::{YuvTransaction, YuvTxType};
+
+use crate::input::Input;
+
+/// Max number of the proofs in the generated transaction.
+const MAX_PROOFS: u8 = 8;
+
+/// Max length of the generated scripts.
+const MAX_SCRIPT_LEN: usize = 64;
+
+/// Length limits of the new owner's script in the [`TransferOwnershipAnnouncement`].
+const MIN_OWNER_SCRIPT_LEN: usize = 16;
+const MAX_OWNER_SCRIPT_LEN: usize = 48;
+
+/// Decodes the value from the raw fuzz input, and checks the round trip of the decoded one.
+pub fn check_decoded<T>(data: &[u8])
+where
+    T: Encodable + Decodable + PartialEq + Debug,
+{
+    let Ok(value) = T::consensus_decode(&mut &data[..]) else {
+        return;
+    };
+
+    assert_round_trip(&value);
+}
+
+pub fn check_generated_proofs(input: &mut Input) {
+    for _ in 0..MAX_PROOFS {
+        assert_round_trip(&pixel_proof(input));
+    }
+}
+
+pub fn check_generated_announcements(input: &mut Input) {
+    if let Some(announcement) = announcement(input) {
+        assert_round_trip(&announcement);
+    }
+}
+
+pub fn check_generated_transaction(input: &mut Input) {
+    let tx_type = match input.u8() % 3 {
+        0 => YuvTxType::Issue {
+            output_proofs: input.bool().then(|| proof_map(input)),
+            announcement: IssueAnnouncement::new(input.chroma(), input.u128()),
+        },
+        1 => YuvTxType::Transfer {
+            input_proofs: proof_map(input),
+            output_proofs: proof_map(input),
+        },
+        _ => match announcement(input) {
+            Some(announcement) => YuvTxType::Announcement(announcement),
+            None => return,
+        },
+    };
+
+    let bitcoin_tx = Transaction {
+        version: 2,
+        lock_time: LockTime::from_consensus(input.u32()),
+        // The transaction without inputs is ambiguous with the segwit one.
+        input: vec![TxIn {
+            previous_output: input.outpoint(),
+            ..Default::default()
+        }],
+        output: vec![TxOut {
+            value: u64::from(input.u32()),
+            script_pubkey: input.script(0, MAX_SCRIPT_LEN),
+        }],
+    };
+
+    assert_round_trip(&YuvTransaction::new(bitcoin_tx, tx_type));
+}
+
+fn assert_round_trip<T>(value: &T)
+where
+    T: Encodable + Decodable + PartialEq + Debug,
+{
+    if let Err(err) = round_trip_check(value) {
+        panic!("{:?} failed the round trip: {}", value, err);
+    }
+}
+
+fn proof_map(input: &mut Input) -> BTreeMap<u32, PixelProof> {
+    (0..input.u8() % MAX_PROOFS)
+        .map(|_| (input.u32(), pixel_proof(input)))
+        .collect()
+}
+
+/// Generates one of the [`PixelProof`] variants, except the bulletproofs.
+fn pixel_proof(input: &mut Input) -> PixelProof {
+    match input.u8() % 9 {
+        0 => PixelProof::EmptyPixel(EmptyPixelProof::new(input.pubkey())),
+        1 => PixelProof::sig(input.pixel(), input.pubkey()),
+        2 => {
+            let pixel = input.pixel();
+            let keys = (0..=input.u8() % 4)
+                .map(|_| input.pubkey())
+                .collect::<Vec<_>>();
+            let required = 1 + input.u8() % keys.len() as u8;
+
+            PixelProof::Multisig(MultisigPixelProof::new(pixel, keys, required))
+        }
+        3 => PixelProof::Lightning(LightningCommitmentProof::new(
+            input.pixel(),
+            input.pubkey(),
+            input.u16(),
+            input.pubkey(),
+        )),
+        4 => {
+            let pixel = input.pixel();
+            let data = LightningHtlcData::new(
+                input.hash160(),
+                input.pubkey(),
+                input.pubkey(),
+                input.hash160(),
+                match input.bool() {
+                    true => HtlcScriptKind::Received {
+                        cltv_expiry: input.u32(),
+                    },
+                    false => HtlcScriptKind::Offered,
+                },
+            );
+
+            PixelProof::LightningHtlc(LightningHtlcProof::new(pixel, data))
+        }
+        5 => PixelProof::p2wsh(
+            input.pixel(),
+            input.pubkey(),
+            input.script(0, MAX_SCRIPT_LEN),
+        ),
+        6 => PixelProof::Musig(MusigPixelProof::new(
+            input.pixel(),
+            vec![input.pubkey(), input.pubkey()],
+        )),
+        7 => PixelProof::Timelock(TimelockPixelProof::new(
+            input.pixel(),
+            input.pubkey(),
+            input.u32(),
+        )),
+        _ => {
+            let pixel = input.pixel();
+            let data = HtlcData::new(input.sha256(), input.pubkey(), input.pubkey(), input.u32());
+
+            PixelProof::Htlc(HtlcPixelProof::new(pixel, data))
+        }
+    }
+}
+
+/// Generates one of the [`Announcement`] kinds, `None` if the generated one is invalid.
+fn announcement(input: &mut Input) -> Option<Announcement> {
+    let chroma = input.chroma();
+
+    let announcement = match input.u8() % 8 {
+        0 => ChromaAnnouncement::new(
+            chroma,
+            input.string(MIN_NAME_SIZE, MAX_NAME_SIZE),
+            input.string(MIN_SYMBOL_SIZE, MAX_SYMBOL_SIZE),
+            input.u8(),
+            input.u128(),
+            input.bool(),
+        )
+        .ok()?
+        .into(),
+        1 => match input.bool() {
+            true => FreezeAnnouncement::new_partial(chroma, input.outpoint(), input.u128()),
+            false => FreezeAnnouncement::new(chroma, input.outpoint()),
+        }
+        .into(),
+        2 => IssueAnnouncement::new(chroma, input.u128()).into(),
+        3 => TransferOwnershipAnnouncement::new(
+            chroma,
+            input.script(MIN_OWNER_SCRIPT_LEN, MAX_OWNER_SCRIPT_LEN),
+        )
+        .into(),
+        4 => BurnAnnouncement::new(chroma, input.u128()).into(),
+        5 => UpdateChromaAnnouncement::new(
+            chroma,
+            input.string(MIN_NAME_SIZE, MAX_NAME_SIZE),
+            input.string(MIN_SYMBOL_SIZE, MAX_SYMBOL_SIZE),
+        )
+        .ok()?
+        .into(),
+        6 => match input.bool() {
+            true => FreezePubkeyAnnouncement::new_unfreeze(chroma, input.pubkey()),
+            false => FreezePubkeyAnnouncement::new(chroma, input.pubkey()),
+        }
+        .into(),
+        _ => DelegateAnnouncement::new(
+            chroma,
+            input.pubkey(),
+            DelegateCapabilities::from_bits(input.u8() & DelegateCapabilities::ALL.bits())
+                .expect("only the known bits are set"),
+        )
+        .into(),
+    };
+
+    Some(announcement)
+}
@@ -0,0 +1,89 @@
+//! Reader of the structured values from the fuzz input.
+use bitcoin::hashes::{hash160, sha256, Hash};
+use bitcoin::secp256k1::PublicKey;
+use bitcoin::{OutPoint, ScriptBuf, Txid};
+use yuv_pixels::{Chroma, Pixel};
+
+use crate::keys::generate_keypair;
+
+/// Fuzz input, read as a sequence of values. When the input is exhausted, the values are read
+/// as zeros, so any input makes the valid values.
+pub struct Input<'a>(&'a [u8]);
+
+impl<'a> Input<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    pub fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0u8; N];
+
+        let len = self.0.len().min(N);
+        bytes[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+
+        bytes
+    }
+
+    pub fn u8(&mut self) -> u8 {
+        self.bytes::<1>()[0]
+    }
+
+    pub fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.bytes())
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.bytes())
+    }
+
+    pub fn u128(&mut self) -> u128 {
+        u128::from_le_bytes(self.bytes())
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.u8() % 2 == 1
+    }
+
+    /// Reads `min_len..=max_len` bytes, the length is read first.
+    pub fn vec(&mut self, min_len: usize, max_len: usize) -> Vec<u8> {
+        let len = min_len + self.u8() as usize % (max_len - min_len + 1);
+
+        (0..len).map(|_| self.u8()).collect()
+    }
+
+    pub fn pubkey(&mut self) -> PublicKey {
+        generate_keypair(&self.bytes::<32>()).1
+    }
+
+    pub fn chroma(&mut self) -> Chroma {
+        Chroma::from(self.pubkey().x_only_public_key().0)
+    }
+
+    pub fn pixel(&mut self) -> Pixel {
+        Pixel::new(self.u128(), self.chroma())
+    }
+
+    pub fn hash160(&mut self) -> hash160::Hash {
+        hash160::Hash::from_byte_array(self.bytes())
+    }
+
+    pub fn sha256(&mut self) -> sha256::Hash {
+        sha256::Hash::from_byte_array(self.bytes())
+    }
+
+    pub fn outpoint(&mut self) -> OutPoint {
+        OutPoint::new(Txid::from_byte_array(self.bytes()), self.u32())
+    }
+
+    pub fn script(&mut self, min_len: usize, max_len: usize) -> ScriptBuf {
+        ScriptBuf::from(self.vec(min_len, max_len))
+    }
+
+    /// Reads the string of `min_len..=max_len` ASCII letters.
+    pub fn string(&mut self, min_len: usize, max_len: usize) -> String {
+        let len = min_len + self.u8() as usize % (max_len - min_len + 1);
+
+        (0..len).map(|_| (b'A' + self.u8() % 26) as char).collect()
+    }
+}